@@ -2,6 +2,8 @@
 //!
 //! Command-line interface for the Immortal Engine prototyping system.
 
+use std::collections::HashSet;
+
 use clap::{Parser, Subcommand};
 use anyhow::Result;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
@@ -55,20 +57,38 @@ enum Commands {
         /// Project file to generate from
         project: String,
 
-        /// Output directory
-        #[arg(short, long, default_value = "generated")]
-        output: String,
+        /// Output directory, overriding the project's setting
+        #[arg(short, long)]
+        output: Option<String>,
 
-        /// Target language
-        #[arg(short, long, default_value = "rust")]
-        target: String,
+        /// Target language, overriding the project's setting
+        #[arg(short, long)]
+        target: Option<String>,
 
         /// Watch for changes and regenerate
         #[arg(short, long)]
         watch: bool,
+
+        /// Only generate nodes carrying one of these tags (comma-separated or repeated),
+        /// into `<output>-<tags>` unless --output is given
+        #[arg(long, value_delimiter = ',')]
+        tags: Vec<String>,
+
+        /// Run the shell commands the project file lists as generation hooks;
+        /// without it they are listed and skipped
+        #[arg(long)]
+        allow_hooks: bool,
+    },
+
+    /// Remove generated files from an output directory, keeping files
+    /// added or edited by hand
+    Clean {
+        /// Output directory to clean
+        output: String,
     },
 
     /// Validate a project file
+    #[command(alias = "lint")]
     Validate {
         /// Project file to validate
         project: String,
@@ -76,6 +96,14 @@ enum Commands {
         /// Output format for validation results
         #[arg(short, long, default_value = "text")]
         format: String,
+
+        /// Apply the fixes of fixable issues, then report what's left; saves the project
+        #[arg(long)]
+        fix: bool,
+
+        /// Revalidate whenever the project file changes, ringing the terminal bell on new errors
+        #[arg(short, long, conflicts_with_all = ["fix", "format"])]
+        watch: bool,
     },
 
     /// List available components
@@ -84,9 +112,22 @@ enum Commands {
         #[arg(short = 'C', long)]
         category: Option<String>,
 
-        /// Search query
+        /// Search query; results are ranked by name, synonym, tag, then
+        /// description
         #[arg(short, long)]
         search: Option<String>,
+
+        /// Only list components with this tag (repeatable)
+        #[arg(short, long = "tag")]
+        tags: Vec<String>,
+
+        /// Also load component definition files from this directory
+        #[arg(short, long)]
+        dir: Option<String>,
+
+        /// Print the JSON Schema for component definition files and exit
+        #[arg(long)]
+        schema: bool,
     },
 
     /// Export a project to different formats
@@ -97,7 +138,7 @@ enum Commands {
         /// Output file path
         output: String,
 
-        /// Export format (json, toml, yaml)
+        /// Export format (json, json-compact, toml, or pdf for a design document)
         #[arg(short, long, default_value = "json")]
         format: String,
     },
@@ -111,13 +152,55 @@ enum Commands {
         #[arg(short, long)]
         output: Option<String>,
 
-        /// Input format (json, toml, openapi, prisma)
+        /// Input format (json, toml, openapi, prisma, rust); a folder is
+        /// read as a Rust project
         #[arg(short, long)]
         format: Option<String>,
     },
 
-    /// Show information about the engine
-    Info,
+    /// Show information about the engine, or statistics for a project
+    Info {
+        /// Project file to summarize
+        project: Option<String>,
+    },
+
+    /// Query a project's nodes, optionally changing every match
+    Query {
+        /// Project file to query
+        project: String,
+
+        /// Query, e.g. 'nodes(type:"data.entity") | connected_to("Database")'
+        query: String,
+
+        /// Output format for the matches (text, json)
+        #[arg(short, long, default_value = "text")]
+        format: String,
+
+        /// Set a config value on every match (key=value); saves the project
+        #[arg(long, value_name = "KEY=VALUE")]
+        set: Vec<String>,
+
+        /// Delete every match; saves the project
+        #[arg(long)]
+        delete: bool,
+    },
+
+    /// Render a project diagram to an SVG or PNG image
+    Render {
+        /// Project file to render
+        project: String,
+
+        /// Output image; the extension picks the format (.svg or .png)
+        output: String,
+
+        /// Pixels per canvas unit
+        #[arg(short, long, default_value = "1.0")]
+        scale: f32,
+
+        /// Leave out the background grid
+        #[arg(long)]
+        no_grid: bool,
+    },
 }
 
 fn main() -> Result<()> {
@@ -143,14 +226,25 @@ fn main() -> Result<()> {
         Commands::Editor { project, port } => {
             cmd_editor(project.as_deref(), port)?;
         }
-        Commands::Generate { project, output, target, watch } => {
-            cmd_generate(&project, &output, &target, watch)?;
+        Commands::Generate { project, output, target, watch, tags, allow_hooks } => {
+            cmd_generate(&project, output.as_deref(), target.as_deref(), watch, &tags, allow_hooks)?;
+        }
+        Commands::Clean { output } => {
+            cmd_clean(&output)?;
         }
-        Commands::Validate { project, format } => {
-            cmd_validate(&project, &format)?;
+        Commands::Validate { project, format, fix, watch } => {
+            if watch {
+                cmd_validate_watch(&project)?;
+            } else {
+                cmd_validate(&project, &format, fix)?;
+            }
         }
-        Commands::Components { category, search } => {
-            cmd_components(category.as_deref(), search.as_deref())?;
+        Commands::Components { category, search, tags, dir, schema } => {
+            if schema {
+                println!("{}", imortal_components::DEFINITION_SCHEMA);
+            } else {
+                cmd_components(category.as_deref(), search.as_deref(), &tags, dir.as_deref())?;
+            }
         }
         Commands::Export { project, output, format } => {
             cmd_export(&project, &output, &format)?;
@@ -158,8 +252,14 @@ fn main() -> Result<()> {
         Commands::Import { input, output, format } => {
             cmd_import(&input, output.as_deref(), format.as_deref())?;
         }
-        Commands::Info => {
-            cmd_info()?;
+        Commands::Info { project } => {
+            cmd_info(project.as_deref())?;
+        }
+        Commands::Query { project, query, format, set, delete } => {
+            cmd_query(&project, &query, &format, &set, delete)?;
+        }
+        Commands::Render { project, output, scale, no_grid } => {
+            cmd_render(&project, &output, scale, no_grid)?;
         }
     }
 
@@ -211,21 +311,90 @@ fn cmd_editor(_project: Option<&str>, port: u16) -> Result<()> {
     Ok(())
 }
 
-fn cmd_generate(project: &str, output: &str, target: &str, watch: bool) -> Result<()> {
+fn cmd_generate(
+    project: &str,
+    output: Option<&str>,
+    target: Option<&str>,
+    watch: bool,
+    tags: &[String],
+    allow_hooks: bool,
+) -> Result<()> {
+    use imortal_codegen::{CodeGenerator, GeneratorConfig};
     use imortal_ir::load_project;
 
     println!("⚙️  Generating code from: {}", project);
-    println!("   Output: {}", output);
-    println!("   Target: {}", target);
 
     // Load the project
     let graph = load_project(project)?;
 
     println!("   Loaded {} nodes and {} edges", graph.node_count(), graph.edge_count());
 
-    // TODO: Implement actual code generation
-    println!("\n   Note: Code generation is not yet implemented.");
-    println!("   This will generate {} code in a future release.", target);
+    // The project's own settings, with any flags on top
+    let mut config = GeneratorConfig::for_project(&graph.meta);
+    if let Some(output) = output {
+        config = config.with_output_dir(output);
+    }
+    if let Some(target) = target {
+        config.target_language = target.to_string();
+    }
+    let tags = imortal_ir::parse_tags(&tags.join(","));
+    if !tags.is_empty() {
+        let tagged = graph.nodes().filter(|node| node.has_any_tag(&tags)).count();
+        if tagged == 0 {
+            anyhow::bail!("No node is tagged {}", tags.join(" or "));
+        }
+        println!("   Tags: {} ({} of {} nodes)", tags.join(", "), tagged, graph.node_count());
+        // Keep a partial application apart from the full one, whose shared
+        // files it would otherwise rewrite
+        if output.is_none() {
+            let suffix: String = tags
+                .join("-")
+                .chars()
+                .map(|c| if c.is_alphanumeric() || c == '-' || c == '_' { c } else { '-' })
+                .collect();
+            let dir = config.output_dir.as_os_str().to_string_lossy().trim_end_matches(['/', '\\']).to_string();
+            config = config.with_output_dir(format!("{}-{}", dir, suffix));
+        }
+        config = config.with_tags(tags);
+    }
+
+    println!("   Output: {}", config.output_dir.display());
+    println!("   Target: {}", config.target_language);
+    println!("   Framework: {}", config.auth_framework.display_name());
+    println!("   Database: {}", config.database_backend.display_name());
+    println!("   Layout: {}", config.layout.display_name());
+    println!("   Inheritance: {}", config.inheritance.display_name());
+    println!("   Tenancy: {}", config.tenancy.display_name());
+    if !config.hooks.is_empty() {
+        let hooks = &config.hooks;
+        println!(
+            "   Hooks: {} pre-generate, {} transform(s), {} post-generate",
+            hooks.pre_generate.len(),
+            hooks.transforms.len(),
+            hooks.post_generate.len(),
+        );
+        // The project file may come from anyone; its commands only run when asked to
+        if !allow_hooks {
+            println!("   ⚠ Skipping the project's hooks (run with --allow-hooks to run them):");
+            for command in hooks.commands() {
+                println!("      {}", command);
+            }
+            config.hooks = Default::default();
+        }
+    }
+
+    if config.target_language != "rust" {
+        anyhow::bail!("Unsupported target language: {}", config.target_language);
+    }
+
+    let output_dir = config.output_dir.clone();
+    let generator = CodeGenerator::with_config(config);
+    let generated = generator.generate_to(&graph, &output_dir)?;
+
+    for warning in &generated.warnings {
+        println!("   ⚠ {}", warning);
+    }
+    println!("\n✅ Generated {} files in {}", generated.file_count(), output_dir.display());
 
     if watch {
         println!("\n   Watch mode enabled - would watch for changes...");
@@ -234,24 +403,60 @@ fn cmd_generate(project: &str, output: &str, target: &str, watch: bool) -> Resul
     Ok(())
 }
 
-fn cmd_validate(project: &str, format: &str) -> Result<()> {
-    use imortal_ir::{load_project, validation};
+fn cmd_clean(output: &str) -> Result<()> {
+    use imortal_codegen::{Manifest, MANIFEST_FILE};
+    use std::path::Path;
+
+    println!("🧹 Cleaning generated files from: {}", output);
+
+    let dir = Path::new(output);
+    let Some(manifest) = Manifest::load(dir)? else {
+        anyhow::bail!("No {} in {}; nothing was generated there", MANIFEST_FILE, output);
+    };
+    let report = manifest.clean(dir)?;
+
+    for path in &report.modified {
+        println!("   ⚠ Kept {}, edited since it was generated", path);
+    }
+    if !report.missing.is_empty() {
+        println!("   {} generated files were already gone", report.missing.len());
+    }
+    println!("\n✅ Removed {} generated files", report.removed.len());
+
+    Ok(())
+}
+
+fn cmd_validate(project: &str, format: &str, fix: bool) -> Result<()> {
+    use imortal_components::{validation, ComponentRegistry};
+    use imortal_ir::{apply_fixes, load_project, save_project, ProjectFormat};
 
     println!("🔍 Validating project: {}", project);
 
     // Load the project
-    let graph = load_project(project)?;
+    let mut graph = load_project(project)?;
 
-    // Run validation
-    let issues = validation::get_all_issues(&graph);
+    // Run validation, including node config against the builtin components
+    let registry = ComponentRegistry::with_builtins();
+    let mut issues = validation::get_all_issues(&graph, &registry);
+
+    if fix {
+        let applied = apply_fixes(&mut graph, &issues);
+        if applied > 0 {
+            let format = ProjectFormat::from_path(std::path::Path::new(project)).unwrap_or(ProjectFormat::Json);
+            save_project(&graph, project, format)?;
+            issues = validation::get_all_issues(&graph, &registry);
+        }
+        println!("🔧 Applied {} fix(es)", applied);
+    }
 
     let errors: Vec<_> = issues.iter().filter(|i| i.is_error()).collect();
     let warnings: Vec<_> = issues.iter().filter(|i| i.is_warning()).collect();
+    let fixable = errors.iter().chain(&warnings).filter(|i| i.fix.is_some()).count();
 
     match format {
         "json" => {
             // TODO: JSON output
-            println!("{{\"errors\": {}, \"warnings\": {}}}", errors.len(), warnings.len());
+            println!("{{\"errors\": {}, \"warnings\": {}, \"fixable\": {}}}", errors.len(), warnings.len(), fixable);
         }
         _ => {
             println!("\n   Nodes: {}", graph.node_count());
@@ -264,12 +469,21 @@ fn cmd_validate(project: &str, format: &str) -> Result<()> {
             } else {
                 for error in &errors {
                     println!("❌ {}", error);
+                    if let Some(fix) = &error.fix {
+                        println!("   🔧 {}", fix.label());
+                    }
                 }
                 for warning in &warnings {
                     println!("⚠️  {}", warning);
+                    if let Some(fix) = &warning.fix {
+                        println!("   🔧 {}", fix.label());
+                    }
                 }
                 println!();
                 println!("   {} error(s), {} warning(s)", errors.len(), warnings.len());
+                if fixable > 0 {
+                    println!("   {} can be fixed with --fix", fixable);
+                }
             }
         }
     }
@@ -277,14 +491,103 @@ fn cmd_validate(project: &str, format: &str) -> Result<()> {
     Ok(())
 }
 
-fn cmd_components(category: Option<&str>, search: Option<&str>) -> Result<()> {
-    use imortal_components::{ComponentRegistry, ComponentCategory};
+/// Revalidate `project` each time it changes, printing one summary line per
+/// change and the errors that are new since the last one
+fn cmd_validate_watch(project: &str) -> Result<()> {
+    use imortal_components::{validation, ComponentRegistry};
+    use imortal_ir::load_project;
+    use std::io::Write;
+    use std::time::{Duration, Instant};
 
     let registry = ComponentRegistry::with_builtins();
+    let stamp = || std::fs::metadata(project).ok().map(|m| (m.modified().ok(), m.len()));
+    let started = Instant::now();
+
+    println!("👀 Watching {} for changes (Ctrl+C to stop)\n", project);
+
+    // Starts as no reading at all, so the first pass reports even a missing file
+    let mut last_stamp = None;
+    let mut known_errors: HashSet<String> = HashSet::new();
+    loop {
+        let current = Some(stamp());
+        if current == last_stamp {
+            std::thread::sleep(Duration::from_millis(500));
+            continue;
+        }
+        last_stamp = current;
+
+        let elapsed = started.elapsed().as_secs();
+        let time = format!("[{:02}:{:02}:{:02}]", elapsed / 3600, elapsed / 60 % 60, elapsed % 60);
+        if last_stamp == Some(None) {
+            println!("{} ⚠️  {} is missing", time, project);
+            continue;
+        }
+        // A script may be halfway through writing it; the next change retries
+        let graph = match load_project(project) {
+            Ok(graph) => graph,
+            Err(e) => {
+                println!("{} ⚠️  Could not load: {}", time, e);
+                continue;
+            }
+        };
+
+        let issues = validation::get_all_issues(&graph, &registry);
+        let errors: HashSet<String> = issues.iter().filter(|i| i.is_error()).map(|i| i.to_string()).collect();
+        let warnings = issues.iter().filter(|i| i.is_warning()).count();
+        let (new_errors, resolved) = error_changes(&known_errors, &errors);
+
+        let summary = watch_summary(errors.len(), warnings, new_errors.len(), resolved);
+        if new_errors.is_empty() {
+            println!("{} {}", time, summary);
+        } else {
+            println!("\x07{} {}", time, summary);
+        }
+        for error in &new_errors {
+            println!("   {}", error);
+        }
+        std::io::stdout().flush()?;
+        known_errors = errors;
+    }
+}
+
+/// Errors in `current` that weren't in `known`, sorted, and how many of
+/// `known` are gone
+fn error_changes<'a>(known: &HashSet<String>, current: &'a HashSet<String>) -> (Vec<&'a String>, usize) {
+    let mut new_errors: Vec<&String> = current.iter().filter(|error| !known.contains(*error)).collect();
+    new_errors.sort();
+    (new_errors, known.difference(current).count())
+}
+
+/// One watch pass, e.g. "❌ 2 error(s), 1 warning(s), 1 new, 3 resolved"
+fn watch_summary(errors: usize, warnings: usize, new_errors: usize, resolved: usize) -> String {
+    let mark = if errors == 0 { "✅" } else { "❌" };
+    let mut summary = format!("{} {} error(s), {} warning(s)", mark, errors, warnings);
+    if new_errors > 0 {
+        summary.push_str(&format!(", {} new", new_errors));
+    }
+    if resolved > 0 {
+        summary.push_str(&format!(", {} resolved", resolved));
+    }
+    summary
+}
+
+fn cmd_components(category: Option<&str>, search: Option<&str>, tags: &[String], dir: Option<&str>) -> Result<()> {
+    use imortal_components::{ComponentRegistry, ComponentCategory, SearchFilter};
+
+    let mut registry = ComponentRegistry::with_builtins();
+    if let Some(dir) = dir {
+        let load = registry.load_dir(dir)?;
+        println!("📂 Loaded {} component(s) from {}", load.loaded.len(), dir);
+        for error in &load.errors {
+            println!("   ❌ {}", error);
+        }
+        println!();
+    }
 
     println!("📦 Available Components\n");
 
-    let components: Vec<_> = if let Some(cat_str) = category {
+    let mut filter = SearchFilter { tags: tags.iter().map(|tag| tag.to_lowercase()).collect(), ..Default::default() };
+    if let Some(cat_str) = category {
         // Filter by category
         let cat = match cat_str.to_lowercase().as_str() {
             "auth" => Some(ComponentCategory::Auth),
@@ -294,21 +597,34 @@ fn cmd_components(category: Option<&str>, search: Option<&str>) -> Result<()> {
             "logic" => Some(ComponentCategory::Logic),
             "ui" => Some(ComponentCategory::Ui),
             "embedded" => Some(ComponentCategory::Embedded),
+            "config" => Some(ComponentCategory::Config),
+            "service" => Some(ComponentCategory::Service),
             "custom" => Some(ComponentCategory::Custom),
             _ => None,
         };
 
         if let Some(c) = cat {
-            registry.by_category(c)
+            filter.categories.insert(c);
         } else {
             println!("Unknown category: {}", cat_str);
             return Ok(());
         }
-    } else if let Some(query) = search {
-        registry.search(query)
-    } else {
-        registry.all().collect()
-    };
+    }
+
+    // Search results are listed by relevance rather than by category
+    if let Some(query) = search {
+        let results = registry.search_with(query, &filter);
+        for comp in &results {
+            println!("   {} {} - {}", comp.icon_text(), comp.name, comp.description);
+            println!("      ID: {}", comp.id);
+            if !comp.tags.is_empty() {
+                println!("      Tags: {}", comp.tags.join(", "));
+            }
+        }
+        println!("\n{} match(es) for \"{}\"", results.len(), query);
+        return Ok(());
+    }
+    let components = registry.search_with("", &filter);
 
     // Group by category
     let mut by_category: std::collections::HashMap<ComponentCategory, Vec<_>> =
@@ -326,7 +642,7 @@ fn cmd_components(category: Option<&str>, search: Option<&str>) -> Result<()> {
             if !comps.is_empty() {
                 println!("{} {}", category.icon(), category.display_name());
                 for comp in comps {
-                    println!("   {} {} - {}", comp.icon, comp.name, comp.description);
+                    println!("   {} {} - {}", comp.icon_text(), comp.name, comp.description);
                     println!("      ID: {}", comp.id);
                 }
                 println!();
@@ -350,6 +666,12 @@ fn cmd_export(project: &str, output: &str, format: &str) -> Result<()> {
     let graph = load_project(project)?;
 
     let fmt = match format.to_lowercase().as_str() {
+        "pdf" => {
+            let pdf = imortal_render::render_document(&graph, imortal_render::PaperSize::A4)?;
+            std::fs::write(output, pdf)?;
+            println!("✅ Exported design document!");
+            return Ok(());
+        }
         "json" => ProjectFormat::Json,
         "json-compact" => ProjectFormat::JsonCompact,
         "toml" => ProjectFormat::Toml,
@@ -366,10 +688,93 @@ fn cmd_export(project: &str, output: &str, format: &str) -> Result<()> {
     Ok(())
 }
 
+fn cmd_query(project: &str, query: &str, format: &str, set: &[String], delete: bool) -> Result<()> {
+    use imortal_core::ConfigValue;
+    use imortal_ir::{load_project, save_project, ProjectFormat, Query};
+
+    let mut graph = load_project(project)?;
+    let matches = Query::parse(query)?.run(&graph);
+
+    let assignments = set
+        .iter()
+        .map(|pair| {
+            let (key, value) = pair
+                .split_once('=')
+                .ok_or_else(|| anyhow::anyhow!("Expected KEY=VALUE, got '{}'", pair))?;
+            Ok((key.trim().to_string(), ConfigValue::parse_literal(value)))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    match format {
+        "json" => {
+            let nodes: Vec<_> = matches
+                .iter()
+                .filter_map(|id| graph.get_node(*id))
+                .map(|node| serde_json::json!({ "id": node.id, "name": node.name, "type": node.component_type }))
+                .collect();
+            println!("{}", serde_json::to_string_pretty(&nodes)?);
+        }
+        _ => {
+            for node in matches.iter().filter_map(|id| graph.get_node(*id)) {
+                println!("{} ({})", node.name, node.component_type);
+            }
+            println!("\n   {} match(es)", matches.len());
+        }
+    }
+
+    if assignments.is_empty() && !delete {
+        return Ok(());
+    }
+    for id in &matches {
+        if delete {
+            graph.remove_node(*id);
+        } else if let Some(node) = graph.get_node_mut(*id) {
+            for (key, value) in &assignments {
+                node.set_config(key, value.clone());
+            }
+        }
+    }
+    let format = ProjectFormat::from_path(std::path::Path::new(project)).unwrap_or(ProjectFormat::Json);
+    save_project(&graph, project, format)?;
+    if delete {
+        println!("🗑  Deleted {} node(s) from {}", matches.len(), project);
+    } else {
+        println!("✏️  Updated {} node(s) in {}", matches.len(), project);
+    }
+
+    Ok(())
+}
+
+fn cmd_render(project: &str, output: &str, scale: f32, no_grid: bool) -> Result<()> {
+    use imortal_ir::load_project;
+    use imortal_render::{render_to_file, RenderOptions};
+
+    println!("🖼️  Rendering project: {}", project);
+    println!("   Output: {}", output);
+
+    if scale <= 0.0 {
+        anyhow::bail!("Scale must be greater than zero");
+    }
+
+    let graph = load_project(project)?;
+    let options = RenderOptions::default().with_scale(scale).with_grid(!no_grid);
+    render_to_file(&graph, output, &options)?;
+
+    let (width, height, _) = options.frame(&graph);
+    println!("✅ Rendered {} nodes to {}x{} image", graph.node_count(), width, height);
+
+    Ok(())
+}
+
 fn cmd_import(input: &str, output: Option<&str>, format: Option<&str>) -> Result<()> {
     println!("📥 Importing from: {}", input);
     println!("   Format: {}", format.unwrap_or("auto-detect"));
 
+    let path = std::path::Path::new(input);
+    if format == Some("rust") || (format.is_none() && path.is_dir()) {
+        return import_rust_project(path, output);
+    }
+
     // TODO: Implement import from various formats (OpenAPI, Prisma schema, etc.)
     println!("\n   Note: Import is not yet fully implemented.");
     println!("   Supported formats will include: JSON, TOML, OpenAPI, Prisma");
@@ -381,7 +786,41 @@ fn cmd_import(input: &str, output: Option<&str>, format: Option<&str>) -> Result
     Ok(())
 }
 
-fn cmd_info() -> Result<()> {
+/// Propose entities and endpoints for an existing Rust project (experimental)
+fn import_rust_project(dir: &std::path::Path, output: Option<&str>) -> Result<()> {
+    use imortal_codegen::reverse::RustImport;
+    use imortal_ir::{save_project, ProjectFormat};
+
+    let import = RustImport::scan(dir)?;
+    for warning in &import.warnings {
+        println!("   ⚠️  Skipped {}", warning);
+    }
+    println!("\n   Entities: {}", import.entities.len());
+    for entity in &import.entities {
+        println!("     • {} ({} fields) in {}", entity.node.name, entity.node.fields.len(), entity.file);
+    }
+    println!("   Endpoints: {}", import.endpoints.len());
+    for endpoint in &import.endpoints {
+        let method = endpoint.node.get_config_str("method").unwrap_or("GET");
+        let path = endpoint.node.get_config_str("path").unwrap_or("/");
+        println!("     • {} {} → {} in {}", method, path, endpoint.handler, endpoint.file);
+    }
+
+    let name = dir
+        .canonicalize()
+        .ok()
+        .and_then(|dir| dir.file_name().map(|n| n.to_string_lossy().into_owned()))
+        .unwrap_or_else(|| "imported".to_string());
+    let graph = import.to_graph(&name)?;
+    let output = output.map(str::to_string).unwrap_or_else(|| format!("{}.imortal", name));
+    let format = ProjectFormat::from_path(std::path::Path::new(&output)).unwrap_or(ProjectFormat::Json);
+    save_project(&graph, &output, format)?;
+    println!("\n✅ Saved {} nodes to {}", graph.node_count(), output);
+
+    Ok(())
+}
+
+fn cmd_info(project: Option<&str>) -> Result<()> {
     use imortal_components::ComponentRegistry;
 
     println!("🔧 Immortal Engine\n");
@@ -390,6 +829,10 @@ fn cmd_info() -> Result<()> {
     println!("   Component Version: {}", imortal_components::COMPONENT_VERSION);
     println!();
 
+    if let Some(project) = project {
+        return print_project_stats(project);
+    }
+
     let registry = ComponentRegistry::with_builtins();
     let stats = registry.stats();
 
@@ -407,3 +850,85 @@ fn cmd_info() -> Result<()> {
 
     Ok(())
 }
+
+fn print_project_stats(project: &str) -> Result<()> {
+    let graph = imortal_ir::load_project(project)?;
+    let stats = graph.stats();
+
+    println!("📁 Project: {}", graph.meta.name);
+    println!("   Nodes: {}", stats.nodes);
+    println!("   Edges: {}", stats.edges);
+    println!("   Groups: {}", stats.groups);
+    println!("   Entity fields: {}", stats.entity_fields);
+    println!("   Avg. connections per node: {:.2}", stats.average_connectivity);
+    if stats.has_cycles {
+        println!("   Dependency depth: {} (graph has cycles)", stats.dag_depth);
+    } else {
+        println!("   Dependency depth: {}", stats.dag_depth);
+    }
+    println!();
+
+    println!("📦 Components by category:");
+    for category in imortal_core::ComponentCategory::all() {
+        let count = stats.category_count(*category);
+        if count > 0 {
+            println!("   {} {}: {}", category.icon(), category.display_name(), count);
+        }
+    }
+
+    if !stats.hotspots.is_empty() {
+        println!();
+        println!("🔥 Hotspots:");
+        for hotspot in &stats.hotspots {
+            println!("   {} ({} connections)", hotspot.name, hotspot.degree);
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(errors: &[&str]) -> HashSet<String> {
+        errors.iter().map(|e| e.to_string()).collect()
+    }
+
+    #[test]
+    fn test_error_changes() {
+        let known = set(&["a", "b"]);
+        let current = set(&["b", "d", "c"]);
+        let (new_errors, resolved) = error_changes(&known, &current);
+        assert_eq!(new_errors, ["c", "d"]);
+        assert_eq!(resolved, 1);
+
+        let (new_errors, resolved) = error_changes(&current, &current);
+        assert!(new_errors.is_empty());
+        assert_eq!(resolved, 0);
+    }
+
+    #[test]
+    fn test_watch_summary() {
+        assert_eq!(watch_summary(0, 0, 0, 0), "✅ 0 error(s), 0 warning(s)");
+        assert_eq!(watch_summary(0, 1, 0, 2), "✅ 0 error(s), 1 warning(s), 2 resolved");
+        assert_eq!(watch_summary(2, 0, 1, 1), "❌ 2 error(s), 0 warning(s), 1 new, 1 resolved");
+    }
+
+    #[test]
+    fn test_generate_hooks_are_opt_in() {
+        let parse = |args: &[&str]| match Cli::try_parse_from(args).unwrap().command {
+            Commands::Generate { allow_hooks, .. } => allow_hooks,
+            _ => unreachable!(),
+        };
+        assert!(!parse(&["imortal", "generate", "p.imortal"]));
+        assert!(parse(&["imortal", "generate", "p.imortal", "--allow-hooks"]));
+    }
+
+    #[test]
+    fn test_watch_rejects_format_and_fix() {
+        assert!(Cli::try_parse_from(["imortal", "validate", "p.imortal", "--watch"]).is_ok());
+        assert!(Cli::try_parse_from(["imortal", "validate", "p.imortal", "--watch", "--format", "json"]).is_err());
+        assert!(Cli::try_parse_from(["imortal", "validate", "p.imortal", "--watch", "--fix"]).is_err());
+    }
+}
@@ -0,0 +1,405 @@
+//! Generation hooks
+//!
+//! [`GenerationHooks`] run around [`CodeGenerator::generate_to`]: hooks
+//! before anything is generated, a transform for each generated file, and
+//! hooks after the files are written. Each is a shell command, as saved in
+//! a project's [`CodegenSettings`], or a Rust callback registered by an
+//! embedder on its [`GeneratorConfig`]. Formatters, license-header
+//! injectors, and linters fit here.
+//!
+//! Shell commands run through `sh -c` (`cmd /C` on Windows) in the output
+//! directory, with `IMORTAL_PROJECT`, `IMORTAL_OUTPUT_DIR`, and
+//! `IMORTAL_STAGE` set. A transform command gets the file on stdin and
+//! `IMORTAL_FILE` set to its path, and prints the new content.
+//!
+//! [`CodeGenerator::generate_to`]: crate::CodeGenerator::generate_to
+//! [`GeneratorConfig`]: crate::GeneratorConfig
+
+use std::fmt;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::Arc;
+
+use imortal_core::{EngineError, EngineResult};
+use imortal_ir::query::glob_match;
+use imortal_ir::{CodegenSettings, TransformCommand};
+
+use crate::generator::GeneratedProject;
+use crate::manifest::content_hash;
+
+/// When a hook runs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HookStage {
+    PreGenerate,
+    /// Rewriting a generated file
+    Transform,
+    PostGenerate,
+}
+
+impl HookStage {
+    /// Identifier passed to commands as `IMORTAL_STAGE`
+    pub fn id(&self) -> &'static str {
+        match self {
+            HookStage::PreGenerate => "pre_generate",
+            HookStage::Transform => "transform",
+            HookStage::PostGenerate => "post_generate",
+        }
+    }
+}
+
+/// What a hook is told about the generation
+#[derive(Debug, Clone)]
+pub struct HookContext {
+    pub stage: HookStage,
+    pub project: String,
+    pub output_dir: PathBuf,
+    /// Paths of the written files, relative to the output directory; empty
+    /// before generating
+    pub files: Vec<String>,
+}
+
+type HookCallback = Arc<dyn Fn(&HookContext) -> Result<(), String> + Send + Sync>;
+type TransformCallback = Arc<dyn Fn(&str, &str) -> Result<String, String> + Send + Sync>;
+
+/// A pre- or post-generation hook
+#[derive(Clone)]
+pub enum Hook {
+    /// Shell command
+    Command(String),
+    /// Rust callback; an `Err` stops generation with its message
+    Callback(HookCallback),
+}
+
+impl Hook {
+    pub fn callback(f: impl Fn(&HookContext) -> Result<(), String> + Send + Sync + 'static) -> Self {
+        Hook::Callback(Arc::new(f))
+    }
+
+    fn run(&self, context: &HookContext) -> EngineResult<()> {
+        match self {
+            Hook::Command(command) => run_command(command, context, &[], None).map(|_| ()),
+            Hook::Callback(f) => f(context).map_err(|e| hook_failed(context.stage.id(), "callback", e)),
+        }
+    }
+}
+
+impl fmt::Debug for Hook {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Hook::Command(command) => f.debug_tuple("Command").field(command).finish(),
+            Hook::Callback(_) => f.write_str("Callback"),
+        }
+    }
+}
+
+/// A rewrite of the generated files whose paths match `pattern`
+#[derive(Clone)]
+pub struct FileTransform {
+    /// Path pattern relative to the output directory, where `*` stands for
+    /// any run of characters, e.g. `*.rs`
+    pub pattern: String,
+    pub action: TransformAction,
+}
+
+/// How a [`FileTransform`] rewrites a file
+#[derive(Clone)]
+pub enum TransformAction {
+    /// Shell command reading the file on stdin and printing the new content
+    Command(String),
+    /// Rust callback given the path and content, returning the new content
+    Callback(TransformCallback),
+}
+
+impl FileTransform {
+    pub fn command(pattern: impl Into<String>, command: impl Into<String>) -> Self {
+        Self { pattern: pattern.into(), action: TransformAction::Command(command.into()) }
+    }
+
+    pub fn callback(
+        pattern: impl Into<String>,
+        f: impl Fn(&str, &str) -> Result<String, String> + Send + Sync + 'static,
+    ) -> Self {
+        Self { pattern: pattern.into(), action: TransformAction::Callback(Arc::new(f)) }
+    }
+
+    /// Whether the transform applies to `path`
+    pub fn matches(&self, path: &str) -> bool {
+        glob_match(&self.pattern, path)
+    }
+
+    fn apply(&self, path: &str, content: &str, context: &HookContext) -> EngineResult<String> {
+        match &self.action {
+            TransformAction::Command(command) => {
+                run_command(command, context, &[("IMORTAL_FILE", path)], Some(content))
+            }
+            TransformAction::Callback(f) => {
+                f(path, content).map_err(|e| hook_failed(context.stage.id(), &format!("callback on {}", path), e))
+            }
+        }
+    }
+}
+
+impl fmt::Debug for FileTransform {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let action = match &self.action {
+            TransformAction::Command(command) => command.as_str(),
+            TransformAction::Callback(_) => "<callback>",
+        };
+        f.debug_struct("FileTransform").field("pattern", &self.pattern).field("action", &action).finish()
+    }
+}
+
+/// Hooks run by [`CodeGenerator::generate_to`](crate::CodeGenerator::generate_to)
+#[derive(Debug, Clone, Default)]
+pub struct GenerationHooks {
+    /// Run before generating; a failure stops generation
+    pub pre_generate: Vec<Hook>,
+    /// Run after the files are written
+    pub post_generate: Vec<Hook>,
+    /// Applied in order to each matching file before it is written
+    pub transforms: Vec<FileTransform>,
+}
+
+impl GenerationHooks {
+    /// The shell commands saved in a project's settings
+    pub fn from_settings(settings: &CodegenSettings) -> Self {
+        Self {
+            pre_generate: settings.pre_generate.iter().cloned().map(Hook::Command).collect(),
+            post_generate: settings.post_generate.iter().cloned().map(Hook::Command).collect(),
+            transforms: settings.transforms.iter().map(|t| FileTransform::command(&t.pattern, &t.command)).collect(),
+        }
+    }
+
+    /// Save the shell commands in a project's settings; callbacks can't be
+    /// saved and are left out
+    pub fn apply_to(&self, settings: &mut CodegenSettings) {
+        let commands = |hooks: &[Hook]| -> Vec<String> {
+            hooks
+                .iter()
+                .filter_map(|hook| match hook {
+                    Hook::Command(command) => Some(command.clone()),
+                    Hook::Callback(_) => None,
+                })
+                .collect()
+        };
+        settings.pre_generate = commands(&self.pre_generate);
+        settings.post_generate = commands(&self.post_generate);
+        settings.transforms = self
+            .transforms
+            .iter()
+            .filter_map(|t| match &t.action {
+                TransformAction::Command(command) => {
+                    Some(TransformCommand { pattern: t.pattern.clone(), command: command.clone() })
+                }
+                TransformAction::Callback(_) => None,
+            })
+            .collect();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pre_generate.is_empty() && self.post_generate.is_empty() && self.transforms.is_empty()
+    }
+
+    /// The shell commands, each with its stage and, for transforms, its
+    /// pattern, e.g. `post_generate: cargo fmt`; callbacks are left out
+    pub fn commands(&self) -> Vec<String> {
+        let mut settings = CodegenSettings::default();
+        self.apply_to(&mut settings);
+        let hooks = |stage: HookStage, commands: Vec<String>| {
+            commands.into_iter().map(move |command| format!("{}: {}", stage.id(), command))
+        };
+        let transforms = settings.transforms.into_iter().map(|t| format!("{} {}: {}", HookStage::Transform.id(), t.pattern, t.command));
+        hooks(HookStage::PreGenerate, settings.pre_generate)
+            .chain(transforms)
+            .chain(hooks(HookStage::PostGenerate, settings.post_generate))
+            .collect()
+    }
+
+    /// Hash of the [`commands`](Self::commands), stable across runs, or
+    /// `None` when there are none; changes whenever a command does, so an
+    /// approval of a project's hooks can be tied to it
+    pub fn commands_hash(&self) -> Option<String> {
+        let commands = self.commands();
+        (!commands.is_empty()).then(|| content_hash(&commands.join("\n")))
+    }
+
+    /// Run the hooks of `context.stage` in order, stopping at the first
+    /// failure
+    pub fn run(&self, context: &HookContext) -> EngineResult<()> {
+        let hooks = match context.stage {
+            HookStage::PreGenerate => &self.pre_generate[..],
+            HookStage::Transform => &[],
+            HookStage::PostGenerate => &self.post_generate[..],
+        };
+        hooks.iter().try_for_each(|hook| hook.run(context))
+    }
+
+    /// Apply the transforms to every matching generated file
+    pub fn transform(&self, project: &mut GeneratedProject, context: &HookContext) -> EngineResult<()> {
+        if self.transforms.is_empty() {
+            return Ok(());
+        }
+        let mut paths: Vec<String> = project.files.keys().cloned().collect();
+        paths.sort();
+        for path in paths {
+            for transform in self.transforms.iter().filter(|t| t.matches(&path)) {
+                let content = &project.files[&path];
+                let transformed = transform.apply(&path, content, context)?;
+                project.files.insert(path.clone(), transformed);
+            }
+        }
+        Ok(())
+    }
+}
+
+fn hook_failed(stage: &str, what: &str, message: impl fmt::Display) -> EngineError {
+    EngineError::CodeGeneration(format!("{} hook `{}` failed: {}", stage, what, message))
+}
+
+/// Run `command` through the shell in the output directory, feeding it
+/// `stdin`, and return what it printed
+fn run_command(command: &str, context: &HookContext, env: &[(&str, &str)], stdin: Option<&str>) -> EngineResult<String> {
+    let stage = context.stage.id();
+    std::fs::create_dir_all(&context.output_dir)?;
+    let mut shell = shell(command);
+    shell
+        .current_dir(&context.output_dir)
+        .env("IMORTAL_PROJECT", &context.project)
+        .env("IMORTAL_OUTPUT_DIR", &context.output_dir)
+        .env("IMORTAL_STAGE", stage)
+        .envs(env.iter().copied())
+        .stdin(if stdin.is_some() { Stdio::piped() } else { Stdio::null() })
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+
+    let mut child = shell.spawn().map_err(|e| hook_failed(stage, command, e))?;
+    if let (Some(input), Some(mut pipe)) = (stdin, child.stdin.take()) {
+        // Written from a thread so a command printing before it has read
+        // everything can't deadlock on a full pipe
+        let input = input.to_string();
+        std::thread::spawn(move || pipe.write_all(input.as_bytes()));
+    }
+    let output = child.wait_with_output().map_err(|e| hook_failed(stage, command, e))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let message = match stderr.trim() {
+            "" => output.status.to_string(),
+            stderr => stderr.to_string(),
+        };
+        return Err(hook_failed(stage, command, message));
+    }
+    String::from_utf8(output.stdout).map_err(|e| hook_failed(stage, command, e))
+}
+
+#[cfg(windows)]
+fn shell(command: &str) -> Command {
+    let mut shell = Command::new("cmd");
+    shell.args(["/C", command]);
+    shell
+}
+
+#[cfg(not(windows))]
+fn shell(command: &str) -> Command {
+    let mut shell = Command::new("sh");
+    shell.args(["-c", command]);
+    shell
+}
+
+/// Context for hooks of `stage` generating `project` into `output_dir`
+pub(crate) fn context(stage: HookStage, project: &str, output_dir: &Path, files: Vec<String>) -> HookContext {
+    HookContext { stage, project: project.to_string(), output_dir: output_dir.to_path_buf(), files }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    #[test]
+    fn test_transforms_and_callbacks() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut project = GeneratedProject::new("shop");
+        project.add_file("src/main.rs", "fn main() {}\n");
+        project.add_file("README.md", "# shop\n");
+
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let record = seen.clone();
+        let hooks = GenerationHooks {
+            pre_generate: vec![Hook::callback(move |cx| {
+                record.lock().unwrap().push(cx.stage);
+                Ok(())
+            })],
+            post_generate: vec![Hook::callback(|_| Err("lint errors".to_string()))],
+            transforms: vec![FileTransform::callback("*.rs", |path, content| {
+                Ok(format!("// SPDX-License-Identifier: MIT ({})\n{}", path, content))
+            })],
+        };
+
+        let pre = context(HookStage::PreGenerate, "shop", dir.path(), Vec::new());
+        hooks.run(&pre).unwrap();
+        assert_eq!(*seen.lock().unwrap(), [HookStage::PreGenerate]);
+
+        let transform = context(HookStage::Transform, "shop", dir.path(), Vec::new());
+        hooks.transform(&mut project, &transform).unwrap();
+        assert!(project.files["src/main.rs"].starts_with("// SPDX-License-Identifier: MIT (src/main.rs)\n"));
+        assert_eq!(project.files["README.md"], "# shop\n");
+
+        let post = context(HookStage::PostGenerate, "shop", dir.path(), Vec::new());
+        let error = hooks.run(&post).unwrap_err().to_string();
+        assert!(error.contains("post_generate hook `callback` failed: lint errors"), "{}", error);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_commands() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut project = GeneratedProject::new("shop");
+        project.add_file("src/lib.rs", "pub fn hello() {}\n");
+
+        let hooks = GenerationHooks {
+            pre_generate: vec![Hook::Command("echo \"$IMORTAL_STAGE $IMORTAL_PROJECT\" > stage.txt".to_string())],
+            post_generate: vec![Hook::Command("echo broken >&2; exit 3".to_string())],
+            transforms: vec![FileTransform::command("src/*", "tr a-z A-Z")],
+        };
+        let pre = context(HookStage::PreGenerate, "shop", dir.path(), Vec::new());
+        hooks.run(&pre).unwrap();
+        assert_eq!(std::fs::read_to_string(dir.path().join("stage.txt")).unwrap(), "pre_generate shop\n");
+
+        let transform = context(HookStage::Transform, "shop", dir.path(), Vec::new());
+        hooks.transform(&mut project, &transform).unwrap();
+        assert_eq!(project.files["src/lib.rs"], "PUB FN HELLO() {}\n");
+
+        let post = context(HookStage::PostGenerate, "shop", dir.path(), Vec::new());
+        let error = hooks.run(&post).unwrap_err().to_string();
+        assert!(error.contains("broken"), "{}", error);
+
+        let mut settings = CodegenSettings::default();
+        hooks.apply_to(&mut settings);
+        assert_eq!(settings.transforms, [TransformCommand { pattern: "src/*".to_string(), command: "tr a-z A-Z".to_string() }]);
+        let restored = GenerationHooks::from_settings(&settings);
+        assert_eq!(restored.pre_generate.len(), 1);
+        assert!(restored.transforms[0].matches("src/models/user.rs"));
+    }
+
+    #[test]
+    fn test_commands_hash() {
+        let callback = GenerationHooks { pre_generate: vec![Hook::callback(|_| Ok(()))], ..Default::default() };
+        assert!(GenerationHooks::default().commands_hash().is_none());
+        assert!(callback.commands_hash().is_none());
+
+        let mut settings = CodegenSettings {
+            post_generate: vec!["cargo fmt".to_string()],
+            transforms: vec![TransformCommand { pattern: "*.rs".to_string(), command: "./license.sh".to_string() }],
+            ..Default::default()
+        };
+        let hooks = GenerationHooks::from_settings(&settings);
+        assert_eq!(hooks.commands(), ["transform *.rs: ./license.sh", "post_generate: cargo fmt"]);
+        let hash = hooks.commands_hash().unwrap();
+        assert_eq!(GenerationHooks::from_settings(&settings).commands_hash().unwrap(), hash);
+
+        // Moving a command to another stage is a different set of hooks
+        settings.pre_generate = std::mem::take(&mut settings.post_generate);
+        assert_ne!(GenerationHooks::from_settings(&settings).commands_hash().unwrap(), hash);
+    }
+}
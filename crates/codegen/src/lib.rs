@@ -34,14 +34,22 @@
 //! ```
 
 pub mod generator;
+pub mod hooks;
+pub mod manifest;
+pub mod reverse;
 pub mod rust;
+pub mod sync;
 pub mod templates;
 
-pub use generator::{CodeGenerator, GeneratorConfig, GeneratedProject};
+pub use generator::{CodeGenerator, GeneratorConfig, GeneratedProject, InheritanceMode, Orm, ProjectLayout, RemovalImpact};
+pub use hooks::{FileTransform, GenerationHooks, Hook, HookContext, HookStage};
+pub use manifest::{CleanReport, Manifest, ManifestEntry, MANIFEST_FILE};
+pub use rust::TenancyMode;
 
 /// Prelude for convenient imports
 pub mod prelude {
-    pub use super::generator::{CodeGenerator, GeneratorConfig, GeneratedProject};
+    pub use super::generator::{CodeGenerator, GeneratorConfig, GeneratedProject, InheritanceMode, Orm, ProjectLayout};
+    pub use super::rust::TenancyMode;
 }
 
 /// Current version of the code generator
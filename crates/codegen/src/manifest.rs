@@ -0,0 +1,270 @@
+//! Output manifest
+//!
+//! [`CodeGenerator::generate_to`] writes a [`Manifest`] to
+//! `.imortal-manifest.json` in the output directory, listing every file it
+//! generated with a hash of what it wrote and the nodes the file came from.
+//! The next generation removes files the project no longer produces, and
+//! `imortal clean` removes them all, leaving files the user added alone.
+//! A generated file whose hash no longer matches was edited by hand and is
+//! kept too. Paths in a manifest must stay inside the output directory; a
+//! manifest listing an absolute path or one with `..` isn't loaded.
+//!
+//! [`CodeGenerator::generate_to`]: crate::CodeGenerator::generate_to
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Component, Path};
+
+use serde::{Deserialize, Serialize};
+
+use imortal_core::{EngineError, EngineResult};
+use imortal_ir::NodeId;
+
+use crate::generator::GeneratedProject;
+
+/// Name of the manifest file in an output directory
+pub const MANIFEST_FILE: &str = ".imortal-manifest.json";
+
+/// Hash of a generated file's content, stable across runs and platforms
+pub fn content_hash(content: &str) -> String {
+    let hash = content
+        .bytes()
+        .fold(0xcbf2_9ce4_8422_2325u64, |hash, byte| (hash ^ byte as u64).wrapping_mul(0x100_0000_01b3));
+    format!("{:016x}", hash)
+}
+
+/// A generated file in a [`Manifest`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ManifestEntry {
+    /// [`content_hash`] of the file as written
+    pub hash: String,
+    /// Nodes the file was generated from; empty for project-wide files
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub nodes: Vec<NodeId>,
+}
+
+/// Files generated into an output directory
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Manifest {
+    /// Name of the project generated
+    pub project: String,
+    /// Engine version that generated it
+    pub version: String,
+    /// Generated files by path relative to the output directory
+    pub files: BTreeMap<String, ManifestEntry>,
+}
+
+/// Outcome of removing generated files, each sorted by path
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CleanReport {
+    /// Files removed
+    pub removed: Vec<String>,
+    /// Files kept because they were edited since they were generated
+    pub modified: Vec<String>,
+    /// Files already gone
+    pub missing: Vec<String>,
+}
+
+impl Manifest {
+    /// Manifest of `project` as it would be written
+    pub fn of(project: &GeneratedProject) -> Self {
+        let files = project
+            .files
+            .iter()
+            .map(|(path, content)| {
+                let mut nodes = project.sources.get(path).cloned().unwrap_or_default();
+                nodes.sort();
+                (path.clone(), ManifestEntry { hash: content_hash(content), nodes })
+            })
+            .collect();
+        Self { project: project.name.clone(), version: crate::CODEGEN_VERSION.to_string(), files }
+    }
+
+    /// Manifest of the output directory `dir`, `None` if it has none
+    pub fn load(dir: &Path) -> EngineResult<Option<Self>> {
+        match fs::read_to_string(dir.join(MANIFEST_FILE)) {
+            Ok(json) => {
+                let manifest: Self = serde_json::from_str(&json)?;
+                if let Some(path) = manifest.files.keys().find(|path| !is_inside(path)) {
+                    return Err(EngineError::Deserialization(format!(
+                        "{} in {} lists '{}', which is outside the output directory",
+                        MANIFEST_FILE,
+                        dir.display(),
+                        path
+                    )));
+                }
+                Ok(Some(manifest))
+            }
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Write the manifest into the output directory `dir`
+    pub fn save(&self, dir: &Path) -> EngineResult<()> {
+        fs::create_dir_all(dir)?;
+        fs::write(dir.join(MANIFEST_FILE), serde_json::to_string_pretty(self)? + "\n")?;
+        Ok(())
+    }
+
+    /// Add the files of `other` this manifest doesn't list, e.g. the files
+    /// of nodes a tag-filtered generation left out
+    pub fn keep_missing(&mut self, other: &Manifest) {
+        for (path, entry) in &other.files {
+            self.files.entry(path.clone()).or_insert_with(|| entry.clone());
+        }
+    }
+
+    /// Files in this manifest that `current` no longer generates, such as
+    /// the model of a deleted entity
+    pub fn stale(&self, current: &GeneratedProject) -> Vec<String> {
+        self.files.keys().filter(|path| !current.files.contains_key(*path)).cloned().collect()
+    }
+
+    /// Files generated from `node`
+    pub fn files_of(&self, node: NodeId) -> Vec<&str> {
+        self.files
+            .iter()
+            .filter(|(_, entry)| entry.nodes.contains(&node))
+            .map(|(path, _)| path.as_str())
+            .collect()
+    }
+
+    /// Remove `paths` from `dir` where they still have the content this
+    /// manifest recorded, then any directories left empty
+    pub fn remove(&self, dir: &Path, paths: &[String]) -> EngineResult<CleanReport> {
+        let mut report = CleanReport::default();
+        for path in paths {
+            let Some(entry) = self.files.get(path).filter(|_| is_inside(path)) else {
+                continue;
+            };
+            let file = dir.join(path);
+            match fs::read_to_string(&file) {
+                Ok(content) if content_hash(&content) == entry.hash => {
+                    fs::remove_file(&file)?;
+                    remove_empty_parents(dir, &file);
+                    report.removed.push(path.clone());
+                }
+                Ok(_) => report.modified.push(path.clone()),
+                // Not UTF-8, so not what we wrote
+                Err(e) if e.kind() == ErrorKind::InvalidData => report.modified.push(path.clone()),
+                Err(e) if e.kind() == ErrorKind::NotFound => report.missing.push(path.clone()),
+                Err(e) => return Err(e.into()),
+            }
+        }
+        report.removed.sort();
+        report.modified.sort();
+        report.missing.sort();
+        Ok(report)
+    }
+
+    /// Remove every generated file from `dir`, keeping those edited since
+    ///
+    /// The manifest is removed too, or rewritten to list only the files
+    /// kept.
+    pub fn clean(&self, dir: &Path) -> EngineResult<CleanReport> {
+        let paths: Vec<String> = self.files.keys().cloned().collect();
+        let report = self.remove(dir, &paths)?;
+        if report.modified.is_empty() {
+            match fs::remove_file(dir.join(MANIFEST_FILE)) {
+                Err(e) if e.kind() != ErrorKind::NotFound => return Err(e.into()),
+                _ => {}
+            }
+        } else {
+            let mut kept = self.clone();
+            kept.files.retain(|path, _| report.modified.contains(path));
+            kept.save(dir)?;
+        }
+        Ok(report)
+    }
+}
+
+/// Whether `path` is relative and stays inside the directory it's joined to
+fn is_inside(path: &str) -> bool {
+    let path = Path::new(path);
+    path.components().next().is_some()
+        && path.components().all(|component| matches!(component, Component::Normal(_) | Component::CurDir))
+}
+
+/// Remove the directories above `file` that are empty, up to `dir`
+fn remove_empty_parents(dir: &Path, file: &Path) {
+    let mut parent = file.parent();
+    while let Some(current) = parent {
+        if current == dir || !current.starts_with(dir) || fs::remove_dir(current).is_err() {
+            break;
+        }
+        parent = current.parent();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use imortal_ir::Node;
+
+    #[test]
+    fn test_manifest_stale_and_clean() {
+        let dir = tempfile::tempdir().unwrap();
+        let user = Node::new("data.entity", "User");
+        let mut project = GeneratedProject::new("shop");
+        project.add_file("Cargo.toml", "[package]");
+        project.add_file("src/models/user.rs", "pub struct User;");
+        project.add_file("src/models/post.rs", "pub struct Post;");
+        project.add_source("src/models/user.rs", user.id);
+        for (path, content) in &project.files {
+            fs::create_dir_all(dir.path().join(path).parent().unwrap()).unwrap();
+            fs::write(dir.path().join(path), content).unwrap();
+        }
+        fs::write(dir.path().join("notes.md"), "mine").unwrap();
+
+        let manifest = Manifest::of(&project);
+        manifest.save(dir.path()).unwrap();
+        let manifest = Manifest::load(dir.path()).unwrap().unwrap();
+        assert_eq!(manifest.files_of(user.id), ["src/models/user.rs"]);
+        assert_eq!(manifest.files["src/models/user.rs"].hash, content_hash("pub struct User;"));
+
+        project.files.remove("src/models/post.rs");
+        assert_eq!(manifest.stale(&project), ["src/models/post.rs"]);
+
+        fs::write(dir.path().join("Cargo.toml"), "[package]\nname = \"edited\"").unwrap();
+        let report = manifest.clean(dir.path()).unwrap();
+        assert_eq!(report.removed, ["src/models/post.rs", "src/models/user.rs"]);
+        assert_eq!(report.modified, ["Cargo.toml"]);
+        assert!(dir.path().join("notes.md").exists());
+        assert!(dir.path().join("Cargo.toml").exists());
+        assert!(!dir.path().join("src").exists());
+        assert_eq!(Manifest::load(dir.path()).unwrap().unwrap().files.len(), 1);
+
+        fs::remove_file(dir.path().join("notes.md")).unwrap();
+        fs::remove_file(dir.path().join("Cargo.toml")).unwrap();
+        let report = Manifest::load(dir.path()).unwrap().unwrap().clean(dir.path()).unwrap();
+        assert_eq!(report.missing, ["Cargo.toml"]);
+        assert!(Manifest::load(dir.path()).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_paths_outside_the_output_dir() {
+        let root = tempfile::tempdir().unwrap();
+        let dir = root.path().join("out");
+        fs::create_dir_all(&dir).unwrap();
+        let victim = root.path().join("victim.txt");
+        fs::write(&victim, "keep me").unwrap();
+
+        let entry = ManifestEntry { hash: content_hash("keep me"), nodes: Vec::new() };
+        for path in ["../victim.txt", victim.to_str().unwrap(), "src/../../victim.txt"] {
+            let mut manifest = Manifest::default();
+            manifest.files.insert(path.to_string(), entry.clone());
+
+            // Removing skips it even without loading
+            assert_eq!(manifest.clean(&dir).unwrap(), CleanReport::default());
+            assert!(victim.exists());
+
+            manifest.save(&dir).unwrap();
+            let error = Manifest::load(&dir).unwrap_err().to_string();
+            assert!(error.contains("outside the output directory"), "{}", error);
+        }
+        assert!(is_inside("src/./models/user.rs"));
+        assert!(!is_inside(""));
+    }
+}
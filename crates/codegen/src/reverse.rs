@@ -0,0 +1,397 @@
+//! Reverse import of an existing Rust project (experimental)
+//!
+//! Scans a Cargo project's sources with `syn` and proposes nodes for it:
+//! structs deriving `Serialize` or `Deserialize` become entities, and axum
+//! `.route("/path", get(handler).post(other))` calls become REST endpoints,
+//! one per method. Routers nested inline with `.nest("/api", ...)` get the
+//! prefix. An endpoint whose handler takes or returns a proposed entity
+//! (`Json<User>`, `Json<Vec<User>>`) is connected to it.
+//!
+//! Only what can be read off the syntax is found; routers built in other
+//! functions and nested by name keep their own paths.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use quote::ToTokens;
+use syn::visit::{self, Visit};
+
+use imortal_core::{EngineError, EngineResult, Position};
+use imortal_ir::{Node, ProjectGraph, ProjectMeta};
+
+use crate::rust::to_pascal_case;
+use crate::sync::{doc_comment, field_from_syn};
+
+/// HTTP methods axum routes are declared with
+const METHODS: [&str; 5] = ["get", "post", "put", "patch", "delete"];
+
+/// A serde struct proposed as an entity
+#[derive(Debug, Clone)]
+pub struct ProposedEntity {
+    /// Source file, relative to the scanned folder
+    pub file: String,
+    /// Whether the struct has an `id` field, so is likely stored rather
+    /// than a request or response shape
+    pub has_id: bool,
+    /// The entity node
+    pub node: Node,
+}
+
+/// An axum route proposed as a REST endpoint
+#[derive(Debug, Clone)]
+pub struct ProposedEndpoint {
+    /// Source file, relative to the scanned folder
+    pub file: String,
+    /// Handler function name
+    pub handler: String,
+    /// Entity the handler takes or returns, if one was proposed
+    pub entity: Option<String>,
+    /// The endpoint node
+    pub node: Node,
+}
+
+/// What a scan of a Rust project found
+#[derive(Debug, Clone, Default)]
+pub struct RustImport {
+    pub entities: Vec<ProposedEntity>,
+    pub endpoints: Vec<ProposedEndpoint>,
+    /// Files that didn't parse, with the error
+    pub warnings: Vec<String>,
+}
+
+impl RustImport {
+    /// Scan every `.rs` file under `dir`, skipping `target` and hidden folders
+    pub fn scan(dir: &Path) -> EngineResult<Self> {
+        if !dir.join("Cargo.toml").is_file() {
+            return Err(EngineError::custom(format!("{} is not a Cargo project", dir.display())));
+        }
+        let mut files: Vec<PathBuf> = walkdir::WalkDir::new(dir)
+            .into_iter()
+            .filter_entry(|e| {
+                let name = e.file_name().to_string_lossy();
+                e.depth() == 0 || !(name.starts_with('.') || name == "target")
+            })
+            .filter_map(Result::ok)
+            .map(|e| e.into_path())
+            .filter(|p| p.extension().is_some_and(|ext| ext == "rs"))
+            .collect();
+        files.sort();
+
+        let mut sources = Vec::new();
+        for path in files {
+            let relative = path.strip_prefix(dir).unwrap_or(&path).to_string_lossy().replace('\\', "/");
+            sources.push((relative, fs::read_to_string(&path)?));
+        }
+        Ok(Self::from_sources(sources.iter().map(|(file, source)| (file.as_str(), source.as_str()))))
+    }
+
+    /// Scan sources given as `(file, source)` pairs
+    pub fn from_sources<'a>(sources: impl IntoIterator<Item = (&'a str, &'a str)>) -> Self {
+        let mut import = Self::default();
+        let mut scanner = Scanner::default();
+        for (file, source) in sources {
+            match syn::parse_file(source) {
+                Ok(parsed) => {
+                    scanner.file = file.to_string();
+                    scanner.visit_file(&parsed);
+                }
+                Err(e) => import.warnings.push(format!("{}: {}", file, e)),
+            }
+        }
+
+        for (file, item) in &scanner.structs {
+            let mut node = Node::new_entity(item.ident.to_string());
+            node.description = doc_comment(&item.attrs);
+            let mut has_id = false;
+            for field in item.fields.iter().filter_map(field_from_syn) {
+                if field.name == "id" {
+                    // Keep the default primary key, with the struct's type
+                    has_id = true;
+                    if let Some(id) = node.fields.iter_mut().find(|f| f.name == "id") {
+                        id.data_type = field.data_type;
+                    }
+                } else {
+                    node.add_field(field);
+                }
+            }
+            import.entities.push(ProposedEntity { file: file.clone(), has_id, node });
+        }
+
+        let entity_names: Vec<String> = import.entities.iter().map(|e| e.node.name.clone()).collect();
+        let mut taken: HashMap<String, usize> = HashMap::new();
+        for route in &scanner.routes {
+            let base = to_pascal_case(&route.handler);
+            let count = taken.entry(base.clone()).or_insert(0);
+            *count += 1;
+            let name = if *count == 1 { base } else { format!("{}{}", base, count) };
+
+            let mut node = Node::new_rest_endpoint(name);
+            node.set_config("method", route.method.to_uppercase());
+            node.set_config("path", route.path.clone());
+            let signature = scanner.handlers.get(&route.handler);
+            node.description = signature.and_then(|(_, doc)| doc.clone());
+            let entity = signature.and_then(|(types, _)| {
+                types
+                    .split(|c: char| !(c.is_alphanumeric() || c == '_'))
+                    .find(|word| entity_names.iter().any(|e| e == word))
+                    .map(str::to_string)
+            });
+            import.endpoints.push(ProposedEndpoint { file: route.file.clone(), handler: route.handler.clone(), entity, node });
+        }
+        import
+    }
+
+    /// A project with the proposed entities in one column and the endpoints
+    /// in the next, each endpoint connected to its entity
+    pub fn to_graph(&self, name: &str) -> EngineResult<ProjectGraph> {
+        let mut graph = ProjectGraph::new(ProjectMeta::new(name));
+        let mut entity_ids = HashMap::new();
+        let mut y = 0.0;
+        for entity in &self.entities {
+            let mut node = entity.node.clone();
+            node.position = Position::new(0.0, y);
+            y += node.size.height + 40.0;
+            entity_ids.insert(node.name.clone(), graph.add_node(node));
+        }
+        let mut y = 0.0;
+        for endpoint in &self.endpoints {
+            let mut node = endpoint.node.clone();
+            node.position = Position::new(320.0, y);
+            y += node.size.height + 40.0;
+            let id = graph.add_node(node);
+            if let Some(entity) = endpoint.entity.as_ref().and_then(|e| entity_ids.get(e)) {
+                graph.connect(*entity, "entity", id, "request")?;
+            }
+        }
+        Ok(graph)
+    }
+}
+
+/// A route call found in the source
+struct Route {
+    file: String,
+    method: String,
+    path: String,
+    handler: String,
+}
+
+/// Collects serde structs, routes, and function signatures
+#[derive(Default)]
+struct Scanner {
+    file: String,
+    /// Prefixes of the `.nest` calls being visited
+    prefixes: Vec<String>,
+    structs: Vec<(String, syn::ItemStruct)>,
+    routes: Vec<Route>,
+    /// Function name -> argument and return types as text, and doc comment
+    handlers: HashMap<String, (String, Option<String>)>,
+}
+
+impl Scanner {
+    fn record_handler(&mut self, sig: &syn::Signature, attrs: &[syn::Attribute]) {
+        let mut types: Vec<String> = sig
+            .inputs
+            .iter()
+            .filter_map(|arg| match arg {
+                syn::FnArg::Typed(arg) => Some(arg.ty.to_token_stream().to_string()),
+                syn::FnArg::Receiver(_) => None,
+            })
+            .collect();
+        if let syn::ReturnType::Type(_, ty) = &sig.output {
+            types.push(ty.to_token_stream().to_string());
+        }
+        self.handlers.insert(sig.ident.to_string(), (types.join(" "), doc_comment(attrs)));
+    }
+
+    /// The `(method, handler)` pairs of a method router like
+    /// `get(list).post(create)` or `axum::routing::get(list)`
+    fn method_router(expr: &syn::Expr, found: &mut Vec<(String, String)>) {
+        match expr {
+            syn::Expr::Call(call) => {
+                if let (syn::Expr::Path(func), Some(handler)) = (&*call.func, call.args.first()) {
+                    let method = func.path.segments.last().map(|s| s.ident.to_string()).unwrap_or_default();
+                    if let (true, Some(handler)) = (METHODS.contains(&method.as_str()), handler_name(handler)) {
+                        found.push((method, handler));
+                    }
+                }
+            }
+            syn::Expr::MethodCall(call) => {
+                Self::method_router(&call.receiver, found);
+                let method = call.method.to_string();
+                if let (true, Some(handler)) = (METHODS.contains(&method.as_str()), call.args.first().and_then(handler_name)) {
+                    found.push((method, handler));
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Last segment of a handler path such as `handlers::list_users`
+fn handler_name(expr: &syn::Expr) -> Option<String> {
+    match expr {
+        syn::Expr::Path(path) => path.path.segments.last().map(|s| s.ident.to_string()),
+        _ => None,
+    }
+}
+
+fn string_literal(expr: &syn::Expr) -> Option<String> {
+    match expr {
+        syn::Expr::Lit(syn::ExprLit { lit: syn::Lit::Str(s), .. }) => Some(s.value()),
+        _ => None,
+    }
+}
+
+fn derives_serde(attrs: &[syn::Attribute]) -> bool {
+    attrs.iter().filter(|a| a.path().is_ident("derive")).any(|a| {
+        let mut serde = false;
+        let _ = a.parse_nested_meta(|meta| {
+            let name = meta.path.segments.last().map(|s| s.ident.to_string()).unwrap_or_default();
+            serde |= name == "Serialize" || name == "Deserialize";
+            Ok(())
+        });
+        serde
+    })
+}
+
+impl<'ast> Visit<'ast> for Scanner {
+    fn visit_item_struct(&mut self, item: &'ast syn::ItemStruct) {
+        if matches!(item.fields, syn::Fields::Named(_)) && derives_serde(&item.attrs) {
+            self.structs.push((self.file.clone(), item.clone()));
+        }
+        visit::visit_item_struct(self, item);
+    }
+
+    fn visit_item_fn(&mut self, item: &'ast syn::ItemFn) {
+        self.record_handler(&item.sig, &item.attrs);
+        visit::visit_item_fn(self, item);
+    }
+
+    fn visit_impl_item_fn(&mut self, item: &'ast syn::ImplItemFn) {
+        self.record_handler(&item.sig, &item.attrs);
+        visit::visit_impl_item_fn(self, item);
+    }
+
+    fn visit_expr_method_call(&mut self, call: &'ast syn::ExprMethodCall) {
+        let path = call.args.first().and_then(string_literal);
+        match (call.method.to_string().as_str(), path, call.args.len()) {
+            ("route", Some(path), 2) => {
+                // Earlier routes in the chain are inside the receiver
+                self.visit_expr(&call.receiver);
+                let mut found = Vec::new();
+                Self::method_router(&call.args[1], &mut found);
+                let path = format!("{}{}", self.prefixes.concat(), path);
+                for (method, handler) in found {
+                    self.routes.push(Route { file: self.file.clone(), method, path: path.clone(), handler });
+                }
+                self.visit_expr(&call.args[1]);
+            }
+            ("nest", Some(prefix), 2) => {
+                self.visit_expr(&call.receiver);
+                self.prefixes.push(prefix.trim_end_matches('/').to_string());
+                self.visit_expr(&call.args[1]);
+                self.prefixes.pop();
+            }
+            _ => visit::visit_expr_method_call(self, call),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use imortal_core::DataType;
+
+    const MODELS: &str = r#"
+use serde::{Deserialize, Serialize};
+
+/// A registered user
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct User {
+    pub id: i64,
+    pub email: String,
+    pub nickname: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct CreateUser {
+    pub email: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct NotSerde {
+    pub value: i32,
+}
+"#;
+
+    const ROUTES: &str = r#"
+use axum::{routing::{get, post}, Json, Router};
+
+pub fn app() -> Router {
+    Router::new()
+        .route("/health", get(health))
+        .nest("/api", Router::new()
+            .route("/users", get(handlers::list_users).post(create_user))
+            .route("/users/:id", axum::routing::delete(delete_user)))
+}
+
+async fn health() -> &'static str { "ok" }
+
+/// List every user
+async fn list_users() -> Json<Vec<User>> { todo!() }
+
+async fn create_user(Json(input): Json<CreateUser>) -> Json<User> { todo!() }
+
+async fn delete_user(Path(id): Path<i64>) {}
+"#;
+
+    #[test]
+    fn test_scan_sources() {
+        let import = RustImport::from_sources([("src/models.rs", MODELS), ("src/main.rs", ROUTES), ("src/broken.rs", "fn (")]);
+        assert_eq!(import.warnings.len(), 1);
+        assert!(import.warnings[0].starts_with("src/broken.rs"));
+
+        let entities: Vec<_> = import.entities.iter().map(|e| (e.node.name.as_str(), e.has_id)).collect();
+        assert_eq!(entities, [("User", true), ("CreateUser", false)]);
+        let user = &import.entities[0].node;
+        assert_eq!(user.description.as_deref(), Some("A registered user"));
+        assert_eq!(user.get_field("id").unwrap().data_type, DataType::Int64);
+        assert_eq!(user.fields.iter().filter(|f| f.name == "id").count(), 1);
+        assert!(!user.get_field("nickname").unwrap().required);
+
+        let endpoints: Vec<_> = import
+            .endpoints
+            .iter()
+            .map(|e| (e.node.name.as_str(), e.node.get_config_str("method").unwrap(), e.node.get_config_str("path").unwrap(), e.entity.as_deref()))
+            .collect();
+        assert_eq!(endpoints, [
+            ("Health", "GET", "/health", None),
+            ("ListUsers", "GET", "/api/users", Some("User")),
+            ("CreateUser", "POST", "/api/users", Some("CreateUser")),
+            ("DeleteUser", "DELETE", "/api/users/:id", None),
+        ]);
+        assert_eq!(import.endpoints[1].handler, "list_users");
+        assert_eq!(import.endpoints[1].node.description.as_deref(), Some("List every user"));
+    }
+
+    #[test]
+    fn test_scan_project_to_graph() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(RustImport::scan(dir.path()).is_err());
+        fs::write(dir.path().join("Cargo.toml"), "[package]\nname = \"app\"\n").unwrap();
+        fs::create_dir_all(dir.path().join("src")).unwrap();
+        fs::create_dir_all(dir.path().join("target/debug")).unwrap();
+        fs::write(dir.path().join("src/models.rs"), MODELS).unwrap();
+        fs::write(dir.path().join("src/main.rs"), ROUTES).unwrap();
+        fs::write(dir.path().join("target/debug/build.rs"), MODELS).unwrap();
+
+        let import = RustImport::scan(dir.path()).unwrap();
+        assert_eq!(import.entities.len(), 2);
+        assert_eq!(import.entities[0].file, "src/models.rs");
+
+        let graph = import.to_graph("app").unwrap();
+        assert_eq!(graph.node_count(), 6);
+        assert_eq!(graph.edge_count(), 2);
+    }
+}
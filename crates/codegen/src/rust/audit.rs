@@ -0,0 +1,526 @@
+//! Audit Log Code Generation
+//!
+//! Entities connected to a `data.audit_log` node have their changes recorded
+//! in an audit table: who changed what, and the old and new values. The
+//! changes are recorded by database triggers (PostgreSQL only) or by hooks
+//! the generated repository code calls after each write. The generated
+//! `audit` module also serves the audit trail over HTTP.
+
+use imortal_core::DataType;
+use imortal_ir::{Node, ProjectGraph};
+
+use crate::rust::migrations::{DatabaseBackend, Migration};
+use crate::rust::{module_path, to_snake_case, AuthFramework};
+
+/// How changes to audited entities are recorded
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum AuditStrategy {
+    /// Functions the repository layer calls after each write
+    #[default]
+    Hooks,
+    /// Database triggers on the audited tables
+    Triggers,
+}
+
+impl AuditStrategy {
+    /// Strategies an audit log can use
+    pub const ALL: [AuditStrategy; 2] = [AuditStrategy::Hooks, AuditStrategy::Triggers];
+
+    /// Identifier stored in the node's `strategy` config
+    pub fn id(&self) -> &'static str {
+        match self {
+            AuditStrategy::Hooks => "hooks",
+            AuditStrategy::Triggers => "triggers",
+        }
+    }
+
+    /// Strategy with the given identifier, ignoring case
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|strategy| strategy.id().eq_ignore_ascii_case(id.trim()))
+    }
+}
+
+/// An audited entity: its name, model path, table, and primary key
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuditedEntity {
+    /// Entity name recorded as the `entity_type` of its changes
+    pub name: String,
+    /// Path of the generated model, e.g. `crate::models::billing::Invoice`
+    pub model_path: String,
+    /// Table the entity is stored in
+    pub table: String,
+    /// Primary key field recorded as the `entity_id` of its changes
+    pub key: String,
+}
+
+impl AuditedEntity {
+    fn from_node(node: &Node) -> Self {
+        let mut path = vec!["crate".to_string(), "models".to_string()];
+        path.extend(module_path(node));
+        path.push(node.name.clone());
+        Self {
+            name: node.name.clone(),
+            model_path: path.join("::"),
+            table: to_snake_case(&node.name),
+            key: node
+                .fields
+                .iter()
+                .find(|f| f.is_primary_key())
+                .map_or_else(|| "id".to_string(), |f| f.name.clone()),
+        }
+    }
+}
+
+/// Audit log generated for a project
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuditConfig {
+    /// Table the changes are recorded in
+    pub table_name: String,
+    /// How changes are recorded
+    pub strategy: AuditStrategy,
+    /// Path the audit trail is served on
+    pub endpoint: String,
+    /// Entities whose changes are recorded, sorted by name
+    pub entities: Vec<AuditedEntity>,
+}
+
+impl AuditConfig {
+    /// Audit log of the first `data.audit_log` node in a graph, recording
+    /// the entities connected to it; `None` when nothing is audited
+    pub fn from_graph(graph: &ProjectGraph) -> Option<Self> {
+        let node = graph.find_nodes_by_type("data.audit_log").into_iter().next()?;
+        let mut entities: Vec<AuditedEntity> = graph
+            .edges()
+            .filter(|e| e.connects_to(node.id))
+            .map(|e| if e.from_node == node.id { e.to_node } else { e.from_node })
+            .filter_map(|id| graph.get_node(id))
+            .filter(|n| n.component_type == "data.entity")
+            .map(AuditedEntity::from_node)
+            .collect();
+        entities.sort_by(|a, b| a.name.cmp(&b.name));
+        entities.dedup();
+        if entities.is_empty() {
+            return None;
+        }
+
+        Some(Self {
+            table_name: node
+                .get_config_str("table_name")
+                .filter(|t| !t.trim().is_empty())
+                .unwrap_or("audit_log")
+                .to_string(),
+            strategy: node.get_config_str("strategy").and_then(AuditStrategy::from_id).unwrap_or_default(),
+            endpoint: node
+                .get_config_str("endpoint")
+                .filter(|p| !p.trim().is_empty())
+                .unwrap_or("/audit")
+                .to_string(),
+            entities,
+        })
+    }
+
+    /// Whether changes are recorded by triggers on this backend; other
+    /// backends fall back to repository hooks
+    pub fn uses_triggers(&self, backend: DatabaseBackend) -> bool {
+        self.strategy == AuditStrategy::Triggers && backend == DatabaseBackend::Postgres
+    }
+}
+
+/// Generate the migration creating the audit table, and the triggers
+/// filling it when they are used
+pub fn generate_audit_migration(config: &AuditConfig, backend: DatabaseBackend) -> Migration {
+    let table = &config.table_name;
+    let id_default = match backend {
+        DatabaseBackend::Postgres => " DEFAULT gen_random_uuid()",
+        _ => "",
+    };
+    let mut up = vec![
+        format!(
+            "CREATE TABLE {table} (\n    id {}{id_default} PRIMARY KEY,\n    entity_type {} NOT NULL,\n    entity_id {} NOT NULL,\n    action {} NOT NULL,\n    old_values {},\n    new_values {},\n    changed_at {} DEFAULT {} NOT NULL\n);",
+            backend.sql_type(&DataType::Uuid),
+            backend.sql_type(&DataType::String),
+            backend.sql_type(&DataType::String),
+            backend.sql_type(&DataType::String),
+            backend.sql_type(&DataType::Json),
+            backend.sql_type(&DataType::Json),
+            backend.sql_type(&DataType::DateTime),
+            backend.current_timestamp(),
+        ),
+        format!("CREATE INDEX IF NOT EXISTS idx_{table}_entity ON {table} (entity_type, entity_id);"),
+    ];
+    let mut down = vec![format!("DROP TABLE IF EXISTS {table};")];
+
+    if config.uses_triggers(backend) {
+        up.push(format!(
+            r#"CREATE OR REPLACE FUNCTION {table}_record() RETURNS TRIGGER AS $$
+BEGIN
+    IF TG_OP = 'INSERT' THEN
+        INSERT INTO {table} (entity_type, entity_id, action, new_values)
+        VALUES (TG_ARGV[0], to_jsonb(NEW) ->> TG_ARGV[1], 'create', to_jsonb(NEW));
+        RETURN NEW;
+    ELSIF TG_OP = 'UPDATE' THEN
+        INSERT INTO {table} (entity_type, entity_id, action, old_values, new_values)
+        VALUES (TG_ARGV[0], to_jsonb(NEW) ->> TG_ARGV[1], 'update', to_jsonb(OLD), to_jsonb(NEW));
+        RETURN NEW;
+    ELSE
+        INSERT INTO {table} (entity_type, entity_id, action, old_values)
+        VALUES (TG_ARGV[0], to_jsonb(OLD) ->> TG_ARGV[1], 'delete', to_jsonb(OLD));
+        RETURN OLD;
+    END IF;
+END;
+$$ LANGUAGE plpgsql;"#
+        ));
+        for entity in &config.entities {
+            up.push(format!(
+                "CREATE TRIGGER {table}_{entity_table} AFTER INSERT OR UPDATE OR DELETE ON {entity_table}\n    FOR EACH ROW EXECUTE FUNCTION {table}_record('{name}', '{key}');",
+                entity_table = entity.table,
+                name = entity.name,
+                key = entity.key,
+            ));
+            down.push(format!("DROP TRIGGER IF EXISTS {table}_{} ON {};", entity.table, entity.table));
+        }
+        down.push(format!("DROP FUNCTION IF EXISTS {table}_record();"));
+        // Triggers go before the table they write to
+        down.rotate_left(1);
+    }
+
+    Migration::new("track_entity_changes", up.join("\n\n"), down.join("\n"))
+}
+
+/// Generate audit.rs content: the audit entry model, repository hooks when
+/// triggers don't record the changes, and the audit trail endpoint
+pub fn generate_audit(framework: AuthFramework, backend: DatabaseBackend, config: &AuditConfig) -> String {
+    let mut content = String::from(
+        r#"//! Audit trail of entity changes
+//!
+//! Generated by Immortal Engine
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::DatabasePool;
+
+/// Kind of change recorded in the audit trail
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditAction {
+    Create,
+    Update,
+    Delete,
+}
+
+impl AuditAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuditAction::Create => "create",
+            AuditAction::Update => "update",
+            AuditAction::Delete => "delete",
+        }
+    }
+}
+
+/// One recorded change
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub id: String,
+    pub entity_type: String,
+    pub entity_id: String,
+    pub action: String,
+    pub old_values: Option<serde_json::Value>,
+    pub new_values: Option<serde_json::Value>,
+    pub changed_at: String,
+}
+"#,
+    );
+
+    let names: Vec<String> = config.entities.iter().map(|e| format!("\"{}\"", e.name)).collect();
+    content.push_str(&format!(
+        "\n/// Entities whose changes are recorded\npub const AUDITED_ENTITIES: [&str; {}] = [{}];\n",
+        names.len(),
+        names.join(", "),
+    ));
+
+    if !config.uses_triggers(backend) {
+        content.push_str(&generate_hooks(backend, config));
+    }
+    content.push_str(&generate_query(backend, config));
+    content.push_str(&generate_routes(framework, config));
+    content
+}
+
+/// Bind parameter `n` in the backend's placeholder syntax, with a cast
+fn placeholder(backend: DatabaseBackend, n: usize, cast: &str) -> String {
+    match backend {
+        DatabaseBackend::Postgres if cast.is_empty() => format!("${}", n),
+        DatabaseBackend::Postgres => format!("${}::{}", n, cast),
+        _ => "?".to_string(),
+    }
+}
+
+/// Generate the `Audited` trait, its impls for the audited models, and the
+/// `record_*` hooks the repository layer calls after each write
+fn generate_hooks(backend: DatabaseBackend, config: &AuditConfig) -> String {
+    let values: Vec<String> = ["uuid", "", "", "", "jsonb", "jsonb"]
+        .into_iter()
+        .enumerate()
+        .map(|(i, cast)| placeholder(backend, i + 1, cast))
+        .collect();
+    let mut content = format!(
+        r#"
+/// Models whose changes are recorded in the audit trail
+pub trait Audited: Serialize {{
+    /// Entity name recorded with each change
+    const ENTITY_TYPE: &'static str;
+
+    /// Primary key recorded with each change
+    fn audit_id(&self) -> String;
+}}
+
+/// Record a change; `old` and `new` are the record before and after it
+pub async fn record<T: Audited>(
+    db: &DatabasePool,
+    action: AuditAction,
+    old: Option<&T>,
+    new: Option<&T>,
+) -> Result<(), sqlx::Error> {{
+    let Some(entity_id) = new.or(old).map(Audited::audit_id) else {{
+        return Ok(());
+    }};
+    let json = |record: Option<&T>| record.and_then(|r| serde_json::to_string(r).ok());
+    sqlx::query("INSERT INTO {table} (id, entity_type, entity_id, action, old_values, new_values) VALUES ({values})")
+        .bind(uuid::Uuid::new_v4().to_string())
+        .bind(T::ENTITY_TYPE)
+        .bind(entity_id)
+        .bind(action.as_str())
+        .bind(json(old))
+        .bind(json(new))
+        .execute(db)
+        .await?;
+    Ok(())
+}}
+
+/// Hook for the repository layer after inserting a record
+pub async fn record_create<T: Audited>(db: &DatabasePool, new: &T) -> Result<(), sqlx::Error> {{
+    record(db, AuditAction::Create, None, Some(new)).await
+}}
+
+/// Hook for the repository layer after updating a record
+pub async fn record_update<T: Audited>(db: &DatabasePool, old: &T, new: &T) -> Result<(), sqlx::Error> {{
+    record(db, AuditAction::Update, Some(old), Some(new)).await
+}}
+
+/// Hook for the repository layer after deleting a record
+pub async fn record_delete<T: Audited>(db: &DatabasePool, old: &T) -> Result<(), sqlx::Error> {{
+    record(db, AuditAction::Delete, Some(old), None).await
+}}
+"#,
+        table = config.table_name,
+        values = values.join(", "),
+    );
+
+    for entity in &config.entities {
+        content.push_str(&format!(
+            r#"
+impl Audited for {path} {{
+    const ENTITY_TYPE: &'static str = "{name}";
+
+    fn audit_id(&self) -> String {{
+        self.{key}.to_string()
+    }}
+}}
+"#,
+            path = entity.model_path,
+            name = entity.name,
+            key = entity.key,
+        ));
+    }
+    content
+}
+
+/// Generate `list_entries`, reading the audit trail newest first
+fn generate_query(backend: DatabaseBackend, config: &AuditConfig) -> String {
+    // JSON, UUID, and timestamp columns are read back as text
+    let text = |column: &str| match backend {
+        DatabaseBackend::Postgres => format!("{}::text AS {}", column, column),
+        DatabaseBackend::Mysql => format!("CAST({} AS CHAR) AS {}", column, column),
+        DatabaseBackend::Sqlite => column.to_string(),
+    };
+    let columns = [
+        text("id"),
+        "entity_type".to_string(),
+        "entity_id".to_string(),
+        "action".to_string(),
+        text("old_values"),
+        text("new_values"),
+        text("changed_at"),
+    ];
+    // `?` placeholders are bound in order, so each filter is bound twice
+    let (filters, binds) = match backend {
+        DatabaseBackend::Postgres => (
+            "($1::text IS NULL OR entity_type = $1) AND ($2::text IS NULL OR entity_id = $2) ORDER BY changed_at DESC LIMIT $3",
+            ".bind(&filter.entity_type)\n        .bind(&filter.entity_id)",
+        ),
+        _ => (
+            "(? IS NULL OR entity_type = ?) AND (? IS NULL OR entity_id = ?) ORDER BY changed_at DESC LIMIT ?",
+            ".bind(&filter.entity_type)\n        .bind(&filter.entity_type)\n        .bind(&filter.entity_id)\n        .bind(&filter.entity_id)",
+        ),
+    };
+
+    format!(
+        r#"
+/// Which changes to list
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct AuditFilter {{
+    pub entity_type: Option<String>,
+    pub entity_id: Option<String>,
+    pub limit: Option<i64>,
+}}
+
+type AuditRow = (String, String, String, String, Option<String>, Option<String>, String);
+
+/// Changes matching the filter, newest first
+pub async fn list_entries(db: &DatabasePool, filter: &AuditFilter) -> Result<Vec<AuditEntry>, sqlx::Error> {{
+    let rows: Vec<AuditRow> = sqlx::query_as(
+        "SELECT {columns} FROM {table} WHERE {filters}",
+    )
+        {binds}
+        .bind(filter.limit.unwrap_or(100).clamp(1, 1000))
+        .fetch_all(db)
+        .await?;
+
+    let json = |value: Option<String>| value.and_then(|v| serde_json::from_str(&v).ok());
+    Ok(rows
+        .into_iter()
+        .map(|(id, entity_type, entity_id, action, old_values, new_values, changed_at)| AuditEntry {{
+            id,
+            entity_type,
+            entity_id,
+            action,
+            old_values: json(old_values),
+            new_values: json(new_values),
+            changed_at,
+        }})
+        .collect())
+}}
+"#,
+        columns = columns.join(", "),
+        table = config.table_name,
+    )
+}
+
+/// Generate the audit trail handler and its router (Axum) or route
+/// configuration (Actix)
+fn generate_routes(framework: AuthFramework, config: &AuditConfig) -> String {
+    match framework {
+        AuthFramework::Axum => format!(
+            r#"
+/// List the audit trail, filtered by `entity_type`, `entity_id`, and `limit`
+pub async fn audit_trail(
+    axum::extract::State(state): axum::extract::State<crate::AppState>,
+    axum::extract::Query(filter): axum::extract::Query<AuditFilter>,
+) -> Result<axum::Json<Vec<AuditEntry>>, (axum::http::StatusCode, String)> {{
+    list_entries(&state.db, &filter)
+        .await
+        .map(axum::Json)
+        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+}}
+
+/// Create the router for the audit trail endpoint
+pub fn router() -> axum::Router<crate::AppState> {{
+    axum::Router::new().route("{}", axum::routing::get(audit_trail))
+}}
+"#,
+            config.endpoint,
+        ),
+        AuthFramework::Actix => format!(
+            r#"
+/// List the audit trail, filtered by `entity_type`, `entity_id`, and `limit`
+pub async fn audit_trail(
+    state: actix_web::web::Data<crate::AppState>,
+    filter: actix_web::web::Query<AuditFilter>,
+) -> actix_web::HttpResponse {{
+    match list_entries(&state.db, &filter).await {{
+        Ok(entries) => actix_web::HttpResponse::Ok().json(entries),
+        Err(e) => actix_web::HttpResponse::InternalServerError().body(e.to_string()),
+    }}
+}}
+
+/// Configure the audit trail endpoint
+pub fn configure(cfg: &mut actix_web::web::ServiceConfig) {{
+    cfg.route("{}", actix_web::web::get().to(audit_trail));
+}}
+"#,
+            config.endpoint,
+        ),
+        AuthFramework::Custom => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use imortal_ir::ProjectMeta;
+
+    fn audited_graph(strategy: &str) -> ProjectGraph {
+        let mut graph = ProjectGraph::new(ProjectMeta::new("blog"));
+        let post = graph.add_node(Node::new_entity("Post"));
+        let user = graph.add_node(Node::new_entity("User").with_namespace("accounts"));
+        graph.add_node(Node::new_entity("Tag"));
+        let mut audit = Node::new_audit_log("Audit Log");
+        audit.set_config("strategy", strategy);
+        let audit = graph.add_node(audit);
+        graph.connect(post, "entity", audit, "entities").unwrap();
+        graph.connect(user, "entity", audit, "entities").unwrap();
+        graph
+    }
+
+    #[test]
+    fn test_from_graph() {
+        assert_eq!(AuditConfig::from_graph(&ProjectGraph::new(ProjectMeta::new("empty"))), None);
+
+        let config = AuditConfig::from_graph(&audited_graph("hooks")).unwrap();
+        let names: Vec<&str> = config.entities.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, ["Post", "User"]);
+        assert_eq!(config.entities[1].model_path, "crate::models::accounts::User");
+        assert_eq!((config.table_name.as_str(), config.endpoint.as_str()), ("audit_log", "/audit"));
+        assert!(!config.uses_triggers(DatabaseBackend::Postgres));
+
+        let config = AuditConfig::from_graph(&audited_graph("triggers")).unwrap();
+        assert!(config.uses_triggers(DatabaseBackend::Postgres));
+        assert!(!config.uses_triggers(DatabaseBackend::Sqlite));
+    }
+
+    #[test]
+    fn test_trigger_migration() {
+        let config = AuditConfig::from_graph(&audited_graph("triggers")).unwrap();
+        let migration = generate_audit_migration(&config, DatabaseBackend::Postgres);
+        assert!(migration.up.contains("CREATE TABLE audit_log (\n    id UUID DEFAULT gen_random_uuid() PRIMARY KEY,"));
+        assert!(migration.up.contains("old_values JSONB,"));
+        assert!(migration.up.contains("CREATE TRIGGER audit_log_post AFTER INSERT OR UPDATE OR DELETE ON post"));
+        assert!(migration.up.contains("EXECUTE FUNCTION audit_log_record('User', 'id');"));
+        assert!(migration.down.starts_with("DROP TRIGGER IF EXISTS audit_log_post ON post;"));
+        assert!(migration.down.ends_with("DROP TABLE IF EXISTS audit_log;"));
+
+        let sqlite = generate_audit_migration(&config, DatabaseBackend::Sqlite);
+        assert!(!sqlite.up.contains("TRIGGER"));
+    }
+
+    #[test]
+    fn test_generate_audit_module() {
+        let config = AuditConfig::from_graph(&audited_graph("hooks")).unwrap();
+        let code = generate_audit(AuthFramework::Axum, DatabaseBackend::Postgres, &config);
+        assert!(code.contains("VALUES ($1::uuid, $2, $3, $4, $5::jsonb, $6::jsonb)"));
+        assert!(code.contains("impl Audited for crate::models::Post {"));
+        assert!(code.contains("pub async fn record_update<T: Audited>("));
+        assert!(code.contains("($1::text IS NULL OR entity_type = $1) AND ($2::text IS NULL OR entity_id = $2)"));
+        assert!(code.contains(".route(\"/audit\", axum::routing::get(audit_trail))"));
+
+        let config = AuditConfig::from_graph(&audited_graph("triggers")).unwrap();
+        let code = generate_audit(AuthFramework::Actix, DatabaseBackend::Postgres, &config);
+        assert!(!code.contains("pub trait Audited"));
+        assert!(code.contains("cfg.route(\"/audit\", actix_web::web::get().to(audit_trail));"));
+
+        let code = generate_audit(AuthFramework::Axum, DatabaseBackend::Sqlite, &config);
+        assert!(code.contains("VALUES (?, ?, ?, ?, ?, ?)"));
+        assert!(code.contains("(? IS NULL OR entity_type = ?)"));
+    }
+}
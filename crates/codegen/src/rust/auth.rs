@@ -17,6 +17,36 @@ pub enum AuthFramework {
     Custom,
 }
 
+impl AuthFramework {
+    /// Frameworks a project can target
+    pub const ALL: [AuthFramework; 2] = [AuthFramework::Axum, AuthFramework::Actix];
+
+    /// Identifier stored in `ProjectMeta::target_framework`
+    pub fn id(&self) -> &'static str {
+        match self {
+            AuthFramework::Axum => "axum",
+            AuthFramework::Actix => "actix",
+            AuthFramework::Custom => "custom",
+        }
+    }
+
+    /// Get the display name for this framework
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            AuthFramework::Axum => "Axum",
+            AuthFramework::Actix => "Actix Web",
+            AuthFramework::Custom => "Custom",
+        }
+    }
+
+    /// Framework with the given identifier, ignoring case
+    pub fn from_id(id: &str) -> Option<Self> {
+        [AuthFramework::Axum, AuthFramework::Actix, AuthFramework::Custom]
+            .into_iter()
+            .find(|framework| framework.id().eq_ignore_ascii_case(id.trim()))
+    }
+}
+
 /// Configuration for auth code generation
 #[derive(Debug, Clone)]
 pub struct AuthConfig {
@@ -992,7 +1022,7 @@ impl GeneratedAuth {
         if let Some(ref session) = self.session_code {
             output.push_str("// ========== Session Management ==========\n\n");
             output.push_str(session);
-            output.push_str("\n");
+            output.push('\n');
         }
 
         output
@@ -1084,28 +1114,6 @@ pub fn generate_auth_routes(framework: AuthFramework) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use imortal_ir::{ProjectMeta, ProjectGraph, Node, Field};
-
-    fn create_test_graph() -> ProjectGraph {
-        let meta = ProjectMeta::new("test_app");
-        let mut graph = ProjectGraph::new(meta);
-
-        // Add login node
-        let login = Node::new_login()
-            .with_field(Field::string("email").required())
-            .with_field(Field::string("password").required().secret());
-        graph.add_node(login);
-
-        // Add register node
-        let register = Node::new_register()
-            .with_field(Field::string("email").required())
-            .with_field(Field::string("password").required().secret())
-            .with_field(Field::string("name").required());
-        graph.add_node(register);
-
-        graph
-    }
-
     #[test]
     fn test_auth_config_default() {
         let config = AuthConfig::default();
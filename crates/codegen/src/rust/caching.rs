@@ -0,0 +1,379 @@
+//! Response Caching Code Generation
+//!
+//! A REST endpoint joined to a cache by a caching edge serves reads from
+//! the cache until they expire, and invalidates the cached read of its URL
+//! after a write. The generated `cache` module holds one store per cache
+//! node, in memory or in Redis; handlers render their key from the edge's
+//! key template.
+
+use imortal_ir::{CachePolicy, Node, ProjectGraph};
+
+use crate::rust::{to_pascal_case, to_snake_case, AuthFramework};
+
+/// Where a cache keeps its entries
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum CacheBackend {
+    /// A map in the service's memory
+    #[default]
+    Memory,
+    /// A Redis server shared by every instance of the service
+    Redis,
+}
+
+/// A cache node endpoints store their responses in
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CacheStore {
+    /// Cache node name
+    pub name: String,
+    /// Function returning the store in the generated module
+    pub accessor: String,
+    /// Where the store keeps its entries
+    pub backend: CacheBackend,
+    /// Backend configured on the node, e.g. "memcached", when it isn't
+    /// generated and the store falls back to memory
+    pub unsupported_backend: Option<String>,
+    /// Redis URL used when the environment doesn't set one
+    pub redis_url: String,
+    /// Prefix of every key in the store
+    pub key_prefix: String,
+}
+
+impl CacheStore {
+    fn from_node(node: &Node) -> Self {
+        let configured = node.get_config_str("backend").unwrap_or("memory");
+        let backend = if configured.eq_ignore_ascii_case("redis") { CacheBackend::Redis } else { CacheBackend::Memory };
+        Self {
+            name: node.name.clone(),
+            accessor: to_snake_case(&to_pascal_case(&node.name)),
+            backend,
+            unsupported_backend: (!configured.eq_ignore_ascii_case("memory") && backend == CacheBackend::Memory)
+                .then(|| configured.to_string()),
+            redis_url: node.get_config_str("redis_url").unwrap_or("redis://localhost:6379").to_string(),
+            key_prefix: node.get_config_str("key_prefix").unwrap_or_default().to_string(),
+        }
+    }
+
+    /// Environment variable overriding the Redis URL
+    pub fn redis_url_var(&self) -> String {
+        format!("{}_REDIS_URL", self.accessor.to_uppercase())
+    }
+}
+
+/// Response caching generated for a project
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CachingConfig {
+    /// Caches endpoints store their responses in, sorted by name
+    pub stores: Vec<CacheStore>,
+}
+
+impl CachingConfig {
+    /// Caches a graph's endpoints use; `None` when none cache
+    pub fn from_graph(graph: &ProjectGraph) -> Option<Self> {
+        let mut stores: Vec<CacheStore> = graph
+            .nodes()
+            .filter(|cache| graph.edges().any(|e| e.is_caching() && e.to_node == cache.id))
+            .map(CacheStore::from_node)
+            .collect();
+        stores.sort_by(|a, b| a.name.cmp(&b.name));
+        (!stores.is_empty()).then_some(Self { stores })
+    }
+
+    /// Crates the generated module needs besides serde_json
+    pub fn dependencies(&self) -> Vec<(&'static str, &'static str)> {
+        if self.stores.iter().any(|s| s.backend == CacheBackend::Redis) {
+            vec![("redis", r#"{ version = "0.25", features = ["tokio-comp"] }"#)]
+        } else {
+            Vec::new()
+        }
+    }
+
+    /// Store a cache node generates
+    pub fn store(&self, cache: &Node) -> Option<&CacheStore> {
+        self.stores.iter().find(|s| s.name == cache.name)
+    }
+}
+
+/// Generate cache.rs content: the memory and Redis stores and an accessor
+/// per cache node
+pub fn generate_cache(config: &CachingConfig) -> String {
+    let mut content = String::from(
+        r#"//! Cached endpoint responses
+//!
+//! Generated by Immortal Engine
+
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+/// Responses kept in the service's memory, each until its TTL passes
+#[derive(Default)]
+pub struct MemoryCache {
+    entries: Mutex<HashMap<String, (Instant, serde_json::Value)>>,
+}
+
+impl MemoryCache {
+    /// Cached response under `key`, unless it expired
+    pub async fn get(&self, key: &str) -> Option<serde_json::Value> {
+        let mut entries = self.entries.lock().unwrap();
+        let fresh = entries
+            .get(key)
+            .filter(|(expires, _)| *expires > Instant::now())
+            .map(|(_, value)| value.clone());
+        if fresh.is_none() {
+            entries.remove(key);
+        }
+        fresh
+    }
+
+    /// Cache a response under `key` for `ttl_seconds`
+    pub async fn set(&self, key: &str, value: &serde_json::Value, ttl_seconds: u64) {
+        let expires = Instant::now() + Duration::from_secs(ttl_seconds);
+        self.entries.lock().unwrap().insert(key.to_string(), (expires, value.clone()));
+    }
+
+    /// Drop the response cached under `key`
+    pub async fn invalidate(&self, key: &str) {
+        self.entries.lock().unwrap().remove(key);
+    }
+}
+"#,
+    );
+
+    if config.stores.iter().any(|s| s.backend == CacheBackend::Redis) {
+        content.push_str(
+            r#"
+/// Responses kept in Redis, shared by every instance of the service; an
+/// unreachable server is treated as a cache miss
+pub struct RedisCache {
+    client: redis::Client,
+}
+
+impl RedisCache {
+    /// Store connecting to the Redis server at `url`
+    pub fn new(url: &str) -> Self {
+        Self { client: redis::Client::open(url).expect("invalid Redis URL") }
+    }
+
+    async fn connection(&self) -> Option<redis::aio::MultiplexedConnection> {
+        self.client
+            .get_multiplexed_async_connection()
+            .await
+            .map_err(|e| tracing::warn!("cache unavailable: {}", e))
+            .ok()
+    }
+
+    /// Cached response under `key`, unless it expired
+    pub async fn get(&self, key: &str) -> Option<serde_json::Value> {
+        let mut connection = self.connection().await?;
+        let cached: Option<String> = redis::AsyncCommands::get(&mut connection, key).await.ok()?;
+        serde_json::from_str(&cached?).ok()
+    }
+
+    /// Cache a response under `key` for `ttl_seconds`
+    pub async fn set(&self, key: &str, value: &serde_json::Value, ttl_seconds: u64) {
+        if let Some(mut connection) = self.connection().await {
+            let _: redis::RedisResult<()> =
+                redis::AsyncCommands::set_ex(&mut connection, key, value.to_string(), ttl_seconds).await;
+        }
+    }
+
+    /// Drop the response cached under `key`
+    pub async fn invalidate(&self, key: &str) {
+        if let Some(mut connection) = self.connection().await {
+            let _: redis::RedisResult<()> = redis::AsyncCommands::del(&mut connection, key).await;
+        }
+    }
+}
+"#,
+        );
+    }
+
+    for store in &config.stores {
+        let (store_type, init) = match store.backend {
+            CacheBackend::Memory => ("MemoryCache", "MemoryCache::default".to_string()),
+            CacheBackend::Redis => (
+                "RedisCache",
+                format!(
+                    "|| {{\n        let url = std::env::var(\"{}\").unwrap_or_else(|_| \"{}\".to_string());\n        RedisCache::new(&url)\n    }}",
+                    store.redis_url_var(),
+                    store.redis_url,
+                ),
+            ),
+        };
+        content.push_str(&format!(
+            r#"
+/// {name} store
+pub fn {accessor}() -> &'static {store_type} {{
+    static STORE: OnceLock<{store_type}> = OnceLock::new();
+    STORE.get_or_init({init})
+}}
+"#,
+            name = store.name,
+            accessor = store.accessor,
+        ));
+    }
+
+    content
+}
+
+/// Expression rendering the cache key of a request in a handler: the
+/// store's prefix, the tenant when `tenant` is set, and the key template.
+/// `{method}` is always GET, so a write renders the key of the read of
+/// its URL, which is the one it invalidates.
+fn key_expression(framework: AuthFramework, store: &CacheStore, policy: &CachePolicy, tenant: bool) -> String {
+    let (path, query) = match framework {
+        AuthFramework::Actix => ("req.path()", "req.query_string()"),
+        _ => ("uri.path()", "uri.query().unwrap_or_default()"),
+    };
+    let literal = |text: &str| text.replace('\\', "\\\\").replace('"', "\\\"").replace('{', "{{").replace('}', "}}");
+
+    let mut format = literal(&store.key_prefix);
+    let mut args = Vec::new();
+    if tenant {
+        format.push_str("{}:");
+        args.push("tenant.0");
+    }
+    let mut rest = policy.key_template.as_str();
+    while let Some(open) = rest.find('{') {
+        let close = rest[open..].find('}').map_or(rest.len(), |at| open + at);
+        format.push_str(&literal(&rest[..open]));
+        match &rest[open + 1..close] {
+            "method" => format.push_str("GET"),
+            "path" => {
+                format.push_str("{}");
+                args.push(path);
+            }
+            "query" => {
+                format.push_str("{}");
+                args.push(query);
+            }
+            // Validation rejects other placeholders
+            _ => {}
+        }
+        rest = rest.get(close + 1..).unwrap_or_default();
+    }
+    format.push_str(&literal(rest));
+
+    if args.is_empty() {
+        format!("\"{}\".to_string()", format.replace("{{", "{").replace("}}", "}"))
+    } else {
+        format!("format!(\"{}\", {})", format, args.join(", "))
+    }
+}
+
+/// Parameter handing a caching handler the request URL its key is
+/// rendered from
+pub fn cache_param(framework: AuthFramework) -> &'static str {
+    match framework {
+        AuthFramework::Axum => "\n    uri: axum::http::Uri,",
+        AuthFramework::Actix => "\n    req: actix_web::HttpRequest,",
+        AuthFramework::Custom => "",
+    }
+}
+
+/// Body of a handler caching `response` in `store`: reads are served from
+/// the cache and stored on a miss, writes invalidate the read of their URL
+pub fn cached_handler_body(
+    framework: AuthFramework,
+    store: &CacheStore,
+    policy: &CachePolicy,
+    method: &str,
+    tenant: bool,
+    response: &str,
+) -> String {
+    let respond = |value: &str| match framework {
+        AuthFramework::Actix => format!("HttpResponse::Ok().json({})", value),
+        _ => format!("Json({})", value),
+    };
+    let store_call = format!("crate::cache::{}()", store.accessor);
+
+    if matches!(method.to_ascii_uppercase().as_str(), "GET" | "HEAD") {
+        format!(
+            r#"    let cache_key = {key};
+    if let Some(cached) = {store_call}.get(&cache_key).await {{
+        return Ok({cached});
+    }}
+
+    // TODO: Implement handler logic
+    let response = {response};
+    {store_call}.set(&cache_key, &response, {ttl}).await;
+    Ok({respond})
+"#,
+            key = key_expression(framework, store, policy, tenant),
+            cached = respond("cached"),
+            ttl = policy.ttl_seconds,
+            respond = respond("response"),
+        )
+    } else {
+        format!(
+            r#"    // TODO: Implement handler logic
+    let response = {response};
+    {store_call}.invalidate(&{key}).await;
+    Ok({respond})
+"#,
+            key = key_expression(framework, store, policy, tenant),
+            respond = respond("response"),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn store(backend: &str) -> CacheStore {
+        CacheStore::from_node(&Node::new_cache("Product Cache").with_config("backend", backend).with_config("key_prefix", "shop:"))
+    }
+
+    #[test]
+    fn test_cache_stores() {
+        let mut graph = ProjectGraph::with_name("shop");
+        let endpoint = graph.add_node(Node::new_rest_endpoint("products"));
+        let cache = graph.add_node(Node::new_cache("Product Cache").with_config("backend", "memcached"));
+        graph.add_node(Node::new_cache("Unused Cache"));
+        assert!(CachingConfig::from_graph(&graph).is_none());
+
+        graph.set_cache(endpoint, Some((cache, CachePolicy::default()))).unwrap();
+        let config = CachingConfig::from_graph(&graph).unwrap();
+        assert_eq!(config.stores.len(), 1);
+        assert_eq!(config.stores[0].accessor, "product_cache");
+        assert_eq!(config.stores[0].backend, CacheBackend::Memory);
+        assert_eq!(config.stores[0].unsupported_backend.as_deref(), Some("memcached"));
+        assert!(config.dependencies().is_empty());
+        assert_eq!(store("redis").redis_url_var(), "PRODUCT_CACHE_REDIS_URL");
+    }
+
+    #[test]
+    fn test_generate_cache() {
+        let code = generate_cache(&CachingConfig { stores: vec![store("memory")] });
+        assert!(code.contains("pub struct MemoryCache {"));
+        assert!(code.contains("pub fn product_cache() -> &'static MemoryCache {"));
+        assert!(!code.contains("RedisCache"));
+
+        let config = CachingConfig { stores: vec![store("redis")] };
+        let code = generate_cache(&config);
+        assert!(code.contains("pub fn product_cache() -> &'static RedisCache {"));
+        assert!(code.contains("std::env::var(\"PRODUCT_CACHE_REDIS_URL\")"));
+        assert_eq!(config.dependencies()[0].0, "redis");
+    }
+
+    #[test]
+    fn test_cached_handler_body() {
+        let policy = CachePolicy::new(60, "{method} {path}?{query}");
+        let body = cached_handler_body(AuthFramework::Axum, &store("memory"), &policy, "GET", false, "serde_json::json!({})");
+        assert!(body.contains(
+            "let cache_key = format!(\"shop:GET {}?{}\", uri.path(), uri.query().unwrap_or_default());"
+        ));
+        assert!(body.contains("return Ok(Json(cached));"));
+        assert!(body.contains("crate::cache::product_cache().set(&cache_key, &response, 60).await;"));
+
+        let body = cached_handler_body(AuthFramework::Actix, &store("memory"), &policy, "DELETE", true, "serde_json::json!({})");
+        assert!(body.contains(
+            "crate::cache::product_cache().invalidate(&format!(\"shop:{}:GET {}?{}\", tenant.0, req.path(), req.query_string())).await;"
+        ));
+        assert!(body.contains("Ok(HttpResponse::Ok().json(response))"));
+
+        let key = key_expression(AuthFramework::Axum, &store("memory"), &CachePolicy::new(60, "all \"products\" {method}"), false);
+        assert_eq!(key, "\"shop:all \\\"products\\\" GET\".to_string()");
+    }
+}
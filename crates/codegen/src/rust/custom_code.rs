@@ -0,0 +1,107 @@
+//! Custom Code Generation
+//!
+//! Each `logic.custom_code` node becomes a function in a generated
+//! `custom_code` module. The node's data inputs are its parameters and its
+//! data outputs its return type, and the user's snippet is the body,
+//! copied as written.
+
+use imortal_core::DataType;
+use imortal_ir::Node;
+
+use crate::rust::{to_pascal_case, to_snake_case};
+
+/// Rust type of a port; ports of type Any carry JSON
+fn port_type(data_type: &DataType) -> String {
+    data_type.to_rust_type().replace("Box<dyn std::any::Any>", "serde_json::Value")
+}
+
+/// Signature of the function generated for a custom code node, e.g.
+/// `pub fn apply_discount(total: f64) -> f64`
+pub fn custom_code_signature(node: &Node) -> String {
+    let params: Vec<String> = node
+        .ports
+        .inputs
+        .iter()
+        .filter(|p| p.is_data())
+        .map(|p| format!("{}: {}", p.id, port_type(&p.data_type)))
+        .collect();
+    let outputs: Vec<String> = node.ports.outputs.iter().filter(|p| p.is_data()).map(|p| port_type(&p.data_type)).collect();
+    let returns = match outputs.as_slice() {
+        [] => String::new(),
+        [single] => format!(" -> {}", single),
+        several => format!(" -> ({})", several.join(", ")),
+    };
+    format!("pub fn {}({}){}", to_snake_case(&to_pascal_case(&node.name)), params.join(", "), returns)
+}
+
+/// Generate custom_code.rs content: one function per custom code node
+pub fn generate_custom_code(nodes: &[&Node], has_models: bool) -> String {
+    let mut content = String::from(
+        "//! Custom code\n//!\n//! Generated by Immortal Engine from the project's custom code nodes\n\n#![allow(unused_imports)]\n\n",
+    );
+    if has_models {
+        content.push_str("use crate::models::*;\n\n");
+    }
+
+    let mut nodes = nodes.to_vec();
+    nodes.sort_by(|a, b| a.name.cmp(&b.name));
+    for node in nodes {
+        let doc = node.description.as_deref().filter(|d| !d.trim().is_empty()).unwrap_or(&node.name);
+        for line in doc.lines() {
+            content.push_str(&format!("/// {}\n", line).replace("/// \n", "///\n"));
+        }
+        content.push_str(&custom_code_signature(node));
+        content.push_str(" {\n");
+        for line in node.custom_code().unwrap_or_default().trim_end().lines() {
+            if line.trim().is_empty() {
+                content.push('\n');
+            } else {
+                content.push_str(&format!("    {}\n", line));
+            }
+        }
+        content.push_str("}\n\n");
+    }
+    content.truncate(content.trim_end().len());
+    content.push('\n');
+    content
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use imortal_core::PortDirection;
+
+    #[test]
+    fn test_custom_code_signature() {
+        let mut node = Node::new_custom_code("Apply Discount");
+        assert_eq!(
+            custom_code_signature(&node),
+            "pub fn apply_discount(input: serde_json::Value) -> serde_json::Value"
+        );
+
+        node.ports.remove("input").unwrap();
+        node.ports.remove("output").unwrap();
+        assert_eq!(custom_code_signature(&node), "pub fn apply_discount()");
+
+        node.add_custom_port(PortDirection::Input, "total", DataType::Float64).unwrap();
+        node.add_custom_port(PortDirection::Input, "customer", DataType::Entity("Customer".to_string())).unwrap();
+        node.add_custom_port(PortDirection::Input, "run", DataType::Trigger).unwrap();
+        node.add_custom_port(PortDirection::Output, "total", DataType::Float64).unwrap();
+        assert_eq!(custom_code_signature(&node), "pub fn apply_discount(total: f64, customer: Customer) -> f64");
+        node.add_custom_port(PortDirection::Output, "tags", DataType::Array(Box::new(DataType::Any))).unwrap();
+        assert!(custom_code_signature(&node).ends_with(" -> (f64, Vec<serde_json::Value>)"));
+    }
+
+    #[test]
+    fn test_generate_custom_code() {
+        let mut node = Node::new_custom_code("Double");
+        node.set_config("code", "let doubled = input.as_f64().unwrap_or(0.0) * 2.0;\n\nserde_json::json!(doubled)");
+        let content = generate_custom_code(&[&node], true);
+        assert!(content.contains("use crate::models::*;"));
+        assert!(content.contains(
+            "/// Double\npub fn double(input: serde_json::Value) -> serde_json::Value {\n    let doubled = input.as_f64().unwrap_or(0.0) * 2.0;\n\n    serde_json::json!(doubled)\n}\n"
+        ));
+        syn::parse_file(&content).unwrap();
+        assert!(!generate_custom_code(&[&node], false).contains("crate::models"));
+    }
+}
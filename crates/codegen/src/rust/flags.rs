@@ -0,0 +1,395 @@
+//! Feature Flag Code Generation
+//!
+//! Flags of `logic.feature_flag` nodes become fields of a generated
+//! `FeatureFlags` struct, read from the node's provider. Handlers of API
+//! nodes behind a flag check it before anything else and answer
+//! `404 Not Found` while it's off.
+
+use imortal_core::DataType;
+use imortal_ir::{FeatureFlag, ProjectGraph};
+
+use crate::rust::config::AppSetting;
+use crate::rust::{to_pascal_case, AuthFramework};
+
+/// Where the generated service reads its flags from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum FlagProvider {
+    /// `FEATURE_<NAME>` environment variables, read on every check
+    #[default]
+    Env,
+    /// `FEATURE_<NAME>` settings of the generated `Config`
+    Config,
+    /// An Unleash server, polled for the flags
+    Unleash,
+}
+
+impl FlagProvider {
+    /// Providers a feature flag node can use
+    pub const ALL: [FlagProvider; 3] = [FlagProvider::Env, FlagProvider::Config, FlagProvider::Unleash];
+
+    /// Identifier stored in the node's `provider` config
+    pub fn id(&self) -> &'static str {
+        match self {
+            FlagProvider::Env => "env",
+            FlagProvider::Config => "config",
+            FlagProvider::Unleash => "unleash",
+        }
+    }
+
+    /// Provider with the given identifier, ignoring case
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|provider| provider.id().eq_ignore_ascii_case(id.trim()))
+    }
+}
+
+/// Feature flags generated for a project
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FeatureFlagsConfig {
+    /// Provider the flags are read from
+    pub provider: FlagProvider,
+    /// Flags of every feature flag node, in definition order
+    pub flags: Vec<FeatureFlag>,
+}
+
+impl FeatureFlagsConfig {
+    /// Flags of the graph's feature flag nodes, read from the provider of
+    /// the first; `None` when no node defines a usable flag
+    pub fn from_graph(graph: &ProjectGraph) -> Option<Self> {
+        let nodes = graph.find_nodes_by_type("logic.feature_flag");
+        let mut flags: Vec<FeatureFlag> = Vec::new();
+        for flag in nodes.iter().flat_map(|n| n.feature_flags()) {
+            if flag.has_valid_name() && !flags.iter().any(|f| f.name == flag.name) {
+                flags.push(flag);
+            }
+        }
+        if flags.is_empty() {
+            return None;
+        }
+        Some(Self {
+            provider: nodes
+                .first()
+                .and_then(|n| n.get_config_str("provider"))
+                .and_then(FlagProvider::from_id)
+                .unwrap_or_default(),
+            flags,
+        })
+    }
+
+    /// Settings the generated module reads, written to `.env.example`
+    pub fn settings(&self) -> Vec<AppSetting> {
+        match self.provider {
+            FlagProvider::Env => Vec::new(),
+            FlagProvider::Config => self
+                .flags
+                .iter()
+                .map(|flag| {
+                    AppSetting::new(format!("feature_{}", flag.name), DataType::Bool, env_var(flag))
+                        .with_default(flag.enabled)
+                        .with_description(format!("Whether the {} feature is on", flag.name))
+                        .in_group("Feature Flags")
+                })
+                .collect(),
+            FlagProvider::Unleash => vec![
+                AppSetting::new("unleash_url", DataType::String, "UNLEASH_URL")
+                    .with_default("http://localhost:4242/api")
+                    .with_description("Unleash API the feature flags are read from")
+                    .in_group("Feature Flags"),
+                AppSetting::new("unleash_api_token", DataType::String, "UNLEASH_API_TOKEN")
+                    .required()
+                    .secret()
+                    .with_description("Unleash client API token")
+                    .in_group("Feature Flags"),
+            ],
+        }
+    }
+
+    /// Crates the generated module needs
+    pub fn dependencies(&self) -> Vec<(&'static str, &'static str)> {
+        match self.provider {
+            FlagProvider::Unleash => vec![("reqwest", r#"{ version = "0.12", features = ["json"] }"#)],
+            FlagProvider::Env | FlagProvider::Config => Vec::new(),
+        }
+    }
+}
+
+/// Environment variable of a flag, e.g. `FEATURE_NEW_CHECKOUT`
+fn env_var(flag: &FeatureFlag) -> String {
+    format!("FEATURE_{}", flag.name.to_ascii_uppercase())
+}
+
+/// First statement of a handler behind a flag, answering `404 Not Found`
+/// while the flag is off
+pub fn flag_guard(flag: &str) -> String {
+    format!(
+        "    crate::flags::require(crate::flags::Flag::{}, &state.config).await?;\n",
+        to_pascal_case(flag),
+    )
+}
+
+/// Generate flags.rs content: the `Flag` enum, the typed `FeatureFlags`
+/// struct, the provider reading it, and the guard handlers call
+pub fn generate_flags(framework: AuthFramework, config: &FeatureFlagsConfig) -> String {
+    let variants: Vec<String> = config.flags.iter().map(|f| to_pascal_case(&f.name)).collect();
+    let list = |line: &dyn Fn(&FeatureFlag, &str) -> String| -> String {
+        config.flags.iter().zip(&variants).map(|(flag, variant)| line(flag, variant)).collect()
+    };
+
+    let mut content = format!(
+        r#"//! Feature flags
+//!
+//! Generated by Immortal Engine
+
+use crate::error::AppError;
+
+/// A feature flag
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Flag {{
+{variants}}}
+
+impl Flag {{
+    /// Every flag
+    pub const ALL: &'static [Flag] = &[{all}];
+
+    /// Name of the flag
+    pub fn name(&self) -> &'static str {{
+        match self {{
+{names}        }}
+    }}
+
+    /// Flag with the given name
+    pub fn from_name(name: &str) -> Option<Self> {{
+        Self::ALL.iter().copied().find(|flag| flag.name() == name)
+    }}
+}}
+
+/// State of every feature flag
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+pub struct FeatureFlags {{
+{fields}}}
+
+impl Default for FeatureFlags {{
+    /// Each flag in its default state
+    fn default() -> Self {{
+        Self {{
+{defaults}        }}
+    }}
+}}
+
+impl FeatureFlags {{
+    /// Whether a flag is on
+    pub fn is_enabled(&self, flag: Flag) -> bool {{
+        match flag {{
+{reads}        }}
+    }}
+
+    /// Turn a flag on or off
+    pub fn set(&mut self, flag: Flag, enabled: bool) {{
+        match flag {{
+{writes}        }}
+    }}
+}}
+
+/// Answer `404 Not Found` unless a flag is on, hiding what's behind it
+pub async fn require(flag: Flag, config: &crate::config::Config) -> Result<(), AppError> {{
+    if current(config).await.is_enabled(flag) {{
+        Ok(())
+    }} else {{
+        Err(AppError::NotFound(format!("{{}} is not available", flag.name())))
+    }}
+}}
+"#,
+        variants = list(&|flag, variant| format!("    /// `{}`\n    {},\n", flag.name, variant)),
+        all = variants.iter().map(|v| format!("Flag::{}", v)).collect::<Vec<_>>().join(", "),
+        names = list(&|flag, variant| format!("            Flag::{} => {:?},\n", variant, flag.name)),
+        fields = list(&|flag, _| format!("    pub {}: bool,\n", flag.name)),
+        defaults = list(&|flag, _| format!("            {}: {},\n", flag.name, flag.enabled)),
+        reads = list(&|flag, variant| format!("            Flag::{} => self.{},\n", variant, flag.name)),
+        writes = list(&|flag, variant| format!("            Flag::{} => self.{} = enabled,\n", variant, flag.name)),
+    );
+
+    content.push_str(&match config.provider {
+        FlagProvider::Env => format!(
+            r#"
+impl Flag {{
+    /// Environment variable turning the flag on or off
+    pub fn env_var(&self) -> &'static str {{
+        match self {{
+{env_vars}        }}
+    }}
+}}
+
+/// Current flags, read from their `FEATURE_<NAME>` environment variables
+/// so they can be flipped without a rebuild
+pub async fn current(_config: &crate::config::Config) -> FeatureFlags {{
+    let mut flags = FeatureFlags::default();
+    for flag in Flag::ALL {{
+        let state = std::env::var(flag.env_var()).ok().map(|value| value.trim().to_ascii_lowercase());
+        match state.as_deref() {{
+            Some("1" | "true" | "on" | "yes") => flags.set(*flag, true),
+            Some("0" | "false" | "off" | "no") => flags.set(*flag, false),
+            _ => {{}}
+        }}
+    }}
+    flags
+}}
+"#,
+            env_vars = list(&|flag, variant| format!("            Flag::{} => {:?},\n", variant, env_var(flag))),
+        ),
+        FlagProvider::Config => format!(
+            r#"
+/// Current flags, from the `FEATURE_<NAME>` settings read at startup
+pub async fn current(config: &crate::config::Config) -> FeatureFlags {{
+    FeatureFlags {{
+{fields}    }}
+}}
+"#,
+            fields = list(&|flag, _| format!("        {name}: config.feature_{name},\n", name = flag.name)),
+        ),
+        FlagProvider::Unleash => r#"
+/// Seconds the flags fetched from Unleash are used before fetching them again
+const REFRESH_SECONDS: u64 = 15;
+
+/// Flags last fetched from Unleash, and when
+static CACHE: tokio::sync::RwLock<Option<(std::time::Instant, FeatureFlags)>> = tokio::sync::RwLock::const_new(None);
+
+#[derive(serde::Deserialize)]
+struct Features {
+    features: Vec<Feature>,
+}
+
+#[derive(serde::Deserialize)]
+struct Feature {
+    name: String,
+    enabled: bool,
+}
+
+/// Current flags, fetched from Unleash at most every `REFRESH_SECONDS`;
+/// while it's unreachable flags keep their last known state
+pub async fn current(config: &crate::config::Config) -> FeatureFlags {
+    let cached = *CACHE.read().await;
+    if let Some((fetched, flags)) = cached {
+        if fetched.elapsed().as_secs() < REFRESH_SECONDS {
+            return flags;
+        }
+    }
+
+    let flags = match fetch(config).await {
+        Ok(flags) => flags,
+        Err(e) => {
+            tracing::warn!(error = %e, "fetching feature flags from Unleash failed");
+            cached.map_or_else(FeatureFlags::default, |(_, flags)| flags)
+        }
+    };
+    *CACHE.write().await = Some((std::time::Instant::now(), flags));
+    flags
+}
+
+/// Fetch the flags from the Unleash client API
+async fn fetch(config: &crate::config::Config) -> Result<FeatureFlags, reqwest::Error> {
+    let features: Features = reqwest::Client::new()
+        .get(format!("{}/client/features", config.unleash_url.trim_end_matches('/')))
+        .header("Authorization", &config.unleash_api_token)
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+
+    let mut flags = FeatureFlags::default();
+    for feature in features.features {
+        if let Some(flag) = Flag::from_name(&feature.name) {
+            flags.set(flag, feature.enabled);
+        }
+    }
+    Ok(flags)
+}
+"#
+        .to_string(),
+    });
+
+    // The flags are also served, so clients can hide what's turned off
+    content.push_str(match framework {
+        AuthFramework::Axum => {
+            r#"
+/// Get the current flags
+async fn list_flags(
+    axum::extract::State(state): axum::extract::State<crate::AppState>,
+) -> axum::Json<FeatureFlags> {
+    axum::Json(current(&state.config).await)
+}
+
+/// Routes of the flags endpoint
+pub fn router() -> axum::Router<crate::AppState> {
+    axum::Router::new().route("/flags", axum::routing::get(list_flags))
+}
+"#
+        }
+        AuthFramework::Actix => {
+            r#"
+/// Get the current flags
+async fn list_flags(state: actix_web::web::Data<crate::AppState>) -> actix_web::HttpResponse {
+    actix_web::HttpResponse::Ok().json(current(&state.config).await)
+}
+
+/// Register the flags endpoint
+pub fn configure(cfg: &mut actix_web::web::ServiceConfig) {
+    cfg.route("/flags", actix_web::web::get().to(list_flags));
+}
+"#
+        }
+        AuthFramework::Custom => "",
+    });
+
+    content
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use imortal_ir::{Node, ProjectMeta};
+
+    fn shop(provider: &str) -> ProjectGraph {
+        let mut graph = ProjectGraph::new(ProjectMeta::new("shop"));
+        let mut flags = Node::new_feature_flag("Flags");
+        flags.set_config("flags", "new_checkout=on, beta_search, Bad Name");
+        flags.set_config("provider", provider);
+        graph.add_node(flags);
+        graph
+    }
+
+    #[test]
+    fn test_config_from_graph() {
+        let config = FeatureFlagsConfig::from_graph(&shop("unleash")).unwrap();
+
+        assert_eq!(config.provider, FlagProvider::Unleash);
+        assert_eq!(config.flags.len(), 2);
+        assert_eq!(config.dependencies().len(), 1);
+        assert!(config.settings().iter().any(|s| s.env_var == "UNLEASH_API_TOKEN" && s.secret));
+
+        let config = FeatureFlagsConfig::from_graph(&shop("config")).unwrap();
+        let settings = config.settings();
+        assert_eq!(settings.len(), 2);
+        assert_eq!(settings[0].env_var, "FEATURE_NEW_CHECKOUT");
+        assert!(FeatureFlagsConfig::from_graph(&ProjectGraph::new(ProjectMeta::new("empty"))).is_none());
+    }
+
+    #[test]
+    fn test_generate_flags() {
+        let config = FeatureFlagsConfig::from_graph(&shop("env")).unwrap();
+
+        let code = generate_flags(AuthFramework::Axum, &config);
+        assert!(code.contains("pub const ALL: &'static [Flag] = &[Flag::NewCheckout, Flag::BetaSearch];"));
+        assert!(code.contains("            new_checkout: true,\n"));
+        assert!(code.contains("Flag::BetaSearch => \"FEATURE_BETA_SEARCH\","));
+        assert!(code.contains("pub fn router() -> axum::Router<crate::AppState>"));
+
+        let config = FeatureFlagsConfig::from_graph(&shop("config")).unwrap();
+        let code = generate_flags(AuthFramework::Actix, &config);
+        assert!(code.contains("        beta_search: config.feature_beta_search,\n"));
+        assert!(code.contains("pub fn configure(cfg: &mut actix_web::web::ServiceConfig)"));
+        assert_eq!(
+            flag_guard("new_checkout"),
+            "    crate::flags::require(crate::flags::Flag::NewCheckout, &state.config).await?;\n"
+        );
+    }
+}
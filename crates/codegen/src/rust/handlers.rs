@@ -3,7 +3,7 @@
 //! This module provides utilities for generating Rust handler functions
 //! from API and route components.
 
-use imortal_ir::Node;
+use imortal_ir::{ApiVersioning, Node};
 use quote::{quote, format_ident};
 use proc_macro2::TokenStream;
 
@@ -13,11 +13,7 @@ pub fn generate_rest_handler(node: &Node) -> String {
 
     // Get configuration
     let method = node.get_config_str("method").unwrap_or("GET");
-    let path = node.fields.iter()
-        .find(|f| f.name == "path")
-        .and_then(|f| f.default_value.as_ref())
-        .and_then(|v| v.as_str())
-        .unwrap_or("/");
+    let path = node.endpoint_path().unwrap_or("/");
 
     let tokens = match method.to_uppercase().as_str() {
         "GET" => generate_get_handler(&handler_name, path),
@@ -74,26 +70,41 @@ fn generate_delete_handler(name: &proc_macro2::Ident, _path: &str) -> TokenStrea
 }
 
 /// Generate router configuration for all API endpoints
-pub fn generate_router(nodes: &[&Node]) -> String {
+///
+/// Routes are prefixed with the endpoint's namespace and then according to
+/// the project's API versioning, e.g. `/api/v1/billing/invoices`. Handlers
+/// of namespaced endpoints are referred to through their module path.
+pub fn generate_router(nodes: &[&Node], versioning: &ApiVersioning) -> String {
     let routes: Vec<TokenStream> = nodes.iter()
         .filter(|n| n.component_type == "api.rest")
         .map(|node| {
-            let handler_name = format_ident!("{}", to_snake_case(&node.name));
+            let modules: Vec<String> = node.namespace.iter().map(|segment| to_snake_case(segment)).collect();
+            let module_idents = modules.iter().map(|module| format_ident!("{}", module));
+            let handler = format_ident!("{}", to_snake_case(&node.name));
+            let handler_name = quote! { #(#module_idents ::)* #handler };
             let method = node.get_config_str("method").unwrap_or("GET");
-            let path = node.fields.iter()
-                .find(|f| f.name == "path")
-                .and_then(|f| f.default_value.as_ref())
-                .and_then(|v| v.as_str())
-                .unwrap_or("/");
-
-            match method.to_uppercase().as_str() {
-                "GET" => quote! { .route(#path, axum::routing::get(#handler_name)) },
-                "POST" => quote! { .route(#path, axum::routing::post(#handler_name)) },
-                "PUT" => quote! { .route(#path, axum::routing::put(#handler_name)) },
-                "DELETE" => quote! { .route(#path, axum::routing::delete(#handler_name)) },
-                "PATCH" => quote! { .route(#path, axum::routing::patch(#handler_name)) },
-                _ => quote! { .route(#path, axum::routing::get(#handler_name)) },
-            }
+            let endpoint = node.endpoint_path().unwrap_or("/").trim_start_matches('/');
+            let prefixed = if endpoint.is_empty() {
+                format!("/{}", modules.join("/"))
+            } else if modules.is_empty() {
+                format!("/{}", endpoint)
+            } else {
+                format!("/{}/{}", modules.join("/"), endpoint)
+            };
+            let path = versioning.route(&prefixed, node.endpoint_version());
+
+            let method_router = match method.to_uppercase().as_str() {
+                "POST" => quote! { axum::routing::post(#handler_name) },
+                "PUT" => quote! { axum::routing::put(#handler_name) },
+                "DELETE" => quote! { axum::routing::delete(#handler_name) },
+                "PATCH" => quote! { axum::routing::patch(#handler_name) },
+                _ => quote! { axum::routing::get(#handler_name) },
+            };
+            // Middleware the node names wraps just this route
+            let layers = node.codegen.middleware.iter()
+                .filter_map(|middleware| syn::parse_str::<syn::Path>(middleware.trim()).ok())
+                .map(|middleware| quote! { .layer(axum::middleware::from_fn(#middleware)) });
+            quote! { .route(#path, #method_router #(#layers)*) }
         })
         .collect();
 
@@ -141,4 +152,50 @@ mod tests {
         assert_eq!(to_snake_case("createUser"), "create_user");
         assert_eq!(to_snake_case("delete-item"), "delete_item");
     }
+
+    #[test]
+    fn test_generate_router_versioned() {
+        let mut users = Node::new_rest_endpoint("list_users");
+        users.set_config("path", "/users");
+        let mut users_v2 = Node::new_rest_endpoint("list_users_v2");
+        users_v2.set_config("path", "/users");
+        users_v2.set_config("version", "v2");
+
+        let router = generate_router(&[&users, &users_v2], &ApiVersioning::new("v1"));
+        assert!(router.contains("\"/api/v1/users\""));
+        assert!(router.contains("\"/api/v2/users\""));
+
+        let router = generate_router(&[&users], &ApiVersioning::default());
+        assert!(router.contains("\"/users\""));
+    }
+
+    #[test]
+    fn test_generate_router_middleware() {
+        let mut admin = Node::new_rest_endpoint("delete_user");
+        admin.set_config("method", "DELETE");
+        admin.set_config("path", "/users/:id");
+        admin.codegen.middleware = vec!["crate::middleware::require_admin".to_string(), "not valid".to_string()];
+        let public = Node::new_rest_endpoint("health");
+
+        let router = generate_router(&[&admin, &public], &ApiVersioning::default());
+        assert!(
+            router.contains("axum :: routing :: delete (delete_user) . layer (axum :: middleware :: from_fn (crate :: middleware :: require_admin)))"),
+            "{}",
+            router
+        );
+        assert_eq!(router.matches(". layer").count(), 1);
+    }
+
+    #[test]
+    fn test_generate_router_namespaced() {
+        let mut invoices = Node::new_rest_endpoint("list_invoices").with_namespace("billing::Invoices");
+        invoices.set_config("path", "/");
+        let mut refunds = Node::new_rest_endpoint("list_refunds").with_namespace("billing");
+        refunds.set_config("path", "/refunds");
+
+        let router = generate_router(&[&invoices, &refunds], &ApiVersioning::new("v1"));
+        assert!(router.contains("\"/api/v1/billing/invoices\""), "{}", router);
+        assert!(router.contains("\"/api/v1/billing/refunds\""), "{}", router);
+        assert!(router.contains("billing :: invoices :: list_invoices"), "{}", router);
+    }
 }
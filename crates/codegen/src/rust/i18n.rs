@@ -0,0 +1,669 @@
+//! Internationalization Code Generation
+//!
+//! A `service.i18n` node lists the locales a service speaks. The generated
+//! `i18n` module loads a Fluent message bundle per locale, negotiates the
+//! locale of each request in a middleware (from the `lang` query parameter,
+//! then `Accept-Language`), and reports entity validation errors in it.
+
+use imortal_core::Validation;
+use imortal_ir::{Node, ProjectGraph};
+
+use crate::rust::{module_path, to_snake_case, AuthFramework};
+
+/// Validation messages of a locale with built-in translations, as Fluent
+/// (id, pattern) pairs
+fn validation_messages(locale: &str) -> Option<[(&'static str, &'static str); 9]> {
+    let language = locale.split(['-', '_']).next().unwrap_or(locale).to_ascii_lowercase();
+    Some(match language.as_str() {
+        "en" => [
+            ("validation-required", "{ $field } is required"),
+            ("validation-min-length", "{ $field } must be at least { $min } characters"),
+            ("validation-max-length", "{ $field } must be at most { $max } characters"),
+            ("validation-min", "{ $field } must be at least { $min }"),
+            ("validation-max", "{ $field } must be at most { $max }"),
+            ("validation-pattern", "{ $field } has an invalid format"),
+            ("validation-email", "{ $field } must be a valid email address"),
+            ("validation-url", "{ $field } must be a valid URL"),
+            ("validation-uuid", "{ $field } must be a valid UUID"),
+        ],
+        "es" => [
+            ("validation-required", "{ $field } es obligatorio"),
+            ("validation-min-length", "{ $field } debe tener al menos { $min } caracteres"),
+            ("validation-max-length", "{ $field } debe tener como máximo { $max } caracteres"),
+            ("validation-min", "{ $field } debe ser como mínimo { $min }"),
+            ("validation-max", "{ $field } debe ser como máximo { $max }"),
+            ("validation-pattern", "{ $field } tiene un formato no válido"),
+            ("validation-email", "{ $field } debe ser un correo electrónico válido"),
+            ("validation-url", "{ $field } debe ser una URL válida"),
+            ("validation-uuid", "{ $field } debe ser un UUID válido"),
+        ],
+        "fr" => [
+            ("validation-required", "{ $field } est obligatoire"),
+            ("validation-min-length", "{ $field } doit contenir au moins { $min } caractères"),
+            ("validation-max-length", "{ $field } doit contenir au plus { $max } caractères"),
+            ("validation-min", "{ $field } doit être supérieur ou égal à { $min }"),
+            ("validation-max", "{ $field } doit être inférieur ou égal à { $max }"),
+            ("validation-pattern", "{ $field } n'a pas un format valide"),
+            ("validation-email", "{ $field } doit être une adresse e-mail valide"),
+            ("validation-url", "{ $field } doit être une URL valide"),
+            ("validation-uuid", "{ $field } doit être un UUID valide"),
+        ],
+        "de" => [
+            ("validation-required", "{ $field } ist erforderlich"),
+            ("validation-min-length", "{ $field } muss mindestens { $min } Zeichen lang sein"),
+            ("validation-max-length", "{ $field } darf höchstens { $max } Zeichen lang sein"),
+            ("validation-min", "{ $field } muss mindestens { $min } sein"),
+            ("validation-max", "{ $field } darf höchstens { $max } sein"),
+            ("validation-pattern", "{ $field } hat ein ungültiges Format"),
+            ("validation-email", "{ $field } muss eine gültige E-Mail-Adresse sein"),
+            ("validation-url", "{ $field } muss eine gültige URL sein"),
+            ("validation-uuid", "{ $field } muss eine gültige UUID sein"),
+        ],
+        _ => return None,
+    })
+}
+
+/// A validation rule of an entity field
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldRule {
+    /// Serialized name of the field
+    pub field: String,
+    /// Name the field goes by in messages
+    pub label: String,
+    /// The rule
+    pub validation: Validation,
+}
+
+/// An entity whose fields have validation rules
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValidatedEntity {
+    /// Entity name
+    pub name: String,
+    /// Path of the generated model, e.g. `crate::models::blog::Post`
+    pub model_path: String,
+    /// Rules of its fields, in declaration order
+    pub rules: Vec<FieldRule>,
+}
+
+/// Internationalization generated for a project
+#[derive(Debug, Clone, PartialEq)]
+pub struct I18nConfig {
+    /// Locale used when a request asks for none that is supported
+    pub default_locale: String,
+    /// Supported locales, the default first
+    pub locales: Vec<String>,
+    /// Query parameter requests pick their locale with
+    pub query_param: String,
+    /// Entities validated in the request's locale, sorted by name
+    pub entities: Vec<ValidatedEntity>,
+}
+
+impl I18nConfig {
+    /// Internationalization of the first `service.i18n` node in a graph,
+    /// validating every entity with field validation rules
+    pub fn from_graph(graph: &ProjectGraph) -> Option<Self> {
+        let node = graph.find_nodes_by_type("service.i18n").into_iter().next()?;
+        let default_locale = node
+            .get_config_str("default_locale")
+            .and_then(locale_tag)
+            .unwrap_or_else(|| "en".to_string());
+        let mut locales = vec![default_locale.clone()];
+        for locale in node.get_config_str("locales").unwrap_or_default().split(',').filter_map(locale_tag) {
+            if !locales.iter().any(|l| l.eq_ignore_ascii_case(&locale)) {
+                locales.push(locale);
+            }
+        }
+
+        let mut entities: Vec<ValidatedEntity> = graph
+            .find_nodes_by_type("data.entity")
+            .into_iter()
+            .map(|n| ValidatedEntity::from_node(graph, n))
+            .filter(|e| !e.rules.is_empty())
+            .collect();
+        entities.sort_by(|a, b| a.name.cmp(&b.name));
+
+        Some(Self {
+            default_locale,
+            locales,
+            query_param: node
+                .get_config_str("query_param")
+                .map(str::trim)
+                .filter(|v| !v.is_empty())
+                .unwrap_or("lang")
+                .to_string(),
+            entities,
+        })
+    }
+
+    /// Locales without built-in translations, whose bundles start as
+    /// copies of the English messages
+    pub fn untranslated(&self) -> Vec<&str> {
+        self.locales
+            .iter()
+            .filter(|l| validation_messages(l).is_none())
+            .map(String::as_str)
+            .collect()
+    }
+
+    /// Whether a field rule matches a regular expression
+    fn has_patterns(&self) -> bool {
+        self.entities
+            .iter()
+            .flat_map(|e| &e.rules)
+            .any(|r| matches!(r.validation, Validation::Pattern(_)))
+    }
+
+    /// Crates the generated module needs
+    pub fn dependencies(&self) -> Vec<(&'static str, &'static str)> {
+        let mut deps = vec![("fluent-bundle", r#""0.15""#), ("unic-langid", r#""0.9""#)];
+        if self.has_patterns() {
+            deps.push(("regex", r#""1""#));
+        }
+        deps
+    }
+}
+
+impl ValidatedEntity {
+    fn from_node(graph: &ProjectGraph, node: &Node) -> Self {
+        let mut path = vec!["crate".to_string(), "models".to_string()];
+        path.extend(module_path(node));
+        path.push(node.name.clone());
+        let rules = graph
+            .effective_fields(node.id)
+            .into_iter()
+            .flat_map(|field| {
+                let label = field.label.clone().unwrap_or_else(|| field.name.clone());
+                let name = to_snake_case(&field.name);
+                // A unit's range, e.g. no negative money, is checked like any other rule
+                let implied = field.unit_validations();
+                field.validations.into_iter().chain(implied).map(move |validation| FieldRule {
+                    field: name.clone(),
+                    label: label.clone(),
+                    validation,
+                })
+            })
+            .collect();
+        Self { name: node.name.clone(), model_path: path.join("::"), rules }
+    }
+}
+
+/// A locale tag with only the characters tags use, or `None` when blank
+fn locale_tag(tag: &str) -> Option<String> {
+    let tag: String = tag.trim().chars().filter(|c| c.is_ascii_alphanumeric() || *c == '-').collect();
+    (!tag.is_empty()).then_some(tag)
+}
+
+/// Fluent message id of a custom validation rule
+pub fn custom_message_id(name: &str) -> String {
+    let id: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_lowercase() } else { '-' })
+        .collect();
+    format!("validation-custom-{}", id.trim_matches('-'))
+}
+
+/// Text as a Fluent pattern, with its braces escaped
+fn fluent_text(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            '{' => "{ \"{\" }".to_string(),
+            '}' => "{ \"}\" }".to_string(),
+            c => c.to_string(),
+        })
+        .collect()
+}
+
+/// Generate a locale's Fluent message bundle: the validation messages,
+/// translated when the locale has built-in translations, and the messages
+/// of custom validation rules
+pub fn generate_message_bundle(locale: &str, config: &I18nConfig) -> String {
+    let (messages, header) = match validation_messages(locale) {
+        Some(messages) => (messages, String::new()),
+        None => (
+            validation_messages("en").unwrap_or_else(|| unreachable!("English messages are built in")),
+            "# TODO: translate the messages below\n".to_string(),
+        ),
+    };
+    let mut content = format!("# Messages in {}\n#\n# Generated by Immortal Engine\n{}\n", locale, header);
+    content.push_str("## Validation\n\n");
+    for (id, pattern) in messages {
+        content.push_str(&format!("{} = {}\n", id, pattern));
+    }
+
+    let mut custom: Vec<(String, &str)> = config
+        .entities
+        .iter()
+        .flat_map(|e| &e.rules)
+        .filter_map(|r| match &r.validation {
+            Validation::Custom { name, message } if !message.trim().is_empty() => {
+                Some((custom_message_id(name), message.trim()))
+            }
+            _ => None,
+        })
+        .collect();
+    custom.sort();
+    custom.dedup_by(|a, b| a.0 == b.0);
+    if !custom.is_empty() {
+        content.push_str("\n## Custom validation\n\n");
+        for (id, message) in custom {
+            content.push_str(&format!("{} = {}\n", id, fluent_text(message)));
+        }
+    }
+    content
+}
+
+/// A field rule as a `Rule` expression of the generated module
+fn rule_expression(validation: &Validation) -> String {
+    match validation {
+        Validation::Required => "Rule::Required".to_string(),
+        Validation::MinLength(n) => format!("Rule::MinLength({})", n),
+        Validation::MaxLength(n) => format!("Rule::MaxLength({})", n),
+        Validation::Min(n) => format!("Rule::Min({:?})", n),
+        Validation::Max(n) => format!("Rule::Max({:?})", n),
+        Validation::Pattern(p) => format!("Rule::Pattern({:?})", p),
+        Validation::Email => "Rule::Email".to_string(),
+        Validation::Url => "Rule::Url".to_string(),
+        Validation::Uuid => "Rule::Uuid".to_string(),
+        Validation::Custom { name, .. } => format!("Rule::Custom({:?})", custom_message_id(name)),
+    }
+}
+
+/// Generate i18n.rs content: the message bundles, the `Locale` extractor
+/// and the middleware negotiating it, and the localized validation of
+/// entities with field rules
+pub fn generate_i18n(framework: AuthFramework, config: &I18nConfig) -> String {
+    let locales: Vec<String> = config.locales.iter().map(|l| format!("{:?}", l)).collect();
+    let sources: Vec<String> = config
+        .locales
+        .iter()
+        .map(|l| format!("    ({:?}, include_str!(\"../locales/{}/main.ftl\")),", l, l))
+        .collect();
+    let pattern_check = if config.has_patterns() {
+        "Rule::Pattern(pattern) => value\n                .as_str()\n                .map_or(true, |s| regex::Regex::new(pattern).is_ok_and(|re| re.is_match(s))),"
+    } else {
+        "Rule::Pattern(_) => true,"
+    };
+
+    let mut content = format!(
+        r#"//! Locales and localized messages
+//!
+//! Generated by Immortal Engine
+
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+use fluent_bundle::{{FluentArgs, FluentResource, FluentValue}};
+
+use crate::error::AppError;
+
+/// Locales the service speaks, the default first
+pub const LOCALES: &[&str] = &[{locales}];
+
+/// Locale of requests asking for none that is supported
+pub const DEFAULT_LOCALE: &str = {default:?};
+
+/// Query parameter requests pick their locale with
+pub const LOCALE_PARAM: &str = {param:?};
+
+/// Message bundle of each locale, in Fluent syntax
+const SOURCES: &[(&str, &str)] = &[
+{sources}
+];
+
+type Bundle = fluent_bundle::concurrent::FluentBundle<FluentResource>;
+
+/// The parsed message bundles, loaded on first use
+fn bundles() -> &'static HashMap<&'static str, Bundle> {{
+    static BUNDLES: OnceLock<HashMap<&'static str, Bundle>> = OnceLock::new();
+    BUNDLES.get_or_init(|| {{
+        SOURCES
+            .iter()
+            .map(|(locale, source)| {{
+                let language: unic_langid::LanguageIdentifier = locale.parse().unwrap_or_default();
+                let mut bundle = Bundle::new_concurrent(vec![language]);
+                // Messages end up in JSON, not bidirectional text
+                bundle.set_use_isolating(false);
+                let resource = FluentResource::try_new(source.to_string()).unwrap_or_else(|(resource, errors)| {{
+                    tracing::warn!(locale, ?errors, "message bundle has errors");
+                    resource
+                }});
+                if let Err(errors) = bundle.add_resource(resource) {{
+                    tracing::warn!(locale, ?errors, "message bundle redefines messages");
+                }}
+                (*locale, bundle)
+            }})
+            .collect()
+    }})
+}}
+
+/// Locale a request is answered in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Locale(&'static str);
+
+impl Default for Locale {{
+    fn default() -> Self {{
+        Locale(DEFAULT_LOCALE)
+    }}
+}}
+
+impl Locale {{
+    /// Tag of the locale, e.g. "en"
+    pub fn id(&self) -> &'static str {{
+        self.0
+    }}
+
+    /// Supported locale matching a tag exactly or by its language
+    pub fn find(tag: &str) -> Option<Self> {{
+        let tag = tag.trim();
+        let language = |tag: &str| tag.split(['-', '_']).next().unwrap_or_default().to_ascii_lowercase();
+        LOCALES
+            .iter()
+            .find(|locale| locale.eq_ignore_ascii_case(tag))
+            .or_else(|| LOCALES.iter().find(|locale| language(locale) == language(tag)))
+            .map(|locale| Locale(locale))
+    }}
+
+    /// Locale of a request: the one it asks for by name, else the most
+    /// preferred supported one of its `Accept-Language`, else the default
+    pub fn negotiate(requested: Option<&str>, accept_language: Option<&str>) -> Self {{
+        if let Some(locale) = requested.and_then(Self::find) {{
+            return locale;
+        }}
+        let mut ranges: Vec<(&str, f32)> = accept_language
+            .unwrap_or_default()
+            .split(',')
+            .filter_map(|range| {{
+                let mut parts = range.split(';');
+                let tag = parts.next()?.trim();
+                let quality = parts
+                    .find_map(|part| part.trim().strip_prefix("q="))
+                    .and_then(|q| q.parse().ok())
+                    .unwrap_or(1.0);
+                (!tag.is_empty() && quality > 0.0).then_some((tag, quality))
+            }})
+            .collect();
+        ranges.sort_by(|a, b| b.1.total_cmp(&a.1));
+        ranges.into_iter().find_map(|(tag, _)| Self::find(tag)).unwrap_or_default()
+    }}
+
+    /// A message in this locale, falling back to the default locale and
+    /// then to the message id
+    pub fn message(&self, id: &str, args: &[(&str, FluentValue<'_>)]) -> String {{
+        let mut fluent_args = FluentArgs::new();
+        for (name, value) in args {{
+            fluent_args.set(*name, value.clone());
+        }}
+        [self.0, DEFAULT_LOCALE]
+            .iter()
+            .find_map(|locale| {{
+                let bundle = bundles().get(locale)?;
+                let pattern = bundle.get_message(id)?.value()?;
+                let mut errors = Vec::new();
+                Some(bundle.format_pattern(pattern, Some(&fluent_args), &mut errors).into_owned())
+            }})
+            .unwrap_or_else(|| id.to_string())
+    }}
+}}
+
+/// Locale a request names in its query string
+fn query_locale(query: Option<&str>) -> Option<&str> {{
+    query?
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .find_map(|(name, value)| (name == LOCALE_PARAM).then_some(value))
+}}
+
+/// A validation rule of a model field
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Rule {{
+    Required,
+    MinLength(usize),
+    MaxLength(usize),
+    Min(f64),
+    Max(f64),
+    Pattern(&'static str),
+    Email,
+    Url,
+    Uuid,
+    /// Checked by hand; reported with the message of this id
+    Custom(&'static str),
+}}
+
+impl Rule {{
+    /// Whether a serialized field value satisfies the rule; absent values
+    /// only fail `Required`
+    fn check(&self, value: &serde_json::Value) -> bool {{
+        if value.is_null() {{
+            return !matches!(self, Rule::Required);
+        }}
+        let length = || value.as_str().map(|s| s.chars().count());
+        match self {{
+            Rule::Required => true,
+            Rule::MinLength(min) => length().map_or(true, |n| n >= *min),
+            Rule::MaxLength(max) => length().map_or(true, |n| n <= *max),
+            Rule::Min(min) => value.as_f64().map_or(true, |n| n >= *min),
+            Rule::Max(max) => value.as_f64().map_or(true, |n| n <= *max),
+            {pattern_check}
+            Rule::Email => value.as_str().map_or(true, |s| {{
+                s.split_once('@').is_some_and(|(user, domain)| !user.is_empty() && domain.contains('.'))
+            }}),
+            Rule::Url => value.as_str().map_or(true, |s| s.starts_with("http://") || s.starts_with("https://")),
+            Rule::Uuid => value.as_str().map_or(true, |s| uuid::Uuid::parse_str(s).is_ok()),
+            Rule::Custom(_) => true,
+        }}
+    }}
+
+    /// Message reporting a field that fails the rule
+    pub fn message(&self, locale: &Locale, field: &str) -> String {{
+        let field = FluentValue::from(field);
+        match self {{
+            Rule::Required => locale.message("validation-required", &[("field", field)]),
+            Rule::MinLength(min) => locale.message("validation-min-length", &[("field", field), ("min", (*min).into())]),
+            Rule::MaxLength(max) => locale.message("validation-max-length", &[("field", field), ("max", (*max).into())]),
+            Rule::Min(min) => locale.message("validation-min", &[("field", field), ("min", (*min).into())]),
+            Rule::Max(max) => locale.message("validation-max", &[("field", field), ("max", (*max).into())]),
+            Rule::Pattern(_) => locale.message("validation-pattern", &[("field", field)]),
+            Rule::Email => locale.message("validation-email", &[("field", field)]),
+            Rule::Url => locale.message("validation-url", &[("field", field)]),
+            Rule::Uuid => locale.message("validation-uuid", &[("field", field)]),
+            Rule::Custom(id) => locale.message(id, &[("field", field)]),
+        }}
+    }}
+}}
+
+/// Models validated against their field rules, reporting failures in the
+/// request's locale
+///
+/// ```ignore
+/// payload.validate(&locale)?;
+/// ```
+pub trait Validate: serde::Serialize {{
+    /// Serialized field name, label, and rule of each field rule
+    const RULES: &'static [(&'static str, &'static str, Rule)];
+
+    fn validate(&self, locale: &Locale) -> Result<(), AppError> {{
+        let value = serde_json::to_value(self).map_err(|e| AppError::Internal(e.to_string()))?;
+        let failures: Vec<String> = Self::RULES
+            .iter()
+            .filter(|(field, _, rule)| !rule.check(value.get(field).unwrap_or(&serde_json::Value::Null)))
+            .map(|(_, label, rule)| rule.message(locale, label))
+            .collect();
+        if failures.is_empty() {{
+            Ok(())
+        }} else {{
+            Err(AppError::Validation(failures.join("; ")))
+        }}
+    }}
+}}
+"#,
+        locales = locales.join(", "),
+        default = config.default_locale,
+        param = config.query_param,
+        sources = sources.join("\n"),
+    );
+
+    for entity in &config.entities {
+        let rules: Vec<String> = entity
+            .rules
+            .iter()
+            .map(|r| format!("        ({:?}, {:?}, {}),", r.field, r.label, rule_expression(&r.validation)))
+            .collect();
+        content.push_str(&format!(
+            "\nimpl Validate for {} {{\n    const RULES: &'static [(&'static str, &'static str, Rule)] = &[\n{}\n    ];\n}}\n",
+            entity.model_path,
+            rules.join("\n"),
+        ));
+    }
+
+    content.push_str(match framework {
+        AuthFramework::Axum => {
+            r#"
+/// Middleware negotiating the locale of each request into its extensions
+/// and naming it in the response's `Content-Language`
+pub async fn negotiate_locale(
+    mut request: axum::extract::Request,
+    next: axum::middleware::Next,
+) -> axum::response::Response {
+    let accept_language = request
+        .headers()
+        .get(axum::http::header::ACCEPT_LANGUAGE)
+        .and_then(|value| value.to_str().ok());
+    let locale = Locale::negotiate(query_locale(request.uri().query()), accept_language);
+    request.extensions_mut().insert(locale);
+    let mut response = next.run(request).await;
+    response.headers_mut().insert(
+        axum::http::header::CONTENT_LANGUAGE,
+        axum::http::HeaderValue::from_static(locale.id()),
+    );
+    response
+}
+
+#[axum::async_trait]
+impl<S: Send + Sync> axum::extract::FromRequestParts<S> for Locale {
+    type Rejection = std::convert::Infallible;
+
+    async fn from_request_parts(parts: &mut axum::http::request::Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        Ok(parts.extensions.get::<Locale>().copied().unwrap_or_else(|| {
+            let accept_language = parts
+                .headers
+                .get(axum::http::header::ACCEPT_LANGUAGE)
+                .and_then(|value| value.to_str().ok());
+            Locale::negotiate(query_locale(parts.uri.query()), accept_language)
+        }))
+    }
+}
+"#
+        }
+        AuthFramework::Actix => {
+            r#"
+/// Middleware negotiating the locale of each request into its extensions
+/// and naming it in the response's `Content-Language`
+pub async fn negotiate_locale(
+    request: actix_web::dev::ServiceRequest,
+    next: actix_web::middleware::Next<impl actix_web::body::MessageBody>,
+) -> Result<actix_web::dev::ServiceResponse<impl actix_web::body::MessageBody>, actix_web::Error> {
+    use actix_web::HttpMessage;
+
+    let accept_language = request
+        .headers()
+        .get(actix_web::http::header::ACCEPT_LANGUAGE)
+        .and_then(|value| value.to_str().ok());
+    let locale = Locale::negotiate(query_locale(Some(request.query_string())), accept_language);
+    request.extensions_mut().insert(locale);
+    let mut response = next.call(request).await?;
+    response.headers_mut().insert(
+        actix_web::http::header::CONTENT_LANGUAGE,
+        actix_web::http::header::HeaderValue::from_static(locale.id()),
+    );
+    Ok(response)
+}
+
+impl actix_web::FromRequest for Locale {
+    type Error = actix_web::Error;
+    type Future = std::future::Ready<Result<Self, Self::Error>>;
+
+    fn from_request(request: &actix_web::HttpRequest, _payload: &mut actix_web::dev::Payload) -> Self::Future {
+        use actix_web::HttpMessage;
+
+        std::future::ready(Ok(request.extensions().get::<Locale>().copied().unwrap_or_else(|| {
+            let accept_language = request
+                .headers()
+                .get(actix_web::http::header::ACCEPT_LANGUAGE)
+                .and_then(|value| value.to_str().ok());
+            Locale::negotiate(query_locale(Some(request.query_string())), accept_language)
+        })))
+    }
+}
+"#
+        }
+        AuthFramework::Custom => "",
+    });
+
+    content
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use imortal_ir::{Field, ProjectMeta, Unit};
+
+    fn shop() -> ProjectGraph {
+        let mut graph = ProjectGraph::new(ProjectMeta::new("shop"));
+        let mut product = Node::new_entity("Product");
+        product.add_field(
+            Field::string("name")
+                .with_validation(Validation::MinLength(3))
+                .with_validation(Validation::Custom {
+                    name: "Unique Name".to_string(),
+                    message: "{name} is taken".to_string(),
+                }),
+        );
+        product.add_field(Field::long("price").with_unit(Unit::Currency("USD".to_string())));
+        graph.add_node(product);
+        graph.add_node(Node::new_entity("Tag"));
+        let mut i18n = Node::new_i18n("I18n");
+        i18n.set_config("locales", "en, es, it");
+        graph.add_node(i18n);
+        graph
+    }
+
+    #[test]
+    fn test_config_from_graph() {
+        let config = I18nConfig::from_graph(&shop()).unwrap();
+
+        assert_eq!(config.locales, ["en", "es", "it"]);
+        assert_eq!(config.untranslated(), ["it"]);
+        assert_eq!(config.entities.len(), 1);
+        assert_eq!(config.entities[0].model_path, "crate::models::Product");
+        assert!(!config.dependencies().iter().any(|(name, _)| *name == "regex"));
+    }
+
+    #[test]
+    fn test_message_bundles() {
+        let config = I18nConfig::from_graph(&shop()).unwrap();
+
+        let es = generate_message_bundle("es", &config);
+        assert!(es.contains("validation-required = { $field } es obligatorio\n"));
+        assert!(es.contains("validation-custom-unique-name = { \"{\" }name{ \"}\" } is taken\n"));
+        assert!(!es.contains("TODO"));
+        assert!(generate_message_bundle("it", &config).contains("# TODO: translate"));
+    }
+
+    #[test]
+    fn test_generate_i18n() {
+        let config = I18nConfig::from_graph(&shop()).unwrap();
+
+        let code = generate_i18n(AuthFramework::Axum, &config);
+        assert!(code.contains("pub const LOCALES: &[&str] = &[\"en\", \"es\", \"it\"];"));
+        assert!(code.contains("(\"it\", include_str!(\"../locales/it/main.ftl\")),"));
+        assert!(code.contains("impl Validate for crate::models::Product {"));
+        assert!(code.contains("(\"name\", \"name\", Rule::MinLength(3)),"));
+        assert!(code.contains("(\"name\", \"name\", Rule::Custom(\"validation-custom-unique-name\")),"));
+        assert!(code.contains("(\"price\", \"price\", Rule::Min(0.0)),"));
+        assert!(code.contains("Rule::Pattern(_) => true,"));
+        assert!(code.contains("impl<S: Send + Sync> axum::extract::FromRequestParts<S> for Locale"));
+
+        let actix = generate_i18n(AuthFramework::Actix, &config);
+        assert!(actix.contains("impl actix_web::FromRequest for Locale"));
+    }
+}
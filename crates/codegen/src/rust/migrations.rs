@@ -4,8 +4,13 @@
 //! from entity nodes in the project graph.
 
 use std::collections::HashMap;
-use imortal_ir::{Node, Field, ProjectGraph};
-use imortal_core::{DataType, EngineResult, EngineError};
+use imortal_ir::field::{FieldConstraint, ForeignKeyBuilder};
+use imortal_ir::{CustomType, Node, Field, ProjectGraph};
+use imortal_core::{DataType, EngineResult, EngineError, NodeId, Validation};
+
+use crate::rust::audit::{generate_audit_migration, AuditConfig};
+use crate::rust::search::{generate_search_migration, SearchConfig};
+use crate::rust::tenancy::tenant_field;
 
 
 /// Supported database backends for migration generation
@@ -18,6 +23,32 @@ pub enum DatabaseBackend {
 }
 
 impl DatabaseBackend {
+    /// Backends a project can target
+    pub const ALL: [DatabaseBackend; 3] = [DatabaseBackend::Postgres, DatabaseBackend::Sqlite, DatabaseBackend::Mysql];
+
+    /// Identifier stored in `ProjectMeta::target_database`
+    pub fn id(&self) -> &'static str {
+        match self {
+            DatabaseBackend::Postgres => "postgres",
+            DatabaseBackend::Sqlite => "sqlite",
+            DatabaseBackend::Mysql => "mysql",
+        }
+    }
+
+    /// Get the display name for this backend
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            DatabaseBackend::Postgres => "PostgreSQL",
+            DatabaseBackend::Sqlite => "SQLite",
+            DatabaseBackend::Mysql => "MySQL",
+        }
+    }
+
+    /// Backend with the given identifier, ignoring case
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|backend| backend.id().eq_ignore_ascii_case(id.trim()))
+    }
+
     /// Get the SQL type for a DataType in this backend
     pub fn sql_type(&self, data_type: &DataType) -> String {
         match self {
@@ -107,6 +138,12 @@ pub struct MigrationConfig {
     pub add_timestamps: bool,
     /// Whether to use UUID for primary keys
     pub uuid_primary_keys: bool,
+    /// Whether every table gets an indexed `tenant_id` column
+    pub tenant_column: bool,
+    /// Tables of entities stored under another name, by entity name
+    pub table_names: HashMap<String, String>,
+    /// Project-defined types, for the columns of fields holding them
+    pub types: Vec<CustomType>,
 }
 
 impl Default for MigrationConfig {
@@ -117,6 +154,9 @@ impl Default for MigrationConfig {
             generate_indexes: true,
             add_timestamps: true,
             uuid_primary_keys: true,
+            tenant_column: false,
+            table_names: HashMap::new(),
+            types: Vec::new(),
         }
     }
 }
@@ -149,6 +189,12 @@ impl MigrationConfig {
         self.schema = Some(schema.into());
         self
     }
+
+    /// Add a `tenant_id` column to every table
+    pub fn with_tenant_column(mut self) -> Self {
+        self.tenant_column = true;
+        self
+    }
 }
 
 /// Generate migrations from a project graph
@@ -164,12 +210,37 @@ impl MigrationGenerator {
 
     /// Generate all migrations for a project
     pub fn generate(&self, graph: &ProjectGraph) -> EngineResult<Vec<Migration>> {
+        // Entities keep the table names their nodes ask for
+        let mut config = self.config.clone();
+        config.table_names.extend(
+            graph.nodes()
+                .filter(|n| n.component_type == "data.entity")
+                .filter_map(|n| Some((n.name.clone(), n.codegen.table_name.clone()?))),
+        );
+        config.types.extend(graph.meta.types.iter().cloned());
+        Self::new(config).generate_migrations(graph)
+    }
+
+    fn generate_migrations(&self, graph: &ProjectGraph) -> EngineResult<Vec<Migration>> {
         let mut migrations = Vec::new();
 
-        // Find all entity nodes
-        let entities: Vec<&Node> = graph.nodes()
-            .filter(|n| n.component_type == "data.entity")
+        // Find all entity nodes, each table holding the fields its entity
+        // inherits, the type and id columns of its polymorphic
+        // relationships, and its tenant as well as its own; skipped entities
+        // have their tables made by hand
+        let flattened: Vec<Node> = graph.nodes()
+            .filter(|n| n.component_type == "data.entity" && !n.codegen.skip)
+            .map(|n| {
+                let mut node = n.clone();
+                node.fields = graph.effective_fields(n.id);
+                node.fields.extend(graph.polymorphic_fields(n.id));
+                if self.config.tenant_column {
+                    node.fields.push(tenant_field());
+                }
+                node
+            })
             .collect();
+        let entities: Vec<&Node> = flattened.iter().collect();
 
         if entities.is_empty() {
             return Ok(migrations);
@@ -190,6 +261,18 @@ impl MigrationGenerator {
         let fk_migrations = self.generate_foreign_key_migrations(&entities, graph)?;
         migrations.extend(fk_migrations);
 
+        if let Some(polymorphic_migration) = self.generate_polymorphic_migration(&entities, graph) {
+            migrations.push(polymorphic_migration);
+        }
+
+        if let Some(audit) = AuditConfig::from_graph(graph) {
+            migrations.push(generate_audit_migration(&audit, self.config.backend));
+        }
+
+        if let Some(search) = SearchConfig::from_graph(graph) {
+            migrations.extend(generate_search_migration(&search, self.config.backend));
+        }
+
         Ok(migrations)
     }
 
@@ -284,7 +367,7 @@ impl MigrationGenerator {
     /// Generate a column definition
     fn generate_column_definition(&self, field: &Field) -> EngineResult<String> {
         let column_name = to_snake_case(&field.name);
-        let sql_type = self.config.backend.sql_type(&field.data_type);
+        let sql_type = self.column_type(&field.data_type);
 
         let mut parts = vec![format!("    {}", column_name), sql_type];
 
@@ -320,9 +403,33 @@ impl MigrationGenerator {
             }
         }
 
+        // Range implied by the unit, e.g. no negative money
+        let bounds: Vec<String> = field
+            .unit_validations()
+            .iter()
+            .filter_map(|validation| match validation {
+                Validation::Min(min) => Some(format!("{} >= {}", column_name, min)),
+                Validation::Max(max) => Some(format!("{} <= {}", column_name, max)),
+                _ => None,
+            })
+            .collect();
+        if !bounds.is_empty() {
+            parts.push(format!("CHECK ({})", bounds.join(" AND ")));
+        }
+
         Ok(parts.join(" "))
     }
 
+    /// Name of a foreign key constraint
+    fn foreign_key_name(table_name: &str, field: &Field, entity: &str) -> String {
+        format!(
+            "fk_{}_{}_{}",
+            to_snake_case(table_name),
+            to_snake_case(&field.name),
+            to_snake_case(entity)
+        )
+    }
+
     /// Generate column-level constraints (foreign keys, etc.)
     fn generate_column_constraint(&self, field: &Field, table_name: &str) -> Option<String> {
         for constraint in &field.constraints {
@@ -332,12 +439,7 @@ impl MigrationGenerator {
                 on_delete,
                 on_update,
             } = constraint {
-                let fk_name = format!(
-                    "fk_{}_{}_{}",
-                    to_snake_case(table_name),
-                    to_snake_case(&field.name),
-                    to_snake_case(entity)
-                );
+                let fk_name = Self::foreign_key_name(table_name, field, entity);
                 let ref_table = self.table_name(entity);
 
                 return Some(format!(
@@ -411,24 +513,40 @@ impl MigrationGenerator {
     }
 
     /// Generate foreign key migrations
-    fn generate_foreign_key_migrations(&self, entities: &[&Node], _graph: &ProjectGraph) -> EngineResult<Vec<Migration>> {
+    fn generate_foreign_key_migrations(&self, entities: &[&Node], graph: &ProjectGraph) -> EngineResult<Vec<Migration>> {
         let mut migrations = Vec::new();
         let mut fk_statements = Vec::new();
         let mut drop_statements = Vec::new();
 
+        // Relationships joined on fields act as constraints on their keys,
+        // unless the key already has one
+        let mut keys: HashMap<(NodeId, &str), FieldConstraint> = HashMap::new();
+        for key in graph.edges().filter_map(|edge| edge.relationship_key()) {
+            if let Some(parent) = graph.get_node(key.parent) {
+                keys.insert((key.child, key.field), ForeignKeyBuilder::new(&parent.name, key.references).build());
+            }
+        }
+
         for entity in entities {
             let table_name = self.table_name(&entity.name);
 
             for field in &entity.fields {
+                let keyed;
+                let field = match keys.get(&(entity.id, field.name.as_str())) {
+                    Some(constraint) if !field.is_foreign_key() => {
+                        keyed = field.clone().with_constraint(constraint.clone());
+                        &keyed
+                    }
+                    _ => field,
+                };
                 if let Some(fk_sql) = self.generate_column_constraint(field, &table_name) {
                     fk_statements.push(format!("{};", fk_sql));
 
-                    let fk_name = format!(
-                        "fk_{}_{}_{}",
-                        to_snake_case(&table_name),
-                        to_snake_case(&field.name),
-                        "ref"
-                    );
+                    let target = field.constraints.iter().find_map(|c| match c {
+                        imortal_ir::field::FieldConstraint::ForeignKey { entity, .. } => Some(entity.as_str()),
+                        _ => None,
+                    });
+                    let fk_name = Self::foreign_key_name(&table_name, field, target.unwrap_or_default());
                     drop_statements.push(format!(
                         "ALTER TABLE {} DROP CONSTRAINT IF EXISTS {};",
                         table_name, fk_name
@@ -448,9 +566,74 @@ impl MigrationGenerator {
         Ok(migrations)
     }
 
+    /// Generate indexes on the type and id column pairs of polymorphic
+    /// relationships, which can't have foreign keys, and checks keeping
+    /// the type column to the association's targets
+    fn generate_polymorphic_migration(&self, entities: &[&Node], graph: &ProjectGraph) -> Option<Migration> {
+        let mut up_statements = Vec::new();
+        let mut down_statements = Vec::new();
+
+        for entity in entities {
+            let table_name = self.table_name(&entity.name);
+            for association in graph.polymorphic_associations(entity.id) {
+                let name = format!("{}_{}", to_snake_case(&entity.name), association.name);
+                let (type_column, id_column) = (association.type_column(), association.id_column());
+                up_statements.push(format!(
+                    "CREATE INDEX IF NOT EXISTS idx_{} ON {} ({}, {});",
+                    name, table_name, type_column, id_column
+                ));
+                down_statements.push(format!("DROP INDEX IF EXISTS idx_{};", name));
+
+                // SQLite can't add constraints to an existing table
+                if self.config.backend != DatabaseBackend::Sqlite {
+                    let targets: Vec<String> = association
+                        .targets
+                        .iter()
+                        .filter_map(|id| graph.get_node(*id))
+                        .map(|target| format!("'{}'", target.name))
+                        .collect();
+                    up_statements.push(format!(
+                        "ALTER TABLE {} ADD CONSTRAINT chk_{}_type CHECK ({} IN ({}));",
+                        table_name, name, type_column, targets.join(", ")
+                    ));
+                    down_statements.push(format!(
+                        "ALTER TABLE {} DROP CONSTRAINT IF EXISTS chk_{}_type;",
+                        table_name, name
+                    ));
+                }
+            }
+        }
+
+        if up_statements.is_empty() {
+            return None;
+        }
+        down_statements.reverse();
+        Some(Migration::new(
+            "add_polymorphic_keys",
+            up_statements.join("\n"),
+            down_statements.join("\n"),
+        ))
+    }
+
+    /// Column type of a field; a project type is stored as its own column
+    /// type if it names one, otherwise as its underlying type
+    fn column_type(&self, data_type: &DataType) -> String {
+        match data_type {
+            DataType::Custom { type_name, .. } => match self.config.types.iter().find(|t| t.name == *type_name) {
+                Some(custom) => custom
+                    .sql_type
+                    .clone()
+                    .unwrap_or_else(|| self.config.backend.sql_type(&custom.underlying)),
+                None => self.config.backend.sql_type(data_type),
+            },
+            DataType::Optional(inner) => self.column_type(inner),
+            _ => self.config.backend.sql_type(data_type),
+        }
+    }
+
     /// Get the full table name (with schema if applicable)
     fn table_name(&self, name: &str) -> String {
-        let snake_name = to_snake_case(name);
+        let snake_name = self.config.table_names.get(name).cloned().unwrap_or_else(|| to_snake_case(name));
         if let Some(ref schema) = self.config.schema {
             format!("{}.{}", schema, snake_name)
         } else {
@@ -573,11 +756,17 @@ pub fn generate_all_migrations(
     graph: &ProjectGraph,
     backend: DatabaseBackend,
 ) -> EngineResult<HashMap<String, String>> {
-    let config = MigrationConfig {
+    generate_all_migrations_with(graph, MigrationConfig {
         backend,
         ..Default::default()
-    };
+    })
+}
 
+/// Generate all migration files for a project with the given configuration
+pub fn generate_all_migrations_with(
+    graph: &ProjectGraph,
+    config: MigrationConfig,
+) -> EngineResult<HashMap<String, String>> {
     let generator = MigrationGenerator::new(config);
     let migrations = generator.generate(graph)?;
 
@@ -592,7 +781,7 @@ pub fn generate_all_migrations(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use imortal_ir::{ProjectMeta, Field};
+    use imortal_ir::Field;
 
     fn create_test_entity() -> Node {
         Node::new_entity("User")
@@ -615,6 +804,112 @@ mod tests {
         assert!(migration.down.contains("DROP TABLE"));
     }
 
+    #[test]
+    fn test_relationship_foreign_key_migration() {
+        use imortal_core::RelationType;
+        use imortal_ir::{ForeignKeyFields, ForeignKeyNaming};
+
+        let mut graph = ProjectGraph::with_name("blog");
+        graph.meta.foreign_keys = ForeignKeyFields::new(ForeignKeyNaming::SnakeCase);
+        let user = graph.add_node(Node::new_entity("User"));
+        let post = graph.add_node(Node::new_entity("Post"));
+        graph.add_relationship(user, post, RelationType::OneToMany).unwrap();
+
+        let migrations = MigrationGenerator::new(MigrationConfig::postgres()).generate(&graph).unwrap();
+        let fks = migrations.iter().find(|m| m.name == "add_foreign_keys").unwrap();
+        assert!(fks.up.contains("ALTER TABLE post ADD CONSTRAINT fk_post_user_id_user FOREIGN KEY (user_id) REFERENCES user(id)"));
+        assert!(fks.down.contains("DROP CONSTRAINT IF EXISTS fk_post_user_id_user;"));
+    }
+
+    #[test]
+    fn test_field_relationship_foreign_key_migration() {
+        use imortal_core::RelationType;
+
+        let mut graph = ProjectGraph::with_name("blog");
+        let user = graph.add_node(Node::new_entity("User"));
+        let mut post = Node::new_entity("Post");
+        post.add_field(Field::uuid("author_id"));
+        let post = graph.add_node(post);
+        graph.add_field_relationship(user, "id", post, "author_id", RelationType::OneToMany).unwrap();
+
+        let migrations = MigrationGenerator::new(MigrationConfig::postgres()).generate(&graph).unwrap();
+        let fks = migrations.iter().find(|m| m.name == "add_foreign_keys").unwrap();
+        assert!(fks.up.contains("ALTER TABLE post ADD CONSTRAINT fk_post_author_id_user FOREIGN KEY (author_id) REFERENCES user(id)"));
+        assert!(fks.down.contains("DROP CONSTRAINT IF EXISTS fk_post_author_id_user;"));
+    }
+
+    #[test]
+    fn test_inherited_columns() {
+        let mut graph = ProjectGraph::with_name("blog");
+        let mut content = Node::new_entity("Content");
+        content.add_field(Field::string("title").required());
+        let content = graph.add_node(content);
+        let mut post = Node::new_entity("Post");
+        post.mixins.push("SoftDelete".to_string());
+        let post = graph.add_node(post);
+        graph.set_parent_entity(post, Some(content)).unwrap();
+
+        let migrations = MigrationGenerator::new(MigrationConfig::postgres()).generate(&graph).unwrap();
+        let schema = &migrations[0].up;
+        let post_table = &schema[schema.find("CREATE TABLE post").unwrap()..];
+        assert!(post_table.contains("title VARCHAR(255) NOT NULL"));
+        assert!(post_table.contains("deleted_at TIMESTAMP WITH TIME ZONE"));
+    }
+
+    #[test]
+    fn test_polymorphic_columns() {
+        let mut graph = ProjectGraph::with_name("blog");
+        let comment = graph.add_node(Node::new_entity("Comment"));
+        let post = graph.add_node(Node::new_entity("Post"));
+        let photo = graph.add_node(Node::new_entity("Photo"));
+        graph.set_polymorphic_targets(comment, "commentable", &[post, photo]).unwrap();
+
+        let migrations = MigrationGenerator::new(MigrationConfig::postgres()).generate(&graph).unwrap();
+        assert!(migrations[0].up.contains("commentable_type VARCHAR(255) NOT NULL"));
+        assert!(migrations[0].up.contains("commentable_id UUID NOT NULL"));
+        let keys = migrations.iter().find(|m| m.name == "add_polymorphic_keys").unwrap();
+        assert!(keys.up.contains("CREATE INDEX IF NOT EXISTS idx_comment_commentable ON comment (commentable_type, commentable_id);"));
+        assert!(keys.up.contains("CHECK (commentable_type IN ('Photo', 'Post'))"));
+        assert!(keys.down.starts_with("ALTER TABLE comment DROP CONSTRAINT IF EXISTS chk_comment_commentable_type;"));
+        assert!(!migrations.iter().any(|m| m.name == "add_foreign_keys"));
+
+        let sqlite = MigrationGenerator::new(MigrationConfig::sqlite()).generate(&graph).unwrap();
+        let keys = sqlite.iter().find(|m| m.name == "add_polymorphic_keys").unwrap();
+        assert!(!keys.up.contains("CHECK"));
+    }
+
+    #[test]
+    fn test_tenant_column() {
+        let mut graph = ProjectGraph::with_name("shop");
+        graph.add_node(Node::new_entity("Order"));
+
+        let migrations = MigrationGenerator::new(MigrationConfig::postgres().with_tenant_column())
+            .generate(&graph)
+            .unwrap();
+        assert!(migrations[0].up.contains("tenant_id UUID NOT NULL"));
+        let indexes = migrations.iter().find(|m| m.name == "create_indexes").unwrap();
+        assert!(indexes.up.contains("CREATE INDEX idx_order_tenant_id ON order (tenant_id);"));
+
+        let migrations = MigrationGenerator::new(MigrationConfig::postgres()).generate(&graph).unwrap();
+        assert!(!migrations[0].up.contains("tenant_id"));
+    }
+
+    #[test]
+    fn test_unit_check_constraints() {
+        use imortal_ir::Unit;
+
+        let entity = Node::new_entity("Product")
+            .with_field(Field::uuid("id").primary_key())
+            .with_field(Field::long("price").required().with_unit(Unit::Currency("USD".to_string())))
+            .with_field(Field::double("discount").with_unit(Unit::Percent))
+            .with_field(Field::int("delta").with_unit(Unit::Seconds).with_validation(Validation::Min(-60.0)));
+        let migration = MigrationGenerator::new(MigrationConfig::postgres()).generate_for_entity(&entity).unwrap();
+        assert!(migration.up.contains("price BIGINT NOT NULL CHECK (price >= 0)"), "{}", migration.up);
+        assert!(migration.up.contains("CHECK (discount >= 0 AND discount <= 100)"));
+        // An explicit minimum wins over the unit's
+        assert!(!migration.up.contains("CHECK (delta"));
+    }
+
     #[test]
     fn test_postgres_types() {
         assert_eq!(postgres_type(&DataType::String), "VARCHAR(255)");
@@ -8,6 +8,17 @@ pub mod models;
 pub mod migrations;
 pub mod auth;
 pub mod config;
+pub mod observability;
+pub mod audit;
+pub mod tenancy;
+pub mod realtime;
+pub mod caching;
+pub mod search;
+pub mod payments;
+pub mod i18n;
+pub mod flags;
+pub mod custom_code;
+pub mod types;
 
 // Re-export common types
 pub use structs::*;
@@ -15,7 +26,20 @@ pub use handlers::*;
 pub use models::*;
 pub use migrations::{Migration, MigrationConfig, MigrationGenerator, DatabaseBackend};
 pub use auth::{AuthConfig, AuthFramework, AuthGenerator, GeneratedAuth};
-pub use config::{generate_config, generate_error};
+pub use config::{
+    builtin_settings, collect_settings, generate_config, generate_env_example, generate_error, AppSetting,
+};
+pub use observability::{generate_observability, instrument_handlers, ObservabilityConfig};
+pub use audit::{generate_audit, generate_audit_migration, AuditConfig, AuditStrategy, AuditedEntity};
+pub use tenancy::{generate_tenancy, tenant_field, TenancyMode, TENANT_COLUMN};
+pub use realtime::{generate_realtime, generate_typescript_client, RealtimeConfig, RealtimeEntity};
+pub use caching::{cache_param, cached_handler_body, generate_cache, CacheBackend, CacheStore, CachingConfig};
+pub use search::{generate_search, generate_search_migration, SearchConfig, SearchEngine, SearchableEntity};
+pub use payments::{generate_payments, CheckoutMode, PaymentProvider, PaymentsConfig, PurchasableEntity};
+pub use i18n::{generate_i18n, generate_message_bundle, FieldRule, I18nConfig, ValidatedEntity};
+pub use flags::{flag_guard, generate_flags, FeatureFlagsConfig, FlagProvider};
+pub use custom_code::{custom_code_signature, generate_custom_code};
+pub use types::generate_types;
 
 use imortal_ir::Node;
 use imortal_core::DataType;
@@ -106,6 +130,11 @@ pub fn to_pascal_case(s: &str) -> String {
         .collect()
 }
 
+/// Rust module path of a node's namespace, one snake_case segment per level
+pub fn module_path(node: &Node) -> Vec<String> {
+    node.namespace.iter().map(|segment| to_snake_case(segment)).collect()
+}
+
 /// Convert string to snake_case
 pub fn to_snake_case(s: &str) -> String {
     let mut result = String::new();
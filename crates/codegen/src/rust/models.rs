@@ -3,11 +3,144 @@
 //! This module provides utilities for generating Rust model/struct code
 //! from entity nodes in the project graph.
 
-use imortal_ir::Node;
+use std::collections::HashSet;
+
+use imortal_ir::{project_type_name, Mixin, Node, ProjectGraph};
 use imortal_core::DataType;
 
+use crate::sync::{protected_region, CUSTOM_DEFAULTS, CUSTOM_FIELDS};
+
+/// A struct embedded in a composed model: field name, type, and doc line
+struct Embedded {
+    field: String,
+    type_name: String,
+    doc: String,
+}
+
 /// Generate a Rust model struct from an entity node
 pub fn generate_model(node: &Node) -> String {
+    model_struct(node, &[], &[])
+}
+
+/// Generate a model that embeds the entity it extends and its mixins
+///
+/// The parent and each mixin are `#[serde(flatten)]` fields, so the model
+/// serializes the same as a flattened one. `AsRef`/`AsMut` reach the parent
+/// and a `Has<Mixin>` trait (from [`generate_mixins`]) each mixin. Fields
+/// the parent or a mixin already has are left out, keeping the inherited
+/// declaration.
+pub fn generate_composed_model(graph: &ProjectGraph, node: &Node) -> String {
+    let parent = graph.parent_entity(node.id).and_then(|id| graph.get_node(id));
+    let mut inherited: HashSet<String> = parent
+        .map(|parent| graph.effective_fields(parent.id).into_iter().map(|f| f.name).collect())
+        .unwrap_or_default();
+    let mixins: Vec<Mixin> = node.mixins.iter()
+        .filter_map(|name| graph.mixin(name))
+        .filter(|mixin| !mixin.fields.iter().all(|f| inherited.contains(&f.name)))
+        .collect();
+    if parent.is_none() && mixins.is_empty() {
+        return format!("{}\n{}", generate_model(node), generate_model_impl(node));
+    }
+    inherited.extend(mixins.iter().flat_map(|m| m.fields.iter().map(|f| f.name.clone())));
+
+    let mut own = node.clone();
+    own.fields.retain(|f| !inherited.contains(&f.name));
+
+    // Paths are relative to the models root, reached through `super`s
+    let root = "super::".repeat(crate::rust::module_path(node).len() + 1);
+    let mut imports = Vec::new();
+    let mut embedded = Vec::new();
+    let mut impls = String::new();
+    if let Some(parent) = parent {
+        let path: String = crate::rust::module_path(parent).iter().map(|m| format!("{}::", m)).collect();
+        imports.push(format!("use {}{}{};", root, path, parent.name));
+        embedded.push(Embedded {
+            field: "base".to_string(),
+            type_name: parent.name.clone(),
+            doc: format!("Fields inherited from {}", parent.name),
+        });
+        impls.push_str(&format!(
+            "\nimpl AsRef<{parent}> for {name} {{\n    fn as_ref(&self) -> &{parent} {{\n        &self.base\n    }}\n}}\n\n\
+             impl AsMut<{parent}> for {name} {{\n    fn as_mut(&mut self) -> &mut {parent} {{\n        &mut self.base\n    }}\n}}\n",
+            parent = parent.name,
+            name = node.name,
+        ));
+    }
+    for mixin in &mixins {
+        let field = to_snake_case(&mixin.name);
+        imports.push(format!("use {}mixins::{{Has{}, {}}};", root, mixin.name, mixin.name));
+        impls.push_str(&format!(
+            "\nimpl Has{mixin} for {name} {{\n    fn {field}(&self) -> &{mixin} {{\n        &self.{field}\n    }}\n\n\
+             \x20   fn {field}_mut(&mut self) -> &mut {mixin} {{\n        &mut self.{field}\n    }}\n}}\n",
+            mixin = mixin.name,
+            name = node.name,
+        ));
+        embedded.push(Embedded {
+            field,
+            type_name: mixin.name.clone(),
+            doc: mixin.description.clone().unwrap_or_else(|| format!("{} fields", mixin.name)),
+        });
+    }
+
+    format!("{}{}\n{}", model_struct(&own, &imports, &embedded), impls, model_impl(&own, &embedded))
+}
+
+/// Generate the `mixins` module: a struct and a `Has<Mixin>` trait per mixin
+pub fn generate_mixins(mixins: &[Mixin]) -> String {
+    let mut output = String::from("//! Field sets shared by models\n//!\n//! Generated by Immortal Engine\n\n");
+    output.push_str("use serde::{Deserialize, Serialize};\n");
+    let fields: Vec<_> = mixins.iter().flat_map(|m| &m.fields).collect();
+    if fields.iter().any(|f| matches!(f.data_type, DataType::Uuid)) {
+        output.push_str("use uuid::Uuid;\n");
+    }
+    if fields.iter().any(|f| matches!(f.data_type, DataType::DateTime | DataType::Date | DataType::Time)) {
+        output.push_str("use chrono::{DateTime, Utc};\n");
+    }
+    if fields.iter().any(|f| project_type_name(&f.data_type).is_some()) {
+        output.push_str("use super::types::*;\n");
+    }
+
+    for mixin in mixins {
+        let field = to_snake_case(&mixin.name);
+        if let Some(description) = &mixin.description {
+            output.push_str(&format!("\n/// {}\n", description));
+        } else {
+            output.push('\n');
+        }
+        output.push_str("#[derive(Debug, Clone, Serialize, Deserialize)]\n");
+        output.push_str(&format!("pub struct {} {{\n", mixin.name));
+        for f in &mixin.fields {
+            output.push_str(&format!("    pub {}: {},\n", to_snake_case(&f.name), f.rust_type()));
+        }
+        output.push_str("}\n");
+
+        output.push_str(&format!("\nimpl Default for {} {{\n    fn default() -> Self {{\n        Self {{\n", mixin.name));
+        for f in &mixin.fields {
+            output.push_str(&format!("            {}: {},\n", to_snake_case(&f.name), get_default_value(&f.data_type, f.required)));
+        }
+        output.push_str("        }\n    }\n}\n");
+
+        output.push_str(&format!("\n/// Models including the {} fields\npub trait Has{} {{\n", mixin.name, mixin.name));
+        output.push_str(&format!("    fn {}(&self) -> &{};\n", field, mixin.name));
+        output.push_str(&format!("    fn {}_mut(&mut self) -> &mut {};\n}}\n", field, mixin.name));
+    }
+    output
+}
+
+/// The model's derives: the standard ones, then any valid paths the node
+/// adds
+fn derive_attribute(node: &Node) -> String {
+    let mut derives = vec!["Debug", "Clone", "Serialize", "Deserialize"];
+    for derive in node.codegen.derives.iter().map(|d| d.trim()) {
+        if syn::parse_str::<syn::Path>(derive).is_ok() && !derives.contains(&derive) {
+            derives.push(derive);
+        }
+    }
+    format!("#[derive({})]\n", derives.join(", "))
+}
+
+/// Model struct with `imports` and `embedded` structs before its own fields
+fn model_struct(node: &Node, imports: &[String], embedded: &[Embedded]) -> String {
     let mut output = String::new();
 
     // Add module documentation
@@ -24,12 +157,30 @@ pub fn generate_model(node: &Node) -> String {
     if has_datetime_field(node) {
         output.push_str("use chrono::{DateTime, Utc};\n");
     }
+    if node.fields.iter().any(|f| project_type_name(&f.data_type).is_some()) {
+        // The types module sits at the models root
+        let root = "super::".repeat(crate::rust::module_path(node).len() + 1);
+        output.push_str(&format!("use {}types::*;\n", root));
+    }
+    if !imports.is_empty() {
+        output.push('\n');
+        for import in imports {
+            output.push_str(import);
+            output.push('\n');
+        }
+    }
     output.push('\n');
 
     // Add struct definition
-    output.push_str("#[derive(Debug, Clone, Serialize, Deserialize)]\n");
+    output.push_str(&derive_attribute(node));
     output.push_str(&format!("pub struct {} {{\n", node.name));
 
+    for embedded in embedded {
+        output.push_str(&format!("    /// {}\n", embedded.doc));
+        output.push_str("    #[serde(flatten)]\n");
+        output.push_str(&format!("    pub {}: {},\n", embedded.field, embedded.type_name));
+    }
+
     for field in &node.fields {
         let rust_type = field.rust_type();
         let field_name = to_snake_case(&field.name);
@@ -38,10 +189,15 @@ pub fn generate_model(node: &Node) -> String {
         if let Some(desc) = &field.description {
             output.push_str(&format!("    /// {}\n", desc));
         }
+        if let Some(unit) = &field.unit {
+            output.push_str(&format!("    /// Unit: {}\n", unit));
+        }
 
         output.push_str(&format!("    pub {}: {},\n", field_name, rust_type));
     }
 
+    // Fields added by hand, kept when regenerating
+    output.push_str(&protected_region("    ", CUSTOM_FIELDS));
     output.push_str("}\n");
 
     output
@@ -49,6 +205,11 @@ pub fn generate_model(node: &Node) -> String {
 
 /// Generate impl block with common methods
 pub fn generate_model_impl(node: &Node) -> String {
+    model_impl(node, &[])
+}
+
+/// Constructor and `Default` impl, defaulting `embedded` structs too
+fn model_impl(node: &Node, embedded: &[Embedded]) -> String {
     let mut output = String::new();
 
     output.push_str(&format!("\nimpl {} {{\n", node.name));
@@ -66,11 +227,15 @@ pub fn generate_model_impl(node: &Node) -> String {
     output.push_str("    fn default() -> Self {\n");
     output.push_str("        Self {\n");
 
+    for embedded in embedded {
+        output.push_str(&format!("            {}: Default::default(),\n", embedded.field));
+    }
     for field in &node.fields {
         let field_name = to_snake_case(&field.name);
         let default_value = get_default_value(&field.data_type, field.required);
         output.push_str(&format!("            {}: {},\n", field_name, default_value));
     }
+    output.push_str(&protected_region("            ", CUSTOM_DEFAULTS));
 
     output.push_str("        }\n");
     output.push_str("    }\n");
@@ -137,6 +302,7 @@ fn to_snake_case(s: &str) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use imortal_ir::{Field, Unit};
 
     #[test]
     fn test_to_snake_case() {
@@ -145,6 +311,22 @@ mod tests {
         assert_eq!(to_snake_case("id"), "id");
     }
 
+    #[test]
+    fn test_extra_derives() {
+        let mut node = Node::new_entity("User");
+        node.codegen.derives = vec!["PartialEq".to_string(), " sqlx::FromRow".to_string(), "Clone".to_string(), "not a path".to_string()];
+        let model = generate_model(&node);
+        assert!(model.contains("#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, sqlx::FromRow)]\npub struct User {"), "{}", model);
+    }
+
+    #[test]
+    fn test_unit_doc() {
+        let mut node = Node::new_entity("Product");
+        node.add_field(Field::long("price").required().with_description("List price in cents").with_unit(Unit::Currency("EUR".to_string())));
+        let model = generate_model(&node);
+        assert!(model.contains("    /// List price in cents\n    /// Unit: currency (EUR)\n    pub price: i64,"), "{}", model);
+    }
+
     #[test]
     fn test_get_default_value() {
         assert_eq!(get_default_value(&DataType::String, true), "String::new()");
@@ -0,0 +1,553 @@
+//! Observability Code Generation
+//!
+//! Generates the `observability` module of a service: `/healthz` and `/readyz`
+//! endpoints, Prometheus metrics via the `metrics` crate, and tracing setup with
+//! an optional OTLP exporter.
+
+use imortal_core::DataType;
+use imortal_ir::{Node, ProjectGraph};
+
+use crate::rust::{AppSetting, AuthFramework};
+
+/// Observability features emitted by the code generator
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ObservabilityConfig {
+    /// Generate `/healthz` and `/readyz` endpoints
+    pub health_checks: bool,
+    /// Generate Prometheus metrics and request instrumentation
+    pub metrics: bool,
+    /// Generate tracing spans in handlers and an OTLP exporter
+    pub tracing: bool,
+    /// Path the Prometheus metrics are served on
+    pub metrics_path: String,
+    /// Default OTLP collector endpoint
+    pub otlp_endpoint: String,
+}
+
+impl Default for ObservabilityConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ObservabilityConfig {
+    /// Create a config with every feature disabled
+    pub fn new() -> Self {
+        Self {
+            health_checks: false,
+            metrics: false,
+            tracing: false,
+            metrics_path: "/metrics".to_string(),
+            otlp_endpoint: "http://localhost:4317".to_string(),
+        }
+    }
+
+    /// Create a config with every feature enabled
+    pub fn all() -> Self {
+        Self {
+            health_checks: true,
+            metrics: true,
+            tracing: true,
+            ..Self::new()
+        }
+    }
+
+    /// Read the toggles from a `service.observability` node
+    pub fn from_node(node: &Node) -> Self {
+        let defaults = Self::all();
+        Self {
+            health_checks: node.get_config_bool("health_checks").unwrap_or(defaults.health_checks),
+            metrics: node.get_config_bool("metrics").unwrap_or(defaults.metrics),
+            tracing: node.get_config_bool("tracing").unwrap_or(defaults.tracing),
+            metrics_path: node.get_config_str("metrics_path")
+                .filter(|p| !p.is_empty())
+                .unwrap_or(&defaults.metrics_path)
+                .to_string(),
+            otlp_endpoint: node.get_config_str("otlp_endpoint")
+                .filter(|e| !e.is_empty())
+                .unwrap_or(&defaults.otlp_endpoint)
+                .to_string(),
+        }
+    }
+
+    /// Combine this config with the `service.observability` nodes in a graph
+    ///
+    /// A feature is enabled when either the generator config or any node
+    /// enables it; node paths and endpoints take precedence.
+    pub fn resolve(&self, graph: &ProjectGraph) -> Self {
+        let mut resolved = self.clone();
+        for node in graph.find_nodes_by_type("service.observability") {
+            let from_node = Self::from_node(node);
+            resolved.health_checks |= from_node.health_checks;
+            resolved.metrics |= from_node.metrics;
+            resolved.tracing |= from_node.tracing;
+            resolved.metrics_path = from_node.metrics_path;
+            resolved.otlp_endpoint = from_node.otlp_endpoint;
+        }
+        resolved
+    }
+
+    // ========== Builder Methods ==========
+
+    /// Enable or disable health check endpoints
+    pub fn with_health_checks(mut self, enabled: bool) -> Self {
+        self.health_checks = enabled;
+        self
+    }
+
+    /// Enable or disable Prometheus metrics
+    pub fn with_metrics(mut self, enabled: bool) -> Self {
+        self.metrics = enabled;
+        self
+    }
+
+    /// Enable or disable tracing instrumentation
+    pub fn with_tracing(mut self, enabled: bool) -> Self {
+        self.tracing = enabled;
+        self
+    }
+
+    /// Set the metrics path
+    pub fn with_metrics_path(mut self, path: impl Into<String>) -> Self {
+        self.metrics_path = path.into();
+        self
+    }
+
+    /// Set the default OTLP collector endpoint
+    pub fn with_otlp_endpoint(mut self, endpoint: impl Into<String>) -> Self {
+        self.otlp_endpoint = endpoint.into();
+        self
+    }
+
+    // ========== Query Methods ==========
+
+    /// Whether any observability feature is enabled
+    pub fn is_enabled(&self) -> bool {
+        self.health_checks || self.metrics || self.tracing
+    }
+
+    /// Settings the generated observability module reads from `Config`
+    pub fn settings(&self, service_name: &str) -> Vec<AppSetting> {
+        if !self.tracing {
+            return Vec::new();
+        }
+
+        vec![
+            AppSetting::new("otlp_endpoint", DataType::String, "OTEL_EXPORTER_OTLP_ENDPOINT")
+                .with_default(self.otlp_endpoint.as_str())
+                .with_description("OTLP collector endpoint for trace export")
+                .in_group("Observability"),
+            AppSetting::new("otel_service_name", DataType::String, "OTEL_SERVICE_NAME")
+                .with_default(service_name)
+                .with_description("Service name reported to the trace collector")
+                .in_group("Observability"),
+        ]
+    }
+
+    /// External dependencies needed by the generated module as (name, spec) pairs
+    pub fn dependencies(&self) -> Vec<(&'static str, &'static str)> {
+        let mut deps = Vec::new();
+        if self.metrics {
+            deps.push(("metrics", r#""0.23""#));
+            deps.push(("metrics-exporter-prometheus", r#"{ version = "0.15", default-features = false }"#));
+        }
+        if self.tracing {
+            deps.push(("opentelemetry", r#""0.24""#));
+            deps.push(("opentelemetry_sdk", r#"{ version = "0.24", features = ["rt-tokio"] }"#));
+            deps.push(("opentelemetry-otlp", r#""0.17""#));
+            deps.push(("tracing-opentelemetry", r#""0.25""#));
+        }
+        deps
+    }
+}
+
+/// Add a tracing span to every `pub async fn` handler in generated source
+///
+/// Handlers on their own line get the attribute on the line above; handlers
+/// rendered inline from token streams get it in place.
+pub fn instrument_handlers(source: &str, config: &ObservabilityConfig) -> String {
+    const ATTR: &str = "#[tracing::instrument(skip_all)]";
+
+    if !config.tracing {
+        return source.to_string();
+    }
+
+    let mut result = source.lines()
+        .map(|line| {
+            let trimmed = line.trim_start();
+            if trimmed.starts_with("pub async fn ") {
+                let indent = &line[..line.len() - trimmed.len()];
+                format!("{}{}\n{}", indent, ATTR, line)
+            } else {
+                line.replace("pub async fn ", &format!("{} pub async fn ", ATTR))
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    if source.ends_with('\n') {
+        result.push('\n');
+    }
+    result
+}
+
+/// Generate observability.rs content
+pub fn generate_observability(
+    framework: AuthFramework,
+    crate_name: &str,
+    config: &ObservabilityConfig,
+) -> String {
+    let mut content = String::from(
+        r#"//! Observability: health checks, metrics, and tracing
+//!
+//! Generated by Immortal Engine
+
+use crate::Config;
+"#,
+    );
+
+    content.push_str(&generate_tracing_init(crate_name, config));
+
+    if config.metrics {
+        content.push_str(&generate_metrics(framework));
+    }
+
+    if config.health_checks {
+        content.push_str(&generate_health_checks(framework));
+    }
+
+    if framework != AuthFramework::Custom && (config.health_checks || config.metrics) {
+        content.push_str(&generate_routes(framework, config));
+    }
+
+    content
+}
+
+/// Generate `init_tracing`, with an OTLP exporter when tracing is enabled
+fn generate_tracing_init(crate_name: &str, config: &ObservabilityConfig) -> String {
+    if !config.tracing {
+        return format!(
+            r#"
+use tracing_subscriber::{{layer::SubscriberExt, util::SubscriberInitExt}};
+
+/// Initialize the tracing subscriber
+pub fn init_tracing(_config: &Config) -> anyhow::Result<()> {{
+    tracing_subscriber::registry()
+        .with(
+            tracing_subscriber::EnvFilter::try_from_default_env()
+                .unwrap_or_else(|_| "{}=debug".into()),
+        )
+        .with(tracing_subscriber::fmt::layer())
+        .init();
+
+    Ok(())
+}}
+"#,
+            crate_name,
+        );
+    }
+
+    format!(
+        r#"
+use opentelemetry::trace::TracerProvider as _;
+use opentelemetry_otlp::WithExportConfig;
+use tracing_subscriber::{{layer::SubscriberExt, util::SubscriberInitExt}};
+
+/// Initialize the tracing subscriber with an OTLP span exporter
+pub fn init_tracing(config: &Config) -> anyhow::Result<()> {{
+    let provider = opentelemetry_otlp::new_pipeline()
+        .tracing()
+        .with_exporter(
+            opentelemetry_otlp::new_exporter()
+                .tonic()
+                .with_endpoint(&config.otlp_endpoint),
+        )
+        .with_trace_config(opentelemetry_sdk::trace::Config::default().with_resource(
+            opentelemetry_sdk::Resource::new(vec![opentelemetry::KeyValue::new(
+                "service.name",
+                config.otel_service_name.clone(),
+            )]),
+        ))
+        .install_batch(opentelemetry_sdk::runtime::Tokio)?;
+
+    let tracer = provider.tracer("{}");
+    opentelemetry::global::set_tracer_provider(provider);
+
+    tracing_subscriber::registry()
+        .with(
+            tracing_subscriber::EnvFilter::try_from_default_env()
+                .unwrap_or_else(|_| "{}=debug".into()),
+        )
+        .with(tracing_subscriber::fmt::layer())
+        .with(tracing_opentelemetry::layer().with_tracer(tracer))
+        .init();
+
+    Ok(())
+}}
+
+/// Flush pending spans before shutdown
+pub fn shutdown_tracing() {{
+    opentelemetry::global::shutdown_tracer_provider();
+}}
+"#,
+        crate_name,
+        crate_name,
+    )
+}
+
+/// Generate the Prometheus recorder and request metrics middleware
+fn generate_metrics(framework: AuthFramework) -> String {
+    let mut content = String::from(
+        r#"
+use std::sync::OnceLock;
+use std::time::Instant;
+
+use metrics_exporter_prometheus::{PrometheusBuilder, PrometheusHandle};
+
+static PROMETHEUS: OnceLock<PrometheusHandle> = OnceLock::new();
+
+/// Install the global Prometheus metrics recorder
+pub fn init_metrics() -> anyhow::Result<()> {
+    let handle = PrometheusBuilder::new().install_recorder()?;
+    let _ = PROMETHEUS.set(handle);
+    Ok(())
+}
+
+/// Render all metrics in the Prometheus text format
+pub fn render_metrics() -> String {
+    PROMETHEUS.get().map(|handle| handle.render()).unwrap_or_default()
+}
+
+/// Record one handled request
+fn record_request(method: &str, path: &str, status: u16, started: Instant) {
+    let labels = [
+        ("method", method.to_string()),
+        ("path", path.to_string()),
+        ("status", status.to_string()),
+    ];
+    metrics::counter!("http_requests_total", &labels).increment(1);
+    metrics::histogram!("http_request_duration_seconds", &labels)
+        .record(started.elapsed().as_secs_f64());
+}
+"#,
+    );
+
+    match framework {
+        AuthFramework::Axum => content.push_str(
+            r#"
+/// Middleware recording request counts and latencies
+pub async fn track_metrics(
+    request: axum::extract::Request,
+    next: axum::middleware::Next,
+) -> axum::response::Response {
+    let started = Instant::now();
+    let method = request.method().to_string();
+    let path = request
+        .extensions()
+        .get::<axum::extract::MatchedPath>()
+        .map(|p| p.as_str().to_string())
+        .unwrap_or_else(|| request.uri().path().to_string());
+
+    let response = next.run(request).await;
+    record_request(&method, &path, response.status().as_u16(), started);
+    response
+}
+"#,
+        ),
+        AuthFramework::Actix => content.push_str(
+            r#"
+/// Middleware recording request counts and latencies
+pub async fn track_metrics(
+    request: actix_web::dev::ServiceRequest,
+    next: actix_web::middleware::Next<impl actix_web::body::MessageBody>,
+) -> Result<actix_web::dev::ServiceResponse<impl actix_web::body::MessageBody>, actix_web::Error> {
+    let started = Instant::now();
+    let method = request.method().to_string();
+    let path = request
+        .match_pattern()
+        .unwrap_or_else(|| request.path().to_string());
+
+    let response = next.call(request).await?;
+    record_request(&method, &path, response.status().as_u16(), started);
+    Ok(response)
+}
+"#,
+        ),
+        AuthFramework::Custom => {}
+    }
+
+    content
+}
+
+/// Generate the `/healthz` and `/readyz` handlers
+fn generate_health_checks(framework: AuthFramework) -> String {
+    match framework {
+        AuthFramework::Axum => r#"
+use axum::{extract::State, http::StatusCode, Json};
+use crate::AppState;
+
+/// Liveness probe: the process is up
+pub async fn healthz() -> Json<serde_json::Value> {
+    Json(serde_json::json!({ "status": "ok" }))
+}
+
+/// Readiness probe: dependencies such as the database are reachable
+pub async fn readyz(State(state): State<AppState>) -> (StatusCode, Json<serde_json::Value>) {
+    match sqlx::query("SELECT 1").execute(&*state.db).await {
+        Ok(_) => (StatusCode::OK, Json(serde_json::json!({ "status": "ready" }))),
+        Err(e) => {
+            tracing::warn!("Readiness check failed: {}", e);
+            (
+                StatusCode::SERVICE_UNAVAILABLE,
+                Json(serde_json::json!({ "status": "unavailable", "error": e.to_string() })),
+            )
+        }
+    }
+}
+"#
+        .to_string(),
+        AuthFramework::Actix => r#"
+use actix_web::{web, HttpResponse};
+use crate::AppState;
+
+/// Liveness probe: the process is up
+pub async fn healthz() -> HttpResponse {
+    HttpResponse::Ok().json(serde_json::json!({ "status": "ok" }))
+}
+
+/// Readiness probe: dependencies such as the database are reachable
+pub async fn readyz(state: web::Data<AppState>) -> HttpResponse {
+    match sqlx::query("SELECT 1").execute(&*state.db).await {
+        Ok(_) => HttpResponse::Ok().json(serde_json::json!({ "status": "ready" })),
+        Err(e) => {
+            tracing::warn!("Readiness check failed: {}", e);
+            HttpResponse::ServiceUnavailable()
+                .json(serde_json::json!({ "status": "unavailable", "error": e.to_string() }))
+        }
+    }
+}
+"#
+        .to_string(),
+        AuthFramework::Custom => String::new(),
+    }
+}
+
+/// Generate the router (Axum) or route configuration (Actix) for the endpoints
+fn generate_routes(framework: AuthFramework, config: &ObservabilityConfig) -> String {
+    let mut routes = Vec::new();
+
+    match framework {
+        AuthFramework::Axum => {
+            if config.health_checks {
+                routes.push("        .route(\"/healthz\", axum::routing::get(healthz))".to_string());
+                routes.push("        .route(\"/readyz\", axum::routing::get(readyz))".to_string());
+            }
+            if config.metrics {
+                routes.push(format!(
+                    "        .route(\"{}\", axum::routing::get(|| async {{ render_metrics() }}))",
+                    config.metrics_path
+                ));
+            }
+
+            format!(
+                r#"
+/// Create the router for the observability endpoints
+pub fn router() -> axum::Router<crate::AppState> {{
+    axum::Router::new()
+{}
+}}
+"#,
+                routes.join("\n"),
+            )
+        }
+        AuthFramework::Actix => {
+            if config.health_checks {
+                routes.push("    cfg.route(\"/healthz\", actix_web::web::get().to(healthz));".to_string());
+                routes.push("    cfg.route(\"/readyz\", actix_web::web::get().to(readyz));".to_string());
+            }
+            if config.metrics {
+                routes.push(format!(
+                    "    cfg.route(\"{}\", actix_web::web::get().to(|| async {{ render_metrics() }}));",
+                    config.metrics_path
+                ));
+            }
+
+            format!(
+                r#"
+/// Configure the observability endpoints
+pub fn configure(cfg: &mut actix_web::web::ServiceConfig) {{
+{}
+}}
+"#,
+                routes.join("\n"),
+            )
+        }
+        AuthFramework::Custom => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use imortal_ir::ProjectMeta;
+
+    #[test]
+    fn test_resolve_from_node() {
+        let mut graph = ProjectGraph::new(ProjectMeta::new("shop"));
+        let mut node = Node::new_observability("Observability");
+        node.set_config("tracing", false);
+        node.set_config("metrics_path", "/prom");
+        graph.add_node(node);
+
+        let config = ObservabilityConfig::new().resolve(&graph);
+        assert!(config.health_checks);
+        assert!(config.metrics);
+        assert!(!config.tracing);
+        assert_eq!(config.metrics_path, "/prom");
+
+        let forced = ObservabilityConfig::new().with_tracing(true).resolve(&graph);
+        assert!(forced.tracing);
+    }
+
+    #[test]
+    fn test_generate_observability_axum() {
+        let code = generate_observability(AuthFramework::Axum, "shop", &ObservabilityConfig::all());
+        assert!(code.contains("pub async fn healthz()"));
+        assert!(code.contains("pub async fn readyz("));
+        assert!(code.contains("PrometheusBuilder::new()"));
+        assert!(code.contains("opentelemetry_otlp::new_pipeline()"));
+        assert!(code.contains(".route(\"/metrics\""));
+        assert!(code.contains("pub async fn track_metrics("));
+    }
+
+    #[test]
+    fn test_generate_observability_actix_health_only() {
+        let config = ObservabilityConfig::new().with_health_checks(true);
+        let code = generate_observability(AuthFramework::Actix, "shop", &config);
+        assert!(code.contains("cfg.route(\"/healthz\""));
+        assert!(!code.contains("PrometheusBuilder"));
+        assert!(!code.contains("opentelemetry"));
+    }
+
+    #[test]
+    fn test_settings_and_dependencies() {
+        assert!(ObservabilityConfig::new().settings("shop").is_empty());
+        assert!(ObservabilityConfig::new().dependencies().is_empty());
+
+        let config = ObservabilityConfig::all();
+        let settings = config.settings("shop");
+        assert!(settings.iter().any(|s| s.env_var == "OTEL_EXPORTER_OTLP_ENDPOINT"));
+        assert!(config.dependencies().iter().any(|(name, _)| *name == "metrics"));
+    }
+
+    #[test]
+    fn test_instrument_handlers() {
+        let source = "/// List users\npub async fn list_users() {}\n# [doc = \" x\"] pub async fn login () { }\n";
+        let instrumented = instrument_handlers(source, &ObservabilityConfig::all());
+
+        assert!(instrumented.contains("/// List users\n#[tracing::instrument(skip_all)]\npub async fn list_users()"));
+        assert!(instrumented.contains("#[tracing::instrument(skip_all)] pub async fn login ()"));
+        assert!(instrumented.ends_with('\n'));
+        assert_eq!(instrument_handlers(source, &ObservabilityConfig::new()), source);
+    }
+}
@@ -0,0 +1,510 @@
+//! Payments Code Generation
+//!
+//! Entities connected to a `service.payments` node are products customers
+//! can buy: each holds the provider's price id in a mapped field. The
+//! generated `payments` module starts checkout sessions for them and
+//! receives the provider's webhooks, verifying their signatures.
+
+use imortal_core::DataType;
+use imortal_ir::{Node, ProjectGraph};
+
+use crate::rust::config::AppSetting;
+use crate::rust::migrations::DatabaseBackend;
+use crate::rust::{to_snake_case, AuthFramework};
+
+/// Payment provider the generated code talks to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum PaymentProvider {
+    /// Stripe Checkout and webhooks
+    #[default]
+    Stripe,
+}
+
+impl PaymentProvider {
+    /// Providers a payments component can use
+    pub const ALL: [PaymentProvider; 1] = [PaymentProvider::Stripe];
+
+    /// Identifier stored in the node's `provider` config
+    pub fn id(&self) -> &'static str {
+        match self {
+            PaymentProvider::Stripe => "stripe",
+        }
+    }
+
+    /// Provider with the given identifier, ignoring case
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|provider| provider.id().eq_ignore_ascii_case(id.trim()))
+    }
+}
+
+/// What a checkout session sells
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum CheckoutMode {
+    /// A one-time payment
+    #[default]
+    Payment,
+    /// A recurring subscription
+    Subscription,
+}
+
+impl CheckoutMode {
+    /// Identifier stored in the node's `mode` config, as Stripe names it
+    pub fn id(&self) -> &'static str {
+        match self {
+            CheckoutMode::Payment => "payment",
+            CheckoutMode::Subscription => "subscription",
+        }
+    }
+
+    /// Mode with the given identifier, ignoring case
+    pub fn from_id(id: &str) -> Option<Self> {
+        [CheckoutMode::Payment, CheckoutMode::Subscription]
+            .into_iter()
+            .find(|mode| mode.id().eq_ignore_ascii_case(id.trim()))
+    }
+}
+
+/// An entity customers can buy
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PurchasableEntity {
+    /// Entity name checkout requests name
+    pub name: String,
+    /// Table the entity is stored in
+    pub table: String,
+    /// Primary key field checkout requests identify the record by
+    pub key: String,
+}
+
+/// Payments generated for a project
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PaymentsConfig {
+    /// Provider the checkout and webhooks go through
+    pub provider: PaymentProvider,
+    /// What checkout sessions sell
+    pub mode: CheckoutMode,
+    /// Field of each product entity holding the provider's price id
+    pub price_field: String,
+    /// Path checkout sessions are started on
+    pub checkout_path: String,
+    /// Path the provider's webhooks are received on
+    pub webhook_path: String,
+    /// Entities customers can buy, sorted by name
+    pub products: Vec<PurchasableEntity>,
+    /// Connected entities left out for lacking the price field
+    pub unmapped: Vec<String>,
+}
+
+impl PaymentsConfig {
+    /// Payments of the first `service.payments` node in a graph, selling
+    /// the entities connected to it
+    pub fn from_graph(graph: &ProjectGraph) -> Option<Self> {
+        let node = graph.find_nodes_by_type("service.payments").into_iter().next()?;
+        let config = |key: &str, default: &str| {
+            node.get_config_str(key)
+                .filter(|v| !v.trim().is_empty())
+                .unwrap_or(default)
+                .to_string()
+        };
+        let price_field = config("price_field", "stripe_price_id");
+
+        let mut connected: Vec<&Node> = graph
+            .edges()
+            .filter(|e| e.connects_to(node.id))
+            .map(|e| if e.from_node == node.id { e.to_node } else { e.from_node })
+            .filter_map(|id| graph.get_node(id))
+            .filter(|n| n.component_type == "data.entity")
+            .collect();
+        connected.sort_by(|a, b| a.name.cmp(&b.name));
+        connected.dedup_by_key(|n| n.id);
+
+        let mut products = Vec::new();
+        let mut unmapped = Vec::new();
+        for entity in connected {
+            let fields = graph.effective_fields(entity.id);
+            let priced = fields
+                .iter()
+                .any(|f| f.name == price_field && matches!(f.data_type, DataType::String | DataType::Text));
+            if !priced {
+                unmapped.push(entity.name.clone());
+                continue;
+            }
+            products.push(PurchasableEntity {
+                name: entity.name.clone(),
+                table: to_snake_case(&entity.name),
+                key: fields
+                    .iter()
+                    .find(|f| f.is_primary_key())
+                    .map_or_else(|| "id".to_string(), |f| f.name.clone()),
+            });
+        }
+
+        Some(Self {
+            provider: node.get_config_str("provider").and_then(PaymentProvider::from_id).unwrap_or_default(),
+            mode: node.get_config_str("mode").and_then(CheckoutMode::from_id).unwrap_or_default(),
+            price_field,
+            checkout_path: config("checkout_path", "/payments/checkout"),
+            webhook_path: config("webhook_path", "/payments/webhook"),
+            products,
+            unmapped,
+        })
+    }
+
+    /// Settings the generated module reads, written to `.env.example`
+    pub fn settings(&self) -> Vec<AppSetting> {
+        vec![
+            AppSetting::new("stripe_secret_key", DataType::String, "STRIPE_SECRET_KEY")
+                .required()
+                .secret()
+                .with_description("Stripe secret API key")
+                .with_example("sk_test_change-me")
+                .in_group("Payments"),
+            AppSetting::new("stripe_webhook_secret", DataType::String, "STRIPE_WEBHOOK_SECRET")
+                .required()
+                .secret()
+                .with_description("Signing secret of the Stripe webhook endpoint")
+                .with_example("whsec_change-me")
+                .in_group("Payments"),
+            AppSetting::new("stripe_publishable_key", DataType::String, "STRIPE_PUBLISHABLE_KEY")
+                .with_description("Stripe publishable key handed to the frontend")
+                .with_example("pk_test_change-me")
+                .in_group("Payments"),
+            AppSetting::new("checkout_success_url", DataType::String, "CHECKOUT_SUCCESS_URL")
+                .with_default("http://localhost:3000/checkout/success")
+                .with_description("Page customers return to after paying")
+                .in_group("Payments"),
+            AppSetting::new("checkout_cancel_url", DataType::String, "CHECKOUT_CANCEL_URL")
+                .with_default("http://localhost:3000/checkout/cancel")
+                .with_description("Page customers return to after abandoning checkout")
+                .in_group("Payments"),
+        ]
+    }
+
+    /// Crates the generated module needs
+    pub fn dependencies(&self) -> Vec<(&'static str, &'static str)> {
+        vec![
+            ("reqwest", r#"{ version = "0.12", features = ["json"] }"#),
+            ("hmac", r#""0.12""#),
+            ("sha2", r#""0.10""#),
+            ("hex", r#""0.4""#),
+        ]
+    }
+}
+
+/// Generate payments.rs content: the checkout session and webhook handlers
+/// with signature verification, and their routes
+pub fn generate_payments(framework: AuthFramework, backend: DatabaseBackend, config: &PaymentsConfig) -> String {
+    let (param, text) = match backend {
+        DatabaseBackend::Postgres => ("$1", "TEXT"),
+        DatabaseBackend::Mysql => ("?", "CHAR"),
+        DatabaseBackend::Sqlite => ("?", "TEXT"),
+    };
+    let lookups: Vec<String> = config
+        .products
+        .iter()
+        .map(|product| {
+            format!(
+                "        \"{name}\" => \"SELECT {price} FROM {table} WHERE CAST({key} AS {text}) = {param}\",",
+                name = product.name,
+                price = config.price_field,
+                table = product.table,
+                key = product.key,
+            )
+        })
+        .collect();
+
+    let mut content = format!(
+        r#"//! Checkout sessions and webhooks for {provider}
+//!
+//! Generated by Immortal Engine
+
+use hmac::{{Hmac, Mac}};
+use serde::{{Deserialize, Serialize}};
+
+use crate::config::DatabasePool;
+
+/// Error of a checkout or webhook call
+pub type PaymentsError = Box<dyn std::error::Error + Send + Sync>;
+
+/// Seconds a webhook signature stays valid, against replayed events
+const SIGNATURE_TOLERANCE_SECONDS: i64 = 300;
+
+/// A product to check out: a record of a purchasable entity
+#[derive(Debug, Clone, Deserialize)]
+pub struct CheckoutRequest {{
+    pub entity: String,
+    pub id: String,
+    pub quantity: Option<u64>,
+}}
+
+/// A started checkout session; send the customer to `url`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CheckoutSession {{
+    pub id: String,
+    pub url: Option<String>,
+}}
+
+/// Price id of a product record, from its `{price_field}` field
+async fn price_of(db: &DatabasePool, request: &CheckoutRequest) -> Result<Option<String>, PaymentsError> {{
+    let sql = match request.entity.as_str() {{
+{lookups}
+        _ => return Ok(None),
+    }};
+    let price: Option<(Option<String>,)> = sqlx::query_as(sql).bind(&request.id).fetch_optional(db).await?;
+    Ok(price.and_then(|(price,)| price))
+}}
+
+/// Start a Stripe Checkout session for a product; `None` when the record
+/// doesn't exist or has no price
+pub async fn create_checkout(
+    db: &DatabasePool,
+    config: &crate::config::Config,
+    request: &CheckoutRequest,
+) -> Result<Option<CheckoutSession>, PaymentsError> {{
+    let Some(price) = price_of(db, request).await? else {{
+        return Ok(None);
+    }};
+    let quantity = request.quantity.unwrap_or(1).max(1).to_string();
+    let form = [
+        ("mode", "{mode}"),
+        ("line_items[0][price]", price.as_str()),
+        ("line_items[0][quantity]", quantity.as_str()),
+        ("success_url", config.checkout_success_url.as_str()),
+        ("cancel_url", config.checkout_cancel_url.as_str()),
+        ("metadata[entity]", request.entity.as_str()),
+        ("metadata[id]", request.id.as_str()),
+    ];
+    let session = reqwest::Client::new()
+        .post("https://api.stripe.com/v1/checkout/sessions")
+        .bearer_auth(&config.stripe_secret_key)
+        .form(&form)
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+    Ok(Some(session))
+}}
+
+/// Check a `Stripe-Signature` header against the raw payload: an
+/// HMAC-SHA256 of `"{{timestamp}}.{{payload}}"` keyed with the webhook secret,
+/// signed within the tolerance
+pub fn verify_signature(payload: &[u8], header: &str, secret: &str) -> bool {{
+    let mut timestamp = None;
+    let mut signatures = Vec::new();
+    for part in header.split(',') {{
+        match part.trim().split_once('=') {{
+            Some(("t", value)) => timestamp = value.parse::<i64>().ok(),
+            Some(("v1", value)) => signatures.extend(hex::decode(value).ok()),
+            _ => {{}}
+        }}
+    }}
+    let Some(timestamp) = timestamp else {{
+        return false;
+    }};
+    if (chrono::Utc::now().timestamp() - timestamp).abs() > SIGNATURE_TOLERANCE_SECONDS {{
+        return false;
+    }}
+
+    signatures.iter().any(|signature| {{
+        let Ok(mut mac) = Hmac::<sha2::Sha256>::new_from_slice(secret.as_bytes()) else {{
+            return false;
+        }};
+        mac.update(timestamp.to_string().as_bytes());
+        mac.update(b".");
+        mac.update(payload);
+        // Constant-time comparison
+        mac.verify_slice(signature).is_ok()
+    }})
+}}
+
+/// A verified webhook event
+#[derive(Debug, Clone, Deserialize)]
+pub struct WebhookEvent {{
+    pub id: String,
+    #[serde(rename = "type")]
+    pub event_type: String,
+    pub data: WebhookData,
+}}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct WebhookData {{
+    pub object: serde_json::Value,
+}}
+
+/// Handle a verified webhook event
+pub async fn handle_event(_db: &DatabasePool, event: &WebhookEvent) -> Result<(), PaymentsError> {{
+    match event.event_type.as_str() {{
+        "checkout.session.completed" => {{
+            let metadata = &event.data.object["metadata"];
+            tracing::info!(
+                entity = metadata["entity"].as_str(),
+                id = metadata["id"].as_str(),
+                "checkout completed"
+            );
+            // TODO: Fulfil the order
+        }}
+        "checkout.session.expired" | "payment_intent.payment_failed" => {{
+            tracing::warn!(event = %event.event_type, "payment not completed");
+        }}
+        _ => {{}}
+    }}
+    Ok(())
+}}
+"#,
+        provider = match config.provider {
+            PaymentProvider::Stripe => "Stripe",
+        },
+        price_field = config.price_field,
+        lookups = lookups.join("\n"),
+        mode = config.mode.id(),
+    );
+
+    content.push_str(&generate_routes(framework, config));
+    content
+}
+
+/// Generate the checkout and webhook handlers and their router (Axum) or
+/// route configuration (Actix)
+fn generate_routes(framework: AuthFramework, config: &PaymentsConfig) -> String {
+    match framework {
+        AuthFramework::Axum => format!(
+            r#"
+/// Start a checkout session for the requested product
+pub async fn checkout(
+    axum::extract::State(state): axum::extract::State<crate::AppState>,
+    axum::Json(request): axum::Json<CheckoutRequest>,
+) -> Result<axum::Json<CheckoutSession>, (axum::http::StatusCode, String)> {{
+    match create_checkout(&state.db, &state.config, &request).await {{
+        Ok(Some(session)) => Ok(axum::Json(session)),
+        Ok(None) => Err((axum::http::StatusCode::NOT_FOUND, "no such product".to_string())),
+        Err(e) => Err((axum::http::StatusCode::BAD_GATEWAY, e.to_string())),
+    }}
+}}
+
+/// Receive a webhook event, rejecting it unless its signature verifies
+pub async fn webhook(
+    axum::extract::State(state): axum::extract::State<crate::AppState>,
+    headers: axum::http::HeaderMap,
+    body: axum::body::Bytes,
+) -> axum::http::StatusCode {{
+    let signature = headers.get("stripe-signature").and_then(|v| v.to_str().ok()).unwrap_or_default();
+    if !verify_signature(&body, signature, &state.config.stripe_webhook_secret) {{
+        return axum::http::StatusCode::BAD_REQUEST;
+    }}
+    let Ok(event) = serde_json::from_slice::<WebhookEvent>(&body) else {{
+        return axum::http::StatusCode::BAD_REQUEST;
+    }};
+    match handle_event(&state.db, &event).await {{
+        Ok(()) => axum::http::StatusCode::OK,
+        // The provider retries failed deliveries
+        Err(e) => {{
+            tracing::error!(event = %event.id, "webhook failed: {{}}", e);
+            axum::http::StatusCode::INTERNAL_SERVER_ERROR
+        }}
+    }}
+}}
+
+/// Create the router for the checkout and webhook endpoints
+pub fn router() -> axum::Router<crate::AppState> {{
+    axum::Router::new()
+        .route("{checkout}", axum::routing::post(checkout))
+        .route("{webhook}", axum::routing::post(webhook))
+}}
+"#,
+            checkout = config.checkout_path,
+            webhook = config.webhook_path,
+        ),
+        AuthFramework::Actix => format!(
+            r#"
+/// Start a checkout session for the requested product
+pub async fn checkout(
+    state: actix_web::web::Data<crate::AppState>,
+    request: actix_web::web::Json<CheckoutRequest>,
+) -> actix_web::HttpResponse {{
+    match create_checkout(&state.db, &state.config, &request).await {{
+        Ok(Some(session)) => actix_web::HttpResponse::Ok().json(session),
+        Ok(None) => actix_web::HttpResponse::NotFound().body("no such product"),
+        Err(e) => actix_web::HttpResponse::BadGateway().body(e.to_string()),
+    }}
+}}
+
+/// Receive a webhook event, rejecting it unless its signature verifies
+pub async fn webhook(
+    state: actix_web::web::Data<crate::AppState>,
+    req: actix_web::HttpRequest,
+    body: actix_web::web::Bytes,
+) -> actix_web::HttpResponse {{
+    let signature = req.headers().get("stripe-signature").and_then(|v| v.to_str().ok()).unwrap_or_default();
+    if !verify_signature(&body, signature, &state.config.stripe_webhook_secret) {{
+        return actix_web::HttpResponse::BadRequest().finish();
+    }}
+    let Ok(event) = serde_json::from_slice::<WebhookEvent>(&body) else {{
+        return actix_web::HttpResponse::BadRequest().finish();
+    }};
+    match handle_event(&state.db, &event).await {{
+        Ok(()) => actix_web::HttpResponse::Ok().finish(),
+        // The provider retries failed deliveries
+        Err(e) => {{
+            tracing::error!(event = %event.id, "webhook failed: {{}}", e);
+            actix_web::HttpResponse::InternalServerError().finish()
+        }}
+    }}
+}}
+
+/// Configure the checkout and webhook endpoints
+pub fn configure(cfg: &mut actix_web::web::ServiceConfig) {{
+    cfg.route("{checkout}", actix_web::web::post().to(checkout))
+        .route("{webhook}", actix_web::web::post().to(webhook));
+}}
+"#,
+            checkout = config.checkout_path,
+            webhook = config.webhook_path,
+        ),
+        AuthFramework::Custom => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use imortal_ir::{Field, ProjectMeta};
+
+    fn shop_graph() -> ProjectGraph {
+        let mut graph = ProjectGraph::new(ProjectMeta::new("shop"));
+        let mut plan = Node::new_entity("Plan");
+        plan.add_field(Field::string("stripe_price_id"));
+        let plan = graph.add_node(plan);
+        let order = graph.add_node(Node::new_entity("Order"));
+        let payments = graph.add_node(Node::new_payments("Payments"));
+        graph.connect(plan, "entity", payments, "products").unwrap();
+        graph.connect(order, "entity", payments, "products").unwrap();
+        graph
+    }
+
+    #[test]
+    fn test_payments_config() {
+        let config = PaymentsConfig::from_graph(&shop_graph()).unwrap();
+        assert_eq!(config.provider, PaymentProvider::Stripe);
+        assert_eq!(config.products.len(), 1);
+        assert_eq!(config.products[0].name, "Plan");
+        assert_eq!(config.unmapped, ["Order"]);
+        let settings = config.settings();
+        let secrets: Vec<&str> = settings.iter().filter(|s| s.secret).map(|s| s.env_var.as_str()).collect();
+        assert_eq!(secrets, ["STRIPE_SECRET_KEY", "STRIPE_WEBHOOK_SECRET"]);
+        assert!(PaymentsConfig::from_graph(&ProjectGraph::with_name("empty")).is_none());
+    }
+
+    #[test]
+    fn test_generate_payments() {
+        let config = PaymentsConfig::from_graph(&shop_graph()).unwrap();
+        let code = generate_payments(AuthFramework::Axum, DatabaseBackend::Postgres, &config);
+        assert!(code.contains("\"Plan\" => \"SELECT stripe_price_id FROM plan WHERE CAST(id AS TEXT) = $1\","));
+        assert!(code.contains("(\"mode\", \"payment\"),"));
+        assert!(code.contains("pub fn verify_signature(payload: &[u8], header: &str, secret: &str) -> bool {"));
+        assert!(code.contains(".route(\"/payments/webhook\", axum::routing::post(webhook))"));
+
+        let code = generate_payments(AuthFramework::Actix, DatabaseBackend::Sqlite, &config);
+        assert!(code.contains("WHERE CAST(id AS TEXT) = ?\","));
+        assert!(code.contains("cfg.route(\"/payments/checkout\", actix_web::web::post().to(checkout))"));
+    }
+}
@@ -0,0 +1,460 @@
+//! Realtime Sync Code Generation
+//!
+//! Entities flagged `realtime` broadcast their create, update, and delete
+//! events to subscribed clients. The generated `realtime` module keeps a
+//! tokio broadcast channel the repository layer publishes to, and serves
+//! it over WebSocket and Server-Sent Events; a TypeScript client subscribes
+//! to it.
+
+use imortal_ir::{Node, ProjectGraph};
+
+use crate::rust::{module_path, AuthFramework};
+
+/// Path of the WebSocket endpoint
+pub const WEBSOCKET_PATH: &str = "/realtime/ws";
+/// Path of the Server-Sent Events endpoint
+pub const EVENTS_PATH: &str = "/realtime/events";
+
+/// An entity broadcasting its changes
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RealtimeEntity {
+    /// Entity name events are tagged with
+    pub name: String,
+    /// Path of the generated model, e.g. `crate::models::blog::Post`
+    pub model_path: String,
+    /// Primary key field events carry as their id
+    pub key: String,
+}
+
+impl RealtimeEntity {
+    fn from_node(node: &Node) -> Self {
+        let mut path = vec!["crate".to_string(), "models".to_string()];
+        path.extend(module_path(node));
+        path.push(node.name.clone());
+        Self {
+            name: node.name.clone(),
+            model_path: path.join("::"),
+            key: node
+                .fields
+                .iter()
+                .find(|f| f.is_primary_key())
+                .map_or_else(|| "id".to_string(), |f| f.name.clone()),
+        }
+    }
+}
+
+/// Realtime sync generated for a project
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RealtimeConfig {
+    /// Entities broadcasting their changes, sorted by name
+    pub entities: Vec<RealtimeEntity>,
+}
+
+impl RealtimeConfig {
+    /// Realtime entities of a graph; `None` when there are none
+    pub fn from_graph(graph: &ProjectGraph) -> Option<Self> {
+        let mut entities: Vec<RealtimeEntity> = graph
+            .nodes()
+            .filter(|n| n.is_realtime())
+            .map(RealtimeEntity::from_node)
+            .collect();
+        entities.sort_by(|a, b| a.name.cmp(&b.name));
+        (!entities.is_empty()).then_some(Self { entities })
+    }
+
+    /// Crates the generated module needs besides the framework and tokio
+    pub fn dependencies(&self, framework: AuthFramework) -> Vec<(&'static str, &'static str)> {
+        let mut deps = vec![("tokio-stream", r#"{ version = "0.1", features = ["sync"] }"#)];
+        if framework == AuthFramework::Actix {
+            deps.push(("actix-ws", r#""0.3""#));
+        }
+        deps
+    }
+}
+
+/// Generate realtime.rs content: the event channel, the `Broadcast` impls
+/// for realtime models, and the WebSocket and SSE endpoints
+pub fn generate_realtime(framework: AuthFramework, config: &RealtimeConfig) -> String {
+    let names: Vec<String> = config.entities.iter().map(|e| format!("\"{}\"", e.name)).collect();
+    let mut content = format!(
+        r#"//! Realtime entity events
+//!
+//! Generated by Immortal Engine
+
+use std::sync::OnceLock;
+
+use serde::{{Deserialize, Serialize}};
+use tokio::sync::broadcast;
+
+/// Entities broadcasting their changes
+pub const REALTIME_ENTITIES: [&str; {count}] = [{names}];
+
+/// Events kept for subscribers that fall behind
+const CHANNEL_CAPACITY: usize = 1024;
+
+/// Kind of change an event reports
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EntityAction {{
+    Created,
+    Updated,
+    Deleted,
+}}
+
+impl EntityAction {{
+    pub fn as_str(&self) -> &'static str {{
+        match self {{
+            EntityAction::Created => "created",
+            EntityAction::Updated => "updated",
+            EntityAction::Deleted => "deleted",
+        }}
+    }}
+}}
+
+/// A change to a realtime entity
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EntityEvent {{
+    pub entity: String,
+    pub action: EntityAction,
+    pub id: String,
+    /// The record after the change, or before it for deletes
+    pub data: serde_json::Value,
+}}
+
+/// Which events a subscriber receives
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct EventFilter {{
+    /// Only events of this entity
+    pub entity: Option<String>,
+    /// Only events of the record with this id
+    pub id: Option<String>,
+}}
+
+impl EventFilter {{
+    pub fn matches(&self, event: &EntityEvent) -> bool {{
+        self.entity.as_ref().map_or(true, |entity| *entity == event.entity)
+            && self.id.as_ref().map_or(true, |id| *id == event.id)
+    }}
+}}
+
+/// The channel events are broadcast on
+pub fn channel() -> &'static broadcast::Sender<EntityEvent> {{
+    static CHANNEL: OnceLock<broadcast::Sender<EntityEvent>> = OnceLock::new();
+    CHANNEL.get_or_init(|| broadcast::channel(CHANNEL_CAPACITY).0)
+}}
+
+/// Models whose changes are broadcast
+pub trait Broadcast: Serialize {{
+    /// Entity name events are tagged with
+    const ENTITY: &'static str;
+
+    /// Primary key events carry as their id
+    fn event_id(&self) -> String;
+}}
+
+/// Broadcast a change; having no subscribers is not an error
+pub fn publish<T: Broadcast>(action: EntityAction, record: &T) {{
+    let event = EntityEvent {{
+        entity: T::ENTITY.to_string(),
+        action,
+        id: record.event_id(),
+        data: serde_json::to_value(record).unwrap_or_default(),
+    }};
+    let _ = channel().send(event);
+}}
+
+/// Hook for the repository layer after inserting a record
+pub fn publish_created<T: Broadcast>(record: &T) {{
+    publish(EntityAction::Created, record);
+}}
+
+/// Hook for the repository layer after updating a record
+pub fn publish_updated<T: Broadcast>(record: &T) {{
+    publish(EntityAction::Updated, record);
+}}
+
+/// Hook for the repository layer after deleting a record
+pub fn publish_deleted<T: Broadcast>(record: &T) {{
+    publish(EntityAction::Deleted, record);
+}}
+"#,
+        count = names.len(),
+        names = names.join(", "),
+    );
+
+    for entity in &config.entities {
+        content.push_str(&format!(
+            r#"
+impl Broadcast for {path} {{
+    const ENTITY: &'static str = "{name}";
+
+    fn event_id(&self) -> String {{
+        self.{key}.to_string()
+    }}
+}}
+"#,
+            path = entity.model_path,
+            name = entity.name,
+            key = entity.key,
+        ));
+    }
+
+    content.push_str(&generate_endpoints(framework));
+    content
+}
+
+/// Generate the WebSocket and SSE handlers and their router (Axum) or
+/// route configuration (Actix)
+fn generate_endpoints(framework: AuthFramework) -> String {
+    match framework {
+        AuthFramework::Axum => format!(
+            r#"
+/// Stream events over a WebSocket, filtered by `entity` and `id`
+pub async fn websocket(
+    upgrade: axum::extract::ws::WebSocketUpgrade,
+    axum::extract::Query(filter): axum::extract::Query<EventFilter>,
+) -> axum::response::Response {{
+    upgrade.on_upgrade(move |socket| forward_events(socket, filter))
+}}
+
+async fn forward_events(mut socket: axum::extract::ws::WebSocket, filter: EventFilter) {{
+    use axum::extract::ws::Message;
+
+    let mut events = channel().subscribe();
+    loop {{
+        tokio::select! {{
+            event = events.recv() => match event {{
+                Ok(event) if filter.matches(&event) => {{
+                    let Ok(text) = serde_json::to_string(&event) else {{ continue }};
+                    if socket.send(Message::Text(text)).await.is_err() {{
+                        break;
+                    }}
+                }}
+                Ok(_) | Err(broadcast::error::RecvError::Lagged(_)) => {{}}
+                Err(broadcast::error::RecvError::Closed) => break,
+            }},
+            message = socket.recv() => match message {{
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                Some(Ok(_)) => {{}}
+            }},
+        }}
+    }}
+}}
+
+/// Stream events as Server-Sent Events, filtered by `entity` and `id`
+pub async fn events(
+    axum::extract::Query(filter): axum::extract::Query<EventFilter>,
+) -> axum::response::sse::Sse<
+    impl tokio_stream::Stream<Item = Result<axum::response::sse::Event, std::convert::Infallible>>,
+> {{
+    use tokio_stream::StreamExt;
+
+    let stream = tokio_stream::wrappers::BroadcastStream::new(channel().subscribe()).filter_map(move |event| {{
+        let event = event.ok().filter(|event| filter.matches(event))?;
+        let sse = axum::response::sse::Event::default().event(event.action.as_str()).json_data(&event).ok()?;
+        Some(Ok(sse))
+    }});
+    axum::response::sse::Sse::new(stream).keep_alive(axum::response::sse::KeepAlive::default())
+}}
+
+/// Create the router for the realtime endpoints
+pub fn router() -> axum::Router<crate::AppState> {{
+    axum::Router::new()
+        .route("{ws}", axum::routing::get(websocket))
+        .route("{sse}", axum::routing::get(events))
+}}
+"#,
+            ws = WEBSOCKET_PATH,
+            sse = EVENTS_PATH,
+        ),
+        AuthFramework::Actix => format!(
+            r#"
+/// Stream events over a WebSocket, filtered by `entity` and `id`
+pub async fn websocket(
+    request: actix_web::HttpRequest,
+    body: actix_web::web::Payload,
+    filter: actix_web::web::Query<EventFilter>,
+) -> Result<actix_web::HttpResponse, actix_web::Error> {{
+    let (response, mut session, mut messages) = actix_ws::handle(&request, body)?;
+    let filter = filter.into_inner();
+    actix_web::rt::spawn(async move {{
+        let mut events = channel().subscribe();
+        loop {{
+            tokio::select! {{
+                event = events.recv() => match event {{
+                    Ok(event) if filter.matches(&event) => {{
+                        let Ok(text) = serde_json::to_string(&event) else {{ continue }};
+                        if session.text(text).await.is_err() {{
+                            break;
+                        }}
+                    }}
+                    Ok(_) | Err(broadcast::error::RecvError::Lagged(_)) => {{}}
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }},
+                message = messages.recv() => match message {{
+                    Some(Ok(actix_ws::Message::Close(_))) | Some(Err(_)) | None => break,
+                    Some(Ok(_)) => {{}}
+                }},
+            }}
+        }}
+        let _ = session.close(None).await;
+    }});
+    Ok(response)
+}}
+
+/// Stream events as Server-Sent Events, filtered by `entity` and `id`
+pub async fn events(filter: actix_web::web::Query<EventFilter>) -> actix_web::HttpResponse {{
+    use tokio_stream::StreamExt;
+
+    let filter = filter.into_inner();
+    let stream = tokio_stream::wrappers::BroadcastStream::new(channel().subscribe()).filter_map(move |event| {{
+        let event = event.ok().filter(|event| filter.matches(event))?;
+        let data = serde_json::to_string(&event).ok()?;
+        let frame = format!("event: {{}}\ndata: {{}}\n\n", event.action.as_str(), data);
+        Some(Ok::<_, actix_web::Error>(actix_web::web::Bytes::from(frame)))
+    }});
+    actix_web::HttpResponse::Ok().content_type("text/event-stream").streaming(stream)
+}}
+
+/// Configure the realtime endpoints
+pub fn configure(cfg: &mut actix_web::web::ServiceConfig) {{
+    cfg.route("{ws}", actix_web::web::get().to(websocket))
+        .route("{sse}", actix_web::web::get().to(events));
+}}
+"#,
+            ws = WEBSOCKET_PATH,
+            sse = EVENTS_PATH,
+        ),
+        AuthFramework::Custom => String::new(),
+    }
+}
+
+/// Generate the TypeScript client subscribing to the realtime endpoints,
+/// with a `subscribeTo<Entity>` function per realtime entity
+pub fn generate_typescript_client(config: &RealtimeConfig) -> String {
+    let names: Vec<String> = config.entities.iter().map(|e| format!("\"{}\"", e.name)).collect();
+    let mut content = format!(
+        r#"// Realtime entity events
+//
+// Generated by Immortal Engine
+
+export type EntityName = {names};
+export type EntityAction = "created" | "updated" | "deleted";
+
+export interface EntityEvent<T = unknown> {{
+  entity: EntityName;
+  action: EntityAction;
+  id: string;
+  /** The record after the change, or before it for deletes */
+  data: T;
+}}
+
+export interface EventFilter {{
+  entity?: EntityName;
+  id?: string;
+}}
+
+function endpoint(baseUrl: string, path: string, filter: EventFilter): URL {{
+  const url = new URL(path, baseUrl);
+  if (filter.entity) url.searchParams.set("entity", filter.entity);
+  if (filter.id) url.searchParams.set("id", filter.id);
+  return url;
+}}
+
+/** Receive events over Server-Sent Events; returns a function ending the subscription */
+export function subscribe<T = unknown>(
+  baseUrl: string,
+  filter: EventFilter,
+  onEvent: (event: EntityEvent<T>) => void,
+): () => void {{
+  const source = new EventSource(endpoint(baseUrl, "{sse}", filter));
+  const listener = (message: MessageEvent<string>) => onEvent(JSON.parse(message.data));
+  for (const action of ["created", "updated", "deleted"]) {{
+    source.addEventListener(action, listener);
+  }}
+  return () => source.close();
+}}
+
+/** Receive events over a WebSocket; close the socket to end the subscription */
+export function connect<T = unknown>(
+  baseUrl: string,
+  filter: EventFilter,
+  onEvent: (event: EntityEvent<T>) => void,
+): WebSocket {{
+  const url = endpoint(baseUrl, "{ws}", filter);
+  url.protocol = url.protocol === "https:" ? "wss:" : "ws:";
+  const socket = new WebSocket(url);
+  socket.onmessage = (message) => onEvent(JSON.parse(message.data));
+  return socket;
+}}
+"#,
+        names = names.join(" | "),
+        sse = EVENTS_PATH,
+        ws = WEBSOCKET_PATH,
+    );
+
+    for entity in &config.entities {
+        content.push_str(&format!(
+            r#"
+/** Receive {name} events, optionally of a single record */
+export function subscribeTo{name}<T = unknown>(
+  baseUrl: string,
+  onEvent: (event: EntityEvent<T>) => void,
+  id?: string,
+): () => void {{
+  return subscribe(baseUrl, {{ entity: "{name}", id }}, onEvent);
+}}
+"#,
+            name = entity.name,
+        ));
+    }
+    content
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use imortal_ir::ProjectMeta;
+
+    fn realtime_graph() -> ProjectGraph {
+        let mut graph = ProjectGraph::new(ProjectMeta::new("blog"));
+        graph.add_node(Node::new_entity("Post").with_config("realtime", true));
+        graph.add_node(Node::new_entity("Comment").with_namespace("posts").with_config("realtime", true));
+        graph.add_node(Node::new_entity("User"));
+        graph
+    }
+
+    #[test]
+    fn test_from_graph() {
+        assert_eq!(RealtimeConfig::from_graph(&ProjectGraph::new(ProjectMeta::new("empty"))), None);
+
+        let config = RealtimeConfig::from_graph(&realtime_graph()).unwrap();
+        let names: Vec<&str> = config.entities.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, ["Comment", "Post"]);
+        assert_eq!(config.entities[0].model_path, "crate::models::posts::Comment");
+        assert!(config.dependencies(AuthFramework::Actix).iter().any(|(name, _)| *name == "actix-ws"));
+    }
+
+    #[test]
+    fn test_generate_realtime() {
+        let config = RealtimeConfig::from_graph(&realtime_graph()).unwrap();
+        let code = generate_realtime(AuthFramework::Axum, &config);
+        assert!(code.contains("pub const REALTIME_ENTITIES: [&str; 2] = [\"Comment\", \"Post\"];"));
+        assert!(code.contains("impl Broadcast for crate::models::Post {"));
+        assert!(code.contains(".route(\"/realtime/ws\", axum::routing::get(websocket))"));
+        assert!(code.contains("axum::response::sse::Sse::new(stream)"));
+
+        let code = generate_realtime(AuthFramework::Actix, &config);
+        assert!(code.contains("actix_ws::handle(&request, body)?"));
+        assert!(code.contains("cfg.route(\"/realtime/ws\", actix_web::web::get().to(websocket))"));
+    }
+
+    #[test]
+    fn test_generate_typescript_client() {
+        let config = RealtimeConfig::from_graph(&realtime_graph()).unwrap();
+        let client = generate_typescript_client(&config);
+        assert!(client.contains("export type EntityName = \"Comment\" | \"Post\";"));
+        assert!(client.contains("new EventSource(endpoint(baseUrl, \"/realtime/events\", filter))"));
+        assert!(client.contains("export function subscribeToPost<T = unknown>("));
+    }
+}
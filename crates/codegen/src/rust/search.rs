@@ -0,0 +1,727 @@
+//! Full-Text Search Code Generation
+//!
+//! Entities connected to a `service.search` node are indexed by their text
+//! fields and searched through a `/search` endpoint returning ranked hits.
+//! The index lives in PostgreSQL (a generated `tsvector` column), in a
+//! Meilisearch server, or in an embedded Tantivy index; the generated
+//! `search` module has the same hooks and query function for each.
+
+use imortal_core::DataType;
+use imortal_ir::{Node, ProjectGraph};
+
+use crate::rust::migrations::{DatabaseBackend, Migration};
+use crate::rust::{module_path, to_snake_case, AuthFramework};
+
+/// Column holding the PostgreSQL full-text document of a row
+pub const SEARCH_VECTOR_COLUMN: &str = "search_vector";
+
+/// What indexes and ranks the searchable entities
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum SearchEngine {
+    /// PostgreSQL full-text search over a generated `tsvector` column
+    #[default]
+    Postgres,
+    /// A Meilisearch server
+    Meilisearch,
+    /// A Tantivy index embedded in the service
+    Tantivy,
+}
+
+impl SearchEngine {
+    /// Engines a search component can use
+    pub const ALL: [SearchEngine; 3] = [SearchEngine::Postgres, SearchEngine::Meilisearch, SearchEngine::Tantivy];
+
+    /// Identifier stored in the node's `engine` config
+    pub fn id(&self) -> &'static str {
+        match self {
+            SearchEngine::Postgres => "postgres",
+            SearchEngine::Meilisearch => "meilisearch",
+            SearchEngine::Tantivy => "tantivy",
+        }
+    }
+
+    /// Engine with the given identifier, ignoring case
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|engine| engine.id().eq_ignore_ascii_case(id.trim()))
+    }
+
+    /// The engine used on a backend; PostgreSQL full-text search needs
+    /// PostgreSQL, so other backends fall back to an embedded Tantivy index
+    pub fn for_backend(self, backend: DatabaseBackend) -> Self {
+        match self {
+            SearchEngine::Postgres if backend != DatabaseBackend::Postgres => SearchEngine::Tantivy,
+            engine => engine,
+        }
+    }
+}
+
+/// A searchable entity: its name, model path, table, primary key, and the
+/// text fields indexed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchableEntity {
+    /// Entity name hits are tagged with
+    pub name: String,
+    /// Path of the generated model, e.g. `crate::models::blog::Post`
+    pub model_path: String,
+    /// Table the entity is stored in
+    pub table: String,
+    /// Primary key field hits carry as their id
+    pub key: String,
+    /// Text fields indexed, in declaration order
+    pub fields: Vec<String>,
+}
+
+impl SearchableEntity {
+    fn from_node(graph: &ProjectGraph, node: &Node) -> Self {
+        let mut path = vec!["crate".to_string(), "models".to_string()];
+        path.extend(module_path(node));
+        path.push(node.name.clone());
+        let fields = graph.effective_fields(node.id);
+        Self {
+            name: node.name.clone(),
+            model_path: path.join("::"),
+            table: to_snake_case(&node.name),
+            key: fields
+                .iter()
+                .find(|f| f.is_primary_key())
+                .map_or_else(|| "id".to_string(), |f| f.name.clone()),
+            fields: fields
+                .iter()
+                .filter(|f| matches!(f.data_type, DataType::String | DataType::Text) && !f.is_primary_key())
+                .map(|f| f.name.clone())
+                .collect(),
+        }
+    }
+}
+
+/// Search generated for a project
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchConfig {
+    /// Engine the component asks for
+    pub engine: SearchEngine,
+    /// Path the search endpoint is served on
+    pub endpoint: String,
+    /// Text search configuration of PostgreSQL, e.g. "english"
+    pub language: String,
+    /// Default Meilisearch URL, overridden by `MEILISEARCH_URL`
+    pub meilisearch_url: String,
+    /// Default Tantivy index directory, overridden by `SEARCH_INDEX_PATH`
+    pub index_path: String,
+    /// Entities searched, sorted by name
+    pub entities: Vec<SearchableEntity>,
+    /// Connected entities left out for having no text fields
+    pub skipped: Vec<String>,
+}
+
+impl SearchConfig {
+    /// Search of the first `service.search` node in a graph over the
+    /// entities connected to it; `None` when nothing is searchable
+    pub fn from_graph(graph: &ProjectGraph) -> Option<Self> {
+        let node = graph.find_nodes_by_type("service.search").into_iter().next()?;
+        let mut entities: Vec<SearchableEntity> = graph
+            .edges()
+            .filter(|e| e.connects_to(node.id))
+            .map(|e| if e.from_node == node.id { e.to_node } else { e.from_node })
+            .filter_map(|id| graph.get_node(id))
+            .filter(|n| n.component_type == "data.entity")
+            .map(|n| SearchableEntity::from_node(graph, n))
+            .collect();
+        entities.sort_by(|a, b| a.name.cmp(&b.name));
+        entities.dedup();
+        let (entities, skipped): (Vec<_>, Vec<_>) = entities.into_iter().partition(|e| !e.fields.is_empty());
+        if entities.is_empty() && skipped.is_empty() {
+            return None;
+        }
+
+        let config = |key: &str, default: &str| {
+            node.get_config_str(key)
+                .filter(|v| !v.trim().is_empty())
+                .unwrap_or(default)
+                .to_string()
+        };
+        Some(Self {
+            engine: node.get_config_str("engine").and_then(SearchEngine::from_id).unwrap_or_default(),
+            endpoint: config("endpoint", "/search"),
+            language: config("language", "english"),
+            meilisearch_url: config("meilisearch_url", "http://localhost:7700"),
+            index_path: config("index_path", "search_index"),
+            entities,
+            skipped: skipped.into_iter().map(|e| e.name).collect(),
+        })
+    }
+
+    /// Crates the generated module needs on a backend
+    pub fn dependencies(&self, backend: DatabaseBackend) -> Vec<(&'static str, &'static str)> {
+        match self.engine.for_backend(backend) {
+            SearchEngine::Postgres => Vec::new(),
+            SearchEngine::Meilisearch => vec![("reqwest", r#"{ version = "0.12", features = ["json"] }"#)],
+            SearchEngine::Tantivy => vec![("tantivy", r#""0.22""#)],
+        }
+    }
+}
+
+/// Generate the migration adding a generated `tsvector` column and its GIN
+/// index to each searchable table, when PostgreSQL does the searching
+pub fn generate_search_migration(config: &SearchConfig, backend: DatabaseBackend) -> Option<Migration> {
+    if config.engine.for_backend(backend) != SearchEngine::Postgres || config.entities.is_empty() {
+        return None;
+    }
+
+    let mut up = Vec::new();
+    let mut down = Vec::new();
+    for entity in &config.entities {
+        let document: Vec<String> = entity.fields.iter().map(|f| format!("coalesce({}, '')", f)).collect();
+        up.push(format!(
+            "ALTER TABLE {table} ADD COLUMN {SEARCH_VECTOR_COLUMN} tsvector\n    GENERATED ALWAYS AS (to_tsvector('{language}', {document})) STORED;\nCREATE INDEX IF NOT EXISTS idx_{table}_search ON {table} USING GIN ({SEARCH_VECTOR_COLUMN});",
+            table = entity.table,
+            language = config.language,
+            document = document.join(" || ' ' || "),
+        ));
+        down.push(format!(
+            "DROP INDEX IF EXISTS idx_{table}_search;\nALTER TABLE {table} DROP COLUMN IF EXISTS {SEARCH_VECTOR_COLUMN};",
+            table = entity.table,
+        ));
+    }
+
+    Some(Migration::new("add_search_index", up.join("\n\n"), down.join("\n")))
+}
+
+/// Generate search.rs content: the `Searchable` impls, the indexing hooks,
+/// the ranked query for the engine, and the search endpoint
+pub fn generate_search(framework: AuthFramework, backend: DatabaseBackend, config: &SearchConfig) -> String {
+    let names: Vec<String> = config.entities.iter().map(|e| format!("\"{}\"", e.name)).collect();
+    let mut content = format!(
+        r#"//! Full-text search over entities
+//!
+//! Generated by Immortal Engine
+
+use serde::{{Deserialize, Serialize}};
+
+use crate::config::DatabasePool;
+
+/// Entities the search covers
+pub const SEARCHABLE_ENTITIES: [&str; {count}] = [{names}];
+
+/// Error of an indexing or search call
+pub type SearchError = Box<dyn std::error::Error + Send + Sync>;
+
+/// What to search for
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct SearchQuery {{
+    pub q: String,
+    pub entity: Option<String>,
+    pub limit: Option<usize>,
+}}
+
+impl SearchQuery {{
+    /// Number of hits to return
+    pub fn limit(&self) -> usize {{
+        self.limit.unwrap_or(20).clamp(1, 100)
+    }}
+}}
+
+/// A record matching a search, best match first
+#[derive(Debug, Clone, Serialize)]
+pub struct SearchHit {{
+    pub entity: String,
+    pub id: String,
+    pub score: f32,
+    pub document: serde_json::Value,
+}}
+
+/// Models included in the search
+pub trait Searchable: Serialize {{
+    /// Entity name hits are tagged with
+    const ENTITY: &'static str;
+    /// Text fields indexed
+    const FIELDS: &'static [&'static str];
+
+    /// Primary key hits carry as their id
+    fn search_id(&self) -> String;
+
+    /// Text of the indexed fields, as one document
+    fn search_text(&self) -> String {{
+        let value = serde_json::to_value(self).unwrap_or_default();
+        Self::FIELDS
+            .iter()
+            .filter_map(|field| value.get(*field).and_then(|v| v.as_str()))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }}
+}}
+"#,
+        count = names.len(),
+        names = names.join(", "),
+    );
+
+    for entity in &config.entities {
+        let fields: Vec<String> = entity.fields.iter().map(|f| format!("\"{}\"", f)).collect();
+        content.push_str(&format!(
+            r#"
+impl Searchable for {path} {{
+    const ENTITY: &'static str = "{name}";
+    const FIELDS: &'static [&'static str] = &[{fields}];
+
+    fn search_id(&self) -> String {{
+        self.{key}.to_string()
+    }}
+}}
+"#,
+            path = entity.model_path,
+            name = entity.name,
+            fields = fields.join(", "),
+            key = entity.key,
+        ));
+    }
+
+    content.push_str(&match config.engine.for_backend(backend) {
+        SearchEngine::Postgres => generate_postgres(config),
+        SearchEngine::Meilisearch => generate_meilisearch(config),
+        SearchEngine::Tantivy => generate_tantivy(config),
+    });
+    content.push_str(&generate_routes(framework, config));
+    content
+}
+
+/// Hooks and query for PostgreSQL full-text search
+fn generate_postgres(config: &SearchConfig) -> String {
+    let selects: Vec<String> = config
+        .entities
+        .iter()
+        .map(|entity| {
+            format!(
+                "SELECT '{name}' AS entity, {key}::text AS id, ts_rank({SEARCH_VECTOR_COLUMN}, query) AS score, to_jsonb({table})::text AS document \
+                 FROM {table}, websearch_to_tsquery('{language}', $1) query \
+                 WHERE {SEARCH_VECTOR_COLUMN} @@ query AND ($3::text IS NULL OR $3 = '{name}')",
+                name = entity.name,
+                key = entity.key,
+                table = entity.table,
+                language = config.language,
+            )
+        })
+        .collect();
+
+    format!(
+        r#"
+/// Hook for the repository layer after inserting or updating a record; the
+/// generated `{SEARCH_VECTOR_COLUMN}` column keeps PostgreSQL's index current
+pub async fn index_document<T: Searchable>(_record: &T) -> Result<(), SearchError> {{
+    Ok(())
+}}
+
+/// Hook for the repository layer after deleting a record
+pub async fn remove_document<T: Searchable>(_id: &str) -> Result<(), SearchError> {{
+    Ok(())
+}}
+
+/// Records matching the query, ranked by `ts_rank`
+pub async fn search(db: &DatabasePool, query: &SearchQuery) -> Result<Vec<SearchHit>, SearchError> {{
+    let rows: Vec<(String, String, f32, String)> = sqlx::query_as(
+        "{selects} ORDER BY score DESC LIMIT $2",
+    )
+    .bind(&query.q)
+    .bind(query.limit() as i64)
+    .bind(&query.entity)
+    .fetch_all(db)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|(entity, id, score, document)| SearchHit {{
+            entity,
+            id,
+            score,
+            document: serde_json::from_str(&document).unwrap_or_default(),
+        }})
+        .collect())
+}}
+"#,
+        selects = selects.join(" UNION ALL "),
+    )
+}
+
+/// Hooks and query for a Meilisearch server, one index per entity
+fn generate_meilisearch(config: &SearchConfig) -> String {
+    let settings: Vec<String> = config
+        .entities
+        .iter()
+        .map(|entity| {
+            let fields: Vec<String> = entity.fields.iter().map(|f| format!("\"{}\"", f)).collect();
+            format!("(\"{}\", \"{}\", &[{}][..])", entity.table, entity.key, fields.join(", "))
+        })
+        .collect();
+    let indexes: Vec<String> = config.entities.iter().map(|e| format!("(\"{}\", \"{}\")", e.name, e.table)).collect();
+
+    format!(
+        r#"
+/// Indexes of the searchable entities: (index, primary key, searchable fields)
+const INDEXES: [(&str, &str, &[&str]); {count}] = [{settings}];
+
+/// Index of each entity
+const ENTITY_INDEXES: [(&str, &str); {count}] = [{indexes}];
+
+/// Client of the Meilisearch server at `MEILISEARCH_URL`, authenticated
+/// with `MEILISEARCH_API_KEY` when set
+struct Meilisearch {{
+    http: reqwest::Client,
+    url: String,
+    api_key: Option<String>,
+}}
+
+impl Meilisearch {{
+    fn request(&self, method: reqwest::Method, path: &str) -> reqwest::RequestBuilder {{
+        let request = self.http.request(method, format!("{{}}{{}}", self.url.trim_end_matches('/'), path));
+        match &self.api_key {{
+            Some(key) => request.bearer_auth(key),
+            None => request,
+        }}
+    }}
+}}
+
+/// The Meilisearch client, with the indexes set up on first use
+async fn meilisearch() -> Result<&'static Meilisearch, SearchError> {{
+    static CLIENT: tokio::sync::OnceCell<Meilisearch> = tokio::sync::OnceCell::const_new();
+    CLIENT
+        .get_or_try_init(|| async {{
+            let client = Meilisearch {{
+                http: reqwest::Client::new(),
+                url: std::env::var("MEILISEARCH_URL").unwrap_or_else(|_| "{url}".to_string()),
+                api_key: std::env::var("MEILISEARCH_API_KEY").ok(),
+            }};
+            for (index, key, fields) in INDEXES {{
+                // Creating an index that exists fails as a task, not a request
+                client
+                    .request(reqwest::Method::POST, "/indexes")
+                    .json(&serde_json::json!({{ "uid": index, "primaryKey": key }}))
+                    .send()
+                    .await?
+                    .error_for_status()?;
+                client
+                    .request(reqwest::Method::PUT, &format!("/indexes/{{}}/settings/searchable-attributes", index))
+                    .json(fields)
+                    .send()
+                    .await?
+                    .error_for_status()?;
+            }}
+            Ok::<_, SearchError>(client)
+        }})
+        .await
+}}
+
+fn index_of(entity: &str) -> Result<&'static str, SearchError> {{
+    ENTITY_INDEXES
+        .iter()
+        .find(|(name, _)| *name == entity)
+        .map(|(_, index)| *index)
+        .ok_or_else(|| format!("{{}} is not searchable", entity).into())
+}}
+
+/// Hook for the repository layer after inserting or updating a record
+pub async fn index_document<T: Searchable>(record: &T) -> Result<(), SearchError> {{
+    meilisearch()
+        .await?
+        .request(reqwest::Method::POST, &format!("/indexes/{{}}/documents", index_of(T::ENTITY)?))
+        .json(&[record])
+        .send()
+        .await?
+        .error_for_status()?;
+    Ok(())
+}}
+
+/// Hook for the repository layer after deleting a record
+pub async fn remove_document<T: Searchable>(id: &str) -> Result<(), SearchError> {{
+    meilisearch()
+        .await?
+        .request(reqwest::Method::DELETE, &format!("/indexes/{{}}/documents/{{}}", index_of(T::ENTITY)?, id))
+        .send()
+        .await?
+        .error_for_status()?;
+    Ok(())
+}}
+
+/// Records matching the query across the entity indexes, ranked by
+/// Meilisearch's ranking score
+pub async fn search(_db: &DatabasePool, query: &SearchQuery) -> Result<Vec<SearchHit>, SearchError> {{
+    let queries: Vec<serde_json::Value> = ENTITY_INDEXES
+        .iter()
+        .filter(|(name, _)| query.entity.as_deref().map_or(true, |entity| entity == *name))
+        .map(|(_, index)| {{
+            serde_json::json!({{ "indexUid": index, "q": query.q, "limit": query.limit(), "showRankingScore": true }})
+        }})
+        .collect();
+    let response: serde_json::Value = meilisearch()
+        .await?
+        .request(reqwest::Method::POST, "/multi-search")
+        .json(&serde_json::json!({{ "queries": queries }}))
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+
+    let mut hits = Vec::new();
+    for result in response["results"].as_array().into_iter().flatten() {{
+        let index = result["indexUid"].as_str().unwrap_or_default();
+        let Some((entity, _)) = ENTITY_INDEXES.iter().find(|(_, i)| *i == index) else {{
+            continue;
+        }};
+        let key = INDEXES.iter().find(|(i, _, _)| *i == index).map_or("id", |(_, key, _)| *key);
+        for hit in result["hits"].as_array().into_iter().flatten() {{
+            let mut document = hit.clone();
+            let score = document
+                .as_object_mut()
+                .and_then(|d| d.remove("_rankingScore"))
+                .and_then(|s| s.as_f64())
+                .unwrap_or_default();
+            hits.push(SearchHit {{
+                entity: entity.to_string(),
+                id: document[key].as_str().map_or_else(|| document[key].to_string(), str::to_string),
+                score: score as f32,
+                document,
+            }});
+        }}
+    }}
+    hits.sort_by(|a, b| b.score.total_cmp(&a.score));
+    hits.truncate(query.limit());
+    Ok(hits)
+}}
+"#,
+        count = config.entities.len(),
+        settings = settings.join(", "),
+        indexes = indexes.join(", "),
+        url = config.meilisearch_url,
+    )
+}
+
+/// Hooks and query for an embedded Tantivy index
+fn generate_tantivy(config: &SearchConfig) -> String {
+    format!(
+        r#"
+/// The embedded index in `SEARCH_INDEX_PATH`, and its fields
+struct SearchIndex {{
+    index: tantivy::Index,
+    writer: std::sync::Mutex<tantivy::IndexWriter>,
+    reader: tantivy::IndexReader,
+    key: tantivy::schema::Field,
+    entity: tantivy::schema::Field,
+    id: tantivy::schema::Field,
+    text: tantivy::schema::Field,
+    document: tantivy::schema::Field,
+}}
+
+/// The index, opened or created on first use
+fn search_index() -> Result<&'static SearchIndex, SearchError> {{
+    use tantivy::schema::{{Schema, STORED, STRING, TEXT}};
+
+    static INDEX: std::sync::OnceLock<SearchIndex> = std::sync::OnceLock::new();
+    if let Some(index) = INDEX.get() {{
+        return Ok(index);
+    }}
+
+    let mut schema = Schema::builder();
+    let key = schema.add_text_field("key", STRING);
+    let entity = schema.add_text_field("entity", STRING | STORED);
+    let id = schema.add_text_field("id", STRING | STORED);
+    let text = schema.add_text_field("text", TEXT);
+    let document = schema.add_text_field("document", STORED);
+    let path = std::env::var("SEARCH_INDEX_PATH").unwrap_or_else(|_| "{path}".to_string());
+    std::fs::create_dir_all(&path)?;
+    let directory = tantivy::directory::MmapDirectory::open(&path)?;
+    let index = tantivy::Index::open_or_create(directory, schema.build())?;
+    let writer = index.writer(50_000_000)?;
+    let reader = index.reader()?;
+    Ok(INDEX.get_or_init(|| SearchIndex {{
+        index,
+        writer: std::sync::Mutex::new(writer),
+        reader,
+        key,
+        entity,
+        id,
+        text,
+        document,
+    }}))
+}}
+
+/// Hook for the repository layer after inserting or updating a record
+pub async fn index_document<T: Searchable>(record: &T) -> Result<(), SearchError> {{
+    let index = search_index()?;
+    let id = record.search_id();
+    let key = format!("{{}}:{{}}", T::ENTITY, id);
+    let mut writer = index.writer.lock().unwrap();
+    writer.delete_term(tantivy::Term::from_field_text(index.key, &key));
+    writer.add_document(tantivy::doc!(
+        index.key => key,
+        index.entity => T::ENTITY,
+        index.id => id,
+        index.text => record.search_text(),
+        index.document => serde_json::to_string(record)?,
+    ))?;
+    writer.commit()?;
+    index.reader.reload()?;
+    Ok(())
+}}
+
+/// Hook for the repository layer after deleting a record
+pub async fn remove_document<T: Searchable>(id: &str) -> Result<(), SearchError> {{
+    let index = search_index()?;
+    let mut writer = index.writer.lock().unwrap();
+    writer.delete_term(tantivy::Term::from_field_text(index.key, &format!("{{}}:{{}}", T::ENTITY, id)));
+    writer.commit()?;
+    index.reader.reload()?;
+    Ok(())
+}}
+
+/// Records matching the query, ranked by BM25
+pub async fn search(_db: &DatabasePool, query: &SearchQuery) -> Result<Vec<SearchHit>, SearchError> {{
+    use tantivy::schema::Value;
+
+    let index = search_index()?;
+    let searcher = index.reader.searcher();
+    let parser = tantivy::query::QueryParser::for_index(&index.index, vec![index.text]);
+    let (parsed, _) = parser.parse_query_lenient(&query.q);
+    // Hits of other entities are dropped, so fetch enough to fill the page
+    let fetched = if query.entity.is_some() {{ query.limit() * SEARCHABLE_ENTITIES.len() }} else {{ query.limit() }};
+    let top = searcher.search(&parsed, &tantivy::collector::TopDocs::with_limit(fetched))?;
+
+    let mut hits = Vec::new();
+    for (score, address) in top {{
+        let doc: tantivy::TantivyDocument = searcher.doc(address)?;
+        let text = |field| doc.get_first(field).and_then(|v| v.as_str()).unwrap_or_default().to_string();
+        let entity = text(index.entity);
+        if query.entity.as_deref().is_some_and(|wanted| wanted != entity) {{
+            continue;
+        }}
+        hits.push(SearchHit {{
+            entity,
+            id: text(index.id),
+            score,
+            document: serde_json::from_str(&text(index.document)).unwrap_or_default(),
+        }});
+    }}
+    hits.truncate(query.limit());
+    Ok(hits)
+}}
+"#,
+        path = config.index_path,
+    )
+}
+
+/// Generate the search handler and its router (Axum) or route
+/// configuration (Actix)
+fn generate_routes(framework: AuthFramework, config: &SearchConfig) -> String {
+    match framework {
+        AuthFramework::Axum => format!(
+            r#"
+/// Search the entities for `q`, optionally one `entity`, up to `limit` hits
+pub async fn search_entities(
+    axum::extract::State(state): axum::extract::State<crate::AppState>,
+    axum::extract::Query(query): axum::extract::Query<SearchQuery>,
+) -> Result<axum::Json<Vec<SearchHit>>, (axum::http::StatusCode, String)> {{
+    if query.q.trim().is_empty() {{
+        return Err((axum::http::StatusCode::BAD_REQUEST, "missing q".to_string()));
+    }}
+    search(&state.db, &query)
+        .await
+        .map(axum::Json)
+        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+}}
+
+/// Create the router for the search endpoint
+pub fn router() -> axum::Router<crate::AppState> {{
+    axum::Router::new().route("{}", axum::routing::get(search_entities))
+}}
+"#,
+            config.endpoint,
+        ),
+        AuthFramework::Actix => format!(
+            r#"
+/// Search the entities for `q`, optionally one `entity`, up to `limit` hits
+pub async fn search_entities(
+    state: actix_web::web::Data<crate::AppState>,
+    query: actix_web::web::Query<SearchQuery>,
+) -> actix_web::HttpResponse {{
+    if query.q.trim().is_empty() {{
+        return actix_web::HttpResponse::BadRequest().body("missing q");
+    }}
+    match search(&state.db, &query).await {{
+        Ok(hits) => actix_web::HttpResponse::Ok().json(hits),
+        Err(e) => actix_web::HttpResponse::InternalServerError().body(e.to_string()),
+    }}
+}}
+
+/// Configure the search endpoint
+pub fn configure(cfg: &mut actix_web::web::ServiceConfig) {{
+    cfg.route("{}", actix_web::web::get().to(search_entities));
+}}
+"#,
+            config.endpoint,
+        ),
+        AuthFramework::Custom => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use imortal_ir::{Field, ProjectMeta};
+
+    fn search_graph(engine: &str) -> ProjectGraph {
+        let mut graph = ProjectGraph::new(ProjectMeta::new("blog"));
+        let mut post = Node::new_entity("Post");
+        post.add_field(Field::string("title"));
+        post.add_field(Field::text("body"));
+        post.add_field(Field::int("views"));
+        let post = graph.add_node(post);
+        let tag = graph.add_node(Node::new_entity("Tag"));
+        let search = graph.add_node(Node::new_search("Search").with_config("engine", engine));
+        graph.connect(post, "entity", search, "entities").unwrap();
+        graph.connect(tag, "entity", search, "entities").unwrap();
+        graph
+    }
+
+    #[test]
+    fn test_search_config() {
+        let config = SearchConfig::from_graph(&search_graph("meilisearch")).unwrap();
+        assert_eq!(config.engine, SearchEngine::Meilisearch);
+        assert_eq!(config.entities.len(), 1);
+        assert_eq!(config.entities[0].fields, ["title", "body"]);
+        assert_eq!(config.skipped, ["Tag"]);
+        assert_eq!(config.dependencies(DatabaseBackend::Sqlite)[0].0, "reqwest");
+        assert_eq!(SearchEngine::Postgres.for_backend(DatabaseBackend::Mysql), SearchEngine::Tantivy);
+        assert!(SearchConfig::from_graph(&ProjectGraph::with_name("empty")).is_none());
+    }
+
+    #[test]
+    fn test_postgres_search() {
+        let config = SearchConfig::from_graph(&search_graph("postgres")).unwrap();
+        let migration = generate_search_migration(&config, DatabaseBackend::Postgres).unwrap();
+        assert!(migration.up.contains(
+            "GENERATED ALWAYS AS (to_tsvector('english', coalesce(title, '') || ' ' || coalesce(body, ''))) STORED;"
+        ));
+        assert!(migration.up.contains("CREATE INDEX IF NOT EXISTS idx_post_search ON post USING GIN (search_vector);"));
+        assert!(migration.down.contains("DROP COLUMN IF EXISTS search_vector;"));
+        assert!(generate_search_migration(&config, DatabaseBackend::Sqlite).is_none());
+
+        let code = generate_search(AuthFramework::Axum, DatabaseBackend::Postgres, &config);
+        assert!(code.contains("impl Searchable for crate::models::Post {"));
+        assert!(code.contains("const FIELDS: &'static [&'static str] = &[\"title\", \"body\"];"));
+        assert!(code.contains("ts_rank(search_vector, query) AS score"));
+        assert!(code.contains("axum::Router::new().route(\"/search\", axum::routing::get(search_entities))"));
+    }
+
+    #[test]
+    fn test_external_search_engines() {
+        let config = SearchConfig::from_graph(&search_graph("meilisearch")).unwrap();
+        let code = generate_search(AuthFramework::Actix, DatabaseBackend::Postgres, &config);
+        assert!(code.contains("const INDEXES: [(&str, &str, &[&str]); 1] = [(\"post\", \"id\", &[\"title\", \"body\"][..])];"));
+        assert!(code.contains("\"/multi-search\""));
+        assert!(code.contains("cfg.route(\"/search\", actix_web::web::get().to(search_entities));"));
+
+        // PostgreSQL full-text search falls back to Tantivy elsewhere
+        let config = SearchConfig::from_graph(&search_graph("postgres")).unwrap();
+        let code = generate_search(AuthFramework::Axum, DatabaseBackend::Sqlite, &config);
+        assert!(code.contains("tantivy::Index::open_or_create"));
+        assert!(code.contains("writer.delete_term("));
+        assert!(!code.contains("ts_rank"));
+    }
+}
@@ -39,6 +39,9 @@ fn generate_field_definition(field: &Field) -> String {
     if let Some(desc) = &field.description {
         output.push_str(&format!("    /// {}\n", desc));
     }
+    if let Some(unit) = &field.unit {
+        output.push_str(&format!("    /// Unit: {}\n", unit));
+    }
 
     // Add serde attributes if needed
     if field.ui_hints.secret {
@@ -0,0 +1,296 @@
+//! Multi-Tenancy Code Generation
+//!
+//! A multi-tenant service keeps each tenant's records apart, either in a
+//! `tenant_id` column on every table or in a database schema per tenant.
+//! The generated `tenancy` module reads the tenant from each request in a
+//! middleware and hands it to handlers through the `TenantId` extractor.
+
+use imortal_ir::Field;
+
+use crate::rust::migrations::DatabaseBackend;
+use crate::rust::AuthFramework;
+
+/// Column holding the owning tenant in column-based tenancy
+pub const TENANT_COLUMN: &str = "tenant_id";
+
+/// How a generated service keeps tenants apart
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum TenancyMode {
+    /// A single tenant; no tenant handling is generated
+    #[default]
+    None,
+    /// Every table has a `tenant_id` column that queries filter on
+    Column,
+    /// Every tenant has its own database schema (PostgreSQL)
+    Schema,
+}
+
+impl TenancyMode {
+    /// Modes a project can use
+    pub const ALL: [TenancyMode; 3] = [TenancyMode::None, TenancyMode::Column, TenancyMode::Schema];
+
+    /// Identifier stored in `CodegenSettings::tenancy`
+    pub fn id(&self) -> &'static str {
+        match self {
+            TenancyMode::None => "none",
+            TenancyMode::Column => "column",
+            TenancyMode::Schema => "schema",
+        }
+    }
+
+    /// Get the display name for this mode
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            TenancyMode::None => "Single Tenant",
+            TenancyMode::Column => "Tenant ID Column",
+            TenancyMode::Schema => "Schema per Tenant",
+        }
+    }
+
+    /// Mode with the given identifier, ignoring case
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|mode| mode.id().eq_ignore_ascii_case(id.trim()))
+    }
+
+    /// Whether tenant handling is generated
+    pub fn is_enabled(&self) -> bool {
+        *self != TenancyMode::None
+    }
+
+    /// The mode used on a backend; schemas per tenant need PostgreSQL, so
+    /// other backends fall back to a tenant column
+    pub fn for_backend(self, backend: DatabaseBackend) -> Self {
+        match self {
+            TenancyMode::Schema if backend != DatabaseBackend::Postgres => TenancyMode::Column,
+            mode => mode,
+        }
+    }
+}
+
+/// The `tenant_id` field added to every model and table in column-based
+/// tenancy
+pub fn tenant_field() -> Field {
+    Field::uuid(TENANT_COLUMN)
+        .required()
+        .indexed()
+        .with_description("Tenant owning the record")
+}
+
+/// Generate tenancy.rs content: the `TenantId` extractor, the middleware
+/// reading it from requests, and the query helpers for `mode`
+pub fn generate_tenancy(framework: AuthFramework, backend: DatabaseBackend, mode: TenancyMode) -> String {
+    let mut content = String::from(
+        r#"//! Tenant of each request
+//!
+//! Generated by Immortal Engine
+
+/// Header requests name their tenant in
+pub const TENANT_HEADER: &str = "x-tenant-id";
+
+/// Tenant a request acts for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TenantId(pub uuid::Uuid);
+"#,
+    );
+
+    content.push_str(match framework {
+        AuthFramework::Axum => {
+            r#"
+/// Middleware reading the tenant from the `x-tenant-id` header into the
+/// request extensions; a malformed tenant is rejected
+pub async fn resolve_tenant(
+    mut request: axum::extract::Request,
+    next: axum::middleware::Next,
+) -> Result<axum::response::Response, axum::http::StatusCode> {
+    if let Some(header) = request.headers().get(TENANT_HEADER) {
+        let tenant = header
+            .to_str()
+            .ok()
+            .and_then(|value| uuid::Uuid::parse_str(value.trim()).ok())
+            .ok_or(axum::http::StatusCode::BAD_REQUEST)?;
+        request.extensions_mut().insert(TenantId(tenant));
+    }
+    Ok(next.run(request).await)
+}
+
+#[axum::async_trait]
+impl<S: Send + Sync> axum::extract::FromRequestParts<S> for TenantId {
+    type Rejection = (axum::http::StatusCode, &'static str);
+
+    async fn from_request_parts(parts: &mut axum::http::request::Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        parts
+            .extensions
+            .get::<TenantId>()
+            .copied()
+            .ok_or((axum::http::StatusCode::BAD_REQUEST, "missing x-tenant-id header"))
+    }
+}
+"#
+        }
+        AuthFramework::Actix => {
+            r#"
+/// Middleware reading the tenant from the `x-tenant-id` header into the
+/// request extensions; a malformed tenant is rejected
+pub async fn resolve_tenant(
+    request: actix_web::dev::ServiceRequest,
+    next: actix_web::middleware::Next<impl actix_web::body::MessageBody>,
+) -> Result<actix_web::dev::ServiceResponse<impl actix_web::body::MessageBody>, actix_web::Error> {
+    use actix_web::HttpMessage;
+
+    if let Some(header) = request.headers().get(TENANT_HEADER) {
+        let tenant = header
+            .to_str()
+            .ok()
+            .and_then(|value| uuid::Uuid::parse_str(value.trim()).ok())
+            .ok_or_else(|| actix_web::error::ErrorBadRequest("malformed x-tenant-id header"))?;
+        request.extensions_mut().insert(TenantId(tenant));
+    }
+    next.call(request).await
+}
+
+impl actix_web::FromRequest for TenantId {
+    type Error = actix_web::Error;
+    type Future = std::future::Ready<Result<Self, Self::Error>>;
+
+    fn from_request(request: &actix_web::HttpRequest, _payload: &mut actix_web::dev::Payload) -> Self::Future {
+        use actix_web::HttpMessage;
+
+        std::future::ready(
+            request
+                .extensions()
+                .get::<TenantId>()
+                .copied()
+                .ok_or_else(|| actix_web::error::ErrorBadRequest("missing x-tenant-id header")),
+        )
+    }
+}
+"#
+        }
+        AuthFramework::Custom => "",
+    });
+
+    match mode.for_backend(backend) {
+        TenancyMode::None => {}
+        TenancyMode::Column => {
+            // `?` placeholders are bound in order, so only PostgreSQL
+            // needs the tenant's parameter number
+            let (doc, params, filter, example) = match backend {
+                DatabaseBackend::Postgres => (
+                    "with the\n/// tenant bound as parameter `param`",
+                    "sql: &str, param: usize",
+                    format!("format!(\"{} = ${{}}\", param)", TENANT_COLUMN),
+                    "\"SELECT * FROM post WHERE published\", 1",
+                ),
+                _ => (
+                    "with the\n/// tenant bound before the query's own parameters",
+                    "sql: &str",
+                    format!("\"{} = ?\"", TENANT_COLUMN),
+                    "\"SELECT * FROM post WHERE published\"",
+                ),
+            };
+            content.push_str(&format!(
+                r#"
+/// Restrict a SELECT, UPDATE, or DELETE to the tenant's rows, {doc}
+///
+/// ```ignore
+/// let sql = tenancy::scope_query({example});
+/// sqlx::query(&sql).bind(tenant.0).fetch_all(db).await?;
+/// ```
+pub fn scope_query({params}) -> String {{
+    let filter = {filter};
+    let upper = sql.to_ascii_uppercase();
+    // Clauses that follow the conditions, so the filter goes in front of them
+    let tail = [" GROUP BY ", " ORDER BY ", " LIMIT ", " RETURNING "]
+        .iter()
+        .filter_map(|clause| upper.find(clause))
+        .min()
+        .unwrap_or(sql.len());
+    match upper[..tail].find(" WHERE ") {{
+        Some(at) => {{
+            let conditions = at + " WHERE ".len();
+            format!("{{}}{{}} AND ({{}}){{}}", &sql[..conditions], filter, &sql[conditions..tail], &sql[tail..])
+        }}
+        None => format!("{{}} WHERE {{}}{{}}", &sql[..tail], filter, &sql[tail..]),
+    }}
+}}
+"#,
+            ));
+        }
+        TenancyMode::Schema => content.push_str(
+            r#"
+impl TenantId {
+    /// Database schema holding the tenant's tables
+    pub fn schema(&self) -> String {
+        format!("tenant_{}", self.0.simple())
+    }
+}
+
+/// Start a transaction reading and writing the tenant's schema
+pub async fn begin(
+    db: &crate::config::DatabasePool,
+    tenant: TenantId,
+) -> Result<sqlx::Transaction<'static, sqlx::Postgres>, sqlx::Error> {
+    let mut tx = db.begin().await?;
+    sqlx::query(&format!("SET LOCAL search_path TO \"{}\", public", tenant.schema()))
+        .execute(&mut *tx)
+        .await?;
+    Ok(tx)
+}
+
+/// Create a tenant's schema; run the migrations against it afterwards
+pub async fn create_schema(db: &crate::config::DatabasePool, tenant: TenantId) -> Result<(), sqlx::Error> {
+    sqlx::query(&format!("CREATE SCHEMA IF NOT EXISTS \"{}\"", tenant.schema()))
+        .execute(db)
+        .await?;
+    Ok(())
+}
+"#,
+        ),
+    }
+
+    content
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tenancy_mode_ids() {
+        for mode in TenancyMode::ALL {
+            assert_eq!(TenancyMode::from_id(mode.id()), Some(mode));
+        }
+        assert_eq!(TenancyMode::from_id(" Schema "), Some(TenancyMode::Schema));
+        assert!(!TenancyMode::default().is_enabled());
+        assert_eq!(TenancyMode::Schema.for_backend(DatabaseBackend::Sqlite), TenancyMode::Column);
+        assert_eq!(TenancyMode::Schema.for_backend(DatabaseBackend::Postgres), TenancyMode::Schema);
+    }
+
+    #[test]
+    fn test_generate_column_tenancy() {
+        let code = generate_tenancy(AuthFramework::Axum, DatabaseBackend::Postgres, TenancyMode::Column);
+        assert!(code.contains("pub struct TenantId(pub uuid::Uuid);"));
+        assert!(code.contains("pub async fn resolve_tenant("));
+        assert!(code.contains("impl<S: Send + Sync> axum::extract::FromRequestParts<S> for TenantId"));
+        assert!(code.contains("pub fn scope_query(sql: &str, param: usize) -> String {"));
+        assert!(code.contains("let filter = format!(\"tenant_id = ${}\", param);"));
+        assert!(!code.contains("search_path"));
+
+        let code = generate_tenancy(AuthFramework::Actix, DatabaseBackend::Mysql, TenancyMode::Column);
+        assert!(code.contains("impl actix_web::FromRequest for TenantId"));
+        assert!(code.contains("pub fn scope_query(sql: &str) -> String {"));
+        assert!(code.contains("let filter = \"tenant_id = ?\";"));
+    }
+
+    #[test]
+    fn test_generate_schema_tenancy() {
+        let code = generate_tenancy(AuthFramework::Axum, DatabaseBackend::Postgres, TenancyMode::Schema);
+        assert!(code.contains("SET LOCAL search_path TO"));
+        assert!(code.contains("pub async fn create_schema("));
+        assert!(!code.contains("pub fn scope_query("));
+
+        // Without PostgreSQL schemas fall back to a tenant column
+        let code = generate_tenancy(AuthFramework::Axum, DatabaseBackend::Sqlite, TenancyMode::Schema);
+        assert!(code.contains("pub fn scope_query("));
+    }
+}
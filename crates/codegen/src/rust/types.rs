@@ -0,0 +1,74 @@
+//! Custom Type Generation
+//!
+//! The project's own data types become a `types` module next to the
+//! models, re-exported from the models root. A newtype is a
+//! `#[serde(transparent)]` tuple struct over its underlying type, convertible
+//! from it and dereferencing to it; an alias is a plain `type` item.
+
+use imortal_ir::{CustomType, TypeRepresentation};
+
+/// Generate types.rs content: one newtype or alias per type
+pub fn generate_types(types: &[CustomType]) -> String {
+    let mut output = String::from("//! Data types of the project\n//!\n//! Generated by Immortal Engine\n\n");
+    if types.iter().any(|t| t.representation == TypeRepresentation::Newtype) {
+        output.push_str("use serde::{Deserialize, Serialize};\n");
+    }
+
+    for custom in types {
+        let underlying = custom.underlying.to_rust_type();
+        output.push('\n');
+        if let Some(description) = custom.description.as_deref().filter(|d| !d.trim().is_empty()) {
+            output.push_str(&format!("/// {}\n", description));
+        }
+        match custom.representation {
+            TypeRepresentation::Alias => {
+                output.push_str(&format!("pub type {} = {};\n", custom.name, underlying));
+            }
+            TypeRepresentation::Newtype => {
+                let with = custom
+                    .serde_with
+                    .as_deref()
+                    .map(|path| format!("#[serde(with = \"{}\")] ", path.trim()))
+                    .unwrap_or_default();
+                output.push_str("#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]\n");
+                output.push_str("#[serde(transparent)]\n");
+                output.push_str(&format!("pub struct {}({}pub {});\n", custom.name, with, underlying));
+                output.push_str(&format!(
+                    "\nimpl From<{underlying}> for {name} {{\n    fn from(value: {underlying}) -> Self {{\n        Self(value)\n    }}\n}}\n",
+                    name = custom.name,
+                ));
+                output.push_str(&format!(
+                    "\nimpl std::ops::Deref for {name} {{\n    type Target = {underlying};\n\n    fn deref(&self) -> &{underlying} {{\n        &self.0\n    }}\n}}\n",
+                    name = custom.name,
+                ));
+            }
+        }
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use imortal_core::DataType;
+
+    #[test]
+    fn test_generate_types() {
+        let types = vec![
+            CustomType::new("Email", DataType::String).with_description("An email address"),
+            CustomType::new("Money", DataType::Int64).as_alias(),
+            CustomType::new("Tags", DataType::Array(Box::new(DataType::String))).with_serde_with("crate::csv"),
+        ];
+        let content = generate_types(&types);
+        assert!(content.contains(
+            "/// An email address\n#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]\n#[serde(transparent)]\npub struct Email(pub String);\n"
+        ));
+        assert!(content.contains("impl From<String> for Email {"));
+        assert!(content.contains("\npub type Money = i64;\n"));
+        assert!(content.contains("pub struct Tags(#[serde(with = \"crate::csv\")] pub Vec<String>);"));
+        syn::parse_file(&content).unwrap();
+
+        // Aliases alone need no serde import
+        assert!(!generate_types(&types[1..2]).contains("use serde"));
+    }
+}
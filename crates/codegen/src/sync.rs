@@ -0,0 +1,416 @@
+//! Reverse sync from generated code
+//!
+//! Generated model structs end in a protected region where fields can be
+//! added by hand, with a matching region in their `Default` impl:
+//!
+//! ```text
+//! pub struct User {
+//!     pub id: Uuid,
+//!     // imortal:begin custom fields
+//!     pub nickname: Option<String>,
+//!     // imortal:end custom fields
+//! }
+//! ```
+//!
+//! Regenerating keeps what was written in protected regions
+//! ([`GeneratedProject::preserve_protected_regions`]). [`hand_added_fields`]
+//! parses the custom fields with `syn` so they can be imported back into the
+//! graph as entity fields; once they are, regenerating declares them in the
+//! struct and drops the hand-written copies from the region.
+
+use std::fs;
+use std::io::ErrorKind;
+use std::path::Path;
+
+use quote::ToTokens;
+
+use imortal_core::{DataType, EngineError, EngineResult, NodeId};
+use imortal_ir::{Field, Node, ProjectGraph};
+
+use crate::generator::{GeneratedProject, ProjectLayout};
+use crate::rust::{module_path, to_snake_case};
+
+/// Region for hand-added fields at the end of a model struct
+pub const CUSTOM_FIELDS: &str = "custom fields";
+/// Region for the defaults of hand-added fields in a model's `Default` impl
+pub const CUSTOM_DEFAULTS: &str = "custom defaults";
+
+const BEGIN: &str = "// imortal:begin ";
+const END: &str = "// imortal:end ";
+
+/// An empty protected region, indented to sit inside generated code
+pub fn protected_region(indent: &str, name: &str) -> String {
+    format!("{indent}{BEGIN}{name}\n{indent}{END}{name}\n")
+}
+
+/// What was written in a protected region, without its marker lines
+pub fn protected_region_content<'a>(source: &'a str, name: &str) -> Option<&'a str> {
+    region_bounds(source, name).map(|(begin, end)| &source[begin..end])
+}
+
+/// Byte range between a region's begin and end lines
+fn region_bounds(source: &str, name: &str) -> Option<(usize, usize)> {
+    let (begin_marker, end_marker) = (format!("{BEGIN}{name}"), format!("{END}{name}"));
+    let mut begin = None;
+    let mut offset = 0;
+    for line in source.split_inclusive('\n') {
+        match begin {
+            None if line.trim() == begin_marker => begin = Some(offset + line.len()),
+            Some(begin) if line.trim() == end_marker => return Some((begin, offset)),
+            _ => {}
+        }
+        offset += line.len();
+    }
+    None
+}
+
+/// Freshly generated code with the protected regions of an existing file
+///
+/// Declarations the generated code now makes itself (`name: ...` lines,
+/// with their doc comments and attributes) are dropped from the kept
+/// regions, so imported fields aren't declared twice.
+pub fn preserve_regions(generated: &str, existing: &str) -> String {
+    let names: Vec<String> = generated
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix(BEGIN))
+        .map(str::to_string)
+        .collect();
+
+    let mut output = generated.to_string();
+    for name in names {
+        let (Some(kept), Some((begin, end))) = (protected_region_content(existing, &name), region_bounds(&output, &name)) else {
+            continue;
+        };
+        let kept = without_declarations(kept, |declared| {
+            output[..begin].lines().chain(output[end..].lines()).any(|line| declared_name(line) == Some(declared))
+        });
+        output.replace_range(begin..end, &kept);
+    }
+    output
+}
+
+/// Drop the `name: ...` lines for which `generated` holds, with the doc
+/// comments and attributes above them
+fn without_declarations(region: &str, generated: impl Fn(&str) -> bool) -> String {
+    let mut output = String::new();
+    let mut attached = String::new();
+    for line in region.split_inclusive('\n') {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("///") || trimmed.starts_with("#[") {
+            attached.push_str(line);
+            continue;
+        }
+        if !declared_name(line).is_some_and(&generated) {
+            output.push_str(&attached);
+            output.push_str(line);
+        }
+        attached.clear();
+    }
+    output + &attached
+}
+
+/// Name declared or initialized by a `[pub] name: ...` line
+fn declared_name(line: &str) -> Option<&str> {
+    let line = line.trim();
+    let line = line.strip_prefix("pub ").unwrap_or(line);
+    let (name, rest) = line.split_once(':')?;
+    let is_ident = !name.is_empty() && name.chars().all(|c| c.is_alphanumeric() || c == '_');
+    (is_ident && !rest.starts_with(':')).then_some(name)
+}
+
+impl GeneratedProject {
+    /// Keep what was written in the protected regions of files already
+    /// generated into `dir`
+    pub fn preserve_protected_regions(&mut self, dir: &Path) -> EngineResult<()> {
+        for (path, content) in &mut self.files {
+            if !content.contains(BEGIN) {
+                continue;
+            }
+            match fs::read_to_string(dir.join(path)) {
+                Ok(existing) => *content = preserve_regions(content, &existing),
+                Err(e) if e.kind() == ErrorKind::NotFound => {}
+                Err(e) => return Err(e.into()),
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A field written by hand in a model's protected region
+#[derive(Debug, Clone)]
+pub struct HandAddedField {
+    /// Entity node the model was generated from
+    pub node_id: NodeId,
+    /// Entity name
+    pub entity: String,
+    /// Model file, relative to the generated project
+    pub file: String,
+    /// The field as it would be added to the entity
+    pub field: Field,
+}
+
+/// Path of an entity's model file in a generated project
+pub fn model_file(node: &Node, layout: ProjectLayout) -> String {
+    let modules: String = module_path(node).iter().map(|segment| format!("{}/", segment)).collect();
+    format!("{}/{}{}.rs", layout.models_dir(), modules, to_snake_case(&node.name))
+}
+
+/// Fields added by hand to the models of a project generated into `dir`
+/// that its entities don't have yet
+///
+/// Entities without a model file, or whose model has no protected region,
+/// are skipped.
+pub fn hand_added_fields(graph: &ProjectGraph, dir: &Path) -> EngineResult<Vec<HandAddedField>> {
+    let layout = ProjectLayout::detect(dir);
+    let mut entities: Vec<&Node> = graph.nodes().filter(|n| n.component_type == "data.entity").collect();
+    entities.sort_by(|a, b| a.name.cmp(&b.name).then(a.id.cmp(&b.id)));
+
+    let mut found = Vec::new();
+    for node in entities {
+        let file = model_file(node, layout);
+        let source = match fs::read_to_string(dir.join(&file)) {
+            Ok(source) => source,
+            Err(e) if e.kind() == ErrorKind::NotFound => continue,
+            Err(e) => return Err(e.into()),
+        };
+        let Some(region) = protected_region_content(&source, CUSTOM_FIELDS) else {
+            continue;
+        };
+
+        let fields = parse_fields(region).map_err(|e| EngineError::CodeGeneration(format!("{}: {}", file, e)))?;
+        for field in fields {
+            if !node.fields.iter().any(|f| to_snake_case(&f.name) == field.name) {
+                found.push(HandAddedField { node_id: node.id, entity: node.name.clone(), file: file.clone(), field });
+            }
+        }
+    }
+    Ok(found)
+}
+
+/// Add hand-added fields to their entities, returning how many were added
+pub fn import_hand_added_fields(graph: &mut ProjectGraph, fields: impl IntoIterator<Item = HandAddedField>) -> usize {
+    let mut count = 0;
+    for found in fields {
+        if let Some(node) = graph.get_node_mut(found.node_id) {
+            if node.get_field(&found.field.name).is_none() {
+                node.add_field(found.field);
+                count += 1;
+            }
+        }
+    }
+    graph.dirty |= count > 0;
+    count
+}
+
+/// Parse field declarations as written in a struct body
+///
+/// `Option<T>` fields become optional, doc comments become descriptions,
+/// and types with no matching [`DataType`] are kept as custom Rust types.
+pub fn parse_fields(source: &str) -> syn::Result<Vec<Field>> {
+    let item: syn::ItemStruct = syn::parse_str(&format!("struct Region {{\n{}\n}}", source))?;
+    Ok(item.fields.iter().filter_map(field_from_syn).collect())
+}
+
+/// An entity field for a named struct field
+pub(crate) fn field_from_syn(f: &syn::Field) -> Option<Field> {
+    let name = f.ident.as_ref()?.to_string();
+    let (data_type, required) = match generic_args(&f.ty, "Option").as_slice() {
+        [inner] => (data_type(inner), false),
+        _ => (data_type(&f.ty), true),
+    };
+    let mut field = Field::new(name, data_type);
+    if required {
+        field = field.required();
+    }
+    if let Some(doc) = doc_comment(&f.attrs) {
+        field = field.with_description(doc);
+    }
+    Some(field)
+}
+
+/// Doc comment lines joined into one description
+pub(crate) fn doc_comment(attrs: &[syn::Attribute]) -> Option<String> {
+    let doc: Vec<String> = attrs.iter().filter_map(doc_line).collect();
+    (!doc.is_empty()).then(|| doc.join(" "))
+}
+
+/// The data type a Rust type is generated from
+fn data_type(ty: &syn::Type) -> DataType {
+    let name = type_name(ty);
+    match (name.as_str(), generic_args(ty, &name).as_slice()) {
+        ("String", _) => DataType::String,
+        ("i32", _) => DataType::Int32,
+        ("i64", _) => DataType::Int64,
+        ("f32", _) => DataType::Float32,
+        ("f64", _) => DataType::Float64,
+        ("bool", _) => DataType::Bool,
+        ("Uuid", _) => DataType::Uuid,
+        ("DateTime", _) => DataType::DateTime,
+        ("NaiveDate", _) => DataType::Date,
+        ("NaiveTime", _) => DataType::Time,
+        ("Value", _) => DataType::Json,
+        ("Vec", [inner]) if type_name(inner) == "u8" => DataType::Bytes,
+        ("Vec", [inner]) => DataType::Array(Box::new(data_type(inner))),
+        ("Option", [inner]) => DataType::Optional(Box::new(data_type(inner))),
+        ("HashMap" | "BTreeMap", [key, value]) => DataType::Map {
+            key: Box::new(data_type(key)),
+            value: Box::new(data_type(value)),
+        },
+        _ => DataType::Custom {
+            domain: "rust".to_string(),
+            type_name: ty.to_token_stream().to_string().replace(' ', ""),
+        },
+    }
+}
+
+/// Last path segment of a type, e.g. `Value` for `serde_json::Value`
+fn type_name(ty: &syn::Type) -> String {
+    match ty {
+        syn::Type::Path(path) => path.path.segments.last().map(|s| s.ident.to_string()).unwrap_or_default(),
+        _ => String::new(),
+    }
+}
+
+/// Type arguments of `ty` if its last path segment is `name`
+fn generic_args<'a>(ty: &'a syn::Type, name: &str) -> Vec<&'a syn::Type> {
+    let syn::Type::Path(path) = ty else {
+        return Vec::new();
+    };
+    let Some(segment) = path.path.segments.last().filter(|s| s.ident == name) else {
+        return Vec::new();
+    };
+    match &segment.arguments {
+        syn::PathArguments::AngleBracketed(args) => args
+            .args
+            .iter()
+            .filter_map(|arg| match arg {
+                syn::GenericArgument::Type(ty) => Some(ty),
+                _ => None,
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn doc_line(attr: &syn::Attribute) -> Option<String> {
+    let syn::Meta::NameValue(meta) = &attr.meta else {
+        return None;
+    };
+    if !meta.path.is_ident("doc") {
+        return None;
+    }
+    match &meta.value {
+        syn::Expr::Lit(syn::ExprLit { lit: syn::Lit::Str(s), .. }) => Some(s.value().trim().to_string()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generator::CodeGenerator;
+    use imortal_ir::ProjectMeta;
+
+    fn shop() -> ProjectGraph {
+        let mut graph = ProjectGraph::new(ProjectMeta::new("shop"));
+        let mut user = Node::new_entity("User");
+        user.add_field(Field::string("email").required());
+        graph.add_node(user);
+        graph.add_node(Node::new_entity("Invoice").with_namespace("billing"));
+        graph
+    }
+
+    /// Write a generated project to disk as a user would
+    fn write(project: &GeneratedProject, dir: &Path) {
+        for (path, content) in &project.files {
+            let path = dir.join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        }
+    }
+
+    /// Write code into a protected region of a file on disk
+    fn edit_region(path: &Path, name: &str, code: &str) {
+        let source = fs::read_to_string(path).unwrap();
+        let (_, end) = region_bounds(&source, name).unwrap();
+        let mut edited = source.clone();
+        edited.insert_str(end, code);
+        fs::write(path, edited).unwrap();
+    }
+
+    #[test]
+    fn test_parse_fields() {
+        let fields = parse_fields(
+            "    /// Shown on profiles\n    pub nickname: Option<String>,\n    pub logins: i64,\n    pub tags: Vec<String>,\n    pub avatar: Vec<u8>,\n    pub extra: serde_json::Value,\n    pub money: rust_decimal::Decimal,\n",
+        )
+        .unwrap();
+        let types: Vec<_> = fields.iter().map(|f| (f.name.as_str(), f.data_type.clone(), f.required)).collect();
+        assert_eq!(types, [
+            ("nickname", DataType::String, false),
+            ("logins", DataType::Int64, true),
+            ("tags", DataType::Array(Box::new(DataType::String)), true),
+            ("avatar", DataType::Bytes, true),
+            ("extra", DataType::Json, true),
+            ("money", DataType::Custom { domain: "rust".to_string(), type_name: "rust_decimal::Decimal".to_string() }, true),
+        ]);
+        assert_eq!(fields[0].description.as_deref(), Some("Shown on profiles"));
+        // Hand-written code that doesn't parse is reported, not guessed at
+        assert!(parse_fields("pub broken: ,").is_err());
+    }
+
+    #[test]
+    fn test_detect_and_import_hand_added_fields() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut graph = shop();
+        let generator = CodeGenerator::new();
+        write(&generator.generate(&graph).unwrap(), dir.path());
+
+        let user = graph.find_nodes_by_name("User")[0].clone();
+        let model = dir.path().join(model_file(&user, ProjectLayout::SingleCrate));
+        edit_region(&model, CUSTOM_FIELDS, "    /// Shown on profiles\n    pub nickname: Option<String>,\n    pub email: String,\n");
+        edit_region(&model, CUSTOM_DEFAULTS, "            nickname: None,\n");
+
+        let found = hand_added_fields(&graph, dir.path()).unwrap();
+        // `email` is already an entity field
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].entity, "User");
+        assert_eq!(found[0].file, "src/models/user.rs");
+        assert_eq!(found[0].field.name, "nickname");
+
+        // Regenerating before importing keeps the hand-written code, except
+        // for the duplicate of a generated field
+        let mut regenerated = generator.generate(&graph).unwrap();
+        regenerated.preserve_protected_regions(dir.path()).unwrap();
+        let code = regenerated.get_file("src/models/user.rs").unwrap();
+        assert!(code.contains("    pub nickname: Option<String>,\n    // imortal:end custom fields"));
+        assert_eq!(code.matches("pub email: String,").count(), 1);
+
+        assert_eq!(import_hand_added_fields(&mut graph, found), 1);
+        assert!(hand_added_fields(&graph, dir.path()).unwrap().is_empty());
+
+        // Once imported, the struct declares the field and the region copy goes
+        let mut regenerated = generator.generate(&graph).unwrap();
+        regenerated.preserve_protected_regions(dir.path()).unwrap();
+        let code = regenerated.get_file("src/models/user.rs").unwrap();
+        assert_eq!(code.matches("pub nickname: Option<String>,").count(), 1);
+        assert_eq!(code.matches("nickname: None,").count(), 1);
+        assert_eq!(code.matches("/// Shown on profiles").count(), 1);
+    }
+
+    #[test]
+    fn test_workspace_layout_is_detected() {
+        let dir = tempfile::tempdir().unwrap();
+        let graph = shop();
+        let generator = CodeGenerator::with_config(crate::GeneratorConfig::default().with_layout(ProjectLayout::Workspace));
+        write(&generator.generate(&graph).unwrap(), dir.path());
+        assert_eq!(ProjectLayout::detect(dir.path()), ProjectLayout::Workspace);
+
+        let invoice = graph.find_nodes_by_name("Invoice")[0];
+        let model = dir.path().join(model_file(invoice, ProjectLayout::Workspace));
+        assert!(model.ends_with("crates/models/src/billing/invoice.rs"));
+        edit_region(&model, CUSTOM_FIELDS, "    pub paid: bool,\n");
+        let found = hand_added_fields(&graph, dir.path()).unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].field.data_type, DataType::Bool);
+    }
+}
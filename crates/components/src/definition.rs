@@ -5,20 +5,22 @@
 //! a Node is created based on the ComponentDefinition.
 
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
 
 use imortal_core::{
-    ComponentCategory, ConfigValue, DataType, PortDirection, PortKind, UiHints, Validation,
+    ComponentCategory, ConfigValue, DataType, IconSource, PortDirection, PortKind, UiHints, Validation,
 };
 use imortal_ir::{Field, Node, Port};
-use imortal_ir::port::PortCollection;
 
 /// Definition of a component type (template)
 ///
 /// This describes what a component looks like and how it behaves.
 /// When a user drags this component onto the canvas, a Node is created
 /// from this definition.
+///
+/// Definition files only need `id`, `name`, and `category`; everything else
+/// falls back to the defaults of [`ComponentDefinition::new`].
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct ComponentDefinition {
     /// Unique identifier for this component type (e.g., "auth.login", "data.entity")
     pub id: String,
@@ -32,7 +34,7 @@ pub struct ComponentDefinition {
     /// Description of what this component does
     pub description: String,
 
-    /// Icon for display (emoji or icon name)
+    /// Icon for display: an emoji, or an SVG or PNG image (see [`IconSource`])
     pub icon: String,
 
     /// Default fields when component is instantiated
@@ -47,6 +49,12 @@ pub struct ComponentDefinition {
     /// Tags for searching and filtering
     pub tags: Vec<String>,
 
+    /// Other names people search for this component by, e.g. "Model" for
+    /// an entity; ranked just below the name in [`ComponentRegistry::search`]
+    ///
+    /// [`ComponentRegistry::search`]: crate::ComponentRegistry::search
+    pub synonyms: Vec<String>,
+
     /// Whether this component is deprecated
     pub deprecated: bool,
 
@@ -72,6 +80,9 @@ pub struct ComponentDefinition {
     /// Documentation URL
     pub docs_url: Option<String>,
 
+    /// Reference documentation shown in the editor
+    pub docs: ComponentDocs,
+
     /// Version of this component definition
     pub version: String,
 }
@@ -89,6 +100,7 @@ impl ComponentDefinition {
             ports: PortDefinitions::default(),
             config: Vec::new(),
             tags: Vec::new(),
+            synonyms: Vec::new(),
             deprecated: false,
             deprecation_message: None,
             min_instances: 0,
@@ -98,6 +110,7 @@ impl ComponentDefinition {
             default_height: 150.0,
             generator: None,
             docs_url: None,
+            docs: ComponentDocs::default(),
             version: "1.0.0".to_string(),
         }
     }
@@ -152,6 +165,12 @@ impl ComponentDefinition {
         self
     }
 
+    /// Add a synonym
+    pub fn with_synonym(mut self, synonym: impl Into<String>) -> Self {
+        self.synonyms.push(synonym.into());
+        self
+    }
+
     /// Set the generator identifier
     pub fn with_generator(mut self, generator: impl Into<String>) -> Self {
         self.generator = Some(generator.into());
@@ -164,6 +183,18 @@ impl ComponentDefinition {
         self
     }
 
+    /// Set the reference documentation (Markdown)
+    pub fn with_documentation(mut self, body: impl Into<String>) -> Self {
+        self.docs.body = body.into();
+        self
+    }
+
+    /// Add an example graph to the documentation
+    pub fn with_example(mut self, example: ComponentExample) -> Self {
+        self.docs.examples.push(example);
+        self
+    }
+
     /// Mark as deprecated
     pub fn deprecated(mut self, message: impl Into<String>) -> Self {
         self.deprecated = true;
@@ -237,6 +268,15 @@ impl ComponentDefinition {
 
     // ========== Query Methods ==========
 
+    /// Reference documentation, falling back to the description
+    pub fn documentation(&self) -> &str {
+        if self.docs.body.trim().is_empty() {
+            &self.description
+        } else {
+            &self.docs.body
+        }
+    }
+
     /// Get a field definition by name
     pub fn get_field(&self, name: &str) -> Option<&FieldDefinition> {
         self.fields.iter().find(|f| f.name == name)
@@ -257,6 +297,21 @@ impl ComponentDefinition {
         self.ports.outputs.iter().find(|p| p.id == id)
     }
 
+    /// First port of this component that `port` can connect to: an input
+    /// for an output port, or an output for an input port
+    pub fn compatible_port(&self, port: &Port) -> Option<&PortDefinition> {
+        let candidates = match port.direction {
+            PortDirection::Output => &self.ports.inputs,
+            PortDirection::Input => &self.ports.outputs,
+        };
+        candidates.iter().find(|def| port.can_connect_to(&def.to_port()))
+    }
+
+    /// The icon as text: the emoji, or the category's emoji for image icons
+    pub fn icon_text(&self) -> &str {
+        IconSource::parse(&self.icon).text_or(self.category.icon())
+    }
+
     /// Check if this component has a specific tag
     pub fn has_tag(&self, tag: &str) -> bool {
         self.tags.iter().any(|t| t == tag)
@@ -269,8 +324,90 @@ impl Default for ComponentDefinition {
     }
 }
 
+/// Reference documentation for a component
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ComponentDocs {
+    /// Markdown body
+    pub body: String,
+
+    /// Small graphs showing the component in use
+    pub examples: Vec<ComponentExample>,
+}
+
+/// A small graph showing how a component is used
+///
+/// Built into a project with [`ComponentRegistry::build_example`](crate::ComponentRegistry::build_example).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ComponentExample {
+    /// Short title
+    pub title: String,
+
+    /// What the example shows
+    #[serde(default)]
+    pub description: String,
+
+    /// Nodes as (component ID, node name)
+    pub nodes: Vec<(String, String)>,
+
+    /// Connections between the nodes
+    #[serde(default)]
+    pub connections: Vec<ExampleConnection>,
+}
+
+/// A connection in a [`ComponentExample`], by node index and port ID
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExampleConnection {
+    pub from: usize,
+    pub from_port: String,
+    pub to: usize,
+    pub to_port: String,
+}
+
+impl ComponentExample {
+    /// Create an empty example
+    pub fn new(title: impl Into<String>) -> Self {
+        Self {
+            title: title.into(),
+            description: String::new(),
+            nodes: Vec::new(),
+            connections: Vec::new(),
+        }
+    }
+
+    /// Set the description
+    pub fn with_description(mut self, description: impl Into<String>) -> Self {
+        self.description = description.into();
+        self
+    }
+
+    /// Add a node instantiated from a component
+    pub fn with_node(mut self, component_id: impl Into<String>, name: impl Into<String>) -> Self {
+        self.nodes.push((component_id.into(), name.into()));
+        self
+    }
+
+    /// Connect an output of one node to an input of another
+    pub fn with_connection(
+        mut self,
+        from: usize,
+        from_port: impl Into<String>,
+        to: usize,
+        to_port: impl Into<String>,
+    ) -> Self {
+        self.connections.push(ExampleConnection {
+            from,
+            from_port: from_port.into(),
+            to,
+            to_port: to_port.into(),
+        });
+        self
+    }
+}
+
 /// Port definitions for a component
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
 pub struct PortDefinitions {
     /// Input port definitions
     pub inputs: Vec<PortDefinition>,
@@ -300,30 +437,46 @@ pub struct PortDefinition {
     pub name: String,
 
     /// Description
+    #[serde(default)]
     pub description: Option<String>,
 
     /// Port direction (input or output)
+    #[serde(default = "default_port_direction")]
     pub direction: PortDirection,
 
     /// Port kind (data, trigger, flow)
+    #[serde(default = "default_port_kind")]
     pub kind: PortKind,
 
     /// Data type accepted/produced by this port
     pub data_type: DataType,
 
     /// Whether multiple connections are allowed
+    #[serde(default)]
     pub multiple: bool,
 
     /// Whether this port is required to be connected
+    #[serde(default)]
     pub required: bool,
 
     /// Default value if not connected (for inputs)
+    #[serde(default)]
     pub default_value: Option<String>,
 
     /// Display order
+    #[serde(default)]
     pub order: i32,
 }
 
+/// Ports in definition files take their direction from the list they are in
+fn default_port_direction() -> PortDirection {
+    PortDirection::Input
+}
+
+fn default_port_kind() -> PortKind {
+    PortKind::Data
+}
+
 impl PortDefinition {
     /// Create a new port definition
     pub fn new(
@@ -444,30 +597,38 @@ pub struct FieldDefinition {
     pub name: String,
 
     /// Display label
+    #[serde(default)]
     pub label: Option<String>,
 
     /// Data type
     pub data_type: DataType,
 
     /// Whether this field is required
+    #[serde(default)]
     pub required: bool,
 
     /// Default value
+    #[serde(default)]
     pub default_value: Option<ConfigValue>,
 
     /// Validation rules
+    #[serde(default)]
     pub validations: Vec<Validation>,
 
     /// UI hints
+    #[serde(default)]
     pub ui_hints: UiHints,
 
     /// Description
+    #[serde(default)]
     pub description: Option<String>,
 
     /// Whether this field is read-only
+    #[serde(default)]
     pub read_only: bool,
 
     /// Whether this is a secret field
+    #[serde(default)]
     pub secret: bool,
 }
 
@@ -640,30 +801,38 @@ pub struct ConfigOption {
     pub name: String,
 
     /// Description
+    #[serde(default)]
     pub description: Option<String>,
 
     /// Type of configuration value
     pub config_type: ConfigType,
 
     /// Default value
+    #[serde(default)]
     pub default_value: Option<ConfigValue>,
 
     /// Whether this option is required
+    #[serde(default)]
     pub required: bool,
 
     /// Possible values (for enum/select types)
+    #[serde(default)]
     pub options: Vec<ConfigOptionValue>,
 
     /// Validation constraints
+    #[serde(default)]
     pub constraints: ConfigConstraints,
 
     /// Display order
+    #[serde(default)]
     pub order: i32,
 
     /// Group/section name for organizing config
+    #[serde(default)]
     pub group: Option<String>,
 
     /// Whether this is an advanced option
+    #[serde(default)]
     pub advanced: bool,
 }
 
@@ -824,11 +993,13 @@ pub struct ConfigOptionValue {
     /// Display label
     pub label: String,
     /// Optional description
+    #[serde(default)]
     pub description: Option<String>,
 }
 
 /// Constraints for configuration values
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
 pub struct ConfigConstraints {
     /// Minimum numeric value
     pub min: Option<f64>,
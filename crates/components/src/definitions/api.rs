@@ -6,15 +6,28 @@
 //! - WebSocket: WebSocket connections
 
 use crate::definition::{
-    ComponentDefinition, ConfigOption, ConfigType, FieldDefinition, PortDefinition,
+    ComponentDefinition, ComponentExample, ConfigOption, FieldDefinition, PortDefinition,
 };
-use imortal_core::{ComponentCategory, DataType, Validation};
+use imortal_core::{ComponentCategory, DataType};
 
 /// Create the REST Endpoint component definition
 pub fn rest_endpoint_component() -> ComponentDefinition {
     ComponentDefinition::new("api.rest", "REST Endpoint", ComponentCategory::Api)
         .with_description("Define a RESTful API endpoint with HTTP methods")
+        .with_documentation(include_str!("docs/api.rest.md"))
+        .with_example(
+            ComponentExample::new("Validated create endpoint")
+                .with_description("Requests are validated before a record is created")
+                .with_node("api.rest", "Create Product")
+                .with_node("logic.validator", "Check Product")
+                .with_node("data.entity", "Product")
+                .with_connection(0, "on_request", 1, "validate")
+                .with_connection(1, "valid", 2, "create"),
+        )
         .with_icon("🔌")
+        .with_synonym("Route")
+        .with_synonym("Handler")
+        .with_synonym("Controller")
         .with_tag("http")
         .with_tag("rest")
         .with_tag("api")
@@ -81,6 +94,11 @@ pub fn rest_endpoint_component() -> ComponentDefinition {
                 .with_default("GET")
                 .with_description("HTTP method for this endpoint"),
         )
+        .with_config(
+            ConfigOption::string("version", "API Version")
+                .with_description("API version, e.g. v2 (empty = project default)")
+                .with_default(""),
+        )
         .with_config(
             ConfigOption::boolean("auth_required", "Require Authentication")
                 .with_default(false)
@@ -127,6 +145,7 @@ pub fn rest_endpoint_component() -> ComponentDefinition {
 pub fn graphql_endpoint_component() -> ComponentDefinition {
     ComponentDefinition::new("api.graphql", "GraphQL", ComponentCategory::Api)
         .with_description("Define a GraphQL API with queries, mutations, and subscriptions")
+        .with_documentation(include_str!("docs/api.graphql.md"))
         .with_icon("◈")
         .with_tag("graphql")
         .with_tag("api")
@@ -225,6 +244,7 @@ pub fn graphql_endpoint_component() -> ComponentDefinition {
 pub fn websocket_component() -> ComponentDefinition {
     ComponentDefinition::new("api.websocket", "WebSocket", ComponentCategory::Api)
         .with_description("WebSocket connection for real-time bidirectional communication")
+        .with_documentation(include_str!("docs/api.websocket.md"))
         .with_icon("🔄")
         .with_tag("websocket")
         .with_tag("realtime")
@@ -7,7 +7,7 @@
 //! - Session: Session management
 
 use crate::definition::{
-    ComponentDefinition, ConfigOption, ConfigType, FieldDefinition, PortDefinition,
+    ComponentDefinition, ComponentExample, ConfigOption, FieldDefinition, PortDefinition,
 };
 use imortal_core::{ComponentCategory, DataType, Validation};
 
@@ -15,7 +15,17 @@ use imortal_core::{ComponentCategory, DataType, Validation};
 pub fn login_component() -> ComponentDefinition {
     ComponentDefinition::new("auth.login", "Login", ComponentCategory::Auth)
         .with_description("User login component with email and password authentication")
+        .with_documentation(include_str!("docs/auth.login.md"))
+        .with_example(
+            ComponentExample::new("Login with a session")
+                .with_description("The issued token is checked by a session on later requests")
+                .with_node("auth.login", "Login")
+                .with_node("auth.session", "Session")
+                .with_connection(0, "token", 1, "token")
+                .with_connection(0, "success", 1, "check"),
+        )
         .with_icon("🔐")
+        .with_synonym("Sign In")
         .with_tag("authentication")
         .with_tag("user")
         // Fields
@@ -99,7 +109,9 @@ pub fn login_component() -> ComponentDefinition {
 pub fn register_component() -> ComponentDefinition {
     ComponentDefinition::new("auth.register", "Register", ComponentCategory::Auth)
         .with_description("User registration component for creating new accounts")
+        .with_documentation(include_str!("docs/auth.register.md"))
         .with_icon("📝")
+        .with_synonym("Sign Up")
         .with_tag("authentication")
         .with_tag("user")
         .with_tag("signup")
@@ -211,7 +223,9 @@ pub fn register_component() -> ComponentDefinition {
 pub fn logout_component() -> ComponentDefinition {
     ComponentDefinition::new("auth.logout", "Logout", ComponentCategory::Auth)
         .with_description("User logout component for ending sessions")
+        .with_documentation(include_str!("docs/auth.logout.md"))
         .with_icon("🚪")
+        .with_synonym("Sign Out")
         .with_tag("authentication")
         .with_tag("session")
         // Input ports
@@ -253,6 +267,7 @@ pub fn logout_component() -> ComponentDefinition {
 pub fn session_component() -> ComponentDefinition {
     ComponentDefinition::new("auth.session", "Session", ComponentCategory::Auth)
         .with_description("Session management component for checking and managing user sessions")
+        .with_documentation(include_str!("docs/auth.session.md"))
         .with_icon("🎫")
         .with_tag("authentication")
         .with_tag("session")
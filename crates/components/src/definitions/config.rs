@@ -0,0 +1,48 @@
+//! Configuration Component Definitions
+//!
+//! This module provides component definitions for application configuration:
+//! - Settings: App-level settings loaded from environment variables
+
+use crate::definition::{ComponentDefinition, PortDefinition};
+use imortal_core::{ComponentCategory, DataType};
+
+/// Create the Settings component definition
+///
+/// Each field on a settings node declares one application setting. The field
+/// name becomes the `Config` struct member, the field type its Rust type, the
+/// default value its fallback, and the `env_var` field metadata the environment
+/// variable it is loaded from (defaults to the name in SCREAMING_SNAKE_CASE).
+/// Other nodes reference a setting in their configuration as `${ENV_VAR}`.
+pub fn settings_component() -> ComponentDefinition {
+    ComponentDefinition::new("config.settings", "Settings", ComponentCategory::Config)
+        .with_description("Declare application settings loaded from environment variables")
+        .with_documentation(include_str!("docs/config.settings.md"))
+        .with_icon("🛠")
+        .with_tag("config")
+        .with_tag("env")
+        .with_tag("settings")
+        // Output ports
+        .with_output(
+            PortDefinition::data_out("settings", "Settings", DataType::Any)
+                .with_description("Settings values for other components")
+                .multiple(),
+        )
+        .allow_custom_fields()
+        .with_default_size(200.0, 120.0)
+        .with_generator("config::settings")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_settings_component() {
+        let component = settings_component();
+
+        assert_eq!(component.id, "config.settings");
+        assert_eq!(component.category, ComponentCategory::Config);
+        assert!(component.allow_custom_fields);
+        assert!(component.ports.outputs.iter().any(|p| p.id == "settings"));
+    }
+}
@@ -4,11 +4,12 @@
 //! - Entity: Core data model/table definition
 //! - Collection: Array/list of entities
 //! - Query: Database query builder
+//! - Reference: Entity shared from another project in the workspace
 
 use crate::definition::{
-    ComponentDefinition, ConfigOption, ConfigType, FieldDefinition, PortDefinition,
+    ComponentDefinition, ComponentExample, ConfigOption, FieldDefinition, PortDefinition,
 };
-use imortal_core::{ComponentCategory, DataType, Validation};
+use imortal_core::{ComponentCategory, DataType};
 
 /// Create the Entity component definition
 ///
@@ -17,6 +18,14 @@ use imortal_core::{ComponentCategory, DataType, Validation};
 pub fn entity_component() -> ComponentDefinition {
     ComponentDefinition::new("data.entity", "Entity", ComponentCategory::Data)
         .with_description("Define a data model with fields and relationships")
+        .with_documentation(include_str!("docs/data.entity.md"))
+        .with_example(
+            ComponentExample::new("Listing records")
+                .with_description("A collection pages through the records of an entity")
+                .with_node("data.entity", "Product")
+                .with_node("data.collection", "Products")
+                .with_connection(0, "entity", 1, "entity_type"),
+        )
         .with_icon("📊")
         .with_field(
             FieldDefinition::uuid("id")
@@ -72,6 +81,11 @@ pub fn entity_component() -> ComponentDefinition {
                 .with_description("Use soft delete instead of hard delete")
                 .with_default(false),
         )
+        .with_config(
+            ConfigOption::boolean("realtime", "Realtime")
+                .with_description("Broadcast create, update, and delete events to subscribed clients")
+                .with_default(false),
+        )
         .with_config(
             ConfigOption::select("id_type", "ID Type")
                 .with_option("uuid", "UUID")
@@ -83,6 +97,9 @@ pub fn entity_component() -> ComponentDefinition {
         .allow_custom_fields()
         .with_default_size(220.0, 200.0)
         .with_generator("data::entity")
+        .with_synonym("Model")
+        .with_synonym("Record")
+        .with_synonym("Table")
         .with_tag("model")
         .with_tag("table")
         .with_tag("struct")
@@ -95,6 +112,7 @@ pub fn entity_component() -> ComponentDefinition {
 pub fn collection_component() -> ComponentDefinition {
     ComponentDefinition::new("data.collection", "Collection", ComponentCategory::Data)
         .with_description("A queryable collection of entities with filtering and pagination")
+        .with_documentation(include_str!("docs/data.collection.md"))
         .with_icon("📚")
         .with_input(
             PortDefinition::data_in("entity_type", "Entity Type", DataType::Entity("Any".to_string()))
@@ -171,6 +189,16 @@ pub fn collection_component() -> ComponentDefinition {
 pub fn query_component() -> ComponentDefinition {
     ComponentDefinition::new("data.query", "Query", ComponentCategory::Data)
         .with_description("Build and execute database queries with conditions and joins")
+        .with_documentation(include_str!("docs/data.query.md"))
+        .with_example(
+            ComponentExample::new("Query behind an endpoint")
+                .with_description("Each request to the endpoint runs a query against the entity")
+                .with_node("api.rest", "List Products")
+                .with_node("data.entity", "Product")
+                .with_node("data.query", "Find Products")
+                .with_connection(1, "entity", 2, "source")
+                .with_connection(0, "on_request", 2, "execute"),
+        )
         .with_icon("🔍")
         .with_input(
             PortDefinition::data_in("source", "Source", DataType::Entity("Any".to_string()))
@@ -344,6 +372,74 @@ pub fn relationship_component() -> ComponentDefinition {
         .with_tag("join")
 }
 
+/// Create the Reference component definition
+///
+/// Points at an entity defined in another project of the same workspace, so
+/// services can share a schema instead of copying it.
+pub fn reference_component() -> ComponentDefinition {
+    ComponentDefinition::new("data.reference", "Reference", ComponentCategory::Data)
+        .with_description("Use an entity defined in another workspace project")
+        .with_documentation(include_str!("docs/data.reference.md"))
+        .with_icon("🔗")
+        .with_output(
+            PortDefinition::data_out("entity", "Entity", DataType::Entity("Any".to_string()))
+                .with_description("The referenced entity"),
+        )
+        .with_config(
+            ConfigOption::string("project", "Project")
+                .required()
+                .with_description("Name of the workspace project that defines the entity"),
+        )
+        .with_config(
+            ConfigOption::string("entity", "Entity")
+                .required()
+                .with_description("Name of the referenced entity"),
+        )
+        .with_default_size(180.0, 80.0)
+        .with_generator("data::reference")
+        .with_tag("workspace")
+        .with_tag("shared")
+        .with_tag("schema")
+}
+
+/// Create an Audit Log component definition
+///
+/// Records changes to the entities connected to it
+pub fn audit_log_component() -> ComponentDefinition {
+    ComponentDefinition::new("data.audit_log", "Audit Log", ComponentCategory::Data)
+        .with_description("Record who changed what in the connected entities")
+        .with_documentation(include_str!("docs/data.audit_log.md"))
+        .with_icon("📜")
+        .with_input(
+            PortDefinition::data_in("entities", "Entities", DataType::Any)
+                .with_description("Entities whose changes are recorded")
+                .multiple(),
+        )
+        .with_config(
+            ConfigOption::string("table_name", "Table")
+                .with_default("audit_log")
+                .with_description("Table the changes are recorded in"),
+        )
+        .with_config(
+            ConfigOption::select("strategy", "Strategy")
+                .with_option("hooks", "Repository Hooks")
+                .with_option("triggers", "Database Triggers")
+                .with_default("hooks")
+                .with_description("How changes are recorded; triggers need PostgreSQL"),
+        )
+        .with_config(
+            ConfigOption::string("endpoint", "Endpoint")
+                .with_default("/audit")
+                .with_description("Path the audit trail is served on"),
+        )
+        .with_instance_limits(0, 1)
+        .with_default_size(180.0, 80.0)
+        .with_generator("data::audit_log")
+        .with_tag("audit")
+        .with_tag("history")
+        .with_tag("compliance")
+}
+
 /// Create a Computed Field component definition
 ///
 /// A virtual/computed field that derives its value from other fields
@@ -407,6 +503,7 @@ mod tests {
         assert_eq!(def.category, ComponentCategory::Data);
         assert!(def.allow_custom_fields);
         assert!(def.fields.iter().any(|f| f.name == "id"));
+        assert!(def.config.iter().any(|c| c.id == "realtime"));
     }
 
     #[test]
@@ -435,6 +532,24 @@ mod tests {
         assert!(node.fields.iter().any(|f| f.name == "id"));
     }
 
+    #[test]
+    fn test_reference_component() {
+        let def = reference_component();
+        assert_eq!(def.id, "data.reference");
+        assert!(def.config.iter().any(|c| c.id == "project"));
+        assert!(def.config.iter().any(|c| c.id == "entity"));
+        assert!(def.ports.outputs.iter().any(|p| p.id == "entity"));
+    }
+
+    #[test]
+    fn test_audit_log_component() {
+        let def = audit_log_component();
+        assert_eq!(def.id, "data.audit_log");
+        assert!(def.ports.inputs.iter().any(|p| p.id == "entities" && p.multiple));
+        assert!(def.config.iter().any(|c| c.id == "strategy"));
+        assert!(def.config.iter().any(|c| c.id == "endpoint"));
+    }
+
     #[test]
     fn test_relationship_component() {
         let def = relationship_component();
@@ -4,11 +4,12 @@
 //! - Validator: Validate data against rules
 //! - Transformer: Transform/map data between formats
 //! - Condition: Conditional branching based on expressions
+//! - Feature Flag: Flags that gate API nodes
 
 use crate::definition::{
-    ComponentDefinition, ConfigOption, ConfigType, FieldDefinition, PortDefinition,
+    ComponentDefinition, ComponentExample, ConfigOption, FieldDefinition, PortDefinition,
 };
-use imortal_core::{ComponentCategory, DataType, Validation};
+use imortal_core::{ComponentCategory, DataType};
 
 /// Create the Validator component definition
 ///
@@ -17,6 +18,7 @@ use imortal_core::{ComponentCategory, DataType, Validation};
 pub fn validator_component() -> ComponentDefinition {
     ComponentDefinition::new("logic.validator", "Validator", ComponentCategory::Logic)
         .with_description("Validate data against configurable rules")
+        .with_documentation(include_str!("docs/logic.validator.md"))
         .with_icon("✅")
         .with_tag("validation")
         .with_tag("rules")
@@ -102,6 +104,7 @@ pub fn validator_component() -> ComponentDefinition {
 pub fn transformer_component() -> ComponentDefinition {
     ComponentDefinition::new("logic.transformer", "Transformer", ComponentCategory::Logic)
         .with_description("Transform and map data between formats")
+        .with_documentation(include_str!("docs/logic.transformer.md"))
         .with_icon("🔄")
         .with_tag("transform")
         .with_tag("map")
@@ -195,6 +198,15 @@ pub fn transformer_component() -> ComponentDefinition {
 pub fn condition_component() -> ComponentDefinition {
     ComponentDefinition::new("logic.condition", "Condition", ComponentCategory::Logic)
         .with_description("Conditional branching based on an expression")
+        .with_documentation(include_str!("docs/logic.condition.md"))
+        .with_example(
+            ComponentExample::new("Branching on session data")
+                .with_description("A valid session is checked for the admin role")
+                .with_node("auth.session", "Session")
+                .with_node("logic.condition", "Is Admin")
+                .with_connection(0, "session_data", 1, "value")
+                .with_connection(0, "valid", 1, "evaluate"),
+        )
         .with_icon("🔀")
         .with_tag("condition")
         .with_tag("if")
@@ -505,6 +517,73 @@ pub fn merge_component() -> ComponentDefinition {
         .with_generator("logic::merge")
 }
 
+/// Create a Feature Flag component definition
+///
+/// A Feature Flag component defines named flags, read from a provider at
+/// runtime. API nodes sit behind a flag through a gating edge or their
+/// `feature_flag` config, and only serve requests while it is on.
+pub fn feature_flag_component() -> ComponentDefinition {
+    ComponentDefinition::new("logic.feature_flag", "Feature Flag", ComponentCategory::Logic)
+        .with_description("Named flags that turn API endpoints on and off")
+        .with_documentation(include_str!("docs/logic.feature_flag.md"))
+        .with_icon("🚩")
+        .with_tag("feature")
+        .with_tag("flag")
+        .with_tag("toggle")
+        // Output ports
+        .with_output(
+            PortDefinition::data_out("gate", "Gate", DataType::Bool)
+                .with_description("Nodes behind one of the flags")
+                .multiple(),
+        )
+        // Configuration
+        .with_config(
+            ConfigOption::string("flags", "Flags")
+                .with_default("new_feature")
+                .required()
+                .with_description("Comma-separated flag names, with =on for flags on by default"),
+        )
+        .with_config(
+            ConfigOption::select("provider", "Provider")
+                .with_option("env", "Environment Variables")
+                .with_option("config", "App Config")
+                .with_option("unleash", "Unleash")
+                .with_default("env")
+                .with_description("Where the generated service reads the flags from"),
+        )
+        .with_default_size(160.0, 80.0)
+        .with_generator("logic::feature_flag")
+}
+
+/// Create the custom code component definition
+pub fn custom_code_component() -> ComponentDefinition {
+    ComponentDefinition::new("logic.custom_code", "Custom Code", ComponentCategory::Logic)
+        .with_description("A hand-written Rust function, for when no component fits")
+        .with_documentation(include_str!("docs/logic.custom_code.md"))
+        .with_icon("🦀")
+        .with_tag("code")
+        .with_tag("rust")
+        .with_tag("custom")
+        // Input ports
+        .with_input(
+            PortDefinition::data_in("input", "Input", DataType::Any)
+                .with_description("A parameter of the generated function"),
+        )
+        // Output ports
+        .with_output(
+            PortDefinition::data_out("output", "Output", DataType::Any)
+                .with_description("What the generated function returns"),
+        )
+        // Configuration
+        .with_config(
+            ConfigOption::string("code", "Code")
+                .with_default("input")
+                .with_description("Rust statements forming the function body, ending with its return value"),
+        )
+        .with_default_size(180.0, 100.0)
+        .with_generator("logic::custom_code")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -554,6 +633,23 @@ mod tests {
         assert!(def.ports.outputs.iter().any(|p| p.id == "complete"));
     }
 
+    #[test]
+    fn test_feature_flag_component() {
+        let def = feature_flag_component();
+        assert_eq!(def.id, "logic.feature_flag");
+        assert!(def.ports.outputs.iter().any(|p| p.id == "gate"));
+        assert!(def.config.iter().any(|c| c.id == "provider"));
+    }
+
+    #[test]
+    fn test_custom_code_component() {
+        let def = custom_code_component();
+        assert_eq!(def.id, "logic.custom_code");
+        assert!(def.ports.inputs.iter().any(|p| p.id == "input"));
+        assert!(def.ports.outputs.iter().any(|p| p.id == "output"));
+        assert!(def.config.iter().any(|c| c.id == "code"));
+    }
+
     #[test]
     fn test_merge_component() {
         let def = merge_component();
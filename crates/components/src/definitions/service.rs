@@ -0,0 +1,245 @@
+//! Service Component Definitions
+//!
+//! This module provides component definitions for cross-cutting service concerns:
+//! - Observability: Health checks, Prometheus metrics, and OTLP tracing
+//! - Search: Full-text search over entities
+//! - Payments: Checkout sessions and webhooks
+//! - I18n: Locales and localized messages
+
+use crate::definition::{ComponentDefinition, ConfigOption, PortDefinition};
+use imortal_core::{ComponentCategory, DataType};
+
+/// Create the Observability component definition
+///
+/// Adding this node to a project makes the code generator emit `/healthz` and
+/// `/readyz` endpoints, a Prometheus metrics endpoint with request metrics,
+/// and tracing spans in every handler exported over OTLP.
+pub fn observability_component() -> ComponentDefinition {
+    ComponentDefinition::new("service.observability", "Observability", ComponentCategory::Service)
+        .with_description("Health checks, Prometheus metrics, and OTLP tracing")
+        .with_documentation(include_str!("docs/service.observability.md"))
+        .with_icon("🛰")
+        .with_tag("observability")
+        .with_tag("metrics")
+        .with_tag("tracing")
+        .with_tag("health")
+        // Configuration
+        .with_config(
+            ConfigOption::boolean("health_checks", "Health Checks")
+                .with_default(true)
+                .with_description("Generate /healthz and /readyz endpoints"),
+        )
+        .with_config(
+            ConfigOption::boolean("metrics", "Prometheus Metrics")
+                .with_default(true)
+                .with_description("Record request metrics and serve them for Prometheus")
+                .in_group("Metrics"),
+        )
+        .with_config(
+            ConfigOption::string("metrics_path", "Metrics Path")
+                .with_default("/metrics")
+                .with_description("Path the metrics are served on")
+                .in_group("Metrics"),
+        )
+        .with_config(
+            ConfigOption::boolean("tracing", "Tracing")
+                .with_default(true)
+                .with_description("Add tracing spans to every handler and export them over OTLP")
+                .in_group("Tracing"),
+        )
+        .with_config(
+            ConfigOption::string("otlp_endpoint", "OTLP Endpoint")
+                .with_default("http://localhost:4317")
+                .with_description("Default collector endpoint (OTEL_EXPORTER_OTLP_ENDPOINT)")
+                .in_group("Tracing"),
+        )
+        .with_instance_limits(0, 1)
+        .with_default_size(180.0, 80.0)
+        .with_generator("service::observability")
+}
+
+/// Create the Search component definition
+///
+/// Indexes the text fields of the connected entities in PostgreSQL,
+/// Meilisearch, or an embedded Tantivy index, and serves ranked results.
+pub fn search_component() -> ComponentDefinition {
+    ComponentDefinition::new("service.search", "Search", ComponentCategory::Service)
+        .with_description("Full-text search over the connected entities")
+        .with_documentation(include_str!("docs/service.search.md"))
+        .with_icon("🔎")
+        .with_tag("search")
+        .with_tag("full-text")
+        .with_tag("meilisearch")
+        .with_tag("tantivy")
+        .with_input(
+            PortDefinition::data_in("entities", "Entities", DataType::Any)
+                .with_description("Entities whose text fields are searched")
+                .multiple(),
+        )
+        // Configuration
+        .with_config(
+            ConfigOption::select("engine", "Engine")
+                .with_option("postgres", "PostgreSQL Full-Text")
+                .with_option("meilisearch", "Meilisearch")
+                .with_option("tantivy", "Tantivy (embedded)")
+                .with_default("postgres")
+                .with_description("What indexes and ranks the entities; PostgreSQL full-text needs PostgreSQL"),
+        )
+        .with_config(
+            ConfigOption::string("endpoint", "Endpoint")
+                .with_default("/search")
+                .with_description("Path the search is served on"),
+        )
+        .with_config(
+            ConfigOption::string("language", "Language")
+                .with_default("english")
+                .with_description("Text search configuration stemming the words")
+                .in_group("PostgreSQL"),
+        )
+        .with_config(
+            ConfigOption::string("meilisearch_url", "Meilisearch URL")
+                .with_default("http://localhost:7700")
+                .with_description("Default server URL (MEILISEARCH_URL)")
+                .in_group("Meilisearch"),
+        )
+        .with_config(
+            ConfigOption::string("index_path", "Index Directory")
+                .with_default("search_index")
+                .with_description("Default directory of the index (SEARCH_INDEX_PATH)")
+                .in_group("Tantivy"),
+        )
+        .with_instance_limits(0, 1)
+        .with_default_size(180.0, 80.0)
+        .with_generator("service::search")
+}
+
+/// Create the Payments component definition
+///
+/// Sells the connected entities through the provider's hosted checkout and
+/// receives its webhooks, verifying their signatures.
+pub fn payments_component() -> ComponentDefinition {
+    ComponentDefinition::new("service.payments", "Payments", ComponentCategory::Service)
+        .with_description("Checkout sessions and webhooks for the connected products")
+        .with_documentation(include_str!("docs/service.payments.md"))
+        .with_icon("💳")
+        .with_tag("payments")
+        .with_tag("stripe")
+        .with_tag("checkout")
+        .with_input(
+            PortDefinition::data_in("products", "Products", DataType::Any)
+                .with_description("Entities customers can buy")
+                .multiple(),
+        )
+        // Configuration
+        .with_config(
+            ConfigOption::select("provider", "Provider")
+                .with_option("stripe", "Stripe")
+                .with_default("stripe")
+                .required()
+                .with_description("Payment provider"),
+        )
+        .with_config(
+            ConfigOption::select("mode", "Checkout Mode")
+                .with_option("payment", "One-time Payment")
+                .with_option("subscription", "Subscription")
+                .with_default("payment")
+                .with_description("Whether products are bought once or subscribed to"),
+        )
+        .with_config(
+            ConfigOption::string("price_field", "Price Field")
+                .with_default("stripe_price_id")
+                .with_description("Field of each product holding the provider's price id"),
+        )
+        .with_config(
+            ConfigOption::string("checkout_path", "Checkout Path")
+                .with_default("/payments/checkout")
+                .with_description("Path checkout sessions are started on")
+                .in_group("Endpoints"),
+        )
+        .with_config(
+            ConfigOption::string("webhook_path", "Webhook Path")
+                .with_default("/payments/webhook")
+                .with_description("Path the provider's webhooks are received on")
+                .in_group("Endpoints"),
+        )
+        .with_instance_limits(0, 1)
+        .with_default_size(180.0, 80.0)
+        .with_generator("service::payments")
+}
+
+/// Create the I18n component definition
+///
+/// Lists the locales the service speaks; each gets a message bundle, and
+/// validation errors are reported in the locale of the request.
+pub fn i18n_component() -> ComponentDefinition {
+    ComponentDefinition::new("service.i18n", "I18n", ComponentCategory::Service)
+        .with_description("Locales, message bundles, and localized validation errors")
+        .with_documentation(include_str!("docs/service.i18n.md"))
+        .with_icon("🌐")
+        .with_tag("i18n")
+        .with_tag("localization")
+        .with_tag("fluent")
+        // Configuration
+        .with_config(
+            ConfigOption::string("default_locale", "Default Locale")
+                .with_default("en")
+                .required()
+                .with_description("Locale of requests asking for none that is supported"),
+        )
+        .with_config(
+            ConfigOption::string("locales", "Locales")
+                .with_default("en")
+                .with_description("Comma-separated locale tags, e.g. \"en, es, pt-BR\""),
+        )
+        .with_config(
+            ConfigOption::string("query_param", "Query Parameter")
+                .with_default("lang")
+                .with_description("Query parameter requests pick their locale with")
+                .advanced(),
+        )
+        .with_instance_limits(0, 1)
+        .with_default_size(160.0, 60.0)
+        .with_generator("service::i18n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_observability_component() {
+        let component = observability_component();
+
+        assert_eq!(component.id, "service.observability");
+        assert_eq!(component.category, ComponentCategory::Service);
+        assert!(component.get_config("tracing").is_some());
+        assert!(component.get_config("metrics_path").is_some());
+    }
+
+    #[test]
+    fn test_search_component() {
+        let component = search_component();
+
+        assert_eq!(component.id, "service.search");
+        assert!(component.get_config("engine").is_some());
+        assert!(component.get_input("entities").is_some_and(|p| p.multiple));
+    }
+
+    #[test]
+    fn test_i18n_component() {
+        let component = i18n_component();
+
+        assert_eq!(component.id, "service.i18n");
+        assert!(component.get_config("locales").is_some());
+        assert!(component.ports.inputs.is_empty());
+    }
+
+    #[test]
+    fn test_payments_component() {
+        let component = payments_component();
+
+        assert_eq!(component.id, "service.payments");
+        assert!(component.get_config("price_field").is_some());
+        assert!(component.get_input("products").is_some_and(|p| p.multiple));
+    }
+}
@@ -6,9 +6,9 @@
 //! - FileStorage: File/blob storage
 
 use crate::definition::{
-    ComponentDefinition, ConfigOption, ConfigType, FieldDefinition, PortDefinition,
+    ComponentDefinition, ComponentExample, ConfigOption, PortDefinition,
 };
-use imortal_core::{ComponentCategory, DataType, Validation};
+use imortal_core::{ComponentCategory, DataType};
 
 /// Create the Database component definition
 ///
@@ -17,7 +17,11 @@ use imortal_core::{ComponentCategory, DataType, Validation};
 pub fn database_component() -> ComponentDefinition {
     ComponentDefinition::new("storage.database", "Database", ComponentCategory::Storage)
         .with_description("Database connection and configuration for data persistence")
+        .with_documentation(include_str!("docs/storage.database.md"))
         .with_icon("💾")
+        .with_synonym("DB")
+        .with_synonym("PostgreSQL")
+        .with_synonym("SQLite")
         .with_tag("persistence")
         .with_tag("sql")
         .with_tag("data")
@@ -174,6 +178,15 @@ pub fn database_component() -> ComponentDefinition {
 pub fn cache_component() -> ComponentDefinition {
     ComponentDefinition::new("storage.cache", "Cache", ComponentCategory::Storage)
         .with_description("In-memory or distributed caching for performance optimization")
+        .with_documentation(include_str!("docs/storage.cache.md"))
+        .with_example(
+            ComponentExample::new("Caching query results")
+                .with_description("Each successful query stores its first row in the cache")
+                .with_node("data.query", "Find Product")
+                .with_node("storage.cache", "Product Cache")
+                .with_connection(0, "first", 1, "value")
+                .with_connection(0, "on_success", 1, "set"),
+        )
         .with_icon("⚡")
         .with_tag("performance")
         .with_tag("memory")
@@ -310,6 +323,7 @@ pub fn cache_component() -> ComponentDefinition {
 pub fn file_storage_component() -> ComponentDefinition {
     ComponentDefinition::new("storage.files", "File Storage", ComponentCategory::Storage)
         .with_description("File and blob storage for documents, images, and binary data")
+        .with_documentation(include_str!("docs/storage.files.md"))
         .with_icon("📁")
         .with_tag("files")
         .with_tag("blob")
@@ -475,6 +489,8 @@ pub fn kv_store_component() -> ComponentDefinition {
     ComponentDefinition::new("storage.kv", "Key-Value Store", ComponentCategory::Storage)
         .with_description("Simple key-value storage for application state and configuration")
         .with_icon("🗄")
+        .with_synonym("KV")
+        .with_synonym("Key Value")
         .with_tag("state")
         .with_tag("config")
         .with_tag("settings")
@@ -13,6 +13,8 @@
 //! - **UI**: Form, Table, Page (future)
 //! - **Logic**: Validator, Transformer, Condition
 //! - **Embedded**: GPIO, Sensor, I2C (future)
+//! - **Config**: Settings
+//! - **Service**: Observability
 //!
 //! # Architecture
 //!
@@ -35,8 +37,12 @@
 //! ```
 
 pub mod definition;
+pub mod loader;
 pub mod registry;
+pub mod search;
 pub mod traits;
+pub mod usage;
+pub mod validation;
 
 pub mod definitions {
     //! Built-in component definitions
@@ -45,18 +51,26 @@ pub mod definitions {
     pub mod api;
     pub mod storage;
     pub mod logic;
+    pub mod config;
+    pub mod service;
 }
 
 // Re-export main types
 pub use definition::{
     ComponentDefinition,
+    ComponentDocs,
+    ComponentExample,
+    ExampleConnection,
     FieldDefinition,
     PortDefinition,
     ConfigOption,
     ConfigType,
 };
+pub use loader::{DefinitionError, DefinitionLoad, DefinitionWatcher, DEFINITION_SCHEMA};
 pub use registry::ComponentRegistry;
+pub use search::{fuzzy_score, MatchKind, SearchFilter};
 pub use traits::{Component, ComponentFactory};
+pub use usage::{ComponentUsage, Deviation, UsageReport};
 
 // Re-export core types commonly used with components
 pub use imortal_core::{
@@ -0,0 +1,423 @@
+//! External component definition files
+//!
+//! Components can be defined outside the engine in `.json` or `.toml` files
+//! that follow [`DEFINITION_SCHEMA`], and loaded with
+//! [`ComponentRegistry::load_dir`](crate::ComponentRegistry::load_dir). Each
+//! file is checked against the schema before it is deserialized, so a
+//! mistake is reported with the file and the place in it (a line and column
+//! for syntax errors, a path like `ports.inputs[0].kind` otherwise) instead
+//! of a bare serde message. [`DefinitionWatcher`] notices when files in the
+//! directory change, so an editor can reload its palette.
+//!
+//! A minimal definition:
+//!
+//! ```toml
+//! id = "billing.invoice"
+//! name = "Invoice"
+//! category = "data"
+//!
+//! [[fields]]
+//! name = "total"
+//! data_type = { kind = "Float64" }
+//!
+//! [[ports.outputs]]
+//! id = "created"
+//! name = "Created"
+//! data_type = { kind = "Trigger" }
+//! kind = "trigger"
+//! ```
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::SystemTime;
+
+use imortal_core::{IconSource, PortDirection};
+use serde_json::Value;
+
+use crate::definition::ComponentDefinition;
+
+/// JSON Schema (draft 2020-12) of a component definition file
+pub const DEFINITION_SCHEMA: &str = include_str!("../schema/component-definition.schema.json");
+
+/// File extensions read as definitions
+pub const DEFINITION_EXTENSIONS: [&str; 2] = ["json", "toml"];
+
+/// [`DEFINITION_SCHEMA`], parsed
+pub fn definition_schema() -> &'static Value {
+    static SCHEMA: OnceLock<Value> = OnceLock::new();
+    SCHEMA.get_or_init(|| serde_json::from_str(DEFINITION_SCHEMA).expect("definition schema is valid JSON"))
+}
+
+/// A problem with a definition file
+#[derive(Debug, Clone, PartialEq)]
+pub struct DefinitionError {
+    pub file: PathBuf,
+    /// Where in the file: `line 3, column 7` or a path like `fields[1].name`
+    pub location: Option<String>,
+    pub message: String,
+}
+
+impl fmt::Display for DefinitionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.location {
+            Some(location) => write!(f, "{}: {}: {}", self.file.display(), location, self.message),
+            None => write!(f, "{}: {}", self.file.display(), self.message),
+        }
+    }
+}
+
+impl std::error::Error for DefinitionError {}
+
+/// Result of loading a directory of definitions
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DefinitionLoad {
+    /// IDs of the components registered, in file name order
+    pub loaded: Vec<String>,
+    /// Files that were skipped, and why
+    pub errors: Vec<DefinitionError>,
+}
+
+/// Definition files in `dir`, sorted by name
+pub fn definition_files(dir: &Path) -> std::io::Result<Vec<PathBuf>> {
+    let mut files: Vec<PathBuf> = std::fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.is_file() && is_definition_file(path))
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+fn is_definition_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| DEFINITION_EXTENSIONS.contains(&ext.to_ascii_lowercase().as_str()))
+}
+
+/// Read one definition file, reporting every schema violation in it
+///
+/// A relative image `icon` is taken to be next to the file.
+pub fn load_definition_file(path: &Path) -> Result<ComponentDefinition, Vec<DefinitionError>> {
+    let error = |location: Option<String>, message: String| DefinitionError { file: path.to_path_buf(), location, message };
+    let text = std::fs::read_to_string(path).map_err(|e| vec![error(None, e.to_string())])?;
+    let is_toml = path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("toml"));
+    let mut definition = parse_definition(&text, is_toml)
+        .map_err(|problems| problems.into_iter().map(|(location, message)| error(location, message)).collect::<Vec<_>>())?;
+
+    if let (IconSource::File(icon), Some(dir)) = (IconSource::parse(&definition.icon), path.parent()) {
+        if Path::new(icon).is_relative() {
+            definition.icon = dir.join(icon).to_string_lossy().into_owned();
+        }
+    }
+    Ok(definition)
+}
+
+/// Parse and check a definition from JSON or TOML text
+///
+/// Errors are `(location, message)` pairs.
+pub fn parse_definition(text: &str, is_toml: bool) -> Result<ComponentDefinition, Vec<(Option<String>, String)>> {
+    let value: Value = if is_toml {
+        let table: toml::Value = toml::from_str(text).map_err(|e| {
+            let location = e.span().map(|span| line_column(text, span.start));
+            vec![(location, e.message().to_string())]
+        })?;
+        serde_json::to_value(table).map_err(|e| vec![(None, e.to_string())])?
+    } else {
+        serde_json::from_str(text)
+            .map_err(|e| vec![(Some(format!("line {}, column {}", e.line(), e.column())), e.to_string())])?
+    };
+
+    let problems = validate_against_schema(&value);
+    if !problems.is_empty() {
+        return Err(problems.into_iter().map(|(path, message)| (Some(path), message)).collect());
+    }
+
+    let mut definition: ComponentDefinition = serde_json::from_value(value).map_err(|e| vec![(None, e.to_string())])?;
+    for port in &mut definition.ports.inputs {
+        port.direction = PortDirection::Input;
+    }
+    for port in &mut definition.ports.outputs {
+        port.direction = PortDirection::Output;
+    }
+    Ok(definition)
+}
+
+/// `line L, column C` of a byte offset, both counted from 1
+fn line_column(text: &str, offset: usize) -> String {
+    let before = &text[..offset.min(text.len())];
+    let line = before.matches('\n').count() + 1;
+    let column = before.rsplit('\n').next().map_or(0, |rest| rest.chars().count()) + 1;
+    format!("line {}, column {}", line, column)
+}
+
+/// Check a value against [`DEFINITION_SCHEMA`], returning `(path, message)`
+/// for each violation
+pub fn validate_against_schema(value: &Value) -> Vec<(String, String)> {
+    let root = definition_schema();
+    let mut problems = Vec::new();
+    check(root, root, value, "", &mut problems);
+    problems
+}
+
+/// The subset of JSON Schema used by the definition schema: `type`, `enum`,
+/// `required`, `properties`, `additionalProperties: false`, `items`,
+/// `minItems`/`maxItems`, `minProperties`/`maxProperties`, `minLength`,
+/// `minimum`, `anyOf`, and local `$ref`s
+fn check(root: &Value, schema: &Value, value: &Value, path: &str, problems: &mut Vec<(String, String)>) {
+    let at = || if path.is_empty() { "(top level)".to_string() } else { path.to_string() };
+
+    if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+        let target = reference
+            .strip_prefix("#/")
+            .map(|pointer| format!("/{}", pointer))
+            .and_then(|pointer| root.pointer(&pointer));
+        if let Some(target) = target {
+            check(root, target, value, path, problems);
+        }
+        return;
+    }
+
+    if let Some(options) = schema.get("anyOf").and_then(Value::as_array) {
+        let matched = options.iter().any(|option| {
+            let mut scratch = Vec::new();
+            check(root, option, value, path, &mut scratch);
+            scratch.is_empty()
+        });
+        if !matched {
+            problems.push((at(), format!("{} is not a valid value here", describe(value))));
+        }
+        return;
+    }
+
+    if let Some(allowed) = schema.get("type") {
+        let types: Vec<&str> = match allowed {
+            Value::String(name) => vec![name.as_str()],
+            Value::Array(names) => names.iter().filter_map(Value::as_str).collect(),
+            _ => Vec::new(),
+        };
+        if !types.iter().any(|name| has_type(value, name)) {
+            problems.push((at(), format!("expected {}, found {}", types.join(" or "), type_name(value))));
+            return;
+        }
+    }
+
+    if let Some(allowed) = schema.get("enum").and_then(Value::as_array) {
+        if !allowed.contains(value) {
+            let names: Vec<String> = allowed.iter().map(|v| v.to_string()).collect();
+            problems.push((at(), format!("{} is not one of {}", describe(value), names.join(", "))));
+            return;
+        }
+    }
+
+    match value {
+        Value::Object(object) => {
+            for name in schema.get("required").and_then(Value::as_array).into_iter().flatten().filter_map(Value::as_str) {
+                if !object.contains_key(name) {
+                    problems.push((at(), format!("missing required property \"{}\"", name)));
+                }
+            }
+            let count = object.len() as u64;
+            if schema.get("minProperties").and_then(Value::as_u64).is_some_and(|min| count < min)
+                || schema.get("maxProperties").and_then(Value::as_u64).is_some_and(|max| count > max)
+            {
+                problems.push((at(), format!("has {} properties", count)));
+            }
+            let properties = schema.get("properties").and_then(Value::as_object);
+            let closed = schema.get("additionalProperties") == Some(&Value::Bool(false));
+            for (name, item) in object {
+                let item_path = if path.is_empty() { name.clone() } else { format!("{}.{}", path, name) };
+                match properties.and_then(|properties| properties.get(name)) {
+                    Some(item_schema) => check(root, item_schema, item, &item_path, problems),
+                    None if closed => {
+                        let known: Vec<&str> = properties.into_iter().flat_map(|p| p.keys()).map(String::as_str).collect();
+                        problems.push((item_path, format!("unknown property; expected one of {}", known.join(", "))));
+                    }
+                    None => {}
+                }
+            }
+        }
+        Value::Array(items) => {
+            let count = items.len() as u64;
+            if let Some(min) = schema.get("minItems").and_then(Value::as_u64).filter(|min| count < *min) {
+                problems.push((at(), format!("expected at least {} items, found {}", min, count)));
+            }
+            if let Some(max) = schema.get("maxItems").and_then(Value::as_u64).filter(|max| count > *max) {
+                problems.push((at(), format!("expected at most {} items, found {}", max, count)));
+            }
+            if let Some(item_schema) = schema.get("items") {
+                for (index, item) in items.iter().enumerate() {
+                    check(root, item_schema, item, &format!("{}[{}]", path, index), problems);
+                }
+            }
+        }
+        Value::String(text) if schema.get("minLength").and_then(Value::as_u64).is_some_and(|min| (text.chars().count() as u64) < min) => {
+            problems.push((at(), "must not be empty".to_string()));
+        }
+        Value::Number(number) => {
+            if let (Some(min), Some(number)) = (schema.get("minimum").and_then(Value::as_f64), number.as_f64()) {
+                if number < min {
+                    problems.push((at(), format!("{} is less than the minimum {}", number, min)));
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+fn has_type(value: &Value, name: &str) -> bool {
+    match name {
+        "null" => value.is_null(),
+        "boolean" => value.is_boolean(),
+        "integer" => value.is_i64() || value.is_u64(),
+        "number" => value.is_number(),
+        "string" => value.is_string(),
+        "array" => value.is_array(),
+        "object" => value.is_object(),
+        _ => false,
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(n) if n.is_f64() => "number",
+        Value::Number(_) => "integer",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+/// A value for an error message: scalars as written, containers by type
+fn describe(value: &Value) -> String {
+    match value {
+        Value::Array(_) | Value::Object(_) => format!("an {}", type_name(value)),
+        _ => value.to_string(),
+    }
+}
+
+/// Notices when definition files in a directory are added, changed, or removed
+#[derive(Debug, Clone)]
+pub struct DefinitionWatcher {
+    dir: PathBuf,
+    /// Modification time and size of each file at the last scan
+    stamps: BTreeMap<PathBuf, (Option<SystemTime>, u64)>,
+}
+
+impl DefinitionWatcher {
+    /// Watch `dir`, which need not exist yet
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        let dir = dir.into();
+        let stamps = scan(&dir);
+        Self { dir, stamps }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Rescan the directory; true if anything changed since the last scan
+    pub fn poll(&mut self) -> bool {
+        let stamps = scan(&self.dir);
+        let changed = stamps != self.stamps;
+        self.stamps = stamps;
+        changed
+    }
+}
+
+fn scan(dir: &Path) -> BTreeMap<PathBuf, (Option<SystemTime>, u64)> {
+    definition_files(dir)
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|path| {
+            let metadata = std::fs::metadata(&path).ok()?;
+            Some((path, (metadata.modified().ok(), metadata.len())))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use imortal_core::{ComponentCategory, DataType, PortKind};
+
+    const INVOICE: &str = r#"
+id = "billing.invoice"
+name = "Invoice"
+category = "data"
+
+[[fields]]
+name = "total"
+data_type = { kind = "Float64" }
+validations = ["required", { min = 0.0 }]
+
+[[ports.outputs]]
+id = "created"
+name = "Created"
+data_type = { kind = "Trigger" }
+kind = "trigger"
+"#;
+
+    #[test]
+    fn test_schema_accepts_builtins() {
+        let registry = crate::ComponentRegistry::with_builtins();
+        for definition in registry.all() {
+            let value = serde_json::to_value(definition).unwrap();
+            assert_eq!(validate_against_schema(&value), Vec::<(String, String)>::new(), "{}", definition.id);
+        }
+    }
+
+    #[test]
+    fn test_parse_toml_definition() {
+        let definition = parse_definition(INVOICE, true).unwrap();
+        assert_eq!(definition.id, "billing.invoice");
+        assert_eq!(definition.category, ComponentCategory::Data);
+        assert_eq!(definition.fields[0].data_type, DataType::Float64);
+        assert_eq!(definition.fields[0].validations.len(), 2);
+        let port = &definition.ports.outputs[0];
+        assert_eq!((port.direction, port.kind), (PortDirection::Output, PortKind::Trigger));
+        // Unset fields take the usual defaults
+        assert_eq!(definition.default_width, 200.0);
+    }
+
+    #[test]
+    fn test_errors_point_at_the_problem() {
+        let json = r#"{
+            "id": "billing.invoice",
+            "category": "money",
+            "ports": { "inputs": [{ "id": "in", "name": "In", "data_type": { "kind": "Float64" }, "kind": "signal" }] },
+            "colour": "red"
+        }"#;
+        let problems = parse_definition(json, false).unwrap_err();
+        let locations: Vec<&str> = problems.iter().filter_map(|(location, _)| location.as_deref()).collect();
+        assert_eq!(locations, ["(top level)", "category", "colour", "ports.inputs[0].kind"]);
+        assert!(problems[0].1.contains("\"name\""));
+
+        let syntax = parse_definition("id = \"a\"\nname = \n", true).unwrap_err();
+        assert_eq!(syntax[0].0.as_deref(), Some("line 2, column 8"));
+        let syntax = parse_definition("{\n  \"id\": }", false).unwrap_err();
+        assert_eq!(syntax[0].0.as_deref(), Some("line 2, column 9"));
+    }
+
+    #[test]
+    fn test_watcher_sees_changes() {
+        let dir = std::env::temp_dir().join(format!("imortal-defs-{}", uuid::Uuid::new_v4()));
+        let mut watcher = DefinitionWatcher::new(&dir);
+        assert!(!watcher.poll());
+
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("notes.txt"), "ignored").unwrap();
+        assert!(!watcher.poll());
+        std::fs::write(dir.join("invoice.toml"), INVOICE).unwrap();
+        assert!(watcher.poll());
+        assert!(!watcher.poll());
+        std::fs::write(dir.join("invoice.toml"), format!("{}\n# edited", INVOICE)).unwrap();
+        assert!(watcher.poll());
+        std::fs::remove_file(dir.join("invoice.toml")).unwrap();
+        assert!(watcher.poll());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -4,13 +4,16 @@
 //! It allows registering, querying, and instantiating components.
 
 use std::collections::HashMap;
+use std::path::Path;
 use std::sync::Arc;
 
-use imortal_core::{ComponentCategory, EngineError, EngineResult};
-use imortal_ir::Node;
+use imortal_core::{ComponentCategory, EngineError, EngineResult, PortKind};
+use imortal_ir::{Edge, Node, Port, ProjectGraph, ProjectMeta};
 
-use crate::definition::ComponentDefinition;
-use crate::definitions::{auth, data, api, storage, logic};
+use crate::definition::{ComponentDefinition, ComponentExample, PortDefinition};
+use crate::loader::{self, DefinitionError, DefinitionLoad};
+use crate::search::{self, SearchFilter};
+use crate::definitions::{auth, data, api, storage, logic, config, service};
 use crate::traits::ComponentFactory;
 
 /// Registry of all available component definitions
@@ -59,6 +62,8 @@ impl ComponentRegistry {
         self.register(data::entity_component());
         self.register(data::collection_component());
         self.register(data::query_component());
+        self.register(data::reference_component());
+        self.register(data::audit_log_component());
 
         // API components
         self.register(api::rest_endpoint_component());
@@ -74,6 +79,17 @@ impl ComponentRegistry {
         self.register(logic::validator_component());
         self.register(logic::transformer_component());
         self.register(logic::condition_component());
+        self.register(logic::feature_flag_component());
+        self.register(logic::custom_code_component());
+
+        // Config components
+        self.register(config::settings_component());
+
+        // Service components
+        self.register(service::observability_component());
+        self.register(service::search_component());
+        self.register(service::payments_component());
+        self.register(service::i18n_component());
 
         self.initialized = true;
     }
@@ -89,7 +105,7 @@ impl ComponentRegistry {
         // Add to category index
         self.by_category
             .entry(category)
-            .or_insert_with(Vec::new)
+            .or_default()
             .push(id);
     }
 
@@ -153,18 +169,36 @@ impl ComponentRegistry {
             .unwrap_or_default()
     }
 
-    /// Get all components matching a search query (searches id, name, description)
+    /// Components matching a search query, most relevant first
+    ///
+    /// Names rank above synonyms, tags and descriptions; see [`search`].
     pub fn search(&self, query: &str) -> Vec<&ComponentDefinition> {
-        let query_lower = query.to_lowercase();
-        self.components
+        self.search_with(query, &SearchFilter::default())
+    }
+
+    /// [`search`](Self::search) narrowed to some categories and tags
+    pub fn search_with(&self, query: &str, filter: &SearchFilter) -> Vec<&ComponentDefinition> {
+        search::rank(self.components.values().map(|arc| arc.as_ref()), query, filter)
+    }
+
+    /// Every tag in use, lowercased and sorted
+    pub fn tags(&self) -> Vec<String> {
+        let mut tags: Vec<String> = self.components.values().flat_map(|def| def.tags.iter().map(|t| t.to_lowercase())).collect();
+        tags.sort();
+        tags.dedup();
+        tags
+    }
+
+    /// Components with a port that `port` can connect to, sorted by name
+    ///
+    /// Each component is paired with the first of its ports that matches.
+    pub fn compatible_with(&self, port: &Port) -> Vec<(&ComponentDefinition, &PortDefinition)> {
+        let mut matches: Vec<_> = self.components
             .values()
-            .filter(|def| {
-                def.id.to_lowercase().contains(&query_lower)
-                    || def.name.to_lowercase().contains(&query_lower)
-                    || def.description.to_lowercase().contains(&query_lower)
-            })
-            .map(|arc| arc.as_ref())
-            .collect()
+            .filter_map(|def| def.compatible_port(port).map(|p| (def.as_ref(), p)))
+            .collect();
+        matches.sort_by(|(a, _), (b, _)| a.name.cmp(&b.name));
+        matches
     }
 
     /// Get all categories that have at least one component
@@ -200,6 +234,65 @@ impl ComponentRegistry {
         Ok(node)
     }
 
+    /// Build a documentation example into a project of its own, with the
+    /// nodes laid out left to right
+    pub fn build_example(&self, example: &ComponentExample) -> EngineResult<ProjectGraph> {
+        let mut graph = ProjectGraph::new(ProjectMeta::new(&example.title));
+        let mut ids = Vec::new();
+        let mut x = 0.0;
+        for (component_id, name) in &example.nodes {
+            let mut node = self.instantiate_at(component_id, x, 0.0)?;
+            node.name = name.clone();
+            x += node.size.width + 80.0;
+            ids.push(graph.add_node(node));
+        }
+
+        for connection in &example.connections {
+            let node = |index: usize| {
+                ids.get(index).copied().ok_or_else(|| {
+                    EngineError::InvalidConnection(format!("example \"{}\" has no node {}", example.title, index))
+                })
+            };
+            let (from, to) = (node(connection.from)?, node(connection.to)?);
+            let trigger = graph
+                .get_node(from)
+                .and_then(|n| n.get_output_port(&connection.from_port))
+                .is_some_and(|port| port.kind == PortKind::Trigger);
+            let edge = if trigger {
+                Edge::trigger(from, &connection.from_port, to, &connection.to_port)
+            } else {
+                Edge::data_flow(from, &connection.from_port, to, &connection.to_port)
+            };
+            graph.add_edge(edge)?;
+        }
+        Ok(graph)
+    }
+
+    /// Register the components defined in the `.json` and `.toml` files
+    /// of `dir`
+    ///
+    /// Files that fail the [definition schema](crate::DEFINITION_SCHEMA) or
+    /// reuse an ID that is already registered are skipped and reported in
+    /// the result; only an unreadable directory is an error.
+    pub fn load_dir(&mut self, dir: impl AsRef<Path>) -> EngineResult<DefinitionLoad> {
+        let mut load = DefinitionLoad::default();
+        for file in loader::definition_files(dir.as_ref())? {
+            match loader::load_definition_file(&file) {
+                Ok(definition) if self.contains(&definition.id) => load.errors.push(DefinitionError {
+                    location: Some("id".to_string()),
+                    message: EngineError::ComponentAlreadyExists(definition.id).to_string(),
+                    file,
+                }),
+                Ok(definition) => {
+                    load.loaded.push(definition.id.clone());
+                    self.register(definition);
+                }
+                Err(errors) => load.errors.extend(errors),
+            }
+        }
+        Ok(load)
+    }
+
     /// Get component statistics
     pub fn stats(&self) -> RegistryStats {
         let mut stats = RegistryStats {
@@ -353,6 +446,53 @@ mod tests {
         assert_eq!(results.len(), 2);
     }
 
+    #[test]
+    fn test_builtin_search() {
+        let registry = ComponentRegistry::with_builtins();
+        assert_eq!(registry.search("model")[0].id, "data.entity");
+        assert_eq!(registry.search("sign up")[0].id, "auth.register");
+        assert_eq!(registry.search("Database")[0].id, "storage.database");
+        assert!(registry.tags().contains(&"sql".to_string()));
+
+        let mut filter = SearchFilter::default();
+        filter.toggle_tag("authentication");
+        let ids: Vec<_> = registry.search_with("session", &filter).iter().map(|c| c.id.as_str()).collect();
+        assert_eq!(ids[0], "auth.session");
+        assert!(ids.iter().all(|id| id.starts_with("auth.")));
+    }
+
+    #[test]
+    fn test_compatible_with() {
+        use crate::definition::PortDefinition;
+        use imortal_core::DataType;
+
+        let mut registry = ComponentRegistry::new();
+        registry.register(
+            ComponentDefinition::new("logic.count", "Counter", ComponentCategory::Logic)
+                .with_input(PortDefinition::data_in("value", "Value", DataType::Int64)),
+        );
+        registry.register(
+            ComponentDefinition::new("logic.format", "Formatter", ComponentCategory::Logic)
+                .with_input(PortDefinition::trigger_in("run", "Run"))
+                .with_input(PortDefinition::data_in("text", "Text", DataType::String)),
+        );
+        registry.register(
+            ComponentDefinition::new("logic.any", "Anything", ComponentCategory::Logic)
+                .with_input(PortDefinition::data_in("input", "Input", DataType::Any)),
+        );
+
+        let output = PortDefinition::data_out("name", "Name", DataType::String).to_port();
+        let matches: Vec<_> = registry.compatible_with(&output)
+            .into_iter()
+            .map(|(def, port)| (def.id.as_str(), port.id.as_str()))
+            .collect();
+        assert_eq!(matches, [("logic.any", "input"), ("logic.format", "text")]);
+
+        // Inputs look for outputs, which none of these have
+        let input = PortDefinition::data_in("name", "Name", DataType::String).to_port();
+        assert!(registry.compatible_with(&input).is_empty());
+    }
+
     #[test]
     fn test_instantiate() {
         let registry = ComponentRegistry::with_builtins();
@@ -383,6 +523,55 @@ mod tests {
         assert!(stats.category_count(ComponentCategory::Auth) > 0);
     }
 
+    #[test]
+    fn test_builtin_docs_and_examples() {
+        let registry = ComponentRegistry::with_builtins();
+        for component in registry.all() {
+            assert!(component.docs.body.starts_with("# "), "{} has no docs", component.id);
+            for example in &component.docs.examples {
+                let graph = registry.build_example(example).unwrap();
+                assert_eq!(graph.node_count(), example.nodes.len());
+                assert_eq!(graph.edge_count(), example.connections.len());
+                assert!(example.nodes.iter().any(|(id, _)| *id == component.id), "{}", example.title);
+            }
+        }
+
+        let login = registry.get("auth.login").unwrap();
+        let graph = registry.build_example(&login.docs.examples[0]).unwrap();
+        // Trigger ports get trigger edges
+        assert!(graph.edges().any(|e| e.from_port == "success" && e.connection_type == imortal_ir::ConnectionType::Trigger));
+
+        let broken = ComponentExample::new("Broken").with_node("auth.login", "Login").with_connection(0, "token", 1, "token");
+        assert!(registry.build_example(&broken).is_err());
+    }
+
+    #[test]
+    fn test_load_dir() {
+        let dir = std::env::temp_dir().join(format!("imortal-components-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("invoice.json"), r#"{ "id": "billing.invoice", "name": "Invoice", "category": "data", "icon": "icons/invoice.svg" }"#).unwrap();
+        std::fs::write(dir.join("login.toml"), "id = \"auth.login\"\nname = \"Login\"\ncategory = \"auth\"\n").unwrap();
+        std::fs::write(dir.join("broken.toml"), "id = \"billing.broken\"\ncategory = \"data\"\n").unwrap();
+        std::fs::write(dir.join("README.md"), "not a definition").unwrap();
+
+        let mut registry = ComponentRegistry::with_builtins();
+        let builtins = registry.len();
+        let load = registry.load_dir(&dir).unwrap();
+        assert_eq!(load.loaded, ["billing.invoice"]);
+        assert_eq!(registry.len(), builtins + 1);
+        // Image icons are found next to the definition file
+        assert_eq!(registry.get("billing.invoice").unwrap().icon, dir.join("icons/invoice.svg").to_string_lossy());
+        assert_eq!(registry.by_category(ComponentCategory::Data).iter().filter(|d| d.id == "billing.invoice").count(), 1);
+
+        // Sorted by file name: broken.toml, then login.toml clashing with a built-in
+        let errors: Vec<String> = load.errors.iter().map(|e| format!("{}: {}", e.location.as_deref().unwrap_or(""), e.message)).collect();
+        assert_eq!(errors, ["(top level): missing required property \"name\"", "id: Component already registered: auth.login"]);
+        assert!(load.errors[0].to_string().contains("broken.toml: (top level)"));
+
+        assert!(registry.load_dir(dir.join("missing")).is_err());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
     #[test]
     fn test_global_registry() {
         let registry = global::registry();
@@ -0,0 +1,163 @@
+//! Component search
+//!
+//! Ranks component definitions against a query for the palette, quick-add
+//! and the CLI. A match on the name beats one on a synonym, which beats a
+//! tag, which beats the description; names, synonyms and tags are matched
+//! as fuzzy subsequences, so "usrent" finds "User Entity".
+
+use std::collections::HashSet;
+
+use imortal_core::ComponentCategory;
+
+use crate::definition::ComponentDefinition;
+
+/// Score how well `query` matches `text` as a case-insensitive subsequence
+///
+/// Returns `None` when some query character is missing. Consecutive matches
+/// and matches at the start of a word score higher.
+pub fn fuzzy_score(query: &str, text: &str) -> Option<u32> {
+    let text: Vec<char> = text.to_lowercase().chars().collect();
+    let mut score = 0;
+    let mut next = 0;
+    let mut previous: Option<usize> = None;
+
+    for q in query.to_lowercase().chars().filter(|c| !c.is_whitespace()) {
+        let found = next + text[next..].iter().position(|&c| c == q)?;
+        score += 1;
+        if previous.is_some_and(|p| p + 1 == found) {
+            score += 4;
+        }
+        if found == 0 || !text[found - 1].is_alphanumeric() {
+            score += 6;
+        }
+        previous = Some(found);
+        next = found + 1;
+    }
+    Some(score)
+}
+
+/// What a search match was found in, best first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum MatchKind {
+    Name,
+    Synonym,
+    Tag,
+    Description,
+}
+
+/// Narrows a search to some categories and tags
+///
+/// Empty sets don't filter; a component must be in one of the categories
+/// and carry every tag.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SearchFilter {
+    pub categories: HashSet<ComponentCategory>,
+    pub tags: HashSet<String>,
+}
+
+impl SearchFilter {
+    /// Whether no category or tag is selected
+    pub fn is_empty(&self) -> bool {
+        self.categories.is_empty() && self.tags.is_empty()
+    }
+
+    /// Whether `component` passes the filter
+    pub fn accepts(&self, component: &ComponentDefinition) -> bool {
+        (self.categories.is_empty() || self.categories.contains(&component.category))
+            && self.tags.iter().all(|tag| component.tags.iter().any(|t| t.eq_ignore_ascii_case(tag)))
+    }
+
+    /// Select `category` if it isn't, or deselect it
+    pub fn toggle_category(&mut self, category: ComponentCategory) {
+        if !self.categories.remove(&category) {
+            self.categories.insert(category);
+        }
+    }
+
+    /// Select `tag` if it isn't, or deselect it
+    pub fn toggle_tag(&mut self, tag: &str) {
+        if !self.tags.remove(tag) {
+            self.tags.insert(tag.to_string());
+        }
+    }
+}
+
+/// Relevance of `component` to `query`, or `None` if it doesn't match
+///
+/// Scores from a better [`MatchKind`] always beat those from a worse one.
+pub fn relevance(component: &ComponentDefinition, query: &str) -> Option<(MatchKind, u32)> {
+    let query = query.trim();
+    if query.is_empty() {
+        return Some((MatchKind::Name, 0));
+    }
+    let lower = query.to_lowercase();
+    let best = |texts: &mut dyn Iterator<Item = &String>| texts.filter_map(|text| fuzzy_score(query, text)).max();
+
+    let name = fuzzy_score(query, &component.name).or(component.id.to_lowercase().contains(&lower).then_some(0));
+    if let Some(score) = name {
+        return Some((MatchKind::Name, score));
+    }
+    if let Some(score) = best(&mut component.synonyms.iter()) {
+        return Some((MatchKind::Synonym, score));
+    }
+    if let Some(score) = best(&mut component.tags.iter()) {
+        return Some((MatchKind::Tag, score));
+    }
+    component.description.to_lowercase().contains(&lower).then_some((MatchKind::Description, 0))
+}
+
+/// Components matching `query` and `filter`, most relevant first, then by
+/// name; an empty query lists every component passing the filter by name
+pub fn rank<'a>(
+    components: impl IntoIterator<Item = &'a ComponentDefinition>,
+    query: &str,
+    filter: &SearchFilter,
+) -> Vec<&'a ComponentDefinition> {
+    let mut scored: Vec<((MatchKind, u32), &ComponentDefinition)> = components
+        .into_iter()
+        .filter(|component| filter.accepts(component))
+        .filter_map(|component| relevance(component, query).map(|score| (score, component)))
+        .collect();
+    scored.sort_by(|((a_kind, a_score), a), ((b_kind, b_score), b)| {
+        a_kind.cmp(b_kind).then(b_score.cmp(a_score)).then_with(|| a.name.cmp(&b.name)).then_with(|| a.id.cmp(&b.id))
+    });
+    scored.into_iter().map(|(_, component)| component).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn component(id: &str, name: &str, category: ComponentCategory) -> ComponentDefinition {
+        ComponentDefinition::new(id, name, category)
+    }
+
+    #[test]
+    fn test_ranking() {
+        let entity = component("data.entity", "Entity", ComponentCategory::Data).with_synonym("Model").with_tag("table");
+        let model = component("logic.model", "Model Runner", ComponentCategory::Logic);
+        let query = component("data.query", "Query", ComponentCategory::Data)
+            .with_tag("sql")
+            .with_description("Reads a model from a table");
+        let components = [&entity, &model, &query];
+
+        let ids = |query: &str, filter: &SearchFilter| -> Vec<&str> {
+            rank(components, query, filter).iter().map(|c| c.id.as_str()).collect()
+        };
+        // Name, then synonym, then description
+        assert_eq!(ids("model", &SearchFilter::default()), ["logic.model", "data.entity", "data.query"]);
+        // Tag beats description
+        assert_eq!(ids("table", &SearchFilter::default()), ["data.entity", "data.query"]);
+        assert_eq!(ids("entty", &SearchFilter::default()), ["data.entity"]);
+        assert_eq!(ids("", &SearchFilter::default()), ["data.entity", "logic.model", "data.query"]);
+
+        let mut filter = SearchFilter::default();
+        filter.toggle_category(ComponentCategory::Data);
+        assert_eq!(ids("model", &filter), ["data.entity", "data.query"]);
+        filter.toggle_tag("SQL");
+        assert_eq!(ids("", &filter), ["data.query"]);
+        filter.toggle_tag("SQL");
+        filter.toggle_category(ComponentCategory::Data);
+        assert!(filter.is_empty());
+    }
+}
@@ -253,6 +253,8 @@ pub trait SchemaSerializable: Component {
     fn to_schema(&self, node: &Node) -> EngineResult<serde_json::Value>;
 
     /// Create a node from a schema representation
+    // Named to pair with `to_schema`; `&self` is the component doing the parsing
+    #[allow(clippy::wrong_self_convention)]
     fn from_schema(&self, schema: &serde_json::Value) -> EngineResult<Node>;
 }
 
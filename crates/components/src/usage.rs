@@ -0,0 +1,164 @@
+//! Component usage within a project
+//!
+//! [`UsageReport`] counts the instances of each registered component in a
+//! project, lists the definitions nothing uses and the component types
+//! nothing defines, and finds nodes whose config or default fields no
+//! longer match their definition.
+
+use std::collections::HashMap;
+
+use imortal_ir::{Node, NodeId, ProjectGraph};
+
+use crate::definition::ComponentDefinition;
+use crate::registry::ComponentRegistry;
+
+/// Instances of one component type
+#[derive(Debug, Clone, PartialEq)]
+pub struct ComponentUsage {
+    pub component_type: String,
+    /// Definition name, or the type itself when it isn't registered
+    pub name: String,
+    /// Instances, sorted by node name
+    pub instances: Vec<NodeId>,
+}
+
+/// How a node differs from its component's defaults
+#[derive(Debug, Clone, PartialEq)]
+pub struct Deviation {
+    pub node_id: NodeId,
+    /// Config options set to something other than their default
+    pub config: Vec<String>,
+    /// Default fields the node no longer has
+    pub missing_fields: Vec<String>,
+    /// Default fields with a different type or requiredness
+    pub changed_fields: Vec<String>,
+}
+
+impl Deviation {
+    /// Compare `node` with `definition`, `None` if it matches the defaults
+    pub fn of(node: &Node, definition: &ComponentDefinition) -> Option<Self> {
+        let config = definition
+            .config
+            .iter()
+            .filter(|option| node.config.get(&option.id) != option.default_value.as_ref())
+            .map(|option| option.id.clone())
+            .collect();
+        let mut missing_fields = Vec::new();
+        let mut changed_fields = Vec::new();
+        for default in &definition.fields {
+            match node.get_field(&default.name) {
+                None => missing_fields.push(default.name.clone()),
+                Some(field) if field.data_type != default.data_type || field.required != default.required => {
+                    changed_fields.push(default.name.clone())
+                }
+                Some(_) => {}
+            }
+        }
+        let deviation = Self { node_id: node.id, config, missing_fields, changed_fields };
+        (!deviation.is_empty()).then_some(deviation)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.config.is_empty() && self.missing_fields.is_empty() && self.changed_fields.is_empty()
+    }
+}
+
+/// Component usage of a project, from [`UsageReport::analyze`]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct UsageReport {
+    /// Registered components with instances, most used first
+    pub used: Vec<ComponentUsage>,
+    /// Registered components without instances, sorted by name
+    pub unused: Vec<String>,
+    /// Component types with instances but no definition, most used first
+    pub unknown: Vec<ComponentUsage>,
+    /// Nodes differing from their definition, sorted by node name
+    pub deviations: Vec<Deviation>,
+}
+
+impl UsageReport {
+    /// Tally `project` against the definitions in `registry`
+    pub fn analyze(registry: &ComponentRegistry, project: &ProjectGraph) -> Self {
+        let mut nodes: Vec<&Node> = project.nodes().collect();
+        nodes.sort_by(|a, b| a.name.cmp(&b.name).then(a.id.cmp(&b.id)));
+
+        let mut by_type: HashMap<&str, Vec<NodeId>> = HashMap::new();
+        for node in &nodes {
+            by_type.entry(node.component_type.as_str()).or_default().push(node.id);
+        }
+
+        let mut report = Self::default();
+        for (component_type, instances) in by_type {
+            let definition = registry.get(component_type);
+            let usage = ComponentUsage {
+                component_type: component_type.to_string(),
+                name: definition.map_or(component_type, |d| d.name.as_str()).to_string(),
+                instances,
+            };
+            if definition.is_some() {
+                report.used.push(usage);
+            } else {
+                report.unknown.push(usage);
+            }
+        }
+        let most_used = |a: &ComponentUsage, b: &ComponentUsage| b.instances.len().cmp(&a.instances.len()).then_with(|| a.name.cmp(&b.name));
+        report.used.sort_by(most_used);
+        report.unknown.sort_by(most_used);
+
+        let mut unused: Vec<&ComponentDefinition> =
+            registry.all().filter(|d| !report.used.iter().any(|u| u.component_type == d.id)).collect();
+        unused.sort_by(|a, b| a.name.cmp(&b.name));
+        report.unused = unused.into_iter().map(|d| d.id.clone()).collect();
+
+        report.deviations = nodes
+            .iter()
+            .filter_map(|node| registry.get(&node.component_type).and_then(|d| Deviation::of(node, d)))
+            .collect();
+        report
+    }
+
+    /// Number of instances of `component_type`
+    pub fn count(&self, component_type: &str) -> usize {
+        self.used
+            .iter()
+            .chain(&self.unknown)
+            .find(|u| u.component_type == component_type)
+            .map_or(0, |u| u.instances.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use imortal_core::{ConfigValue, DataType};
+
+    #[test]
+    fn test_usage_report() {
+        let registry = ComponentRegistry::with_builtins();
+        let mut project = ProjectGraph::with_name("shop");
+        let user = project.add_node(registry.instantiate_with_name("data.entity", "User").unwrap());
+        let mut post = registry.instantiate_with_name("data.entity", "Post").unwrap();
+        post.fields.retain(|f| f.name != "id");
+        let post = project.add_node(post);
+        let mut db = registry.instantiate("storage.database").unwrap();
+        db.config.insert("connection_string".to_string(), ConfigValue::String("postgres://localhost".to_string()));
+        let db = project.add_node(db);
+        let mut legacy = Node::new("legacy.widget", "Widget");
+        legacy.add_field(imortal_ir::Field::new("id", DataType::Uuid));
+        project.add_node(legacy);
+
+        let report = UsageReport::analyze(&registry, &project);
+        assert_eq!(report.used[0].component_type, "data.entity");
+        assert_eq!(report.used[0].instances, [post, user]);
+        assert_eq!(report.count("storage.database"), 1);
+        assert_eq!(report.unknown[0].name, "legacy.widget");
+        assert!(report.unused.contains(&"auth.login".to_string()));
+        assert!(!report.unused.contains(&"data.entity".to_string()));
+
+        assert_eq!(report.deviations.len(), 2);
+        assert_eq!(report.deviations[0].node_id, db);
+        assert_eq!(report.deviations[0].config, ["connection_string"]);
+        assert_eq!(report.deviations[1].node_id, post);
+        assert_eq!(report.deviations[1].missing_fields, ["id"]);
+    }
+}
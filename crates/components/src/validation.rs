@@ -0,0 +1,287 @@
+//! Component config validation
+//!
+//! `imortal_ir::validation` only sees the graph, so it can't tell whether a
+//! node's `config` matches what its component accepts. [`ConfigSchemaRule`]
+//! checks each node against the [`ConfigOption`]s of its
+//! [`ComponentDefinition`]: unknown keys, missing required options, values of
+//! the wrong type, and values outside the option's constraints. A missing
+//! option gets a [`QuickFix`] where its type has an obvious value.
+
+use imortal_core::ConfigValue;
+use imortal_ir::validation::{QuickFix, ValidationError, ValidationErrorKind, ValidationResult, ValidationRule, ValidationSeverity, Validator};
+use imortal_ir::{Node, ProjectGraph};
+
+use crate::definition::{ComponentDefinition, ConfigOption, ConfigType};
+use crate::registry::ComponentRegistry;
+
+/// Validates node config against the component definitions in a registry
+///
+/// Nodes whose component isn't registered are skipped.
+pub struct ConfigSchemaRule {
+    registry: ComponentRegistry,
+}
+
+impl ConfigSchemaRule {
+    /// Create a rule checking against `registry`
+    pub fn new(registry: ComponentRegistry) -> Self {
+        Self { registry }
+    }
+}
+
+impl ValidationRule for ConfigSchemaRule {
+    fn name(&self) -> &'static str {
+        "Component Config"
+    }
+
+    fn validate(&self, graph: &ProjectGraph) -> Vec<ValidationError> {
+        let mut nodes: Vec<&Node> = graph.nodes().collect();
+        nodes.sort_by(|a, b| a.name.cmp(&b.name).then(a.id.cmp(&b.id)));
+        nodes
+            .into_iter()
+            .filter_map(|node| self.registry.get(&node.component_type).map(|def| validate_config(def, node)))
+            .flatten()
+            .collect()
+    }
+}
+
+/// Check one node's config against its component definition
+pub fn validate_config(definition: &ComponentDefinition, node: &Node) -> Vec<ValidationError> {
+    let mut errors = Vec::new();
+    let error = |kind, message: String| ValidationError::for_node(kind, format!("'{}' {}", node.name, message), node.id);
+
+    let mut keys: Vec<&String> = node.config.keys().collect();
+    keys.sort();
+    for key in keys {
+        if definition.get_config(key).is_none() {
+            errors.push(
+                error(
+                    ValidationErrorKind::UnknownConfigKey,
+                    format!("config '{}' is not an option of {}", key, definition.name),
+                )
+                .as_warning(),
+            );
+        }
+    }
+
+    for option in &definition.config {
+        match node.config.get(&option.id) {
+            None | Some(ConfigValue::Null) => {
+                if option.required && option.default_value.is_none() {
+                    let mut missing = error(
+                        ValidationErrorKind::MissingConfigOption,
+                        format!("config '{}' is required", option.id),
+                    );
+                    if let Some(value) = fill_value(option) {
+                        missing = missing.with_fix(QuickFix::SetConfig { node_id: node.id, key: option.id.clone(), value });
+                    }
+                    errors.push(missing);
+                }
+            }
+            Some(value) => {
+                if let Err(problem) = check_value(option, value) {
+                    errors.push(error(
+                        ValidationErrorKind::InvalidConfigValue,
+                        format!("config '{}' {}", option.id, problem),
+                    ));
+                }
+            }
+        }
+    }
+
+    errors
+}
+
+/// Value to fill a missing required option with: its first choice, its
+/// minimum, or `false`
+///
+/// Free text has no such value, so the user has to enter it.
+fn fill_value(option: &ConfigOption) -> Option<ConfigValue> {
+    let value = match option.config_type {
+        ConfigType::Select => ConfigValue::from(option.options.first()?.value.as_str()),
+        ConfigType::MultiSelect => ConfigValue::Array(Vec::new()),
+        ConfigType::Boolean => ConfigValue::Bool(false),
+        ConfigType::Integer | ConfigType::Duration => ConfigValue::Int(option.constraints.min.unwrap_or(0.0).ceil() as i64),
+        ConfigType::Float => ConfigValue::Float(option.constraints.min.unwrap_or(0.0)),
+        _ => return None,
+    };
+    check_value(option, &value).ok().map(|_| value)
+}
+
+/// Check a value's type and constraints, describing the first problem
+fn check_value(option: &ConfigOption, value: &ConfigValue) -> Result<(), String> {
+    let expected = match option.config_type {
+        ConfigType::Integer => "an integer",
+        ConfigType::Float => "a number",
+        ConfigType::Boolean => "true or false",
+        ConfigType::MultiSelect => "a list of strings",
+        ConfigType::Duration => "a duration",
+        ConfigType::Json => "JSON",
+        _ => "a string",
+    };
+    let wrong_type = || format!("should be {}, found {}", expected, describe(value));
+
+    match (option.config_type, value) {
+        (ConfigType::Integer, ConfigValue::Int(i)) => check_range(option, *i as f64),
+        (ConfigType::Float, ConfigValue::Int(i)) => check_range(option, *i as f64),
+        (ConfigType::Float, ConfigValue::Float(f)) => check_range(option, *f),
+        (ConfigType::Boolean, ConfigValue::Bool(_)) | (ConfigType::Json, _) => Ok(()),
+        (ConfigType::Duration, ConfigValue::Int(i)) => check_range(option, *i as f64),
+        (ConfigType::MultiSelect, ConfigValue::Array(items)) => items.iter().try_for_each(|item| match item {
+            ConfigValue::String(s) => check_choice(option, s),
+            other => Err(format!("should only contain strings, found {}", describe(other))),
+        }),
+        (ConfigType::Select, ConfigValue::String(s)) => check_choice(option, s),
+        (
+            ConfigType::String | ConfigType::Text | ConfigType::Color | ConfigType::Duration | ConfigType::Path
+            | ConfigType::Url | ConfigType::Code,
+            ConfigValue::String(s),
+        ) => check_length(option, s),
+        _ => Err(wrong_type()),
+    }
+}
+
+fn check_range(option: &ConfigOption, value: f64) -> Result<(), String> {
+    if let Some(min) = option.constraints.min.filter(|min| value < *min) {
+        return Err(format!("is {}, below the minimum of {}", value, min));
+    }
+    if let Some(max) = option.constraints.max.filter(|max| value > *max) {
+        return Err(format!("is {}, above the maximum of {}", value, max));
+    }
+    Ok(())
+}
+
+fn check_length(option: &ConfigOption, value: &str) -> Result<(), String> {
+    let length = value.chars().count();
+    if let Some(min) = option.constraints.min_length.filter(|min| length < *min) {
+        return Err(format!("is {} characters, shorter than {}", length, min));
+    }
+    if let Some(max) = option.constraints.max_length.filter(|max| length > *max) {
+        return Err(format!("is {} characters, longer than {}", length, max));
+    }
+    Ok(())
+}
+
+fn check_choice(option: &ConfigOption, value: &str) -> Result<(), String> {
+    if option.options.is_empty() || option.options.iter().any(|o| o.value == value) {
+        return Ok(());
+    }
+    let choices: Vec<&str> = option.options.iter().map(|o| o.value.as_str()).collect();
+    Err(format!("is '{}', expected one of: {}", value, choices.join(", ")))
+}
+
+fn describe(value: &ConfigValue) -> String {
+    match value {
+        ConfigValue::Null => "null".to_string(),
+        ConfigValue::Bool(b) => format!("boolean {}", b),
+        ConfigValue::Int(i) => format!("integer {}", i),
+        ConfigValue::Float(f) => format!("number {}", f),
+        ConfigValue::String(s) => format!("string \"{}\"", s),
+        ConfigValue::Array(_) => "a list".to_string(),
+        ConfigValue::Object(_) => "an object".to_string(),
+    }
+}
+
+/// The default IR validator plus config checks against `registry`
+pub fn validator(registry: &ComponentRegistry) -> Validator {
+    let mut validator = Validator::new();
+    validator.add_rule(Box::new(ConfigSchemaRule::new(registry.clone())));
+    validator
+}
+
+/// Validate a project, including component config
+pub fn validate(graph: &ProjectGraph, registry: &ComponentRegistry) -> ValidationResult {
+    validator(registry).validate(graph)
+}
+
+/// All validation issues of a project, including component config
+pub fn get_all_issues(graph: &ProjectGraph, registry: &ComponentRegistry) -> Vec<ValidationError> {
+    validator(registry).min_severity(ValidationSeverity::Info).validate_all(graph)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use imortal_core::ComponentCategory;
+
+    fn definition() -> ComponentDefinition {
+        ComponentDefinition::new("storage.test", "Test Store", ComponentCategory::Storage)
+            .with_config(ConfigOption::string("host", "Host").required())
+            .with_config(ConfigOption::integer("port", "Port").with_min(1.0).with_max(65535.0).with_default(5432))
+            .with_config(ConfigOption::select("backend", "Backend").with_option("postgres", "PostgreSQL").with_option("sqlite", "SQLite"))
+            .with_config(ConfigOption::string("name", "Name").with_length(1, 8))
+    }
+
+    fn messages(node: &Node) -> Vec<(ValidationErrorKind, String)> {
+        validate_config(&definition(), node).into_iter().map(|e| (e.kind, e.message)).collect()
+    }
+
+    #[test]
+    fn test_valid_config() {
+        let mut node = Node::new("storage.test", "Store");
+        node.set_config("host", "localhost");
+        node.set_config("backend", "sqlite");
+        // Options that aren't required can be left out
+        assert!(messages(&node).is_empty());
+    }
+
+    #[test]
+    fn test_config_problems() {
+        let mut node = Node::new("storage.test", "Store");
+        node.set_config("port", 70000i64);
+        node.set_config("backend", "oracle");
+        node.set_config("name", "much too long");
+        node.set_config("hostname", "localhost");
+
+        let found = messages(&node);
+        assert_eq!(found, [
+            (ValidationErrorKind::UnknownConfigKey, "'Store' config 'hostname' is not an option of Test Store".to_string()),
+            (ValidationErrorKind::MissingConfigOption, "'Store' config 'host' is required".to_string()),
+            (ValidationErrorKind::InvalidConfigValue, "'Store' config 'port' is 70000, above the maximum of 65535".to_string()),
+            (ValidationErrorKind::InvalidConfigValue, "'Store' config 'backend' is 'oracle', expected one of: postgres, sqlite".to_string()),
+            (ValidationErrorKind::InvalidConfigValue, "'Store' config 'name' is 13 characters, longer than 8".to_string()),
+        ]);
+
+        node.config.clear();
+        node.set_config("host", "localhost");
+        node.set_config("port", "5432");
+        assert_eq!(messages(&node), [(
+            ValidationErrorKind::InvalidConfigValue,
+            "'Store' config 'port' should be an integer, found string \"5432\"".to_string(),
+        )]);
+    }
+
+    #[test]
+    fn test_missing_option_fix() {
+        let definition = definition()
+            .with_config(ConfigOption::select("mode", "Mode").with_option("fast", "Fast").required())
+            .with_config(ConfigOption::integer("workers", "Workers").with_min(2.0).required());
+        let mut graph = ProjectGraph::with_name("fix");
+        let id = graph.add_node(Node::new("storage.test", "Store"));
+
+        let issues = validate_config(&definition, graph.get_node(id).unwrap());
+        let fixed: Vec<_> = issues.iter().map(|e| e.fix.as_ref().map(QuickFix::label)).collect();
+        // Free text has nothing to fill in
+        assert_eq!(fixed, [None, Some("Set 'mode' to \"fast\"".to_string()), Some("Set 'workers' to 2".to_string())]);
+
+        assert_eq!(imortal_ir::apply_fixes(&mut graph, &issues), 2);
+        assert_eq!(validate_config(&definition, graph.get_node(id).unwrap()).len(), 1);
+    }
+
+    #[test]
+    fn test_builtin_defaults_are_valid() {
+        let registry = ComponentRegistry::with_builtins();
+        let mut graph = ProjectGraph::with_name("defaults");
+        for definition in registry.all() {
+            graph.add_node(definition.instantiate_default());
+        }
+
+        // Fresh nodes may still lack required options with no sensible
+        // default, but every default they do have must be valid
+        let issues: Vec<_> = ConfigSchemaRule::new(registry)
+            .validate(&graph)
+            .into_iter()
+            .filter(|e| e.kind != ValidationErrorKind::MissingConfigOption)
+            .collect();
+        assert!(issues.is_empty(), "{:#?}", issues);
+    }
+}
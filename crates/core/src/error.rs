@@ -26,6 +26,9 @@ pub enum EngineError {
     #[error("Cycle detected in graph: {0}")]
     CycleDetected(String),
 
+    #[error("Invalid query: {0}")]
+    InvalidQuery(String),
+
     // ========== Component Errors ==========
     #[error("Component not found: {0}")]
     ComponentNotFound(String),
@@ -82,6 +85,9 @@ pub enum EngineError {
     #[error("Invalid project structure: {0}")]
     InvalidProjectStructure(String),
 
+    #[error("Project is locked by {0}")]
+    ProjectLocked(String),
+
     // ========== Generic Errors ==========
     #[error("Internal error: {0}")]
     Internal(String),
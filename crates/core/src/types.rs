@@ -93,10 +93,11 @@ impl Rect {
 }
 
 /// Data types supported by the engine
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
 #[serde(tag = "kind", content = "value")]
 pub enum DataType {
     // Primitive types
+    #[default]
     String,
     Text, // Long text/content
     Int32,
@@ -198,18 +199,31 @@ impl DataType {
             DataType::Custom { type_name, .. } => type_name.clone(),
         }
     }
-}
 
-impl Default for DataType {
-    fn default() -> Self {
-        DataType::String
+    /// Rename references to an entity, including nested ones
+    ///
+    /// Returns whether anything changed.
+    pub fn rename_entity(&mut self, old: &str, new: &str) -> bool {
+        match self {
+            DataType::Reference(entity) | DataType::Entity(entity) if entity == old => {
+                *entity = new.to_string();
+                true
+            }
+            DataType::Optional(inner) | DataType::Array(inner) => inner.rename_entity(old, new),
+            DataType::Map { key, value } => {
+                let key_changed = key.rename_entity(old, new);
+                value.rename_entity(old, new) || key_changed
+            }
+            _ => false,
+        }
     }
 }
 
 /// Configuration values for component settings
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum ConfigValue {
+    #[default]
     Null,
     Bool(bool),
     Int(i64),
@@ -266,11 +280,19 @@ impl ConfigValue {
     pub fn is_null(&self) -> bool {
         matches!(self, ConfigValue::Null)
     }
-}
 
-impl Default for ConfigValue {
-    fn default() -> Self {
-        ConfigValue::Null
+    /// Value typed on a command line or in a text box: booleans and numbers
+    /// keep their type, anything else is a string
+    pub fn parse_literal(text: &str) -> Self {
+        match text {
+            "true" => ConfigValue::Bool(true),
+            "false" => ConfigValue::Bool(false),
+            _ => text
+                .parse::<i64>()
+                .map(ConfigValue::Int)
+                .or_else(|_| text.parse::<f64>().map(ConfigValue::Float))
+                .unwrap_or_else(|_| ConfigValue::String(text.to_string())),
+        }
     }
 }
 
@@ -328,6 +350,10 @@ pub enum ComponentCategory {
     Logic,
     /// Embedded/hardware components (GPIO, Sensor, I2C)
     Embedded,
+    /// Application configuration components (Settings)
+    Config,
+    /// Cross-cutting service components (Observability)
+    Service,
     /// Custom/user-defined components
     Custom,
 }
@@ -342,6 +368,8 @@ impl ComponentCategory {
             ComponentCategory::Ui => "UI",
             ComponentCategory::Logic => "Logic",
             ComponentCategory::Embedded => "Embedded",
+            ComponentCategory::Config => "Configuration",
+            ComponentCategory::Service => "Services",
             ComponentCategory::Custom => "Custom",
         }
     }
@@ -355,6 +383,8 @@ impl ComponentCategory {
             ComponentCategory::Ui => "🖼",
             ComponentCategory::Logic => "⚙",
             ComponentCategory::Embedded => "🔧",
+            ComponentCategory::Config => "🛠",
+            ComponentCategory::Service => "🛰",
             ComponentCategory::Custom => "📦",
         }
     }
@@ -368,11 +398,56 @@ impl ComponentCategory {
             ComponentCategory::Ui,
             ComponentCategory::Logic,
             ComponentCategory::Embedded,
+            ComponentCategory::Config,
+            ComponentCategory::Service,
             ComponentCategory::Custom,
         ]
     }
 }
 
+/// What a component or node icon refers to
+///
+/// Icons are usually emoji. An icon ending in `.svg` or `.png` is an image
+/// instead: `builtin:<name>.svg` names one bundled with the editor, and
+/// anything else is a file path.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IconSource<'a> {
+    /// Text drawn as is, usually an emoji
+    Text(&'a str),
+    /// An image bundled with the editor, by file name
+    Builtin(&'a str),
+    /// An image file
+    File(&'a str),
+}
+
+impl<'a> IconSource<'a> {
+    /// Prefix of bundled image icons
+    pub const BUILTIN_PREFIX: &'static str = "builtin:";
+
+    pub fn parse(icon: &'a str) -> Self {
+        let lower = icon.to_ascii_lowercase();
+        if !(lower.ends_with(".svg") || lower.ends_with(".png")) {
+            IconSource::Text(icon)
+        } else if let Some(name) = icon.strip_prefix(Self::BUILTIN_PREFIX) {
+            IconSource::Builtin(name)
+        } else {
+            IconSource::File(icon)
+        }
+    }
+
+    pub fn is_image(&self) -> bool {
+        !matches!(self, IconSource::Text(_))
+    }
+
+    /// The icon as text, or `fallback` for images where only text can be drawn
+    pub fn text_or(&self, fallback: &'a str) -> &'a str {
+        match self {
+            IconSource::Text(text) => text,
+            _ => fallback,
+        }
+    }
+}
+
 /// Type of connection between components
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -387,6 +462,13 @@ pub enum ConnectionType {
     Trigger,
     /// Structural dependency (target requires source)
     Dependency,
+    /// Source entity extends the target entity, inheriting its fields
+    Inheritance,
+    /// Source endpoint caches its responses in the target cache
+    Caches,
+    /// Source feature flag gates the target, which only serves requests
+    /// while the flag is on
+    Gates,
 }
 
 /// Entity relationship types
@@ -397,6 +479,9 @@ pub enum RelationType {
     OneToMany,
     ManyToOne,
     ManyToMany,
+    /// Belongs to one of several entities, e.g. a comment on a post or a
+    /// photo, stored as a type and id column pair
+    Polymorphic,
 }
 
 impl RelationType {
@@ -406,6 +491,7 @@ impl RelationType {
             RelationType::OneToMany => "One to Many",
             RelationType::ManyToOne => "Many to One",
             RelationType::ManyToMany => "Many to Many",
+            RelationType::Polymorphic => "Polymorphic",
         }
     }
 
@@ -415,6 +501,7 @@ impl RelationType {
             RelationType::OneToMany => "1 ───< *",
             RelationType::ManyToOne => "* >─── 1",
             RelationType::ManyToMany => "* >──< *",
+            RelationType::Polymorphic => "* >─┬─ 1",
         }
     }
 }
@@ -476,6 +563,7 @@ impl Validation {
 
 /// UI hints for rendering fields in the editor
 #[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct UiHints {
     /// Display label (defaults to field name if not set)
     pub label: Option<String>,
@@ -528,6 +616,14 @@ impl UiHints {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_config_value_parse_literal() {
+        assert_eq!(ConfigValue::parse_literal("true"), ConfigValue::Bool(true));
+        assert_eq!(ConfigValue::parse_literal("42"), ConfigValue::Int(42));
+        assert_eq!(ConfigValue::parse_literal("1.5"), ConfigValue::Float(1.5));
+        assert_eq!(ConfigValue::parse_literal("postgres"), ConfigValue::String("postgres".to_string()));
+    }
+
     #[test]
     fn test_position() {
         let p1 = Position::new(0.0, 0.0);
@@ -535,6 +631,14 @@ mod tests {
         assert!((p1.distance_to(&p2) - 5.0).abs() < f32::EPSILON);
     }
 
+    #[test]
+    fn test_data_type_rename_entity() {
+        let mut ty = DataType::Array(Box::new(DataType::Reference("User".to_string())));
+        assert!(ty.rename_entity("User", "Account"));
+        assert_eq!(ty.to_rust_type(), "Vec<Account>");
+        assert!(!ty.rename_entity("User", "Member"));
+    }
+
     #[test]
     fn test_rect_contains() {
         let rect = Rect::new(Position::new(10.0, 10.0), Size::new(100.0, 50.0));
@@ -550,6 +654,15 @@ mod tests {
         assert!(!DataType::String.is_compatible_with(&DataType::Int32));
     }
 
+    #[test]
+    fn test_icon_source() {
+        assert_eq!(IconSource::parse("🔐"), IconSource::Text("🔐"));
+        assert_eq!(IconSource::parse("builtin:database.svg"), IconSource::Builtin("database.svg"));
+        assert_eq!(IconSource::parse("/icons/Invoice.PNG"), IconSource::File("/icons/Invoice.PNG"));
+        assert_eq!(IconSource::parse("icons/invoice.svg").text_or("📦"), "📦");
+        assert_eq!(IconSource::parse("🔐").text_or("📦"), "🔐");
+    }
+
     #[test]
     fn test_config_value_conversions() {
         let val: ConfigValue = "test".into();
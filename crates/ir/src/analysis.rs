@@ -0,0 +1,297 @@
+//! Graph analysis for Immortal Engine IR
+//!
+//! Finds structural clutter that validation lets through: orphaned nodes,
+//! duplicate entities, shadowed endpoints, and logic nodes whose results
+//! are never used. Unlike validation, findings come with bulk fixes.
+
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet};
+
+use imortal_core::{ComponentCategory, NodeId};
+
+use crate::graph::ProjectGraph;
+use crate::validation::normalize_route;
+
+/// The kind of issue a graph analysis finding describes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FindingKind {
+    /// Node with no edges at all
+    OrphanedNode,
+    /// Several entities with the same name
+    DuplicateEntity,
+    /// Several endpoints with the same method and path; only the first is reachable
+    ShadowedEndpoint,
+    /// Logic node whose output is never connected
+    UnusedLogic,
+}
+
+impl FindingKind {
+    /// Get a human-readable name for this kind
+    pub fn name(&self) -> &'static str {
+        match self {
+            FindingKind::OrphanedNode => "Orphaned Nodes",
+            FindingKind::DuplicateEntity => "Duplicate Entities",
+            FindingKind::ShadowedEndpoint => "Shadowed Endpoints",
+            FindingKind::UnusedLogic => "Unused Logic",
+        }
+    }
+
+    /// All finding kinds, in report order
+    pub fn all() -> &'static [FindingKind] {
+        &[
+            FindingKind::OrphanedNode,
+            FindingKind::DuplicateEntity,
+            FindingKind::ShadowedEndpoint,
+            FindingKind::UnusedLogic,
+        ]
+    }
+}
+
+/// A single analysis finding
+#[derive(Debug, Clone, PartialEq)]
+pub struct Finding {
+    /// What kind of issue this is
+    pub kind: FindingKind,
+    /// Nodes involved; for duplicates and shadowed endpoints the first node
+    /// is the one that is kept or reachable
+    pub nodes: Vec<NodeId>,
+    /// Human-readable description
+    pub message: String,
+}
+
+/// Result of analyzing a project graph
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GraphAnalysis {
+    /// All findings, grouped by kind in [`FindingKind::all`] order
+    pub findings: Vec<Finding>,
+}
+
+impl GraphAnalysis {
+    /// Analyze a project graph
+    pub fn analyze(graph: &ProjectGraph) -> Self {
+        let mut findings = Vec::new();
+        findings.extend(orphaned_nodes(graph));
+        findings.extend(duplicate_entities(graph));
+        findings.extend(shadowed_endpoints(graph));
+        findings.extend(unused_logic(graph));
+        Self { findings }
+    }
+
+    // ========== Query Methods ==========
+
+    /// Check if nothing was found
+    pub fn is_clean(&self) -> bool {
+        self.findings.is_empty()
+    }
+
+    /// Get findings of a specific kind
+    pub fn of_kind(&self, kind: FindingKind) -> impl Iterator<Item = &Finding> {
+        self.findings.iter().filter(move |f| f.kind == kind)
+    }
+
+    /// Count findings of a specific kind
+    pub fn count(&self, kind: FindingKind) -> usize {
+        self.of_kind(kind).count()
+    }
+
+    // ========== Bulk Fixes ==========
+
+    /// Delete every orphaned node, returning how many were removed
+    pub fn delete_orphans(&self, graph: &mut ProjectGraph) -> usize {
+        self.of_kind(FindingKind::OrphanedNode)
+            .flat_map(|f| f.nodes.iter())
+            .filter(|id| graph.remove_node(**id).is_some())
+            .count()
+    }
+
+    /// Merge each group of duplicate entities into its first node, returning
+    /// how many nodes were merged away
+    pub fn merge_duplicates(&self, graph: &mut ProjectGraph) -> usize {
+        let mut merged = 0;
+        for finding in self.of_kind(FindingKind::DuplicateEntity) {
+            let Some((&keep, rest)) = finding.nodes.split_first() else {
+                continue;
+            };
+            for &duplicate in rest {
+                if graph.merge_node_into(duplicate, keep) {
+                    merged += 1;
+                }
+            }
+        }
+        merged
+    }
+}
+
+/// Nodes without any edges
+///
+/// Config and service nodes apply project-wide and are never connected, so
+/// they are not reported.
+fn orphaned_nodes(graph: &ProjectGraph) -> Vec<Finding> {
+    let connected: HashSet<NodeId> = graph
+        .edges()
+        .flat_map(|e| [e.from_node, e.to_node])
+        .collect();
+
+    let mut orphans: Vec<_> = graph
+        .nodes()
+        .filter(|n| !matches!(n.category, ComponentCategory::Config | ComponentCategory::Service))
+        .filter(|n| !connected.contains(&n.id))
+        .collect();
+    orphans.sort_by(|a, b| a.name.cmp(&b.name).then(a.id.cmp(&b.id)));
+
+    orphans
+        .into_iter()
+        .map(|n| Finding {
+            kind: FindingKind::OrphanedNode,
+            nodes: vec![n.id],
+            message: format!("'{}' has no connections", n.name),
+        })
+        .collect()
+}
+
+/// Entities sharing a name (case-insensitive)
+///
+/// The best-connected entity of each group is listed first and is the one
+/// kept when merging.
+fn duplicate_entities(graph: &ProjectGraph) -> Vec<Finding> {
+    let mut by_name: HashMap<String, Vec<NodeId>> = HashMap::new();
+    for node in graph.find_nodes_by_type("data.entity") {
+        by_name.entry(node.name.to_lowercase()).or_default().push(node.id);
+    }
+
+    let mut groups: Vec<_> = by_name.into_values().filter(|ids| ids.len() > 1).collect();
+    for ids in &mut groups {
+        ids.sort_by_key(|id| (Reverse(graph.edges_for_node(*id).len()), *id));
+    }
+    groups.sort_by_key(|ids| graph.get_node(ids[0]).map(|n| n.name.clone()));
+
+    groups
+        .into_iter()
+        .map(|ids| {
+            let name = graph.get_node(ids[0]).map(|n| n.name.as_str()).unwrap_or_default();
+            Finding {
+                kind: FindingKind::DuplicateEntity,
+                message: format!("Entity '{}' is defined {} times", name, ids.len()),
+                nodes: ids,
+            }
+        })
+        .collect()
+}
+
+/// REST endpoints that resolve to the same method and versioned route
+fn shadowed_endpoints(graph: &ProjectGraph) -> Vec<Finding> {
+    let versioning = &graph.meta.api_versioning;
+    let mut endpoints = graph.find_nodes_by_type("api.rest");
+    endpoints.sort_by(|a, b| a.name.cmp(&b.name).then(a.id.cmp(&b.id)));
+
+    let mut routes: Vec<((String, String), String, Vec<NodeId>)> = Vec::new();
+    for node in endpoints {
+        let method = node.get_config_str("method").unwrap_or("GET").to_uppercase();
+        let route = versioning.route(node.endpoint_path().unwrap_or("/"), node.endpoint_version());
+        let key = (method, normalize_route(&route));
+
+        match routes.iter_mut().find(|(k, _, _)| *k == key) {
+            Some((_, _, ids)) => ids.push(node.id),
+            None => routes.push((key, route, vec![node.id])),
+        }
+    }
+
+    routes
+        .into_iter()
+        .filter(|(_, _, ids)| ids.len() > 1)
+        .map(|((method, _), route, ids)| Finding {
+            kind: FindingKind::ShadowedEndpoint,
+            message: format!("{} endpoints share {} {}", ids.len(), method, route),
+            nodes: ids,
+        })
+        .collect()
+}
+
+/// Connected logic nodes with no outgoing edges
+///
+/// Logic nodes without any edges are already reported as orphans.
+fn unused_logic(graph: &ProjectGraph) -> Vec<Finding> {
+    let mut unused: Vec<_> = graph
+        .nodes()
+        .filter(|n| n.category == ComponentCategory::Logic)
+        .filter(|n| !graph.edges_for_node(n.id).is_empty() && graph.outgoing_edges(n.id).is_empty())
+        .collect();
+    unused.sort_by(|a, b| a.name.cmp(&b.name).then(a.id.cmp(&b.id)));
+
+    unused
+        .into_iter()
+        .map(|n| Finding {
+            kind: FindingKind::UnusedLogic,
+            nodes: vec![n.id],
+            message: format!("Output of '{}' is never used", n.name),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Edge, Node};
+
+    fn endpoint(name: &str, path: &str) -> Node {
+        let mut node = Node::new_rest_endpoint(name);
+        node.set_config("path", path);
+        node
+    }
+
+    #[test]
+    fn test_clean_graph() {
+        let mut graph = ProjectGraph::with_name("test");
+        let user = graph.add_node(Node::new_entity("User"));
+        let api = graph.add_node(endpoint("Users", "/users"));
+        graph.add_edge(Edge::dependency(user, api)).unwrap();
+
+        assert!(GraphAnalysis::analyze(&graph).is_clean());
+    }
+
+    #[test]
+    fn test_findings() {
+        let mut graph = ProjectGraph::with_name("test");
+        let user = graph.add_node(Node::new_entity("User"));
+        let copy = graph.add_node(Node::new_entity("user"));
+        let list = graph.add_node(endpoint("List Users", "/users/:id"));
+        let fetch = graph.add_node(endpoint("Fetch User", "/users/{user_id}"));
+        let check = graph.add_node(Node::new("logic.validator", "Check").with_category(ComponentCategory::Logic));
+        let settings = graph.add_node(Node::new_settings("Settings"));
+        let orphan = graph.add_node(Node::new_entity("Tag"));
+        graph.add_edge(Edge::dependency(user, list)).unwrap();
+        graph.add_edge(Edge::dependency(user, fetch)).unwrap();
+        graph.add_edge(Edge::dependency(copy, check)).unwrap();
+
+        let analysis = GraphAnalysis::analyze(&graph);
+        assert_eq!(analysis.count(FindingKind::OrphanedNode), 1);
+        assert_eq!(analysis.of_kind(FindingKind::OrphanedNode).next().unwrap().nodes, vec![orphan]);
+        assert!(!analysis.findings.iter().any(|f| f.nodes.contains(&settings)));
+
+        let duplicate = analysis.of_kind(FindingKind::DuplicateEntity).next().unwrap();
+        assert_eq!(duplicate.nodes, vec![user, copy]);
+
+        let shadowed = analysis.of_kind(FindingKind::ShadowedEndpoint).next().unwrap();
+        assert_eq!(shadowed.nodes, vec![fetch, list]);
+
+        assert_eq!(analysis.of_kind(FindingKind::UnusedLogic).next().unwrap().nodes, vec![check]);
+    }
+
+    #[test]
+    fn test_bulk_fixes() {
+        let mut graph = ProjectGraph::with_name("test");
+        let user = graph.add_node(Node::new_entity("User"));
+        let copy = graph.add_node(Node::new_entity("User"));
+        let api = graph.add_node(endpoint("Users", "/users"));
+        graph.add_node(Node::new_entity("Tag"));
+        graph.add_edge(Edge::dependency(user, api)).unwrap();
+        graph.add_edge(Edge::dependency(copy, api)).unwrap();
+
+        let analysis = GraphAnalysis::analyze(&graph);
+        assert_eq!(analysis.delete_orphans(&mut graph), 1);
+        assert_eq!(analysis.merge_duplicates(&mut graph), 1);
+        assert_eq!(graph.node_count(), 2);
+        assert_eq!(graph.edge_count(), 1);
+        assert!(GraphAnalysis::analyze(&graph).is_clean());
+    }
+}
@@ -0,0 +1,505 @@
+//! Assistant hook for graph suggestions
+//!
+//! The engine bundles no assistant. Embedders wire in their own backend,
+//! usually a language model, by implementing [`SuggestionProvider`]: it
+//! gets an [`AssistRequest`] (what the user asked for and a JSON excerpt of
+//! the graph) and answers with JSON in the [`PROPOSAL_FORMAT`], which
+//! parses into a [`Proposal`] of nodes and edges for the user to review
+//! before [`Proposal::apply`] adds the ones they keep.
+//!
+//! ```
+//! use imortal_ir::assist::{AssistRequest, ProviderFuture, SuggestionProvider};
+//!
+//! struct Echo;
+//!
+//! impl SuggestionProvider for Echo {
+//!     fn name(&self) -> &str {
+//!         "Echo"
+//!     }
+//!
+//!     fn suggest<'a>(&'a self, request: &'a AssistRequest) -> ProviderFuture<'a> {
+//!         // A real provider sends `request.prompt()` to its model
+//!         Box::pin(async move { Ok(format!(r#"{{"summary": "{}"}}"#, request.instruction)) })
+//!     }
+//! }
+//! ```
+
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake, Waker};
+
+use imortal_core::{ConnectionType, DataType, EdgeId, NodeId, RelationType};
+use serde::{Deserialize, Serialize};
+
+use crate::edge::Edge;
+use crate::field::Field;
+use crate::graph::ProjectGraph;
+use crate::node::Node;
+use crate::placement::PlacementStrategy;
+
+/// Most nodes sent in an excerpt of a whole project
+pub const MAX_EXCERPT_NODES: usize = 200;
+
+/// What a provider's answer must look like, included in every prompt
+pub const PROPOSAL_FORMAT: &str = r#"Answer with one JSON object and nothing else:
+{
+  "summary": "what the proposal does",
+  "nodes": [{"key": "n1", "component_type": "data.entity", "name": "Order",
+             "fields": [{"name": "total", "type": "i64", "required": true}], "reason": "why"}],
+  "edges": [{"from": "n1", "to": "User", "kind": "many_to_one", "reason": "why"}]
+}
+Edge ends are the key of a proposed node, or the id or name of an existing node.
+Edge kinds: dependency, one_to_one, one_to_many, many_to_one, many_to_many.
+Field types: string, text, i32, i64, f32, f64, bool, uuid, datetime, date, time, bytes, json, or ref:<Entity>."#;
+
+/// A boxed future, as returned by [`SuggestionProvider::suggest`]
+pub type ProviderFuture<'a> = Pin<Box<dyn Future<Output = Result<String, String>> + Send + 'a>>;
+
+/// A backend proposing changes to a graph
+///
+/// The editor polls [`suggest`](Self::suggest) on a worker thread with
+/// [`block_on`], which has no reactor of its own: providers built on a
+/// runtime such as tokio should enter or spawn onto it inside `suggest`.
+pub trait SuggestionProvider: Send + Sync {
+    /// Name shown in the editor, e.g. "Local model"
+    fn name(&self) -> &str;
+
+    /// Answer `request` with JSON in the [`PROPOSAL_FORMAT`], or an error
+    /// message to show the user
+    fn suggest<'a>(&'a self, request: &'a AssistRequest) -> ProviderFuture<'a>;
+}
+
+/// What the user asked for, and the part of the graph it's about
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AssistRequest {
+    pub instruction: String,
+    pub excerpt: GraphExcerpt,
+}
+
+impl AssistRequest {
+    /// A request about the `focus` nodes and their neighbours, or the whole
+    /// graph when `focus` is empty
+    pub fn new(graph: &ProjectGraph, focus: &[NodeId], instruction: impl Into<String>) -> Self {
+        Self { instruction: instruction.into(), excerpt: GraphExcerpt::new(graph, focus) }
+    }
+
+    /// The full prompt: instruction, answer format, and graph excerpt
+    pub fn prompt(&self) -> String {
+        format!(
+            "{}\n\n{}\n\nThe project so far:\n{}",
+            self.instruction,
+            PROPOSAL_FORMAT,
+            serde_json::to_string_pretty(&self.excerpt).unwrap_or_default(),
+        )
+    }
+}
+
+/// A serializable summary of part of a graph
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct GraphExcerpt {
+    pub project: String,
+    pub nodes: Vec<ExcerptNode>,
+    pub edges: Vec<ExcerptEdge>,
+    /// Whether nodes were left out to keep the excerpt short
+    pub truncated: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ExcerptNode {
+    pub id: NodeId,
+    pub component_type: String,
+    pub name: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub fields: Vec<ExcerptField>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ExcerptField {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub data_type: String,
+    pub required: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ExcerptEdge {
+    pub from: NodeId,
+    pub to: NodeId,
+    pub kind: ConnectionType,
+}
+
+impl GraphExcerpt {
+    /// The `focus` nodes and their neighbours, or the whole graph (up to
+    /// [`MAX_EXCERPT_NODES`]) when `focus` is empty
+    pub fn new(graph: &ProjectGraph, focus: &[NodeId]) -> Self {
+        let mut included: HashSet<NodeId> = focus.iter().copied().filter(|id| graph.has_node(*id)).collect();
+        if focus.is_empty() {
+            included = graph.nodes().map(|n| n.id).collect();
+        } else {
+            for edge in graph.edges() {
+                if focus.contains(&edge.from_node) || focus.contains(&edge.to_node) {
+                    included.extend([edge.from_node, edge.to_node]);
+                }
+            }
+        }
+
+        let mut nodes: Vec<&Node> = graph.nodes().filter(|n| included.contains(&n.id)).collect();
+        nodes.sort_by(|a, b| a.name.cmp(&b.name).then(a.id.cmp(&b.id)));
+        let truncated = nodes.len() > MAX_EXCERPT_NODES;
+        nodes.truncate(MAX_EXCERPT_NODES);
+        let kept: HashSet<NodeId> = nodes.iter().map(|n| n.id).collect();
+
+        let mut edges: Vec<ExcerptEdge> = graph
+            .edges()
+            .filter(|e| kept.contains(&e.from_node) && kept.contains(&e.to_node))
+            .map(|e| ExcerptEdge { from: e.from_node, to: e.to_node, kind: e.connection_type.clone() })
+            .collect();
+        edges.sort_by_key(|e| (e.from, e.to));
+
+        Self {
+            project: graph.meta.name.clone(),
+            nodes: nodes
+                .into_iter()
+                .map(|n| ExcerptNode {
+                    id: n.id,
+                    component_type: n.component_type.clone(),
+                    name: n.name.clone(),
+                    fields: n
+                        .fields
+                        .iter()
+                        .map(|f| ExcerptField { name: f.name.clone(), data_type: type_name(&f.data_type), required: f.required })
+                        .collect(),
+                })
+                .collect(),
+            edges,
+            truncated,
+        }
+    }
+}
+
+/// Name of a data type in the [`PROPOSAL_FORMAT`]
+pub fn type_name(data_type: &DataType) -> String {
+    match data_type {
+        DataType::String => "string".to_string(),
+        DataType::Text => "text".to_string(),
+        DataType::Int32 => "i32".to_string(),
+        DataType::Int64 => "i64".to_string(),
+        DataType::Float32 => "f32".to_string(),
+        DataType::Float64 => "f64".to_string(),
+        DataType::Bool => "bool".to_string(),
+        DataType::Uuid => "uuid".to_string(),
+        DataType::DateTime => "datetime".to_string(),
+        DataType::Date => "date".to_string(),
+        DataType::Time => "time".to_string(),
+        DataType::Bytes => "bytes".to_string(),
+        DataType::Optional(inner) => type_name(inner),
+        DataType::Reference(entity) | DataType::Entity(entity) => format!("ref:{}", entity),
+        _ => "json".to_string(),
+    }
+}
+
+/// A data type from its name in the [`PROPOSAL_FORMAT`]
+pub fn parse_type_name(name: &str) -> Option<DataType> {
+    let name = name.trim();
+    if let Some(entity) = name.strip_prefix("ref:") {
+        return Some(DataType::Reference(entity.trim().to_string()));
+    }
+    Some(match name.to_ascii_lowercase().as_str() {
+        "string" => DataType::String,
+        "text" => DataType::Text,
+        "i32" | "int" | "integer" => DataType::Int32,
+        "i64" => DataType::Int64,
+        "f32" | "float" => DataType::Float32,
+        "f64" | "double" => DataType::Float64,
+        "bool" | "boolean" => DataType::Bool,
+        "uuid" => DataType::Uuid,
+        "datetime" => DataType::DateTime,
+        "date" => DataType::Date,
+        "time" => DataType::Time,
+        "bytes" => DataType::Bytes,
+        "json" => DataType::Json,
+        _ => return None,
+    })
+}
+
+/// Nodes and edges a provider proposes
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Proposal {
+    #[serde(default)]
+    pub summary: String,
+    #[serde(default)]
+    pub nodes: Vec<ProposedNode>,
+    #[serde(default)]
+    pub edges: Vec<ProposedEdge>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProposedNode {
+    /// Name edges use for this node
+    pub key: String,
+    pub component_type: String,
+    pub name: String,
+    #[serde(default)]
+    pub fields: Vec<ProposedField>,
+    #[serde(default)]
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProposedField {
+    pub name: String,
+    #[serde(rename = "type", default = "default_field_type")]
+    pub data_type: String,
+    #[serde(default)]
+    pub required: bool,
+}
+
+fn default_field_type() -> String {
+    "string".to_string()
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProposedEdge {
+    /// Key of a proposed node, or id or name of an existing one
+    pub from: String,
+    pub to: String,
+    #[serde(default)]
+    pub kind: ProposedEdgeKind,
+    #[serde(default)]
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProposedEdgeKind {
+    #[default]
+    Dependency,
+    OneToOne,
+    OneToMany,
+    ManyToOne,
+    ManyToMany,
+}
+
+impl ProposedEdgeKind {
+    fn relation_type(self) -> Option<RelationType> {
+        match self {
+            ProposedEdgeKind::Dependency => None,
+            ProposedEdgeKind::OneToOne => Some(RelationType::OneToOne),
+            ProposedEdgeKind::OneToMany => Some(RelationType::OneToMany),
+            ProposedEdgeKind::ManyToOne => Some(RelationType::ManyToOne),
+            ProposedEdgeKind::ManyToMany => Some(RelationType::ManyToMany),
+        }
+    }
+}
+
+/// What [`Proposal::apply`] added and what it couldn't
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AppliedProposal {
+    pub nodes: Vec<NodeId>,
+    pub edges: Vec<EdgeId>,
+    /// One message per proposed item that wasn't added
+    pub skipped: Vec<String>,
+}
+
+impl Proposal {
+    /// Parse a provider's answer, ignoring text around the JSON object such
+    /// as a Markdown code fence
+    pub fn from_json(answer: &str) -> Result<Self, serde_json::Error> {
+        let json = match (answer.find('{'), answer.rfind('}')) {
+            (Some(start), Some(end)) if start < end => &answer[start..=end],
+            _ => answer,
+        };
+        serde_json::from_str(json)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty() && self.edges.is_empty()
+    }
+
+    /// Add the proposed nodes and edges whose entry in `keep_nodes` or
+    /// `keep_edges` is true
+    ///
+    /// `make_node` creates a node of a component type with a name, or
+    /// `None` for types it doesn't know; `placement` positions it. Edges to
+    /// proposed nodes that weren't added are skipped, as are any to names
+    /// no node has.
+    pub fn apply(
+        &self,
+        graph: &mut ProjectGraph,
+        keep_nodes: &[bool],
+        keep_edges: &[bool],
+        make_node: impl Fn(&str, &str) -> Option<Node>,
+        placement: &dyn PlacementStrategy,
+    ) -> AppliedProposal {
+        let mut applied = AppliedProposal::default();
+        let mut keys = HashMap::new();
+        for (proposed, _) in self.nodes.iter().zip(keep_nodes).filter(|(_, keep)| **keep) {
+            let Some(mut node) = make_node(&proposed.component_type, &proposed.name) else {
+                applied.skipped.push(format!("'{}': unknown component type '{}'", proposed.name, proposed.component_type));
+                continue;
+            };
+            for field in &proposed.fields {
+                if node.get_field(&field.name).is_some() {
+                    continue;
+                }
+                let data_type = parse_type_name(&field.data_type).unwrap_or(DataType::String);
+                let mut new_field = Field::new(&field.name, data_type);
+                if field.required {
+                    new_field = new_field.required();
+                }
+                node.add_field(new_field);
+            }
+            node.position = placement.place(graph, &node);
+            let id = graph.add_node(node);
+            keys.insert(proposed.key.as_str(), id);
+            applied.nodes.push(id);
+        }
+
+        for (proposed, _) in self.edges.iter().zip(keep_edges).filter(|(_, keep)| **keep) {
+            let (Some(from), Some(to)) = (self.resolve(graph, &keys, &proposed.from), self.resolve(graph, &keys, &proposed.to)) else {
+                applied.skipped.push(format!("{} → {}: no such node", proposed.from, proposed.to));
+                continue;
+            };
+            let result = match proposed.kind.relation_type() {
+                Some(relation_type) => graph.add_relationship(from, to, relation_type),
+                None => graph.add_edge(Edge::dependency(from, to)),
+            };
+            match result {
+                Ok(id) => applied.edges.push(id),
+                Err(e) => applied.skipped.push(format!("{} → {}: {}", proposed.from, proposed.to, e)),
+            }
+        }
+        applied
+    }
+
+    /// Node an edge end names: a proposed node added under that key, or an
+    /// existing node with that id or name; a proposed node that wasn't
+    /// added resolves to nothing
+    fn resolve(&self, graph: &ProjectGraph, keys: &HashMap<&str, NodeId>, name: &str) -> Option<NodeId> {
+        if let Some(id) = keys.get(name) {
+            return Some(*id);
+        }
+        if self.nodes.iter().any(|n| n.key == name) {
+            return None;
+        }
+        if let Some(id) = name.parse::<NodeId>().ok().filter(|id| graph.has_node(*id)) {
+            return Some(id);
+        }
+        let mut named = graph.nodes().filter(|n| n.name == name);
+        match (named.next(), named.next()) {
+            (Some(node), None) => Some(node.id),
+            _ => None,
+        }
+    }
+}
+
+/// Run a future to completion on the current thread, parking it while the
+/// future waits
+pub fn block_on<F: Future>(future: F) -> F::Output {
+    struct ThreadWaker(std::thread::Thread);
+
+    impl Wake for ThreadWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark();
+        }
+    }
+
+    let waker = Waker::from(Arc::new(ThreadWaker(std::thread::current())));
+    let mut cx = Context::from_waker(&waker);
+    let mut future = std::pin::pin!(future);
+    loop {
+        match future.as_mut().poll(&mut cx) {
+            Poll::Ready(output) => return output,
+            Poll::Pending => std::thread::park(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::placement::ConstraintPlacement;
+
+    struct Canned(&'static str);
+
+    impl SuggestionProvider for Canned {
+        fn name(&self) -> &str {
+            "Canned"
+        }
+
+        fn suggest<'a>(&'a self, request: &'a AssistRequest) -> ProviderFuture<'a> {
+            Box::pin(async move {
+                assert!(request.prompt().contains("\"name\": \"User\""));
+                Ok(self.0.to_string())
+            })
+        }
+    }
+
+    fn make_node(component_type: &str, name: &str) -> Option<Node> {
+        match component_type {
+            "data.entity" => Some(Node::new_entity(name)),
+            "api.rest" => Some(Node::new_rest_endpoint(name)),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_excerpt() {
+        let mut graph = ProjectGraph::with_name("shop");
+        let user = graph.add_node(Node::new_entity("User"));
+        let db = graph.add_node(Node::new_database("Main"));
+        graph.add_node(Node::new_entity("Tag"));
+        graph.add_edge(Edge::dependency(db, user)).unwrap();
+
+        let excerpt = GraphExcerpt::new(&graph, &[user]);
+        let names: Vec<_> = excerpt.nodes.iter().map(|n| n.name.as_str()).collect();
+        assert_eq!(names, ["Main", "User"]);
+        assert_eq!(excerpt.edges.len(), 1);
+        assert_eq!(excerpt.nodes[1].fields[0].data_type, "uuid");
+        assert_eq!(GraphExcerpt::new(&graph, &[]).nodes.len(), 3);
+
+        assert_eq!(parse_type_name("ref:User"), Some(DataType::Reference("User".to_string())));
+        assert_eq!(parse_type_name(&type_name(&DataType::Float64)), Some(DataType::Float64));
+        assert_eq!(parse_type_name("money"), None);
+    }
+
+    #[test]
+    fn test_provider_proposal() {
+        let mut graph = ProjectGraph::with_name("shop");
+        let user = graph.add_node(Node::new_entity("User"));
+        let answer = r#"Here you go:
+```json
+{"summary": "Orders",
+ "nodes": [{"key": "n1", "component_type": "data.entity", "name": "Order",
+            "fields": [{"name": "total", "type": "i64", "required": true}, {"name": "note"}]},
+           {"key": "n2", "component_type": "api.rest", "name": "Orders"},
+           {"key": "n3", "component_type": "ai.magic", "name": "Oracle"}],
+ "edges": [{"from": "n1", "to": "User", "kind": "many_to_one"},
+           {"from": "n1", "to": "n2"},
+           {"from": "n3", "to": "n1"},
+           {"from": "n1", "to": "Nobody"}]}
+```"#;
+        let provider: Arc<dyn SuggestionProvider> = Arc::new(Canned(answer));
+        let request = AssistRequest::new(&graph, &[], "Add orders");
+        let proposal = Proposal::from_json(&block_on(provider.suggest(&request)).unwrap()).unwrap();
+        assert_eq!(proposal.nodes.len(), 3);
+        assert_eq!(proposal.edges[1].kind, ProposedEdgeKind::Dependency);
+
+        // The user unticks the endpoint
+        let applied = proposal.apply(&mut graph, &[true, false, true], &[true; 4], make_node, &ConstraintPlacement::default());
+        assert_eq!(applied.nodes.len(), 1);
+        assert_eq!(applied.edges.len(), 1);
+        assert_eq!(applied.skipped.len(), 4, "{:?}", applied.skipped);
+        let order = graph.get_node(applied.nodes[0]).unwrap();
+        assert_eq!(order.get_field("total").unwrap().data_type, DataType::Int64);
+        assert!(order.get_field("total").unwrap().required);
+        assert_eq!(order.get_field("note").unwrap().data_type, DataType::String);
+        let edge = graph.get_edge(applied.edges[0]).unwrap();
+        assert_eq!((edge.from_node, edge.to_node), (order.id, user));
+        assert_eq!(edge.relationship_type(), Some(RelationType::ManyToOne));
+
+        assert!(Proposal::from_json("no idea").is_err());
+    }
+}
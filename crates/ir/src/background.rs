@@ -0,0 +1,165 @@
+//! Canvas background layers
+//!
+//! A project can have layers drawn beneath everything else on the canvas:
+//! an imported image, such as an existing architecture diagram to trace
+//! over, or a set of labelled swimlanes. Layers are saved in the project
+//! file, images included.
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use uuid::Uuid;
+
+use imortal_core::{Position, Size};
+
+use crate::group::GroupColor;
+
+/// A layer drawn beneath the canvas content
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BackgroundLayer {
+    /// Unique identifier
+    pub id: Uuid,
+    /// Name shown in the layer list
+    pub name: String,
+    /// Whether the layer is drawn
+    #[serde(default = "default_visible")]
+    pub visible: bool,
+    /// Opacity (0.0 - 1.0)
+    #[serde(default = "default_opacity")]
+    pub opacity: f32,
+    /// What the layer shows
+    pub kind: BackgroundKind,
+}
+
+fn default_visible() -> bool {
+    true
+}
+
+fn default_opacity() -> f32 {
+    0.5
+}
+
+/// What a background layer shows
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum BackgroundKind {
+    /// An image placed on the canvas
+    Image {
+        /// PNG data, base64-encoded in the project file
+        #[serde(serialize_with = "encode_base64", deserialize_with = "decode_base64")]
+        data: Vec<u8>,
+        /// Top-left corner, in canvas units
+        position: Position,
+        /// Size on the canvas, in canvas units
+        size: Size,
+    },
+    /// Lanes running across the whole canvas
+    Swimlanes {
+        orientation: LaneOrientation,
+        /// Where the first lane starts, across the lanes
+        #[serde(default)]
+        start: f32,
+        lanes: Vec<Lane>,
+    },
+}
+
+/// Direction swimlanes run in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum LaneOrientation {
+    /// Lanes are rows, stacked top to bottom
+    #[default]
+    Horizontal,
+    /// Lanes are columns, side by side from left to right
+    Vertical,
+}
+
+/// One swimlane
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Lane {
+    /// Label drawn at the start of the lane
+    pub label: String,
+    /// Height of a row or width of a column, in canvas units
+    pub size: f32,
+    /// Lane tint
+    #[serde(default)]
+    pub color: GroupColor,
+}
+
+impl BackgroundLayer {
+    /// Width or height of a new lane, in canvas units
+    pub const DEFAULT_LANE_SIZE: f32 = 300.0;
+
+    /// An image layer at the canvas origin
+    pub fn image(name: impl Into<String>, data: Vec<u8>, size: Size) -> Self {
+        Self::new(name, BackgroundKind::Image { data, position: Position::zero(), size })
+    }
+
+    /// Swimlanes with the given labels, alternating tints
+    pub fn swimlanes(name: impl Into<String>, orientation: LaneOrientation, labels: &[&str]) -> Self {
+        let tints = [GroupColor::Blue, GroupColor::Gray];
+        let lanes = labels
+            .iter()
+            .zip(tints.iter().cycle())
+            .map(|(label, color)| Lane { label: label.to_string(), size: Self::DEFAULT_LANE_SIZE, color: *color })
+            .collect();
+        Self::new(name, BackgroundKind::Swimlanes { orientation, start: 0.0, lanes })
+    }
+
+    fn new(name: impl Into<String>, kind: BackgroundKind) -> Self {
+        Self { id: Uuid::new_v4(), name: name.into(), visible: true, opacity: default_opacity(), kind }
+    }
+
+    /// Where each lane starts and ends across the lanes, in canvas units
+    pub fn lane_bounds(&self) -> Vec<(&Lane, f32, f32)> {
+        let BackgroundKind::Swimlanes { start, lanes, .. } = &self.kind else {
+            return Vec::new();
+        };
+        let mut offset = *start;
+        lanes
+            .iter()
+            .map(|lane| {
+                let bounds = (lane, offset, offset + lane.size);
+                offset += lane.size;
+                bounds
+            })
+            .collect()
+    }
+}
+
+fn encode_base64<S: Serializer>(data: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+    use base64::Engine;
+    serializer.serialize_str(&base64::engine::general_purpose::STANDARD.encode(data))
+}
+
+fn decode_base64<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+    use base64::Engine;
+    let text = String::deserialize(deserializer)?;
+    base64::engine::general_purpose::STANDARD.decode(text).map_err(serde::de::Error::custom)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_layers_round_trip() {
+        let image = BackgroundLayer::image("Legacy diagram", vec![0x89, b'P', b'N', b'G', 0, 255], Size::new(640.0, 480.0));
+        let json = serde_json::to_string(&image).unwrap();
+        assert!(json.contains("\"data\":\"iVBORwD/\""));
+        assert_eq!(serde_json::from_str::<BackgroundLayer>(&json).unwrap(), image);
+
+        let mut lanes = BackgroundLayer::swimlanes("Teams", LaneOrientation::Vertical, &["Web", "Billing", "Data"]);
+        if let BackgroundKind::Swimlanes { start, lanes, .. } = &mut lanes.kind {
+            *start = -100.0;
+            lanes[1].size = 500.0;
+        }
+        let bounds: Vec<_> = lanes.lane_bounds().into_iter().map(|(lane, from, to)| (lane.label.as_str(), from, to)).collect();
+        assert_eq!(bounds, [("Web", -100.0, 200.0), ("Billing", 200.0, 700.0), ("Data", 700.0, 1000.0)]);
+        assert!(image.lane_bounds().is_empty());
+
+        // Visibility and opacity can be left out
+        let minimal = r#"{"id":"00000000-0000-0000-0000-000000000001","name":"Old","kind":{"type":"swimlanes","orientation":"horizontal","lanes":[]}}"#;
+        let minimal: BackgroundLayer = serde_json::from_str(minimal).unwrap();
+        assert!(minimal.visible);
+        assert_eq!(minimal.opacity, 0.5);
+    }
+}
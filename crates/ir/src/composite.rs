@@ -0,0 +1,618 @@
+//! Composite nodes
+//!
+//! A group can be collapsed into a single composite node that carries the
+//! group's nodes, edges, and nested groups as a [`Subgraph`]. Edges crossing
+//! the group boundary are reattached to ports on the composite, each
+//! remembering the inner port it stands for. Expanding the composite puts
+//! everything back, and [`ProjectGraph::flatten`] expands every composite so
+//! code generation sees the plain graph.
+//!
+//! A composite pinned as a template can declare [`TemplateParameter`]s.
+//! Instantiating it replaces `{{name}}` placeholders in names, module paths,
+//! and config values throughout the subgraph with the values given.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+use imortal_core::{ComponentCategory, ConfigValue, DataType, EdgeId, EngineError, EngineResult, NodeId, PortId};
+
+use crate::field::RELATIONSHIP_METADATA_KEY;
+use crate::graph::ProjectGraph;
+use crate::group::Group;
+use crate::node::Node;
+use crate::port::Port;
+use crate::project::ProjectMeta;
+
+/// Component type of composite nodes
+pub const COMPOSITE_COMPONENT: &str = "composite";
+
+/// Contents of a composite node
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Subgraph {
+    /// Nodes, edges, and nested groups inside the composite
+    pub graph: ProjectGraph,
+    /// The group the composite was collapsed from, restored on expanding
+    pub group: Group,
+    /// What each of the composite's ports leads to inside
+    pub ports: Vec<BoundaryPort>,
+    /// Values asked for when the composite is instantiated as a template
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub parameters: Vec<TemplateParameter>,
+}
+
+/// A composite port standing in for a port of an inner node
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BoundaryPort {
+    /// Port on the composite node
+    pub port: PortId,
+    /// Inner node the port leads to
+    pub node_id: NodeId,
+    /// Port of the inner node
+    pub node_port: PortId,
+    /// Whether edges leave the composite through this port
+    pub is_output: bool,
+}
+
+/// A value filled in when a composite template is instantiated
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TemplateParameter {
+    /// Name used in `{{name}}` placeholders
+    pub name: String,
+    /// Prompt shown when instantiating
+    pub label: String,
+    /// Value suggested in the prompt, also used when none is given
+    #[serde(default)]
+    pub default: String,
+}
+
+impl TemplateParameter {
+    /// Create a parameter with an empty default
+    pub fn new(name: impl Into<String>, label: impl Into<String>) -> Self {
+        Self { name: name.into(), label: label.into(), default: String::new() }
+    }
+
+    /// Set the suggested value
+    pub fn with_default(mut self, default: impl Into<String>) -> Self {
+        self.default = default.into();
+        self
+    }
+
+    /// The placeholder standing for this parameter, e.g. `{{entity}}`
+    pub fn placeholder(&self) -> String {
+        format!("{{{{{}}}}}", self.name)
+    }
+}
+
+/// Replace `{{name}}` placeholders with their values
+pub fn substitute(text: &str, values: &HashMap<String, String>) -> String {
+    values.iter().fold(text.to_string(), |text, (name, value)| text.replace(&format!("{{{{{}}}}}", name), value))
+}
+
+impl Subgraph {
+    /// Whether any node, edge, or group ID is also used in `graph`
+    fn clashes_with(&self, graph: &ProjectGraph) -> bool {
+        self.graph.nodes.keys().any(|id| graph.nodes.contains_key(id))
+            || self.graph.edges.keys().any(|id| graph.edges.contains_key(id))
+            || self.graph.groups.keys().chain([&self.group.id]).any(|id| graph.groups.contains_key(id))
+    }
+
+    /// Give every node, edge, and group fresh IDs, e.g. so a duplicated
+    /// composite can be expanded next to the original
+    fn renumber(&mut self) {
+        let nodes: HashMap<NodeId, NodeId> = self.graph.nodes.keys().map(|id| (*id, Uuid::new_v4())).collect();
+        let edges: HashMap<EdgeId, EdgeId> = self.graph.edges.keys().map(|id| (*id, Uuid::new_v4())).collect();
+        let groups: HashMap<Uuid, Uuid> =
+            self.graph.groups.keys().chain([&self.group.id]).map(|id| (*id, Uuid::new_v4())).collect();
+        let node = |id: &NodeId| nodes.get(id).copied().unwrap_or(*id);
+        let group = |id: &Uuid| groups.get(id).copied().unwrap_or(*id);
+
+        let graph = &mut self.graph;
+        graph.nodes = std::mem::take(&mut graph.nodes)
+            .into_values()
+            .map(|mut n| {
+                n.id = node(&n.id);
+                n.group_id = n.group_id.as_ref().map(group);
+                for field in &mut n.fields {
+                    let edge = field.metadata.get(RELATIONSHIP_METADATA_KEY).and_then(|v| v.as_str()).and_then(|v| v.parse().ok());
+                    if let Some(new_edge) = edge.and_then(|edge: EdgeId| edges.get(&edge)) {
+                        field.metadata.insert(RELATIONSHIP_METADATA_KEY.to_string(), ConfigValue::String(new_edge.to_string()));
+                    }
+                }
+                (n.id, n)
+            })
+            .collect();
+        graph.edges = std::mem::take(&mut graph.edges)
+            .into_values()
+            .map(|mut e| {
+                e.id = edges[&e.id];
+                e.from_node = node(&e.from_node);
+                e.to_node = node(&e.to_node);
+                (e.id, e)
+            })
+            .collect();
+        graph.groups = std::mem::take(&mut graph.groups)
+            .into_values()
+            .map(|mut g| {
+                g.id = group(&g.id);
+                g.parent_id = g.parent_id.as_ref().map(group);
+                g.node_ids = g.node_ids.iter().map(node).collect();
+                (g.id, g)
+            })
+            .collect();
+        graph.clear_selection();
+
+        self.group.id = group(&self.group.id);
+        self.group.node_ids = self.group.node_ids.iter().map(node).collect();
+        for port in &mut self.ports {
+            port.node_id = node(&port.node_id);
+        }
+    }
+}
+
+impl Node {
+    /// A fresh copy of a composite template with its parameters filled in
+    ///
+    /// Parameters missing from `values` take their default. The copy gets
+    /// new IDs throughout and no longer has parameters.
+    pub fn instantiate_template(&self, values: &HashMap<String, String>) -> Node {
+        let mut node = self.clone();
+        node.id = Uuid::new_v4();
+        node.group_id = None;
+        node.selected = false;
+        if let Some(sub) = &mut node.subgraph {
+            let filled: HashMap<String, String> = sub.parameters.iter()
+                .map(|p| (p.name.clone(), values.get(&p.name).unwrap_or(&p.default).clone()))
+                .collect();
+            sub.parameters.clear();
+            sub.renumber();
+            substitute_node(&mut node, &filled);
+        }
+        node
+    }
+}
+
+/// Fill placeholders in a node and, for composites, everything inside it
+fn substitute_node(node: &mut Node, values: &HashMap<String, String>) {
+    node.name = substitute(&node.name, values);
+    node.description = node.description.as_deref().map(|d| substitute(d, values));
+    node.namespace = node.namespace.iter().map(|segment| substitute(segment, values)).collect();
+    for value in node.config.values_mut() {
+        substitute_config(value, values);
+    }
+    for port in node.ports.inputs.iter_mut().chain(node.ports.outputs.iter_mut()) {
+        port.name = substitute(&port.name, values);
+    }
+
+    let Some(sub) = &mut node.subgraph else {
+        return;
+    };
+    sub.graph.meta.name = substitute(&sub.graph.meta.name, values);
+    sub.group.name = substitute(&sub.group.name, values);
+    for inner in sub.graph.nodes.values_mut() {
+        substitute_node(inner, values);
+    }
+    for group in sub.graph.groups.values_mut() {
+        group.name = substitute(&group.name, values);
+    }
+    for edge in sub.graph.edges.values_mut() {
+        edge.label = edge.label.as_deref().map(|label| substitute(label, values));
+    }
+}
+
+fn substitute_config(value: &mut ConfigValue, values: &HashMap<String, String>) {
+    match value {
+        ConfigValue::String(s) => *s = substitute(s, values),
+        ConfigValue::Array(items) => items.iter_mut().for_each(|item| substitute_config(item, values)),
+        ConfigValue::Object(fields) => fields.values_mut().for_each(|field| substitute_config(field, values)),
+        _ => {}
+    }
+}
+
+impl ProjectGraph {
+    /// Pin a copy of a composite node to the palette as a template
+    ///
+    /// The node may come from this graph or from inside one of its
+    /// composites. A template with the same name is replaced.
+    pub fn pin_template(&mut self, mut template: Node) -> EngineResult<()> {
+        if !template.is_composite() {
+            return Err(EngineError::Custom(format!("'{}' is not a composite node", template.name)));
+        }
+        template.group_id = None;
+        template.namespace.clear();
+        template.selected = false;
+        self.templates.retain(|t| t.name != template.name);
+        self.templates.push(template);
+        self.touch();
+        Ok(())
+    }
+
+    /// Remove a template from the palette
+    pub fn unpin_template(&mut self, index: usize) -> Option<Node> {
+        let template = (index < self.templates.len()).then(|| self.templates.remove(index));
+        self.dirty |= template.is_some();
+        template
+    }
+
+    /// Collapse a group into a composite node
+    ///
+    /// The group's nodes, nested groups, and the edges between them move into
+    /// the composite's subgraph. Edges crossing the group boundary are
+    /// reattached to a composite port per inner port they used. Returns the
+    /// composite's ID.
+    pub fn collapse_group(&mut self, group_id: Uuid) -> EngineResult<NodeId> {
+        let group = self.groups.get(&group_id).cloned()
+            .ok_or_else(|| EngineError::Custom(format!("Group not found: {}", group_id)))?;
+
+        // Nested groups go along with the group
+        let mut group_ids = HashSet::from([group_id]);
+        loop {
+            let nested: Vec<Uuid> = self.groups.values()
+                .filter(|g| !group_ids.contains(&g.id) && g.parent_id.is_some_and(|p| group_ids.contains(&p)))
+                .map(|g| g.id)
+                .collect();
+            if nested.is_empty() {
+                break;
+            }
+            group_ids.extend(nested);
+        }
+        let node_ids: HashSet<NodeId> = group_ids.iter()
+            .flat_map(|id| self.groups[id].node_ids.iter().copied())
+            .filter(|id| self.nodes.contains_key(id))
+            .collect();
+        if node_ids.is_empty() {
+            return Err(EngineError::Custom(format!("Group '{}' has no nodes to collapse", group.name)));
+        }
+
+        let mut inner = ProjectGraph::new(ProjectMeta { name: group.name.clone(), ..self.meta.clone() });
+        let mut composite = Node::new(COMPOSITE_COMPONENT, &group.name);
+        composite.category = ComponentCategory::Custom;
+        composite.icon = Some("🧩".to_string());
+        composite.description = group.description.clone();
+        composite.position = group.position;
+        composite.group_id = group.parent_id.filter(|parent| self.groups.contains_key(parent));
+        let mut ports = Vec::new();
+
+        let mut edge_ids: Vec<EdgeId> = self.edges.keys().copied().collect();
+        edge_ids.sort();
+        for id in edge_ids {
+            let edge = &self.edges[&id];
+            match (node_ids.contains(&edge.from_node), node_ids.contains(&edge.to_node)) {
+                (true, true) => {
+                    let edge = self.edges.remove(&id).expect("edge listed above");
+                    inner.edges.insert(id, edge);
+                }
+                (true, false) => {
+                    let port = boundary_port(&mut composite, &mut ports, &self.nodes[&edge.from_node], &edge.from_port, true);
+                    let edge = self.edges.get_mut(&id).expect("edge listed above");
+                    edge.from_node = composite.id;
+                    edge.from_port = port;
+                }
+                (false, true) => {
+                    let port = boundary_port(&mut composite, &mut ports, &self.nodes[&edge.to_node], &edge.to_port, false);
+                    let edge = self.edges.get_mut(&id).expect("edge listed above");
+                    edge.to_node = composite.id;
+                    edge.to_port = port;
+                }
+                (false, false) => {}
+            }
+        }
+
+        for id in &node_ids {
+            if let Some(node) = self.nodes.remove(id) {
+                inner.nodes.insert(*id, node);
+            }
+            self.selected_nodes.remove(id);
+        }
+        for id in &group_ids {
+            if let Some(nested) = self.groups.remove(id).filter(|g| g.id != group_id) {
+                inner.groups.insert(*id, nested);
+            }
+            self.selected_groups.remove(id);
+        }
+        for other in self.groups.values_mut() {
+            other.node_ids.retain(|id| !node_ids.contains(id));
+        }
+        if let Some(parent) = composite.group_id.and_then(|parent| self.groups.get_mut(&parent)) {
+            parent.add_node(composite.id);
+        }
+
+        let composite_id = composite.id;
+        composite.subgraph = Some(Box::new(Subgraph { graph: inner, group, ports, parameters: Vec::new() }));
+        self.nodes.insert(composite_id, composite);
+        self.touch();
+        Ok(composite_id)
+    }
+
+    /// Expand a composite node back into its group
+    ///
+    /// The inner nodes are placed relative to where the composite was moved,
+    /// and edges on its ports are reattached to the inner ports. Edges on a
+    /// composite port with nothing inside are dropped. Returns the group's ID.
+    pub fn expand_composite(&mut self, node_id: NodeId) -> EngineResult<Uuid> {
+        let node = self.nodes.get(&node_id).ok_or_else(|| EngineError::NodeNotFound(node_id.to_string()))?;
+        if !node.is_composite() {
+            return Err(EngineError::Custom(format!("'{}' is not a composite node", node.name)));
+        }
+        let mut node = self.nodes.remove(&node_id).expect("checked above");
+        let mut sub = *node.subgraph.take().expect("checked above");
+        self.selected_nodes.remove(&node_id);
+        for group in self.groups.values_mut() {
+            group.remove_node(&node_id);
+        }
+        if sub.clashes_with(self) {
+            sub.renumber();
+        }
+
+        let (dx, dy) = (node.position.x - sub.group.position.x, node.position.y - sub.group.position.y);
+        let mut group = sub.group;
+        group.position.x += dx;
+        group.position.y += dy;
+        group.parent_id = node.group_id.filter(|parent| self.groups.contains_key(parent));
+
+        // Nodes added while editing the subgraph join the group too
+        group.node_ids.clear();
+        for (id, mut inner) in sub.graph.nodes {
+            inner.position.x += dx;
+            inner.position.y += dy;
+            // A disabled composite's nodes stay out of the design
+            inner.enabled &= node.enabled;
+            if inner.group_id.is_none_or(|g| g == group.id || !sub.graph.groups.contains_key(&g)) {
+                inner.group_id = Some(group.id);
+                group.add_node(id);
+            }
+            self.nodes.insert(id, inner);
+        }
+        for (id, mut nested) in sub.graph.groups {
+            nested.position.x += dx;
+            nested.position.y += dy;
+            nested.parent_id = Some(nested.parent_id.filter(|p| *p != group.id).unwrap_or(group.id));
+            self.groups.insert(id, nested);
+        }
+        self.groups.insert(group.id, group.clone());
+        self.edges.extend(sub.graph.edges);
+
+        // Reattach edges on the composite's ports to the inner ports
+        let outer: Vec<EdgeId> = self.edges.values().filter(|e| e.connects_to(node_id)).map(|e| e.id).collect();
+        for id in outer {
+            let mut edge = self.edges.remove(&id).expect("edge listed above");
+            let inner_port = |port: &PortId, is_output: bool| {
+                sub.ports.iter()
+                    .find(|p| p.port == *port && p.is_output == is_output && self.nodes.contains_key(&p.node_id))
+                    .map(|p| (p.node_id, p.node_port.clone()))
+            };
+            let mut attached = true;
+            if edge.from_node == node_id {
+                match inner_port(&edge.from_port, true) {
+                    Some((inner, port)) => (edge.from_node, edge.from_port) = (inner, port),
+                    None => attached = false,
+                }
+            }
+            if edge.to_node == node_id {
+                match inner_port(&edge.to_port, false) {
+                    Some((inner, port)) => (edge.to_node, edge.to_port) = (inner, port),
+                    None => attached = false,
+                }
+            }
+            if attached {
+                self.edges.insert(id, edge);
+            }
+        }
+
+        self.touch();
+        Ok(group.id)
+    }
+
+    /// Replace a composite's subgraph after it was edited on its own
+    ///
+    /// Composite ports whose inner node was deleted are removed, along with
+    /// the edges using them.
+    pub fn set_subgraph(&mut self, node_id: NodeId, graph: ProjectGraph) -> EngineResult<()> {
+        let node = self.nodes.get_mut(&node_id).ok_or_else(|| EngineError::NodeNotFound(node_id.to_string()))?;
+        let name = node.name.clone();
+        let sub = node.subgraph.as_mut().ok_or_else(|| EngineError::Custom(format!("'{}' is not a composite node", name)))?;
+
+        let (kept, removed): (Vec<BoundaryPort>, Vec<BoundaryPort>) =
+            std::mem::take(&mut sub.ports).into_iter().partition(|p| graph.has_node(p.node_id));
+        sub.ports = kept;
+        sub.graph = graph;
+        for port in &removed {
+            node.ports.remove(&port.port);
+        }
+
+        let stale: Vec<EdgeId> = self.edges.values()
+            .filter(|e| removed.iter().any(|p| if p.is_output {
+                e.from_node == node_id && e.from_port == p.port
+            } else {
+                e.to_node == node_id && e.to_port == p.port
+            }))
+            .map(|e| e.id)
+            .collect();
+        for id in stale {
+            self.remove_edge(id);
+        }
+        self.touch();
+        Ok(())
+    }
+
+    /// A copy of the graph with every composite node expanded, recursively
+    ///
+    /// Code generation works on the flattened graph, so composites behave
+    /// exactly like the groups they were collapsed from.
+    pub fn flatten(&self) -> ProjectGraph {
+        let mut flat = self.clone();
+        while let Some(id) = flat.nodes.values().find(|n| n.is_composite()).map(|n| n.id) {
+            if flat.expand_composite(id).is_err() {
+                break;
+            }
+        }
+        flat
+    }
+}
+
+/// Composite port for edges using `port` of an inner node, added on first use
+fn boundary_port(composite: &mut Node, ports: &mut Vec<BoundaryPort>, inner: &Node, port: &str, is_output: bool) -> PortId {
+    let existing = ports.iter().find(|p| p.node_id == inner.id && p.node_port == port && p.is_output == is_output);
+    if let Some(existing) = existing {
+        return existing.port.clone();
+    }
+
+    let id = composite.ports.unique_id(&format!("{} {}", inner.name, port));
+    let mut boundary = inner.get_port(port)
+        .filter(|p| p.is_output() == is_output)
+        .cloned()
+        .unwrap_or_else(|| if is_output {
+            Port::data_out(port, port, DataType::Any)
+        } else {
+            Port::data_in(port, port, DataType::Any)
+        });
+    boundary.id = id.clone();
+    boundary.name = format!("{} {}", inner.name, boundary.name);
+    if is_output {
+        composite.ports.add_output(boundary);
+    } else {
+        composite.ports.add_input(boundary);
+    }
+
+    ports.push(BoundaryPort { port: id.clone(), node_id: inner.id, node_port: port.to_string(), is_output });
+    id
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::edge::Edge;
+
+    /// User and Post grouped as "Blog", with an endpoint outside reading
+    /// both and a relationship between them
+    fn blog() -> (ProjectGraph, Uuid, [NodeId; 3]) {
+        let mut graph = ProjectGraph::with_name("test");
+        let user = graph.add_node(Node::new_entity("User"));
+        let post = graph.add_node(Node::new_entity("Post"));
+        let endpoint = graph.add_node(Node::new_rest_endpoint("Posts"));
+        graph.add_edge(Edge::dependency(user, post)).unwrap();
+        graph.add_edge(Edge::dependency(user, endpoint)).unwrap();
+        graph.add_edge(Edge::dependency(post, endpoint)).unwrap();
+        graph.add_edge(Edge::dependency(endpoint, post)).unwrap();
+
+        graph.select_node(user);
+        graph.select_node(post);
+        let group = graph.group_selected_nodes("Blog").unwrap();
+        graph.clear_selection();
+        (graph, group, [user, post, endpoint])
+    }
+
+    #[test]
+    fn test_collapse_and_expand() {
+        let (mut graph, group, [user, post, endpoint]) = blog();
+
+        let composite = graph.collapse_group(group).unwrap();
+        assert_eq!(graph.node_count(), 2);
+        assert_eq!(graph.group_count(), 0);
+        let node = graph.get_node(composite).unwrap();
+        assert!(node.is_composite());
+        assert_eq!(node.name, "Blog");
+        let sub = node.subgraph.as_ref().unwrap();
+        assert_eq!(sub.graph.node_count(), 2);
+        assert_eq!(sub.graph.edge_count(), 1);
+        // One output per inner node, one input for the edge coming back
+        assert_eq!(sub.ports.len(), 3);
+        assert_eq!(node.ports.outputs.len(), 2);
+        assert_eq!(node.ports.inputs.len(), 1);
+        assert_eq!(graph.edge_count(), 3);
+        assert!(graph.edges().all(|e| e.connects_to(composite) && e.connects_to(endpoint)));
+
+        // Moving the composite moves what's inside
+        assert!(!graph.has_node(user));
+        graph.get_node_mut(composite).unwrap().position.x += 100.0;
+        let expanded = graph.expand_composite(composite).unwrap();
+        assert_eq!(expanded, group);
+        assert!(!graph.has_node(composite));
+        assert_eq!(graph.node_count(), 3);
+        assert_eq!(graph.edge_count(), 4);
+        assert!(graph.get_group(group).unwrap().contains_node(&post));
+        assert_eq!(graph.get_node(user).unwrap().position.x, Node::new_entity("User").position.x + 100.0);
+        assert_eq!(graph.outgoing_edges(endpoint)[0].to_node, post);
+        assert_eq!(graph.incoming_edges(endpoint).len(), 2);
+
+        assert!(graph.expand_composite(endpoint).is_err());
+        assert!(graph.collapse_group(Uuid::new_v4()).is_err());
+    }
+
+    #[test]
+    fn test_flatten_duplicated_composite() {
+        let (mut graph, group, [_, _, endpoint]) = blog();
+        let composite = graph.collapse_group(group).unwrap();
+        let copy = graph.duplicate_node(composite).unwrap();
+        let port = graph.get_node(copy).unwrap().ports.outputs[0].id.clone();
+        let mut edge = Edge::dependency(copy, endpoint);
+        edge.from_port = port;
+        graph.add_edge(edge).unwrap();
+
+        let flat = graph.flatten();
+        assert!(flat.nodes().all(|n| !n.is_composite()));
+        assert_eq!(flat.node_count(), 5);
+        assert_eq!(flat.group_count(), 2);
+        assert_eq!(flat.edge_count(), 4 + 1 + 1);
+        assert_eq!(flat.find_nodes_by_name("User").len(), 2);
+        // The original graph is untouched
+        assert_eq!(graph.node_count(), 3);
+    }
+
+    #[test]
+    fn test_instantiate_template() {
+        let mut graph = ProjectGraph::with_name("test");
+        let entity = graph.add_node(Node::new_entity("{{entity}}"));
+        let mut endpoint = Node::new_rest_endpoint("list_{{entity}}").with_namespace("{{prefix}}");
+        endpoint.set_config("path", "/{{prefix}}/items");
+        let endpoint = graph.add_node(endpoint);
+        graph.add_edge(Edge::dependency(entity, endpoint)).unwrap();
+        graph.select_node(entity);
+        graph.select_node(endpoint);
+        let group = graph.group_selected_nodes("{{entity}} API").unwrap();
+        let composite = graph.collapse_group(group).unwrap();
+        graph.get_node_mut(composite).unwrap().subgraph.as_mut().unwrap().parameters = vec![
+            TemplateParameter::new("entity", "Entity name"),
+            TemplateParameter::new("prefix", "Path prefix").with_default("api"),
+        ];
+        let template = graph.get_node(composite).unwrap().clone();
+        graph.pin_template(template.clone()).unwrap();
+        graph.pin_template(template).unwrap();
+        assert_eq!(graph.templates.len(), 1);
+        assert!(graph.pin_template(Node::new_entity("User")).is_err());
+
+        let values = HashMap::from([("entity".to_string(), "Invoice".to_string())]);
+        let node = graph.templates[0].instantiate_template(&values);
+        assert_ne!(node.id, composite);
+        assert_eq!(node.name, "Invoice API");
+        let sub = node.subgraph.as_ref().unwrap();
+        assert!(sub.parameters.is_empty());
+        assert!(!sub.graph.has_node(entity));
+        assert!(!sub.graph.find_nodes_by_name("Invoice").is_empty());
+        let list = sub.graph.find_nodes_by_name("list_Invoice")[0];
+        assert_eq!(list.namespace, ["api"]);
+        assert_eq!(list.get_config("path").and_then(|v| v.as_str()), Some("/api/items"));
+        assert_eq!(sub.graph.edge_count(), 1);
+
+        // Instances flatten side by side
+        graph.add_node(node);
+        graph.add_node(graph.templates[0].instantiate_template(&HashMap::new()));
+        assert_eq!(graph.flatten().node_count(), 6);
+    }
+
+    #[test]
+    fn test_set_subgraph_drops_orphaned_ports() {
+        let (mut graph, group, [user, _, endpoint]) = blog();
+        let composite = graph.collapse_group(group).unwrap();
+
+        let mut inner = graph.get_node(composite).unwrap().subgraph.as_ref().unwrap().graph.clone();
+        inner.remove_node(user);
+        graph.set_subgraph(composite, inner).unwrap();
+
+        let node = graph.get_node(composite).unwrap();
+        assert_eq!(node.subgraph.as_ref().unwrap().ports.len(), 2);
+        assert_eq!(node.ports.outputs.len(), 1);
+        assert_eq!(graph.edges_between(composite, endpoint).len(), 2);
+        assert!(graph.set_subgraph(endpoint, ProjectGraph::with_name("x")).is_err());
+    }
+}
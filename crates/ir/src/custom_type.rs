@@ -0,0 +1,176 @@
+//! Project-defined data types
+//!
+//! A custom type such as `Email` or `Money` gives a name to a built-in
+//! representation, so fields can say what they hold rather than how. Types
+//! live in [`ProjectMeta::types`](crate::ProjectMeta::types) and fields use
+//! them as `DataType::Custom` in the [`PROJECT_DOMAIN`]. Code generation
+//! emits a newtype or type alias per type, and stores fields of the type in
+//! columns of the underlying type unless the type names its own.
+
+use serde::{Deserialize, Serialize};
+
+use imortal_core::DataType;
+
+/// Domain of `DataType::Custom` for the project's own types
+pub const PROJECT_DOMAIN: &str = "project";
+
+/// How a custom type is generated
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TypeRepresentation {
+    /// A tuple struct wrapping the underlying type, distinct from it
+    #[default]
+    Newtype,
+    /// Another name for the underlying type
+    Alias,
+}
+
+impl TypeRepresentation {
+    pub fn all() -> &'static [TypeRepresentation] {
+        &[TypeRepresentation::Newtype, TypeRepresentation::Alias]
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            TypeRepresentation::Newtype => "Newtype",
+            TypeRepresentation::Alias => "Type alias",
+        }
+    }
+}
+
+/// A named type fields can use
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CustomType {
+    /// Rust name of the type, e.g. "Email"
+    pub name: String,
+
+    /// What values of the type are
+    #[serde(default)]
+    pub description: Option<String>,
+
+    /// Built-in type the values are stored as
+    pub underlying: DataType,
+
+    #[serde(default)]
+    pub representation: TypeRepresentation,
+
+    /// Module serializing the values, for `#[serde(with = "...")]`, instead
+    /// of serializing them as the underlying type
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub serde_with: Option<String>,
+
+    /// Column type, instead of the underlying type's
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sql_type: Option<String>,
+}
+
+impl CustomType {
+    /// Create a newtype over `underlying`
+    pub fn new(name: impl Into<String>, underlying: DataType) -> Self {
+        Self {
+            name: name.into(),
+            description: None,
+            underlying,
+            representation: TypeRepresentation::Newtype,
+            serde_with: None,
+            sql_type: None,
+        }
+    }
+
+    /// Set the description
+    pub fn with_description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// Generate the type as an alias rather than a newtype
+    pub fn as_alias(mut self) -> Self {
+        self.representation = TypeRepresentation::Alias;
+        self
+    }
+
+    /// Store values of the type in columns of `sql_type`
+    pub fn with_sql_type(mut self, sql_type: impl Into<String>) -> Self {
+        self.sql_type = Some(sql_type.into());
+        self
+    }
+
+    /// Serialize values of the type with the module at `path`
+    pub fn with_serde_with(mut self, path: impl Into<String>) -> Self {
+        self.serde_with = Some(path.into());
+        self
+    }
+
+    /// Data type of fields holding values of this type
+    pub fn data_type(&self) -> DataType {
+        DataType::Custom { domain: PROJECT_DOMAIN.to_string(), type_name: self.name.clone() }
+    }
+
+    /// Whether the name is a PascalCase identifier, usable as a Rust type
+    pub fn has_valid_name(&self) -> bool {
+        let mut chars = self.name.chars();
+        chars.next().is_some_and(|c| c.is_ascii_uppercase()) && chars.all(|c| c.is_ascii_alphanumeric())
+    }
+
+    /// Whether values can be stored as `underlying`: a built-in scalar,
+    /// optionally in a list or map, not an entity or another custom type
+    pub fn is_valid_underlying(data_type: &DataType) -> bool {
+        match data_type {
+            DataType::Optional(inner) | DataType::Array(inner) => Self::is_valid_underlying(inner),
+            DataType::Map { key, value } => Self::is_valid_underlying(key) && Self::is_valid_underlying(value),
+            DataType::Any
+            | DataType::Trigger
+            | DataType::Entity(_)
+            | DataType::Reference(_)
+            | DataType::Custom { .. } => false,
+            _ => true,
+        }
+    }
+}
+
+/// Name of the project type `data_type` refers to, if it's one
+pub fn project_type_name(data_type: &DataType) -> Option<&str> {
+    match data_type {
+        DataType::Custom { domain, type_name } if domain == PROJECT_DOMAIN => Some(type_name),
+        DataType::Optional(inner) | DataType::Array(inner) => project_type_name(inner),
+        _ => None,
+    }
+}
+
+/// Point references to the project type `old` at `new`, returning whether
+/// `data_type` changed
+pub fn rename_project_type(data_type: &mut DataType, old: &str, new: &str) -> bool {
+    match data_type {
+        DataType::Custom { domain, type_name } if domain == PROJECT_DOMAIN && type_name == old => {
+            *type_name = new.to_string();
+            true
+        }
+        DataType::Optional(inner) | DataType::Array(inner) => rename_project_type(inner, old, new),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_custom_type() {
+        let email = CustomType::new("Email", DataType::String).with_sql_type("CITEXT");
+        assert!(email.has_valid_name());
+        assert!(!CustomType::new("email_address", DataType::String).has_valid_name());
+        assert_eq!(project_type_name(&email.data_type()), Some("Email"));
+        assert_eq!(project_type_name(&DataType::Array(Box::new(email.data_type()))), Some("Email"));
+        assert_eq!(project_type_name(&DataType::String), None);
+
+        assert!(CustomType::is_valid_underlying(&DataType::Array(Box::new(DataType::Int64))));
+        assert!(!CustomType::is_valid_underlying(&DataType::Entity("User".to_string())));
+        assert!(!CustomType::is_valid_underlying(&email.data_type()));
+
+        // Optional settings are left out of the project file
+        let json = serde_json::to_string(&CustomType::new("Money", DataType::Int64).as_alias()).unwrap();
+        assert!(!json.contains("sql_type") && json.contains(r#""representation":"alias""#), "{}", json);
+        let restored: CustomType = serde_json::from_str(&serde_json::to_string(&email).unwrap()).unwrap();
+        assert_eq!(restored, email);
+    }
+}
@@ -9,6 +9,10 @@ use uuid::Uuid;
 
 use imortal_core::{ConfigValue, ConnectionType, EdgeId, NodeId, PortId, RelationType};
 
+/// Port a relationship edge uses when it joins whole entities rather than
+/// particular fields
+pub const ENTITY_PORT: &str = "entity";
+
 /// An edge (connection) between two nodes
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Edge {
@@ -36,6 +40,19 @@ pub struct Edge {
     /// User-defined label for this edge
     pub label: Option<String>,
 
+    /// Name of the association a polymorphic relationship belongs to, such
+    /// as "commentable"; its edges from one entity form the target set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub association: Option<String>,
+
+    /// How a caching edge's endpoint caches its responses
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache: Option<CachePolicy>,
+
+    /// Flag of a gating edge's feature flag node its target sits behind
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub flag: Option<String>,
+
     /// Whether this edge is enabled (disabled edges are shown dimmed)
     pub enabled: bool,
 
@@ -76,9 +93,12 @@ impl Edge {
             connection_type: ConnectionType::DataFlow,
             data_mapping: None,
             label: None,
+            association: None,
+            cache: None,
+            flag: None,
             enabled: true,
             selected: false,
-            style: EdgeStyle::default(),
+            style: EdgeStyle::for_connection(&ConnectionType::DataFlow),
             description: None,
             metadata: HashMap::new(),
             waypoints: Vec::new(),
@@ -93,9 +113,7 @@ impl Edge {
         to_node: NodeId,
         to_port: impl Into<PortId>,
     ) -> Self {
-        let mut edge = Self::new(from_node, from_port, to_node, to_port);
-        edge.connection_type = ConnectionType::DataFlow;
-        edge
+        Self::new(from_node, from_port, to_node, to_port)
     }
 
     /// Create a trigger edge
@@ -105,10 +123,7 @@ impl Edge {
         to_node: NodeId,
         to_port: impl Into<PortId>,
     ) -> Self {
-        let mut edge = Self::new(from_node, from_port, to_node, to_port);
-        edge.connection_type = ConnectionType::Trigger;
-        edge.style.line_style = LineStyle::Dashed;
-        edge
+        Self::new(from_node, from_port, to_node, to_port).with_connection_type(ConnectionType::Trigger)
     }
 
     /// Create a navigation edge
@@ -118,10 +133,7 @@ impl Edge {
         to_node: NodeId,
         to_port: impl Into<PortId>,
     ) -> Self {
-        let mut edge = Self::new(from_node, from_port, to_node, to_port);
-        edge.connection_type = ConnectionType::Navigation;
-        edge.style.color = EdgeColor::Blue;
-        edge
+        Self::new(from_node, from_port, to_node, to_port).with_connection_type(ConnectionType::Navigation)
     }
 
     /// Create a relationship edge between entities
@@ -130,37 +142,61 @@ impl Edge {
         to_node: NodeId,
         relation_type: RelationType,
     ) -> Self {
-        let mut edge = Self::new(from_node, "entity", to_node, "entity");
-        edge.connection_type = ConnectionType::Relationship(relation_type);
-        edge.style.color = EdgeColor::Purple;
-        edge.style.arrow_end = match relation_type {
-            RelationType::OneToOne => ArrowStyle::Arrow,
-            RelationType::OneToMany => ArrowStyle::ManyArrow,
-            RelationType::ManyToOne => ArrowStyle::Arrow,
-            RelationType::ManyToMany => ArrowStyle::ManyArrow,
-        };
-        edge.style.arrow_start = match relation_type {
-            RelationType::OneToOne => ArrowStyle::None,
-            RelationType::OneToMany => ArrowStyle::None,
-            RelationType::ManyToOne => ArrowStyle::ManyArrow,
-            RelationType::ManyToMany => ArrowStyle::ManyArrow,
-        };
+        Self::relationship_on_fields(from_node, ENTITY_PORT, to_node, ENTITY_PORT, relation_type)
+    }
+
+    /// Create a relationship edge joining a field of each entity, such as
+    /// `User.id` and `Post.author_id`
+    pub fn relationship_on_fields(
+        from_node: NodeId,
+        from_field: impl Into<PortId>,
+        to_node: NodeId,
+        to_field: impl Into<PortId>,
+        relation_type: RelationType,
+    ) -> Self {
+        Self::new(from_node, from_field, to_node, to_field)
+            .with_connection_type(ConnectionType::Relationship(relation_type))
+    }
+
+    /// Create one edge of a polymorphic relationship, from the entity that
+    /// belongs to one of several others to one of those targets
+    pub fn polymorphic(child: NodeId, association: impl Into<String>, target: NodeId) -> Self {
+        let mut edge = Self::relationship(child, target, RelationType::Polymorphic);
+        edge.association = Some(association.into());
+        edge
+    }
+
+    /// Create an inheritance edge from an entity to the entity it extends
+    pub fn inheritance(child: NodeId, parent: NodeId) -> Self {
+        Self::new(child, ENTITY_PORT, parent, ENTITY_PORT).with_connection_type(ConnectionType::Inheritance)
+    }
+
+    /// Create a caching edge from an endpoint's responses to the cache
+    /// holding them
+    pub fn caches(endpoint: NodeId, cache: NodeId, policy: CachePolicy) -> Self {
+        let mut edge = Self::new(endpoint, "response", cache, "value").with_connection_type(ConnectionType::Caches);
+        edge.cache = Some(policy);
+        edge
+    }
+
+    /// Create a gating edge putting a node behind a flag of a feature flag
+    /// node
+    pub fn gates(flags: NodeId, target: NodeId, flag: impl Into<String>) -> Self {
+        let mut edge = Self::new(flags, "gate", target, "request").with_connection_type(ConnectionType::Gates);
+        edge.flag = Some(flag.into());
         edge
     }
 
     /// Create a dependency edge
     pub fn dependency(from_node: NodeId, to_node: NodeId) -> Self {
-        let mut edge = Self::new(from_node, "out", to_node, "in");
-        edge.connection_type = ConnectionType::Dependency;
-        edge.style.line_style = LineStyle::Dotted;
-        edge.style.color = EdgeColor::Gray;
-        edge
+        Self::new(from_node, "out", to_node, "in").with_connection_type(ConnectionType::Dependency)
     }
 
     // ========== Builder Methods ==========
 
-    /// Set the connection type
+    /// Set the connection type, along with that type's default style
     pub fn with_connection_type(mut self, connection_type: ConnectionType) -> Self {
+        self.style = EdgeStyle::for_connection(&connection_type);
         self.connection_type = connection_type;
         self
     }
@@ -265,6 +301,43 @@ impl Edge {
         }
     }
 
+    /// Field of the source entity a relationship joins on, if one was picked
+    pub fn from_field(&self) -> Option<&str> {
+        Some(self.from_port.as_str()).filter(|port| self.is_relationship() && *port != ENTITY_PORT)
+    }
+
+    /// Field of the target entity a relationship joins on, if one was picked
+    pub fn to_field(&self) -> Option<&str> {
+        Some(self.to_port.as_str()).filter(|port| self.is_relationship() && *port != ENTITY_PORT)
+    }
+
+    /// Foreign key a relationship joins on, when it names a field on both
+    /// entities; many-to-many relationships go through a join table and
+    /// polymorphic ones a type and id column pair instead
+    pub fn relationship_key(&self) -> Option<RelationshipKey<'_>> {
+        let (from_field, to_field) = (self.from_field()?, self.to_field()?);
+        match self.relationship_type()? {
+            RelationType::OneToOne | RelationType::OneToMany => Some(RelationshipKey {
+                child: self.to_node,
+                field: to_field,
+                parent: self.from_node,
+                references: from_field,
+            }),
+            RelationType::ManyToOne => Some(RelationshipKey {
+                child: self.from_node,
+                field: from_field,
+                parent: self.to_node,
+                references: to_field,
+            }),
+            RelationType::ManyToMany | RelationType::Polymorphic => None,
+        }
+    }
+
+    /// Check if this is an edge of a polymorphic relationship
+    pub fn is_polymorphic(&self) -> bool {
+        self.relationship_type() == Some(RelationType::Polymorphic)
+    }
+
     /// Check if this is a navigation edge
     pub fn is_navigation(&self) -> bool {
         matches!(self.connection_type, ConnectionType::Navigation)
@@ -275,8 +348,35 @@ impl Edge {
         matches!(self.connection_type, ConnectionType::Dependency)
     }
 
+    /// Check if this is an inheritance edge
+    pub fn is_inheritance(&self) -> bool {
+        matches!(self.connection_type, ConnectionType::Inheritance)
+    }
+
+    /// Check if this is a caching edge
+    pub fn is_caching(&self) -> bool {
+        matches!(self.connection_type, ConnectionType::Caches)
+    }
+
+    /// Check if this is a feature flag gating edge
+    pub fn is_gate(&self) -> bool {
+        matches!(self.connection_type, ConnectionType::Gates)
+    }
+
     // ========== Mutation Methods ==========
 
+    /// Go back to the default style for this edge's connection type
+    pub fn reset_style(&mut self) {
+        self.style = EdgeStyle::for_connection(&self.connection_type);
+    }
+
+    /// Join a relationship on a field of each entity, or on the whole entity
+    /// for `None`
+    pub fn set_fields(&mut self, from_field: Option<&str>, to_field: Option<&str>) {
+        self.from_port = from_field.unwrap_or(ENTITY_PORT).to_string();
+        self.to_port = to_field.unwrap_or(ENTITY_PORT).to_string();
+    }
+
     /// Toggle selection state
     pub fn toggle_selected(&mut self) {
         self.selected = !self.selected;
@@ -352,6 +452,9 @@ impl Default for Edge {
             connection_type: ConnectionType::DataFlow,
             data_mapping: None,
             label: None,
+            association: None,
+            cache: None,
+            flag: None,
             enabled: true,
             selected: false,
             style: EdgeStyle::default(),
@@ -377,6 +480,93 @@ impl std::hash::Hash for Edge {
     }
 }
 
+/// The foreign key field of a relationship and the field it references
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RelationshipKey<'a> {
+    /// Entity holding the foreign key
+    pub child: NodeId,
+    /// Foreign key field on the child
+    pub field: &'a str,
+    /// Entity referenced
+    pub parent: NodeId,
+    /// Field of the parent the foreign key references
+    pub references: &'a str,
+}
+
+/// A polymorphic relationship: an entity belonging to one of several
+/// target entities, stored as a type and id column pair
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PolymorphicAssociation {
+    /// Entity holding the type and id columns
+    pub child: NodeId,
+    /// Association name the columns are named after, e.g. "commentable"
+    pub name: String,
+    /// Entities the child can belong to, sorted by name
+    pub targets: Vec<NodeId>,
+}
+
+impl PolymorphicAssociation {
+    /// Column naming which target a row belongs to, e.g. `commentable_type`
+    pub fn type_column(&self) -> String {
+        format!("{}_type", self.name)
+    }
+
+    /// Column holding the id of the target row, e.g. `commentable_id`
+    pub fn id_column(&self) -> String {
+        format!("{}_id", self.name)
+    }
+}
+
+/// How an endpoint caches its responses: reads are served from the cache
+/// until they expire, and writes invalidate the key they render
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CachePolicy {
+    /// Seconds a cached response stays fresh
+    pub ttl_seconds: u64,
+
+    /// Key responses are cached under, with `{method}`, `{path}`, and
+    /// `{query}` replaced by the request's
+    pub key_template: String,
+}
+
+impl CachePolicy {
+    /// Placeholders a key template can use
+    pub const PLACEHOLDERS: [&'static str; 3] = ["method", "path", "query"];
+
+    /// Create a policy
+    pub fn new(ttl_seconds: u64, key_template: impl Into<String>) -> Self {
+        Self { ttl_seconds, key_template: key_template.into() }
+    }
+
+    /// Placeholders in the key template in order, or what's wrong with
+    /// its braces
+    pub fn placeholders(&self) -> Result<Vec<&str>, String> {
+        let mut placeholders = Vec::new();
+        let mut rest = self.key_template.as_str();
+        while let Some(open) = rest.find(['{', '}']) {
+            if rest[open..].starts_with('}') {
+                return Err("'}' without a matching '{'".to_string());
+            }
+            let after = &rest[open + 1..];
+            let close = after.find('}').ok_or("'{' without a matching '}'")?;
+            let name = &after[..close];
+            if name.contains('{') {
+                return Err("placeholders can't be nested".to_string());
+            }
+            placeholders.push(name);
+            rest = &after[close + 1..];
+        }
+        Ok(placeholders)
+    }
+}
+
+impl Default for CachePolicy {
+    fn default() -> Self {
+        Self::new(300, "{path}?{query}")
+    }
+}
+
 /// Data mapping configuration for transforming data between ports
 #[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct DataMapping {
@@ -536,6 +726,65 @@ pub struct EdgeStyle {
     pub opacity: f32,
 }
 
+impl EdgeStyle {
+    /// Default style for a kind of connection, so each kind reads
+    /// differently on the canvas
+    pub fn for_connection(connection_type: &ConnectionType) -> Self {
+        let base = Self::default();
+        match connection_type {
+            ConnectionType::DataFlow => Self { arrow_end: ArrowStyle::FilledArrow, ..base },
+            ConnectionType::Navigation => Self { color: EdgeColor::Blue, ..base },
+            ConnectionType::Relationship(relation_type) => {
+                let many_end = matches!(relation_type, RelationType::OneToMany | RelationType::ManyToMany);
+                let many_start = matches!(
+                    relation_type,
+                    RelationType::ManyToOne | RelationType::ManyToMany | RelationType::Polymorphic
+                );
+                // Dashed: each edge is one of the targets a row may belong to
+                let polymorphic = *relation_type == RelationType::Polymorphic;
+                Self {
+                    color: EdgeColor::Purple,
+                    thickness: 1.5,
+                    line_style: if polymorphic { LineStyle::Dashed } else { LineStyle::Solid },
+                    arrow_start: if many_start { ArrowStyle::ManyArrow } else { ArrowStyle::None },
+                    arrow_end: if many_end { ArrowStyle::ManyArrow } else { ArrowStyle::Arrow },
+                    ..base
+                }
+            }
+            ConnectionType::Trigger => Self {
+                color: EdgeColor::Orange,
+                line_style: LineStyle::Dashed,
+                arrow_end: ArrowStyle::FilledArrow,
+                ..base
+            },
+            ConnectionType::Dependency => Self {
+                color: EdgeColor::Gray,
+                line_style: LineStyle::Dotted,
+                thickness: 1.5,
+                ..base
+            },
+            // UML generalization: a hollow triangle pointing at the parent
+            ConnectionType::Inheritance => Self {
+                color: EdgeColor::Teal,
+                arrow_end: ArrowStyle::Triangle,
+                ..base
+            },
+            ConnectionType::Caches => Self {
+                color: EdgeColor::Green,
+                line_style: LineStyle::Dashed,
+                thickness: 1.5,
+                ..base
+            },
+            ConnectionType::Gates => Self {
+                color: EdgeColor::Yellow,
+                line_style: LineStyle::Dashed,
+                thickness: 1.5,
+                ..base
+            },
+        }
+    }
+}
+
 impl Default for EdgeStyle {
     fn default() -> Self {
         Self {
@@ -552,9 +801,10 @@ impl Default for EdgeStyle {
 }
 
 /// Edge color options
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum EdgeColor {
+    #[default]
     Default,
     Red,
     Orange,
@@ -570,6 +820,24 @@ pub enum EdgeColor {
 }
 
 impl EdgeColor {
+    /// All colors, for pickers
+    pub fn all() -> &'static [EdgeColor] {
+        &[
+            EdgeColor::Default,
+            EdgeColor::Red,
+            EdgeColor::Orange,
+            EdgeColor::Yellow,
+            EdgeColor::Green,
+            EdgeColor::Teal,
+            EdgeColor::Blue,
+            EdgeColor::Indigo,
+            EdgeColor::Purple,
+            EdgeColor::Pink,
+            EdgeColor::Gray,
+            EdgeColor::White,
+        ]
+    }
+
     /// Get the RGB color values (0-255)
     pub fn rgb(&self) -> (u8, u8, u8) {
         match self {
@@ -595,12 +863,6 @@ impl EdgeColor {
     }
 }
 
-impl Default for EdgeColor {
-    fn default() -> Self {
-        EdgeColor::Default
-    }
-}
-
 /// Line style options
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Default)]
 #[serde(rename_all = "snake_case")]
@@ -611,6 +873,21 @@ pub enum LineStyle {
     Dotted,
 }
 
+impl LineStyle {
+    /// All line styles, for pickers
+    pub fn all() -> &'static [LineStyle] {
+        &[LineStyle::Solid, LineStyle::Dashed, LineStyle::Dotted]
+    }
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            LineStyle::Solid => "Solid",
+            LineStyle::Dashed => "Dashed",
+            LineStyle::Dotted => "Dotted",
+        }
+    }
+}
+
 /// Arrow style options
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Default)]
 #[serde(rename_all = "snake_case")]
@@ -624,6 +901,38 @@ pub enum ArrowStyle {
     Circle,
     FilledCircle,
     ManyArrow, // For one-to-many relationships (crow's foot)
+    Triangle,  // Hollow, for inheritance
+}
+
+impl ArrowStyle {
+    /// All arrow styles, for pickers
+    pub fn all() -> &'static [ArrowStyle] {
+        &[
+            ArrowStyle::None,
+            ArrowStyle::Arrow,
+            ArrowStyle::FilledArrow,
+            ArrowStyle::Diamond,
+            ArrowStyle::FilledDiamond,
+            ArrowStyle::Circle,
+            ArrowStyle::FilledCircle,
+            ArrowStyle::ManyArrow,
+            ArrowStyle::Triangle,
+        ]
+    }
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            ArrowStyle::None => "None",
+            ArrowStyle::Arrow => "Arrow",
+            ArrowStyle::FilledArrow => "Filled Arrow",
+            ArrowStyle::Diamond => "Diamond",
+            ArrowStyle::FilledDiamond => "Filled Diamond",
+            ArrowStyle::Circle => "Circle",
+            ArrowStyle::FilledCircle => "Filled Circle",
+            ArrowStyle::ManyArrow => "Crow's Foot",
+            ArrowStyle::Triangle => "Triangle",
+        }
+    }
 }
 
 /// Curve style options
@@ -708,6 +1017,32 @@ mod tests {
         assert_eq!(rel_edge.relationship_type(), Some(RelationType::OneToMany));
     }
 
+    #[test]
+    fn test_relationship_fields() {
+        let (user, post) = (Uuid::new_v4(), Uuid::new_v4());
+
+        let mut edge = Edge::relationship(user, post, RelationType::OneToMany);
+        assert_eq!((edge.from_field(), edge.to_field()), (None, None));
+        assert_eq!(edge.relationship_key(), None);
+
+        edge.set_fields(Some("id"), Some("author_id"));
+        let key = edge.relationship_key().unwrap();
+        assert_eq!((key.child, key.field, key.parent, key.references), (post, "author_id", user, "id"));
+
+        // The many side holds the key whichever way the edge points
+        let edge = Edge::relationship_on_fields(post, "author_id", user, "id", RelationType::ManyToOne);
+        assert_eq!(edge.relationship_key().map(|k| (k.child, k.field)), Some((post, "author_id")));
+        let edge = Edge::relationship_on_fields(post, "id", user, "id", RelationType::ManyToMany);
+        assert_eq!(edge.relationship_key(), None);
+        let mut edge = Edge::polymorphic(post, "commentable", user);
+        edge.set_fields(Some("id"), Some("id"));
+        assert_eq!(edge.relationship_key(), None);
+        assert!(edge.is_polymorphic() && edge.style.line_style == LineStyle::Dashed);
+
+        // Ports of other edges aren't fields
+        assert_eq!(Edge::data_flow(user, "out", post, "in").from_field(), None);
+    }
+
     #[test]
     fn test_edge_connects_to() {
         let node_a = Uuid::new_v4();
@@ -735,6 +1070,26 @@ mod tests {
         assert_eq!(edge.waypoints.len(), 1);
     }
 
+    #[test]
+    fn test_styles_per_connection_type() {
+        let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+        let data = Edge::data_flow(a, "out", b, "in");
+        let trigger = Edge::trigger(a, "out", b, "in");
+        let dependency = Edge::dependency(a, b);
+        assert_eq!(data.style.line_style, LineStyle::Solid);
+        assert_eq!(trigger.style.line_style, LineStyle::Dashed);
+        assert_eq!(dependency.style.line_style, LineStyle::Dotted);
+        assert_ne!(data.style, trigger.style);
+
+        let many = Edge::relationship(a, b, RelationType::ManyToMany);
+        assert_eq!((many.style.arrow_start, many.style.arrow_end), (ArrowStyle::ManyArrow, ArrowStyle::ManyArrow));
+
+        let mut edited = trigger.with_color(EdgeColor::Green);
+        edited.style.thickness = 4.0;
+        edited.reset_style();
+        assert_eq!(edited.style, EdgeStyle::for_connection(&ConnectionType::Trigger));
+    }
+
     #[test]
     fn test_data_mapping() {
         let mapping = DataMapping::new()
@@ -812,4 +1167,18 @@ mod tests {
         assert_eq!(original.from_node, duplicate.from_node);
         assert_eq!(original.label, duplicate.label);
     }
+
+    #[test]
+    fn test_cache_policy_placeholders() {
+        let policy = CachePolicy::default();
+        assert_eq!(policy.placeholders().unwrap(), ["path", "query"]);
+        assert_eq!(CachePolicy::new(60, "products").placeholders().unwrap(), Vec::<&str>::new());
+        assert!(CachePolicy::new(60, "{path").placeholders().is_err());
+        assert!(CachePolicy::new(60, "path}").placeholders().is_err());
+        assert!(CachePolicy::new(60, "{pa{th}}").placeholders().is_err());
+
+        let edge = Edge::caches(NodeId::new_v4(), NodeId::new_v4(), policy.clone());
+        assert!(edge.is_caching());
+        assert_eq!(edge.cache, Some(policy));
+    }
 }
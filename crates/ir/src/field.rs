@@ -9,6 +9,12 @@ use uuid::Uuid;
 
 use imortal_core::{DataType, UiHints, Validation, ConfigValue};
 
+/// Metadata key holding the environment variable of a settings field
+pub const ENV_VAR_METADATA_KEY: &str = "env_var";
+
+/// Metadata key holding the id of the relationship edge that created a foreign key field
+pub const RELATIONSHIP_METADATA_KEY: &str = "relationship_edge";
+
 /// A field within a component
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Field {
@@ -53,6 +59,10 @@ pub struct Field {
 
     /// Custom metadata
     pub metadata: std::collections::HashMap<String, ConfigValue>,
+
+    /// Unit of a numeric field's values
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unit: Option<Unit>,
 }
 
 impl Field {
@@ -73,6 +83,7 @@ impl Field {
             deprecated: false,
             deprecation_message: None,
             metadata: std::collections::HashMap::new(),
+            unit: None,
         }
     }
 
@@ -266,6 +277,17 @@ impl Field {
         self
     }
 
+    /// Set the unit of the field's values
+    pub fn with_unit(mut self, unit: Unit) -> Self {
+        self.unit = Some(unit);
+        self
+    }
+
+    /// Set the environment variable this field is loaded from (for settings fields)
+    pub fn with_env_var(self, env_var: impl Into<String>) -> Self {
+        self.with_metadata(ENV_VAR_METADATA_KEY, env_var.into())
+    }
+
     // ============ Utility Methods ============
 
     /// Get the display label (falls back to name)
@@ -293,6 +315,63 @@ impl Field {
         self.constraints.iter().any(|c| matches!(c, FieldConstraint::ForeignKey { .. }))
     }
 
+    /// Whether this field can hold a foreign key to `target` on `target_entity`
+    pub fn can_reference(&self, target: &Field, target_entity: &str) -> bool {
+        let key = match &self.data_type {
+            DataType::Optional(inner) => inner.as_ref(),
+            data_type => data_type,
+        };
+        match key {
+            DataType::Reference(entity) => entity == target_entity,
+            key => key.is_compatible_with(&target.data_type) || target.data_type.is_compatible_with(key),
+        }
+    }
+
+    /// Get the environment variable this field is loaded from
+    ///
+    /// Falls back to the field name in SCREAMING_SNAKE_CASE when no explicit
+    /// variable has been set with [`Field::with_env_var`].
+    pub fn env_var(&self) -> String {
+        if let Some(ConfigValue::String(var)) = self.metadata.get(ENV_VAR_METADATA_KEY) {
+            if !var.is_empty() {
+                return var.clone();
+            }
+        }
+
+        let mut result = String::new();
+        let mut prev_is_lower = false;
+        for c in self.name.chars() {
+            if c.is_uppercase() && prev_is_lower {
+                result.push('_');
+            }
+            if c.is_alphanumeric() {
+                result.extend(c.to_uppercase());
+            } else {
+                result.push('_');
+            }
+            prev_is_lower = c.is_lowercase() || c.is_ascii_digit();
+        }
+        result
+    }
+
+    /// Min and max validations implied by the field's unit that aren't
+    /// already among its validations
+    pub fn unit_validations(&self) -> Vec<Validation> {
+        let Some((min, max)) = self.unit.as_ref().map(Unit::bounds) else {
+            return Vec::new();
+        };
+        let has_min = self.validations.iter().any(|v| matches!(v, Validation::Min(_)));
+        let has_max = self.validations.iter().any(|v| matches!(v, Validation::Max(_)));
+        let mut implied = Vec::new();
+        if !has_min {
+            implied.push(Validation::Min(min));
+        }
+        if let Some(max) = max.filter(|_| !has_max) {
+            implied.push(Validation::Max(max));
+        }
+        implied
+    }
+
     /// Get the Rust type for this field
     pub fn rust_type(&self) -> String {
         let base_type = self.data_type.to_rust_type();
@@ -378,6 +457,83 @@ impl Default for Field {
     }
 }
 
+/// Unit of a numeric field's values
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Unit {
+    /// An amount of money in an ISO 4217 currency, e.g. "USD"
+    Currency(String),
+    Bytes,
+    Seconds,
+    /// A percentage from 0 to 100
+    Percent,
+}
+
+impl Unit {
+    /// One of each kind, for pickers
+    pub fn all() -> [Unit; 4] {
+        [Unit::Currency("USD".to_string()), Unit::Bytes, Unit::Seconds, Unit::Percent]
+    }
+
+    /// Short form shown next to values, e.g. "USD" or "%"
+    pub fn symbol(&self) -> &str {
+        match self {
+            Unit::Currency(code) => code,
+            Unit::Bytes => "B",
+            Unit::Seconds => "s",
+            Unit::Percent => "%",
+        }
+    }
+
+    /// Name of the unit's kind, as in generated documentation
+    pub fn name(&self) -> &'static str {
+        match self {
+            Unit::Currency(_) => "currency",
+            Unit::Bytes => "bytes",
+            Unit::Seconds => "seconds",
+            Unit::Percent => "percent",
+        }
+    }
+
+    /// Whether this is the same kind of unit as `other`, ignoring the currency
+    pub fn same_kind(&self, other: &Unit) -> bool {
+        std::mem::discriminant(self) == std::mem::discriminant(other)
+    }
+
+    /// Smallest and largest values the unit allows; none allow negatives
+    pub fn bounds(&self) -> (f64, Option<f64>) {
+        match self {
+            Unit::Percent => (0.0, Some(100.0)),
+            _ => (0.0, None),
+        }
+    }
+
+    /// Whether a field of `data_type` can carry a unit
+    pub fn applies_to(data_type: &DataType) -> bool {
+        match data_type {
+            DataType::Optional(inner) => Self::applies_to(inner),
+            data_type => matches!(
+                data_type,
+                DataType::Int32 | DataType::Int64 | DataType::Float32 | DataType::Float64
+            ),
+        }
+    }
+
+    /// Whether a currency code is three uppercase letters
+    pub fn is_valid_currency(code: &str) -> bool {
+        code.len() == 3 && code.chars().all(|c| c.is_ascii_uppercase())
+    }
+}
+
+impl std::fmt::Display for Unit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Unit::Currency(code) => write!(f, "currency ({})", code),
+            unit => f.write_str(unit.name()),
+        }
+    }
+}
+
 /// Field constraints (database-level)
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -507,6 +663,16 @@ mod tests {
         assert!(field.is_unique());
     }
 
+    #[test]
+    fn test_env_var() {
+        assert_eq!(Field::string("database_url").env_var(), "DATABASE_URL");
+        assert_eq!(Field::string("apiKey").env_var(), "API_KEY");
+        assert_eq!(Field::string("smtp-host").env_var(), "SMTP_HOST");
+
+        let field = Field::string("api_key").with_env_var("STRIPE_API_KEY");
+        assert_eq!(field.env_var(), "STRIPE_API_KEY");
+    }
+
     #[test]
     fn test_primary_key_field() {
         let field = Field::uuid("id").primary_key();
@@ -552,4 +718,29 @@ mod tests {
         assert!(password.ui_hints.secret);
         assert!(password.validations.iter().any(|v| matches!(v, Validation::MinLength(8))));
     }
+
+    #[test]
+    fn test_field_unit() {
+        assert!(Unit::applies_to(&DataType::Int64));
+        assert!(Unit::applies_to(&DataType::Optional(Box::new(DataType::Float64))));
+        assert!(!Unit::applies_to(&DataType::String));
+        assert!(Unit::is_valid_currency("EUR"));
+        assert!(!Unit::is_valid_currency("eur"));
+
+        let price = Field::long("price").with_unit(Unit::Currency("EUR".to_string()));
+        assert_eq!(price.unit.as_ref().unwrap().symbol(), "EUR");
+        assert_eq!(price.unit_validations(), vec![Validation::Min(0.0)]);
+
+        let discount = Field::double("discount").with_unit(Unit::Percent).with_validation(Validation::Max(50.0));
+        assert_eq!(discount.unit_validations(), vec![Validation::Min(0.0)]);
+        let ratio = Field::double("ratio").with_unit(Unit::Percent);
+        assert_eq!(ratio.unit_validations(), vec![Validation::Min(0.0), Validation::Max(100.0)]);
+        assert!(Field::int("count").unit_validations().is_empty());
+
+        // Fields without a unit serialize as before, and old files still load
+        let json = serde_json::to_string(&Field::int("count")).unwrap();
+        assert!(!json.contains("unit"));
+        let loaded: Field = serde_json::from_str(&serde_json::to_string(&price).unwrap()).unwrap();
+        assert_eq!(loaded, price);
+    }
 }
@@ -0,0 +1,472 @@
+//! Inferring entity fields from a sample record
+//!
+//! "Import Fields…" takes a pasted JSON object, or a CSV header line followed
+//! by one data row, and proposes a field per key or column with a
+//! [`DataType`] guessed from its value. The proposals are reviewed and
+//! adjusted before being turned into [`Field`]s.
+
+use imortal_core::{DataType, EngineError, EngineResult, RelationType};
+use serde_json::Value;
+
+use crate::field::Field;
+
+/// Longest string still inferred as `String` rather than `Text`
+const MAX_STRING_LEN: usize = 255;
+
+/// A field proposed from a sample
+#[derive(Debug, Clone, PartialEq)]
+pub struct InferredField {
+    /// Field name, normalized to snake_case
+    pub name: String,
+    /// Type guessed from the sample value
+    pub data_type: DataType,
+    /// False when the sample value was null or empty
+    pub required: bool,
+    /// The sample value, for the review table
+    pub sample: String,
+}
+
+impl InferredField {
+    /// The field to add to the entity
+    pub fn to_field(&self) -> Field {
+        let mut field = Field::new(&self.name, self.data_type.clone());
+        field.required = self.required;
+        field
+    }
+}
+
+/// Propose fields from a JSON object or a CSV header + row sample
+///
+/// Input starting with `{` or `[` is read as JSON (an array uses its first
+/// object); anything else as CSV.
+pub fn infer_fields(sample: &str) -> EngineResult<Vec<InferredField>> {
+    let sample = sample.trim();
+    if sample.is_empty() {
+        return Err(EngineError::Deserialization("The sample is empty".to_string()));
+    }
+    let fields = if sample.starts_with('{') || sample.starts_with('[') {
+        infer_json(sample)?
+    } else {
+        infer_csv(sample)?
+    };
+    if fields.is_empty() {
+        return Err(EngineError::Deserialization("The sample has no fields".to_string()));
+    }
+    Ok(fields)
+}
+
+fn infer_json(sample: &str) -> EngineResult<Vec<InferredField>> {
+    let text = first_object(sample)?;
+    let mut fields = Vec::new();
+    for (key, _, value) in object_entries(text) {
+        push_json_field(&mut fields, &key, &value);
+    }
+    Ok(fields)
+}
+
+/// Propose an entity from a JSON payload, with nested objects as related
+/// entities: an object becomes a one-to-one relationship, an array of
+/// objects a one-to-many relationship to an entity named for the key
+/// in the singular (`line_items` → `LineItem`)
+///
+/// A payload with a single object key, such as `{"user": {...}}`, is named
+/// for that key; otherwise the entity is named `default_name`.
+pub fn infer_entity(sample: &str, default_name: &str) -> EngineResult<InferredEntity> {
+    let text = first_object(sample.trim())?;
+    let entries = object_entries(text);
+    let entity = match entries.as_slice() {
+        [(key, raw, value)] if is_entity_value(value) => {
+            infer_object(entity_name(key, value.is_array()), first_object(raw)?)
+        }
+        _ => infer_object(default_name.to_string(), text),
+    };
+    if entity.fields.is_empty() && entity.children.is_empty() {
+        return Err(EngineError::Deserialization("The sample has no fields".to_string()));
+    }
+    Ok(entity)
+}
+
+/// An entity proposed from a JSON payload
+#[derive(Debug, Clone, PartialEq)]
+pub struct InferredEntity {
+    /// Entity name, in PascalCase
+    pub name: String,
+    /// Fields from the payload's scalar and array values
+    pub fields: Vec<InferredField>,
+    /// Entities nested in the payload, and how this entity relates to each
+    pub children: Vec<(RelationType, InferredEntity)>,
+}
+
+/// Entity from the text of a JSON object
+fn infer_object(name: String, text: &str) -> InferredEntity {
+    let mut entity = InferredEntity { name, fields: Vec::new(), children: Vec::new() };
+    for (key, raw, value) in object_entries(text) {
+        if !is_entity_value(&value) {
+            push_json_field(&mut entity.fields, &key, &value);
+            continue;
+        }
+        let relation = if value.is_array() { RelationType::OneToMany } else { RelationType::OneToOne };
+        let object = raw.trim_start_matches('[').trim_start();
+        entity.children.push((relation, infer_object(entity_name(&key, value.is_array()), object)));
+    }
+    entity
+}
+
+/// Whether a JSON value is a non-empty object, or an array starting with one
+fn is_entity_value(value: &Value) -> bool {
+    match value {
+        Value::Object(object) => !object.is_empty(),
+        Value::Array(items) => items.first().is_some_and(|first| first.is_object() && is_entity_value(first)),
+        _ => false,
+    }
+}
+
+fn push_json_field(fields: &mut Vec<InferredField>, key: &str, value: &Value) {
+    let (data_type, required) = match value {
+        Value::Null => (DataType::String, false),
+        Value::Bool(_) => (DataType::Bool, true),
+        Value::Number(n) => (number_type(n), true),
+        Value::String(s) => (string_type(s), !s.is_empty()),
+        Value::Array(_) | Value::Object(_) => (DataType::Json, true),
+    };
+    let sample = match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    };
+    push_field(fields, key, data_type, required, sample);
+}
+
+/// Text of a JSON object, or of the first object of an array
+fn first_object(sample: &str) -> EngineResult<&str> {
+    let value: Value = serde_json::from_str(sample)
+        .map_err(|e| EngineError::Deserialization(format!("JSON parse error: {}", e)))?;
+    match value {
+        Value::Object(_) => Ok(sample),
+        Value::Array(items) if items.first().is_some_and(Value::is_object) => {
+            Ok(sample.trim_start_matches('[').trim_start())
+        }
+        Value::Array(_) => Err(EngineError::Deserialization("Expected an array of objects".to_string())),
+        _ => Err(EngineError::Deserialization("Expected a JSON object".to_string())),
+    }
+}
+
+/// PascalCase entity name for a key, singular for the key of an array
+fn entity_name(key: &str, plural: bool) -> String {
+    let mut name = field_name(key);
+    if plural {
+        name = singular(&name);
+    }
+    name.split('_')
+        .map(|word| {
+            let mut chars = word.chars();
+            chars.next().map(|c| c.to_uppercase().chain(chars).collect::<String>()).unwrap_or_default()
+        })
+        .collect()
+}
+
+/// English singular of a snake_case word, for the common plural endings
+fn singular(name: &str) -> String {
+    if let Some(stem) = name.strip_suffix("ies") {
+        format!("{}y", stem)
+    } else if ["sses", "xes", "ches", "shes"].iter().any(|end| name.ends_with(end)) {
+        name[..name.len() - 2].to_string()
+    } else if name.ends_with('s') && !name.ends_with("ss") {
+        name[..name.len() - 1].to_string()
+    } else {
+        name.to_string()
+    }
+}
+
+fn infer_csv(sample: &str) -> EngineResult<Vec<InferredField>> {
+    let mut lines = sample.lines().filter(|line| !line.trim().is_empty());
+    let header = split_csv_line(lines.next().unwrap_or_default());
+    let row = lines.next().map(split_csv_line).unwrap_or_default();
+    if row.len() > header.len() {
+        return Err(EngineError::Deserialization(format!(
+            "The row has {} values but the header only {} columns",
+            row.len(),
+            header.len()
+        )));
+    }
+
+    let mut fields = Vec::new();
+    for (idx, column) in header.iter().enumerate() {
+        let value = row.get(idx).map(|v| v.trim()).unwrap_or_default();
+        let data_type = if value.is_empty() { DataType::String } else { text_type(value) };
+        push_field(&mut fields, column, data_type, !value.is_empty(), value.to_string());
+    }
+    Ok(fields)
+}
+
+/// Add a field unless its name is empty or already taken
+fn push_field(fields: &mut Vec<InferredField>, key: &str, data_type: DataType, required: bool, sample: String) {
+    let name = field_name(key);
+    if !name.is_empty() && !fields.iter().any(|f| f.name == name) {
+        fields.push(InferredField { name, data_type, required, sample });
+    }
+}
+
+/// Normalize a JSON key or CSV column to a snake_case field name
+///
+/// `firstName`, `First Name` and `first-name` all become `first_name`.
+pub fn field_name(key: &str) -> String {
+    let mut name = String::new();
+    let mut prev_lower = false;
+    for c in key.trim().chars() {
+        if c.is_alphanumeric() {
+            if c.is_uppercase() && prev_lower {
+                name.push('_');
+            }
+            name.extend(c.to_lowercase());
+            prev_lower = c.is_lowercase() || c.is_numeric();
+        } else {
+            if !name.is_empty() && !name.ends_with('_') {
+                name.push('_');
+            }
+            prev_lower = false;
+        }
+    }
+    let name = name.trim_end_matches('_').to_string();
+    if name.starts_with(|c: char| c.is_numeric()) {
+        format!("field_{}", name)
+    } else {
+        name
+    }
+}
+
+fn number_type(n: &serde_json::Number) -> DataType {
+    match n.as_i64() {
+        Some(i) if i32::try_from(i).is_ok() => DataType::Int32,
+        Some(_) => DataType::Int64,
+        None => DataType::Float64,
+    }
+}
+
+/// Type of a JSON string; numbers and booleans in strings stay strings
+fn string_type(s: &str) -> DataType {
+    match text_type(s) {
+        DataType::Uuid | DataType::DateTime | DataType::Date | DataType::Text => text_type(s),
+        _ => DataType::String,
+    }
+}
+
+/// Type of an untyped value, such as a CSV cell
+fn text_type(s: &str) -> DataType {
+    if s.eq_ignore_ascii_case("true") || s.eq_ignore_ascii_case("false") {
+        DataType::Bool
+    } else if let Ok(i) = s.parse::<i64>() {
+        if i32::try_from(i).is_ok() { DataType::Int32 } else { DataType::Int64 }
+    } else if s.parse::<f64>().is_ok_and(f64::is_finite) {
+        DataType::Float64
+    } else if uuid::Uuid::parse_str(s).is_ok() {
+        DataType::Uuid
+    } else if is_date(s) {
+        DataType::Date
+    } else if s.get(..10).is_some_and(is_date) && matches!(s.as_bytes().get(10), Some(b'T' | b' ')) {
+        DataType::DateTime
+    } else if s.chars().count() > MAX_STRING_LEN || s.contains('\n') {
+        DataType::Text
+    } else {
+        DataType::String
+    }
+}
+
+/// `YYYY-MM-DD`
+fn is_date(s: &str) -> bool {
+    let b = s.as_bytes();
+    b.len() == 10
+        && b[4] == b'-'
+        && b[7] == b'-'
+        && b.iter().enumerate().all(|(i, c)| i == 4 || i == 7 || c.is_ascii_digit())
+}
+
+/// Split a CSV line on commas outside double quotes, unescaping `""`
+fn split_csv_line(line: &str) -> Vec<String> {
+    let mut values = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                current.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => values.push(std::mem::take(&mut current)),
+            c => current.push(c),
+        }
+    }
+    values.push(current);
+    values
+}
+
+/// Keys, value text and values of the outermost JSON object in `text`, in
+/// the order written (serde_json's map is sorted); values that fail to parse
+/// are skipped
+fn object_entries(text: &str) -> Vec<(String, &str, Value)> {
+    let mut entries = Vec::new();
+    let mut depth = 0usize;
+    let mut chars = text.char_indices();
+    let mut last_string = None;
+    let mut key: Option<(String, usize)> = None;
+    let mut finish = |key: &mut Option<(String, usize)>, end: usize| {
+        if let Some((name, start)) = key.take() {
+            let raw = text[start..end].trim();
+            if let Ok(value) = serde_json::from_str(raw) {
+                entries.push((name, raw, value));
+            }
+        }
+    };
+    while let Some((idx, c)) = chars.next() {
+        match c {
+            '"' => {
+                let mut s = String::new();
+                while let Some((_, c)) = chars.next() {
+                    match c {
+                        '\\' => {
+                            if let Some((_, escaped)) = chars.next() {
+                                s.push(escaped);
+                            }
+                        }
+                        '"' => break,
+                        c => s.push(c),
+                    }
+                }
+                last_string = Some(s);
+                continue;
+            }
+            ':' if depth == 1 => {
+                if let Some(name) = last_string.take() {
+                    key = Some((name, idx + 1));
+                }
+            }
+            ',' if depth == 1 => finish(&mut key, idx),
+            '{' | '[' => depth += 1,
+            '}' | ']' => {
+                depth = depth.saturating_sub(1);
+                // The first object of an array ends the sample
+                if depth == 0 {
+                    finish(&mut key, idx);
+                    break;
+                }
+            }
+            _ => {}
+        }
+        if !c.is_whitespace() {
+            last_string = None;
+        }
+    }
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn summary(fields: &[InferredField]) -> Vec<(&str, DataType, bool)> {
+        fields.iter().map(|f| (f.name.as_str(), f.data_type.clone(), f.required)).collect()
+    }
+
+    #[test]
+    fn test_infer_json() {
+        let fields = infer_fields(r#"{
+            "userId": 42,
+            "email": "ada@example.com",
+            "balance": 10.5,
+            "views": 9000000000,
+            "active": true,
+            "tags": ["a", "b"],
+            "createdAt": "2024-05-01T10:00:00Z",
+            "birthday": "1990-01-31",
+            "token": "67e55044-10b1-426f-9247-bb680e5fe0c8",
+            "zip": "01234",
+            "nickname": null
+        }"#).unwrap();
+        assert_eq!(summary(&fields), vec![
+            ("user_id", DataType::Int32, true),
+            ("email", DataType::String, true),
+            ("balance", DataType::Float64, true),
+            ("views", DataType::Int64, true),
+            ("active", DataType::Bool, true),
+            ("tags", DataType::Json, true),
+            ("created_at", DataType::DateTime, true),
+            ("birthday", DataType::Date, true),
+            ("token", DataType::Uuid, true),
+            ("zip", DataType::String, true),
+            ("nickname", DataType::String, false),
+        ]);
+        assert_eq!(fields[1].sample, "ada@example.com");
+
+        // An array uses its first object
+        let fields = infer_fields(r#"[{"b": 1, "a": {"z": 2}}, {"c": 3}]"#).unwrap();
+        assert_eq!(summary(&fields), vec![("b", DataType::Int32, true), ("a", DataType::Json, true)]);
+
+        assert!(infer_fields("{ not json").is_err());
+        assert!(infer_fields("[1, 2]").is_err());
+        assert!(infer_fields("{}").is_err());
+    }
+
+    #[test]
+    fn test_infer_csv() {
+        let fields = infer_fields("id,First Name,score,\"notes, misc\",joined\n7,\"Lovelace, Ada\",3.5,,2024-05-01 10:00:00\n").unwrap();
+        assert_eq!(summary(&fields), vec![
+            ("id", DataType::Int32, true),
+            ("first_name", DataType::String, true),
+            ("score", DataType::Float64, true),
+            ("notes_misc", DataType::String, false),
+            ("joined", DataType::DateTime, true),
+        ]);
+        assert_eq!(fields[1].sample, "Lovelace, Ada");
+
+        // A header alone proposes untyped, optional fields
+        let fields = infer_fields("name,name,age").unwrap();
+        assert_eq!(summary(&fields), vec![("name", DataType::String, false), ("age", DataType::String, false)]);
+
+        assert!(infer_fields("a,b\n1,2,3").is_err());
+        assert!(infer_fields("  ").is_err());
+    }
+
+    #[test]
+    fn test_infer_entity() {
+        let entity = infer_entity(r#"{
+            "orderNo": "A-1",
+            "customer": {"name": "Ada", "address": {"city": "London"}},
+            "lineItems": [{"sku": "X", "qty": 2}, {"sku": "Y"}],
+            "tags": ["rush"],
+            "meta": {}
+        }"#, "Entity").unwrap();
+        assert_eq!(entity.name, "Entity");
+        assert_eq!(summary(&entity.fields), vec![
+            ("order_no", DataType::String, true),
+            ("tags", DataType::Json, true),
+            ("meta", DataType::Json, true),
+        ]);
+        let children: Vec<_> = entity.children.iter().map(|(rt, e)| (*rt, e.name.as_str())).collect();
+        assert_eq!(children, vec![(RelationType::OneToOne, "Customer"), (RelationType::OneToMany, "LineItem")]);
+        let customer = &entity.children[0].1;
+        assert_eq!(summary(&customer.fields), vec![("name", DataType::String, true)]);
+        assert_eq!(customer.children[0].1.name, "Address");
+        assert_eq!(summary(&entity.children[1].1.fields), vec![("sku", DataType::String, true), ("qty", DataType::Int32, true)]);
+
+        // A single wrapping key names the entity
+        let entity = infer_entity(r#"{"categories": [{"title": "Books"}]}"#, "Entity").unwrap();
+        assert_eq!((entity.name.as_str(), entity.fields.len()), ("Category", 1));
+        assert_eq!(entity_name("addresses", true), "Address");
+        assert_eq!(entity_name("boxes", true), "Box");
+
+        assert!(infer_entity("{}", "Entity").is_err());
+        assert!(infer_entity("name,age", "Entity").is_err());
+    }
+
+    #[test]
+    fn test_field_name() {
+        assert_eq!(field_name("firstName"), "first_name");
+        assert_eq!(field_name("First Name"), "first_name");
+        assert_eq!(field_name("first-name"), "first_name");
+        assert_eq!(field_name("HTTPStatus"), "httpstatus");
+        assert_eq!(field_name("2fa"), "field_2fa");
+        assert_eq!(field_name("  "), "");
+        assert_eq!(infer_fields("{\"a\": 1}").unwrap()[0].to_field().data_type, DataType::Int32);
+    }
+}
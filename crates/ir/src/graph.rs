@@ -5,15 +5,22 @@
 //! managing the graph, querying relationships, and validating the structure.
 
 use serde::{Deserialize, Serialize};
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::atomic::{AtomicU64, Ordering};
 use uuid::Uuid;
 
-use imortal_core::{EdgeId, NodeId, EngineError, EngineResult, RelationType, ConnectionType};
+use imortal_core::{ConfigValue, DataType, EdgeId, NodeId, EngineError, EngineResult, Position, RelationType, ConnectionType};
 
-use crate::edge::Edge;
+use crate::background::BackgroundLayer;
+use crate::edge::{CachePolicy, Edge, PolymorphicAssociation};
+use crate::field::{Field, FieldConstraint, ForeignKeyBuilder, RELATIONSHIP_METADATA_KEY};
+use crate::field_import::InferredEntity;
 use crate::group::Group;
+use crate::custom_type::{project_type_name, rename_project_type, CustomType};
+use crate::mixin::Mixin;
 use crate::node::Node;
-use crate::project::ProjectMeta;
+use crate::port::Port;
+use crate::project::{split_words, ProjectMeta};
 
 /// The main graph structure for an Immortal Engine project
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -45,9 +52,34 @@ pub struct ProjectGraph {
     /// Canvas viewport state
     pub viewport: Viewport,
 
+    /// Bookmarked viewpoints, in presentation order
+    #[serde(default)]
+    pub slides: Vec<Slide>,
+
+    /// Named viewport bookmarks for navigation
+    #[serde(default)]
+    pub bookmarks: Vec<Bookmark>,
+
+    /// Composite nodes pinned to the palette as reusable templates
+    #[serde(default)]
+    pub templates: Vec<Node>,
+
+    /// Images and swimlanes drawn beneath the canvas content, bottom first
+    #[serde(default)]
+    pub backgrounds: Vec<BackgroundLayer>,
+
+    /// Nodes loaded as an outline, whose fields, config, metadata,
+    /// description, and subgraph haven't been read from storage yet
+    #[serde(skip)]
+    pub unloaded: HashSet<NodeId>,
+
     /// Whether the graph has been modified since last save
     #[serde(skip)]
     pub dirty: bool,
+
+    /// Changes whenever the graph is marked modified; see [`Self::revision`]
+    #[serde(skip)]
+    revision: u64,
 }
 
 impl ProjectGraph {
@@ -62,7 +94,13 @@ impl ProjectGraph {
             selected_edges: HashSet::new(),
             selected_groups: HashSet::new(),
             viewport: Viewport::default(),
+            slides: Vec::new(),
+            bookmarks: Vec::new(),
+            templates: Vec::new(),
+            backgrounds: Vec::new(),
+            unloaded: HashSet::new(),
             dirty: false,
+            revision: 0,
         }
     }
 
@@ -77,7 +115,7 @@ impl ProjectGraph {
     pub fn add_node(&mut self, node: Node) -> NodeId {
         let id = node.id;
         self.nodes.insert(id, node);
-        self.dirty = true;
+        self.touch();
         id
     }
 
@@ -94,7 +132,7 @@ impl ProjectGraph {
 
     /// Get a mutable node by ID
     pub fn get_node_mut(&mut self, id: NodeId) -> Option<&mut Node> {
-        self.dirty = true;
+        self.touch();
         self.nodes.get_mut(&id)
     }
 
@@ -110,8 +148,9 @@ impl ProjectGraph {
 
         // Remove from selection
         self.selected_nodes.remove(&id);
+        self.unloaded.remove(&id);
 
-        self.dirty = true;
+        self.touch();
         self.nodes.remove(&id)
     }
 
@@ -120,6 +159,17 @@ impl ProjectGraph {
         self.nodes.contains_key(&id)
     }
 
+    /// Whether a node's details have been loaded (always true unless the
+    /// project was opened as an outline)
+    pub fn is_loaded(&self, id: NodeId) -> bool {
+        !self.unloaded.contains(&id)
+    }
+
+    /// Whether every node's details have been loaded
+    pub fn fully_loaded(&self) -> bool {
+        self.unloaded.is_empty()
+    }
+
     /// Get the number of nodes
     pub fn node_count(&self) -> usize {
         self.nodes.len()
@@ -165,21 +215,604 @@ impl ProjectGraph {
         self.nodes
             .values()
             .filter(|n| {
-                let (nx, ny, nw, nh) = n.bounds();
+                let (nx, ny, nw, nh) = n.effective_bounds();
                 nx < x + width && nx + nw > x && ny < y + height && ny + nh > y
             })
             .collect()
     }
 
+    /// Every namespace used by a node, including the modules enclosing
+    /// them, sorted so parents come before their children
+    pub fn namespaces(&self) -> Vec<Vec<String>> {
+        let mut namespaces: Vec<Vec<String>> = self.nodes
+            .values()
+            .flat_map(|n| (1..=n.namespace.len()).map(|len| n.namespace[..len].to_vec()))
+            .collect();
+        namespaces.sort();
+        namespaces.dedup();
+        namespaces
+    }
+
+    /// Names of the modules directly inside `namespace`
+    pub fn child_namespaces(&self, namespace: &[String]) -> Vec<String> {
+        self.namespaces()
+            .into_iter()
+            .filter(|ns| ns.len() == namespace.len() + 1 && ns.starts_with(namespace))
+            .filter_map(|mut ns| ns.pop())
+            .collect()
+    }
+
     /// Duplicate a node (creates a copy with new ID)
     pub fn duplicate_node(&mut self, id: NodeId) -> Option<NodeId> {
         let node = self.nodes.get(&id)?.duplicate();
         let new_id = node.id;
         self.nodes.insert(new_id, node);
-        self.dirty = true;
+        self.touch();
         Some(new_id)
     }
 
+    /// Collapse or expand every node
+    pub fn set_all_collapsed(&mut self, collapsed: bool) {
+        for node in self.nodes.values_mut() {
+            node.collapsed = collapsed;
+        }
+        self.touch();
+    }
+
+    /// A copy without the disabled nodes and their edges, the project as
+    /// code generation sees it
+    pub fn without_disabled(&self) -> ProjectGraph {
+        let mut enabled = self.clone();
+        let disabled: Vec<NodeId> = self.nodes.values().filter(|n| !n.enabled).map(|n| n.id).collect();
+        for id in disabled {
+            enabled.remove_node(id);
+        }
+        enabled
+    }
+
+    /// A copy with only the nodes carrying one of `tags` and the edges
+    /// between them, for generating part of a project; empty `tags` keeps
+    /// every node
+    pub fn tagged(&self, tags: &[String]) -> ProjectGraph {
+        let mut tagged = self.clone();
+        let untagged: Vec<NodeId> = self.nodes.values().filter(|n| !n.has_any_tag(tags)).map(|n| n.id).collect();
+        for id in untagged {
+            tagged.remove_node(id);
+        }
+        tagged
+    }
+
+    /// Tags used by the nodes with how many nodes carry each, sorted by tag
+    pub fn tags(&self) -> Vec<(String, usize)> {
+        let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+        for tag in self.nodes.values().flat_map(|n| &n.tags) {
+            *counts.entry(tag).or_default() += 1;
+        }
+        counts.into_iter().map(|(tag, count)| (tag.to_string(), count)).collect()
+    }
+
+    /// Copy some nodes into a standalone project
+    ///
+    /// Takes the nodes, the edges between them, and the groups containing
+    /// them (with their parent groups). References that would dangle are
+    /// fixed up: edges to other nodes are left out, groups keep only the
+    /// extracted nodes, and foreign keys to entities that weren't extracted
+    /// are dropped. IDs are kept, so the extract can be compared against the
+    /// original.
+    pub fn extract(&self, node_ids: &HashSet<NodeId>) -> Extraction {
+        let mut project = ProjectGraph::new(self.meta.clone());
+        project.viewport = self.viewport;
+        let mut extraction = Extraction::default();
+
+        for id in node_ids {
+            if let Some(node) = self.nodes.get(id) {
+                project.nodes.insert(*id, node.clone());
+            }
+        }
+
+        for edge in self.edges.values() {
+            match (project.has_node(edge.from_node), project.has_node(edge.to_node)) {
+                (true, true) => {
+                    project.edges.insert(edge.id, edge.clone());
+                }
+                (false, false) => {}
+                _ => extraction.dropped_edges += 1,
+            }
+        }
+
+        // Containing groups and their ancestors
+        let mut group_ids: Vec<Uuid> = project.nodes.values().filter_map(|node| node.group_id).collect();
+        while let Some(id) = group_ids.pop() {
+            if project.groups.contains_key(&id) {
+                continue;
+            }
+            if let Some(group) = self.groups.get(&id) {
+                let mut group = group.clone();
+                group.node_ids.retain(|node| node_ids.contains(node));
+                group_ids.extend(group.parent_id);
+                project.groups.insert(id, group);
+            }
+        }
+        for node in project.nodes.values_mut() {
+            if node.group_id.is_some_and(|group| !project.groups.contains_key(&group)) {
+                node.group_id = None;
+            }
+        }
+
+        let entities: HashSet<String> = project
+            .nodes
+            .values()
+            .filter(|node| node.component_type == "data.entity")
+            .map(|node| node.name.clone())
+            .collect();
+        for node in project.nodes.values_mut() {
+            for field in &mut node.fields {
+                let before = field.constraints.len();
+                field.constraints.retain(|c| match c {
+                    FieldConstraint::ForeignKey { entity, .. } => entities.contains(entity),
+                    _ => true,
+                });
+                if field.constraints.len() < before {
+                    extraction.dropped_foreign_keys += 1;
+                }
+                // The relationship that added the field may not have come along
+                let edge = field.metadata.get(RELATIONSHIP_METADATA_KEY).and_then(|v| v.as_str());
+                if edge.is_some_and(|edge| !project.edges.keys().any(|id| id.to_string() == edge)) {
+                    field.metadata.remove(RELATIONSHIP_METADATA_KEY);
+                }
+            }
+        }
+
+        project.touch();
+        extraction.project = project;
+        extraction
+    }
+
+    /// Nodes of `other` with the same name and component as one of ours,
+    /// paired with ours and sorted by name
+    pub fn import_collisions<'a>(&'a self, other: &'a ProjectGraph) -> Vec<(&'a Node, &'a Node)> {
+        let mut collisions: Vec<(&Node, &Node)> = other
+            .nodes()
+            .filter_map(|node| {
+                self.nodes()
+                    .find(|n| n.name == node.name && n.component_type == node.component_type)
+                    .map(|existing| (existing, node))
+            })
+            .collect();
+        collisions.sort_by(|a, b| a.1.name.cmp(&b.1.name));
+        collisions
+    }
+
+    /// Merge another project into this one as a subgraph
+    ///
+    /// Everything imported gets a new ID and is placed to the right of the
+    /// existing nodes, inside a new group named after the other project.
+    /// A node with the same name and component as an existing one is handled
+    /// by `collision`. Edges that would become self-loops or repeat an
+    /// existing connection are left out.
+    pub fn import_project(&mut self, other: &ProjectGraph, collision: ImportCollision) -> ImportSummary {
+        self.import_project_with(other, |_, _| collision)
+    }
+
+    /// [`import_project`](Self::import_project), deciding each collision
+    /// separately: `resolve` gets the existing node and the imported one
+    pub fn import_project_with(
+        &mut self,
+        other: &ProjectGraph,
+        resolve: impl Fn(&Node, &Node) -> ImportCollision,
+    ) -> ImportSummary {
+        let mut incoming = other.clone();
+        let mut summary = ImportSummary::default();
+        let edge_map: HashMap<EdgeId, EdgeId> = incoming.edges.keys().map(|id| (*id, Uuid::new_v4())).collect();
+        let remap_field = |field: &mut Field| {
+            let edge = field.metadata.get(RELATIONSHIP_METADATA_KEY).and_then(|v| v.as_str()).and_then(|v| v.parse().ok());
+            if let Some(new_edge) = edge.and_then(|edge: EdgeId| edge_map.get(&edge)) {
+                field.metadata.insert(RELATIONSHIP_METADATA_KEY.to_string(), ConfigValue::String(new_edge.to_string()));
+            }
+        };
+
+        // Imported node ID -> node in this graph, for collisions and new IDs alike
+        let mut node_map: HashMap<NodeId, NodeId> = HashMap::new();
+        let collisions: Vec<(NodeId, NodeId, ImportCollision)> = self
+            .import_collisions(other)
+            .into_iter()
+            .map(|(existing, node)| (existing.id, node.id, resolve(existing, node)))
+            .collect();
+        for (existing, id, collision) in collisions {
+            let node = &other.nodes[&id];
+            match collision {
+                ImportCollision::Rename => {
+                    let taken = |name: &str| self.nodes().chain(incoming.nodes()).any(|n| n.name == name);
+                    let new_name = (2..).map(|n| format!("{}{}", node.name, n)).find(|name| !taken(name)).expect("unbounded");
+                    // Renaming within the imported project updates its own references
+                    if incoming.rename_node(node.id, new_name.clone()).is_ok() {
+                        summary.renamed.push((node.name.clone(), new_name));
+                    }
+                }
+                ImportCollision::Merge => {
+                    if let (Some(target), Some(node)) = (self.nodes.get_mut(&existing), incoming.nodes.get(&node.id)) {
+                        for field in &node.fields {
+                            if target.get_field(&field.name).is_none() {
+                                let mut field = field.clone();
+                                remap_field(&mut field);
+                                target.add_field(field);
+                            }
+                        }
+                    }
+                    node_map.insert(node.id, existing);
+                    summary.merged += 1;
+                }
+                ImportCollision::Replace => {
+                    let edges = &self.edges;
+                    if let (Some(target), Some(node)) = (self.nodes.get_mut(&existing), incoming.nodes.get(&node.id)) {
+                        // Foreign keys our own relationships added stay, or those edges would lose their fields
+                        let kept: Vec<Field> = target
+                            .fields
+                            .iter()
+                            .filter(|f| {
+                                let edge = f.metadata.get(RELATIONSHIP_METADATA_KEY).and_then(|v| v.as_str());
+                                edge.is_some_and(|edge| edges.keys().any(|id| id.to_string() == edge))
+                                    && node.get_field(&f.name).is_none()
+                            })
+                            .cloned()
+                            .collect();
+                        target.fields = node.fields.clone();
+                        target.fields.iter_mut().for_each(remap_field);
+                        target.fields.extend(kept);
+                        target.config = node.config.clone();
+                        target.description = node.description.clone();
+                    }
+                    node_map.insert(node.id, existing);
+                    summary.replaced += 1;
+                }
+                ImportCollision::Skip => {
+                    node_map.insert(node.id, existing);
+                    summary.skipped += 1;
+                }
+            }
+        }
+
+        let group_map: HashMap<Uuid, Uuid> = incoming.groups.keys().map(|id| (*id, Uuid::new_v4())).collect();
+
+        // Place the import to the right of the existing content
+        let mut new_nodes: Vec<Node> = incoming.nodes.values().filter(|n| !node_map.contains_key(&n.id)).cloned().collect();
+        new_nodes.sort_by(|a, b| a.name.cmp(&b.name));
+        let right = self.nodes().map(|n| n.position.x + n.size.width).fold(None, |max: Option<f32>, x| Some(max.map_or(x, |m| m.max(x))));
+        let top = self.nodes().map(|n| n.position.y).fold(None, |min: Option<f32>, y| Some(min.map_or(y, |m| m.min(y))));
+        let left = new_nodes.iter().map(|n| n.position.x).fold(f32::MAX, f32::min);
+        let upper = new_nodes.iter().map(|n| n.position.y).fold(f32::MAX, f32::min);
+        let (dx, dy) = if new_nodes.is_empty() {
+            (0.0, 0.0)
+        } else {
+            (right.map_or(0.0, |r| r + 150.0) - left, top.unwrap_or(0.0) - upper)
+        };
+
+        let mut wrapper = Group::new(other.meta.name.clone());
+        let mut bounds = Vec::new();
+        for mut node in new_nodes {
+            let new_id = Uuid::new_v4();
+            node_map.insert(node.id, new_id);
+            node.id = new_id;
+            node.position.x += dx;
+            node.position.y += dy;
+            node.group_id = node.group_id.and_then(|group| group_map.get(&group).copied());
+            if node.group_id.is_none() {
+                node.group_id = Some(wrapper.id);
+                wrapper.add_node(new_id);
+            }
+            node.fields.iter_mut().for_each(remap_field);
+            bounds.push(node.bounds());
+            summary.added.push(new_id);
+            self.nodes.insert(new_id, node);
+        }
+
+        for (old_id, mut group) in std::mem::take(&mut incoming.groups) {
+            group.id = group_map[&old_id];
+            group.node_ids = group.node_ids.iter().filter_map(|id| node_map.get(id)).filter(|id| summary.added.contains(id)).copied().collect();
+            group.parent_id = Some(group.parent_id.and_then(|parent| group_map.get(&parent).copied()).unwrap_or(wrapper.id));
+            group.translate(dx, dy);
+            bounds.push(group.bounds());
+            self.groups.insert(group.id, group);
+        }
+
+        let mut edges: Vec<Edge> = incoming.edges.into_values().collect();
+        edges.sort_by_key(|edge| edge.id);
+        for mut edge in edges {
+            let (Some(&from), Some(&to)) = (node_map.get(&edge.from_node), node_map.get(&edge.to_node)) else {
+                continue;
+            };
+            edge.id = edge_map[&edge.id];
+            edge.from_node = from;
+            edge.to_node = to;
+            let repeated = from == to
+                || self.edges.values().any(|e| e.is_from_to(from, to) && e.connection_type == edge.connection_type);
+            if !repeated {
+                self.edges.insert(edge.id, edge);
+            }
+        }
+
+        if !summary.added.is_empty() {
+            if let Some(group_bounds) = wrapper.calculate_bounds_from_nodes(bounds) {
+                wrapper.fit_to_bounds(group_bounds);
+            }
+            summary.group = Some(wrapper.id);
+            self.groups.insert(wrapper.id, wrapper);
+        }
+
+        self.touch();
+        summary
+    }
+
+    /// Merge `duplicate` into `target` and remove `duplicate`
+    ///
+    /// Edges are rewired onto `target` (dropping self-loops and edges that
+    /// would repeat an existing connection), and fields missing from `target`
+    /// are copied over. Returns false if either node is missing.
+    pub fn merge_node_into(&mut self, duplicate: NodeId, target: NodeId) -> bool {
+        if duplicate == target || !self.has_node(target) {
+            return false;
+        }
+        let Some(removed) = self.nodes.get(&duplicate).cloned() else {
+            return false;
+        };
+
+        let rewired: Vec<Edge> = self
+            .edges
+            .values()
+            .filter(|e| e.connects_to(duplicate))
+            .cloned()
+            .collect();
+        self.remove_node(duplicate);
+
+        for mut edge in rewired {
+            if edge.from_node == duplicate {
+                edge.from_node = target;
+            }
+            if edge.to_node == duplicate {
+                edge.to_node = target;
+            }
+            let repeated = edge.from_node == edge.to_node
+                || self.edges.values().any(|e| {
+                    e.is_from_to(edge.from_node, edge.to_node)
+                        && e.connection_type == edge.connection_type
+                });
+            if !repeated {
+                self.edges.insert(edge.id, edge);
+            }
+        }
+
+        if let Some(node) = self.nodes.get_mut(&target) {
+            for field in removed.fields {
+                if node.get_field(&field.name).is_none() {
+                    node.add_field(field);
+                }
+            }
+        }
+
+        self.touch();
+        true
+    }
+
+    // ========== Entity Refactoring ==========
+
+    /// Get an entity node, or an error if it is missing or not an entity
+    fn entity(&self, id: NodeId) -> EngineResult<&Node> {
+        let node = self
+            .get_node(id)
+            .ok_or_else(|| EngineError::NodeNotFound(id.to_string()))?;
+        if node.component_type != "data.entity" {
+            return Err(EngineError::custom(format!("'{}' is not an entity", node.name)));
+        }
+        Ok(node)
+    }
+
+    /// Names of fields that both nodes define, but differently
+    ///
+    /// Field IDs are ignored; only the definitions are compared.
+    pub fn field_conflicts(&self, a: NodeId, b: NodeId) -> Vec<String> {
+        let (Some(a), Some(b)) = (self.get_node(a), self.get_node(b)) else {
+            return Vec::new();
+        };
+        a.fields
+            .iter()
+            .filter(|fa| {
+                b.get_field(&fa.name)
+                    .is_some_and(|fb| Field { id: fa.id, ..fb.clone() } != **fa)
+            })
+            .map(|fa| fa.name.clone())
+            .collect()
+    }
+
+    /// Merge entity `source` into entity `target`
+    ///
+    /// The result has the union of both entities' fields. For conflicting
+    /// fields named in `prefer_source`, `source`'s definition replaces
+    /// `target`'s; other conflicts keep `target`'s. All edges of `source`
+    /// are rewired onto `target`.
+    pub fn merge_entities(
+        &mut self,
+        source: NodeId,
+        target: NodeId,
+        prefer_source: &[String],
+    ) -> EngineResult<()> {
+        if source == target {
+            return Err(EngineError::custom("Cannot merge an entity into itself"));
+        }
+        let replacements: Vec<Field> = self
+            .entity(source)?
+            .fields
+            .iter()
+            .filter(|f| prefer_source.contains(&f.name))
+            .cloned()
+            .collect();
+        self.entity(target)?;
+
+        if let Some(node) = self.nodes.get_mut(&target) {
+            for field in replacements {
+                match node.get_field_mut(&field.name) {
+                    Some(existing) => *existing = field,
+                    None => node.add_field(field),
+                }
+            }
+        }
+
+        self.merge_node_into(source, target);
+        Ok(())
+    }
+
+    /// Split fields out of an entity into a new entity
+    ///
+    /// The named fields (primary keys excluded) move to a new entity placed
+    /// to the right of the original, linked to it by a one-to-one
+    /// relationship. Returns the new entity's ID.
+    pub fn split_entity(
+        &mut self,
+        id: NodeId,
+        fields: &[String],
+        new_name: impl Into<String>,
+    ) -> EngineResult<NodeId> {
+        let source = self.entity(id)?;
+        let moved: Vec<String> = source
+            .fields
+            .iter()
+            .filter(|f| fields.contains(&f.name) && !f.is_primary_key())
+            .map(|f| f.name.clone())
+            .collect();
+        if moved.is_empty() {
+            return Err(EngineError::custom(format!(
+                "No fields selected to split out of '{}'",
+                source.name
+            )));
+        }
+
+        let (x, y, width, _) = source.effective_bounds();
+        let mut extracted = Node::new_entity(new_name).with_position(x + width + 60.0, y);
+        if let Some(node) = self.nodes.get_mut(&id) {
+            for name in &moved {
+                if let Some(field) = node.remove_field(name) {
+                    extracted.add_field(field);
+                }
+            }
+        }
+
+        let new_id = self.add_node(extracted);
+        self.add_relationship(id, new_id, RelationType::OneToOne)?;
+        Ok(new_id)
+    }
+
+    /// Rename a node and update everything that refers to it by name
+    ///
+    /// For entities this also rewrites port and field types
+    /// (`DataType::Entity`/`Reference`), config strings on other nodes that
+    /// equal the name or use it as a path segment (`/users` for `User`), and
+    /// edge labels mentioning it. Every touched location is reported.
+    pub fn rename_node(&mut self, id: NodeId, new_name: impl Into<String>) -> EngineResult<RenameSummary> {
+        let new_name = new_name.into().trim().to_string();
+        if new_name.is_empty() {
+            return Err(EngineError::custom("Name cannot be empty"));
+        }
+        let node = self
+            .get_node(id)
+            .ok_or_else(|| EngineError::NodeNotFound(id.to_string()))?;
+        let old_name = node.name.clone();
+        let is_entity = node.component_type == "data.entity";
+
+        let mut summary = RenameSummary {
+            old_name: old_name.clone(),
+            new_name: new_name.clone(),
+            changes: Vec::new(),
+        };
+        if old_name == new_name {
+            return Ok(summary);
+        }
+        if is_entity && self.nodes.values().any(|n| {
+            n.id != id && n.component_type == "data.entity" && n.name == new_name
+        }) {
+            return Err(EngineError::custom(format!("An entity named '{}' already exists", new_name)));
+        }
+
+        if let Some(node) = self.nodes.get_mut(&id) {
+            node.name = new_name.clone();
+        }
+        summary.record(Some(id), None, format!("Node '{}' renamed to '{}'", old_name, new_name));
+        self.touch();
+
+        if !is_entity {
+            return Ok(summary);
+        }
+
+        let (old_segment, new_segment) = (old_name.to_lowercase(), new_name.to_lowercase());
+        for node in self.nodes.values_mut() {
+            for port in node.ports.inputs.iter_mut().chain(node.ports.outputs.iter_mut()) {
+                let relabeled = node.id == id && port.name == old_name;
+                if relabeled {
+                    port.name = new_name.clone();
+                }
+                if port.data_type.rename_entity(&old_name, &new_name) || relabeled {
+                    summary.record(Some(node.id), None, format!("Port '{}' on '{}'", port.id, node.name));
+                }
+            }
+
+            for field in &mut node.fields {
+                if field.data_type.rename_entity(&old_name, &new_name) {
+                    summary.record(Some(node.id), None, format!("Field '{}.{}' type", node.name, field.name));
+                }
+                for constraint in &mut field.constraints {
+                    if let FieldConstraint::ForeignKey { entity, .. } = constraint {
+                        if *entity == old_name {
+                            *entity = new_name.clone();
+                            summary.record(Some(node.id), None, format!("Field '{}.{}' foreign key", node.name, field.name));
+                        }
+                    }
+                }
+            }
+
+            if node.id == id {
+                continue;
+            }
+            let mut keys: Vec<&String> = node.config.keys().collect();
+            keys.sort();
+            let mut updates = Vec::new();
+            for key in keys {
+                let Some(value) = node.config.get(key).and_then(|v| v.as_str()) else {
+                    continue;
+                };
+                let renamed = if value == old_name {
+                    Some(new_name.clone())
+                } else if value.starts_with('/') {
+                    rename_path_segments(value, &old_segment, &new_segment)
+                } else {
+                    None
+                };
+                if let Some(renamed) = renamed {
+                    summary.record(
+                        Some(node.id),
+                        None,
+                        format!("Config '{}' on '{}': {} → {}", key, node.name, value, renamed),
+                    );
+                    updates.push((key.clone(), renamed));
+                }
+            }
+            for (key, value) in updates {
+                node.set_config(key, value);
+            }
+        }
+
+        let mut edges: Vec<&mut Edge> = self.edges.values_mut().collect();
+        edges.sort_by_key(|e| e.id);
+        for edge in edges {
+            let Some(label) = &edge.label else {
+                continue;
+            };
+            if let Some(renamed) = replace_word(label, &old_name, &new_name) {
+                summary.record(None, Some(edge.id), format!("Edge label: {} → {}", label, renamed));
+                edge.label = Some(renamed);
+            }
+        }
+
+        Ok(summary)
+    }
+
     // ========== Edge Operations ==========
 
     /// Add an edge to the graph and return its ID
@@ -192,11 +825,16 @@ impl ProjectGraph {
             return Err(EngineError::NodeNotFound(edge.to_node.to_string()));
         }
 
-        // Skip port validation for relationship and dependency edges
-        // (they connect entities/nodes directly rather than specific ports)
+        // Skip port validation for relationship, dependency, inheritance,
+        // caching, and gating edges (they connect entities/nodes directly
+        // rather than specific ports)
         let skip_port_validation = matches!(
             edge.connection_type,
-            ConnectionType::Relationship(_) | ConnectionType::Dependency
+            ConnectionType::Relationship(_)
+                | ConnectionType::Dependency
+                | ConnectionType::Inheritance
+                | ConnectionType::Caches
+                | ConnectionType::Gates
         );
 
         if !skip_port_validation {
@@ -220,11 +858,48 @@ impl ProjectGraph {
         }
 
         let id = edge.id;
+        // A relationship naming its fields joins on a key the user already has
+        let creates_foreign_key = self.meta.foreign_keys.enabled
+            && edge.relationship_type() == Some(RelationType::OneToMany)
+            && edge.relationship_key().is_none();
         self.edges.insert(id, edge);
-        self.dirty = true;
+        self.touch();
+        if creates_foreign_key {
+            self.add_foreign_key_field(id);
+        }
         Ok(id)
     }
 
+    /// Add the `<parent>_id` field of a one-to-many relationship to the child entity
+    ///
+    /// Does nothing if the child already has a field of that name.
+    fn add_foreign_key_field(&mut self, edge_id: EdgeId) {
+        let Some(edge) = self.edges.get(&edge_id) else {
+            return;
+        };
+        let (parent_id, child_id) = (edge.from_node, edge.to_node);
+        let Some(parent) = self.nodes.get(&parent_id).filter(|n| n.component_type == "data.entity") else {
+            return;
+        };
+        let parent_name = parent.name.clone();
+        let name = self.meta.foreign_keys.field_name(&parent_name);
+        let Some(child) = self.nodes.get_mut(&child_id).filter(|n| n.component_type == "data.entity") else {
+            return;
+        };
+        if child.get_field(&name).is_some() {
+            return;
+        }
+
+        let mut field = Field::reference(name, &parent_name)
+            .with_constraint(ForeignKeyBuilder::new(&parent_name, "id").build())
+            .with_metadata(RELATIONSHIP_METADATA_KEY, edge_id.to_string());
+        // A self-referencing tree needs somewhere to stop
+        if parent_id != child_id {
+            field = field.required();
+        }
+        child.add_field(field);
+    }
+
     /// Create and add a data flow edge between two ports
     pub fn connect(
         &mut self,
@@ -248,6 +923,439 @@ impl ProjectGraph {
         self.add_edge(edge)
     }
 
+    /// Create a relationship edge joining a field of each entity, such as
+    /// `User.id` and `Post.author_id`
+    pub fn add_field_relationship(
+        &mut self,
+        from_node: NodeId,
+        from_field: &str,
+        to_node: NodeId,
+        to_field: &str,
+        relation_type: RelationType,
+    ) -> EngineResult<EdgeId> {
+        let edge = Edge::relationship_on_fields(from_node, from_field, to_node, to_field, relation_type);
+        if let Some(problem) = self.relationship_field_problem(&edge) {
+            return Err(EngineError::InvalidConnection(problem));
+        }
+        self.add_edge(edge)
+    }
+
+    /// Add an entity inferred from a JSON payload at `position`, with the
+    /// entities nested in it laid out to its right and joined by
+    /// relationships; returns the new nodes, the root first
+    ///
+    /// Entity names already taken get a numeric suffix (`Address2`).
+    pub fn add_inferred_entity(&mut self, entity: &InferredEntity, position: Position) -> EngineResult<Vec<NodeId>> {
+        let mut added = Vec::new();
+        self.add_inferred_entity_at(entity, position, &mut added)?;
+        Ok(added)
+    }
+
+    fn add_inferred_entity_at(&mut self, entity: &InferredEntity, position: Position, added: &mut Vec<NodeId>) -> EngineResult<NodeId> {
+        const COLUMN_GAP: f32 = 80.0;
+        const ROW_GAP: f32 = 40.0;
+
+        let mut name = entity.name.clone();
+        let mut suffix = 1;
+        while self.find_nodes_by_type("data.entity").iter().any(|n| n.name == name) {
+            suffix += 1;
+            name = format!("{}{}", entity.name, suffix);
+        }
+        let mut node = Node::new_entity(&name);
+        node.position = position;
+        for field in &entity.fields {
+            if node.get_field(&field.name).is_none() {
+                node.add_field(field.to_field());
+            }
+        }
+        let (_, _, width, _) = node.effective_bounds();
+        let id = self.add_node(node);
+        added.push(id);
+
+        let mut child_position = Position::new(position.x + width + COLUMN_GAP, position.y);
+        for (relation_type, child) in &entity.children {
+            let child_id = self.add_inferred_entity_at(child, child_position, added)?;
+            self.add_relationship(id, child_id, *relation_type)?;
+            // Below the child's own subtree
+            let bottom = added
+                .iter()
+                .skip_while(|n| **n != child_id)
+                .filter_map(|n| self.get_node(*n))
+                .map(|n| n.position.y + n.effective_bounds().3)
+                .fold(child_position.y, f32::max);
+            child_position.y = bottom + ROW_GAP;
+        }
+        Ok(id)
+    }
+
+    /// Join a relationship on a field of each entity, or on the whole entity
+    /// for `None`
+    pub fn set_relationship_fields(
+        &mut self,
+        edge_id: EdgeId,
+        from_field: Option<&str>,
+        to_field: Option<&str>,
+    ) -> EngineResult<()> {
+        let mut edge = self
+            .get_edge(edge_id)
+            .filter(|e| e.is_relationship())
+            .cloned()
+            .ok_or_else(|| EngineError::EdgeNotFound(edge_id.to_string()))?;
+        edge.set_fields(from_field, to_field);
+        if let Some(problem) = self.relationship_field_problem(&edge) {
+            return Err(EngineError::InvalidConnection(problem));
+        }
+        self.edges.insert(edge_id, edge);
+        self.touch();
+        Ok(())
+    }
+
+    /// What is wrong with the fields a relationship joins on: a field its
+    /// entity doesn't have, or a foreign key of the wrong type
+    pub fn relationship_field_problem(&self, edge: &Edge) -> Option<String> {
+        for (node_id, field) in [(edge.from_node, edge.from_field()), (edge.to_node, edge.to_field())] {
+            if let (Some(node), Some(field)) = (self.get_node(node_id), field) {
+                if node.get_field(field).is_none() {
+                    return Some(format!("{} has no field '{}'", node.name, field));
+                }
+            }
+        }
+
+        let key = edge.relationship_key()?;
+        let (child, parent) = (self.get_node(key.child)?, self.get_node(key.parent)?);
+        let (field, target) = (child.get_field(key.field)?, parent.get_field(key.references)?);
+        (!field.can_reference(target, &parent.name)).then(|| {
+            format!(
+                "{}.{} ({}) can't hold a key to {}.{} ({})",
+                child.name,
+                field.name,
+                field.data_type.to_rust_type(),
+                parent.name,
+                target.name,
+                target.data_type.to_rust_type()
+            )
+        })
+    }
+
+    /// Fields a relationship joins on, such as `Post.author_id → User.id`,
+    /// or `None` when it joins whole entities
+    pub fn relationship_label(&self, edge: &Edge) -> Option<String> {
+        if edge.from_field().is_none() && edge.to_field().is_none() {
+            return None;
+        }
+        let end = |node_id: NodeId, field: Option<&str>| {
+            let name = self.get_node(node_id).map_or("?", |n| n.name.as_str());
+            match field {
+                Some(field) => format!("{}.{}", name, field),
+                None => name.to_string(),
+            }
+        };
+        Some(match edge.relationship_key() {
+            Some(key) => format!("{} → {}", end(key.child, Some(key.field)), end(key.parent, Some(key.references))),
+            None => format!("{} ↔ {}", end(edge.from_node, edge.from_field()), end(edge.to_node, edge.to_field())),
+        })
+    }
+
+    /// Polymorphic relationships of an entity, sorted by name, each with
+    /// the set of entities it can belong to
+    pub fn polymorphic_associations(&self, child: NodeId) -> Vec<PolymorphicAssociation> {
+        let mut associations: Vec<PolymorphicAssociation> = Vec::new();
+        for edge in self.edges.values().filter(|e| e.is_polymorphic() && e.from_node == child) {
+            let name = self.association_name(edge);
+            match associations.iter_mut().find(|a| a.name == name) {
+                Some(association) if association.targets.contains(&edge.to_node) => {}
+                Some(association) => association.targets.push(edge.to_node),
+                None => associations.push(PolymorphicAssociation { child, name, targets: vec![edge.to_node] }),
+            }
+        }
+        let target_name = |id: &NodeId| self.get_node(*id).map(|n| n.name.clone()).unwrap_or_default();
+        for association in &mut associations {
+            association.targets.sort_by_key(target_name);
+        }
+        associations.sort_by(|a, b| a.name.cmp(&b.name));
+        associations
+    }
+
+    /// Association a polymorphic edge belongs to, named after its entity
+    /// ("commentable" for `Comment`) when the edge doesn't name one
+    pub fn association_name(&self, edge: &Edge) -> String {
+        edge.association.clone().filter(|name| !name.trim().is_empty()).unwrap_or_else(|| {
+            let child = self.get_node(edge.from_node).map_or("", |n| n.name.as_str());
+            format!("{}able", split_words(child).join("_"))
+        })
+    }
+
+    /// Make an entity belong to one of `targets` through the polymorphic
+    /// association `name`, replacing its previous targets; no targets
+    /// removes the association
+    pub fn set_polymorphic_targets(&mut self, child: NodeId, name: &str, targets: &[NodeId]) -> EngineResult<()> {
+        for id in std::iter::once(child).chain(targets.iter().copied()) {
+            match self.get_node(id) {
+                Some(node) if node.component_type == "data.entity" => {}
+                Some(node) => {
+                    return Err(EngineError::InvalidConnection(format!("{} is not an entity", node.name)));
+                }
+                None => return Err(EngineError::NodeNotFound(id.to_string())),
+            }
+        }
+
+        let current: Vec<(EdgeId, NodeId)> = self
+            .edges
+            .values()
+            .filter(|e| e.is_polymorphic() && e.from_node == child && self.association_name(e) == name)
+            .map(|e| (e.id, e.to_node))
+            .collect();
+        for (edge_id, target) in &current {
+            if !targets.contains(target) {
+                self.remove_edge(*edge_id);
+            }
+        }
+        for &target in targets {
+            if !current.iter().any(|(_, existing)| *existing == target) {
+                self.add_edge(Edge::polymorphic(child, name, target))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Type and id fields of an entity's polymorphic relationships that it
+    /// doesn't declare itself; the id takes the type of the targets' `id`
+    pub fn polymorphic_fields(&self, child: NodeId) -> Vec<Field> {
+        let Some(node) = self.get_node(child) else {
+            return Vec::new();
+        };
+        let mut fields = Vec::new();
+        for association in self.polymorphic_associations(child) {
+            let targets: Vec<&Node> = association.targets.iter().filter_map(|id| self.get_node(*id)).collect();
+            let id_column = association.id_column();
+            if node.get_field(&association.type_column()).is_none() {
+                let names: Vec<&str> = targets.iter().map(|n| n.name.as_str()).collect();
+                fields.push(
+                    Field::string(association.type_column())
+                        .required()
+                        .with_description(format!("Entity {} refers to: {}", id_column, names.join(", "))),
+                );
+            }
+            if node.get_field(&id_column).is_none() {
+                let data_type = targets
+                    .iter()
+                    .find_map(|n| n.get_field("id"))
+                    .map_or(DataType::Uuid, |f| f.data_type.clone());
+                fields.push(Field::new(id_column, data_type).required());
+            }
+        }
+        fields
+    }
+
+    /// Cache an endpoint stores its responses in, and how
+    pub fn cache_for(&self, endpoint: NodeId) -> Option<(&Node, &CachePolicy)> {
+        self.edges
+            .values()
+            .filter(|e| e.is_caching() && e.from_node == endpoint)
+            .find_map(|e| Some((self.get_node(e.to_node)?, e.cache.as_ref()?)))
+    }
+
+    /// Cache a REST endpoint's responses in a cache node with a policy, or
+    /// stop caching them with `None`; replaces any cache it used before
+    pub fn set_cache(&mut self, endpoint: NodeId, cache: Option<(NodeId, CachePolicy)>) -> EngineResult<Option<EdgeId>> {
+        let nodes = std::iter::once((endpoint, "api.rest")).chain(cache.as_ref().map(|(id, _)| (*id, "storage.cache")));
+        for (id, component_type) in nodes {
+            match self.get_node(id) {
+                Some(node) if node.component_type == component_type => {}
+                Some(node) => {
+                    return Err(EngineError::InvalidConnection(format!("'{}' is not a {}", node.name, component_type)));
+                }
+                None => return Err(EngineError::NodeNotFound(id.to_string())),
+            }
+        }
+
+        let previous: Vec<EdgeId> = self
+            .edges
+            .values()
+            .filter(|e| e.is_caching() && e.from_node == endpoint)
+            .map(|e| e.id)
+            .collect();
+        for id in previous {
+            self.remove_edge(id);
+        }
+        cache.map(|(cache, policy)| self.add_edge(Edge::caches(endpoint, cache, policy))).transpose()
+    }
+
+    /// Feature flag node and flag a node sits behind: the one of its gating
+    /// edge, else the flag its `feature_flag` config names
+    pub fn flag_gate(&self, node: NodeId) -> Option<(&Node, &str)> {
+        let gated = self
+            .edges
+            .values()
+            .filter(|e| e.is_gate() && e.to_node == node)
+            .find_map(|e| Some((self.get_node(e.from_node)?, e.flag.as_deref()?)));
+        if gated.is_some() {
+            return gated;
+        }
+
+        let name = self.get_node(node)?.get_config_str("feature_flag").map(str::trim).filter(|n| !n.is_empty())?;
+        self.find_nodes_by_type("logic.feature_flag")
+            .into_iter()
+            .find(|flags| flags.feature_flags().iter().any(|f| f.name == name))
+            .map(|flags| (flags, name))
+    }
+
+    /// Put an API node behind a flag of a feature flag node, or take it out
+    /// from behind its flag with `None`; replaces any gate it had before
+    pub fn set_flag_gate(&mut self, node: NodeId, gate: Option<(NodeId, String)>) -> EngineResult<Option<EdgeId>> {
+        match self.get_node(node) {
+            Some(n) if n.component_type.starts_with("api.") => {}
+            Some(n) => return Err(EngineError::InvalidConnection(format!("'{}' is not an API node", n.name))),
+            None => return Err(EngineError::NodeNotFound(node.to_string())),
+        }
+        if let Some((flags, flag)) = &gate {
+            match self.get_node(*flags) {
+                Some(n) if !n.feature_flags().iter().any(|f| &f.name == flag) => {
+                    return Err(EngineError::InvalidConnection(format!("'{}' defines no flag '{}'", n.name, flag)));
+                }
+                Some(_) => {}
+                None => return Err(EngineError::NodeNotFound(flags.to_string())),
+            }
+        }
+
+        let previous: Vec<EdgeId> = self
+            .edges
+            .values()
+            .filter(|e| e.is_gate() && e.to_node == node)
+            .map(|e| e.id)
+            .collect();
+        for id in previous {
+            self.remove_edge(id);
+        }
+        gate.map(|(flags, flag)| self.add_edge(Edge::gates(flags, node, flag))).transpose()
+    }
+
+    /// Entity a node extends, if any
+    pub fn parent_entity(&self, id: NodeId) -> Option<NodeId> {
+        self.edges
+            .values()
+            .find(|e| e.is_inheritance() && e.from_node == id)
+            .map(|e| e.to_node)
+    }
+
+    /// Entities a node extends, nearest first, stopping short of a cycle
+    pub fn ancestors(&self, id: NodeId) -> Vec<NodeId> {
+        let mut ancestors = Vec::new();
+        let mut current = id;
+        while let Some(parent) = self.parent_entity(current) {
+            if parent == id || ancestors.contains(&parent) {
+                break;
+            }
+            ancestors.push(parent);
+            current = parent;
+        }
+        ancestors
+    }
+
+    /// Make an entity extend another, or no other for `None`, replacing
+    /// whatever it extended before
+    pub fn set_parent_entity(&mut self, child: NodeId, parent: Option<NodeId>) -> EngineResult<Option<EdgeId>> {
+        for id in std::iter::once(child).chain(parent) {
+            match self.get_node(id) {
+                Some(node) if node.component_type == "data.entity" => {}
+                Some(node) => return Err(EngineError::InvalidConnection(format!("'{}' is not an entity", node.name))),
+                None => return Err(EngineError::NodeNotFound(id.to_string())),
+            }
+        }
+        if parent.is_some_and(|parent| parent == child || self.ancestors(parent).contains(&child)) {
+            return Err(EngineError::InvalidConnection("An entity can't extend itself or its descendants".to_string()));
+        }
+
+        let previous: Vec<EdgeId> = self
+            .edges
+            .values()
+            .filter(|e| e.is_inheritance() && e.from_node == child)
+            .map(|e| e.id)
+            .collect();
+        for id in previous {
+            self.remove_edge(id);
+        }
+        parent.map(|parent| self.add_edge(Edge::inheritance(child, parent))).transpose()
+    }
+
+    /// A mixin by name, the project's own before the built-in ones
+    pub fn mixin(&self, name: &str) -> Option<Mixin> {
+        self.mixins().into_iter().find(|m| m.name == name)
+    }
+
+    /// Every mixin entities can include: the project's own, then the
+    /// built-in ones it doesn't replace
+    pub fn mixins(&self) -> Vec<Mixin> {
+        let mut mixins = self.meta.mixins.clone();
+        for mixin in Mixin::builtin() {
+            if !mixins.iter().any(|m| m.name == mixin.name) {
+                mixins.push(mixin);
+            }
+        }
+        mixins
+    }
+
+    /// A project-defined data type by name
+    pub fn custom_type(&self, name: &str) -> Option<&CustomType> {
+        self.meta.types.iter().find(|t| t.name == name)
+    }
+
+    /// Number of fields, of nodes and project mixins, holding the project
+    /// type `name`
+    pub fn custom_type_usages(&self, name: &str) -> usize {
+        self.nodes
+            .values()
+            .flat_map(|n| &n.fields)
+            .chain(self.meta.mixins.iter().flat_map(|m| &m.fields))
+            .filter(|f| project_type_name(&f.data_type) == Some(name))
+            .count()
+    }
+
+    /// Rename a project type together with the fields holding it,
+    /// returning how many fields changed
+    pub fn rename_custom_type(&mut self, old: &str, new: &str) -> usize {
+        let Some(custom) = self.meta.types.iter_mut().find(|t| t.name == old) else {
+            return 0;
+        };
+        custom.name = new.to_string();
+        let fields = self
+            .nodes
+            .values_mut()
+            .flat_map(|n| n.fields.iter_mut())
+            .chain(self.meta.mixins.iter_mut().flat_map(|m| m.fields.iter_mut()));
+        let changed = fields.map(|f| rename_project_type(&mut f.data_type, old, new)).filter(|changed| *changed).count();
+        self.touch();
+        changed
+    }
+
+    /// Fields of an entity including the ones it inherits
+    ///
+    /// The entities it extends come first, furthest first, each with its
+    /// own fields followed by those of its mixins. A field declared again
+    /// further down replaces the inherited one where it stands, while a mixin
+    /// never replaces a field that is already there.
+    pub fn effective_fields(&self, id: NodeId) -> Vec<Field> {
+        let mut chain = self.ancestors(id);
+        chain.reverse();
+        chain.push(id);
+
+        let mut fields: Vec<Field> = Vec::new();
+        for node in chain.iter().filter_map(|id| self.get_node(*id)) {
+            for field in &node.fields {
+                match fields.iter_mut().find(|f| f.name == field.name) {
+                    Some(inherited) => *inherited = field.clone(),
+                    None => fields.push(field.clone()),
+                }
+            }
+            for field in node.mixins.iter().filter_map(|name| self.mixin(name)).flat_map(|m| m.fields) {
+                if !fields.iter().any(|f| f.name == field.name) {
+                    fields.push(field);
+                }
+            }
+        }
+        fields
+    }
+
     /// Get an edge by ID
     pub fn get_edge(&self, id: EdgeId) -> Option<&Edge> {
         self.edges.get(&id)
@@ -255,15 +1363,20 @@ impl ProjectGraph {
 
     /// Get a mutable edge by ID
     pub fn get_edge_mut(&mut self, id: EdgeId) -> Option<&mut Edge> {
-        self.dirty = true;
+        self.touch();
         self.edges.get_mut(&id)
     }
 
-    /// Remove an edge
+    /// Remove an edge, along with any foreign key field it created
     pub fn remove_edge(&mut self, id: EdgeId) -> Option<Edge> {
         self.selected_edges.remove(&id);
-        self.dirty = true;
-        self.edges.remove(&id)
+        self.touch();
+        let edge = self.edges.remove(&id)?;
+        if let Some(child) = self.nodes.get_mut(&edge.to_node) {
+            let marker = ConfigValue::String(id.to_string());
+            child.fields.retain(|f| f.metadata.get(RELATIONSHIP_METADATA_KEY) != Some(&marker));
+        }
+        Some(edge)
     }
 
     /// Remove all edges connected to a node
@@ -319,6 +1432,42 @@ impl ProjectGraph {
             .collect()
     }
 
+    /// Get edges attached to a specific port of a node
+    pub fn port_edges(&self, node_id: NodeId, port_id: &str) -> Vec<&Edge> {
+        self.edges
+            .values()
+            .filter(|e| {
+                (e.from_node == node_id && e.from_port == port_id)
+                    || (e.to_node == node_id && e.to_port == port_id)
+            })
+            .collect()
+    }
+
+    /// Remove a user-editable port and every edge attached to it
+    pub fn remove_port(&mut self, node_id: NodeId, port_id: &str) -> EngineResult<Port> {
+        let node = self
+            .get_node(node_id)
+            .ok_or_else(|| EngineError::NodeNotFound(node_id.to_string()))?;
+        if !node.allows_port_editing() {
+            return Err(EngineError::custom(format!("Ports of '{}' cannot be edited", node.name)));
+        }
+
+        let attached: Vec<EdgeId> = self.port_edges(node_id, port_id).iter().map(|e| e.id).collect();
+        let port = self
+            .nodes
+            .get_mut(&node_id)
+            .and_then(|n| n.ports.remove(port_id))
+            .ok_or_else(|| EngineError::PortNotFound {
+                node_id: node_id.to_string(),
+                port_id: port_id.to_string(),
+            })?;
+        for edge_id in attached {
+            self.remove_edge(edge_id);
+        }
+        self.touch();
+        Ok(port)
+    }
+
     /// Get edges between two specific nodes
     pub fn edges_between(&self, node_a: NodeId, node_b: NodeId) -> Vec<&Edge> {
         self.edges
@@ -366,13 +1515,108 @@ impl ProjectGraph {
             .collect()
     }
 
+    /// Find nodes that feed the given node, directly or through others, up
+    /// to `depth` connections away (any distance when `None`)
+    pub fn upstream_within(&self, node_id: NodeId, depth: Option<usize>) -> HashSet<NodeId> {
+        self.reachable(node_id, depth, Self::upstream_nodes)
+    }
+
+    /// Find nodes the given node feeds, directly or through others, up to
+    /// `depth` connections away (any distance when `None`)
+    pub fn downstream_within(&self, node_id: NodeId, depth: Option<usize>) -> HashSet<NodeId> {
+        self.reachable(node_id, depth, Self::downstream_nodes)
+    }
+
+    /// What deleting `node_ids` affects: the nodes downstream of them, at
+    /// any distance, that aren't being deleted themselves, and the edges a
+    /// cascade delete of both would remove
+    pub fn deletion_impact(&self, node_ids: &HashSet<NodeId>) -> DeletionImpact {
+        let dependents: HashSet<NodeId> = node_ids
+            .iter()
+            .flat_map(|id| self.downstream_within(*id, None))
+            .filter(|id| !node_ids.contains(id))
+            .collect();
+        let edges = self
+            .edges
+            .values()
+            .filter(|e| [e.from_node, e.to_node].iter().any(|id| node_ids.contains(id) || dependents.contains(id)))
+            .map(|e| e.id)
+            .collect();
+        DeletionImpact { dependents, edges }
+    }
+
+    /// Nodes that `node_ids` need in order to be generated on their own,
+    /// at any distance, not counting `node_ids` themselves
+    ///
+    /// A node needs the nodes feeding it data, events, or flags and those
+    /// it depends on, the entities it extends, the caches it uses, and the
+    /// entities its foreign keys reference. Navigation doesn't count, and
+    /// neither do relationships without a foreign key.
+    pub fn required_dependencies(&self, node_ids: &HashSet<NodeId>) -> HashSet<NodeId> {
+        let mut found = HashSet::new();
+        let mut pending: Vec<NodeId> = node_ids.iter().copied().collect();
+        while let Some(id) = pending.pop() {
+            let Some(node) = self.nodes.get(&id) else {
+                continue;
+            };
+            let needed = self.edges.values().filter_map(|edge| match edge.connection_type {
+                ConnectionType::DataFlow | ConnectionType::Trigger | ConnectionType::Dependency | ConnectionType::Gates
+                    if edge.to_node == id =>
+                {
+                    Some(edge.from_node)
+                }
+                ConnectionType::Inheritance | ConnectionType::Caches if edge.from_node == id => Some(edge.to_node),
+                _ => None,
+            });
+            let referenced = node
+                .fields
+                .iter()
+                .flat_map(|field| &field.constraints)
+                .filter_map(|constraint| match constraint {
+                    FieldConstraint::ForeignKey { entity, .. } => self
+                        .nodes
+                        .values()
+                        .find(|n| n.component_type == "data.entity" && n.name == *entity)
+                        .map(|n| n.id),
+                    _ => None,
+                });
+            for next in needed.chain(referenced).collect::<Vec<_>>() {
+                if !node_ids.contains(&next) && found.insert(next) {
+                    pending.push(next);
+                }
+            }
+        }
+        found
+    }
+
+    /// Breadth-first walk from `start` along `next`, not including `start`
+    fn reachable(
+        &self,
+        start: NodeId,
+        depth: Option<usize>,
+        next: fn(&Self, NodeId) -> HashSet<NodeId>,
+    ) -> HashSet<NodeId> {
+        let mut found = HashSet::new();
+        let mut frontier = vec![start];
+        let mut hops = 0;
+        while !frontier.is_empty() && depth.is_none_or(|depth| hops < depth) {
+            hops += 1;
+            frontier = frontier
+                .into_iter()
+                .flat_map(|id| next(self, id))
+                .filter(|id| *id != start && found.insert(*id))
+                .collect();
+        }
+        found
+    }
+
     // ========== Group Operations ==========
 
     /// Add a group to the graph
     pub fn add_group(&mut self, group: Group) -> Uuid {
         let id = group.id;
         self.groups.insert(id, group);
-        self.dirty = true;
+        self.touch();
         id
     }
 
@@ -389,7 +1633,7 @@ impl ProjectGraph {
 
     /// Get a mutable group by ID
     pub fn get_group_mut(&mut self, id: Uuid) -> Option<&mut Group> {
-        self.dirty = true;
+        self.touch();
         self.groups.get_mut(&id)
     }
 
@@ -414,7 +1658,7 @@ impl ProjectGraph {
         }
 
         self.selected_groups.remove(&id);
-        self.dirty = true;
+        self.touch();
         self.groups.remove(&id)
     }
 
@@ -447,7 +1691,7 @@ impl ProjectGraph {
             group.add_node(node_id);
         }
 
-        self.dirty = true;
+        self.touch();
         Ok(())
     }
 
@@ -464,7 +1708,7 @@ impl ProjectGraph {
             if let Some(group) = self.groups.get_mut(&gid) {
                 group.remove_node(&node_id);
             }
-            self.dirty = true;
+            self.touch();
         }
 
         Ok(())
@@ -499,7 +1743,7 @@ impl ProjectGraph {
 
         let id = group.id;
         self.groups.insert(id, group);
-        self.dirty = true;
+        self.touch();
         Some(id)
     }
 
@@ -519,7 +1763,7 @@ impl ProjectGraph {
 
         self.groups.remove(&group_id);
         self.selected_groups.remove(&group_id);
-        self.dirty = true;
+        self.touch();
 
         Ok(node_ids)
     }
@@ -744,6 +1988,16 @@ impl ProjectGraph {
         self.set_zoom(self.viewport.zoom * factor);
     }
 
+    /// Zoom by a factor around a point given in canvas coordinates, keeping
+    /// whatever is under that point in place (e.g. the center of a pinch)
+    pub fn zoom_at(&mut self, factor: f32, x: f32, y: f32) {
+        let old_zoom = self.viewport.zoom;
+        self.set_zoom(old_zoom * factor);
+        let scale = self.viewport.zoom / old_zoom;
+        self.viewport.pan_x = x - (x - self.viewport.pan_x) * scale;
+        self.viewport.pan_y = y - (y - self.viewport.pan_y) * scale;
+    }
+
     /// Reset the viewport to default
     pub fn reset_viewport(&mut self) {
         self.viewport = Viewport::default();
@@ -790,6 +2044,65 @@ impl ProjectGraph {
         self.viewport.pan_y = -min_y * zoom + (canvas_height - content_height * zoom) / 2.0;
     }
 
+    /// Bookmark the current viewport as a slide, returning its index
+    pub fn add_slide(&mut self, name: impl Into<String>) -> usize {
+        self.slides.push(Slide {
+            name: name.into(),
+            viewport: self.viewport,
+        });
+        self.slides.len() - 1
+    }
+
+    /// Remove a slide
+    pub fn remove_slide(&mut self, index: usize) -> Option<Slide> {
+        (index < self.slides.len()).then(|| self.slides.remove(index))
+    }
+
+    /// Bookmark the current viewport, returning the bookmark's index
+    ///
+    /// If exactly one group is selected, the bookmark remembers it and
+    /// selects it again when restored.
+    pub fn add_bookmark(&mut self, name: impl Into<String>) -> usize {
+        let group = match self.selected_groups.len() {
+            1 => self.selected_groups.iter().next().copied(),
+            _ => None,
+        };
+        self.bookmarks.push(Bookmark {
+            name: name.into(),
+            viewport: self.viewport,
+            group,
+        });
+        self.bookmarks.len() - 1
+    }
+
+    /// Remove a bookmark
+    pub fn remove_bookmark(&mut self, index: usize) -> Option<Bookmark> {
+        (index < self.bookmarks.len()).then(|| self.bookmarks.remove(index))
+    }
+
+    /// Jump to a bookmark, restoring its viewport and group selection
+    ///
+    /// Returns false if there is no bookmark at `index`.
+    pub fn go_to_bookmark(&mut self, index: usize) -> bool {
+        let Some(bookmark) = self.bookmarks.get(index).cloned() else {
+            return false;
+        };
+        self.viewport = bookmark.viewport;
+        if let Some(group) = bookmark.group.filter(|id| self.groups.contains_key(id)) {
+            self.clear_selection();
+            self.select_group(group);
+        }
+        true
+    }
+
+    /// Move a slide to another position in the presentation order
+    pub fn move_slide(&mut self, from: usize, to: usize) {
+        if from < self.slides.len() && to < self.slides.len() {
+            let slide = self.slides.remove(from);
+            self.slides.insert(to, slide);
+        }
+    }
+
     // ========== Utility Methods ==========
 
     /// Check if the graph is empty
@@ -803,7 +2116,7 @@ impl ProjectGraph {
         self.edges.clear();
         self.groups.clear();
         self.clear_selection();
-        self.dirty = true;
+        self.touch();
     }
 
     /// Mark the graph as saved (not dirty)
@@ -811,6 +2124,22 @@ impl ProjectGraph {
         self.dirty = false;
     }
 
+    /// Mark the graph as modified
+    pub fn touch(&mut self) {
+        static NEXT_REVISION: AtomicU64 = AtomicU64::new(1);
+        self.dirty = true;
+        self.revision = NEXT_REVISION.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Identifies the graph's state as of the last change
+    ///
+    /// Revisions are unique across graphs, so a state restored by undo
+    /// doesn't match one reached by a different edit. Changes made to the
+    /// public collections directly, without [`Self::touch`], don't count.
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
     /// Get the maximum z-index among all nodes
     pub fn max_node_z_index(&self) -> i32 {
         self.nodes.values().map(|n| n.z_index).max().unwrap_or(0)
@@ -821,7 +2150,7 @@ impl ProjectGraph {
         let max_z = self.max_node_z_index();
         if let Some(node) = self.nodes.get_mut(&id) {
             node.bring_to_front(max_z);
-            self.dirty = true;
+            self.touch();
         }
     }
 
@@ -829,7 +2158,7 @@ impl ProjectGraph {
     pub fn send_node_to_back(&mut self, id: NodeId) {
         if let Some(node) = self.nodes.get_mut(&id) {
             node.send_to_back();
-            self.dirty = true;
+            self.touch();
         }
     }
 
@@ -840,7 +2169,7 @@ impl ProjectGraph {
                 node.translate(dx, dy);
             }
         }
-        self.dirty = true;
+        self.touch();
     }
 
     /// Align selected nodes to a specific alignment
@@ -913,7 +2242,7 @@ impl ProjectGraph {
                 }
             }
         }
-        self.dirty = true;
+        self.touch();
     }
 }
 
@@ -944,6 +2273,218 @@ impl Default for Viewport {
     }
 }
 
+impl Viewport {
+    /// Interpolate towards another viewport; `t` runs from 0.0 (self) to 1.0 (other)
+    pub fn lerp(&self, other: &Viewport, t: f32) -> Viewport {
+        let t = t.clamp(0.0, 1.0);
+        Viewport {
+            pan_x: self.pan_x + (other.pan_x - self.pan_x) * t,
+            pan_y: self.pan_y + (other.pan_y - self.pan_y) * t,
+            zoom: self.zoom + (other.zoom - self.zoom) * t,
+        }
+    }
+}
+
+/// A named position on the canvas
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Bookmark {
+    /// Bookmark name
+    pub name: String,
+    /// Viewport to restore
+    pub viewport: Viewport,
+    /// Group to select when restored
+    #[serde(default)]
+    pub group: Option<Uuid>,
+}
+
+/// A bookmarked viewpoint of the canvas, used as a presentation slide
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Slide {
+    /// Slide title
+    pub name: String,
+    /// Viewport the slide shows
+    pub viewport: Viewport,
+}
+
+/// A location updated by [`ProjectGraph::rename_node`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct RenameChange {
+    /// Node that was touched, if any
+    pub node_id: Option<NodeId>,
+    /// Edge that was touched, if any
+    pub edge_id: Option<EdgeId>,
+    /// Human-readable description of the change
+    pub description: String,
+}
+
+/// What [`ProjectGraph::import_project`] does with an imported node that has
+/// the same name and component as an existing one
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum ImportCollision {
+    /// Import it under a numbered name, e.g. `User2`
+    #[default]
+    Rename,
+    /// Use the existing node, adding the fields it lacks
+    Merge,
+    /// Use the existing node as it is
+    Skip,
+    /// Use the existing node, taking the imported fields, configuration and
+    /// description in place of its own
+    Replace,
+}
+
+impl ImportCollision {
+    /// All strategies, for pickers
+    pub fn all() -> &'static [ImportCollision] {
+        &[ImportCollision::Rename, ImportCollision::Merge, ImportCollision::Skip, ImportCollision::Replace]
+    }
+}
+
+/// How an imported node's fields differ from those of the existing node it
+/// collides with, by field name
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FieldDifferences {
+    /// Fields only the imported node has
+    pub added: Vec<String>,
+    /// Fields only the existing node has
+    pub missing: Vec<String>,
+    /// Fields both have, with a different type, requiredness, constraints
+    /// or validations
+    pub changed: Vec<String>,
+}
+
+impl FieldDifferences {
+    /// Compare `existing` with the imported node `theirs`
+    pub fn between(existing: &Node, theirs: &Node) -> Self {
+        let mut differences = Self::default();
+        for field in &theirs.fields {
+            match existing.get_field(&field.name) {
+                None => differences.added.push(field.name.clone()),
+                Some(mine)
+                    if mine.data_type != field.data_type
+                        || mine.required != field.required
+                        || mine.constraints != field.constraints
+                        || mine.validations != field.validations =>
+                {
+                    differences.changed.push(field.name.clone())
+                }
+                Some(_) => {}
+            }
+        }
+        differences.missing =
+            existing.fields.iter().filter(|f| theirs.get_field(&f.name).is_none()).map(|f| f.name.clone()).collect();
+        differences
+    }
+
+    /// Whether the two nodes have the same fields
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.missing.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// Result of [`ProjectGraph::import_project`]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ImportSummary {
+    /// Group holding the import, unless nothing new was added
+    pub group: Option<Uuid>,
+    /// New nodes
+    pub added: Vec<NodeId>,
+    /// Imported nodes renamed to avoid a collision, as (old, new) names
+    pub renamed: Vec<(String, String)>,
+    /// Imported nodes merged into existing ones
+    pub merged: usize,
+    /// Imported nodes skipped in favour of existing ones
+    pub skipped: usize,
+    /// Existing nodes that took the imported node's fields
+    pub replaced: usize,
+}
+
+/// A standalone copy of part of a project, from [`ProjectGraph::extract`]
+#[derive(Debug, Clone, Default)]
+pub struct Extraction {
+    pub project: ProjectGraph,
+    /// Edges between an extracted and a left-out node
+    pub dropped_edges: usize,
+    /// Foreign keys to entities that were left out
+    pub dropped_foreign_keys: usize,
+}
+
+/// Nodes and edges affected by deleting nodes, from
+/// [`ProjectGraph::deletion_impact`]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DeletionImpact {
+    /// Nodes that depend on the deleted ones, directly or through others
+    pub dependents: HashSet<NodeId>,
+    /// Edges touching the deleted nodes or their dependents
+    pub edges: HashSet<EdgeId>,
+}
+
+/// Every location touched by a rename
+#[derive(Debug, Clone, PartialEq)]
+pub struct RenameSummary {
+    /// Name before the rename
+    pub old_name: String,
+    /// Name after the rename
+    pub new_name: String,
+    /// Touched locations, starting with the renamed node itself
+    pub changes: Vec<RenameChange>,
+}
+
+impl RenameSummary {
+    fn record(&mut self, node_id: Option<NodeId>, edge_id: Option<EdgeId>, description: String) {
+        self.changes.push(RenameChange { node_id, edge_id, description });
+    }
+
+    /// Number of locations updated besides the renamed node
+    pub fn dependent_count(&self) -> usize {
+        self.changes.len().saturating_sub(1)
+    }
+}
+
+/// Replace `/old/` and `/olds/` path segments; `None` if nothing matched
+fn rename_path_segments(path: &str, old: &str, new: &str) -> Option<String> {
+    let plural_old = format!("{}s", old);
+    let mut changed = false;
+    let renamed = path
+        .split('/')
+        .map(|segment| {
+            if segment == old {
+                changed = true;
+                new.to_string()
+            } else if segment == plural_old {
+                changed = true;
+                format!("{}s", new)
+            } else {
+                segment.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("/");
+    changed.then_some(renamed)
+}
+
+/// Replace whole-word occurrences of `old`; `None` if there were none
+fn replace_word(text: &str, old: &str, new: &str) -> Option<String> {
+    let is_word = |c: char| c.is_alphanumeric() || c == '_';
+    let mut result = String::with_capacity(text.len());
+    let mut last = 0;
+    let mut changed = false;
+
+    for (idx, _) in text.match_indices(old) {
+        let end = idx + old.len();
+        let before = text[..idx].chars().next_back();
+        let after = text[end..].chars().next();
+        if !before.is_some_and(is_word) && !after.is_some_and(is_word) {
+            result.push_str(&text[last..idx]);
+            result.push_str(new);
+            last = end;
+            changed = true;
+        }
+    }
+    result.push_str(&text[last..]);
+    changed.then_some(result)
+}
+
 /// Alignment options for nodes
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Alignment {
@@ -958,6 +2499,129 @@ pub enum Alignment {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use imortal_core::{ComponentCategory, DataType, PortDirection};
+
+    #[test]
+    fn test_merge_node_into() {
+        let mut graph = ProjectGraph::with_name("test");
+        let user = graph.add_node(Node::new_entity("User"));
+        let mut copy = Node::new_entity("User");
+        copy.add_field(Field::string("email"));
+        let copy = graph.add_node(copy);
+        let endpoint = graph.add_node(Node::new_rest_endpoint("Users"));
+        graph.add_edge(Edge::dependency(user, endpoint)).unwrap();
+        graph.add_edge(Edge::dependency(copy, endpoint)).unwrap();
+        graph.add_edge(Edge::dependency(endpoint, copy)).unwrap();
+
+        assert!(graph.merge_node_into(copy, user));
+        assert!(!graph.has_node(copy));
+        assert_eq!(graph.edge_count(), 2);
+        assert!(graph.edges().all(|e| e.connects_to(user)));
+        assert!(graph.get_node(user).unwrap().get_field("email").is_some());
+        assert!(!graph.merge_node_into(copy, user));
+    }
+
+    #[test]
+    fn test_merge_entities_with_conflicts() {
+        let mut graph = ProjectGraph::with_name("test");
+        let mut user = Node::new_entity("User");
+        user.add_field(Field::string("email"));
+        user.add_field(Field::string("name"));
+        let user = graph.add_node(user);
+        let mut account = Node::new_entity("Account");
+        account.add_field(Field::string("email").required());
+        account.add_field(Field::string("name").required());
+        account.add_field(Field::string("plan"));
+        let account = graph.add_node(account);
+
+        assert_eq!(graph.field_conflicts(account, user), vec!["email", "name"]);
+        graph.merge_entities(account, user, &["email".to_string()]).unwrap();
+
+        let merged = graph.get_node(user).unwrap();
+        assert!(merged.get_field("email").unwrap().required);
+        assert!(!merged.get_field("name").unwrap().required);
+        assert!(merged.get_field("plan").is_some());
+        assert!(!graph.has_node(account));
+        assert!(graph.merge_entities(user, user, &[]).is_err());
+    }
+
+    #[test]
+    fn test_split_entity() {
+        let mut graph = ProjectGraph::with_name("test");
+        let mut user = Node::new_entity("User");
+        user.add_field(Field::string("email"));
+        user.add_field(Field::string("street"));
+        user.add_field(Field::string("city"));
+        let user = graph.add_node(user);
+
+        let fields = vec!["id".to_string(), "street".to_string(), "city".to_string()];
+        let address = graph.split_entity(user, &fields, "Address").unwrap();
+
+        let user_node = graph.get_node(user).unwrap();
+        assert!(user_node.get_field("id").is_some());
+        assert!(user_node.get_field("street").is_none());
+        let address_node = graph.get_node(address).unwrap();
+        assert!(address_node.get_field("city").is_some());
+        assert_eq!(address_node.fields.len(), 3);
+        assert!(graph.edges().any(|e| {
+            e.is_from_to(user, address)
+                && e.connection_type == ConnectionType::Relationship(RelationType::OneToOne)
+        }));
+
+        assert!(graph.split_entity(user, &["id".to_string()], "Empty").is_err());
+    }
+
+    #[test]
+    fn test_remove_port_drops_attached_edges() {
+        let mut graph = ProjectGraph::with_name("test");
+        let mut logic = Node::new("logic.transformer", "Map").with_category(ComponentCategory::Logic);
+        let port = logic.add_custom_port(PortDirection::Output, "Result", DataType::Any).unwrap();
+        let logic = graph.add_node(logic);
+        let user = graph.add_node(Node::new_entity("User"));
+        graph.add_edge(Edge::new(logic, port.clone(), user, "entity")).unwrap();
+        graph.add_edge(Edge::dependency(logic, user)).unwrap();
+
+        assert_eq!(graph.port_edges(logic, &port).len(), 1);
+        assert_eq!(graph.remove_port(logic, &port).unwrap().name, "Result");
+        assert_eq!(graph.edge_count(), 1);
+        assert!(graph.remove_port(user, "entity").is_err());
+    }
+
+    #[test]
+    fn test_rename_entity_propagates() {
+        let mut graph = ProjectGraph::with_name("test");
+        let user = graph.add_node(Node::new_entity("User"));
+        let mut post = Node::new_entity("Post");
+        post.add_field(Field::new("author", DataType::Reference("User".to_string())));
+        let post = graph.add_node(post);
+        let list = graph.add_node(Node::new_rest_endpoint("Users"));
+        let mut login = Node::new_login();
+        login.set_config("entity", "User");
+        let login = graph.add_node(login);
+        let edge = graph.add_edge(Edge::dependency(user, list).with_label("User list")).unwrap();
+        graph.add_edge(Edge::dependency(post, list).with_label("UserPosts")).unwrap();
+
+        let summary = graph.rename_node(user, "Account").unwrap();
+        assert!(summary.dependent_count() >= 5);
+
+        let user_node = graph.get_node(user).unwrap();
+        assert_eq!(user_node.name, "Account");
+        assert_eq!(user_node.get_output_port("entity").unwrap().data_type, DataType::Entity("Account".to_string()));
+        assert_eq!(
+            graph.get_node(post).unwrap().get_field("author").unwrap().data_type,
+            DataType::Reference("Account".to_string())
+        );
+        assert_eq!(graph.get_node(list).unwrap().get_config_str("path"), Some("/accounts"));
+        let login_node = graph.get_node(login).unwrap();
+        assert_eq!(login_node.get_config_str("entity"), Some("Account"));
+        assert_eq!(login_node.get_output_port("user").unwrap().data_type, DataType::Entity("Account".to_string()));
+        assert_eq!(graph.get_edge(edge).unwrap().label.as_deref(), Some("Account list"));
+        assert!(graph.edges().any(|e| e.label.as_deref() == Some("UserPosts")));
+
+        graph.add_node(Node::new_entity("Member"));
+        assert!(graph.rename_node(user, "Member").is_err());
+        assert!(graph.rename_node(user, "  ").is_err());
+    }
 
     #[test]
     fn test_graph_creation() {
@@ -978,6 +2642,25 @@ mod tests {
         assert!(graph.dirty);
     }
 
+    #[test]
+    fn test_revision() {
+        let mut graph = ProjectGraph::with_name("test");
+        let before = graph.clone();
+        let id = graph.add_node(Node::new_entity("User"));
+        let added = graph.revision();
+        assert_ne!(added, before.revision());
+
+        graph.mark_saved();
+        assert_eq!(graph.revision(), added);
+        graph.get_node_mut(id).unwrap().name = "Account".to_string();
+        assert_ne!(graph.revision(), added);
+
+        // A different edit from the same earlier state gets its own revision
+        let mut other = before;
+        other.add_node(Node::new_entity("Post"));
+        assert_ne!(other.revision(), added);
+    }
+
     #[test]
     fn test_connect_nodes() {
         let mut graph = ProjectGraph::with_name("test");
@@ -1006,6 +2689,417 @@ mod tests {
         assert_eq!(graph.edge_count(), 0);
     }
 
+    #[test]
+    fn test_namespaces() {
+        let mut graph = ProjectGraph::with_name("test");
+        graph.add_node(Node::new_entity("User"));
+        graph.add_node(Node::new_entity("Invoice").with_namespace("billing::invoices"));
+        graph.add_node(Node::new_entity("Refund").with_namespace("billing::refunds"));
+        graph.add_node(Node::new_entity("Parcel").with_namespace("shipping"));
+
+        let paths: Vec<String> = graph.namespaces().iter().map(|ns| ns.join("::")).collect();
+        assert_eq!(paths, ["billing", "billing::invoices", "billing::refunds", "shipping"]);
+        assert_eq!(graph.child_namespaces(&[]), ["billing", "shipping"]);
+        assert_eq!(graph.child_namespaces(&["billing".to_string()]), ["invoices", "refunds"]);
+        assert!(graph.child_namespaces(&["shipping".to_string()]).is_empty());
+    }
+
+    #[test]
+    fn test_import_project() {
+        use crate::project::{ForeignKeyFields, ForeignKeyNaming};
+
+        let mut graph = ProjectGraph::with_name("shop");
+        let user = graph.add_node(Node::new_entity("User").with_position(0.0, 100.0));
+
+        let mut blog = ProjectGraph::with_name("blog");
+        blog.meta.foreign_keys = ForeignKeyFields::new(ForeignKeyNaming::SnakeCase);
+        let mut author = Node::new_entity("User");
+        author.add_field(Field::new("bio", DataType::Text));
+        let author = blog.add_node(author);
+        let comment = blog.add_node(Node::new_entity("Comment"));
+        blog.add_relationship(author, comment, RelationType::OneToMany).unwrap();
+
+        let count = |graph: &ProjectGraph, name: &str| graph.nodes().filter(|n| n.name == name).count();
+
+        // Rename keeps both, pointing the imported foreign key at the new name
+        let mut renamed = graph.clone();
+        let summary = renamed.import_project(&blog, ImportCollision::Rename);
+        assert_eq!(summary.renamed, [("User".to_string(), "User2".to_string())]);
+        assert_eq!(summary.added.len(), 2);
+        assert_eq!(renamed.node_count(), 3);
+        assert_eq!(renamed.edge_count(), 1);
+        let comment = renamed.nodes().find(|n| n.name == "Comment").unwrap();
+        let field = comment.get_field("user_id").unwrap();
+        assert!(field.constraints.iter().any(|c| matches!(c, FieldConstraint::ForeignKey { entity, .. } if entity == "User2")));
+        // The field still knows which (remapped) relationship added it
+        let edge = renamed.edges.keys().next().unwrap().to_string();
+        assert_eq!(field.metadata.get(RELATIONSHIP_METADATA_KEY).and_then(|v| v.as_str()), Some(edge.as_str()));
+
+        // Everything new sits in one group to the right of the existing nodes
+        let group = &renamed.groups[&summary.group.unwrap()];
+        assert_eq!(group.name, "blog");
+        assert_eq!(group.node_ids.len(), 2);
+        assert!(summary.added.iter().all(|id| renamed.get_node(*id).unwrap().position.x > 0.0));
+        assert!(summary.added.iter().all(|id| !blog.nodes.contains_key(id)));
+
+        // Merge connects the import to the existing entity and adds its fields
+        let mut merged = graph.clone();
+        let summary = merged.import_project(&blog, ImportCollision::Merge);
+        assert_eq!((summary.merged, summary.added.len()), (1, 1));
+        assert_eq!(count(&merged, "User"), 1);
+        assert!(merged.get_node(user).unwrap().get_field("bio").is_some());
+        assert!(merged.edges().any(|e| e.from_node == user));
+
+        // Skip connects to the existing entity without changing it
+        let summary = graph.import_project(&blog, ImportCollision::Skip);
+        assert_eq!(summary.skipped, 1);
+        assert!(graph.get_node(user).unwrap().get_field("bio").is_none());
+        assert!(graph.edges().any(|e| e.from_node == user));
+    }
+
+    #[test]
+    fn test_import_project_with() {
+        use crate::project::{ForeignKeyFields, ForeignKeyNaming};
+
+        let mut graph = ProjectGraph::with_name("shop");
+        graph.meta.foreign_keys = ForeignKeyFields::new(ForeignKeyNaming::SnakeCase);
+        let mut user = Node::new_entity("User");
+        user.add_field(Field::new("nickname", DataType::String));
+        user.add_field(Field::new("age", DataType::Int32));
+        let user = graph.add_node(user);
+        let tag = graph.add_node(Node::new_entity("Tag"));
+        let order = graph.add_node(Node::new_entity("Order"));
+        graph.add_relationship(user, order, RelationType::OneToMany).unwrap();
+
+        let mut other = ProjectGraph::with_name("crm");
+        let mut theirs = Node::new_entity("Order");
+        theirs.add_field(Field::new("total", DataType::Float64));
+        other.add_node(theirs);
+        let mut theirs = Node::new_entity("User");
+        theirs.add_field(Field::new("age", DataType::Int64));
+        theirs.add_field(Field::new("bio", DataType::Text));
+        other.add_node(theirs);
+        other.add_node(Node::new_entity("Tag"));
+
+        let collisions = graph.import_collisions(&other);
+        let differences = FieldDifferences::between(collisions[2].0, collisions[2].1);
+        assert_eq!(differences.added, ["bio"]);
+        assert_eq!(differences.missing, ["nickname"]);
+        assert_eq!(differences.changed, ["age"]);
+        assert!(FieldDifferences::between(collisions[1].0, collisions[1].1).is_empty());
+
+        let summary = graph.import_project_with(&other, |existing, _| match existing.name.as_str() {
+            "User" => ImportCollision::Replace,
+            "Order" => ImportCollision::Replace,
+            _ => ImportCollision::Skip,
+        });
+        assert_eq!((summary.replaced, summary.skipped, summary.added.len()), (2, 1, 0));
+        assert_eq!(graph.node_count(), 3);
+        assert!(graph.has_node(tag));
+
+        let user = graph.get_node(user).unwrap();
+        assert!(user.get_field("nickname").is_none());
+        assert_eq!(user.get_field("age").unwrap().data_type, DataType::Int64);
+        assert!(user.get_field("bio").is_some());
+        // The foreign key our relationship added survives taking theirs
+        let order = graph.get_node(order).unwrap();
+        assert!(order.get_field("total").is_some());
+        assert!(order.get_field("user_id").is_some());
+    }
+
+    #[test]
+    fn test_without_disabled() {
+        let mut graph = ProjectGraph::with_name("test");
+        let user = graph.add_node(Node::new_entity("User"));
+        let draft = graph.add_node(Node::new_entity("Draft"));
+        graph.add_edge(Edge::dependency(user, draft)).unwrap();
+        graph.get_node_mut(draft).unwrap().enabled = false;
+
+        let enabled = graph.without_disabled();
+        assert!(enabled.has_node(user));
+        assert!(!enabled.has_node(draft));
+        assert!(enabled.edges.is_empty());
+        assert_eq!(graph.node_count(), 2);
+    }
+
+    #[test]
+    fn test_tagged() {
+        let mut graph = ProjectGraph::with_name("test");
+        let user = graph.add_node(Node::new_entity("User").with_tag("MVP"));
+        let order = graph.add_node(Node::new_entity("Order").with_tag("MVP").with_tag("phase-2"));
+        let invoice = graph.add_node(Node::new_entity("Invoice").with_tag("phase-2"));
+        graph.add_edge(Edge::dependency(user, order)).unwrap();
+        graph.add_edge(Edge::dependency(order, invoice)).unwrap();
+        assert_eq!(graph.tags(), [("MVP".to_string(), 2), ("phase-2".to_string(), 2)]);
+
+        let mvp = graph.tagged(&["MVP".to_string()]);
+        assert!(mvp.has_node(user) && mvp.has_node(order));
+        assert!(!mvp.has_node(invoice));
+        assert_eq!(mvp.edge_count(), 1);
+        assert_eq!(graph.tagged(&[]).node_count(), 3);
+        assert_eq!(crate::parse_tags(" MVP, ,phase-2,MVP"), ["MVP", "phase-2"]);
+    }
+
+    #[test]
+    fn test_extract() {
+        use crate::project::{ForeignKeyFields, ForeignKeyNaming};
+
+        let mut graph = ProjectGraph::with_name("monolith");
+        graph.meta.foreign_keys = ForeignKeyFields::new(ForeignKeyNaming::SnakeCase);
+        let user = graph.add_node(Node::new_entity("User"));
+        let order = graph.add_node(Node::new_entity("Order"));
+        let item = graph.add_node(Node::new_entity("Item"));
+        graph.add_relationship(order, item, RelationType::OneToMany).unwrap();
+        let user_orders = graph.add_relationship(user, order, RelationType::OneToMany).unwrap();
+
+        let outer = graph.create_group("Backend");
+        let inner = graph.create_group("Orders");
+        graph.groups.get_mut(&inner).unwrap().parent_id = Some(outer);
+        for id in [order, item, user] {
+            graph.add_node_to_group(id, inner).unwrap();
+        }
+
+        let extraction = graph.extract(&HashSet::from([order, item]));
+        let orders = &extraction.project;
+        assert_eq!(orders.node_count(), 2);
+        assert_eq!(orders.edge_count(), 1);
+        assert_eq!(extraction.dropped_edges, 1);
+
+        // Both groups come along, holding only extracted nodes
+        assert_eq!(orders.group_count(), 2);
+        assert_eq!(orders.groups[&inner].node_ids, HashSet::from([order, item]));
+        assert_eq!(orders.groups[&inner].parent_id, Some(outer));
+
+        // Order's foreign key to the left-out User entity is dropped, and
+        // the field no longer claims the missing relationship
+        assert_eq!(extraction.dropped_foreign_keys, 1);
+        let field = orders.get_node(order).unwrap().get_field("user_id").unwrap();
+        assert!(!field.is_foreign_key());
+        assert!(!field.metadata.contains_key(RELATIONSHIP_METADATA_KEY));
+        assert!(orders.get_node(item).unwrap().get_field("order_id").unwrap().is_foreign_key());
+        assert!(!orders.edges.contains_key(&user_orders));
+    }
+
+    #[test]
+    fn test_relationship_foreign_key_fields() {
+        use crate::project::{ForeignKeyFields, ForeignKeyNaming};
+
+        let mut graph = ProjectGraph::with_name("test");
+        let user = graph.add_node(Node::new_entity("User"));
+        let post = graph.add_node(Node::new_entity("Post"));
+
+        // Off by default
+        let edge = graph.add_relationship(user, post, RelationType::OneToMany).unwrap();
+        assert!(graph.get_node(post).unwrap().get_field("user_id").is_none());
+        graph.remove_edge(edge);
+
+        graph.meta.foreign_keys = ForeignKeyFields::new(ForeignKeyNaming::SnakeCase);
+        let edge = graph.add_relationship(user, post, RelationType::OneToMany).unwrap();
+        let field = graph.get_node(post).unwrap().get_field("user_id").unwrap();
+        assert_eq!(field.data_type, DataType::Reference("User".to_string()));
+        assert!(field.is_foreign_key());
+        assert!(field.required);
+
+        // Renaming the parent retargets the constraint
+        graph.rename_node(user, "Author").unwrap();
+        let field = graph.get_node(post).unwrap().get_field("user_id").unwrap();
+        assert!(field.constraints.iter().any(|c| matches!(c, FieldConstraint::ForeignKey { entity, .. } if entity == "Author")));
+
+        // Other relationship kinds don't add fields
+        graph.add_relationship(post, user, RelationType::ManyToMany).unwrap();
+        assert!(graph.get_node(user).unwrap().get_field("post_id").is_none());
+
+        graph.remove_edge(edge);
+        assert!(graph.get_node(post).unwrap().get_field("user_id").is_none());
+    }
+
+    #[test]
+    fn test_add_inferred_entity() {
+        let mut graph = ProjectGraph::with_name("test");
+        graph.add_node(Node::new_entity("Address"));
+        let entity = crate::infer_entity(
+            r#"{"name": "Ada", "address": {"city": "London"}, "posts": [{"title": "Hi"}], "tags": [{"label": "a"}]}"#,
+            "User",
+        ).unwrap();
+        let added = graph.add_inferred_entity(&entity, Position::new(100.0, 50.0)).unwrap();
+        assert_eq!(added.len(), 4);
+
+        let names: Vec<_> = added.iter().map(|id| graph.get_node(*id).unwrap().name.clone()).collect();
+        assert_eq!(names, vec!["User", "Address2", "Post", "Tag"]);
+        let user = graph.get_node(added[0]).unwrap();
+        assert_eq!((user.position.x, user.position.y), (100.0, 50.0));
+        assert!(user.get_field("id").is_some() && user.get_field("name").is_some());
+
+        let relation = |to: NodeId| graph.edges().find(|e| e.from_node == added[0] && e.to_node == to).and_then(|e| e.relationship_type());
+        assert_eq!(relation(added[1]), Some(RelationType::OneToOne));
+        assert_eq!(relation(added[2]), Some(RelationType::OneToMany));
+
+        // Children stack to the right without overlapping
+        let bounds: Vec<_> = added[1..].iter().map(|id| graph.get_node(*id).unwrap().effective_bounds()).collect();
+        assert!(bounds.iter().all(|b| b.0 > 100.0 + user.effective_bounds().2));
+        assert!(bounds.windows(2).all(|w| w[1].1 >= w[0].1 + w[0].3));
+    }
+
+    #[test]
+    fn test_relationship_on_fields() {
+        use crate::project::{ForeignKeyFields, ForeignKeyNaming};
+
+        let mut graph = ProjectGraph::with_name("test");
+        graph.meta.foreign_keys = ForeignKeyFields::new(ForeignKeyNaming::SnakeCase);
+        let user = graph.add_node(Node::new_entity("User"));
+        let mut post = Node::new_entity("Post");
+        post.add_field(Field::uuid("author_id"));
+        post.add_field(Field::string("title"));
+        let post = graph.add_node(post);
+
+        // Joining on a key the post already has adds no field of its own
+        let edge = graph.add_field_relationship(user, "id", post, "author_id", RelationType::OneToMany).unwrap();
+        assert!(graph.get_node(post).unwrap().get_field("user_id").is_none());
+        let label = graph.relationship_label(graph.get_edge(edge).unwrap());
+        assert_eq!(label.as_deref(), Some("Post.author_id → User.id"));
+
+        assert!(graph.set_relationship_fields(edge, Some("id"), Some("title")).unwrap_err().to_string().contains("Post.title"));
+        assert!(graph.set_relationship_fields(edge, Some("id"), Some("editor_id")).unwrap_err().to_string().contains("no field"));
+        assert_eq!(graph.get_edge(edge).unwrap().to_field(), Some("author_id"));
+
+        graph.set_relationship_fields(edge, None, None).unwrap();
+        assert_eq!(graph.relationship_label(graph.get_edge(edge).unwrap()), None);
+    }
+
+    #[test]
+    fn test_polymorphic_targets() {
+        let mut graph = ProjectGraph::with_name("blog");
+        let comment = graph.add_node(Node::new_entity("Comment"));
+        let post = graph.add_node(Node::new_entity("Post"));
+        let photo = graph.add_node(Node::new_entity("Photo"));
+        let endpoint = graph.add_node(Node::new_rest_endpoint("comments"));
+
+        graph.set_polymorphic_targets(comment, "commentable", &[post, photo]).unwrap();
+        let associations = graph.polymorphic_associations(comment);
+        assert_eq!(associations.len(), 1);
+        assert_eq!(associations[0].targets, [photo, post]);
+        let fields = graph.polymorphic_fields(comment);
+        let names: Vec<&str> = fields.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(names, ["commentable_type", "commentable_id"]);
+        assert_eq!(fields[1].data_type, DataType::Uuid);
+        // No foreign key field, as there is no single table to reference
+        assert_eq!(graph.get_node(comment).unwrap().fields.len(), 1);
+
+        graph.set_polymorphic_targets(comment, "commentable", &[post]).unwrap();
+        assert_eq!(graph.polymorphic_associations(comment)[0].targets, [post]);
+        assert!(graph.set_polymorphic_targets(comment, "commentable", &[endpoint]).is_err());
+        graph.set_polymorphic_targets(comment, "commentable", &[]).unwrap();
+        assert!(graph.polymorphic_associations(comment).is_empty());
+
+        // Unnamed edges, as a hand-edited project file might have
+        graph.add_relationship(comment, post, RelationType::Polymorphic).unwrap();
+        assert_eq!(graph.polymorphic_associations(comment)[0].name, "commentable");
+    }
+
+    #[test]
+    fn test_flag_gate() {
+        let mut graph = ProjectGraph::new(ProjectMeta::new("shop"));
+        let mut flags = Node::new_feature_flag("Flags");
+        flags.set_config("flags", "new_checkout, beta_search");
+        let flags = graph.add_node(flags);
+        let endpoint = graph.add_node(Node::new_rest_endpoint("Checkout"));
+
+        graph.set_flag_gate(endpoint, Some((flags, "new_checkout".to_string()))).unwrap();
+        assert_eq!(graph.flag_gate(endpoint).map(|(n, flag)| (n.id, flag)), Some((flags, "new_checkout")));
+
+        // A node sits behind one flag at a time
+        graph.set_flag_gate(endpoint, Some((flags, "beta_search".to_string()))).unwrap();
+        assert_eq!(graph.edges().filter(|e| e.is_gate()).count(), 1);
+        assert!(graph.set_flag_gate(endpoint, Some((flags, "missing".to_string()))).is_err());
+        assert!(graph.set_flag_gate(flags, Some((flags, "beta_search".to_string()))).is_err());
+
+        // Without a gating edge the `feature_flag` config names the flag
+        graph.set_flag_gate(endpoint, None).unwrap();
+        assert!(graph.flag_gate(endpoint).is_none());
+        graph.get_node_mut(endpoint).unwrap().set_config("feature_flag", "new_checkout");
+        assert_eq!(graph.flag_gate(endpoint).map(|(n, flag)| (n.id, flag)), Some((flags, "new_checkout")));
+    }
+
+    #[test]
+    fn test_set_cache() {
+        let mut graph = ProjectGraph::with_name("shop");
+        let endpoint = graph.add_node(Node::new_rest_endpoint("products"));
+        let cache = graph.add_node(Node::new_cache("Product Cache"));
+        let other = graph.add_node(Node::new_cache("Other Cache"));
+        let entity = graph.add_node(Node::new_entity("Product"));
+
+        graph.set_cache(endpoint, Some((cache, CachePolicy::default()))).unwrap();
+        let (node, policy) = graph.cache_for(endpoint).unwrap();
+        assert_eq!(node.id, cache);
+        assert_eq!(policy.ttl_seconds, 300);
+
+        // An endpoint uses one cache at a time
+        graph.set_cache(endpoint, Some((other, CachePolicy::new(60, "{path}")))).unwrap();
+        assert_eq!(graph.edges().filter(|e| e.is_caching()).count(), 1);
+        assert_eq!(graph.cache_for(endpoint).unwrap().0.id, other);
+
+        assert!(graph.set_cache(endpoint, Some((entity, CachePolicy::default()))).is_err());
+        assert!(graph.set_cache(cache, Some((other, CachePolicy::default()))).is_err());
+        graph.set_cache(endpoint, None).unwrap();
+        assert!(graph.cache_for(endpoint).is_none());
+    }
+
+    #[test]
+    fn test_custom_types() {
+        let mut graph = ProjectGraph::with_name("shop");
+        let email = CustomType::new("Email", DataType::String);
+        let mut user = Node::new_entity("User");
+        user.add_field(Field::new("email", email.data_type()));
+        user.add_field(Field::new("backup_emails", DataType::Array(Box::new(email.data_type()))));
+        graph.add_node(user);
+        graph.meta.mixins.push(Mixin::new("Contact").with_field(Field::new("contact_email", email.data_type())));
+        graph.meta.types.push(email);
+
+        assert_eq!(graph.custom_type("Email").map(|t| &t.underlying), Some(&DataType::String));
+        assert_eq!(graph.custom_type_usages("Email"), 3);
+        assert_eq!(graph.rename_custom_type("Email", "EmailAddress"), 3);
+        assert!(graph.custom_type("Email").is_none());
+        assert_eq!(graph.custom_type_usages("EmailAddress"), 3);
+        assert_eq!(graph.rename_custom_type("Missing", "Other"), 0);
+    }
+
+    #[test]
+    fn test_inheritance_and_mixins() {
+        let mut graph = ProjectGraph::with_name("test");
+        let mut content = Node::new_entity("Content");
+        content.add_field(Field::string("title"));
+        content.mixins.push("Timestamps".to_string());
+        let content = graph.add_node(content);
+        let mut post = Node::new_entity("Post");
+        post.add_field(Field::text("title"));
+        post.add_field(Field::text("body"));
+        post.mixins.push("SoftDelete".to_string());
+        let post = graph.add_node(post);
+        let draft = graph.add_node(Node::new_entity("Draft"));
+
+        graph.set_parent_entity(post, Some(content)).unwrap();
+        graph.set_parent_entity(draft, Some(post)).unwrap();
+        assert_eq!(graph.ancestors(draft), [post, content]);
+
+        let fields = graph.effective_fields(draft);
+        let names: Vec<&str> = fields.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(names, ["id", "title", "created_at", "updated_at", "body", "deleted_at"]);
+        assert_eq!(fields[1].data_type, DataType::Text, "Post redeclares the title");
+
+        // No cycles, and a new parent replaces the old one
+        assert!(graph.set_parent_entity(content, Some(draft)).is_err());
+        graph.set_parent_entity(draft, Some(content)).unwrap();
+        assert_eq!(graph.ancestors(draft), [content]);
+        assert_eq!(graph.edges().filter(|e| e.is_inheritance()).count(), 2);
+        graph.set_parent_entity(draft, None).unwrap();
+        assert_eq!(graph.parent_entity(draft), None);
+
+        // Project mixins replace built-in ones of the same name
+        graph.meta.mixins.push(Mixin::new("Timestamps").with_field(Field::datetime("stamped_at")));
+        assert!(graph.effective_fields(post).iter().any(|f| f.name == "stamped_at"));
+        assert_eq!(graph.mixins().iter().filter(|m| m.name == "Timestamps").count(), 1);
+    }
+
     #[test]
     fn test_selection() {
         let mut graph = ProjectGraph::with_name("test");
@@ -1024,6 +3118,20 @@ mod tests {
         assert!(!graph.has_selection());
     }
 
+    #[test]
+    fn test_set_all_collapsed() {
+        let mut graph = ProjectGraph::with_name("test");
+        let user = graph.add_node(Node::new_entity("User"));
+        graph.add_node(Node::new_database("Main"));
+
+        graph.get_node_mut(user).unwrap().toggle_collapsed();
+        graph.set_all_collapsed(true);
+        assert!(graph.nodes().all(|n| n.collapsed));
+
+        graph.set_all_collapsed(false);
+        assert!(graph.nodes().all(|n| !n.collapsed));
+    }
+
     #[test]
     fn test_groups() {
         let mut graph = ProjectGraph::with_name("test");
@@ -1110,4 +3218,126 @@ mod tests {
         let downstream = graph.downstream_nodes(user);
         assert!(downstream.contains(&post));
     }
+
+    #[test]
+    fn test_upstream_downstream_within() {
+        let mut graph = ProjectGraph::with_name("test");
+        let ids: Vec<NodeId> = ["A", "B", "C", "D"].iter().map(|n| graph.add_node(Node::new_entity(*n))).collect();
+        for pair in ids.windows(2) {
+            graph.add_edge(Edge::dependency(pair[0], pair[1])).unwrap();
+        }
+        // A cycle back to the start doesn't loop or include it
+        graph.add_edge(Edge::dependency(ids[3], ids[0])).unwrap();
+
+        assert_eq!(graph.downstream_within(ids[0], Some(1)), HashSet::from([ids[1]]));
+        assert_eq!(graph.downstream_within(ids[0], Some(2)), HashSet::from([ids[1], ids[2]]));
+        assert_eq!(graph.downstream_within(ids[0], None), HashSet::from([ids[1], ids[2], ids[3]]));
+        assert_eq!(graph.upstream_within(ids[2], Some(2)), HashSet::from([ids[1], ids[0]]));
+        assert!(graph.upstream_within(ids[2], Some(0)).is_empty());
+    }
+
+    #[test]
+    fn test_deletion_impact() {
+        let mut graph = ProjectGraph::with_name("test");
+        let ids: Vec<NodeId> = ["A", "B", "C", "D"].iter().map(|n| graph.add_node(Node::new_entity(*n))).collect();
+        let ab = graph.add_edge(Edge::dependency(ids[0], ids[1])).unwrap();
+        let bc = graph.add_edge(Edge::dependency(ids[1], ids[2])).unwrap();
+        let da = graph.add_edge(Edge::dependency(ids[3], ids[0])).unwrap();
+
+        let impact = graph.deletion_impact(&HashSet::from([ids[0]]));
+        assert_eq!(impact.dependents, HashSet::from([ids[1], ids[2]]));
+        assert_eq!(impact.edges, HashSet::from([ab, bc, da]));
+
+        // Deleting a dependent along with its source doesn't list it
+        let impact = graph.deletion_impact(&HashSet::from([ids[0], ids[1]]));
+        assert_eq!(impact.dependents, HashSet::from([ids[2]]));
+        let impact = graph.deletion_impact(&HashSet::from([ids[2]]));
+        assert!(impact.dependents.is_empty());
+        assert_eq!(impact.edges, HashSet::from([bc]));
+    }
+
+    #[test]
+    fn test_required_dependencies() {
+        use crate::field::ForeignKeyBuilder;
+
+        let mut graph = ProjectGraph::with_name("test");
+        let base = graph.add_node(Node::new_entity("Base"));
+        let user = graph.add_node(Node::new_entity("User"));
+        let mut post = Node::new_entity("Post");
+        post.add_field(Field::new("author_id", DataType::Uuid).with_constraint(ForeignKeyBuilder::new("User", "id").build()));
+        let post = graph.add_node(post);
+        let endpoint = graph.add_node(Node::new("api.rest", "Posts"));
+        let screen = graph.add_node(Node::new("ui.page", "Home"));
+        let unrelated = graph.add_node(Node::new_entity("Audit"));
+        // Without the ports these components would have
+        for edge in [
+            Edge::inheritance(user, base),
+            Edge::data_flow(post, "out", endpoint, "in"),
+            Edge::navigation(screen, "out", endpoint, "in"),
+            Edge::dependency(endpoint, unrelated),
+        ] {
+            graph.edges.insert(edge.id, edge);
+        }
+
+        let needed = graph.required_dependencies(&HashSet::from([endpoint]));
+        assert_eq!(needed, HashSet::from([post, user, base]));
+        assert!(graph.required_dependencies(&HashSet::from([unrelated])).contains(&endpoint));
+        assert!(graph.required_dependencies(&HashSet::from([base])).is_empty());
+    }
+
+    #[test]
+    fn test_slides() {
+        let mut graph = ProjectGraph::with_name("test");
+        graph.add_slide("Overview");
+        graph.set_pan(-400.0, 120.0);
+        graph.set_zoom(2.0);
+        let detail = graph.add_slide("Auth flow");
+
+        assert_eq!(detail, 1);
+        assert_eq!(graph.slides[1].viewport, graph.viewport);
+        graph.move_slide(1, 0);
+        assert_eq!(graph.slides[0].name, "Auth flow");
+        assert_eq!(graph.remove_slide(1).unwrap().viewport, Viewport::default());
+        assert!(graph.remove_slide(5).is_none());
+
+        let halfway = Viewport::default().lerp(&graph.slides[0].viewport, 0.5);
+        assert_eq!(halfway, Viewport { pan_x: -200.0, pan_y: 60.0, zoom: 1.5 });
+    }
+
+    #[test]
+    fn test_bookmarks() {
+        let mut graph = ProjectGraph::with_name("test");
+        let group = graph.add_group(Group::new("Auth"));
+        graph.set_pan(250.0, -80.0);
+        graph.select_group(group);
+        let auth = graph.add_bookmark("Auth");
+        graph.clear_selection();
+        graph.reset_viewport();
+        graph.add_bookmark("Home");
+
+        assert!(graph.go_to_bookmark(auth));
+        assert_eq!(graph.viewport.pan_x, 250.0);
+        assert!(graph.selected_groups.contains(&group));
+        assert_eq!(graph.bookmarks[1].group, None);
+
+        assert_eq!(graph.remove_bookmark(auth).unwrap().name, "Auth");
+        assert!(!graph.go_to_bookmark(1));
+    }
+
+    #[test]
+    fn test_zoom_at_keeps_anchor() {
+        let mut graph = ProjectGraph::with_name("test");
+        graph.set_pan(100.0, 50.0);
+
+        // World point under the anchor before and after zooming
+        let world = |g: &ProjectGraph| ((300.0 - g.viewport.pan_x) / g.viewport.zoom, (200.0 - g.viewport.pan_y) / g.viewport.zoom);
+        let before = world(&graph);
+        graph.zoom_at(2.0, 300.0, 200.0);
+        assert_eq!(graph.viewport.zoom, 2.0);
+        assert_eq!(world(&graph), before);
+
+        graph.zoom_at(100.0, 300.0, 200.0);
+        assert_eq!(graph.viewport.zoom, 5.0);
+        assert_eq!(world(&graph), before);
+    }
 }
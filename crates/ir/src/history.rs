@@ -0,0 +1,200 @@
+//! Snapshot history of saved project files
+//!
+//! Every save can copy the project file into a `.imortal_history/` directory
+//! next to it, so earlier versions can be restored after a bad edit. Snapshots
+//! are named `<file stem>.<unix millis>.<extension>` and pruned to the newest
+//! [`SnapshotConfig::max_count`] that fit in [`SnapshotConfig::max_total_bytes`].
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use imortal_core::EngineResult;
+
+use crate::graph::ProjectGraph;
+use crate::serialization::load_project;
+
+/// Directory holding snapshots, next to the project file
+pub const HISTORY_DIR: &str = ".imortal_history";
+
+/// How many snapshots to keep
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SnapshotConfig {
+    /// Whether saving takes a snapshot
+    pub enabled: bool,
+    /// Most snapshots kept per project
+    pub max_count: usize,
+    /// Most bytes all of a project's snapshots may take up
+    pub max_total_bytes: u64,
+}
+
+impl Default for SnapshotConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_count: 20,
+            max_total_bytes: 50 * 1024 * 1024,
+        }
+    }
+}
+
+/// A saved copy of a project file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Snapshot {
+    /// Snapshot file
+    pub path: PathBuf,
+    /// When the snapshot was taken, in milliseconds since the Unix epoch
+    pub timestamp: u64,
+    /// File size in bytes
+    pub size: u64,
+}
+
+impl Snapshot {
+    /// Load the project as it was when the snapshot was taken
+    pub fn load(&self) -> EngineResult<ProjectGraph> {
+        load_project(&self.path)
+    }
+}
+
+/// Directory the snapshots of a project file go in
+pub fn history_dir(project: impl AsRef<Path>) -> PathBuf {
+    let project = project.as_ref();
+    project.parent().unwrap_or(Path::new("")).join(HISTORY_DIR)
+}
+
+/// Copy a just-saved project file into its history and prune old snapshots
+///
+/// Returns the new snapshot, or `None` when snapshots are disabled or the
+/// file is unchanged since the latest one.
+pub fn take_snapshot(project: impl AsRef<Path>, config: &SnapshotConfig) -> EngineResult<Option<Snapshot>> {
+    let project = project.as_ref();
+    if !config.enabled || config.max_count == 0 {
+        return Ok(None);
+    }
+
+    let contents = fs::read(project)?;
+    let existing = list_snapshots(project)?;
+    if let Some(latest) = existing.first() {
+        if fs::read(&latest.path).is_ok_and(|previous| previous == contents) {
+            return Ok(None);
+        }
+    }
+
+    let dir = history_dir(project);
+    fs::create_dir_all(&dir)?;
+    // Saves within the same millisecond still get distinct files
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_millis() as u64).unwrap_or(0);
+    let timestamp = existing.first().map_or(now, |latest| now.max(latest.timestamp + 1));
+    let path = dir.join(snapshot_name(project, timestamp));
+    fs::write(&path, &contents)?;
+
+    let snapshot = Snapshot { path, timestamp, size: contents.len() as u64 };
+    prune(std::iter::once(snapshot.clone()).chain(existing), config)?;
+    Ok(Some(snapshot))
+}
+
+/// Snapshots of a project file, newest first
+pub fn list_snapshots(project: impl AsRef<Path>) -> EngineResult<Vec<Snapshot>> {
+    let project = project.as_ref();
+    let dir = history_dir(project);
+    if !dir.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    let (stem, extension) = name_parts(project);
+    let mut snapshots = Vec::new();
+    for entry in fs::read_dir(&dir)? {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().to_string();
+        let timestamp = name
+            .strip_prefix(&format!("{}.", stem))
+            .and_then(|rest| rest.strip_suffix(&format!(".{}", extension)))
+            .and_then(|timestamp| timestamp.parse().ok());
+        if let Some(timestamp) = timestamp {
+            snapshots.push(Snapshot { path: entry.path(), timestamp, size: entry.metadata()?.len() });
+        }
+    }
+    snapshots.sort_by_key(|snapshot| std::cmp::Reverse(snapshot.timestamp));
+    Ok(snapshots)
+}
+
+/// Delete snapshots beyond the count and size limits, always keeping the
+/// newest
+fn prune(snapshots: impl Iterator<Item = Snapshot>, config: &SnapshotConfig) -> EngineResult<()> {
+    let mut total = 0;
+    for (index, snapshot) in snapshots.enumerate() {
+        total += snapshot.size;
+        if index > 0 && (index >= config.max_count || total > config.max_total_bytes) {
+            fs::remove_file(&snapshot.path)?;
+        }
+    }
+    Ok(())
+}
+
+fn name_parts(project: &Path) -> (String, String) {
+    let stem = project.file_stem().unwrap_or_default().to_string_lossy().to_string();
+    let extension = project.extension().unwrap_or_default().to_string_lossy().to_string();
+    (stem, extension)
+}
+
+fn snapshot_name(project: &Path, timestamp: u64) -> String {
+    let (stem, extension) = name_parts(project);
+    format!("{}.{}.{}", stem, timestamp, extension)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::node::Node;
+    use crate::project::ProjectMeta;
+    use crate::serialization::{save_project, ProjectFormat};
+
+    #[test]
+    fn test_snapshots_roll_over() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("shop.imortal");
+        let config = SnapshotConfig { max_count: 3, ..SnapshotConfig::default() };
+        let mut project = ProjectGraph::new(ProjectMeta::new("shop"));
+
+        for i in 0..5 {
+            project.add_node(Node::new_entity(format!("Entity{}", i)));
+            save_project(&project, &path, ProjectFormat::Json).unwrap();
+            assert!(take_snapshot(&path, &config).unwrap().is_some());
+        }
+        // Saving again without changes doesn't add a snapshot
+        assert!(take_snapshot(&path, &config).unwrap().is_none());
+
+        let snapshots = list_snapshots(&path).unwrap();
+        let counts: Vec<_> = snapshots.iter().map(|s| s.load().unwrap().node_count()).collect();
+        assert_eq!(counts, [5, 4, 3]);
+        assert!(snapshots[0].path.starts_with(dir.path().join(HISTORY_DIR)));
+
+        // Another project in the same directory has its own history
+        assert!(list_snapshots(dir.path().join("blog.imortal")).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_snapshot_size_limit() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("shop.imortal");
+        let mut project = ProjectGraph::new(ProjectMeta::new("shop"));
+        save_project(&project, &path, ProjectFormat::Json).unwrap();
+        let size = fs::metadata(&path).unwrap().len();
+
+        // Room for one snapshot, but the newest is always kept
+        let config = SnapshotConfig { max_total_bytes: size, ..SnapshotConfig::default() };
+        take_snapshot(&path, &config).unwrap();
+        project.add_node(Node::new_entity("User"));
+        save_project(&project, &path, ProjectFormat::Json).unwrap();
+        take_snapshot(&path, &config).unwrap();
+
+        let snapshots = list_snapshots(&path).unwrap();
+        assert_eq!(snapshots.len(), 1);
+        assert_eq!(snapshots[0].load().unwrap().node_count(), 1);
+
+        let disabled = SnapshotConfig { enabled: false, ..SnapshotConfig::default() };
+        assert!(take_snapshot(&path, &disabled).unwrap().is_none());
+    }
+}
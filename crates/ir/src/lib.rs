@@ -58,21 +58,55 @@ pub mod node;
 pub mod edge;
 pub mod port;
 pub mod field;
+pub mod field_import;
 pub mod project;
 pub mod group;
+pub mod background;
 pub mod validation;
+pub mod query;
+pub mod analysis;
+pub mod assist;
+pub mod stats;
 pub mod serialization;
+pub mod workspace;
+pub mod lock;
+pub mod history;
+pub mod composite;
+pub mod mixin;
+pub mod custom_type;
+pub mod macros;
+pub mod placement;
+pub mod suggestions;
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
 
 // Re-export main types at crate root
-pub use graph::ProjectGraph;
-pub use node::Node;
-pub use edge::{Edge, DataMapping};
+pub use graph::{Bookmark, DeletionImpact, Extraction, FieldDifferences, ImportCollision, ImportSummary, ProjectGraph, RenameChange, RenameSummary, Slide};
+pub use node::{parse_feature_flags, parse_namespace, parse_rust_snippet, parse_tags, CodegenHints, FeatureFlag, Node, SnippetError};
+pub use edge::{Edge, CachePolicy, DataMapping, EdgeStyle, EdgeColor, LineStyle, ArrowStyle, PolymorphicAssociation, RelationshipKey};
 pub use port::Port;
-pub use field::Field;
-pub use project::ProjectMeta;
+pub use field::{Field, Unit};
+pub use field_import::{infer_entity, infer_fields, InferredEntity, InferredField};
+pub use project::{ApiVersioning, CodegenSettings, ForeignKeyFields, ForeignKeyNaming, ProjectMeta, TransformCommand};
 pub use group::Group;
-pub use validation::{ValidationError, ValidationResult, Validator};
-pub use serialization::{ProjectFormat, load_project, save_project};
+pub use background::{BackgroundKind, BackgroundLayer, Lane, LaneOrientation};
+pub use validation::{apply_fixes, QuickFix, ValidationError, ValidationResult, Validator};
+pub use query::Query;
+pub use analysis::{Finding, FindingKind, GraphAnalysis};
+pub use assist::{AssistRequest, Proposal, SuggestionProvider};
+pub use stats::{estimated_lines, Hotspot, NodeMetric, ProjectStats};
+pub use serialization::{LoadMode, LoadedProject, ProjectFormat, QuarantineReport, load_project, load_project_lazy, load_project_with_mode, save_project};
+pub use workspace::{Workspace, WorkspaceProject};
+pub use lock::{LockInfo, LockStatus, ProjectLock};
+pub use history::{Snapshot, SnapshotConfig};
+pub use composite::{substitute, BoundaryPort, Subgraph, TemplateParameter, COMPOSITE_COMPONENT};
+pub use mixin::Mixin;
+pub use custom_type::{project_type_name, CustomType, TypeRepresentation, PROJECT_DOMAIN};
+pub use macros::{Macro, MacroReplay, MacroStep};
+pub use placement::{ConstraintPlacement, KeepPosition, PlacementStrategy};
+pub use suggestions::{suggest_connections, Suggestion, SuggestionKind};
+#[cfg(feature = "sqlite")]
+pub use sqlite::{ProjectDb, SaveStats};
 
 // Re-export core types that are commonly used with IR
 pub use imortal_core::{
@@ -107,6 +141,7 @@ pub mod prelude {
         ValidationError,
         ValidationResult,
         Validator,
+        GraphAnalysis,
         ProjectFormat,
         load_project,
         save_project,
@@ -121,5 +156,11 @@ pub const IR_VERSION: &str = "1.0.0";
 /// File extension for Immortal Engine project files
 pub const PROJECT_EXTENSION: &str = "imortal";
 
+/// File extension for Immortal Engine workspace files
+pub const WORKSPACE_EXTENSION: &str = "imortal-ws";
+
 /// File extension for Immortal Engine component files
 pub const COMPONENT_EXTENSION: &str = "icomp";
+
+/// File extension for projects stored in a SQLite database
+pub const DB_EXTENSION: &str = "imortaldb";
@@ -0,0 +1,428 @@
+//! Advisory project file locks
+//!
+//! An editor holding a project open writes `<project>.lock` next to it with
+//! its PID and hostname. Another editor opening the same file sees the lock
+//! and can open read-only or take over. Nothing stops other programs from
+//! writing the project; the lock only keeps cooperating editors from
+//! silently overwriting each other.
+//!
+//! A lock left behind by a crashed editor on this machine is stale and is
+//! taken over automatically. Locks from other hosts are never considered
+//! stale, since there's no way to tell whether their process is still alive,
+//! and neither is any lock when this machine's hostname can't be found.
+//! Each lock is told apart by its token, so two tabs of one editor opening
+//! the same file see each other's lock like any other editor's.
+//!
+//! Lock files are never written in place: a lock is written to a scratch
+//! file first and linked or renamed into place, so it never exists without
+//! its contents. A lock file that can't be parsed anyway, say written by
+//! hand, holds the project while it was modified recently and is replaced
+//! like a stale lock after that.
+
+use std::fmt;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use imortal_core::{EngineError, EngineResult};
+
+/// Extension appended to the project file name
+pub const LOCK_EXTENSION: &str = "lock";
+
+/// Hostname recorded when this machine's can't be found
+const UNKNOWN_HOST: &str = "unknown";
+
+/// Times to retry taking a lock when another editor replaces a stale lock
+/// at the same moment
+const ACQUIRE_ATTEMPTS: usize = 5;
+
+/// How long a lock file that can't be parsed still holds the project
+const UNREADABLE_GRACE: Duration = Duration::from_secs(10);
+
+/// Who holds a project lock
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LockInfo {
+    /// Process ID of the editor
+    pub pid: u32,
+    /// Host the editor runs on
+    pub hostname: String,
+    /// When the lock was taken, in seconds since the Unix epoch
+    pub acquired_at: u64,
+    /// Distinguishes two locks taken by the same process
+    pub token: Uuid,
+}
+
+impl LockInfo {
+    /// A new lock for this process
+    pub fn current() -> Self {
+        Self {
+            pid: std::process::id(),
+            hostname: hostname().unwrap_or_else(|| UNKNOWN_HOST.to_string()),
+            acquired_at: SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0),
+            token: Uuid::new_v4(),
+        }
+    }
+
+    /// Holder of a lock file that can't be parsed, modified at `modified`
+    fn unreadable(modified: SystemTime) -> Self {
+        Self {
+            pid: 0,
+            hostname: UNKNOWN_HOST.to_string(),
+            acquired_at: modified.duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0),
+            token: Uuid::nil(),
+        }
+    }
+
+    /// Whether the lock was left by an editor on this host that is no
+    /// longer running
+    pub fn is_stale(&self) -> bool {
+        hostname().is_some_and(|host| host == self.hostname) && !process_running(self.pid)
+    }
+}
+
+impl fmt::Display for LockInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.token.is_nil() {
+            return write!(f, "another editor");
+        }
+        write!(f, "process {} on {}", self.pid, self.hostname)
+    }
+}
+
+/// Result of trying to lock a project
+#[derive(Debug)]
+pub enum LockStatus {
+    /// The lock is ours, possibly after replacing a stale one
+    Acquired(ProjectLock),
+    /// Another editor holds the project
+    HeldBy(LockInfo),
+}
+
+/// A held project lock, released when dropped
+#[derive(Debug)]
+pub struct ProjectLock {
+    path: PathBuf,
+    info: LockInfo,
+}
+
+impl ProjectLock {
+    /// Lock file used for a project file
+    pub fn lock_path(project: impl AsRef<Path>) -> PathBuf {
+        let project = project.as_ref();
+        let mut name = project.file_name().unwrap_or_default().to_os_string();
+        name.push(".");
+        name.push(LOCK_EXTENSION);
+        project.with_file_name(name)
+    }
+
+    /// Lock a project unless another running editor holds it
+    ///
+    /// Stale locks, and unreadable lock files not modified recently, are
+    /// replaced. A lock taken earlier by this process, say in another tab,
+    /// holds the project like any other.
+    pub fn acquire(project: impl AsRef<Path>) -> EngineResult<LockStatus> {
+        let path = Self::lock_path(project);
+        let info = LockInfo::current();
+        let contents = serialize(&info)?;
+
+        for _ in 0..ACQUIRE_ATTEMPTS {
+            if create_exclusive(&path, &contents)? {
+                return Ok(LockStatus::Acquired(Self { path, info }));
+            }
+
+            let found = match fs::read_to_string(&path) {
+                Ok(found) => found,
+                Err(e) if e.kind() == ErrorKind::NotFound => continue,
+                Err(e) => return Err(e.into()),
+            };
+            match parse_lock(&path, &found) {
+                Some(holder) if !holder.is_stale() => return Ok(LockStatus::HeldBy(holder)),
+                _ => remove_stale(&path, &found)?,
+            }
+        }
+
+        // Kept losing the race to other editors
+        match read_lock(&path)? {
+            Some(holder) => Ok(LockStatus::HeldBy(holder)),
+            None => Err(EngineError::ProjectLocked("another editor".to_string())),
+        }
+    }
+
+    /// Take the lock whoever holds it
+    pub fn take_over(project: impl AsRef<Path>) -> EngineResult<Self> {
+        Self::write(Self::lock_path(project), LockInfo::current())
+    }
+
+    /// Current holder of a project's lock, if any
+    pub fn holder(project: impl AsRef<Path>) -> EngineResult<Option<LockInfo>> {
+        read_lock(&Self::lock_path(project))
+    }
+
+    /// This lock's details
+    pub fn info(&self) -> &LockInfo {
+        &self.info
+    }
+
+    /// Check that the lock is still ours before writing the project
+    ///
+    /// Fails with the new holder when another editor, or another tab of
+    /// this one, took the lock over.
+    pub fn verify(&self) -> EngineResult<()> {
+        match read_lock(&self.path)? {
+            Some(holder) if holder == self.info => Ok(()),
+            Some(holder) => Err(EngineError::ProjectLocked(holder.to_string())),
+            // Deleted by hand; claim it again
+            None => write_atomic(&self.path, &serialize(&self.info)?),
+        }
+    }
+
+    fn write(path: PathBuf, info: LockInfo) -> EngineResult<Self> {
+        write_atomic(&path, &serialize(&info)?)?;
+        Ok(Self { path, info })
+    }
+}
+
+impl Drop for ProjectLock {
+    fn drop(&mut self) {
+        // Leave the file alone if another editor has taken over
+        if matches!(read_lock(&self.path), Ok(Some(holder)) if holder == self.info) {
+            let _ = fs::remove_file(&self.path);
+        }
+    }
+}
+
+fn serialize(info: &LockInfo) -> EngineResult<String> {
+    serde_json::to_string_pretty(info).map_err(|e| EngineError::Serialization(format!("JSON serialize error: {}", e)))
+}
+
+/// Read a lock file; a missing file is `None`, as is one that can't be
+/// parsed and wasn't modified recently
+fn read_lock(path: &Path) -> EngineResult<Option<LockInfo>> {
+    match fs::read_to_string(path) {
+        Ok(contents) => Ok(parse_lock(path, &contents)),
+        Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// The holder of the lock file at `path` containing `contents`
+///
+/// Contents that can't be parsed hold the project for [`UNREADABLE_GRACE`]
+/// after the file was modified, then count as held by nobody.
+fn parse_lock(path: &Path, contents: &str) -> Option<LockInfo> {
+    if let Ok(holder) = serde_json::from_str(contents) {
+        return Some(holder);
+    }
+    // When in doubt, the lock holds
+    let modified = fs::metadata(path).and_then(|m| m.modified()).unwrap_or_else(|_| SystemTime::now());
+    let recent = modified.elapsed().map_or(true, |age| age < UNREADABLE_GRACE);
+    recent.then(|| LockInfo::unreadable(modified))
+}
+
+/// Create the lock file at `path` with `contents` unless it exists; `false`
+/// if it does
+///
+/// The contents go to a scratch file that is then hard linked into place,
+/// which fails if the lock exists, so the lock is never seen empty.
+fn create_exclusive(path: &Path, contents: &str) -> EngineResult<bool> {
+    let temp = scratch_path(path, "new");
+    fs::write(&temp, contents)?;
+    let linked = fs::hard_link(&temp, path);
+    let _ = fs::remove_file(&temp);
+    match linked {
+        Ok(()) => Ok(true),
+        Err(e) if e.kind() == ErrorKind::AlreadyExists => Ok(false),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// File next to `path` that only this call uses
+fn scratch_path(path: &Path, purpose: &str) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(format!(".{}-{}", purpose, Uuid::new_v4()));
+    path.with_file_name(name)
+}
+
+/// Replace `path` in one step, so readers never see half a lock
+fn write_atomic(path: &Path, contents: &str) -> EngineResult<()> {
+    let temp = scratch_path(path, "tmp");
+    fs::write(&temp, contents)?;
+    fs::rename(&temp, path).inspect_err(|_| {
+        let _ = fs::remove_file(&temp);
+    })?;
+    Ok(())
+}
+
+/// Remove the stale lock file at `path`, whose contents were `stale`,
+/// unless another editor has replaced it since
+///
+/// The file is first moved aside, which only one editor can do; a fresh
+/// lock moved aside by mistake is put back.
+fn remove_stale(path: &Path, stale: &str) -> EngineResult<()> {
+    let aside = scratch_path(path, "stale");
+    match fs::rename(path, &aside) {
+        Ok(()) => {}
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(e.into()),
+    }
+    if fs::read_to_string(&aside).ok().as_deref() != Some(stale) {
+        // Fails if yet another editor locked it meanwhile, which is theirs
+        let _ = fs::hard_link(&aside, path);
+    }
+    fs::remove_file(&aside)?;
+    Ok(())
+}
+
+/// This machine's hostname, `None` if it can't be found
+fn hostname() -> Option<String> {
+    let name = gethostname::gethostname().to_string_lossy().trim().to_string();
+    (!name.is_empty() && name != UNKNOWN_HOST).then_some(name)
+}
+
+fn process_running(pid: u32) -> bool {
+    if cfg!(target_os = "linux") {
+        return Path::new("/proc").join(pid.to_string()).exists();
+    }
+    if cfg!(unix) {
+        return std::process::Command::new("kill")
+            .args(["-0", &pid.to_string()])
+            .stderr(std::process::Stdio::null())
+            .status()
+            .map(|status| status.success())
+            .unwrap_or(true);
+    }
+    // No way to tell, so never treat the lock as stale
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn other_editor(hostname: &str, pid: u32) -> LockInfo {
+        LockInfo { pid, hostname: hostname.to_string(), ..LockInfo::current() }
+    }
+
+    fn acquired(status: LockStatus) -> ProjectLock {
+        match status {
+            LockStatus::Acquired(lock) => lock,
+            LockStatus::HeldBy(holder) => panic!("held by {}", holder),
+        }
+    }
+
+    #[test]
+    fn test_lock_lifecycle() {
+        let dir = tempfile::tempdir().unwrap();
+        let project = dir.path().join("shop.imortal");
+        let lock_path = ProjectLock::lock_path(&project);
+        assert_eq!(lock_path, dir.path().join("shop.imortal.lock"));
+
+        let lock = acquired(ProjectLock::acquire(&project).unwrap());
+        assert_eq!(ProjectLock::holder(&project).unwrap().as_ref(), Some(lock.info()));
+        lock.verify().unwrap();
+
+        drop(lock);
+        assert!(!lock_path.exists());
+    }
+
+    #[test]
+    fn test_lock_held_and_taken_over() {
+        let dir = tempfile::tempdir().unwrap();
+        let project = dir.path().join("shop.imortal");
+        let lock = acquired(ProjectLock::acquire(&project).unwrap());
+
+        // Another machine's lock is respected
+        let remote = other_editor("elsewhere.example", 42);
+        fs::write(ProjectLock::lock_path(&project), serialize(&remote).unwrap()).unwrap();
+        match ProjectLock::acquire(&project).unwrap() {
+            LockStatus::HeldBy(holder) => assert_eq!(holder, remote),
+            LockStatus::Acquired(_) => panic!("remote lock was ignored"),
+        }
+        assert!(lock.verify().unwrap_err().to_string().contains("elsewhere.example"));
+        // Dropping a lock that was taken over leaves the new holder's file
+        drop(lock);
+        assert_eq!(ProjectLock::holder(&project).unwrap(), Some(remote));
+
+        let forced = ProjectLock::take_over(&project).unwrap();
+        assert_eq!(forced.info().pid, std::process::id());
+        forced.verify().unwrap();
+    }
+
+    #[test]
+    fn test_same_process_lock_is_held() {
+        let dir = tempfile::tempdir().unwrap();
+        let project = dir.path().join("shop.imortal");
+        let first = acquired(ProjectLock::acquire(&project).unwrap());
+
+        // A second tab of this editor opening the same file
+        match ProjectLock::acquire(&project).unwrap() {
+            LockStatus::HeldBy(holder) => assert_eq!(&holder, first.info()),
+            LockStatus::Acquired(_) => panic!("two tabs share one lock"),
+        }
+        let second = ProjectLock::take_over(&project).unwrap();
+        assert!(first.verify().is_err());
+        second.verify().unwrap();
+    }
+
+    #[test]
+    fn test_unknown_host_is_never_stale() {
+        // A lock from a machine whose name couldn't be found
+        let unknown = other_editor(UNKNOWN_HOST, u32::MAX);
+        assert!(!unknown.is_stale());
+    }
+
+    #[test]
+    fn test_stale_lock_is_replaced() {
+        let dir = tempfile::tempdir().unwrap();
+        let project = dir.path().join("shop.imortal");
+
+        // A process on this host that isn't running
+        let crashed = other_editor(&hostname().unwrap(), u32::MAX);
+        assert!(crashed.is_stale());
+        fs::write(ProjectLock::lock_path(&project), serialize(&crashed).unwrap()).unwrap();
+        let lock = acquired(ProjectLock::acquire(&project).unwrap());
+        assert_eq!(lock.info().pid, std::process::id());
+        drop(lock);
+
+        // A garbled lock file doesn't block anyone once it's old
+        fs::write(ProjectLock::lock_path(&project), "not json").unwrap();
+        let old = SystemTime::now() - UNREADABLE_GRACE * 2;
+        fs::File::options().write(true).open(ProjectLock::lock_path(&project)).unwrap().set_modified(old).unwrap();
+        let _lock = acquired(ProjectLock::acquire(&project).unwrap());
+        // Only the lock itself is left next to the project
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn test_fresh_unreadable_lock_is_held() {
+        let dir = tempfile::tempdir().unwrap();
+        let project = dir.path().join("shop.imortal");
+
+        // What another editor's lock looked like while half written
+        fs::write(ProjectLock::lock_path(&project), "{\"pid\": 4").unwrap();
+        match ProjectLock::acquire(&project).unwrap() {
+            LockStatus::HeldBy(holder) => {
+                assert!(!holder.is_stale());
+                assert_eq!(holder.to_string(), "another editor");
+            }
+            LockStatus::Acquired(_) => panic!("a lock being written was replaced"),
+        }
+        assert!(ProjectLock::holder(&project).unwrap().is_some());
+        assert_eq!(fs::read_to_string(ProjectLock::lock_path(&project)).unwrap(), "{\"pid\": 4");
+    }
+
+    #[test]
+    fn test_lock_is_created_with_its_contents() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("shop.imortal.lock");
+        assert!(create_exclusive(&path, "first").unwrap());
+        assert!(!create_exclusive(&path, "second").unwrap());
+        assert_eq!(fs::read_to_string(&path).unwrap(), "first");
+        // No scratch files are left behind
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+}
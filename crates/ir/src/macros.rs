@@ -0,0 +1,224 @@
+//! Recorded edit macros
+//!
+//! A [`Macro`] is a named list of edit steps captured while the user works,
+//! saved as JSON, and replayed on any project. Steps that change existing
+//! nodes act on the selection at replay time, so a macro recorded as "add
+//! `created_by` and `updated_by` to this entity" adds them to every selected
+//! entity. Adding a node selects it, as it does in the editor, so later steps
+//! apply to the new node.
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use imortal_core::{ConfigValue, EngineError, EngineResult, NodeId};
+
+use crate::field::Field;
+use crate::graph::ProjectGraph;
+use crate::node::Node;
+
+/// One recorded edit
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum MacroStep {
+    /// Add a copy of a node, then select it
+    AddNode { node: Box<Node> },
+    /// Add a field to each selected entity that has none of that name
+    AddField { field: Box<Field> },
+    /// Remove a field from each selected entity
+    RemoveField { name: String },
+    /// Set a config value on each selected node
+    SetConfig { key: String, value: ConfigValue },
+    /// Include a mixin in each selected entity
+    IncludeMixin { name: String },
+    /// Stop including a mixin in each selected entity
+    ExcludeMixin { name: String },
+}
+
+impl MacroStep {
+    /// One-line description for the macro manager
+    pub fn describe(&self) -> String {
+        match self {
+            MacroStep::AddNode { node } => format!("Add {} '{}'", node.component_type, node.name),
+            MacroStep::AddField { field } => format!("Add field '{}'", field.name),
+            MacroStep::RemoveField { name } => format!("Remove field '{}'", name),
+            MacroStep::SetConfig { key, .. } => format!("Set config '{}'", key),
+            MacroStep::IncludeMixin { name } => format!("Include mixin {}", name),
+            MacroStep::ExcludeMixin { name } => format!("Exclude mixin {}", name),
+        }
+    }
+}
+
+/// A named, replayable list of edits
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Macro {
+    /// Name shown in the macro manager
+    pub name: String,
+
+    /// What the macro does
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
+    /// Edits, in the order they were made
+    #[serde(default)]
+    pub steps: Vec<MacroStep>,
+}
+
+/// What replaying a macro changed
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MacroReplay {
+    /// Nodes added by the macro
+    pub added: Vec<NodeId>,
+    /// Existing or added nodes the later steps changed
+    pub changed: usize,
+}
+
+impl Macro {
+    /// Create an empty macro
+    pub fn new(name: impl Into<String>) -> Self {
+        Self { name: name.into(), description: None, steps: Vec::new() }
+    }
+
+    /// Append a step; setting the same config key again replaces the last
+    /// step rather than adding another, so dragging a value records once
+    pub fn record(&mut self, step: MacroStep) {
+        if let (Some(MacroStep::SetConfig { key: last, value }), MacroStep::SetConfig { key, value: new }) =
+            (self.steps.last_mut(), &step)
+        {
+            if last == key {
+                *value = new.clone();
+                return;
+            }
+        }
+        self.steps.push(step);
+    }
+
+    /// Serialize to pretty JSON
+    pub fn to_json(&self) -> EngineResult<String> {
+        serde_json::to_string_pretty(self).map_err(|e| EngineError::Serialization(format!("JSON serialize error: {}", e)))
+    }
+
+    /// Parse from JSON
+    pub fn from_json(json: &str) -> EngineResult<Self> {
+        serde_json::from_str(json).map_err(|e| EngineError::Deserialization(format!("JSON parse error: {}", e)))
+    }
+
+    /// Apply the steps to `graph`, starting from its current selection
+    pub fn replay(&self, graph: &mut ProjectGraph) -> MacroReplay {
+        let mut replay = MacroReplay::default();
+        let mut changed = std::collections::HashSet::new();
+        for step in &self.steps {
+            if let MacroStep::AddNode { node } = step {
+                let mut node = (**node).clone();
+                node.id = Uuid::new_v4();
+                for field in &mut node.fields {
+                    field.id = Uuid::new_v4();
+                }
+                node.selected = false;
+                let id = graph.add_node(node);
+                graph.clear_selection();
+                graph.select_node(id);
+                replay.added.push(id);
+                continue;
+            }
+
+            let selected: Vec<NodeId> = graph.selected_nodes.iter().copied().collect();
+            for id in selected {
+                let Some(node) = graph.get_node_mut(id) else { continue };
+                let is_entity = node.component_type == "data.entity";
+                let did_change = match step {
+                    MacroStep::AddNode { .. } => unreachable!("handled above"),
+                    MacroStep::AddField { field } if is_entity && node.get_field(&field.name).is_none() => {
+                        node.add_field(Field { id: Uuid::new_v4(), ..(**field).clone() });
+                        true
+                    }
+                    MacroStep::RemoveField { name } if is_entity => node.remove_field(name).is_some(),
+                    MacroStep::SetConfig { key, value } => {
+                        node.set_config(key.as_str(), value.clone());
+                        true
+                    }
+                    MacroStep::IncludeMixin { name } if is_entity && !node.mixins.contains(name) => {
+                        node.mixins.push(name.clone());
+                        true
+                    }
+                    MacroStep::ExcludeMixin { name } if is_entity && node.mixins.contains(name) => {
+                        node.mixins.retain(|m| m != name);
+                        true
+                    }
+                    _ => false,
+                };
+                if did_change {
+                    changed.insert(id);
+                }
+            }
+        }
+        if !replay.added.is_empty() || !changed.is_empty() {
+            graph.touch();
+        }
+        replay.changed = changed.len();
+        replay
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use imortal_core::DataType;
+
+    fn audit_fields() -> Macro {
+        let mut audit = Macro::new("Audit fields");
+        audit.record(MacroStep::AddField { field: Box::new(Field::new("created_by", DataType::Uuid)) });
+        audit.record(MacroStep::AddField { field: Box::new(Field::new("updated_by", DataType::Uuid)) });
+        audit.record(MacroStep::IncludeMixin { name: "Timestamps".to_string() });
+        audit
+    }
+
+    #[test]
+    fn test_replay_on_selection() {
+        let mut graph = ProjectGraph::with_name("test");
+        let user = graph.add_node(Node::new_entity("User"));
+        let mut post = Node::new_entity("Post");
+        post.add_field(Field::new("created_by", DataType::String));
+        let post = graph.add_node(post);
+        let other = graph.add_node(Node::new_entity("Other"));
+        graph.select_node(user);
+        graph.select_node(post);
+
+        let replay = audit_fields().replay(&mut graph);
+        assert_eq!(replay, MacroReplay { added: Vec::new(), changed: 2 });
+        let user = graph.get_node(user).unwrap();
+        assert!(user.get_field("created_by").is_some() && user.get_field("updated_by").is_some());
+        assert_eq!(user.mixins, vec!["Timestamps".to_string()]);
+        // An existing field of the same name is left alone
+        assert_eq!(graph.get_node(post).unwrap().get_field("created_by").unwrap().data_type, DataType::String);
+        assert!(graph.get_node(other).unwrap().get_field("created_by").is_none());
+
+        // Replaying twice adds nothing more
+        assert_eq!(audit_fields().replay(&mut graph).changed, 0);
+    }
+
+    #[test]
+    fn test_added_node_takes_later_steps() {
+        let mut recorded = Macro::new("Settings");
+        recorded.record(MacroStep::AddNode { node: Box::new(Node::new("storage.database", "Database")) });
+        recorded.record(MacroStep::SetConfig { key: "backend".to_string(), value: "mysql".into() });
+        recorded.record(MacroStep::SetConfig { key: "backend".to_string(), value: "postgres".into() });
+        assert_eq!(recorded.steps.len(), 2);
+
+        let json = recorded.to_json().unwrap();
+        assert!(json.contains("\"op\": \"set_config\""));
+        let loaded = Macro::from_json(&json).unwrap();
+        assert_eq!(loaded, recorded);
+
+        let mut graph = ProjectGraph::with_name("test");
+        let replay = loaded.replay(&mut graph);
+        assert_eq!(replay.added.len(), 1);
+        assert_eq!(replay.changed, 1);
+        let db = graph.get_node(replay.added[0]).unwrap();
+        assert_eq!(db.get_config_str("backend"), Some("postgres"));
+
+        // Each replay adds a fresh node
+        let again = loaded.replay(&mut graph);
+        assert_ne!(again.added, replay.added);
+        assert!(Macro::from_json("{").is_err());
+    }
+}
@@ -0,0 +1,76 @@
+//! Reusable field sets for entities
+//!
+//! A mixin such as `Timestamps` or `Auditable` is a named list of fields an
+//! entity can include by name ([`Node::mixins`](crate::Node::mixins)) instead
+//! of declaring them again. The built-in mixins are always available;
+//! projects can add their own in [`ProjectMeta::mixins`](crate::ProjectMeta::mixins),
+//! which take precedence over built-ins of the same name.
+
+use serde::{Deserialize, Serialize};
+
+use crate::field::Field;
+
+/// A named set of fields entities can include
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Mixin {
+    /// Name entities include the mixin by, e.g. "Timestamps"
+    pub name: String,
+
+    /// What the fields are for
+    #[serde(default)]
+    pub description: Option<String>,
+
+    /// Fields added to every entity including the mixin
+    #[serde(default)]
+    pub fields: Vec<Field>,
+}
+
+impl Mixin {
+    /// Create an empty mixin
+    pub fn new(name: impl Into<String>) -> Self {
+        Self { name: name.into(), description: None, fields: Vec::new() }
+    }
+
+    /// Set the description
+    pub fn with_description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// Add a field
+    pub fn with_field(mut self, field: Field) -> Self {
+        self.fields.push(field);
+        self
+    }
+
+    /// Mixins every project can use
+    pub fn builtin() -> Vec<Mixin> {
+        vec![
+            Mixin::new("Timestamps")
+                .with_description("When the record was created and last updated")
+                .with_field(Field::datetime("created_at").required())
+                .with_field(Field::datetime("updated_at").required()),
+            Mixin::new("Auditable")
+                .with_description("Who created and last updated the record")
+                .with_field(Field::uuid("created_by"))
+                .with_field(Field::uuid("updated_by")),
+            Mixin::new("SoftDelete")
+                .with_description("When the record was deleted, instead of removing it")
+                .with_field(Field::datetime("deleted_at")),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_mixins() {
+        let builtin = Mixin::builtin();
+        let timestamps = builtin.iter().find(|m| m.name == "Timestamps").unwrap();
+        let names: Vec<&str> = timestamps.fields.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(names, ["created_at", "updated_at"]);
+        assert!(builtin.iter().all(|m| m.description.is_some() && !m.fields.is_empty()));
+    }
+}
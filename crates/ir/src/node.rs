@@ -9,8 +9,10 @@ use uuid::Uuid;
 
 use imortal_core::{
     ConfigValue, Position, Size, NodeId, ComponentCategory, DataType,
+    EngineError, EngineResult, PortDirection, PortId, PortKind,
 };
 
+use crate::composite::Subgraph;
 use crate::field::Field;
 use crate::port::{Port, PortCollection};
 
@@ -33,12 +35,14 @@ pub struct Node {
     pub size: Size,
 
     /// Fields defined in this component instance
+    #[serde(default)]
     pub fields: Vec<Field>,
 
     /// Input and output ports for connections
     pub ports: PortCollection,
 
     /// Configuration values for this component
+    #[serde(default)]
     pub config: HashMap<String, ConfigValue>,
 
     /// Category for visual organization
@@ -57,6 +61,16 @@ pub struct Node {
     /// Whether this node is visible
     pub visible: bool,
 
+    /// Whether this node is part of the design; a disabled node stays on
+    /// the canvas but is left out of code generation, and its validation
+    /// issues are only informational
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+
+    /// Hints changing the code generated for this node
+    #[serde(default, skip_serializing_if = "CodegenHints::is_default")]
+    pub codegen: CodegenHints,
+
     /// Optional description/documentation
     pub description: Option<String>,
 
@@ -70,6 +84,7 @@ pub struct Node {
     pub tags: Vec<String>,
 
     /// Custom metadata
+    #[serde(default)]
     pub metadata: HashMap<String, ConfigValue>,
 
     /// Z-index for layering (higher = on top)
@@ -78,6 +93,21 @@ pub struct Node {
     /// Group ID if this node belongs to a group
     pub group_id: Option<Uuid>,
 
+    /// Module path this node lives in, outermost first (e.g. `["billing", "invoices"]`)
+    ///
+    /// Codegen maps it to nested Rust modules and route prefixes; an empty
+    /// path is the crate root.
+    #[serde(default)]
+    pub namespace: Vec<String>,
+
+    /// Mixins whose fields this entity includes, by name (e.g. "Timestamps")
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub mixins: Vec<String>,
+
+    /// Inner graph of a composite node collapsed from a group
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub subgraph: Option<Box<Subgraph>>,
+
     /// Timestamp when this node was created
     pub created_at: Option<String>,
 
@@ -85,7 +115,22 @@ pub struct Node {
     pub modified_at: Option<String>,
 }
 
+fn default_enabled() -> bool {
+    true
+}
+
 impl Node {
+    /// Height of the node header on the canvas
+    pub const HEADER_HEIGHT: f32 = 25.0;
+    /// Height of one detail row on the canvas
+    pub const ROW_HEIGHT: f32 = 18.0;
+    /// Padding below the last detail row
+    pub const CONTENT_PADDING: f32 = 8.0;
+    /// Minimum width for manual resizing
+    pub const MIN_WIDTH: f32 = 100.0;
+    /// Maximum width for manual resizing
+    pub const MAX_WIDTH: f32 = 600.0;
+
     /// Create a new node with the given component type and name
     pub fn new(component_type: impl Into<String>, name: impl Into<String>) -> Self {
         Self {
@@ -102,6 +147,8 @@ impl Node {
             selected: false,
             locked: false,
             visible: true,
+            enabled: true,
+            codegen: CodegenHints::default(),
             description: None,
             icon: None,
             color: None,
@@ -109,6 +156,9 @@ impl Node {
             metadata: HashMap::new(),
             z_index: 0,
             group_id: None,
+            namespace: Vec::new(),
+            mixins: Vec::new(),
+            subgraph: None,
             created_at: None,
             modified_at: None,
         }
@@ -233,6 +283,7 @@ impl Node {
         node.config.insert("method".to_string(), ConfigValue::String("GET".to_string()));
         node.config.insert("path".to_string(), ConfigValue::String(format!("/{}", name.to_lowercase())));
         node.config.insert("auth_required".to_string(), ConfigValue::Bool(false));
+        node.config.insert("version".to_string(), ConfigValue::String(String::new()));
 
         // Add ports
         node.ports.add_input(Port::data_in("request", "Request", DataType::Any));
@@ -242,6 +293,38 @@ impl Node {
         node
     }
 
+    /// Create a new feature flag component
+    pub fn new_feature_flag(name: impl Into<String>) -> Self {
+        let mut node = Self::new("logic.feature_flag", name);
+        node.category = ComponentCategory::Logic;
+        node.icon = Some("🚩".to_string());
+
+        // Add configuration
+        node.config.insert("flags".to_string(), ConfigValue::String("new_feature".to_string()));
+        node.config.insert("provider".to_string(), ConfigValue::String("env".to_string()));
+
+        // Add ports
+        node.ports.add_output(Port::data_out("gate", "Gate", DataType::Bool));
+
+        node
+    }
+
+    /// Create a new custom code component
+    pub fn new_custom_code(name: impl Into<String>) -> Self {
+        let mut node = Self::new("logic.custom_code", name);
+        node.category = ComponentCategory::Logic;
+        node.icon = Some("🦀".to_string());
+
+        // Add configuration
+        node.config.insert("code".to_string(), ConfigValue::String("input".to_string()));
+
+        // Add ports
+        node.ports.add_input(Port::data_in("input", "Input", DataType::Any));
+        node.ports.add_output(Port::data_out("output", "Output", DataType::Any));
+
+        node
+    }
+
     /// Create a new database component
     pub fn new_database(name: impl Into<String>) -> Self {
         let name = name.into();
@@ -260,6 +343,179 @@ impl Node {
         node
     }
 
+    /// Create a new cache component
+    pub fn new_cache(name: impl Into<String>) -> Self {
+        let mut node = Self::new("storage.cache", name);
+        node.category = ComponentCategory::Storage;
+        node.icon = Some("⚡".to_string());
+
+        // Add configuration
+        node.config.insert("backend".to_string(), ConfigValue::String("memory".to_string()));
+        node.config.insert("redis_url".to_string(), ConfigValue::String("redis://localhost:6379".to_string()));
+        node.config.insert("default_ttl".to_string(), ConfigValue::Int(3600));
+        node.config.insert("key_prefix".to_string(), ConfigValue::String(String::new()));
+
+        // Add ports
+        node.ports.add_input(Port::data_in("key", "Key", DataType::String));
+        node.ports.add_input(Port::data_in("value", "Value", DataType::Any));
+        node.ports.add_output(Port::data_out("result", "Result", DataType::Any));
+
+        node
+    }
+
+    /// Create a new application settings component
+    ///
+    /// Each field declares one setting; its environment variable is stored
+    /// in the field metadata (see [`Field::with_env_var`]).
+    pub fn new_settings(name: impl Into<String>) -> Self {
+        let mut node = Self::new("config.settings", name);
+        node.category = ComponentCategory::Config;
+        node.icon = Some("🛠".to_string());
+
+        // Add ports
+        node.ports.add_output(Port::data_out("settings", "Settings", DataType::Any));
+
+        node
+    }
+
+    /// Create a new observability component
+    ///
+    /// Enables health checks, Prometheus metrics, and OTLP tracing in the
+    /// generated service.
+    pub fn new_observability(name: impl Into<String>) -> Self {
+        let mut node = Self::new("service.observability", name);
+        node.category = ComponentCategory::Service;
+        node.icon = Some("🛰".to_string());
+
+        // Add configuration
+        node.config.insert("health_checks".to_string(), ConfigValue::Bool(true));
+        node.config.insert("metrics".to_string(), ConfigValue::Bool(true));
+        node.config.insert("metrics_path".to_string(), ConfigValue::String("/metrics".to_string()));
+        node.config.insert("tracing".to_string(), ConfigValue::Bool(true));
+        node.config.insert("otlp_endpoint".to_string(), ConfigValue::String("http://localhost:4317".to_string()));
+
+        node
+    }
+
+    /// Create a new search component
+    ///
+    /// Indexes the text fields of the entities connected to its input port
+    /// and serves ranked results at its endpoint.
+    pub fn new_search(name: impl Into<String>) -> Self {
+        let mut node = Self::new("service.search", name);
+        node.category = ComponentCategory::Service;
+        node.icon = Some("🔎".to_string());
+
+        // Add configuration
+        node.config.insert("engine".to_string(), ConfigValue::String("postgres".to_string()));
+        node.config.insert("endpoint".to_string(), ConfigValue::String("/search".to_string()));
+        node.config.insert("language".to_string(), ConfigValue::String("english".to_string()));
+        node.config.insert("meilisearch_url".to_string(), ConfigValue::String("http://localhost:7700".to_string()));
+        node.config.insert("index_path".to_string(), ConfigValue::String("search_index".to_string()));
+
+        // Add ports
+        node.ports.add_input(
+            Port::data_in("entities", "Entities", DataType::Any).with_multiple(true),
+        );
+
+        node
+    }
+
+    /// Create a new internationalization component
+    pub fn new_i18n(name: impl Into<String>) -> Self {
+        let mut node = Self::new("service.i18n", name);
+        node.category = ComponentCategory::Service;
+        node.icon = Some("🌐".to_string());
+
+        // Add configuration
+        node.config.insert("default_locale".to_string(), ConfigValue::String("en".to_string()));
+        node.config.insert("locales".to_string(), ConfigValue::String("en".to_string()));
+        node.config.insert("query_param".to_string(), ConfigValue::String("lang".to_string()));
+
+        node
+    }
+
+    /// Create a new payments component
+    ///
+    /// Sells the entities connected to its input port, each holding its
+    /// provider price id in the `price_field` config's field.
+    pub fn new_payments(name: impl Into<String>) -> Self {
+        let mut node = Self::new("service.payments", name);
+        node.category = ComponentCategory::Service;
+        node.icon = Some("💳".to_string());
+
+        // Add configuration
+        node.config.insert("provider".to_string(), ConfigValue::String("stripe".to_string()));
+        node.config.insert("mode".to_string(), ConfigValue::String("payment".to_string()));
+        node.config.insert("price_field".to_string(), ConfigValue::String("stripe_price_id".to_string()));
+        node.config.insert("checkout_path".to_string(), ConfigValue::String("/payments/checkout".to_string()));
+        node.config.insert("webhook_path".to_string(), ConfigValue::String("/payments/webhook".to_string()));
+
+        // Add ports
+        node.ports.add_input(
+            Port::data_in("products", "Products", DataType::Any).with_multiple(true),
+        );
+
+        node
+    }
+
+    /// Create a new audit log component
+    ///
+    /// Records create, update, and delete of the entities connected to its
+    /// input port, with their old and new values.
+    pub fn new_audit_log(name: impl Into<String>) -> Self {
+        let mut node = Self::new("data.audit_log", name);
+        node.category = ComponentCategory::Data;
+        node.icon = Some("📜".to_string());
+
+        // Add configuration
+        node.config.insert("table_name".to_string(), ConfigValue::String("audit_log".to_string()));
+        node.config.insert("strategy".to_string(), ConfigValue::String("hooks".to_string()));
+        node.config.insert("endpoint".to_string(), ConfigValue::String("/audit".to_string()));
+
+        // Add ports
+        node.ports.add_input(
+            Port::data_in("entities", "Entities", DataType::Any).with_multiple(true),
+        );
+
+        node
+    }
+
+    ///
+    /// The reference is resolved against the workspace (see
+    /// [`crate::Workspace::validate_references`]) and exposes the entity
+    /// through its output port.
+    pub fn new_reference(project: impl Into<String>, entity: impl Into<String>) -> Self {
+        let (project, entity) = (project.into(), entity.into());
+        let mut node = Self::new("data.reference", format!("{}.{}", project, entity));
+        node.category = ComponentCategory::Data;
+        node.icon = Some("🔗".to_string());
+
+        // Add configuration
+        node.config.insert("project".to_string(), ConfigValue::String(project));
+        node.config.insert("entity".to_string(), ConfigValue::String(entity.clone()));
+
+        // Add ports
+        node.ports.add_output(Port::data_out(
+            "entity",
+            &entity,
+            DataType::Entity(entity.clone()),
+        ));
+
+        node
+    }
+
+    /// The `(project, entity)` a reference node points at
+    pub fn reference_target(&self) -> Option<(&str, &str)> {
+        if self.component_type != "data.reference" {
+            return None;
+        }
+        Some((
+            self.get_config_str("project").unwrap_or_default(),
+            self.get_config_str("entity").unwrap_or_default(),
+        ))
+    }
+
     // ========== Builder Methods ==========
 
     /// Set the position
@@ -334,6 +590,12 @@ impl Node {
         self
     }
 
+    /// Set the namespace from a path like `billing::invoices` or `billing/invoices`
+    pub fn with_namespace(mut self, path: &str) -> Self {
+        self.namespace = parse_namespace(path);
+        self
+    }
+
     /// Set locked state
     pub fn locked(mut self) -> Self {
         self.locked = true;
@@ -373,6 +635,22 @@ impl Node {
         self.ports.get_output(id)
     }
 
+    /// Get the path of an API endpoint
+    ///
+    /// Reads the `path` config, falling back to the default of a `path` field.
+    pub fn endpoint_path(&self) -> Option<&str> {
+        self.get_config_str("path")
+            .or_else(|| self.get_field("path")?.default_value.as_ref()?.as_str())
+            .filter(|p| !p.is_empty())
+    }
+
+    /// Get the API version an endpoint declares, if any
+    pub fn endpoint_version(&self) -> Option<&str> {
+        self.get_config_str("version")
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+    }
+
     /// Get a configuration value
     pub fn get_config(&self, key: &str) -> Option<&ConfigValue> {
         self.config.get(key)
@@ -393,31 +671,100 @@ impl Node {
         self.tags.iter().any(|t| t == tag)
     }
 
-    /// Get the center position of the node
+    /// Check if this node has one of `tags`, or `tags` is empty
+    pub fn has_any_tag(&self, tags: &[String]) -> bool {
+        tags.is_empty() || tags.iter().any(|tag| self.has_tag(tag))
+    }
+
+    /// Check if this entity broadcasts its create, update, and delete
+    /// events to subscribed clients
+    pub fn is_realtime(&self) -> bool {
+        self.component_type == "data.entity" && self.get_config_bool("realtime").unwrap_or(false)
+    }
+
+    /// Flags a feature flag node defines in its `flags` config
+    pub fn feature_flags(&self) -> Vec<FeatureFlag> {
+        if self.component_type != "logic.feature_flag" {
+            return Vec::new();
+        }
+        parse_feature_flags(self.get_config_str("flags").unwrap_or_default())
+    }
+
+    /// Rust snippet of a custom code node, the body of its generated function
+    pub fn custom_code(&self) -> Option<&str> {
+        if self.component_type != "logic.custom_code" {
+            return None;
+        }
+        Some(self.get_config_str("code").unwrap_or_default())
+    }
+
+    /// Check if this is a composite node with a subgraph inside
+    pub fn is_composite(&self) -> bool {
+        self.subgraph.is_some()
+    }
+
+    /// Namespace as a `::`-separated path, empty at the root
+    pub fn namespace_path(&self) -> String {
+        self.namespace.join("::")
+    }
+
+    /// Check if this node is in `namespace` or one of its submodules
+    pub fn in_namespace(&self, namespace: &[String]) -> bool {
+        self.namespace.starts_with(namespace)
+    }
+
+    /// Number of detail rows shown below the header on the canvas
+    pub fn content_rows(&self) -> usize {
+        match self.component_type.as_str() {
+            "data.entity" => self.fields.len(),
+            "storage.database" => 5, // backend, host:port, database, username, ssl
+            "api.rest" => 3,         // method, path, auth
+            _ => 0,
+        }
+    }
+
+    /// Height needed to show the detail rows, if the node has any
+    pub fn content_height(&self) -> Option<f32> {
+        match self.content_rows() {
+            0 => None,
+            rows => Some(Self::HEADER_HEIGHT + rows as f32 * Self::ROW_HEIGHT + Self::CONTENT_PADDING),
+        }
+    }
+
+    /// Get the center position of the node as rendered
     pub fn center(&self) -> Position {
-        Position::new(
-            self.position.x + self.size.width / 2.0,
-            self.position.y + self.size.height / 2.0,
-        )
+        let (x, y, width, height) = self.effective_bounds();
+        Position::new(x + width / 2.0, y + height / 2.0)
     }
 
-    /// Get the bounding rectangle
+    /// Get the stored bounding rectangle
     pub fn bounds(&self) -> (f32, f32, f32, f32) {
         (self.position.x, self.position.y, self.size.width, self.size.height)
     }
 
+    /// Get the bounding rectangle as rendered on the canvas
+    ///
+    /// Collapsed nodes only occupy their header. Ports, edges, and hit-tests
+    /// should all use these bounds.
+    pub fn effective_bounds(&self) -> (f32, f32, f32, f32) {
+        let height = if self.collapsed {
+            Self::HEADER_HEIGHT
+        } else {
+            self.size.height
+        };
+        (self.position.x, self.position.y, self.size.width, height)
+    }
+
     /// Check if a point is inside this node
     pub fn contains_point(&self, x: f32, y: f32) -> bool {
-        x >= self.position.x
-            && x <= self.position.x + self.size.width
-            && y >= self.position.y
-            && y <= self.position.y + self.size.height
+        let (nx, ny, width, height) = self.effective_bounds();
+        x >= nx && x <= nx + width && y >= ny && y <= ny + height
     }
 
     /// Check if this node intersects with another
     pub fn intersects(&self, other: &Node) -> bool {
-        let (x1, y1, w1, h1) = self.bounds();
-        let (x2, y2, w2, h2) = other.bounds();
+        let (x1, y1, w1, h1) = self.effective_bounds();
+        let (x2, y2, w2, h2) = other.effective_bounds();
 
         x1 < x2 + w2 && x1 + w1 > x2 && y1 < y2 + h2 && y1 + h1 > y2
     }
@@ -446,6 +793,93 @@ impl Node {
         }
     }
 
+    /// Set the width, clamped to [`Node::MIN_WIDTH`, `Node::MAX_WIDTH`]
+    pub fn set_width(&mut self, width: f32) {
+        if !self.locked {
+            self.size.width = width.clamp(Self::MIN_WIDTH, Self::MAX_WIDTH);
+        }
+    }
+
+    /// Store the height needed by the detail rows back on the node
+    ///
+    /// Returns whether the size changed. Nodes without detail rows keep
+    /// their size.
+    pub fn fit_to_content(&mut self) -> bool {
+        match self.content_height() {
+            Some(height) if self.size.height != height => {
+                self.size.height = height;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Whether the user may add, remove, rename, and retype ports
+    pub fn allows_port_editing(&self) -> bool {
+        matches!(self.category, ComponentCategory::Logic | ComponentCategory::Custom)
+    }
+
+    fn ensure_port_editing(&self) -> EngineResult<()> {
+        if self.allows_port_editing() {
+            Ok(())
+        } else {
+            Err(EngineError::custom(format!("Ports of '{}' cannot be edited", self.name)))
+        }
+    }
+
+    /// Add a user-defined port, returning its generated ID
+    pub fn add_custom_port(
+        &mut self,
+        direction: PortDirection,
+        name: impl Into<String>,
+        data_type: DataType,
+    ) -> EngineResult<PortId> {
+        self.ensure_port_editing()?;
+        let name = name.into();
+        let id = self.ports.unique_id(&name);
+        let mut port = Port::new(id.clone(), name, direction, DataType::Any);
+        port.order = self.ports.len() as i32;
+        Self::apply_port_type(&mut port, data_type);
+
+        match direction {
+            PortDirection::Input => self.ports.add_input(port),
+            PortDirection::Output => self.ports.add_output(port),
+        };
+        Ok(id)
+    }
+
+    /// Rename a port; its ID, and so its connections, are unchanged
+    pub fn rename_port(&mut self, id: &str, name: impl Into<String>) -> EngineResult<()> {
+        self.ensure_port_editing()?;
+        let port = self.port_mut(id)?;
+        port.name = name.into();
+        Ok(())
+    }
+
+    /// Change a port's data type
+    pub fn set_port_type(&mut self, id: &str, data_type: DataType) -> EngineResult<()> {
+        self.ensure_port_editing()?;
+        Self::apply_port_type(self.port_mut(id)?, data_type);
+        Ok(())
+    }
+
+    fn port_mut(&mut self, id: &str) -> EngineResult<&mut Port> {
+        let node_id = self.id.to_string();
+        self.ports.get_mut(id).ok_or_else(|| EngineError::PortNotFound {
+            node_id,
+            port_id: id.to_string(),
+        })
+    }
+
+    fn apply_port_type(port: &mut Port, data_type: DataType) {
+        port.kind = if data_type == DataType::Trigger {
+            PortKind::Trigger
+        } else {
+            PortKind::Data
+        };
+        port.data_type = data_type;
+    }
+
     /// Toggle collapsed state
     pub fn toggle_collapsed(&mut self) {
         self.collapsed = !self.collapsed;
@@ -518,9 +952,10 @@ impl std::hash::Hash for Node {
 }
 
 /// Color options for node headers
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum NodeColor {
+    #[default]
     Default,
     Red,
     Orange,
@@ -576,16 +1011,163 @@ impl NodeColor {
     }
 }
 
-impl Default for NodeColor {
-    fn default() -> Self {
-        NodeColor::Default
+/// Per-node hints to code generation
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CodegenHints {
+    /// Derives added to an entity's model struct, e.g. `PartialEq` or
+    /// `sqlx::FromRow`
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub derives: Vec<String>,
+    /// Table an entity is stored in instead of its snake_case name
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub table_name: Option<String>,
+    /// Paths of middleware functions wrapping an endpoint's route, e.g.
+    /// `crate::middleware::require_admin`
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub middleware: Vec<String>,
+    /// Generate no code for the node, e.g. because it's written by hand
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub skip: bool,
+}
+
+impl CodegenHints {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// A flag defined by a feature flag node
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct FeatureFlag {
+    /// Flag name, a snake_case identifier
+    pub name: String,
+    /// Whether the flag is on when its provider doesn't say
+    pub enabled: bool,
+}
+
+impl FeatureFlag {
+    /// Whether the name is a snake_case identifier, usable as a field of
+    /// the generated flags struct
+    pub fn has_valid_name(&self) -> bool {
+        let mut chars = self.name.chars();
+        chars.next().is_some_and(|c| c.is_ascii_lowercase())
+            && chars.all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+    }
+}
+
+/// Parse a feature flag list like `new_checkout=on, beta_search`: flags
+/// are off by default unless given `=on` or `=true`
+pub fn parse_feature_flags(spec: &str) -> Vec<FeatureFlag> {
+    spec.split(',')
+        .map(str::trim)
+        .filter(|flag| !flag.is_empty())
+        .map(|flag| {
+            let (name, state) = flag.split_once('=').unwrap_or((flag, "off"));
+            FeatureFlag {
+                name: name.trim().to_string(),
+                enabled: matches!(state.trim().to_ascii_lowercase().as_str(), "on" | "true" | "yes" | "1"),
+            }
+        })
+        .collect()
+}
+
+/// Why a custom code snippet doesn't parse
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SnippetError {
+    /// Line of the snippet the error is on, starting at 1
+    pub line: usize,
+    pub message: String,
+}
+
+impl std::fmt::Display for SnippetError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+/// Check that `code` parses as the body of a Rust function
+pub fn parse_rust_snippet(code: &str) -> Result<(), SnippetError> {
+    // The braces go on lines of their own so snippet lines keep their numbers
+    // after the first
+    syn::parse_str::<syn::Block>(&format!("{{\n{}\n}}", code))
+        .map(|_| ())
+        .map_err(|e| SnippetError {
+            line: e.span().start().line.saturating_sub(1).clamp(1, code.lines().count().max(1)),
+            message: e.to_string(),
+        })
+}
+
+/// Split a namespace path like `billing::invoices` or `billing/invoices`
+/// into its segments, dropping empty ones
+pub fn parse_namespace(path: &str) -> Vec<String> {
+    path.replace("::", "/")
+        .split('/')
+        .map(str::trim)
+        .filter(|segment| !segment.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Split comma-separated tags like `MVP, phase-2`, dropping empty and
+/// repeated ones
+pub fn parse_tags(text: &str) -> Vec<String> {
+    let mut tags: Vec<String> = Vec::new();
+    for tag in text.split(',').map(str::trim).filter(|tag| !tag.is_empty()) {
+        if !tags.iter().any(|t| t == tag) {
+            tags.push(tag.to_string());
+        }
     }
+    tags
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_feature_flags() {
+        let mut node = Node::new_feature_flag("Flags");
+        node.set_config("flags", "new_checkout=on, beta_search, , Dark Mode=true");
+
+        let flags = node.feature_flags();
+        assert_eq!(flags.len(), 3);
+        assert_eq!(flags[0], FeatureFlag { name: "new_checkout".to_string(), enabled: true });
+        assert!(!flags[1].enabled);
+        assert!(flags[1].has_valid_name());
+        assert!(!flags[2].has_valid_name());
+        assert!(Node::new_entity("User").feature_flags().is_empty());
+    }
+
+    #[test]
+    fn test_custom_code() {
+        let mut node = Node::new_custom_code("Discount");
+        assert_eq!(node.custom_code(), Some("input"));
+        assert!(Node::new_entity("User").custom_code().is_none());
+
+        node.set_config("code", "let total = input * 2;\ntotal");
+        assert!(parse_rust_snippet(node.custom_code().unwrap()).is_ok());
+        assert!(parse_rust_snippet("").is_ok());
+
+        let error = parse_rust_snippet("let a = 1;\nlet b = ;\nb").unwrap_err();
+        assert_eq!(error.line, 2);
+        assert!(error.to_string().starts_with("line 2: "));
+        assert!(parse_rust_snippet("fn broken(").is_err());
+    }
+
+    #[test]
+    fn test_codegen_hints_serialization() {
+        let mut node = Node::new_entity("User");
+        assert!(!serde_json::to_string(&node).unwrap().contains("codegen"));
+
+        node.codegen.table_name = Some("app_users".to_string());
+        node.codegen.derives = vec!["PartialEq".to_string()];
+        let json = serde_json::to_string(&node).unwrap();
+        assert!(json.contains(r#""codegen":{"derives":["PartialEq"],"table_name":"app_users"}"#), "{}", json);
+        let restored: Node = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.codegen, node.codegen);
+    }
+
     #[test]
     fn test_node_creation() {
         let node = Node::new("test.component", "Test Node");
@@ -615,6 +1197,20 @@ mod tests {
         assert!(node.ports.get_output("failure").is_some());
     }
 
+    #[test]
+    fn test_namespace() {
+        let node = Node::new_entity("Invoice").with_namespace("billing::invoices");
+        assert_eq!(node.namespace, ["billing", "invoices"]);
+        assert_eq!(node.namespace_path(), "billing::invoices");
+        assert_eq!(parse_namespace(" billing / invoices/ "), node.namespace);
+        assert!(parse_namespace("::").is_empty());
+
+        assert!(node.in_namespace(&[]));
+        assert!(node.in_namespace(&["billing".to_string()]));
+        assert!(!node.in_namespace(&["shipping".to_string()]));
+        assert!(Node::new_entity("User").namespace_path().is_empty());
+    }
+
     #[test]
     fn test_node_builder() {
         let node = Node::new("test", "Test")
@@ -642,6 +1238,44 @@ mod tests {
         assert!(!node.contains_point(350.0, 150.0));
     }
 
+    #[test]
+    fn test_effective_bounds() {
+        let mut node = Node::new_entity("User").with_position(10.0, 20.0);
+        node.fields.push(Field::string("email"));
+
+        assert!(node.fit_to_content());
+        assert!(!node.fit_to_content());
+        let height = Node::HEADER_HEIGHT + 2.0 * Node::ROW_HEIGHT + Node::CONTENT_PADDING;
+        assert_eq!(node.effective_bounds(), (10.0, 20.0, node.size.width, height));
+
+        node.toggle_collapsed();
+        assert_eq!(node.effective_bounds().3, Node::HEADER_HEIGHT);
+        assert!(!node.contains_point(15.0, 20.0 + Node::HEADER_HEIGHT + 5.0));
+
+        node.set_width(10_000.0);
+        assert_eq!(node.size.width, Node::MAX_WIDTH);
+        node.set_width(0.0);
+        assert_eq!(node.size.width, Node::MIN_WIDTH);
+    }
+
+    #[test]
+    fn test_port_editing() {
+        let mut entity = Node::new_entity("User");
+        assert!(entity.add_custom_port(PortDirection::Input, "Extra", DataType::Any).is_err());
+
+        let mut logic = Node::new("logic.transformer", "Map").with_category(ComponentCategory::Logic);
+        let id = logic.add_custom_port(PortDirection::Output, "On Done", DataType::Trigger).unwrap();
+        assert_eq!(id, "on_done");
+        assert_eq!(logic.get_output_port(&id).unwrap().kind, PortKind::Trigger);
+
+        logic.rename_port(&id, "Finished").unwrap();
+        logic.set_port_type(&id, DataType::Int64).unwrap();
+        let port = logic.get_output_port(&id).unwrap();
+        assert_eq!(port.name, "Finished");
+        assert_eq!(port.kind, PortKind::Data);
+        assert!(logic.set_port_type("missing", DataType::Any).is_err());
+    }
+
     #[test]
     fn test_node_translate() {
         let mut node = Node::new("test", "Test")
@@ -0,0 +1,156 @@
+//! Placement of newly added nodes
+//!
+//! A [`PlacementStrategy`] picks where a node goes when it is added without
+//! a drop position, such as from a palette click. [`ConstraintPlacement`]
+//! puts it next to the nodes it relates to, or else in its category's
+//! column, then moves it down until it overlaps nothing.
+
+use imortal_core::{ComponentCategory, Position};
+
+use crate::graph::ProjectGraph;
+use crate::node::Node;
+
+/// Decides the position of a node about to be added to a graph
+pub trait PlacementStrategy {
+    /// Position for `node`, which is not yet in `graph`
+    fn place(&self, graph: &ProjectGraph, node: &Node) -> Position;
+}
+
+/// Keep the position the node already has
+#[derive(Debug, Clone, Copy, Default)]
+pub struct KeepPosition;
+
+impl PlacementStrategy for KeepPosition {
+    fn place(&self, _graph: &ProjectGraph, node: &Node) -> Position {
+        node.position
+    }
+}
+
+/// Place near related nodes, in the category column, without overlaps
+#[derive(Debug, Clone, Copy)]
+pub struct ConstraintPlacement {
+    /// Minimum gap between the node and its neighbours
+    pub spacing: f32,
+    /// Width of a category column when the category has no nodes yet
+    pub column_width: f32,
+}
+
+impl Default for ConstraintPlacement {
+    fn default() -> Self {
+        Self { spacing: 40.0, column_width: 280.0 }
+    }
+}
+
+impl ConstraintPlacement {
+    /// Nodes the new node relates to: the selection, and nodes its config
+    /// names (an endpoint's `entity`, say)
+    fn related<'a>(graph: &'a ProjectGraph, node: &Node) -> Vec<&'a Node> {
+        let names: Vec<&str> = node.config.values().filter_map(|v| v.as_str()).collect();
+        graph
+            .nodes()
+            .filter(|n| graph.selected_nodes.contains(&n.id) || names.contains(&n.name.as_str()))
+            .collect()
+    }
+
+    /// Preferred top-left corner before overlaps are resolved
+    fn anchor(&self, graph: &ProjectGraph, node: &Node) -> Position {
+        let related = Self::related(graph, node);
+        if !related.is_empty() {
+            let right = related.iter().map(|n| n.position.x + n.size.width).fold(f32::MIN, f32::max);
+            let top = related.iter().map(|n| n.position.y).fold(f32::MAX, f32::min);
+            return Position::new(right + self.spacing, top);
+        }
+
+        let column: Vec<&Node> = graph.nodes().filter(|n| n.category == node.category).collect();
+        if !column.is_empty() {
+            let left = column.iter().map(|n| n.position.x).fold(f32::MAX, f32::min);
+            let top = column.iter().map(|n| n.position.y).fold(f32::MAX, f32::min);
+            return Position::new(left, top);
+        }
+
+        let index = ComponentCategory::all().iter().position(|c| *c == node.category).unwrap_or(0);
+        let top = graph.nodes().map(|n| n.position.y).fold(None, |min: Option<f32>, y| Some(min.map_or(y, |m| m.min(y))));
+        Position::new(index as f32 * self.column_width, top.unwrap_or(0.0))
+    }
+}
+
+impl PlacementStrategy for ConstraintPlacement {
+    fn place(&self, graph: &ProjectGraph, node: &Node) -> Position {
+        let mut position = self.anchor(graph, node);
+        let (width, height) = (node.size.width, node.size.height);
+        // Each step moves below a blocking node, so this ends within one
+        // step per node
+        for _ in 0..=graph.node_count() {
+            let blocking = graph
+                .nodes()
+                .filter(|n| {
+                    let (x, y, w, h) = n.effective_bounds();
+                    position.x < x + w + self.spacing
+                        && position.x + width + self.spacing > x
+                        && position.y < y + h + self.spacing
+                        && position.y + height + self.spacing > y
+                })
+                .map(|n| {
+                    let (_, y, _, h) = n.effective_bounds();
+                    y + h
+                })
+                .fold(None, |max: Option<f32>, bottom| Some(max.map_or(bottom, |m| m.max(bottom))));
+            match blocking {
+                Some(bottom) => position.y = bottom + self.spacing,
+                None => break,
+            }
+        }
+        position
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(mut node: Node, x: f32, y: f32) -> Node {
+        node.position = Position::new(x, y);
+        node
+    }
+
+    #[test]
+    fn test_category_columns() {
+        let placement = ConstraintPlacement::default();
+        let mut graph = ProjectGraph::with_name("test");
+        let mut database = Node::new("storage.database", "Database");
+        database.category = ComponentCategory::Storage;
+        let index = ComponentCategory::all().iter().position(|c| *c == ComponentCategory::Storage).unwrap();
+        assert_eq!(placement.place(&graph, &database), Position::new(index as f32 * 280.0, 0.0));
+
+        // Entities stack down their column, skipping the ones already there
+        let user = graph.add_node(at(Node::new_entity("User"), 100.0, 50.0));
+        let height = graph.get_node(user).unwrap().size.height;
+        let post = Node::new_entity("Post");
+        assert_eq!(placement.place(&graph, &post), Position::new(100.0, 50.0 + height + 40.0));
+        graph.add_node(at(post, 100.0, 50.0 + height + 40.0));
+        assert_eq!(placement.place(&graph, &Node::new_entity("Tag")).y, 50.0 + 2.0 * (height + 40.0));
+    }
+
+    #[test]
+    fn test_near_related_nodes() {
+        let placement = ConstraintPlacement::default();
+        let mut graph = ProjectGraph::with_name("test");
+        let user = graph.add_node(at(Node::new_entity("User"), 0.0, 0.0));
+        let width = graph.get_node(user).unwrap().size.width;
+        let blocker = graph.add_node(at(Node::new("logic.validator", "Check"), width + 40.0, 0.0));
+        let height = graph.get_node(blocker).unwrap().size.height;
+
+        // Selected nodes pull the new node to their right, below anything in the way
+        graph.select_node(user);
+        let api = Node::new("api.rest", "Users");
+        assert_eq!(placement.place(&graph, &api), Position::new(width + 40.0, height + 40.0));
+
+        // So do nodes its config names
+        graph.clear_selection();
+        let mut api = Node::new("api.rest", "Users");
+        api.set_config("entity", "User");
+        assert_eq!(placement.place(&graph, &api).x, width + 40.0);
+
+        assert_eq!(KeepPosition.place(&graph, &at(api, 7.0, 9.0)), Position::new(7.0, 9.0));
+    }
+}
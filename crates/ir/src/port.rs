@@ -329,6 +329,34 @@ impl PortCollection {
         self.inputs.is_empty() && self.outputs.is_empty()
     }
 
+    /// Remove a port by ID (searches both inputs and outputs)
+    pub fn remove(&mut self, id: &str) -> Option<Port> {
+        if let Some(idx) = self.inputs.iter().position(|p| p.id == id) {
+            return Some(self.inputs.remove(idx));
+        }
+        let idx = self.outputs.iter().position(|p| p.id == id)?;
+        Some(self.outputs.remove(idx))
+    }
+
+    /// Derive an unused port ID from a display name
+    pub fn unique_id(&self, name: &str) -> PortId {
+        let base: String = name
+            .trim()
+            .to_lowercase()
+            .chars()
+            .map(|c| if c.is_alphanumeric() { c } else { '_' })
+            .collect();
+        let base = if base.is_empty() { "port".to_string() } else { base };
+
+        let mut id = base.clone();
+        let mut n = 2;
+        while self.get(&id).is_some() {
+            id = format!("{}_{}", base, n);
+            n += 1;
+        }
+        id
+    }
+
     /// Sort ports by their order field
     pub fn sort_by_order(&mut self) {
         self.inputs.sort_by_key(|p| p.order);
@@ -340,6 +368,18 @@ impl PortCollection {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_port_collection_unique_id_and_remove() {
+        let mut ports = PortCollection::new();
+        ports.add_input(Port::data_in("input", "Input", DataType::Any));
+        assert_eq!(ports.unique_id("Input"), "input_2");
+        assert_eq!(ports.unique_id("Order Total"), "order_total");
+
+        assert!(ports.remove("input").is_some());
+        assert!(ports.remove("input").is_none());
+        assert!(ports.is_empty());
+    }
+
     #[test]
     fn test_port_creation() {
         let port = Port::data_in("user_id", "User ID", DataType::Uuid);
@@ -8,6 +8,9 @@ use std::collections::HashMap;
 
 use imortal_core::ConfigValue;
 
+use crate::custom_type::CustomType;
+use crate::mixin::Mixin;
+
 /// Metadata for an Immortal Engine project
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ProjectMeta {
@@ -50,6 +53,10 @@ pub struct ProjectMeta {
     /// Target framework (e.g., "axum", "actix", "rocket")
     pub target_framework: Option<String>,
 
+    /// Target database (e.g., "postgres", "sqlite", "mysql")
+    #[serde(default)]
+    pub target_database: Option<String>,
+
     /// Output directory for generated code
     pub output_dir: String,
 
@@ -59,6 +66,26 @@ pub struct ProjectMeta {
     /// Whether to generate documentation
     pub generate_docs: bool,
 
+    /// Remaining code generation settings
+    #[serde(default)]
+    pub codegen: CodegenSettings,
+
+    /// API versioning applied to endpoint routes
+    #[serde(default)]
+    pub api_versioning: ApiVersioning,
+
+    /// Reference fields created from one-to-many relationships
+    #[serde(default)]
+    pub foreign_keys: ForeignKeyFields,
+
+    /// Field sets entities can include, besides the built-in ones
+    #[serde(default)]
+    pub mixins: Vec<Mixin>,
+
+    /// Data types fields can use, besides the built-in ones
+    #[serde(default)]
+    pub types: Vec<CustomType>,
+
     /// Custom metadata
     pub metadata: HashMap<String, ConfigValue>,
 
@@ -89,9 +116,15 @@ impl ProjectMeta {
             domains: HashMap::new(),
             target_language: "rust".to_string(),
             target_framework: None,
+            target_database: None,
             output_dir: "generated".to_string(),
             generate_tests: true,
             generate_docs: true,
+            codegen: CodegenSettings::default(),
+            api_versioning: ApiVersioning::default(),
+            foreign_keys: ForeignKeyFields::default(),
+            mixins: Vec::new(),
+            types: Vec::new(),
             metadata: HashMap::new(),
             ir_version: crate::IR_VERSION.to_string(),
             created_at: None,
@@ -167,6 +200,12 @@ impl ProjectMeta {
         self
     }
 
+    /// Set the target database
+    pub fn with_target_database(mut self, database: impl Into<String>) -> Self {
+        self.target_database = Some(database.into());
+        self
+    }
+
     /// Set the output directory
     pub fn with_output_dir(mut self, dir: impl Into<String>) -> Self {
         self.output_dir = dir.into();
@@ -185,6 +224,30 @@ impl ProjectMeta {
         self
     }
 
+    /// Set the code generation settings
+    pub fn with_codegen(mut self, codegen: CodegenSettings) -> Self {
+        self.codegen = codegen;
+        self
+    }
+
+    /// Set the API versioning settings
+    pub fn with_api_versioning(mut self, versioning: ApiVersioning) -> Self {
+        self.api_versioning = versioning;
+        self
+    }
+
+    /// Set how relationships create foreign key fields
+    pub fn with_foreign_keys(mut self, foreign_keys: ForeignKeyFields) -> Self {
+        self.foreign_keys = foreign_keys;
+        self
+    }
+
+    /// Add a mixin entities can include
+    pub fn with_mixin(mut self, mixin: Mixin) -> Self {
+        self.mixins.push(mixin);
+        self
+    }
+
     /// Add custom metadata
     pub fn with_metadata(mut self, key: impl Into<String>, value: impl Into<ConfigValue>) -> Self {
         self.metadata.insert(key.into(), value.into());
@@ -237,10 +300,13 @@ impl ProjectMeta {
     /// Enable a domain with default configuration
     pub fn enable_domain(&mut self, domain_id: impl Into<String>) {
         let id = domain_id.into();
-        if !self.domains.contains_key(&id) {
-            self.domains.insert(id, DomainConfig::default());
-        } else if let Some(config) = self.domains.get_mut(&id) {
-            config.enabled = true;
+        match self.domains.entry(id) {
+            std::collections::hash_map::Entry::Vacant(entry) => {
+                entry.insert(DomainConfig::default());
+            }
+            std::collections::hash_map::Entry::Occupied(mut entry) => {
+                entry.get_mut().enabled = true;
+            }
         }
     }
 
@@ -263,6 +329,248 @@ impl Default for ProjectMeta {
     }
 }
 
+/// Code generation settings saved with the project
+///
+/// Together with the target framework, database, output directory, and the
+/// test and doc toggles on [`ProjectMeta`], these make up the generator
+/// configuration, so the CLI and the editor generate the same code.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CodegenSettings {
+    /// Database access library (e.g., "sqlx"); `None` uses the default
+    pub orm: Option<String>,
+
+    /// Crate layout ("single" or "workspace"); `None` uses the default
+    pub layout: Option<String>,
+
+    /// How models get inherited and mixin fields ("flatten" or
+    /// "compose"); `None` uses the default
+    pub inheritance: Option<String>,
+
+    /// How tenants are kept apart ("none", "column", or "schema"); `None`
+    /// generates a single-tenant service
+    pub tenancy: Option<String>,
+
+    /// Whether to generate database migrations
+    pub generate_migrations: bool,
+
+    /// Whether to format the generated code
+    pub format_code: bool,
+
+    /// Whether to generate health check endpoints
+    pub health_checks: bool,
+
+    /// Whether to generate Prometheus metrics
+    pub metrics: bool,
+
+    /// Whether to instrument handlers with tracing
+    pub tracing: bool,
+
+    /// Shell commands run in the output directory before generating
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub pre_generate: Vec<String>,
+
+    /// Shell commands run in the output directory after the files are
+    /// written, e.g. `cargo fmt`
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub post_generate: Vec<String>,
+
+    /// Shell commands generated files are piped through before being written
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub transforms: Vec<TransformCommand>,
+}
+
+/// A shell command rewriting the generated files whose paths match
+/// `pattern` (`*` standing for any run of characters): it reads a file on
+/// stdin and prints the new content
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TransformCommand {
+    pub pattern: String,
+    pub command: String,
+}
+
+impl Default for CodegenSettings {
+    fn default() -> Self {
+        Self {
+            orm: None,
+            layout: None,
+            inheritance: None,
+            tenancy: None,
+            generate_migrations: true,
+            format_code: true,
+            health_checks: false,
+            metrics: false,
+            tracing: false,
+            pre_generate: Vec::new(),
+            post_generate: Vec::new(),
+            transforms: Vec::new(),
+        }
+    }
+}
+
+/// Project-level API versioning settings
+///
+/// Endpoints are served under `{prefix}/{version}{path}`, e.g. `/api/v1/users`.
+/// An endpoint's own `version` config takes precedence over the default version.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ApiVersioning {
+    /// Whether endpoints without their own version get the default version
+    pub enabled: bool,
+
+    /// Route prefix placed before the version segment
+    pub prefix: String,
+
+    /// Version used by endpoints that don't set one
+    pub default_version: String,
+}
+
+impl ApiVersioning {
+    /// Create enabled versioning with the given default version
+    pub fn new(default_version: impl Into<String>) -> Self {
+        Self {
+            enabled: true,
+            default_version: default_version.into(),
+            ..Self::default()
+        }
+    }
+
+    /// Set the route prefix
+    pub fn with_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.prefix = prefix.into();
+        self
+    }
+
+    /// Resolve the version of an endpoint from its own version setting
+    pub fn version_for<'a>(&'a self, endpoint_version: Option<&'a str>) -> Option<&'a str> {
+        match endpoint_version.map(str::trim).filter(|v| !v.is_empty()) {
+            Some(version) => Some(version),
+            None if self.enabled && !self.default_version.is_empty() => Some(&self.default_version),
+            None => None,
+        }
+    }
+
+    /// Build the full route of an endpoint
+    pub fn route(&self, path: &str, endpoint_version: Option<&str>) -> String {
+        let path = if path.starts_with('/') {
+            path.to_string()
+        } else {
+            format!("/{}", path)
+        };
+
+        match self.version_for(endpoint_version) {
+            Some(version) => {
+                let prefix = self.prefix.trim_end_matches('/');
+                let version = version.trim_matches('/');
+                if path == "/" {
+                    format!("{}/{}", prefix, version)
+                } else {
+                    format!("{}/{}{}", prefix, version, path)
+                }
+            }
+            None => path,
+        }
+    }
+}
+
+impl Default for ApiVersioning {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            prefix: "/api".to_string(),
+            default_version: "v1".to_string(),
+        }
+    }
+}
+
+/// Project-level settings for foreign key fields created from relationships
+///
+/// When enabled, a one-to-many relationship from `User` to `Post` adds a
+/// `user_id` reference field to `Post`, removed again with the relationship.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ForeignKeyFields {
+    /// Whether relationships add the field to the child entity
+    pub enabled: bool,
+
+    /// How the field is named after the parent entity
+    pub naming: ForeignKeyNaming,
+}
+
+impl ForeignKeyFields {
+    /// Enabled with the given naming convention
+    pub fn new(naming: ForeignKeyNaming) -> Self {
+        Self { enabled: true, naming }
+    }
+
+    /// Name of the field referencing `parent`
+    pub fn field_name(&self, parent: &str) -> String {
+        self.naming.field_name(parent)
+    }
+}
+
+/// Naming convention of foreign key fields
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ForeignKeyNaming {
+    /// `user_id`
+    #[default]
+    SnakeCase,
+    /// `userId`
+    CamelCase,
+    /// `UserId`
+    PascalCase,
+}
+
+impl ForeignKeyNaming {
+    /// All conventions, for pickers
+    pub fn all() -> &'static [ForeignKeyNaming] {
+        &[ForeignKeyNaming::SnakeCase, ForeignKeyNaming::CamelCase, ForeignKeyNaming::PascalCase]
+    }
+
+    /// Name of the field referencing `parent` under this convention
+    pub fn field_name(self, parent: &str) -> String {
+        let mut words = split_words(parent);
+        words.push("id".to_string());
+        let capitalize = |word: &String| {
+            let mut chars = word.chars();
+            chars.next().map(|c| c.to_uppercase().chain(chars).collect::<String>()).unwrap_or_default()
+        };
+        match self {
+            ForeignKeyNaming::SnakeCase => words.join("_"),
+            ForeignKeyNaming::CamelCase => {
+                let (first, rest) = words.split_first().expect("at least the id word");
+                std::iter::once(first.clone()).chain(rest.iter().map(capitalize)).collect()
+            }
+            ForeignKeyNaming::PascalCase => words.iter().map(capitalize).collect(),
+        }
+    }
+}
+
+/// Lowercase words of a name, split at spaces, punctuation, and case changes
+pub(crate) fn split_words(name: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut current = String::new();
+    let mut prev_lower = false;
+    for c in name.chars() {
+        if !c.is_alphanumeric() {
+            if !current.is_empty() {
+                words.push(std::mem::take(&mut current));
+            }
+            prev_lower = false;
+            continue;
+        }
+        if c.is_uppercase() && prev_lower {
+            words.push(std::mem::take(&mut current));
+        }
+        prev_lower = c.is_lowercase() || c.is_ascii_digit();
+        current.extend(c.to_lowercase());
+    }
+    if !current.is_empty() {
+        words.push(current);
+    }
+    words
+}
+
 /// Configuration for a specific domain
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct DomainConfig {
@@ -584,4 +892,36 @@ mod tests {
         config.remove_setting("key");
         assert!(config.get_setting("key").is_none());
     }
+
+    #[test]
+    fn test_api_versioning_routes() {
+        let disabled = ApiVersioning::default();
+        assert_eq!(disabled.route("/users", None), "/users");
+        assert_eq!(disabled.route("/users", Some("v2")), "/api/v2/users");
+
+        let versioning = ApiVersioning::new("v1");
+        assert_eq!(versioning.route("/users", None), "/api/v1/users");
+        assert_eq!(versioning.route("users", Some("")), "/api/v1/users");
+        assert_eq!(versioning.route("/users/:id", Some("v2")), "/api/v2/users/:id");
+        assert_eq!(versioning.clone().with_prefix("/").route("/", None), "/v1");
+    }
+
+    #[test]
+    fn test_foreign_key_naming() {
+        assert_eq!(ForeignKeyNaming::SnakeCase.field_name("User"), "user_id");
+        assert_eq!(ForeignKeyNaming::SnakeCase.field_name("OrderItem"), "order_item_id");
+        assert_eq!(ForeignKeyNaming::CamelCase.field_name("Order Item"), "orderItemId");
+        assert_eq!(ForeignKeyNaming::PascalCase.field_name("order_item"), "OrderItemId");
+        assert!(!ForeignKeyFields::default().enabled);
+    }
+
+    #[test]
+    fn test_api_versioning_deserialize_default() {
+        let meta = ProjectMeta::new("legacy");
+        let mut json = serde_json::to_value(&meta).unwrap();
+        json.as_object_mut().unwrap().remove("api_versioning");
+
+        let meta: ProjectMeta = serde_json::from_value(json).unwrap();
+        assert_eq!(meta.api_versioning, ApiVersioning::default());
+    }
 }
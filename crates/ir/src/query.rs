@@ -0,0 +1,452 @@
+//! Graph query language
+//!
+//! A query is a pipeline of stages separated by `|`, each narrowing or
+//! mapping the set of nodes from the stage before:
+//!
+//! ```text
+//! nodes(type:"data.entity") | connected_to("Database") | !has_field("deleted_at")
+//! ```
+//!
+//! | Stage                       | Result                                             |
+//! |-----------------------------|----------------------------------------------------|
+//! | `nodes(key:"value", ..)`    | All nodes, filtered by `type`, `name`, `category`, `module` or `field` |
+//! | `selected()`                | The selected nodes                                 |
+//! | `type("api.*")`             | Nodes whose component type matches                 |
+//! | `name("User*")`             | Nodes whose name matches                           |
+//! | `category("data")`          | Nodes of a category (`data`, `api`, `auth`, ..)    |
+//! | `in_module("billing")`      | Nodes in a module or one nested in it              |
+//! | `has_field("email")`        | Nodes with a matching field                        |
+//! | `config("backend", "pg")`   | Nodes with a config key, or with that value        |
+//! | `connected_to("Database")`  | Nodes with an edge to a node whose name or type matches |
+//! | `connected()`               | The nodes connected to the current ones            |
+//! | `limit(10)`                 | The first nodes                                    |
+//!
+//! Patterns match case-insensitively, with `*` standing for any run of
+//! characters. Prefixing a filter with `!` keeps the nodes it would drop.
+//! The first stage may be a filter, which starts from all nodes. Results
+//! are ordered by name.
+
+use std::collections::HashSet;
+use std::fmt;
+use std::str::FromStr;
+
+use imortal_core::{ConfigValue, EngineError, EngineResult, NodeId};
+
+use crate::graph::ProjectGraph;
+use crate::node::Node;
+
+/// A parsed query, run against a graph with [`Query::run`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Query {
+    stages: Vec<Stage>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Stage {
+    /// All nodes, filtered
+    Nodes(Vec<Filter>),
+    /// The selected nodes
+    Selected,
+    /// Keep the nodes matching the filter, or those not matching when negated
+    Filter { filter: Filter, negated: bool },
+    /// Neighbours of the current nodes
+    Connected,
+    /// The first n nodes
+    Limit(usize),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Filter {
+    Type(String),
+    Name(String),
+    Category(String),
+    Module(String),
+    HasField(String),
+    Config(String, Option<String>),
+    ConnectedTo(String),
+}
+
+impl Query {
+    /// Parse a query
+    pub fn parse(source: &str) -> EngineResult<Self> {
+        let mut stages = Vec::new();
+        for (idx, text) in split_outside_quotes(source, '|').into_iter().enumerate() {
+            let stage = parse_stage(text.trim())?;
+            if idx > 0 && matches!(stage, Stage::Nodes(_) | Stage::Selected) {
+                return Err(invalid(format!("'{}' can only start a query", text.trim())));
+            }
+            stages.push(stage);
+        }
+        Ok(Self { stages })
+    }
+
+    /// Ids of the nodes matching the query, ordered by name
+    pub fn run(&self, graph: &ProjectGraph) -> Vec<NodeId> {
+        let mut all: Vec<&Node> = graph.nodes.values().collect();
+        all.sort_by(|a, b| a.name.cmp(&b.name).then_with(|| a.id.to_string().cmp(&b.id.to_string())));
+        let order = |ids: &HashSet<NodeId>| all.iter().map(|n| n.id).filter(|id| ids.contains(id)).collect::<Vec<_>>();
+
+        let mut current: Vec<NodeId> = all.iter().map(|n| n.id).collect();
+        for stage in &self.stages {
+            current = match stage {
+                Stage::Nodes(filters) => all
+                    .iter()
+                    .filter(|n| filters.iter().all(|f| f.matches(graph, n)))
+                    .map(|n| n.id)
+                    .collect(),
+                Stage::Selected => order(&graph.selected_nodes.iter().copied().collect()),
+                Stage::Filter { filter, negated } => current
+                    .into_iter()
+                    .filter(|id| graph.get_node(*id).is_some_and(|n| filter.matches(graph, n) != *negated))
+                    .collect(),
+                Stage::Connected => {
+                    order(&current.iter().flat_map(|id| graph.connected_nodes(*id)).collect())
+                }
+                Stage::Limit(n) => current.into_iter().take(*n).collect(),
+            };
+        }
+        current
+    }
+}
+
+impl FromStr for Query {
+    type Err = EngineError;
+
+    fn from_str(source: &str) -> EngineResult<Self> {
+        Self::parse(source)
+    }
+}
+
+impl fmt::Display for Query {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let stages: Vec<String> = self.stages.iter().map(Stage::to_string).collect();
+        write!(f, "{}", stages.join(" | "))
+    }
+}
+
+impl fmt::Display for Stage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Stage::Nodes(filters) => {
+                let args: Vec<String> = filters
+                    .iter()
+                    .map(|filter| {
+                        let (key, pattern) = match filter {
+                            Filter::Type(p) => ("type", p),
+                            Filter::Name(p) => ("name", p),
+                            Filter::Category(p) => ("category", p),
+                            Filter::Module(p) => ("module", p),
+                            Filter::HasField(p) => ("field", p),
+                            Filter::Config(..) | Filter::ConnectedTo(_) => unreachable!("not a nodes() key"),
+                        };
+                        format!("{}:{:?}", key, pattern)
+                    })
+                    .collect();
+                write!(f, "nodes({})", args.join(", "))
+            }
+            Stage::Selected => write!(f, "selected()"),
+            Stage::Filter { filter, negated } => {
+                if *negated {
+                    write!(f, "!")?;
+                }
+                match filter {
+                    Filter::Type(p) => write!(f, "type({:?})", p),
+                    Filter::Name(p) => write!(f, "name({:?})", p),
+                    Filter::Category(p) => write!(f, "category({:?})", p),
+                    Filter::Module(p) => write!(f, "in_module({:?})", p),
+                    Filter::HasField(p) => write!(f, "has_field({:?})", p),
+                    Filter::Config(key, Some(value)) => write!(f, "config({:?}, {:?})", key, value),
+                    Filter::Config(key, None) => write!(f, "config({:?})", key),
+                    Filter::ConnectedTo(p) => write!(f, "connected_to({:?})", p),
+                }
+            }
+            Stage::Connected => write!(f, "connected()"),
+            Stage::Limit(n) => write!(f, "limit({})", n),
+        }
+    }
+}
+
+impl Filter {
+    fn matches(&self, graph: &ProjectGraph, node: &Node) -> bool {
+        match self {
+            Filter::Type(pattern) => glob_match(pattern, &node.component_type),
+            Filter::Name(pattern) => glob_match(pattern, &node.name),
+            Filter::Category(pattern) => {
+                let prefix = node.component_type.split('.').next().unwrap_or_default();
+                glob_match(pattern, prefix) || glob_match(pattern, node.category.display_name())
+            }
+            Filter::Module(path) => {
+                let module = crate::parse_namespace(path);
+                node.namespace.starts_with(&module)
+            }
+            Filter::HasField(pattern) => node.fields.iter().any(|f| glob_match(pattern, &f.name)),
+            Filter::Config(key, value) => match (node.get_config(key), value) {
+                (Some(actual), Some(value)) => config_text(actual).is_some_and(|text| glob_match(value, &text)),
+                (Some(_), None) => true,
+                (None, _) => false,
+            },
+            Filter::ConnectedTo(pattern) => graph.connected_nodes(node.id).into_iter().any(|id| {
+                graph
+                    .get_node(id)
+                    .is_some_and(|other| glob_match(pattern, &other.name) || glob_match(pattern, &other.component_type))
+            }),
+        }
+    }
+}
+
+/// Text a config value is matched by; lists and objects have none
+fn config_text(value: &ConfigValue) -> Option<String> {
+    match value {
+        ConfigValue::String(s) => Some(s.clone()),
+        ConfigValue::Bool(b) => Some(b.to_string()),
+        ConfigValue::Int(i) => Some(i.to_string()),
+        ConfigValue::Float(f) => Some(f.to_string()),
+        ConfigValue::Null | ConfigValue::Array(_) | ConfigValue::Object(_) => None,
+    }
+}
+
+/// Case-insensitive match where `*` stands for any run of characters
+pub fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern = pattern.to_lowercase();
+    let text = text.to_lowercase();
+    let parts: Vec<&str> = pattern.split('*').collect();
+    if parts.len() == 1 {
+        return pattern == text;
+    }
+    let (first, last) = (parts[0], parts[parts.len() - 1]);
+    if !text.starts_with(first) || !text[first.len()..].ends_with(last) {
+        return false;
+    }
+    let mut rest = &text[first.len()..text.len() - last.len()];
+    for part in &parts[1..parts.len() - 1] {
+        match rest.find(part) {
+            Some(at) => rest = &rest[at + part.len()..],
+            None => return false,
+        }
+    }
+    true
+}
+
+fn invalid(message: impl Into<String>) -> EngineError {
+    EngineError::InvalidQuery(message.into())
+}
+
+/// Split on `separator` where it isn't inside a double-quoted string
+fn split_outside_quotes(source: &str, separator: char) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut start = 0;
+    let mut quoted = false;
+    let mut escaped = false;
+    for (idx, c) in source.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if quoted => escaped = true,
+            '"' => quoted = !quoted,
+            c if c == separator && !quoted => {
+                parts.push(&source[start..idx]);
+                start = idx + c.len_utf8();
+            }
+            _ => {}
+        }
+    }
+    parts.push(&source[start..]);
+    parts
+}
+
+/// A stage argument: an optional `key:` and a value
+struct Arg {
+    key: Option<String>,
+    value: String,
+}
+
+fn parse_stage(text: &str) -> EngineResult<Stage> {
+    if text.is_empty() {
+        return Err(invalid("Empty stage"));
+    }
+    let (negated, text) = match text.strip_prefix('!') {
+        Some(rest) => (true, rest.trim_start()),
+        None => (false, text),
+    };
+    let open = text.find('(').ok_or_else(|| invalid(format!("Expected '(' after '{}'", text)))?;
+    let name = text[..open].trim();
+    let inner = text[open + 1..]
+        .trim_end()
+        .strip_suffix(')')
+        .ok_or_else(|| invalid(format!("Missing ')' in '{}'", text)))?;
+    let args = parse_args(inner)?;
+
+    let positional = |count: usize| -> EngineResult<Vec<String>> {
+        if args.len() != count || args.iter().any(|a| a.key.is_some()) {
+            return Err(invalid(format!("{}() takes {} argument(s)", name, count)));
+        }
+        Ok(args.iter().map(|a| a.value.clone()).collect())
+    };
+    let filter = match name {
+        "nodes" | "selected" | "connected" | "limit" if negated => {
+            return Err(invalid(format!("{}() can't be negated", name)));
+        }
+        "nodes" => {
+            let filters = args
+                .iter()
+                .map(|arg| {
+                    let value = arg.value.clone();
+                    match arg.key.as_deref() {
+                        Some("type") => Ok(Filter::Type(value)),
+                        Some("name") => Ok(Filter::Name(value)),
+                        Some("category") => Ok(Filter::Category(value)),
+                        Some("module") => Ok(Filter::Module(value)),
+                        Some("field") => Ok(Filter::HasField(value)),
+                        Some(key) => Err(invalid(format!("nodes() has no '{}' filter", key))),
+                        None => Err(invalid("nodes() filters are written key:\"value\"")),
+                    }
+                })
+                .collect::<EngineResult<_>>()?;
+            return Ok(Stage::Nodes(filters));
+        }
+        "selected" => {
+            positional(0)?;
+            return Ok(Stage::Selected);
+        }
+        "connected" => {
+            positional(0)?;
+            return Ok(Stage::Connected);
+        }
+        "limit" => {
+            let n = positional(1)?.remove(0);
+            let n = n.parse().map_err(|_| invalid(format!("limit() takes a count, not '{}'", n)))?;
+            return Ok(Stage::Limit(n));
+        }
+        "type" => Filter::Type(positional(1)?.remove(0)),
+        "name" => Filter::Name(positional(1)?.remove(0)),
+        "category" => Filter::Category(positional(1)?.remove(0)),
+        "in_module" => Filter::Module(positional(1)?.remove(0)),
+        "has_field" => Filter::HasField(positional(1)?.remove(0)),
+        "connected_to" => Filter::ConnectedTo(positional(1)?.remove(0)),
+        "config" => {
+            let mut values = if args.len() == 1 { positional(1)? } else { positional(2)? };
+            let key = values.remove(0);
+            Filter::Config(key, values.pop())
+        }
+        _ => return Err(invalid(format!("Unknown stage '{}'", name))),
+    };
+    Ok(Stage::Filter { filter, negated })
+}
+
+fn parse_args(inner: &str) -> EngineResult<Vec<Arg>> {
+    if inner.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+    split_outside_quotes(inner, ',')
+        .into_iter()
+        .map(|arg| {
+            let arg = arg.trim();
+            let (key, value) = match split_outside_quotes(arg, ':').as_slice() {
+                [value] => (None, *value),
+                [key, value] => (Some(key.trim().to_string()), value.trim()),
+                _ => return Err(invalid(format!("Malformed argument '{}'", arg))),
+            };
+            Ok(Arg { key, value: parse_value(value)? })
+        })
+        .collect()
+}
+
+/// A double-quoted string, or a bare word such as `10` or `api.*`
+fn parse_value(text: &str) -> EngineResult<String> {
+    if let Some(inner) = text.strip_prefix('"') {
+        let inner = inner.strip_suffix('"').ok_or_else(|| invalid(format!("Unterminated string {}", text)))?;
+        let mut value = String::new();
+        let mut chars = inner.chars();
+        while let Some(c) = chars.next() {
+            if c == '\\' {
+                value.extend(chars.next());
+            } else {
+                value.push(c);
+            }
+        }
+        Ok(value)
+    } else if !text.is_empty() && text.chars().all(|c| c.is_alphanumeric() || "_-.*".contains(c)) {
+        Ok(text.to_string())
+    } else {
+        Err(invalid(format!("Expected a value, found '{}'", text)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::field::Field;
+    use imortal_core::DataType;
+
+    fn names(graph: &ProjectGraph, query: &str) -> Vec<String> {
+        let ids = Query::parse(query).unwrap().run(graph);
+        ids.iter().map(|id| graph.get_node(*id).unwrap().name.clone()).collect()
+    }
+
+    fn sample() -> ProjectGraph {
+        let mut graph = ProjectGraph::with_name("test");
+        let mut user = Node::new_entity("User");
+        user.add_field(Field::new("email", DataType::String));
+        let user = graph.add_node(user);
+        let mut invoice = Node::new_entity("Invoice");
+        invoice.namespace = vec!["billing".to_string()];
+        let invoice = graph.add_node(invoice);
+        graph.add_node(Node::new_entity("Draft"));
+        let mut db = Node::new("storage.database", "Database");
+        db.set_config("backend", "postgres");
+        let db = graph.add_node(db);
+        let api = graph.add_node(Node::new_rest_endpoint("Users API"));
+        graph.add_edge(crate::Edge::dependency(user, db)).unwrap();
+        graph.add_edge(crate::Edge::dependency(invoice, db)).unwrap();
+        graph.add_edge(crate::Edge::dependency(api, user)).unwrap();
+        graph
+    }
+
+    #[test]
+    fn test_query_filters() {
+        let graph = sample();
+        assert_eq!(names(&graph, r#"nodes(type:"data.entity")"#), vec!["Draft", "Invoice", "User"]);
+        assert_eq!(names(&graph, r#"nodes(type:"data.entity") | connected_to("Database")"#), vec!["Invoice", "User"]);
+        assert_eq!(names(&graph, r#"type("data.*") | !connected_to("storage.*")"#), vec!["Draft"]);
+        assert_eq!(names(&graph, r#"has_field("EMAIL")"#), vec!["User"]);
+        assert_eq!(names(&graph, r#"nodes(module:"billing")"#), vec!["Invoice"]);
+        assert_eq!(names(&graph, r#"config(backend, "post*")"#), vec!["Database"]);
+        assert_eq!(names(&graph, r#"config("backend", "mysql")"#), Vec::<String>::new());
+        assert_eq!(names(&graph, "category(api)"), vec!["Users API"]);
+        assert_eq!(names(&graph, r#"name("u*") | connected()"#), vec!["Database", "User", "Users API"]);
+        assert_eq!(names(&graph, "nodes() | limit(2)"), vec!["Database", "Draft"]);
+        assert!(names(&graph, "selected()").is_empty());
+    }
+
+    #[test]
+    fn test_query_parse_errors() {
+        for bad in [
+            "",
+            "nodes(",
+            "frobnicate()",
+            "type()",
+            "nodes(colour:\"red\")",
+            "type(\"x\") | nodes()",
+            "!limit(3)",
+            "limit(many)",
+            "name(\"unterminated)",
+            "name(a b)",
+        ] {
+            assert!(matches!(Query::parse(bad), Err(EngineError::InvalidQuery(_))), "{}", bad);
+        }
+
+        // Quoted separators stay in the value
+        let query: Query = r#"name("a|b, c:d") | !config("x")"#.parse().unwrap();
+        assert_eq!(query.to_string(), r#"name("a|b, c:d") | !config("x")"#);
+        assert_eq!(Query::parse(&query.to_string()).unwrap(), query);
+    }
+
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match("api.*", "api.rest"));
+        assert!(glob_match("*ser*", "User"));
+        assert!(glob_match("a*b*c", "abbc"));
+        assert!(!glob_match("a*b*c", "acb"));
+        assert!(!glob_match("api.*", "data.api"));
+        assert!(glob_match("*", ""));
+    }
+}
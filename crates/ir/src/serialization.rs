@@ -2,17 +2,32 @@
 //!
 //! This module provides functionality to serialize and deserialize ProjectGraph
 //! instances to various formats (JSON, TOML) and handle file I/O operations.
+//!
+//! Loading is [`LoadMode::Strict`] by default and fails on any unreadable
+//! entry or dangling reference. [`LoadMode::Permissive`] loads what it can and
+//! moves the rest into a [`QuarantineReport`], so one bad edge doesn't cost the
+//! whole project.
+//!
+//! Files with the `.imortaldb` extension are SQLite databases handled by
+//! `crate::sqlite` when the `sqlite` feature is enabled; the format passed to
+//! [`save_project`] is ignored for them.
 
+use std::fmt;
 use std::fs;
 use std::io::{Read, Write};
 use std::path::Path;
 
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
 
 use imortal_core::{EngineError, EngineResult};
 
+use crate::edge::Edge;
 use crate::graph::ProjectGraph;
-use crate::{IR_VERSION, PROJECT_EXTENSION};
+use crate::group::Group;
+use crate::node::Node;
+use crate::{COMPONENT_EXTENSION, DB_EXTENSION, IR_VERSION, PROJECT_EXTENSION};
 
 /// Supported project file formats
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
@@ -41,6 +56,7 @@ impl ProjectFormat {
             "json" => Some(ProjectFormat::Json),
             "toml" => Some(ProjectFormat::Toml),
             PROJECT_EXTENSION => Some(ProjectFormat::Json), // .imortal files are JSON
+            COMPONENT_EXTENSION => Some(ProjectFormat::Json), // .icomp files are projects too
             _ => None,
         }
     }
@@ -76,10 +92,7 @@ impl ProjectFile {
 
     /// Check if this file is compatible with the current IR version
     pub fn is_compatible(&self) -> bool {
-        // For now, just check major version
-        let current_major = IR_VERSION.split('.').next().unwrap_or("1");
-        let file_major = self.ir_version.split('.').next().unwrap_or("0");
-        current_major == file_major
+        is_compatible_version(&self.ir_version)
     }
 
     /// Get the project, consuming the wrapper
@@ -88,8 +101,122 @@ impl ProjectFile {
     }
 }
 
+/// How strictly a project file is checked when loading
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum LoadMode {
+    /// Fail on any unreadable entry or dangling reference
+    #[default]
+    Strict,
+    /// Load what can be read and quarantine the rest
+    Permissive,
+}
+
+/// What kind of entry was quarantined
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum QuarantineKind {
+    /// A node that couldn't be read
+    Node,
+    /// An edge that couldn't be read or points at a missing node
+    Edge,
+    /// A group that couldn't be read
+    Group,
+    /// A reference to a missing group or node that was cleared
+    GroupReference,
+}
+
+impl fmt::Display for QuarantineKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            QuarantineKind::Node => "Node",
+            QuarantineKind::Edge => "Edge",
+            QuarantineKind::Group => "Group",
+            QuarantineKind::GroupReference => "Group reference",
+        })
+    }
+}
+
+/// An entry dropped or cleared while loading
+#[derive(Debug, Clone, PartialEq)]
+pub struct QuarantinedItem {
+    /// What kind of entry it was
+    pub kind: QuarantineKind,
+    /// ID of the entry, or of the node/group holding the reference
+    pub id: String,
+    /// Why it was quarantined
+    pub reason: String,
+    /// The dropped entry as it was in the file, if it was dropped whole
+    pub data: Option<Value>,
+}
+
+impl fmt::Display for QuarantinedItem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}: {}", self.kind, self.id, self.reason)
+    }
+}
+
+/// Everything a permissive load had to drop or clear
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct QuarantineReport {
+    pub items: Vec<QuarantinedItem>,
+}
+
+impl QuarantineReport {
+    /// Whether the project loaded without repairs
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Number of quarantined entries
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub(crate) fn add(&mut self, kind: QuarantineKind, id: impl ToString, reason: impl Into<String>, data: Option<Value>) {
+        self.items.push(QuarantinedItem {
+            kind,
+            id: id.to_string(),
+            reason: reason.into(),
+            data,
+        });
+    }
+}
+
+/// A loaded project with the repairs made to it
+#[derive(Debug, Clone)]
+pub struct LoadedProject {
+    pub project: ProjectGraph,
+    /// Always empty for [`LoadMode::Strict`]
+    pub quarantine: QuarantineReport,
+}
+
 /// Load a project from a file path
 pub fn load_project(path: impl AsRef<Path>) -> EngineResult<ProjectGraph> {
+    load_project_with_mode(path, LoadMode::Strict).map(|loaded| loaded.project)
+}
+
+/// Load a project, leaving node details unloaded when it's a `.imortaldb`
+/// database with at least `min_nodes` nodes
+///
+/// Positions, ports, and edges load right away; fields, config, and the
+/// rest of each node are read on demand with
+/// [`ProjectDb::load_details`](crate::sqlite::ProjectDb::load_details).
+/// Other files, and smaller databases, load fully.
+pub fn load_project_lazy(path: impl AsRef<Path>, min_nodes: usize) -> EngineResult<ProjectGraph> {
+    let path = path.as_ref();
+    #[cfg(feature = "sqlite")]
+    if is_database(path) && path.exists() {
+        let db = crate::sqlite::ProjectDb::open(path)?;
+        if db.node_count()? >= min_nodes {
+            return db.load_outline();
+        }
+    }
+    #[cfg(not(feature = "sqlite"))]
+    let _ = min_nodes;
+    load_project(path)
+}
+
+/// Load a project from a file path in the given mode
+pub fn load_project_with_mode(path: impl AsRef<Path>, mode: LoadMode) -> EngineResult<LoadedProject> {
     let path = path.as_ref();
 
     // Check file exists
@@ -97,6 +224,10 @@ pub fn load_project(path: impl AsRef<Path>) -> EngineResult<ProjectGraph> {
         return Err(EngineError::FileNotFound(path.display().to_string()));
     }
 
+    if is_database(path) {
+        return load_database(path, mode);
+    }
+
     // Detect format from extension
     let format = ProjectFormat::from_path(path)
         .unwrap_or(ProjectFormat::Json);
@@ -107,26 +238,10 @@ pub fn load_project(path: impl AsRef<Path>) -> EngineResult<ProjectGraph> {
     file.read_to_string(&mut contents)?;
 
     // Parse based on format
-    let project_file: ProjectFile = match format {
-        ProjectFormat::Json | ProjectFormat::JsonCompact => {
-            serde_json::from_str(&contents)
-                .map_err(|e| EngineError::Deserialization(format!("JSON parse error: {}", e)))?
-        }
-        ProjectFormat::Toml => {
-            toml::from_str(&contents)
-                .map_err(|e| EngineError::Deserialization(format!("TOML parse error: {}", e)))?
-        }
-    };
-
-    // Check compatibility
-    if !project_file.is_compatible() {
-        return Err(EngineError::Deserialization(format!(
-            "Incompatible IR version: file is {}, current is {}",
-            project_file.ir_version, IR_VERSION
-        )));
+    match format {
+        ProjectFormat::Json | ProjectFormat::JsonCompact => load_from_json_with_mode(&contents, mode),
+        ProjectFormat::Toml => load_from_toml_with_mode(&contents, mode),
     }
-
-    Ok(project_file.into_project())
 }
 
 /// Save a project to a file path
@@ -144,6 +259,16 @@ pub fn save_project(
         }
     }
 
+    if is_database(path) {
+        return save_database(project, path);
+    }
+    if !project.fully_loaded() {
+        return Err(EngineError::InvalidProjectStructure(format!(
+            "{} node(s) were opened as an outline and their details aren't loaded",
+            project.unloaded.len()
+        )));
+    }
+
     // Create wrapper
     let project_file = ProjectFile::new(project.clone());
 
@@ -171,19 +296,48 @@ pub fn save_project(
     Ok(())
 }
 
+/// Whether a path names a `.imortaldb` project database
+pub fn is_database(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == DB_EXTENSION)
+}
+
+#[cfg(feature = "sqlite")]
+fn load_database(path: &Path, mode: LoadMode) -> EngineResult<LoadedProject> {
+    crate::sqlite::load_project_db(path, mode)
+}
+
+#[cfg(not(feature = "sqlite"))]
+fn load_database(path: &Path, _mode: LoadMode) -> EngineResult<LoadedProject> {
+    Err(database_unsupported(path))
+}
+
+#[cfg(feature = "sqlite")]
+fn save_database(project: &ProjectGraph, path: &Path) -> EngineResult<()> {
+    crate::sqlite::save_project_db(project, path).map(|_| ())
+}
+
+#[cfg(not(feature = "sqlite"))]
+fn save_database(_project: &ProjectGraph, path: &Path) -> EngineResult<()> {
+    Err(database_unsupported(path))
+}
+
+#[cfg(not(feature = "sqlite"))]
+fn database_unsupported(path: &Path) -> EngineError {
+    EngineError::Custom(format!("{} is a project database, but SQLite support is not enabled", path.display()))
+}
+
 /// Load a project from a JSON string
 pub fn load_from_json(json: &str) -> EngineResult<ProjectGraph> {
-    let project_file: ProjectFile = serde_json::from_str(json)
-        .map_err(|e| EngineError::Deserialization(format!("JSON parse error: {}", e)))?;
+    load_from_json_with_mode(json, LoadMode::Strict).map(|loaded| loaded.project)
+}
 
-    if !project_file.is_compatible() {
-        return Err(EngineError::Deserialization(format!(
-            "Incompatible IR version: file is {}, current is {}",
-            project_file.ir_version, IR_VERSION
-        )));
+/// Load a project from a JSON string in the given mode
+pub fn load_from_json_with_mode(json: &str, mode: LoadMode) -> EngineResult<LoadedProject> {
+    let parse_error = |e: serde_json::Error| EngineError::Deserialization(format!("JSON parse error: {}", e));
+    match mode {
+        LoadMode::Strict => finish_strict(serde_json::from_str(json).map_err(parse_error)?),
+        LoadMode::Permissive => recover(serde_json::from_str(json).map_err(parse_error)?),
     }
-
-    Ok(project_file.into_project())
 }
 
 /// Save a project to a JSON string
@@ -201,17 +355,21 @@ pub fn save_to_json(project: &ProjectGraph, pretty: bool) -> EngineResult<String
 
 /// Load a project from a TOML string
 pub fn load_from_toml(toml_str: &str) -> EngineResult<ProjectGraph> {
-    let project_file: ProjectFile = toml::from_str(toml_str)
-        .map_err(|e| EngineError::Deserialization(format!("TOML parse error: {}", e)))?;
+    load_from_toml_with_mode(toml_str, LoadMode::Strict).map(|loaded| loaded.project)
+}
 
-    if !project_file.is_compatible() {
-        return Err(EngineError::Deserialization(format!(
-            "Incompatible IR version: file is {}, current is {}",
-            project_file.ir_version, IR_VERSION
-        )));
+/// Load a project from a TOML string in the given mode
+pub fn load_from_toml_with_mode(toml_str: &str, mode: LoadMode) -> EngineResult<LoadedProject> {
+    let parse_error = |e: toml::de::Error| EngineError::Deserialization(format!("TOML parse error: {}", e));
+    match mode {
+        LoadMode::Strict => finish_strict(toml::from_str(toml_str).map_err(parse_error)?),
+        LoadMode::Permissive => {
+            let value: toml::Value = toml::from_str(toml_str).map_err(parse_error)?;
+            let value = serde_json::to_value(value)
+                .map_err(|e| EngineError::Deserialization(format!("TOML parse error: {}", e)))?;
+            recover(value)
+        }
     }
-
-    Ok(project_file.into_project())
 }
 
 /// Save a project to a TOML string
@@ -239,6 +397,157 @@ pub fn import_graph_json(json: &str) -> EngineResult<ProjectGraph> {
         .map_err(|e| EngineError::Deserialization(format!("JSON parse error: {}", e)))
 }
 
+pub(crate) fn is_compatible_version(ir_version: &str) -> bool {
+    // For now, just check major version
+    let current_major = IR_VERSION.split('.').next().unwrap_or("1");
+    let file_major = ir_version.split('.').next().unwrap_or("0");
+    current_major == file_major
+}
+
+pub(crate) fn incompatible_version(ir_version: &str) -> EngineError {
+    EngineError::Deserialization(format!(
+        "Incompatible IR version: file is {}, current is {}",
+        ir_version, IR_VERSION
+    ))
+}
+
+/// Reject a parsed file that is incompatible or has dangling references
+pub(crate) fn finish_strict(project_file: ProjectFile) -> EngineResult<LoadedProject> {
+    if !project_file.is_compatible() {
+        return Err(incompatible_version(&project_file.ir_version));
+    }
+
+    let mut project = project_file.into_project();
+    let problems = repair(&mut project);
+    if !problems.is_empty() {
+        let problems: Vec<String> = problems.items.iter().map(|item| item.to_string()).collect();
+        return Err(EngineError::Deserialization(format!(
+            "Project has {} structural problem(s): {}",
+            problems.len(),
+            problems.join("; ")
+        )));
+    }
+
+    Ok(LoadedProject { project, quarantine: QuarantineReport::default() })
+}
+
+/// Load a project file entry by entry, quarantining what can't be read
+///
+/// Only a broken project header (metadata, viewport, version) is fatal.
+fn recover(mut file: Value) -> EngineResult<LoadedProject> {
+    let ir_version = file.get("ir_version").and_then(Value::as_str).unwrap_or_default().to_string();
+    if !is_compatible_version(&ir_version) {
+        return Err(incompatible_version(&ir_version));
+    }
+
+    let Some(project) = file.get_mut("project").and_then(Value::as_object_mut) else {
+        return Err(EngineError::Deserialization("Missing project".to_string()));
+    };
+
+    let mut quarantine = QuarantineReport::default();
+    let nodes: Vec<Node> = take_entries(project, "nodes", QuarantineKind::Node, &mut quarantine);
+    let edges: Vec<Edge> = take_entries(project, "edges", QuarantineKind::Edge, &mut quarantine);
+    let groups: Vec<Group> = take_entries(project, "groups", QuarantineKind::Group, &mut quarantine);
+
+    let mut graph: ProjectGraph = serde_json::from_value(Value::Object(std::mem::take(project)))
+        .map_err(|e| EngineError::Deserialization(format!("Unreadable project header: {}", e)))?;
+    graph.nodes = nodes.into_iter().map(|node| (node.id, node)).collect();
+    graph.edges = edges.into_iter().map(|edge| (edge.id, edge)).collect();
+    graph.groups = groups.into_iter().map(|group| (group.id, group)).collect();
+
+    quarantine.items.extend(repair(&mut graph).items);
+    Ok(LoadedProject { project: graph, quarantine })
+}
+
+/// Read each entry of the map at `key`, leaving an empty map in its place
+fn take_entries<T: DeserializeOwned>(
+    project: &mut Map<String, Value>,
+    key: &str,
+    kind: QuarantineKind,
+    quarantine: &mut QuarantineReport,
+) -> Vec<T> {
+    let entries = match project.insert(key.to_string(), Value::Object(Map::new())) {
+        Some(Value::Object(entries)) => entries,
+        Some(Value::Null) | None => return Vec::new(),
+        Some(other) => {
+            quarantine.add(kind, key, format!("'{}' is not a map", key), Some(other));
+            return Vec::new();
+        }
+    };
+
+    let mut entries: Vec<(String, Value)> = entries.into_iter().collect();
+    entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+    entries
+        .into_iter()
+        .filter_map(|(id, entry)| match serde_json::from_value(entry.clone()) {
+            Ok(item) => Some(item),
+            Err(e) => {
+                quarantine.add(kind, id, format!("unreadable: {}", e), Some(entry));
+                None
+            }
+        })
+        .collect()
+}
+
+/// Drop edges to missing nodes and clear references to missing groups
+/// and nodes, reporting each repair
+pub(crate) fn repair(graph: &mut ProjectGraph) -> QuarantineReport {
+    let mut quarantine = QuarantineReport::default();
+
+    let mut edge_ids: Vec<_> = graph.edges.keys().copied().collect();
+    edge_ids.sort();
+    for id in edge_ids {
+        let edge = &graph.edges[&id];
+        let missing = [edge.from_node, edge.to_node].into_iter().find(|node| !graph.nodes.contains_key(node));
+        if let Some(missing) = missing {
+            let edge = graph.edges.remove(&id).expect("edge exists");
+            let data = serde_json::to_value(&edge).ok();
+            quarantine.add(QuarantineKind::Edge, id, format!("node {} does not exist", missing), data);
+        }
+    }
+
+    let mut node_ids: Vec<_> = graph.nodes.keys().copied().collect();
+    node_ids.sort();
+    for id in node_ids {
+        let node = graph.nodes.get_mut(&id).expect("node exists");
+        if let Some(group) = node.group_id.filter(|group| !graph.groups.contains_key(group)) {
+            node.group_id = None;
+            quarantine.add(QuarantineKind::GroupReference, id, format!("group {} does not exist", group), None);
+        }
+    }
+
+    let mut group_ids: Vec<_> = graph.groups.keys().copied().collect();
+    group_ids.sort();
+    for id in group_ids {
+        let parent_missing = graph.groups[&id].parent_id.filter(|parent| !graph.groups.contains_key(parent));
+        let group = graph.groups.get_mut(&id).expect("group exists");
+        if let Some(parent) = parent_missing {
+            group.parent_id = None;
+            quarantine.add(QuarantineKind::GroupReference, id, format!("parent group {} does not exist", parent), None);
+        }
+        let mut missing: Vec<_> = group.node_ids.iter().filter(|node| !graph.nodes.contains_key(node)).copied().collect();
+        missing.sort();
+        for node in missing {
+            group.node_ids.remove(&node);
+            quarantine.add(QuarantineKind::GroupReference, id, format!("node {} does not exist", node), None);
+        }
+    }
+
+    for bookmark in &mut graph.bookmarks {
+        if let Some(group) = bookmark.group.filter(|group| !graph.groups.contains_key(group)) {
+            bookmark.group = None;
+            quarantine.add(
+                QuarantineKind::GroupReference,
+                &bookmark.name,
+                format!("bookmark group {} does not exist", group),
+                None,
+            );
+        }
+    }
+
+    quarantine
+}
+
 /// Auto-save configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AutoSaveConfig {
@@ -314,7 +623,6 @@ impl RecentProjects {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::node::Node;
     use crate::project::ProjectMeta;
 
     fn create_test_project() -> ProjectGraph {
@@ -329,6 +637,7 @@ mod tests {
         assert_eq!(ProjectFormat::from_extension("json"), Some(ProjectFormat::Json));
         assert_eq!(ProjectFormat::from_extension("toml"), Some(ProjectFormat::Toml));
         assert_eq!(ProjectFormat::from_extension("imortal"), Some(ProjectFormat::Json));
+        assert_eq!(ProjectFormat::from_extension("icomp"), Some(ProjectFormat::Json));
         assert_eq!(ProjectFormat::from_extension("unknown"), None);
     }
 
@@ -356,13 +665,64 @@ mod tests {
 
     #[test]
     fn test_toml_roundtrip() {
-        let project = create_test_project();
+        let mut project = create_test_project();
+        project.add_slide("Overview");
+        project.backgrounds.push(crate::BackgroundLayer::image("Diagram", vec![1, 2, 3], imortal_core::Size::new(64.0, 32.0)));
+        project.backgrounds.push(crate::BackgroundLayer::swimlanes("Teams", crate::LaneOrientation::Horizontal, &["Web", "Data"]));
 
         let toml_str = save_to_toml(&project).unwrap();
         let loaded = load_from_toml(&toml_str).unwrap();
 
         assert_eq!(project.meta.name, loaded.meta.name);
         assert_eq!(project.node_count(), loaded.node_count());
+        assert_eq!(project.slides, loaded.slides);
+        assert_eq!(project.backgrounds, loaded.backgrounds);
+    }
+
+    #[test]
+    fn test_permissive_load_quarantines_broken_entries() {
+        let mut project = create_test_project();
+        let ids: Vec<_> = project.nodes.keys().copied().collect();
+        let edge = Edge::trigger(ids[0], "out", ids[1], "in");
+        let edge_id = edge.id;
+        project.edges.insert(edge.id, edge);
+
+        let mut file = serde_json::to_value(ProjectFile::new(project)).unwrap();
+        let graph = &mut file["project"];
+        let missing = uuid::Uuid::new_v4();
+        // An edge that no longer parses, one to a deleted node, and a node in
+        // a deleted group
+        graph["edges"]["not-an-edge"] = serde_json::json!({ "id": "garbage" });
+        let mut dangling = graph["edges"][edge_id.to_string()].clone();
+        let dangling_id = uuid::Uuid::new_v4();
+        dangling["id"] = serde_json::json!(dangling_id);
+        dangling["to_node"] = serde_json::json!(missing);
+        graph["edges"][dangling_id.to_string()] = dangling;
+        graph["nodes"][ids[0].to_string()]["group_id"] = serde_json::json!(missing);
+        let json = file.to_string();
+
+        let error = load_from_json(&json).unwrap_err().to_string();
+        assert!(error.contains("JSON parse error"), "{}", error);
+
+        let loaded = load_from_json_with_mode(&json, LoadMode::Permissive).unwrap();
+        assert_eq!(loaded.project.node_count(), 2);
+        assert_eq!(loaded.project.edge_count(), 1);
+        assert!(loaded.project.edges.contains_key(&edge_id));
+        assert_eq!(loaded.project.nodes[&ids[0]].group_id, None);
+
+        let kinds: Vec<_> = loaded.quarantine.items.iter().map(|item| (item.kind, item.id.clone())).collect();
+        assert_eq!(kinds, [
+            (QuarantineKind::Edge, "not-an-edge".to_string()),
+            (QuarantineKind::Edge, dangling_id.to_string()),
+            (QuarantineKind::GroupReference, ids[0].to_string()),
+        ]);
+        // Dropped entries keep their data so they can be restored by hand
+        assert!(loaded.quarantine.items[..2].iter().all(|item| item.data.is_some()));
+
+        // Strict loading rejects dangling references even when everything parses
+        file["project"]["edges"].as_object_mut().unwrap().remove("not-an-edge");
+        let error = load_from_json(&file.to_string()).unwrap_err().to_string();
+        assert!(error.contains("2 structural problem(s)"), "{}", error);
     }
 
     #[test]
@@ -0,0 +1,663 @@
+//! SQLite project storage
+//!
+//! Projects saved with the `.imortaldb` extension go in a SQLite database
+//! instead of a single JSON document. Every node, edge and group is a row
+//! holding its JSON plus a hash of it, so a save only rewrites the rows that
+//! changed and one namespace can be loaded without reading the rest of a very
+//! large project. Everything else (metadata, viewport, slides, bookmarks,
+//! templates, background layers) is kept in a single header row.
+//!
+//! A node's row splits its outline (name, position, ports, ...) from its
+//! body (fields, config, metadata, description, subgraph).
+//! [`ProjectDb::load_outline`] reads only the outlines and the edges, so a
+//! project with tens of thousands of nodes opens quickly, and
+//! [`ProjectDb::load_details`] fills in bodies as nodes are shown or
+//! selected.
+//!
+//! [`save_project`](crate::save_project) and [`load_project`](crate::load_project)
+//! pick this backend by extension, so converting between JSON and a database
+//! is a load followed by a save.
+
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+use rusqlite::types::Value as SqlValue;
+use rusqlite::{params, params_from_iter, Connection, OptionalExtension, Transaction};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use uuid::Uuid;
+
+use imortal_core::{ConfigValue, EngineError, EngineResult, NodeId};
+
+use crate::composite::Subgraph;
+use crate::field::Field;
+use crate::graph::ProjectGraph;
+use crate::group::Group;
+use crate::node::Node;
+use crate::serialization::{
+    finish_strict, incompatible_version, is_compatible_version, repair, LoadMode, LoadedProject, ProjectFile, QuarantineKind,
+    QuarantineReport,
+};
+use crate::IR_VERSION;
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS header (key TEXT PRIMARY KEY, value TEXT NOT NULL);
+    CREATE TABLE IF NOT EXISTS nodes (
+        id TEXT PRIMARY KEY, hash INTEGER NOT NULL, namespace TEXT NOT NULL, data TEXT NOT NULL,
+        body_hash INTEGER NOT NULL, body TEXT NOT NULL
+    );
+    CREATE INDEX IF NOT EXISTS nodes_namespace ON nodes (namespace);
+    CREATE TABLE IF NOT EXISTS edges (
+        id TEXT PRIMARY KEY, hash INTEGER NOT NULL, from_node TEXT NOT NULL, to_node TEXT NOT NULL, data TEXT NOT NULL
+    );
+    CREATE INDEX IF NOT EXISTS edges_from_node ON edges (from_node);
+    CREATE INDEX IF NOT EXISTS edges_to_node ON edges (to_node);
+    CREATE TABLE IF NOT EXISTS node_groups (id TEXT PRIMARY KEY, hash INTEGER NOT NULL, data TEXT NOT NULL);
+";
+
+/// Nodes in namespace `?1` or below it, `?2` being `?1` followed by `::`
+/// (or empty for the root namespace)
+const IN_NAMESPACE: &str = "namespace = ?1 OR substr(namespace, 1, length(?2)) = ?2";
+
+/// Most IDs bound in one `IN (...)` query
+const ID_BATCH: usize = 500;
+
+/// Node keys stored in the body column rather than the outline
+const BODY_KEYS: [&str; 5] = ["fields", "config", "metadata", "description", "subgraph"];
+
+/// A table of entries stored as JSON rows
+struct Table {
+    name: &'static str,
+    kind: QuarantineKind,
+    /// Indexed columns besides `id`, `hash` and `data`
+    columns: &'static [&'static str],
+    /// Whether rows also have `body_hash` and `body` columns
+    body: bool,
+}
+
+const NODES: Table = Table { name: "nodes", kind: QuarantineKind::Node, columns: &["namespace"], body: true };
+const EDGES: Table = Table { name: "edges", kind: QuarantineKind::Edge, columns: &["from_node", "to_node"], body: false };
+const GROUPS: Table = Table { name: "node_groups", kind: QuarantineKind::Group, columns: &[], body: false };
+
+/// The parts of a node left out of its outline
+#[derive(Deserialize)]
+struct NodeBody {
+    #[serde(default)]
+    fields: Vec<Field>,
+    #[serde(default)]
+    config: HashMap<String, ConfigValue>,
+    #[serde(default)]
+    metadata: HashMap<String, ConfigValue>,
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default)]
+    subgraph: Option<Box<Subgraph>>,
+}
+
+impl NodeBody {
+    fn apply(self, node: &mut Node) {
+        node.fields = self.fields;
+        node.config = self.config;
+        node.metadata = self.metadata;
+        node.description = self.description;
+        node.subgraph = self.subgraph;
+    }
+}
+
+/// One entry ready to be written
+struct Row {
+    id: Uuid,
+    columns: Vec<String>,
+    data: String,
+    /// A node's body, unless it was never loaded
+    body: Option<String>,
+}
+
+impl Row {
+    fn new(id: Uuid, columns: Vec<String>, entry: &impl Serialize) -> EngineResult<Self> {
+        // Going through `Value` sorts map keys, so an unchanged entry always
+        // serializes (and hashes) the same
+        let mut data = serde_json::to_value(entry)?;
+        sort_node_sets(&mut data);
+        Ok(Self { id, columns, data: data.to_string(), body: None })
+    }
+
+    /// A node split into outline and body; the body is left out when the
+    /// node's details were never loaded
+    fn node(node: &Node, loaded: bool) -> EngineResult<Self> {
+        let mut row = Self::new(node.id, vec![node.namespace_path()], node)?;
+        let Ok(Value::Object(mut outline)) = serde_json::from_str::<Value>(&row.data) else {
+            return Err(EngineError::Serialization(format!("Node {} is not a JSON object", node.id)));
+        };
+        let body: serde_json::Map<String, Value> =
+            BODY_KEYS.iter().filter_map(|key| outline.remove(*key).map(|value| (key.to_string(), value))).collect();
+        row.data = Value::Object(outline).to_string();
+        row.body = loaded.then(|| Value::Object(body).to_string());
+        Ok(row)
+    }
+}
+
+/// Sort the `node_ids` sets of groups, including those inside composite
+/// nodes, which otherwise serialize in hash order
+fn sort_node_sets(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                match value {
+                    Value::Array(ids) if key == "node_ids" => ids.sort_by(|a, b| a.as_str().cmp(&b.as_str())),
+                    _ => sort_node_sets(value),
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(sort_node_sets),
+        _ => {}
+    }
+}
+
+/// How many rows a save touched
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SaveStats {
+    /// Rows inserted or rewritten
+    pub written: usize,
+    /// Rows of removed entries
+    pub deleted: usize,
+}
+
+/// An open `.imortaldb` project database
+pub struct ProjectDb {
+    conn: Connection,
+}
+
+impl ProjectDb {
+    /// Open a project database, creating it if it doesn't exist
+    pub fn open(path: impl AsRef<Path>) -> EngineResult<Self> {
+        let conn = Connection::open(path).map_err(sql_error)?;
+        conn.execute_batch(SCHEMA).map_err(sql_error)?;
+        Ok(Self { conn })
+    }
+
+    /// Store a project, writing only the rows that changed since the last save
+    ///
+    /// Entries missing from `project` are deleted, so this expects the whole
+    /// project rather than the result of [`ProjectDb::load_namespace`]. Nodes
+    /// whose details were never loaded keep their stored body.
+    pub fn save(&mut self, project: &ProjectGraph) -> EngineResult<SaveStats> {
+        let tx = self.conn.transaction().map_err(sql_error)?;
+
+        let mut header = ProjectGraph::new(project.meta.clone());
+        header.viewport = project.viewport;
+        header.slides = project.slides.clone();
+        header.bookmarks = project.bookmarks.clone();
+        header.templates = project.templates.clone();
+        header.backgrounds = project.backgrounds.clone();
+        let header = serde_json::to_string(&header)?;
+        tx.execute(
+            "INSERT OR REPLACE INTO header (key, value) VALUES ('ir_version', ?1), ('project', ?2)",
+            params![IR_VERSION, header],
+        )
+        .map_err(sql_error)?;
+
+        let nodes = project.nodes.values().map(|node| Row::node(node, project.is_loaded(node.id)));
+        let edges = project
+            .edges
+            .values()
+            .map(|edge| Row::new(edge.id, vec![edge.from_node.to_string(), edge.to_node.to_string()], edge));
+        let groups = project.groups.values().map(|group| Row::new(group.id, Vec::new(), group));
+
+        let mut stats = SaveStats::default();
+        sync_table(&tx, &NODES, nodes, &mut stats)?;
+        sync_table(&tx, &EDGES, edges, &mut stats)?;
+        sync_table(&tx, &GROUPS, groups, &mut stats)?;
+
+        tx.commit().map_err(sql_error)?;
+        Ok(stats)
+    }
+
+    /// Load the whole project
+    ///
+    /// Strict loading fails on any unreadable row or dangling reference;
+    /// permissive loading quarantines them like it does for JSON files.
+    pub fn load(&self, mode: LoadMode) -> EngineResult<LoadedProject> {
+        let (ir_version, mut project) = self.load_header()?;
+        let mut quarantine = QuarantineReport::default();
+        let strict = mode == LoadMode::Strict;
+
+        project.nodes = self.load_nodes("", &[], strict, &mut quarantine)?;
+        project.edges = self.load_rows(&EDGES, "", &[], strict, &mut quarantine)?;
+        project.groups = self.load_rows(&GROUPS, "", &[], strict, &mut quarantine)?;
+
+        if strict {
+            return finish_strict(ProjectFile { ir_version, format: "imortal".to_string(), project });
+        }
+        quarantine.items.extend(repair(&mut project).items);
+        Ok(LoadedProject { project, quarantine })
+    }
+
+    /// Load only the nodes in `namespace` or its submodules, the edges
+    /// between them, and the groups holding them
+    ///
+    /// Reads just the matching rows, which is much faster than a full load
+    /// when working on one part of a very large project.
+    pub fn load_namespace(&self, namespace: &[String]) -> EngineResult<ProjectGraph> {
+        let (_, mut project) = self.load_header()?;
+        let path = namespace.join("::");
+        let prefix = if path.is_empty() { String::new() } else { format!("{}::", path) };
+        let mut quarantine = QuarantineReport::default();
+
+        let filter = format!("WHERE {}", IN_NAMESPACE);
+        project.nodes = self.load_nodes(&filter, &[path.as_str(), prefix.as_str()], true, &mut quarantine)?;
+        let filter = format!(
+            "WHERE from_node IN (SELECT id FROM nodes WHERE {0}) AND to_node IN (SELECT id FROM nodes WHERE {0})",
+            IN_NAMESPACE
+        );
+        project.edges = self.load_rows(&EDGES, &filter, &[path.as_str(), prefix.as_str()], true, &mut quarantine)?;
+
+        let groups: HashMap<Uuid, Group> = self.load_rows(&GROUPS, "", &[], true, &mut quarantine)?;
+        let loaded: HashSet<Uuid> = project.nodes.keys().copied().collect();
+        project.groups = groups
+            .into_iter()
+            .filter_map(|(id, mut group)| {
+                group.node_ids.retain(|node| loaded.contains(node));
+                (!group.node_ids.is_empty()).then_some((id, group))
+            })
+            .collect();
+        // Clears references to groups and nodes that were left out
+        repair(&mut project);
+        Ok(project)
+    }
+
+    /// Load every node's outline, plus all edges and groups, leaving node
+    /// details for [`ProjectDb::load_details`]
+    ///
+    /// The unloaded nodes are listed in [`ProjectGraph::unloaded`]. Saving
+    /// such a project back to this database keeps their stored details.
+    pub fn load_outline(&self) -> EngineResult<ProjectGraph> {
+        let (ir_version, mut project) = self.load_header()?;
+        let mut quarantine = QuarantineReport::default();
+
+        project.nodes = self.load_rows(&NODES, "", &[], true, &mut quarantine)?;
+        project.edges = self.load_rows(&EDGES, "", &[], true, &mut quarantine)?;
+        project.groups = self.load_rows(&GROUPS, "", &[], true, &mut quarantine)?;
+        let mut project = finish_strict(ProjectFile { ir_version, format: "imortal".to_string(), project })?.project;
+        project.unloaded = project.nodes.keys().copied().collect();
+        Ok(project)
+    }
+
+    /// Load the details of those of `ids` that are still unloaded in
+    /// `project`, returning how many were loaded
+    ///
+    /// Doesn't mark the project dirty, since nothing changed.
+    pub fn load_details(&self, project: &mut ProjectGraph, ids: &[NodeId]) -> EngineResult<usize> {
+        let ids: Vec<String> = ids.iter().filter(|id| project.unloaded.contains(id)).map(|id| id.to_string()).collect();
+        let mut loaded = 0;
+        for batch in ids.chunks(ID_BATCH) {
+            let placeholders = vec!["?"; batch.len()].join(", ");
+            let filter = format!("WHERE id IN ({})", placeholders);
+            let batch: Vec<&str> = batch.iter().map(String::as_str).collect();
+            for (id, body) in self.load_bodies(&filter, &batch, true, &mut QuarantineReport::default())? {
+                if let Some(node) = project.nodes.get_mut(&id) {
+                    body.apply(node);
+                    project.unloaded.remove(&id);
+                    loaded += 1;
+                }
+            }
+        }
+        Ok(loaded)
+    }
+
+    /// Number of nodes stored, without loading any
+    pub fn node_count(&self) -> EngineResult<usize> {
+        self.conn
+            .query_row("SELECT COUNT(*) FROM nodes", [], |row| row.get::<_, i64>(0))
+            .map(|count| count as usize)
+            .map_err(sql_error)
+    }
+
+    fn load_header(&self) -> EngineResult<(String, ProjectGraph)> {
+        let value = |key: &str| {
+            self.conn
+                .query_row("SELECT value FROM header WHERE key = ?1", [key], |row| row.get::<_, String>(0))
+                .optional()
+                .map_err(sql_error)?
+                .ok_or_else(|| EngineError::Deserialization(format!("Project database has no {}", key)))
+        };
+
+        let ir_version = value("ir_version")?;
+        if !is_compatible_version(&ir_version) {
+            return Err(incompatible_version(&ir_version));
+        }
+        let project = serde_json::from_str(&value("project")?)
+            .map_err(|e| EngineError::Deserialization(format!("Unreadable project header: {}", e)))?;
+        Ok((ir_version, project))
+    }
+
+    /// Read whole nodes matching `filter`; a node whose body is unreadable
+    /// is quarantined with it
+    fn load_nodes(
+        &self,
+        filter: &str,
+        params: &[&str],
+        strict: bool,
+        quarantine: &mut QuarantineReport,
+    ) -> EngineResult<HashMap<Uuid, Node>> {
+        let mut nodes: HashMap<Uuid, Node> = self.load_rows(&NODES, filter, params, strict, quarantine)?;
+        let bodies = self.load_bodies(filter, params, strict, quarantine)?;
+        nodes.retain(|id, _| bodies.contains_key(id));
+        for (id, body) in bodies {
+            if let Some(node) = nodes.get_mut(&id) {
+                body.apply(node);
+            }
+        }
+        Ok(nodes)
+    }
+
+    /// Read the node bodies matching `filter`, keyed by node ID
+    fn load_bodies(
+        &self,
+        filter: &str,
+        params: &[&str],
+        strict: bool,
+        quarantine: &mut QuarantineReport,
+    ) -> EngineResult<HashMap<Uuid, NodeBody>> {
+        self.read_rows(&NODES, "body", filter, params, strict, quarantine)
+    }
+
+    /// Read the rows of `table` matching `filter`, keyed by ID
+    fn load_rows<T: DeserializeOwned>(
+        &self,
+        table: &Table,
+        filter: &str,
+        params: &[&str],
+        strict: bool,
+        quarantine: &mut QuarantineReport,
+    ) -> EngineResult<HashMap<Uuid, T>> {
+        self.read_rows(table, "data", filter, params, strict, quarantine)
+    }
+
+    /// Parse the JSON in `column` of the rows of `table` matching `filter`
+    fn read_rows<T: DeserializeOwned>(
+        &self,
+        table: &Table,
+        column: &str,
+        filter: &str,
+        params: &[&str],
+        strict: bool,
+        quarantine: &mut QuarantineReport,
+    ) -> EngineResult<HashMap<Uuid, T>> {
+        let mut stmt = self
+            .conn
+            .prepare(&format!("SELECT id, {} FROM {} {} ORDER BY id", column, table.name, filter))
+            .map_err(sql_error)?;
+        let rows = stmt
+            .query_map(params_from_iter(params), |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))
+            .map_err(sql_error)?;
+
+        let mut entries = HashMap::new();
+        for row in rows {
+            let (id, data) = row.map_err(sql_error)?;
+            let parsed = Uuid::parse_str(&id)
+                .map_err(|e| e.to_string())
+                .and_then(|uuid| serde_json::from_str(&data).map(|entry| (uuid, entry)).map_err(|e| e.to_string()));
+            match parsed {
+                Ok((uuid, entry)) => {
+                    entries.insert(uuid, entry);
+                }
+                Err(e) if strict => {
+                    return Err(EngineError::Deserialization(format!(
+                        "Unreadable {} of row {} in {}: {}",
+                        column, id, table.name, e
+                    )));
+                }
+                Err(e) => {
+                    let data = serde_json::from_str::<Value>(&data).ok();
+                    quarantine.add(table.kind, id, format!("unreadable: {}", e), data);
+                }
+            }
+        }
+        Ok(entries)
+    }
+}
+
+/// Write the rows whose hash changed and delete the ones no longer present
+fn sync_table(
+    tx: &Transaction,
+    table: &Table,
+    rows: impl Iterator<Item = EngineResult<Row>>,
+    stats: &mut SaveStats,
+) -> EngineResult<()> {
+    let body_hash = if table.body { "body_hash" } else { "NULL" };
+    let mut stored: HashMap<String, (i64, Option<i64>)> = {
+        let mut stmt = tx.prepare(&format!("SELECT id, hash, {} FROM {}", body_hash, table.name)).map_err(sql_error)?;
+        let rows = stmt.query_map([], |row| Ok((row.get(0)?, (row.get(1)?, row.get(2)?)))).map_err(sql_error)?;
+        rows.collect::<Result<_, _>>().map_err(sql_error)?
+    };
+
+    let outline: Vec<&str> = ["hash"].into_iter().chain(table.columns.iter().copied()).chain(["data"]).collect();
+    let body: &[&str] = if table.body { &["body_hash", "body"] } else { &[] };
+    let columns: Vec<&str> = ["id"].into_iter().chain(outline.iter().copied()).chain(body.iter().copied()).collect();
+    let placeholders: Vec<String> = (1..=columns.len()).map(|i| format!("?{}", i)).collect();
+    let mut insert = tx
+        .prepare(&format!(
+            "INSERT OR REPLACE INTO {} ({}) VALUES ({})",
+            table.name,
+            columns.join(", "),
+            placeholders.join(", ")
+        ))
+        .map_err(sql_error)?;
+    let assignments: Vec<String> = outline.iter().enumerate().map(|(i, column)| format!("{} = ?{}", column, i + 2)).collect();
+    let mut update_outline = tx
+        .prepare(&format!("UPDATE {} SET {} WHERE id = ?1", table.name, assignments.join(", ")))
+        .map_err(sql_error)?;
+
+    for row in rows {
+        let row = row?;
+        let id = row.id.to_string();
+        let hash = fnv1a(&row.data);
+        let body = row.body.map(|body| (fnv1a(&body), body));
+        let previous = stored.remove(&id);
+        let unchanged_body = match (&body, previous) {
+            (Some((body_hash, _)), Some((_, stored_body))) => stored_body == Some(*body_hash),
+            (Some(_), None) => false,
+            // An unloaded body is whatever is stored
+            (None, _) => true,
+        };
+        if previous.is_some_and(|(stored, _)| stored == hash) && unchanged_body {
+            continue;
+        }
+
+        let values = [SqlValue::Text(id.clone()), SqlValue::Integer(hash)]
+            .into_iter()
+            .chain(row.columns.into_iter().map(SqlValue::Text))
+            .chain([SqlValue::Text(row.data)]);
+        match body {
+            Some((body_hash, body)) => {
+                let values = values.chain([SqlValue::Integer(body_hash), SqlValue::Text(body)]);
+                insert.execute(params_from_iter(values)).map_err(sql_error)?;
+            }
+            None if !table.body => {
+                insert.execute(params_from_iter(values)).map_err(sql_error)?;
+            }
+            None if previous.is_some() => {
+                update_outline.execute(params_from_iter(values)).map_err(sql_error)?;
+            }
+            None => {
+                return Err(EngineError::InvalidProjectStructure(format!(
+                    "Details of node {} were never loaded and aren't in this database",
+                    id
+                )));
+            }
+        }
+        stats.written += 1;
+    }
+
+    // Whatever wasn't seen has been removed from the project
+    let mut delete = tx.prepare(&format!("DELETE FROM {} WHERE id = ?1", table.name)).map_err(sql_error)?;
+    for id in stored.keys() {
+        delete.execute([id]).map_err(sql_error)?;
+        stats.deleted += 1;
+    }
+    Ok(())
+}
+
+/// A hash that is stable across runs, unlike the standard library's
+fn fnv1a(data: &str) -> i64 {
+    let hash = data.bytes().fold(0xcbf2_9ce4_8422_2325u64, |hash, byte| (hash ^ byte as u64).wrapping_mul(0x100_0000_01b3));
+    hash as i64
+}
+
+fn sql_error(e: rusqlite::Error) -> EngineError {
+    EngineError::Custom(format!("SQLite error: {}", e))
+}
+
+/// Save a project to a database, writing only what changed
+pub fn save_project_db(project: &ProjectGraph, path: impl AsRef<Path>) -> EngineResult<SaveStats> {
+    ProjectDb::open(path)?.save(project)
+}
+
+/// Load a project from a database
+pub fn load_project_db(path: impl AsRef<Path>, mode: LoadMode) -> EngineResult<LoadedProject> {
+    ProjectDb::open(path)?.load(mode)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::edge::Edge;
+    use imortal_core::DataType;
+    use crate::serialization::{load_project, save_project, ProjectFormat};
+
+    fn shop() -> ProjectGraph {
+        let mut project = ProjectGraph::with_name("shop");
+        let user = project.add_node(Node::new_entity("User"));
+        let invoice = project.add_node(Node::new_entity("Invoice").with_namespace("billing"));
+        let line = project.add_node(Node::new_entity("Line").with_namespace("billing::lines"));
+        project.add_edge(Edge::dependency(invoice, user)).unwrap();
+        project.add_edge(Edge::dependency(line, invoice)).unwrap();
+        let mut group = Group::new("Billing");
+        group.add_node(invoice);
+        group.add_node(line);
+        project.groups.insert(group.id, group);
+        project
+    }
+
+    #[test]
+    fn test_roundtrip_and_conversion() {
+        let dir = tempfile::tempdir().unwrap();
+        let json = dir.path().join("shop.imortal");
+        let db = dir.path().join("shop.imortaldb");
+        let project = shop();
+
+        // Converting is just saving under the other extension
+        save_project(&project, &json, ProjectFormat::Json).unwrap();
+        save_project(&load_project(&json).unwrap(), &db, ProjectFormat::Json).unwrap();
+        let loaded = load_project(&db).unwrap();
+        assert_eq!(loaded.meta.name, "shop");
+        assert_eq!(loaded.node_count(), 3);
+        assert_eq!(loaded.edges.len(), 2);
+        assert_eq!(loaded.groups.values().next().unwrap().node_ids.len(), 2);
+
+        let back = dir.path().join("back.imortal");
+        save_project(&loaded, &back, ProjectFormat::Json).unwrap();
+        assert_eq!(load_project(&back).unwrap().nodes, project.nodes);
+    }
+
+    #[test]
+    fn test_incremental_save() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("shop.imortaldb");
+        let project = shop();
+        let mut db = ProjectDb::open(&path).unwrap();
+        assert_eq!(db.save(&project).unwrap(), SaveStats { written: 6, deleted: 0 });
+        assert_eq!(db.save(&project).unwrap(), SaveStats::default());
+
+        // A fresh load round-trips without rewriting anything
+        let mut project = ProjectDb::open(&path).unwrap().load(LoadMode::Strict).unwrap().project;
+        assert_eq!(db.save(&project).unwrap(), SaveStats::default());
+
+        let user = project.find_nodes_by_name("User")[0].id;
+        project.get_node_mut(user).unwrap().description = Some("A customer".to_string());
+        assert_eq!(db.save(&project).unwrap(), SaveStats { written: 1, deleted: 0 });
+
+        // Removing a node takes its edge with it
+        project.remove_node(user);
+        assert_eq!(db.save(&project).unwrap(), SaveStats { written: 0, deleted: 2 });
+        assert_eq!(load_project(&path).unwrap().node_count(), 2);
+    }
+
+    #[test]
+    fn test_load_namespace() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("shop.imortaldb");
+        save_project_db(&shop(), &path).unwrap();
+        let db = ProjectDb::open(&path).unwrap();
+
+        let billing = db.load_namespace(&["billing".to_string()]).unwrap();
+        let mut names: Vec<_> = billing.nodes.values().map(|n| n.name.as_str()).collect();
+        names.sort();
+        assert_eq!(names, ["Invoice", "Line"]);
+        // Only the edge inside the namespace comes along
+        assert_eq!(billing.edges.len(), 1);
+        assert_eq!(billing.groups.len(), 1);
+
+        let lines = db.load_namespace(&["billing".to_string(), "lines".to_string()]).unwrap();
+        assert_eq!(lines.node_count(), 1);
+        assert!(lines.edges.is_empty());
+        assert!(db.load_namespace(&["bill".to_string()]).unwrap().nodes.is_empty());
+        assert_eq!(db.load_namespace(&[]).unwrap().node_count(), 3);
+    }
+
+    #[test]
+    fn test_outline_load() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("shop.imortaldb");
+        let mut project = shop();
+        let user = project.find_nodes_by_name("User")[0].id;
+        project.get_node_mut(user).unwrap().add_field(Field::new("email", DataType::String));
+        let mut db = ProjectDb::open(&path).unwrap();
+        db.save(&project).unwrap();
+        assert_eq!(db.node_count().unwrap(), 3);
+
+        let mut outline = db.load_outline().unwrap();
+        assert_eq!(outline.edges.len(), 2);
+        assert!(!outline.is_loaded(user));
+        assert!(outline.nodes[&user].fields.is_empty());
+
+        // Moving an unloaded node only rewrites its outline
+        outline.get_node_mut(user).unwrap().position.x = 500.0;
+        assert_eq!(db.save(&outline).unwrap(), SaveStats { written: 1, deleted: 0 });
+        let stored = db.load(LoadMode::Strict).unwrap().project;
+        assert_eq!(stored.nodes[&user].position.x, 500.0);
+        assert!(stored.nodes[&user].fields.iter().any(|f| f.name == "email"));
+
+        // Another database doesn't have the details to keep
+        let copy = dir.path().join("copy.imortaldb");
+        assert!(save_project(&outline, &copy, ProjectFormat::Json).is_err());
+        assert!(save_project(&outline, dir.path().join("shop.imortal"), ProjectFormat::Json).is_err());
+
+        assert_eq!(db.load_details(&mut outline, &[user, user]).unwrap(), 1);
+        assert!(outline.is_loaded(user));
+        assert_eq!(outline.nodes[&user].fields, project.nodes[&user].fields);
+        assert_eq!(db.load_details(&mut outline, &[user]).unwrap(), 0);
+        let rest: Vec<_> = outline.unloaded.iter().copied().collect();
+        db.load_details(&mut outline, &rest).unwrap();
+        assert!(outline.fully_loaded());
+        save_project(&outline, &copy, ProjectFormat::Json).unwrap();
+    }
+
+    #[test]
+    fn test_permissive_load_quarantines_broken_rows() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("shop.imortaldb");
+        let mut db = ProjectDb::open(&path).unwrap();
+        db.save(&shop()).unwrap();
+        db.conn.execute("UPDATE nodes SET data = '{}' WHERE namespace = ''", []).unwrap();
+
+        assert!(db.load(LoadMode::Strict).is_err());
+        let loaded = db.load(LoadMode::Permissive).unwrap();
+        assert_eq!(loaded.project.node_count(), 2);
+        // The broken node plus the edge pointing at it
+        assert_eq!(loaded.quarantine.len(), 2);
+    }
+}
@@ -0,0 +1,306 @@
+//! Project statistics and complexity metrics
+//!
+//! Gives a quick sense of the scope of a project: how many components of
+//! each kind it has, how densely they are connected, how deep the
+//! dependency chain goes, and which nodes concentrate the most connections.
+//! [`NodeMetric`]s give the same kind of measure per node, for heatmaps.
+
+use std::collections::{HashMap, VecDeque};
+
+use imortal_core::{ComponentCategory, NodeId};
+use serde::{Deserialize, Serialize};
+
+use crate::graph::ProjectGraph;
+use crate::node::Node;
+
+/// Maximum number of hotspots reported
+const MAX_HOTSPOTS: usize = 5;
+
+/// Minimum number of connections for a node to count as a hotspot
+const MIN_HOTSPOT_DEGREE: usize = 3;
+
+/// A node with unusually many connections
+#[derive(Debug, Clone, PartialEq)]
+pub struct Hotspot {
+    /// The node's ID
+    pub node_id: NodeId,
+    /// The node's name
+    pub name: String,
+    /// Number of edges touching the node
+    pub degree: usize,
+}
+
+/// Size and complexity metrics for a project graph
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ProjectStats {
+    /// Total number of nodes
+    pub nodes: usize,
+    /// Total number of edges
+    pub edges: usize,
+    /// Total number of groups
+    pub groups: usize,
+    /// Total number of fields across all entities
+    pub entity_fields: usize,
+    /// Nodes per category
+    pub by_category: HashMap<ComponentCategory, usize>,
+    /// Average number of edges per node
+    pub average_connectivity: f32,
+    /// Number of edges on the longest dependency path
+    pub dag_depth: usize,
+    /// Whether the graph contains cycles (excluded from `dag_depth`)
+    pub has_cycles: bool,
+    /// Most connected nodes, most connected first
+    pub hotspots: Vec<Hotspot>,
+}
+
+impl ProjectStats {
+    /// Get count for a specific category
+    pub fn category_count(&self, category: ComponentCategory) -> usize {
+        self.by_category.get(&category).copied().unwrap_or(0)
+    }
+}
+
+/// A per-node measure of size or coupling
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NodeMetric {
+    /// Number of edges touching the node
+    Degree,
+    /// Number of fields
+    FieldCount,
+    /// Rough number of lines the code generator writes for the node
+    EstimatedLines,
+    /// Number of validation errors and warnings about the node
+    Issues,
+}
+
+impl NodeMetric {
+    pub fn all() -> &'static [NodeMetric] {
+        &[NodeMetric::Degree, NodeMetric::FieldCount, NodeMetric::EstimatedLines, NodeMetric::Issues]
+    }
+
+    /// Display name
+    pub fn name(&self) -> &'static str {
+        match self {
+            NodeMetric::Degree => "Connections",
+            NodeMetric::FieldCount => "Fields",
+            NodeMetric::EstimatedLines => "Generated lines (est.)",
+            NodeMetric::Issues => "Validation issues",
+        }
+    }
+}
+
+/// Rough number of lines generated for a node
+///
+/// An entity gets a model, DTOs, a migration, and CRUD handlers, all of
+/// which grow with its fields; other components get a module whose size
+/// follows their ports and configuration.
+pub fn estimated_lines(node: &Node) -> usize {
+    if node.component_type == "data.entity" {
+        60 + 14 * node.fields.len()
+    } else {
+        20 + 6 * node.ports.len() + 3 * node.config.len() + 4 * node.fields.len()
+    }
+}
+
+impl ProjectGraph {
+    /// Compute size and complexity metrics for this project
+    ///
+    /// A node is a hotspot when it has at least three connections and at
+    /// least twice the average.
+    pub fn stats(&self) -> ProjectStats {
+        let mut stats = ProjectStats {
+            nodes: self.node_count(),
+            edges: self.edge_count(),
+            groups: self.group_count(),
+            ..Default::default()
+        };
+
+        let mut degree: HashMap<NodeId, usize> = HashMap::new();
+        for node in self.nodes() {
+            *stats.by_category.entry(node.category).or_default() += 1;
+            if node.component_type == "data.entity" {
+                stats.entity_fields += node.fields.len();
+            }
+            degree.insert(node.id, 0);
+        }
+        for edge in self.edges() {
+            *degree.entry(edge.from_node).or_default() += 1;
+            *degree.entry(edge.to_node).or_default() += 1;
+        }
+
+        if stats.nodes > 0 {
+            stats.average_connectivity = (2 * stats.edges) as f32 / stats.nodes as f32;
+        }
+
+        let threshold = MIN_HOTSPOT_DEGREE.max((2.0 * stats.average_connectivity).ceil() as usize);
+        let mut hotspots: Vec<Hotspot> = self
+            .nodes()
+            .filter_map(|node| {
+                let degree = degree.get(&node.id).copied().unwrap_or(0);
+                (degree >= threshold).then(|| Hotspot {
+                    node_id: node.id,
+                    name: node.name.clone(),
+                    degree,
+                })
+            })
+            .collect();
+        hotspots.sort_by(|a, b| b.degree.cmp(&a.degree).then(a.name.cmp(&b.name)));
+        hotspots.truncate(MAX_HOTSPOTS);
+        stats.hotspots = hotspots;
+
+        let (depth, has_cycles) = self.longest_path();
+        stats.dag_depth = depth;
+        stats.has_cycles = has_cycles;
+
+        stats
+    }
+
+    /// A metric's value for every node
+    pub fn node_metric(&self, metric: NodeMetric) -> HashMap<NodeId, usize> {
+        let mut values: HashMap<NodeId, usize> = self.node_ids().map(|id| (*id, 0)).collect();
+        match metric {
+            NodeMetric::Degree => {
+                for edge in self.edges() {
+                    for id in [edge.from_node, edge.to_node] {
+                        if let Some(value) = values.get_mut(&id) {
+                            *value += 1;
+                        }
+                    }
+                }
+            }
+            NodeMetric::FieldCount => {
+                for node in self.nodes() {
+                    values.insert(node.id, node.fields.len());
+                }
+            }
+            NodeMetric::EstimatedLines => {
+                for node in self.nodes() {
+                    values.insert(node.id, estimated_lines(node));
+                }
+            }
+            NodeMetric::Issues => {
+                for issue in crate::validation::get_all_issues(self) {
+                    if let Some(value) = issue.node_id.and_then(|id| values.get_mut(&id)) {
+                        *value += 1;
+                    }
+                }
+            }
+        }
+        values
+    }
+
+    /// Longest path (in edges) through the acyclic part of the graph, and
+    /// whether any nodes were left out because they sit on a cycle
+    fn longest_path(&self) -> (usize, bool) {
+        let mut in_degree: HashMap<NodeId, usize> = self.node_ids().map(|id| (*id, 0)).collect();
+        for edge in self.edges() {
+            if edge.from_node != edge.to_node {
+                *in_degree.entry(edge.to_node).or_default() += 1;
+            }
+        }
+
+        let mut depth: HashMap<NodeId, usize> = HashMap::new();
+        let mut queue: VecDeque<NodeId> = in_degree
+            .iter()
+            .filter(|(_, d)| **d == 0)
+            .map(|(id, _)| *id)
+            .collect();
+        let mut visited = 0;
+        let mut longest = 0;
+
+        while let Some(id) = queue.pop_front() {
+            visited += 1;
+            let current = depth.get(&id).copied().unwrap_or(0);
+            longest = longest.max(current);
+
+            for edge in self.outgoing_edges(id) {
+                if edge.to_node == id {
+                    continue;
+                }
+                let next = depth.entry(edge.to_node).or_default();
+                *next = (*next).max(current + 1);
+                if let Some(d) = in_degree.get_mut(&edge.to_node) {
+                    *d -= 1;
+                    if *d == 0 {
+                        queue.push_back(edge.to_node);
+                    }
+                }
+            }
+        }
+
+        (longest, visited < in_degree.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{estimated_lines, NodeMetric};
+    use crate::{Edge, Node, ProjectGraph};
+    use imortal_core::ComponentCategory;
+
+    #[test]
+    fn test_empty_stats() {
+        let stats = ProjectGraph::with_name("empty").stats();
+        assert_eq!(stats.nodes, 0);
+        assert_eq!(stats.average_connectivity, 0.0);
+        assert_eq!(stats.dag_depth, 0);
+        assert!(!stats.has_cycles);
+    }
+
+    #[test]
+    fn test_stats() {
+        let mut graph = ProjectGraph::with_name("test");
+        let user = graph.add_node(Node::new_entity("User"));
+        let db = graph.add_node(Node::new_database("DB"));
+        let endpoints: Vec<_> = ["A", "B", "C"]
+            .iter()
+            .map(|name| graph.add_node(Node::new_rest_endpoint(*name)))
+            .collect();
+        graph.add_edge(Edge::dependency(db, user)).unwrap();
+        for endpoint in &endpoints {
+            graph.add_edge(Edge::dependency(user, *endpoint)).unwrap();
+        }
+
+        let stats = graph.stats();
+        assert_eq!(stats.nodes, 5);
+        assert_eq!(stats.edges, 4);
+        assert_eq!(stats.category_count(ComponentCategory::Api), 3);
+        assert_eq!(stats.entity_fields, 1);
+        assert!((stats.average_connectivity - 1.6).abs() < f32::EPSILON);
+        assert_eq!(stats.dag_depth, 2);
+        assert!(!stats.has_cycles);
+        assert_eq!(stats.hotspots.len(), 1);
+        assert_eq!(stats.hotspots[0].node_id, user);
+        assert_eq!(stats.hotspots[0].degree, 4);
+
+        graph.add_edge(Edge::dependency(endpoints[0], db)).unwrap();
+        assert!(graph.stats().has_cycles);
+    }
+
+    #[test]
+    fn test_node_metrics() {
+        let mut graph = ProjectGraph::with_name("test");
+        let user = graph.add_node(Node::new_entity("User"));
+        let db = graph.add_node(Node::new_database("DB"));
+        let endpoint = graph.add_node(Node::new_rest_endpoint("Users"));
+        graph.add_edge(Edge::dependency(db, user)).unwrap();
+        graph.add_edge(Edge::dependency(user, endpoint)).unwrap();
+
+        let degree = graph.node_metric(NodeMetric::Degree);
+        assert_eq!((degree[&user], degree[&db], degree[&endpoint]), (2, 1, 1));
+
+        let fields = graph.node_metric(NodeMetric::FieldCount);
+        assert_eq!(fields[&user], 1);
+
+        let lines = graph.node_metric(NodeMetric::EstimatedLines);
+        assert_eq!(lines[&user], estimated_lines(graph.get_node(user).unwrap()));
+        graph.get_node_mut(user).unwrap().fields.push(crate::Field::new("email", imortal_core::DataType::String));
+        assert_eq!(graph.node_metric(NodeMetric::EstimatedLines)[&user], lines[&user] + 14);
+
+        let issues = graph.node_metric(NodeMetric::Issues);
+        assert_eq!(issues.len(), 3);
+        let expected = crate::validation::get_all_issues(&graph).iter().filter(|i| i.node_id == Some(user)).count();
+        assert_eq!(graph.node_metric(NodeMetric::Issues)[&user], expected);
+    }
+}
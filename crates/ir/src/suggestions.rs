@@ -0,0 +1,271 @@
+//! Connection suggestions for Immortal Engine IR
+//!
+//! Heuristics proposing edges a project probably wants but doesn't have:
+//! entities stored in no database, REST endpoints named after an entity
+//! they don't use, and `<entity>_id` fields without a relationship. Each
+//! suggestion can be accepted to add its edge.
+
+use imortal_core::{EdgeId, EngineResult, NodeId, RelationType};
+
+use crate::edge::Edge;
+use crate::graph::ProjectGraph;
+use crate::node::Node;
+
+/// The heuristic a suggestion comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SuggestionKind {
+    /// Entity connected to no database; suggests a dependency from one
+    EntityStorage,
+    /// Endpoint whose name or path names an entity; suggests a dependency
+    /// from the entity
+    EndpointEntity,
+    /// Field named after another entity, like `author_id`; suggests a
+    /// one-to-many relationship on it
+    ForeignKeyField,
+}
+
+impl SuggestionKind {
+    /// Get a human-readable name for this kind
+    pub fn name(&self) -> &'static str {
+        match self {
+            SuggestionKind::EntityStorage => "Entity Storage",
+            SuggestionKind::EndpointEntity => "Endpoint Entities",
+            SuggestionKind::ForeignKeyField => "Foreign Keys",
+        }
+    }
+
+    /// All suggestion kinds, in report order
+    pub fn all() -> &'static [SuggestionKind] {
+        &[SuggestionKind::EntityStorage, SuggestionKind::EndpointEntity, SuggestionKind::ForeignKeyField]
+    }
+}
+
+/// A proposed edge
+#[derive(Debug, Clone, PartialEq)]
+pub struct Suggestion {
+    pub kind: SuggestionKind,
+    pub from: NodeId,
+    pub to: NodeId,
+    /// For foreign keys, the fields joined: the target's key, then the
+    /// field holding it
+    pub fields: Option<(String, String)>,
+    /// Human-readable description
+    pub message: String,
+}
+
+impl Suggestion {
+    /// The edge accepting the suggestion would add
+    pub fn edge(&self) -> Edge {
+        match &self.fields {
+            Some((from_field, to_field)) => {
+                Edge::relationship_on_fields(self.from, from_field.as_str(), self.to, to_field.as_str(), RelationType::OneToMany)
+            }
+            None => Edge::dependency(self.from, self.to),
+        }
+    }
+
+    /// Add the suggested edge to `graph`
+    pub fn accept(&self, graph: &mut ProjectGraph) -> EngineResult<EdgeId> {
+        match &self.fields {
+            Some((from_field, to_field)) => {
+                graph.add_field_relationship(self.from, from_field, self.to, to_field, RelationType::OneToMany)
+            }
+            None => graph.add_edge(self.edge()),
+        }
+    }
+}
+
+/// Suggested connections for `graph`, grouped by kind in
+/// [`SuggestionKind::all`] order
+///
+/// Disabled nodes are left out, as are pairs already joined by any edge.
+pub fn suggest_connections(graph: &ProjectGraph) -> Vec<Suggestion> {
+    let mut suggestions = Vec::new();
+    suggestions.extend(entity_storage(graph));
+    suggestions.extend(endpoint_entities(graph));
+    suggestions.extend(foreign_key_fields(graph));
+    suggestions
+}
+
+/// Enabled nodes of a component type, sorted by name
+fn enabled_of_type<'a>(graph: &'a ProjectGraph, component_type: &str) -> Vec<&'a Node> {
+    let mut nodes: Vec<_> = graph.find_nodes_by_type(component_type).into_iter().filter(|n| n.enabled).collect();
+    nodes.sort_by(|a, b| a.name.cmp(&b.name).then(a.id.cmp(&b.id)));
+    nodes
+}
+
+fn connected(graph: &ProjectGraph, a: NodeId, b: NodeId) -> bool {
+    graph.edges().any(|e| e.is_between(a, b))
+}
+
+/// Lowercase letters and digits only, so "order-items" matches "OrderItems"
+fn normalize(word: &str) -> String {
+    word.chars().filter(|c| c.is_alphanumeric()).flat_map(char::to_lowercase).collect()
+}
+
+/// A word and what it might be the plural of, e.g. "boxes" gives "boxes",
+/// "boxe" and "box"
+fn singular_forms(word: &str) -> Vec<String> {
+    let mut forms = vec![word.to_string()];
+    if let Some(stem) = word.strip_suffix("ies") {
+        forms.push(format!("{}y", stem));
+    }
+    if let Some(stem) = word.strip_suffix('s') {
+        forms.push(stem.to_string());
+        forms.extend(stem.strip_suffix('e').map(str::to_string));
+    }
+    forms
+}
+
+/// Entities no database is connected to, each paired with the first
+/// database by name
+fn entity_storage(graph: &ProjectGraph) -> Vec<Suggestion> {
+    let databases = enabled_of_type(graph, "storage.database");
+    let Some(first) = databases.first() else {
+        return Vec::new();
+    };
+    enabled_of_type(graph, "data.entity")
+        .into_iter()
+        .filter(|entity| !databases.iter().any(|db| connected(graph, db.id, entity.id)))
+        .map(|entity| Suggestion {
+            kind: SuggestionKind::EntityStorage,
+            from: first.id,
+            to: entity.id,
+            fields: None,
+            message: format!("Store '{}' in '{}'", entity.name, first.name),
+        })
+        .collect()
+}
+
+/// Endpoints with a name word or path segment naming an entity, in the
+/// singular or plural
+fn endpoint_entities(graph: &ProjectGraph) -> Vec<Suggestion> {
+    let entities = enabled_of_type(graph, "data.entity");
+    let mut suggestions = Vec::new();
+    for endpoint in enabled_of_type(graph, "api.rest") {
+        let path_segments = endpoint
+            .endpoint_path()
+            .unwrap_or_default()
+            .split('/')
+            .filter(|s| !s.starts_with(':') && !s.starts_with('{'));
+        let words: Vec<String> = endpoint
+            .name
+            .split_whitespace()
+            .chain(path_segments)
+            .flat_map(|word| singular_forms(&normalize(word)))
+            .filter(|word| !word.is_empty())
+            .collect();
+        for entity in &entities {
+            let name = normalize(&entity.name);
+            if words.contains(&name) && !connected(graph, entity.id, endpoint.id) {
+                suggestions.push(Suggestion {
+                    kind: SuggestionKind::EndpointEntity,
+                    from: entity.id,
+                    to: endpoint.id,
+                    fields: None,
+                    message: format!("'{}' serves '{}'", endpoint.name, entity.name),
+                });
+            }
+        }
+    }
+    suggestions
+}
+
+/// Fields named `<entity>_id` (or `<entity>Id`) of the same type as that
+/// entity's primary key, with no relationship between the two entities
+fn foreign_key_fields(graph: &ProjectGraph) -> Vec<Suggestion> {
+    let entities = enabled_of_type(graph, "data.entity");
+    let mut suggestions = Vec::new();
+    for child in &entities {
+        for field in &child.fields {
+            if field.is_primary_key() || field.is_foreign_key() {
+                continue;
+            }
+            let Some(stem) = field.name.strip_suffix("_id").or_else(|| field.name.strip_suffix("Id")) else {
+                continue;
+            };
+            let stem = normalize(stem);
+            for target in entities.iter().filter(|t| t.id != child.id && normalize(&t.name) == stem) {
+                let Some(key) = target.fields.iter().find(|f| f.is_primary_key()) else {
+                    continue;
+                };
+                if !field.can_reference(key, &target.name) || connected(graph, target.id, child.id) {
+                    continue;
+                }
+                suggestions.push(Suggestion {
+                    kind: SuggestionKind::ForeignKeyField,
+                    from: target.id,
+                    to: child.id,
+                    fields: Some((key.name.clone(), field.name.clone())),
+                    message: format!("'{}.{}' references '{}'", child.name, field.name, target.name),
+                });
+            }
+        }
+    }
+    suggestions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Field;
+
+    fn endpoint(name: &str, path: &str) -> Node {
+        let mut node = Node::new_rest_endpoint(name);
+        node.set_config("path", path);
+        node
+    }
+
+    #[test]
+    fn test_singular_forms() {
+        assert!(singular_forms("users").contains(&"user".to_string()));
+        assert!(singular_forms("categories").contains(&"category".to_string()));
+        assert!(singular_forms("boxes").contains(&"box".to_string()));
+        assert!(singular_forms("houses").contains(&"house".to_string()));
+        assert_eq!(singular_forms("address")[0], "address");
+        assert_eq!(normalize("order-items"), "orderitems");
+    }
+
+    #[test]
+    fn test_suggestions() {
+        let mut graph = ProjectGraph::with_name("shop");
+        let db = graph.add_node(Node::new_database("Main"));
+        let user = graph.add_node(Node::new_entity("User"));
+        let mut item = Node::new_entity("OrderItem");
+        item.add_field(Field::uuid("user_id"));
+        let item = graph.add_node(item);
+        let list = graph.add_node(endpoint("List Users", "/users"));
+        let items = graph.add_node(endpoint("Items", "/order-items/:id"));
+        let health = graph.add_node(endpoint("Health", "/health"));
+        graph.add_edge(Edge::dependency(db, user)).unwrap();
+
+        let suggestions = suggest_connections(&graph);
+        let pairs: Vec<_> = suggestions.iter().map(|s| (s.kind, s.from, s.to)).collect();
+        assert_eq!(pairs, vec![
+            (SuggestionKind::EntityStorage, db, item),
+            (SuggestionKind::EndpointEntity, item, items),
+            (SuggestionKind::EndpointEntity, user, list),
+            (SuggestionKind::ForeignKeyField, user, item),
+        ]);
+        assert!(!pairs.iter().any(|(_, from, to)| *from == health || *to == health));
+        assert_eq!(suggestions[3].fields, Some(("id".to_string(), "user_id".to_string())));
+
+        for suggestion in &suggestions {
+            suggestion.accept(&mut graph).unwrap();
+        }
+        assert!(suggest_connections(&graph).is_empty());
+        assert!(graph.edges().any(|e| e.relationship_type() == Some(RelationType::OneToMany) && e.from_field() == Some("id")));
+    }
+
+    #[test]
+    fn test_disabled_and_without_database() {
+        let mut graph = ProjectGraph::with_name("test");
+        graph.add_node(Node::new_entity("User"));
+        assert!(suggest_connections(&graph).is_empty());
+
+        let mut endpoint = endpoint("Users", "/users");
+        endpoint.enabled = false;
+        graph.add_node(endpoint);
+        assert!(suggest_connections(&graph).is_empty());
+    }
+}
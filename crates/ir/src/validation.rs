@@ -5,8 +5,13 @@
 
 use std::collections::{HashMap, HashSet};
 
-use imortal_core::{NodeId, EdgeId, ConnectionType};
+use uuid::Uuid;
 
+use imortal_core::{NodeId, EdgeId, ConnectionType, ConfigValue, DataType};
+
+use crate::custom_type::{project_type_name, CustomType};
+use crate::edge::CachePolicy;
+use crate::field::{Field, FieldConstraint, Unit};
 use crate::graph::ProjectGraph;
 
 /// Result of a validation operation
@@ -25,6 +30,8 @@ pub struct ValidationError {
     pub edge_id: Option<EdgeId>,
     /// Severity of the error
     pub severity: ValidationSeverity,
+    /// Change that resolves the issue, where one can be made without asking
+    pub fix: Option<QuickFix>,
 }
 
 impl ValidationError {
@@ -36,6 +43,7 @@ impl ValidationError {
             node_id: None,
             edge_id: None,
             severity: ValidationSeverity::Error,
+            fix: None,
         }
     }
 
@@ -47,6 +55,7 @@ impl ValidationError {
             node_id: Some(node_id),
             edge_id: None,
             severity: ValidationSeverity::Error,
+            fix: None,
         }
     }
 
@@ -58,6 +67,7 @@ impl ValidationError {
             node_id: None,
             edge_id: Some(edge_id),
             severity: ValidationSeverity::Error,
+            fix: None,
         }
     }
 
@@ -73,6 +83,12 @@ impl ValidationError {
         self
     }
 
+    /// Attach a fix that resolves the issue
+    pub fn with_fix(mut self, fix: QuickFix) -> Self {
+        self.fix = Some(fix);
+        self
+    }
+
     /// Check if this is an error (not warning or info)
     pub fn is_error(&self) -> bool {
         matches!(self.severity, ValidationSeverity::Error)
@@ -104,6 +120,95 @@ impl std::fmt::Display for ValidationError {
 
 impl std::error::Error for ValidationError {}
 
+/// A change to the graph that resolves a validation issue
+#[derive(Debug, Clone, PartialEq)]
+pub enum QuickFix {
+    /// Set a missing config option of a node
+    SetConfig {
+        node_id: NodeId,
+        key: String,
+        value: ConfigValue,
+    },
+    /// Remove an edge, such as one whose node no longer exists
+    RemoveEdge(EdgeId),
+    /// Give an entity a primary key: its `id` field, or a new UUID `id`
+    AddPrimaryKey(NodeId),
+    /// Rename a field whose name another field of its node already has
+    RenameField {
+        node_id: NodeId,
+        field_id: Uuid,
+        name: String,
+    },
+}
+
+impl QuickFix {
+    /// Short description of the change, for a button or a log line
+    pub fn label(&self) -> String {
+        match self {
+            Self::SetConfig { key, value, .. } => format!("Set '{}' to {}", key, serde_json::to_string(value).unwrap_or_default()),
+            Self::RemoveEdge(_) => "Remove the connection".to_string(),
+            Self::AddPrimaryKey(_) => "Add an 'id' primary key".to_string(),
+            Self::RenameField { name, .. } => format!("Rename the field to '{}'", name),
+        }
+    }
+
+    /// Apply the fix, returning whether it changed `graph`
+    ///
+    /// A fix whose target is gone, or was already fixed, changes nothing.
+    pub fn apply(&self, graph: &mut ProjectGraph) -> bool {
+        match self {
+            Self::SetConfig { node_id, key, value } => match graph.get_node_mut(*node_id) {
+                Some(node) if node.config.get(key).is_none_or(ConfigValue::is_null) => {
+                    node.set_config(key.clone(), value.clone());
+                    true
+                }
+                _ => false,
+            },
+            Self::RemoveEdge(id) => graph.remove_edge(*id).is_some(),
+            Self::AddPrimaryKey(node_id) => {
+                let Some(node) = graph.get_node_mut(*node_id) else {
+                    return false;
+                };
+                if node.fields.iter().any(|f| f.is_primary_key()) {
+                    return false;
+                }
+                match node.get_field_mut("id") {
+                    Some(id) => {
+                        id.constraints.push(FieldConstraint::PrimaryKey);
+                        id.required = true;
+                    }
+                    None => node.fields.insert(0, Field::uuid("id").primary_key()),
+                }
+                true
+            }
+            Self::RenameField { node_id, field_id, name } => {
+                let Some(node) = graph.get_node_mut(*node_id) else {
+                    return false;
+                };
+                if node.get_field(name).is_some() {
+                    return false;
+                }
+                match node.fields.iter_mut().find(|f| f.id == *field_id) {
+                    Some(field) => {
+                        field.name = name.clone();
+                        true
+                    }
+                    None => false,
+                }
+            }
+        }
+    }
+}
+
+/// Apply the fixes of `issues` in order, returning how many changed `graph`
+pub fn apply_fixes(graph: &mut ProjectGraph, issues: &[ValidationError]) -> usize {
+    issues
+        .iter()
+        .filter_map(|issue| issue.fix.as_ref())
+        .filter(|fix| fix.apply(graph))
+        .count()
+}
+
 /// Categories of validation errors
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum ValidationErrorKind {
@@ -137,13 +242,27 @@ pub enum ValidationErrorKind {
     InvalidDatabaseConfig,
     InvalidApiConfig,
     InvalidEmbeddedConfig,
+    DuplicateEndpoint,
 
     // Schema errors
     InvalidSchema,
     MissingPrimaryKey,
+    DuplicateField,
     InvalidRelationship,
     CircularReference,
 
+    // Component config errors
+    UnknownConfigKey,
+    MissingConfigOption,
+    InvalidConfigValue,
+
+    // Settings errors
+    DuplicateSetting,
+    UnresolvedSetting,
+
+    // Workspace errors
+    BrokenReference,
+
     // General errors
     Custom,
 }
@@ -173,10 +292,18 @@ impl ValidationErrorKind {
             Self::InvalidDatabaseConfig => "Invalid Database Config",
             Self::InvalidApiConfig => "Invalid API Config",
             Self::InvalidEmbeddedConfig => "Invalid Embedded Config",
+            Self::DuplicateEndpoint => "Duplicate Endpoint",
             Self::InvalidSchema => "Invalid Schema",
             Self::MissingPrimaryKey => "Missing Primary Key",
+            Self::DuplicateField => "Duplicate Field",
             Self::InvalidRelationship => "Invalid Relationship",
             Self::CircularReference => "Circular Reference",
+            Self::UnknownConfigKey => "Unknown Config Key",
+            Self::MissingConfigOption => "Missing Config Option",
+            Self::InvalidConfigValue => "Invalid Config Value",
+            Self::DuplicateSetting => "Duplicate Setting",
+            Self::UnresolvedSetting => "Unresolved Setting",
+            Self::BrokenReference => "Broken Reference",
             Self::Custom => "Custom Error",
         }
     }
@@ -220,6 +347,18 @@ impl Validator {
         validator.add_rule(Box::new(RequiredFieldsRule));
         validator.add_rule(Box::new(EntityPrimaryKeyRule));
         validator.add_rule(Box::new(DuplicateNameRule));
+        validator.add_rule(Box::new(DuplicateFieldRule));
+        validator.add_rule(Box::new(SettingsReferenceRule));
+        validator.add_rule(Box::new(EndpointCollisionRule));
+        validator.add_rule(Box::new(ReferenceTargetRule));
+        validator.add_rule(Box::new(InheritanceRule));
+        validator.add_rule(Box::new(PolymorphicRule));
+        validator.add_rule(Box::new(TenancyRule));
+        validator.add_rule(Box::new(CachingRule));
+        validator.add_rule(Box::new(FeatureFlagRule));
+        validator.add_rule(Box::new(CustomCodeRule));
+        validator.add_rule(Box::new(CustomTypeRule));
+        validator.add_rule(Box::new(FieldUnitRule));
 
         validator
     }
@@ -255,7 +394,7 @@ impl Validator {
         let mut all_errors = Vec::new();
 
         for rule in &self.rules {
-            let errors = rule.validate(graph);
+            let errors = downgrade_disabled(graph, rule.validate(graph));
 
             // Filter by severity
             let filtered: Vec<_> = errors
@@ -286,7 +425,7 @@ impl Validator {
         let mut all_errors = Vec::new();
 
         for rule in &self.rules {
-            let errors = rule.validate(graph);
+            let errors = downgrade_disabled(graph, rule.validate(graph));
             let filtered: Vec<_> = errors
                 .into_iter()
                 .filter(|e| self.should_include(e.severity))
@@ -320,6 +459,25 @@ impl Default for Validator {
     }
 }
 
+/// Make issues about disabled nodes, or edges touching one, informational
+fn downgrade_disabled(graph: &ProjectGraph, errors: Vec<ValidationError>) -> Vec<ValidationError> {
+    let disabled = |id: NodeId| graph.get_node(id).is_some_and(|node| !node.enabled);
+    errors
+        .into_iter()
+        .map(|error| {
+            let edge_disabled = error
+                .edge_id
+                .and_then(|id| graph.get_edge(id))
+                .is_some_and(|edge| disabled(edge.from_node) || disabled(edge.to_node));
+            if edge_disabled || error.node_id.is_some_and(disabled) {
+                error.as_info()
+            } else {
+                error
+            }
+        })
+        .collect()
+}
+
 /// Trait for validation rules
 pub trait ValidationRule: Send + Sync {
     /// The name of this rule
@@ -349,14 +507,14 @@ impl ValidationRule for NodeExistsRule {
                     ValidationErrorKind::DanglingEdge,
                     format!("Edge references non-existent source node: {}", edge.from_node),
                     edge.id,
-                ));
+                ).with_fix(QuickFix::RemoveEdge(edge.id)));
             }
             if !graph.has_node(edge.to_node) {
                 errors.push(ValidationError::for_edge(
                     ValidationErrorKind::DanglingEdge,
                     format!("Edge references non-existent target node: {}", edge.to_node),
                     edge.id,
-                ));
+                ).with_fix(QuickFix::RemoveEdge(edge.id)));
             }
         }
 
@@ -388,8 +546,9 @@ impl ValidationRule for EdgeValidityRule {
         let mut errors = Vec::new();
 
         for edge in graph.edges() {
-            // Check source port exists
-            if let Some(from_node) = graph.get_node(edge.from_node) {
+            // Check source port exists; the fields relationships join on
+            // are checked with port compatibility
+            if let Some(from_node) = graph.get_node(edge.from_node).filter(|_| edge.from_field().is_none()) {
                 if from_node.get_output_port(&edge.from_port).is_none() {
                     errors.push(ValidationError::for_edge(
                         ValidationErrorKind::MissingPort,
@@ -403,7 +562,7 @@ impl ValidationRule for EdgeValidityRule {
             }
 
             // Check target port exists
-            if let Some(to_node) = graph.get_node(edge.to_node) {
+            if let Some(to_node) = graph.get_node(edge.to_node).filter(|_| edge.to_field().is_none()) {
                 if to_node.get_input_port(&edge.to_port).is_none() {
                     errors.push(ValidationError::for_edge(
                         ValidationErrorKind::MissingPort,
@@ -415,6 +574,21 @@ impl ValidationRule for EdgeValidityRule {
                     ));
                 }
             }
+
+            // Details that only one connection type uses; whether that type
+            // has its details is checked by the type's own rule
+            let misplaced = [
+                (edge.association.is_some() && !edge.is_polymorphic(), "an association, which only polymorphic relationships have"),
+                (edge.cache.is_some() && !edge.is_caching(), "a cache policy, which only caching edges have"),
+                (edge.flag.is_some() && !edge.is_gate(), "a feature flag, which only gating edges name"),
+            ];
+            for (_, detail) in misplaced.iter().filter(|(misplaced, _)| *misplaced) {
+                errors.push(ValidationError::for_edge(
+                    ValidationErrorKind::InvalidEdgeType,
+                    format!("Edge has {}", detail),
+                    edge.id,
+                ));
+            }
         }
 
         errors
@@ -433,9 +607,16 @@ impl ValidationRule for PortCompatibilityRule {
         let mut errors = Vec::new();
 
         for edge in graph.edges() {
-            // Skip port compatibility check for relationship edges
-            // Relationships connect different entity types by design
+            // Relationships connect different entity types by design, so
+            // only the fields they join on are checked
             if matches!(edge.connection_type, ConnectionType::Relationship(_)) {
+                if let Some(problem) = graph.relationship_field_problem(edge) {
+                    errors.push(ValidationError::for_edge(ValidationErrorKind::InvalidRelationship, problem, edge.id));
+                }
+                continue;
+            }
+            // Inheritance joins two entities; `InheritanceRule` checks it
+            if edge.is_inheritance() {
                 continue;
             }
 
@@ -508,7 +689,7 @@ impl ValidationRule for EntityPrimaryKeyRule {
                         ValidationErrorKind::MissingPrimaryKey,
                         format!("Entity '{}' has no primary key field", node.name),
                         node.id,
-                    ).as_warning());
+                    ).as_warning().with_fix(QuickFix::AddPrimaryKey(node.id)));
                 }
             }
         }
@@ -532,7 +713,7 @@ impl ValidationRule for DuplicateNameRule {
         for node in graph.nodes() {
             let type_names = names_by_type
                 .entry(&node.component_type)
-                .or_insert_with(HashMap::new);
+                .or_default();
 
             if let Some(&existing_id) = type_names.get(node.name.as_str()) {
                 errors.push(ValidationError::for_node(
@@ -552,6 +733,677 @@ impl ValidationRule for DuplicateNameRule {
     }
 }
 
+/// Validates that no two fields of a node share a name
+pub struct DuplicateFieldRule;
+
+impl ValidationRule for DuplicateFieldRule {
+    fn name(&self) -> &'static str {
+        "Unique Fields"
+    }
+
+    fn validate(&self, graph: &ProjectGraph) -> Vec<ValidationError> {
+        let mut errors = Vec::new();
+
+        for node in graph.nodes() {
+            let taken: HashSet<&str> = node.fields.iter().map(|f| f.name.as_str()).collect();
+            let mut seen = HashSet::new();
+            let mut renamed = Vec::new();
+            for field in &node.fields {
+                if seen.insert(field.name.as_str()) {
+                    continue;
+                }
+                // Suggest the first free numbered name, keeping it free for
+                // later duplicates
+                let name = (2..)
+                    .map(|n| format!("{}_{}", field.name, n))
+                    .find(|name| !taken.contains(name.as_str()) && !renamed.contains(name))
+                    .unwrap_or_default();
+                renamed.push(name.clone());
+                errors.push(
+                    ValidationError::for_node(
+                        ValidationErrorKind::DuplicateField,
+                        format!("'{}' has more than one field named '{}'", node.name, field.name),
+                        node.id,
+                    )
+                    .with_fix(QuickFix::RenameField { node_id: node.id, field_id: field.id, name }),
+                );
+            }
+        }
+
+        errors
+    }
+}
+
+/// Environment variables every generated service provides without a settings node
+const BUILTIN_ENV_VARS: &[&str] = &[
+    "HOST",
+    "PORT",
+    "DATABASE_URL",
+    "JWT_SECRET",
+    "JWT_EXPIRY_HOURS",
+    "ENVIRONMENT",
+    "RUST_LOG",
+];
+
+/// Extract the `${VAR}` references from a configuration string
+fn env_references(value: &str) -> Vec<&str> {
+    let mut refs = Vec::new();
+    let mut rest = value;
+    while let Some(start) = rest.find("${") {
+        rest = &rest[start + 2..];
+        match rest.find('}') {
+            Some(end) => {
+                refs.push(&rest[..end]);
+                rest = &rest[end + 1..];
+            }
+            None => break,
+        }
+    }
+    refs
+}
+
+/// Validates settings declared on `config.settings` nodes and the
+/// `${ENV_VAR}` references other nodes make to them
+pub struct SettingsReferenceRule;
+
+impl ValidationRule for SettingsReferenceRule {
+    fn name(&self) -> &'static str {
+        "Settings References"
+    }
+
+    fn validate(&self, graph: &ProjectGraph) -> Vec<ValidationError> {
+        let mut errors = Vec::new();
+        let settings_nodes = graph.find_nodes_by_type("config.settings");
+
+        // Without settings nodes, references are left to the environment
+        if settings_nodes.is_empty() {
+            return errors;
+        }
+
+        let mut declared: HashSet<String> = HashSet::new();
+        for node in &settings_nodes {
+            for field in &node.fields {
+                let env_var = field.env_var();
+                if !declared.insert(env_var.clone()) {
+                    errors.push(ValidationError::for_node(
+                        ValidationErrorKind::DuplicateSetting,
+                        format!("Setting '{}' is declared more than once", env_var),
+                        node.id,
+                    ));
+                }
+            }
+        }
+
+        for node in graph.nodes() {
+            for value in node.config.values() {
+                let ConfigValue::String(value) = value else {
+                    continue;
+                };
+                for reference in env_references(value) {
+                    if !declared.contains(reference) && !BUILTIN_ENV_VARS.contains(&reference) {
+                        errors.push(ValidationError::for_node(
+                            ValidationErrorKind::UnresolvedSetting,
+                            format!(
+                                "Node '{}' references '${{{}}}', which no settings node declares",
+                                node.name, reference
+                            ),
+                            node.id,
+                        ).as_warning());
+                    }
+                }
+            }
+        }
+
+        errors
+    }
+}
+
+/// Normalize path parameters (`:id`, `{id}`) so equivalent routes compare equal
+pub(crate) fn normalize_route(route: &str) -> String {
+    route.split('/')
+        .map(|segment| {
+            if segment.starts_with(':') || (segment.starts_with('{') && segment.ends_with('}')) {
+                "{}"
+            } else {
+                segment
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Validates that no two REST endpoints share a method, path, and API version
+pub struct EndpointCollisionRule;
+
+impl ValidationRule for EndpointCollisionRule {
+    fn name(&self) -> &'static str {
+        "Endpoint Collisions"
+    }
+
+    fn validate(&self, graph: &ProjectGraph) -> Vec<ValidationError> {
+        let mut errors = Vec::new();
+        let versioning = &graph.meta.api_versioning;
+        let mut routes: HashMap<(String, String), &str> = HashMap::new();
+
+        let mut endpoints = graph.find_nodes_by_type("api.rest");
+        endpoints.sort_by(|a, b| a.name.cmp(&b.name));
+
+        for node in endpoints {
+            let method = node.get_config_str("method").unwrap_or("GET").to_uppercase();
+            let route = versioning.route(node.endpoint_path().unwrap_or("/"), node.endpoint_version());
+
+            match routes.entry((method.clone(), normalize_route(&route))) {
+                std::collections::hash_map::Entry::Occupied(existing) => {
+                    errors.push(ValidationError::for_node(
+                        ValidationErrorKind::DuplicateEndpoint,
+                        format!(
+                            "Endpoint '{}' ({} {}) collides with endpoint '{}'",
+                            node.name, method, route, existing.get()
+                        ),
+                        node.id,
+                    ).as_warning());
+                }
+                std::collections::hash_map::Entry::Vacant(entry) => {
+                    entry.insert(&node.name);
+                }
+            }
+        }
+
+        errors
+    }
+}
+
+/// Validates that reference nodes name a project and an entity
+///
+/// Whether the target exists can only be checked with the whole workspace
+/// loaded; see [`crate::Workspace::validate_references`].
+pub struct ReferenceTargetRule;
+
+impl ValidationRule for ReferenceTargetRule {
+    fn name(&self) -> &'static str {
+        "Reference Targets"
+    }
+
+    fn validate(&self, graph: &ProjectGraph) -> Vec<ValidationError> {
+        graph
+            .find_nodes_by_type("data.reference")
+            .into_iter()
+            .filter_map(|node| {
+                let (project, entity) = node.reference_target()?;
+                let missing = match (project.is_empty(), entity.is_empty()) {
+                    (true, true) => "a project and an entity",
+                    (true, false) => "a project",
+                    (false, true) => "an entity",
+                    (false, false) => return None,
+                };
+                Some(ValidationError::for_node(
+                    ValidationErrorKind::BrokenReference,
+                    format!("Reference '{}' does not name {}", node.name, missing),
+                    node.id,
+                ))
+            })
+            .collect()
+    }
+}
+
+/// Validates entity inheritance: entities extend at most one other entity,
+/// without cycles, and include only mixins that exist
+pub struct InheritanceRule;
+
+impl ValidationRule for InheritanceRule {
+    fn name(&self) -> &'static str {
+        "Inheritance"
+    }
+
+    fn validate(&self, graph: &ProjectGraph) -> Vec<ValidationError> {
+        let mut errors = Vec::new();
+        let is_entity = |id| graph.get_node(id).is_some_and(|n| n.component_type == "data.entity");
+
+        for edge in graph.edges().filter(|e| e.is_inheritance()) {
+            if !is_entity(edge.from_node) || !is_entity(edge.to_node) {
+                errors.push(ValidationError::for_edge(
+                    ValidationErrorKind::InvalidRelationship,
+                    "Only entities can extend entities",
+                    edge.id,
+                ));
+            }
+        }
+
+        for node in graph.find_nodes_by_type("data.entity") {
+            if graph.edges().filter(|e| e.is_inheritance() && e.from_node == node.id).count() > 1 {
+                errors.push(ValidationError::for_node(
+                    ValidationErrorKind::InvalidRelationship,
+                    format!("Entity '{}' extends more than one entity", node.name),
+                    node.id,
+                ));
+            }
+            let last = graph.ancestors(node.id).last().copied().unwrap_or(node.id);
+            if graph.parent_entity(last).is_some_and(|parent| parent == node.id) {
+                errors.push(ValidationError::for_node(
+                    ValidationErrorKind::CircularReference,
+                    format!("Entity '{}' extends itself through inheritance", node.name),
+                    node.id,
+                ));
+            }
+            for mixin in node.mixins.iter().filter(|name| graph.mixin(name).is_none()) {
+                errors.push(ValidationError::for_node(
+                    ValidationErrorKind::InvalidSchema,
+                    format!("Entity '{}' includes unknown mixin '{}'", node.name, mixin),
+                    node.id,
+                ));
+            }
+        }
+
+        errors
+    }
+}
+
+/// Validates polymorphic relationships: they join entities, and the
+/// targets of one association share an id type the id column can hold
+pub struct PolymorphicRule;
+
+impl ValidationRule for PolymorphicRule {
+    fn name(&self) -> &'static str {
+        "Polymorphic Relationship"
+    }
+
+    fn validate(&self, graph: &ProjectGraph) -> Vec<ValidationError> {
+        let mut errors = Vec::new();
+        let is_entity = |id| graph.get_node(id).is_some_and(|n| n.component_type == "data.entity");
+
+        for edge in graph.edges().filter(|e| e.is_polymorphic()) {
+            if !is_entity(edge.from_node) || !is_entity(edge.to_node) {
+                let target = graph.get_node(edge.to_node).map_or("?", |n| n.name.as_str());
+                errors.push(ValidationError::for_edge(
+                    ValidationErrorKind::InvalidRelationship,
+                    format!("Polymorphic target '{}' is not an entity", target),
+                    edge.id,
+                ));
+            }
+        }
+
+        for node in graph.find_nodes_by_type("data.entity") {
+            for association in graph.polymorphic_associations(node.id) {
+                let id_types: Vec<(&str, Option<&DataType>)> = association
+                    .targets
+                    .iter()
+                    .filter_map(|id| graph.get_node(*id))
+                    .filter(|target| target.component_type == "data.entity")
+                    .map(|target| (target.name.as_str(), target.get_field("id").map(|f| &f.data_type)))
+                    .collect();
+                if let Some((first, first_type)) = id_types.first() {
+                    if let Some((other, _)) = id_types.iter().find(|(_, data_type)| data_type != first_type) {
+                        errors.push(ValidationError::for_node(
+                            ValidationErrorKind::InvalidRelationship,
+                            format!(
+                                "'{}' of entity '{}' targets {} and {}, whose ids have different types",
+                                association.name, node.name, first, other
+                            ),
+                            node.id,
+                        ));
+                    }
+                }
+                if association.targets.len() == 1 {
+                    errors.push(
+                        ValidationError::for_node(
+                            ValidationErrorKind::InvalidRelationship,
+                            format!(
+                                "'{}' of entity '{}' has a single target; a plain relationship would do",
+                                association.name, node.name
+                            ),
+                            node.id,
+                        )
+                        .as_warning(),
+                    );
+                }
+            }
+        }
+
+        errors
+    }
+}
+
+/// Validates that a multi-tenant project has authentication, since the
+/// tenant a request names is only trusted for authenticated users
+pub struct TenancyRule;
+
+impl ValidationRule for TenancyRule {
+    fn name(&self) -> &'static str {
+        "Multi-Tenancy"
+    }
+
+    fn validate(&self, graph: &ProjectGraph) -> Vec<ValidationError> {
+        let tenancy = graph.meta.codegen.tenancy.as_deref().map(str::trim).unwrap_or("");
+        let multi_tenant = !tenancy.is_empty() && !tenancy.eq_ignore_ascii_case("none");
+        if !multi_tenant || graph.nodes().any(|n| n.component_type.starts_with("auth.")) {
+            return Vec::new();
+        }
+        vec![ValidationError::new(
+            ValidationErrorKind::InvalidConfigValue,
+            "Multi-tenancy needs an authentication component to identify the users of each tenant",
+        )]
+    }
+}
+
+/// Longest TTL a caching edge has before it's flagged; responses kept for
+/// more than a day rarely reflect the data behind them
+const MAX_CACHE_TTL_SECONDS: u64 = 24 * 60 * 60;
+
+/// Validates caching edges: they join a REST endpoint to a cache, with a
+/// TTL and a key template that renders
+pub struct CachingRule;
+
+impl ValidationRule for CachingRule {
+    fn name(&self) -> &'static str {
+        "Caching"
+    }
+
+    fn validate(&self, graph: &ProjectGraph) -> Vec<ValidationError> {
+        let mut errors = Vec::new();
+        let is_type = |id, component_type: &str| graph.get_node(id).is_some_and(|n| n.component_type == component_type);
+
+        for edge in graph.edges().filter(|e| e.is_caching()) {
+            let endpoint = graph.get_node(edge.from_node).map_or("?", |n| n.name.as_str());
+            if !is_type(edge.from_node, "api.rest") || !is_type(edge.to_node, "storage.cache") {
+                errors.push(ValidationError::for_edge(
+                    ValidationErrorKind::InvalidConnection,
+                    "Only REST endpoints can cache their responses, and only in a cache",
+                    edge.id,
+                ));
+            }
+            if graph.edges().filter(|e| e.is_caching() && e.from_node == edge.from_node).count() > 1 {
+                errors.push(ValidationError::for_edge(
+                    ValidationErrorKind::InvalidConnection,
+                    format!("Endpoint '{}' caches its responses in more than one cache", endpoint),
+                    edge.id,
+                ));
+            }
+
+            let Some(policy) = &edge.cache else {
+                errors.push(ValidationError::for_edge(
+                    ValidationErrorKind::MissingConfigOption,
+                    format!("Caching edge of '{}' has no TTL or key template", endpoint),
+                    edge.id,
+                ));
+                continue;
+            };
+            if policy.ttl_seconds == 0 {
+                errors.push(ValidationError::for_edge(
+                    ValidationErrorKind::InvalidConfigValue,
+                    format!("Endpoint '{}' caches its responses with a TTL of 0 seconds", endpoint),
+                    edge.id,
+                ));
+            } else if policy.ttl_seconds > MAX_CACHE_TTL_SECONDS {
+                errors.push(
+                    ValidationError::for_edge(
+                        ValidationErrorKind::InvalidConfigValue,
+                        format!("Endpoint '{}' caches its responses for more than a day", endpoint),
+                        edge.id,
+                    )
+                    .as_warning(),
+                );
+            }
+            match policy.placeholders() {
+                Ok(placeholders) => {
+                    for unknown in placeholders.iter().filter(|p| !CachePolicy::PLACEHOLDERS.contains(p)) {
+                        errors.push(ValidationError::for_edge(
+                            ValidationErrorKind::InvalidConfigValue,
+                            format!(
+                                "Cache key of '{}' uses unknown placeholder '{{{}}}'; use {{method}}, {{path}}, or {{query}}",
+                                endpoint, unknown
+                            ),
+                            edge.id,
+                        ));
+                    }
+                    // Without the path every URL of the endpoint shares one key
+                    if !placeholders.contains(&"path") {
+                        errors.push(
+                            ValidationError::for_edge(
+                                ValidationErrorKind::InvalidConfigValue,
+                                format!("Cache key of '{}' doesn't use {{path}}, so its URLs share cached responses", endpoint),
+                                edge.id,
+                            )
+                            .as_warning(),
+                        );
+                    }
+                }
+                Err(problem) => errors.push(ValidationError::for_edge(
+                    ValidationErrorKind::InvalidConfigValue,
+                    format!("Cache key of '{}' is malformed: {}", endpoint, problem),
+                    edge.id,
+                )),
+            }
+        }
+
+        errors
+    }
+}
+
+/// Validates feature flags: their names are unique snake_case identifiers,
+/// and the flags gating edges and `feature_flag` configs name are defined
+pub struct FeatureFlagRule;
+
+impl ValidationRule for FeatureFlagRule {
+    fn name(&self) -> &'static str {
+        "Feature Flags"
+    }
+
+    fn validate(&self, graph: &ProjectGraph) -> Vec<ValidationError> {
+        let mut errors = Vec::new();
+        let flag_nodes = graph.find_nodes_by_type("logic.feature_flag");
+        let mut defined = HashSet::new();
+        for node in &flag_nodes {
+            let flags = node.feature_flags();
+            if flags.is_empty() {
+                errors.push(
+                    ValidationError::for_node(
+                        ValidationErrorKind::MissingConfigOption,
+                        format!("Feature flag node '{}' defines no flags", node.name),
+                        node.id,
+                    )
+                    .as_warning(),
+                );
+            }
+            for flag in flags {
+                if !flag.has_valid_name() {
+                    errors.push(ValidationError::for_node(
+                        ValidationErrorKind::InvalidConfigValue,
+                        format!("Feature flag '{}' isn't a snake_case identifier", flag.name),
+                        node.id,
+                    ));
+                } else if !defined.insert(flag.name.clone()) {
+                    errors.push(ValidationError::for_node(
+                        ValidationErrorKind::InvalidConfigValue,
+                        format!("Feature flag '{}' is defined more than once", flag.name),
+                        node.id,
+                    ));
+                }
+            }
+        }
+
+        for edge in graph.edges().filter(|e| e.is_gate()) {
+            let target = graph.get_node(edge.to_node).map_or("?", |n| n.name.as_str());
+            let Some(flags) = graph.get_node(edge.from_node).filter(|n| n.component_type == "logic.feature_flag") else {
+                errors.push(ValidationError::for_edge(
+                    ValidationErrorKind::InvalidConnection,
+                    format!("'{}' is gated by a node that isn't a feature flag", target),
+                    edge.id,
+                ));
+                continue;
+            };
+            if !graph.get_node(edge.to_node).is_some_and(|n| n.component_type.starts_with("api.")) {
+                errors.push(ValidationError::for_edge(
+                    ValidationErrorKind::InvalidConnection,
+                    format!("Only API nodes can sit behind a feature flag, not '{}'", target),
+                    edge.id,
+                ));
+            }
+            if graph.edges().filter(|e| e.is_gate() && e.to_node == edge.to_node).count() > 1 {
+                errors.push(ValidationError::for_edge(
+                    ValidationErrorKind::InvalidConnection,
+                    format!("'{}' sits behind more than one feature flag", target),
+                    edge.id,
+                ));
+            }
+            match edge.flag.as_deref() {
+                None => errors.push(ValidationError::for_edge(
+                    ValidationErrorKind::MissingConfigOption,
+                    format!("Gating edge of '{}' names no flag", target),
+                    edge.id,
+                )),
+                Some(flag) if !flags.feature_flags().iter().any(|f| f.name == flag) => {
+                    errors.push(ValidationError::for_edge(
+                        ValidationErrorKind::InvalidConfigValue,
+                        format!("'{}' sits behind flag '{}', which '{}' doesn't define", target, flag, flags.name),
+                        edge.id,
+                    ));
+                }
+                Some(_) => {}
+            }
+        }
+
+        for node in graph.nodes() {
+            let Some(flag) = node.get_config_str("feature_flag").map(str::trim).filter(|f| !f.is_empty()) else {
+                continue;
+            };
+            if !flag_nodes.iter().any(|n| n.feature_flags().iter().any(|f| f.name == flag)) {
+                errors.push(ValidationError::for_node(
+                    ValidationErrorKind::InvalidConfigValue,
+                    format!("'{}' references feature flag '{}', which no feature flag node defines", node.name, flag),
+                    node.id,
+                ));
+            }
+        }
+
+        errors
+    }
+}
+
+/// Validates custom code nodes: the snippet parses as a function body and
+/// the data ports, which become its parameters, are named like identifiers
+pub struct CustomCodeRule;
+
+impl ValidationRule for CustomCodeRule {
+    fn name(&self) -> &'static str {
+        "Custom Code"
+    }
+
+    fn validate(&self, graph: &ProjectGraph) -> Vec<ValidationError> {
+        let mut errors = Vec::new();
+        for node in graph.nodes() {
+            let Some(code) = node.custom_code() else {
+                continue;
+            };
+            if code.trim().is_empty() {
+                errors.push(
+                    ValidationError::for_node(
+                        ValidationErrorKind::MissingConfigOption,
+                        format!("Custom code node '{}' has no code", node.name),
+                        node.id,
+                    )
+                    .as_warning(),
+                );
+            } else if let Err(e) = crate::node::parse_rust_snippet(code) {
+                errors.push(ValidationError::for_node(
+                    ValidationErrorKind::InvalidConfigValue,
+                    format!("Code of '{}' doesn't parse at {}", node.name, e),
+                    node.id,
+                ));
+            }
+            for port in node.ports.inputs.iter().filter(|p| p.is_data()) {
+                if syn::parse_str::<syn::Ident>(&port.id).is_err() {
+                    errors.push(ValidationError::for_node(
+                        ValidationErrorKind::InvalidConfigValue,
+                        format!("Input '{}' of '{}' can't be a Rust parameter name; rename it", port.id, node.name),
+                        node.id,
+                    ));
+                }
+            }
+        }
+        errors
+    }
+}
+
+/// Validates project-defined types: unique PascalCase names that no entity
+/// has, a built-in underlying type, and fields only using defined types
+pub struct CustomTypeRule;
+
+impl ValidationRule for CustomTypeRule {
+    fn name(&self) -> &'static str {
+        "Custom Types"
+    }
+
+    fn validate(&self, graph: &ProjectGraph) -> Vec<ValidationError> {
+        let mut errors = Vec::new();
+        let mut defined = HashSet::new();
+        for custom in &graph.meta.types {
+            let problem = if !custom.has_valid_name() {
+                Some(format!("Type '{}' isn't a PascalCase identifier", custom.name))
+            } else if !defined.insert(custom.name.as_str()) {
+                Some(format!("Type '{}' is defined more than once", custom.name))
+            } else if !graph.find_nodes_by_type("data.entity").iter().all(|n| n.name != custom.name) {
+                Some(format!("Type '{}' has the name of an entity", custom.name))
+            } else if !CustomType::is_valid_underlying(&custom.underlying) {
+                Some(format!("Type '{}' must be stored as a built-in type", custom.name))
+            } else if custom.serde_with.as_deref().is_some_and(|path| syn::parse_str::<syn::Path>(path).is_err()) {
+                Some(format!("Serde module of type '{}' isn't a Rust path", custom.name))
+            } else {
+                None
+            };
+            if let Some(message) = problem {
+                errors.push(ValidationError::new(ValidationErrorKind::InvalidSchema, message));
+            }
+        }
+
+        for node in graph.nodes() {
+            for field in &node.fields {
+                if let Some(name) = project_type_name(&field.data_type).filter(|name| graph.custom_type(name).is_none()) {
+                    errors.push(ValidationError::for_node(
+                        ValidationErrorKind::InvalidFieldType,
+                        format!("Field '{}.{}' uses type '{}', which the project doesn't define", node.name, field.name, name),
+                        node.id,
+                    ));
+                }
+            }
+        }
+        errors
+    }
+}
+
+/// Validates field units: only numeric fields, or project types stored as
+/// numbers, carry one, and currencies are ISO 4217 codes
+pub struct FieldUnitRule;
+
+impl ValidationRule for FieldUnitRule {
+    fn name(&self) -> &'static str {
+        "Field Units"
+    }
+
+    fn validate(&self, graph: &ProjectGraph) -> Vec<ValidationError> {
+        let mut errors = Vec::new();
+        for node in graph.nodes() {
+            for field in &node.fields {
+                let Some(unit) = &field.unit else { continue };
+                let storage = project_type_name(&field.data_type)
+                    .and_then(|name| graph.custom_type(name))
+                    .map_or(&field.data_type, |custom| &custom.underlying);
+                let problem = if !Unit::applies_to(storage) {
+                    Some(format!("Field '{}.{}' has a unit but isn't numeric", node.name, field.name))
+                } else if let Unit::Currency(code) = unit {
+                    (!Unit::is_valid_currency(code))
+                        .then(|| format!("Currency of '{}.{}' isn't a three-letter code like USD", node.name, field.name))
+                } else {
+                    None
+                };
+                if let Some(message) = problem {
+                    errors.push(ValidationError::for_node(ValidationErrorKind::InvalidFieldType, message, node.id));
+                }
+            }
+        }
+        errors
+    }
+}
+
 /// Validates that there are no cycles in data flow
 pub struct CyclicDependencyRule;
 
@@ -571,7 +1423,7 @@ impl ValidationRule for CyclicDependencyRule {
         for edge in graph.edges() {
             if edge.is_data_flow() {
                 adj.entry(edge.from_node)
-                    .or_insert_with(Vec::new)
+                    .or_default()
                     .push(edge.to_node);
             }
         }
@@ -581,14 +1433,14 @@ impl ValidationRule for CyclicDependencyRule {
         let mut rec_stack = HashSet::new();
 
         for node_id in graph.node_ids() {
-            if !visited.contains(node_id) {
-                if has_cycle(*node_id, &adj, &mut visited, &mut rec_stack) {
-                    errors.push(ValidationError::new(
-                        ValidationErrorKind::CyclicDependency,
-                        "Cyclic dependency detected in data flow",
-                    ));
-                    break; // One cycle error is enough
-                }
+            if !visited.contains(node_id)
+                && has_cycle(*node_id, &adj, &mut visited, &mut rec_stack)
+            {
+                errors.push(ValidationError::new(
+                    ValidationErrorKind::CyclicDependency,
+                    "Cyclic dependency detected in data flow",
+                ));
+                break; // One cycle error is enough
             }
         }
 
@@ -641,10 +1493,12 @@ pub fn is_valid(graph: &ProjectGraph) -> bool {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::edge::Edge;
+    use crate::field::Field;
     use crate::node::Node;
 
     use crate::project::ProjectMeta;
-    use imortal_core::RelationType;
+    use imortal_core::{PortDirection, RelationType};
 
     fn create_test_graph() -> ProjectGraph {
         let mut graph = ProjectGraph::new(ProjectMeta::new("test"));
@@ -681,6 +1535,30 @@ mod tests {
         assert!(errors.iter().any(|e| e.kind == ValidationErrorKind::DuplicateNodeName));
     }
 
+    #[test]
+    fn test_disabled_node_issues_are_info() {
+        let mut graph = ProjectGraph::with_name("test");
+        let user = graph.add_node(Node::new_entity("User"));
+        let mut draft = Node::new_entity("Draft");
+        draft.fields.clear();
+        draft.enabled = false;
+        let draft = graph.add_node(draft);
+        let edge = graph.add_edge(Edge::dependency(user, draft)).unwrap();
+
+        // The missing primary key warning drops below the default severity
+        assert!(Validator::new().validate_all(&graph).iter().all(|e| e.node_id != Some(draft)));
+        let issues = get_all_issues(&graph);
+        assert!(issues.iter().any(|e| e.node_id == Some(draft) && e.severity == ValidationSeverity::Info));
+
+        let errors = vec![
+            ValidationError::for_node(ValidationErrorKind::Custom, "user", user),
+            ValidationError::for_node(ValidationErrorKind::Custom, "draft", draft),
+            ValidationError::for_edge(ValidationErrorKind::Custom, "edge", edge),
+        ];
+        let severities: Vec<_> = downgrade_disabled(&graph, errors).iter().map(|e| e.severity).collect();
+        assert_eq!(severities, [ValidationSeverity::Error, ValidationSeverity::Info, ValidationSeverity::Info]);
+    }
+
     #[test]
     fn test_validator_builder() {
         let validator = Validator::new()
@@ -712,4 +1590,344 @@ mod tests {
         assert!(warning.is_warning());
         assert!(!warning.is_error());
     }
+
+    #[test]
+    fn test_settings_references() {
+        use crate::field::Field;
+
+        let mut graph = ProjectGraph::new(ProjectMeta::new("test"));
+        graph.add_node(
+            Node::new_settings("Mail")
+                .with_field(Field::string("smtp_host"))
+                .with_field(Field::string("host").with_env_var("SMTP_HOST")),
+        );
+        let mut mailer = Node::new_entity("Mailer");
+        mailer.set_config("host", "${SMTP_HOST}");
+        mailer.set_config("url", "${DATABASE_URL}");
+        mailer.set_config("key", "${MAILER_KEY}");
+        graph.add_node(mailer);
+
+        let errors = SettingsReferenceRule.validate(&graph);
+        assert_eq!(errors.len(), 2);
+        assert!(errors.iter().any(|e| e.kind == ValidationErrorKind::DuplicateSetting));
+        assert!(errors.iter().any(|e| {
+            e.kind == ValidationErrorKind::UnresolvedSetting && e.message.contains("MAILER_KEY")
+        }));
+    }
+
+    #[test]
+    fn test_env_references() {
+        assert_eq!(env_references("${A}:${B}/x"), vec!["A", "B"]);
+        assert!(env_references("plain ${unterminated").is_empty());
+    }
+
+    #[test]
+    fn test_endpoint_collisions() {
+        use crate::project::ApiVersioning;
+
+        let mut graph = ProjectGraph::new(
+            ProjectMeta::new("test").with_api_versioning(ApiVersioning::new("v1")),
+        );
+
+        let mut get_user = Node::new_rest_endpoint("get_user");
+        get_user.set_config("path", "/users/:id");
+        graph.add_node(get_user);
+
+        // Same route with a differently named parameter
+        let mut get_user_again = Node::new_rest_endpoint("get_user_again");
+        get_user_again.set_config("path", "/users/{user_id}");
+        graph.add_node(get_user_again);
+
+        // Same path under another version
+        let mut get_user_v2 = Node::new_rest_endpoint("get_user_v2");
+        get_user_v2.set_config("path", "/users/:id");
+        get_user_v2.set_config("version", "v2");
+        graph.add_node(get_user_v2);
+
+        // Same path with another method
+        let mut delete_user = Node::new_rest_endpoint("delete_user");
+        delete_user.set_config("path", "/users/:id");
+        delete_user.set_config("method", "DELETE");
+        graph.add_node(delete_user);
+
+        let errors = EndpointCollisionRule.validate(&graph);
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].is_warning());
+        assert!(errors[0].message.contains("GET /api/v1/users/{user_id}"));
+    }
+
+    #[test]
+    fn test_reference_targets() {
+        let mut graph = ProjectGraph::with_name("orders");
+        graph.add_node(Node::new_reference("accounts", "User"));
+        let incomplete = graph.add_node(Node::new_reference("accounts", ""));
+
+        let errors = ReferenceTargetRule.validate(&graph);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].node_id, Some(incomplete));
+        assert!(errors[0].message.contains("an entity"));
+    }
+
+    #[test]
+    fn test_inheritance() {
+        let mut graph = ProjectGraph::with_name("blog");
+        let content = graph.add_node(Node::new_entity("Content"));
+        let mut post = Node::new_entity("Post");
+        post.mixins = vec!["Timestamps".to_string(), "Versioned".to_string()];
+        let post = graph.add_node(post);
+        graph.set_parent_entity(post, Some(content)).unwrap();
+
+        let errors = InheritanceRule.validate(&graph);
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].message.contains("unknown mixin 'Versioned'"));
+        assert!(PortCompatibilityRule.validate(&graph).is_empty());
+        assert!(EdgeValidityRule.validate(&graph).is_empty());
+
+        // A cycle, as a hand-edited project file might have
+        graph.get_node_mut(post).unwrap().mixins.pop();
+        graph.add_edge(Edge::inheritance(content, post)).unwrap();
+        let errors = InheritanceRule.validate(&graph);
+        assert_eq!(errors.len(), 2);
+        assert!(errors.iter().all(|e| e.kind == ValidationErrorKind::CircularReference));
+    }
+
+    #[test]
+    fn test_polymorphic() {
+        let mut graph = ProjectGraph::with_name("blog");
+        let comment = graph.add_node(Node::new_entity("Comment"));
+        let post = graph.add_node(Node::new_entity("Post"));
+        let mut photo = Node::new_entity("Photo");
+        photo.fields[0].data_type = DataType::Int64;
+        let photo = graph.add_node(photo);
+        graph.set_polymorphic_targets(comment, "commentable", &[post, photo]).unwrap();
+
+        let errors = PolymorphicRule.validate(&graph);
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].message.contains("different types"));
+        assert!(PortCompatibilityRule.validate(&graph).is_empty());
+
+        graph.set_polymorphic_targets(comment, "commentable", &[post]).unwrap();
+        let errors = PolymorphicRule.validate(&graph);
+        assert!(errors.len() == 1 && !errors[0].is_error());
+
+        // Edited after the fact, e.g. in the project file
+        let endpoint = graph.add_node(Node::new_rest_endpoint("comments"));
+        graph.add_edge(Edge::polymorphic(comment, "commentable", endpoint)).unwrap();
+        let errors = PolymorphicRule.validate(&graph);
+        assert!(errors.iter().any(|e| e.message.contains("'comments' is not an entity")));
+    }
+
+    #[test]
+    fn test_tenancy_needs_auth() {
+        let mut graph = ProjectGraph::with_name("shop");
+        graph.add_node(Node::new_entity("Order"));
+        assert!(TenancyRule.validate(&graph).is_empty());
+
+        graph.meta.codegen.tenancy = Some("column".to_string());
+        let errors = TenancyRule.validate(&graph);
+        assert!(errors.len() == 1 && errors[0].is_error());
+
+        graph.add_node(Node::new("auth.login", "Login"));
+        assert!(TenancyRule.validate(&graph).is_empty());
+
+        graph.meta.codegen.tenancy = Some("none".to_string());
+        assert!(TenancyRule.validate(&graph).is_empty());
+    }
+
+    #[test]
+    fn test_caching_edges() {
+        let mut graph = ProjectGraph::with_name("shop");
+        let endpoint = graph.add_node(Node::new_rest_endpoint("products"));
+        let cache = graph.add_node(Node::new_cache("Product Cache"));
+        let edge = graph.set_cache(endpoint, Some((cache, CachePolicy::default()))).unwrap().unwrap();
+        assert!(CachingRule.validate(&graph).is_empty());
+        if let Err(errors) = Validator::new().validate(&graph) {
+            assert!(errors.iter().all(|e| !e.is_error()), "{:?}", errors);
+        }
+
+        let mut check = |policy: CachePolicy| {
+            graph.get_edge_mut(edge).unwrap().cache = Some(policy);
+            let errors = CachingRule.validate(&graph);
+            (errors.iter().filter(|e| e.is_error()).count(), errors.len())
+        };
+        assert_eq!(check(CachePolicy::new(0, "{path}")), (1, 1));
+        assert_eq!(check(CachePolicy::new(7 * 24 * 3600, "{path}")), (0, 1));
+        assert_eq!(check(CachePolicy::new(60, "{path}:{user}")), (1, 1));
+        assert_eq!(check(CachePolicy::new(60, "{path")), (1, 1));
+        assert_eq!(check(CachePolicy::new(60, "{method}")), (0, 1));
+    }
+
+    #[test]
+    fn test_edge_details_match_connection_type() {
+        let mut graph = ProjectGraph::with_name("shop");
+        let endpoint = graph.add_node(Node::new_rest_endpoint("products"));
+        let cache = graph.add_node(Node::new_cache("Product Cache"));
+        let edge = graph.add_edge(Edge::caches(endpoint, cache, CachePolicy::default())).unwrap();
+        let misplaced = |graph: &ProjectGraph| {
+            EdgeValidityRule
+                .validate(graph)
+                .into_iter()
+                .filter(|e| e.kind == ValidationErrorKind::InvalidEdgeType)
+                .map(|e| e.message)
+                .collect::<Vec<_>>()
+        };
+        assert!(misplaced(&graph).is_empty());
+
+        // Edited after the fact, e.g. in the project file
+        let details = graph.get_edge_mut(edge).unwrap();
+        details.association = Some("cacheable".to_string());
+        details.flag = Some("new_checkout".to_string());
+        let messages = misplaced(&graph);
+        assert_eq!(messages.len(), 2);
+        assert!(messages[0].contains("an association") && messages[1].contains("a feature flag"));
+
+        let details = graph.get_edge_mut(edge).unwrap();
+        details.connection_type = ConnectionType::DataFlow;
+        details.association = None;
+        details.flag = None;
+        let messages = misplaced(&graph);
+        assert!(messages.len() == 1 && messages[0].contains("a cache policy"));
+    }
+
+    #[test]
+    fn test_feature_flags() {
+        let mut graph = ProjectGraph::with_name("shop");
+        let mut flags = Node::new_feature_flag("Flags");
+        flags.set_config("flags", "new_checkout=on");
+        let flags = graph.add_node(flags);
+        let endpoint = graph.add_node(Node::new_rest_endpoint("checkout"));
+        let edge = graph.set_flag_gate(endpoint, Some((flags, "new_checkout".to_string()))).unwrap().unwrap();
+        assert!(FeatureFlagRule.validate(&graph).is_empty());
+
+        graph.get_edge_mut(edge).unwrap().flag = Some("old_checkout".to_string());
+        assert_eq!(FeatureFlagRule.validate(&graph).len(), 1);
+        graph.get_edge_mut(edge).unwrap().flag = Some("new_checkout".to_string());
+
+        // Duplicate and malformed flags, and a config naming an unknown flag
+        let mut more = Node::new_feature_flag("More Flags");
+        more.set_config("flags", "new_checkout, Beta");
+        graph.add_node(more);
+        graph.get_node_mut(endpoint).unwrap().set_config("feature_flag", "dark_mode");
+        let errors = FeatureFlagRule.validate(&graph);
+        assert_eq!(errors.len(), 3);
+        assert!(errors.iter().any(|e| e.message.contains("more than once")));
+        assert!(errors.iter().any(|e| e.message.contains("'dark_mode'")));
+    }
+
+    #[test]
+    fn test_custom_code() {
+        let mut graph = ProjectGraph::with_name("shop");
+        let id = graph.add_node(Node::new_custom_code("Discount"));
+        assert!(CustomCodeRule.validate(&graph).is_empty());
+
+        let node = graph.get_node_mut(id).unwrap();
+        node.set_config("code", "let price = input;\nprice *");
+        node.add_custom_port(PortDirection::Input, "type", DataType::Float64).unwrap();
+        let errors = CustomCodeRule.validate(&graph);
+        assert_eq!(errors.len(), 2);
+        assert!(errors.iter().all(|e| e.severity == ValidationSeverity::Error));
+        assert!(errors[0].message.contains("at line 2"), "{}", errors[0].message);
+        assert!(errors[1].message.contains("'type'"));
+
+        graph.get_node_mut(id).unwrap().set_config("code", " ");
+        assert_eq!(CustomCodeRule.validate(&graph)[0].severity, ValidationSeverity::Warning);
+    }
+
+    #[test]
+    fn test_custom_types() {
+        let mut graph = ProjectGraph::with_name("shop");
+        let email = CustomType::new("Email", DataType::String);
+        let mut user = Node::new_entity("User");
+        user.add_field(Field::new("email", email.data_type()));
+        let user = graph.add_node(user);
+        graph.meta.types.push(email);
+        assert!(CustomTypeRule.validate(&graph).is_empty());
+
+        graph.meta.types.push(CustomType::new("User", DataType::Uuid));
+        graph.meta.types.push(CustomType::new("Tags", DataType::Entity("User".to_string())));
+        graph.meta.types.push(CustomType::new("Email", DataType::Text));
+        graph.meta.types.push(CustomType::new("Money", DataType::Int64).with_serde_with("not a path"));
+        graph.get_node_mut(user).unwrap().add_field(Field::new("price", CustomType::new("Price", DataType::Int64).data_type()));
+        let errors = CustomTypeRule.validate(&graph);
+        assert_eq!(errors.len(), 5, "{:?}", errors);
+        assert!(errors.iter().any(|e| e.message.contains("name of an entity")));
+        assert!(errors.iter().any(|e| e.message.contains("more than once")));
+        assert_eq!(errors.last().unwrap().node_id, Some(user));
+    }
+
+    #[test]
+    fn test_field_units() {
+        let mut graph = ProjectGraph::with_name("shop");
+        let mut product = Node::new_entity("Product");
+        product.add_field(Field::long("price").with_unit(Unit::Currency("USD".to_string())));
+        product.add_field(Field::new("weight", CustomType::new("Grams", DataType::Float64).data_type()).with_unit(Unit::Percent));
+        let product = graph.add_node(product);
+        graph.meta.types.push(CustomType::new("Grams", DataType::Float64));
+        assert!(FieldUnitRule.validate(&graph).is_empty());
+
+        let node = graph.get_node_mut(product).unwrap();
+        node.add_field(Field::string("sku").with_unit(Unit::Bytes));
+        node.add_field(Field::double("tax").with_unit(Unit::Currency("usd".to_string())));
+        let errors = FieldUnitRule.validate(&graph);
+        assert_eq!(errors.len(), 2, "{:?}", errors);
+        assert!(errors[0].message.contains("Product.sku"));
+        assert!(errors[1].message.contains("three-letter"));
+    }
+
+    #[test]
+    fn test_relationship_field_types() {
+        let mut graph = ProjectGraph::with_name("blog");
+        let user = graph.add_node(Node::new_entity("User"));
+        let mut post = Node::new_entity("Post");
+        post.add_field(Field::int("author_id"));
+        let post = graph.add_node(post);
+        let edge = graph.add_relationship(user, post, RelationType::OneToMany).unwrap();
+        assert!(PortCompatibilityRule.validate(&graph).is_empty());
+        graph.get_edge_mut(edge).unwrap().set_fields(Some("id"), Some("id"));
+        assert!(EdgeValidityRule.validate(&graph).is_empty());
+
+        // Edited after the fact, e.g. in the project file
+        graph.get_edge_mut(edge).unwrap().set_fields(Some("id"), Some("author_id"));
+        let errors = PortCompatibilityRule.validate(&graph);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].kind, ValidationErrorKind::InvalidRelationship);
+        assert!(errors[0].message.contains("Post.author_id"));
+    }
+
+    #[test]
+    fn test_quick_fixes() {
+        let mut graph = ProjectGraph::with_name("shop");
+        let user = graph.add_node(Node::new_entity("User"));
+        let mut order = Node::new_entity("Order");
+        order.fields.retain(|f| !f.is_primary_key());
+        order.add_field(Field::string("status"));
+        order.add_field(Field::string("status"));
+        order.add_field(Field::string("status_2"));
+        order.add_field(Field::string("status"));
+        let order = graph.add_node(order);
+        let edge = graph.add_edge(Edge::dependency(user, order)).unwrap();
+        graph.nodes.remove(&user);
+
+        let issues = Validator::new().validate_all(&graph);
+        let fixes: Vec<_> = issues.iter().filter_map(|e| e.fix.clone()).collect();
+        assert!(fixes.contains(&QuickFix::RemoveEdge(edge)));
+        assert!(fixes.contains(&QuickFix::AddPrimaryKey(order)));
+        let renames: Vec<_> = fixes
+            .iter()
+            .filter_map(|fix| match fix {
+                QuickFix::RenameField { name, .. } => Some(name.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(renames, ["status_3", "status_4"]);
+
+        assert_eq!(apply_fixes(&mut graph, &issues), 4);
+        assert!(graph.get_edge(edge).is_none());
+        let order = graph.get_node(order).unwrap();
+        assert!(order.fields[0].is_primary_key());
+        assert!(order.get_field("status_4").is_some());
+        assert!(Validator::new().validate_all(&graph).iter().all(|e| e.fix.is_none()));
+        // Applied again, nothing is left to change
+        assert_eq!(apply_fixes(&mut graph, &issues), 0);
+    }
 }
@@ -0,0 +1,182 @@
+//! Multi-project workspaces
+//!
+//! A workspace file groups several project files, e.g. one per service in a
+//! microservice setup. Projects in a workspace can share schemas through
+//! `data.reference` nodes, which point at an entity in another project and
+//! are checked with [`Workspace::validate_references`].
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use imortal_core::{EngineError, EngineResult};
+
+use crate::graph::ProjectGraph;
+use crate::serialization::load_project;
+use crate::validation::{ValidationError, ValidationErrorKind};
+
+/// A project that belongs to a workspace
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WorkspaceProject {
+    /// Name used by reference nodes to address this project
+    pub name: String,
+    /// Project file, relative to the workspace file
+    pub path: PathBuf,
+}
+
+/// A group of related projects
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Workspace {
+    /// Workspace name
+    pub name: String,
+    /// Member projects
+    #[serde(default)]
+    pub projects: Vec<WorkspaceProject>,
+}
+
+impl Workspace {
+    /// Create an empty workspace
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            projects: Vec::new(),
+        }
+    }
+
+    /// Add a project
+    pub fn with_project(mut self, name: impl Into<String>, path: impl Into<PathBuf>) -> Self {
+        self.add_project(name, path);
+        self
+    }
+
+    /// Add a project, replacing any project with the same name
+    pub fn add_project(&mut self, name: impl Into<String>, path: impl Into<PathBuf>) {
+        let project = WorkspaceProject {
+            name: name.into(),
+            path: path.into(),
+        };
+        match self.projects.iter_mut().find(|p| p.name == project.name) {
+            Some(existing) => *existing = project,
+            None => self.projects.push(project),
+        }
+    }
+
+    /// Get a member project by name
+    pub fn project(&self, name: &str) -> Option<&WorkspaceProject> {
+        self.projects.iter().find(|p| p.name == name)
+    }
+
+    /// Load a workspace file
+    pub fn load(path: impl AsRef<Path>) -> EngineResult<Self> {
+        let path = path.as_ref();
+        if !path.exists() {
+            return Err(EngineError::FileNotFound(path.display().to_string()));
+        }
+        let contents = fs::read_to_string(path)?;
+        serde_json::from_str(&contents)
+            .map_err(|e| EngineError::Deserialization(format!("JSON parse error: {}", e)))
+    }
+
+    /// Save to a workspace file
+    pub fn save(&self, path: impl AsRef<Path>) -> EngineResult<()> {
+        let contents = serde_json::to_string_pretty(self)
+            .map_err(|e| EngineError::Serialization(e.to_string()))?;
+        fs::write(path, contents)?;
+        Ok(())
+    }
+
+    /// Load every member project, resolving paths against the workspace file's directory
+    pub fn load_projects(&self, workspace_file: impl AsRef<Path>) -> EngineResult<Vec<(String, PathBuf, ProjectGraph)>> {
+        let base = workspace_file.as_ref().parent().unwrap_or(Path::new(""));
+        self.projects
+            .iter()
+            .map(|p| {
+                let path = base.join(&p.path);
+                let graph = load_project(&path)?;
+                Ok((p.name.clone(), path, graph))
+            })
+            .collect()
+    }
+
+    /// Check every reference node against the loaded projects
+    ///
+    /// `projects` pairs each workspace project name with its graph. A
+    /// reference is broken when its project is not in the workspace, or the
+    /// project has no entity with the referenced name.
+    pub fn validate_references(&self, projects: &[(&str, &ProjectGraph)]) -> Vec<ValidationError> {
+        let mut errors = Vec::new();
+
+        for (source, graph) in projects {
+            for node in graph.find_nodes_by_type("data.reference") {
+                let Some((project, entity)) = node.reference_target() else {
+                    continue;
+                };
+                if project.is_empty() || entity.is_empty() {
+                    // Reported by ReferenceTargetRule
+                    continue;
+                }
+
+                let message = if self.project(project).is_none() {
+                    format!("Reference '{}' in '{}': project '{}' is not in the workspace", node.name, source, project)
+                } else {
+                    match projects.iter().find(|(name, _)| *name == project) {
+                        None => format!("Reference '{}' in '{}': project '{}' is not loaded", node.name, source, project),
+                        Some((_, target)) if !target
+                            .find_nodes_by_type("data.entity")
+                            .iter()
+                            .any(|n| n.name == entity) =>
+                        {
+                            format!("Reference '{}' in '{}': project '{}' has no entity '{}'", node.name, source, project, entity)
+                        }
+                        Some(_) => continue,
+                    }
+                };
+                errors.push(ValidationError::for_node(ValidationErrorKind::BrokenReference, message, node.id));
+            }
+        }
+
+        errors
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Node;
+
+    #[test]
+    fn test_workspace_projects() {
+        let mut workspace = Workspace::new("shop")
+            .with_project("accounts", "accounts/accounts.imortal")
+            .with_project("orders", "orders.imortal");
+        workspace.add_project("orders", "orders/orders.imortal");
+
+        assert_eq!(workspace.projects.len(), 2);
+        assert_eq!(workspace.project("orders").unwrap().path, PathBuf::from("orders/orders.imortal"));
+
+        let json = serde_json::to_string(&workspace).unwrap();
+        assert_eq!(serde_json::from_str::<Workspace>(&json).unwrap(), workspace);
+    }
+
+    #[test]
+    fn test_validate_references() {
+        let workspace = Workspace::new("shop")
+            .with_project("accounts", "accounts.imortal")
+            .with_project("orders", "orders.imortal");
+
+        let mut accounts = ProjectGraph::with_name("accounts");
+        accounts.add_node(Node::new_entity("User"));
+
+        let mut orders = ProjectGraph::with_name("orders");
+        orders.add_node(Node::new_reference("accounts", "User"));
+        let missing_entity = orders.add_node(Node::new_reference("accounts", "Invoice"));
+        let missing_project = orders.add_node(Node::new_reference("billing", "Invoice"));
+
+        let errors = workspace.validate_references(&[("accounts", &accounts), ("orders", &orders)]);
+        assert_eq!(errors.len(), 2);
+        assert!(errors.iter().all(|e| e.kind == ValidationErrorKind::BrokenReference));
+        assert!(errors.iter().any(|e| e.node_id == Some(missing_entity) && e.message.contains("no entity 'Invoice'")));
+        assert!(errors.iter().any(|e| e.node_id == Some(missing_project) && e.message.contains("not in the workspace")));
+    }
+}
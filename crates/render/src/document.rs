@@ -0,0 +1,430 @@
+//! Design document export
+//!
+//! [`render_document`] builds a shareable PDF from the IR: a title page
+//! with the project's details, the rendered diagram, a data dictionary of
+//! every entity's fields, the REST endpoints, and the validation report.
+//! Text is written as PDF text in Helvetica so it stays sharp and
+//! searchable; only the diagram is an image.
+
+use imortal_core::{DataType, EngineResult};
+use imortal_ir::validation::{get_all_issues, ValidationSeverity};
+use imortal_ir::ProjectGraph;
+
+use crate::painter::{Color, Point, Rect, Rounding};
+use crate::pdf::{text_width, PdfContent, PdfDocument};
+use crate::png::PngPainter;
+use crate::print::PaperSize;
+use crate::scene::{self, Style, Transform};
+use crate::Painter;
+
+/// Page margin, in points
+const MARGIN: f32 = 54.0;
+
+/// Body text size, in points
+const BODY: f32 = 10.0;
+
+/// Resolution the diagram is drawn at, in dots per inch
+const DIAGRAM_DPI: f32 = 200.0;
+
+/// Longest side of the diagram image, in pixels
+const MAX_DIAGRAM_PIXELS: f32 = 4000.0;
+
+const INK: Color = Color::rgb(30, 30, 35);
+const MUTED: Color = Color::rgb(110, 110, 120);
+const RULE: Color = Color::rgb(200, 200, 208);
+const SHADE: Color = Color::rgb(238, 239, 243);
+
+/// Render a graph as a design document
+pub fn render_document(graph: &ProjectGraph, paper: PaperSize) -> EngineResult<Vec<u8>> {
+    render_document_with_style(graph, paper, &Style::print())
+}
+
+/// Render a graph as a design document, drawing the diagram with `style`
+pub fn render_document_with_style(graph: &ProjectGraph, paper: PaperSize, style: &Style) -> EngineResult<Vec<u8>> {
+    let (width, height) = paper.points();
+    let mut writer = Writer::new(graph.meta.name.clone(), width, height);
+
+    title_page(&mut writer, graph);
+    writer.new_page();
+    diagram(&mut writer, graph, style)?;
+    writer.new_page();
+    data_dictionary(&mut writer, graph);
+    endpoints(&mut writer, graph);
+    validation_report(&mut writer, graph);
+    Ok(writer.finish())
+}
+
+fn title_page(writer: &mut Writer, graph: &ProjectGraph) {
+    let meta = &graph.meta;
+    writer.space(120.0);
+    writer.heading(&meta.name, 28.0);
+    writer.paragraph(&format!("Design document · version {}", meta.version), 12.0, MUTED);
+    writer.space(12.0);
+    if let Some(description) = meta.description.as_deref().filter(|d| !d.trim().is_empty()) {
+        writer.paragraph(description, 12.0, INK);
+        writer.space(12.0);
+    }
+
+    let entities = graph.find_nodes_by_type("data.entity").len();
+    let endpoints = graph.find_nodes_by_type("api.rest").len();
+    let mut details = vec![
+        ("Entities", entities.to_string()),
+        ("Endpoints", endpoints.to_string()),
+        ("Components", graph.node_count().to_string()),
+        ("Connections", graph.edge_count().to_string()),
+    ];
+    if !meta.authors.is_empty() {
+        details.push(("Authors", meta.authors.join(", ")));
+    }
+    if let Some(license) = &meta.license {
+        details.push(("License", license.clone()));
+    }
+    if let Some(repository) = &meta.repository {
+        details.push(("Repository", repository.clone()));
+    }
+    let rows = details.into_iter().map(|(label, value)| vec![label.to_string(), value]).collect();
+    writer.table(&[("", 0.3), ("", 0.7)], rows);
+}
+
+/// The whole graph, fitted to the page width and the space left on it
+fn diagram(writer: &mut Writer, graph: &ProjectGraph, style: &Style) -> EngineResult<()> {
+    writer.heading("Diagram", 18.0);
+    let Some(bounds) = scene::content_bounds(graph) else {
+        writer.paragraph("The project has no components yet.", BODY, MUTED);
+        return Ok(());
+    };
+
+    let padding = 20.0;
+    let (content_width, content_height) = (bounds.width() + 2.0 * padding, bounds.height() + 2.0 * padding);
+    let (space_width, space_height) = (writer.content_width(), writer.space_left());
+    let points_per_unit = (space_width / content_width).min(space_height / content_height);
+    let (printed_width, printed_height) = (content_width * points_per_unit, content_height * points_per_unit);
+
+    let pixels_per_unit = (points_per_unit * DIAGRAM_DPI / 72.0).min(MAX_DIAGRAM_PIXELS / content_width.max(content_height));
+    let (pixel_width, pixel_height) =
+        ((content_width * pixels_per_unit).ceil() as u32, (content_height * pixels_per_unit).ceil() as u32);
+    let mut painter = PngPainter::new(pixel_width, pixel_height)?;
+    let transform = Transform::new(
+        Point::new((padding - bounds.min.x) * pixels_per_unit, (padding - bounds.min.y) * pixels_per_unit),
+        pixels_per_unit,
+    );
+    let clip = Rect::from_min_size(Point::default(), pixel_width as f32, pixel_height as f32);
+    painter.fill_rect(clip, Rounding::default(), Color::WHITE);
+    scene::draw_project(&mut painter, graph, style, transform, clip);
+
+    let x = MARGIN + (space_width - printed_width) / 2.0;
+    let y = writer.y;
+    writer.page.image(x, y, printed_width, printed_height, painter.size(), &painter.to_rgb());
+    writer.y += printed_height;
+    Ok(())
+}
+
+/// Each entity's fields, inherited and mixed-in ones included
+fn data_dictionary(writer: &mut Writer, graph: &ProjectGraph) {
+    writer.heading("Data Dictionary", 18.0);
+    let mut entities = graph.find_nodes_by_type("data.entity");
+    entities.sort_by(|a, b| a.name.cmp(&b.name));
+    if entities.is_empty() {
+        writer.paragraph("The project has no entities.", BODY, MUTED);
+    }
+
+    for entity in entities {
+        writer.keep_together(BODY * 6.0);
+        writer.heading(&entity.name, 13.0);
+        if let Some(description) = entity.description.as_deref().filter(|d| !d.trim().is_empty()) {
+            writer.paragraph(description, BODY, MUTED);
+        }
+        let rows = graph
+            .effective_fields(entity.id)
+            .into_iter()
+            .map(|field| {
+                let mut keys = Vec::new();
+                if field.is_primary_key() {
+                    keys.push("Primary key");
+                }
+                if field.is_unique() {
+                    keys.push("Unique");
+                }
+                vec![
+                    field.name.clone(),
+                    type_label(&field.data_type),
+                    if field.required { "Yes" } else { "No" }.to_string(),
+                    keys.join(", "),
+                    field.description.clone().unwrap_or_default(),
+                ]
+            })
+            .collect();
+        writer.table(
+            &[("Field", 0.2), ("Type", 0.2), ("Required", 0.11), ("Keys", 0.17), ("Description", 0.32)],
+            rows,
+        );
+        writer.space(8.0);
+    }
+}
+
+/// REST endpoints by route
+fn endpoints(writer: &mut Writer, graph: &ProjectGraph) {
+    writer.keep_together(BODY * 8.0);
+    writer.heading("Endpoints", 18.0);
+    let versioning = &graph.meta.api_versioning;
+    let mut rows: Vec<Vec<String>> = graph
+        .find_nodes_by_type("api.rest")
+        .into_iter()
+        .map(|node| {
+            vec![
+                node.get_config_str("method").unwrap_or("GET").to_uppercase(),
+                versioning.route(node.endpoint_path().unwrap_or("/"), node.endpoint_version()),
+                node.name.clone(),
+                node.get_config_str("entity").unwrap_or_default().to_string(),
+            ]
+        })
+        .collect();
+    if rows.is_empty() {
+        writer.paragraph("The project has no REST endpoints.", BODY, MUTED);
+        return;
+    }
+    rows.sort_by(|a, b| a[1].cmp(&b[1]).then(a[0].cmp(&b[0])));
+    writer.table(&[("Method", 0.12), ("Path", 0.4), ("Name", 0.26), ("Entity", 0.22)], rows);
+    writer.space(8.0);
+}
+
+/// Every validation problem, errors first
+fn validation_report(writer: &mut Writer, graph: &ProjectGraph) {
+    writer.keep_together(BODY * 8.0);
+    writer.heading("Validation", 18.0);
+    let mut issues = get_all_issues(graph);
+    if issues.is_empty() {
+        writer.paragraph("No problems found.", BODY, INK);
+        return;
+    }
+    issues.sort_by_key(|issue| issue.severity as u8);
+    let rows = issues
+        .into_iter()
+        .map(|issue| {
+            let severity = match issue.severity {
+                ValidationSeverity::Error => "Error",
+                ValidationSeverity::Warning => "Warning",
+                ValidationSeverity::Info => "Info",
+            };
+            let subject = issue.node_id.and_then(|id| graph.get_node(id)).map(|n| n.name.clone()).unwrap_or_default();
+            vec![severity.to_string(), subject, issue.message]
+        })
+        .collect();
+    writer.table(&[("Severity", 0.13), ("Component", 0.22), ("Problem", 0.65)], rows);
+}
+
+/// Type as named in the data dictionary
+fn type_label(data_type: &DataType) -> String {
+    match data_type {
+        DataType::String => "String".to_string(),
+        DataType::Text => "Text".to_string(),
+        DataType::Int32 => "Integer".to_string(),
+        DataType::Int64 => "BigInt".to_string(),
+        DataType::Float32 => "Float".to_string(),
+        DataType::Float64 => "Double".to_string(),
+        DataType::Bool => "Boolean".to_string(),
+        DataType::Uuid => "Uuid".to_string(),
+        DataType::DateTime => "DateTime".to_string(),
+        DataType::Date => "Date".to_string(),
+        DataType::Time => "Time".to_string(),
+        DataType::Bytes => "Bytes".to_string(),
+        DataType::Json => "JSON".to_string(),
+        DataType::Optional(inner) => format!("Optional<{}>", type_label(inner)),
+        DataType::Array(inner) => format!("Array<{}>", type_label(inner)),
+        DataType::Reference(name) => format!("Reference ({})", name),
+        DataType::Entity(name) => format!("Entity ({})", name),
+        other => other.to_rust_type(),
+    }
+}
+
+/// Lays text out top to bottom, starting new pages as they fill
+struct Writer {
+    title: String,
+    pdf: PdfDocument,
+    page: PdfContent,
+    width: f32,
+    height: f32,
+    /// Top of the next line, in points from the top of the page
+    y: f32,
+}
+
+impl Writer {
+    fn new(title: String, width: f32, height: f32) -> Self {
+        Self { title, pdf: PdfDocument::new(), page: PdfContent::new(height), width, height, y: MARGIN }
+    }
+
+    fn content_width(&self) -> f32 {
+        self.width - 2.0 * MARGIN
+    }
+
+    /// Height left on the page above the bottom margin
+    fn space_left(&self) -> f32 {
+        self.height - MARGIN - self.y
+    }
+
+    /// Finish the page with its number and start a new one
+    fn new_page(&mut self) {
+        let number = self.pdf.page_count() + 1;
+        let footer = format!("{} · {}", self.title, number);
+        let y = self.height - MARGIN / 2.0;
+        self.page.text(MARGIN, y, 8.0, false, MUTED, &footer);
+        let page = std::mem::replace(&mut self.page, PdfContent::new(self.height));
+        self.pdf.add_content_page(self.width, self.height, page);
+        self.y = MARGIN;
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        self.new_page();
+        self.pdf.finish()
+    }
+
+    /// Start a new page unless `height` fits on this one
+    fn keep_together(&mut self, height: f32) {
+        if self.space_left() < height {
+            self.new_page();
+        }
+    }
+
+    fn space(&mut self, height: f32) {
+        self.y += height;
+    }
+
+    fn heading(&mut self, text: &str, size: f32) {
+        self.keep_together(size * 2.5);
+        self.y += size * 0.6;
+        for line in wrap(text, size, true, self.content_width()) {
+            self.y += size * 1.2;
+            self.page.text(MARGIN, self.y - size * 0.25, size, true, INK, &line);
+        }
+        self.y += size * 0.5;
+    }
+
+    fn paragraph(&mut self, text: &str, size: f32, color: Color) {
+        for line in wrap(text, size, false, self.content_width()) {
+            self.keep_together(size * 1.4);
+            self.y += size * 1.4;
+            self.page.text(MARGIN, self.y - size * 0.35, size, false, color, &line);
+        }
+        self.y += size * 0.4;
+    }
+
+    /// A table with columns given as (header, share of the width); an empty
+    /// first header leaves out the header row. Cells wrap, and the header
+    /// repeats on each page the table runs onto.
+    fn table(&mut self, columns: &[(&str, f32)], rows: Vec<Vec<String>>) {
+        let size = BODY * 0.9;
+        let line = size * 1.35;
+        let pad = 4.0;
+        let widths: Vec<f32> = columns.iter().map(|(_, share)| share * self.content_width()).collect();
+        let header: Vec<String> = columns.iter().map(|(name, _)| name.to_string()).collect();
+        let has_header = !columns[0].0.is_empty();
+
+        let draw_row = |writer: &mut Writer, cells: &[String], bold: bool| {
+            let wrapped: Vec<Vec<String>> = cells
+                .iter()
+                .zip(&widths)
+                .map(|(cell, width)| wrap(cell, size, bold, width - 2.0 * pad))
+                .collect();
+            let lines = wrapped.iter().map(Vec::len).max().unwrap_or(1).max(1);
+            let height = lines as f32 * line + pad;
+            if bold {
+                writer.page.fill_rect(MARGIN, writer.y, writer.content_width(), height, SHADE);
+            }
+            let mut x = MARGIN;
+            for (cell, width) in wrapped.iter().zip(&widths) {
+                for (i, text) in cell.iter().enumerate() {
+                    let baseline = writer.y + pad / 2.0 + (i as f32 + 1.0) * line - size * 0.35;
+                    writer.page.text(x + pad, baseline, size, bold, INK, text);
+                }
+                x += width;
+            }
+            writer.y += height;
+            let y = writer.y;
+            writer.page.line((MARGIN, y), (MARGIN + writer.content_width(), y), 0.5, RULE);
+        };
+
+        if has_header {
+            self.keep_together(line * 3.0);
+            draw_row(self, &header, true);
+        }
+        for row in rows {
+            if self.space_left() < line * 2.0 {
+                self.new_page();
+                if has_header {
+                    draw_row(self, &header, true);
+                }
+            }
+            draw_row(self, &row, false);
+        }
+        self.y += pad;
+    }
+}
+
+/// Break text into lines no wider than `width`, splitting overlong words
+fn wrap(text: &str, size: f32, bold: bool, width: f32) -> Vec<String> {
+    let mut lines = Vec::new();
+    for paragraph in text.lines() {
+        let mut current = String::new();
+        for word in paragraph.split_whitespace() {
+            let candidate = if current.is_empty() { word.to_string() } else { format!("{} {}", current, word) };
+            if text_width(&candidate, size, bold) <= width {
+                current = candidate;
+                continue;
+            }
+            if !current.is_empty() {
+                lines.push(std::mem::take(&mut current));
+            }
+            // A word wider than the line is split wherever it overflows
+            for c in word.chars() {
+                if !current.is_empty() && text_width(&format!("{}{}", current, c), size, bold) > width {
+                    lines.push(std::mem::take(&mut current));
+                }
+                current.push(c);
+            }
+        }
+        lines.push(current);
+    }
+    if lines.is_empty() {
+        lines.push(String::new());
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use imortal_ir::{Field, Node};
+
+    #[test]
+    fn test_wrap() {
+        let lines = wrap("the quick brown fox jumps over the lazy dog", 10.0, false, 80.0);
+        assert!(lines.len() > 1);
+        assert!(lines.iter().all(|line| text_width(line, 10.0, false) <= 80.0));
+        assert_eq!(lines.join(" "), "the quick brown fox jumps over the lazy dog");
+
+        let long = wrap("abcdefghijklmnopqrstuvwxyz", 10.0, false, 40.0);
+        assert!(long.len() > 1);
+        assert_eq!(long.concat(), "abcdefghijklmnopqrstuvwxyz");
+        assert_eq!(wrap("", 10.0, false, 40.0), vec![String::new()]);
+    }
+
+    #[test]
+    fn test_render_document() {
+        let mut graph = ProjectGraph::with_name("Shop");
+        let mut user = Node::new_entity("User");
+        user.fields.push(Field::new("email", DataType::String).required());
+        graph.add_node(user);
+        let mut endpoint = Node::new("api.rest", "List users");
+        endpoint.set_config("path", "/users");
+        endpoint.set_config("entity", "User");
+        graph.add_node(endpoint);
+
+        let pdf = render_document(&graph, PaperSize::Letter).unwrap();
+        let text = String::from_utf8_lossy(&pdf);
+        assert!(text.starts_with("%PDF"));
+        assert!(text.contains("/Count 3"), "title, diagram, and details pages");
+        for expected in ["(Data Dictionary)", "(email)", "(String)", "(Endpoints)", "(/users)", "(Validation)"] {
+            assert!(text.contains(expected), "missing {}", expected);
+        }
+    }
+}
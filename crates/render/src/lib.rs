@@ -0,0 +1,200 @@
+//! Immortal Engine Rendering
+//!
+//! Renders a [`ProjectGraph`] to SVG or PNG without the interactive editor,
+//! for diagrams in generated READMEs and CI artifacts, to tiled PDF pages
+//! for printing, in [`print`], and to a PDF design document, in
+//! [`document`]. The drawing code in
+//! [`scene`] works against the [`Painter`] trait, which the editor canvas
+//! also implements, so images match what the canvas shows.
+//!
+//! ```no_run
+//! use imortal_render::{render_to_file, RenderOptions};
+//!
+//! let graph = imortal_ir::load_project("shop.imortal")?;
+//! render_to_file(&graph, "docs/shop.svg", &RenderOptions::default())?;
+//! # Ok::<(), imortal_core::EngineError>(())
+//! ```
+
+pub mod document;
+pub mod painter;
+pub mod pdf;
+pub mod png;
+pub mod print;
+pub mod scene;
+pub mod svg;
+
+use std::path::Path;
+
+use imortal_core::{EngineError, EngineResult};
+use imortal_ir::ProjectGraph;
+
+pub use painter::{Align, Color, Painter, Point, Rect, Rounding};
+pub use document::{render_document, render_document_with_style};
+pub use pdf::{PdfContent, PdfDocument};
+pub use png::PngPainter;
+pub use print::{render_pdf, PageLayout, PaperSize, PrintOptions};
+pub use scene::{draw_edge_path, draw_project, font_size, stroke_width, CategoryStyle, Style, Transform, MIN_TEXT_SIZE};
+pub use svg::SvgPainter;
+
+/// Output image format
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageFormat {
+    Svg,
+    Png,
+}
+
+impl ImageFormat {
+    /// Pick a format from a file extension
+    pub fn from_path(path: impl AsRef<Path>) -> Option<Self> {
+        let extension = path.as_ref().extension()?.to_str()?.to_lowercase();
+        match extension.as_str() {
+            "svg" => Some(ImageFormat::Svg),
+            "png" => Some(ImageFormat::Png),
+            _ => None,
+        }
+    }
+}
+
+/// How to frame and draw a headless render
+#[derive(Debug, Clone, PartialEq)]
+pub struct RenderOptions {
+    /// Output pixels per canvas unit
+    pub scale: f32,
+    /// Margin around the content, in canvas units
+    pub padding: f32,
+    /// Colors and sizes
+    pub style: Style,
+}
+
+impl Default for RenderOptions {
+    fn default() -> Self {
+        Self {
+            scale: 1.0,
+            padding: 40.0,
+            style: Style {
+                show_selection: false,
+                ..Style::default()
+            },
+        }
+    }
+}
+
+impl RenderOptions {
+    /// Set the scale factor
+    pub fn with_scale(mut self, scale: f32) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    /// Show or hide the background grid
+    pub fn with_grid(mut self, show_grid: bool) -> Self {
+        self.style.show_grid = show_grid;
+        self
+    }
+
+    /// Image size and canvas transform that fit the whole graph
+    ///
+    /// The saved viewport is ignored so renders are framed the same way no
+    /// matter where the project was last scrolled to.
+    pub fn frame(&self, graph: &ProjectGraph) -> (u32, u32, Transform) {
+        let bounds = scene::content_bounds(graph).unwrap_or_default();
+        let width = ((bounds.width() + 2.0 * self.padding) * self.scale).ceil().max(1.0);
+        let height = ((bounds.height() + 2.0 * self.padding) * self.scale).ceil().max(1.0);
+        let transform = Transform::new(
+            Point::new((self.padding - bounds.min.x) * self.scale, (self.padding - bounds.min.y) * self.scale),
+            self.scale,
+        );
+        (width as u32, height as u32, transform)
+    }
+}
+
+/// Render a graph to an SVG document
+pub fn render_svg(graph: &ProjectGraph, options: &RenderOptions) -> String {
+    let (width, height, transform) = options.frame(graph);
+    let mut painter = SvgPainter::new(width, height);
+    draw_project(&mut painter, graph, &options.style, transform, clip(width, height));
+    painter.finish()
+}
+
+/// Render a graph to PNG bytes
+pub fn render_png(graph: &ProjectGraph, options: &RenderOptions) -> EngineResult<Vec<u8>> {
+    let (width, height, transform) = options.frame(graph);
+    let mut painter = PngPainter::new(width, height)?;
+    draw_project(&mut painter, graph, &options.style, transform, clip(width, height));
+    painter.finish()
+}
+
+/// Render a graph to a file, choosing SVG or PNG from its extension
+pub fn render_to_file(graph: &ProjectGraph, path: impl AsRef<Path>, options: &RenderOptions) -> EngineResult<()> {
+    let path = path.as_ref();
+    match ImageFormat::from_path(path) {
+        Some(ImageFormat::Svg) => std::fs::write(path, render_svg(graph, options))?,
+        Some(ImageFormat::Png) => std::fs::write(path, render_png(graph, options)?)?,
+        None => {
+            return Err(EngineError::UnsupportedTarget(format!(
+                "Cannot render to '{}': use a .svg or .png file",
+                path.display()
+            )))
+        }
+    }
+    Ok(())
+}
+
+fn clip(width: u32, height: u32) -> Rect {
+    Rect::from_min_size(Point::default(), width as f32, height as f32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use imortal_ir::{Edge, Node};
+
+    fn sample() -> ProjectGraph {
+        let mut graph = ProjectGraph::with_name("shop");
+        let mut user = Node::new_entity("User");
+        user.selected = true;
+        let user = graph.add_node(user);
+        let mut order = Node::new_entity("Order");
+        order.position.x = 400.0;
+        let order = graph.add_node(order);
+        graph.add_edge(Edge::dependency(user, order)).unwrap();
+        graph
+    }
+
+    #[test]
+    fn test_frame_fits_content() {
+        let graph = sample();
+        let bounds = scene::content_bounds(&graph).unwrap();
+        let (width, height, transform) = RenderOptions::default().with_scale(2.0).frame(&graph);
+
+        assert_eq!(width, ((bounds.width() + 80.0) * 2.0).ceil() as u32);
+        assert_eq!(height, ((bounds.height() + 80.0) * 2.0).ceil() as u32);
+        assert_eq!(transform.apply(bounds.min.x, bounds.min.y), Point::new(80.0, 80.0));
+    }
+
+    #[test]
+    fn test_render_svg() {
+        let svg = render_svg(&sample(), &RenderOptions::default().with_grid(false));
+
+        assert!(svg.contains("User</text>"));
+        assert!(svg.contains("Order</text>"));
+        // The dependency is dotted, with an open arrow head
+        assert!(svg.contains("<polyline"), "edge arrow head is drawn");
+        // Headless renders don't show the editor's selection
+        assert!(!svg.contains("#3c5078"));
+    }
+
+    #[test]
+    fn test_render_to_file() {
+        let dir = std::env::temp_dir().join(format!("imortal_render_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let graph = sample();
+
+        render_to_file(&graph, dir.join("shop.png"), &RenderOptions::default()).unwrap();
+        let bytes = std::fs::read(dir.join("shop.png")).unwrap();
+        assert_eq!(&bytes[..4], b"\x89PNG");
+
+        assert!(render_to_file(&graph, dir.join("shop.gif"), &RenderOptions::default()).is_err());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,148 @@
+//! Painter abstraction
+//!
+//! The scene is drawn through the [`Painter`] trait so the same drawing code
+//! targets the editor canvas, an SVG document, or a PNG image. Coordinates
+//! are in output pixels with y growing downwards.
+
+/// An RGBA color, not premultiplied
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Color {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+    pub a: u8,
+}
+
+impl Color {
+    pub const WHITE: Color = Color::rgb(255, 255, 255);
+    pub const TRANSPARENT: Color = Color::rgba(0, 0, 0, 0);
+
+    /// An opaque color
+    pub const fn rgb(r: u8, g: u8, b: u8) -> Self {
+        Self { r, g, b, a: 255 }
+    }
+
+    /// A color with alpha
+    pub const fn rgba(r: u8, g: u8, b: u8, a: u8) -> Self {
+        Self { r, g, b, a }
+    }
+}
+
+/// A point in output coordinates
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Point {
+    pub x: f32,
+    pub y: f32,
+}
+
+impl Point {
+    pub const fn new(x: f32, y: f32) -> Self {
+        Self { x, y }
+    }
+}
+
+/// An axis-aligned rectangle
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Rect {
+    pub min: Point,
+    pub max: Point,
+}
+
+impl Rect {
+    /// Create a rectangle from its top-left corner and size
+    pub fn from_min_size(min: Point, width: f32, height: f32) -> Self {
+        Self {
+            min,
+            max: Point::new(min.x + width, min.y + height),
+        }
+    }
+
+    pub fn width(&self) -> f32 {
+        self.max.x - self.min.x
+    }
+
+    pub fn height(&self) -> f32 {
+        self.max.y - self.min.y
+    }
+
+    pub fn center(&self) -> Point {
+        Point::new((self.min.x + self.max.x) / 2.0, (self.min.y + self.max.y) / 2.0)
+    }
+
+    /// Whether two rectangles overlap
+    pub fn intersects(&self, other: &Rect) -> bool {
+        self.min.x <= other.max.x && other.min.x <= self.max.x && self.min.y <= other.max.y && other.min.y <= self.max.y
+    }
+
+    /// Smallest rectangle containing both
+    pub fn union(&self, other: &Rect) -> Rect {
+        Rect {
+            min: Point::new(self.min.x.min(other.min.x), self.min.y.min(other.min.y)),
+            max: Point::new(self.max.x.max(other.max.x), self.max.y.max(other.max.y)),
+        }
+    }
+}
+
+/// Corner radii of a filled rectangle
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Rounding {
+    /// Radius of the two top corners
+    pub top: f32,
+    /// Radius of the two bottom corners
+    pub bottom: f32,
+}
+
+impl Rounding {
+    /// The same radius on every corner
+    pub const fn same(radius: f32) -> Self {
+        Self { top: radius, bottom: radius }
+    }
+}
+
+/// Horizontal anchor of a text run; text is always vertically centered
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Align {
+    Left,
+    Center,
+}
+
+/// A drawing surface
+pub trait Painter {
+    /// Fill a rectangle
+    fn fill_rect(&mut self, rect: Rect, rounding: Rounding, color: Color);
+
+    /// Outline a rectangle
+    fn stroke_rect(&mut self, rect: Rect, radius: f32, width: f32, color: Color);
+
+    /// Draw a polyline through `points`
+    fn line(&mut self, points: &[Point], width: f32, color: Color);
+
+    /// Fill a circle
+    fn fill_circle(&mut self, center: Point, radius: f32, color: Color);
+
+    /// Outline a circle
+    fn stroke_circle(&mut self, center: Point, radius: f32, width: f32, color: Color);
+
+    /// Fill a convex polygon
+    fn fill_polygon(&mut self, points: &[Point], color: Color);
+
+    /// Draw a single line of text at `pos`
+    fn text(&mut self, pos: Point, align: Align, text: &str, size: f32, color: Color);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rect_geometry() {
+        let a = Rect::from_min_size(Point::new(0.0, 0.0), 100.0, 50.0);
+        let b = Rect::from_min_size(Point::new(90.0, 40.0), 20.0, 20.0);
+        let c = Rect::from_min_size(Point::new(200.0, 0.0), 10.0, 10.0);
+
+        assert_eq!(a.center(), Point::new(50.0, 25.0));
+        assert!(a.intersects(&b));
+        assert!(!a.intersects(&c));
+        assert_eq!(a.union(&c).width(), 210.0);
+    }
+}
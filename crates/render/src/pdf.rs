@@ -0,0 +1,305 @@
+//! PDF output
+//!
+//! A minimal PDF writer. A page is either one full-page image, drawn with
+//! [`PngPainter`](crate::PngPainter) at print resolution so it looks exactly
+//! like a PNG export, or [`PdfContent`]: text in the standard Helvetica
+//! fonts, lines, rectangles, and images, for documents whose text should
+//! stay sharp and searchable.
+
+use std::fmt::Write;
+
+use crate::painter::Color;
+
+/// A PDF document built page by page
+#[derive(Debug, Clone, Default)]
+pub struct PdfDocument {
+    pages: Vec<(f32, f32, PdfContent)>,
+}
+
+impl PdfDocument {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a page `width` by `height` points filled by an RGB image of
+    /// `pixels` (width, height)
+    pub fn add_page(&mut self, width: f32, height: f32, pixels: (u32, u32), rgb: &[u8]) {
+        let mut content = PdfContent::new(height);
+        content.image(0.0, 0.0, width, height, pixels, rgb);
+        self.add_content_page(width, height, content);
+    }
+
+    /// Add a page `width` by `height` points with vector content
+    pub fn add_content_page(&mut self, width: f32, height: f32, content: PdfContent) {
+        self.pages.push((width, height, content));
+    }
+
+    /// Number of pages added so far
+    pub fn page_count(&self) -> usize {
+        self.pages.len()
+    }
+
+    /// Write the document
+    ///
+    /// Objects are numbered catalog (1), page tree (2), the regular and bold
+    /// fonts (3, 4), then each page followed by its contents and images.
+    pub fn finish(self) -> Vec<u8> {
+        let mut out = b"%PDF-1.4\n%\xE2\xE3\xCF\xD3\n".to_vec();
+        let mut offsets = vec![0usize];
+
+        let mut object = |out: &mut Vec<u8>, body: &[u8]| {
+            let id = offsets.len();
+            offsets.push(out.len());
+            out.extend_from_slice(format!("{} 0 obj\n", id).as_bytes());
+            out.extend_from_slice(body);
+            out.extend_from_slice(b"\nendobj\n");
+            id
+        };
+
+        // Page ids are known up front so the page tree can come first
+        let mut page_ids = Vec::new();
+        let mut next = 5;
+        for (_, _, content) in &self.pages {
+            page_ids.push(next);
+            next += 2 + content.images.len();
+        }
+        object(&mut out, b"<< /Type /Catalog /Pages 2 0 R >>");
+        let kids: Vec<String> = page_ids.iter().map(|id| format!("{} 0 R", id)).collect();
+        object(&mut out, format!("<< /Type /Pages /Kids [{}] /Count {} >>", kids.join(" "), self.pages.len()).as_bytes());
+        for font in ["Helvetica", "Helvetica-Bold"] {
+            let body = format!("<< /Type /Font /Subtype /Type1 /BaseFont /{} /Encoding /WinAnsiEncoding >>", font);
+            object(&mut out, body.as_bytes());
+        }
+
+        for (page_id, (width, height, content)) in page_ids.into_iter().zip(&self.pages) {
+            let images: Vec<String> = (0..content.images.len())
+                .map(|i| format!("/Im{} {} 0 R", i, page_id + 2 + i))
+                .collect();
+            let body = format!(
+                "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {} {}] \
+                 /Resources << /Font << /F1 3 0 R /F2 4 0 R >> /XObject << {} >> >> /Contents {} 0 R >>",
+                num(*width),
+                num(*height),
+                images.join(" "),
+                page_id + 1,
+            );
+            object(&mut out, body.as_bytes());
+            object(&mut out, &stream(&format!("<< /Length {} >>", content.ops.len()), content.ops.as_bytes()));
+            for image in &content.images {
+                let header = format!(
+                    "<< /Type /XObject /Subtype /Image /Width {} /Height {} /ColorSpace /DeviceRGB \
+                     /BitsPerComponent 8 /Filter /FlateDecode /Length {} >>",
+                    image.pixels.0,
+                    image.pixels.1,
+                    image.data.len(),
+                );
+                object(&mut out, &stream(&header, &image.data));
+            }
+        }
+
+        let xref = out.len();
+        let mut table = format!("xref\n0 {}\n0000000000 65535 f \n", offsets.len());
+        for offset in &offsets[1..] {
+            let _ = writeln!(table, "{:010} 00000 n ", offset);
+        }
+        let _ = write!(table, "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n", offsets.len(), xref);
+        out.extend_from_slice(table.as_bytes());
+        out
+    }
+}
+
+/// Drawing on one page, in points from its top-left corner
+#[derive(Debug, Clone, Default)]
+pub struct PdfContent {
+    /// Page height, to flip y into PDF's bottom-up coordinates
+    height: f32,
+    ops: String,
+    images: Vec<PdfImage>,
+}
+
+#[derive(Debug, Clone)]
+struct PdfImage {
+    pixels: (u32, u32),
+    /// Zlib-compressed RGB
+    data: Vec<u8>,
+}
+
+impl PdfContent {
+    /// Empty content for a page `height` points tall
+    pub fn new(height: f32) -> Self {
+        Self { height, ..Self::default() }
+    }
+
+    /// Text with its baseline's left end at (`x`, `y`); characters outside
+    /// Windows-1252 print as `?`
+    pub fn text(&mut self, x: f32, y: f32, size: f32, bold: bool, color: Color, text: &str) {
+        let font = if bold { "F2" } else { "F1" };
+        let _ = writeln!(
+            self.ops,
+            "BT {} rg /{} {} Tf {} {} Td ({}) Tj ET",
+            rgb(color),
+            font,
+            num(size),
+            num(x),
+            num(self.height - y),
+            escape(text),
+        );
+    }
+
+    /// A filled rectangle
+    pub fn fill_rect(&mut self, x: f32, y: f32, width: f32, height: f32, color: Color) {
+        let _ = writeln!(
+            self.ops,
+            "{} rg {} {} {} {} re f",
+            rgb(color),
+            num(x),
+            num(self.height - y - height),
+            num(width),
+            num(height),
+        );
+    }
+
+    /// A straight line
+    pub fn line(&mut self, from: (f32, f32), to: (f32, f32), width: f32, color: Color) {
+        let _ = writeln!(
+            self.ops,
+            "{} RG {} w {} {} m {} {} l S",
+            rgb(color),
+            num(width),
+            num(from.0),
+            num(self.height - from.1),
+            num(to.0),
+            num(self.height - to.1),
+        );
+    }
+
+    /// An RGB image of `pixels` (width, height) stretched over a rectangle
+    pub fn image(&mut self, x: f32, y: f32, width: f32, height: f32, pixels: (u32, u32), rgb: &[u8]) {
+        debug_assert_eq!(rgb.len(), pixels.0 as usize * pixels.1 as usize * 3);
+        let _ = writeln!(
+            self.ops,
+            "q {} 0 0 {} {} {} cm /Im{} Do Q",
+            num(width),
+            num(height),
+            num(x),
+            num(self.height - y - height),
+            self.images.len(),
+        );
+        self.images.push(PdfImage { pixels, data: miniz_oxide::deflate::compress_to_vec_zlib(rgb, 6) });
+    }
+}
+
+/// Estimated width of `text` in points at `size` in Helvetica, or its bold
+/// face, which runs about 6% wider
+pub fn text_width(text: &str, size: f32, bold: bool) -> f32 {
+    // Advance widths of printable ASCII, in thousandths of the font size
+    const WIDTHS: [u16; 95] = [
+        278, 278, 355, 556, 556, 889, 667, 191, 333, 333, 389, 584, 278, 333, 278, 278, // space to /
+        556, 556, 556, 556, 556, 556, 556, 556, 556, 556, 278, 278, 584, 584, 584, 556, // 0 to ?
+        1015, 667, 667, 722, 722, 667, 611, 778, 722, 278, 500, 667, 556, 833, 722, 778, // @ to O
+        667, 778, 722, 667, 611, 722, 667, 944, 667, 667, 611, 278, 278, 278, 469, 556, // P to _
+        333, 556, 556, 500, 556, 556, 278, 556, 556, 222, 222, 500, 222, 833, 556, 556, // ` to o
+        556, 556, 333, 500, 278, 556, 500, 722, 500, 500, 500, 334, 260, 334, 584, // p to ~
+    ];
+    let units: u32 = text
+        .chars()
+        .map(|c| match c as u32 {
+            code @ 32..=126 => WIDTHS[(code - 32) as usize] as u32,
+            _ => 556,
+        })
+        .sum();
+    units as f32 / 1000.0 * size * if bold { 1.06 } else { 1.0 }
+}
+
+/// Fill color operands
+fn rgb(color: Color) -> String {
+    format!("{} {} {}", num(color.r as f32 / 255.0), num(color.g as f32 / 255.0), num(color.b as f32 / 255.0))
+}
+
+/// A string literal body in Windows-1252, escaping what PDF needs escaped
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        let byte = match c {
+            '\u{20}'..='\u{7e}' | '\u{a0}'..='\u{ff}' => c as u32 as u8,
+            '•' => 0x95,
+            '–' => 0x96,
+            '—' => 0x97,
+            '…' => 0x85,
+            '‘' => 0x91,
+            '’' => 0x92,
+            '“' => 0x93,
+            '”' => 0x94,
+            _ => b'?',
+        };
+        match byte {
+            b'(' | b')' | b'\\' => {
+                escaped.push('\\');
+                escaped.push(byte as char);
+            }
+            0x20..=0x7e => escaped.push(byte as char),
+            _ => {
+                let _ = write!(escaped, "\\{:03o}", byte);
+            }
+        }
+    }
+    escaped
+}
+
+/// A stream object's body
+fn stream(header: &str, data: &[u8]) -> Vec<u8> {
+    let mut body = format!("{}\nstream\n", header).into_bytes();
+    body.extend_from_slice(data);
+    body.extend_from_slice(b"\nendstream");
+    body
+}
+
+/// A number without needless decimals
+fn num(value: f32) -> String {
+    let rounded = (value * 100.0).round() / 100.0;
+    if rounded.fract() == 0.0 {
+        format!("{}", rounded as i64)
+    } else {
+        format!("{}", rounded)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pdf_document() {
+        let mut pdf = PdfDocument::new();
+        pdf.add_page(595.28, 841.89, (2, 1), &[255, 255, 255, 0, 0, 0]);
+        let mut content = PdfContent::new(792.0);
+        content.text(72.0, 72.0, 12.0, true, Color::rgb(0, 0, 0), "Orders (v2) — café");
+        content.line((72.0, 80.0), (540.0, 80.0), 0.5, Color::rgb(0, 0, 0));
+        pdf.add_content_page(612.0, 792.0, content);
+        assert_eq!(pdf.page_count(), 2);
+        let bytes = pdf.finish();
+        let text = String::from_utf8_lossy(&bytes);
+
+        assert!(text.starts_with("%PDF-1.4"));
+        assert!(text.ends_with("%%EOF\n"));
+        assert!(text.contains("/Kids [5 0 R 8 0 R] /Count 2"));
+        assert!(text.contains("/MediaBox [0 0 595.28 841.89]"));
+        assert!(text.contains("/Width 2 /Height 1"));
+        assert!(text.contains("/F2 12 Tf 72 720 Td (Orders \\(v2\\) \\227 caf\\351) Tj"));
+        assert!(text.contains("72 712 m 540 712 l S"));
+
+        // Every object's xref offset points at its header
+        let xref = text.find("xref\n").unwrap();
+        let entries: Vec<usize> = text[xref..].lines().skip(3).take(9).map(|line| line[..10].parse().unwrap()).collect();
+        assert_eq!(entries.len(), 9);
+        for (i, offset) in entries.iter().enumerate() {
+            assert!(bytes[*offset..].starts_with(format!("{} 0 obj", i + 1).as_bytes()));
+        }
+    }
+
+    #[test]
+    fn test_text_width() {
+        assert!((text_width("Hi", 10.0, false) - 9.44).abs() < 1e-4);
+        assert!(text_width("Hi", 10.0, true) > text_width("Hi", 10.0, false));
+    }
+}
@@ -0,0 +1,222 @@
+//! PNG output
+//!
+//! Shapes are rasterized with tiny-skia and text with ab_glyph, using the
+//! same fonts the editor ships (Ubuntu, with emoji fallbacks for icons).
+
+use std::sync::OnceLock;
+
+use ab_glyph::{Font, FontRef, PxScale, ScaleFont};
+use tiny_skia::{FillRule, Paint, PathBuilder, Pixmap, PremultipliedColorU8, Stroke, Transform};
+
+use imortal_core::{EngineError, EngineResult};
+
+use crate::painter::{Align, Color, Painter, Point, Rect, Rounding};
+
+/// A [`Painter`] that draws into an RGBA pixmap
+pub struct PngPainter {
+    pixmap: Pixmap,
+}
+
+impl PngPainter {
+    /// Create a transparent image of the given size in pixels
+    pub fn new(width: u32, height: u32) -> EngineResult<Self> {
+        let pixmap = Pixmap::new(width.max(1), height.max(1))
+            .ok_or_else(|| EngineError::Serialization(format!("Cannot create a {}x{} image", width, height)))?;
+        Ok(Self { pixmap })
+    }
+
+    /// Encode the image as PNG
+    pub fn finish(self) -> EngineResult<Vec<u8>> {
+        self.pixmap.encode_png().map_err(|e| EngineError::Serialization(e.to_string()))
+    }
+
+    /// Width and height in pixels
+    pub fn size(&self) -> (u32, u32) {
+        (self.pixmap.width(), self.pixmap.height())
+    }
+
+    /// Pixels as RGB triples, row by row, dropping alpha
+    pub fn to_rgb(&self) -> Vec<u8> {
+        self.pixmap
+            .pixels()
+            .iter()
+            .flat_map(|pixel| {
+                let color = pixel.demultiply();
+                [color.red(), color.green(), color.blue()]
+            })
+            .collect()
+    }
+
+    fn fill_path(&mut self, builder: PathBuilder, color: Color) {
+        if let Some(path) = builder.finish() {
+            self.pixmap.fill_path(&path, &paint(color), FillRule::Winding, Transform::identity(), None);
+        }
+    }
+
+    fn stroke_path(&mut self, builder: PathBuilder, width: f32, color: Color) {
+        if let Some(path) = builder.finish() {
+            let stroke = Stroke { width, ..Stroke::default() };
+            self.pixmap.stroke_path(&path, &paint(color), &stroke, Transform::identity(), None);
+        }
+    }
+
+    /// Blend one pixel of glyph coverage in `color`
+    fn blend(&mut self, x: i32, y: i32, color: Color, coverage: f32) {
+        if x < 0 || y < 0 || x >= self.pixmap.width() as i32 || y >= self.pixmap.height() as i32 {
+            return;
+        }
+        let index = (y as u32 * self.pixmap.width() + x as u32) as usize;
+        let alpha = color.a as f32 / 255.0 * coverage.clamp(0.0, 1.0);
+        let dst = self.pixmap.pixels()[index];
+        let over = |src: u8, dst: u8| (src as f32 * alpha + dst as f32 * (1.0 - alpha)).round() as u8;
+        let blended = PremultipliedColorU8::from_rgba(
+            over(color.r, dst.red()),
+            over(color.g, dst.green()),
+            over(color.b, dst.blue()),
+            over(255, dst.alpha()),
+        );
+        if let Some(pixel) = blended {
+            self.pixmap.pixels_mut()[index] = pixel;
+        }
+    }
+}
+
+impl Painter for PngPainter {
+    fn fill_rect(&mut self, rect: Rect, rounding: Rounding, color: Color) {
+        let (t, b) = (rounding.top, rounding.bottom);
+        let (x0, y0, x1, y1) = (rect.min.x, rect.min.y, rect.max.x, rect.max.y);
+        let mut builder = PathBuilder::new();
+        builder.move_to(x0 + t, y0);
+        builder.line_to(x1 - t, y0);
+        builder.quad_to(x1, y0, x1, y0 + t);
+        builder.line_to(x1, y1 - b);
+        builder.quad_to(x1, y1, x1 - b, y1);
+        builder.line_to(x0 + b, y1);
+        builder.quad_to(x0, y1, x0, y1 - b);
+        builder.line_to(x0, y0 + t);
+        builder.quad_to(x0, y0, x0 + t, y0);
+        builder.close();
+        self.fill_path(builder, color);
+    }
+
+    fn stroke_rect(&mut self, rect: Rect, radius: f32, width: f32, color: Color) {
+        let (r, (x0, y0, x1, y1)) = (radius, (rect.min.x, rect.min.y, rect.max.x, rect.max.y));
+        let mut builder = PathBuilder::new();
+        builder.move_to(x0 + r, y0);
+        builder.line_to(x1 - r, y0);
+        builder.quad_to(x1, y0, x1, y0 + r);
+        builder.line_to(x1, y1 - r);
+        builder.quad_to(x1, y1, x1 - r, y1);
+        builder.line_to(x0 + r, y1);
+        builder.quad_to(x0, y1, x0, y1 - r);
+        builder.line_to(x0, y0 + r);
+        builder.quad_to(x0, y0, x0 + r, y0);
+        builder.close();
+        self.stroke_path(builder, width, color);
+    }
+
+    fn line(&mut self, points: &[Point], width: f32, color: Color) {
+        let Some((first, rest)) = points.split_first() else {
+            return;
+        };
+        let mut builder = PathBuilder::new();
+        builder.move_to(first.x, first.y);
+        for point in rest {
+            builder.line_to(point.x, point.y);
+        }
+        self.stroke_path(builder, width, color);
+    }
+
+    fn fill_circle(&mut self, center: Point, radius: f32, color: Color) {
+        let mut builder = PathBuilder::new();
+        builder.push_circle(center.x, center.y, radius);
+        self.fill_path(builder, color);
+    }
+
+    fn stroke_circle(&mut self, center: Point, radius: f32, width: f32, color: Color) {
+        let mut builder = PathBuilder::new();
+        builder.push_circle(center.x, center.y, radius);
+        self.stroke_path(builder, width, color);
+    }
+
+    fn fill_polygon(&mut self, points: &[Point], color: Color) {
+        let Some((first, rest)) = points.split_first() else {
+            return;
+        };
+        let mut builder = PathBuilder::new();
+        builder.move_to(first.x, first.y);
+        for point in rest {
+            builder.line_to(point.x, point.y);
+        }
+        builder.close();
+        self.fill_path(builder, color);
+    }
+
+    fn text(&mut self, pos: Point, align: Align, text: &str, size: f32, color: Color) {
+        let fonts = fonts();
+        let scale = PxScale::from(size);
+        let font_for = |c: char| fonts.iter().find(|f| f.glyph_id(c).0 != 0).unwrap_or(&fonts[0]);
+
+        let width: f32 = text.chars().map(|c| font_for(c).as_scaled(scale).h_advance(font_for(c).glyph_id(c))).sum();
+        let main = fonts[0].as_scaled(scale);
+        let baseline = pos.y + (main.ascent() + main.descent()) / 2.0;
+        let mut x = match align {
+            Align::Left => pos.x,
+            Align::Center => pos.x - width / 2.0,
+        };
+
+        for c in text.chars() {
+            let font = font_for(c);
+            let id = font.glyph_id(c);
+            let glyph = id.with_scale_and_position(scale, ab_glyph::point(x, baseline));
+            if let Some(outline) = font.outline_glyph(glyph) {
+                let bounds = outline.px_bounds();
+                outline.draw(|gx, gy, coverage| {
+                    self.blend(bounds.min.x as i32 + gx as i32, bounds.min.y as i32 + gy as i32, color, coverage);
+                });
+            }
+            x += font.as_scaled(scale).h_advance(id);
+        }
+    }
+}
+
+fn paint(color: Color) -> Paint<'static> {
+    let mut paint = Paint::default();
+    paint.set_color_rgba8(color.r, color.g, color.b, color.a);
+    paint.anti_alias = true;
+    paint
+}
+
+/// Text fonts in fallback order
+fn fonts() -> &'static [FontRef<'static>] {
+    static FONTS: OnceLock<Vec<FontRef<'static>>> = OnceLock::new();
+    FONTS.get_or_init(|| {
+        [
+            epaint_default_fonts::UBUNTU_LIGHT,
+            epaint_default_fonts::NOTO_EMOJI_REGULAR,
+            epaint_default_fonts::EMOJI_ICON,
+        ]
+        .into_iter()
+        .filter_map(|data| FontRef::try_from_slice(data).ok())
+        .collect()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_png_painter() {
+        let mut png = PngPainter::new(64, 32).unwrap();
+        png.fill_rect(Rect::from_min_size(Point::new(0.0, 0.0), 64.0, 32.0), Rounding::default(), Color::rgb(0, 0, 0));
+        png.text(Point::new(32.0, 16.0), Align::Center, "Hi", 20.0, Color::WHITE);
+
+        // Text leaves some bright pixels on the black background
+        assert!(png.pixmap.pixels().iter().any(|p| p.red() > 128));
+        assert_eq!(png.pixmap.pixels()[0].red(), 0);
+
+        let bytes = png.finish().unwrap();
+        assert_eq!(&bytes[..8], b"\x89PNG\r\n\x1a\n");
+    }
+}
@@ -0,0 +1,421 @@
+//! Printing with page tiling
+//!
+//! [`render_pdf`] splits a graph across as many pages as it needs at the
+//! chosen scale. Each page's printable area shows one tile of the canvas,
+//! with crop marks at its corners for trimming and joining the sheets. An
+//! optional first page shows the whole graph with the page grid over it,
+//! and a legend of the categories and connection types used.
+
+use imortal_core::{ComponentCategory, ConnectionType, EngineResult};
+use imortal_ir::{EdgeStyle, ProjectGraph};
+
+use crate::painter::{Align, Color, Painter, Point, Rect, Rounding};
+use crate::pdf::PdfDocument;
+use crate::png::PngPainter;
+use crate::scene::{self, Style, Transform};
+
+/// Points per inch, the unit of PDF page sizes
+const POINTS_PER_INCH: f32 = 72.0;
+
+/// Canvas units per inch at scale 1, as on a standard-density screen
+const UNITS_PER_INCH: f32 = 96.0;
+
+/// Space left around the content, in canvas units
+const PADDING: f32 = 20.0;
+
+/// Color of crop marks, page labels, and the legend
+const INK: Color = Color::rgb(40, 40, 45);
+
+/// Paper sizes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PaperSize {
+    #[default]
+    A4,
+    A3,
+    Letter,
+    Legal,
+}
+
+impl PaperSize {
+    pub fn all() -> &'static [PaperSize] {
+        &[PaperSize::A4, PaperSize::A3, PaperSize::Letter, PaperSize::Legal]
+    }
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            PaperSize::A4 => "A4",
+            PaperSize::A3 => "A3",
+            PaperSize::Letter => "Letter",
+            PaperSize::Legal => "Legal",
+        }
+    }
+
+    /// Portrait width and height in points
+    pub fn points(&self) -> (f32, f32) {
+        match self {
+            PaperSize::A4 => (595.28, 841.89),
+            PaperSize::A3 => (841.89, 1190.55),
+            PaperSize::Letter => (612.0, 792.0),
+            PaperSize::Legal => (612.0, 1008.0),
+        }
+    }
+}
+
+/// How to lay out and draw a printout
+#[derive(Debug, Clone, PartialEq)]
+pub struct PrintOptions {
+    pub paper: PaperSize,
+    pub landscape: bool,
+    /// Printed size relative to the canvas at zoom 1; at 1.0 a canvas unit
+    /// prints 1/96 inch wide
+    pub scale: f32,
+    /// Blank border around each page's printable area, in points
+    pub margin: f32,
+    /// Resolution pages are drawn at, in dots per inch
+    pub dpi: f32,
+    /// Mark the corners of each printable area
+    pub crop_marks: bool,
+    /// Start with an overview and legend page
+    pub legend: bool,
+    /// Colors and sizes
+    pub style: Style,
+}
+
+impl Default for PrintOptions {
+    fn default() -> Self {
+        Self {
+            paper: PaperSize::A4,
+            landscape: false,
+            scale: 1.0,
+            margin: 36.0,
+            dpi: 150.0,
+            crop_marks: true,
+            legend: true,
+            style: Style::print(),
+        }
+    }
+}
+
+/// How the canvas is split into pages
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PageLayout {
+    pub columns: u32,
+    pub rows: u32,
+    /// Canvas area the pages cover together, in canvas units
+    pub area: Rect,
+    /// Canvas width and height one page covers
+    pub tile: (f32, f32),
+}
+
+impl PageLayout {
+    /// Pages of the canvas, not counting the overview
+    pub fn page_count(&self) -> u32 {
+        self.columns * self.rows
+    }
+
+    /// Canvas area shown on the page at `column` and `row`
+    pub fn tile_rect(&self, column: u32, row: u32) -> Rect {
+        Rect::from_min_size(
+            Point::new(self.area.min.x + column as f32 * self.tile.0, self.area.min.y + row as f32 * self.tile.1),
+            self.tile.0,
+            self.tile.1,
+        )
+    }
+}
+
+impl PrintOptions {
+    /// Page width and height in points, turned for landscape
+    pub fn page_size(&self) -> (f32, f32) {
+        let (width, height) = self.paper.points();
+        if self.landscape {
+            (height, width)
+        } else {
+            (width, height)
+        }
+    }
+
+    /// Width and height of the printable area in points
+    fn printable_size(&self) -> (f32, f32) {
+        let (width, height) = self.page_size();
+        ((width - 2.0 * self.margin).max(1.0), (height - 2.0 * self.margin).max(1.0))
+    }
+
+    /// Tiles of the graph's content, centered in the pages they fill
+    pub fn layout(&self, graph: &ProjectGraph) -> PageLayout {
+        let bounds = scene::content_bounds(graph).unwrap_or_default();
+        let (printable_width, printable_height) = self.printable_size();
+        let units_per_point = UNITS_PER_INCH / POINTS_PER_INCH / self.scale.max(0.01);
+        let tile = (printable_width * units_per_point, printable_height * units_per_point);
+
+        let columns = ((bounds.width() + 2.0 * PADDING) / tile.0).ceil().max(1.0) as u32;
+        let rows = ((bounds.height() + 2.0 * PADDING) / tile.1).ceil().max(1.0) as u32;
+        let (width, height) = (columns as f32 * tile.0, rows as f32 * tile.1);
+        let center = bounds.center();
+        let area = Rect::from_min_size(Point::new(center.x - width / 2.0, center.y - height / 2.0), width, height);
+        PageLayout { columns, rows, area, tile }
+    }
+}
+
+/// Render a graph as a tiled PDF for printing
+pub fn render_pdf(graph: &ProjectGraph, options: &PrintOptions) -> EngineResult<Vec<u8>> {
+    let layout = options.layout(graph);
+    let total = layout.page_count() + u32::from(options.legend);
+    let mut pdf = PdfDocument::new();
+    let (width, height) = options.page_size();
+    let mut add = |page: Page| pdf.add_page(width, height, page.painter.size(), &page.painter.to_rgb());
+
+    if options.legend {
+        let mut page = Page::new(options)?;
+        page.overview(graph, options, &layout);
+        page.footer(&format!("{} · overview · page 1/{}", graph.meta.name, total));
+        add(page);
+    }
+    for row in 0..layout.rows {
+        for column in 0..layout.columns {
+            let number = u32::from(options.legend) + row * layout.columns + column + 1;
+            let mut page = Page::new(options)?;
+            page.tile(graph, options, layout.tile_rect(column, row));
+            page.footer(&format!(
+                "{} · page {}/{} · row {}, column {}",
+                graph.meta.name,
+                number,
+                total,
+                row + 1,
+                column + 1
+            ));
+            add(page);
+        }
+    }
+    Ok(pdf.finish())
+}
+
+/// One page being drawn
+struct Page {
+    painter: PngPainter,
+    /// Pixels per point
+    scale: f32,
+    /// Printable area in pixels
+    printable: Rect,
+    /// Whole page in pixels
+    bounds: Rect,
+    crop_marks: bool,
+    margin: f32,
+}
+
+impl Page {
+    fn new(options: &PrintOptions) -> EngineResult<Self> {
+        let scale = options.dpi / POINTS_PER_INCH;
+        let (width, height) = options.page_size();
+        let (pixel_width, pixel_height) = ((width * scale).round() as u32, (height * scale).round() as u32);
+        let mut painter = PngPainter::new(pixel_width, pixel_height)?;
+        let bounds = Rect::from_min_size(Point::default(), pixel_width as f32, pixel_height as f32);
+        painter.fill_rect(bounds, Rounding::default(), Color::WHITE);
+
+        let margin = options.margin * scale;
+        let (printable_width, printable_height) = options.printable_size();
+        let printable = Rect::from_min_size(Point::new(margin, margin), printable_width * scale, printable_height * scale);
+        Ok(Self { painter, scale, printable, bounds, crop_marks: options.crop_marks, margin })
+    }
+
+    /// Draw the canvas area `tile` into the printable area
+    fn tile(&mut self, graph: &ProjectGraph, options: &PrintOptions, tile: Rect) {
+        let zoom = self.printable.width() / tile.width();
+        let transform = Transform::new(
+            Point::new(self.printable.min.x - tile.min.x * zoom, self.printable.min.y - tile.min.y * zoom),
+            zoom,
+        );
+        scene::draw_project(&mut self.painter, graph, &options.style, transform, self.printable);
+        self.clear_margins();
+    }
+
+    /// Draw the whole graph with the page grid over it, and the legend below
+    fn overview(&mut self, graph: &ProjectGraph, options: &PrintOptions, layout: &PageLayout) {
+        let legend = legend_entries(graph);
+        let line = 14.0 * self.scale;
+        let legend_height = (legend.categories.len().max(legend.connections.len()) as f32 + 2.0) * line;
+        let available = Rect {
+            min: self.printable.min,
+            max: Point::new(self.printable.max.x, (self.printable.max.y - legend_height).max(self.printable.min.y + line)),
+        };
+
+        // The page grid, fitted into the space above the legend
+        let zoom = (available.width() / layout.area.width()).min(available.height() / layout.area.height());
+        let size = (layout.area.width() * zoom, layout.area.height() * zoom);
+        let grid = Rect::from_min_size(Point::new(available.center().x - size.0 / 2.0, available.min.y), size.0, size.1);
+        let transform = Transform::new(
+            Point::new(grid.min.x - layout.area.min.x * zoom, grid.min.y - layout.area.min.y * zoom),
+            zoom,
+        );
+        scene::draw_project(&mut self.painter, graph, &options.style, transform, grid);
+        self.clear_margins();
+        for row in 0..layout.rows {
+            for column in 0..layout.columns {
+                let tile = layout.tile_rect(column, row);
+                let rect = transform.rect(tile.min.x, tile.min.y, tile.width(), tile.height());
+                self.painter.stroke_rect(rect, 0.0, self.scale, Color::rgba(40, 40, 45, 160));
+                let number = row * layout.columns + column + 2;
+                self.painter.text(rect.center(), Align::Center, &number.to_string(), 16.0 * self.scale, Color::rgba(40, 40, 45, 160));
+            }
+        }
+
+        // Legend columns: categories, then connection types
+        let top = grid.max.y + line * 1.5;
+        let left = self.printable.min.x;
+        let middle = self.printable.center().x;
+        let size = 9.0 * self.scale;
+        self.painter.text(Point::new(left, top), Align::Left, "Categories", size * 1.1, INK);
+        for (i, category) in legend.categories.iter().enumerate() {
+            let y = top + (i as f32 + 1.0) * line;
+            let swatch = Rect::from_min_size(Point::new(left, y - size / 2.0), size * 1.6, size);
+            self.painter.fill_rect(swatch, Rounding::same(2.0 * self.scale), options.style.header_color(*category));
+            self.painter.text(Point::new(left + size * 2.2, y), Align::Left, category.display_name(), size, INK);
+        }
+        self.painter.text(Point::new(middle, top), Align::Left, "Connections", size * 1.1, INK);
+        for (i, connection) in legend.connections.iter().enumerate() {
+            let y = top + (i as f32 + 1.0) * line;
+            let style = EdgeStyle::for_connection(connection);
+            let (r, g, b) = style.color.rgb();
+            let points = [Point::new(middle, y), Point::new(middle + size * 3.5, y)];
+            let width = style.thickness * 0.75 * self.scale;
+            scene::draw_edge_path(&mut self.painter, &points, &style, width, size * 0.8, Color::rgb(r, g, b));
+            self.painter.text(Point::new(middle + size * 4.5, y), Align::Left, &connection_name(connection), size, INK);
+        }
+    }
+
+    /// Blank out drawing that spilled past the printable area, then mark
+    /// its corners
+    fn clear_margins(&mut self) {
+        let (page, area) = (self.bounds, self.printable);
+        for rect in [
+            Rect { min: page.min, max: Point::new(page.max.x, area.min.y) },
+            Rect { min: Point::new(page.min.x, area.max.y), max: page.max },
+            Rect { min: Point::new(page.min.x, area.min.y), max: Point::new(area.min.x, area.max.y) },
+            Rect { min: Point::new(area.max.x, area.min.y), max: Point::new(page.max.x, area.max.y) },
+        ] {
+            self.painter.fill_rect(rect, Rounding::default(), Color::WHITE);
+        }
+
+        if self.crop_marks {
+            let gap = 4.0 * self.scale;
+            let length = (self.margin - gap * 2.0).min(18.0 * self.scale);
+            if length > 0.0 {
+                for (corner, dx, dy) in [
+                    (area.min, -1.0, -1.0),
+                    (Point::new(area.max.x, area.min.y), 1.0, -1.0),
+                    (Point::new(area.min.x, area.max.y), -1.0, 1.0),
+                    (area.max, 1.0, 1.0),
+                ] {
+                    let horizontal = [
+                        Point::new(corner.x + dx * gap, corner.y),
+                        Point::new(corner.x + dx * (gap + length), corner.y),
+                    ];
+                    let vertical = [
+                        Point::new(corner.x, corner.y + dy * gap),
+                        Point::new(corner.x, corner.y + dy * (gap + length)),
+                    ];
+                    self.painter.line(&horizontal, 0.5 * self.scale, INK);
+                    self.painter.line(&vertical, 0.5 * self.scale, INK);
+                }
+            }
+        }
+    }
+
+    /// Label the page in the middle of its bottom margin, clear of the
+    /// crop marks, if there is room
+    fn footer(&mut self, label: &str) {
+        let size = 8.0 * self.scale;
+        if self.margin >= size * 2.0 {
+            let y = self.printable.max.y + self.margin / 2.0;
+            self.painter.text(Point::new(self.printable.center().x, y), Align::Center, label, size, INK);
+        }
+    }
+}
+
+/// Categories and connection types a graph uses
+struct Legend {
+    categories: Vec<ComponentCategory>,
+    connections: Vec<ConnectionType>,
+}
+
+fn legend_entries(graph: &ProjectGraph) -> Legend {
+    let categories = ComponentCategory::all()
+        .iter()
+        .copied()
+        .filter(|category| graph.nodes.values().any(|node| node.category == *category))
+        .collect();
+    let mut connections: Vec<ConnectionType> = Vec::new();
+    let mut edges: Vec<_> = graph.edges.values().collect();
+    edges.sort_by_key(|edge| edge.id);
+    for edge in edges {
+        if !connections.contains(&edge.connection_type) {
+            connections.push(edge.connection_type.clone());
+        }
+    }
+    Legend { categories, connections }
+}
+
+/// Legend label of a connection type
+fn connection_name(connection: &ConnectionType) -> String {
+    match connection {
+        ConnectionType::DataFlow => "Data flow".to_string(),
+        ConnectionType::Navigation => "Navigation".to_string(),
+        ConnectionType::Relationship(relation) => format!("Relationship ({})", relation.display_name()),
+        ConnectionType::Trigger => "Trigger".to_string(),
+        ConnectionType::Dependency => "Dependency".to_string(),
+        ConnectionType::Inheritance => "Inheritance".to_string(),
+        ConnectionType::Caches => "Caches".to_string(),
+        ConnectionType::Gates => "Gates".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use imortal_ir::{Edge, Node};
+
+    fn wide_graph() -> ProjectGraph {
+        let mut graph = ProjectGraph::with_name("shop");
+        let user = graph.add_node(Node::new_entity("User"));
+        let mut order = Node::new_entity("Order");
+        order.position.x = 1500.0;
+        let order = graph.add_node(order);
+        graph.add_edge(Edge::dependency(user, order)).unwrap();
+        graph
+    }
+
+    #[test]
+    fn test_layout_tiles_content() {
+        let graph = wide_graph();
+        let bounds = scene::content_bounds(&graph).unwrap();
+        let options = PrintOptions::default();
+        let layout = options.layout(&graph);
+
+        // A4 portrait less half-inch margins, in canvas units at scale 1
+        assert!((layout.tile.0 - (595.28 - 72.0) * 96.0 / 72.0).abs() < 0.01);
+        assert_eq!((layout.columns, layout.rows), (3, 1));
+        assert!(layout.area.min.x < bounds.min.x && layout.area.max.x > bounds.max.x);
+        assert_eq!(layout.tile_rect(1, 0).min.x, layout.area.min.x + layout.tile.0);
+
+        // Landscape and a smaller scale fit it on one page
+        let fitted = PrintOptions { landscape: true, scale: 0.5, ..PrintOptions::default() };
+        assert_eq!(fitted.layout(&graph).page_count(), 1);
+        assert_eq!(fitted.page_size(), (841.89, 595.28));
+    }
+
+    #[test]
+    fn test_render_pdf() {
+        let graph = wide_graph();
+        let options = PrintOptions { dpi: 36.0, ..PrintOptions::default() };
+        let pdf = render_pdf(&graph, &options).unwrap();
+        let text = String::from_utf8_lossy(&pdf);
+        assert!(text.starts_with("%PDF"));
+        assert!(text.contains("/Count 4"), "an overview page and three tiles");
+
+        let legend = legend_entries(&graph);
+        assert_eq!(legend.categories, vec![ComponentCategory::Data]);
+        assert_eq!(legend.connections, vec![ConnectionType::Dependency]);
+
+        let plain = render_pdf(&graph, &PrintOptions { legend: false, ..options }).unwrap();
+        assert!(String::from_utf8_lossy(&plain).contains("/Count 3"));
+    }
+}
@@ -0,0 +1,632 @@
+//! Drawing a project graph
+//!
+//! Draws the background grid, groups, edges, and nodes of a [`ProjectGraph`]
+//! onto any [`Painter`]. The editor canvas and the headless renderers share
+//! this code, so exported images look like the canvas.
+
+use std::collections::HashMap;
+
+use imortal_core::{ComponentCategory, DataType, IconSource};
+use imortal_ir::{ArrowStyle, Edge, EdgeStyle, Group, LineStyle, Node, ProjectGraph};
+
+use crate::painter::{Align, Color, Painter, Point, Rect, Rounding};
+
+/// Colors and sizes used when drawing a graph
+#[derive(Debug, Clone, PartialEq)]
+pub struct Style {
+    /// Background color
+    pub background_color: Color,
+    /// Whether to draw the grid
+    pub show_grid: bool,
+    /// Grid size in canvas units
+    pub grid_size: f32,
+    /// Grid color
+    pub grid_color: Color,
+    /// Node background color
+    pub node_background_color: Color,
+    /// Node selected background color
+    pub node_selected_color: Color,
+    /// Node border color
+    pub node_border_color: Color,
+    /// Node selected border color
+    pub node_selected_border_color: Color,
+    /// Node border width
+    pub node_border_width: f32,
+    /// Node corner radius
+    pub node_corner_radius: f32,
+    /// Node header height
+    pub node_header_height: f32,
+    /// Port radius
+    pub port_radius: f32,
+    /// Width of edges of the default thickness; others scale with it
+    pub edge_width: f32,
+    /// Edge selected color
+    pub edge_selected_color: Color,
+    /// Arrow size
+    pub arrow_size: f32,
+    /// Highlight selected nodes and edges
+    pub show_selection: bool,
+    /// Per-category overrides of the header color, corner radius, and icon
+    pub categories: HashMap<ComponentCategory, CategoryStyle>,
+}
+
+/// How nodes of one category differ from the defaults; `None` keeps the default
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CategoryStyle {
+    /// Header color instead of [`category_color`]
+    pub header_color: Option<Color>,
+    /// Corner radius instead of [`Style::node_corner_radius`]
+    pub corner_radius: Option<f32>,
+    /// Icon for nodes without their own, instead of the category's emoji
+    pub icon: Option<String>,
+}
+
+impl Default for Style {
+    fn default() -> Self {
+        Self {
+            background_color: Color::rgb(30, 30, 35),
+            show_grid: true,
+            grid_size: 20.0,
+            grid_color: Color::rgba(255, 255, 255, 20),
+            node_background_color: Color::rgb(50, 50, 55),
+            node_selected_color: Color::rgb(60, 80, 120),
+            node_border_color: Color::rgb(80, 80, 85),
+            node_selected_border_color: Color::rgb(100, 150, 255),
+            node_border_width: 2.0,
+            node_corner_radius: 5.0,
+            node_header_height: 25.0,
+            port_radius: 6.0,
+            edge_width: 2.0,
+            edge_selected_color: Color::rgb(100, 200, 255),
+            arrow_size: 10.0,
+            show_selection: true,
+            categories: HashMap::new(),
+        }
+    }
+}
+
+impl Style {
+    /// Light colors for paper: a white background and no grid
+    pub fn print() -> Self {
+        Self {
+            background_color: Color::WHITE,
+            show_grid: false,
+            node_background_color: Color::rgb(246, 246, 248),
+            node_border_color: Color::rgb(120, 120, 130),
+            show_selection: false,
+            ..Self::default()
+        }
+    }
+
+    /// Header color of nodes in `category`
+    pub fn header_color(&self, category: ComponentCategory) -> Color {
+        self.categories
+            .get(&category)
+            .and_then(|style| style.header_color)
+            .unwrap_or_else(|| category_color(category))
+    }
+
+    /// Corner radius of nodes in `category`
+    pub fn corner_radius(&self, category: ComponentCategory) -> f32 {
+        self.categories
+            .get(&category)
+            .and_then(|style| style.corner_radius)
+            .unwrap_or(self.node_corner_radius)
+    }
+
+    /// Icon of a node, falling back to its category's
+    pub fn node_icon<'a>(&'a self, node: &'a Node) -> Option<&'a str> {
+        node.icon
+            .as_deref()
+            .or_else(|| self.categories.get(&node.category).and_then(|style| style.icon.as_deref()))
+    }
+}
+
+/// Header color of disabled nodes
+pub const DISABLED_HEADER_COLOR: Color = Color::rgb(90, 90, 90);
+
+/// Smallest text drawn, in output units; smaller labels are left out
+pub const MIN_TEXT_SIZE: f32 = 6.0;
+
+/// Mapping from canvas units to output pixels
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Transform {
+    /// Output position of the canvas origin
+    pub offset: Point,
+    /// Output pixels per canvas unit
+    pub zoom: f32,
+    /// Physical pixels per output unit, for text and lines drawn on high-DPI screens
+    pub pixels_per_point: f32,
+}
+
+impl Transform {
+    pub fn new(offset: Point, zoom: f32) -> Self {
+        Self { offset, zoom, pixels_per_point: 1.0 }
+    }
+
+    /// Set the physical pixels per output unit
+    pub fn with_pixels_per_point(mut self, pixels_per_point: f32) -> Self {
+        self.pixels_per_point = pixels_per_point;
+        self
+    }
+
+    /// Output size of text `size` canvas units tall; see [`font_size`]
+    pub fn font_size(&self, size: f32) -> Option<f32> {
+        font_size(size, self.zoom, self.pixels_per_point)
+    }
+
+    /// Output width of a line `width` canvas units wide; see [`stroke_width`]
+    pub fn stroke_width(&self, width: f32) -> f32 {
+        stroke_width(width, self.zoom, self.pixels_per_point)
+    }
+
+    /// Map a canvas position to output coordinates
+    pub fn apply(&self, x: f32, y: f32) -> Point {
+        Point::new(self.offset.x + x * self.zoom, self.offset.y + y * self.zoom)
+    }
+
+    /// Map a canvas rectangle to output coordinates
+    pub fn rect(&self, x: f32, y: f32, width: f32, height: f32) -> Rect {
+        Rect::from_min_size(self.apply(x, y), width * self.zoom, height * self.zoom)
+    }
+}
+
+/// Size to draw text `size` canvas units tall at `zoom`, or `None` when it
+/// would be smaller than [`MIN_TEXT_SIZE`] and unreadable
+///
+/// Sizes snap to whole physical pixels, and to coarser steps for large
+/// text, so zooming reuses a few crisply rasterized sizes instead of
+/// a new, blurry one per frame.
+pub fn font_size(size: f32, zoom: f32, pixels_per_point: f32) -> Option<f32> {
+    let points = size * zoom;
+    if points < MIN_TEXT_SIZE {
+        return None;
+    }
+    let pixels = points * pixels_per_point;
+    let step = if pixels < 32.0 {
+        1.0
+    } else if pixels < 64.0 {
+        2.0
+    } else {
+        4.0
+    };
+    Some((pixels / step).round() * step / pixels_per_point)
+}
+
+/// Width to draw a line `width` canvas units wide at `zoom`
+///
+/// Never thinner than one physical pixel, so lines stay visible when zoomed
+/// out, and snapped to half pixels so they don't shimmer while zooming.
+pub fn stroke_width(width: f32, zoom: f32, pixels_per_point: f32) -> f32 {
+    let pixels = (width * zoom * pixels_per_point).max(1.0);
+    (pixels * 2.0).round() / 2.0 / pixels_per_point
+}
+
+/// Draw a whole project into `clip`
+pub fn draw_project(painter: &mut impl Painter, graph: &ProjectGraph, style: &Style, transform: Transform, clip: Rect) {
+    painter.fill_rect(clip, Rounding::default(), style.background_color);
+
+    if style.show_grid {
+        draw_grid(painter, style, transform, clip);
+    }
+
+    // Sorted so exported images are stable between runs
+    let mut groups: Vec<&Group> = graph.groups.values().collect();
+    groups.sort_by_key(|g| g.id);
+    for group in groups {
+        draw_group(painter, style, transform, group);
+    }
+
+    let mut edges: Vec<&Edge> = graph.edges.values().collect();
+    edges.sort_by_key(|e| e.id);
+    for edge in edges {
+        draw_edge(painter, graph, style, transform, edge);
+    }
+
+    let mut nodes: Vec<&Node> = graph.nodes.values().collect();
+    nodes.sort_by_key(|n| n.id);
+    for node in nodes {
+        draw_node(painter, style, transform, clip, node);
+    }
+}
+
+/// Bounding box of all nodes and groups, in canvas units
+pub fn content_bounds(graph: &ProjectGraph) -> Option<Rect> {
+    let nodes = graph.nodes.values().map(|n| {
+        let (x, y, width, height) = n.effective_bounds();
+        Rect::from_min_size(Point::new(x, y), width, height)
+    });
+    let groups = graph.groups.values().map(|g| {
+        Rect::from_min_size(Point::new(g.position.x, g.position.y), g.size.width, g.size.height)
+    });
+    nodes.chain(groups).reduce(|a, b| a.union(&b))
+}
+
+/// Draw the background grid
+pub fn draw_grid(painter: &mut impl Painter, style: &Style, transform: Transform, clip: Rect) {
+    let grid_size = style.grid_size * transform.zoom;
+    if grid_size < 2.0 {
+        return;
+    }
+
+    let start_x = ((clip.min.x - transform.offset.x) / grid_size).floor() * grid_size + transform.offset.x;
+    let start_y = ((clip.min.y - transform.offset.y) / grid_size).floor() * grid_size + transform.offset.y;
+
+    let mut x = start_x;
+    while x < clip.max.x {
+        painter.line(&[Point::new(x, clip.min.y), Point::new(x, clip.max.y)], 1.0, style.grid_color);
+        x += grid_size;
+    }
+
+    let mut y = start_y;
+    while y < clip.max.y {
+        painter.line(&[Point::new(clip.min.x, y), Point::new(clip.max.x, y)], 1.0, style.grid_color);
+        y += grid_size;
+    }
+}
+
+/// Draw a node with its header and ports
+pub fn draw_node(painter: &mut impl Painter, style: &Style, transform: Transform, clip: Rect, node: &Node) {
+    let (x, y, width, height) = node.effective_bounds();
+    let node_rect = transform.rect(x, y, width, height);
+
+    // Skip if not visible
+    if !clip.intersects(&node_rect) {
+        return;
+    }
+
+    let selected = style.show_selection && node.selected;
+    let radius = style.corner_radius(node.category);
+    let bg_color = if selected { style.node_selected_color } else { style.node_background_color };
+    painter.fill_rect(node_rect, Rounding::same(radius), bg_color);
+
+    let border_color = if selected { style.node_selected_border_color } else { style.node_border_color };
+    painter.stroke_rect(node_rect, radius, style.node_border_width, border_color);
+
+    // Header
+    let header_height = style.node_header_height * transform.zoom;
+    let header_rect = Rect::from_min_size(node_rect.min, node_rect.width(), header_height);
+    let header_color = if node.enabled { style.header_color(node.category) } else { DISABLED_HEADER_COLOR };
+    painter.fill_rect(header_rect, Rounding { top: radius, bottom: 0.0 }, header_color);
+
+    // Image icons are drawn by the editor; here they fall back to the category's emoji
+    if let Some(size) = transform.font_size(14.0) {
+        let icon = style.node_icon(node).map_or("📦", |icon| IconSource::parse(icon).text_or(node.category.icon()));
+        painter.text(header_rect.center(), Align::Center, &format!("{} {}", icon, node.name), size, Color::WHITE);
+    }
+
+    draw_node_ports(painter, style, transform, node_rect, node);
+}
+
+/// Draw the input ports down the left side of a node and the outputs down the right
+fn draw_node_ports(painter: &mut impl Painter, style: &Style, transform: Transform, node_rect: Rect, node: &Node) {
+    let port_radius = style.port_radius * transform.zoom;
+    let header_height = style.node_header_height * transform.zoom;
+    let body_height = node_rect.height() - header_height;
+
+    let sides = [(node_rect.min.x, &node.ports.inputs), (node_rect.max.x, &node.ports.outputs)];
+    for (x, ports) in sides {
+        let count = ports.len() as f32;
+        for (i, port) in ports.iter().enumerate() {
+            let y = node_rect.min.y + header_height + (i as f32 + 1.0) * body_height / (count + 1.0);
+            let center = Point::new(x, y);
+            painter.fill_circle(center, port_radius, port_color(&port.data_type));
+            painter.stroke_circle(center, port_radius, 1.0, Color::WHITE);
+        }
+    }
+}
+
+/// Draw an edge as a bezier curve with an arrow head
+pub fn draw_edge(painter: &mut impl Painter, graph: &ProjectGraph, style: &Style, transform: Transform, edge: &Edge) {
+    let (Some(from_node), Some(to_node)) = (graph.get_node(edge.from_node), graph.get_node(edge.to_node)) else {
+        return;
+    };
+
+    let (fx, fy, fw, fh) = from_node.effective_bounds();
+    let (tx, ty, _, th) = to_node.effective_bounds();
+    let start = transform.apply(fx + fw, fy + fh / 2.0);
+    let end = transform.apply(tx, ty + th / 2.0);
+
+    let color = if style.show_selection && edge.selected {
+        style.edge_selected_color
+    } else {
+        let (r, g, b) = edge.style.color.rgb();
+        Color::rgb(r, g, b)
+    };
+
+    let control_offset = ((end.x - start.x).abs() / 2.0).max(50.0 * transform.zoom);
+    let control1 = Point::new(start.x + control_offset, start.y);
+    let control2 = Point::new(end.x - control_offset, end.y);
+
+    // Approximate the curve with line segments
+    let segments = 20;
+    let points: Vec<Point> = (0..=segments)
+        .map(|i| cubic_bezier(start, control1, control2, end, i as f32 / segments as f32))
+        .collect();
+    let thickness = style.edge_width * edge.style.thickness / EdgeStyle::default().thickness;
+    let width = transform.stroke_width(thickness);
+    draw_edge_path(painter, &points, &edge.style, width, style.arrow_size * transform.zoom, color);
+}
+
+/// Draw an edge along `points` in its line style, `width` wide, with the
+/// arrow heads its style asks for, `arrow_size` long
+pub fn draw_edge_path(
+    painter: &mut impl Painter,
+    points: &[Point],
+    edge_style: &EdgeStyle,
+    width: f32,
+    arrow_size: f32,
+    color: Color,
+) {
+    let [first, second, ..] = points else {
+        return;
+    };
+    match edge_style.line_style {
+        LineStyle::Solid => painter.line(points, width, color),
+        LineStyle::Dashed => {
+            for dash in dashes(points, width * 4.0, width * 3.0) {
+                painter.line(&dash, width, color);
+            }
+        }
+        LineStyle::Dotted => {
+            for dot in points_along(points, width * 2.5) {
+                painter.fill_circle(dot, width * 0.75, color);
+            }
+        }
+    }
+
+    let (last, before_last) = (points[points.len() - 1], points[points.len() - 2]);
+    draw_arrow_head(painter, last, before_last, edge_style.arrow_end, arrow_size, width, color);
+    draw_arrow_head(painter, *first, *second, edge_style.arrow_start, arrow_size, width, color);
+}
+
+/// Pieces of a polyline `dash` long with `gap` between them
+fn dashes(points: &[Point], dash: f32, gap: f32) -> Vec<Vec<Point>> {
+    let mut result = Vec::new();
+    let mut current = Vec::new();
+    // Distance into the current dash-and-gap period
+    let mut phase = 0.0;
+    for pair in points.windows(2) {
+        let (a, b) = (pair[0], pair[1]);
+        let length = distance(a, b);
+        let at = |d: f32| Point::new(a.x + (b.x - a.x) * d / length, a.y + (b.y - a.y) * d / length);
+        let mut d = 0.0;
+        while d < length {
+            if phase < dash {
+                if current.is_empty() {
+                    current.push(at(d));
+                }
+                let step = (dash - phase).min(length - d);
+                d += step;
+                phase += step;
+                current.push(at(d));
+                if phase >= dash {
+                    result.push(std::mem::take(&mut current));
+                }
+            } else {
+                let step = (dash + gap - phase).min(length - d);
+                d += step;
+                phase += step;
+                if phase >= dash + gap {
+                    phase = 0.0;
+                }
+            }
+        }
+    }
+    if current.len() > 1 {
+        result.push(current);
+    }
+    result
+}
+
+/// Points every `spacing` along a polyline, starting with its first point
+fn points_along(points: &[Point], spacing: f32) -> Vec<Point> {
+    let mut result = points.first().copied().into_iter().collect::<Vec<_>>();
+    // Distance left before the next point
+    let mut remaining = spacing;
+    for pair in points.windows(2) {
+        let (a, b) = (pair[0], pair[1]);
+        let length = distance(a, b);
+        let mut d = remaining;
+        while d <= length {
+            result.push(Point::new(a.x + (b.x - a.x) * d / length, a.y + (b.y - a.y) * d / length));
+            d += spacing;
+        }
+        remaining = d - length;
+    }
+    result
+}
+
+fn distance(a: Point, b: Point) -> f32 {
+    ((b.x - a.x).powi(2) + (b.y - a.y).powi(2)).sqrt()
+}
+
+/// Draw an arrow head of `arrow` style with its tip at `tip`, pointing away
+/// from `from`
+fn draw_arrow_head(
+    painter: &mut impl Painter,
+    tip: Point,
+    from: Point,
+    arrow: ArrowStyle,
+    size: f32,
+    width: f32,
+    color: Color,
+) {
+    let (dx, dy) = (tip.x - from.x, tip.y - from.y);
+    let length = distance(from, tip);
+    if length == 0.0 || arrow == ArrowStyle::None {
+        return;
+    }
+    let (dx, dy) = (dx / length, dy / length);
+    // `back` units behind the tip and `side` units to either side
+    let at = |back: f32, side: f32| Point::new(tip.x - dx * back - dy * side, tip.y - dy * back + dx * side);
+    let half = size * 0.5;
+    match arrow {
+        ArrowStyle::None => {}
+        ArrowStyle::Arrow => painter.line(&[at(size, half), tip, at(size, -half)], width, color),
+        ArrowStyle::FilledArrow => painter.fill_polygon(&[tip, at(size, half), at(size, -half)], color),
+        ArrowStyle::Triangle => painter.line(&[tip, at(size, half), at(size, -half), tip], width, color),
+        ArrowStyle::Diamond => {
+            painter.line(&[tip, at(half, half * 0.6), at(size, 0.0), at(half, -half * 0.6), tip], width, color)
+        }
+        ArrowStyle::FilledDiamond => {
+            painter.fill_polygon(&[tip, at(half, half * 0.6), at(size, 0.0), at(half, -half * 0.6)], color)
+        }
+        ArrowStyle::Circle => painter.stroke_circle(at(half * 0.6, 0.0), half * 0.6, width, color),
+        ArrowStyle::FilledCircle => painter.fill_circle(at(half * 0.6, 0.0), half * 0.6, color),
+        // Crow's foot: three prongs spreading out to the target
+        ArrowStyle::ManyArrow => {
+            for side in [half, 0.0, -half] {
+                painter.line(&[at(size, 0.0), at(0.0, side)], width, color);
+            }
+        }
+    }
+}
+
+/// Draw a group as a translucent box with an optional title
+pub fn draw_group(painter: &mut impl Painter, _style: &Style, transform: Transform, group: &Group) {
+    let group_rect = transform.rect(group.position.x, group.position.y, group.size.width, group.size.height);
+
+    let (r, g, b) = group.color.rgb();
+    let opaque = Color::rgb(r, g, b);
+    painter.fill_rect(group_rect, Rounding::same(8.0), Color::rgba(r, g, b, (group.opacity * 255.0) as u8));
+    painter.stroke_rect(group_rect, 8.0, 1.0, opaque);
+
+    if let Some(size) = transform.font_size(12.0).filter(|_| group.show_header) {
+        painter.text(Point::new(group_rect.min.x + 10.0, group_rect.min.y + 15.0), Align::Left, &group.name, size, opaque);
+    }
+}
+
+/// Calculate a point on a cubic bezier curve
+pub fn cubic_bezier(p0: Point, p1: Point, p2: Point, p3: Point, t: f32) -> Point {
+    let t2 = t * t;
+    let t3 = t2 * t;
+    let mt = 1.0 - t;
+    let mt2 = mt * mt;
+    let mt3 = mt2 * mt;
+
+    Point::new(
+        mt3 * p0.x + 3.0 * mt2 * t * p1.x + 3.0 * mt * t2 * p2.x + t3 * p3.x,
+        mt3 * p0.y + 3.0 * mt2 * t * p1.y + 3.0 * mt * t2 * p2.y + t3 * p3.y,
+    )
+}
+
+/// Get color for a component category
+pub fn category_color(category: ComponentCategory) -> Color {
+    match category {
+        ComponentCategory::Auth => Color::rgb(200, 80, 80),
+        ComponentCategory::Data => Color::rgb(80, 150, 200),
+        ComponentCategory::Api => Color::rgb(150, 200, 80),
+        ComponentCategory::Storage => Color::rgb(200, 150, 80),
+        ComponentCategory::Logic => Color::rgb(200, 80, 200),
+        ComponentCategory::Ui => Color::rgb(80, 200, 200),
+        ComponentCategory::Embedded => Color::rgb(200, 200, 80),
+        ComponentCategory::Config => Color::rgb(120, 120, 200),
+        ComponentCategory::Service => Color::rgb(80, 180, 140),
+        ComponentCategory::Custom => Color::rgb(100, 100, 100),
+    }
+}
+
+/// Get color for a port based on data type
+pub fn port_color(data_type: &DataType) -> Color {
+    match data_type {
+        DataType::String | DataType::Text => Color::rgb(255, 200, 100),
+        DataType::Int32 | DataType::Int64 => Color::rgb(100, 200, 255),
+        DataType::Float32 | DataType::Float64 => Color::rgb(100, 255, 200),
+        DataType::Bool => Color::rgb(255, 100, 100),
+        DataType::Entity(_) | DataType::Reference(_) => Color::rgb(200, 100, 255),
+        DataType::Array(_) => Color::rgb(255, 150, 200),
+        DataType::Trigger => Color::rgb(255, 255, 100),
+        DataType::Any => Color::WHITE,
+        _ => Color::rgb(150, 150, 150),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_category_overrides() {
+        let mut style = Style::default();
+        let mut node = Node::new_entity("User");
+        node.icon = None;
+        assert_eq!(style.header_color(ComponentCategory::Data), category_color(ComponentCategory::Data));
+        assert_eq!(style.corner_radius(ComponentCategory::Data), style.node_corner_radius);
+        assert_eq!(style.node_icon(&node), None);
+
+        style.categories.insert(
+            ComponentCategory::Data,
+            CategoryStyle { header_color: Some(Color::rgb(1, 2, 3)), corner_radius: Some(0.0), icon: Some("🗃".into()) },
+        );
+        assert_eq!(style.header_color(ComponentCategory::Data), Color::rgb(1, 2, 3));
+        assert_eq!(style.corner_radius(ComponentCategory::Data), 0.0);
+        assert_eq!(style.header_color(ComponentCategory::Api), category_color(ComponentCategory::Api));
+        assert_eq!(style.node_icon(&node), Some("🗃"));
+        // A node's own icon wins
+        node.icon = Some("👤".into());
+        assert_eq!(style.node_icon(&node), Some("👤"));
+    }
+
+    #[test]
+    fn test_cubic_bezier() {
+        let p0 = Point::new(0.0, 0.0);
+        let p1 = Point::new(100.0, 0.0);
+        let p2 = Point::new(100.0, 100.0);
+        let p3 = Point::new(200.0, 100.0);
+
+        assert_eq!(cubic_bezier(p0, p1, p2, p3, 0.0), p0);
+        assert_eq!(cubic_bezier(p0, p1, p2, p3, 1.0), p3);
+    }
+
+    #[test]
+    fn test_font_and_stroke_scaling() {
+        // Snapped to whole physical pixels, then coarser steps
+        assert_eq!(font_size(14.0, 1.03, 1.0), Some(14.0));
+        assert_eq!(font_size(14.0, 1.03, 2.0), Some(14.5));
+        assert_eq!(font_size(14.0, 3.1, 1.0), Some(44.0));
+        // Too small to read
+        assert_eq!(font_size(14.0, 0.4, 2.0), None);
+
+        assert_eq!(stroke_width(2.0, 1.0, 1.0), 2.0);
+        assert_eq!(stroke_width(2.0, 0.1, 2.0), 0.5);
+        assert_eq!(stroke_width(2.0, 0.6, 1.0), 1.0);
+
+        let transform = Transform::new(Point::new(0.0, 0.0), 2.0).with_pixels_per_point(1.5);
+        assert_eq!(transform.font_size(12.0), font_size(12.0, 2.0, 1.5));
+    }
+
+    #[test]
+    fn test_dashes_and_dots() {
+        let line = [Point::new(0.0, 0.0), Point::new(10.0, 0.0), Point::new(20.0, 0.0)];
+        let pieces = dashes(&line, 4.0, 3.0);
+        // 0-4, 7-11 (across the corner), 14-18
+        assert_eq!(pieces.len(), 3);
+        assert_eq!(pieces[1], vec![Point::new(7.0, 0.0), Point::new(10.0, 0.0), Point::new(11.0, 0.0)]);
+        assert_eq!(pieces[2].last(), Some(&Point::new(18.0, 0.0)));
+
+        let dots = points_along(&line, 5.0);
+        assert_eq!(dots.iter().map(|p| p.x).collect::<Vec<_>>(), [0.0, 5.0, 10.0, 15.0, 20.0]);
+        assert!(dashes(&line[..1], 4.0, 3.0).is_empty());
+    }
+
+    #[test]
+    fn test_content_bounds() {
+        let mut graph = ProjectGraph::with_name("test");
+        assert_eq!(content_bounds(&graph), None);
+
+        let mut a = Node::new_entity("A");
+        a.position.x = -100.0;
+        a.position.y = 50.0;
+        graph.add_node(a);
+        let mut b = Node::new_entity("B");
+        b.position.x = 300.0;
+        b.position.y = 200.0;
+        let (_, _, width, height) = b.effective_bounds();
+        graph.add_node(b);
+
+        let bounds = content_bounds(&graph).unwrap();
+        assert_eq!(bounds.min, Point::new(-100.0, 50.0));
+        assert_eq!(bounds.max, Point::new(300.0 + width, 200.0 + height));
+    }
+}
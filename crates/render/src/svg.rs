@@ -0,0 +1,148 @@
+//! SVG output
+
+use std::fmt::Write;
+
+use crate::painter::{Align, Color, Painter, Point, Rect, Rounding};
+
+/// A [`Painter`] that builds an SVG document
+#[derive(Debug, Clone)]
+pub struct SvgPainter {
+    width: u32,
+    height: u32,
+    body: String,
+}
+
+impl SvgPainter {
+    /// Start an empty document of the given size in pixels
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            body: String::new(),
+        }
+    }
+
+    /// Finish the document
+    pub fn finish(self) -> String {
+        format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\" \
+             font-family=\"Ubuntu, 'Segoe UI', Helvetica, Arial, sans-serif\">\n{body}</svg>\n",
+            w = self.width,
+            h = self.height,
+            body = self.body,
+        )
+    }
+}
+
+impl Painter for SvgPainter {
+    fn fill_rect(&mut self, rect: Rect, rounding: Rounding, color: Color) {
+        if rounding.top == rounding.bottom {
+            let _ = writeln!(
+                self.body,
+                "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" rx=\"{}\"{}/>",
+                num(rect.min.x), num(rect.min.y), num(rect.width()), num(rect.height()), num(rounding.top), fill(color),
+            );
+            return;
+        }
+
+        let (t, b) = (rounding.top, rounding.bottom);
+        let (x0, y0, x1, y1) = (rect.min.x, rect.min.y, rect.max.x, rect.max.y);
+        let _ = writeln!(
+            self.body,
+            "<path d=\"M{} {} H{} Q{} {} {} {} V{} Q{} {} {} {} H{} Q{} {} {} {} V{} Q{} {} {} {} Z\"{}/>",
+            num(x0 + t), num(y0), num(x1 - t), num(x1), num(y0), num(x1), num(y0 + t),
+            num(y1 - b), num(x1), num(y1), num(x1 - b), num(y1),
+            num(x0 + b), num(x0), num(y1), num(x0), num(y1 - b),
+            num(y0 + t), num(x0), num(y0), num(x0 + t), num(y0),
+            fill(color),
+        );
+    }
+
+    fn stroke_rect(&mut self, rect: Rect, radius: f32, width: f32, color: Color) {
+        let _ = writeln!(
+            self.body,
+            "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" rx=\"{}\" fill=\"none\"{}/>",
+            num(rect.min.x), num(rect.min.y), num(rect.width()), num(rect.height()), num(radius), stroke(color, width),
+        );
+    }
+
+    fn line(&mut self, points: &[Point], width: f32, color: Color) {
+        let _ = writeln!(self.body, "<polyline points=\"{}\" fill=\"none\"{}/>", points_attr(points), stroke(color, width));
+    }
+
+    fn fill_circle(&mut self, center: Point, radius: f32, color: Color) {
+        let _ = writeln!(self.body, "<circle cx=\"{}\" cy=\"{}\" r=\"{}\"{}/>", num(center.x), num(center.y), num(radius), fill(color));
+    }
+
+    fn stroke_circle(&mut self, center: Point, radius: f32, width: f32, color: Color) {
+        let _ = writeln!(
+            self.body,
+            "<circle cx=\"{}\" cy=\"{}\" r=\"{}\" fill=\"none\"{}/>",
+            num(center.x), num(center.y), num(radius), stroke(color, width),
+        );
+    }
+
+    fn fill_polygon(&mut self, points: &[Point], color: Color) {
+        let _ = writeln!(self.body, "<polygon points=\"{}\"{}/>", points_attr(points), fill(color));
+    }
+
+    fn text(&mut self, pos: Point, align: Align, text: &str, size: f32, color: Color) {
+        let anchor = match align {
+            Align::Left => "start",
+            Align::Center => "middle",
+        };
+        let _ = writeln!(
+            self.body,
+            "<text x=\"{}\" y=\"{}\" font-size=\"{}\" text-anchor=\"{}\" dominant-baseline=\"central\"{}>{}</text>",
+            num(pos.x), num(pos.y), num(size), anchor, fill(color), escape(text),
+        );
+    }
+}
+
+/// Format a coordinate with at most two decimals
+fn num(value: f32) -> String {
+    let rounded = (value * 100.0).round() / 100.0;
+    if rounded == 0.0 { "0".to_string() } else { rounded.to_string() }
+}
+
+fn points_attr(points: &[Point]) -> String {
+    points.iter().map(|p| format!("{},{}", num(p.x), num(p.y))).collect::<Vec<_>>().join(" ")
+}
+
+fn fill(color: Color) -> String {
+    let mut attr = format!(" fill=\"#{:02x}{:02x}{:02x}\"", color.r, color.g, color.b);
+    if color.a < 255 {
+        let _ = write!(attr, " fill-opacity=\"{}\"", num(color.a as f32 / 255.0));
+    }
+    attr
+}
+
+fn stroke(color: Color, width: f32) -> String {
+    let mut attr = format!(" stroke=\"#{:02x}{:02x}{:02x}\" stroke-width=\"{}\"", color.r, color.g, color.b, num(width));
+    if color.a < 255 {
+        let _ = write!(attr, " stroke-opacity=\"{}\"", num(color.a as f32 / 255.0));
+    }
+    attr
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_svg_elements() {
+        let mut svg = SvgPainter::new(200, 100);
+        svg.fill_rect(Rect::from_min_size(Point::new(0.0, 0.0), 200.0, 100.0), Rounding::same(4.0), Color::rgba(255, 0, 0, 128));
+        svg.text(Point::new(10.0, 20.0), Align::Left, "A <b> & C", 12.0, Color::WHITE);
+        let doc = svg.finish();
+
+        assert!(doc.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"200\" height=\"100\""));
+        assert!(doc.contains("fill=\"#ff0000\" fill-opacity=\"0.5\""));
+        assert!(doc.contains(">A &lt;b&gt; &amp; C</text>"));
+        assert!(doc.trim_end().ends_with("</svg>"));
+    }
+}
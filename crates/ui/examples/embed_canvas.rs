@@ -0,0 +1,72 @@
+//! Hosting the Immortal Engine graph editor in your own egui application
+//!
+//! Run with `cargo run -p imortal_ui --example embed_canvas`. Drag nodes to
+//! move them, click or drag between ports to connect them, Delete removes
+//! the selection, and Ctrl+Z / Ctrl+Y undo and redo.
+
+use eframe::egui;
+use imortal_ir::{Node, ProjectGraph};
+use imortal_ui::canvas::{CanvasWidget, GraphEditorState};
+
+struct EmbedApp {
+    editor: GraphEditorState,
+    changes: usize,
+}
+
+impl EmbedApp {
+    fn new() -> Self {
+        let mut project = ProjectGraph::with_name("embedded");
+        let mut user = Node::new_entity("User");
+        user.position.x = 80.0;
+        user.position.y = 80.0;
+        let mut post = Node::new_entity("Post");
+        post.position.x = 380.0;
+        post.position.y = 160.0;
+        project.add_node(user);
+        project.add_node(post);
+        Self { editor: GraphEditorState::new(project), changes: 0 }
+    }
+}
+
+impl eframe::App for EmbedApp {
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        egui::TopBottomPanel::top("toolbar").show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                if ui.button("Add entity").clicked() {
+                    let mut node = Node::new_entity("Entity");
+                    node.position.x = 200.0;
+                    node.position.y = 320.0;
+                    self.editor.add_node(node);
+                }
+                if ui.add_enabled(self.editor.history().can_undo(), egui::Button::new("Undo")).clicked() {
+                    self.editor.undo();
+                }
+                if ui.add_enabled(self.editor.history().can_redo(), egui::Button::new("Redo")).clicked() {
+                    self.editor.redo();
+                }
+                let project = self.editor.project();
+                ui.label(format!(
+                    "{} nodes, {} connections, {} changes",
+                    project.node_count(),
+                    project.edge_count(),
+                    self.changes
+                ));
+            });
+        });
+
+        egui::CentralPanel::default().frame(egui::Frame::none()).show(ctx, |ui| {
+            let response = CanvasWidget::new().show_editor(ui, &mut self.editor);
+            if response.changed {
+                self.changes += 1;
+            }
+        });
+    }
+}
+
+fn main() -> eframe::Result<()> {
+    eframe::run_native(
+        "Embedded graph editor",
+        eframe::NativeOptions::default(),
+        Box::new(|_cc| Ok(Box::new(EmbedApp::new()))),
+    )
+}
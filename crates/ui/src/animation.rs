@@ -0,0 +1,231 @@
+//! Canvas animations
+//!
+//! Smooth pan/zoom for fit-to-content and bookmark jumps, fades for nodes
+//! as they are added and deleted, and a pulse along new connections. Every
+//! duration is scaled by `UiConfig::animation_speed`; with animations turned
+//! off, or a speed of zero, changes show up at once.
+
+use std::collections::HashMap;
+
+use imortal_core::{EdgeId, NodeId};
+use imortal_ir::graph::Viewport;
+use imortal_ir::Node;
+
+/// Duration of a viewport move at the default speed, in seconds
+pub const VIEWPORT_SECONDS: f32 = 0.4;
+/// Duration of a node fading in or out at the default speed, in seconds
+pub const FADE_SECONDS: f32 = 0.25;
+/// Duration of the pulse along a new connection at the default speed, in seconds
+pub const PULSE_SECONDS: f32 = 0.6;
+
+/// Speed the base durations are given for
+const DEFAULT_SPEED: f32 = 0.5;
+
+/// An in-progress pan/zoom move
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct ViewportMove {
+    from: Viewport,
+    to: Viewport,
+    elapsed: f32,
+    duration: f32,
+}
+
+/// A deleted node drawn fading out
+#[derive(Debug, Clone)]
+struct Removal {
+    node: Node,
+    elapsed: f32,
+    duration: f32,
+}
+
+/// Running canvas animations
+#[derive(Debug, Clone)]
+pub struct Animations {
+    enabled: bool,
+    speed: f32,
+    viewport: Option<ViewportMove>,
+    /// Nodes fading in: elapsed and total seconds
+    spawns: HashMap<NodeId, (f32, f32)>,
+    removals: Vec<Removal>,
+    /// Connections pulsing: elapsed and total seconds
+    pulses: HashMap<EdgeId, (f32, f32)>,
+}
+
+impl Default for Animations {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            speed: DEFAULT_SPEED,
+            viewport: None,
+            spawns: HashMap::new(),
+            removals: Vec::new(),
+            pulses: HashMap::new(),
+        }
+    }
+}
+
+impl Animations {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Apply the configured setting and speed (0.0 - 1.0); turning
+    /// animations off finishes the running ones
+    pub fn configure(&mut self, enabled: bool, speed: f32) {
+        self.enabled = enabled;
+        self.speed = speed.clamp(0.0, 1.0);
+        if self.duration(1.0).is_none() {
+            self.viewport = None;
+            self.spawns.clear();
+            self.removals.clear();
+            self.pulses.clear();
+        }
+    }
+
+    /// How long an animation of `base` seconds at the default speed takes,
+    /// or `None` when animations are off
+    fn duration(&self, base: f32) -> Option<f32> {
+        (self.enabled && self.speed > 0.0).then(|| base * DEFAULT_SPEED / self.speed)
+    }
+
+    /// Whether anything is still moving, so the canvas needs repainting
+    pub fn is_animating(&self) -> bool {
+        self.viewport.is_some() || !self.spawns.is_empty() || !self.removals.is_empty() || !self.pulses.is_empty()
+    }
+
+    /// Animate the viewport from `from` to `to`; returns false when
+    /// animations are off and the viewport should jump straight there
+    pub fn move_viewport(&mut self, from: Viewport, to: Viewport) -> bool {
+        let Some(duration) = self.duration(VIEWPORT_SECONDS).filter(|_| from != to) else {
+            self.viewport = None;
+            return false;
+        };
+        self.viewport = Some(ViewportMove { from, to, elapsed: 0.0, duration });
+        true
+    }
+
+    /// Stop a viewport move where it is, e.g. when the user pans or zooms
+    pub fn cancel_viewport(&mut self) {
+        self.viewport = None;
+    }
+
+    /// Fade in a node that was just added
+    pub fn node_added(&mut self, id: NodeId) {
+        if let Some(duration) = self.duration(FADE_SECONDS) {
+            self.spawns.insert(id, (0.0, duration));
+        }
+    }
+
+    /// Fade out a copy of a node that was just deleted
+    pub fn node_removed(&mut self, node: Node) {
+        if let Some(duration) = self.duration(FADE_SECONDS) {
+            self.spawns.remove(&node.id);
+            self.removals.push(Removal { node, elapsed: 0.0, duration });
+        }
+    }
+
+    /// Pulse along a connection that was just created
+    pub fn edge_created(&mut self, id: EdgeId) {
+        if let Some(duration) = self.duration(PULSE_SECONDS) {
+            self.pulses.insert(id, (0.0, duration));
+        }
+    }
+
+    /// Advance every animation by `dt` seconds, returning the viewport to
+    /// show while one is moving
+    pub fn tick(&mut self, dt: f32) -> Option<Viewport> {
+        for (elapsed, _) in self.spawns.values_mut().chain(self.pulses.values_mut()) {
+            *elapsed += dt;
+        }
+        self.spawns.retain(|_, (elapsed, duration)| elapsed < duration);
+        self.pulses.retain(|_, (elapsed, duration)| elapsed < duration);
+        for removal in &mut self.removals {
+            removal.elapsed += dt;
+        }
+        self.removals.retain(|r| r.elapsed < r.duration);
+
+        let movement = self.viewport.as_mut()?;
+        movement.elapsed += dt;
+        let t = (movement.elapsed / movement.duration).min(1.0);
+        let viewport = movement.from.lerp(&movement.to, ease_out(t));
+        if t >= 1.0 {
+            self.viewport = None;
+        }
+        Some(viewport)
+    }
+
+    /// Opacity to draw a node with, below 1.0 while it fades in
+    pub fn node_opacity(&self, id: NodeId) -> f32 {
+        self.spawns.get(&id).map_or(1.0, |(elapsed, duration)| ease_out(elapsed / duration))
+    }
+
+    /// Deleted nodes still fading out, with the opacity to draw them at
+    pub fn removed_nodes(&self) -> impl Iterator<Item = (&Node, f32)> {
+        self.removals.iter().map(|r| (&r.node, 1.0 - ease_out(r.elapsed / r.duration)))
+    }
+
+    /// How far the pulse along a new connection has travelled (0.0 - 1.0)
+    pub fn edge_pulse(&self, id: EdgeId) -> Option<f32> {
+        self.pulses.get(&id).map(|(elapsed, duration)| ease_out(elapsed / duration))
+    }
+}
+
+/// Cubic ease-out: fast start, slow finish
+fn ease_out(t: f32) -> f32 {
+    1.0 - (1.0 - t.clamp(0.0, 1.0)).powi(3)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_viewport_move_scaled_by_speed() {
+        let to = Viewport { pan_x: 100.0, ..Viewport::default() };
+        let mut animations = Animations::new();
+        animations.configure(true, 1.0);
+        assert!(animations.move_viewport(Viewport::default(), to));
+
+        // Twice the default speed takes half the time
+        let halfway = animations.tick(VIEWPORT_SECONDS / 4.0).unwrap();
+        assert!(halfway.pan_x > 50.0 && halfway.pan_x < 100.0);
+        assert_eq!(animations.tick(VIEWPORT_SECONDS / 4.0), Some(to));
+        assert_eq!(animations.tick(0.1), None);
+        assert!(!animations.is_animating());
+    }
+
+    #[test]
+    fn test_fades_and_pulses() {
+        let mut animations = Animations::new();
+        let node = Node::new_entity("User");
+        let id = node.id;
+        animations.node_added(id);
+        assert_eq!(animations.node_opacity(id), 0.0);
+
+        animations.node_removed(node);
+        assert_eq!(animations.node_opacity(id), 1.0);
+        assert_eq!(animations.removed_nodes().next().map(|(_, opacity)| opacity), Some(1.0));
+
+        let edge = EdgeId::new_v4();
+        animations.edge_created(edge);
+        animations.tick(FADE_SECONDS);
+        assert_eq!(animations.removed_nodes().count(), 0);
+        assert!(animations.edge_pulse(edge).is_some_and(|t| t > 0.0 && t < 1.0));
+        animations.tick(PULSE_SECONDS);
+        assert!(!animations.is_animating());
+    }
+
+    #[test]
+    fn test_disabled_is_instant() {
+        let mut animations = Animations::new();
+        animations.node_added(NodeId::new_v4());
+        animations.configure(false, 0.5);
+        assert!(!animations.is_animating());
+
+        animations.configure(true, 0.0);
+        let to = Viewport { zoom: 2.0, ..Viewport::default() };
+        assert!(!animations.move_viewport(Viewport::default(), to));
+        animations.edge_created(EdgeId::new_v4());
+        assert!(!animations.is_animating());
+    }
+}
@@ -4,15 +4,66 @@
 //! all UI components: canvas, palette, properties panel, etc.
 
 use eframe::egui;
-use imortal_ir::{ProjectGraph, ProjectMeta, Node, Edge, Field};
-use imortal_core::{DataType, NodeId};
-use imortal_components::ComponentRegistry;
-use std::path::PathBuf;
-
+use imortal_ir::assist::block_on;
+use imortal_ir::{ProjectGraph, ProjectMeta, Node, Edge, CachePolicy, DeletionImpact, Field, FieldDifferences, FindingKind, GraphAnalysis, ImportCollision, InferredField, LoadMode, LockInfo, Macro, MacroStep, BackgroundKind, BackgroundLayer, LaneOrientation, ConstraintPlacement, TransformCommand, PlacementStrategy, LockStatus, ProjectLock, QuarantineReport, QuickFix, RenameSummary, Snapshot, TemplateParameter, Unit, ValidationError, Workspace, AssistRequest, Proposal, SuggestionProvider};
+use imortal_core::{DataType, EdgeId, EngineError, EngineResult, IconSource, NodeId, PortDirection, Validation};
+use imortal_ir::field::FieldConstraint;
+use imortal_ir::graph::Viewport;
+use imortal_render::{PaperSize, PrintOptions};
+use imortal_components::{ComponentDefinition, ComponentExample, ComponentRegistry, DefinitionWatcher, UsageReport};
+use imortal_codegen::{CodeGenerator, GenerationHooks, GeneratorConfig, InheritanceMode, Orm, ProjectLayout, RemovalImpact, TenancyMode};
+use imortal_codegen::rust::auth::AuthFramework;
+use imortal_codegen::rust::migrations::DatabaseBackend;
+use imortal_codegen::reverse::RustImport;
+use imortal_codegen::sync::{self as code_sync, HandAddedField};
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use crate::crash::{self, CrashRescue};
+use crate::canvas::backgrounds::{self, BackgroundTextures};
+use crate::canvas::bundles::EdgeBundle;
+use crate::canvas::guides::{self, NodeDrag};
+use crate::canvas::heatmap::{self, Heatmap};
+use crate::canvas::{CanvasConfig, CanvasGeometry, CanvasResponse, CanvasWidget, ComponentDrag, DoubleClick, EguiPainter};
+use crate::docs::{self, ComponentDocsPanel, DocsAction};
+use crate::events::{EditorEvent, EditorEvents};
+use crate::animation::Animations;
+use crate::i18n::{self, tr, trf};
+use crate::icons::{self, IconCache};
+use crate::history_panel::HistoryPanel;
+use crate::hook_approvals::HookApprovals;
+use crate::suggestions_panel::{SuggestionAction, SuggestionsPanel};
+use crate::connections::{self, connection_type_name, ConnectionAction, ConnectionForm, ConnectionMatrix};
+use crate::log_panel::LogPanel;
+use crate::macros::{MacroAction, MacroManager};
+use crate::navigation::{self, Direction};
+use crate::notifications::{self, Notification, NotificationAction, Notifications};
+use crate::presentation::Presentation;
+use crate::query_console::{QueryAction, QueryConsole};
+use crate::quick_add::{self, Candidate, QuickAdd, RecentComponents};
+use crate::schema::{self, SchemaAction, SchemaPanel};
+use crate::session::{OpenPanels, Session};
 use crate::state::{EditorState, History};
+use crate::tasks::{Executor, TaskContext, TaskEvent, TaskId};
+use crate::theme::{CategoryOverride, CategoryOverrides};
+use crate::tutorial::{Tutorial, TutorialEvent, TutorialTarget};
+use crate::validations::{self, ValidationBadge};
+use crate::views::ViewSet;
 use crate::welcome::{WelcomeScreen, WelcomeAction, NewProjectInfo};
 use crate::UiConfig;
 
+/// Project databases with at least this many nodes open as an outline,
+/// loading node details as they come into view
+const LAZY_LOAD_NODES: usize = 2_000;
+
+/// Longest a crash could lose unsaved changes for
+const RESCUE_INTERVAL: std::time::Duration = std::time::Duration::from_secs(2);
+
+/// How often the component definitions folder is checked for changes
+const COMPONENT_SCAN_INTERVAL: std::time::Duration = std::time::Duration::from_secs(1);
+
 /// Application mode - either showing welcome screen or the editor
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum AppMode {
@@ -22,6 +73,213 @@ pub enum AppMode {
     Editor,
 }
 
+/// State of the "Merge Entities" dialog
+struct MergeDialog {
+    /// Entity merged away
+    source: NodeId,
+    /// Entity kept
+    target: NodeId,
+    /// Conflicting field names, and whether to take the source's definition
+    conflicts: Vec<(String, bool)>,
+}
+
+/// State of the "Project Settings" dialog
+struct ProjectSettings {
+    /// Metadata being edited, applied on save
+    meta: ProjectMeta,
+    /// Comma-separated authors
+    authors: String,
+    /// Comma-separated tags
+    tags: String,
+    /// Pre-generate hook commands, one per line
+    pre_generate: String,
+    /// Post-generate hook commands, one per line
+    post_generate: String,
+    /// File transforms, one `<pattern> <command>` per line
+    transforms: String,
+}
+
+/// State of the "Split Entity" dialog
+struct SplitDialog {
+    /// Entity being split
+    entity: NodeId,
+    /// Name of the entity to create
+    new_name: String,
+    /// Fields selected for extraction
+    selected: std::collections::HashSet<String>,
+}
+
+/// An entity name being edited in place on the canvas
+struct InlineRename {
+    node_id: NodeId,
+    name: String,
+    /// Pane the editor was opened in
+    primary: bool,
+    /// Whether the editor has taken keyboard focus yet
+    focused: bool,
+}
+
+/// An open project tab
+///
+/// The active tab's project, path, and history live directly on
+/// [`ImmortalApp`]; its slot here is stale until the user switches away.
+struct ProjectTab {
+    project: ProjectGraph,
+    path: Option<PathBuf>,
+    history: History,
+    views: ViewSet,
+    lock: Option<ProjectLock>,
+    read_only: Option<LockInfo>,
+}
+
+impl ProjectTab {
+    fn new(project: ProjectGraph, path: Option<PathBuf>) -> Self {
+        Self { project, path, history: History::new(), views: ViewSet::new(), lock: None, read_only: None }
+    }
+
+    /// Slot for the tab that is currently live on the app
+    fn live() -> Self {
+        Self::new(ProjectGraph::new(ProjectMeta::new("Untitled")), None)
+    }
+}
+
+/// The project being edited around an entered composite node
+///
+/// While a composite is entered its subgraph is the live project; leaving
+/// writes the subgraph back into the composite if it was edited.
+struct CompositeFrame {
+    /// The composite node, in `project`
+    node_id: NodeId,
+    project: ProjectGraph,
+    history: History,
+    namespace: Vec<String>,
+}
+
+/// A loaded project whose file another editor has locked, waiting for the
+/// user to open it read-only or take over
+struct LockConflict {
+    project: ProjectGraph,
+    path: PathBuf,
+    holder: LockInfo,
+    new_tab: bool,
+    /// Status to show once the project is open
+    status: String,
+}
+
+/// State of the "Restore Version" dialog
+struct RestoreDialog {
+    /// Snapshots newest first, each loaded or with the reason it couldn't be
+    versions: Vec<(Snapshot, Result<ProjectGraph, String>)>,
+    selected: Option<usize>,
+}
+
+/// State of the "Import Fields…" dialog
+struct FieldImport {
+    /// Entity the fields are appended to
+    node: NodeId,
+    /// Pasted JSON object or CSV header + row
+    sample: String,
+    /// Proposed fields, and whether each is imported
+    rows: Vec<(bool, InferredField)>,
+    /// Why the sample couldn't be read
+    error: Option<String>,
+}
+
+/// A deletion awaiting confirmation because other nodes depend on the
+/// deleted ones
+struct PendingDeletion {
+    /// Nodes and edges the user asked to delete
+    nodes: HashSet<NodeId>,
+    edges: HashSet<EdgeId>,
+    impact: DeletionImpact,
+    /// Generated files a cascade delete removes or changes, or why they
+    /// couldn't be worked out
+    artifacts: Result<RemovalImpact, String>,
+}
+
+/// Review of what an assistant proposed, before applying it
+struct AssistReview {
+    proposal: Proposal,
+    /// Whether each proposed node is added
+    nodes: Vec<bool>,
+    /// Whether each proposed edge is added
+    edges: Vec<bool>,
+}
+
+/// A project waiting on the import reconciliation dialog
+struct PendingImport {
+    other: ProjectGraph,
+    /// Imported node -> what to do about the existing node it collides with
+    choices: HashMap<NodeId, ImportCollision>,
+}
+
+impl PendingImport {
+    /// Every collision defaults to merging fields rather than duplicating
+    fn new(project: &ProjectGraph, other: ProjectGraph) -> Self {
+        let choices = project.import_collisions(&other).iter().map(|(_, theirs)| (theirs.id, ImportCollision::Merge)).collect();
+        Self { other, choices }
+    }
+}
+
+/// Selected nodes waiting on the "Generate Selection" dialog
+struct SelectionGeneration {
+    selected: HashSet<NodeId>,
+    /// Nodes the selection needs to be generated, sorted by name
+    dependencies: Vec<NodeId>,
+}
+
+/// Generation waiting on approval of the project's hooks
+struct PendingHookRun {
+    project: ProjectGraph,
+    config: GeneratorConfig,
+    output_dir: PathBuf,
+    /// The hook commands, as shown for approval
+    commands: Vec<String>,
+    /// Hash of those commands
+    hash: String,
+}
+
+/// State of the "Import Rust Project" review dialog
+struct RustImportReview {
+    /// Name of the scanned project folder
+    name: String,
+    /// What the scan proposed
+    import: RustImport,
+    /// Whether each proposed entity is imported
+    entities: Vec<bool>,
+    /// Whether each proposed endpoint is imported
+    endpoints: Vec<bool>,
+}
+
+/// Tab shown in the properties panel
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+enum PropertiesTab {
+    #[default]
+    General,
+    /// Summary of every constraint on an entity's fields
+    Validation,
+    /// Hints changing the code generated for the node
+    Advanced,
+    /// The node's connections, and a form adding one
+    Connections,
+}
+
+/// Result of a background task, delivered to `update`
+enum TaskMessage {
+    /// Generated files: output folder, file count, warnings
+    Generated(EngineResult<(PathBuf, usize, Vec<String>)>),
+    /// A project file loaded for import
+    Imported(EngineResult<Box<ProjectGraph>>),
+    /// A Rust project scanned for import: folder name and proposals
+    RustScanned(EngineResult<(String, Box<RustImport>)>),
+    /// Connection test of a database node
+    ConnectionTested(NodeId, Result<String, String>),
+    /// An assistant's proposal, or why there is none
+    Assisted(Result<Proposal, String>),
+    /// File written, with the message to show either way
+    Written(Result<String, String>),
+}
+
 /// The main Immortal Engine application
 pub struct ImmortalApp {
     /// Current application mode
@@ -48,8 +306,114 @@ pub struct ImmortalApp {
     /// Whether the settings dialog is open
     show_settings: bool,
 
-    /// Whether the new project dialog is open
-    show_new_project: bool,
+    /// Whether the graph analysis panel is open
+    show_analysis: bool,
+
+    /// Whether the project info (statistics) dialog is open
+    show_project_info: bool,
+
+    /// Schema overview (data dictionary) panel
+    schema_panel: SchemaPanel,
+
+    /// Tools > Query Console
+    query_console: QueryConsole,
+
+    /// Where nodes added without a drop position are placed
+    placement: Box<dyn PlacementStrategy>,
+
+    /// Macro being recorded, if recording is on
+    macro_recording: Option<Macro>,
+
+    /// Tools > Macros
+    macro_manager: MacroManager,
+
+    /// Component docs popover and reference browser
+    component_docs: ComponentDocsPanel,
+
+    /// First-run tutorial overlay
+    tutorial: Tutorial,
+    /// Captured log events
+    log_panel: LogPanel,
+    /// Undo history timeline
+    history_panel: HistoryPanel,
+    /// Suggested connections
+    suggestions_panel: SuggestionsPanel,
+    /// Tools > Connection Matrix
+    connection_matrix: ConnectionMatrix,
+    /// "Add connection" picker of the Connections tab
+    connection_form: Option<ConnectionForm>,
+    /// Per-category header colors, widths, corner radii, and icons
+    category_overrides: CategoryOverrides,
+    /// Reopen the last session on launch; saved with the session
+    restore_session: bool,
+    /// Palette categories that are expanded
+    palette_expanded: HashSet<imortal_core::ComponentCategory>,
+    /// Values of the metric View > Heatmap colors nodes by
+    heatmap: Option<Heatmap>,
+    /// Open or close the palette categories to match `palette_expanded`
+    /// on the next frame
+    palette_restore: bool,
+    /// Crash from the last run, offered for restore
+    crash_rescue: Option<CrashRescue>,
+    /// When the crash handler's copy of the project was last updated, and
+    /// the project revision copied, if it holds one
+    rescue_synced: Option<(std::time::Instant, u64)>,
+    /// Embedders' listeners for editor events
+    event_listeners: Vec<Box<dyn EditorEvents>>,
+    /// Embedder's assistant backend, if any
+    assist_provider: Option<Arc<dyn SuggestionProvider>>,
+    /// Open "Ask Assistant" dialog
+    assist_open: bool,
+    assist_instruction: String,
+    /// Send only the selection and its neighbours
+    assist_selection_only: bool,
+    /// Open review of an assistant's proposal
+    assist_review: Option<AssistReview>,
+    /// Folder of user component definitions, reloaded when its files change
+    component_watcher: Option<DefinitionWatcher>,
+    /// Components registered from that folder
+    custom_components: Vec<String>,
+    /// When the folder was last checked; `None` until the first load
+    component_scan: Option<std::time::Instant>,
+    /// Image icons of components
+    icons: IconCache,
+    /// Textures of image background layers
+    background_textures: BackgroundTextures,
+    /// Whether View > Background Layers is open
+    backgrounds_open: bool,
+    /// Whether Tools > Data Types is open
+    types_open: bool,
+    /// Whether Tools > Component Usage is open
+    usage_open: bool,
+    /// Name being typed for a project type, by its index
+    type_name_edit: Option<(usize, String)>,
+    /// Name of the project type about to be added
+    new_type_name: String,
+    /// Running canvas animations
+    animations: Animations,
+
+    /// Open entity refactoring dialogs
+    merge_dialog: Option<MergeDialog>,
+    split_dialog: Option<SplitDialog>,
+
+    /// File > Print settings, while the dialog is open
+    print_dialog: Option<PrintOptions>,
+
+    /// Node name being edited; applied as a rename when editing ends
+    name_edit: Option<(NodeId, String)>,
+
+    /// Locations touched by the last rename, shown until dismissed
+    rename_summary: Option<RenameSummary>,
+
+    /// State for adding a new port
+    new_port_name: String,
+    new_port_type: usize,
+
+    /// Port awaiting confirmation because removing it deletes edges (node, port, edge count)
+    pending_port_removal: Option<(NodeId, String, usize)>,
+
+    /// Deletion awaiting confirmation in the impact dialog
+    pending_deletion: Option<PendingDeletion>,
 
     /// State for adding a new field
     new_field_name: String,
@@ -60,9 +424,32 @@ pub struct ImmortalApp {
     connection_from_node: Option<NodeId>,
     connection_from_port: String,
     connection_mouse_pos: egui::Pos2,
-
-    /// Status message to display
-    status_message: Option<(String, std::time::Instant)>,
+    /// Node whose width is being dragged via its resize handle
+    resizing_node: Option<NodeId>,
+    /// Selected nodes being dragged, snapping to alignment guides
+    node_drag: Option<NodeDrag>,
+
+    /// Node with keyboard focus on the canvas
+    focused_node: Option<NodeId>,
+    /// Move keyboard focus to the properties panel on the next frame
+    focus_properties: bool,
+    /// Entity being renamed on its header
+    inline_rename: Option<InlineRename>,
+    /// Quick-add component search, while open
+    quick_add: Option<QuickAdd>,
+    /// Components added recently, ranked first in quick-add
+    recent_components: RecentComponents,
+    /// Database node whose connection dialog is open
+    connection_dialog: Option<NodeId>,
+
+    /// Toasts and their history
+    notifications: Notifications,
+    /// Background work off the UI thread
+    tasks: Executor<TaskMessage>,
+    /// Running code generation, if any
+    generation_task: Option<TaskId>,
+    /// Running connection tests by database node
+    connection_tests: HashMap<NodeId, TaskId>,
 
     /// Current project file path
     project_path: Option<PathBuf>,
@@ -70,85 +457,220 @@ pub struct ImmortalApp {
     /// Undo/Redo history
     history: History,
 
+    /// Canvas views onto the live project
+    views: ViewSet,
+
+    /// Running presentation; the editor is read-only while set
+    presentation: Option<Presentation>,
+
+    /// Name of the bookmark being added, while the dialog is open
+    new_bookmark: Option<String>,
+
+    /// Properties panel tab for entity nodes
+    properties_tab: PropertiesTab,
+
+    /// Entity field whose validation editor is open
+    validation_editor: Option<(NodeId, uuid::Uuid)>,
+
+    /// Open "Import Fields…" dialog
+    field_import: Option<FieldImport>,
+
+    /// Open "Import Rust Project" review
+    rust_import: Option<RustImportReview>,
+
+    /// Open project tabs, and which one is live in `project`/`project_path`/`history`/`views`
+    tabs: Vec<ProjectTab>,
+    active_tab: usize,
+
+    /// Open workspace and its file
+    workspace: Option<(Workspace, PathBuf)>,
+
+    /// Broken references found in the workspace, shown until dismissed
+    reference_report: Option<Vec<ValidationError>>,
+    /// Errors from the last "Validate Project", while the report is open
+    validation_report: Option<Vec<ValidationError>>,
+    /// A project that failed to load strictly: (path, error, open in a new tab)
+    repair_offer: Option<(PathBuf, String, bool)>,
+    /// What "Open with Repairs" dropped or cleared, shown until dismissed
+    quarantine_report: Option<QuarantineReport>,
+    /// Lock on the live project's file, released when the project is closed
+    project_lock: Option<ProjectLock>,
+    /// Holder of the lock when the live project was opened read-only
+    read_only: Option<LockInfo>,
+    /// A project waiting on the lock dialog
+    lock_conflict: Option<LockConflict>,
+    /// Open "Restore Version" dialog
+    restore_dialog: Option<RestoreDialog>,
+    /// Project waiting to be imported, with a decision per collision
+    pending_import: Option<PendingImport>,
+    /// Selection waiting to be generated as a project of its own
+    selection_generation: Option<SelectionGeneration>,
+    /// Generation waiting on the hooks dialog
+    pending_hook_run: Option<PendingHookRun>,
+    /// Answers about project files' hooks
+    hook_approvals: HookApprovals,
+    /// Hashes of hooks approved for a project not saved to a file yet
+    unsaved_hook_approvals: HashSet<String>,
+    /// Module path being typed for a node in the properties panel
+    namespace_edit: Option<(NodeId, String)>,
+    /// Comma-separated tags being typed for a node in the properties panel
+    tags_edit: Option<(NodeId, String)>,
+    /// Codegen hints being typed for a node in the Advanced tab: derives,
+    /// table name, and middleware
+    hints_edit: Option<(NodeId, [String; 3])>,
+    /// Snippet being typed for a custom code node
+    code_edit: Option<(NodeId, String)>,
+    /// Name of a polymorphic association being added in the properties panel
+    new_association: String,
+    /// Entered composite nodes, outermost first
+    composite_stack: Vec<CompositeFrame>,
+    /// Parameter being added to the selected composite
+    new_parameter: TemplateParameter,
+    /// Pinned template being added, with the parameter values typed so far
+    template_prompt: Option<(usize, Vec<String>)>,
+    /// Fields found in generated code, each checked for import, and where the code is
+    code_sync: Option<(PathBuf, Vec<(HandAddedField, bool)>)>,
+    /// Open "Project Settings" dialog
+    project_settings: Option<ProjectSettings>,
+
     /// Database connection test result (node_id, success, message, timestamp)
     db_connection_result: Option<(NodeId, bool, String, std::time::Instant)>,
 }
 
 impl ImmortalApp {
     /// Create a new application - starts with welcome screen
-    pub fn new(_cc: &eframe::CreationContext<'_>) -> Self {
-        Self {
-            mode: AppMode::Welcome,
-            welcome_screen: WelcomeScreen::new(),
-            project: ProjectGraph::new(ProjectMeta::new("Untitled")),
-            state: EditorState::new(),
-            registry: ComponentRegistry::with_builtins(),
-            config: UiConfig::default(),
-            show_about: false,
-            show_settings: false,
-            show_new_project: false,
-            status_message: None,
-            project_path: None,
-            new_field_name: String::new(),
-            new_field_type: 0,
-            drawing_connection: false,
-            connection_from_node: None,
-            connection_from_port: String::new(),
-            connection_mouse_pos: egui::Pos2::ZERO,
-            history: History::new(),
-            db_connection_result: None,
-        }
+    pub fn new(cc: &eframe::CreationContext<'_>) -> Self {
+        let mut app = Self::base(cc);
+        app.restore_last_session();
+        app
     }
 
     /// Create a new application with an existing project (skips welcome screen)
-    pub fn with_project(_cc: &eframe::CreationContext<'_>, project: ProjectGraph) -> Self {
-        Self {
-            mode: AppMode::Editor,
-            welcome_screen: WelcomeScreen::new(),
-            project,
-            state: EditorState::new(),
-            registry: ComponentRegistry::with_builtins(),
-            config: UiConfig::default(),
-            show_about: false,
-            show_settings: false,
-            show_new_project: false,
-            status_message: None,
-            project_path: None,
-            new_field_name: String::new(),
-            new_field_type: 0,
-            drawing_connection: false,
-            connection_from_node: None,
-            connection_from_port: String::new(),
-            connection_mouse_pos: egui::Pos2::ZERO,
-            history: History::new(),
-            db_connection_result: None,
-        }
+    pub fn with_project(cc: &eframe::CreationContext<'_>, project: ProjectGraph) -> Self {
+        Self { mode: AppMode::Editor, project, ..Self::base(cc) }
     }
 
     /// Create a new application with a project loaded from a path
-    pub fn with_project_path(_cc: &eframe::CreationContext<'_>, project: ProjectGraph, path: PathBuf) -> Self {
-        let mut welcome_screen = WelcomeScreen::new();
-        welcome_screen.add_recent_project(project.meta.name.clone(), path.clone());
+    pub fn with_project_path(cc: &eframe::CreationContext<'_>, project: ProjectGraph, path: PathBuf) -> Self {
+        let mut app = Self::with_project(cc, project);
+        app.welcome_screen.add_recent_project(app.project.meta.name.clone(), path.clone());
+        app.project_path = Some(path);
+        app
+    }
 
+    /// The application as it starts, on the welcome screen with an untitled
+    /// project; the public constructors start from it
+    fn base(cc: &eframe::CreationContext<'_>) -> Self {
+        icons::install(&cc.egui_ctx);
         Self {
-            mode: AppMode::Editor,
-            welcome_screen,
-            project,
+            mode: AppMode::Welcome,
+            welcome_screen: WelcomeScreen::new(),
+            project: ProjectGraph::new(ProjectMeta::new("Untitled")),
             state: EditorState::new(),
             registry: ComponentRegistry::with_builtins(),
             config: UiConfig::default(),
             show_about: false,
             show_settings: false,
-            show_new_project: false,
-            status_message: None,
-            project_path: Some(path),
+            show_analysis: false,
+            show_project_info: false,
+            schema_panel: SchemaPanel::new(),
+            query_console: QueryConsole::new(),
+            placement: Box::new(ConstraintPlacement::default()),
+            macro_recording: None,
+            macro_manager: MacroManager::new(macros_dir()),
+            component_docs: ComponentDocsPanel::new(),
+            tutorial: Tutorial::load(),
+            log_panel: LogPanel::new(),
+            history_panel: HistoryPanel::new(),
+            suggestions_panel: SuggestionsPanel::new(),
+            connection_matrix: ConnectionMatrix::new(),
+            connection_form: None,
+            category_overrides: CategoryOverrides::load(),
+            restore_session: Session::load().restore,
+            palette_expanded: HashSet::new(),
+            palette_restore: false,
+            heatmap: None,
+            crash_rescue: crash::pending_rescue(),
+            rescue_synced: None,
+            event_listeners: Vec::new(),
+            assist_provider: None,
+            assist_open: false,
+            assist_instruction: String::new(),
+            assist_selection_only: true,
+            assist_review: None,
+            component_watcher: components_dir().map(DefinitionWatcher::new),
+            custom_components: Vec::new(),
+            component_scan: None,
+            icons: IconCache::new(),
+            background_textures: BackgroundTextures::new(),
+            backgrounds_open: false,
+            types_open: false,
+            usage_open: false,
+            type_name_edit: None,
+            new_type_name: String::new(),
+            animations: Animations::new(),
+            merge_dialog: None,
+            split_dialog: None,
+            print_dialog: None,
+            name_edit: None,
+            rename_summary: None,
+            new_port_name: String::new(),
+            new_port_type: 0,
+            pending_port_removal: None,
+            pending_deletion: None,
+            notifications: Notifications::new(),
+            tasks: Executor::new(),
+            generation_task: None,
+            connection_tests: HashMap::new(),
+            project_path: None,
             new_field_name: String::new(),
             new_field_type: 0,
             drawing_connection: false,
             connection_from_node: None,
             connection_from_port: String::new(),
             connection_mouse_pos: egui::Pos2::ZERO,
+            resizing_node: None,
+            node_drag: None,
+            focused_node: None,
+            focus_properties: false,
+            inline_rename: None,
+            quick_add: None,
+            recent_components: RecentComponents::default(),
+            connection_dialog: None,
             history: History::new(),
+            views: ViewSet::new(),
+            presentation: None,
+            new_bookmark: None,
+            properties_tab: PropertiesTab::default(),
+            validation_editor: None,
+            field_import: None,
+            tabs: vec![ProjectTab::live()],
+            active_tab: 0,
+            workspace: None,
+            reference_report: None,
+            validation_report: None,
+            repair_offer: None,
+            quarantine_report: None,
+            project_lock: None,
+            read_only: None,
+            lock_conflict: None,
+            restore_dialog: None,
+            pending_import: None,
+            selection_generation: None,
+            pending_hook_run: None,
+            hook_approvals: HookApprovals::load(),
+            unsaved_hook_approvals: HashSet::new(),
+            rust_import: None,
+            namespace_edit: None,
+            tags_edit: None,
+            hints_edit: None,
+            code_edit: None,
+            new_association: String::new(),
+            composite_stack: Vec::new(),
+            new_parameter: TemplateParameter::new("", ""),
+            template_prompt: None,
+            code_sync: None,
+            project_settings: None,
             db_connection_result: None,
         }
     }
@@ -175,7 +697,7 @@ impl ImmortalApp {
         let project_dir = info.location.join(sanitize_project_name(&info.name));
 
         if let Err(e) = std::fs::create_dir_all(&project_dir) {
-            self.set_status(format!("Failed to create project directory: {}", e));
+            self.set_error(format!("Failed to create project directory: {}", e));
             return;
         }
 
@@ -196,42 +718,237 @@ impl ImmortalApp {
                 // Add to recent projects
                 self.welcome_screen.add_recent_project(info.name.clone(), project_file.clone());
 
-                // Switch to editor mode
+                // Switch to editor mode, releasing the old lock first in
+                // case the new project overwrote the open one
+                self.project_lock = None;
+                (self.project_lock, self.read_only) = Self::lock_or_read_only(&project_file);
                 self.project = project;
                 self.project_path = Some(project_file);
+                self.composite_stack.clear();
+                self.template_prompt = None;
                 self.state = EditorState::new();
                 self.history = History::new();
+                self.views = ViewSet::new();
                 self.mode = AppMode::Editor;
                 self.welcome_screen.close();
 
                 self.set_status(format!("Created project: {}", info.name));
             }
             Err(e) => {
-                self.set_status(format!("Failed to save project: {}", e));
+                self.set_error(format!("Failed to save project: {}", e));
             }
         }
     }
 
     /// Load a project from a file path
     fn load_project_from_path(&mut self, path: PathBuf) {
-        match imortal_ir::load_project(&path) {
+        match imortal_ir::load_project_lazy(&path, LAZY_LOAD_NODES) {
             Ok(project) => {
-                // Add to recent projects
-                self.welcome_screen.add_recent_project(project.meta.name.clone(), path.clone());
+                let status = format!("Opened project: {}", project.meta.name);
+                self.open_loaded_project(project, path, false, status);
+            }
+            Err(e) => self.load_failed(path, e, false),
+        }
+    }
 
-                // Switch to editor mode
-                self.project = project;
-                self.project_path = Some(path);
-                self.state = EditorState::new();
-                self.history = History::new();
-                self.mode = AppMode::Editor;
-                self.welcome_screen.close();
+    /// Reopen the project open when the editor last closed, as it was left
+    ///
+    /// Skipped when restoring is off, the project is gone, or there is a
+    /// crash to recover from instead.
+    fn restore_last_session(&mut self) {
+        let session = Session::load();
+        let panels = session.panels;
+        self.log_panel.visible = panels.log;
+        self.history_panel.visible = panels.history;
+        self.suggestions_panel.visible = panels.suggestions;
+        self.query_console.visible = panels.query_console;
+        self.notifications.drawer_open = panels.notifications;
+        self.config.show_minimap = panels.minimap;
+        self.palette_expanded = session.expanded_categories.iter().copied().collect();
+        self.palette_restore = true;
+
+        let Some(path) = session.project_to_restore().filter(|_| self.crash_rescue.is_none()) else {
+            return;
+        };
+        let path = path.to_path_buf();
+        self.load_project_from_path(path.clone());
+        // A project locked elsewhere waits for the user; leave it be
+        if self.project_path.as_ref() != Some(&path) {
+            return;
+        }
+        if let Some(viewport) = session.viewport {
+            self.project.viewport = viewport;
+        }
+        let project = &mut self.project;
+        project.clear_selection();
+        project.selected_nodes = session.selected_nodes.into_iter().filter(|id| project.nodes.contains_key(id)).collect();
+        project.selected_edges = session.selected_edges.into_iter().filter(|id| project.edges.contains_key(id)).collect();
+    }
 
-                self.set_status(format!("Opened project: {}", self.project.meta.name));
-            }
+    /// Save what [`restore_last_session`](Self::restore_last_session) reopens
+    fn save_session(&self) {
+        let project = self.root_project();
+        let open = self.mode == AppMode::Editor && self.project_path.is_some();
+        Session {
+            restore: self.restore_session,
+            project: self.project_path.clone().filter(|_| open),
+            viewport: open.then_some(project.viewport),
+            selected_nodes: if open { project.selected_nodes.iter().copied().collect() } else { Vec::new() },
+            selected_edges: if open { project.selected_edges.iter().copied().collect() } else { Vec::new() },
+            expanded_categories: self.palette_expanded.iter().copied().collect(),
+            panels: OpenPanels {
+                log: self.log_panel.visible,
+                history: self.history_panel.visible,
+                suggestions: self.suggestions_panel.visible,
+                query_console: self.query_console.visible,
+                notifications: self.notifications.drawer_open,
+                minimap: self.config.show_minimap,
+            },
+        }
+        .save();
+    }
+
+    /// Switch to editor mode with a freshly loaded project
+    fn show_loaded_project(&mut self, project: ProjectGraph, path: PathBuf) {
+        // Add to recent projects
+        self.welcome_screen.add_recent_project(project.meta.name.clone(), path.clone());
+
+        // Switch to editor mode
+        self.project = project;
+        self.project_path = Some(path);
+        self.composite_stack.clear();
+        self.template_prompt = None;
+        self.state = EditorState::new();
+        self.history = History::new();
+        self.views = ViewSet::new();
+        self.mode = AppMode::Editor;
+        self.welcome_screen.close();
+    }
+
+    /// Report a failed load, offering to open with repairs when the file
+    /// was read but is damaged
+    fn load_failed(&mut self, path: PathBuf, error: imortal_core::EngineError, new_tab: bool) {
+        self.set_error(format!("Failed to load project: {}", error));
+        if matches!(error, imortal_core::EngineError::Deserialization(_)) {
+            self.repair_offer = Some((path, error.to_string(), new_tab));
+        }
+    }
+
+    /// Load a damaged project permissively, showing what was repaired
+    fn open_with_repairs(&mut self, path: PathBuf, new_tab: bool) {
+        let loaded = match imortal_ir::load_project_with_mode(&path, LoadMode::Permissive) {
+            Ok(loaded) => loaded,
             Err(e) => {
-                self.set_status(format!("Failed to load project: {}", e));
+                self.set_error(format!("Failed to load project: {}", e));
+                return;
+            }
+        };
+
+        let mut project = loaded.project;
+        // Unsaved until the user saves the repaired project over the original
+        project.touch();
+        let status = trf("recovery.opened", &[&project.meta.name, &loaded.quarantine.len()]);
+        self.quarantine_report = Some(loaded.quarantine).filter(|report| !report.is_empty());
+        self.open_loaded_project(project, path, new_tab, status);
+    }
+
+    /// Lock and show a loaded project, asking first when another editor
+    /// has it open
+    fn open_loaded_project(&mut self, project: ProjectGraph, path: PathBuf, new_tab: bool, status: String) {
+        if let Some(index) = new_tab.then(|| self.tab_index_for_path(&path)).flatten() {
+            self.switch_tab(index);
+            return;
+        }
+        // Reopening the live project, e.g. to revert, keeps its lock
+        if !new_tab && self.project_path.as_deref() == Some(path.as_path()) {
+            if let Some(lock) = self.project_lock.take() {
+                self.show_project(project, path, new_tab, Some(lock), None, status);
+                return;
+            }
+        }
+
+        match ProjectLock::acquire(&path) {
+            Ok(LockStatus::Acquired(lock)) => self.show_project(project, path, new_tab, Some(lock), None, status),
+            Ok(LockStatus::HeldBy(holder)) => {
+                self.lock_conflict = Some(LockConflict { project, path, holder, new_tab, status });
+            }
+            // E.g. a read-only directory: open without a lock rather than not at all
+            Err(e) => self.show_project(project, path, new_tab, None, None, format!("{} ({})", status, e)),
+        }
+    }
+
+    /// Show a project in the live slot or a new tab, with its lock state
+    fn show_project(
+        &mut self,
+        project: ProjectGraph,
+        path: PathBuf,
+        new_tab: bool,
+        lock: Option<ProjectLock>,
+        read_only: Option<LockInfo>,
+        status: String,
+    ) {
+        if new_tab {
+            self.welcome_screen.add_recent_project(project.meta.name.clone(), path.clone());
+            self.open_tab(project, Some(path));
+        } else {
+            self.show_loaded_project(project, path);
+        }
+        self.project_lock = lock;
+        self.read_only = read_only;
+        self.set_status(status);
+        if let Some(path) = self.project_path.clone() {
+            self.emit(EditorEvent::ProjectOpened { name: self.project.meta.name.clone(), path });
+        }
+    }
+
+    /// Lock a project opened without asking, falling back to read-only
+    fn lock_or_read_only(path: &Path) -> (Option<ProjectLock>, Option<LockInfo>) {
+        match ProjectLock::acquire(path) {
+            Ok(LockStatus::Acquired(lock)) => (Some(lock), None),
+            Ok(LockStatus::HeldBy(holder)) => (None, Some(holder)),
+            Err(_) => (None, None),
+        }
+    }
+
+    /// Load the details of nodes opened as an outline from the project
+    /// database
+    fn load_node_details(&mut self, ids: &[NodeId]) {
+        if ids.is_empty() {
+            return;
+        }
+        let Some(path) = self.project_path.clone() else {
+            return;
+        };
+        let loaded = imortal_ir::ProjectDb::open(&path).and_then(|db| db.load_details(&mut self.project, ids));
+        if let Err(e) = loaded {
+            self.set_error(trf("lazy.load_failed", &[&e]));
+        }
+    }
+
+    /// Load every node's details, for operations on the whole project
+    fn load_all_node_details(&mut self) {
+        let ids: Vec<NodeId> = self.project.unloaded.iter().copied().collect();
+        self.load_node_details(&ids);
+    }
+
+    /// Check that the live project may be written to `path`, locking the
+    /// file when it's a new location
+    fn check_save_lock(&mut self, path: &Path) -> EngineResult<()> {
+        if self.project_path.as_deref() == Some(path) {
+            if let Some(holder) = &self.read_only {
+                return Err(EngineError::ProjectLocked(holder.to_string()));
+            }
+            if let Some(lock) = &self.project_lock {
+                return lock.verify();
+            }
+        }
+        match ProjectLock::acquire(path)? {
+            LockStatus::Acquired(lock) => {
+                self.project_lock = Some(lock);
+                self.read_only = None;
+                Ok(())
             }
+            LockStatus::HeldBy(holder) => Err(EngineError::ProjectLocked(holder.to_string())),
         }
     }
 
@@ -242,10 +959,174 @@ impl ImmortalApp {
     }
 
     /// Save current state for undo
+    /// Add a listener for editor events, e.g. an embedder's analytics
+    pub fn with_event_listener(mut self, listener: impl EditorEvents + 'static) -> Self {
+        self.add_event_listener(listener);
+        self
+    }
+
+    /// Use an assistant backend for Tools > Ask Assistant
+    pub fn with_suggestion_provider(mut self, provider: impl SuggestionProvider + 'static) -> Self {
+        self.assist_provider = Some(Arc::new(provider));
+        self
+    }
+
+    /// Add a listener for editor events
+    pub fn add_event_listener(&mut self, listener: impl EditorEvents + 'static) {
+        self.event_listeners.push(Box::new(listener));
+    }
+
+    /// Tell the listeners about something the user did
+    fn emit(&mut self, event: EditorEvent) {
+        for listener in &mut self.event_listeners {
+            listener.on_event(&event);
+        }
+    }
+
+    /// A node was just added to the live project
+    fn node_added(&mut self, id: NodeId) {
+        self.animations.node_added(id);
+        if self.macro_recording.is_some() {
+            if let Some(node) = self.project.get_node(id) {
+                let node = Box::new(node.clone());
+                self.record_macro_step(MacroStep::AddNode { node });
+            }
+        }
+        if !self.event_listeners.is_empty() {
+            let component_type = self.project.get_node(id).map(|n| n.component_type.clone()).unwrap_or_default();
+            self.emit(EditorEvent::NodeAdded { id, component_type });
+        }
+    }
+
+    /// A connection was just created in the live project
+    fn connection_created(&mut self, id: EdgeId) {
+        self.animations.edge_created(id);
+        self.emit(EditorEvent::ConnectionCreated { id });
+    }
+
+    /// Delete the selected nodes and connections as one undo step,
+    /// returning how many items were deleted. When other nodes depend on
+    /// the selected ones, the impact dialog asks first and nothing is
+    /// deleted yet.
+    fn delete_selection(&mut self) -> usize {
+        let nodes = self.project.selected_nodes.clone();
+        let impact = self.project.deletion_impact(&nodes);
+        if impact.dependents.is_empty() {
+            return self.delete_selection_confirmed();
+        }
+
+        self.load_all_node_details();
+        let cascade: HashSet<NodeId> = nodes.union(&impact.dependents).copied().collect();
+        let artifacts = CodeGenerator::with_config(GeneratorConfig::for_project(&self.project.meta))
+            .removal_impact(&self.project, &cascade)
+            .map_err(|e| e.to_string());
+        self.pending_deletion = Some(PendingDeletion {
+            nodes,
+            edges: self.project.selected_edges.clone(),
+            impact,
+            artifacts,
+        });
+        0
+    }
+
+    /// Enable or disable the selected nodes as one undo step
+    fn set_selection_enabled(&mut self, enabled: bool) {
+        self.save_undo_state(if enabled { "Enable components" } else { "Disable components" });
+        let mut count = 0;
+        for id in self.project.selected_nodes.clone() {
+            if let Some(node) = self.project.get_node_mut(id).filter(|node| node.enabled != enabled) {
+                node.enabled = enabled;
+                count += 1;
+            }
+        }
+        self.set_status(trf(if enabled { "disable.enabled" } else { "disable.disabled" }, &[&count]));
+    }
+
+    /// Delete the selected nodes and connections without asking
+    fn delete_selection_confirmed(&mut self) -> usize {
+        let (nodes, edges) = (self.project.selected_nodes.len(), self.project.selected_edges.len());
+        if nodes + edges == 0 {
+            return 0;
+        }
+        self.save_undo_state(&format!("Delete {} item(s)", nodes + edges));
+        for id in self.project.selected_nodes.clone() {
+            if let Some(node) = self.project.get_node(id) {
+                self.animations.node_removed(node.clone());
+            }
+        }
+        self.project.delete_selected();
+        self.emit(EditorEvent::Deleted { nodes, edges });
+        nodes + edges
+    }
+
     fn save_undo_state(&mut self, action_name: &str) {
+        crash::record_action(action_name);
         self.history.push(action_name, self.project.clone());
     }
 
+    /// Keep the crash handler's copy of the project current while it has
+    /// unsaved changes
+    ///
+    /// Copying a large project takes a while, so it's copied at most every
+    /// [`RESCUE_INTERVAL`], and only when it changed since the last copy.
+    fn sync_rescue(&mut self) {
+        let revision = self.project.revision();
+        match self.rescue_synced {
+            Some(_) if !self.project.dirty => {
+                crash::clear_project();
+                self.rescue_synced = None;
+            }
+            Some((_, synced)) if synced == revision => {}
+            Some((at, _)) if at.elapsed() < RESCUE_INTERVAL => {}
+            _ if self.project.dirty => {
+                crash::set_project(&self.project, self.project_path.as_deref());
+                self.rescue_synced = Some((std::time::Instant::now(), revision));
+            }
+            _ => {}
+        }
+    }
+
+    /// Load user component definitions on the first frame, then reload
+    /// them whenever files in their folder change
+    fn poll_component_definitions(&mut self, ctx: &egui::Context) {
+        if self.component_scan.is_some_and(|scanned| scanned.elapsed() < COMPONENT_SCAN_INTERVAL) {
+            return;
+        }
+        let first = self.component_scan.is_none();
+        self.component_scan = Some(std::time::Instant::now());
+        let Some(watcher) = &mut self.component_watcher else {
+            return;
+        };
+        if !watcher.poll() && !first {
+            return;
+        }
+        let dir = watcher.dir().to_path_buf();
+
+        for id in self.custom_components.drain(..) {
+            self.registry.unregister(&id);
+        }
+        self.icons.reload(ctx);
+        if !dir.is_dir() {
+            return;
+        }
+        match self.registry.load_dir(&dir) {
+            Ok(load) => {
+                tracing::info!(count = load.loaded.len(), errors = load.errors.len(), "Loaded component definitions from {}", dir.display());
+                for error in &load.errors {
+                    self.notify(
+                        Notification::warning(trf("components.invalid", &[error]))
+                            .with_action(NotificationAction::OpenFolder(dir.clone())),
+                    );
+                }
+                if !first {
+                    self.set_status(trf("components.reloaded", &[&load.loaded.len()]));
+                }
+                self.custom_components = load.loaded;
+            }
+            Err(e) => self.set_error(trf("components.load_failed", &[&dir.display(), &e])),
+        }
+    }
+
     /// Undo the last action
     fn undo(&mut self) {
         if let Some(previous_state) = self.history.undo(self.project.clone()) {
@@ -270,84 +1151,343 @@ impl ImmortalApp {
         }
     }
 
-    /// Set a status message that will be displayed briefly
-    pub fn set_status(&mut self, message: impl Into<String>) {
-        self.status_message = Some((message.into(), std::time::Instant::now()));
+    /// Jump to a state in the undo history timeline
+    fn jump_to_history(&mut self, index: usize) {
+        if let Some(state) = self.history.jump_to(index, self.project.clone()) {
+            self.project = state;
+            let action_name = self.history.undo_action_name().map_or_else(|| tr("timeline.start").to_string(), str::to_string);
+            self.set_status(trf("timeline.jumped", &[&action_name]));
+        }
     }
 
-    /// Clear the status message
-    pub fn clear_status(&mut self) {
-        self.status_message = None;
+    /// Apply an edit from the Connections tab or the connection matrix
+    fn handle_connection_action(&mut self, action: ConnectionAction) {
+        match action {
+            ConnectionAction::Add(edge) => {
+                self.save_undo_state("Create connection");
+                match self.project.add_edge(*edge) {
+                    Ok(id) => {
+                        self.connection_created(id);
+                        self.set_status(tr("connections.created"));
+                    }
+                    Err(e) => self.set_error(trf("connections.failed", &[&e])),
+                }
+            }
+            ConnectionAction::Delete(id) => {
+                self.save_undo_state("Delete connection");
+                if self.project.remove_edge(id).is_some() {
+                    self.project.selected_edges.remove(&id);
+                    self.emit(EditorEvent::Deleted { nodes: 0, edges: 1 });
+                    self.set_status(tr("connections.deleted"));
+                }
+            }
+            ConnectionAction::Select(id) => {
+                self.project.clear_selection();
+                self.project.select_edge(id);
+            }
+        }
     }
 
-    /// Render the menu bar
-    fn render_menu_bar(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
-        egui::TopBottomPanel::top("menu_bar").show(ctx, |ui| {
-            egui::menu::bar(ui, |ui| {
-                // File menu
-                ui.menu_button("File", |ui| {
-                    if ui.button("New Project...").clicked() {
-                        // Go to welcome screen in new project mode
-                        self.mode = AppMode::Welcome;
-                        self.welcome_screen.open();
-                        ui.close_menu();
-                    }
-                    if ui.button("Open...").clicked() {
-                        self.open_project();
-                        ui.close_menu();
+    /// Accept suggested connections as one undo step, or select a node
+    /// one of them mentions
+    fn handle_suggestion_action(&mut self, action: SuggestionAction) {
+        match action {
+            SuggestionAction::Focus(id) => {
+                self.project.clear_selection();
+                self.project.select_node(id);
+            }
+            SuggestionAction::Accept(suggestions) => {
+                self.save_undo_state("Accept suggested connections");
+                let mut failed = Vec::new();
+                let mut created = 0;
+                for suggestion in &suggestions {
+                    match suggestion.accept(&mut self.project) {
+                        Ok(id) => {
+                            self.connection_created(id);
+                            created += 1;
+                        }
+                        Err(e) => failed.push(format!("{}: {}", suggestion.message, e)),
                     }
+                }
+                if failed.is_empty() {
+                    self.set_status(trf("suggestions.accepted", &[&created]));
+                } else {
+                    self.set_error(trf("suggestions.failed", &[&failed.join("; ")]));
+                }
+            }
+        }
+    }
 
-                    // Recent projects submenu
-                    let recent_projects = self.welcome_screen.recent_projects.clone();
-                    if !recent_projects.is_empty() {
-                        ui.menu_button("Open Recent", |ui| {
-                            for project in &recent_projects {
-                                if ui.button(&project.name).clicked() {
-                                    self.load_project_from_path(project.path.clone());
-                                    ui.close_menu();
-                                }
-                            }
-                            ui.separator();
-                            if ui.button("Clear Recent Projects").clicked() {
-                                self.welcome_screen.recent_projects.clear();
-                                self.welcome_screen.save_recent_projects();
-                                ui.close_menu();
-                            }
-                        });
-                    }
+    /// Show a message briefly, and keep it in the notification history
+    pub fn set_status(&mut self, message: impl Into<String>) {
+        self.notify(Notification::info(message));
+    }
 
-                    ui.separator();
-                    if ui.button("Save").clicked() {
-                        self.save_project();
-                        ui.close_menu();
-                    }
-                    if ui.button("Save As...").clicked() {
-                        self.save_project_as();
+    /// Show an error briefly, and keep it in the notification history
+    pub fn set_error(&mut self, message: impl Into<String>) {
+        self.notify(Notification::error(message));
+    }
+
+    /// Post a notification
+    pub fn notify(&mut self, notification: Notification) {
+        self.notifications.push(notification);
+    }
+
+    /// Close any toasts showing
+    pub fn clear_status(&mut self) {
+        self.notifications.dismiss_all();
+    }
+
+    /// Run `work` off the UI thread, with a progress toast
+    fn spawn_task<F>(&mut self, label: String, work: F) -> TaskId
+    where
+        F: FnOnce(&TaskContext<TaskMessage>) -> TaskMessage + Send + 'static,
+    {
+        let id = self.tasks.spawn(label.clone(), work);
+        self.notifications.start_task(id, label);
+        id
+    }
+
+    /// Apply progress and results of background tasks
+    fn poll_tasks(&mut self) {
+        for event in self.tasks.poll() {
+            if !matches!(event, TaskEvent::Progress { .. }) {
+                let id = event.id();
+                self.connection_tests.retain(|_, task| *task != id);
+                if self.generation_task == Some(id) {
+                    self.generation_task = None;
+                }
+            }
+            match event {
+                TaskEvent::Progress { id, fraction, message } => self.notifications.task_progress(id, fraction, message),
+                TaskEvent::Finished { id, result } => {
+                    let outcome = self.apply_task_result(result);
+                    self.notifications.finish_task(id, outcome);
+                }
+                TaskEvent::Cancelled { id } => self.notifications.finish_task(id, Notification::info(tr("notifications.task_cancelled"))),
+                TaskEvent::Failed { id } => self.notifications.finish_task(id, Notification::error(tr("notifications.task_failed"))),
+            }
+        }
+    }
+
+    /// Apply a finished task's result, returning the notification to show
+    fn apply_task_result(&mut self, result: TaskMessage) -> Notification {
+        match result {
+            TaskMessage::Generated(Ok((dir, files, warnings))) => {
+                self.emit(EditorEvent::GenerationFinished { output_dir: dir.clone(), files, warnings: warnings.len() });
+                let outcome = if warnings.is_empty() {
+                    Notification::success(trf("generate.done", &[&files, &dir.display()]))
+                } else {
+                    Notification::warning(trf("generate.done_with_warnings", &[&files, &dir.display(), &warnings.join("; ")]))
+                };
+                outcome.with_action(NotificationAction::OpenFolder(dir))
+            }
+            TaskMessage::Generated(Err(e)) => {
+                self.emit(EditorEvent::GenerationFailed { error: e.to_string() });
+                Notification::error(trf("generate.failed", &[&e]))
+            }
+            TaskMessage::Imported(Ok(other)) if self.project.import_collisions(&other).is_empty() => {
+                Notification::success(self.import_project(*other, HashMap::new()))
+            }
+            TaskMessage::Imported(Ok(other)) => {
+                let outcome = Notification::info(trf("import.loaded", &[&other.meta.name]));
+                self.pending_import = Some(PendingImport::new(&self.project, *other));
+                outcome
+            }
+            TaskMessage::Imported(Err(e)) => Notification::error(format!("Failed to load project: {}", e)),
+            TaskMessage::RustScanned(Ok((name, import))) => {
+                let outcome = Notification::info(trf("rust_import.scanned", &[&import.entities.len(), &import.endpoints.len(), &name]));
+                for warning in &import.warnings {
+                    tracing::warn!("Rust import skipped {}", warning);
+                }
+                self.rust_import = Some(RustImportReview {
+                    name,
+                    entities: import.entities.iter().map(|e| e.has_id).collect(),
+                    endpoints: vec![true; import.endpoints.len()],
+                    import: *import,
+                });
+                outcome
+            }
+            TaskMessage::RustScanned(Err(e)) => Notification::error(trf("rust_import.failed", &[&e])),
+            TaskMessage::ConnectionTested(node_id, result) => {
+                let (success, message) = match result {
+                    Ok(msg) => (true, msg),
+                    Err(err) => (false, err),
+                };
+                let outcome = if success { Notification::success(&message) } else { Notification::error(&message) };
+                self.db_connection_result = Some((node_id, success, message, std::time::Instant::now()));
+                outcome
+            }
+            TaskMessage::Assisted(Ok(proposal)) if proposal.is_empty() => Notification::info(tr("assist.nothing")),
+            TaskMessage::Assisted(Ok(proposal)) => {
+                let outcome = Notification::info(trf("assist.received", &[&proposal.nodes.len(), &proposal.edges.len()]));
+                self.assist_review = Some(AssistReview {
+                    nodes: vec![true; proposal.nodes.len()],
+                    edges: vec![true; proposal.edges.len()],
+                    proposal,
+                });
+                outcome
+            }
+            TaskMessage::Assisted(Err(e)) => Notification::error(trf("assist.failed", &[&e])),
+            TaskMessage::Written(Ok(message)) => Notification::success(message),
+            TaskMessage::Written(Err(message)) => Notification::error(message),
+        }
+    }
+
+    /// Do what the user clicked in a notification
+    fn handle_notification_action(&mut self, action: NotificationAction) {
+        match action {
+            NotificationAction::OpenFolder(dir) => {
+                if let Err(e) = notifications::open_path(&dir) {
+                    self.set_error(trf("notifications.open_folder_failed", &[&dir.display(), &e]));
+                }
+            }
+            NotificationAction::ShowValidation => self.validate_project(),
+            NotificationAction::CancelTask(id) => self.tasks.cancel(id),
+        }
+    }
+
+    /// Test a database node's connection in the background
+    fn start_connection_test(&mut self, node_id: NodeId) {
+        if self.connection_test_running(node_id) {
+            return;
+        }
+        let Some(node) = self.project.get_node(node_id) else {
+            return;
+        };
+        let config = node.config.clone();
+        let task = self.spawn_task(trf("connection_test.running", &[&node.name]), move |_| {
+            TaskMessage::ConnectionTested(node_id, Self::test_database_connection(&config))
+        });
+        self.connection_tests.insert(node_id, task);
+    }
+
+    fn connection_test_running(&self, node_id: NodeId) -> bool {
+        self.connection_tests.contains_key(&node_id)
+    }
+
+    /// Render the menu bar
+    fn render_menu_bar(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        egui::TopBottomPanel::top("menu_bar").show(ctx, |ui| {
+            egui::menu::bar(ui, |ui| {
+                // File menu
+                ui.menu_button(tr("menu.file"), |ui| {
+                    if ui.button(tr("menu.file.new_project")).clicked() {
+                        // Go to welcome screen in new project mode
+                        self.mode = AppMode::Welcome;
+                        self.welcome_screen.open();
+                        ui.close_menu();
+                    }
+                    if ui.button(tr("menu.file.open")).clicked() {
+                        self.open_project();
+                        ui.close_menu();
+                    }
+                    if ui.button(tr("menu.file.open_in_tab")).clicked() {
+                        self.open_project_in_new_tab();
+                        ui.close_menu();
+                    }
+
+                    // Recent projects submenu
+                    let recent_projects = self.welcome_screen.recent_projects.clone();
+                    if !recent_projects.is_empty() {
+                        ui.menu_button(tr("menu.file.open_recent"), |ui| {
+                            for project in &recent_projects {
+                                if ui.button(&project.name).clicked() {
+                                    self.load_project_from_path(project.path.clone());
+                                    ui.close_menu();
+                                }
+                            }
+                            ui.separator();
+                            if ui.button(tr("menu.file.clear_recent")).clicked() {
+                                self.welcome_screen.recent_projects.clear();
+                                self.welcome_screen.save_recent_projects();
+                                ui.close_menu();
+                            }
+                        });
+                    }
+
+                    ui.separator();
+                    if ui.button(tr("menu.file.save")).clicked() {
+                        self.save_project();
+                        ui.close_menu();
+                    }
+                    if ui.button(tr("menu.file.save_as")).clicked() {
+                        self.save_project_as();
+                        ui.close_menu();
+                    }
+                    if ui.add_enabled(self.project_path.is_some(), egui::Button::new(tr("menu.file.restore_version"))).clicked() {
+                        self.open_restore_dialog();
+                        ui.close_menu();
+                    }
+                    if ui.button(tr("menu.file.import_project")).clicked() {
+                        self.pick_import();
+                        ui.close_menu();
+                    }
+                    if ui.button(tr("menu.file.import_rust")).clicked() {
+                        self.pick_rust_import();
+                        ui.close_menu();
+                    }
+                    let has_selection = !self.project.selected_nodes.is_empty();
+                    if ui.add_enabled(has_selection, egui::Button::new(tr("menu.file.export_selection"))).clicked() {
+                        self.export_selection();
+                        ui.close_menu();
+                    }
+                    ui.separator();
+                    if ui.button(tr("menu.file.open_workspace")).clicked() {
+                        self.open_workspace();
+                        ui.close_menu();
+                    }
+                    if ui.button(tr("menu.file.save_workspace")).clicked() {
+                        self.save_workspace();
+                        ui.close_menu();
+                    }
+                    if ui.add_enabled(self.workspace.is_some(), egui::Button::new(tr("menu.file.check_references"))).clicked() {
+                        self.check_references();
                         ui.close_menu();
                     }
                     ui.separator();
-                    if ui.button("Export...").clicked() {
+                    if ui.button(tr("menu.file.export")).clicked() {
                         // TODO: Export dialog
                         ui.close_menu();
                     }
+                    if ui.button(tr("menu.file.print")).clicked() {
+                        self.print_dialog.get_or_insert_with(PrintOptions::default);
+                        ui.close_menu();
+                    }
+                    if ui.button(tr("menu.file.export_document")).clicked() {
+                        self.export_design_document();
+                        ui.close_menu();
+                    }
+                    if ui.button(tr("menu.file.project_info")).clicked() {
+                        self.show_project_info = true;
+                        ui.close_menu();
+                    }
+                    if ui.button(tr("menu.file.project_settings")).clicked() {
+                        self.open_project_settings();
+                        ui.close_menu();
+                    }
                     ui.separator();
-                    if ui.button("Close Project").clicked() {
+                    if ui.button(tr("menu.file.close_project")).clicked() {
                         // Return to welcome screen
                         self.show_welcome_screen();
                         ui.close_menu();
                     }
+                    if ui.button(tr("menu.file.settings")).clicked() {
+                        self.show_settings = true;
+                        ui.close_menu();
+                    }
                     ui.separator();
-                    if ui.button("Quit").clicked() {
+                    if ui.button(tr("menu.file.quit")).clicked() {
                         ctx.send_viewport_cmd(egui::ViewportCommand::Close);
                     }
                 });
 
                 // Edit menu
-                ui.menu_button("Edit", |ui| {
+                ui.menu_button(tr("menu.edit"), |ui| {
                     let undo_text = if let Some(action) = self.history.undo_action_name() {
-                        format!("Undo: {} (Ctrl+Z)", action)
+                        trf("menu.edit.undo_action", &[&action])
                     } else {
-                        "Undo (Ctrl+Z)".to_string()
+                        tr("menu.edit.undo").to_string()
                     };
                     if ui.add_enabled(self.history.can_undo(), egui::Button::new(undo_text)).clicked() {
                         self.undo();
@@ -355,115 +1495,380 @@ impl ImmortalApp {
                     }
 
                     let redo_text = if let Some(action) = self.history.redo_action_name() {
-                        format!("Redo: {} (Ctrl+Y)", action)
+                        trf("menu.edit.redo_action", &[&action])
                     } else {
-                        "Redo (Ctrl+Y)".to_string()
+                        tr("menu.edit.redo").to_string()
                     };
                     if ui.add_enabled(self.history.can_redo(), egui::Button::new(redo_text)).clicked() {
                         self.redo();
                         ui.close_menu();
                     }
                     ui.separator();
-                    if ui.button("Cut").clicked() {
+                    if ui.button(tr("menu.edit.cut")).clicked() {
                         // TODO: Cut
                         ui.close_menu();
                     }
-                    if ui.button("Copy").clicked() {
+                    if ui.button(tr("menu.edit.copy")).clicked() {
                         // TODO: Copy
                         ui.close_menu();
                     }
-                    if ui.button("Paste").clicked() {
+                    if ui.button(tr("menu.edit.paste")).clicked() {
                         // TODO: Paste
                         ui.close_menu();
                     }
                     ui.separator();
-                    if ui.button("Select All").clicked() {
-                        self.project.select_all();
+                    let entities = self.selected_entities();
+                    if ui.add_enabled(entities.len() == 2, egui::Button::new(tr("menu.edit.merge_entities")))
+                        .on_hover_text(tr("menu.edit.merge_entities_hint"))
+                        .clicked()
+                    {
+                        self.open_merge_dialog(entities[1], entities[0]);
+                        ui.close_menu();
+                    }
+                    if ui.add_enabled(entities.len() == 1, egui::Button::new(tr("menu.edit.split_entity")))
+                        .on_hover_text(tr("menu.edit.split_entity_hint"))
+                        .clicked()
+                    {
+                        self.split_dialog = Some(SplitDialog {
+                            entity: entities[0],
+                            new_name: String::new(),
+                            selected: Default::default(),
+                        });
+                        ui.close_menu();
+                    }
+                    ui.separator();
+                    if ui.button(tr("menu.edit.select_all")).clicked() {
+                        self.select_all_shown();
                         ui.close_menu();
                     }
-                    if ui.button("Deselect All").clicked() {
+                    if ui.button(tr("menu.edit.deselect_all")).clicked() {
                         self.project.clear_selection();
                         ui.close_menu();
                     }
                     ui.separator();
-                    if ui.button("Delete Selected (Del)").clicked() {
-                        let count = self.project.selected_nodes.len() + self.project.selected_edges.len();
-                        if count > 0 {
-                            self.save_undo_state(&format!("Delete {} item(s)", count));
-                            self.project.delete_selected();
-                        }
+                    if ui.button(tr("menu.edit.delete_selected")).clicked() {
+                        self.delete_selection();
                         ui.close_menu();
                     }
                 });
 
                 // View menu
-                ui.menu_button("View", |ui| {
-                    ui.checkbox(&mut self.config.show_grid, "Show Grid");
-                    ui.checkbox(&mut self.config.snap_to_grid, "Snap to Grid");
-                    ui.checkbox(&mut self.config.show_minimap, "Show Minimap");
+                ui.menu_button(tr("menu.view"), |ui| {
+                    ui.checkbox(&mut self.config.show_grid, tr("menu.view.show_grid"));
+                    ui.checkbox(&mut self.config.snap_to_grid, tr("menu.view.snap_to_grid"));
+                    ui.checkbox(&mut self.config.show_minimap, tr("menu.view.show_minimap"));
+                    ui.checkbox(&mut self.config.alignment_guides, tr("menu.view.alignment_guides"));
+                    ui.checkbox(&mut self.log_panel.visible, tr("menu.view.log"));
+                    ui.checkbox(&mut self.history_panel.visible, tr("menu.view.history"));
+                    ui.checkbox(&mut self.suggestions_panel.visible, tr("menu.view.suggestions"));
+                    ui.checkbox(&mut self.config.highlight_flow, tr("menu.view.highlight_flow"));
+                    ui.add_enabled_ui(self.config.highlight_flow, |ui| {
+                        ui.horizontal(|ui| {
+                            ui.label(tr("menu.view.highlight_depth"));
+                            ui.add(egui::Slider::new(&mut self.config.highlight_depth, 0..=10).custom_formatter(|n, _| {
+                                if n == 0.0 { tr("menu.view.highlight_depth_all").to_string() } else { n.to_string() }
+                            }));
+                        });
+                    });
+                    ui.checkbox(&mut self.config.bundle_edges, tr("menu.view.bundle_edges"))
+                        .on_hover_text(tr("menu.view.bundle_edges_hint"));
+                    ui.menu_button(tr("menu.view.heatmap"), |ui| {
+                        ui.radio_value(&mut self.config.heatmap, None, tr("menu.view.heatmap_off"));
+                        for metric in imortal_ir::NodeMetric::all() {
+                            ui.radio_value(&mut self.config.heatmap, Some(*metric), heatmap::metric_name(*metric));
+                        }
+                    });
+                    if ui.button(tr("menu.view.backgrounds")).clicked() {
+                        self.backgrounds_open = true;
+                        ui.close_menu();
+                    }
                     ui.separator();
-                    if ui.button("Zoom In").clicked() {
+                    if ui.button(tr("menu.view.zoom_in")).clicked() {
                         self.project.zoom(1.2);
                         ui.close_menu();
                     }
-                    if ui.button("Zoom Out").clicked() {
+                    if ui.button(tr("menu.view.zoom_out")).clicked() {
                         self.project.zoom(0.8);
                         ui.close_menu();
                     }
-                    if ui.button("Reset Zoom").clicked() {
+                    if ui.button(tr("menu.view.reset_zoom")).clicked() {
                         self.project.set_zoom(1.0);
                         ui.close_menu();
                     }
                     ui.separator();
-                    if ui.button("Fit to Content").clicked() {
-                        // Get the available size from the UI
-                        self.project.fit_to_content(800.0, 600.0);
+                    if ui.button(tr("menu.view.fit_to_content")).clicked() {
+                        self.fit_to_content();
+                        ui.close_menu();
+                    }
+                    ui.separator();
+                    if ui.button(tr("menu.view.new_view")).clicked() {
+                        self.views.add(&mut self.project.viewport);
+                        ui.close_menu();
+                    }
+                    let split_text = if self.views.split().is_some() { tr("menu.view.unsplit_canvas") } else { tr("menu.view.split_canvas") };
+                    if ui.button(split_text).clicked() {
+                        self.views.toggle_split(&self.project.viewport);
+                        ui.close_menu();
+                    }
+                    ui.separator();
+                    if ui.button(tr("menu.view.add_bookmark")).clicked() {
+                        self.new_bookmark = Some(format!("Bookmark {}", self.project.bookmarks.len() + 1));
+                        ui.close_menu();
+                    }
+                    ui.separator();
+                    if ui.button(tr("menu.view.add_slide")).clicked() {
+                        self.save_undo_state("Add slide");
+                        let name = format!("Slide {}", self.project.slides.len() + 1);
+                        self.project.add_slide(&name);
+                        self.set_status(format!("Added {} from the current view", name));
+                        ui.close_menu();
+                    }
+                    if !self.project.slides.is_empty() {
+                        ui.menu_button(tr("menu.view.slides"), |ui| {
+                            let mut remove = None;
+                            for (index, slide) in self.project.slides.iter().enumerate() {
+                                ui.horizontal(|ui| {
+                                    if ui.button(format!("{}. {}", index + 1, slide.name)).clicked() {
+                                        self.project.viewport = slide.viewport;
+                                    }
+                                    if ui.small_button("×").on_hover_text(tr("menu.view.remove_slide")).clicked() {
+                                        remove = Some(index);
+                                    }
+                                });
+                            }
+                            if let Some(index) = remove {
+                                self.save_undo_state("Remove slide");
+                                self.project.remove_slide(index);
+                            }
+                        });
+                    }
+                    if ui.button(tr("menu.view.presentation")).clicked() {
+                        self.start_presentation();
+                        ui.close_menu();
+                    }
+                    ui.separator();
+                    if ui.button(tr("menu.view.schema_overview")).clicked() {
+                        self.schema_panel.visible = true;
+                        ui.close_menu();
+                    }
+                    ui.separator();
+                    if ui.button(tr("menu.view.collapse_all")).clicked() {
+                        self.save_undo_state("Collapse all nodes");
+                        self.project.set_all_collapsed(true);
+                        ui.close_menu();
+                    }
+                    if ui.button(tr("menu.view.expand_all")).clicked() {
+                        self.save_undo_state("Expand all nodes");
+                        self.project.set_all_collapsed(false);
                         ui.close_menu();
                     }
                 });
 
                 // Generate menu
-                ui.menu_button("Generate", |ui| {
-                    if ui.button("Generate Code...").clicked() {
+                let generate_menu = ui.menu_button(tr("menu.generate"), |ui| {
+                    if ui.button(tr("menu.generate.generate_code")).clicked() {
                         self.generate_code();
                         ui.close_menu();
                     }
-                    if ui.button("Preview Code").clicked() {
+                    let has_selection = !self.project.selected_nodes.is_empty();
+                    if ui.add_enabled(has_selection, egui::Button::new(tr("menu.generate.generate_selection"))).clicked() {
+                        self.open_selection_generation();
+                        ui.close_menu();
+                    }
+                    if ui.button(tr("menu.generate.preview_code")).clicked() {
                         // TODO: Code preview
                         ui.close_menu();
                     }
                     ui.separator();
-                    if ui.button("Validate Project").clicked() {
+                    if ui.button(tr("menu.generate.validate")).clicked() {
                         self.validate_project();
                         ui.close_menu();
                     }
+                    if ui.button(tr("menu.generate.analyze")).clicked() {
+                        self.show_analysis = true;
+                        ui.close_menu();
+                    }
+                    ui.separator();
+                    if ui.button(tr("menu.generate.sync_fields")).clicked() {
+                        self.sync_fields_from_code();
+                        ui.close_menu();
+                    }
+                });
+                self.tutorial.set_target(TutorialTarget::GenerateMenu, generate_menu.response.rect);
+
+                ui.menu_button(tr("menu.tools"), |ui| {
+                    if ui.button(tr("menu.tools.query_console")).clicked() {
+                        self.query_console.visible = true;
+                        ui.close_menu();
+                    }
+                    ui.separator();
+                    let record = if self.macro_recording.is_some() { "menu.tools.stop_recording" } else { "menu.tools.record_macro" };
+                    if ui.button(tr(record)).clicked() {
+                        self.toggle_macro_recording();
+                        ui.close_menu();
+                    }
+                    if ui.button(tr("menu.tools.macros")).clicked() {
+                        self.macro_manager.open();
+                        ui.close_menu();
+                    }
+                    if ui.button(tr("menu.tools.types")).clicked() {
+                        self.types_open = true;
+                        ui.close_menu();
+                    }
+                    if ui.button(tr("menu.tools.usage")).clicked() {
+                        self.usage_open = true;
+                        ui.close_menu();
+                    }
+                    if ui.button(tr("menu.tools.connection_matrix")).clicked() {
+                        self.connection_matrix.visible = true;
+                        ui.close_menu();
+                    }
+                    let assist = ui
+                        .add_enabled(self.assist_provider.is_some(), egui::Button::new(tr("menu.tools.assist")))
+                        .on_disabled_hover_text(tr("assist.no_provider"));
+                    if assist.clicked() {
+                        self.assist_open = true;
+                        ui.close_menu();
+                    }
                 });
 
                 // Help menu
-                ui.menu_button("Help", |ui| {
-                    if ui.button("Documentation").clicked() {
+                ui.menu_button(tr("menu.help"), |ui| {
+                    if ui.button(tr("menu.help.documentation")).clicked() {
                         // TODO: Open docs
                         ui.close_menu();
                     }
+                    if ui.button(tr("menu.help.component_reference")).clicked() {
+                        self.component_docs.open_reference(None);
+                        ui.close_menu();
+                    }
+                    if ui.button(tr("menu.help.tutorial")).clicked() {
+                        self.tutorial.restart();
+                        ui.close_menu();
+                    }
                     ui.separator();
-                    if ui.button("About").clicked() {
+                    if ui.button(tr("menu.help.about")).clicked() {
                         self.show_about = true;
                         ui.close_menu();
                     }
                 });
+
+                ui.separator();
+                self.render_bookmark_dropdown(ui);
             });
         });
     }
 
+    /// Dropdown listing the project's bookmarks with their shortcuts
+    fn render_bookmark_dropdown(&mut self, ui: &mut egui::Ui) {
+        let mut go_to = None;
+        let mut remove = None;
+        egui::ComboBox::from_id_salt("bookmarks")
+            .selected_text(tr("menu.bookmarks"))
+            .show_ui(ui, |ui| {
+                if self.project.bookmarks.is_empty() {
+                    ui.label(tr("menu.bookmarks.empty"));
+                }
+                for (index, bookmark) in self.project.bookmarks.iter().enumerate() {
+                    ui.horizontal(|ui| {
+                        let shortcut = if index < 9 { format!("Ctrl+{}", index + 1) } else { String::new() };
+                        if ui.selectable_label(false, format!("{}  {}", bookmark.name, shortcut)).clicked() {
+                            go_to = Some(index);
+                        }
+                        if ui.small_button("×").on_hover_text(tr("menu.bookmarks.remove")).clicked() {
+                            remove = Some(index);
+                        }
+                    });
+                }
+            });
+
+        if let Some(index) = go_to {
+            self.go_to_bookmark(index);
+        }
+        if let Some(index) = remove {
+            self.save_undo_state("Remove bookmark");
+            self.project.remove_bookmark(index);
+        }
+    }
+
+    fn go_to_bookmark(&mut self, index: usize) {
+        let from = self.project.viewport;
+        if self.project.go_to_bookmark(index) {
+            self.animate_viewport_from(from);
+            let name = self.project.bookmarks[index].name.clone();
+            self.set_status(format!("Jumped to bookmark: {}", name));
+        }
+    }
+
+    /// Frame every node, easing the viewport there
+    fn fit_to_content(&mut self) {
+        let from = self.project.viewport;
+        self.project.fit_to_content(800.0, 600.0);
+        self.animate_viewport_from(from);
+    }
+
+    /// Ease from `from` to the viewport just set, unless animations are off
+    fn animate_viewport_from(&mut self, from: Viewport) {
+        if self.animations.move_viewport(from, self.project.viewport) {
+            self.project.viewport = from;
+        }
+    }
+
+    /// Dialog for naming a new bookmark
+    fn render_bookmark_dialog(&mut self, ctx: &egui::Context) {
+        let Some(name) = self.new_bookmark.as_mut() else {
+            return;
+        };
+
+        let mut add = false;
+        let mut cancel = false;
+        egui::Window::new(tr("bookmark.title"))
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                ui.label(tr("bookmark.description"));
+                let response = ui.text_edit_singleline(name);
+                response.request_focus();
+                if response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                    add = true;
+                }
+                ui.horizontal(|ui| {
+                    if ui.add_enabled(!name.trim().is_empty(), egui::Button::new(tr("dialog.add"))).clicked() {
+                        add = true;
+                    }
+                    if ui.button(tr("dialog.cancel")).clicked() {
+                        cancel = true;
+                    }
+                });
+            });
+
+        if add && !name.trim().is_empty() {
+            let name = name.trim().to_string();
+            self.save_undo_state("Add bookmark");
+            let index = self.project.add_bookmark(&name);
+            self.new_bookmark = None;
+            if index < 9 {
+                self.set_status(format!("Added bookmark '{}' (Ctrl+{})", name, index + 1));
+            } else {
+                self.set_status(format!("Added bookmark '{}'", name));
+            }
+        } else if cancel {
+            self.new_bookmark = None;
+        }
+    }
+
     /// Render the component palette (left panel)
     fn render_palette(&mut self, ctx: &egui::Context) {
-        egui::SidePanel::left("palette")
+        let mut use_template = None;
+        let mut unpin = None;
+        let panel = egui::SidePanel::left("palette")
             .resizable(true)
             .default_width(200.0)
             .min_width(150.0)
             .max_width(400.0)
             .show(ctx, |ui| {
-                ui.heading("Components");
+                ui.heading(tr("palette.title"));
                 ui.separator();
 
                 // Search box
@@ -471,28 +1876,50 @@ impl ImmortalApp {
                     ui.label("🔍");
                     ui.text_edit_singleline(&mut self.state.palette_search);
                 });
+                if !self.state.namespace.is_empty() {
+                    ui.weak(trf("palette.adding_to", &[&self.state.namespace.join("::")]));
+                }
+
+                // Ranked by the registry, plus components whose translated
+                // name or description matches
+                let search = self.state.palette_search.trim().to_lowercase();
+                let filter = &self.state.palette_filter;
+                let mut matches: Vec<&ComponentDefinition> = if search.is_empty() {
+                    imortal_core::ComponentCategory::all()
+                        .iter()
+                        .flat_map(|category| self.registry.by_category(*category))
+                        .filter(|c| filter.accepts(c))
+                        .collect()
+                } else {
+                    self.registry.search_with(&search, filter)
+                };
+                if !search.is_empty() {
+                    let translated = self.registry.all().filter(|c| {
+                        filter.accepts(c)
+                            && !matches.iter().any(|m| m.id == c.id)
+                            && [i18n::lookup(&format!("component.{}.name", c.id)), i18n::lookup(&format!("component.{}.description", c.id))]
+                                .into_iter()
+                                .flatten()
+                                .any(|text| text.to_lowercase().contains(&search))
+                    });
+                    let translated: Vec<_> = translated.collect();
+                    matches.extend(translated);
+                }
+                render_palette_chips(ui, &self.registry, &matches, &mut self.state.palette_filter);
                 ui.separator();
 
                 // Component categories
+                let show_descriptions = self.config.show_descriptions;
+                let matches: Vec<ComponentDefinition> = matches.into_iter().cloned().collect();
                 egui::ScrollArea::vertical().show(ui, |ui| {
                     for category in imortal_core::ComponentCategory::all() {
-                        let components = self.registry.by_category(*category);
-                        if components.is_empty() {
-                            continue;
-                        }
-
-                        // Filter by search and collect info we need
-                        let search = self.state.palette_search.to_lowercase();
-                        let show_descriptions = self.config.show_descriptions;
-                        let filtered: Vec<_> = components.into_iter()
-                            .filter(|c| {
-                                search.is_empty() ||
-                                c.name.to_lowercase().contains(&search) ||
-                                c.description.to_lowercase().contains(&search)
-                            })
+                        // Collect the info we need to avoid borrow issues
+                        let filtered: Vec<_> = matches.iter()
+                            .filter(|c| c.category == *category)
                             .map(|c| {
-                                // Clone the info we need to avoid borrow issues
-                                (c.id.clone(), c.icon.to_string(), c.name.clone(), c.description.clone(), c.instantiate_default())
+                                let name = i18n::lookup(&format!("component.{}.name", c.id)).unwrap_or(&c.name).to_string();
+                                let description = i18n::lookup(&format!("component.{}.description", c.id)).unwrap_or(&c.description).to_string();
+                                (c.id.clone(), c.icon.clone(), name, description, c.instantiate_default(), c.clone())
                             })
                             .collect();
 
@@ -500,29 +1927,89 @@ impl ImmortalApp {
                             continue;
                         }
 
-                        let header = format!("{} {}", category.icon(), category.display_name());
-                        ui.collapsing(header, |ui| {
-                            for (_id, icon, name, description, node_template) in &filtered {
-                                let response = ui.add(
-                                    egui::Button::new(format!("{} {}", icon, name))
-                                        .min_size(egui::vec2(ui.available_width(), 0.0))
-                                );
+                        let category_name = i18n::lookup(&format!("category.{:?}", category).to_lowercase()).unwrap_or(category.display_name());
+                        let header = format!("{} {}", category.icon(), category_name);
+                        let open = self.palette_restore.then(|| self.palette_expanded.contains(category));
+                        let collapsing = egui::CollapsingHeader::new(header).open(open).show(ui, |ui| {
+                            for (id, icon, name, _, node_template, component) in &filtered {
+                                // Click to place automatically, or drag onto the canvas
+                                let (response, info) = ui.horizontal(|ui| {
+                                    let button = match self.icons.image(icon, 16.0) {
+                                        Some(image) => egui::Button::image_and_text(image, name.as_str()),
+                                        None => egui::Button::new(format!("{} {}", component.icon_text(), name)),
+                                    };
+                                    let response = ui.add(
+                                        button
+                                            .min_size(egui::vec2(ui.available_width() - 26.0, 0.0))
+                                            .sense(egui::Sense::click_and_drag())
+                                    );
+                                    let info = ui.small_button("ⓘ").on_hover_text(tr("docs.open"));
+                                    (response, info)
+                                }).inner;
+                                if info.clicked() {
+                                    self.component_docs.open_popover(id, info.rect.right_top());
+                                }
+                                response.dnd_set_drag_payload(ComponentDrag { component_id: id.clone() });
 
                                 if response.clicked() {
                                     // Save state for undo, then add component
                                     self.save_undo_state(&format!("Add {}", name));
-                                    self.project.add_node(node_template.clone());
+                                    let mut node = node_template.clone();
+                                    self.apply_default_width(&mut node);
+                                    node.namespace = self.state.namespace.clone();
+                                    node.position = self.placement.place(&self.project, &node);
+                                    let node_id = self.project.add_node(node);
+                                    self.node_added(node_id);
+                                    self.recent_components.record(id);
                                     self.set_status(format!("Added {}", name));
                                 }
 
                                 if show_descriptions {
-                                    response.on_hover_text(description);
+                                    response.on_hover_ui(|ui| docs::component_tooltip(ui, component));
                                 }
                             }
                         });
+                        if collapsing.openness > 0.0 {
+                            self.palette_expanded.insert(*category);
+                        } else {
+                            self.palette_expanded.remove(category);
+                        }
+                    }
+                    self.palette_restore = false;
+
+                    // Composites pinned as templates
+                    let search = self.state.palette_search.to_lowercase();
+                    let templates: Vec<(usize, String)> = self.root_project().templates.iter()
+                        .enumerate()
+                        .filter(|(_, template)| search.is_empty() || template.name.to_lowercase().contains(&search))
+                        .map(|(index, template)| (index, template.name.clone()))
+                        .collect();
+                    if !templates.is_empty() {
+                        ui.collapsing(format!("📌 {}", tr("template.palette")), |ui| {
+                            for (index, name) in &templates {
+                                ui.horizontal(|ui| {
+                                    if ui.button(format!("🧩 {}", name)).clicked() {
+                                        use_template = Some(*index);
+                                    }
+                                    if ui.small_button("×").on_hover_text(tr("template.unpin")).clicked() {
+                                        unpin = Some(*index);
+                                    }
+                                });
+                            }
+                        });
                     }
                 });
             });
+        self.tutorial.set_target(TutorialTarget::Palette, panel.response.rect);
+
+        if let Some(index) = use_template {
+            self.use_template(index);
+        }
+        if let Some(index) = unpin {
+            if let Some(template) = self.edit_root_project("Unpin template", |project| project.unpin_template(index)) {
+                self.set_status(trf("template.unpinned", &[&template.name]));
+            }
+        }
     }
 
     /// Render the properties panel (right panel)
@@ -533,50 +2020,292 @@ impl ImmortalApp {
             .min_width(200.0)
             .max_width(500.0)
             .show(ctx, |ui| {
-                ui.heading("Properties");
+                ui.heading(tr("properties.title"));
                 ui.separator();
 
-                if self.project.selected_nodes.is_empty() {
-                    ui.label("Select a component to view its properties");
+                let single_edge = match (self.project.selected_nodes.len(), self.project.selected_edges.len()) {
+                    (0, 1) => self.project.selected_edges.iter().next().and_then(|id| self.project.edges.get(id)).cloned(),
+                    _ => None,
+                };
+                if let Some(edge) = single_edge {
+                    self.render_edge_properties(ui, edge);
+                } else if self.project.selected_nodes.is_empty() {
+                    ui.label(tr("properties.empty"));
                 } else if self.project.selected_nodes.len() == 1 {
                     let node_id = *self.project.selected_nodes.iter().next().unwrap();
                     if let Some(node) = self.project.nodes.get(&node_id) {
                         self.render_node_properties(ui, node.clone());
                     }
                 } else {
-                    ui.label(format!("{} components selected", self.project.selected_nodes.len()));
+                    ui.label(trf("properties.multiple", &[&self.project.selected_nodes.len()]));
                 }
             });
     }
 
-    /// Render properties for a single node
-    fn render_node_properties(&mut self, ui: &mut egui::Ui, node: Node) {
-        // Node name
-        ui.horizontal(|ui| {
-            ui.label("Name:");
-            let mut name = node.name.clone();
-            if ui.text_edit_singleline(&mut name).changed() {
-                if let Some(n) = self.project.get_node_mut(node.id) {
-                    n.name = name;
-                }
-            }
-        });
-
+    /// Render properties for a single connection, with its style editable
+    fn render_edge_properties(&mut self, ui: &mut egui::Ui, edge: Edge) {
+        let endpoint = |id| self.project.get_node(id).map(|n| n.name.clone()).unwrap_or_default();
+        ui.label(trf("edge.endpoints", &[&endpoint(edge.from_node), &endpoint(edge.to_node)]));
         ui.horizontal(|ui| {
-            ui.label("Type:");
-            ui.label(&node.component_type);
+            ui.label(tr("edge.type"));
+            ui.label(connection_type_name(&edge.connection_type));
         });
 
-        ui.separator();
-
-        // Delete button
+        // Polymorphic relationships are edited as a target set on the entity
+        if edge.is_polymorphic() {
+            let association = self
+                .project
+                .polymorphic_associations(edge.from_node)
+                .into_iter()
+                .find(|a| a.name == self.project.association_name(&edge));
+            if let Some(association) = association {
+                let targets: Vec<String> = association.targets.iter().map(|id| endpoint(*id)).collect();
+                ui.label(trf("polymorphic.targets", &[&association.name, &targets.join(", ")]));
+            }
+        } else if edge.is_relationship() {
+            // Relationships can join on a field of each entity
+            let current = (edge.from_field().map(str::to_string), edge.to_field().map(str::to_string));
+            let mut fields = current.clone();
+            egui::Grid::new("edge_fields").num_columns(2).show(ui, |ui| {
+                for (label, node_id, field, salt) in [
+                    (tr("edge.from_field"), edge.from_node, &mut fields.0, "edge_from_field"),
+                    (tr("edge.to_field"), edge.to_node, &mut fields.1, "edge_to_field"),
+                ] {
+                    ui.label(label);
+                    let selected = field.clone().unwrap_or_else(|| tr("edge.whole_entity").to_string());
+                    egui::ComboBox::from_id_salt(salt).selected_text(selected).show_ui(ui, |ui| {
+                        ui.selectable_value(field, None, tr("edge.whole_entity"));
+                        for f in self.project.get_node(node_id).map(|n| n.fields.as_slice()).unwrap_or_default() {
+                            ui.selectable_value(field, Some(f.name.clone()), format!("{}: {}", f.name, f.data_type.to_rust_type()));
+                        }
+                    });
+                    ui.end_row();
+                }
+            });
+
+            if fields != current {
+                let mut candidate = edge.clone();
+                candidate.set_fields(fields.0.as_deref(), fields.1.as_deref());
+                match self.project.relationship_field_problem(&candidate) {
+                    Some(problem) => self.set_error(trf("edge.fields_rejected", &[&problem])),
+                    None => {
+                        self.save_undo_state("Set relationship fields");
+                        if let Some(e) = self.project.edges.get_mut(&edge.id) {
+                            e.set_fields(fields.0.as_deref(), fields.1.as_deref());
+                        }
+                    }
+                }
+            }
+        } else if let Some(policy) = edge.cache.clone() {
+            self.render_cache_policy(ui, edge.id, policy);
+        } else if let Some(flag) = &edge.flag {
+            ui.label(trf("flags.edge", &[flag]));
+        }
+        ui.separator();
+
+        let mut style = edge.style.clone();
+        let mut started_edit = false;
+        egui::Grid::new("edge_style").num_columns(2).show(ui, |ui| {
+            ui.label(tr("edge.line_style"));
+            egui::ComboBox::from_id_salt("edge_line_style")
+                .selected_text(style.line_style.display_name())
+                .show_ui(ui, |ui| {
+                    for line_style in imortal_ir::LineStyle::all() {
+                        started_edit |= ui.selectable_value(&mut style.line_style, *line_style, line_style.display_name()).changed();
+                    }
+                });
+            ui.end_row();
+
+            // One undo step per drag, not per frame of it
+            ui.label(tr("edge.thickness"));
+            let thickness = ui.add(egui::Slider::new(&mut style.thickness, 0.5..=8.0).step_by(0.5));
+            started_edit |= thickness.drag_started() || (thickness.changed() && !thickness.dragged());
+            ui.end_row();
+
+            for (label, arrow, salt) in [
+                (tr("edge.arrow_start"), &mut style.arrow_start, "edge_arrow_start"),
+                (tr("edge.arrow_end"), &mut style.arrow_end, "edge_arrow_end"),
+            ] {
+                ui.label(label);
+                egui::ComboBox::from_id_salt(salt)
+                    .selected_text(arrow.display_name())
+                    .show_ui(ui, |ui| {
+                        for option in imortal_ir::ArrowStyle::all() {
+                            started_edit |= ui.selectable_value(arrow, *option, option.display_name()).changed();
+                        }
+                    });
+                ui.end_row();
+            }
+
+            ui.label(tr("edge.color"));
+            let (r, g, b) = style.color.rgb();
+            egui::ComboBox::from_id_salt("edge_color")
+                .selected_text(egui::RichText::new("⬤").color(egui::Color32::from_rgb(r, g, b)))
+                .show_ui(ui, |ui| {
+                    for color in imortal_ir::EdgeColor::all() {
+                        let (r, g, b) = color.rgb();
+                        let swatch = egui::RichText::new(format!("⬤ {:?}", color)).color(egui::Color32::from_rgb(r, g, b));
+                        started_edit |= ui.selectable_value(&mut style.color, *color, swatch).changed();
+                    }
+                });
+            ui.end_row();
+        });
+
+        let defaults = imortal_ir::EdgeStyle::for_connection(&edge.connection_type);
+        if ui.add_enabled(style != defaults, egui::Button::new(tr("edge.reset_style"))).clicked() {
+            style = defaults;
+            started_edit = true;
+        }
+
+        if style != edge.style {
+            if started_edit {
+                self.save_undo_state("Edit connection style");
+            }
+            if let Some(e) = self.project.edges.get_mut(&edge.id) {
+                e.style = style;
+            }
+        }
+    }
+
+    /// Render properties for a single node
+    fn render_node_properties(&mut self, ui: &mut egui::Ui, node: Node) {
+        // Node name
+        ui.horizontal(|ui| {
+            ui.label("Name:");
+            let mut name = match &self.name_edit {
+                Some((id, name)) if *id == node.id => name.clone(),
+                _ => node.name.clone(),
+            };
+            let response = ui.text_edit_singleline(&mut name);
+            if std::mem::take(&mut self.focus_properties) {
+                response.request_focus();
+            }
+            if response.changed() {
+                self.name_edit = Some((node.id, name.clone()));
+            }
+            if response.lost_focus() && self.name_edit.take().is_some() && name != node.name {
+                self.rename_node(node.id, name);
+            }
+        });
+
+        ui.horizontal(|ui| {
+            ui.label("Type:");
+            ui.label(&node.component_type);
+        });
+
+        let mut enabled = node.enabled;
+        if ui.checkbox(&mut enabled, tr("disable.enabled_field")).on_hover_text(tr("disable.hint")).changed() {
+            self.project.clear_selection();
+            self.project.select_node(node.id);
+            self.set_selection_enabled(enabled);
+        }
+
+        let entity = node.component_type == "data.entity";
+        if !entity && self.properties_tab == PropertiesTab::Validation {
+            self.properties_tab = PropertiesTab::General;
+        }
+        ui.horizontal(|ui| {
+            ui.selectable_value(&mut self.properties_tab, PropertiesTab::General, tr("validation.tab_general"));
+            if entity {
+                ui.selectable_value(&mut self.properties_tab, PropertiesTab::Validation, tr("validation.tab_summary"));
+            }
+            ui.selectable_value(&mut self.properties_tab, PropertiesTab::Connections, tr("connections.tab"));
+            ui.selectable_value(&mut self.properties_tab, PropertiesTab::Advanced, tr("codegen_hints.tab"));
+        });
+        match self.properties_tab {
+            PropertiesTab::General => {}
+            PropertiesTab::Validation => {
+                ui.separator();
+                self.render_validation_summary(ui, &node);
+                return;
+            }
+            PropertiesTab::Advanced => {
+                ui.separator();
+                self.render_codegen_hints(ui, &node);
+                return;
+            }
+            PropertiesTab::Connections => {
+                ui.separator();
+                let form = match self.connection_form.take() {
+                    Some(form) if form.node == node.id => form,
+                    _ => ConnectionForm::new(node.id),
+                };
+                let form = self.connection_form.insert(form);
+                if let Some(action) = connections::show_node_connections(ui, &self.project, &node, form) {
+                    self.handle_connection_action(action);
+                }
+                return;
+            }
+        }
+
+        // Module the node lives in, applied when the field loses focus
+        ui.horizontal(|ui| {
+            ui.label(tr("namespace.field"));
+            let mut path = match &self.namespace_edit {
+                Some((id, path)) if *id == node.id => path.clone(),
+                _ => node.namespace_path(),
+            };
+            let response = ui.add(egui::TextEdit::singleline(&mut path).hint_text("billing::invoices"));
+            if response.changed() {
+                self.namespace_edit = Some((node.id, path.clone()));
+            }
+            if response.lost_focus() && self.namespace_edit.take().is_some() {
+                let namespace = imortal_ir::parse_namespace(&path);
+                if namespace != node.namespace {
+                    self.save_undo_state("Move to module");
+                    if let Some(n) = self.project.get_node_mut(node.id) {
+                        n.namespace = namespace.clone();
+                    }
+                    // Moved out of the entered module, so it's hidden now
+                    if !namespace.starts_with(&self.state.namespace) {
+                        self.project.clear_selection();
+                    }
+                    let module = if namespace.is_empty() { tr("namespace.root").to_string() } else { namespace.join("::") };
+                    self.set_status(trf("namespace.moved", &[&node.name, &module]));
+                }
+            }
+        });
+
+        // Tags for the tag bar and tag-scoped generation, applied when the
+        // field loses focus
+        ui.horizontal(|ui| {
+            ui.label(tr("tags.field"));
+            let mut text = match &self.tags_edit {
+                Some((id, text)) if *id == node.id => text.clone(),
+                _ => node.tags.join(", "),
+            };
+            let response = ui.add(egui::TextEdit::singleline(&mut text).hint_text("MVP, phase-2"));
+            if response.changed() {
+                self.tags_edit = Some((node.id, text.clone()));
+            }
+            if response.lost_focus() && self.tags_edit.take().is_some() {
+                let tags = imortal_ir::parse_tags(&text);
+                if tags != node.tags {
+                    self.save_undo_state("Edit tags");
+                    if let Some(n) = self.project.get_node_mut(node.id) {
+                        n.tags = tags;
+                    }
+                    self.set_status(trf("tags.edited", &[&node.name]));
+                }
+            }
+        });
+
+        if node.is_composite() {
+            self.render_template_parameters(ui, &node);
+        }
+
+        ui.separator();
+
+        // Delete button
         let node_id_to_delete = node.id;
         let node_name = node.name.clone();
         if ui.button("🗑 Delete Component").clicked() {
-            self.save_undo_state(&format!("Delete {}", node_name));
-            self.project.remove_node(node_id_to_delete);
-            self.set_status("Component deleted");
-            return; // Exit early since node no longer exists
+            self.project.clear_selection();
+            self.project.select_node(node_id_to_delete);
+            if self.delete_selection() > 0 {
+                self.set_status(format!("Deleted {}", node_name));
+            }
+            return; // Exit early since node may no longer exist
         }
 
         ui.separator();
@@ -584,13 +2313,14 @@ impl ImmortalApp {
         // Fields section
         let node_id = node.id;
         let is_entity = node.component_type == "data.entity";
+        let is_settings = node.component_type == "config.settings";
+        let fields_editable = is_entity || is_settings;
 
         egui::CollapsingHeader::new("Fields")
             .default_open(true)
             .show(ui, |ui| {
                 // List existing fields with edit/delete options
                 let mut field_to_delete: Option<uuid::Uuid> = None;
-                let mut field_updates: Vec<(uuid::Uuid, String, DataType, bool)> = Vec::new();
 
                 for field in &node.fields {
                     ui.horizontal(|ui| {
@@ -604,18 +2334,33 @@ impl ImmortalApp {
                         }
 
                         // Data type display
-                        ui.label(format!("{}", Self::data_type_display(&field.data_type)));
+                        match imortal_ir::project_type_name(&field.data_type) {
+                            Some(name) => ui.label(format!("🏷 {}", name)),
+                            None => ui.label(Self::data_type_display(&field.data_type).to_string()),
+                        };
+                        if let Some(unit) = &field.unit {
+                            ui.weak(unit.symbol()).on_hover_text(unit.to_string());
+                        }
 
                         // Required indicator
                         if field.required {
                             ui.label("*");
                         }
 
+                        if is_entity && ui.small_button("✔").on_hover_text(tr("validation.edit")).clicked() {
+                            self.validation_editor = Some((node_id, field.id));
+                        }
+
+                        // Environment variable the setting is loaded from
+                        if is_settings {
+                            ui.weak(format!("${}", field.env_var()));
+                        }
+
                         // Delete button (not for system fields)
-                        if !is_system_field && is_entity {
-                            if ui.small_button("🗑").on_hover_text("Delete field").clicked() {
-                                field_to_delete = Some(field.id);
-                            }
+                        if !is_system_field && fields_editable
+                            && ui.small_button("🗑").on_hover_text("Delete field").clicked()
+                        {
+                            field_to_delete = Some(field.id);
                         }
                     });
                 }
@@ -624,13 +2369,21 @@ impl ImmortalApp {
                 if let Some(field_id) = field_to_delete {
                     self.save_undo_state("Delete field");
                     if let Some(n) = self.project.get_node_mut(node_id) {
+                        let removed = n.fields.iter().find(|f| f.id == field_id).map(|f| f.name.clone());
                         n.fields.retain(|f| f.id != field_id);
                         self.set_status("Field deleted");
+                        if let Some(name) = removed {
+                            self.record_macro_step(MacroStep::RemoveField { name });
+                        }
                     }
                 }
 
-                // Add new field section (only for entities)
-                if is_entity {
+                if is_entity && ui.button(tr("field_import.open")).clicked() {
+                    self.open_field_import(node_id);
+                }
+
+                // Add new field section (entities and settings)
+                if fields_editable {
                     ui.separator();
                     ui.label("Add New Field:");
 
@@ -639,25 +2392,33 @@ impl ImmortalApp {
                         ui.text_edit_singleline(&mut self.new_field_name);
                     });
 
+                    let choices = self.field_type_choices(is_entity);
                     ui.horizontal(|ui| {
                         ui.label("Type:");
+                        let selected = choices.get(self.new_field_type).map_or("String", |(label, _)| label.as_str());
                         egui::ComboBox::from_id_salt("field_type")
-                            .selected_text(Self::data_type_options()[self.new_field_type])
+                            .selected_text(selected)
                             .show_ui(ui, |ui| {
-                                for (idx, type_name) in Self::data_type_options().iter().enumerate() {
-                                    ui.selectable_value(&mut self.new_field_type, idx, *type_name);
+                                for (idx, (label, _)) in choices.iter().enumerate() {
+                                    ui.selectable_value(&mut self.new_field_type, idx, label);
+                                }
+                                if is_entity && ui.small_button(tr("types.manage")).clicked() {
+                                    self.types_open = true;
                                 }
                             });
                     });
 
                     if ui.button("➕ Add Field").clicked() && !self.new_field_name.is_empty() {
-                        let data_type = Self::index_to_data_type(self.new_field_type);
+                        let data_type = choices.get(self.new_field_type)
+                            .map(|(_, dt)| dt.clone())
+                            .unwrap_or(DataType::String);
                         let new_field = Field::new(self.new_field_name.clone(), data_type);
 
                         self.save_undo_state(&format!("Add field: {}", self.new_field_name));
                         if let Some(n) = self.project.get_node_mut(node_id) {
-                            n.fields.push(new_field);
+                            n.fields.push(new_field.clone());
                             self.set_status(format!("Added field: {}", self.new_field_name));
+                            self.record_macro_step(MacroStep::AddField { field: Box::new(new_field) });
                         }
                         self.new_field_name.clear();
                         self.new_field_type = 0;
@@ -665,8 +2426,34 @@ impl ImmortalApp {
                 }
             });
 
+        if is_entity {
+            let mut realtime = node.is_realtime();
+            if ui.checkbox(&mut realtime, tr("realtime.broadcast")).on_hover_text(tr("realtime.hint")).changed() {
+                self.save_undo_state("Toggle realtime");
+                if let Some(n) = self.project.get_node_mut(node.id) {
+                    n.set_config("realtime", realtime);
+                }
+                self.record_macro_step(MacroStep::SetConfig { key: "realtime".to_string(), value: realtime.into() });
+            }
+            self.render_inheritance(ui, &node);
+            self.render_polymorphic(ui, &node);
+        }
+        if node.component_type == "api.rest" {
+            self.render_caching(ui, &node);
+        }
+        if node.component_type.starts_with("api.") {
+            self.render_flag_gate(ui, &node);
+        }
+        if node.custom_code().is_some() {
+            self.render_custom_code(ui, &node);
+        }
+
         // Ports section
         ui.collapsing("Ports", |ui| {
+            if node.allows_port_editing() {
+                self.render_port_editor(ui, &node);
+                return;
+            }
             if !node.ports.inputs.is_empty() {
                 ui.label("Inputs:");
                 for port in &node.ports.inputs {
@@ -700,8 +2487,10 @@ impl ImmortalApp {
             }
         }
 
-        // Configuration section - editable
-        if !node.config.is_empty() {
+        // Configuration section - editable; a custom code node's snippet has
+        // an editor of its own
+        let hidden_config = |key: &str| node.custom_code().is_some() && key == "code";
+        if node.config.keys().any(|key| !hidden_config(key)) {
             egui::CollapsingHeader::new("Configuration")
                 .default_open(true)
                 .show(ui, |ui| {
@@ -709,7 +2498,7 @@ impl ImmortalApp {
 
                     // Sort config keys for consistent display, with important ones first
                     let mut config_clone: Vec<(String, imortal_core::ConfigValue)> =
-                        node.config.iter().map(|(k, v)| (k.clone(), v.clone())).collect();
+                        node.config.iter().filter(|(k, _)| !hidden_config(k)).map(|(k, v)| (k.clone(), v.clone())).collect();
 
                     // Custom sort: backend first, then connection settings, then others
                     config_clone.sort_by(|(a, _), (b, _)| {
@@ -812,7 +2601,10 @@ impl ImmortalApp {
                     if !config_updates.is_empty() {
                         if let Some(n) = self.project.get_node_mut(node_id) {
                             for (key, value) in config_updates {
-                                n.config.insert(key, value);
+                                n.config.insert(key.clone(), value.clone());
+                                if let Some(recording) = &mut self.macro_recording {
+                                    recording.record(MacroStep::SetConfig { key, value });
+                                }
                             }
                         }
                     }
@@ -824,16 +2616,12 @@ impl ImmortalApp {
                         ui.add_space(8.0);
 
                         ui.horizontal(|ui| {
-                            if ui.button("🔌 Test Connection").clicked() {
-                                let result = Self::test_database_connection(&node.config);
-                                match result {
-                                    Ok(msg) => {
-                                        self.db_connection_result = Some((node_id, true, msg, std::time::Instant::now()));
-                                    }
-                                    Err(err) => {
-                                        self.db_connection_result = Some((node_id, false, err, std::time::Instant::now()));
-                                    }
-                                }
+                            let testing = self.connection_test_running(node_id);
+                            if ui.add_enabled(!testing, egui::Button::new(tr("connection_test.test"))).clicked() {
+                                self.start_connection_test(node_id);
+                            }
+                            if testing {
+                                ui.spinner();
                             }
                         });
 
@@ -970,7 +2758,7 @@ impl ImmortalApp {
     }
 
     /// Get display string for a data type
-    fn data_type_display(dt: &DataType) -> &'static str {
+    pub(crate) fn data_type_display(dt: &DataType) -> &'static str {
         match dt {
             DataType::String => "String",
             DataType::Text => "Text",
@@ -994,7 +2782,7 @@ impl ImmortalApp {
     }
 
     /// Get list of available data types for the dropdown
-    fn data_type_options() -> &'static [&'static str] {
+    pub(crate) fn data_type_options() -> &'static [&'static str] {
         &[
             "String",
             "Text",
@@ -1010,7 +2798,7 @@ impl ImmortalApp {
     }
 
     /// Convert dropdown index to DataType
-    fn index_to_data_type(idx: usize) -> DataType {
+    pub(crate) fn index_to_data_type(idx: usize) -> DataType {
         match idx {
             0 => DataType::String,
             1 => DataType::Text,
@@ -1026,344 +2814,1079 @@ impl ImmortalApp {
         }
     }
 
-    /// Get the number of content items to display for a node (for height calculation)
-    fn get_node_content_items(node: &Node) -> usize {
-        match node.component_type.as_str() {
-            "data.entity" => node.fields.len(),
-            "storage.database" => 5,  // backend, host:port, database, username, ssl
-            "api.rest" => 3,          // method, path, auth
-            _ => 0,
+    /// Field type choices: the built-in types, then for entities the
+    /// project's own types
+    fn field_type_choices(&self, entity: bool) -> Vec<(String, DataType)> {
+        let mut choices: Vec<(String, DataType)> = Self::data_type_options().iter().enumerate()
+            .map(|(idx, name)| (name.to_string(), Self::index_to_data_type(idx)))
+            .collect();
+        if entity {
+            choices.extend(self.project.meta.types.iter().map(|t| (format!("🏷 {}", t.name), t.data_type())));
         }
+        choices
     }
 
-    /// Render the main canvas
-    fn render_canvas(&mut self, ctx: &egui::Context) {
-        egui::CentralPanel::default().show(ctx, |ui| {
-            // Canvas background
-            let (rect, response) = ui.allocate_exact_size(
-                ui.available_size(),
-                egui::Sense::click_and_drag()
-            );
-
-            let painter = ui.painter_at(rect);
-
-            // Draw background
-            painter.rect_filled(rect, 0.0, egui::Color32::from_rgb(30, 30, 35));
-
-            // Draw grid if enabled
-            if self.config.show_grid {
-                self.draw_grid(&painter, rect);
-            }
-
-            let zoom = self.project.viewport.zoom;
-            let pan = egui::vec2(self.project.viewport.pan_x, self.project.viewport.pan_y);
+    /// Port type choices: Any, Trigger, the field types, and one entry per entity
+    fn port_type_choices(&self) -> Vec<(String, DataType)> {
+        let mut choices = vec![
+            ("Any".to_string(), DataType::Any),
+            ("Trigger".to_string(), DataType::Trigger),
+        ];
+        choices.extend(Self::data_type_options().iter().enumerate()
+            .map(|(idx, name)| (name.to_string(), Self::index_to_data_type(idx))));
+
+        let mut entities: Vec<&str> = self.project.find_nodes_by_type("data.entity")
+            .into_iter()
+            .map(|n| n.name.as_str())
+            .collect();
+        entities.sort();
+        choices.extend(entities.into_iter()
+            .map(|name| (format!("Entity: {}", name), DataType::Entity(name.to_string()))));
+        choices
+    }
 
-            // Track mouse position for connection drawing
-            if let Some(pos) = ui.input(|i| i.pointer.hover_pos()) {
-                self.connection_mouse_pos = pos;
-            }
+    /// Editable port list for logic and custom components
+    fn render_port_editor(&mut self, ui: &mut egui::Ui, node: &Node) {
+        let node_id = node.id;
+        let choices = self.port_type_choices();
 
-            // Handle canvas interactions - panning with drag
-            if response.dragged_by(egui::PointerButton::Middle) ||
-               (response.dragged() && ui.input(|i| i.modifiers.shift)) {
-                let delta = response.drag_delta();
-                self.project.pan(delta.x, delta.y);
+        for (label, ports) in [("Inputs:", &node.ports.inputs), ("Outputs:", &node.ports.outputs)] {
+            if ports.is_empty() {
+                continue;
             }
-
-            // Check for port interactions
-            let mut clicked_port: Option<(NodeId, String, bool)> = None; // (node_id, port_name, is_output)
-            let mut hovered_port: Option<(NodeId, String, bool)> = None;
-            let mut port_was_clicked = false;
-
-            // Get mouse state
-            let mouse_clicked = ui.input(|i| i.pointer.primary_clicked());
-            let pointer_pos = ui.input(|i| i.pointer.interact_pos()).or_else(|| ui.input(|i| i.pointer.hover_pos()));
-
-            if let Some(pointer_pos) = pointer_pos {
-                // Check all nodes for port hover/click
-                for node in self.project.nodes.values() {
-                    let node_screen_pos = rect.min + pan + egui::vec2(node.position.x * zoom, node.position.y * zoom);
-                    let node_width = node.size.width * zoom;
-
-                    // Calculate actual node height (same logic as draw_node)
-                    let header_height = 25.0 * zoom;
-                    let field_height_val = 18.0 * zoom;
-                    let content_items = Self::get_node_content_items(node);
-
-                    let node_height = if content_items > 0 {
-                        header_height + (content_items as f32 * field_height_val) + (8.0 * zoom)
-                    } else {
-                        node.size.height * zoom
-                    };
-
-                    // Output port (right side)
-                    let output_port_pos = egui::pos2(
-                        node_screen_pos.x + node_width + 8.0,
-                        node_screen_pos.y + node_height / 2.0
-                    );
-                    let dist_to_output = pointer_pos.distance(output_port_pos);
-                    if dist_to_output < 15.0 {
-                        hovered_port = Some((node.id, "output".to_string(), true));
-                        if mouse_clicked {
-                            clicked_port = Some((node.id, "output".to_string(), true));
+            ui.label(label);
+            for port in ports {
+                ui.horizontal(|ui| {
+                    let mut name = port.name.clone();
+                    if ui.add(egui::TextEdit::singleline(&mut name).desired_width(90.0)).changed() {
+                        if let Some(n) = self.project.get_node_mut(node_id) {
+                            let _ = n.rename_port(&port.id, name);
                         }
                     }
 
-                    // Input port (left side)
-                    let input_port_pos = egui::pos2(
-                        node_screen_pos.x - 8.0,
-                        node_screen_pos.y + node_height / 2.0
-                    );
-                    let dist_to_input = pointer_pos.distance(input_port_pos);
-                    if dist_to_input < 15.0 {
-                        hovered_port = Some((node.id, "input".to_string(), false));
-                        if mouse_clicked {
-                            clicked_port = Some((node.id, "input".to_string(), false));
+                    let current = choices.iter()
+                        .find(|(_, dt)| *dt == port.data_type)
+                        .map(|(label, _)| label.clone())
+                        .unwrap_or_else(|| Self::data_type_display(&port.data_type).to_string());
+                    egui::ComboBox::from_id_salt(("port_type", &port.id))
+                        .selected_text(current)
+                        .width(90.0)
+                        .show_ui(ui, |ui| {
+                            for (label, data_type) in &choices {
+                                if ui.selectable_label(*data_type == port.data_type, label).clicked() {
+                                    self.save_undo_state("Change port type");
+                                    if let Some(n) = self.project.get_node_mut(node_id) {
+                                        let _ = n.set_port_type(&port.id, data_type.clone());
+                                    }
+                                }
+                            }
+                        });
+
+                    if ui.small_button("🗑").on_hover_text("Remove port").clicked() {
+                        let connections = self.project.port_edges(node_id, &port.id).len();
+                        if connections == 0 {
+                            self.remove_port(node_id, &port.id);
+                        } else {
+                            self.pending_port_removal = Some((node_id, port.id.clone(), connections));
                         }
                     }
-                }
+                });
             }
+        }
 
-            // Handle port clicks for connection drawing
-            if let Some((node_id, port_name, _is_output)) = clicked_port {
-                port_was_clicked = true;
-                if self.drawing_connection {
-                    // Complete the connection
-                    if let Some(from_node_id) = self.connection_from_node {
-                        if from_node_id != node_id {
-                            // Determine source and target based on which port was clicked first
-                            let (source_id, target_id) = if self.connection_from_port == "output" {
-                                (from_node_id, node_id)
-                            } else {
-                                (node_id, from_node_id)
-                            };
-
-                            // Get actual port names from the nodes
-                            let from_port = self.project.get_node(source_id)
-                                .and_then(|n| n.ports.outputs.first())
-                                .map(|p| p.id.clone())
-                                .unwrap_or_else(|| "out".to_string());
-
-                            let to_port = self.project.get_node(target_id)
-                                .and_then(|n| n.ports.inputs.first())
-                                .map(|p| p.id.clone())
-                                .unwrap_or_else(|| "in".to_string());
-
-                            // Create dependency edge (skips port validation)
-                            self.save_undo_state("Create connection");
-                            let edge = Edge::dependency(source_id, target_id);
-                            match self.project.add_edge(edge) {
-                                Ok(_) => self.set_status("Connection created"),
-                                Err(e) => self.set_status(format!("Failed: {}", e)),
-                            }
+        ui.separator();
+        ui.horizontal(|ui| {
+            ui.add(egui::TextEdit::singleline(&mut self.new_port_name)
+                .hint_text("Port name")
+                .desired_width(90.0));
+            let selected = choices.get(self.new_port_type).map(|(label, _)| label.as_str()).unwrap_or("Any");
+            egui::ComboBox::from_id_salt("new_port_type")
+                .selected_text(selected)
+                .width(90.0)
+                .show_ui(ui, |ui| {
+                    for (idx, (label, _)) in choices.iter().enumerate() {
+                        ui.selectable_value(&mut self.new_port_type, idx, label);
+                    }
+                });
+        });
+        ui.horizontal(|ui| {
+            let ready = !self.new_port_name.trim().is_empty();
+            for (label, direction) in [("➕ Input", PortDirection::Input), ("➕ Output", PortDirection::Output)] {
+                if ui.add_enabled(ready, egui::Button::new(label)).clicked() {
+                    let data_type = choices.get(self.new_port_type)
+                        .map(|(_, dt)| dt.clone())
+                        .unwrap_or(DataType::Any);
+                    let name = self.new_port_name.trim().to_string();
+                    self.save_undo_state("Add port");
+                    if let Some(n) = self.project.get_node_mut(node_id) {
+                        match n.add_custom_port(direction, name.as_str(), data_type) {
+                            Ok(_) => self.set_status(format!("Added port: {}", name)),
+                            Err(e) => self.set_error(format!("Failed: {}", e)),
                         }
                     }
-                    self.drawing_connection = false;
-                    self.connection_from_node = None;
-                    self.connection_from_port.clear();
-                } else {
-                    // Start drawing connection
-                    self.drawing_connection = true;
-                    self.connection_from_node = Some(node_id);
-                    self.connection_from_port = port_name;
-                    self.set_status("Click another port to connect, or press Escape to cancel");
+                    self.new_port_name.clear();
                 }
             }
+        });
+    }
 
-            // Cancel connection drawing with Escape or right-click
-            if self.drawing_connection {
-                if ui.input(|i| i.key_pressed(egui::Key::Escape)) || response.secondary_clicked() {
-                    self.drawing_connection = false;
-                    self.connection_from_node = None;
-                    self.connection_from_port.clear();
-                    self.set_status("Connection cancelled");
-                }
-            }
+    /// Remove a port together with its connections, as one undo step
+    fn remove_port(&mut self, node_id: NodeId, port_id: &str) {
+        self.save_undo_state("Remove port");
+        match self.project.remove_port(node_id, port_id) {
+            Ok(port) => self.set_status(format!("Removed port: {}", port.name)),
+            Err(e) => self.set_error(format!("Failed: {}", e)),
+        }
+    }
 
-            // Handle Delete/Backspace to delete selected nodes and edges
-            let delete_pressed = ui.input(|i| {
-                i.key_pressed(egui::Key::Delete) || i.key_pressed(egui::Key::Backspace)
+    /// Ask before removing a port that still has connections
+    fn render_port_removal_confirm(&mut self, ctx: &egui::Context) {
+        let Some((node_id, port_id, connections)) = self.pending_port_removal.clone() else {
+            return;
+        };
+        let port_name = self.project.get_node(node_id)
+            .and_then(|n| n.ports.get(&port_id))
+            .map(|p| p.name.clone())
+            .unwrap_or(port_id.clone());
+        let mut remove = false;
+        let mut cancel = false;
+
+        egui::Window::new(tr("remove_port.title"))
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                ui.label(trf("remove_port.summary", &[&port_name, &connections]));
+                ui.add_space(10.0);
+                ui.horizontal(|ui| {
+                    if ui.button(tr("remove_port.remove")).clicked() {
+                        remove = true;
+                    }
+                    if ui.button(tr("dialog.cancel")).clicked() {
+                        cancel = true;
+                    }
+                });
             });
-            if delete_pressed && !self.drawing_connection {
-                let selected_count = self.project.selected_nodes.len() + self.project.selected_edges.len();
-                if selected_count > 0 {
-                    self.save_undo_state(&format!("Delete {} item(s)", selected_count));
-                    self.project.delete_selected();
-                    self.set_status(format!("Deleted {} item(s)", selected_count));
-                }
-            }
-
-            // Handle Undo/Redo keyboard shortcuts
-            let ctrl_held = ui.input(|i| i.modifiers.ctrl || i.modifiers.mac_cmd);
-            let shift_held = ui.input(|i| i.modifiers.shift);
-
-            if ctrl_held && ui.input(|i| i.key_pressed(egui::Key::Z)) {
-                if shift_held {
-                    self.redo();
-                } else {
-                    self.undo();
-                }
-            }
-            if ctrl_held && ui.input(|i| i.key_pressed(egui::Key::Y)) {
-                self.redo();
-            }
-
-            // Handle node selection on click (only if not clicking a port)
-            if mouse_clicked && !port_was_clicked && !self.drawing_connection {
-                if let Some(pointer_pos) = response.interact_pointer_pos() {
-                    // Check if click is on any node
-                    let mut clicked_node_id = None;
-                    for node in self.project.nodes.values() {
-                        let node_rect = egui::Rect::from_min_size(
-                            rect.min + pan + egui::vec2(node.position.x * zoom, node.position.y * zoom),
-                            egui::vec2(node.size.width * zoom, node.size.height * zoom)
-                        );
 
-                        if node_rect.contains(pointer_pos) {
-                            clicked_node_id = Some(node.id);
-                            break;
-                        }
-                    }
+        if remove {
+            self.pending_port_removal = None;
+            self.remove_port(node_id, &port_id);
+        } else if cancel {
+            self.pending_port_removal = None;
+        }
+    }
 
-                    // Update selection
-                    let shift_held = ui.input(|i| i.modifiers.shift);
-                    if let Some(node_id) = clicked_node_id {
-                        if shift_held {
-                            // Toggle selection with shift
-                            if self.project.selected_nodes.contains(&node_id) {
-                                self.project.deselect_node(node_id);
-                            } else {
-                                self.project.select_node(node_id);
+    /// Ask before deleting nodes others depend on, listing the dependent
+    /// nodes, the connections, and the generated files affected
+    fn render_deletion_impact(&mut self, ctx: &egui::Context) {
+        let Some(pending) = &self.pending_deletion else {
+            return;
+        };
+        let node_label = |id: &NodeId| {
+            self.project.get_node(*id).map_or_else(|| id.to_string(), |node| format!("{} ({})", node.name, node.component_type))
+        };
+        let node_name = |id: NodeId| self.project.get_node(id).map_or_else(|| id.to_string(), |node| node.name.clone());
+        let mut dependents: Vec<String> = pending.impact.dependents.iter().map(node_label).collect();
+        dependents.sort();
+        let mut edges: Vec<String> = pending
+            .impact
+            .edges
+            .iter()
+            .filter_map(|id| self.project.edges.get(id))
+            .map(|edge| format!("{} → {}", node_name(edge.from_node), node_name(edge.to_node)))
+            .collect();
+        edges.sort();
+        let (mut cascade, mut delete_only, mut cancel) = (false, false, false);
+
+        egui::Window::new(tr("delete_impact.title"))
+            .collapsible(false)
+            .resizable(true)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                ui.label(trf("delete_impact.summary", &[&pending.nodes.len(), &dependents.len()]));
+                ui.add_space(6.0);
+                egui::ScrollArea::vertical().max_height(320.0).show(ui, |ui| {
+                    egui::CollapsingHeader::new(trf("delete_impact.dependents", &[&dependents.len()]))
+                        .default_open(true)
+                        .show(ui, |ui| {
+                            for name in &dependents {
+                                ui.label(format!("• {}", name));
                             }
-                        } else {
-                            // Single select
-                            self.project.clear_selection();
-                            self.project.select_node(node_id);
+                        });
+                    egui::CollapsingHeader::new(trf("delete_impact.edges", &[&edges.len()])).show(ui, |ui| {
+                        for edge in &edges {
+                            ui.label(format!("• {}", edge));
                         }
-                    } else {
-                        // Clicked on empty canvas - deselect all
-                        if !shift_held {
-                            self.project.clear_selection();
+                    });
+                    match &pending.artifacts {
+                        Ok(artifacts) => {
+                            let count = artifacts.removed.len() + artifacts.changed.len();
+                            egui::CollapsingHeader::new(trf("delete_impact.artifacts", &[&count])).show(ui, |ui| {
+                                for path in &artifacts.removed {
+                                    ui.label(trf("delete_impact.removed_file", &[path]));
+                                }
+                                for path in &artifacts.changed {
+                                    ui.label(trf("delete_impact.changed_file", &[path]));
+                                }
+                            });
+                        }
+                        Err(e) => {
+                            ui.weak(trf("delete_impact.artifacts_unknown", &[e]));
                         }
                     }
-                }
-            }
+                });
+                ui.add_space(10.0);
+                ui.horizontal(|ui| {
+                    if ui.button(tr("delete_impact.cascade")).on_hover_text(tr("delete_impact.cascade_hint")).clicked() {
+                        cascade = true;
+                    }
+                    if ui.button(tr("delete_impact.delete_only")).clicked() {
+                        delete_only = true;
+                    }
+                    if ui.button(tr("delete_impact.cancel")).clicked() {
+                        cancel = true;
+                    }
+                });
+            });
 
-            // Handle node dragging (only if not drawing connection)
-            if !self.drawing_connection && response.dragged_by(egui::PointerButton::Primary) && !ui.input(|i| i.modifiers.shift) {
-                let delta = response.drag_delta();
+        if !(cascade || delete_only || cancel) {
+            return;
+        }
+        let Some(pending) = self.pending_deletion.take() else {
+            return;
+        };
+        if cancel {
+            return;
+        }
+        // Delete what was asked for, not whatever is selected now
+        self.project.clear_selection();
+        let nodes = if cascade { &pending.nodes | &pending.impact.dependents } else { pending.nodes };
+        for id in nodes {
+            self.project.select_node(id);
+        }
+        for id in pending.edges {
+            self.project.select_edge(id);
+        }
+        let deleted = self.delete_selection_confirmed();
+        self.set_status(trf("delete_impact.deleted", &[&deleted]));
+    }
 
-                // Move selected nodes
-                if !self.project.selected_nodes.is_empty() {
-                    let selected: Vec<_> = self.project.selected_nodes.iter().cloned().collect();
-                    for node_id in selected {
-                        if let Some(node) = self.project.get_node_mut(node_id) {
-                            node.position.x += delta.x / zoom;
-                            node.position.y += delta.y / zoom;
-                        }
-                    }
-                }
-            }
+    /// Render the main canvas
+    fn render_canvas(&mut self, ctx: &egui::Context) {
+        self.animations.configure(self.config.animations, self.config.animation_speed);
+        if let Some(viewport) = self.animations.tick(ctx.input(|i| i.stable_dt)) {
+            self.project.viewport = viewport;
+        }
+        if self.animations.is_animating() {
+            ctx.request_repaint();
+        }
 
-            // Draw edges first (below nodes)
-            for edge in self.project.edges.values() {
-                self.draw_edge(&painter, rect, edge);
+        let panel = egui::CentralPanel::default().show(ctx, |ui| {
+            if self.views.split().is_none() {
+                self.render_canvas_pane(ui, true);
+                return;
             }
 
-            // Draw nodes with ports
-            for node in self.project.nodes.values() {
-                let is_selected = self.project.selected_nodes.contains(&node.id);
-                self.draw_node(&painter, rect, node, is_selected);
+            // Split canvas: the active view on the left, the split view on the right
+            ui.columns(2, |columns| {
+                self.render_canvas_pane(&mut columns[0], true);
+                if let Some(viewport) = self.views.split_viewport_mut() {
+                    std::mem::swap(&mut self.project.viewport, viewport);
+                }
+                self.render_canvas_pane(&mut columns[1], false);
+                if let Some(viewport) = self.views.split_viewport_mut() {
+                    std::mem::swap(&mut self.project.viewport, viewport);
+                }
+            });
+        });
+        self.tutorial.set_target(TutorialTarget::Canvas, panel.response.rect);
+    }
 
-                // Draw ports on node
-                let node_screen_pos = rect.min + pan + egui::vec2(node.position.x * zoom, node.position.y * zoom);
-                let node_width = node.size.width * zoom;
+    // Presentation mode
 
-                // Calculate actual node height (same logic as draw_node)
-                let header_height = 25.0 * zoom;
-                let field_height = 18.0 * zoom;
-                let content_items = Self::get_node_content_items(node);
+    fn start_presentation(&mut self) {
+        let mut presentation = Presentation::new();
+        presentation.go_to(&self.project.slides, 0, self.project.viewport);
+        self.presentation = Some(presentation);
+        self.drawing_connection = false;
+        self.connection_from_node = None;
+        self.resizing_node = None;
+        self.node_drag = None;
+    }
 
-                let node_height = if content_items > 0 {
-                    header_height + (content_items as f32 * field_height) + (8.0 * zoom)
-                } else {
-                    node.size.height * zoom
-                };
+    /// Render the read-only presentation: canvas only, with slide navigation
+    fn render_presentation(&mut self, ctx: &egui::Context) {
+        let Some(mut presentation) = self.presentation.take() else {
+            return;
+        };
 
-                // Output port (right side) - green circle
-                let output_port_pos = egui::pos2(
-                    node_screen_pos.x + node_width + 8.0,
-                    node_screen_pos.y + node_height / 2.0
-                );
-                let output_hovered = hovered_port.as_ref().map_or(false, |(nid, _, is_out)| *nid == node.id && *is_out);
-                let output_color = if output_hovered {
-                    egui::Color32::from_rgb(100, 255, 100)
-                } else {
-                    egui::Color32::from_rgb(80, 200, 80)
-                };
-                painter.circle_filled(output_port_pos, if output_hovered { 8.0 } else { 6.0 }, output_color);
-                painter.circle_stroke(output_port_pos, if output_hovered { 8.0 } else { 6.0 }, egui::Stroke::new(1.0, egui::Color32::WHITE));
+        let (next, previous, exit) = ctx.input(|i| (
+            i.key_pressed(egui::Key::ArrowRight) || i.key_pressed(egui::Key::Space) || i.key_pressed(egui::Key::PageDown),
+            i.key_pressed(egui::Key::ArrowLeft) || i.key_pressed(egui::Key::PageUp),
+            i.key_pressed(egui::Key::Escape) || i.key_pressed(egui::Key::F5),
+        ));
+        if next {
+            presentation.next(&self.project.slides, self.project.viewport);
+        }
+        if previous {
+            presentation.previous(&self.project.slides, self.project.viewport);
+        }
 
-                // Input port (left side) - blue circle
-                let input_port_pos = egui::pos2(
-                    node_screen_pos.x - 8.0,
-                    node_screen_pos.y + node_height / 2.0
-                );
-                let input_hovered = hovered_port.as_ref().map_or(false, |(nid, _, is_out)| *nid == node.id && !*is_out);
-                let input_color = if input_hovered {
-                    egui::Color32::from_rgb(100, 150, 255)
-                } else {
-                    egui::Color32::from_rgb(80, 120, 200)
-                };
-                painter.circle_filled(input_port_pos, if input_hovered { 8.0 } else { 6.0 }, input_color);
-                painter.circle_stroke(input_port_pos, if input_hovered { 8.0 } else { 6.0 }, egui::Stroke::new(1.0, egui::Color32::WHITE));
-            }
+        if let Some(viewport) = presentation.tick(ctx.input(|i| i.stable_dt)) {
+            self.project.viewport = viewport;
+            ctx.request_repaint();
+        }
 
-            // Draw connection being drawn
-            if self.drawing_connection {
-                if let Some(from_node_id) = self.connection_from_node {
-                    if let Some(from_node) = self.project.get_node(from_node_id) {
-                        let from_screen_pos = rect.min + pan + egui::vec2(from_node.position.x * zoom, from_node.position.y * zoom);
-                        let from_width = from_node.size.width * zoom;
-                        let from_height = from_node.size.height * zoom;
-
-                        let start_pos = if self.connection_from_port == "output" {
-                            egui::pos2(from_screen_pos.x + from_width + 8.0, from_screen_pos.y + from_height / 2.0)
-                        } else {
-                            egui::pos2(from_screen_pos.x - 8.0, from_screen_pos.y + from_height / 2.0)
+        let mut exit = exit;
+        let slides = &self.project.slides;
+        egui::Area::new(egui::Id::new("presentation_controls"))
+            .anchor(egui::Align2::CENTER_BOTTOM, [0.0, -16.0])
+            .show(ctx, |ui| {
+                egui::Frame::popup(ui.style()).show(ui, |ui| {
+                    ui.horizontal(|ui| {
+                        if ui.add_enabled(presentation.slide() > 0, egui::Button::new("◀")).clicked() {
+                            presentation.previous(slides, self.project.viewport);
+                        }
+                        match slides.get(presentation.slide()) {
+                            Some(slide) => ui.label(format!("{} / {}  {}", presentation.slide() + 1, slides.len(), slide.name)),
+                            None => ui.label("No slides"),
                         };
+                        if ui.add_enabled(presentation.slide() + 1 < slides.len(), egui::Button::new("▶")).clicked() {
+                            presentation.next(slides, self.project.viewport);
+                        }
+                        ui.separator();
+                        if ui.button("Exit (Esc)").clicked() {
+                            exit = true;
+                        }
+                    });
+                });
+            });
 
-                        // Draw line to mouse
-                        painter.line_segment(
-                            [start_pos, self.connection_mouse_pos],
-                            egui::Stroke::new(2.0, egui::Color32::from_rgb(255, 200, 100))
-                        );
+        if !exit {
+            self.presentation = Some(presentation);
+            egui::CentralPanel::default()
+                .frame(egui::Frame::none())
+                .show(ctx, |ui| self.render_canvas_pane(ui, true));
+        }
+    }
 
-                        // Draw circle at mouse position
-                        painter.circle_filled(self.connection_mouse_pos, 5.0, egui::Color32::from_rgb(255, 200, 100));
+    /// Start a connection from a port, or complete the one being drawn
+    fn connect_port(&mut self, node_id: NodeId, port_name: String) {
+        if self.drawing_connection {
+            // Complete the connection
+            if let Some(from_node_id) = self.connection_from_node {
+                if from_node_id != node_id {
+                    // Determine source and target based on which port was clicked first
+                    let (source_id, target_id) = if self.connection_from_port == "output" {
+                        (from_node_id, node_id)
+                    } else {
+                        (node_id, from_node_id)
+                    };
+
+                    // Create dependency edge (skips port validation)
+                    self.save_undo_state("Create connection");
+                    let edge = Edge::dependency(source_id, target_id);
+                    match self.project.add_edge(edge) {
+                        Ok(id) => {
+                            self.connection_created(id);
+                            self.set_status("Connection created");
+                        }
+                        Err(e) => self.set_error(format!("Failed: {}", e)),
+                    }
+                }
+            }
+            self.drawing_connection = false;
+            self.connection_from_node = None;
+            self.connection_from_port.clear();
+        } else {
+            // Start drawing connection
+            self.drawing_connection = true;
+            self.connection_from_node = Some(node_id);
+            self.connection_from_port = port_name;
+            self.set_status("Click another port to connect, or empty canvas to add a component; Escape cancels");
+        }
+    }
+
+    /// Keyboard-only editing: Tab/arrows move focus, Alt+arrows move nodes,
+    /// Enter opens properties, C starts or finishes a connection
+    fn handle_canvas_keyboard(&mut self, ui: &mut egui::Ui) {
+        use egui::{Key, Modifiers};
+
+        if self.focused_node.is_some_and(|id| self.project.get_node(id).is_none()) {
+            self.focused_node = None;
+        }
+
+        let (tab, shift_tab) = ui.input_mut(|i| (
+            i.consume_key(Modifiers::NONE, Key::Tab),
+            i.consume_key(Modifiers::SHIFT, Key::Tab),
+        ));
+        if tab || shift_tab {
+            let next = navigation::cycle_focus(&self.shown_project(), self.focused_node, shift_tab);
+            self.focused_node = next;
+        }
+
+        let arrows = [
+            (Key::ArrowLeft, Direction::Left, (-1.0, 0.0)),
+            (Key::ArrowRight, Direction::Right, (1.0, 0.0)),
+            (Key::ArrowUp, Direction::Up, (0.0, -1.0)),
+            (Key::ArrowDown, Direction::Down, (0.0, 1.0)),
+        ];
+        let modifiers = ui.input(|i| i.modifiers);
+        for (key, direction, (dx, dy)) in arrows {
+            if !ui.input(|i| i.key_pressed(key)) {
+                continue;
+            }
+            let Some(focused) = self.focused_node else {
+                let first = navigation::cycle_focus(&self.shown_project(), None, false);
+                self.focused_node = first;
+                continue;
+            };
+
+            if modifiers.alt {
+                // Move the focused node, or the whole selection if it is part of it
+                let step = if modifiers.shift { 1.0 } else { self.config.grid_size };
+                let nodes: Vec<NodeId> = if self.project.selected_nodes.contains(&focused) {
+                    self.project.selected_nodes.iter().copied().collect()
+                } else {
+                    vec![focused]
+                };
+                self.save_undo_state("Move node");
+                for node_id in nodes {
+                    if let Some(node) = self.project.get_node_mut(node_id) {
+                        node.position.x += dx * step;
+                        node.position.y += dy * step;
+                    }
+                }
+            } else {
+                let next = navigation::nearest_in_direction(&self.shown_project(), focused, direction);
+                self.focused_node = next.or(self.focused_node);
+            }
+        }
+
+        let Some(focused) = self.focused_node else {
+            return;
+        };
+        if ui.input(|i| i.key_pressed(Key::Enter)) {
+            self.project.clear_selection();
+            self.project.select_node(focused);
+            self.focus_properties = true;
+        }
+        if ui.input(|i| i.key_pressed(Key::C)) {
+            self.connect_port(focused, "output".to_string());
+            if self.drawing_connection {
+                self.set_status("Move focus to the target node and press C to connect, or Escape to cancel");
+            }
+        }
+        if !self.drawing_connection && ui.input(|i| i.key_pressed(Key::Escape)) {
+            self.focused_node = None;
+        }
+    }
+
+    /// Description of a node for screen readers
+    fn accessible_label(&self, node: &Node, is_selected: bool) -> String {
+        let mut label = format!("{} component {}", node.category.display_name(), node.name);
+        if !node.fields.is_empty() {
+            label.push_str(&format!(", {} field(s)", node.fields.len()));
+        }
+        label.push_str(&format!(", {} connection(s)", self.project.edges_for_node(node.id).len()));
+        if !node.enabled {
+            label.push_str(", disabled");
+        }
+        if is_selected {
+            label.push_str(", selected");
+        }
+        if self.focused_node == Some(node.id) {
+            label.push_str(", focused");
+        }
+        label
+    }
+
+    /// Canvas context menu
+    fn render_canvas_context_menu(&mut self, ui: &mut egui::Ui) {
+        let selected = self.project.selected_nodes.len() + self.project.selected_edges.len();
+        if ui.add_enabled(!self.project.selected_nodes.is_empty(), egui::Button::new("Duplicate")).clicked() {
+            self.save_undo_state("Duplicate");
+            self.project.duplicate_selected();
+            ui.close_menu();
+        }
+        if ui.add_enabled(selected > 0, egui::Button::new("Delete")).clicked() {
+            let deleted = self.delete_selection();
+            if deleted > 0 {
+                self.set_status(format!("Deleted {} item(s)", deleted));
+            }
+            ui.close_menu();
+        }
+        // Disable unless every selected node already is
+        let enable = !self.project.selected_nodes.is_empty()
+            && self.project.selected_nodes.iter().all(|id| self.project.get_node(*id).is_some_and(|n| !n.enabled));
+        let label = if enable { tr("disable.enable") } else { tr("disable.disable") };
+        if ui
+            .add_enabled(!self.project.selected_nodes.is_empty(), egui::Button::new(label))
+            .on_hover_text(tr("disable.hint"))
+            .clicked()
+        {
+            self.set_selection_enabled(enable);
+            ui.close_menu();
+        }
+
+        let single = (self.project.selected_nodes.len() == 1)
+            .then(|| self.project.selected_nodes.iter().next().and_then(|id| self.project.get_node(*id)))
+            .flatten();
+        let group = single.and_then(|node| node.group_id).filter(|id| self.project.get_group(*id).is_some());
+        let composite = single.filter(|node| node.is_composite()).map(|node| node.id);
+        let entity = single.filter(|node| node.component_type == "data.entity").map(|node| node.id);
+        if let Some(node_id) = entity {
+            if ui.button(tr("field_import.open")).clicked() {
+                self.open_field_import(node_id);
+                ui.close_menu();
+            }
+        }
+        if let Some(group_id) = group {
+            if ui.button(tr("composite.collapse")).clicked() {
+                self.collapse_to_composite(group_id);
+                ui.close_menu();
+            }
+        }
+        if let Some(node_id) = composite {
+            if ui.button(tr("composite.enter")).clicked() {
+                self.enter_composite(node_id);
+                ui.close_menu();
+            }
+            if ui.button(tr("composite.expand")).clicked() {
+                self.expand_composite(node_id);
+                ui.close_menu();
+            }
+        }
+        ui.separator();
+        if ui.button("Select All").clicked() {
+            self.select_all_shown();
+            ui.close_menu();
+        }
+        if ui.button("Fit to Content").clicked() {
+            self.fit_to_content();
+            ui.close_menu();
+        }
+        if ui.button("Add Bookmark...").clicked() {
+            self.new_bookmark = Some(format!("Bookmark {}", self.project.bookmarks.len() + 1));
+            ui.close_menu();
+        }
+    }
+
+    /// Update selection, node layout, and the project from canvas input
+    fn apply_canvas_response(&mut self, canvas_response: &CanvasResponse, geometry: &CanvasGeometry, shift_held: bool, primary: bool) {
+        if let Some(node_id) = canvas_response.toggled_node {
+            self.save_undo_state("Toggle node details");
+            if let Some(node) = self.project.get_node_mut(node_id) {
+                node.toggle_collapsed();
+            }
+        }
+
+        if let Some((node_id, field_id)) = canvas_response.clicked_badge {
+            self.focused_node = Some(node_id);
+            self.project.clear_selection();
+            self.project.select_node(node_id);
+            self.validation_editor = Some((node_id, field_id));
+        }
+
+        if let Some(node_id) = canvas_response.clicked_node {
+            self.focused_node = Some(node_id);
+            if shift_held {
+                // Toggle selection with shift
+                if self.project.selected_nodes.contains(&node_id) {
+                    self.project.deselect_node(node_id);
+                } else {
+                    self.project.select_node(node_id);
+                }
+            } else {
+                // Single select
+                self.project.clear_selection();
+                self.project.select_node(node_id);
+            }
+        }
+
+        if let Some(edge_id) = canvas_response.clicked_edge {
+            if shift_held && self.project.selected_edges.contains(&edge_id) {
+                self.project.deselect_edge(edge_id);
+            } else {
+                if !shift_held {
+                    self.project.clear_selection();
+                }
+                self.project.select_edge(edge_id);
+            }
+        }
+
+        // Clicked on empty canvas - deselect all
+        if canvas_response.clicked_background && !shift_held {
+            self.project.clear_selection();
+        }
+
+        // Double-click renames entities in place, opens a database's connection
+        // dialog, enters composites, and otherwise edits the name in the
+        // properties panel; on empty canvas it searches for a component to
+        // add there
+        if let Some(double_click) = canvas_response.double_click {
+            if let Some(node_id) = double_click.node() {
+                self.focused_node = Some(node_id);
+                self.project.clear_selection();
+                self.project.select_node(node_id);
+            }
+            match double_click {
+                DoubleClick::Rename(node_id) => {
+                    let name = self.project.get_node(node_id).map(|n| n.name.clone()).unwrap_or_default();
+                    self.inline_rename = Some(InlineRename { node_id, name, primary, focused: false });
+                }
+                DoubleClick::OpenConnection(node_id) => self.connection_dialog = Some(node_id),
+                DoubleClick::EnterComposite(node_id) => self.enter_composite(node_id),
+                DoubleClick::EditProperties(_) => self.focus_properties = true,
+                DoubleClick::QuickAdd(position) => {
+                    if let Some(screen_pos) = canvas_response.pointer_pos {
+                        self.quick_add = Some(QuickAdd::new(position, screen_pos));
+                    }
+                }
+            }
+        }
+
+        // Start resizing when a drag begins on a selected node's resize handle
+        if let Some(node_id) = canvas_response.resize_started {
+            self.save_undo_state("Resize node");
+            self.resizing_node = Some(node_id);
+        }
+
+        if let Some(delta) = canvas_response.drag_delta {
+            if let Some(node_id) = self.resizing_node {
+                // Resize width only; height follows the node's content
+                if let Some(node) = self.project.get_node_mut(node_id) {
+                    node.set_width(node.size.width + delta.x);
+                }
+            } else if !self.project.selected_nodes.is_empty() {
+                // Move selected nodes, snapping them to the nodes around them
+                let drag = self.node_drag.get_or_insert_with(|| NodeDrag::new(&self.project));
+                drag.update(&mut self.project, geometry, delta, self.config.alignment_guides);
+            }
+        }
+        if canvas_response.drag_stopped {
+            self.resizing_node = None;
+            self.node_drag = None;
+        }
+
+        // Component dragged in from the palette
+        if let Some(drop) = &canvas_response.dropped {
+            if let Some(component) = self.registry.get(&drop.component_id) {
+                let mut node = component.instantiate_default();
+                self.apply_default_width(&mut node);
+                node.position = drop.position;
+                node.namespace = self.state.namespace.clone();
+                let name = node.name.clone();
+                self.save_undo_state(&format!("Add {}", name));
+                let node_id = self.project.add_node(node);
+                self.node_added(node_id);
+                self.recent_components.record(&drop.component_id);
+                self.set_status(format!("Added {}", name));
+            }
+        }
+    }
+
+    /// Render one canvas pane using the project's current viewport
+    ///
+    /// Keyboard shortcuts are only handled by the primary pane so they apply once.
+    fn render_canvas_pane(&mut self, ui: &mut egui::Ui, primary: bool) {
+        // Canvas background
+        let (rect, response) = ui.allocate_exact_size(
+            ui.available_size(),
+            egui::Sense::click_and_drag()
+        );
+
+        let painter = ui.painter_at(rect);
+
+        // Draw background
+        painter.rect_filled(rect, 0.0, egui::Color32::from_rgb(30, 30, 35));
+
+        // Draw grid if enabled
+        if self.config.show_grid {
+            self.draw_grid(&painter, rect);
+        }
+
+        // Background layers sit beneath edges, nodes, and groups
+        if !self.project.backgrounds.is_empty() {
+            let geometry = CanvasGeometry::new(rect, &self.project);
+            self.background_textures.paint(&painter, &geometry, &self.project.backgrounds);
+        }
+
+        // Load details of nodes scrolled into view or selected
+        if !self.project.fully_loaded() {
+            let geometry = CanvasGeometry::new(rect, &self.project);
+            let corner = |pos| {
+                let position = geometry.to_canvas(pos);
+                egui::pos2(position.x, position.y)
+            };
+            let view = egui::Rect::from_two_pos(corner(rect.min), corner(rect.max));
+            let wanted: Vec<NodeId> = self
+                .project
+                .unloaded
+                .iter()
+                .filter(|id| {
+                    self.project.selected_nodes.contains(id)
+                        || self.project.get_node(**id).is_some_and(|node| {
+                            let min = egui::pos2(node.position.x, node.position.y);
+                            view.intersects(egui::Rect::from_min_size(min, egui::vec2(node.size.width, node.size.height)))
+                        })
+                })
+                .copied()
+                .collect();
+            self.load_node_details(&wanted);
+        }
+
+        // Store content-driven heights so every hit-test sees the rendered
+        // size; outlines keep their stored size until their details load
+        let unloaded = &self.project.unloaded;
+        for node in self.project.nodes.values_mut().filter(|node| !unloaded.contains(&node.id)) {
+            node.fit_to_content();
+        }
+
+        response.widget_info(|| egui::WidgetInfo::labeled(
+            egui::WidgetType::Other,
+            true,
+            format!("Canvas with {} components and {} connections", self.project.node_count(), self.project.edge_count()),
+        ));
+
+        // Presentations are read-only: any drag pans and scrolling zooms
+        let editable = self.presentation.is_none();
+
+        let canvas = CanvasWidget::with_config(CanvasConfig {
+            show_grid: self.config.show_grid,
+            grid_size: self.config.grid_size,
+            port_hit_radius: self.config.port_hit_radius,
+            touch_gestures: self.config.touch_gestures,
+            ..CanvasConfig::default()
+        })
+        .editable(editable)
+        .connecting(self.drawing_connection)
+        .namespace(&self.state.namespace)
+        .tags(self.hidden_by_tags());
+        let canvas_response = canvas.interact(ui, &response, &mut self.project, rect);
+        if primary && (canvas_response.panned || canvas_response.zoomed) {
+            self.animations.cancel_viewport();
+        }
+
+        // Track mouse position for connection drawing
+        if let Some(pos) = canvas_response.pointer_pos {
+            self.connection_mouse_pos = pos;
+        }
+
+        // Handle port clicks for connection drawing
+        if let Some(port) = canvas_response.clicked_port.clone() {
+            self.connect_port(port.node_id, port.port);
+        }
+
+        // Dropping a connection on empty canvas offers components to connect it to
+        if let (Some(position), Some(screen_pos)) = (canvas_response.connection_dropped, canvas_response.pointer_pos) {
+            if let Some(node_id) = self.connection_from_node {
+                let is_output = self.connection_from_port == "output";
+                self.quick_add = Some(QuickAdd::connecting(position, screen_pos, node_id, is_output));
+            }
+            self.drawing_connection = false;
+            self.connection_from_node = None;
+            self.connection_from_port.clear();
+        }
+
+        // Cancel connection drawing with Escape or right-click (long-press on touch)
+        let cancelled_connection = self.drawing_connection && canvas_response.secondary_clicked;
+        if self.drawing_connection
+            && ((primary && ui.input(|i| i.key_pressed(egui::Key::Escape))) || canvas_response.secondary_clicked)
+        {
+            self.drawing_connection = false;
+            self.connection_from_node = None;
+            self.connection_from_port.clear();
+            self.set_status("Connection cancelled");
+        }
+
+        // Handle Delete/Backspace to delete selected nodes and edges
+        let delete_pressed = ui.input(|i| {
+            i.key_pressed(egui::Key::Delete) || i.key_pressed(egui::Key::Backspace)
+        });
+        if primary && editable && delete_pressed && !self.drawing_connection && !ui.ctx().wants_keyboard_input() {
+            let deleted = self.delete_selection();
+            if deleted > 0 {
+                self.set_status(format!("Deleted {} item(s)", deleted));
+            }
+        }
+
+        // Handle Undo/Redo keyboard shortcuts
+        let ctrl_held = ui.input(|i| i.modifiers.ctrl || i.modifiers.mac_cmd);
+        let shift_held = ui.input(|i| i.modifiers.shift);
+
+        if primary && editable && ctrl_held && ui.input(|i| i.key_pressed(egui::Key::Z)) {
+            if shift_held {
+                self.redo();
+            } else {
+                self.undo();
+            }
+        }
+        if primary && editable && ctrl_held && ui.input(|i| i.key_pressed(egui::Key::Y)) {
+            self.redo();
+        }
+
+        // Space, or Tab while the pointer is over the canvas, opens quick-add there
+        if primary && editable && !self.drawing_connection && self.quick_add.is_none() && !ui.ctx().wants_keyboard_input() {
+            let hover_pos = ui.input(|i| i.pointer.hover_pos()).filter(|pos| rect.contains(*pos));
+            let open = ui.input_mut(|i| {
+                let space = i.consume_key(egui::Modifiers::NONE, egui::Key::Space);
+                let tab = hover_pos.is_some() && i.consume_key(egui::Modifiers::NONE, egui::Key::Tab);
+                if space {
+                    // Keep the space out of the search field
+                    i.events.retain(|event| !matches!(event, egui::Event::Text(text) if text == " "));
+                }
+                space || tab
+            });
+            if open {
+                let screen_pos = hover_pos.unwrap_or(rect.center());
+                let position = CanvasGeometry::new(rect, &self.project).to_canvas(screen_pos);
+                self.quick_add = Some(QuickAdd::new(position, screen_pos));
+            }
+        }
+
+        if primary && editable && !ui.ctx().wants_keyboard_input() {
+            self.handle_canvas_keyboard(ui);
+
+            // Pasting a JSON payload creates an entity from it under the pointer
+            let pasted = ui.input(|i| i.events.iter().find_map(|event| match event {
+                egui::Event::Paste(text) if text.trim_start().starts_with(['{', '[']) => Some(text.clone()),
+                _ => None,
+            }));
+            if let Some(json) = pasted {
+                let hover_pos = ui.input(|i| i.pointer.hover_pos()).filter(|pos| rect.contains(*pos));
+                let position = CanvasGeometry::new(rect, &self.project).to_canvas(hover_pos.unwrap_or(rect.center()));
+                self.paste_json_entity(&json, position);
+            }
+        }
+
+        let drag_geometry =
+            CanvasGeometry::new(rect, &self.project).with_namespace(&self.state.namespace).with_tags(self.hidden_by_tags());
+        self.apply_canvas_response(&canvas_response, &drag_geometry, shift_held, primary);
+
+        // Context menu on right-click or long-press, for the node under the pointer
+        if editable && !cancelled_connection {
+            if let Some(node_id) = canvas_response.context_node.filter(|id| !self.project.selected_nodes.contains(id)) {
+                self.project.clear_selection();
+                self.project.select_node(node_id);
+            }
+            response.context_menu(|ui| self.render_canvas_context_menu(ui));
+        }
+
+        let geometry =
+            CanvasGeometry::new(rect, &self.project).with_namespace(&self.state.namespace).with_tags(self.hidden_by_tags());
+        let hovered_port = canvas_response.hovered_port.as_ref().map(|port| (port.node_id, port.is_output));
+
+        // Upstream and downstream of the selection stand out from the rest
+        let flow = (editable && self.config.highlight_flow)
+            .then(|| FlowHighlight::new(&self.project, self.config.highlight_depth))
+            .flatten();
+        let dimmed = |painter: &egui::Painter, emphasized: bool| {
+            let mut painter = painter.clone();
+            if !emphasized {
+                painter.multiply_opacity(FLOW_DIM_OPACITY);
+            }
+            painter
+        };
+
+        // Bundles drawn in place of their edges unless hovered or selected
+        let mut bundled = HashSet::new();
+        if self.config.bundle_edges {
+            for bundle in EdgeBundle::find(&self.project, |node| geometry.shows(node)) {
+                let points = bundle.points(&self.project, &geometry);
+                if bundle.expanded(&self.project, &points, canvas_response.pointer_pos, canvas_response.hovered_edge) {
+                    continue;
+                }
+                let emphasized = flow.as_ref().is_none_or(|flow| {
+                    bundle.edges.iter().filter_map(|id| self.project.edges.get(id)).any(|edge| flow.includes_edge(edge))
+                });
+                bundle.paint(&dimmed(&painter, emphasized), &points, geometry.zoom);
+                bundled.extend(bundle.edges);
+            }
+        }
+
+        // Draw edges first (below nodes), skipping those into hidden modules
+        let shown = |id| self.project.get_node(id).is_some_and(|node| geometry.shows(node));
+        for edge in self.project.edges.values().filter(|edge| shown(edge.from_node) && shown(edge.to_node) && !bundled.contains(&edge.id)) {
+            let hovered = editable && canvas_response.hovered_edge == Some(edge.id);
+            let mut painter = dimmed(&painter, flow.as_ref().is_none_or(|flow| flow.includes_edge(edge)));
+            if !self.tag_filter_includes(edge.from_node) || !self.tag_filter_includes(edge.to_node) {
+                painter.multiply_opacity(FLOW_DIM_OPACITY);
+            }
+            self.draw_edge(&painter, &geometry, edge, hovered);
+        }
+
+        // Deleted nodes fading out
+        for (node, opacity) in self.animations.removed_nodes().filter(|(node, _)| geometry.shows(node)) {
+            let mut painter = painter.clone();
+            painter.multiply_opacity(opacity);
+            self.draw_node(&painter, &geometry, node, false);
+        }
+
+        // Nodes colored by a metric, with values refreshed now and then
+        match self.config.heatmap {
+            Some(metric) if !self.heatmap.as_ref().is_some_and(|heatmap| heatmap.is_current(metric)) => {
+                self.heatmap = Some(Heatmap::compute(&self.project, metric));
+            }
+            Some(_) => {}
+            None => self.heatmap = None,
+        }
+
+        // Draw nodes with ports, fading in the ones just added
+        for node in self.project.nodes.values().filter(|node| geometry.shows(node)) {
+            let is_selected = editable && self.project.selected_nodes.contains(&node.id);
+            let mut painter = dimmed(&painter, flow.as_ref().is_none_or(|flow| flow.includes(node.id)));
+            painter.multiply_opacity(self.animations.node_opacity(node.id));
+            if !node.enabled {
+                painter.multiply_opacity(DISABLED_NODE_OPACITY);
+            }
+            if !node.has_any_tag(&self.state.tag_filter) {
+                painter.multiply_opacity(FLOW_DIM_OPACITY);
+            }
+            self.draw_node(&painter, &geometry, node, is_selected);
+            if let Some((heatmap, value)) = self.heatmap.as_ref().and_then(|heatmap| Some((heatmap, heatmap.value(node.id)?))) {
+                heatmap.paint_node(&painter, geometry.node_rect(node), value, geometry.zoom);
+            }
+            if let Some(color) = flow.as_ref().and_then(|flow| flow.ring_color(node.id)) {
+                painter.rect_stroke(geometry.node_rect(node).expand(3.0), 7.0, egui::Stroke::new(2.0, color));
+            }
+
+            // Draw ports on node
+            let node_rect = geometry.node_rect(node);
+
+            // Output port (right side) - green circle
+            let output_port_pos = geometry.output_port_pos(node);
+            let output_hovered = hovered_port == Some((node.id, true));
+            let output_color = if output_hovered {
+                egui::Color32::from_rgb(100, 255, 100)
+            } else {
+                egui::Color32::from_rgb(80, 200, 80)
+            };
+            painter.circle_filled(output_port_pos, if output_hovered { 8.0 } else { 6.0 }, output_color);
+            painter.circle_stroke(output_port_pos, if output_hovered { 8.0 } else { 6.0 }, egui::Stroke::new(1.0, egui::Color32::WHITE));
+
+            // Input port (left side) - blue circle
+            let input_port_pos = geometry.input_port_pos(node);
+            let input_hovered = hovered_port == Some((node.id, false));
+            let input_color = if input_hovered {
+                egui::Color32::from_rgb(100, 150, 255)
+            } else {
+                egui::Color32::from_rgb(80, 120, 200)
+            };
+            painter.circle_filled(input_port_pos, if input_hovered { 8.0 } else { 6.0 }, input_color);
+            painter.circle_stroke(input_port_pos, if input_hovered { 8.0 } else { 6.0 }, egui::Stroke::new(1.0, egui::Color32::WHITE));
+
+            // Keyboard focus ring
+            if self.focused_node == Some(node.id) {
+                painter.rect_stroke(node_rect.expand(4.0), 7.0, egui::Stroke::new(2.0, egui::Color32::from_rgb(255, 200, 100)));
+            }
+
+            // Screen-reader labels for the painted node and its ports
+            let label = self.accessible_label(node, is_selected);
+            ui.interact(node_rect, ui.id().with(("canvas_node", node.id)), egui::Sense::hover())
+                .widget_info(|| egui::WidgetInfo::labeled(egui::WidgetType::Other, true, &label));
+            for (port_pos, direction) in [(output_port_pos, "Output"), (input_port_pos, "Input")] {
+                let port_rect = egui::Rect::from_center_size(port_pos, egui::vec2(16.0, 16.0));
+                ui.interact(port_rect, ui.id().with(("canvas_port", node.id, direction)), egui::Sense::hover())
+                    .widget_info(|| egui::WidgetInfo::labeled(egui::WidgetType::Other, true, format!("{} port of {}", direction, node.name)));
+            }
+        }
+
+        // Alignment guides the dragged nodes snapped to
+        if let Some(drag) = &self.node_drag {
+            guides::paint(&painter, &geometry, drag.guides());
+        }
+
+        // In-place rename editor over the entity's header
+        if let Some(rename) = self.inline_rename.as_mut().filter(|r| r.primary == primary) {
+            let header = self.project.get_node(rename.node_id).map(|node| {
+                let node_rect = geometry.node_rect(node);
+                egui::Rect::from_min_size(node_rect.min, egui::vec2(node_rect.width(), Node::HEADER_HEIGHT * geometry.zoom))
+            });
+            match header {
+                Some(header) => {
+                    let response = ui.put(
+                        header.shrink(2.0),
+                        egui::TextEdit::singleline(&mut rename.name).font(egui::FontId::proportional(
+                            imortal_render::font_size(13.0, geometry.zoom, ui.ctx().pixels_per_point())
+                                .unwrap_or(imortal_render::MIN_TEXT_SIZE),
+                        )),
+                    );
+                    if !std::mem::replace(&mut rename.focused, true) {
+                        response.request_focus();
+                    }
+                    if ui.input(|i| i.key_pressed(egui::Key::Escape)) {
+                        self.inline_rename = None;
+                    } else if response.lost_focus() {
+                        let rename = self.inline_rename.take().expect("editor is open");
+                        let unchanged = self.project.get_node(rename.node_id).is_some_and(|n| n.name == rename.name);
+                        if !unchanged && !rename.name.trim().is_empty() {
+                            self.rename_node(rename.node_id, rename.name);
+                        }
                     }
                 }
+                // Node was deleted or undone away
+                None => self.inline_rename = None,
+            }
+        }
+
+        // Draw connection being drawn
+        if self.drawing_connection {
+            if let Some(from_node_id) = self.connection_from_node {
+                if let Some(from_node) = self.project.get_node(from_node_id) {
+                    let start_pos = if self.connection_from_port == "output" {
+                        geometry.output_port_pos(from_node)
+                    } else {
+                        geometry.input_port_pos(from_node)
+                    };
+
+                    // Draw line to mouse
+                    painter.line_segment(
+                        [start_pos, self.connection_mouse_pos],
+                        egui::Stroke::new(2.0, egui::Color32::from_rgb(255, 200, 100))
+                    );
+
+                    // Draw circle at mouse position
+                    painter.circle_filled(self.connection_mouse_pos, 5.0, egui::Color32::from_rgb(255, 200, 100));
+                }
             }
+        }
 
-            // Draw groups
-            for group in self.project.groups.values() {
+        // Draw groups, leaving out those with nothing in the entered module
+        for group in self.project.groups.values() {
+            let shown = self.state.namespace.is_empty()
+                || group.node_ids.iter().any(|id| self.project.get_node(*id).is_some_and(|n| n.in_namespace(&self.state.namespace)));
+            if shown {
                 self.draw_group(&painter, rect, group);
             }
+        }
 
-            // Status bar info
+        if let Some(heatmap) = &self.heatmap {
+            heatmap.paint_legend(&painter, rect);
+        }
+
+        // Status bar info
+        ui.put(
+            egui::Rect::from_min_size(
+                rect.min + egui::vec2(10.0, rect.height() - 25.0),
+                egui::vec2(200.0, 20.0)
+            ),
+            egui::Label::new(format!(
+                "Zoom: {:.0}% | Nodes: {} | Edges: {}",
+                self.project.viewport.zoom * 100.0,
+                self.project.node_count(),
+                self.project.edge_count()
+            ))
+        );
+
+        // Name the view shown in each pane of a split canvas
+        if let Some(split) = self.views.split() {
+            let index = if primary { self.views.active() } else { split };
             ui.put(
-                egui::Rect::from_min_size(
-                    rect.min + egui::vec2(10.0, rect.height() - 25.0),
-                    egui::vec2(200.0, 20.0)
-                ),
-                egui::Label::new(format!(
-                    "Zoom: {:.0}% | Nodes: {} | Edges: {}",
-                    self.project.viewport.zoom * 100.0,
-                    self.project.node_count(),
-                    self.project.edge_count()
-                ))
+                egui::Rect::from_min_size(rect.min + egui::vec2(10.0, 8.0), egui::vec2(200.0, 20.0)),
+                egui::Label::new(egui::RichText::new(format!("👁 {}", self.views.views()[index].name)).strong())
             );
-        });
+        }
     }
 
     /// Draw the background grid
@@ -1394,25 +3917,12 @@ impl ImmortalApp {
     }
 
     /// Draw a node on the canvas
-    fn draw_node(&self, painter: &egui::Painter, canvas_rect: egui::Rect, node: &Node, is_selected: bool) {
-        let zoom = self.project.viewport.zoom;
-        let pan = egui::vec2(self.project.viewport.pan_x, self.project.viewport.pan_y);
+    fn draw_node(&self, painter: &egui::Painter, geometry: &CanvasGeometry, node: &Node, is_selected: bool) {
+        let zoom = geometry.zoom;
 
-        // Calculate node height based on content
-        let header_height = 25.0 * zoom;
-        let field_height = 18.0 * zoom;
-        let content_items = Self::get_node_content_items(node);
-
-        let calculated_height = if content_items > 0 {
-            header_height + (content_items as f32 * field_height) + (8.0 * zoom)
-        } else {
-            node.size.height * zoom
-        };
-
-        let node_rect = egui::Rect::from_min_size(
-            canvas_rect.min + pan + egui::vec2(node.position.x * zoom, node.position.y * zoom),
-            egui::vec2(node.size.width * zoom, calculated_height)
-        );
+        let header_height = Node::HEADER_HEIGHT * zoom;
+        let field_height = Node::ROW_HEIGHT * zoom;
+        let node_rect = geometry.node_rect(node);
 
         // Node background
         let bg_color = if is_selected {
@@ -1421,7 +3931,8 @@ impl ImmortalApp {
             egui::Color32::from_rgb(50, 50, 55)
         };
 
-        painter.rect_filled(node_rect, 5.0, bg_color);
+        let radius = self.category_overrides.corner_radius(node.category, 5.0);
+        painter.rect_filled(node_rect, radius, bg_color);
 
         // Node border
         let border_color = if is_selected {
@@ -1429,7 +3940,22 @@ impl ImmortalApp {
         } else {
             egui::Color32::from_rgb(80, 80, 85)
         };
-        painter.rect_stroke(node_rect, 5.0, egui::Stroke::new(2.0, border_color));
+        painter.rect_stroke(node_rect, radius, egui::Stroke::new(2.0, border_color));
+
+        // Width resize handle (bottom-right corner) on selected nodes
+        if is_selected && !node.locked {
+            let handle = geometry.resize_handle_rect(node);
+            for i in 1..=3 {
+                let offset = handle.width() * i as f32 / 3.0;
+                painter.line_segment(
+                    [
+                        egui::pos2(handle.max.x - offset, handle.max.y - 1.0),
+                        egui::pos2(handle.max.x - 1.0, handle.max.y - offset),
+                    ],
+                    egui::Stroke::new(1.0, border_color)
+                );
+            }
+        }
 
         // Node header
         let header_rect = egui::Rect::from_min_size(
@@ -1437,30 +3963,62 @@ impl ImmortalApp {
             egui::vec2(node_rect.width(), header_height)
         );
 
-        let header_color = match node.category {
-            imortal_core::ComponentCategory::Auth => egui::Color32::from_rgb(200, 80, 80),
-            imortal_core::ComponentCategory::Data => egui::Color32::from_rgb(80, 150, 200),
-            imortal_core::ComponentCategory::Api => egui::Color32::from_rgb(150, 200, 80),
-            imortal_core::ComponentCategory::Storage => egui::Color32::from_rgb(200, 150, 80),
-            imortal_core::ComponentCategory::Logic => egui::Color32::from_rgb(200, 80, 200),
-            _ => egui::Color32::from_rgb(100, 100, 100),
+        let header_color = if node.enabled {
+            self.category_overrides.header_color(node.category)
+        } else {
+            egui::Color32::from_gray(90)
         };
 
         painter.rect_filled(
             egui::Rect::from_min_size(header_rect.min, egui::vec2(header_rect.width(), header_rect.height())),
-            egui::Rounding { nw: 5.0, ne: 5.0, sw: 0.0, se: 0.0 },
+            egui::Rounding { nw: radius, ne: radius, sw: 0.0, se: 0.0 },
             header_color
         );
 
-        // Node title
-        let icon = node.icon.as_deref().unwrap_or("📦");
-        painter.text(
-            header_rect.center(),
-            egui::Align2::CENTER_CENTER,
-            format!("{} {}", icon, node.name),
-            egui::FontId::proportional(14.0 * zoom),
-            egui::Color32::WHITE
-        );
+        // Node title, after an image icon drawn at the zoomed size or an emoji
+        let ppp = painter.ctx().pixels_per_point();
+        if let Some(font_size) = imortal_render::font_size(14.0, zoom, ppp) {
+            let icon = self.category_overrides.node_icon(node).unwrap_or("📦");
+            let font = egui::FontId::proportional(font_size);
+            let icon_size = 16.0 * zoom;
+            let title = painter.layout_no_wrap(node.name.clone(), font.clone(), egui::Color32::WHITE);
+            let gap = 4.0 * zoom;
+            let left = header_rect.center().x - (icon_size + gap + title.size().x) / 2.0;
+            let icon_rect = egui::Rect::from_min_size(
+                egui::pos2(left, header_rect.center().y - icon_size / 2.0),
+                egui::vec2(icon_size, icon_size),
+            );
+            if self.icons.paint(painter, icon, icon_rect) {
+                let title_pos = egui::pos2(icon_rect.max.x + gap, header_rect.center().y - title.size().y / 2.0);
+                painter.galley(title_pos, title, egui::Color32::WHITE);
+            } else {
+                painter.text(
+                    header_rect.center(),
+                    egui::Align2::CENTER_CENTER,
+                    format!("{} {}", IconSource::parse(icon).text_or(node.category.icon()), node.name),
+                    font,
+                    egui::Color32::WHITE
+                );
+            }
+        }
+
+        // Collapse/expand chevron
+        if let Some(toggle_rect) = geometry.collapse_toggle_rect(node) {
+            canvas_text(
+                painter,
+                toggle_rect.center(),
+                egui::Align2::CENTER_CENTER,
+                if node.collapsed { "▶" } else { "▼" },
+                10.0,
+                zoom,
+                egui::Color32::WHITE,
+            );
+        }
+
+        // Collapsed nodes only show their header
+        if node.collapsed {
+            return;
+        }
 
         // Draw fields for entity nodes
         let is_entity = node.component_type == "data.entity";
@@ -1485,24 +4043,37 @@ impl ImmortalApp {
 
                 let name_color = if field.is_primary_key() { pk_color } else { text_color };
 
-                painter.text(
+                canvas_text(
+                    painter,
                     egui::pos2(node_rect.min.x + (8.0 * zoom), field_y),
                     egui::Align2::LEFT_TOP,
                     &field_name,
-                    egui::FontId::proportional(12.0 * zoom),
-                    name_color
+                    12.0,
+                    zoom,
+                    name_color,
                 );
 
-                // Field type (right-aligned)
-                let type_str = Self::data_type_display(&field.data_type);
-                painter.text(
+                // Field type (right-aligned), with its unit
+                let type_str = match &field.unit {
+                    Some(unit) => format!("{} {}", Self::data_type_display(&field.data_type), unit.symbol()),
+                    None => Self::data_type_display(&field.data_type).to_string(),
+                };
+                canvas_text(
+                    painter,
                     egui::pos2(node_rect.max.x - (8.0 * zoom), field_y),
                     egui::Align2::RIGHT_TOP,
-                    type_str,
-                    egui::FontId::proportional(10.0 * zoom),
-                    type_color
+                    &type_str,
+                    10.0,
+                    zoom,
+                    type_color,
                 );
             }
+
+            // Validation badges, left of the type column
+            for (_, badge, rect) in geometry.field_badge_rects(node) {
+                painter.rect_filled(rect, 3.0 * zoom, badge.color());
+                canvas_text(painter, rect.center(), egui::Align2::CENTER_CENTER, badge.label(), 8.0, zoom, egui::Color32::WHITE);
+            }
         }
 
         // Draw database connection details for storage.database nodes
@@ -1541,46 +4112,56 @@ impl ImmortalApp {
 
             // Row 1: Backend type
             let row_y = field_start_y;
-            painter.text(
+            canvas_text(
+                painter,
                 egui::pos2(node_rect.min.x + (8.0 * zoom), row_y),
                 egui::Align2::LEFT_TOP,
                 format!("{} {}", backend_icon, backend.to_uppercase()),
-                egui::FontId::proportional(12.0 * zoom),
-                highlight_color
+                12.0,
+                zoom,
+                highlight_color,
             );
 
             // Row 2: Host and port
             let row_y = field_start_y + field_height;
-            painter.text(
+            canvas_text(
+                painter,
                 egui::pos2(node_rect.min.x + (8.0 * zoom), row_y),
                 egui::Align2::LEFT_TOP,
-                "Host:",
-                egui::FontId::proportional(11.0 * zoom),
-                label_color
+                tr("connection_dialog.host"),
+                11.0,
+                zoom,
+                label_color,
             );
-            painter.text(
+            canvas_text(
+                painter,
                 egui::pos2(node_rect.max.x - (8.0 * zoom), row_y),
                 egui::Align2::RIGHT_TOP,
                 format!("{}:{}", host, port),
-                egui::FontId::proportional(11.0 * zoom),
-                value_color
+                11.0,
+                zoom,
+                value_color,
             );
 
             // Row 3: Database name
             let row_y = field_start_y + (field_height * 2.0);
-            painter.text(
+            canvas_text(
+                painter,
                 egui::pos2(node_rect.min.x + (8.0 * zoom), row_y),
                 egui::Align2::LEFT_TOP,
-                "Database:",
-                egui::FontId::proportional(11.0 * zoom),
-                label_color
+                tr("connection_dialog.database"),
+                11.0,
+                zoom,
+                label_color,
             );
-            painter.text(
+            canvas_text(
+                painter,
                 egui::pos2(node_rect.max.x - (8.0 * zoom), row_y),
                 egui::Align2::RIGHT_TOP,
                 &database,
-                egui::FontId::proportional(11.0 * zoom),
-                value_color
+                11.0,
+                zoom,
+                value_color,
             );
 
             // Row 4: Username
@@ -1588,19 +4169,23 @@ impl ImmortalApp {
                 .and_then(|v| if let imortal_core::ConfigValue::String(s) = v { Some(s.clone()) } else { None })
                 .unwrap_or_else(|| "—".to_string());
             let row_y = field_start_y + (field_height * 3.0);
-            painter.text(
+            canvas_text(
+                painter,
                 egui::pos2(node_rect.min.x + (8.0 * zoom), row_y),
                 egui::Align2::LEFT_TOP,
                 "User:",
-                egui::FontId::proportional(11.0 * zoom),
-                label_color
+                11.0,
+                zoom,
+                label_color,
             );
-            painter.text(
+            canvas_text(
+                painter,
                 egui::pos2(node_rect.max.x - (8.0 * zoom), row_y),
                 egui::Align2::RIGHT_TOP,
                 &username,
-                egui::FontId::proportional(11.0 * zoom),
-                value_color
+                11.0,
+                zoom,
+                value_color,
             );
 
             // Row 5: SSL status
@@ -1608,12 +4193,14 @@ impl ImmortalApp {
             let ssl_icon = if ssl { "🔒" } else { "🔓" };
             let ssl_text = if ssl { "SSL Enabled" } else { "SSL Disabled" };
             let ssl_color = if ssl { egui::Color32::from_rgb(100, 200, 100) } else { egui::Color32::from_rgb(200, 150, 100) };
-            painter.text(
+            canvas_text(
+                painter,
                 egui::pos2(node_rect.min.x + (8.0 * zoom), row_y),
                 egui::Align2::LEFT_TOP,
                 format!("{} {}", ssl_icon, ssl_text),
-                egui::FontId::proportional(11.0 * zoom),
-                ssl_color
+                11.0,
+                zoom,
+                ssl_color,
             );
         }
 
@@ -1646,29 +4233,35 @@ impl ImmortalApp {
 
             // Row 1: HTTP Method
             let row_y = field_start_y;
-            painter.text(
+            canvas_text(
+                painter,
                 egui::pos2(node_rect.min.x + (8.0 * zoom), row_y),
                 egui::Align2::LEFT_TOP,
                 method,
-                egui::FontId::proportional(12.0 * zoom),
-                method_color
+                12.0,
+                zoom,
+                method_color,
             );
 
             // Row 2: Path
             let row_y = field_start_y + field_height;
-            painter.text(
+            canvas_text(
+                painter,
                 egui::pos2(node_rect.min.x + (8.0 * zoom), row_y),
                 egui::Align2::LEFT_TOP,
                 "Path:",
-                egui::FontId::proportional(11.0 * zoom),
-                label_color
+                11.0,
+                zoom,
+                label_color,
             );
-            painter.text(
+            canvas_text(
+                painter,
                 egui::pos2(node_rect.max.x - (8.0 * zoom), row_y),
                 egui::Align2::RIGHT_TOP,
                 &path,
-                egui::FontId::proportional(11.0 * zoom),
-                value_color
+                11.0,
+                zoom,
+                value_color,
             );
 
             // Row 3: Auth status
@@ -1676,103 +4269,70 @@ impl ImmortalApp {
             let auth_icon = if auth_required { "🔐" } else { "🔓" };
             let auth_text = if auth_required { "Auth Required" } else { "Public" };
             let auth_color = if auth_required { egui::Color32::from_rgb(255, 200, 100) } else { egui::Color32::from_rgb(150, 150, 150) };
-            painter.text(
+            canvas_text(
+                painter,
                 egui::pos2(node_rect.min.x + (8.0 * zoom), row_y),
                 egui::Align2::LEFT_TOP,
                 format!("{} {}", auth_icon, auth_text),
-                egui::FontId::proportional(11.0 * zoom),
-                auth_color
+                11.0,
+                zoom,
+                auth_color,
             );
         }
     }
 
-    /// Calculate the actual rendered height of a node (accounting for content)
-    fn calculate_node_height(&self, node: &Node, zoom: f32) -> f32 {
-        let header_height = 25.0 * zoom;
-        let field_height = 18.0 * zoom;
-        let content_items = Self::get_node_content_items(node);
-
-        if content_items > 0 {
-            header_height + (content_items as f32 * field_height) + (8.0 * zoom)
-        } else {
-            node.size.height * zoom
-        }
-    }
-
     /// Draw an edge on the canvas
-    fn draw_edge(&self, painter: &egui::Painter, canvas_rect: egui::Rect, edge: &Edge) {
-        let zoom = self.project.viewport.zoom;
-        let pan = egui::vec2(self.project.viewport.pan_x, self.project.viewport.pan_y);
+    fn draw_edge(&self, painter: &egui::Painter, geometry: &CanvasGeometry, edge: &Edge, hovered: bool) {
+        let zoom = geometry.zoom;
 
         // Get source and target nodes
-        let from_node = match self.project.get_node(edge.from_node) {
-            Some(n) => n,
-            None => return,
-        };
-        let to_node = match self.project.get_node(edge.to_node) {
-            Some(n) => n,
-            None => return,
+        let (Some(from_node), Some(to_node)) = (self.project.get_node(edge.from_node), self.project.get_node(edge.to_node)) else {
+            return;
         };
 
-        // Calculate actual node heights (accounting for entity fields)
-        let from_height = self.calculate_node_height(from_node, zoom);
-        let to_height = self.calculate_node_height(to_node, zoom);
-
-        // Calculate screen positions of nodes
-        let from_screen_pos = canvas_rect.min + pan + egui::vec2(from_node.position.x * zoom, from_node.position.y * zoom);
-        let to_screen_pos = canvas_rect.min + pan + egui::vec2(to_node.position.x * zoom, to_node.position.y * zoom);
-
-        let from_width = from_node.size.width * zoom;
-
-        // Output port position (right side of from_node, +8 pixels outside)
-        let start = egui::pos2(
-            from_screen_pos.x + from_width + 8.0,
-            from_screen_pos.y + from_height / 2.0
-        );
-
-        // Input port position (left side of to_node, -8 pixels outside)
-        let end = egui::pos2(
-            to_screen_pos.x - 8.0,
-            to_screen_pos.y + to_height / 2.0
-        );
-
-        // Draw bezier curve
-        let control_offset = ((end.x - start.x).abs() / 2.0).max(50.0);
-        let control1 = egui::pos2(start.x + control_offset, start.y);
-        let control2 = egui::pos2(end.x - control_offset, end.y);
-
         let color = if edge.selected {
             egui::Color32::from_rgb(100, 200, 255)
         } else {
             let (r, g, b) = edge.style.color.rgb();
             egui::Color32::from_rgb(r, g, b)
         };
+        let thickness = if hovered { edge.style.thickness * 1.5 } else { edge.style.thickness };
+        let width = imortal_render::stroke_width(thickness, zoom, painter.ctx().pixels_per_point());
+
+        // Draw the curve from the output port to the input port in the edge's
+        // line and arrow styles
+        let points: Vec<imortal_render::Point> = geometry.edge_points(from_node, to_node)
+            .into_iter()
+            .map(|p| imortal_render::Point::new(p.x, p.y))
+            .collect();
+        let [r, g, b, a] = color.to_srgba_unmultiplied();
+        imortal_render::draw_edge_path(
+            &mut EguiPainter::new(painter),
+            &points,
+            &edge.style,
+            width,
+            10.0 * zoom,
+            imortal_render::Color::rgba(r, g, b, a),
+        );
 
-        // Draw the curve using line segments
-        let points = bezier_points(start, control1, control2, end, 20);
-        for i in 0..points.len() - 1 {
-            painter.line_segment(
-                [points[i], points[i + 1]],
-                egui::Stroke::new(2.0 * zoom, color)
-            );
+        // The edge's label, with the fields a relationship joins on
+        let label = match (edge.label.clone(), self.project.relationship_label(edge)) {
+            (Some(label), Some(fields)) => Some(format!("{} ({})", label, fields)),
+            (label, fields) => label.or(fields),
+        };
+        if let Some(label) = label {
+            let curve = geometry.edge_points(from_node, to_node);
+            let middle = curve[curve.len() / 2] - egui::vec2(0.0, 4.0 * zoom);
+            canvas_text(painter, middle, egui::Align2::CENTER_BOTTOM, label, 11.0, zoom, color);
         }
 
-        // Draw arrow head
-        let arrow_size = 10.0 * zoom;
-        let direction = (end - points[points.len() - 2]).normalized();
-        let perpendicular = egui::vec2(-direction.y, direction.x);
-
-        let arrow_points = vec![
-            end,
-            end - direction * arrow_size + perpendicular * arrow_size * 0.5,
-            end - direction * arrow_size - perpendicular * arrow_size * 0.5,
-        ];
-
-        painter.add(egui::Shape::convex_polygon(
-            arrow_points,
-            color,
-            egui::Stroke::NONE
-        ));
+        // A glow travelling from the source to the target of a new connection
+        if let Some(t) = self.animations.edge_pulse(edge.id) {
+            let curve = geometry.edge_points(from_node, to_node);
+            let index = ((curve.len() - 1) as f32 * t).round() as usize;
+            let glow = egui::Color32::WHITE.gamma_multiply(1.0 - t);
+            painter.circle_filled(curve[index], 5.0 * zoom.max(0.5), glow);
+        }
     }
 
     /// Draw a group on the canvas
@@ -1793,12 +4353,14 @@ impl ImmortalApp {
 
         // Group title
         if group.show_header {
-            painter.text(
+            canvas_text(
+                painter,
                 group_rect.min + egui::vec2(10.0, 15.0),
                 egui::Align2::LEFT_CENTER,
                 &group.name,
-                egui::FontId::proportional(12.0 * zoom),
-                egui::Color32::from_rgb(r, g, b)
+                12.0,
+                zoom,
+                egui::Color32::from_rgb(r, g, b),
             );
         }
     }
@@ -1807,14 +4369,7 @@ impl ImmortalApp {
     fn render_status_bar(&mut self, ctx: &egui::Context) {
         egui::TopBottomPanel::bottom("status_bar").show(ctx, |ui| {
             ui.horizontal(|ui| {
-                // Status message
-                if let Some((msg, time)) = &self.status_message {
-                    if time.elapsed().as_secs() < 5 {
-                        ui.label(msg);
-                    } else {
-                        self.status_message = None;
-                    }
-                }
+                self.notifications.status_button(ui);
 
                 ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
                     ui.label(format!(
@@ -1823,6 +4378,16 @@ impl ImmortalApp {
                         self.project.node_count(),
                         self.project.edge_count()
                     ));
+                    if let Some(holder) = &self.read_only {
+                        ui.label(egui::RichText::new(tr("lock.read_only_badge")).color(egui::Color32::YELLOW))
+                            .on_hover_text(trf("lock.held_by", &[holder]));
+                    }
+                    if let Some(recording) = &self.macro_recording {
+                        let badge = egui::RichText::new(trf("macros.recording", &[&recording.steps.len()])).color(egui::Color32::LIGHT_RED);
+                        if ui.button(badge).on_hover_text(tr("menu.tools.stop_recording")).clicked() {
+                            self.toggle_macro_recording();
+                        }
+                    }
                 });
             });
         });
@@ -1832,19 +4397,19 @@ impl ImmortalApp {
     fn render_dialogs(&mut self, ctx: &egui::Context) {
         // About dialog
         if self.show_about {
-            egui::Window::new("About Immortal Engine")
+            egui::Window::new(tr("about.title"))
                 .collapsible(false)
                 .resizable(false)
                 .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
                 .show(ctx, |ui| {
                     ui.vertical_centered(|ui| {
                         ui.heading("Immortal Engine");
-                        ui.label(format!("Version {}", env!("CARGO_PKG_VERSION")));
+                        ui.label(trf("about.version", &[&env!("CARGO_PKG_VERSION")]));
                         ui.add_space(10.0);
-                        ui.label("Visual Prototyping System");
-                        ui.label("Build applications by dragging components and drawing connections");
+                        ui.label(tr("about.tagline"));
+                        ui.label(tr("about.description"));
                         ui.add_space(20.0);
-                        if ui.button("Close").clicked() {
+                        if ui.button(tr("dialog.close")).clicked() {
                             self.show_about = false;
                         }
                     });
@@ -1853,145 +4418,4473 @@ impl ImmortalApp {
 
         // Settings dialog
         if self.show_settings {
-            egui::Window::new("Settings")
+            egui::Window::new(tr("settings.title"))
                 .collapsible(false)
                 .resizable(true)
                 .show(ctx, |ui| {
-                    ui.checkbox(&mut self.config.dark_mode, "Dark Mode");
-                    ui.checkbox(&mut self.config.show_grid, "Show Grid");
-                    ui.checkbox(&mut self.config.snap_to_grid, "Snap to Grid");
-                    ui.checkbox(&mut self.config.show_minimap, "Show Minimap");
+                    ui.horizontal(|ui| {
+                        ui.label(tr("settings.language"));
+                        egui::ComboBox::from_id_salt("language")
+                            .selected_text(self.config.language.native_name())
+                            .show_ui(ui, |ui| {
+                                for language in i18n::Language::all() {
+                                    ui.selectable_value(&mut self.config.language, *language, language.native_name());
+                                }
+                            });
+                    });
+                    ui.separator();
+
+                    ui.checkbox(&mut self.config.dark_mode, tr("settings.dark_mode"));
+                    ui.checkbox(&mut self.config.show_grid, tr("menu.view.show_grid"));
+                    ui.checkbox(&mut self.config.snap_to_grid, tr("menu.view.snap_to_grid"));
+                    ui.checkbox(&mut self.config.show_minimap, tr("menu.view.show_minimap"));
+                    ui.checkbox(&mut self.restore_session, tr("settings.restore_session"))
+                        .on_hover_text(tr("settings.restore_session_hint"));
 
                     ui.horizontal(|ui| {
-                        ui.label("Grid Size:");
+                        ui.label(tr("settings.grid_size"));
                         ui.add(egui::Slider::new(&mut self.config.grid_size, 10.0..=50.0));
                     });
+                    ui.checkbox(&mut self.config.animations, tr("settings.animations"));
+                    ui.add_enabled_ui(self.config.animations, |ui| {
+                        ui.horizontal(|ui| {
+                            ui.label(tr("settings.animation_speed"));
+                            ui.add(egui::Slider::new(&mut self.config.animation_speed, 0.0..=1.0));
+                        });
+                    });
+
+                    ui.separator();
+                    ui.label(tr("settings.touch"));
+                    ui.checkbox(&mut self.config.touch_gestures, tr("settings.touch_gestures"));
+                    ui.horizontal(|ui| {
+                        ui.label(tr("settings.port_hit_radius"));
+                        ui.add(egui::Slider::new(&mut self.config.port_hit_radius, 8.0..=40.0).suffix(" px"));
+                    });
+
+                    ui.separator();
+                    ui.label(tr("settings.history"));
+                    let snapshots = &mut self.config.snapshots;
+                    ui.checkbox(&mut snapshots.enabled, tr("settings.snapshots"));
+                    ui.add_enabled_ui(snapshots.enabled, |ui| {
+                        ui.horizontal(|ui| {
+                            ui.label(tr("settings.snapshot_count"));
+                            ui.add(egui::DragValue::new(&mut snapshots.max_count).range(1..=200));
+                        });
+                        ui.horizontal(|ui| {
+                            ui.label(tr("settings.snapshot_size"));
+                            let mut megabytes = snapshots.max_total_bytes / (1024 * 1024);
+                            if ui.add(egui::DragValue::new(&mut megabytes).range(1..=10_000).suffix(" MB")).changed() {
+                                snapshots.max_total_bytes = megabytes * 1024 * 1024;
+                            }
+                        });
+                    });
+
+                    // Saved with the project rather than the editor
+                    ui.separator();
+                    ui.label(tr("settings.project"));
+                    let foreign_keys = &mut self.project.meta.foreign_keys;
+                    ui.checkbox(&mut foreign_keys.enabled, tr("settings.foreign_keys"));
+                    ui.add_enabled_ui(foreign_keys.enabled, |ui| {
+                        ui.horizontal(|ui| {
+                            ui.label(tr("settings.foreign_key_naming"));
+                            egui::ComboBox::from_id_salt("foreign_key_naming")
+                                .selected_text(foreign_keys.field_name("Parent"))
+                                .show_ui(ui, |ui| {
+                                    for naming in imortal_ir::ForeignKeyNaming::all() {
+                                        ui.selectable_value(&mut foreign_keys.naming, *naming, naming.field_name("Parent"));
+                                    }
+                                });
+                        });
+                    });
+
+                    ui.separator();
+                    ui.collapsing(tr("settings.category_styles"), |ui| {
+                        ui.weak(tr("settings.category_styles_hint"));
+                        if category_styles_editor(ui, &mut self.category_overrides) {
+                            if let Err(e) = self.category_overrides.save() {
+                                self.set_error(trf("settings.category_styles_failed", &[&e]));
+                            }
+                        }
+                    });
 
                     ui.add_space(10.0);
-                    if ui.button("Close").clicked() {
+                    if ui.button(tr("dialog.close")).clicked() {
                         self.show_settings = false;
                     }
                 });
         }
+
+        // Whole-project views need every node's details
+        if self.show_analysis || self.show_project_info || self.schema_panel.visible || self.query_console.visible {
+            self.load_all_node_details();
+        }
+
+        // Graph analysis panel
+        if self.show_analysis {
+            self.render_analysis(ctx);
+        }
+
+        // Project info dialog
+        if self.show_project_info {
+            self.render_project_info(ctx);
+        }
+
+        self.render_merge_dialog(ctx);
+        self.render_split_dialog(ctx);
+        self.render_rename_summary(ctx);
+        self.render_port_removal_confirm(ctx);
+        self.render_deletion_impact(ctx);
+        self.render_reference_report(ctx);
+        self.render_validation_report(ctx);
+        self.render_repair_offer(ctx);
+        self.render_lock_conflict(ctx);
+        self.render_quarantine_report(ctx);
+        self.render_restore_dialog(ctx);
+        self.render_import_dialog(ctx);
+        self.render_rust_import(ctx);
+        self.render_assist_dialog(ctx);
+        self.render_assist_review(ctx);
+        self.render_template_prompt(ctx);
+        self.render_code_sync(ctx);
+        self.render_project_settings(ctx);
+        self.render_quick_add(ctx);
+        self.render_connection_dialog(ctx);
+        self.render_bookmark_dialog(ctx);
+        self.render_validation_editor(ctx);
+        self.render_field_import(ctx);
+        self.render_backgrounds_dialog(ctx);
+        self.render_types_dialog(ctx);
+        self.render_usage_report(ctx);
+        if let Some(action) = self.connection_matrix.show(ctx, &self.project) {
+            self.handle_connection_action(action);
+        }
+        self.render_selection_generation(ctx);
+        self.render_hook_confirmation(ctx);
+        self.render_print_dialog(ctx);
+
+        // Schema overview panel
+        for action in self.schema_panel.show(ctx, &self.project) {
+            self.apply_schema_action(action);
+        }
+
+        for action in self.query_console.show(ctx, &self.project) {
+            self.apply_query_action(action);
+        }
+
+        for action in self.macro_manager.show(ctx) {
+            match action {
+                MacroAction::Replay(recorded) => self.replay_macro(&recorded),
+            }
+        }
+
+        // Component docs
+        if self.component_docs.is_open() {
+            let meta = self.root_project().meta.clone();
+            for action in self.component_docs.show(ctx, &self.registry, &meta) {
+                match action {
+                    DocsAction::InsertExample(example) => self.insert_component_example(&example),
+                }
+            }
+        }
     }
 
-    // File operations
-    fn open_project(&mut self) {
-        if let Some(path) = rfd::FileDialog::new()
-            .add_filter("Immortal Project", &["imortal"])
-            .pick_file()
-        {
-            self.load_project_from_path(path);
+    /// Add a documentation example to the project as a single undo step
+    fn insert_component_example(&mut self, example: &ComponentExample) {
+        let graph = match self.registry.build_example(example) {
+            Ok(graph) => graph,
+            Err(e) => {
+                self.set_error(format!("Failed to insert example: {}", e));
+                return;
+            }
+        };
+
+        self.save_undo_state(&format!("Insert example {}", example.title));
+        let summary = self.project.import_project(&graph, ImportCollision::Rename);
+        self.project.clear_selection();
+        for id in &summary.added {
+            if let Some(node) = self.project.get_node_mut(*id) {
+                node.namespace = self.state.namespace.clone();
+            }
+            self.project.select_node(*id);
         }
+        self.set_status(trf("docs.example_inserted", &[&example.title]));
     }
 
-    fn save_project(&mut self) {
-        if let Some(path) = &self.project_path.clone() {
-            match imortal_ir::save_project(&self.project, &path, imortal_ir::ProjectFormat::Json) {
-                Ok(_) => self.set_status("Project saved"),
-                Err(e) => self.set_status(format!("Failed to save: {}", e)),
+    /// Rename a node, propagating the new name to everything that refers to it
+    fn rename_node(&mut self, node_id: NodeId, name: String) {
+        self.save_undo_state("Rename");
+        match self.project.rename_node(node_id, name) {
+            Ok(summary) => {
+                self.set_status(format!(
+                    "Renamed '{}' to '{}' ({} reference(s) updated)",
+                    summary.old_name, summary.new_name, summary.dependent_count()
+                ));
+                if summary.dependent_count() > 0 {
+                    self.rename_summary = Some(summary);
+                }
             }
-        } else {
-            self.save_project_as();
+            Err(e) => self.set_error(format!("Rename failed: {}", e)),
         }
     }
 
-    fn save_project_as(&mut self) {
-        if let Some(path) = rfd::FileDialog::new()
-            .add_filter("Immortal Project", &["imortal"])
-            .set_file_name(&format!("{}.imortal", self.project.meta.name))
-            .save_file()
-        {
-            self.project_path = Some(path.clone());
+    /// Render the list of locations updated by the last rename
+    fn render_rename_summary(&mut self, ctx: &egui::Context) {
+        let Some(summary) = &self.rename_summary else {
+            return;
+        };
+        let mut close = false;
 
-            // Update recent projects
-            self.welcome_screen.add_recent_project(self.project.meta.name.clone(), path.clone());
+        egui::Window::new(format!("Renamed '{}' to '{}'", summary.old_name, summary.new_name))
+            .collapsible(false)
+            .resizable(true)
+            .show(ctx, |ui| {
+                ui.label(format!("{} location(s) updated:", summary.changes.len()));
+                egui::ScrollArea::vertical().max_height(300.0).show(ui, |ui| {
+                    for change in &summary.changes {
+                        ui.label(format!("• {}", change.description));
+                    }
+                });
+                ui.add_space(10.0);
+                if ui.button("Close").clicked() {
+                    close = true;
+                }
+            });
 
-            match imortal_ir::save_project(&self.project, &path, imortal_ir::ProjectFormat::Json) {
-                Ok(_) => self.set_status("Project saved"),
-                Err(e) => self.set_status(format!("Failed to save: {}", e)),
+        if close {
+            self.rename_summary = None;
+        }
+    }
+
+    /// Apply an edit or selection made in the schema panel
+    /// Apply a batch operation from the query console
+    fn apply_query_action(&mut self, action: QueryAction) {
+        match action {
+            QueryAction::Select(nodes) => {
+                self.project.clear_selection();
+                for &id in &nodes {
+                    self.project.select_node(id);
+                }
+                self.focused_node = nodes.first().copied();
+            }
+            QueryAction::Delete(nodes) => {
+                self.save_undo_state(&format!("Delete {} queried node(s)", nodes.len()));
+                for &id in &nodes {
+                    if let Some(node) = self.project.remove_node(id) {
+                        self.animations.node_removed(node);
+                    }
+                }
+                self.emit(EditorEvent::Deleted { nodes: nodes.len(), edges: 0 });
+                self.set_status(trf("query.deleted", &[&nodes.len()]));
+            }
+            QueryAction::SetConfig { nodes, key, value } => {
+                self.save_undo_state(&format!("Set {} on queried nodes", key));
+                for &id in &nodes {
+                    if let Some(node) = self.project.get_node_mut(id) {
+                        node.set_config(key.as_str(), value.clone());
+                    }
+                }
+                self.set_status(trf("query.updated", &[&key, &nodes.len()]));
             }
         }
     }
 
-    fn generate_code(&mut self) {
-        self.set_status("Code generation not yet implemented");
+    /// Start recording a macro, or stop and hand it to the macro manager
+    fn toggle_macro_recording(&mut self) {
+        match self.macro_recording.take() {
+            Some(recorded) if recorded.steps.is_empty() => self.set_status(tr("macros.nothing_recorded")),
+            Some(recorded) => self.macro_manager.save_recording(recorded),
+            None => {
+                self.macro_recording = Some(Macro::new(""));
+                self.set_status(tr("macros.started"));
+            }
+        }
     }
 
-    fn validate_project(&mut self) {
-        match imortal_ir::validation::validate(&self.project) {
-            Ok(_) => self.set_status("✅ Project is valid"),
-            Err(errors) => self.set_status(format!("❌ {} validation errors found", errors.len())),
+    /// Add an edit to the macro being recorded, if any
+    fn record_macro_step(&mut self, step: MacroStep) {
+        if let Some(recording) = &mut self.macro_recording {
+            recording.record(step);
         }
     }
-}
 
-impl eframe::App for ImmortalApp {
-    fn update(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) {
-        // Set dark/light mode
-        if self.config.dark_mode {
-            ctx.set_visuals(egui::Visuals::dark());
-        } else {
-            ctx.set_visuals(egui::Visuals::light());
+    /// Replay a macro on the selection as one undo step
+    fn replay_macro(&mut self, recorded: &Macro) {
+        self.save_undo_state(&format!("Replay macro {}", recorded.name));
+        // Added nodes are recorded as the macro's own steps, not as snapshots
+        let recording = self.macro_recording.take();
+        let replay = recorded.replay(&mut self.project);
+        for &id in &replay.added {
+            self.node_added(id);
+        }
+        self.macro_recording = recording;
+        for step in &recorded.steps {
+            self.record_macro_step(step.clone());
         }
+        self.set_status(trf("macros.replayed", &[&recorded.name, &replay.added.len(), &replay.changed]));
+    }
 
-        match self.mode {
-            AppMode::Welcome => {
-                // Show welcome screen
-                let action = self.welcome_screen.show(ctx);
-                self.handle_welcome_action(action);
+    fn apply_schema_action(&mut self, action: SchemaAction) {
+        let (node_id, field_id) = match &action {
+            SchemaAction::Select(node_id) => {
+                self.project.clear_selection();
+                self.project.select_node(*node_id);
+                return;
             }
-            AppMode::Editor => {
-                // Render all editor UI components
-                self.render_menu_bar(ctx, frame);
-                self.render_status_bar(ctx);
-                self.render_palette(ctx);
-                self.render_properties(ctx);
-                self.render_canvas(ctx);
-                self.render_dialogs(ctx);
+            SchemaAction::ExportCsv => {
+                self.export_schema_csv();
+                return;
             }
+            SchemaAction::RenameField { node_id, field_id, .. }
+            | SchemaAction::SetType { node_id, field_id, .. }
+            | SchemaAction::SetRequired { node_id, field_id, .. } => (*node_id, *field_id),
+        };
+
+        if !matches!(action, SchemaAction::RenameField { .. }) {
+            self.save_undo_state("Edit field");
+        }
+        let Some(field) = self.project.get_node_mut(node_id)
+            .and_then(|n| n.fields.iter_mut().find(|f| f.id == field_id))
+        else {
+            return;
+        };
+        match action {
+            SchemaAction::RenameField { name, .. } => field.name = name,
+            SchemaAction::SetType { data_type, .. } => field.data_type = data_type,
+            SchemaAction::SetRequired { required, .. } => field.required = required,
+            SchemaAction::Select(_) | SchemaAction::ExportCsv => {}
         }
     }
-}
 
-/// Sanitize a project name for use as a directory/file name
-fn sanitize_project_name(name: &str) -> String {
-    name.chars()
-        .map(|c| {
-            if c.is_alphanumeric() || c == '-' || c == '_' {
-                c
-            } else if c == ' ' {
-                '_'
-            } else {
-                '_'
+    fn export_schema_csv(&mut self) {
+        self.load_all_node_details();
+        if let Some(path) = rfd::FileDialog::new()
+            .add_filter("CSV", &["csv"])
+            .set_file_name(format!("{}_schema.csv", sanitize_project_name(&self.project.meta.name)))
+            .save_file()
+        {
+            let csv = schema::to_csv(&schema::schema_rows(&self.project));
+            self.spawn_task(trf("task.writing", &[&path.display()]), move |_| {
+                TaskMessage::Written(match std::fs::write(&path, csv) {
+                    Ok(_) => Ok(format!("Schema exported to {}", path.display())),
+                    Err(e) => Err(format!("Failed to export schema: {}", e)),
+                })
+            });
+        }
+    }
+
+    /// File > Print: paper, scale, and marks, with the page count they
+    /// give, then print through the system's PDF viewer or save the PDF
+    fn render_print_dialog(&mut self, ctx: &egui::Context) {
+        let Some(options) = &mut self.print_dialog else {
+            return;
+        };
+
+        let layout = options.layout(&self.project);
+        let mut open = true;
+        let mut print = false;
+        let mut save = false;
+        egui::Window::new(tr("print.title"))
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                egui::Grid::new("print_options").num_columns(2).show(ui, |ui| {
+                    ui.label(tr("print.paper"));
+                    egui::ComboBox::from_id_salt("print_paper")
+                        .selected_text(options.paper.display_name())
+                        .show_ui(ui, |ui| {
+                            for paper in PaperSize::all() {
+                                ui.selectable_value(&mut options.paper, *paper, paper.display_name());
+                            }
+                        });
+                    ui.end_row();
+
+                    ui.label(tr("print.orientation"));
+                    ui.horizontal(|ui| {
+                        ui.radio_value(&mut options.landscape, false, tr("print.portrait"));
+                        ui.radio_value(&mut options.landscape, true, tr("print.landscape"));
+                    });
+                    ui.end_row();
+
+                    ui.label(tr("print.scale"));
+                    let mut percent = options.scale * 100.0;
+                    if ui.add(egui::Slider::new(&mut percent, 10.0..=400.0).suffix("%").logarithmic(true)).changed() {
+                        options.scale = percent / 100.0;
+                    }
+                    ui.end_row();
+                });
+                ui.checkbox(&mut options.crop_marks, tr("print.crop_marks"));
+                ui.checkbox(&mut options.legend, tr("print.legend")).on_hover_text(tr("print.legend_hint"));
+                ui.separator();
+
+                ui.label(trf("print.pages", &[&layout.columns, &layout.rows, &layout.page_count()]));
+                if options.legend {
+                    ui.weak(tr("print.plus_overview"));
+                }
+                ui.horizontal(|ui| {
+                    print = ui.button(tr("print.print")).on_hover_text(tr("print.print_hint")).clicked();
+                    save = ui.button(tr("print.save_pdf")).clicked();
+                });
+            });
+        if !open {
+            self.print_dialog = None;
+        }
+        if print {
+            self.print_pdf(None);
+        } else if save {
+            let name = sanitize_project_name(&self.project.meta.name);
+            if let Some(path) = rfd::FileDialog::new().add_filter("PDF", &["pdf"]).set_file_name(format!("{}.pdf", name)).save_file() {
+                self.print_pdf(Some(path));
             }
-        })
-        .collect()
-}
+        }
+    }
+
+    /// Render the print dialog's pages in the background, then save them to
+    /// `path` or, without one, open them in the system's PDF viewer to print
+    fn print_pdf(&mut self, path: Option<PathBuf>) {
+        let Some(options) = self.print_dialog.take() else {
+            return;
+        };
+        self.load_all_node_details();
+
+        let mut options = options;
+        self.category_overrides.apply_to(&mut options.style);
+        let project = self.project.clone();
+        let name = sanitize_project_name(&project.meta.name);
+        self.spawn_task(trf("print.rendering", &[&project.meta.name]), move |_| {
+            let preview = path.is_none();
+            let path = path.unwrap_or_else(|| std::env::temp_dir().join(format!("{}-print.pdf", name)));
+            let written = imortal_render::render_pdf(&project, &options)
+                .map_err(|e| e.to_string())
+                .and_then(|pdf| std::fs::write(&path, pdf).map_err(|e| e.to_string()));
+            TaskMessage::Written(match written {
+                Ok(()) if preview => notifications::open_path(&path)
+                    .map(|_| tr("print.opened").to_string())
+                    .map_err(|e| trf("print.failed", &[&e])),
+                Ok(()) => Ok(trf("print.saved", &[&path.display()])),
+                Err(e) => Err(trf("print.failed", &[&e])),
+            })
+        });
+    }
+
+    /// Give a new node its category's default width, if one is set
+    fn apply_default_width(&self, node: &mut Node) {
+        if let Some(width) = self.category_overrides.default_width(node.category) {
+            node.size.width = width;
+        }
+    }
+
+    /// Save a PDF design document: diagram, data dictionary, endpoints, and
+    /// validation report
+    fn export_design_document(&mut self) {
+        self.load_all_node_details();
+        let name = sanitize_project_name(&self.project.meta.name);
+        let Some(path) = rfd::FileDialog::new().add_filter("PDF", &["pdf"]).set_file_name(format!("{}_design.pdf", name)).save_file() else {
+            return;
+        };
 
-/// Calculate points along a cubic bezier curve
-fn bezier_points(
-    p0: egui::Pos2,
-    p1: egui::Pos2,
-    p2: egui::Pos2,
-    p3: egui::Pos2,
-    segments: usize
-) -> Vec<egui::Pos2> {
-    let mut points = Vec::with_capacity(segments + 1);
+        let project = self.project.clone();
+        let mut style = imortal_render::Style::print();
+        self.category_overrides.apply_to(&mut style);
+        self.spawn_task(trf("task.writing", &[&path.display()]), move |_| {
+            let written = imortal_render::render_document_with_style(&project, PaperSize::A4, &style)
+                .map_err(|e| e.to_string())
+                .and_then(|pdf| std::fs::write(&path, pdf).map_err(|e| e.to_string()));
+            TaskMessage::Written(match written {
+                Ok(()) => Ok(trf("document.saved", &[&path.display()])),
+                Err(e) => Err(trf("document.failed", &[&e])),
+            })
+        });
+    }
+
+    /// Selected entity nodes, ordered by name
+    fn selected_entities(&self) -> Vec<NodeId> {
+        let mut entities: Vec<&Node> = self.project.selected_nodes.iter()
+            .filter_map(|id| self.project.get_node(*id))
+            .filter(|n| n.component_type == "data.entity")
+            .collect();
+        entities.sort_by(|a, b| a.name.cmp(&b.name));
+        entities.into_iter().map(|n| n.id).collect()
+    }
+
+    fn open_merge_dialog(&mut self, source: NodeId, target: NodeId) {
+        let conflicts = self.project.field_conflicts(source, target)
+            .into_iter()
+            .map(|name| (name, false))
+            .collect();
+        self.merge_dialog = Some(MergeDialog { source, target, conflicts });
+    }
+
+    /// Render the merge dialog: pick the kept entity and resolve field conflicts
+    fn render_merge_dialog(&mut self, ctx: &egui::Context) {
+        let Some(dialog) = &mut self.merge_dialog else {
+            return;
+        };
+        let name_of = |id: NodeId| self.project.get_node(id).map(|n| n.name.clone()).unwrap_or_default();
+        let (source_name, target_name) = (name_of(dialog.source), name_of(dialog.target));
+        let mut swap = false;
+        let mut apply = false;
+        let mut close = false;
+
+        egui::Window::new(tr("merge.title"))
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label(trf("merge.summary", &[&source_name, &target_name]));
+                    if ui.small_button("⇄").on_hover_text(tr("merge.swap")).clicked() {
+                        swap = true;
+                    }
+                });
+                ui.label(tr("merge.hint"));
+
+                if !dialog.conflicts.is_empty() {
+                    ui.add_space(8.0);
+                    ui.label(tr("merge.conflicts"));
+                    egui::Grid::new("merge_conflicts").striped(true).show(ui, |ui| {
+                        for (field, prefer_source) in &mut dialog.conflicts {
+                            ui.label(field.as_str());
+                            ui.radio_value(prefer_source, false, &target_name);
+                            ui.radio_value(prefer_source, true, &source_name);
+                            ui.end_row();
+                        }
+                    });
+                }
+
+                ui.add_space(10.0);
+                ui.horizontal(|ui| {
+                    if ui.button(tr("merge.merge")).clicked() {
+                        apply = true;
+                    }
+                    if ui.button(tr("dialog.cancel")).clicked() {
+                        close = true;
+                    }
+                });
+            });
+
+        if swap {
+            let (source, target) = (dialog.target, dialog.source);
+            self.open_merge_dialog(source, target);
+        } else if apply {
+            let Some(dialog) = self.merge_dialog.take() else {
+                return;
+            };
+            let prefer_source: Vec<String> = dialog.conflicts.into_iter()
+                .filter(|(_, prefer_source)| *prefer_source)
+                .map(|(name, _)| name)
+                .collect();
+            self.save_undo_state("Merge entities");
+            match self.project.merge_entities(dialog.source, dialog.target, &prefer_source) {
+                Ok(()) => {
+                    self.project.select_node(dialog.target);
+                    self.set_status(trf("merge.done", &[&source_name, &target_name]));
+                }
+                Err(e) => self.set_error(trf("dialog.failed", &[&e])),
+            }
+        } else if close {
+            self.merge_dialog = None;
+        }
+    }
+
+    /// Render the split dialog: pick fields to extract into a new entity
+    fn render_split_dialog(&mut self, ctx: &egui::Context) {
+        let Some(dialog) = &mut self.split_dialog else {
+            return;
+        };
+        let Some(entity) = self.project.get_node(dialog.entity) else {
+            self.split_dialog = None;
+            return;
+        };
+        let entity_name = entity.name.clone();
+        let mut apply = false;
+        let mut close = false;
+
+        egui::Window::new(tr("split.title"))
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                ui.label(trf("split.summary", &[&entity_name]));
+                ui.horizontal(|ui| {
+                    ui.label(tr("split.new_entity"));
+                    ui.text_edit_singleline(&mut dialog.new_name);
+                });
 
-    for i in 0..=segments {
-        let t = i as f32 / segments as f32;
-        let t2 = t * t;
-        let t3 = t2 * t;
-        let mt = 1.0 - t;
-        let mt2 = mt * mt;
-        let mt3 = mt2 * mt;
+                ui.add_space(8.0);
+                for field in entity.fields.iter().filter(|f| !f.is_primary_key()) {
+                    let mut checked = dialog.selected.contains(&field.name);
+                    if ui.checkbox(&mut checked, format!("{}: {}", field.name, Self::data_type_display(&field.data_type))).changed() {
+                        if checked {
+                            dialog.selected.insert(field.name.clone());
+                        } else {
+                            dialog.selected.remove(&field.name);
+                        }
+                    }
+                }
 
-        let x = mt3 * p0.x + 3.0 * mt2 * t * p1.x + 3.0 * mt * t2 * p2.x + t3 * p3.x;
-        let y = mt3 * p0.y + 3.0 * mt2 * t * p1.y + 3.0 * mt * t2 * p2.y + t3 * p3.y;
+                ui.add_space(10.0);
+                ui.horizontal(|ui| {
+                    let ready = !dialog.new_name.trim().is_empty() && !dialog.selected.is_empty();
+                    if ui.add_enabled(ready, egui::Button::new(tr("split.split"))).clicked() {
+                        apply = true;
+                    }
+                    if ui.button(tr("dialog.cancel")).clicked() {
+                        close = true;
+                    }
+                });
+            });
 
-        points.push(egui::pos2(x, y));
+        if apply {
+            let Some(dialog) = self.split_dialog.take() else {
+                return;
+            };
+            let fields: Vec<String> = dialog.selected.into_iter().collect();
+            let new_name = dialog.new_name.trim().to_string();
+            self.save_undo_state("Split entity");
+            match self.project.split_entity(dialog.entity, &fields, new_name.as_str()) {
+                Ok(_) => self.set_status(trf("split.done", &[&fields.len(), &entity_name, &new_name])),
+                Err(e) => self.set_error(trf("dialog.failed", &[&e])),
+            }
+        } else if close {
+            self.split_dialog = None;
+        }
     }
 
-    points
+    /// Render project statistics and complexity metrics
+    fn render_project_info(&mut self, ctx: &egui::Context) {
+        let stats = self.project.stats();
+        let mut focus_node = None;
+
+        egui::Window::new(tr("project_info.title"))
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.heading(&self.project.meta.name);
+                ui.add_space(6.0);
+
+                egui::Grid::new("project_stats").num_columns(2).striped(true).show(ui, |ui| {
+                    ui.label(tr("project_info.nodes"));
+                    ui.label(stats.nodes.to_string());
+                    ui.end_row();
+                    ui.label(tr("project_info.connections"));
+                    ui.label(stats.edges.to_string());
+                    ui.end_row();
+                    ui.label(tr("project_info.groups"));
+                    ui.label(stats.groups.to_string());
+                    ui.end_row();
+                    ui.label(tr("project_info.entity_fields"));
+                    ui.label(stats.entity_fields.to_string());
+                    ui.end_row();
+                    ui.label(tr("project_info.connectivity"));
+                    ui.label(format!("{:.2}", stats.average_connectivity));
+                    ui.end_row();
+                    ui.label(tr("project_info.depth"));
+                    ui.label(if stats.has_cycles {
+                        trf("project_info.depth_cycles", &[&stats.dag_depth])
+                    } else {
+                        stats.dag_depth.to_string()
+                    });
+                    ui.end_row();
+                });
+
+                ui.add_space(8.0);
+                ui.strong(tr("project_info.by_category"));
+                for category in imortal_core::ComponentCategory::all() {
+                    let count = stats.category_count(*category);
+                    if count > 0 {
+                        let name = i18n::lookup(&format!("category.{:?}", category).to_lowercase()).unwrap_or(category.display_name());
+                        ui.label(format!("{} {}: {}", category.icon(), name, count));
+                    }
+                }
+
+                if !stats.hotspots.is_empty() {
+                    ui.add_space(8.0);
+                    ui.strong(tr("project_info.hotspots"));
+                    for hotspot in &stats.hotspots {
+                        if ui.link(trf("project_info.hotspot", &[&hotspot.name, &hotspot.degree])).clicked() {
+                            focus_node = Some(hotspot.node_id);
+                        }
+                    }
+                }
+
+                ui.add_space(10.0);
+                if ui.button(tr("dialog.close")).clicked() {
+                    self.show_project_info = false;
+                }
+            });
+
+        if let Some(node_id) = focus_node {
+            self.project.clear_selection();
+            self.project.select_node(node_id);
+        }
+    }
+
+    /// Render the graph analysis panel with its bulk-fix actions
+    fn render_analysis(&mut self, ctx: &egui::Context) {
+        // Re-run every frame so the panel follows edits on the canvas
+        let analysis = GraphAnalysis::analyze(&self.project);
+        let mut focus_node = None;
+        let mut delete_orphans = false;
+        let mut merge_duplicates = false;
+
+        egui::Window::new(tr("analysis.title"))
+            .collapsible(false)
+            .resizable(true)
+            .default_width(360.0)
+            .show(ctx, |ui| {
+                if analysis.is_clean() {
+                    ui.label(tr("analysis.clean"));
+                }
+
+                egui::ScrollArea::vertical().max_height(320.0).show(ui, |ui| {
+                    for kind in FindingKind::all() {
+                        let count = analysis.count(*kind);
+                        if count == 0 {
+                            continue;
+                        }
+                        let key = format!("analysis.kind.{}", kind.name().to_lowercase().replace(' ', "_"));
+                        let name = i18n::lookup(&key).unwrap_or(kind.name());
+                        egui::CollapsingHeader::new(format!("{} ({})", name, count))
+                            .default_open(true)
+                            .show(ui, |ui| {
+                                for finding in analysis.of_kind(*kind) {
+                                    ui.label(&finding.message);
+                                    ui.horizontal_wrapped(|ui| {
+                                        for id in &finding.nodes {
+                                            let name = self.project.get_node(*id)
+                                                .map(|n| n.name.as_str())
+                                                .unwrap_or("?");
+                                            if ui.small_button(name).on_hover_text(tr("analysis.select")).clicked() {
+                                                focus_node = Some(*id);
+                                            }
+                                        }
+                                    });
+                                }
+                            });
+                    }
+                });
+
+                ui.separator();
+                ui.horizontal(|ui| {
+                    let orphans = analysis.count(FindingKind::OrphanedNode);
+                    if ui.add_enabled(orphans > 0, egui::Button::new(trf("analysis.delete_orphans", &[&orphans]))).clicked() {
+                        delete_orphans = true;
+                    }
+                    let duplicates = analysis.count(FindingKind::DuplicateEntity);
+                    if ui.add_enabled(duplicates > 0, egui::Button::new(trf("analysis.merge_duplicates", &[&duplicates])))
+                        .on_hover_text(tr("analysis.merge_duplicates_hint"))
+                        .clicked()
+                    {
+                        merge_duplicates = true;
+                    }
+                    if ui.button(tr("dialog.close")).clicked() {
+                        self.show_analysis = false;
+                    }
+                });
+            });
+
+        if let Some(node_id) = focus_node {
+            self.project.clear_selection();
+            self.project.select_node(node_id);
+        }
+        if delete_orphans {
+            self.save_undo_state("Delete orphaned nodes");
+            let count = analysis.delete_orphans(&mut self.project);
+            self.set_status(trf("analysis.deleted", &[&count]));
+        }
+        if merge_duplicates {
+            self.save_undo_state("Merge duplicate entities");
+            let count = analysis.merge_duplicates(&mut self.project);
+            self.set_status(trf("analysis.merged", &[&count]));
+        }
+    }
+
+    /// Render Tools > Component Usage: instances per component, unused
+    /// definitions, and nodes differing from their defaults
+    fn render_usage_report(&mut self, ctx: &egui::Context) {
+        if !self.usage_open {
+            return;
+        }
+        // Re-run every frame so the report follows edits on the canvas
+        let report = UsageReport::analyze(&self.registry, &self.project);
+        let node_name = |id: &NodeId| self.project.get_node(*id).map_or("?", |n| n.name.as_str()).to_string();
+        let mut reveal = None;
+        let mut select = None;
+        let mut open = true;
+
+        egui::Window::new(tr("usage.title"))
+            .open(&mut open)
+            .resizable(true)
+            .default_width(380.0)
+            .show(ctx, |ui| {
+                let types = report.used.len() + report.unknown.len();
+                ui.label(trf("usage.summary", &[&self.project.node_count(), &types, &report.unused.len()]));
+                ui.separator();
+                egui::ScrollArea::vertical().max_height(420.0).show(ui, |ui| {
+                    egui::CollapsingHeader::new(trf("usage.instances", &[&types]))
+                        .default_open(true)
+                        .show(ui, |ui| {
+                            for usage in report.used.iter().chain(&report.unknown) {
+                                let icon = self.registry.get(&usage.component_type).map_or("❓", |d| d.icon_text());
+                                let header = format!("{} {} ({})", icon, usage.name, usage.instances.len());
+                                egui::CollapsingHeader::new(header).id_salt(("usage", &usage.component_type)).show(ui, |ui| {
+                                    if self.registry.get(&usage.component_type).is_none() {
+                                        ui.weak(tr("usage.unregistered"));
+                                    }
+                                    ui.horizontal_wrapped(|ui| {
+                                        for id in &usage.instances {
+                                            if ui.small_button(node_name(id)).on_hover_text(tr("usage.reveal")).clicked() {
+                                                reveal = Some(*id);
+                                            }
+                                        }
+                                    });
+                                    if usage.instances.len() > 1 && ui.small_button(tr("usage.select_all")).clicked() {
+                                        select = Some(usage.instances.clone());
+                                    }
+                                });
+                            }
+                        });
+
+                    egui::CollapsingHeader::new(trf("usage.unused", &[&report.unused.len()])).show(ui, |ui| {
+                        ui.horizontal_wrapped(|ui| {
+                            for id in &report.unused {
+                                if let Some(definition) = self.registry.get(id) {
+                                    ui.label(format!("{} {}", definition.icon_text(), definition.name)).on_hover_text(id);
+                                }
+                            }
+                        });
+                    });
+
+                    egui::CollapsingHeader::new(trf("usage.deviations", &[&report.deviations.len()]))
+                        .default_open(!report.deviations.is_empty())
+                        .show(ui, |ui| {
+                            if report.deviations.is_empty() {
+                                ui.weak(tr("usage.no_deviations"));
+                            }
+                            for deviation in &report.deviations {
+                                ui.horizontal_wrapped(|ui| {
+                                    if ui.small_button(node_name(&deviation.node_id)).on_hover_text(tr("usage.reveal")).clicked() {
+                                        reveal = Some(deviation.node_id);
+                                    }
+                                    for (key, names) in [
+                                        ("usage.config", &deviation.config),
+                                        ("usage.missing_fields", &deviation.missing_fields),
+                                        ("usage.changed_fields", &deviation.changed_fields),
+                                    ] {
+                                        if !names.is_empty() {
+                                            ui.weak(trf(key, &[&names.join(", ")]));
+                                        }
+                                    }
+                                });
+                            }
+                        });
+                });
+            });
+        self.usage_open = open;
+
+        if let Some(node_id) = reveal {
+            self.reveal_node(node_id);
+        }
+        if let Some(ids) = select {
+            self.project.clear_selection();
+            for id in ids {
+                self.project.select_node(id);
+            }
+        }
+    }
+
+    /// Select a node and ease the viewport to center on it
+    fn reveal_node(&mut self, node_id: NodeId) {
+        let Some(center) = self.project.get_node(node_id).map(|n| n.center()) else {
+            return;
+        };
+        self.project.clear_selection();
+        self.project.select_node(node_id);
+        let from = self.project.viewport;
+        let zoom = self.project.viewport.zoom;
+        // Same nominal canvas size as fit_to_content
+        self.project.viewport.pan_x = 400.0 - center.x * zoom;
+        self.project.viewport.pan_y = 300.0 - center.y * zoom;
+        self.animate_viewport_from(from);
+    }
+
+    // File operations
+    fn open_project(&mut self) {
+        if let Some(path) = rfd::FileDialog::new()
+            .add_filter("Immortal Project", &[imortal_ir::PROJECT_EXTENSION, imortal_ir::DB_EXTENSION])
+            .pick_file()
+        {
+            self.load_project_from_path(path);
+        }
+    }
+
+    fn open_project_in_new_tab(&mut self) {
+        if let Some(path) = rfd::FileDialog::new()
+            .add_filter("Immortal Project", &[imortal_ir::PROJECT_EXTENSION, imortal_ir::DB_EXTENSION])
+            .pick_file()
+        {
+            match imortal_ir::load_project_lazy(&path, LAZY_LOAD_NODES) {
+                Ok(project) => {
+                    let status = format!("Opened project: {}", project.meta.name);
+                    self.open_loaded_project(project, path, true, status);
+                }
+                Err(e) => self.load_failed(path, e, true),
+            }
+        }
+    }
+
+    // Project tabs
+
+    /// Exchange the live project state with the active tab's slot
+    fn swap_active_tab(&mut self) {
+        let tab = &mut self.tabs[self.active_tab];
+        std::mem::swap(&mut self.project, &mut tab.project);
+        std::mem::swap(&mut self.project_path, &mut tab.path);
+        std::mem::swap(&mut self.history, &mut tab.history);
+        std::mem::swap(&mut self.views, &mut tab.views);
+        std::mem::swap(&mut self.project_lock, &mut tab.lock);
+        std::mem::swap(&mut self.read_only, &mut tab.read_only);
+    }
+
+    /// Make another tab live
+    fn switch_tab(&mut self, index: usize) {
+        if index == self.active_tab || index >= self.tabs.len() {
+            return;
+        }
+        self.leave_composites();
+        self.swap_active_tab();
+        self.active_tab = index;
+        self.swap_active_tab();
+
+        self.tutorial.reset_counts();
+
+        // Interaction state refers to nodes of the previous project
+        self.drawing_connection = false;
+        self.connection_from_node = None;
+        self.resizing_node = None;
+        self.node_drag = None;
+        self.name_edit = None;
+        self.merge_dialog = None;
+        self.split_dialog = None;
+        self.pending_port_removal = None;
+        self.namespace_edit = None;
+        self.tags_edit = None;
+        self.template_prompt = None;
+        self.code_sync = None;
+        self.project_settings = None;
+        self.state.namespace.clear();
+        self.mode = AppMode::Editor;
+    }
+
+    /// Open a project in a new tab, or switch to it if its file is already open
+    fn open_tab(&mut self, project: ProjectGraph, path: Option<PathBuf>) {
+        if let Some(index) = path.as_ref().and_then(|p| self.tab_index_for_path(p)) {
+            self.switch_tab(index);
+            return;
+        }
+        self.tabs.push(ProjectTab::new(project, path));
+        self.switch_tab(self.tabs.len() - 1);
+        self.welcome_screen.close();
+    }
+
+    /// Close a tab; the last remaining tab cannot be closed
+    fn close_tab(&mut self, index: usize) {
+        if self.tabs.len() < 2 || index >= self.tabs.len() {
+            return;
+        }
+        if index == self.active_tab {
+            self.switch_tab(if index == 0 { 1 } else { index - 1 });
+        }
+        self.tabs.remove(index);
+        if index < self.active_tab {
+            self.active_tab -= 1;
+        }
+    }
+
+    fn tab_index_for_path(&self, path: &std::path::Path) -> Option<usize> {
+        (0..self.tabs.len()).find(|i| self.tab_path(*i).as_deref() == Some(path))
+    }
+
+    fn tab_project(&self, index: usize) -> &ProjectGraph {
+        if index == self.active_tab { self.root_project() } else { &self.tabs[index].project }
+    }
+
+    fn tab_path(&self, index: usize) -> Option<PathBuf> {
+        if index == self.active_tab { self.project_path.clone() } else { self.tabs[index].path.clone() }
+    }
+
+    /// Render the project and view tabs; only shown when there is more than one of either
+    fn render_tab_bar(&mut self, ctx: &egui::Context) {
+        if self.tabs.len() < 2 && self.views.len() < 2 {
+            return;
+        }
+
+        let mut switch_to = None;
+        let mut close = None;
+        let mut activate_view = None;
+        let mut close_view = None;
+        egui::TopBottomPanel::top("tab_bar").show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                for index in (0..self.tabs.len()).filter(|_| self.tabs.len() > 1) {
+                    let name = self.tab_project(index).meta.name.clone();
+                    let path = self.tab_path(index);
+                    let response = ui.selectable_label(index == self.active_tab, format!("📄 {}", name));
+                    let response = match path {
+                        Some(path) => response.on_hover_text(path.display().to_string()),
+                        None => response,
+                    };
+                    if response.clicked() {
+                        switch_to = Some(index);
+                    }
+                    if ui.small_button("×").on_hover_text("Close tab").clicked() {
+                        close = Some(index);
+                    }
+                    ui.separator();
+                }
+
+                if self.views.len() > 1 {
+                    for (index, view) in self.views.views().iter().enumerate() {
+                        let marker = if self.views.split() == Some(index) { "◧ " } else { "" };
+                        if ui.selectable_label(index == self.views.active(), format!("{}👁 {}", marker, view.name)).clicked() {
+                            activate_view = Some(index);
+                        }
+                        if ui.small_button("×").on_hover_text("Close view").clicked() {
+                            close_view = Some(index);
+                        }
+                    }
+                }
+                if ui.small_button("+").on_hover_text("New view").clicked() {
+                    self.views.add(&mut self.project.viewport);
+                }
+            });
+        });
+
+        if let Some(index) = switch_to {
+            self.switch_tab(index);
+        }
+        if let Some(index) = close {
+            self.close_tab(index);
+        }
+        if let Some(index) = activate_view {
+            self.views.activate(index, &mut self.project.viewport);
+        }
+        if let Some(index) = close_view {
+            self.views.close(index, &mut self.project.viewport);
+        }
+    }
+
+    // Namespaces
+
+    /// The live project as the canvas shows it, with only the entered
+    /// module's nodes
+    fn shown_project(&self) -> Cow<'_, ProjectGraph> {
+        if self.state.namespace.is_empty() {
+            return Cow::Borrowed(&self.project);
+        }
+        let shown = self.project.nodes()
+            .filter(|node| node.in_namespace(&self.state.namespace))
+            .map(|node| node.id)
+            .collect();
+        Cow::Owned(self.project.extract(&shown).project)
+    }
+
+    /// Select everything the canvas shows
+    fn select_all_shown(&mut self) {
+        if self.state.namespace.is_empty() {
+            self.project.select_all();
+            return;
+        }
+        self.project.clear_selection();
+        let shown: Vec<NodeId> = self.shown_project().node_ids().copied().collect();
+        for node_id in shown {
+            self.project.select_node(node_id);
+        }
+    }
+
+    /// Tags the canvas hides nodes without; empty while the tag bar only
+    /// dims them
+    fn hidden_by_tags(&self) -> &[String] {
+        if self.state.hide_untagged { &self.state.tag_filter } else { &[] }
+    }
+
+    /// Whether a node carries a tag selected in the tag bar, or none is
+    fn tag_filter_includes(&self, id: NodeId) -> bool {
+        self.project.get_node(id).is_none_or(|node| node.has_any_tag(&self.state.tag_filter))
+    }
+
+    /// Enter a module, hiding everything outside it; an empty path shows the
+    /// whole project again
+    fn enter_namespace(&mut self, namespace: Vec<String>) {
+        self.project.clear_selection();
+        self.focused_node = None;
+        self.inline_rename = None;
+        if namespace.is_empty() {
+            self.set_status(tr("namespace.left"));
+        } else {
+            self.set_status(trf("namespace.entered", &[&namespace.join("::")]));
+        }
+        self.state.namespace = namespace;
+    }
+
+    /// Render the breadcrumb bar leading to the entered composites and module
+    ///
+    /// Only shown once the project uses namespaces or a composite is entered.
+    fn render_breadcrumbs(&mut self, ctx: &egui::Context) {
+        if self.state.namespace.is_empty() && self.composite_stack.is_empty() && self.project.namespaces().is_empty() {
+            return;
+        }
+
+        let mut enter = None;
+        let mut leave_to = None;
+        egui::TopBottomPanel::top("breadcrumbs").show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                let current = &self.state.namespace;
+                let depth = self.composite_stack.len();
+                if ui.selectable_label(current.is_empty() && depth == 0, format!("🏠 {}", tr("namespace.root"))).clicked() {
+                    leave_to = Some(0);
+                    enter = Some(Vec::new());
+                }
+                for (index, frame) in self.composite_stack.iter().enumerate() {
+                    let name = frame.project.get_node(frame.node_id).map_or("?", |node| node.name.as_str());
+                    ui.label("›");
+                    let response = ui.selectable_label(current.is_empty() && index + 1 == depth, format!("🧩 {}", name));
+                    if response.on_hover_text(tr("composite.leave_hint")).clicked() {
+                        leave_to = Some(index + 1);
+                        enter = Some(Vec::new());
+                    }
+                }
+                for (depth, segment) in current.iter().enumerate() {
+                    ui.label("›");
+                    if ui.selectable_label(depth + 1 == current.len(), format!("📁 {}", segment)).clicked() {
+                        enter = Some(current[..=depth].to_vec());
+                    }
+                }
+
+                let children = self.project.child_namespaces(current);
+                if !children.is_empty() {
+                    ui.label("›");
+                    ui.menu_button(tr("namespace.enter"), |ui| {
+                        for child in children {
+                            if ui.button(format!("📁 {}", child)).clicked() {
+                                let mut namespace = current.clone();
+                                namespace.push(child);
+                                enter = Some(namespace);
+                                ui.close_menu();
+                            }
+                        }
+                    });
+                }
+
+                if !current.is_empty() {
+                    let shown = self.project.nodes().filter(|node| node.in_namespace(current)).count();
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        ui.weak(trf("namespace.showing", &[&shown, &self.project.node_count()]));
+                    });
+                }
+            });
+        });
+
+        if let Some(depth) = leave_to {
+            while self.composite_stack.len() > depth {
+                self.leave_composite();
+            }
+        }
+        if let Some(namespace) = enter.filter(|namespace| *namespace != self.state.namespace) {
+            self.enter_namespace(namespace);
+        }
+    }
+
+    /// Render the tag bar, which filters the canvas to the nodes carrying
+    /// the selected tags by dimming or hiding the rest
+    fn render_tag_bar(&mut self, ctx: &egui::Context) {
+        let tags = self.project.tags();
+        self.state.tag_filter.retain(|tag| tags.iter().any(|(t, _)| t == tag));
+        if tags.is_empty() {
+            return;
+        }
+
+        let mut changed = false;
+        egui::TopBottomPanel::top("tag_bar").show(ctx, |ui| {
+            ui.horizontal_wrapped(|ui| {
+                ui.label(tr("tags.filter"));
+                let filter = &mut self.state.tag_filter;
+                for (tag, count) in &tags {
+                    let selected = filter.contains(tag);
+                    if ui.selectable_label(selected, format!("🏷 {} ({})", tag, count)).clicked() {
+                        if selected {
+                            filter.retain(|t| t != tag);
+                        } else {
+                            filter.push(tag.clone());
+                        }
+                        changed = true;
+                    }
+                }
+                if filter.is_empty() {
+                    return;
+                }
+
+                ui.separator();
+                changed |= ui.checkbox(&mut self.state.hide_untagged, tr("tags.hide")).on_hover_text(tr("tags.hide_hint")).changed();
+                if ui.button(tr("tags.clear")).clicked() {
+                    self.state.tag_filter.clear();
+                    changed = true;
+                }
+                let shown = self.project.nodes().filter(|node| node.has_any_tag(&self.state.tag_filter)).count();
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    ui.weak(trf("tags.showing", &[&shown, &self.project.node_count()]));
+                });
+            });
+        });
+
+        // Hidden nodes don't stay selected
+        if changed && self.state.hide_untagged {
+            let filter = &self.state.tag_filter;
+            let hidden: Vec<NodeId> = self
+                .project
+                .selected_nodes
+                .iter()
+                .filter(|id| self.project.get_node(**id).is_some_and(|node| !node.has_any_tag(filter)))
+                .copied()
+                .collect();
+            for id in hidden {
+                self.project.deselect_node(id);
+            }
+        }
+    }
+
+    // Composites
+
+    /// Collapse a group into a composite node as one undo step
+    fn collapse_to_composite(&mut self, group_id: uuid::Uuid) {
+        let mut project = self.project.clone();
+        match project.collapse_group(group_id) {
+            Ok(node_id) => {
+                self.save_undo_state("Collapse to composite");
+                self.project = project;
+                self.project.clear_selection();
+                self.project.select_node(node_id);
+                let name = self.project.get_node(node_id).map(|node| node.name.clone()).unwrap_or_default();
+                self.set_status(trf("composite.collapsed", &[&name]));
+            }
+            Err(e) => self.set_error(trf("composite.failed", &[&e])),
+        }
+    }
+
+    /// Put a composite's nodes back on the canvas as a group
+    fn expand_composite(&mut self, node_id: NodeId) {
+        let mut project = self.project.clone();
+        match project.expand_composite(node_id) {
+            Ok(group_id) => {
+                self.save_undo_state("Expand composite");
+                self.project = project;
+                self.project.clear_selection();
+                let nodes: Vec<NodeId> = self.project.get_group(group_id).map(|g| g.nodes().copied().collect()).unwrap_or_default();
+                for id in nodes {
+                    self.project.select_node(id);
+                }
+                self.focused_node = None;
+                self.set_status(tr("composite.expanded"));
+            }
+            Err(e) => self.set_error(trf("composite.failed", &[&e])),
+        }
+    }
+
+    /// Edit a composite's subgraph on its own canvas, with its own undo history
+    fn enter_composite(&mut self, node_id: NodeId) {
+        let Some(node) = self.project.get_node(node_id).filter(|node| node.is_composite()) else {
+            return;
+        };
+        let name = node.name.clone();
+        let inner = node.subgraph.as_ref().map(|sub| sub.graph.clone()).unwrap_or_default();
+
+        let project = std::mem::replace(&mut self.project, inner);
+        let history = std::mem::take(&mut self.history);
+        let namespace = std::mem::take(&mut self.state.namespace);
+        self.composite_stack.push(CompositeFrame { node_id, project, history, namespace });
+
+        self.focused_node = None;
+        self.inline_rename = None;
+        self.namespace_edit = None;
+        self.tags_edit = None;
+        self.drawing_connection = false;
+        self.connection_from_node = None;
+        self.resizing_node = None;
+        self.node_drag = None;
+        self.project.clear_selection();
+        self.project.fit_to_content(800.0, 600.0);
+        self.set_status(trf("composite.entered", &[&name]));
+    }
+
+    /// Return to the project around the innermost entered composite
+    ///
+    /// Edits made inside become one undo step of the outer project.
+    fn leave_composite(&mut self) -> bool {
+        let Some(frame) = self.composite_stack.pop() else {
+            return false;
+        };
+        let inner = std::mem::replace(&mut self.project, frame.project);
+        let edited = std::mem::replace(&mut self.history, frame.history).can_undo();
+        self.state.namespace = frame.namespace;
+        self.focused_node = None;
+        self.inline_rename = None;
+        self.namespace_edit = None;
+        self.tags_edit = None;
+        self.drawing_connection = false;
+        self.connection_from_node = None;
+        self.resizing_node = None;
+        self.node_drag = None;
+
+        if edited {
+            self.save_undo_state("Edit composite");
+            if let Err(e) = self.project.set_subgraph(frame.node_id, inner) {
+                self.set_error(trf("composite.failed", &[&e]));
+            }
+        }
+        true
+    }
+
+    /// Leave all entered composites, e.g. before saving the whole project
+    fn leave_composites(&mut self) {
+        while self.leave_composite() {}
+    }
+
+    /// The tab's project, outside any entered composite
+    fn root_project(&self) -> &ProjectGraph {
+        self.composite_stack.first().map_or(&self.project, |frame| &frame.project)
+    }
+
+    /// Change the project outside any entered composite, as an undo step there
+    fn edit_root_project<R>(&mut self, action_name: &str, edit: impl FnOnce(&mut ProjectGraph) -> R) -> R {
+        match self.composite_stack.first_mut() {
+            Some(frame) => {
+                frame.history.push(action_name, frame.project.clone());
+                edit(&mut frame.project)
+            }
+            None => {
+                self.save_undo_state(action_name);
+                edit(&mut self.project)
+            }
+        }
+    }
+
+    // Project settings
+
+    fn open_project_settings(&mut self) {
+        let meta = self.root_project().meta.clone();
+        self.project_settings = Some(ProjectSettings {
+            authors: meta.authors.join(", "),
+            tags: meta.keywords.join(", "),
+            pre_generate: meta.codegen.pre_generate.join("\n"),
+            post_generate: meta.codegen.post_generate.join("\n"),
+            transforms: meta.codegen.transforms.iter().map(|t| format!("{} {}", t.pattern, t.command)).collect::<Vec<_>>().join("\n"),
+            meta,
+        });
+    }
+
+    /// Edit the project's metadata and code generation settings
+    fn render_project_settings(&mut self, ctx: &egui::Context) {
+        let Some(settings) = &mut self.project_settings else {
+            return;
+        };
+
+        let mut open = true;
+        let mut save = false;
+        let mut cancel = false;
+        egui::Window::new(tr("project_settings.title"))
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                let meta = &mut settings.meta;
+                egui::Grid::new("project_settings").num_columns(2).show(ui, |ui| {
+                    ui.label(tr("project_settings.name"));
+                    ui.text_edit_singleline(&mut meta.name);
+                    ui.end_row();
+
+                    ui.label(tr("project_settings.version"));
+                    ui.text_edit_singleline(&mut meta.version);
+                    ui.end_row();
+
+                    ui.label(tr("project_settings.description"));
+                    let mut description = meta.description.clone().unwrap_or_default();
+                    if ui.text_edit_multiline(&mut description).changed() {
+                        meta.description = Some(description).filter(|d| !d.trim().is_empty());
+                    }
+                    ui.end_row();
+
+                    ui.label(tr("project_settings.authors"));
+                    ui.text_edit_singleline(&mut settings.authors).on_hover_text(tr("project_settings.comma_separated"));
+                    ui.end_row();
+
+                    ui.label(tr("project_settings.license"));
+                    let mut license = meta.license.clone().unwrap_or_default();
+                    if ui.text_edit_singleline(&mut license).changed() {
+                        meta.license = Some(license.trim().to_string()).filter(|l| !l.is_empty());
+                    }
+                    ui.end_row();
+
+                    ui.label(tr("project_settings.tags"));
+                    ui.text_edit_singleline(&mut settings.tags).on_hover_text(tr("project_settings.comma_separated"));
+                    ui.end_row();
+
+                    ui.label(tr("project_settings.framework"));
+                    let selected = meta.target_framework.as_deref().and_then(AuthFramework::from_id);
+                    egui::ComboBox::from_id_salt("project_framework")
+                        .selected_text(selected.map_or(tr("project_settings.default"), |f| f.display_name()))
+                        .show_ui(ui, |ui| {
+                            ui.selectable_value(&mut meta.target_framework, None, tr("project_settings.default"));
+                            for framework in AuthFramework::ALL {
+                                ui.selectable_value(
+                                    &mut meta.target_framework,
+                                    Some(framework.id().to_string()),
+                                    framework.display_name(),
+                                );
+                            }
+                        });
+                    ui.end_row();
+
+                    ui.label(tr("project_settings.database"));
+                    let selected = meta.target_database.as_deref().and_then(DatabaseBackend::from_id);
+                    egui::ComboBox::from_id_salt("project_database")
+                        .selected_text(selected.map_or(tr("project_settings.default"), |d| d.display_name()))
+                        .show_ui(ui, |ui| {
+                            ui.selectable_value(&mut meta.target_database, None, tr("project_settings.default"));
+                            for backend in DatabaseBackend::ALL {
+                                ui.selectable_value(
+                                    &mut meta.target_database,
+                                    Some(backend.id().to_string()),
+                                    backend.display_name(),
+                                );
+                            }
+                        });
+                    ui.end_row();
+                });
+
+                egui::CollapsingHeader::new(tr("project_settings.codegen")).show(ui, |ui| {
+                    let codegen = &mut meta.codegen;
+                    egui::Grid::new("project_codegen").num_columns(2).show(ui, |ui| {
+                        ui.label(tr("project_settings.output_dir"));
+                        ui.text_edit_singleline(&mut meta.output_dir);
+                        ui.end_row();
+
+                        ui.label(tr("project_settings.orm"));
+                        let selected = codegen.orm.as_deref().and_then(Orm::from_id);
+                        egui::ComboBox::from_id_salt("project_orm")
+                            .selected_text(selected.map_or(tr("project_settings.default"), |o| o.display_name()))
+                            .show_ui(ui, |ui| {
+                                ui.selectable_value(&mut codegen.orm, None, tr("project_settings.default"));
+                                for orm in Orm::ALL {
+                                    ui.selectable_value(&mut codegen.orm, Some(orm.id().to_string()), orm.display_name());
+                                }
+                            });
+                        ui.end_row();
+
+                        ui.label(tr("project_settings.layout"));
+                        let selected = codegen.layout.as_deref().and_then(ProjectLayout::from_id);
+                        egui::ComboBox::from_id_salt("project_layout")
+                            .selected_text(selected.map_or(tr("project_settings.default"), |l| l.display_name()))
+                            .show_ui(ui, |ui| {
+                                ui.selectable_value(&mut codegen.layout, None, tr("project_settings.default"));
+                                for layout in ProjectLayout::ALL {
+                                    ui.selectable_value(
+                                        &mut codegen.layout,
+                                        Some(layout.id().to_string()),
+                                        layout.display_name(),
+                                    );
+                                }
+                            });
+                        ui.end_row();
+
+                        ui.label(tr("project_settings.inheritance"));
+                        let selected = codegen.inheritance.as_deref().and_then(InheritanceMode::from_id);
+                        egui::ComboBox::from_id_salt("project_inheritance")
+                            .selected_text(selected.map_or(tr("project_settings.default"), |m| m.display_name()))
+                            .show_ui(ui, |ui| {
+                                ui.selectable_value(&mut codegen.inheritance, None, tr("project_settings.default"));
+                                for mode in InheritanceMode::ALL {
+                                    ui.selectable_value(
+                                        &mut codegen.inheritance,
+                                        Some(mode.id().to_string()),
+                                        mode.display_name(),
+                                    );
+                                }
+                            });
+                        ui.end_row();
+
+                        // Unset is a single tenant, so there's no separate default
+                        ui.label(tr("project_settings.tenancy"));
+                        let mut tenancy = codegen.tenancy.as_deref().and_then(TenancyMode::from_id).unwrap_or_default();
+                        egui::ComboBox::from_id_salt("project_tenancy")
+                            .selected_text(tenancy.display_name())
+                            .show_ui(ui, |ui| {
+                                for mode in TenancyMode::ALL {
+                                    ui.selectable_value(&mut tenancy, mode, mode.display_name());
+                                }
+                            });
+                        codegen.tenancy = tenancy.is_enabled().then(|| tenancy.id().to_string());
+                        ui.end_row();
+                    });
+
+                    ui.checkbox(&mut meta.generate_tests, tr("project_settings.generate_tests"));
+                    ui.checkbox(&mut meta.generate_docs, tr("project_settings.generate_docs"));
+                    ui.checkbox(&mut codegen.generate_migrations, tr("project_settings.generate_migrations"));
+                    ui.checkbox(&mut codegen.format_code, tr("project_settings.format_code"));
+                    ui.checkbox(&mut codegen.health_checks, tr("project_settings.health_checks"));
+                    ui.checkbox(&mut codegen.metrics, tr("project_settings.metrics"));
+                    ui.checkbox(&mut codegen.tracing, tr("project_settings.tracing"));
+                });
+
+                egui::CollapsingHeader::new(tr("project_settings.hooks")).show(ui, |ui| {
+                    ui.colored_label(ui.visuals().warn_fg_color, tr("project_settings.hooks_warning"));
+                    for (label, hint, text) in [
+                        ("project_settings.pre_generate", "cargo clean", &mut settings.pre_generate),
+                        ("project_settings.post_generate", "cargo fmt", &mut settings.post_generate),
+                        ("project_settings.transforms", "*.rs ./add-license-header.sh", &mut settings.transforms),
+                    ] {
+                        ui.label(tr(label));
+                        ui.add(egui::TextEdit::multiline(text).code_editor().desired_rows(2).hint_text(hint));
+                    }
+                });
+
+                ui.add_space(8.0);
+                ui.horizontal(|ui| {
+                    let valid = !meta.name.trim().is_empty();
+                    save = ui.add_enabled(valid, egui::Button::new(tr("project_settings.save"))).clicked();
+                    cancel = ui.button(tr("dialog.cancel")).clicked();
+                });
+            });
+
+        if save {
+            let Some(settings) = self.project_settings.take() else {
+                return;
+            };
+            let split = |text: &str| -> Vec<String> {
+                text.split(',').map(str::trim).filter(|item| !item.is_empty()).map(String::from).collect()
+            };
+            let mut meta = settings.meta;
+            meta.name = meta.name.trim().to_string();
+            meta.authors = split(&settings.authors);
+            meta.keywords = split(&settings.tags);
+            let lines = |text: &str| -> Vec<String> { text.lines().map(str::trim).filter(|l| !l.is_empty()).map(String::from).collect() };
+            meta.codegen.pre_generate = lines(&settings.pre_generate);
+            meta.codegen.post_generate = lines(&settings.post_generate);
+            meta.codegen.transforms = lines(&settings.transforms)
+                .iter()
+                .filter_map(|line| line.split_once(char::is_whitespace))
+                .map(|(pattern, command)| TransformCommand { pattern: pattern.to_string(), command: command.trim().to_string() })
+                .collect();
+            // Hooks typed here are the user's own and run without asking
+            let hooks = GenerationHooks::from_settings(&meta.codegen).commands_hash();
+            if hooks != GenerationHooks::from_settings(&self.root_project().meta.codegen).commands_hash() {
+                if let Some(hash) = hooks {
+                    self.record_hook_decision(hash, true);
+                }
+            }
+            self.edit_root_project("Edit project settings", |project| project.meta = meta);
+            self.set_status(tr("project_settings.saved"));
+        } else if cancel || !open {
+            self.project_settings = None;
+        }
+    }
+
+    /// Entity an entity extends and the mixins it includes
+    fn render_inheritance(&mut self, ui: &mut egui::Ui, node: &Node) {
+        egui::CollapsingHeader::new(tr("inheritance.title")).show(ui, |ui| {
+            let parent = self.project.parent_entity(node.id);
+            let parent_name = |project: &ProjectGraph, id: Option<NodeId>| {
+                id.and_then(|id| project.get_node(id))
+                    .map_or(tr("inheritance.none").to_string(), |n| n.name.clone())
+            };
+            let mut choice = parent;
+            ui.horizontal(|ui| {
+                ui.label(tr("inheritance.extends"));
+                egui::ComboBox::from_id_salt(("entity_parent", node.id))
+                    .selected_text(parent_name(&self.project, parent))
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(&mut choice, None, tr("inheritance.none"));
+                        // Entities that don't already extend this one
+                        for other in self.project.nodes() {
+                            if other.component_type == "data.entity"
+                                && other.id != node.id
+                                && !self.project.ancestors(other.id).contains(&node.id)
+                            {
+                                ui.selectable_value(&mut choice, Some(other.id), &other.name);
+                            }
+                        }
+                    });
+            });
+            if choice != parent {
+                self.save_undo_state("Change parent entity");
+                match self.project.set_parent_entity(node.id, choice) {
+                    Ok(_) if choice.is_some() => {
+                        self.set_status(trf("inheritance.extended", &[&node.name, &parent_name(&self.project, choice)]));
+                    }
+                    Ok(_) => self.set_status(trf("inheritance.detached", &[&node.name])),
+                    Err(e) => self.set_error(trf("inheritance.rejected", &[&e])),
+                }
+            }
+
+            ui.label(tr("inheritance.mixins"));
+            let mut toggled = None;
+            for mixin in self.project.mixins() {
+                let mut included = node.mixins.contains(&mixin.name);
+                let response = ui.checkbox(&mut included, &mixin.name);
+                let response = match &mixin.description {
+                    Some(description) => response.on_hover_text(description),
+                    None => response,
+                };
+                if response.changed() {
+                    toggled = Some((mixin.name, included));
+                }
+            }
+            if let Some((name, included)) = toggled {
+                self.save_undo_state(&format!("Toggle mixin {}", name));
+                if let Some(n) = self.project.get_node_mut(node.id) {
+                    n.mixins.retain(|m| *m != name);
+                    if included {
+                        n.mixins.push(name.clone());
+                    }
+                }
+                self.record_macro_step(if included { MacroStep::IncludeMixin { name } } else { MacroStep::ExcludeMixin { name } });
+            }
+
+            let inherited: Vec<String> = self
+                .project
+                .effective_fields(node.id)
+                .into_iter()
+                .filter(|f| !node.fields.iter().any(|own| own.name == f.name))
+                .map(|f| f.name)
+                .collect();
+            if !inherited.is_empty() {
+                ui.weak(trf("inheritance.inherited", &[&inherited.join(", ")]));
+            }
+        });
+    }
+
+    /// Cache an endpoint serves its responses from
+    fn render_caching(&mut self, ui: &mut egui::Ui, node: &Node) {
+        egui::CollapsingHeader::new(tr("caching.title")).show(ui, |ui| {
+            let current = self.project.cache_for(node.id).map(|(cache, policy)| (cache.id, policy.clone()));
+            let cache_name = |project: &ProjectGraph, id: Option<NodeId>| {
+                id.and_then(|id| project.get_node(id))
+                    .map_or(tr("caching.none").to_string(), |n| n.name.clone())
+            };
+            let cache = current.as_ref().map(|(id, _)| *id);
+            let mut choice = cache;
+            ui.horizontal(|ui| {
+                ui.label(tr("caching.cache"));
+                egui::ComboBox::from_id_salt(("endpoint_cache", node.id))
+                    .selected_text(cache_name(&self.project, cache))
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(&mut choice, None, tr("caching.none"));
+                        for other in self.project.find_nodes_by_type("storage.cache") {
+                            ui.selectable_value(&mut choice, Some(other.id), &other.name);
+                        }
+                    });
+            });
+            if choice != cache {
+                self.save_undo_state("Change endpoint cache");
+                // Moving to another cache keeps the policy
+                let policy = current.map(|(_, policy)| policy).unwrap_or_default();
+                match self.project.set_cache(node.id, choice.map(|id| (id, policy))) {
+                    Ok(_) if choice.is_some() => {
+                        self.set_status(trf("caching.set", &[&node.name, &cache_name(&self.project, choice)]));
+                    }
+                    Ok(_) => self.set_status(trf("caching.cleared", &[&node.name])),
+                    Err(e) => self.set_error(trf("caching.rejected", &[&e])),
+                }
+            }
+
+            let edge = self
+                .project
+                .edges()
+                .find(|e| e.is_caching() && e.from_node == node.id)
+                .and_then(|e| Some((e.id, e.cache.clone()?)));
+            if let Some((edge_id, policy)) = edge {
+                self.render_cache_policy(ui, edge_id, policy);
+            }
+        });
+    }
+
+    /// Feature flag an API node sits behind
+    fn render_flag_gate(&mut self, ui: &mut egui::Ui, node: &Node) {
+        egui::CollapsingHeader::new(tr("flags.title")).show(ui, |ui| {
+            let current = self
+                .project
+                .edges()
+                .find(|e| e.is_gate() && e.to_node == node.id)
+                .and_then(|e| Some((e.from_node, e.flag.clone()?)));
+            let gate_name = |project: &ProjectGraph, gate: &Option<(NodeId, String)>| match gate {
+                Some((id, flag)) => project.get_node(*id).map_or(flag.clone(), |n| format!("{} › {}", n.name, flag)),
+                None => tr("flags.none").to_string(),
+            };
+            let mut choice = current.clone();
+            ui.horizontal(|ui| {
+                ui.label(tr("flags.flag"));
+                egui::ComboBox::from_id_salt(("flag_gate", node.id))
+                    .selected_text(gate_name(&self.project, &current))
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(&mut choice, None, tr("flags.none"));
+                        for flags in self.project.find_nodes_by_type("logic.feature_flag") {
+                            for flag in flags.feature_flags() {
+                                let gate = Some((flags.id, flag.name));
+                                let text = gate_name(&self.project, &gate);
+                                ui.selectable_value(&mut choice, gate, text);
+                            }
+                        }
+                    });
+            });
+            if choice != current {
+                self.save_undo_state("Change feature flag");
+                match self.project.set_flag_gate(node.id, choice.clone()) {
+                    Ok(_) if choice.is_some() => {
+                        self.set_status(trf("flags.set", &[&node.name, &gate_name(&self.project, &choice)]));
+                    }
+                    Ok(_) => self.set_status(trf("flags.cleared", &[&node.name])),
+                    Err(e) => self.set_error(trf("flags.rejected", &[&e])),
+                }
+            }
+
+            // Without a gating edge the `feature_flag` config can name one
+            if choice.is_none() {
+                if let Some((_, flag)) = self.project.flag_gate(node.id) {
+                    ui.label(trf("flags.from_config", &[&flag]));
+                }
+            }
+        });
+    }
+
+    /// Create an entity, and the related entities nested in it, from a
+    /// pasted JSON payload
+    fn paste_json_entity(&mut self, json: &str, position: imortal_ir::Position) {
+        let entity = match imortal_ir::infer_entity(json, "Entity") {
+            Ok(entity) => entity,
+            Err(e) => {
+                self.set_error(trf("paste.json_failed", &[&e]));
+                return;
+            }
+        };
+        self.save_undo_state("Paste JSON entity");
+        let added = match self.project.add_inferred_entity(&entity, position) {
+            Ok(added) => added,
+            Err(e) => {
+                self.set_error(trf("paste.json_failed", &[&e]));
+                return;
+            }
+        };
+        self.project.clear_selection();
+        for &id in &added {
+            if let Some(node) = self.project.get_node_mut(id) {
+                node.namespace = self.state.namespace.clone();
+            }
+            self.project.select_node(id);
+            self.node_added(id);
+        }
+        let name = self.project.get_node(added[0]).map(|n| n.name.clone()).unwrap_or_default();
+        self.set_status(trf("paste.json_created", &[&name, &(added.len() - 1)]));
+    }
+
+    fn open_field_import(&mut self, node: NodeId) {
+        self.field_import = Some(FieldImport { node, sample: String::new(), rows: Vec::new(), error: None });
+    }
+
+    /// Dialog inferring fields from a pasted sample, reviewed before they're
+    /// appended to the entity
+    fn render_field_import(&mut self, ctx: &egui::Context) {
+        let Some(import) = self.field_import.as_mut() else {
+            return;
+        };
+        let Some(node) = self.project.get_node(import.node) else {
+            self.field_import = None;
+            return;
+        };
+        let existing: HashSet<String> = node.fields.iter().map(|f| f.name.clone()).collect();
+        let entity_name = node.name.clone();
+
+        let mut apply = false;
+        let mut cancel = false;
+        egui::Window::new(trf("field_import.title", &[&entity_name]))
+            .collapsible(false)
+            .resizable(true)
+            .default_width(520.0)
+            .show(ctx, |ui| {
+                ui.label(tr("field_import.description"));
+                ui.add(
+                    egui::TextEdit::multiline(&mut import.sample)
+                        .code_editor()
+                        .desired_rows(6)
+                        .desired_width(f32::INFINITY)
+                        .hint_text("{\"name\": \"Ada\", \"age\": 36}"),
+                );
+                if ui.button(tr("field_import.infer")).clicked() {
+                    match imortal_ir::infer_fields(&import.sample) {
+                        Ok(fields) => {
+                            import.rows = fields.into_iter().map(|f| (!existing.contains(&f.name), f)).collect();
+                            import.error = None;
+                        }
+                        Err(e) => {
+                            import.rows.clear();
+                            import.error = Some(e.to_string());
+                        }
+                    }
+                }
+                if let Some(error) = &import.error {
+                    ui.colored_label(ui.visuals().error_fg_color, error);
+                }
+
+                if !import.rows.is_empty() {
+                    ui.separator();
+                    egui::ScrollArea::vertical().max_height(300.0).show(ui, |ui| {
+                        egui::Grid::new("field_import_rows").num_columns(5).striped(true).show(ui, |ui| {
+                            ui.label("");
+                            ui.strong(tr("field_import.name"));
+                            ui.strong(tr("field_import.type"));
+                            ui.strong(tr("field_import.required"));
+                            ui.strong(tr("field_import.sample"));
+                            ui.end_row();
+                            for (idx, (include, field)) in import.rows.iter_mut().enumerate() {
+                                let taken = existing.contains(&field.name);
+                                if taken {
+                                    *include = false;
+                                }
+                                ui.add_enabled(!taken, egui::Checkbox::without_text(include));
+                                let name = ui.text_edit_singleline(&mut field.name);
+                                if taken {
+                                    name.on_hover_text(tr("field_import.taken"));
+                                }
+                                egui::ComboBox::from_id_salt(("field_import_type", idx))
+                                    .selected_text(Self::data_type_display(&field.data_type))
+                                    .show_ui(ui, |ui| {
+                                        for (type_idx, type_name) in Self::data_type_options().iter().enumerate() {
+                                            let data_type = Self::index_to_data_type(type_idx);
+                                            ui.selectable_value(&mut field.data_type, data_type, *type_name);
+                                        }
+                                        // Inferred from samples, though not offered when adding a field by hand
+                                        ui.selectable_value(&mut field.data_type, DataType::Uuid, "Uuid");
+                                    });
+                                ui.checkbox(&mut field.required, "");
+                                ui.weak(truncate_sample(&field.sample));
+                                ui.end_row();
+                            }
+                        });
+                    });
+                }
+
+                let count = import.rows.iter().filter(|(include, _)| *include).count();
+                ui.horizontal(|ui| {
+                    if ui.add_enabled(count > 0, egui::Button::new(trf("field_import.apply", &[&count]))).clicked() {
+                        apply = true;
+                    }
+                    if ui.button(tr("dialog.cancel")).clicked() {
+                        cancel = true;
+                    }
+                });
+            });
+
+        if apply {
+            let Some(import) = self.field_import.take() else {
+                return;
+            };
+            let mut fields: Vec<Field> = Vec::new();
+            for (_, inferred) in import.rows.iter().filter(|(include, _)| *include) {
+                let name = inferred.name.trim();
+                if !name.is_empty() && !existing.contains(name) && !fields.iter().any(|f| f.name == name) {
+                    fields.push(InferredField { name: name.to_string(), ..inferred.clone() }.to_field());
+                }
+            }
+            let count = fields.len();
+            self.save_undo_state("Import fields");
+            if let Some(n) = self.project.get_node_mut(import.node) {
+                n.fields.extend(fields.iter().cloned());
+            }
+            for field in fields {
+                self.record_macro_step(MacroStep::AddField { field: Box::new(field) });
+            }
+            self.set_status(trf("field_import.done", &[&count, &entity_name]));
+        } else if cancel {
+            self.field_import = None;
+        }
+    }
+
+    /// Every constraint on an entity's fields, with a button to edit each field
+    /// Advanced tab of the properties panel: hints changing the code
+    /// generated for the node, applied when a field loses focus
+    fn render_codegen_hints(&mut self, ui: &mut egui::Ui, node: &Node) {
+        let hints = &node.codegen;
+        let [mut derives, mut table_name, mut middleware] = match &self.hints_edit {
+            Some((id, text)) if *id == node.id => text.clone(),
+            _ => [hints.derives.join(", "), hints.table_name.clone().unwrap_or_default(), hints.middleware.join("\n")],
+        };
+        let mut skip = hints.skip;
+        let (mut changed, mut commit) = (false, false);
+
+        ui.small(tr("codegen_hints.hint"));
+        ui.add_space(4.0);
+        commit |= ui.checkbox(&mut skip, tr("codegen_hints.skip")).on_hover_text(tr("codegen_hints.skip_hint")).changed();
+        if node.component_type == "data.entity" {
+            ui.label(tr("codegen_hints.derives"));
+            let response = ui.add(egui::TextEdit::singleline(&mut derives).hint_text("PartialEq, sqlx::FromRow"));
+            changed |= response.changed();
+            commit |= response.lost_focus();
+
+            ui.label(tr("codegen_hints.table_name"));
+            let default_table = imortal_codegen::rust::to_snake_case(&node.name);
+            let response = ui.add(egui::TextEdit::singleline(&mut table_name).hint_text(default_table));
+            changed |= response.changed();
+            commit |= response.lost_focus();
+        }
+        if node.component_type == "api.rest" {
+            ui.label(tr("codegen_hints.middleware"));
+            let response = ui.add(
+                egui::TextEdit::multiline(&mut middleware)
+                    .desired_rows(3)
+                    .hint_text("crate::middleware::require_admin"),
+            );
+            changed |= response.changed();
+            commit |= response.lost_focus();
+        }
+
+        if changed {
+            self.hints_edit = Some((node.id, [derives.clone(), table_name.clone(), middleware.clone()]));
+        }
+        if commit {
+            self.hints_edit = None;
+            let list = |text: &str| text.split([',', '\n']).map(str::trim).filter(|s| !s.is_empty()).map(str::to_string).collect();
+            let edited = imortal_ir::CodegenHints {
+                derives: list(&derives),
+                table_name: Some(table_name.trim().to_string()).filter(|name| !name.is_empty()),
+                middleware: list(&middleware),
+                skip,
+            };
+            if edited != node.codegen {
+                self.save_undo_state("Edit codegen hints");
+                if let Some(n) = self.project.get_node_mut(node.id) {
+                    n.codegen = edited;
+                }
+            }
+        }
+    }
+
+    /// Code editor of a custom code node, between the signature and closing
+    /// brace of the function it becomes, with where it fails to parse
+    fn render_custom_code(&mut self, ui: &mut egui::Ui, node: &Node) {
+        egui::CollapsingHeader::new(tr("custom_code.title")).default_open(true).show(ui, |ui| {
+            let mut code = match &self.code_edit {
+                Some((id, code)) if *id == node.id => code.clone(),
+                _ => node.custom_code().unwrap_or_default().to_string(),
+            };
+            let signature = format!("{} {{", imortal_codegen::rust::custom_code_signature(node));
+            ui.label(egui::RichText::new(signature).monospace().weak());
+            let response = ui.add(
+                egui::TextEdit::multiline(&mut code)
+                    .code_editor()
+                    .desired_rows(8)
+                    .desired_width(f32::INFINITY),
+            );
+            ui.label(egui::RichText::new("}").monospace().weak());
+
+            match imortal_ir::parse_rust_snippet(&code) {
+                Ok(()) => ui.small(tr("custom_code.parses")),
+                Err(e) => ui.colored_label(ui.visuals().error_fg_color, trf("custom_code.error", &[&e.line, &e.message])),
+            };
+            ui.small(tr("custom_code.hint"));
+
+            if response.changed() {
+                self.code_edit = Some((node.id, code.clone()));
+            }
+            if response.lost_focus() && self.code_edit.take().is_some() && Some(code.as_str()) != node.custom_code() {
+                self.save_undo_state("Edit custom code");
+                if let Some(n) = self.project.get_node_mut(node.id) {
+                    n.set_config("code", code.clone());
+                }
+                if let Some(recording) = &mut self.macro_recording {
+                    recording.record(MacroStep::SetConfig { key: "code".to_string(), value: code.into() });
+                }
+            }
+        });
+    }
+
+    fn render_validation_summary(&mut self, ui: &mut egui::Ui, node: &Node) {
+        let mut any = false;
+        for field in &node.fields {
+            let lines = validations::field_summary(field);
+            if lines.is_empty() {
+                continue;
+            }
+            any = true;
+            ui.horizontal(|ui| {
+                ui.strong(&field.name);
+                for badge in ValidationBadge::for_field(field) {
+                    ui.label(egui::RichText::new(badge.label()).color(badge.color()));
+                }
+                if ui.small_button("✏").on_hover_text(tr("validation.edit")).clicked() {
+                    self.validation_editor = Some((node.id, field.id));
+                }
+            });
+            for line in lines {
+                ui.label(format!("  • {}", line));
+            }
+        }
+        if !any {
+            ui.weak(tr("validation.none"));
+        }
+    }
+
+    /// Window editing one entity field's validations and unique constraint
+    fn render_validation_editor(&mut self, ctx: &egui::Context) {
+        let Some((node_id, field_id)) = self.validation_editor else {
+            return;
+        };
+        let Some(field) = self.project.get_node(node_id).and_then(|n| n.fields.iter().find(|f| f.id == field_id)).cloned() else {
+            // Field or entity deleted while the editor was open
+            self.validation_editor = None;
+            return;
+        };
+
+        // Project types count as numeric when stored as numbers
+        let storage = imortal_ir::project_type_name(&field.data_type)
+            .and_then(|name| self.project.custom_type(name))
+            .map_or(&field.data_type, |custom| &custom.underlying);
+        let numeric = Unit::applies_to(storage);
+
+        let mut edited = field.clone();
+        let mut started_edit = false;
+        let mut open = true;
+        egui::Window::new(trf("validation.editor_title", &[&field.name]))
+            .id(egui::Id::new("validation_editor"))
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                started_edit |= ui.checkbox(&mut edited.required, tr("validation.required")).changed();
+                let mut unique = edited.is_unique();
+                if ui.checkbox(&mut unique, tr("validation.unique")).changed() {
+                    started_edit = true;
+                    if unique {
+                        edited.constraints.push(FieldConstraint::Unique);
+                    } else {
+                        edited.constraints.retain(|c| *c != FieldConstraint::Unique);
+                    }
+                }
+                if numeric || edited.unit.is_some() {
+                    self.render_unit_picker(ui, field_id, &mut edited, &mut started_edit);
+                }
+                ui.separator();
+
+                // One undo step per drag or text edit, not per frame of it
+                let mut remove = None;
+                egui::Grid::new(("validations", field_id)).num_columns(3).show(ui, |ui| {
+                    for (idx, validation) in edited.validations.iter_mut().enumerate() {
+                        ui.label(validations::kind_name(validation));
+                        match validation {
+                            Validation::MinLength(n) | Validation::MaxLength(n) => {
+                                let r = ui.add(egui::DragValue::new(n));
+                                started_edit |= r.drag_started() || (r.changed() && !r.dragged());
+                            }
+                            Validation::Min(n) | Validation::Max(n) => {
+                                let r = ui.add(egui::DragValue::new(n));
+                                started_edit |= r.drag_started() || (r.changed() && !r.dragged());
+                            }
+                            Validation::Pattern(pattern) => {
+                                started_edit |= ui.text_edit_singleline(pattern).gained_focus();
+                            }
+                            Validation::Custom { name, message } => {
+                                ui.vertical(|ui| {
+                                    started_edit |= ui.add(egui::TextEdit::singleline(name).hint_text("validator")).gained_focus();
+                                    started_edit |= ui.add(egui::TextEdit::singleline(message).hint_text(tr("validation.message"))).gained_focus();
+                                });
+                            }
+                            Validation::Required | Validation::Email | Validation::Url | Validation::Uuid => {
+                                ui.label("");
+                            }
+                        }
+                        if ui.small_button("🗑").clicked() {
+                            remove = Some(idx);
+                        }
+                        ui.end_row();
+                    }
+                });
+                if let Some(idx) = remove {
+                    edited.validations.remove(idx);
+                    started_edit = true;
+                }
+                if edited.validations.is_empty() {
+                    ui.weak(tr("validation.none"));
+                }
+
+                ui.menu_button(tr("validation.add"), |ui| {
+                    for template in validations::templates() {
+                        if ui.button(validations::kind_name(&template)).clicked() {
+                            edited.validations.push(template);
+                            started_edit = true;
+                            ui.close_menu();
+                        }
+                    }
+                });
+            });
+        if !open {
+            self.validation_editor = None;
+        }
+
+        if edited != field {
+            if started_edit {
+                self.save_undo_state("Edit validations");
+            }
+            if let Some(f) = self.project.get_node_mut(node_id).and_then(|n| n.fields.iter_mut().find(|f| f.id == field_id)) {
+                *f = edited;
+            }
+        }
+    }
+
+    /// Unit of a numeric field, with the range it implies
+    fn render_unit_picker(&self, ui: &mut egui::Ui, field_id: uuid::Uuid, edited: &mut Field, started_edit: &mut bool) {
+        ui.horizontal(|ui| {
+            ui.label(tr("units.unit"));
+            let selected = edited.unit.as_ref().map_or(tr("units.none"), validations::unit_name);
+            egui::ComboBox::from_id_salt(("field_unit", field_id))
+                .selected_text(selected)
+                .show_ui(ui, |ui| {
+                    if ui.selectable_label(edited.unit.is_none(), tr("units.none")).clicked() && edited.unit.is_some() {
+                        edited.unit = None;
+                        *started_edit = true;
+                    }
+                    for unit in Unit::all() {
+                        let current = edited.unit.as_ref().is_some_and(|u| u.same_kind(&unit));
+                        if ui.selectable_label(current, validations::unit_name(&unit)).clicked() && !current {
+                            edited.unit = Some(unit);
+                            *started_edit = true;
+                        }
+                    }
+                });
+            if let Some(Unit::Currency(code)) = &mut edited.unit {
+                let response = ui.add(egui::TextEdit::singleline(code).desired_width(40.0).hint_text("USD"));
+                *started_edit |= response.gained_focus();
+                code.make_ascii_uppercase();
+            }
+        });
+
+        if let Some(Unit::Currency(code)) = &edited.unit {
+            if !Unit::is_valid_currency(code) {
+                ui.colored_label(ui.visuals().error_fg_color, tr("units.currency_invalid"));
+            }
+        }
+        for validation in edited.unit_validations() {
+            ui.small(trf("units.implies", &[&validation.error_message()]));
+        }
+    }
+
+    /// TTL and key template of a caching edge
+    fn render_cache_policy(&mut self, ui: &mut egui::Ui, edge_id: EdgeId, policy: CachePolicy) {
+        let mut edited = policy.clone();
+        let mut started_edit = false;
+        egui::Grid::new(("cache_policy", edge_id)).num_columns(2).show(ui, |ui| {
+            // One undo step per drag or text edit, not per frame of it
+            ui.label(tr("caching.ttl"));
+            let ttl = ui.add(egui::DragValue::new(&mut edited.ttl_seconds).range(1..=u64::MAX));
+            started_edit |= ttl.drag_started() || (ttl.changed() && !ttl.dragged());
+            ui.end_row();
+
+            ui.label(tr("caching.key"));
+            let key = ui.text_edit_singleline(&mut edited.key_template).on_hover_text(tr("caching.key_hint"));
+            started_edit |= key.gained_focus();
+            ui.end_row();
+        });
+        if let Err(problem) = edited.placeholders() {
+            ui.colored_label(ui.visuals().error_fg_color, trf("caching.key_malformed", &[&problem]));
+        }
+
+        if edited != policy {
+            if started_edit {
+                self.save_undo_state("Edit cache policy");
+            }
+            if let Some(e) = self.project.edges.get_mut(&edge_id) {
+                e.cache = Some(edited);
+            }
+        }
+    }
+
+    /// Polymorphic relationships of an entity, each a set of entities it
+    /// can belong to
+    fn render_polymorphic(&mut self, ui: &mut egui::Ui, node: &Node) {
+        egui::CollapsingHeader::new(tr("polymorphic.title")).show(ui, |ui| {
+            let mut names: Vec<String> =
+                self.project.polymorphic_associations(node.id).into_iter().map(|a| a.name).collect();
+            ui.horizontal(|ui| {
+                ui.label(tr("polymorphic.new"));
+                ui.add(egui::TextEdit::singleline(&mut self.new_association).hint_text("commentable"));
+            });
+            let pending = self.new_association.trim().to_string();
+            if !pending.is_empty() && !names.contains(&pending) {
+                names.push(pending.clone());
+            }
+
+            let mut entities: Vec<(NodeId, String)> = self
+                .project
+                .find_nodes_by_type("data.entity")
+                .into_iter()
+                .filter(|n| n.id != node.id)
+                .map(|n| (n.id, n.name.clone()))
+                .collect();
+            entities.sort_by(|a, b| a.1.cmp(&b.1));
+            let mut change = None;
+            for name in names {
+                let targets = self
+                    .project
+                    .polymorphic_associations(node.id)
+                    .into_iter()
+                    .find(|a| a.name == name)
+                    .map(|a| a.targets)
+                    .unwrap_or_default();
+                ui.label(egui::RichText::new(trf("polymorphic.belongs_to", &[&name])).strong());
+                ui.horizontal_wrapped(|ui| {
+                    for (id, entity) in &entities {
+                        let mut included = targets.contains(id);
+                        if ui.checkbox(&mut included, entity).changed() {
+                            let mut targets = targets.clone();
+                            targets.retain(|t| t != id);
+                            if included {
+                                targets.push(*id);
+                            }
+                            change = Some((name.clone(), targets));
+                        }
+                    }
+                });
+            }
+
+            if let Some((name, targets)) = change {
+                self.save_undo_state("Change polymorphic targets");
+                match self.project.set_polymorphic_targets(node.id, &name, &targets) {
+                    Ok(()) => {
+                        if name == pending {
+                            self.new_association.clear();
+                        }
+                        self.set_status(trf("polymorphic.updated", &[&name, &targets.len()]));
+                    }
+                    Err(e) => self.set_error(trf("polymorphic.rejected", &[&e])),
+                }
+            }
+        });
+    }
+
+    /// Template parameters of a composite, and pinning it to the palette
+    fn render_template_parameters(&mut self, ui: &mut egui::Ui, node: &Node) {
+        let parameters = node.subgraph.as_ref().map(|sub| sub.parameters.clone()).unwrap_or_default();
+        egui::CollapsingHeader::new(tr("template.parameters"))
+            .default_open(true)
+            .show(ui, |ui| {
+                ui.weak(tr("template.hint"));
+                let mut remove = None;
+                for (index, parameter) in parameters.iter().enumerate() {
+                    ui.horizontal(|ui| {
+                        ui.monospace(parameter.placeholder());
+                        ui.label(&parameter.label);
+                        if !parameter.default.is_empty() {
+                            ui.weak(format!("= {}", parameter.default));
+                        }
+                        if ui.small_button("🗑").on_hover_text(tr("template.remove_parameter")).clicked() {
+                            remove = Some(index);
+                        }
+                    });
+                }
+                if let Some(index) = remove {
+                    self.save_undo_state("Remove template parameter");
+                    if let Some(sub) = self.project.get_node_mut(node.id).and_then(|n| n.subgraph.as_mut()) {
+                        sub.parameters.remove(index);
+                    }
+                }
+
+                ui.separator();
+                egui::Grid::new("new_parameter").num_columns(2).show(ui, |ui| {
+                    ui.label(tr("template.name"));
+                    ui.text_edit_singleline(&mut self.new_parameter.name);
+                    ui.end_row();
+                    ui.label(tr("template.label"));
+                    ui.text_edit_singleline(&mut self.new_parameter.label);
+                    ui.end_row();
+                    ui.label(tr("template.default"));
+                    ui.text_edit_singleline(&mut self.new_parameter.default);
+                    ui.end_row();
+                });
+                let name = self.new_parameter.name.trim().to_string();
+                let valid = !name.is_empty() && !parameters.iter().any(|p| p.name == name);
+                if ui.add_enabled(valid, egui::Button::new(tr("template.add_parameter"))).clicked() {
+                    let mut parameter = std::mem::replace(&mut self.new_parameter, TemplateParameter::new("", ""));
+                    if parameter.label.trim().is_empty() {
+                        parameter.label = name.clone();
+                    }
+                    parameter.name = name;
+                    self.save_undo_state("Add template parameter");
+                    if let Some(sub) = self.project.get_node_mut(node.id).and_then(|n| n.subgraph.as_mut()) {
+                        sub.parameters.push(parameter);
+                    }
+                }
+
+                ui.separator();
+                if ui.button(tr("template.pin")).clicked() {
+                    match self.edit_root_project("Pin template", |project| project.pin_template(node.clone())) {
+                        Ok(()) => self.set_status(trf("template.pinned", &[&node.name])),
+                        Err(e) => self.set_error(trf("composite.failed", &[&e])),
+                    }
+                }
+            });
+    }
+
+    /// Add an instance of a pinned template, asking for its parameters first
+    fn use_template(&mut self, index: usize) {
+        let Some(template) = self.root_project().templates.get(index) else {
+            return;
+        };
+        let defaults: Vec<String> = template.subgraph.as_ref()
+            .map(|sub| sub.parameters.iter().map(|p| p.default.clone()).collect())
+            .unwrap_or_default();
+        if defaults.is_empty() {
+            self.add_template_instance(index, HashMap::new());
+        } else {
+            self.template_prompt = Some((index, defaults));
+        }
+    }
+
+    /// Add a template instance with its parameters filled in
+    fn add_template_instance(&mut self, index: usize, values: HashMap<String, String>) {
+        let Some(template) = self.root_project().templates.get(index) else {
+            return;
+        };
+        let mut node = template.instantiate_template(&values);
+        node.namespace = self.state.namespace.clone();
+        node.position = self.placement.place(&self.project, &node);
+        let name = node.name.clone();
+
+        self.save_undo_state(&format!("Add {}", name));
+        self.project.clear_selection();
+        let node_id = self.project.add_node(node);
+        self.project.select_node(node_id);
+        self.node_added(node_id);
+        self.set_status(format!("Added {}", name));
+    }
+
+    /// Ask for the parameter values of a template being added
+    fn render_template_prompt(&mut self, ctx: &egui::Context) {
+        let Some((index, values)) = &mut self.template_prompt else {
+            return;
+        };
+        let root = self.composite_stack.first().map_or(&self.project, |frame| &frame.project);
+        let Some(template) = root.templates.get(*index) else {
+            self.template_prompt = None;
+            return;
+        };
+        let parameters = template.subgraph.as_ref().map(|sub| sub.parameters.as_slice()).unwrap_or_default();
+
+        let mut add = false;
+        let mut cancel = false;
+        egui::Window::new(trf("template.prompt_title", &[&template.name]))
+            .id(egui::Id::new("template_prompt"))
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                egui::Grid::new("template_values").num_columns(2).show(ui, |ui| {
+                    for (parameter, value) in parameters.iter().zip(values.iter_mut()) {
+                        ui.label(&parameter.label).on_hover_text(parameter.placeholder());
+                        ui.text_edit_singleline(value);
+                        ui.end_row();
+                    }
+                });
+                ui.add_space(8.0);
+                ui.horizontal(|ui| {
+                    add = ui.button(tr("template.add")).clicked();
+                    cancel = ui.button(tr("dialog.cancel")).clicked();
+                });
+            });
+
+        if add {
+            let names: Vec<String> = parameters.iter().map(|p| p.name.clone()).collect();
+            if let Some((index, values)) = self.template_prompt.take() {
+                self.add_template_instance(index, names.into_iter().zip(values).collect());
+            }
+        } else if cancel {
+            self.template_prompt = None;
+        }
+    }
+
+    // Workspaces
+
+    fn open_workspace(&mut self) {
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("Immortal Workspace", &[imortal_ir::WORKSPACE_EXTENSION])
+            .pick_file()
+        else {
+            return;
+        };
+
+        let loaded = Workspace::load(&path).and_then(|ws| ws.load_projects(&path).map(|projects| (ws, projects)));
+        match loaded {
+            Ok((workspace, projects)) => {
+                let count = projects.len();
+                for (_, project_path, project) in projects {
+                    let (lock, read_only) = Self::lock_or_read_only(&project_path);
+                    self.open_tab(project, Some(project_path));
+                    self.project_lock = lock;
+                    self.read_only = read_only;
+                }
+                self.set_status(format!("Opened workspace '{}' with {} project(s)", workspace.name, count));
+                self.workspace = Some((workspace, path));
+                self.check_references();
+            }
+            Err(e) => self.set_error(format!("Failed to open workspace: {}", e)),
+        }
+    }
+
+    /// Save the open tabs as a workspace, keeping project names from the current workspace
+    fn save_workspace(&mut self) {
+        let default_name = self.workspace.as_ref().map(|(ws, _)| ws.name.clone()).unwrap_or_else(|| "workspace".to_string());
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("Immortal Workspace", &[imortal_ir::WORKSPACE_EXTENSION])
+            .set_file_name(format!("{}.{}", default_name, imortal_ir::WORKSPACE_EXTENSION))
+            .save_file()
+        else {
+            return;
+        };
+
+        let base = path.parent().map(|p| p.to_path_buf()).unwrap_or_default();
+        let name = path.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or(default_name);
+        let mut workspace = Workspace::new(name);
+        let mut unsaved = 0;
+        for index in 0..self.tabs.len() {
+            let Some(project_path) = self.tab_path(index) else {
+                unsaved += 1;
+                continue;
+            };
+            let project_name = self.workspace_project_name(&project_path)
+                .unwrap_or_else(|| self.tab_project(index).meta.name.clone());
+            let relative = project_path.strip_prefix(&base).map(|p| p.to_path_buf()).unwrap_or(project_path);
+            workspace.add_project(project_name, relative);
+        }
+
+        match workspace.save(&path) {
+            Ok(_) if unsaved > 0 => self.set_status(format!("Workspace saved; {} unsaved project(s) left out", unsaved)),
+            Ok(_) => self.set_status("Workspace saved"),
+            Err(e) => self.set_error(format!("Failed to save workspace: {}", e)),
+        }
+        self.workspace = Some((workspace, path));
+    }
+
+    /// Name of the workspace project stored at `path`, if any
+    fn workspace_project_name(&self, path: &std::path::Path) -> Option<String> {
+        let (workspace, file) = self.workspace.as_ref()?;
+        let base = file.parent().unwrap_or(std::path::Path::new(""));
+        workspace.projects.iter().find(|p| base.join(&p.path) == path).map(|p| p.name.clone())
+    }
+
+    /// Validate reference nodes across all open workspace projects
+    fn check_references(&mut self) {
+        let Some((workspace, _)) = &self.workspace else {
+            return;
+        };
+
+        let projects: Vec<(String, &ProjectGraph)> = (0..self.tabs.len())
+            .filter_map(|index| {
+                let name = self.workspace_project_name(&self.tab_path(index)?)?;
+                Some((name, self.tab_project(index)))
+            })
+            .collect();
+        let projects: Vec<(&str, &ProjectGraph)> = projects.iter().map(|(name, graph)| (name.as_str(), *graph)).collect();
+        let errors = workspace.validate_references(&projects);
+
+        if errors.is_empty() {
+            self.reference_report = None;
+            self.set_status("✅ All workspace references resolve");
+        } else {
+            self.set_error(trf("references.broken", &[&errors.len()]));
+            self.reference_report = Some(errors);
+        }
+    }
+
+    /// List broken workspace references; clicking one shows its node
+    fn render_reference_report(&mut self, ctx: &egui::Context) {
+        let Some(errors) = &self.reference_report else {
+            return;
+        };
+
+        let mut open = true;
+        let mut reveal = None;
+        egui::Window::new(tr("references.title"))
+            .open(&mut open)
+            .resizable(true)
+            .default_width(420.0)
+            .show(ctx, |ui| {
+                for error in errors {
+                    if ui.selectable_label(false, format!("❌ {}", error.message)).clicked() {
+                        reveal = error.node_id;
+                    }
+                }
+            });
+
+        if let Some(node_id) = reveal {
+            if let Some(index) = (0..self.tabs.len()).find(|i| self.tab_project(*i).get_node(node_id).is_some()) {
+                self.switch_tab(index);
+                self.project.clear_selection();
+                self.project.select_node(node_id);
+            }
+        }
+        if !open {
+            self.reference_report = None;
+        }
+    }
+
+    /// Offer to open a project that failed to load, with repairs
+    fn render_repair_offer(&mut self, ctx: &egui::Context) {
+        let Some((path, error, new_tab)) = &self.repair_offer else {
+            return;
+        };
+
+        let mut repair = false;
+        let mut cancel = false;
+        egui::Window::new(tr("recovery.title"))
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                ui.label(egui::RichText::new(path.display().to_string()).strong());
+                ui.label(egui::RichText::new(error).color(egui::Color32::LIGHT_RED));
+                ui.add_space(8.0);
+                ui.label(tr("recovery.explanation"));
+                ui.add_space(8.0);
+                ui.horizontal(|ui| {
+                    repair = ui.button(tr("recovery.open")).clicked();
+                    cancel = ui.button(tr("recovery.cancel")).clicked();
+                });
+            });
+
+        if repair {
+            let (path, new_tab) = (path.clone(), *new_tab);
+            self.repair_offer = None;
+            self.open_with_repairs(path, new_tab);
+        } else if cancel {
+            self.repair_offer = None;
+        }
+    }
+
+    /// Offer to restore the project rescued when the last run crashed
+    fn render_crash_rescue(&mut self, ctx: &egui::Context) {
+        let Some(rescue) = &self.crash_rescue else {
+            return;
+        };
+
+        let mut restore = false;
+        let mut open_report = false;
+        let mut dismiss = false;
+        egui::Window::new(tr("crash.title"))
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                ui.label(tr("crash.explanation"));
+                ui.label(egui::RichText::new(&rescue.message).color(egui::Color32::LIGHT_RED));
+                ui.add_space(8.0);
+                match &rescue.project_name {
+                    Some(name) if rescue.project.is_some() => {
+                        ui.label(trf("crash.rescued", &[name]));
+                    }
+                    _ => {
+                        ui.label(tr("crash.nothing_rescued"));
+                    }
+                }
+                ui.add_space(8.0);
+                ui.horizontal(|ui| {
+                    if rescue.project.is_some() {
+                        restore = ui.button(tr("crash.restore")).clicked();
+                    }
+                    open_report = ui.button(tr("crash.open_report")).clicked();
+                    dismiss = ui.button(tr("crash.dismiss")).clicked();
+                });
+            });
+
+        if open_report {
+            let report = rescue.report.clone();
+            if let Err(e) = notifications::open_path(&report) {
+                self.set_error(trf("notifications.open_folder_failed", &[&report.display(), &e]));
+            }
+        }
+        if restore {
+            if let Some(rescue) = self.crash_rescue.take() {
+                rescue.dismiss();
+                self.restore_rescued_project(rescue);
+            }
+        } else if dismiss {
+            if let Some(rescue) = self.crash_rescue.take() {
+                rescue.dismiss();
+            }
+        }
+    }
+
+    /// Open a rescued project in place of the one that was open when the
+    /// editor crashed
+    fn restore_rescued_project(&mut self, rescue: CrashRescue) {
+        let Some(rescued) = rescue.project else {
+            return;
+        };
+        match imortal_ir::load_project(&rescued) {
+            Ok(mut project) => {
+                // Unsaved until the user saves over the original
+                project.touch();
+                let status = trf("crash.restored", &[&project.meta.name]);
+                let path = rescue.original_path.unwrap_or(rescued);
+                self.open_loaded_project(project, path, false, status);
+            }
+            Err(e) => self.set_error(format!("Failed to load project: {}", e)),
+        }
+    }
+
+    /// Ask what to do with a project another editor has locked
+    fn render_lock_conflict(&mut self, ctx: &egui::Context) {
+        let Some(conflict) = &self.lock_conflict else {
+            return;
+        };
+
+        #[derive(PartialEq)]
+        enum Choice {
+            ReadOnly,
+            TakeOver,
+            Cancel,
+        }
+        let mut choice = None;
+        egui::Window::new(tr("lock.title"))
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                ui.label(egui::RichText::new(conflict.path.display().to_string()).strong());
+                ui.label(trf("lock.held_by", &[&conflict.holder]));
+                ui.add_space(8.0);
+                ui.label(tr("lock.explanation"));
+                ui.add_space(8.0);
+                ui.horizontal(|ui| {
+                    if ui.button(tr("lock.read_only")).clicked() {
+                        choice = Some(Choice::ReadOnly);
+                    }
+                    if ui.button(tr("lock.take_over")).clicked() {
+                        choice = Some(Choice::TakeOver);
+                    }
+                    if ui.button(tr("lock.cancel")).clicked() {
+                        choice = Some(Choice::Cancel);
+                    }
+                });
+            });
+
+        let Some(choice) = choice else {
+            return;
+        };
+        let Some(conflict) = self.lock_conflict.take() else {
+            return;
+        };
+        let LockConflict { project, path, holder, new_tab, status } = conflict;
+        match choice {
+            Choice::ReadOnly => {
+                let status = format!("{} — {}", status, tr("lock.read_only_badge"));
+                self.show_project(project, path, new_tab, None, Some(holder), status);
+            }
+            Choice::TakeOver => match ProjectLock::take_over(&path) {
+                Ok(lock) => self.show_project(project, path, new_tab, Some(lock), None, status),
+                Err(e) => self.set_error(format!("Failed to lock project: {}", e)),
+            },
+            Choice::Cancel => self.quarantine_report = None,
+        }
+    }
+
+    /// Render the entries quarantined by "Open with Repairs"
+    fn render_quarantine_report(&mut self, ctx: &egui::Context) {
+        let Some(report) = &self.quarantine_report else {
+            return;
+        };
+
+        let mut open = true;
+        let mut reveal = None;
+        egui::Window::new(tr("recovery.report_title"))
+            .open(&mut open)
+            .resizable(true)
+            .default_width(420.0)
+            .show(ctx, |ui| {
+                ui.label(tr("recovery.report_intro"));
+                ui.separator();
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    for item in &report.items {
+                        let response = ui.selectable_label(false, format!("⚠ {}", item));
+                        if let Some(data) = &item.data {
+                            response.clone().on_hover_text(data.to_string());
+                        }
+                        if response.clicked() {
+                            reveal = item.id.parse::<NodeId>().ok();
+                        }
+                    }
+                });
+            });
+
+        if let Some(node_id) = reveal.filter(|id| self.project.get_node(*id).is_some()) {
+            self.project.clear_selection();
+            self.project.select_node(node_id);
+        }
+        if !open {
+            self.quarantine_report = None;
+        }
+    }
+
+    fn save_project(&mut self) {
+        self.leave_composites();
+        if let Some(path) = &self.project_path.clone() {
+            if let Err(e) = self.check_save_lock(path) {
+                self.set_error(trf("lock.save_blocked", &[&e]));
+                return;
+            }
+            match imortal_ir::save_project(&self.project, path, imortal_ir::ProjectFormat::Json) {
+                Ok(_) => self.project_saved(path),
+                Err(e) => self.set_error(format!("Failed to save: {}", e)),
+            }
+        } else {
+            self.save_project_as();
+        }
+    }
+
+    fn save_project_as(&mut self) {
+        self.leave_composites();
+        self.load_all_node_details();
+        if let Some(path) = rfd::FileDialog::new()
+            .add_filter("Immortal Project", &[imortal_ir::PROJECT_EXTENSION])
+            .add_filter("Immortal Project Database", &[imortal_ir::DB_EXTENSION])
+            .set_file_name(format!("{}.imortal", self.project.meta.name))
+            .save_file()
+        {
+            if let Err(e) = self.check_save_lock(&path) {
+                self.set_error(trf("lock.save_blocked", &[&e]));
+                return;
+            }
+            self.project_path = Some(path.clone());
+
+            // Update recent projects
+            self.welcome_screen.add_recent_project(self.project.meta.name.clone(), path.clone());
+
+            match imortal_ir::save_project(&self.project, &path, imortal_ir::ProjectFormat::Json) {
+                Ok(_) => self.project_saved(&path),
+                Err(e) => self.set_error(format!("Failed to save: {}", e)),
+            }
+        }
+    }
+
+    /// Pick a project to import, asking how to handle collisions if any
+    fn pick_import(&mut self) {
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("Immortal Project", &[imortal_ir::PROJECT_EXTENSION, imortal_ir::COMPONENT_EXTENSION])
+            .pick_file()
+        else {
+            return;
+        };
+
+        let name = path.file_name().unwrap_or_default().to_string_lossy().to_string();
+        self.spawn_task(trf("import.loading", &[&name]), move |_| TaskMessage::Imported(imortal_ir::load_project(&path).map(Box::new)));
+    }
+
+    /// Pick a PNG image and add it as a background layer at the top-left
+    /// of the current view
+    fn pick_background_image(&mut self) {
+        let Some(path) = rfd::FileDialog::new().add_filter("PNG", &["png"]).pick_file() else {
+            return;
+        };
+
+        let name = path.file_stem().unwrap_or_default().to_string_lossy().to_string();
+        let viewport = &self.project.viewport;
+        let position = imortal_ir::Position::new(-viewport.pan_x / viewport.zoom, -viewport.pan_y / viewport.zoom);
+        let layer = std::fs::read(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|data| backgrounds::image_layer(name.clone(), data, position).map_err(|e| e.to_string()));
+        match layer {
+            Ok(layer) => {
+                self.save_undo_state("Add background image");
+                self.project.backgrounds.push(layer);
+                self.set_status(trf("backgrounds.image_added", &[&name]));
+            }
+            Err(e) => self.set_error(trf("backgrounds.image_failed", &[&name, &e])),
+        }
+    }
+
+    /// View > Background Layers: add, order, show, and edit the layers drawn
+    /// beneath the canvas content
+    fn render_backgrounds_dialog(&mut self, ctx: &egui::Context) {
+        if !self.backgrounds_open {
+            return;
+        }
+
+        let mut edited = self.project.backgrounds.clone();
+        let mut started_edit = false;
+        let mut add_image = false;
+        let mut open = true;
+        egui::Window::new(tr("backgrounds.title"))
+            .open(&mut open)
+            .resizable(true)
+            .default_size([420.0, 420.0])
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    add_image = ui.button(tr("backgrounds.add_image")).clicked();
+                    ui.menu_button(tr("backgrounds.add_lanes"), |ui| {
+                        for orientation in [LaneOrientation::Horizontal, LaneOrientation::Vertical] {
+                            if ui.button(lane_orientation_name(orientation)).clicked() {
+                                let labels: Vec<String> = (1..=3).map(|n| trf("backgrounds.lane_label", &[&n])).collect();
+                                let labels: Vec<&str> = labels.iter().map(String::as_str).collect();
+                                edited.push(BackgroundLayer::swimlanes(tr("backgrounds.lanes_name"), orientation, &labels));
+                                started_edit = true;
+                                ui.close_menu();
+                            }
+                        }
+                    });
+                });
+                ui.weak(tr("backgrounds.hint"));
+                ui.separator();
+                if edited.is_empty() {
+                    ui.weak(tr("backgrounds.empty"));
+                }
+
+                // One undo step per drag or text edit, not per frame of it
+                let mut remove = None;
+                let mut lower = None;
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    let count = edited.len();
+                    for (idx, layer) in edited.iter_mut().enumerate() {
+                        ui.push_id(layer.id, |ui| {
+                            ui.horizontal(|ui| {
+                                started_edit |= ui.checkbox(&mut layer.visible, "").on_hover_text(tr("backgrounds.visible")).changed();
+                                started_edit |= ui.add(egui::TextEdit::singleline(&mut layer.name).desired_width(160.0)).gained_focus();
+                                if ui.add_enabled(idx > 0, egui::Button::new("⏶").small()).on_hover_text(tr("backgrounds.lower")).clicked() {
+                                    lower = Some(idx);
+                                }
+                                if ui.add_enabled(idx + 1 < count, egui::Button::new("⏷").small()).on_hover_text(tr("backgrounds.raise")).clicked() {
+                                    lower = Some(idx + 1);
+                                }
+                                if ui.small_button("🗑").on_hover_text(tr("backgrounds.remove")).clicked() {
+                                    remove = Some(idx);
+                                }
+                            });
+                            ui.horizontal(|ui| {
+                                ui.label(tr("backgrounds.opacity"));
+                                let r = ui.add(egui::Slider::new(&mut layer.opacity, 0.05..=1.0));
+                                started_edit |= r.drag_started() || (r.changed() && !r.dragged());
+                            });
+                            match &mut layer.kind {
+                                BackgroundKind::Image { position, size, .. } => {
+                                    egui::Grid::new("image").num_columns(3).show(ui, |ui| {
+                                        ui.label(tr("backgrounds.position"));
+                                        for value in [&mut position.x, &mut position.y] {
+                                            let r = ui.add(egui::DragValue::new(value));
+                                            started_edit |= r.drag_started() || (r.changed() && !r.dragged());
+                                        }
+                                        ui.end_row();
+                                        ui.label(tr("backgrounds.size"));
+                                        for value in [&mut size.width, &mut size.height] {
+                                            let r = ui.add(egui::DragValue::new(value).range(1.0..=f32::MAX));
+                                            started_edit |= r.drag_started() || (r.changed() && !r.dragged());
+                                        }
+                                        ui.end_row();
+                                    });
+                                }
+                                BackgroundKind::Swimlanes { orientation, start, lanes } => {
+                                    ui.horizontal(|ui| {
+                                        egui::ComboBox::from_id_salt("orientation")
+                                            .selected_text(lane_orientation_name(*orientation))
+                                            .show_ui(ui, |ui| {
+                                                for option in [LaneOrientation::Horizontal, LaneOrientation::Vertical] {
+                                                    started_edit |= ui.selectable_value(orientation, option, lane_orientation_name(option)).changed();
+                                                }
+                                            });
+                                        ui.label(tr("backgrounds.start"));
+                                        let r = ui.add(egui::DragValue::new(start));
+                                        started_edit |= r.drag_started() || (r.changed() && !r.dragged());
+                                    });
+                                    let mut remove_lane = None;
+                                    egui::Grid::new("lanes").num_columns(4).show(ui, |ui| {
+                                        for (lane_idx, lane) in lanes.iter_mut().enumerate() {
+                                            started_edit |= ui.add(egui::TextEdit::singleline(&mut lane.label).desired_width(140.0)).gained_focus();
+                                            let r = ui.add(egui::DragValue::new(&mut lane.size).range(20.0..=f32::MAX));
+                                            started_edit |= r.drag_started() || (r.changed() && !r.dragged());
+                                            let (red, green, blue) = lane.color.rgb();
+                                            egui::ComboBox::from_id_salt(("lane_color", lane_idx))
+                                                .width(40.0)
+                                                .selected_text(egui::RichText::new("⬤").color(egui::Color32::from_rgb(red, green, blue)))
+                                                .show_ui(ui, |ui| {
+                                                    for color in imortal_ir::group::GroupColor::all() {
+                                                        let (red, green, blue) = color.rgb();
+                                                        let swatch = egui::RichText::new("⬤").color(egui::Color32::from_rgb(red, green, blue));
+                                                        started_edit |= ui.selectable_value(&mut lane.color, *color, swatch).changed();
+                                                    }
+                                                });
+                                            if ui.small_button("🗑").clicked() {
+                                                remove_lane = Some(lane_idx);
+                                            }
+                                            ui.end_row();
+                                        }
+                                    });
+                                    if let Some(lane_idx) = remove_lane {
+                                        lanes.remove(lane_idx);
+                                        started_edit = true;
+                                    }
+                                    if ui.small_button(tr("backgrounds.add_lane")).clicked() {
+                                        let color = lanes.last().map_or(imortal_ir::group::GroupColor::Blue, |lane| match lane.color {
+                                            imortal_ir::group::GroupColor::Blue => imortal_ir::group::GroupColor::Gray,
+                                            _ => imortal_ir::group::GroupColor::Blue,
+                                        });
+                                        let label = trf("backgrounds.lane_label", &[&(lanes.len() + 1)]);
+                                        lanes.push(imortal_ir::Lane { label, size: BackgroundLayer::DEFAULT_LANE_SIZE, color });
+                                        started_edit = true;
+                                    }
+                                }
+                            }
+                        });
+                        ui.separator();
+                    }
+                });
+                if let Some(idx) = remove {
+                    edited.remove(idx);
+                    started_edit = true;
+                }
+                if let Some(idx) = lower {
+                    edited.swap(idx - 1, idx);
+                    started_edit = true;
+                }
+            });
+        self.backgrounds_open = open;
+
+        if edited != self.project.backgrounds {
+            if started_edit {
+                self.save_undo_state("Edit background layers");
+            }
+            self.project.backgrounds = edited;
+        }
+        if add_image {
+            self.pick_background_image();
+        }
+    }
+
+    /// Manage the project's own data types: their names, what they're
+    /// stored as, and how they're serialized and stored in the database
+    fn render_types_dialog(&mut self, ctx: &egui::Context) {
+        if !self.types_open {
+            return;
+        }
+        const UNDERLYING: [DataType; 13] = [
+            DataType::String, DataType::Text, DataType::Int32, DataType::Int64, DataType::Float32,
+            DataType::Float64, DataType::Bool, DataType::Uuid, DataType::DateTime, DataType::Date,
+            DataType::Time, DataType::Bytes, DataType::Json,
+        ];
+
+        let mut edited = self.project.meta.types.clone();
+        let mut started_edit = false;
+        let mut rename = None;
+        let mut open = true;
+        egui::Window::new(tr("types.title"))
+            .open(&mut open)
+            .resizable(true)
+            .default_size([460.0, 380.0])
+            .show(ctx, |ui| {
+                ui.weak(tr("types.hint"));
+                ui.horizontal(|ui| {
+                    ui.add(egui::TextEdit::singleline(&mut self.new_type_name).hint_text("Email").desired_width(160.0));
+                    let name = self.new_type_name.trim().to_string();
+                    let taken = edited.iter().any(|t| t.name == name);
+                    if ui.add_enabled(!name.is_empty() && !taken, egui::Button::new(tr("types.add"))).clicked() {
+                        edited.push(imortal_ir::CustomType::new(name, DataType::String));
+                        self.new_type_name.clear();
+                        started_edit = true;
+                    }
+                });
+                ui.separator();
+                if edited.is_empty() {
+                    ui.weak(tr("types.empty"));
+                }
+
+                let mut remove = None;
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    for (idx, custom) in edited.iter_mut().enumerate() {
+                        ui.push_id(idx, |ui| {
+                            let usages = self.project.custom_type_usages(&custom.name);
+                            ui.horizontal(|ui| {
+                                // Renamed when done typing, with the fields holding the type
+                                let mut name = match &self.type_name_edit {
+                                    Some((edit_idx, name)) if *edit_idx == idx => name.clone(),
+                                    _ => custom.name.clone(),
+                                };
+                                let response = ui.add(egui::TextEdit::singleline(&mut name).desired_width(140.0));
+                                if response.changed() {
+                                    self.type_name_edit = Some((idx, name.clone()));
+                                }
+                                if response.lost_focus() && self.type_name_edit.take().is_some() && name.trim() != custom.name {
+                                    rename = Some((custom.name.clone(), name.trim().to_string()));
+                                }
+                                ui.weak(trf("types.usages", &[&usages]));
+                                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                                    let button = ui.add_enabled(usages == 0, egui::Button::new("🗑").small());
+                                    if button.on_hover_text(tr("types.remove")).on_disabled_hover_text(tr("types.in_use")).clicked() {
+                                        remove = Some(idx);
+                                    }
+                                });
+                            });
+                            egui::Grid::new("type").num_columns(2).show(ui, |ui| {
+                                ui.label(tr("types.underlying"));
+                                egui::ComboBox::from_id_salt("underlying")
+                                    .selected_text(Self::data_type_display(&custom.underlying))
+                                    .show_ui(ui, |ui| {
+                                        for data_type in UNDERLYING {
+                                            let label = Self::data_type_display(&data_type);
+                                            started_edit |= ui.selectable_value(&mut custom.underlying, data_type, label).changed();
+                                        }
+                                    });
+                                ui.end_row();
+                                ui.label(tr("types.representation"));
+                                ui.horizontal(|ui| {
+                                    for representation in imortal_ir::TypeRepresentation::all() {
+                                        started_edit |= ui.radio_value(&mut custom.representation, *representation, representation.label()).changed();
+                                    }
+                                });
+                                ui.end_row();
+
+                                // Empty text means the default mapping
+                                let mut optional = |ui: &mut egui::Ui, label: &str, value: &mut Option<String>, hint: String| {
+                                    ui.label(label);
+                                    let mut text = value.clone().unwrap_or_default();
+                                    let response = ui.add(egui::TextEdit::singleline(&mut text).hint_text(hint).desired_width(200.0));
+                                    started_edit |= response.gained_focus();
+                                    if response.changed() {
+                                        *value = Some(text.trim().to_string()).filter(|t| !t.is_empty());
+                                    }
+                                    ui.end_row();
+                                };
+                                let sql_default = self.project.meta.target_database.as_deref()
+                                    .and_then(DatabaseBackend::from_id)
+                                    .unwrap_or(DatabaseBackend::Postgres)
+                                    .sql_type(&custom.underlying);
+                                optional(ui, tr("types.sql_type"), &mut custom.sql_type, sql_default);
+                                if custom.representation == imortal_ir::TypeRepresentation::Newtype {
+                                    optional(ui, tr("types.serde_with"), &mut custom.serde_with, tr("types.serde_default").to_string());
+                                }
+                                optional(ui, tr("types.description"), &mut custom.description, String::new());
+                            });
+                        });
+                        ui.separator();
+                    }
+                });
+                if let Some(idx) = remove {
+                    edited.remove(idx);
+                    started_edit = true;
+                }
+            });
+        self.types_open = open;
+
+        if edited != self.project.meta.types {
+            if started_edit {
+                self.save_undo_state("Edit data types");
+            }
+            self.project.meta.types = edited;
+            self.project.touch();
+        }
+        if let Some((old, new)) = rename {
+            let valid = imortal_ir::CustomType::new(new.as_str(), DataType::String).has_valid_name();
+            if !valid || self.project.meta.types.iter().any(|t| t.name == new) {
+                self.set_error(trf("types.rename_rejected", &[&new]));
+            } else {
+                self.save_undo_state("Rename data type");
+                let fields = self.project.rename_custom_type(&old, &new);
+                self.set_status(trf("types.renamed", &[&old, &new, &fields]));
+            }
+        }
+    }
+
+    /// Pick a Rust project folder to scan for entities and endpoints
+    fn pick_rust_import(&mut self) {
+        let Some(dir) = rfd::FileDialog::new().pick_folder() else {
+            return;
+        };
+
+        let name = dir.file_name().unwrap_or_default().to_string_lossy().to_string();
+        self.spawn_task(trf("import.loading", &[&name]), move |_| {
+            TaskMessage::RustScanned(RustImport::scan(&dir).map(|import| (name, Box::new(import))))
+        });
+    }
+
+    /// Review what a Rust project scan proposed before importing it
+    fn render_rust_import(&mut self, ctx: &egui::Context) {
+        let Some(review) = &mut self.rust_import else {
+            return;
+        };
+
+        let mut import = false;
+        let mut cancel = false;
+        egui::Window::new(trf("rust_import.title", &[&review.name]))
+            .collapsible(false)
+            .resizable(true)
+            .default_size([460.0, 420.0])
+            .show(ctx, |ui| {
+                ui.weak(tr("rust_import.hint"));
+                if !review.import.warnings.is_empty() {
+                    ui.colored_label(egui::Color32::YELLOW, trf("rust_import.warnings", &[&review.import.warnings.len()]))
+                        .on_hover_text(review.import.warnings.join("\n"));
+                }
+                egui::ScrollArea::vertical().max_height(300.0).show(ui, |ui| {
+                    ui.strong(tr("rust_import.entities"));
+                    for (entity, include) in review.import.entities.iter().zip(&mut review.entities) {
+                        let label = trf("rust_import.entity", &[&entity.node.name, &entity.node.fields.len()]);
+                        ui.checkbox(include, label).on_hover_text(&entity.file);
+                    }
+                    ui.add_space(6.0);
+                    ui.strong(tr("rust_import.endpoints"));
+                    for (endpoint, include) in review.import.endpoints.iter().zip(&mut review.endpoints) {
+                        let method = endpoint.node.get_config_str("method").unwrap_or("GET");
+                        let path = endpoint.node.get_config_str("path").unwrap_or("/");
+                        let label = format!("{} {}  →  {}", method, path, endpoint.handler);
+                        ui.checkbox(include, label).on_hover_text(&endpoint.file);
+                    }
+                });
+                ui.separator();
+                ui.horizontal(|ui| {
+                    import = ui.button(tr("import.import")).clicked();
+                    cancel = ui.button(tr("dialog.cancel")).clicked();
+                });
+            });
+
+        if import {
+            let Some(review) = self.rust_import.take() else {
+                return;
+            };
+            let mut chosen = review.import;
+            let mut entities = review.entities.into_iter();
+            chosen.entities.retain(|_| entities.next().unwrap_or(false));
+            let mut endpoints = review.endpoints.into_iter();
+            chosen.endpoints.retain(|_| endpoints.next().unwrap_or(false));
+            match chosen.to_graph(&review.name) {
+                Ok(graph) if self.project.import_collisions(&graph).is_empty() => {
+                    let message = self.import_project(graph, HashMap::new());
+                    self.notify(Notification::success(message));
+                }
+                Ok(graph) => self.pending_import = Some(PendingImport::new(&self.project, graph)),
+                Err(e) => self.set_error(trf("rust_import.failed", &[&e])),
+            }
+        } else if cancel {
+            self.rust_import = None;
+        }
+    }
+
+    /// Ask the embedder's assistant about the project or the selection
+    fn render_assist_dialog(&mut self, ctx: &egui::Context) {
+        let Some(provider) = self.assist_provider.clone().filter(|_| self.assist_open) else {
+            return;
+        };
+
+        let mut open = true;
+        let mut send = false;
+        egui::Window::new(trf("assist.title", &[&provider.name()]))
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(true)
+            .default_width(420.0)
+            .show(ctx, |ui| {
+                ui.weak(tr("assist.hint"));
+                ui.add(
+                    egui::TextEdit::multiline(&mut self.assist_instruction)
+                        .hint_text(tr("assist.instruction_hint"))
+                        .desired_rows(4)
+                        .desired_width(f32::INFINITY),
+                );
+                let selected = self.project.selected_nodes.len();
+                ui.add_enabled_ui(selected > 0, |ui| {
+                    ui.checkbox(&mut self.assist_selection_only, trf("assist.selection_only", &[&selected]));
+                });
+                ui.separator();
+                ui.horizontal(|ui| {
+                    send = ui
+                        .add_enabled(!self.assist_instruction.trim().is_empty(), egui::Button::new(tr("assist.send")))
+                        .clicked();
+                });
+            });
+
+        if send {
+            let focus: Vec<NodeId> = if self.assist_selection_only {
+                self.project.selected_nodes.iter().copied().collect()
+            } else {
+                Vec::new()
+            };
+            let request = AssistRequest::new(&self.project, &focus, self.assist_instruction.trim());
+            self.spawn_task(trf("assist.running", &[&provider.name()]), move |_| {
+                TaskMessage::Assisted(
+                    block_on(provider.suggest(&request))
+                        .and_then(|answer| Proposal::from_json(&answer).map_err(|e| e.to_string())),
+                )
+            });
+            open = false;
+        }
+        self.assist_open = open;
+    }
+
+    /// Review what an assistant proposed before adding the chosen parts
+    fn render_assist_review(&mut self, ctx: &egui::Context) {
+        let Some(review) = &mut self.assist_review else {
+            return;
+        };
+
+        let mut apply = false;
+        let mut cancel = false;
+        egui::Window::new(tr("assist.review_title"))
+            .collapsible(false)
+            .resizable(true)
+            .default_size([460.0, 420.0])
+            .show(ctx, |ui| {
+                if !review.proposal.summary.is_empty() {
+                    ui.label(&review.proposal.summary);
+                }
+                ui.weak(tr("assist.review_hint"));
+                egui::ScrollArea::vertical().max_height(300.0).show(ui, |ui| {
+                    if !review.proposal.nodes.is_empty() {
+                        ui.strong(tr("assist.nodes"));
+                    }
+                    for (node, include) in review.proposal.nodes.iter().zip(&mut review.nodes) {
+                        let mut label = format!("{} ({})", node.name, node.component_type);
+                        if !node.fields.is_empty() {
+                            let fields: Vec<String> = node.fields.iter().map(|f| format!("{}: {}", f.name, f.data_type)).collect();
+                            label.push_str(&format!(" — {}", fields.join(", ")));
+                        }
+                        let response = ui.checkbox(include, label);
+                        if !node.reason.is_empty() {
+                            response.on_hover_text(&node.reason);
+                        }
+                    }
+                    if !review.proposal.edges.is_empty() {
+                        ui.add_space(6.0);
+                        ui.strong(tr("assist.edges"));
+                    }
+                    for (edge, include) in review.proposal.edges.iter().zip(&mut review.edges) {
+                        let label = format!("{}  →  {}  ({:?})", edge.from, edge.to, edge.kind);
+                        let response = ui.checkbox(include, label);
+                        if !edge.reason.is_empty() {
+                            response.on_hover_text(&edge.reason);
+                        }
+                    }
+                });
+                ui.separator();
+                ui.horizontal(|ui| {
+                    apply = ui.button(tr("assist.apply")).clicked();
+                    cancel = ui.button(tr("dialog.cancel")).clicked();
+                });
+            });
+
+        if apply {
+            let Some(review) = self.assist_review.take() else {
+                return;
+            };
+            self.save_undo_state("Apply assistant proposal");
+            let registry = &self.registry;
+            let applied = review.proposal.apply(
+                &mut self.project,
+                &review.nodes,
+                &review.edges,
+                |component_type, name| registry.instantiate_with_name(component_type, name).ok(),
+                self.placement.as_ref(),
+            );
+            self.project.clear_selection();
+            for id in &applied.nodes {
+                self.project.select_node(*id);
+                self.node_added(*id);
+            }
+            for id in &applied.edges {
+                self.connection_created(*id);
+            }
+            for skipped in &applied.skipped {
+                tracing::warn!("Assistant proposal item skipped: {}", skipped);
+            }
+            let message = trf("assist.applied", &[&applied.nodes.len(), &applied.edges.len()]);
+            if applied.skipped.is_empty() {
+                self.set_status(message);
+            } else {
+                self.notify(Notification::warning(format!("{} {}", message, trf("assist.skipped", &[&applied.skipped.join("; ")]))));
+            }
+        } else if cancel {
+            self.assist_review = None;
+        }
+    }
+
+    /// Merge another project into the live one as a single undo step,
+    /// returning a summary for the user; `choices` decides each collision
+    /// by imported node
+    fn import_project(&mut self, other: ProjectGraph, choices: HashMap<NodeId, ImportCollision>) -> String {
+        self.save_undo_state("Import Project");
+        let summary = self.project.import_project_with(&other, |_, theirs| choices.get(&theirs.id).copied().unwrap_or_default());
+
+        self.project.clear_selection();
+        for id in &summary.added {
+            self.project.select_node(*id);
+        }
+        self.emit(EditorEvent::ProjectImported { name: other.meta.name.clone(), nodes_added: summary.added.len() });
+        trf(
+            "import.done",
+            &[&summary.added.len(), &other.meta.name, &summary.merged, &summary.replaced, &summary.skipped, &summary.renamed.len()],
+        )
+    }
+
+    /// Reconcile imported nodes named like existing ones, one decision each
+    fn render_import_dialog(&mut self, ctx: &egui::Context) {
+        let Some(pending) = &mut self.pending_import else {
+            return;
+        };
+
+        let mut import = false;
+        let mut cancel = false;
+        egui::Window::new(tr("import.title"))
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                let collisions = self.project.import_collisions(&pending.other);
+                ui.label(trf("import.collisions", &[&pending.other.meta.name, &collisions.len()]));
+                ui.horizontal(|ui| {
+                    ui.label(tr("import.all"));
+                    for option in ImportCollision::all() {
+                        if ui.small_button(import_collision_name(*option)).on_hover_text(import_collision_hint(*option)).clicked() {
+                            pending.choices.values_mut().for_each(|choice| *choice = *option);
+                        }
+                    }
+                });
+                ui.separator();
+                egui::ScrollArea::vertical().max_height(280.0).show(ui, |ui| {
+                    egui::Grid::new("import_reconcile").num_columns(3).striped(true).show(ui, |ui| {
+                        for (mine, theirs) in &collisions {
+                            ui.strong(&mine.name);
+                            let differences = FieldDifferences::between(mine, theirs);
+                            if differences.is_empty() {
+                                ui.weak(tr("import.same_fields"));
+                            } else {
+                                let counts = trf(
+                                    "import.differences",
+                                    &[&differences.added.len(), &differences.missing.len(), &differences.changed.len()],
+                                );
+                                let details = [
+                                    ("import.theirs_only", &differences.added),
+                                    ("import.mine_only", &differences.missing),
+                                    ("import.changed", &differences.changed),
+                                ]
+                                .iter()
+                                .filter(|(_, names)| !names.is_empty())
+                                .map(|(key, names)| trf(key, &[&names.join(", ")]))
+                                .collect::<Vec<_>>()
+                                .join("\n");
+                                ui.label(counts).on_hover_text(details);
+                            }
+                            let choice = pending.choices.entry(theirs.id).or_insert(ImportCollision::Merge);
+                            egui::ComboBox::from_id_salt(("import_choice", theirs.id))
+                                .selected_text(import_collision_name(*choice))
+                                .show_ui(ui, |ui| {
+                                    for option in ImportCollision::all() {
+                                        ui.selectable_value(choice, *option, import_collision_name(*option))
+                                            .on_hover_text(import_collision_hint(*option));
+                                    }
+                                });
+                            ui.end_row();
+                        }
+                    });
+                });
+                ui.add_space(8.0);
+                ui.horizontal(|ui| {
+                    import = ui.button(tr("import.import")).clicked();
+                    cancel = ui.button(tr("dialog.cancel")).clicked();
+                });
+            });
+
+        if import {
+            if let Some(pending) = self.pending_import.take() {
+                let message = self.import_project(pending.other, pending.choices);
+                self.notify(Notification::success(message));
+            }
+        } else if cancel {
+            self.pending_import = None;
+        }
+    }
+
+    /// Save the selected nodes as a standalone project or component file
+    fn export_selection(&mut self) {
+        self.load_all_node_details();
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("Immortal Project", &[imortal_ir::PROJECT_EXTENSION])
+            .add_filter("Immortal Component", &[imortal_ir::COMPONENT_EXTENSION])
+            .set_file_name(format!("{}-extract.{}", self.project.meta.name, imortal_ir::PROJECT_EXTENSION))
+            .save_file()
+        else {
+            return;
+        };
+
+        let extraction = self.project.extract(&self.project.selected_nodes);
+        let mut project = extraction.project;
+        if let Some(name) = path.file_stem() {
+            project.meta.name = name.to_string_lossy().to_string();
+        }
+        let done = trf(
+            "export_selection.done",
+            &[&project.node_count(), &path.display(), &extraction.dropped_edges, &extraction.dropped_foreign_keys],
+        );
+        self.spawn_task(trf("task.writing", &[&path.display()]), move |_| {
+            TaskMessage::Written(match imortal_ir::save_project(&project, &path, imortal_ir::ProjectFormat::Json) {
+                Ok(_) => Ok(done),
+                Err(e) => Err(format!("Failed to save: {}", e)),
+            })
+        });
+    }
+
+    /// Snapshot a just-saved project into its history
+    fn project_saved(&mut self, path: &Path) {
+        self.emit(EditorEvent::ProjectSaved { path: path.to_path_buf() });
+        match imortal_ir::history::take_snapshot(path, &self.config.snapshots) {
+            Ok(_) => self.set_status("Project saved"),
+            Err(e) => self.set_error(trf("history.snapshot_failed", &[&e])),
+        }
+    }
+
+    /// Open the "Restore Version" dialog with the live project's snapshots
+    fn open_restore_dialog(&mut self) {
+        let Some(path) = &self.project_path else {
+            self.set_status(tr("history.unsaved"));
+            return;
+        };
+        match imortal_ir::history::list_snapshots(path) {
+            Ok(snapshots) if snapshots.is_empty() => self.set_status(tr("history.none")),
+            Ok(snapshots) => {
+                let versions = snapshots
+                    .into_iter()
+                    .map(|snapshot| {
+                        let project = snapshot.load().map_err(|e| e.to_string());
+                        (snapshot, project)
+                    })
+                    .collect();
+                self.restore_dialog = Some(RestoreDialog { versions, selected: None });
+            }
+            Err(e) => self.set_error(format!("Failed to read history: {}", e)),
+        }
+    }
+
+    /// Render the snapshot list with a preview of the selected version
+    fn render_restore_dialog(&mut self, ctx: &egui::Context) {
+        let Some(dialog) = &mut self.restore_dialog else {
+            return;
+        };
+
+        let current = &self.project;
+        let mut open = true;
+        let mut restore = None;
+        egui::Window::new(tr("history.title"))
+            .open(&mut open)
+            .resizable(true)
+            .default_size([560.0, 360.0])
+            .show(ctx, |ui| {
+                ui.horizontal_top(|ui| {
+                    ui.vertical(|ui| {
+                        ui.set_width(220.0);
+                        egui::ScrollArea::vertical().id_salt("restore_versions").show(ui, |ui| {
+                            for (index, (snapshot, project)) in dialog.versions.iter().enumerate() {
+                                let counts = match project {
+                                    Ok(project) => trf("history.counts", &[&project.node_count(), &project.edge_count()]),
+                                    Err(_) => tr("history.unreadable").to_string(),
+                                };
+                                let label = format!("{}\n{}", time_ago(snapshot.timestamp), counts);
+                                if ui.selectable_label(dialog.selected == Some(index), label).clicked() {
+                                    dialog.selected = Some(index);
+                                }
+                            }
+                        });
+                    });
+                    ui.separator();
+                    ui.vertical(|ui| {
+                        let Some((snapshot, project)) = dialog.selected.and_then(|index| dialog.versions.get(index)) else {
+                            ui.label(tr("history.select"));
+                            return;
+                        };
+                        let project = match project {
+                            Ok(project) => project,
+                            Err(e) => {
+                                ui.colored_label(egui::Color32::LIGHT_RED, e);
+                                return;
+                            }
+                        };
+
+                        ui.heading(&project.meta.name);
+                        ui.label(time_ago(snapshot.timestamp));
+                        ui.label(trf("history.counts", &[&project.node_count(), &project.edge_count()]));
+
+                        // What restoring would change, compared to the live project
+                        let mut added: Vec<&str> = project
+                            .nodes()
+                            .filter(|node| current.get_node(node.id).is_none())
+                            .map(|node| node.name.as_str())
+                            .collect();
+                        let mut removed: Vec<&str> = current
+                            .nodes()
+                            .filter(|node| project.get_node(node.id).is_none())
+                            .map(|node| node.name.as_str())
+                            .collect();
+                        added.sort();
+                        removed.sort();
+                        ui.separator();
+                        egui::ScrollArea::vertical().id_salt("restore_preview").max_height(220.0).show(ui, |ui| {
+                            if added.is_empty() && removed.is_empty() {
+                                ui.label(tr("history.same_nodes"));
+                            }
+                            for name in added {
+                                ui.colored_label(egui::Color32::LIGHT_GREEN, format!("+ {}", name));
+                            }
+                            for name in removed {
+                                ui.colored_label(egui::Color32::LIGHT_RED, format!("− {}", name));
+                            }
+                        });
+                        ui.separator();
+                        if ui.button(tr("history.restore")).clicked() {
+                            restore = Some((project.clone(), snapshot.timestamp));
+                        }
+                    });
+                });
+            });
+
+        if let Some((project, timestamp)) = restore {
+            self.leave_composites();
+            self.save_undo_state("Restore Version");
+            self.project = project;
+            // Restoring only changes the editor; saving writes it back
+            self.project.touch();
+            self.restore_dialog = None;
+            self.set_status(trf("history.restored", &[&time_ago(timestamp)]));
+        } else if !open {
+            self.restore_dialog = None;
+        }
+    }
+
+    fn generate_code(&mut self) {
+        self.leave_composites();
+        self.load_all_node_details();
+        if self.generation_task.is_some() {
+            self.set_status(tr("generate.already_running"));
+            return;
+        }
+
+        let config = GeneratorConfig::for_project(&self.project.meta);
+        if config.target_language != "rust" {
+            self.set_error(trf("generate.unsupported_target", &[&config.target_language]));
+            return;
+        }
+        // A relative output folder sits next to the project file
+        let output_dir = if config.output_dir.is_absolute() {
+            config.output_dir.clone()
+        } else if let Some(dir) = self.project_path.as_ref().and_then(|path| path.parent()) {
+            dir.join(&config.output_dir)
+        } else if let Some(dir) = rfd::FileDialog::new().pick_folder() {
+            dir
+        } else {
+            return;
+        };
+
+        self.start_generation(self.project.clone(), config, output_dir);
+        self.tutorial.handle(TutorialEvent::Generated);
+    }
+
+    /// Generate `project` into `output_dir` in the background, first asking
+    /// before running hooks that came from the project file
+    fn start_generation(&mut self, project: ProjectGraph, mut config: GeneratorConfig, output_dir: PathBuf) {
+        if let Some(hash) = config.hooks.commands_hash() {
+            match self.hook_decision(&hash) {
+                Some(true) => {}
+                Some(false) => {
+                    config.hooks = GenerationHooks::default();
+                    self.set_status(tr("generate.hooks_skipped"));
+                }
+                None => {
+                    let commands = config.hooks.commands();
+                    self.pending_hook_run = Some(PendingHookRun { project, config, output_dir, commands, hash });
+                    return;
+                }
+            }
+        }
+        self.spawn_generation(project, config, output_dir);
+    }
+
+    /// The answer given about hooks hashing to `hash` in the open project
+    fn hook_decision(&self, hash: &str) -> Option<bool> {
+        match &self.project_path {
+            Some(path) => self.hook_approvals.decision(path, hash),
+            None => self.unsaved_hook_approvals.contains(hash).then_some(true),
+        }
+    }
+
+    /// Remember an answer about hooks hashing to `hash` in the open project
+    fn record_hook_decision(&mut self, hash: String, allowed: bool) {
+        match &self.project_path {
+            Some(path) => {
+                self.hook_approvals.record(path, &hash, allowed);
+                if let Err(e) = self.hook_approvals.save() {
+                    tracing::warn!("Failed to save hook approvals: {}", e);
+                }
+            }
+            None if allowed => {
+                self.unsaved_hook_approvals.insert(hash);
+            }
+            None => {}
+        }
+    }
+
+    fn spawn_generation(&mut self, project: ProjectGraph, config: GeneratorConfig, output_dir: PathBuf) {
+        self.emit(EditorEvent::GenerationStarted { output_dir: output_dir.clone() });
+        let task = self.spawn_task(tr("generate.running").to_string(), move |cx| {
+            TaskMessage::Generated(generate_to_disk(&project, config, output_dir, cx))
+        });
+        self.generation_task = Some(task);
+    }
+
+    /// Render the dialog asking whether to run the hooks of a project file
+    /// about to be generated, remembering the answer for that file
+    fn render_hook_confirmation(&mut self, ctx: &egui::Context) {
+        let Some(pending) = &self.pending_hook_run else {
+            return;
+        };
+        let mut open = true;
+        let mut choice = None;
+        let mut cancel = false;
+
+        egui::Window::new(tr("hooks_confirm.title"))
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.label(tr("hooks_confirm.summary"));
+                egui::ScrollArea::vertical().max_height(200.0).show(ui, |ui| {
+                    for command in &pending.commands {
+                        ui.monospace(command);
+                    }
+                });
+                ui.colored_label(ui.visuals().warn_fg_color, tr("hooks_confirm.hint"));
+                ui.separator();
+                ui.horizontal(|ui| {
+                    if ui.button(tr("hooks_confirm.run")).clicked() {
+                        choice = Some(true);
+                    }
+                    if ui.button(tr("hooks_confirm.skip")).clicked() {
+                        choice = Some(false);
+                    }
+                    cancel = ui.button(tr("dialog.cancel")).clicked();
+                });
+            });
+
+        if let Some(allowed) = choice {
+            let Some(PendingHookRun { project, mut config, output_dir, hash, .. }) = self.pending_hook_run.take() else {
+                return;
+            };
+            self.record_hook_decision(hash, allowed);
+            if !allowed {
+                config.hooks = GenerationHooks::default();
+            }
+            self.spawn_generation(project, config, output_dir);
+        } else if cancel || !open {
+            self.pending_hook_run = None;
+        }
+    }
+
+    /// Open the "Generate Selection" dialog for the selected nodes and the
+    /// nodes they need
+    fn open_selection_generation(&mut self) {
+        self.load_all_node_details();
+        let selected = self.project.selected_nodes.clone();
+        if selected.is_empty() {
+            return;
+        }
+        let mut dependencies: Vec<NodeId> = self.project.required_dependencies(&selected).into_iter().collect();
+        let name = |id: &NodeId| self.project.get_node(*id).map(|n| n.name.clone()).unwrap_or_default();
+        dependencies.sort_by_key(name);
+        self.selection_generation = Some(SelectionGeneration { selected, dependencies });
+    }
+
+    /// Render the "Generate Selection" dialog, which generates the selection
+    /// and its dependencies into a folder of their own
+    fn render_selection_generation(&mut self, ctx: &egui::Context) {
+        let Some(pending) = &self.selection_generation else {
+            return;
+        };
+        let mut open = true;
+        let mut generate = false;
+        let mut cancel = false;
+
+        egui::Window::new(tr("generate_selection.title"))
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.label(trf("generate_selection.summary", &[&pending.selected.len(), &pending.dependencies.len()]));
+                if !pending.dependencies.is_empty() {
+                    ui.label(tr("generate_selection.dependencies"));
+                    egui::ScrollArea::vertical().max_height(200.0).show(ui, |ui| {
+                        for id in &pending.dependencies {
+                            if let Some(node) = self.project.get_node(*id) {
+                                let icon = self.registry.get(&node.component_type).map_or("❓", |d| d.icon_text());
+                                ui.label(format!("{} {}", icon, node.name));
+                            }
+                        }
+                    });
+                }
+                ui.weak(tr("generate_selection.hint"));
+                ui.separator();
+                ui.horizontal(|ui| {
+                    generate = ui.button(tr("generate_selection.generate")).clicked();
+                    cancel = ui.button(tr("dialog.cancel")).clicked();
+                });
+            });
+
+        if generate {
+            self.generate_selection();
+        } else if cancel || !open {
+            self.selection_generation = None;
+        }
+    }
+
+    /// Generate the pending selection and its dependencies as a minimal
+    /// project, into a folder picked now so the full project's output is
+    /// left alone
+    fn generate_selection(&mut self) {
+        if self.generation_task.is_some() {
+            self.set_status(tr("generate.already_running"));
+            return;
+        }
+        let config = GeneratorConfig::for_project(&self.project.meta);
+        if config.target_language != "rust" {
+            self.set_error(trf("generate.unsupported_target", &[&config.target_language]));
+            return;
+        }
+        let mut dialog = rfd::FileDialog::new();
+        if let Some(dir) = self.project_path.as_ref().and_then(|path| path.parent()) {
+            dialog = dialog.set_directory(dir);
+        }
+        let Some(output_dir) = dialog.pick_folder() else {
+            return;
+        };
+        let Some(pending) = self.selection_generation.take() else {
+            return;
+        };
+
+        let mut nodes = pending.selected;
+        nodes.extend(pending.dependencies);
+        let extraction = self.project.extract(&nodes);
+        self.start_generation(extraction.project, config, output_dir);
+    }
+
+    fn validate_project(&mut self) {
+        self.leave_composites();
+        self.load_all_node_details();
+        let issues = imortal_components::validation::validator(&self.registry).validate_all(&self.project);
+        let errors = issues.iter().filter(|e| e.is_error()).count();
+        if errors == 0 {
+            // Warnings stay listed when they can be fixed from the report
+            self.validation_report = issues.iter().any(|e| e.fix.is_some()).then_some(issues);
+            self.set_status(tr("validation.valid"));
+            self.emit(EditorEvent::ValidationPassed);
+        } else {
+            self.emit(EditorEvent::ValidationFailed {
+                errors: issues.iter().filter(|e| e.is_error()).map(|e| e.to_string()).collect(),
+            });
+            self.notify(
+                Notification::error(trf("validation.errors_found", &[&errors]))
+                    .with_action(NotificationAction::ShowValidation),
+            );
+            self.validation_report = Some(issues);
+        }
+        self.tutorial.handle(TutorialEvent::Validated);
+    }
+
+    /// Look for fields added by hand to a generated project's models
+    fn sync_fields_from_code(&mut self) {
+        self.leave_composites();
+        self.load_all_node_details();
+        let Some(dir) = rfd::FileDialog::new().pick_folder() else {
+            return;
+        };
+
+        match code_sync::hand_added_fields(&self.project, &dir) {
+            Ok(found) if found.is_empty() => self.set_status(trf("code_sync.none", &[&dir.display()])),
+            Ok(found) => self.code_sync = Some((dir, found.into_iter().map(|field| (field, true)).collect())),
+            Err(e) => self.set_error(trf("code_sync.failed", &[&e])),
+        }
+    }
+
+    /// Offer to import fields found in generated code as entity fields
+    fn render_code_sync(&mut self, ctx: &egui::Context) {
+        let Some((dir, found)) = &mut self.code_sync else {
+            return;
+        };
+
+        let mut open = true;
+        let mut import = false;
+        egui::Window::new(tr("code_sync.title"))
+            .open(&mut open)
+            .resizable(true)
+            .default_width(420.0)
+            .show(ctx, |ui| {
+                ui.label(tr("code_sync.intro"));
+                ui.weak(dir.display().to_string());
+                ui.separator();
+                egui::ScrollArea::vertical().max_height(300.0).show(ui, |ui| {
+                    for (found, checked) in found.iter_mut() {
+                        let field = &found.field;
+                        let optional = if field.required { "" } else { "?" };
+                        let label = format!("{}.{}: {}{}", found.entity, field.name, Self::data_type_display(&field.data_type), optional);
+                        ui.checkbox(checked, label).on_hover_text(&found.file);
+                    }
+                });
+                ui.add_space(8.0);
+                let any = found.iter().any(|(_, checked)| *checked);
+                import = ui.add_enabled(any, egui::Button::new(tr("code_sync.import"))).clicked();
+            });
+
+        if import {
+            if let Some((_, found)) = self.code_sync.take() {
+                self.save_undo_state("Import fields from code");
+                let selected = found.into_iter().filter(|(_, checked)| *checked).map(|(field, _)| field);
+                let count = code_sync::import_hand_added_fields(&mut self.project, selected);
+                self.set_status(trf("code_sync.imported", &[&count]));
+            }
+        } else if !open {
+            self.code_sync = None;
+        }
+    }
+
+    /// Render the quick-add search popup at its canvas position
+    ///
+    /// Up/Down (or Tab/Shift+Tab) move the highlight, Enter adds the highlighted
+    /// component, and Escape or clicking elsewhere closes the popup.
+    fn render_quick_add(&mut self, ctx: &egui::Context) {
+        use egui::{Key, Modifiers};
+
+        let Some(quick_add) = &mut self.quick_add else {
+            return;
+        };
+
+        // A dropped connection only offers components that can take it
+        let source = quick_add.connect_from.and_then(|(node_id, is_output)| {
+            self.project.get_node(node_id).map(|node| (node, is_output))
+        });
+        let connectable: Option<Vec<String>> = source.map(|(node, is_output)| {
+            quick_add::connectable(&self.registry, node, is_output).iter().map(|c| c.component.id.clone()).collect()
+        });
+        let candidates = self.registry.all()
+            .filter(|c| connectable.as_ref().is_none_or(|ids| ids.contains(&c.id)))
+            .map(|c| Candidate {
+                id: c.id.clone(),
+                icon: c.icon_text().to_string(),
+                name: i18n::lookup(&format!("component.{}.name", c.id)).unwrap_or(&c.name).to_string(),
+                description: i18n::lookup(&format!("component.{}.description", c.id)).unwrap_or(&c.description).to_string(),
+            })
+            .collect();
+        let matches = quick_add::rank(candidates, &quick_add.search, &self.recent_components);
+
+        // Take the navigation keys before the search field sees them
+        let (down, up) = ctx.input_mut(|i| (
+            i.consume_key(Modifiers::NONE, Key::ArrowDown) || i.consume_key(Modifiers::NONE, Key::Tab),
+            i.consume_key(Modifiers::NONE, Key::ArrowUp) || i.consume_key(Modifiers::SHIFT, Key::Tab),
+        ));
+        if down {
+            quick_add.selected += 1;
+        }
+        if up {
+            quick_add.selected = quick_add.selected.saturating_sub(1);
+        }
+        quick_add.selected = quick_add.selected.min(matches.len().saturating_sub(1));
+
+        let mut chosen = None;
+        let mut close = false;
+        let area = egui::Area::new(egui::Id::new("quick_add"))
+            .order(egui::Order::Foreground)
+            .fixed_pos(quick_add.screen_pos)
+            .show(ctx, |ui| {
+                egui::Frame::popup(ui.style()).show(ui, |ui| {
+                    ui.set_width(240.0);
+                    let hint = if source.is_some() { "🔍 Connect to..." } else { "🔍 Add component..." };
+                    let response = ui.add(egui::TextEdit::singleline(&mut quick_add.search).hint_text(hint));
+                    response.request_focus();
+                    if response.changed() {
+                        quick_add.selected = 0;
+                    }
+                    egui::ScrollArea::vertical().max_height(240.0).show(ui, |ui| {
+                        for (index, candidate) in matches.iter().enumerate() {
+                            let is_selected = index == quick_add.selected;
+                            let recent = if self.recent_components.rank(&candidate.id).is_some() { "  🕘" } else { "" };
+                            let row = ui.selectable_label(is_selected, format!("{} {}{}", candidate.icon, candidate.name, recent))
+                                .on_hover_text(&candidate.description);
+                            if is_selected && (up || down) {
+                                row.scroll_to_me(None);
+                            }
+                            if row.clicked() {
+                                chosen = Some(candidate.id.clone());
+                            }
+                        }
+                        if matches.is_empty() {
+                            ui.weak(if source.is_some() { "No compatible components" } else { "No matching components" });
+                        }
+                    });
+                    if ui.input(|i| i.key_pressed(Key::Enter)) {
+                        chosen = matches.get(quick_add.selected).map(|c| c.id.clone());
+                    }
+                    close = ui.input(|i| i.key_pressed(Key::Escape));
+                });
+            });
+        if area.response.clicked_elsewhere() {
+            close = true;
+        }
+
+        if let Some(id) = chosen {
+            let position = quick_add.position;
+            let connect_from = quick_add.connect_from;
+            self.quick_add = None;
+            if let Some(component) = self.registry.get(&id) {
+                let mut node = component.instantiate_default();
+                self.apply_default_width(&mut node);
+                node.position = position;
+                node.namespace = self.state.namespace.clone();
+                let name = node.name.clone();
+
+                // Work out the ports to join before the node is added
+                let edge = connect_from.and_then(|(source_id, is_output)| {
+                    let source = self.project.get_node(source_id)?;
+                    let link = quick_add::connectable(&self.registry, source, is_output)
+                        .into_iter()
+                        .find(|c| c.component.id == id)?;
+                    let (from, from_port, to, to_port) = if is_output {
+                        (source_id, &link.port.id, node.id, &link.component_port.id)
+                    } else {
+                        (node.id, &link.component_port.id, source_id, &link.port.id)
+                    };
+                    Some(match link.port.kind {
+                        imortal_core::PortKind::Trigger => Edge::trigger(from, from_port.clone(), to, to_port.clone()),
+                        _ => Edge::data_flow(from, from_port.clone(), to, to_port.clone()),
+                    })
+                });
+                if connect_from.is_some_and(|(_, is_output)| !is_output) {
+                    // Put the new node's output where the connection was dropped
+                    node.position.x -= node.size.width;
+                }
+
+                self.save_undo_state(&format!("Add {}", name));
+                let node_id = self.project.add_node(node);
+                self.project.clear_selection();
+                self.project.select_node(node_id);
+                self.node_added(node_id);
+                self.recent_components.record(&id);
+                match edge.map(|edge| self.project.add_edge(edge)) {
+                    Some(Ok(edge_id)) => {
+                        self.connection_created(edge_id);
+                        self.set_status(format!("Added and connected {}", name));
+                    }
+                    Some(Err(e)) => self.set_error(format!("Added {}, but connecting failed: {}", name, e)),
+                    None => self.set_status(format!("Added {}", name)),
+                }
+            }
+        } else if close {
+            self.quick_add = None;
+        }
+    }
+
+    /// Render the connection dialog of a database node
+    fn render_connection_dialog(&mut self, ctx: &egui::Context) {
+        let Some(node_id) = self.connection_dialog else {
+            return;
+        };
+        let Some(node) = self.project.get_node(node_id).cloned() else {
+            self.connection_dialog = None;
+            return;
+        };
+
+        let string = |key: &str, default: &str| match node.config.get(key) {
+            Some(imortal_core::ConfigValue::String(s)) => s.clone(),
+            _ => default.to_string(),
+        };
+        let mut backend = string("backend", "postgres");
+        let mut host = string("host", "localhost");
+        let mut port = match node.config.get("port") {
+            Some(imortal_core::ConfigValue::Int(i)) => *i,
+            _ => 5432,
+        };
+        let mut database = string("database", "");
+        let mut username = string("username", "");
+        let mut password = string("password", "");
+
+        let mut open = true;
+        let mut changed = false;
+        let mut test = false;
+        egui::Window::new(format!("🗄 {}", node.name))
+            .id(egui::Id::new("connection_dialog"))
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                egui::Grid::new("connection_fields").num_columns(2).show(ui, |ui| {
+                    ui.label(tr("connection_dialog.backend"));
+                    egui::ComboBox::from_id_salt("connection_backend")
+                        .selected_text(&backend)
+                        .show_ui(ui, |ui| {
+                            for option in ["postgres", "mysql", "sqlite", "mssql", "mongodb"] {
+                                changed |= ui.selectable_value(&mut backend, option.to_string(), option).changed();
+                            }
+                        });
+                    ui.end_row();
+                    ui.label(tr("connection_dialog.host"));
+                    changed |= ui.text_edit_singleline(&mut host).changed();
+                    ui.end_row();
+                    ui.label(tr("connection_dialog.port"));
+                    changed |= ui.add(egui::DragValue::new(&mut port).range(0..=65535)).changed();
+                    ui.end_row();
+                    ui.label(tr("connection_dialog.database"));
+                    changed |= ui.text_edit_singleline(&mut database).changed();
+                    ui.end_row();
+                    ui.label(tr("connection_dialog.username"));
+                    changed |= ui.text_edit_singleline(&mut username).changed();
+                    ui.end_row();
+                    ui.label(tr("connection_dialog.password"));
+                    changed |= ui.add(egui::TextEdit::singleline(&mut password).password(true)).changed();
+                    ui.end_row();
+                });
+
+                ui.separator();
+                let testing = self.connection_test_running(node_id);
+                ui.horizontal(|ui| {
+                    test = ui.add_enabled(!testing, egui::Button::new(tr("connection_test.test"))).clicked();
+                    if testing {
+                        ui.spinner();
+                    }
+                });
+                if let Some((result_node_id, success, message, _)) = &self.db_connection_result {
+                    if *result_node_id == node_id {
+                        let color = if *success {
+                            egui::Color32::from_rgb(100, 255, 100)
+                        } else {
+                            egui::Color32::from_rgb(255, 100, 100)
+                        };
+                        ui.colored_label(color, message);
+                    }
+                }
+            });
+
+        if changed {
+            if let Some(n) = self.project.get_node_mut(node_id) {
+                use imortal_core::ConfigValue;
+                n.config.insert("backend".to_string(), ConfigValue::String(backend));
+                n.config.insert("host".to_string(), ConfigValue::String(host));
+                n.config.insert("port".to_string(), ConfigValue::Int(port));
+                n.config.insert("database".to_string(), ConfigValue::String(database));
+                n.config.insert("username".to_string(), ConfigValue::String(username));
+                n.config.insert("password".to_string(), ConfigValue::String(password));
+            }
+        }
+        if test {
+            self.start_connection_test(node_id);
+        }
+        if !open {
+            self.connection_dialog = None;
+        }
+    }
+
+    /// List validation errors by kind; clicking one selects its node
+    fn render_validation_report(&mut self, ctx: &egui::Context) {
+        let Some(errors) = &self.validation_report else {
+            return;
+        };
+
+        let mut open = true;
+        let mut reveal = None;
+        let mut fixes: Vec<QuickFix> = Vec::new();
+        egui::Window::new(tr("validation.title"))
+            .open(&mut open)
+            .resizable(true)
+            .default_width(420.0)
+            .show(ctx, |ui| {
+                let fixable: Vec<&QuickFix> = errors.iter().filter_map(|e| e.fix.as_ref()).collect();
+                if !fixable.is_empty() {
+                    if ui.button(trf("validation.fix_all", &[&fixable.len()])).clicked() {
+                        fixes = fixable.into_iter().cloned().collect();
+                    }
+                    ui.separator();
+                }
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    for error in errors {
+                        let key = format!("validation.kind.{}", error.kind.name().to_lowercase().replace(' ', "_"));
+                        let kind = i18n::lookup(&key).unwrap_or(error.kind.name());
+                        let icon = if error.is_error() { "❌" } else { "⚠" };
+                        ui.horizontal(|ui| {
+                            if let Some(fix) = &error.fix {
+                                if ui.small_button(tr("validation.fix")).on_hover_text(fix.label()).clicked() {
+                                    fixes = vec![fix.clone()];
+                                }
+                            }
+                            if ui.selectable_label(false, format!("{} {}: {}", icon, kind, error.message)).clicked() {
+                                reveal = error.node_id;
+                            }
+                        });
+                    }
+                });
+            });
+
+        if !fixes.is_empty() {
+            self.save_undo_state(if fixes.len() == 1 { "Apply fix" } else { "Apply fixes" });
+            let applied = fixes.iter().filter(|fix| fix.apply(&mut self.project)).count();
+            self.validate_project();
+            self.set_status(trf("validation.fixed", &[&applied]));
+            return;
+        }
+
+        if let Some(node_id) = reveal {
+            if self.project.get_node(node_id).is_some() {
+                self.project.clear_selection();
+                self.project.select_node(node_id);
+            }
+        }
+        if !open {
+            self.validation_report = None;
+        }
+    }
+}
+
+impl eframe::App for ImmortalApp {
+    fn update(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) {
+        i18n::set_language(self.config.language);
+        self.poll_tasks();
+
+        // Set dark/light mode
+        if self.config.dark_mode {
+            ctx.set_visuals(egui::Visuals::dark());
+        } else {
+            ctx.set_visuals(egui::Visuals::light());
+        }
+
+        match self.mode {
+            AppMode::Welcome => {
+                // Show welcome screen
+                let action = self.welcome_screen.show(ctx);
+                self.handle_welcome_action(action);
+                self.render_repair_offer(ctx);
+                self.render_lock_conflict(ctx);
+            }
+            AppMode::Editor if self.presentation.is_some() => {
+                self.render_presentation(ctx);
+            }
+            AppMode::Editor => {
+                if ctx.input(|i| i.key_pressed(egui::Key::F5)) {
+                    self.start_presentation();
+                }
+
+                // Ctrl+1..9 jumps to the first nine bookmarks
+                const BOOKMARK_KEYS: [egui::Key; 9] = [
+                    egui::Key::Num1, egui::Key::Num2, egui::Key::Num3,
+                    egui::Key::Num4, egui::Key::Num5, egui::Key::Num6,
+                    egui::Key::Num7, egui::Key::Num8, egui::Key::Num9,
+                ];
+                let bookmark_key = ctx.input(|i| {
+                    (i.modifiers.ctrl || i.modifiers.mac_cmd)
+                        .then(|| BOOKMARK_KEYS.iter().position(|key| i.key_pressed(*key)))
+                        .flatten()
+                });
+                if let Some(index) = bookmark_key {
+                    self.go_to_bookmark(index);
+                }
+
+                // Render all editor UI components
+                self.render_menu_bar(ctx, frame);
+                self.render_tab_bar(ctx);
+                self.render_breadcrumbs(ctx);
+                self.render_tag_bar(ctx);
+                self.render_status_bar(ctx);
+                self.log_panel.show(ctx);
+                if let Some(index) = self.history_panel.show(ctx, &self.history, &self.project, &self.category_overrides) {
+                    self.jump_to_history(index);
+                }
+                if let Some(action) = self.suggestions_panel.show(ctx, &self.project) {
+                    self.handle_suggestion_action(action);
+                }
+                if let Some(action) = self.notifications.show_drawer(ctx) {
+                    self.handle_notification_action(action);
+                }
+                self.render_palette(ctx);
+                self.render_properties(ctx);
+                self.render_canvas(ctx);
+                self.render_dialogs(ctx);
+
+                self.tutorial.observe(&self.project);
+                self.tutorial.show(ctx);
+            }
+        }
+
+        if let Some(action) = self.notifications.show_toasts(ctx) {
+            self.handle_notification_action(action);
+        }
+        self.render_crash_rescue(ctx);
+        self.sync_rescue();
+        self.poll_component_definitions(ctx);
+    }
+
+    fn on_exit(&mut self, _gl: Option<&eframe::glow::Context>) {
+        self.save_session();
+    }
+}
+
+/// Opacity of nodes and connections outside a highlighted flow
+const FLOW_DIM_OPACITY: f32 = 0.25;
+
+/// Opacity of disabled nodes
+const DISABLED_NODE_OPACITY: f32 = 0.5;
+
+/// The selection with the nodes upstream and downstream of it
+struct FlowHighlight {
+    selected: HashSet<NodeId>,
+    upstream: HashSet<NodeId>,
+    downstream: HashSet<NodeId>,
+}
+
+impl FlowHighlight {
+    /// Follow connections `depth` steps from the selected nodes (any
+    /// distance for 0); `None` when nothing is selected
+    fn new(project: &ProjectGraph, depth: usize) -> Option<Self> {
+        if project.selected_nodes.is_empty() {
+            return None;
+        }
+        let depth = (depth > 0).then_some(depth);
+        let selected: HashSet<NodeId> = project.selected_nodes.iter().copied().collect();
+        let mut upstream = HashSet::new();
+        let mut downstream = HashSet::new();
+        for id in &selected {
+            upstream.extend(project.upstream_within(*id, depth));
+            downstream.extend(project.downstream_within(*id, depth));
+        }
+        Some(Self { selected, upstream, downstream })
+    }
+
+    fn includes(&self, id: NodeId) -> bool {
+        self.selected.contains(&id) || self.upstream.contains(&id) || self.downstream.contains(&id)
+    }
+
+    /// Whether a connection runs along the flow into or out of the selection
+    fn includes_edge(&self, edge: &Edge) -> bool {
+        let into = self.upstream.contains(&edge.from_node)
+            && (self.upstream.contains(&edge.to_node) || self.selected.contains(&edge.to_node));
+        let out = self.downstream.contains(&edge.to_node)
+            && (self.downstream.contains(&edge.from_node) || self.selected.contains(&edge.from_node));
+        let within = self.selected.contains(&edge.from_node) && self.selected.contains(&edge.to_node);
+        into || out || within
+    }
+
+    /// Outline color marking upstream and downstream nodes
+    fn ring_color(&self, id: NodeId) -> Option<egui::Color32> {
+        if self.selected.contains(&id) {
+            None
+        } else if self.upstream.contains(&id) {
+            Some(egui::Color32::from_rgb(110, 190, 255))
+        } else if self.downstream.contains(&id) {
+            Some(egui::Color32::from_rgb(255, 170, 80))
+        } else {
+            None
+        }
+    }
+}
+
+/// A sample value cut to fit the import review table
+fn truncate_sample(sample: &str) -> String {
+    const MAX_CHARS: usize = 32;
+    if sample.chars().count() > MAX_CHARS {
+        format!("{}…", sample.chars().take(MAX_CHARS).collect::<String>())
+    } else {
+        sample.to_string()
+    }
+}
+
+/// Rows of per-category style overrides in the settings window; returns
+/// whether anything changed
+fn category_styles_editor(ui: &mut egui::Ui, overrides: &mut CategoryOverrides) -> bool {
+    let mut changed = false;
+    egui::Grid::new("category_styles").num_columns(6).striped(true).show(ui, |ui| {
+        ui.strong(tr("settings.category"));
+        ui.strong(tr("settings.header_color"));
+        ui.strong(tr("settings.default_width"));
+        ui.strong(tr("settings.corner_radius"));
+        ui.strong(tr("settings.icon"));
+        ui.label("");
+        ui.end_row();
+
+        for category in imortal_core::ComponentCategory::all() {
+            let mut edit = overrides.get(*category).cloned().unwrap_or_default();
+            let before = edit.clone();
+            let name = i18n::lookup(&format!("category.{:?}", category).to_lowercase()).unwrap_or(category.display_name());
+            ui.label(format!("{} {}", category.icon(), name));
+
+            let mut color = overrides.header_color(*category);
+            if ui.color_edit_button_srgba(&mut color).changed() {
+                edit.header_color = Some([color.r(), color.g(), color.b()]);
+            }
+            let mut width = edit.default_width.unwrap_or(imortal_core::Size::default_component().width);
+            if ui.add(egui::DragValue::new(&mut width).range(Node::MIN_WIDTH..=Node::MAX_WIDTH).speed(5.0)).changed() {
+                edit.default_width = Some(width);
+            }
+            let mut radius = edit.corner_radius.unwrap_or(5.0);
+            if ui.add(egui::DragValue::new(&mut radius).range(0.0..=20.0).speed(0.5)).changed() {
+                edit.corner_radius = Some(radius);
+            }
+            let mut icon = edit.icon.clone().unwrap_or_default();
+            if ui.add(egui::TextEdit::singleline(&mut icon).desired_width(60.0).hint_text(category.icon())).changed() {
+                edit.icon = Some(icon.trim().to_string()).filter(|icon| !icon.is_empty());
+            }
+            if ui.add_enabled(!edit.is_empty(), egui::Button::new(tr("settings.reset"))).clicked() {
+                edit = CategoryOverride::default();
+            }
+            ui.end_row();
+
+            if edit != before {
+                changed = true;
+                if edit.is_empty() {
+                    overrides.categories.remove(category);
+                } else {
+                    overrides.categories.insert(*category, edit);
+                }
+            }
+        }
+    });
+    changed
+}
+
+/// Name of a swimlane orientation in the background layers window
+fn lane_orientation_name(orientation: LaneOrientation) -> &'static str {
+    match orientation {
+        LaneOrientation::Horizontal => tr("backgrounds.horizontal"),
+        LaneOrientation::Vertical => tr("backgrounds.vertical"),
+    }
+}
+
+/// Category chips, then chips for the tags most common among `matches`
+/// (and any already selected), narrowing the palette when toggled
+fn render_palette_chips(
+    ui: &mut egui::Ui,
+    registry: &ComponentRegistry,
+    matches: &[&ComponentDefinition],
+    filter: &mut imortal_components::SearchFilter,
+) {
+    const TAG_CHIPS: usize = 8;
+
+    ui.horizontal_wrapped(|ui| {
+        ui.spacing_mut().item_spacing.x = 2.0;
+        for category in imortal_core::ComponentCategory::all() {
+            if registry.by_category(*category).is_empty() {
+                continue;
+            }
+            let name = i18n::lookup(&format!("category.{:?}", category).to_lowercase()).unwrap_or(category.display_name());
+            if ui.selectable_label(filter.categories.contains(category), category.icon()).on_hover_text(name).clicked() {
+                filter.toggle_category(*category);
+            }
+        }
+    });
+
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for tag in matches.iter().flat_map(|c| &c.tags) {
+        *counts.entry(tag.to_lowercase()).or_default() += 1;
+    }
+    let mut tags: Vec<(String, usize)> = counts.into_iter().filter(|(tag, _)| !filter.tags.contains(tag)).collect();
+    tags.sort_by(|(a, a_count), (b, b_count)| b_count.cmp(a_count).then(a.cmp(b)));
+    let mut selected: Vec<String> = filter.tags.iter().cloned().collect();
+    selected.sort();
+    let shown = selected.iter().map(|tag| (tag.clone(), true)).chain(tags.into_iter().take(TAG_CHIPS).map(|(tag, _)| (tag, false)));
+
+    ui.horizontal_wrapped(|ui| {
+        ui.spacing_mut().item_spacing.x = 2.0;
+        for (tag, on) in shown {
+            if ui.selectable_label(on, egui::RichText::new(format!("#{}", tag)).small()).clicked() {
+                filter.toggle_tag(&tag);
+            }
+        }
+        if !filter.is_empty() && ui.small_button("✕").on_hover_text(tr("palette.clear_filters")).clicked() {
+            *filter = Default::default();
+        }
+    });
+}
+
+/// Name of a collision decision in the import dialog
+fn import_collision_name(collision: ImportCollision) -> &'static str {
+    match collision {
+        ImportCollision::Merge => tr("import.merge"),
+        ImportCollision::Replace => tr("import.replace"),
+        ImportCollision::Skip => tr("import.skip"),
+        ImportCollision::Rename => tr("import.rename"),
+    }
+}
+
+fn import_collision_hint(collision: ImportCollision) -> &'static str {
+    match collision {
+        ImportCollision::Merge => tr("import.merge_hint"),
+        ImportCollision::Replace => tr("import.replace_hint"),
+        ImportCollision::Skip => tr("import.skip_hint"),
+        ImportCollision::Rename => tr("import.rename_hint"),
+    }
+}
+
+/// Draw canvas text `size` tall at zoom 1, sized for crisp glyphs at the
+/// current zoom and DPI; left out when too small to read
+fn canvas_text(
+    painter: &egui::Painter,
+    pos: egui::Pos2,
+    anchor: egui::Align2,
+    text: impl ToString,
+    size: f32,
+    zoom: f32,
+    color: egui::Color32,
+) {
+    if let Some(size) = imortal_render::font_size(size, zoom, painter.ctx().pixels_per_point()) {
+        painter.text(pos, anchor, text, egui::FontId::proportional(size), color);
+    }
+}
+
+/// Folder user component definitions are loaded from
+fn components_dir() -> Option<PathBuf> {
+    dirs::config_dir().map(|p| p.join("immortal-engine").join("components"))
+}
+
+/// Folder recorded macros are saved in
+fn macros_dir() -> Option<PathBuf> {
+    dirs::config_dir().map(|p| p.join("immortal-engine").join("macros"))
+}
+
+/// Generate a project into `output_dir`, keeping protected regions of files
+/// already there; returns the folder, file count, and warnings
+fn generate_to_disk(
+    project: &ProjectGraph,
+    config: GeneratorConfig,
+    output_dir: PathBuf,
+    cx: &TaskContext<TaskMessage>,
+) -> EngineResult<(PathBuf, usize, Vec<String>)> {
+    let generator = CodeGenerator::with_config(config);
+    cx.progress(0.1, tr("generate.generating"));
+    // Runs the project's hooks, and stops before touching any files if cancelled
+    let generated = generator.generate_to_with(project, &output_dir, || cx.is_cancelled())?;
+    Ok((output_dir, generated.file_count(), generated.warnings))
+}
+
+/// How long ago a Unix timestamp in milliseconds was, e.g. "5 min ago"
+fn time_ago(timestamp: u64) -> String {
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0);
+    let minutes = now.saturating_sub(timestamp) / 60_000;
+    match minutes {
+        0 => tr("history.just_now").to_string(),
+        1..60 => trf("history.minutes_ago", &[&minutes]),
+        60..1440 => trf("history.hours_ago", &[&(minutes / 60)]),
+        _ => trf("history.days_ago", &[&(minutes / 1440)]),
+    }
+}
+
+/// Sanitize a project name for use as a directory/file name
+fn sanitize_project_name(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
 }
@@ -0,0 +1,169 @@
+//! Background layers beneath the canvas content
+//!
+//! A project's visible [`BackgroundLayer`]s are drawn over the grid and
+//! under edges, nodes, and groups, first layer lowest. Image layers are
+//! decoded once and kept as textures until their data changes; swimlanes
+//! run across the whole canvas with their labels pinned to its edge.
+
+use std::collections::HashMap;
+
+use eframe::egui;
+use imortal_ir::{BackgroundKind, BackgroundLayer, LaneOrientation, Size};
+use uuid::Uuid;
+
+use super::CanvasGeometry;
+
+/// Decode PNG data into an image egui can upload
+pub fn decode_image(data: &[u8]) -> image::ImageResult<egui::ColorImage> {
+    let image = image::load_from_memory_with_format(data, image::ImageFormat::Png)?.to_rgba8();
+    let size = [image.width() as usize, image.height() as usize];
+    Ok(egui::ColorImage::from_rgba_unmultiplied(size, image.as_raw()))
+}
+
+/// An image layer at `position`, at the image's own size in canvas units
+pub fn image_layer(name: impl Into<String>, data: Vec<u8>, position: imortal_ir::Position) -> image::ImageResult<BackgroundLayer> {
+    let [width, height] = decode_image(&data)?.size;
+    let mut layer = BackgroundLayer::image(name, data, Size::new(width as f32, height as f32));
+    if let BackgroundKind::Image { position: at, .. } = &mut layer.kind {
+        *at = position;
+    }
+    Ok(layer)
+}
+
+/// Screen rect of a lane running from `from` to `to` across the lanes,
+/// stretched along them to fill `clip`
+pub fn lane_rect(geometry: &CanvasGeometry, clip: egui::Rect, orientation: LaneOrientation, from: f32, to: f32) -> egui::Rect {
+    match orientation {
+        LaneOrientation::Horizontal => egui::Rect::from_x_y_ranges(
+            clip.x_range(),
+            geometry.origin.y + from * geometry.zoom..=geometry.origin.y + to * geometry.zoom,
+        ),
+        LaneOrientation::Vertical => egui::Rect::from_x_y_ranges(
+            geometry.origin.x + from * geometry.zoom..=geometry.origin.x + to * geometry.zoom,
+            clip.y_range(),
+        ),
+    }
+}
+
+/// Textures of image layers, by layer
+#[derive(Default)]
+pub struct BackgroundTextures {
+    /// Texture, or `None` when the data didn't decode, with the address and
+    /// length of the data it was made from
+    textures: HashMap<Uuid, ((usize, usize), Option<egui::TextureHandle>)>,
+}
+
+impl BackgroundTextures {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Texture of an image layer, decoding `data` when it is new or changed
+    fn texture(&mut self, ctx: &egui::Context, id: Uuid, data: &[u8]) -> Option<&egui::TextureHandle> {
+        // Comparing the buffer itself every frame would cost as much as
+        // decoding it; edits replace the buffer, so its address will do
+        let key = (data.as_ptr() as usize, data.len());
+        let entry = self.textures.entry(id).or_insert_with(|| ((0, 0), None));
+        if entry.0 != key {
+            let texture = match decode_image(data) {
+                Ok(image) => Some(ctx.load_texture(format!("background-{}", id), image, egui::TextureOptions::LINEAR)),
+                Err(e) => {
+                    tracing::warn!("Failed to decode background image: {}", e);
+                    None
+                }
+            };
+            *entry = (key, texture);
+        }
+        entry.1.as_ref()
+    }
+
+    /// Draw the visible layers, dropping textures of layers that are gone
+    pub fn paint(&mut self, painter: &egui::Painter, geometry: &CanvasGeometry, layers: &[BackgroundLayer]) {
+        self.textures.retain(|id, _| layers.iter().any(|layer| layer.id == *id));
+        let clip = painter.clip_rect();
+
+        for layer in layers.iter().filter(|layer| layer.visible) {
+            let opacity = layer.opacity.clamp(0.0, 1.0);
+            match &layer.kind {
+                BackgroundKind::Image { data, position, size } => {
+                    let rect = egui::Rect::from_min_size(
+                        geometry.to_screen(*position),
+                        egui::vec2(size.width, size.height) * geometry.zoom,
+                    );
+                    if !rect.intersects(clip) {
+                        continue;
+                    }
+                    if let Some(texture) = self.texture(painter.ctx(), layer.id, data) {
+                        let uv = egui::Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0));
+                        painter.image(texture.id(), rect, uv, egui::Color32::WHITE.gamma_multiply(opacity));
+                    }
+                }
+                BackgroundKind::Swimlanes { orientation, .. } => {
+                    for (lane, from, to) in layer.lane_bounds() {
+                        let rect = lane_rect(geometry, clip, *orientation, from, to);
+                        if !rect.intersects(clip) {
+                            continue;
+                        }
+                        let (r, g, b) = lane.color.rgb();
+                        let color = egui::Color32::from_rgb(r, g, b);
+                        painter.rect_filled(rect, 0.0, color.gamma_multiply(opacity * 0.4));
+
+                        let stroke = egui::Stroke::new(1.0, color.gamma_multiply(opacity));
+                        let (start, label_pos) = match orientation {
+                            LaneOrientation::Horizontal => (
+                                [rect.left_top(), rect.right_top()],
+                                egui::pos2(clip.min.x + 8.0, rect.min.y.max(clip.min.y) + 6.0),
+                            ),
+                            LaneOrientation::Vertical => (
+                                [rect.left_top(), rect.left_bottom()],
+                                egui::pos2(rect.min.x.max(clip.min.x) + 8.0, clip.min.y + 6.0),
+                            ),
+                        };
+                        painter.line_segment(start, stroke);
+                        painter.text(
+                            label_pos,
+                            egui::Align2::LEFT_TOP,
+                            &lane.label,
+                            egui::FontId::proportional(14.0),
+                            egui::Color32::WHITE.gamma_multiply(opacity.max(0.3)),
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use imortal_ir::{Position, ProjectGraph};
+
+    #[test]
+    fn test_image_layer_and_lane_rects() {
+        let mut png = Vec::new();
+        image::RgbaImage::new(4, 2)
+            .write_to(&mut std::io::Cursor::new(&mut png), image::ImageFormat::Png)
+            .unwrap();
+        let layer = image_layer("Diagram", png, Position::new(10.0, 20.0)).unwrap();
+        match &layer.kind {
+            BackgroundKind::Image { position, size, .. } => {
+                assert_eq!(*position, Position::new(10.0, 20.0));
+                assert_eq!(*size, Size::new(4.0, 2.0));
+            }
+            other => panic!("expected an image layer, got {:?}", other),
+        }
+        assert!(image_layer("Broken", b"not a png".to_vec(), Position::zero()).is_err());
+
+        let mut project = ProjectGraph::with_name("test");
+        project.viewport.zoom = 2.0;
+        let clip = egui::Rect::from_min_size(egui::Pos2::ZERO, egui::vec2(800.0, 600.0));
+        let geometry = CanvasGeometry::new(clip, &project);
+        let row = lane_rect(&geometry, clip, LaneOrientation::Horizontal, 100.0, 250.0);
+        assert_eq!((row.min.y, row.max.y), (geometry.origin.y + 200.0, geometry.origin.y + 500.0));
+        assert_eq!(row.x_range(), clip.x_range());
+        let column = lane_rect(&geometry, clip, LaneOrientation::Vertical, 0.0, 50.0);
+        assert_eq!(column.width(), 100.0);
+        assert_eq!(column.y_range(), clip.y_range());
+    }
+}
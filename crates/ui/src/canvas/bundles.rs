@@ -0,0 +1,167 @@
+//! Edge bundling for dense graphs
+//!
+//! With View > Bundle Edges on, edges running between the same two regions
+//! of the canvas are drawn as one thicker path with a count badge. The
+//! canvas is cut into square regions; a bundle forms once
+//! [`MIN_BUNDLE_EDGES`] edges join the same pair. A bundle shows its edges
+//! again while it is hovered or one of its edges or end nodes is selected.
+
+use std::collections::BTreeMap;
+
+use eframe::egui;
+use imortal_ir::{Edge, EdgeId, Node, NodeId, ProjectGraph};
+
+use super::{distance_to_segment, CanvasGeometry};
+
+/// Side of a bundling region, in canvas units
+pub const REGION_SIZE: f32 = 400.0;
+
+/// Fewest edges between two regions that are bundled
+pub const MIN_BUNDLE_EDGES: usize = 3;
+
+/// Pointer distance within which a bundle counts as hovered, in screen pixels
+const HOVER_DISTANCE: f32 = 10.0;
+
+/// Color bundles are drawn in
+const BUNDLE_COLOR: egui::Color32 = egui::Color32::from_rgb(140, 150, 175);
+
+/// Column and row of a region
+type Region = (i32, i32);
+
+/// Region of the canvas a node's center falls in
+fn region(node: &Node) -> Region {
+    let (x, y, width, height) = node.effective_bounds();
+    (((x + width / 2.0) / REGION_SIZE).floor() as i32, ((y + height / 2.0) / REGION_SIZE).floor() as i32)
+}
+
+/// Edges drawn together between two regions
+#[derive(Debug, Clone, PartialEq)]
+pub struct EdgeBundle {
+    pub edges: Vec<EdgeId>,
+    /// Nodes the edges start from
+    pub sources: Vec<NodeId>,
+    /// Nodes the edges end at
+    pub targets: Vec<NodeId>,
+}
+
+impl EdgeBundle {
+    /// Bundles of the edges between nodes for which `shown` holds
+    pub fn find(project: &ProjectGraph, shown: impl Fn(&Node) -> bool) -> Vec<EdgeBundle> {
+        let mut by_regions: BTreeMap<(Region, Region), Vec<&Edge>> = BTreeMap::new();
+        for edge in project.edges.values() {
+            let (Some(from), Some(to)) = (project.get_node(edge.from_node), project.get_node(edge.to_node)) else {
+                continue;
+            };
+            let regions = (region(from), region(to));
+            if shown(from) && shown(to) && regions.0 != regions.1 {
+                by_regions.entry(regions).or_default().push(edge);
+            }
+        }
+
+        by_regions
+            .into_values()
+            .filter(|edges| edges.len() >= MIN_BUNDLE_EDGES)
+            .map(|mut edges| {
+                edges.sort_by_key(|edge| edge.id);
+                let mut bundle = EdgeBundle { edges: Vec::new(), sources: Vec::new(), targets: Vec::new() };
+                for edge in edges {
+                    bundle.edges.push(edge.id);
+                    if !bundle.sources.contains(&edge.from_node) {
+                        bundle.sources.push(edge.from_node);
+                    }
+                    if !bundle.targets.contains(&edge.to_node) {
+                        bundle.targets.push(edge.to_node);
+                    }
+                }
+                bundle
+            })
+            .collect()
+    }
+
+    /// Screen points of the bundle's path, from the middle of its sources'
+    /// output ports to the middle of its targets' input ports
+    pub fn points(&self, project: &ProjectGraph, geometry: &CanvasGeometry) -> Vec<egui::Pos2> {
+        let center = |ids: &[NodeId], port: &dyn Fn(&Node) -> egui::Pos2| {
+            let ports: Vec<egui::Pos2> = ids.iter().filter_map(|id| project.get_node(*id)).map(port).collect();
+            let sum = ports.iter().fold(egui::Vec2::ZERO, |sum, p| sum + p.to_vec2());
+            (sum / ports.len().max(1) as f32).to_pos2()
+        };
+        let start = center(&self.sources, &|node| geometry.output_port_pos(node));
+        let end = center(&self.targets, &|node| geometry.input_port_pos(node));
+        CanvasGeometry::curve_points(start, end)
+    }
+
+    /// Whether the bundle's edges are drawn one by one: it is hovered, or
+    /// one of its edges or end nodes is selected
+    pub fn expanded(&self, project: &ProjectGraph, points: &[egui::Pos2], pointer: Option<egui::Pos2>, hovered_edge: Option<EdgeId>) -> bool {
+        let selected = self.edges.iter().any(|id| project.edges.get(id).is_some_and(|e| e.selected) || hovered_edge == Some(*id))
+            || self.sources.iter().chain(&self.targets).any(|id| project.selected_nodes.contains(id));
+        let hovered = pointer.is_some_and(|pos| {
+            points.windows(2).any(|segment| distance_to_segment(pos, segment[0], segment[1]) <= HOVER_DISTANCE)
+        });
+        selected || hovered
+    }
+
+    /// Draw the bundle as one path, thicker the more edges it holds, with
+    /// their count at its middle
+    pub fn paint(&self, painter: &egui::Painter, points: &[egui::Pos2], zoom: f32) {
+        let width = (1.5 + (self.edges.len() as f32).sqrt() * 1.5) * zoom.max(0.5);
+        painter.add(egui::Shape::line(points.to_vec(), egui::Stroke::new(width, BUNDLE_COLOR.gamma_multiply(0.7))));
+
+        let middle = points[points.len() / 2];
+        let radius = 9.0 * zoom.clamp(0.6, 1.5);
+        painter.circle(middle, radius, egui::Color32::from_rgb(45, 48, 60), egui::Stroke::new(1.5, BUNDLE_COLOR));
+        painter.text(
+            middle,
+            egui::Align2::CENTER_CENTER,
+            self.edges.len().to_string(),
+            egui::FontId::proportional(radius * 1.1),
+            egui::Color32::WHITE,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node_at(project: &mut ProjectGraph, name: &str, x: f32, y: f32) -> NodeId {
+        let mut node = Node::new_entity(name);
+        node.position.x = x;
+        node.position.y = y;
+        project.add_node(node)
+    }
+
+    #[test]
+    fn test_bundles_between_regions() {
+        let mut project = ProjectGraph::with_name("test");
+        let left: Vec<NodeId> = (0..3).map(|i| node_at(&mut project, &format!("L{}", i), 0.0, i as f32 * 100.0)).collect();
+        let right: Vec<NodeId> = (0..2).map(|i| node_at(&mut project, &format!("R{}", i), 1000.0, i as f32 * 100.0)).collect();
+        let far = node_at(&mut project, "Far", 0.0, 2000.0);
+        for (i, from) in left.iter().enumerate() {
+            project.add_edge(Edge::dependency(*from, right[i % 2])).unwrap();
+        }
+        // Too few to bundle, and within a single region
+        project.add_edge(Edge::dependency(left[0], far)).unwrap();
+        project.add_edge(Edge::dependency(left[0], left[1])).unwrap();
+
+        let bundles = EdgeBundle::find(&project, |_| true);
+        assert_eq!(bundles.len(), 1);
+        assert_eq!(bundles[0].edges.len(), 3);
+        assert_eq!(bundles[0].sources.len(), 3);
+        assert_eq!(bundles[0].targets.len(), 2);
+
+        // Hidden nodes leave too few edges to bundle
+        assert!(EdgeBundle::find(&project, |node| node.name != "L2").is_empty());
+
+        let rect = egui::Rect::from_min_size(egui::Pos2::ZERO, egui::vec2(2000.0, 1000.0));
+        let geometry = CanvasGeometry::new(rect, &project);
+        let points = bundles[0].points(&project, &geometry);
+        let middle = points[points.len() / 2];
+        assert!(!bundles[0].expanded(&project, &points, None, None));
+        assert!(bundles[0].expanded(&project, &points, Some(middle + egui::vec2(0.0, 5.0)), None));
+        assert!(bundles[0].expanded(&project, &points, None, Some(bundles[0].edges[1])));
+        project.select_node(right[1]);
+        assert!(bundles[0].expanded(&project, &points, None, None));
+    }
+}
@@ -0,0 +1,392 @@
+//! Embeddable graph editor
+//!
+//! [`GraphEditorState`] holds a project together with everything needed to
+//! edit it interactively: undo history, the connection being drawn, and the
+//! drag in progress. [`CanvasWidget::show_editor`] draws it and applies
+//! pointer and keyboard input, so another egui application can host a full
+//! editor without the rest of [`ImmortalApp`](crate::ImmortalApp):
+//!
+//! ```no_run
+//! use eframe::egui;
+//! use imortal_ir::{Node, ProjectGraph};
+//! use imortal_ui::canvas::{CanvasWidget, GraphEditorState};
+//!
+//! let mut project = ProjectGraph::with_name("shop");
+//! project.add_node(Node::new_entity("User"));
+//! let mut editor = GraphEditorState::new(project);
+//!
+//! # fn frame(ctx: &egui::Context, editor: &mut GraphEditorState) {
+//! egui::CentralPanel::default().show(ctx, |ui| {
+//!     let response = CanvasWidget::new().show_editor(ui, editor);
+//!     if response.changed {
+//!         // Save or re-validate editor.project()
+//!     }
+//! });
+//! # }
+//! ```
+//!
+//! Selecting, moving, resizing, connecting, deleting, and undo/redo are
+//! handled here. Anything that needs the component registry, such as
+//! components dropped from a palette, is reported in the response for the
+//! host to handle, usually with [`GraphEditorState::add_node`].
+
+use eframe::egui;
+use imortal_ir::{Edge, EdgeId, Node, NodeId, ProjectGraph};
+
+use super::{CanvasGeometry, CanvasResponse, CanvasWidget, PortHit};
+use crate::state::History;
+
+/// A project being edited in a [`CanvasWidget`], with its undo history
+#[derive(Debug)]
+pub struct GraphEditorState {
+    project: ProjectGraph,
+    history: History,
+    /// Port a connection is being drawn from
+    connecting_from: Option<PortHit>,
+    /// A drag is moving the selection; its undo state is already saved
+    moving: bool,
+    /// Node whose width a drag is changing
+    resizing: Option<NodeId>,
+}
+
+/// What happened in a [`GraphEditorState`] this frame
+#[derive(Debug, Clone, Default)]
+pub struct GraphEditorResponse {
+    /// Raw canvas interaction, for anything the editor doesn't handle itself
+    pub canvas: CanvasResponse,
+    /// Whether nodes or connections changed, including by undo and redo;
+    /// panning and zooming don't count
+    pub changed: bool,
+    /// Connection created this frame
+    pub connected: Option<EdgeId>,
+    /// Number of nodes and connections deleted this frame
+    pub deleted: usize,
+}
+
+impl GraphEditorState {
+    /// Edit `project`, starting with an empty history
+    pub fn new(project: ProjectGraph) -> Self {
+        Self::with_history(project, History::new())
+    }
+
+    /// Edit `project` with a history of a custom size or with earlier entries
+    pub fn with_history(project: ProjectGraph, history: History) -> Self {
+        Self { project, history, connecting_from: None, moving: false, resizing: None }
+    }
+
+    pub fn project(&self) -> &ProjectGraph {
+        &self.project
+    }
+
+    /// Change the project directly; call [`save_undo_state`](Self::save_undo_state)
+    /// first to make the change undoable
+    pub fn project_mut(&mut self) -> &mut ProjectGraph {
+        &mut self.project
+    }
+
+    /// Stop editing and take the project back
+    pub fn into_project(self) -> ProjectGraph {
+        self.project
+    }
+
+    pub fn history(&self) -> &History {
+        &self.history
+    }
+
+    /// Remember the current project so the next change can be undone
+    pub fn save_undo_state(&mut self, action_name: &str) {
+        self.history.push(action_name, self.project.clone());
+    }
+
+    /// Undo the last change; returns false if there was nothing to undo
+    pub fn undo(&mut self) -> bool {
+        match self.history.undo(self.project.clone()) {
+            Some(previous) => {
+                self.project = previous;
+                self.cancel_connection();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Redo the last undone change; returns false if there was nothing to redo
+    pub fn redo(&mut self) -> bool {
+        match self.history.redo(self.project.clone()) {
+            Some(next) => {
+                self.project = next;
+                self.cancel_connection();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Add a node, undoably
+    pub fn add_node(&mut self, node: Node) -> NodeId {
+        self.save_undo_state(&format!("Add {}", node.name));
+        self.project.add_node(node)
+    }
+
+    /// Delete the selected nodes and connections, undoably; returns how many
+    pub fn delete_selection(&mut self) -> usize {
+        let count = self.project.selected_nodes.len() + self.project.selected_edges.len();
+        if count > 0 {
+            self.save_undo_state(&format!("Delete {} item(s)", count));
+            self.project.delete_selected();
+        }
+        count
+    }
+
+    /// Port a connection is being drawn from
+    pub fn connecting_from(&self) -> Option<&PortHit> {
+        self.connecting_from.as_ref()
+    }
+
+    pub fn is_connecting(&self) -> bool {
+        self.connecting_from.is_some()
+    }
+
+    /// Stop drawing a connection
+    pub fn cancel_connection(&mut self) {
+        self.connecting_from = None;
+    }
+
+    /// Connect the node of one port to the node of another, output to input
+    ///
+    /// Creates a dependency edge, which needs no matching port types.
+    pub fn connect(&mut self, from: &PortHit, to: &PortHit) -> Option<EdgeId> {
+        if from.node_id == to.node_id {
+            return None;
+        }
+        let (source, target) = if from.is_output { (from.node_id, to.node_id) } else { (to.node_id, from.node_id) };
+        let before = self.project.clone();
+        match self.project.add_edge(Edge::dependency(source, target)) {
+            Ok(id) => {
+                self.history.push("Create connection", before);
+                Some(id)
+            }
+            Err(e) => {
+                tracing::debug!("Connection rejected: {}", e);
+                None
+            }
+        }
+    }
+
+    /// Apply one frame of canvas interaction: selection, moving, resizing,
+    /// and connecting
+    pub fn apply(&mut self, canvas: &CanvasResponse, shift_held: bool) -> GraphEditorResponse {
+        let mut response = GraphEditorResponse::default();
+
+        if let Some(node_id) = canvas.toggled_node {
+            self.save_undo_state("Toggle node details");
+            if let Some(node) = self.project.get_node_mut(node_id) {
+                node.toggle_collapsed();
+                response.changed = true;
+            }
+        }
+
+        if let Some(node_id) = canvas.clicked_node {
+            if !shift_held {
+                self.project.clear_selection();
+                self.project.select_node(node_id);
+            } else if self.project.selected_nodes.contains(&node_id) {
+                self.project.deselect_node(node_id);
+            } else {
+                self.project.select_node(node_id);
+            }
+        }
+        if let Some(edge_id) = canvas.clicked_edge {
+            if shift_held && self.project.selected_edges.contains(&edge_id) {
+                self.project.deselect_edge(edge_id);
+            } else {
+                if !shift_held {
+                    self.project.clear_selection();
+                }
+                self.project.select_edge(edge_id);
+            }
+        }
+        if canvas.clicked_background && !shift_held {
+            self.project.clear_selection();
+        }
+
+        // Ports: the first click or drag starts a connection, the next completes it
+        if let Some(port) = &canvas.clicked_port {
+            match self.connecting_from.take() {
+                Some(from) if canvas.connection_completed => response.connected = self.connect(&from, port),
+                _ if canvas.connection_started => self.connecting_from = Some(port.clone()),
+                _ => {}
+            }
+        }
+        if canvas.connection_dropped.is_some() || (self.is_connecting() && canvas.secondary_clicked) {
+            self.cancel_connection();
+        }
+        response.changed |= response.connected.is_some();
+
+        if let Some(node_id) = canvas.resize_started {
+            self.save_undo_state("Resize node");
+            self.resizing = Some(node_id);
+        }
+        if let Some(delta) = canvas.drag_delta {
+            if let Some(node_id) = self.resizing {
+                // Resize width only; height follows the node's content
+                if let Some(node) = self.project.get_node_mut(node_id) {
+                    node.set_width(node.size.width + delta.x);
+                }
+            } else {
+                // Dragging an unselected node moves just that node
+                if !self.moving {
+                    if let Some(node_id) = canvas.hovered_node.filter(|id| !self.project.selected_nodes.contains(id)) {
+                        self.project.clear_selection();
+                        self.project.select_node(node_id);
+                    }
+                    if !self.project.selected_nodes.is_empty() {
+                        self.save_undo_state("Move nodes");
+                        self.moving = true;
+                    }
+                }
+                let selected: Vec<NodeId> = self.project.selected_nodes.iter().copied().collect();
+                for node_id in selected {
+                    if let Some(node) = self.project.get_node_mut(node_id) {
+                        node.position.x += delta.x;
+                        node.position.y += delta.y;
+                    }
+                }
+            }
+            response.changed = true;
+        }
+        if canvas.drag_stopped {
+            self.moving = false;
+            self.resizing = None;
+        }
+
+        response.canvas = canvas.clone();
+        response
+    }
+}
+
+impl CanvasWidget {
+    /// Draw an editable project and apply the user's edits to it
+    ///
+    /// Besides what [`apply`](GraphEditorState::apply) handles, Delete or
+    /// Backspace deletes the selection, Ctrl+Z undoes, Ctrl+Shift+Z or Ctrl+Y
+    /// redoes, and Escape cancels a connection. Keys are ignored while
+    /// another widget has keyboard focus.
+    pub fn show_editor(&mut self, ui: &mut egui::Ui, state: &mut GraphEditorState) -> GraphEditorResponse {
+        // Store content-driven heights so hit-testing sees the drawn size
+        for node in state.project.nodes.values_mut() {
+            node.fit_to_content();
+        }
+
+        self.connecting = state.is_connecting();
+        let rect = ui.available_rect_before_wrap();
+        let canvas = self.show(ui, &mut state.project);
+        let shift_held = ui.input(|i| i.modifiers.shift);
+        let mut response = state.apply(&canvas, shift_held);
+
+        if self.editable && !ui.ctx().wants_keyboard_input() {
+            let (delete, escape, undo, redo) = ui.input(|i| {
+                let command = i.modifiers.command;
+                (
+                    i.key_pressed(egui::Key::Delete) || i.key_pressed(egui::Key::Backspace),
+                    i.key_pressed(egui::Key::Escape),
+                    command && !i.modifiers.shift && i.key_pressed(egui::Key::Z),
+                    command && ((i.modifiers.shift && i.key_pressed(egui::Key::Z)) || i.key_pressed(egui::Key::Y)),
+                )
+            });
+            if escape {
+                state.cancel_connection();
+            } else if delete && !state.is_connecting() {
+                response.deleted = state.delete_selection();
+            }
+            if (undo && state.undo()) || (redo && state.redo()) || response.deleted > 0 {
+                response.changed = true;
+            }
+        }
+
+        // Rubber band from the port a connection is being drawn from
+        if let (Some(from), Some(pointer)) = (&state.connecting_from, canvas.pointer_pos) {
+            let geometry = CanvasGeometry::new(rect, &state.project).with_namespace(&self.namespace);
+            if let Some(node) = state.project.get_node(from.node_id) {
+                let start = if from.is_output { geometry.output_port_pos(node) } else { geometry.input_port_pos(node) };
+                let stroke = egui::Stroke::new(self.config.edge_width, self.config.edge_selected_color);
+                ui.painter_at(rect).line_segment([start, pointer], stroke);
+            }
+        }
+        response
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn port(node_id: NodeId, is_output: bool) -> PortHit {
+        let port = if is_output { "output" } else { "input" };
+        PortHit { node_id, port: port.to_string(), is_output }
+    }
+
+    fn editor() -> (GraphEditorState, NodeId, NodeId) {
+        let mut project = ProjectGraph::with_name("shop");
+        let user = project.add_node(Node::new_entity("User"));
+        let order = project.add_node(Node::new_entity("Order"));
+        (GraphEditorState::new(project), user, order)
+    }
+
+    #[test]
+    fn test_select_and_drag_is_one_undo_step() {
+        let (mut editor, user, _) = editor();
+        let start = editor.project().get_node(user).unwrap().position;
+
+        // Dragging an unselected node selects and moves it
+        let drag = CanvasResponse { hovered_node: Some(user), drag_delta: Some(egui::vec2(10.0, 5.0)), ..Default::default() };
+        assert!(editor.apply(&drag, false).changed);
+        editor.apply(&drag, false);
+        editor.apply(&CanvasResponse { drag_stopped: true, ..Default::default() }, false);
+
+        assert!(editor.project().selected_nodes.contains(&user));
+        let moved = editor.project().get_node(user).unwrap().position;
+        assert_eq!((moved.x, moved.y), (start.x + 20.0, start.y + 10.0));
+
+        assert!(editor.undo());
+        let restored = editor.project().get_node(user).unwrap().position;
+        assert_eq!((restored.x, restored.y), (start.x, start.y));
+        assert!(!editor.undo());
+        assert!(editor.redo());
+    }
+
+    #[test]
+    fn test_connect_ports() {
+        let (mut editor, user, order) = editor();
+
+        // Starting from an input port makes the other node the source
+        let start = CanvasResponse { clicked_port: Some(port(order, false)), connection_started: true, ..Default::default() };
+        editor.apply(&start, false);
+        assert_eq!(editor.connecting_from(), Some(&port(order, false)));
+
+        let finish = CanvasResponse { clicked_port: Some(port(user, true)), connection_completed: true, ..Default::default() };
+        let response = editor.apply(&finish, false);
+        let edge = editor.project().get_edge(response.connected.unwrap()).unwrap();
+        assert_eq!((edge.from_node, edge.to_node), (user, order));
+        assert!(response.changed && !editor.is_connecting());
+
+        // A node can't be connected to itself
+        assert_eq!(editor.connect(&port(user, true), &port(user, false)), None);
+
+        editor.undo();
+        assert_eq!(editor.project().edge_count(), 0);
+    }
+
+    #[test]
+    fn test_delete_selection() {
+        let (mut editor, user, _) = editor();
+        assert_eq!(editor.delete_selection(), 0);
+        assert!(!editor.history().can_undo());
+
+        editor.apply(&CanvasResponse { clicked_node: Some(user), ..Default::default() }, false);
+        assert_eq!(editor.delete_selection(), 1);
+        assert_eq!(editor.project().node_count(), 1);
+        editor.undo();
+        assert_eq!(editor.into_project().node_count(), 2);
+    }
+}
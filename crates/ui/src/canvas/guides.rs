@@ -0,0 +1,357 @@
+//! Alignment guides and equal spacing for dragged nodes
+//!
+//! While nodes are dragged, [`NodeDrag`] lines the selection up with the
+//! edges and centers of other nodes, and spaces it evenly between its
+//! neighbours, snapping once it comes within a few pixels, much like design
+//! tools do. [`paint`] draws the guides it found over the canvas.
+
+use std::collections::HashMap;
+
+use eframe::egui;
+use imortal_ir::{NodeId, Position, ProjectGraph};
+
+use super::CanvasGeometry;
+
+/// Screen distance within which dragged nodes snap to a guide
+pub const SNAP_DISTANCE: f32 = 6.0;
+
+/// Color guides are drawn in
+const GUIDE_COLOR: egui::Color32 = egui::Color32::from_rgb(255, 90, 160);
+
+/// Direction a guide measures along
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Axis {
+    /// Horizontal positions; alignment guides are vertical lines
+    X,
+    /// Vertical positions; alignment guides are horizontal lines
+    Y,
+}
+
+/// A line the dragged nodes line up with, in canvas units
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AlignmentGuide {
+    pub axis: Axis,
+    /// x of a vertical guide, y of a horizontal one
+    pub position: f32,
+    /// Where the line starts and ends across `axis`
+    pub start: f32,
+    pub end: f32,
+}
+
+/// Equal gaps between the dragged nodes and their neighbours, in canvas units
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpacingGuide {
+    pub axis: Axis,
+    /// Start and end of each gap along `axis`
+    pub gaps: Vec<(f32, f32)>,
+    /// Where across `axis` the gaps are marked
+    pub cross: f32,
+}
+
+/// What the dragged nodes snap to
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Guides {
+    /// Move that lands the dragged nodes on the guides, in canvas units
+    pub offset: egui::Vec2,
+    pub alignments: Vec<AlignmentGuide>,
+    pub spacings: Vec<SpacingGuide>,
+}
+
+impl Guides {
+    pub fn is_empty(&self) -> bool {
+        self.alignments.is_empty() && self.spacings.is_empty()
+    }
+}
+
+/// Find the guides for a rect being dragged among `others`, snapping when
+/// within `tolerance`; everything is in canvas units
+pub fn find(moving: egui::Rect, others: &[egui::Rect], tolerance: f32) -> Guides {
+    let offset = egui::vec2(
+        snap_offset(Axis::X, moving, others, tolerance),
+        snap_offset(Axis::Y, moving, others, tolerance),
+    );
+    let snapped = moving.translate(offset);
+    let mut guides = Guides { offset, ..Guides::default() };
+    for axis in [Axis::X, Axis::Y] {
+        guides.alignments.extend(alignments(axis, snapped, others));
+        guides.spacings.extend(spacings(axis, snapped, others));
+    }
+    guides
+}
+
+/// Start and end of a rect along an axis
+fn span(rect: egui::Rect, axis: Axis) -> (f32, f32) {
+    match axis {
+        Axis::X => (rect.min.x, rect.max.x),
+        Axis::Y => (rect.min.y, rect.max.y),
+    }
+}
+
+fn cross_axis(axis: Axis) -> Axis {
+    match axis {
+        Axis::X => Axis::Y,
+        Axis::Y => Axis::X,
+    }
+}
+
+/// Start, center, and end of a rect along an axis
+fn anchors(rect: egui::Rect, axis: Axis) -> [f32; 3] {
+    let (start, end) = span(rect, axis);
+    [start, (start + end) / 2.0, end]
+}
+
+/// Smallest move along `axis` that aligns or evenly spaces `moving`, or 0.0
+fn snap_offset(axis: Axis, moving: egui::Rect, others: &[egui::Rect], tolerance: f32) -> f32 {
+    let aligned = others.iter().flat_map(|other| {
+        let targets = anchors(*other, axis);
+        anchors(moving, axis).into_iter().flat_map(move |from| targets.map(|to| to - from))
+    });
+    let spaced = spacing_targets(axis, moving, others).into_iter().map(|(start, _)| start - span(moving, axis).0);
+    aligned
+        .chain(spaced)
+        .filter(|offset| offset.abs() <= tolerance)
+        .min_by(|a, b| a.abs().total_cmp(&b.abs()))
+        .unwrap_or(0.0)
+}
+
+/// Nodes in the same row (or column) as `moving`, before and after it along
+/// `axis`, nearest first
+fn neighbours(axis: Axis, moving: egui::Rect, others: &[egui::Rect]) -> (Vec<egui::Rect>, Vec<egui::Rect>) {
+    let (cross_start, cross_end) = span(moving, cross_axis(axis));
+    let (start, end) = span(moving, axis);
+    let middle = (start + end) / 2.0;
+    let row = others.iter().copied().filter(|other| {
+        let (other_start, other_end) = span(*other, cross_axis(axis));
+        other_start < cross_end && other_end > cross_start
+    });
+    let (mut before, mut after): (Vec<_>, Vec<_>) = row.partition(|other| span(*other, axis).1 <= middle);
+    after.retain(|other| span(*other, axis).0 >= middle);
+    before.sort_by(|a, b| span(*b, axis).1.total_cmp(&span(*a, axis).1));
+    after.sort_by(|a, b| span(*a, axis).0.total_cmp(&span(*b, axis).0));
+    (before, after)
+}
+
+/// Positions along `axis` where `moving` would be evenly spaced, with the
+/// gaps that would then be equal
+fn spacing_targets(axis: Axis, moving: egui::Rect, others: &[egui::Rect]) -> Vec<(f32, Vec<(f32, f32)>)> {
+    let (start, end) = span(moving, axis);
+    let size = end - start;
+    let (before, after) = neighbours(axis, moving, others);
+    let mut targets = Vec::new();
+
+    // Centered between the nearest neighbours on either side
+    if let (Some(b), Some(a)) = (before.first(), after.first()) {
+        let (b_end, a_start) = (span(*b, axis).1, span(*a, axis).0);
+        let gap = (a_start - b_end - size) / 2.0;
+        if gap > 0.0 {
+            targets.push((b_end + gap, vec![(b_end, b_end + gap), (a_start - gap, a_start)]));
+        }
+    }
+    // Continuing the gap between the two nearest neighbours on one side
+    if let [near, far, ..] = before.as_slice() {
+        let (near_start, near_end) = span(*near, axis);
+        let gap = near_start - span(*far, axis).1;
+        if gap > 0.0 {
+            targets.push((near_end + gap, vec![(near_start - gap, near_start), (near_end, near_end + gap)]));
+        }
+    }
+    if let [near, far, ..] = after.as_slice() {
+        let (near_start, near_end) = span(*near, axis);
+        let gap = span(*far, axis).0 - near_end;
+        if gap > 0.0 {
+            targets.push((near_start - gap - size, vec![(near_start - gap, near_start), (near_end, near_end + gap)]));
+        }
+    }
+    targets
+}
+
+/// Lines `moving` is aligned with along `axis`, merged per position
+fn alignments(axis: Axis, moving: egui::Rect, others: &[egui::Rect]) -> Vec<AlignmentGuide> {
+    let mut guides: Vec<AlignmentGuide> = Vec::new();
+    for other in others {
+        for position in anchors(moving, axis) {
+            if !anchors(*other, axis).iter().any(|anchor| (anchor - position).abs() < 0.5) {
+                continue;
+            }
+            let (a, b) = (span(moving, cross_axis(axis)), span(*other, cross_axis(axis)));
+            let (start, end) = (a.0.min(b.0), a.1.max(b.1));
+            match guides.iter_mut().find(|g| (g.position - position).abs() < 0.5) {
+                Some(guide) => {
+                    guide.start = guide.start.min(start);
+                    guide.end = guide.end.max(end);
+                }
+                None => guides.push(AlignmentGuide { axis, position, start, end }),
+            }
+        }
+    }
+    guides
+}
+
+/// Equal gaps `moving` sits at along `axis`
+fn spacings(axis: Axis, moving: egui::Rect, others: &[egui::Rect]) -> Vec<SpacingGuide> {
+    let start = span(moving, axis).0;
+    let (cross_start, cross_end) = span(moving, cross_axis(axis));
+    spacing_targets(axis, moving, others)
+        .into_iter()
+        .filter(|(target, _)| (target - start).abs() < 0.5)
+        .map(|(_, gaps)| SpacingGuide { axis, gaps, cross: (cross_start + cross_end) / 2.0 })
+        .collect()
+}
+
+/// A drag of the selected nodes that snaps to guides
+///
+/// The nodes follow the pointer from where the drag started, so snapping
+/// never holds them back once the pointer moves past a guide.
+#[derive(Debug, Clone, Default)]
+pub struct NodeDrag {
+    /// Where each dragged node started
+    origins: HashMap<NodeId, Position>,
+    /// How far the pointer has moved, in canvas units
+    moved: egui::Vec2,
+    guides: Guides,
+}
+
+impl NodeDrag {
+    /// Start dragging the selected nodes
+    pub fn new(project: &ProjectGraph) -> Self {
+        let origins = project.selected_nodes.iter()
+            .filter_map(|id| project.get_node(*id))
+            .map(|node| (node.id, node.position))
+            .collect();
+        Self { origins, ..Self::default() }
+    }
+
+    /// Move the nodes `delta` canvas units further, snapping them to the
+    /// other nodes `geometry` shows when `snap` is set
+    pub fn update(&mut self, project: &mut ProjectGraph, geometry: &CanvasGeometry, delta: egui::Vec2, snap: bool) {
+        self.moved += delta;
+        self.guides = Guides::default();
+        if snap {
+            let bounds = self.origins.iter()
+                .filter_map(|(id, origin)| {
+                    let (_, _, width, height) = project.get_node(*id)?.effective_bounds();
+                    Some(egui::Rect::from_min_size(egui::pos2(origin.x, origin.y), egui::vec2(width, height)))
+                })
+                .reduce(|a, b| a.union(b));
+            if let Some(bounds) = bounds {
+                let others: Vec<egui::Rect> = project.nodes.values()
+                    .filter(|node| geometry.shows(node) && !self.origins.contains_key(&node.id))
+                    .map(|node| {
+                        let (x, y, width, height) = node.effective_bounds();
+                        egui::Rect::from_min_size(egui::pos2(x, y), egui::vec2(width, height))
+                    })
+                    .collect();
+                self.guides = find(bounds.translate(self.moved), &others, SNAP_DISTANCE / geometry.zoom);
+            }
+        }
+
+        let offset = self.moved + self.guides.offset;
+        for (id, origin) in &self.origins {
+            if let Some(node) = project.get_node_mut(*id) {
+                node.position.x = origin.x + offset.x;
+                node.position.y = origin.y + offset.y;
+            }
+        }
+    }
+
+    /// Guides the nodes are currently snapped to
+    pub fn guides(&self) -> &Guides {
+        &self.guides
+    }
+}
+
+/// Draw alignment lines and equal-gap markers over the canvas
+pub fn paint(painter: &egui::Painter, geometry: &CanvasGeometry, guides: &Guides) {
+    let stroke = egui::Stroke::new(1.0, GUIDE_COLOR);
+    let point = |axis: Axis, along: f32, across: f32| match axis {
+        Axis::X => geometry.to_screen(Position::new(along, across)),
+        Axis::Y => geometry.to_screen(Position::new(across, along)),
+    };
+
+    for guide in &guides.alignments {
+        painter.line_segment([point(guide.axis, guide.position, guide.start), point(guide.axis, guide.position, guide.end)], stroke);
+    }
+
+    // Each gap as a line with end ticks, so equal gaps read as equal
+    for spacing in &guides.spacings {
+        for (start, end) in &spacing.gaps {
+            let (a, b) = (point(spacing.axis, *start, spacing.cross), point(spacing.axis, *end, spacing.cross));
+            painter.line_segment([a, b], stroke);
+            let tick = match spacing.axis {
+                Axis::X => egui::vec2(0.0, 4.0),
+                Axis::Y => egui::vec2(4.0, 0.0),
+            };
+            for end in [a, b] {
+                painter.line_segment([end - tick, end + tick], stroke);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use imortal_ir::Node;
+
+    fn rect(x: f32, y: f32) -> egui::Rect {
+        egui::Rect::from_min_size(egui::pos2(x, y), egui::vec2(100.0, 50.0))
+    }
+
+    #[test]
+    fn test_snaps_to_edges_and_centers() {
+        let others = [rect(0.0, 0.0)];
+
+        // Left edges 3 apart, nothing lines up vertically
+        let guides = find(rect(3.0, 200.0), &others, 5.0);
+        assert_eq!(guides.offset, egui::vec2(-3.0, 0.0));
+        assert_eq!(guides.alignments.len(), 3, "left, center, and right all line up");
+        assert_eq!((guides.alignments[0].start, guides.alignments[0].end), (0.0, 250.0));
+
+        // Too far to snap
+        let guides = find(rect(20.0, 200.0), &others, 5.0);
+        assert_eq!(guides.offset, egui::Vec2::ZERO);
+        assert!(guides.is_empty());
+    }
+
+    #[test]
+    fn test_snaps_to_equal_spacing() {
+        // Centered between two nodes 340 apart leaves 120 on either side
+        let between = [rect(0.0, 0.0), rect(440.0, 0.0)];
+        let guides = find(rect(218.0, 3.0), &between, 5.0);
+        assert_eq!(guides.offset, egui::vec2(2.0, -3.0));
+        let spacing = guides.spacings.iter().find(|s| s.axis == Axis::X).unwrap();
+        assert_eq!(spacing.gaps, [(100.0, 220.0), (320.0, 440.0)]);
+
+        // Continuing a row with the same gap
+        let row = [rect(0.0, 0.0), rect(140.0, 0.0)];
+        let guides = find(rect(284.0, 0.0), &row, 5.0);
+        assert_eq!(guides.offset.x, -4.0);
+        assert_eq!(guides.spacings[0].gaps, [(100.0, 140.0), (240.0, 280.0)]);
+    }
+
+    #[test]
+    fn test_drag_follows_pointer_past_guides() {
+        let mut project = ProjectGraph::with_name("guides");
+        let mut anchor = Node::new_entity("Anchor");
+        anchor.position = Position::new(0.0, 0.0);
+        let mut dragged = Node::new_entity("Dragged");
+        dragged.position = Position::new(10.0, 300.0);
+        project.add_node(anchor);
+        let id = project.add_node(dragged);
+        project.select_node(id);
+        let geometry = CanvasGeometry::new(egui::Rect::from_min_size(egui::Pos2::ZERO, egui::vec2(800.0, 600.0)), &project);
+
+        let mut drag = NodeDrag::new(&project);
+        drag.update(&mut project, &geometry, egui::vec2(-8.0, 0.0), true);
+        assert_eq!(project.get_node(id).unwrap().position.x, 0.0);
+        assert!(!drag.guides().is_empty());
+
+        // Snapping didn't eat the movement: 20 more pulls clear of the guide
+        drag.update(&mut project, &geometry, egui::vec2(20.0, 0.0), true);
+        assert_eq!(project.get_node(id).unwrap().position.x, 22.0);
+
+        drag.update(&mut project, &geometry, egui::vec2(-20.0, 0.0), false);
+        assert_eq!(project.get_node(id).unwrap().position.x, 2.0);
+        assert!(drag.guides().is_empty());
+    }
+}
@@ -0,0 +1,148 @@
+//! Heatmap mode
+//!
+//! With View > Heatmap set to a [`NodeMetric`], each node is tinted from
+//! cool to hot by its value relative to the largest in the project and
+//! shows the value in a badge, with a legend in the corner of the canvas.
+//! Overly coupled or oversized components stand out at a glance. Values
+//! are recomputed at most every [`REFRESH_INTERVAL`], as counting
+//! validation issues means validating the whole project.
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use eframe::egui;
+use imortal_ir::{NodeId, NodeMetric, ProjectGraph};
+
+use crate::i18n::tr;
+
+/// How long computed values are reused
+pub const REFRESH_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Gradient stops from the lowest value to the highest
+const GRADIENT: [egui::Color32; 4] = [
+    egui::Color32::from_rgb(60, 110, 200),
+    egui::Color32::from_rgb(80, 190, 140),
+    egui::Color32::from_rgb(240, 200, 70),
+    egui::Color32::from_rgb(220, 70, 60),
+];
+
+/// A metric's values for the nodes of a project
+#[derive(Debug, Clone)]
+pub struct Heatmap {
+    pub metric: NodeMetric,
+    values: HashMap<NodeId, usize>,
+    /// Largest value, the hot end of the scale
+    max: usize,
+    computed: Instant,
+}
+
+impl Heatmap {
+    pub fn compute(project: &ProjectGraph, metric: NodeMetric) -> Self {
+        let values = project.node_metric(metric);
+        let max = values.values().copied().max().unwrap_or(0);
+        Self { metric, values, max, computed: Instant::now() }
+    }
+
+    /// Whether the values are for `metric` and recent enough to reuse
+    pub fn is_current(&self, metric: NodeMetric) -> bool {
+        self.metric == metric && self.computed.elapsed() < REFRESH_INTERVAL
+    }
+
+    pub fn value(&self, id: NodeId) -> Option<usize> {
+        self.values.get(&id).copied()
+    }
+
+    /// Where `value` falls between zero and the largest value
+    pub fn heat(&self, value: usize) -> f32 {
+        if self.max == 0 {
+            0.0
+        } else {
+            (value as f32 / self.max as f32).clamp(0.0, 1.0)
+        }
+    }
+
+    /// Tint a node's rect by its value and badge it with the value
+    pub fn paint_node(&self, painter: &egui::Painter, rect: egui::Rect, value: usize, zoom: f32) {
+        let color = heat_color(self.heat(value));
+        painter.rect_filled(rect, 5.0 * zoom, color.gamma_multiply(0.35));
+        painter.rect_stroke(rect.expand(2.0), 6.0 * zoom, egui::Stroke::new(2.0, color));
+
+        let text = painter.layout_no_wrap(value.to_string(), egui::FontId::proportional(11.0), egui::Color32::BLACK);
+        let badge = egui::Rect::from_center_size(rect.right_top(), text.size() + egui::vec2(10.0, 4.0));
+        painter.rect_filled(badge, badge.height() / 2.0, color);
+        painter.galley(badge.center() - text.size() / 2.0, text, egui::Color32::BLACK);
+    }
+
+    /// The metric's name and a gradient from zero to the largest value, at
+    /// the bottom right of `clip`
+    pub fn paint_legend(&self, painter: &egui::Painter, clip: egui::Rect) {
+        let size = egui::vec2(180.0, 46.0);
+        let frame = egui::Rect::from_min_size(clip.right_bottom() - size - egui::vec2(10.0, 10.0), size);
+        painter.rect_filled(frame, 4.0, egui::Color32::from_black_alpha(180));
+        let text_color = egui::Color32::from_gray(220);
+        let font = egui::FontId::proportional(11.0);
+        painter.text(frame.min + egui::vec2(8.0, 6.0), egui::Align2::LEFT_TOP, metric_name(self.metric), font.clone(), text_color);
+
+        let bar = egui::Rect::from_min_size(frame.min + egui::vec2(8.0, 22.0), egui::vec2(size.x - 16.0, 8.0));
+        let steps = 32;
+        for i in 0..steps {
+            let t = i as f32 / steps as f32;
+            let left = bar.min.x + bar.width() * t;
+            let step = egui::Rect::from_x_y_ranges(left..=left + bar.width() / steps as f32 + 0.5, bar.y_range());
+            painter.rect_filled(step, 0.0, heat_color(t));
+        }
+        painter.text(bar.left_bottom() + egui::vec2(0.0, 2.0), egui::Align2::LEFT_TOP, "0", font.clone(), text_color);
+        painter.text(bar.right_bottom() + egui::vec2(0.0, 2.0), egui::Align2::RIGHT_TOP, self.max.to_string(), font, text_color);
+    }
+}
+
+/// Color at `t` along the gradient, 0.0 coolest and 1.0 hottest
+pub fn heat_color(t: f32) -> egui::Color32 {
+    let scaled = t.clamp(0.0, 1.0) * (GRADIENT.len() - 1) as f32;
+    let index = (scaled.floor() as usize).min(GRADIENT.len() - 2);
+    let (from, to) = (GRADIENT[index], GRADIENT[index + 1]);
+    let f = scaled - index as f32;
+    let mix = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * f).round() as u8;
+    egui::Color32::from_rgb(mix(from.r(), to.r()), mix(from.g(), to.g()), mix(from.b(), to.b()))
+}
+
+/// Name of a metric in the View menu and the legend
+pub fn metric_name(metric: NodeMetric) -> &'static str {
+    match metric {
+        NodeMetric::Degree => tr("heatmap.degree"),
+        NodeMetric::FieldCount => tr("heatmap.fields"),
+        NodeMetric::EstimatedLines => tr("heatmap.lines"),
+        NodeMetric::Issues => tr("heatmap.issues"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use imortal_ir::{Edge, Node};
+
+    #[test]
+    fn test_heatmap_scale() {
+        let mut project = ProjectGraph::with_name("test");
+        let user = project.add_node(Node::new_entity("User"));
+        let db = project.add_node(Node::new_database("DB"));
+        let lonely = project.add_node(Node::new_rest_endpoint("Health"));
+        project.add_edge(Edge::dependency(db, user)).unwrap();
+
+        let heatmap = Heatmap::compute(&project, NodeMetric::Degree);
+        assert!(heatmap.is_current(NodeMetric::Degree));
+        assert!(!heatmap.is_current(NodeMetric::Issues));
+        assert_eq!(heatmap.value(user), Some(1));
+        assert_eq!(heatmap.heat(heatmap.value(lonely).unwrap()), 0.0);
+        assert_eq!(heatmap.heat(heatmap.value(db).unwrap()), 1.0);
+
+        assert_eq!(heat_color(0.0), GRADIENT[0]);
+        assert_eq!(heat_color(1.0), GRADIENT[3]);
+        assert_eq!(heat_color(2.0), GRADIENT[3]);
+        assert_eq!(heat_color(1.0 / 3.0), GRADIENT[1]);
+
+        // An empty or all-zero project doesn't divide by zero
+        let empty = Heatmap::compute(&ProjectGraph::with_name("empty"), NodeMetric::FieldCount);
+        assert_eq!(empty.heat(0), 0.0);
+    }
+}
@@ -5,29 +5,82 @@
 //! - Node rendering and layout
 //! - Edge/connection rendering
 //! - Pan and zoom
-//! - Selection and interaction
+//! - Hit-testing and interaction reporting
 //! - Grid rendering
+//! - A self-contained editor for embedding, in [`editor`]
+//! - Alignment guides for dragged nodes, in [`guides`]
+//! - Image and swimlane background layers, in [`backgrounds`]
+
+pub mod backgrounds;
+pub mod bundles;
+pub mod editor;
+pub mod guides;
+pub mod heatmap;
+
+pub use editor::{GraphEditorResponse, GraphEditorState};
 
 use eframe::egui;
-use imortal_ir::{ProjectGraph, Node, Edge, Group, NodeId, EdgeId};
+use imortal_ir::{ProjectGraph, Node, NodeId, EdgeId, Position};
+use crate::validations::ValidationBadge;
+use imortal_render::{Align, Color, Point, Rect, Rounding, Style, Transform};
 
 /// The main canvas widget for the visual editor
+///
+/// Draws a project and turns pointer input into a [`CanvasResponse`]. The
+/// widget only changes the viewport (pan and zoom); selection, moving nodes,
+/// and connections are left to the caller, which reacts to the response.
 pub struct CanvasWidget {
     /// Canvas configuration
     pub config: CanvasConfig,
+    /// Report clicks, drags, and drops; a read-only canvas only navigates
+    editable: bool,
+    /// A connection is being drawn, so port clicks complete it
+    connecting: bool,
+    /// Only nodes in this namespace are shown
+    namespace: Vec<String>,
+    /// Only nodes carrying one of these tags are shown; empty shows all
+    tags: Vec<String>,
 }
 
 impl CanvasWidget {
     /// Create a new canvas widget with default configuration
     pub fn new() -> Self {
-        Self {
-            config: CanvasConfig::default(),
-        }
+        Self::with_config(CanvasConfig::default())
     }
 
     /// Create a canvas widget with custom configuration
     pub fn with_config(config: CanvasConfig) -> Self {
-        Self { config }
+        Self {
+            config,
+            editable: true,
+            connecting: false,
+            namespace: Vec::new(),
+            tags: Vec::new(),
+        }
+    }
+
+    /// Make the canvas read-only: any drag pans and only navigation is reported
+    pub fn editable(mut self, editable: bool) -> Self {
+        self.editable = editable;
+        self
+    }
+
+    /// Tell the canvas a connection is being drawn
+    pub fn connecting(mut self, connecting: bool) -> Self {
+        self.connecting = connecting;
+        self
+    }
+
+    /// Show only the nodes inside a namespace, hiding everything else
+    pub fn namespace(mut self, namespace: &[String]) -> Self {
+        self.namespace = namespace.to_vec();
+        self
+    }
+
+    /// Show only the nodes carrying one of `tags`; empty shows every node
+    pub fn tags(mut self, tags: &[String]) -> Self {
+        self.tags = tags.to_vec();
+        self
     }
 
     /// Render the canvas
@@ -37,308 +90,380 @@ impl CanvasWidget {
             egui::Sense::click_and_drag(),
         );
 
+        // Draw background, grid, groups, edges, and nodes
         let painter = ui.painter_at(rect);
+        let transform = Transform::new(
+            Point::new(rect.min.x + project.viewport.pan_x, rect.min.y + project.viewport.pan_y),
+            project.viewport.zoom,
+        )
+        .with_pixels_per_point(ui.ctx().pixels_per_point());
+        let clip = Rect {
+            min: Point::new(rect.min.x, rect.min.y),
+            max: Point::new(rect.max.x, rect.max.y),
+        };
+        let mut painter = EguiPainter::new(&painter);
+        if self.namespace.is_empty() && self.tags.is_empty() {
+            imortal_render::draw_project(&mut painter, project, &self.config.style(), transform, clip);
+        } else {
+            let shown = project
+                .nodes
+                .values()
+                .filter(|n| n.in_namespace(&self.namespace) && n.has_any_tag(&self.tags))
+                .map(|n| n.id)
+                .collect();
+            imortal_render::draw_project(&mut painter, &project.extract(&shown).project, &self.config.style(), transform, clip);
+        }
 
-        // Draw background
-        painter.rect_filled(rect, 0.0, self.config.background_color);
+        self.interact(ui, &response, project, rect)
+    }
 
-        // Draw grid if enabled
-        if self.config.show_grid {
-            self.draw_grid(&painter, rect, project);
-        }
+    /// Handle pointer input on a canvas drawn into `rect`
+    ///
+    /// Pans and zooms the viewport, then reports what is under the pointer
+    /// and what was clicked, dragged, or dropped.
+    pub fn interact(
+        &self,
+        ui: &egui::Ui,
+        response: &egui::Response,
+        project: &mut ProjectGraph,
+        rect: egui::Rect,
+    ) -> CanvasResponse {
+        let mut canvas_response = CanvasResponse::default();
+        let shift_held = ui.input(|i| i.modifiers.shift);
 
-        // Draw groups (behind nodes)
-        for group in project.groups.values() {
-            self.draw_group(&painter, rect, project, group);
+        // Pan with the middle button or Shift+drag; on a read-only canvas any drag pans
+        if response.dragged_by(egui::PointerButton::Middle) ||
+           (response.dragged() && (!self.editable || shift_held)) {
+            let delta = response.drag_delta();
+            project.pan(delta.x, delta.y);
+            canvas_response.panned = true;
         }
 
-        // Draw edges
-        for edge in project.edges.values() {
-            self.draw_edge(&painter, rect, project, edge);
+        // Zoom with the scroll wheel
+        if response.contains_pointer() {
+            let scroll = ui.input(|i| i.smooth_scroll_delta.y);
+            if scroll != 0.0 {
+                project.zoom(1.0 + scroll * 0.002);
+                canvas_response.zoomed = true;
+            }
         }
 
-        // Draw nodes
-        for node in project.nodes.values() {
-            self.draw_node(&painter, rect, project, node);
+        // Touch gestures: two-finger pan and pinch zoom around the fingers
+        let multi_touch = ui.input(|i| i.multi_touch())
+            .filter(|touch| self.config.touch_gestures && rect.contains(touch.start_pos));
+        if let Some(touch) = multi_touch {
+            project.pan(touch.translation_delta.x, touch.translation_delta.y);
+            canvas_response.panned = true;
+            if touch.zoom_delta != 1.0 {
+                let anchor = ui.input(|i| i.pointer.hover_pos()).unwrap_or(touch.start_pos) - rect.min;
+                project.zoom_at(touch.zoom_delta, anchor.x, anchor.y);
+                canvas_response.zoomed = true;
+            }
         }
 
-        // Handle interactions
-        let canvas_response = self.handle_interactions(ui, &response, project, rect);
-
-        canvas_response
-    }
+        // Hit-test after navigating so results match what is drawn this frame
+        let geometry = CanvasGeometry::new(rect, project).with_namespace(&self.namespace).with_tags(&self.tags);
+        canvas_response.pointer_pos = ui.input(|i| i.pointer.hover_pos()).filter(|p| rect.contains(*p));
+        if let Some(pos) = canvas_response.pointer_pos {
+            canvas_response.hovered_node = geometry.node_at(project, pos);
+            if canvas_response.hovered_node.is_none() {
+                canvas_response.hovered_edge = geometry.edge_at(project, pos, EDGE_HIT_DISTANCE);
+            }
+            if self.editable {
+                canvas_response.hovered_port = geometry.port_at(project, pos, self.config.port_hit_radius);
+            }
+        }
 
-    /// Draw the background grid
-    fn draw_grid(&self, painter: &egui::Painter, rect: egui::Rect, project: &ProjectGraph) {
-        let zoom = project.viewport.zoom;
-        let pan_x = project.viewport.pan_x;
-        let pan_y = project.viewport.pan_y;
+        if !self.editable {
+            return canvas_response;
+        }
 
-        let grid_size = self.config.grid_size * zoom;
-        let grid_color = self.config.grid_color;
+        let pointer_pos = response.interact_pointer_pos();
+
+        // Clicks: ports first, then chevrons, validation badges, nodes, edges, and the background
+        if response.clicked() {
+            if let Some(pos) = pointer_pos {
+                if let Some(port) = geometry.port_at(project, pos, self.config.port_hit_radius) {
+                    canvas_response.connection_started = !self.connecting;
+                    canvas_response.connection_completed = self.connecting;
+                    canvas_response.clicked_port = Some(port);
+                } else if self.connecting {
+                    if geometry.node_at(project, pos).is_none() {
+                        canvas_response.connection_dropped = Some(geometry.to_canvas(pos));
+                    }
+                } else {
+                    let toggled = project.nodes.values()
+                        .filter(|node| geometry.shows(node))
+                        .find(|node| geometry.collapse_toggle_rect(node).is_some_and(|r| r.contains(pos)));
+                    if let Some(node) = toggled {
+                        canvas_response.toggled_node = Some(node.id);
+                    } else if let Some(badge) = geometry.field_badge_at(project, pos) {
+                        canvas_response.clicked_badge = Some(badge);
+                    } else if let Some(node_id) = geometry.node_at(project, pos) {
+                        canvas_response.clicked_node = Some(node_id);
+                    } else if let Some(edge_id) = geometry.edge_at(project, pos, EDGE_HIT_DISTANCE) {
+                        canvas_response.clicked_edge = Some(edge_id);
+                    } else {
+                        canvas_response.clicked_background = true;
+                    }
+                }
+            }
+        }
+        if response.double_clicked() {
+            canvas_response.double_click = pointer_pos.map(|pos| geometry.double_click_at(project, pos));
+        }
 
-        // Calculate visible grid lines
-        let start_x = ((rect.min.x - pan_x) / grid_size).floor() * grid_size + pan_x;
-        let start_y = ((rect.min.y - pan_y) / grid_size).floor() * grid_size + pan_y;
+        // Right-click or long-press
+        if response.secondary_clicked() {
+            canvas_response.secondary_clicked = true;
+            canvas_response.context_node = pointer_pos.and_then(|pos| geometry.node_at(project, pos));
+        }
 
-        // Draw vertical lines
-        let mut x = start_x;
-        while x < rect.max.x {
-            painter.line_segment(
-                [egui::pos2(x, rect.min.y), egui::pos2(x, rect.max.y)],
-                egui::Stroke::new(1.0, grid_color),
-            );
-            x += grid_size;
+        // Drags: a drag starting on a port draws a connection, one starting
+        // on a selected node's corner handle resizes it
+        let port_drag = !self.connecting && response.drag_started_by(egui::PointerButton::Primary) && !shift_held
+            && pointer_pos.is_some_and(|pos| geometry.port_at(project, pos, self.config.port_hit_radius).is_some());
+        if port_drag {
+            canvas_response.connection_started = true;
+            canvas_response.clicked_port = pointer_pos.and_then(|pos| geometry.port_at(project, pos, self.config.port_hit_radius));
+        } else if !self.connecting && response.drag_started_by(egui::PointerButton::Primary) && !shift_held {
+            canvas_response.resize_started = pointer_pos.and_then(|pos| {
+                project.selected_nodes.iter()
+                    .filter_map(|id| project.get_node(*id))
+                    .filter(|node| geometry.shows(node))
+                    .find(|node| geometry.resize_handle_rect(node).contains(pos))
+                    .map(|node| node.id)
+            });
+        }
+        if !self.connecting && !port_drag && multi_touch.is_none()
+            && response.dragged_by(egui::PointerButton::Primary) && !shift_held
+        {
+            canvas_response.drag_delta = Some(response.drag_delta() / geometry.zoom);
+        }
+        canvas_response.drag_stopped = response.drag_stopped();
+
+        // Releasing a connection drag on a port completes it, and on empty canvas drops it
+        if self.connecting && response.drag_stopped_by(egui::PointerButton::Primary) {
+            if let Some(pos) = response.hover_pos().or(pointer_pos) {
+                if let Some(port) = geometry.port_at(project, pos, self.config.port_hit_radius) {
+                    canvas_response.connection_completed = true;
+                    canvas_response.clicked_port = Some(port);
+                } else if geometry.node_at(project, pos).is_none() {
+                    canvas_response.connection_dropped = Some(geometry.to_canvas(pos));
+                }
+            }
         }
 
-        // Draw horizontal lines
-        let mut y = start_y;
-        while y < rect.max.y {
-            painter.line_segment(
-                [egui::pos2(rect.min.x, y), egui::pos2(rect.max.x, y)],
-                egui::Stroke::new(1.0, grid_color),
-            );
-            y += grid_size;
+        // Components dragged in from the palette
+        if let Some(payload) = response.dnd_release_payload::<ComponentDrag>() {
+            if let Some(pos) = response.hover_pos().or(pointer_pos) {
+                canvas_response.dropped = Some(CanvasDrop {
+                    component_id: payload.component_id.clone(),
+                    position: geometry.to_canvas(pos),
+                });
+            }
         }
+
+        canvas_response
     }
+}
 
-    /// Draw a node on the canvas
-    fn draw_node(&self, painter: &egui::Painter, rect: egui::Rect, project: &ProjectGraph, node: &Node) {
-        let zoom = project.viewport.zoom;
-        let pan = egui::vec2(project.viewport.pan_x, project.viewport.pan_y);
+/// Pointer distance within which an edge counts as hovered, in screen pixels
+const EDGE_HIT_DISTANCE: f32 = 6.0;
+
+/// Screen geometry of a canvas: where nodes, ports, and edges are drawn
+///
+/// Drawing and hit-testing both go through this so they always agree.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CanvasGeometry {
+    /// Screen position of the canvas origin
+    pub origin: egui::Pos2,
+    /// Screen pixels per canvas unit
+    pub zoom: f32,
+    /// Namespace whose nodes are shown; empty shows every node
+    pub namespace: Vec<String>,
+    /// Tags of the nodes shown; empty shows every node
+    pub tags: Vec<String>,
+}
 
-        let node_rect = egui::Rect::from_min_size(
-            rect.min + pan + egui::vec2(node.position.x * zoom, node.position.y * zoom),
-            egui::vec2(node.size.width * zoom, node.size.height * zoom),
-        );
+impl CanvasGeometry {
+    /// Width kept clear for the type text at the right of a field row, in canvas units
+    pub const BADGE_TYPE_WIDTH: f32 = 64.0;
 
-        // Skip if not visible
-        if !rect.intersects(node_rect) {
-            return;
+    /// Geometry of `project` drawn into `rect` at its current viewport
+    pub fn new(rect: egui::Rect, project: &ProjectGraph) -> Self {
+        Self {
+            origin: rect.min + egui::vec2(project.viewport.pan_x, project.viewport.pan_y),
+            zoom: project.viewport.zoom,
+            namespace: Vec::new(),
+            tags: Vec::new(),
         }
+    }
 
-        // Node background
-        let bg_color = if node.selected {
-            self.config.node_selected_color
-        } else {
-            self.config.node_background_color
-        };
+    /// Only show and hit-test nodes inside `namespace`
+    pub fn with_namespace(mut self, namespace: &[String]) -> Self {
+        self.namespace = namespace.to_vec();
+        self
+    }
 
-        painter.rect_filled(node_rect, self.config.node_corner_radius, bg_color);
+    /// Only show and hit-test nodes carrying one of `tags`
+    pub fn with_tags(mut self, tags: &[String]) -> Self {
+        self.tags = tags.to_vec();
+        self
+    }
 
-        // Node border
-        let border_color = if node.selected {
-            self.config.node_selected_border_color
-        } else {
-            self.config.node_border_color
-        };
+    /// Whether a node is drawn
+    pub fn shows(&self, node: &Node) -> bool {
+        node.in_namespace(&self.namespace) && node.has_any_tag(&self.tags)
+    }
 
-        painter.rect_stroke(
-            node_rect,
-            self.config.node_corner_radius,
-            egui::Stroke::new(self.config.node_border_width, border_color),
-        );
+    /// Convert a screen position to canvas coordinates
+    pub fn to_canvas(&self, pos: egui::Pos2) -> Position {
+        Position::new((pos.x - self.origin.x) / self.zoom, (pos.y - self.origin.y) / self.zoom)
+    }
 
-        // Node header
-        let header_height = self.config.node_header_height * zoom;
-        let header_rect = egui::Rect::from_min_size(
-            node_rect.min,
-            egui::vec2(node_rect.width(), header_height),
-        );
+    /// Convert canvas coordinates to a screen position
+    pub fn to_screen(&self, pos: Position) -> egui::Pos2 {
+        self.origin + egui::vec2(pos.x * self.zoom, pos.y * self.zoom)
+    }
 
-        let header_color = get_category_color(node.category);
-        painter.rect_filled(
-            header_rect,
-            egui::Rounding {
-                nw: self.config.node_corner_radius,
-                ne: self.config.node_corner_radius,
-                sw: 0.0,
-                se: 0.0,
-            },
-            header_color,
-        );
+    /// Screen rect of a node, from its effective bounds
+    pub fn node_rect(&self, node: &Node) -> egui::Rect {
+        let (x, y, width, height) = node.effective_bounds();
+        egui::Rect::from_min_size(
+            self.origin + egui::vec2(x * self.zoom, y * self.zoom),
+            egui::vec2(width * self.zoom, height * self.zoom),
+        )
+    }
 
-        // Node title
-        let icon = node.icon.as_deref().unwrap_or("📦");
-        let title = format!("{} {}", icon, node.name);
-        painter.text(
-            header_rect.center(),
-            egui::Align2::CENTER_CENTER,
-            title,
-            egui::FontId::proportional(14.0 * zoom),
-            egui::Color32::WHITE,
-        );
+    /// Screen position of a node's output port, just outside its right edge
+    pub fn output_port_pos(&self, node: &Node) -> egui::Pos2 {
+        self.node_rect(node).right_center() + egui::vec2(8.0, 0.0)
+    }
 
-        // Draw ports
-        self.draw_node_ports(painter, node_rect, project, node);
+    /// Screen position of a node's input port, just outside its left edge
+    pub fn input_port_pos(&self, node: &Node) -> egui::Pos2 {
+        self.node_rect(node).left_center() - egui::vec2(8.0, 0.0)
     }
 
-    /// Draw ports for a node
-    fn draw_node_ports(
-        &self,
-        painter: &egui::Painter,
-        node_rect: egui::Rect,
-        project: &ProjectGraph,
-        node: &Node,
-    ) {
-        let zoom = project.viewport.zoom;
-        let port_radius = self.config.port_radius * zoom;
-        let header_height = self.config.node_header_height * zoom;
-
-        // Input ports (left side)
-        let input_count = node.ports.inputs.len();
-        for (i, port) in node.ports.inputs.iter().enumerate() {
-            let y_offset = header_height + (i as f32 + 1.0) * (node_rect.height() - header_height) / (input_count as f32 + 1.0);
-            let port_pos = egui::pos2(node_rect.min.x, node_rect.min.y + y_offset);
-
-            let color = get_port_color(&port.data_type);
-            painter.circle_filled(port_pos, port_radius, color);
-            painter.circle_stroke(port_pos, port_radius, egui::Stroke::new(1.0, egui::Color32::WHITE));
+    /// Screen rect of the collapse/expand chevron in a node's header
+    ///
+    /// Only nodes with detail rows have a chevron.
+    pub fn collapse_toggle_rect(&self, node: &Node) -> Option<egui::Rect> {
+        if node.content_rows() == 0 {
+            return None;
         }
+        Some(egui::Rect::from_min_size(self.node_rect(node).min, egui::vec2(20.0 * self.zoom, Node::HEADER_HEIGHT * self.zoom)))
+    }
 
-        // Output ports (right side)
-        let output_count = node.ports.outputs.len();
-        for (i, port) in node.ports.outputs.iter().enumerate() {
-            let y_offset = header_height + (i as f32 + 1.0) * (node_rect.height() - header_height) / (output_count as f32 + 1.0);
-            let port_pos = egui::pos2(node_rect.max.x, node_rect.min.y + y_offset);
-
-            let color = get_port_color(&port.data_type);
-            painter.circle_filled(port_pos, port_radius, color);
-            painter.circle_stroke(port_pos, port_radius, egui::Stroke::new(1.0, egui::Color32::WHITE));
+    /// Screen rects of the validation badges on an entity's field rows
+    ///
+    /// Badges sit at the right end of each row, ending where the type text
+    /// starts. Collapsed nodes have no visible rows, so no badges.
+    pub fn field_badge_rects(&self, node: &Node) -> Vec<(uuid::Uuid, ValidationBadge, egui::Rect)> {
+        if node.component_type != "data.entity" || node.collapsed {
+            return Vec::new();
         }
+        let node_rect = self.node_rect(node);
+        let size = 12.0 * self.zoom;
+        let gap = 2.0 * self.zoom;
+        let mut rects = Vec::new();
+        for (idx, field) in node.fields.iter().enumerate() {
+            let row_y = node_rect.min.y + (Node::HEADER_HEIGHT + 4.0 + idx as f32 * Node::ROW_HEIGHT) * self.zoom;
+            let mut right = node_rect.max.x - Self::BADGE_TYPE_WIDTH * self.zoom;
+            for badge in ValidationBadge::for_field(field).into_iter().rev() {
+                let rect = egui::Rect::from_min_size(egui::pos2(right - size, row_y + gap), egui::vec2(size, size));
+                rects.push((field.id, badge, rect));
+                right -= size + gap;
+            }
+        }
+        rects
     }
 
-    /// Draw an edge on the canvas
-    fn draw_edge(&self, painter: &egui::Painter, rect: egui::Rect, project: &ProjectGraph, edge: &Edge) {
-        let from_node = match project.get_node(edge.from_node) {
-            Some(n) => n,
-            None => return,
-        };
-        let to_node = match project.get_node(edge.to_node) {
-            Some(n) => n,
-            None => return,
-        };
-
-        let zoom = project.viewport.zoom;
-        let pan = egui::vec2(project.viewport.pan_x, project.viewport.pan_y);
+    /// Field and badge under a screen position
+    pub fn field_badge_at(&self, project: &ProjectGraph, pos: egui::Pos2) -> Option<(NodeId, uuid::Uuid)> {
+        project.nodes.values()
+            .filter(|node| self.shows(node))
+            .find_map(|node| {
+                self.field_badge_rects(node).into_iter()
+                    .find(|(_, _, rect)| rect.contains(pos))
+                    .map(|(field_id, _, _)| (node.id, field_id))
+            })
+    }
 
-        // Calculate start and end points
-        let start = rect.min + pan + egui::vec2(
-            (from_node.position.x + from_node.size.width) * zoom,
-            (from_node.position.y + from_node.size.height / 2.0) * zoom,
-        );
-        let end = rect.min + pan + egui::vec2(
-            to_node.position.x * zoom,
-            (to_node.position.y + to_node.size.height / 2.0) * zoom,
-        );
+    /// Screen rect of the width resize handle in a node's bottom-right corner
+    pub fn resize_handle_rect(&self, node: &Node) -> egui::Rect {
+        let node_rect = self.node_rect(node);
+        let size = 10.0 * self.zoom;
+        egui::Rect::from_min_max(node_rect.max - egui::vec2(size, size), node_rect.max)
+    }
 
-        // Draw bezier curve
-        let color = if edge.selected {
-            self.config.edge_selected_color
-        } else {
-            let (r, g, b) = edge.style.color.rgb();
-            egui::Color32::from_rgb(r, g, b)
-        };
+    /// Points along an edge's curve, from the source's output port to the target's input port
+    pub fn edge_points(&self, from: &Node, to: &Node) -> Vec<egui::Pos2> {
+        Self::curve_points(self.output_port_pos(from), self.input_port_pos(to))
+    }
 
-        let control_offset = ((end.x - start.x).abs() / 2.0).max(50.0 * zoom);
+    /// Points along an edge-shaped curve between two screen positions
+    pub fn curve_points(start: egui::Pos2, end: egui::Pos2) -> Vec<egui::Pos2> {
+        let control_offset = ((end.x - start.x).abs() / 2.0).max(50.0);
         let control1 = egui::pos2(start.x + control_offset, start.y);
         let control2 = egui::pos2(end.x - control_offset, end.y);
 
-        // Draw using line segments approximating the bezier curve
-        let segments = 20;
-        let mut prev_point = start;
-        for i in 1..=segments {
-            let t = i as f32 / segments as f32;
-            let point = cubic_bezier(start, control1, control2, end, t);
-            painter.line_segment(
-                [prev_point, point],
-                egui::Stroke::new(self.config.edge_width * zoom, color),
-            );
-            prev_point = point;
-        }
-
-        // Draw arrow head
-        let arrow_size = self.config.arrow_size * zoom;
-        let direction = (end - prev_point).normalized();
-        let perpendicular = egui::vec2(-direction.y, direction.x);
-
-        let arrow_points = vec![
-            end,
-            end - direction * arrow_size + perpendicular * arrow_size * 0.5,
-            end - direction * arrow_size - perpendicular * arrow_size * 0.5,
-        ];
-
-        painter.add(egui::Shape::convex_polygon(
-            arrow_points,
-            color,
-            egui::Stroke::NONE,
-        ));
+        (0..=EDGE_SEGMENTS)
+            .map(|i| cubic_bezier(start, control1, control2, end, i as f32 / EDGE_SEGMENTS as f32))
+            .collect()
     }
 
-    /// Draw a group on the canvas
-    fn draw_group(&self, painter: &egui::Painter, rect: egui::Rect, project: &ProjectGraph, group: &Group) {
-        let zoom = project.viewport.zoom;
-        let pan = egui::vec2(project.viewport.pan_x, project.viewport.pan_y);
-
-        let group_rect = egui::Rect::from_min_size(
-            rect.min + pan + egui::vec2(group.position.x * zoom, group.position.y * zoom),
-            egui::vec2(group.size.width * zoom, group.size.height * zoom),
-        );
-
-        let (r, g, b) = group.color.rgb();
-        let bg_color = egui::Color32::from_rgba_unmultiplied(r, g, b, (group.opacity * 255.0) as u8);
-
-        painter.rect_filled(group_rect, 8.0, bg_color);
-        painter.rect_stroke(
-            group_rect,
-            8.0,
-            egui::Stroke::new(1.0, egui::Color32::from_rgb(r, g, b)),
-        );
-
-        // Group title
-        if group.show_header {
-            painter.text(
-                group_rect.min + egui::vec2(10.0, 15.0),
-                egui::Align2::LEFT_CENTER,
-                &group.name,
-                egui::FontId::proportional(12.0 * zoom),
-                egui::Color32::from_rgb(r, g, b),
-            );
-        }
+    /// Topmost node under a screen position
+    pub fn node_at(&self, project: &ProjectGraph, pos: egui::Pos2) -> Option<NodeId> {
+        project.nodes.values()
+            .filter(|node| self.shows(node))
+            .find(|node| self.node_rect(node).contains(pos))
+            .map(|node| node.id)
     }
 
-    /// Handle user interactions with the canvas
-    fn handle_interactions(
-        &self,
-        _ui: &mut egui::Ui,
-        response: &egui::Response,
-        project: &mut ProjectGraph,
-        _rect: egui::Rect,
-    ) -> CanvasResponse {
-        let mut canvas_response = CanvasResponse::default();
-
-        // Handle panning
-        if response.dragged_by(egui::PointerButton::Middle) ||
-           (response.dragged() && response.ctx.input(|i| i.modifiers.shift)) {
-            let delta = response.drag_delta();
-            project.pan(delta.x, delta.y);
-            canvas_response.panned = true;
+    /// What a double-click at a screen position does
+    pub fn double_click_at(&self, project: &ProjectGraph, pos: egui::Pos2) -> DoubleClick {
+        let Some(node) = self.node_at(project, pos).and_then(|id| project.get_node(id)) else {
+            return DoubleClick::QuickAdd(self.to_canvas(pos));
+        };
+        match node.component_type.as_str() {
+            "data.entity" => DoubleClick::Rename(node.id),
+            "storage.database" => DoubleClick::OpenConnection(node.id),
+            imortal_ir::COMPOSITE_COMPONENT => DoubleClick::EnterComposite(node.id),
+            _ => DoubleClick::EditProperties(node.id),
         }
+    }
 
-        // Handle zooming with scroll
-        if response.hovered() {
-            let scroll_delta = response.ctx.input(|i| i.raw_scroll_delta.y);
-            if scroll_delta != 0.0 {
-                let zoom_factor = if scroll_delta > 0.0 { 1.1 } else { 0.9 };
-                project.zoom(zoom_factor);
-                canvas_response.zoomed = true;
+    /// Port within `radius` of a screen position
+    pub fn port_at(&self, project: &ProjectGraph, pos: egui::Pos2, radius: f32) -> Option<PortHit> {
+        project.nodes.values().filter(|node| self.shows(node)).find_map(|node| {
+            if pos.distance(self.input_port_pos(node)) < radius {
+                Some(PortHit { node_id: node.id, port: "input".to_string(), is_output: false })
+            } else if pos.distance(self.output_port_pos(node)) < radius {
+                Some(PortHit { node_id: node.id, port: "output".to_string(), is_output: true })
+            } else {
+                None
             }
-        }
+        })
+    }
 
-        canvas_response
+    /// Edge whose curve passes within `distance` of a screen position
+    pub fn edge_at(&self, project: &ProjectGraph, pos: egui::Pos2, distance: f32) -> Option<EdgeId> {
+        project.edges.values().find_map(|edge| {
+            let from = project.get_node(edge.from_node).filter(|node| self.shows(node))?;
+            let to = project.get_node(edge.to_node).filter(|node| self.shows(node))?;
+            let points = self.edge_points(from, to);
+            points.windows(2)
+                .any(|segment| distance_to_segment(pos, segment[0], segment[1]) <= distance)
+                .then_some(edge.id)
+        })
     }
 }
 
+/// Number of line segments used to draw an edge
+const EDGE_SEGMENTS: usize = 20;
+
 impl Default for CanvasWidget {
     fn default() -> Self {
         Self::new()
@@ -378,6 +503,10 @@ pub struct CanvasConfig {
     pub edge_selected_color: egui::Color32,
     /// Arrow size
     pub arrow_size: f32,
+    /// Distance in pixels within which a click hits a port
+    pub port_hit_radius: f32,
+    /// Two-finger pan and pinch zoom on touchscreens
+    pub touch_gestures: bool,
 }
 
 impl Default for CanvasConfig {
@@ -398,6 +527,33 @@ impl Default for CanvasConfig {
             edge_width: 2.0,
             edge_selected_color: egui::Color32::from_rgb(100, 200, 255),
             arrow_size: 10.0,
+            port_hit_radius: 15.0,
+            touch_gestures: true,
+        }
+    }
+}
+
+impl CanvasConfig {
+    /// The drawing style for this configuration
+    pub fn style(&self) -> Style {
+        Style {
+            background_color: from_color32(self.background_color),
+            show_grid: self.show_grid,
+            grid_size: self.grid_size,
+            grid_color: from_color32(self.grid_color),
+            node_background_color: from_color32(self.node_background_color),
+            node_selected_color: from_color32(self.node_selected_color),
+            node_border_color: from_color32(self.node_border_color),
+            node_selected_border_color: from_color32(self.node_selected_border_color),
+            node_border_width: self.node_border_width,
+            node_corner_radius: self.node_corner_radius,
+            node_header_height: self.node_header_height,
+            port_radius: self.port_radius,
+            edge_width: self.edge_width,
+            edge_selected_color: from_color32(self.edge_selected_color),
+            arrow_size: self.arrow_size,
+            show_selection: true,
+            categories: Default::default(),
         }
     }
 }
@@ -409,59 +565,199 @@ pub struct CanvasResponse {
     pub panned: bool,
     /// Whether the canvas was zoomed
     pub zoomed: bool,
+    /// Pointer position over the canvas, in screen coordinates
+    pub pointer_pos: Option<egui::Pos2>,
+    /// Node under the pointer
+    pub hovered_node: Option<NodeId>,
+    /// Edge under the pointer, when no node is
+    pub hovered_edge: Option<EdgeId>,
+    /// Port under the pointer
+    pub hovered_port: Option<PortHit>,
     /// Node that was clicked
     pub clicked_node: Option<NodeId>,
     /// Edge that was clicked
     pub clicked_edge: Option<EdgeId>,
-    /// Whether a connection drag was started
+    /// Port that was clicked
+    pub clicked_port: Option<PortHit>,
+    /// Whether empty canvas was clicked
+    pub clicked_background: bool,
+    /// Node whose collapse chevron was clicked
+    pub toggled_node: Option<NodeId>,
+    /// Entity node and field whose validation badge was clicked
+    pub clicked_badge: Option<(NodeId, uuid::Uuid)>,
+    /// What a double-click does
+    pub double_click: Option<DoubleClick>,
+    /// Whether the canvas was right-clicked or long-pressed
+    pub secondary_clicked: bool,
+    /// Node under a right-click or long-press
+    pub context_node: Option<NodeId>,
+    /// Selected node whose resize handle a drag started on
+    pub resize_started: Option<NodeId>,
+    /// Primary-button drag this frame, in canvas units
+    pub drag_delta: Option<egui::Vec2>,
+    /// Whether a drag ended this frame
+    pub drag_stopped: bool,
+    /// Component dropped from the palette
+    pub dropped: Option<CanvasDrop>,
+    /// Whether a port click or drag started a connection
     pub connection_started: bool,
-    /// Whether a connection was completed
+    /// Whether a port click or drag release completed a connection
     pub connection_completed: bool,
+    /// Canvas position where a connection being drawn was dropped on empty canvas
+    pub connection_dropped: Option<Position>,
+}
+
+/// What a double-click on the canvas does
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DoubleClick {
+    /// Rename an entity in place, on its header
+    Rename(NodeId),
+    /// Open a database's connection dialog
+    OpenConnection(NodeId),
+    /// Enter a composite to edit its subgraph
+    EnterComposite(NodeId),
+    /// Edit the node's name in the properties panel
+    EditProperties(NodeId),
+    /// Search for a component to add at this canvas position
+    QuickAdd(Position),
+}
+
+impl DoubleClick {
+    /// The node double-clicked, if any
+    pub fn node(&self) -> Option<NodeId> {
+        match *self {
+            DoubleClick::Rename(id)
+            | DoubleClick::OpenConnection(id)
+            | DoubleClick::EnterComposite(id)
+            | DoubleClick::EditProperties(id) => Some(id),
+            DoubleClick::QuickAdd(_) => None,
+        }
+    }
+}
+
+/// A port hit by the pointer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PortHit {
+    /// Node the port belongs to
+    pub node_id: NodeId,
+    /// Port name ("input" or "output")
+    pub port: String,
+    /// Whether this is the output side
+    pub is_output: bool,
+}
+
+/// Drag payload of a palette component
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ComponentDrag {
+    /// Registry id of the component
+    pub component_id: String,
+}
+
+/// A palette component dropped on the canvas
+#[derive(Debug, Clone, PartialEq)]
+pub struct CanvasDrop {
+    /// Registry id of the component
+    pub component_id: String,
+    /// Drop position in canvas coordinates
+    pub position: Position,
 }
 
 /// Calculate a point on a cubic bezier curve
 fn cubic_bezier(p0: egui::Pos2, p1: egui::Pos2, p2: egui::Pos2, p3: egui::Pos2, t: f32) -> egui::Pos2 {
-    let t2 = t * t;
-    let t3 = t2 * t;
-    let mt = 1.0 - t;
-    let mt2 = mt * mt;
-    let mt3 = mt2 * mt;
-
-    egui::pos2(
-        mt3 * p0.x + 3.0 * mt2 * t * p1.x + 3.0 * mt * t2 * p2.x + t3 * p3.x,
-        mt3 * p0.y + 3.0 * mt2 * t * p1.y + 3.0 * mt * t2 * p2.y + t3 * p3.y,
-    )
+    let point = imortal_render::scene::cubic_bezier(
+        Point::new(p0.x, p0.y),
+        Point::new(p1.x, p1.y),
+        Point::new(p2.x, p2.y),
+        Point::new(p3.x, p3.y),
+        t,
+    );
+    egui::pos2(point.x, point.y)
 }
 
-/// Get color for a component category
-fn get_category_color(category: imortal_core::ComponentCategory) -> egui::Color32 {
-    match category {
-        imortal_core::ComponentCategory::Auth => egui::Color32::from_rgb(200, 80, 80),
-        imortal_core::ComponentCategory::Data => egui::Color32::from_rgb(80, 150, 200),
-        imortal_core::ComponentCategory::Api => egui::Color32::from_rgb(150, 200, 80),
-        imortal_core::ComponentCategory::Storage => egui::Color32::from_rgb(200, 150, 80),
-        imortal_core::ComponentCategory::Logic => egui::Color32::from_rgb(200, 80, 200),
-        imortal_core::ComponentCategory::Ui => egui::Color32::from_rgb(80, 200, 200),
-        imortal_core::ComponentCategory::Embedded => egui::Color32::from_rgb(200, 200, 80),
-        imortal_core::ComponentCategory::Custom => egui::Color32::from_rgb(100, 100, 100),
+/// Distance from a point to a line segment
+fn distance_to_segment(pos: egui::Pos2, a: egui::Pos2, b: egui::Pos2) -> f32 {
+    let ab = b - a;
+    let length_sq = ab.length_sq();
+    if length_sq == 0.0 {
+        return pos.distance(a);
     }
+    let t = ((pos - a).dot(ab) / length_sq).clamp(0.0, 1.0);
+    pos.distance(a + ab * t)
+}
+
+/// Draws render scenes onto an egui painter
+///
+/// Lets the canvas share its drawing code with the headless renderers in
+/// `imortal_render`.
+pub struct EguiPainter<'a> {
+    painter: &'a egui::Painter,
 }
 
-/// Get color for a port based on data type
-fn get_port_color(data_type: &imortal_core::DataType) -> egui::Color32 {
-    match data_type {
-        imortal_core::DataType::String | imortal_core::DataType::Text => egui::Color32::from_rgb(255, 200, 100),
-        imortal_core::DataType::Int32 | imortal_core::DataType::Int64 => egui::Color32::from_rgb(100, 200, 255),
-        imortal_core::DataType::Float32 | imortal_core::DataType::Float64 => egui::Color32::from_rgb(100, 255, 200),
-        imortal_core::DataType::Bool => egui::Color32::from_rgb(255, 100, 100),
-        imortal_core::DataType::Entity(_) | imortal_core::DataType::Reference(_) => egui::Color32::from_rgb(200, 100, 255),
-        imortal_core::DataType::Array(_) => egui::Color32::from_rgb(255, 150, 200),
-        imortal_core::DataType::Trigger => egui::Color32::from_rgb(255, 255, 100),
-        imortal_core::DataType::Any => egui::Color32::WHITE,
-        _ => egui::Color32::from_rgb(150, 150, 150),
+impl<'a> EguiPainter<'a> {
+    pub fn new(painter: &'a egui::Painter) -> Self {
+        Self { painter }
     }
 }
 
+impl imortal_render::Painter for EguiPainter<'_> {
+    fn fill_rect(&mut self, rect: Rect, rounding: Rounding, color: Color) {
+        let rounding = egui::Rounding {
+            nw: rounding.top,
+            ne: rounding.top,
+            sw: rounding.bottom,
+            se: rounding.bottom,
+        };
+        self.painter.rect_filled(to_egui_rect(rect), rounding, to_color32(color));
+    }
+
+    fn stroke_rect(&mut self, rect: Rect, radius: f32, width: f32, color: Color) {
+        self.painter.rect_stroke(to_egui_rect(rect), radius, egui::Stroke::new(width, to_color32(color)));
+    }
+
+    fn line(&mut self, points: &[Point], width: f32, color: Color) {
+        let points = points.iter().map(|p| to_pos2(*p)).collect();
+        self.painter.add(egui::Shape::line(points, egui::Stroke::new(width, to_color32(color))));
+    }
+
+    fn fill_circle(&mut self, center: Point, radius: f32, color: Color) {
+        self.painter.circle_filled(to_pos2(center), radius, to_color32(color));
+    }
+
+    fn stroke_circle(&mut self, center: Point, radius: f32, width: f32, color: Color) {
+        self.painter.circle_stroke(to_pos2(center), radius, egui::Stroke::new(width, to_color32(color)));
+    }
+
+    fn fill_polygon(&mut self, points: &[Point], color: Color) {
+        let points = points.iter().map(|p| to_pos2(*p)).collect();
+        self.painter.add(egui::Shape::convex_polygon(points, to_color32(color), egui::Stroke::NONE));
+    }
+
+    fn text(&mut self, pos: Point, align: Align, text: &str, size: f32, color: Color) {
+        let anchor = match align {
+            Align::Left => egui::Align2::LEFT_CENTER,
+            Align::Center => egui::Align2::CENTER_CENTER,
+        };
+        self.painter.text(to_pos2(pos), anchor, text, egui::FontId::proportional(size), to_color32(color));
+    }
+}
+
+fn to_pos2(point: Point) -> egui::Pos2 {
+    egui::pos2(point.x, point.y)
+}
+
+fn to_egui_rect(rect: Rect) -> egui::Rect {
+    egui::Rect::from_min_max(to_pos2(rect.min), to_pos2(rect.max))
+}
+
+fn to_color32(color: Color) -> egui::Color32 {
+    egui::Color32::from_rgba_unmultiplied(color.r, color.g, color.b, color.a)
+}
+
+fn from_color32(color: egui::Color32) -> Color {
+    let [r, g, b, a] = color.to_srgba_unmultiplied();
+    Color::rgba(r, g, b, a)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -474,18 +770,108 @@ mod tests {
     }
 
     #[test]
-    fn test_cubic_bezier() {
-        let p0 = egui::pos2(0.0, 0.0);
-        let p1 = egui::pos2(1.0, 0.0);
-        let p2 = egui::pos2(1.0, 1.0);
-        let p3 = egui::pos2(2.0, 1.0);
-
-        let start = cubic_bezier(p0, p1, p2, p3, 0.0);
-        assert!((start.x - p0.x).abs() < 0.001);
-        assert!((start.y - p0.y).abs() < 0.001);
-
-        let end = cubic_bezier(p0, p1, p2, p3, 1.0);
-        assert!((end.x - p3.x).abs() < 0.001);
-        assert!((end.y - p3.y).abs() < 0.001);
+    fn test_geometry_hit_testing() {
+        let mut project = ProjectGraph::with_name("test");
+        let user = project.add_node(Node::new_entity("User"));
+        let mut order = Node::new_entity("Order");
+        order.position.x = 500.0;
+        let order = project.add_node(order);
+        let edge = project.add_edge(imortal_ir::Edge::dependency(user, order)).unwrap();
+
+        project.viewport.pan_x = 100.0;
+        project.viewport.zoom = 2.0;
+        let rect = egui::Rect::from_min_size(egui::pos2(10.0, 20.0), egui::vec2(2000.0, 1000.0));
+        let geometry = CanvasGeometry::new(rect, &project);
+
+        let position = geometry.to_canvas(egui::pos2(110.0, 20.0));
+        assert_eq!((position.x, position.y), (0.0, 0.0));
+
+        let user_node = project.get_node(user).unwrap();
+        let inside = geometry.node_rect(user_node).center();
+        assert_eq!(geometry.node_at(&project, inside), Some(user));
+
+        let port = geometry.port_at(&project, geometry.output_port_pos(user_node), 15.0).unwrap();
+        assert_eq!((port.node_id, port.is_output), (user, true));
+
+        let points = geometry.edge_points(user_node, project.get_node(order).unwrap());
+        let on_curve = points[EDGE_SEGMENTS / 2];
+        assert_eq!(geometry.edge_at(&project, on_curve, EDGE_HIT_DISTANCE), Some(edge));
+        assert_eq!(geometry.edge_at(&project, on_curve + egui::vec2(0.0, 50.0), EDGE_HIT_DISTANCE), None);
+
+        // Nodes outside the entered namespace can't be hit
+        project.get_node_mut(user).unwrap().namespace = vec!["accounts".to_string()];
+        let geometry = geometry.with_namespace(&["billing".to_string()]);
+        assert_eq!(geometry.node_at(&project, inside), None);
+        assert_eq!(geometry.edge_at(&project, on_curve, EDGE_HIT_DISTANCE), None);
+
+        // So can nodes without a filtered tag
+        project.get_node_mut(user).unwrap().namespace.clear();
+        let geometry = geometry.with_namespace(&[]).with_tags(&["MVP".to_string()]);
+        assert_eq!(geometry.node_at(&project, inside), None);
+        project.get_node_mut(user).unwrap().tags.push("MVP".to_string());
+        assert_eq!(geometry.node_at(&project, inside), Some(user));
+    }
+
+    #[test]
+    fn test_double_click_at() {
+        let mut project = ProjectGraph::with_name("test");
+        let mut nodes = Vec::new();
+        for (i, node) in [Node::new_entity("User"), Node::new_database("db"), Node::new_login()].into_iter().enumerate() {
+            let mut node = node;
+            node.position.x = i as f32 * 400.0;
+            nodes.push(project.add_node(node));
+        }
+        let mut composite = Node::new_entity("Billing");
+        composite.component_type = imortal_ir::COMPOSITE_COMPONENT.to_string();
+        composite.position.y = 400.0;
+        nodes.push(project.add_node(composite));
+
+        let rect = egui::Rect::from_min_size(egui::Pos2::ZERO, egui::vec2(2000.0, 1000.0));
+        let geometry = CanvasGeometry::new(rect, &project);
+        let at = |id: NodeId| geometry.double_click_at(&project, geometry.node_rect(project.get_node(id).unwrap()).center());
+        assert_eq!(at(nodes[0]), DoubleClick::Rename(nodes[0]));
+        assert_eq!(at(nodes[1]), DoubleClick::OpenConnection(nodes[1]));
+        assert_eq!(at(nodes[2]), DoubleClick::EditProperties(nodes[2]));
+        assert_eq!(at(nodes[3]), DoubleClick::EnterComposite(nodes[3]));
+        assert_eq!(at(nodes[3]).node(), Some(nodes[3]));
+
+        // Empty canvas opens quick-add at the canvas position under the pointer
+        let empty = egui::pos2(1900.0, 900.0);
+        assert_eq!(geometry.double_click_at(&project, empty), DoubleClick::QuickAdd(geometry.to_canvas(empty)));
+        assert_eq!(geometry.double_click_at(&project, empty).node(), None);
+
+        // A node hidden by the tag filter is empty canvas too
+        let geometry = geometry.with_tags(&["MVP".to_string()]);
+        let center = geometry.node_rect(project.get_node(nodes[0]).unwrap()).center();
+        assert!(matches!(geometry.double_click_at(&project, center), DoubleClick::QuickAdd(_)));
+    }
+
+    #[test]
+    fn test_field_badge_rects() {
+        let mut project = ProjectGraph::with_name("test");
+        let mut user = Node::new_entity("User");
+        user.fields.push(imortal_ir::Field::new("email", imortal_core::DataType::String).unique());
+        let field_id = user.fields.last().unwrap().id;
+        let user = project.add_node(user);
+        let rect = egui::Rect::from_min_size(egui::Pos2::ZERO, egui::vec2(2000.0, 1000.0));
+        let geometry = CanvasGeometry::new(rect, &project);
+
+        let node = project.get_node(user).unwrap();
+        let badges = geometry.field_badge_rects(node);
+        assert_eq!(badges.len(), 1);
+        let (id, badge, badge_rect) = badges[0];
+        assert_eq!((id, badge), (field_id, ValidationBadge::Unique));
+        assert!(geometry.node_rect(node).contains_rect(badge_rect));
+        assert_eq!(geometry.field_badge_at(&project, badge_rect.center()), Some((user, field_id)));
+
+        project.get_node_mut(user).unwrap().toggle_collapsed();
+        assert!(geometry.field_badge_rects(project.get_node(user).unwrap()).is_empty());
+    }
+
+    #[test]
+    fn test_canvas_config_style() {
+        let style = CanvasConfig::default().style();
+        assert_eq!(style, Style::default());
+        assert_eq!(to_color32(from_color32(egui::Color32::from_rgb(1, 2, 3))), egui::Color32::from_rgb(1, 2, 3));
     }
 }
@@ -0,0 +1,426 @@
+//! Connections without drag gestures
+//!
+//! The properties panel's Connections tab lists a node's edges with a
+//! delete button each and a [`ConnectionForm`] for adding one by picking the
+//! other node, the direction, the connection type, and the ports.
+//! [`ConnectionMatrix`] shows every pair of nodes at once: a cell per source
+//! and target holding their connections, with a menu to add one where there
+//! is none.
+
+use eframe::egui;
+use imortal_core::{ConnectionType, EdgeId, RelationType};
+use imortal_ir::{Edge, Node, NodeId, Port, ProjectGraph};
+
+use crate::i18n::{tr, trf};
+
+/// What the user did in the Connections tab or the matrix
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConnectionAction {
+    /// Add the edge
+    Add(Box<Edge>),
+    /// Delete the edge
+    Delete(EdgeId),
+    /// Select the edge on the canvas
+    Select(EdgeId),
+}
+
+/// Connection types offered by the form and the matrix
+///
+/// Caching and gating edges carry a policy or a flag, so they are still
+/// drawn on the canvas.
+pub const FORM_TYPES: [ConnectionType; 8] = [
+    ConnectionType::DataFlow,
+    ConnectionType::Trigger,
+    ConnectionType::Navigation,
+    ConnectionType::Dependency,
+    ConnectionType::Relationship(RelationType::OneToOne),
+    ConnectionType::Relationship(RelationType::OneToMany),
+    ConnectionType::Relationship(RelationType::ManyToMany),
+    ConnectionType::Inheritance,
+];
+
+/// Whether edges of `connection_type` join particular ports rather than
+/// whole nodes
+pub fn uses_ports(connection_type: &ConnectionType) -> bool {
+    matches!(connection_type, ConnectionType::DataFlow | ConnectionType::Trigger | ConnectionType::Navigation)
+}
+
+/// Name of a connection type in the editor
+pub fn connection_type_name(connection_type: &ConnectionType) -> String {
+    match connection_type {
+        ConnectionType::DataFlow => tr("edge.data_flow").to_string(),
+        ConnectionType::Navigation => tr("edge.navigation").to_string(),
+        ConnectionType::Relationship(relation) => trf("edge.relationship", &[&relation.display_name()]),
+        ConnectionType::Trigger => tr("edge.trigger").to_string(),
+        ConnectionType::Dependency => tr("edge.dependency").to_string(),
+        ConnectionType::Inheritance => tr("edge.inheritance").to_string(),
+        ConnectionType::Caches => tr("edge.caches").to_string(),
+        ConnectionType::Gates => tr("edge.gates").to_string(),
+    }
+}
+
+/// The "Add connection" picker for one node
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConnectionForm {
+    /// Node whose properties are shown
+    pub node: NodeId,
+    /// Connect from the node to the other one, rather than the other way
+    pub outgoing: bool,
+    /// The other node
+    pub other: Option<NodeId>,
+    pub connection_type: ConnectionType,
+    /// Output port of the source; `None` picks the first that fits
+    pub from_port: Option<String>,
+    /// Input port of the target; `None` picks the first that fits
+    pub to_port: Option<String>,
+}
+
+impl ConnectionForm {
+    pub fn new(node: NodeId) -> Self {
+        Self {
+            node,
+            outgoing: true,
+            other: None,
+            connection_type: ConnectionType::DataFlow,
+            from_port: None,
+            to_port: None,
+        }
+    }
+
+    /// Source and target of the edge, once the other node is picked
+    pub fn ends(&self) -> Option<(NodeId, NodeId)> {
+        let other = self.other?;
+        Some(if self.outgoing { (self.node, other) } else { (other, self.node) })
+    }
+
+    /// The edge the form describes, or why it can't be made
+    pub fn edge(&self, project: &ProjectGraph) -> Result<Edge, String> {
+        let (from, to) = self.ends().ok_or_else(|| tr("connections.pick_node").to_string())?;
+        let (Some(source), Some(target)) = (project.get_node(from), project.get_node(to)) else {
+            return Err(tr("connections.pick_node").to_string());
+        };
+        let entities = source.component_type == "data.entity" && target.component_type == "data.entity";
+
+        match &self.connection_type {
+            ConnectionType::Relationship(relation) if entities => Ok(Edge::relationship(from, to, *relation)),
+            ConnectionType::Inheritance if entities && from != to => Ok(Edge::inheritance(from, to)),
+            ConnectionType::Relationship(_) | ConnectionType::Inheritance => Err(tr("connections.entities_only").to_string()),
+            ConnectionType::Dependency => Ok(Edge::dependency(from, to)),
+            connection_type => {
+                let (from_port, to_port) = self
+                    .ports(source, target)
+                    .ok_or_else(|| trf("connections.no_ports", &[&source.name, &target.name]))?;
+                Ok(match connection_type {
+                    ConnectionType::Trigger => Edge::trigger(from, from_port, to, to_port),
+                    ConnectionType::Navigation => Edge::navigation(from, from_port, to, to_port),
+                    _ => Edge::data_flow(from, from_port, to, to_port),
+                })
+            }
+        }
+    }
+
+    /// The chosen ports, or the first compatible pair among those not chosen
+    fn ports(&self, source: &Node, target: &Node) -> Option<(String, String)> {
+        let chosen = |port: &Port, choice: &Option<String>| choice.as_ref().is_none_or(|id| port.id == *id);
+        source
+            .ports
+            .outputs
+            .iter()
+            .filter(|out| chosen(out, &self.from_port))
+            .find_map(|out| {
+                let input = target.ports.inputs.iter().find(|input| chosen(input, &self.to_port) && out.can_connect_to(input))?;
+                Some((out.id.clone(), input.id.clone()))
+            })
+    }
+}
+
+/// Nodes other than `node`, sorted by name
+fn other_nodes(project: &ProjectGraph, node: NodeId) -> Vec<&Node> {
+    let mut nodes: Vec<&Node> = project.nodes().filter(|n| n.id != node).collect();
+    nodes.sort_by(|a, b| a.name.cmp(&b.name).then(a.id.cmp(&b.id)));
+    nodes
+}
+
+/// Show the Connections tab for `node`: its edges, then the form
+pub fn show_node_connections(
+    ui: &mut egui::Ui,
+    project: &ProjectGraph,
+    node: &Node,
+    form: &mut ConnectionForm,
+) -> Option<ConnectionAction> {
+    let mut action = None;
+    let name = |id: NodeId| project.get_node(id).map_or("?", |n| n.name.as_str()).to_string();
+
+    let mut edges = project.edges_for_node(node.id);
+    edges.sort_by_key(|edge| (edge.from_node != node.id, name(if edge.from_node == node.id { edge.to_node } else { edge.from_node })));
+    if edges.is_empty() {
+        ui.weak(tr("connections.none"));
+    }
+    egui::Grid::new("node_connections").num_columns(3).striped(true).show(ui, |ui| {
+        for edge in edges {
+            let text = if edge.from_node == node.id {
+                format!("→ {}", name(edge.to_node))
+            } else {
+                format!("← {}", name(edge.from_node))
+            };
+            if ui.selectable_label(false, text).on_hover_text(tr("connections.select")).clicked() {
+                action = Some(ConnectionAction::Select(edge.id));
+            }
+            ui.weak(connection_type_name(&edge.connection_type));
+            if ui.small_button("🗑").on_hover_text(tr("connections.delete")).clicked() {
+                action = Some(ConnectionAction::Delete(edge.id));
+            }
+            ui.end_row();
+        }
+    });
+
+    ui.separator();
+    ui.strong(tr("connections.add"));
+    egui::Grid::new("add_connection").num_columns(2).show(ui, |ui| {
+        ui.label(tr("connections.direction"));
+        ui.horizontal(|ui| {
+            ui.radio_value(&mut form.outgoing, true, tr("connections.to"));
+            ui.radio_value(&mut form.outgoing, false, tr("connections.from"));
+        });
+        ui.end_row();
+
+        ui.label(tr("connections.node"));
+        let selected = form.other.map_or_else(|| tr("connections.pick_node").to_string(), name);
+        egui::ComboBox::from_id_salt("connection_other").selected_text(selected).show_ui(ui, |ui| {
+            for other in other_nodes(project, node.id) {
+                if ui.selectable_value(&mut form.other, Some(other.id), &other.name).changed() {
+                    form.from_port = None;
+                    form.to_port = None;
+                }
+            }
+        });
+        ui.end_row();
+
+        ui.label(tr("edge.type"));
+        egui::ComboBox::from_id_salt("connection_type")
+            .selected_text(connection_type_name(&form.connection_type))
+            .show_ui(ui, |ui| {
+                for connection_type in FORM_TYPES {
+                    let label = connection_type_name(&connection_type);
+                    ui.selectable_value(&mut form.connection_type, connection_type, label);
+                }
+            });
+        ui.end_row();
+
+        let ends = form.ends().and_then(|(from, to)| Some((project.get_node(from)?, project.get_node(to)?)));
+        if let Some((source, target)) = ends.filter(|_| uses_ports(&form.connection_type)) {
+            for (label, ports, choice, salt) in [
+                (tr("connections.from_port"), &source.ports.outputs, &mut form.from_port, "connection_from_port"),
+                (tr("connections.to_port"), &target.ports.inputs, &mut form.to_port, "connection_to_port"),
+            ] {
+                ui.label(label);
+                let selected = choice.clone().unwrap_or_else(|| tr("connections.any_port").to_string());
+                egui::ComboBox::from_id_salt(salt).selected_text(selected).show_ui(ui, |ui| {
+                    ui.selectable_value(choice, None, tr("connections.any_port"));
+                    for port in ports {
+                        ui.selectable_value(choice, Some(port.id.clone()), format!("{} ({})", port.name, port.id));
+                    }
+                });
+                ui.end_row();
+            }
+        }
+    });
+
+    match form.edge(project) {
+        Ok(edge) => {
+            if ui.button(tr("connections.connect")).clicked() {
+                action = Some(ConnectionAction::Add(Box::new(edge)));
+            }
+        }
+        Err(problem) => {
+            ui.add_enabled(false, egui::Button::new(tr("connections.connect")));
+            if form.other.is_some() {
+                ui.weak(problem);
+            }
+        }
+    }
+    action
+}
+
+/// Short mark for a connection type in a matrix cell
+fn type_mark(connection_type: &ConnectionType) -> &'static str {
+    match connection_type {
+        ConnectionType::DataFlow => "→",
+        ConnectionType::Navigation => "⇢",
+        ConnectionType::Relationship(relation) => match relation {
+            RelationType::OneToOne => "1:1",
+            RelationType::OneToMany => "1:N",
+            RelationType::ManyToOne => "N:1",
+            RelationType::ManyToMany => "N:N",
+            RelationType::Polymorphic => "1:*",
+        },
+        ConnectionType::Trigger => "⚡",
+        ConnectionType::Dependency => "◆",
+        ConnectionType::Inheritance => "▷",
+        ConnectionType::Caches => "⛁",
+        ConnectionType::Gates => "🚩",
+    }
+}
+
+/// State of the Connection Matrix window
+#[derive(Debug, Default)]
+pub struct ConnectionMatrix {
+    /// Whether the window is open
+    pub visible: bool,
+    /// Only rows and columns whose node name contains this
+    filter: String,
+}
+
+impl ConnectionMatrix {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Show the window: a row per source node and a column per target
+    pub fn show(&mut self, ctx: &egui::Context, project: &ProjectGraph) -> Option<ConnectionAction> {
+        if !self.visible {
+            return None;
+        }
+        let filter = self.filter.to_lowercase();
+        let mut nodes: Vec<&Node> = project.nodes().filter(|n| n.name.to_lowercase().contains(&filter)).collect();
+        nodes.sort_by(|a, b| a.name.cmp(&b.name).then(a.id.cmp(&b.id)));
+        let mut action = None;
+        let mut open = true;
+
+        egui::Window::new(tr("connections.matrix_title"))
+            .open(&mut open)
+            .resizable(true)
+            .default_size([560.0, 420.0])
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("🔍");
+                    ui.text_edit_singleline(&mut self.filter);
+                });
+                ui.small(tr("connections.matrix_hint"));
+                ui.separator();
+                if nodes.is_empty() {
+                    ui.weak(tr("connections.matrix_empty"));
+                    return;
+                }
+
+                egui::ScrollArea::both().auto_shrink([false, false]).show(ui, |ui| {
+                    egui::Grid::new("connection_matrix").striped(true).show(ui, |ui| {
+                        ui.weak(tr("connections.matrix_corner"));
+                        for target in &nodes {
+                            ui.label(short_name(&target.name)).on_hover_text(&target.name);
+                        }
+                        ui.end_row();
+
+                        for source in &nodes {
+                            ui.label(short_name(&source.name)).on_hover_text(&source.name);
+                            for target in &nodes {
+                                let edges: Vec<&Edge> = project
+                                    .edges
+                                    .values()
+                                    .filter(|e| e.from_node == source.id && e.to_node == target.id)
+                                    .collect();
+                                if let Some(cell) = matrix_cell(ui, project, source, target, &edges) {
+                                    action = Some(cell);
+                                }
+                            }
+                            ui.end_row();
+                        }
+                    });
+                });
+            });
+
+        if !open {
+            self.visible = false;
+        }
+        action
+    }
+}
+
+/// One cell of the matrix: the connections from `source` to `target`, or a
+/// menu adding one
+fn matrix_cell(ui: &mut egui::Ui, project: &ProjectGraph, source: &Node, target: &Node, edges: &[&Edge]) -> Option<ConnectionAction> {
+    let mut action = None;
+    if edges.is_empty() {
+        let response = ui.menu_button(egui::RichText::new("·").weak(), |ui| {
+            ui.label(trf("edge.endpoints", &[&source.name, &target.name]));
+            ui.separator();
+            for connection_type in FORM_TYPES {
+                let form = ConnectionForm { other: Some(target.id), connection_type: connection_type.clone(), ..ConnectionForm::new(source.id) };
+                let edge = form.edge(project);
+                let button = ui.add_enabled(edge.is_ok(), egui::Button::new(connection_type_name(&connection_type)));
+                let button = match &edge {
+                    Err(problem) => button.on_disabled_hover_text(problem),
+                    Ok(_) => button,
+                };
+                if button.clicked() {
+                    action = edge.ok().map(|edge| ConnectionAction::Add(Box::new(edge)));
+                    ui.close_menu();
+                }
+            }
+        });
+        response.response.on_hover_text(trf("connections.matrix_add", &[&source.name, &target.name]));
+        return action;
+    }
+
+    ui.horizontal(|ui| {
+        for edge in edges {
+            let response = ui
+                .small_button(type_mark(&edge.connection_type))
+                .on_hover_text(format!("{}\n{}", connection_type_name(&edge.connection_type), tr("connections.matrix_cell_hint")));
+            if response.clicked() {
+                action = Some(ConnectionAction::Select(edge.id));
+            }
+            if response.secondary_clicked() {
+                action = Some(ConnectionAction::Delete(edge.id));
+            }
+        }
+    });
+    action
+}
+
+/// A node name cut short enough for a matrix header
+fn short_name(name: &str) -> String {
+    const MAX: usize = 12;
+    if name.chars().count() <= MAX {
+        name.to_string()
+    } else {
+        format!("{}…", name.chars().take(MAX - 1).collect::<String>())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_connection_form() {
+        let mut project = ProjectGraph::with_name("test");
+        let user = project.add_node(Node::new_entity("User"));
+        let post = project.add_node(Node::new_entity("Post"));
+        let endpoint = project.add_node(Node::new_rest_endpoint("Users"));
+
+        let mut form = ConnectionForm::new(user);
+        assert!(form.edge(&project).is_err());
+
+        // The first compatible ports, unless ports are picked
+        form.other = Some(endpoint);
+        let edge = form.edge(&project).unwrap();
+        assert_eq!((edge.from_node, edge.from_port.as_str(), edge.to_port.as_str()), (user, "entity", "request"));
+        form.to_port = Some("missing".to_string());
+        assert!(form.edge(&project).is_err());
+
+        // Incoming, and node-level types
+        form.to_port = None;
+        form.outgoing = false;
+        form.connection_type = ConnectionType::Dependency;
+        let edge = form.edge(&project).unwrap();
+        assert_eq!((edge.from_node, edge.to_node), (endpoint, user));
+        assert!(project.add_edge(edge).is_ok());
+
+        form.connection_type = ConnectionType::Relationship(RelationType::OneToMany);
+        assert!(form.edge(&project).is_err());
+        form.other = Some(post);
+        assert_eq!(form.edge(&project).unwrap().connection_type, ConnectionType::Relationship(RelationType::OneToMany));
+        assert_eq!(short_name("AVeryLongEntityName"), "AVeryLongEn…");
+    }
+}
@@ -0,0 +1,286 @@
+//! Crash reporting and project rescue for Immortal Engine UI
+//!
+//! The binary installs [`install_panic_hook`]. While the editor runs it
+//! keeps a copy of the live project (when it has unsaved changes) and the
+//! names of recent actions here. If anything panics, the hook saves that
+//! copy as an emergency project, writes a crash report with the backtrace,
+//! recent actions, and recent log events, and leaves a note in the crash
+//! folder. On the next launch [`pending_rescue`] finds the note so the app
+//! can offer to restore the project and open the report. Panics inside
+//! [`catch_task`], which the background task executor reports as failed
+//! tasks, aren't crashes and are left alone.
+
+use std::cell::Cell;
+use std::collections::VecDeque;
+use std::fmt::Write as _;
+use std::panic::PanicHookInfo;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use imortal_ir::ProjectGraph;
+use serde::{Deserialize, Serialize};
+
+use crate::log_panel::LogBuffer;
+
+/// Recent actions kept for the report
+const ACTION_LIMIT: usize = 50;
+/// Recent log events copied into the report
+const LOG_LINES: usize = 100;
+/// Name of the note left for the next launch
+const PENDING_FILE: &str = "pending.json";
+
+/// What the editor was doing, for the panic hook
+#[derive(Default)]
+struct RescueState {
+    /// Live project, when it has unsaved changes
+    project: Option<ProjectGraph>,
+    /// Where the live project is saved
+    path: Option<PathBuf>,
+    actions: VecDeque<String>,
+}
+
+thread_local! {
+    /// Whether this thread is running a task whose panics are caught
+    static IN_TASK: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Run `work`, catching a panic as a failed result instead of a crash
+pub fn catch_task<R>(work: impl FnOnce() -> R) -> std::thread::Result<R> {
+    let outer = IN_TASK.replace(true);
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(work));
+    IN_TASK.set(outer);
+    result
+}
+
+fn state() -> &'static Mutex<RescueState> {
+    static STATE: OnceLock<Mutex<RescueState>> = OnceLock::new();
+    STATE.get_or_init(Mutex::default)
+}
+
+/// Keep a copy of the live project to save if the editor crashes
+pub fn set_project(project: &ProjectGraph, path: Option<&Path>) {
+    if let Ok(mut state) = state().lock() {
+        state.project = Some(project.clone());
+        state.path = path.map(Path::to_path_buf);
+    }
+}
+
+/// Forget the copy, e.g. once everything is saved
+pub fn clear_project() {
+    if let Ok(mut state) = state().lock() {
+        state.project = None;
+    }
+}
+
+/// Remember an action for the crash report
+pub fn record_action(name: &str) {
+    if let Ok(mut state) = state().lock() {
+        if state.actions.len() == ACTION_LIMIT {
+            state.actions.pop_front();
+        }
+        state.actions.push_back(name.to_string());
+    }
+}
+
+/// Folder crash reports and rescued projects go to
+pub fn crash_dir() -> Option<PathBuf> {
+    dirs::config_dir().map(|p| p.join("immortal-engine").join("crashes"))
+}
+
+/// A crash left for the next launch to deal with
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CrashRescue {
+    /// Crash report
+    pub report: PathBuf,
+    /// Emergency copy of the project, if it had unsaved changes
+    pub project: Option<PathBuf>,
+    /// Where the project was saved before the crash
+    pub original_path: Option<PathBuf>,
+    pub project_name: Option<String>,
+    /// Panic message and location
+    pub message: String,
+    /// Unix time of the crash in milliseconds
+    pub timestamp: u64,
+}
+
+impl CrashRescue {
+    /// Stop offering this rescue; the report and project stay on disk
+    pub fn dismiss(&self) {
+        if let Some(dir) = self.report.parent() {
+            let _ = std::fs::remove_file(dir.join(PENDING_FILE));
+        }
+    }
+}
+
+/// Install a panic hook that rescues the project and writes a crash report,
+/// then runs the previous hook
+pub fn install_panic_hook() {
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        if IN_TASK.get() {
+            previous(info);
+            return;
+        }
+        if let Some(dir) = crash_dir() {
+            match write_crash(&dir, &panic_message(info)) {
+                Ok(rescue) => eprintln!("Crash report written to {}", rescue.report.display()),
+                Err(e) => eprintln!("Failed to write crash report: {}", e),
+            }
+        }
+        previous(info);
+    }));
+}
+
+/// Crash left by the last run, if it hasn't been dealt with
+pub fn pending_rescue() -> Option<CrashRescue> {
+    pending_rescue_in(&crash_dir()?)
+}
+
+fn pending_rescue_in(dir: &Path) -> Option<CrashRescue> {
+    let content = std::fs::read_to_string(dir.join(PENDING_FILE)).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+fn panic_message(info: &PanicHookInfo<'_>) -> String {
+    let payload = info
+        .payload()
+        .downcast_ref::<&str>()
+        .map(|s| s.to_string())
+        .or_else(|| info.payload().downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "unknown panic".to_string());
+    match info.location() {
+        Some(location) => format!("{} at {}:{}", payload, location.file(), location.line()),
+        None => payload,
+    }
+}
+
+/// Save the rescued project and the report into `dir`, and leave a note
+fn write_crash(dir: &Path, message: &str) -> std::io::Result<CrashRescue> {
+    std::fs::create_dir_all(dir)?;
+    let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_millis() as u64);
+
+    // The panic may have happened while the state was locked
+    let (project, original_path, actions) = match state().try_lock() {
+        Ok(state) => (state.project.clone(), state.path.clone(), state.actions.iter().cloned().collect()),
+        Err(_) => (None, None, Vec::new()),
+    };
+
+    let project_file = match &project {
+        Some(project) => {
+            let path = dir.join(format!("rescue-{}.{}", timestamp, imortal_ir::PROJECT_EXTENSION));
+            imortal_ir::save_project(project, &path, imortal_ir::ProjectFormat::Json).ok().map(|_| path)
+        }
+        None => None,
+    };
+
+    let report = dir.join(format!("crash-{}.txt", timestamp));
+    let backtrace = std::backtrace::Backtrace::force_capture().to_string();
+    std::fs::write(
+        &report,
+        report_text(message, &backtrace, &actions, &LogBuffer::global().recent_lines(LOG_LINES), project_file.as_deref()),
+    )?;
+
+    let rescue = CrashRescue {
+        report,
+        project: project_file,
+        original_path,
+        project_name: project.map(|p| p.meta.name),
+        message: message.to_string(),
+        timestamp,
+    };
+    if let Ok(content) = serde_json::to_string_pretty(&rescue) {
+        std::fs::write(dir.join(PENDING_FILE), content)?;
+    }
+    Ok(rescue)
+}
+
+/// Text of a crash report
+pub fn report_text(message: &str, backtrace: &str, actions: &[String], log: &[String], rescued: Option<&Path>) -> String {
+    let mut text = String::new();
+    let _ = writeln!(text, "Immortal Engine {} crashed", env!("CARGO_PKG_VERSION"));
+    let _ = writeln!(text, "OS: {} {}", std::env::consts::OS, std::env::consts::ARCH);
+    let _ = writeln!(text, "Panic: {}", message);
+    match rescued {
+        Some(path) => {
+            let _ = writeln!(text, "Unsaved project rescued to: {}", path.display());
+        }
+        None => {
+            let _ = writeln!(text, "No unsaved project to rescue");
+        }
+    }
+
+    let _ = writeln!(text, "\nRecent actions (oldest first):");
+    if actions.is_empty() {
+        let _ = writeln!(text, "  (none)");
+    }
+    for action in actions {
+        let _ = writeln!(text, "  {}", action);
+    }
+
+    let _ = writeln!(text, "\nRecent log:");
+    for line in log {
+        let _ = writeln!(text, "  {}", line);
+    }
+
+    let _ = writeln!(text, "\nBacktrace:\n{}", backtrace);
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use imortal_ir::{Node, ProjectMeta};
+
+    #[test]
+    fn test_report_text() {
+        let text = report_text(
+            "index out of bounds at app.rs:10",
+            "0: main",
+            &["Add Entity".to_string(), "Connect".to_string()],
+            &["INFO opened".to_string()],
+            Some(Path::new("/tmp/rescue.imortal")),
+        );
+        assert!(text.contains("Panic: index out of bounds at app.rs:10"));
+        assert!(text.contains("rescued to: /tmp/rescue.imortal"));
+        assert!(text.contains("  Add Entity\n  Connect\n"));
+        assert!(text.contains("  INFO opened"));
+        assert!(text.ends_with("Backtrace:\n0: main\n"));
+    }
+
+    #[test]
+    fn test_crash_rescues_project_for_next_launch() {
+        let dir = std::env::temp_dir().join(format!("imortal-crash-{}", uuid::Uuid::new_v4()));
+        let mut project = ProjectGraph::new(ProjectMeta::new("shop"));
+        project.add_node(Node::new_entity("User"));
+        set_project(&project, Some(Path::new("/projects/shop.imortal")));
+        record_action("Add Entity");
+
+        let rescue = write_crash(&dir, "boom").unwrap();
+        assert_eq!(pending_rescue_in(&dir), Some(rescue.clone()));
+        assert_eq!(rescue.original_path.as_deref(), Some(Path::new("/projects/shop.imortal")));
+        let restored = imortal_ir::load_project(rescue.project.as_ref().unwrap()).unwrap();
+        assert_eq!(restored.node_count(), 1);
+        assert!(std::fs::read_to_string(&rescue.report).unwrap().contains("Add Entity"));
+
+        // Offered once
+        rescue.dismiss();
+        assert_eq!(pending_rescue_in(&dir), None);
+        assert!(rescue.report.exists());
+
+        clear_project();
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_caught_task_panics_are_not_crashes() {
+        assert!(!IN_TASK.get());
+        let result = catch_task(|| {
+            assert!(IN_TASK.get());
+            panic!("task failed")
+        });
+        assert!(result.is_err());
+        assert!(!IN_TASK.get());
+        assert_eq!(catch_task(|| 4).ok(), Some(4));
+    }
+}
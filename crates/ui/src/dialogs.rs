@@ -10,6 +10,8 @@
 
 use eframe::egui;
 
+use crate::i18n::{tr, trf};
+
 /// Result of a dialog interaction
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum DialogResult {
@@ -79,24 +81,24 @@ impl NewProjectDialog {
 
         let mut result = DialogResult::Open;
 
-        egui::Window::new("New Project")
+        egui::Window::new(tr("new_project.title"))
             .collapsible(false)
             .resizable(false)
             .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
             .show(ctx, |ui| {
                 ui.vertical(|ui| {
                     ui.horizontal(|ui| {
-                        ui.label("Project Name:");
+                        ui.label(tr("new_project.name"));
                         ui.text_edit_singleline(&mut self.name);
                     });
 
                     ui.horizontal(|ui| {
-                        ui.label("Description:");
+                        ui.label(tr("new_project.description"));
                         ui.text_edit_singleline(&mut self.description);
                     });
 
                     ui.horizontal(|ui| {
-                        ui.label("Template:");
+                        ui.label(tr("new_project.template"));
                         egui::ComboBox::from_id_salt("template_select")
                             .selected_text(&self.template)
                             .show_ui(ui, |ui| {
@@ -109,11 +111,11 @@ impl NewProjectDialog {
                     ui.add_space(10.0);
 
                     ui.horizontal(|ui| {
-                        if ui.button("Create").clicked() && !self.name.is_empty() {
+                        if ui.button(tr("dialog.create")).clicked() && !self.name.is_empty() {
                             result = DialogResult::Confirmed;
                             self.visible = false;
                         }
-                        if ui.button("Cancel").clicked() {
+                        if ui.button(tr("dialog.cancel")).clicked() {
                             result = DialogResult::Cancelled;
                             self.visible = false;
                         }
@@ -184,39 +186,39 @@ impl SettingsDialog {
 
         let mut result = DialogResult::Open;
 
-        egui::Window::new("Settings")
+        egui::Window::new(tr("settings.title"))
             .collapsible(false)
             .resizable(true)
             .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
             .show(ctx, |ui| {
-                ui.heading("Appearance");
-                ui.checkbox(&mut self.dark_mode, "Dark Mode");
+                ui.heading(tr("settings.appearance"));
+                ui.checkbox(&mut self.dark_mode, tr("settings.dark_mode"));
                 ui.add_space(10.0);
 
-                ui.heading("Canvas");
-                ui.checkbox(&mut self.show_grid, "Show Grid");
-                ui.checkbox(&mut self.snap_to_grid, "Snap to Grid");
+                ui.heading(tr("settings.canvas"));
+                ui.checkbox(&mut self.show_grid, tr("menu.view.show_grid"));
+                ui.checkbox(&mut self.snap_to_grid, tr("menu.view.snap_to_grid"));
                 ui.horizontal(|ui| {
-                    ui.label("Grid Size:");
+                    ui.label(tr("settings.grid_size"));
                     ui.add(egui::Slider::new(&mut self.grid_size, 10.0..=50.0));
                 });
-                ui.checkbox(&mut self.show_minimap, "Show Minimap");
+                ui.checkbox(&mut self.show_minimap, tr("menu.view.show_minimap"));
                 ui.add_space(10.0);
 
-                ui.heading("Editor");
-                ui.checkbox(&mut self.show_descriptions, "Show Component Descriptions");
+                ui.heading(tr("settings.editor"));
+                ui.checkbox(&mut self.show_descriptions, tr("settings.show_descriptions"));
                 ui.horizontal(|ui| {
-                    ui.label("Auto-save interval (seconds, 0 = disabled):");
+                    ui.label(tr("settings.auto_save"));
                     ui.add(egui::DragValue::new(&mut self.auto_save_interval).range(0..=600));
                 });
                 ui.add_space(20.0);
 
                 ui.horizontal(|ui| {
-                    if ui.button("Save").clicked() {
+                    if ui.button(tr("dialog.save")).clicked() {
                         result = DialogResult::Confirmed;
                         self.visible = false;
                     }
-                    if ui.button("Cancel").clicked() {
+                    if ui.button(tr("dialog.cancel")).clicked() {
                         result = DialogResult::Cancelled;
                         self.visible = false;
                     }
@@ -228,17 +230,12 @@ impl SettingsDialog {
 }
 
 /// About dialog
+#[derive(Default)]
 pub struct AboutDialog {
     /// Whether the dialog is visible
     pub visible: bool,
 }
 
-impl Default for AboutDialog {
-    fn default() -> Self {
-        Self { visible: false }
-    }
-}
-
 impl AboutDialog {
     /// Create a new about dialog
     pub fn new() -> Self {
@@ -263,7 +260,7 @@ impl AboutDialog {
 
         let mut result = DialogResult::Open;
 
-        egui::Window::new("About Immortal Engine")
+        egui::Window::new(tr("about.title"))
             .collapsible(false)
             .resizable(false)
             .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
@@ -271,18 +268,17 @@ impl AboutDialog {
                 ui.vertical_centered(|ui| {
                     ui.heading("🔧 Immortal Engine");
                     ui.add_space(10.0);
-                    ui.label(format!("Version {}", env!("CARGO_PKG_VERSION")));
+                    ui.label(trf("about.version", &[&env!("CARGO_PKG_VERSION")]));
                     ui.add_space(10.0);
-                    ui.label("Visual Prototyping System");
-                    ui.label("Build applications by dragging components");
-                    ui.label("and drawing connections between them.");
+                    ui.label(tr("about.tagline"));
+                    ui.label(tr("about.description"));
                     ui.add_space(20.0);
-                    ui.label("Licensed under MIT");
+                    ui.label(tr("about.license"));
                     ui.add_space(10.0);
-                    ui.hyperlink_to("GitHub Repository", "https://github.com/yourusername/imortal_engine");
+                    ui.hyperlink_to(tr("about.repository"), "https://github.com/yourusername/imortal_engine");
                     ui.add_space(20.0);
 
-                    if ui.button("Close").clicked() {
+                    if ui.button(tr("dialog.close")).clicked() {
                         result = DialogResult::Confirmed;
                         self.visible = false;
                     }
@@ -311,10 +307,10 @@ impl Default for ConfirmDialog {
     fn default() -> Self {
         Self {
             visible: false,
-            title: "Confirm".to_string(),
-            message: "Are you sure?".to_string(),
-            confirm_text: "Yes".to_string(),
-            cancel_text: "No".to_string(),
+            title: tr("dialog.confirm").to_string(),
+            message: tr("dialog.confirm_message").to_string(),
+            confirm_text: tr("dialog.yes").to_string(),
+            cancel_text: tr("dialog.no").to_string(),
         }
     }
 }
@@ -330,8 +326,8 @@ impl ConfirmDialog {
         self.visible = true;
         self.title = title.to_string();
         self.message = message.to_string();
-        self.confirm_text = "Yes".to_string();
-        self.cancel_text = "No".to_string();
+        self.confirm_text = tr("dialog.yes").to_string();
+        self.cancel_text = tr("dialog.no").to_string();
     }
 
     /// Open with custom button text
@@ -441,45 +437,45 @@ impl ExportDialog {
 
         let mut result = DialogResult::Open;
 
-        egui::Window::new("Export Project")
+        egui::Window::new(tr("export.title"))
             .collapsible(false)
             .resizable(true)
             .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
             .show(ctx, |ui| {
                 ui.horizontal(|ui| {
-                    ui.label("Output Path:");
+                    ui.label(tr("export.output_path"));
                     ui.text_edit_singleline(&mut self.output_path);
-                    if ui.button("Browse...").clicked() {
+                    if ui.button(tr("dialog.browse")).clicked() {
                         // TODO: File dialog
                     }
                 });
 
                 ui.horizontal(|ui| {
-                    ui.label("Format:");
+                    ui.label(tr("export.format"));
                     egui::ComboBox::from_id_salt("export_format")
                         .selected_text(match self.format {
-                            ExportFormat::RustProject => "Rust Project",
-                            ExportFormat::JsonSchema => "JSON Schema",
-                            ExportFormat::OpenApi => "OpenAPI Spec",
+                            ExportFormat::RustProject => tr("export.rust_project"),
+                            ExportFormat::JsonSchema => tr("export.json_schema"),
+                            ExportFormat::OpenApi => tr("export.openapi"),
                         })
                         .show_ui(ui, |ui| {
-                            ui.selectable_value(&mut self.format, ExportFormat::RustProject, "Rust Project");
-                            ui.selectable_value(&mut self.format, ExportFormat::JsonSchema, "JSON Schema");
-                            ui.selectable_value(&mut self.format, ExportFormat::OpenApi, "OpenAPI Spec");
+                            ui.selectable_value(&mut self.format, ExportFormat::RustProject, tr("export.rust_project"));
+                            ui.selectable_value(&mut self.format, ExportFormat::JsonSchema, tr("export.json_schema"));
+                            ui.selectable_value(&mut self.format, ExportFormat::OpenApi, tr("export.openapi"));
                         });
                 });
 
-                ui.checkbox(&mut self.include_tests, "Generate Tests");
-                ui.checkbox(&mut self.include_docs, "Generate Documentation");
+                ui.checkbox(&mut self.include_tests, tr("export.tests"));
+                ui.checkbox(&mut self.include_docs, tr("export.docs"));
 
                 ui.add_space(20.0);
 
                 ui.horizontal(|ui| {
-                    if ui.button("Export").clicked() {
+                    if ui.button(tr("export.export")).clicked() {
                         result = DialogResult::Confirmed;
                         self.visible = false;
                     }
-                    if ui.button("Cancel").clicked() {
+                    if ui.button(tr("dialog.cancel")).clicked() {
                         result = DialogResult::Cancelled;
                         self.visible = false;
                     }
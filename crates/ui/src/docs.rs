@@ -0,0 +1,353 @@
+//! Component documentation for Immortal Engine UI
+//!
+//! The popover opened from the palette and the Help > Component Reference
+//! browser both show a component's Markdown docs, its ports, example graphs
+//! that can be inserted into the project, and a preview of the code it
+//! generates. Inserting an example is returned as a [`DocsAction`] for the
+//! app to apply, so it goes through undo.
+
+use std::collections::HashMap;
+
+use eframe::egui;
+use egui_commonmark::{CommonMarkCache, CommonMarkViewer};
+use imortal_codegen::{CodeGenerator, GeneratorConfig};
+use imortal_components::{ComponentDefinition, ComponentExample, ComponentRegistry, PortDefinition};
+use imortal_core::ComponentCategory;
+use imortal_ir::ProjectMeta;
+
+use crate::i18n::{self, tr};
+
+/// Something the user asked for in the docs
+#[derive(Debug, Clone, PartialEq)]
+pub enum DocsAction {
+    /// Add an example graph to the project
+    InsertExample(ComponentExample),
+}
+
+/// Generated files for one component, or why generation failed
+type Preview = Result<Vec<(String, String)>, String>;
+
+/// State of the docs popover and the component reference browser
+pub struct ComponentDocsPanel {
+    /// Component shown in the popover, and where it was opened
+    popover: Option<(String, egui::Pos2)>,
+    /// Whether the reference browser is open
+    pub reference_visible: bool,
+    /// Component selected in the reference browser
+    selected: Option<String>,
+    /// Search text in the reference browser
+    search: String,
+    /// Rendered Markdown
+    markdown: CommonMarkCache,
+    /// Generated code per component, computed when first expanded
+    previews: HashMap<String, Preview>,
+}
+
+impl Default for ComponentDocsPanel {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ComponentDocsPanel {
+    pub fn new() -> Self {
+        Self {
+            popover: None,
+            reference_visible: false,
+            selected: None,
+            search: String::new(),
+            markdown: CommonMarkCache::default(),
+            previews: HashMap::new(),
+        }
+    }
+
+    /// Show a component's docs in a popover at `pos`
+    pub fn open_popover(&mut self, component_id: &str, pos: egui::Pos2) {
+        self.popover = Some((component_id.to_string(), pos));
+        // Project settings may have changed what the component generates
+        self.previews.clear();
+    }
+
+    /// Open the reference browser, optionally at one component
+    pub fn open_reference(&mut self, component_id: Option<&str>) {
+        self.reference_visible = true;
+        if let Some(id) = component_id {
+            self.selected = Some(id.to_string());
+        }
+        self.previews.clear();
+    }
+
+    /// Whether the popover or the reference browser is showing
+    pub fn is_open(&self) -> bool {
+        self.popover.is_some() || self.reference_visible
+    }
+
+    /// Render the popover and the reference browser
+    pub fn show(&mut self, ctx: &egui::Context, registry: &ComponentRegistry, meta: &ProjectMeta) -> Vec<DocsAction> {
+        let mut actions = Vec::new();
+
+        if let Some((id, pos)) = self.popover.clone() {
+            let mut open = true;
+            match registry.get(&id) {
+                Some(component) => {
+                    egui::Window::new(format!("{} {}", component.icon_text(), display_name(component)))
+                        .id(egui::Id::new("component_docs_popover"))
+                        .open(&mut open)
+                        .collapsible(false)
+                        .resizable(true)
+                        .default_pos(pos)
+                        .default_size([380.0, 420.0])
+                        .show(ctx, |ui| {
+                            egui::ScrollArea::vertical().auto_shrink([false, false]).show(ui, |ui| {
+                                actions.extend(self.component_docs(ui, component, meta));
+                            });
+                        });
+                }
+                None => open = false,
+            }
+            if !open {
+                self.popover = None;
+            }
+        }
+
+        if self.reference_visible {
+            let mut open = true;
+            egui::Window::new(tr("docs.reference"))
+                .open(&mut open)
+                .resizable(true)
+                .default_size([720.0, 520.0])
+                .show(ctx, |ui| {
+                    egui::SidePanel::left("component_reference_list")
+                        .resizable(true)
+                        .default_width(200.0)
+                        .show_inside(ui, |ui| self.reference_list(ui, registry));
+                    egui::CentralPanel::default().show_inside(ui, |ui| {
+                        let selected = self.selected.as_deref().and_then(|id| registry.get(id));
+                        egui::ScrollArea::vertical().auto_shrink([false, false]).show(ui, |ui| match selected {
+                            Some(component) => actions.extend(self.component_docs(ui, component, meta)),
+                            None => {
+                                ui.weak(tr("docs.pick_component"));
+                            }
+                        });
+                    });
+                });
+            self.reference_visible = open;
+        }
+
+        actions
+    }
+
+    /// Searchable list of components by category
+    fn reference_list(&mut self, ui: &mut egui::Ui, registry: &ComponentRegistry) {
+        ui.horizontal(|ui| {
+            ui.label("🔍");
+            ui.text_edit_singleline(&mut self.search);
+        });
+        ui.separator();
+
+        let search = self.search.to_lowercase();
+        egui::ScrollArea::vertical().auto_shrink([false, false]).show(ui, |ui| {
+            for category in ComponentCategory::all() {
+                let mut components: Vec<_> = registry
+                    .by_category(*category)
+                    .into_iter()
+                    .filter(|c| search.is_empty() || matches_search(c, &search))
+                    .collect();
+                if components.is_empty() {
+                    continue;
+                }
+                components.sort_by_key(|c| display_name(c));
+
+                ui.label(egui::RichText::new(format!("{} {}", category.icon(), category.display_name())).strong());
+                for component in components {
+                    let selected = self.selected.as_deref() == Some(component.id.as_str());
+                    let label = format!("{} {}", component.icon_text(), display_name(component));
+                    if ui.selectable_label(selected, label).clicked() {
+                        self.selected = Some(component.id.clone());
+                    }
+                }
+                ui.add_space(4.0);
+            }
+        });
+    }
+
+    /// A component's docs, ports, examples, and generated code
+    fn component_docs(&mut self, ui: &mut egui::Ui, component: &ComponentDefinition, meta: &ProjectMeta) -> Vec<DocsAction> {
+        let mut actions = Vec::new();
+
+        ui.horizontal_wrapped(|ui| {
+            ui.weak(format!("{} · {}", component.category.display_name(), component.id));
+            for tag in &component.tags {
+                ui.label(egui::RichText::new(format!("#{}", tag)).small().color(egui::Color32::GRAY));
+            }
+        });
+        if component.deprecated {
+            let message = component.deprecation_message.as_deref().unwrap_or_default();
+            ui.colored_label(egui::Color32::from_rgb(220, 160, 60), format!("⚠ {} {}", tr("docs.deprecated"), message));
+        }
+        ui.separator();
+
+        CommonMarkViewer::new().show(ui, &mut self.markdown, component.documentation());
+        if let Some(url) = &component.docs_url {
+            ui.hyperlink_to(tr("docs.more"), url);
+        }
+
+        if !component.ports.inputs.is_empty() || !component.ports.outputs.is_empty() {
+            egui::CollapsingHeader::new(tr("docs.ports"))
+                .id_salt(("docs_ports", &component.id))
+                .show(ui, |ui| {
+                    port_list(ui, "→", &component.ports.inputs);
+                    port_list(ui, "←", &component.ports.outputs);
+                });
+        }
+
+        if !component.docs.examples.is_empty() {
+            egui::CollapsingHeader::new(tr("docs.examples"))
+                .id_salt(("docs_examples", &component.id))
+                .default_open(true)
+                .show(ui, |ui| {
+                    for example in &component.docs.examples {
+                        ui.group(|ui| {
+                            ui.horizontal(|ui| {
+                                ui.strong(&example.title);
+                                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                                    if ui.small_button(tr("docs.insert_example")).clicked() {
+                                        actions.push(DocsAction::InsertExample(example.clone()));
+                                    }
+                                });
+                            });
+                            if !example.description.is_empty() {
+                                ui.label(&example.description);
+                            }
+                            for line in example_lines(example) {
+                                ui.monospace(line);
+                            }
+                        });
+                    }
+                });
+        }
+
+        egui::CollapsingHeader::new(tr("docs.generated_code"))
+            .id_salt(("docs_code", &component.id))
+            .show(ui, |ui| {
+                let preview = self.previews.entry(component.id.clone()).or_insert_with(|| {
+                    CodeGenerator::with_config(GeneratorConfig::for_project(meta))
+                        .preview(component.instantiate_default())
+                        .map_err(|e| e.to_string())
+                });
+                match preview {
+                    Ok(files) if files.is_empty() => {
+                        ui.weak(tr("docs.no_code"));
+                    }
+                    Ok(files) => {
+                        for (path, content) in files.iter() {
+                            egui::CollapsingHeader::new(egui::RichText::new(path.as_str()).monospace())
+                                .id_salt(("docs_file", &component.id, path.as_str()))
+                                .show(ui, |ui| {
+                                    egui::ScrollArea::both()
+                                        .id_salt(("docs_file_scroll", path.as_str()))
+                                        .max_height(240.0)
+                                        .show(ui, |ui| {
+                                            ui.add(
+                                                egui::TextEdit::multiline(&mut content.as_str())
+                                                    .code_editor()
+                                                    .desired_width(f32::INFINITY),
+                                            );
+                                        });
+                                });
+                        }
+                    }
+                    Err(e) => {
+                        ui.colored_label(egui::Color32::from_rgb(220, 80, 80), e.as_str());
+                    }
+                }
+            });
+
+        actions
+    }
+}
+
+/// Tooltip shown when hovering a component in the palette
+pub fn component_tooltip(ui: &mut egui::Ui, component: &ComponentDefinition) {
+    ui.set_max_width(320.0);
+    ui.strong(display_name(component));
+    ui.label(description(component));
+    if !component.tags.is_empty() {
+        ui.horizontal_wrapped(|ui| {
+            for tag in &component.tags {
+                ui.label(egui::RichText::new(format!("#{}", tag)).small().color(egui::Color32::GRAY));
+            }
+        });
+    }
+    ui.add_space(4.0);
+    ui.weak(tr("docs.hover_hint"));
+}
+
+fn port_list(ui: &mut egui::Ui, arrow: &str, ports: &[PortDefinition]) {
+    for port in ports {
+        ui.horizontal_wrapped(|ui| {
+            ui.monospace(format!("{} {}", arrow, port.id));
+            ui.label(&port.name);
+            if let Some(description) = &port.description {
+                ui.weak(description);
+            }
+        });
+    }
+}
+
+/// One line per connection of an example, e.g. `Login.token → Session.token`;
+/// unconnected nodes get a line of their own
+pub fn example_lines(example: &ComponentExample) -> Vec<String> {
+    let name = |index: usize| example.nodes.get(index).map_or("?", |(_, name)| name.as_str());
+    let mut lines: Vec<String> = example
+        .connections
+        .iter()
+        .map(|c| format!("{}.{} → {}.{}", name(c.from), c.from_port, name(c.to), c.to_port))
+        .collect();
+    for (index, (component_id, node_name)) in example.nodes.iter().enumerate() {
+        if !example.connections.iter().any(|c| c.from == index || c.to == index) {
+            lines.push(format!("{} ({})", node_name, component_id));
+        }
+    }
+    lines
+}
+
+/// Component name in the current language
+fn display_name(component: &ComponentDefinition) -> String {
+    i18n::lookup(&format!("component.{}.name", component.id)).unwrap_or(&component.name).to_string()
+}
+
+/// Component description in the current language
+fn description(component: &ComponentDefinition) -> String {
+    i18n::lookup(&format!("component.{}.description", component.id)).unwrap_or(&component.description).to_string()
+}
+
+fn matches_search(component: &ComponentDefinition, search: &str) -> bool {
+    display_name(component).to_lowercase().contains(search)
+        || description(component).to_lowercase().contains(search)
+        || component.tags.iter().any(|tag| tag.to_lowercase().contains(search))
+        || component.docs.body.to_lowercase().contains(search)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_example_lines() {
+        let example = ComponentExample::new("Login with a session")
+            .with_node("auth.login", "Login")
+            .with_node("auth.session", "Session")
+            .with_node("config.settings", "Settings")
+            .with_connection(0, "token", 1, "token");
+        assert_eq!(example_lines(&example), ["Login.token → Session.token", "Settings (config.settings)"]);
+    }
+
+    #[test]
+    fn test_reference_search_matches_docs() {
+        let registry = ComponentRegistry::with_builtins();
+        let entity = registry.get("data.entity").unwrap();
+        assert!(matches_search(entity, "soft delete"));
+        assert!(!matches_search(entity, "smtp"));
+    }
+}
@@ -0,0 +1,87 @@
+//! Editor events for embedders
+//!
+//! Applications that embed [`ImmortalApp`](crate::ImmortalApp) can register
+//! an [`EditorEvents`] listener to hear about what the user does: nodes
+//! added, validation results, code generation runs, and so on. Nothing is
+//! collected or sent anywhere unless a listener is registered, and the
+//! listener decides what to do with each event.
+//!
+//! ```no_run
+//! use imortal_ui::events::EditorEvent;
+//! use imortal_ui::ImmortalApp;
+//!
+//! # fn app(cc: &eframe::CreationContext<'_>) -> ImmortalApp {
+//! ImmortalApp::new(cc).with_event_listener(|event: &EditorEvent| {
+//!     if let EditorEvent::ValidationFailed { errors } = event {
+//!         eprintln!("{} validation error(s)", errors.len());
+//!     }
+//! })
+//! # }
+//! ```
+
+use std::path::PathBuf;
+
+use imortal_core::{EdgeId, NodeId};
+
+/// Something the user did in the editor
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum EditorEvent {
+    /// A project was opened from disk
+    ProjectOpened { name: String, path: PathBuf },
+    /// The live project was saved
+    ProjectSaved { path: PathBuf },
+    /// A component was added to the project
+    NodeAdded { id: NodeId, component_type: String },
+    /// Nodes and connections were deleted
+    Deleted { nodes: usize, edges: usize },
+    /// Two nodes were connected
+    ConnectionCreated { id: EdgeId },
+    /// Another project was merged into the live one
+    ProjectImported { name: String, nodes_added: usize },
+    /// Validation found no errors
+    ValidationPassed,
+    /// Validation found errors, one message each
+    ValidationFailed { errors: Vec<String> },
+    /// Code generation started
+    GenerationStarted { output_dir: PathBuf },
+    /// Code generation wrote its files
+    GenerationFinished { output_dir: PathBuf, files: usize, warnings: usize },
+    /// Code generation failed
+    GenerationFailed { error: String },
+}
+
+/// Receives editor events
+///
+/// Implemented for any `FnMut(&EditorEvent)` closure. Listeners are called
+/// on the UI thread while the editor updates, so they should return quickly
+/// and hand slow work to a thread of their own.
+pub trait EditorEvents {
+    fn on_event(&mut self, event: &EditorEvent);
+}
+
+impl<F: FnMut(&EditorEvent)> EditorEvents for F {
+    fn on_event(&mut self, event: &EditorEvent) {
+        self(event)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    #[test]
+    fn test_closure_listener() {
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let mut listener: Box<dyn EditorEvents> = Box::new({
+            let seen = Rc::clone(&seen);
+            move |event: &EditorEvent| seen.borrow_mut().push(event.clone())
+        });
+
+        listener.on_event(&EditorEvent::ValidationPassed);
+        listener.on_event(&EditorEvent::Deleted { nodes: 2, edges: 1 });
+        assert_eq!(*seen.borrow(), [EditorEvent::ValidationPassed, EditorEvent::Deleted { nodes: 2, edges: 1 }]);
+    }
+}
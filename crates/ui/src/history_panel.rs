@@ -0,0 +1,225 @@
+//! History timeline panel
+//!
+//! [`HistoryPanel`] lists every state in the history, oldest at the top, each
+//! with the action that led to it, when it happened, and a thumbnail of the
+//! diagram. An edit made after jumping back starts a new branch; the states
+//! it left behind stay listed, indented under the state they branched from.
+//! Clicking any state jumps straight to it.
+
+use std::collections::HashMap;
+use std::time::SystemTime;
+
+use eframe::egui;
+use imortal_core::ComponentCategory;
+use imortal_ir::ProjectGraph;
+
+use crate::i18n::{tr, trf};
+use crate::state::History;
+use crate::theme::CategoryOverrides;
+
+/// Size of a state's thumbnail
+const THUMBNAIL_SIZE: egui::Vec2 = egui::vec2(72.0, 48.0);
+
+/// Indent of each level of branching
+const BRANCH_INDENT: f32 = 12.0;
+
+/// State of the History panel
+#[derive(Debug, Default)]
+pub struct HistoryPanel {
+    /// Whether the panel is showing
+    pub visible: bool,
+}
+
+impl HistoryPanel {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Show the panel at the right of the window; call before the central
+    /// panel. Returns the timeline index of a state the user clicked.
+    pub fn show(
+        &mut self,
+        ctx: &egui::Context,
+        history: &History,
+        current: &ProjectGraph,
+        styles: &CategoryOverrides,
+    ) -> Option<usize> {
+        if !self.visible {
+            return None;
+        }
+        let timeline = history.timeline();
+        let current_index = history.current_index();
+        let mut clicked = None;
+
+        egui::SidePanel::right("history_panel")
+            .resizable(true)
+            .default_width(220.0)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.strong(tr("timeline.title"));
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        if ui.small_button("✕").clicked() {
+                            self.visible = false;
+                        }
+                    });
+                });
+                ui.small(tr("timeline.hint"));
+                ui.separator();
+
+                if timeline.len() == 1 {
+                    ui.weak(tr("timeline.empty"));
+                    return;
+                }
+                egui::ScrollArea::vertical().auto_shrink([false, false]).show(ui, |ui| {
+                    for (index, entry) in timeline.iter().enumerate() {
+                        let project = entry.snapshot.unwrap_or(current);
+                        let is_current = index == current_index;
+                        let name = entry.action_name.map_or_else(|| tr("timeline.start").to_string(), str::to_string);
+
+                        let response = ui
+                            .horizontal(|ui| {
+                                ui.add_space(entry.depth as f32 * BRANCH_INDENT);
+                                let (rect, _) = ui.allocate_exact_size(THUMBNAIL_SIZE, egui::Sense::hover());
+                                paint_thumbnail(ui.painter(), rect, project, styles, entry.ahead);
+                                ui.vertical(|ui| {
+                                    let mut text = egui::RichText::new(name);
+                                    if is_current {
+                                        text = text.strong();
+                                    } else if entry.ahead {
+                                        text = text.weak();
+                                    }
+                                    ui.label(text);
+                                    if let Some(timestamp) = entry.timestamp {
+                                        ui.small(elapsed(timestamp));
+                                    }
+                                    if is_current {
+                                        ui.small(tr("timeline.current"));
+                                    }
+                                });
+                            })
+                            .response;
+                        let response = ui.interact(response.rect, ui.id().with(("history_entry", index)), egui::Sense::click());
+                        if is_current {
+                            ui.painter().rect_stroke(response.rect.expand(2.0), 4.0, ui.visuals().selection.stroke);
+                        } else if response.hovered() {
+                            ui.painter().rect_stroke(response.rect.expand(2.0), 4.0, ui.visuals().widgets.hovered.bg_stroke);
+                        }
+                        if response.clicked() && !is_current {
+                            clicked = Some(index);
+                        }
+                        ui.add_space(4.0);
+                    }
+                });
+            });
+
+        clicked
+    }
+}
+
+/// A diagram scaled down to a thumbnail
+#[derive(Debug, Default)]
+struct Thumbnail {
+    /// Screen rect and category of each node
+    nodes: Vec<(egui::Rect, ComponentCategory)>,
+    /// Edges as pairs of indices into `nodes`
+    edges: Vec<(usize, usize)>,
+}
+
+/// The nodes and edges of `project`, scaled to fit `rect`
+fn thumbnail_layout(project: &ProjectGraph, rect: egui::Rect) -> Thumbnail {
+    let mut nodes: Vec<_> = project.nodes.values().collect();
+    nodes.sort_by_key(|node| node.id);
+    let Some(bounds) = nodes
+        .iter()
+        .map(|node| {
+            let (x, y, width, height) = node.effective_bounds();
+            egui::Rect::from_min_size(egui::pos2(x, y), egui::vec2(width, height))
+        })
+        .reduce(|a, b| a.union(b))
+    else {
+        return Thumbnail::default();
+    };
+
+    let area = rect.shrink(4.0);
+    let scale = (area.width() / bounds.width().max(1.0)).min(area.height() / bounds.height().max(1.0));
+    let offset = area.center() - bounds.center().to_vec2() * scale;
+    let rects = nodes
+        .iter()
+        .map(|node| {
+            let (x, y, width, height) = node.effective_bounds();
+            let min = offset + egui::vec2(x, y) * scale;
+            (egui::Rect::from_min_size(min, egui::vec2(width, height) * scale), node.category)
+        })
+        .collect();
+
+    let index: HashMap<_, _> = nodes.iter().enumerate().map(|(i, node)| (node.id, i)).collect();
+    let edges = project
+        .edges
+        .values()
+        .filter_map(|edge| Some((*index.get(&edge.from_node)?, *index.get(&edge.to_node)?)))
+        .collect();
+    Thumbnail { nodes: rects, edges }
+}
+
+/// Draw a miniature of the diagram, dimmed for states ahead of the current one
+fn paint_thumbnail(painter: &egui::Painter, rect: egui::Rect, project: &ProjectGraph, styles: &CategoryOverrides, dimmed: bool) {
+    let visuals = painter.ctx().style().visuals.clone();
+    painter.rect_filled(rect, 3.0, visuals.extreme_bg_color);
+    painter.rect_stroke(rect, 3.0, visuals.widgets.noninteractive.bg_stroke);
+
+    let opacity = if dimmed { 0.4 } else { 1.0 };
+    let Thumbnail { nodes, edges } = thumbnail_layout(project, rect);
+    let edge_stroke = egui::Stroke::new(0.5, visuals.weak_text_color().gamma_multiply(opacity));
+    for (from, to) in edges {
+        painter.line_segment([nodes[from].0.center(), nodes[to].0.center()], edge_stroke);
+    }
+    for (node, category) in nodes {
+        painter.rect_filled(node, 1.0, styles.header_color(category).gamma_multiply(opacity));
+    }
+}
+
+/// How long ago an action was, e.g. "3 min ago"
+fn elapsed(time: SystemTime) -> String {
+    let minutes = time.elapsed().map_or(0, |d| d.as_secs() / 60);
+    match minutes {
+        0 => tr("history.just_now").to_string(),
+        1..60 => trf("history.minutes_ago", &[&minutes]),
+        _ => trf("history.hours_ago", &[&(minutes / 60)]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use imortal_core::{Position, Size};
+    use imortal_ir::{Edge, Node};
+
+    #[test]
+    fn test_thumbnail_layout() {
+        let rect = egui::Rect::from_min_size(egui::pos2(100.0, 100.0), THUMBNAIL_SIZE);
+        let mut project = ProjectGraph::with_name("test");
+        assert!(thumbnail_layout(&project, rect).nodes.is_empty());
+
+        let mut user = Node::new_entity("User");
+        user.position = Position::new(0.0, 0.0);
+        user.size = Size::new(200.0, 100.0);
+        let mut post = Node::new_entity("Post");
+        post.position = Position::new(600.0, 300.0);
+        post.size = Size::new(200.0, 100.0);
+        let (user_id, post_id) = (user.id, post.id);
+        project.add_node(user);
+        project.add_node(post);
+        let edge = Edge::new(user_id, "out", post_id, "in");
+        project.edges.insert(edge.id, edge);
+
+        let Thumbnail { nodes, edges } = thumbnail_layout(&project, rect);
+        assert_eq!(nodes.len(), 2);
+        assert_eq!(edges.len(), 1);
+        // The diagram is 800 by 400, so width limits the scale
+        let area = rect.shrink(4.0);
+        let all = nodes.iter().map(|(r, _)| *r).reduce(|a, b| a.union(b)).unwrap();
+        assert!((all.width() - area.width()).abs() < 1e-3);
+        assert!(area.expand(1e-3).contains_rect(all));
+        assert!((all.center() - area.center()).length() < 1e-3);
+    }
+}
@@ -0,0 +1,105 @@
+//! Approval of a project's generation hooks
+//!
+//! A project file can carry shell commands to run around code generation,
+//! so opening someone else's project and generating it would run their
+//! commands. Before running hooks that came from a file, the editor asks
+//! once, and [`HookApprovals`] remembers the answer for that file and those
+//! exact commands: when the hooks change, it asks again.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+/// An answer for one project file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HookApproval {
+    /// [`GenerationHooks::commands_hash`](imortal_codegen::GenerationHooks::commands_hash)
+    /// of the hooks the answer was for
+    pub hash: String,
+    /// Whether they may run
+    pub allowed: bool,
+}
+
+/// Answers given about project hooks, by project file
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct HookApprovals {
+    #[serde(default)]
+    pub projects: BTreeMap<PathBuf, HookApproval>,
+}
+
+impl HookApprovals {
+    /// Where the answers are saved
+    pub fn path() -> Option<PathBuf> {
+        dirs::config_dir().map(|p| p.join("immortal-engine").join("hook_approvals.json"))
+    }
+
+    /// The saved answers, or none when there are none
+    pub fn load() -> Self {
+        Self::path().map(|path| Self::load_from(&path)).unwrap_or_default()
+    }
+
+    /// The answers saved at `path`, or none when it can't be read
+    pub fn load_from(path: &Path) -> Self {
+        std::fs::read_to_string(path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) -> std::io::Result<()> {
+        match Self::path() {
+            Some(path) => self.save_to(&path),
+            None => Ok(()),
+        }
+    }
+
+    pub fn save_to(&self, path: &Path) -> std::io::Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(self)?)
+    }
+
+    /// The answer given for `project` with hooks hashing to `hash`, or
+    /// `None` if it hasn't been asked about these hooks
+    pub fn decision(&self, project: &Path, hash: &str) -> Option<bool> {
+        self.projects.get(project).filter(|approval| approval.hash == hash).map(|approval| approval.allowed)
+    }
+
+    /// Remember an answer, replacing any for other hooks of the same file
+    pub fn record(&mut self, project: &Path, hash: &str, allowed: bool) {
+        self.projects.insert(project.to_path_buf(), HookApproval { hash: hash.to_string(), allowed });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hook_approvals() {
+        let dir = std::env::temp_dir().join(format!("imortal-hooks-{}", uuid::Uuid::new_v4()));
+        let path = dir.join("hook_approvals.json");
+        let mut approvals = HookApprovals::load_from(&path);
+        assert_eq!(approvals, HookApprovals::default());
+
+        let shop = Path::new("/projects/shop.imortal");
+        let blog = Path::new("/projects/blog.imortal");
+        approvals.record(shop, "1111", true);
+        approvals.record(blog, "2222", false);
+        approvals.save_to(&path).unwrap();
+
+        let mut approvals = HookApprovals::load_from(&path);
+        assert_eq!(approvals.decision(shop, "1111"), Some(true));
+        assert_eq!(approvals.decision(blog, "2222"), Some(false));
+        // Changed hooks, or the same hooks in another file, are asked again
+        assert_eq!(approvals.decision(shop, "3333"), None);
+        assert_eq!(approvals.decision(Path::new("/elsewhere/shop.imortal"), "1111"), None);
+
+        approvals.record(shop, "3333", false);
+        assert_eq!(approvals.decision(shop, "1111"), None);
+        assert_eq!(approvals.decision(shop, "3333"), Some(false));
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -0,0 +1,175 @@
+//! Localization for Immortal Engine UI
+//!
+//! Strings are looked up by key in per-language catalogs shipped in
+//! `crates/ui/locales/<code>.txt`. Each catalog line is `key = value`;
+//! blank lines and lines starting with `#` are ignored, and `{}` marks a
+//! placeholder filled in by [`trf`]. Keys missing from a catalog fall back
+//! to English, then to the key itself, so a partial translation never
+//! leaves a label empty.
+//!
+//! Adding a language means adding a catalog file and a [`Language`] variant.
+
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::sync::OnceLock;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+/// A UI language with a shipped catalog
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, serde::Serialize, serde::Deserialize)]
+pub enum Language {
+    #[default]
+    English,
+    Spanish,
+}
+
+impl Language {
+    /// All supported languages, in picker order
+    pub fn all() -> &'static [Language] {
+        &[Language::English, Language::Spanish]
+    }
+
+    /// ISO 639-1 code, also the catalog file name
+    pub fn code(&self) -> &'static str {
+        match self {
+            Language::English => "en",
+            Language::Spanish => "es",
+        }
+    }
+
+    /// Name of the language in that language, for the picker
+    pub fn native_name(&self) -> &'static str {
+        match self {
+            Language::English => "English",
+            Language::Spanish => "Español",
+        }
+    }
+
+    fn source(&self) -> &'static str {
+        match self {
+            Language::English => include_str!("../locales/en.txt"),
+            Language::Spanish => include_str!("../locales/es.txt"),
+        }
+    }
+
+    fn catalog(&self) -> &'static HashMap<&'static str, &'static str> {
+        static ENGLISH: OnceLock<HashMap<&'static str, &'static str>> = OnceLock::new();
+        static SPANISH: OnceLock<HashMap<&'static str, &'static str>> = OnceLock::new();
+        let cell = match self {
+            Language::English => &ENGLISH,
+            Language::Spanish => &SPANISH,
+        };
+        cell.get_or_init(|| parse_catalog(self.source()))
+    }
+
+    fn from_index(index: u8) -> Self {
+        Self::all().get(index as usize).copied().unwrap_or_default()
+    }
+
+    fn index(&self) -> u8 {
+        Self::all().iter().position(|l| l == self).unwrap_or(0) as u8
+    }
+}
+
+static CURRENT: AtomicU8 = AtomicU8::new(0);
+
+/// Switch the UI language
+pub fn set_language(language: Language) {
+    CURRENT.store(language.index(), Ordering::Relaxed);
+}
+
+/// The current UI language
+pub fn language() -> Language {
+    Language::from_index(CURRENT.load(Ordering::Relaxed))
+}
+
+/// Translate a key into the current language
+pub fn tr(key: &'static str) -> &'static str {
+    translate(language(), key).unwrap_or(key)
+}
+
+/// Translate a key and fill its `{}` placeholders in order
+pub fn trf(key: &'static str, args: &[&dyn Display]) -> String {
+    fill(tr(key), args)
+}
+
+/// Translate a key built at runtime, e.g. `component.<id>.name`
+pub fn lookup(key: &str) -> Option<&'static str> {
+    translate(language(), key)
+}
+
+/// Translate a key into a specific language, falling back to English
+pub fn translate(language: Language, key: &str) -> Option<&'static str> {
+    language
+        .catalog()
+        .get(key)
+        .or_else(|| Language::English.catalog().get(key))
+        .copied()
+}
+
+fn fill(template: &str, args: &[&dyn Display]) -> String {
+    let mut parts = template.split("{}");
+    let mut out = parts.next().unwrap_or_default().to_string();
+    for (index, part) in parts.enumerate() {
+        if let Some(arg) = args.get(index) {
+            out.push_str(&arg.to_string());
+        }
+        out.push_str(part);
+    }
+    out
+}
+
+fn parse_catalog(source: &'static str) -> HashMap<&'static str, &'static str> {
+    source
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| line.split_once('='))
+        .map(|(key, value)| (key.trim(), value.trim()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_catalogs_cover_english_keys() {
+        let english = Language::English.catalog();
+        assert!(!english.is_empty());
+        for language in Language::all() {
+            let catalog = language.catalog();
+            for key in english.keys() {
+                assert!(catalog.contains_key(key), "{} catalog is missing '{}'", language.code(), key);
+            }
+            for key in catalog.keys() {
+                assert!(english.contains_key(key), "{} catalog has unknown key '{}'", language.code(), key);
+            }
+        }
+    }
+
+    #[test]
+    fn test_catalog_covers_builtin_components() {
+        let registry = imortal_components::ComponentRegistry::with_builtins();
+        let english = Language::English.catalog();
+        for component in registry.all() {
+            for part in ["name", "description"] {
+                let key = format!("component.{}.{}", component.id, part);
+                assert!(english.contains_key(key.as_str()), "catalog is missing '{}'", key);
+            }
+        }
+    }
+
+    #[test]
+    fn test_translate_and_fallback() {
+        assert_eq!(translate(Language::English, "menu.file"), Some("File"));
+        assert_eq!(translate(Language::Spanish, "menu.file"), Some("Archivo"));
+        assert_eq!(translate(Language::Spanish, "no.such.key"), None);
+    }
+
+    #[test]
+    fn test_fill_placeholders() {
+        assert_eq!(fill("Opened project: {}", &[&"Shop"]), "Opened project: Shop");
+        assert_eq!(fill("{} of {}", &[&1, &3]), "1 of 3");
+        assert_eq!(fill("No placeholders", &[&1]), "No placeholders");
+    }
+}
@@ -0,0 +1,143 @@
+//! Image icons for components
+//!
+//! Component and node icons are usually emoji, drawn as text. Icons naming
+//! an SVG or PNG ([`IconSource`]) are loaded through egui's image loaders,
+//! which keep one texture per icon and size. Sizes are rounded up to a few
+//! steps in physical pixels, so SVGs are rasterized again when zooming
+//! changes the size noticeably, and stay crisp, without a new texture
+//! on every frame of a zoom. [`IconCache`] remembers which files it
+//! loaded so they can be read again after they change.
+
+use std::cell::RefCell;
+use std::collections::HashSet;
+
+use eframe::egui;
+use egui::load::{SizeHint, SizedTexture, TexturePoll};
+use imortal_core::IconSource;
+
+/// Icons bundled with the editor, used as `builtin:<name>`
+pub const BUILTIN_ICONS: [(&str, &[u8]); 8] = [
+    ("api.svg", include_bytes!("../assets/icons/api.svg")),
+    ("bolt.svg", include_bytes!("../assets/icons/bolt.svg")),
+    ("database.svg", include_bytes!("../assets/icons/database.svg")),
+    ("file.svg", include_bytes!("../assets/icons/file.svg")),
+    ("gear.svg", include_bytes!("../assets/icons/gear.svg")),
+    ("lock.svg", include_bytes!("../assets/icons/lock.svg")),
+    ("table.svg", include_bytes!("../assets/icons/table.svg")),
+    ("user.svg", include_bytes!("../assets/icons/user.svg")),
+];
+
+/// Physical pixel sizes are rounded up to a multiple of this
+const SIZE_STEP: u32 = 16;
+/// Largest size an icon is rasterized at, in physical pixels
+const MAX_SIZE: u32 = 512;
+
+/// Install the image loaders and the bundled icons; call once at startup
+pub fn install(ctx: &egui::Context) {
+    egui_extras::install_image_loaders(ctx);
+    for (name, bytes) in BUILTIN_ICONS {
+        ctx.include_bytes(builtin_uri(name), bytes);
+    }
+}
+
+fn builtin_uri(name: &str) -> String {
+    format!("bytes://icons/{}", name)
+}
+
+/// Image URI of an icon, or `None` for a text icon
+pub fn uri(icon: &str) -> Option<String> {
+    match IconSource::parse(icon) {
+        IconSource::Text(_) => None,
+        IconSource::Builtin(name) => Some(builtin_uri(name)),
+        IconSource::File(path) => Some(format!("file://{}", path)),
+    }
+}
+
+/// Size to rasterize an icon drawn `points` tall at `pixels_per_point`
+pub fn size_hint(points: f32, pixels_per_point: f32) -> SizeHint {
+    let pixels = (points * pixels_per_point).ceil().max(1.0) as u32;
+    SizeHint::Height(pixels.div_ceil(SIZE_STEP).saturating_mul(SIZE_STEP).min(MAX_SIZE))
+}
+
+/// Loads image icons and keeps track of the files among them
+#[derive(Default)]
+pub struct IconCache {
+    /// URIs of icon files loaded so far
+    files: RefCell<HashSet<String>>,
+}
+
+impl IconCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Texture of an image icon at `size` points, or `None` for text icons
+    /// and images that are still loading or failed to load
+    pub fn texture(&self, ctx: &egui::Context, icon: &str, size: f32) -> Option<SizedTexture> {
+        let uri = uri(icon)?;
+        let hint = size_hint(size, ctx.pixels_per_point());
+        let result = ctx.try_load_texture(&uri, egui::TextureOptions::LINEAR, hint);
+        if uri.starts_with("file://") {
+            self.files.borrow_mut().insert(uri.clone());
+        }
+        match result {
+            Ok(TexturePoll::Ready { texture }) => Some(texture),
+            Ok(TexturePoll::Pending { .. }) => None,
+            Err(e) => {
+                tracing::debug!("Failed to load icon {}: {}", uri, e);
+                None
+            }
+        }
+    }
+
+    /// Draw an image icon centred in `rect`, keeping its aspect ratio;
+    /// returns false if there is no image to draw
+    pub fn paint(&self, painter: &egui::Painter, icon: &str, rect: egui::Rect) -> bool {
+        let Some(texture) = self.texture(painter.ctx(), icon, rect.height()) else {
+            return false;
+        };
+        let scale = (rect.width() / texture.size.x).min(rect.height() / texture.size.y);
+        let image_rect = egui::Rect::from_center_size(rect.center(), texture.size * scale);
+        let uv = egui::Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0));
+        painter.image(texture.id, image_rect, uv, egui::Color32::WHITE);
+        true
+    }
+
+    /// An image widget for an image icon, `size` points square
+    pub fn image(&self, icon: &str, size: f32) -> Option<egui::Image<'static>> {
+        let uri = uri(icon)?;
+        if uri.starts_with("file://") {
+            self.files.borrow_mut().insert(uri.clone());
+        }
+        Some(egui::Image::new(uri).fit_to_exact_size(egui::vec2(size, size)))
+    }
+
+    /// Drop loaded icon files so changed files are read again
+    pub fn reload(&self, ctx: &egui::Context) {
+        for uri in self.files.borrow_mut().drain() {
+            ctx.forget_image(&uri);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_icon_uris() {
+        assert_eq!(uri("🔐"), None);
+        assert_eq!(uri("builtin:database.svg").as_deref(), Some("bytes://icons/database.svg"));
+        assert_eq!(uri("/home/me/icons/invoice.png").as_deref(), Some("file:///home/me/icons/invoice.png"));
+        assert!(BUILTIN_ICONS.iter().all(|(name, bytes)| name.ends_with(".svg") && bytes.starts_with(b"<svg")));
+    }
+
+    #[test]
+    fn test_size_hint_steps() {
+        assert!(matches!(size_hint(16.0, 1.0), SizeHint::Height(h) if h == 16));
+        assert!(matches!(size_hint(17.0, 1.0), SizeHint::Height(h) if h == 32));
+        assert!(matches!(size_hint(16.0, 2.0), SizeHint::Height(h) if h == 32));
+        assert!(matches!(size_hint(0.0, 1.0), SizeHint::Height(h) if h == 16));
+        assert!(matches!(size_hint(1000.0, 2.0), SizeHint::Height(h) if h == MAX_SIZE));
+    }
+}
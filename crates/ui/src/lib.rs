@@ -26,14 +26,37 @@
 //! └─────────────────────────────────────────────────────────────┘
 //! ```
 
+pub mod animation;
 pub mod app;
 pub mod canvas;
+pub mod connections;
+pub mod crash;
+pub mod history_panel;
+pub mod hook_approvals;
+pub mod i18n;
+pub mod icons;
+pub mod log_panel;
+pub mod macros;
+pub mod navigation;
+pub mod notifications;
 pub mod palette;
+pub mod presentation;
 pub mod properties;
+pub mod query_console;
+pub mod quick_add;
+pub mod schema;
+pub mod session;
+pub mod suggestions_panel;
 pub mod toolbar;
+pub mod tutorial;
+pub mod validations;
 pub mod dialogs;
+pub mod docs;
+pub mod events;
 pub mod theme;
 pub mod state;
+pub mod tasks;
+pub mod views;
 pub mod welcome;
 
 pub use app::{ImmortalApp, AppMode};
@@ -133,14 +156,34 @@ pub struct UiConfig {
     pub snap_to_grid: bool,
     /// Show minimap
     pub show_minimap: bool,
+    /// Snap dragged nodes to the edges, centers, and spacing of other nodes
+    pub alignment_guides: bool,
+    /// Highlight what feeds and what is fed by the selection, dimming the rest
+    pub highlight_flow: bool,
+    /// Connections to follow when highlighting (0 = any distance)
+    pub highlight_depth: usize,
+    /// Draw many edges between the same two regions as one bundle
+    pub bundle_edges: bool,
+    /// Color nodes by this metric
+    pub heatmap: Option<imortal_ir::NodeMetric>,
     /// Theme (dark/light)
     pub dark_mode: bool,
     /// Auto-save interval in seconds (0 = disabled)
     pub auto_save_interval: u32,
     /// Show component descriptions in palette
     pub show_descriptions: bool,
-    /// Animation speed (0.0 - 1.0)
+    /// Animate viewport moves, added and deleted nodes, and new connections
+    pub animations: bool,
+    /// Animation speed (0.0 - 1.0); 0.5 is the default pace, 0.0 turns animations off
     pub animation_speed: f32,
+    /// Distance in pixels within which a tap or click hits a port
+    pub port_hit_radius: f32,
+    /// Two-finger pan and pinch zoom on touchscreens
+    pub touch_gestures: bool,
+    /// UI language
+    pub language: i18n::Language,
+    /// Project snapshots kept in `.imortal_history/` on save
+    pub snapshots: imortal_ir::SnapshotConfig,
 }
 
 impl Default for UiConfig {
@@ -150,10 +193,20 @@ impl Default for UiConfig {
             grid_size: 20.0,
             snap_to_grid: true,
             show_minimap: true,
+            alignment_guides: true,
+            highlight_flow: false,
+            highlight_depth: 2,
+            bundle_edges: false,
+            heatmap: None,
             dark_mode: true,
             auto_save_interval: 60,
             show_descriptions: true,
+            animations: true,
             animation_speed: 0.5,
+            port_hit_radius: 15.0,
+            touch_gestures: true,
+            language: i18n::Language::English,
+            snapshots: imortal_ir::SnapshotConfig::default(),
         }
     }
 }
@@ -162,7 +215,7 @@ impl Default for UiConfig {
 pub mod prelude {
     pub use super::{ImmortalApp, AppMode, UiConfig, run, run_with_project, run_with_project_path};
     pub use super::state::EditorState;
-    pub use super::canvas::CanvasWidget;
+    pub use super::canvas::{CanvasWidget, GraphEditorState};
     pub use super::palette::PaletteWidget;
     pub use super::properties::PropertiesPanel;
     pub use super::welcome::{WelcomeScreen, WelcomeAction, NewProjectInfo, RecentProject};
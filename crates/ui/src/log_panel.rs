@@ -0,0 +1,334 @@
+//! Log panel for Immortal Engine UI
+//!
+//! [`LogLayer`] is a `tracing` layer that keeps recent events from every
+//! crate in a shared [`LogBuffer`]; the binary installs it next to the
+//! terminal output. [`LogPanel`] shows the buffer in the editor with a level
+//! filter and search, and copies what is shown to the clipboard, so users
+//! can attach logs to a report without relaunching from a terminal.
+
+use std::collections::VecDeque;
+use std::fmt::{self, Write as _};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::SystemTime;
+
+use eframe::egui;
+use tracing::field::{Field, Visit};
+use tracing::{Event, Level, Subscriber};
+use tracing_subscriber::layer::Context;
+use tracing_subscriber::Layer;
+
+use crate::i18n::{tr, trf};
+
+/// Most records kept; older ones are dropped
+const CAPACITY: usize = 5000;
+
+/// One captured event
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogRecord {
+    pub time: SystemTime,
+    pub level: Level,
+    /// Module the event came from, e.g. `imortal_ir::sqlite`
+    pub target: String,
+    pub message: String,
+    /// Other fields of the event, as `name=value`
+    pub fields: Vec<(String, String)>,
+}
+
+impl LogRecord {
+    /// The record as one line of text, as copied to the clipboard
+    pub fn to_line(&self) -> String {
+        let seconds = self.time.duration_since(SystemTime::UNIX_EPOCH).map_or(0.0, |d| d.as_secs_f64());
+        let mut line = format!("{:.3} {:>5} {}: {}", seconds, self.level, self.target, self.message);
+        for (name, value) in &self.fields {
+            let _ = write!(line, " {}={}", name, value);
+        }
+        line
+    }
+
+    /// Whether the message, target, or a field contains `search`, ignoring case
+    pub fn matches(&self, search: &str) -> bool {
+        let search = search.to_lowercase();
+        self.message.to_lowercase().contains(&search)
+            || self.target.to_lowercase().contains(&search)
+            || self.fields.iter().any(|(name, value)| {
+                name.to_lowercase().contains(&search) || value.to_lowercase().contains(&search)
+            })
+    }
+}
+
+/// Recent log records, shared between the layer and the panel
+#[derive(Debug, Clone, Default)]
+pub struct LogBuffer {
+    records: Arc<Mutex<VecDeque<LogRecord>>>,
+}
+
+impl LogBuffer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The buffer the editor's panel reads
+    pub fn global() -> &'static LogBuffer {
+        static GLOBAL: OnceLock<LogBuffer> = OnceLock::new();
+        GLOBAL.get_or_init(LogBuffer::new)
+    }
+
+    pub fn push(&self, record: LogRecord) {
+        if let Ok(mut records) = self.records.lock() {
+            if records.len() == CAPACITY {
+                records.pop_front();
+            }
+            records.push_back(record);
+        }
+    }
+
+    /// Records at `max_level` or more severe that match `search`, oldest first
+    pub fn filtered(&self, max_level: Level, search: &str) -> Vec<LogRecord> {
+        let Ok(records) = self.records.lock() else {
+            return Vec::new();
+        };
+        records
+            .iter()
+            .filter(|r| r.level <= max_level && (search.is_empty() || r.matches(search)))
+            .cloned()
+            .collect()
+    }
+
+    /// The newest `count` records as text, without waiting if the buffer
+    /// is busy (e.g. from a panic hook)
+    pub fn recent_lines(&self, count: usize) -> Vec<String> {
+        let Ok(records) = self.records.try_lock() else {
+            return Vec::new();
+        };
+        records.iter().skip(records.len().saturating_sub(count)).map(LogRecord::to_line).collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.records.lock().map_or(0, |records| records.len())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn clear(&self) {
+        if let Ok(mut records) = self.records.lock() {
+            records.clear();
+        }
+    }
+}
+
+/// A `tracing` layer that captures events into a [`LogBuffer`]
+pub struct LogLayer {
+    buffer: LogBuffer,
+}
+
+impl LogLayer {
+    /// Capture into the buffer the editor's panel reads
+    pub fn new() -> Self {
+        Self::with_buffer(LogBuffer::global().clone())
+    }
+
+    pub fn with_buffer(buffer: LogBuffer) -> Self {
+        Self { buffer }
+    }
+}
+
+impl Default for LogLayer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S: Subscriber> Layer<S> for LogLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let metadata = event.metadata();
+        let mut visitor = FieldVisitor::default();
+        event.record(&mut visitor);
+        self.buffer.push(LogRecord {
+            time: SystemTime::now(),
+            level: *metadata.level(),
+            target: metadata.target().to_string(),
+            message: visitor.message,
+            fields: visitor.fields,
+        });
+    }
+}
+
+/// Splits an event's `message` field from the rest
+#[derive(Default)]
+struct FieldVisitor {
+    message: String,
+    fields: Vec<(String, String)>,
+}
+
+impl Visit for FieldVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message = value.to_string();
+        } else {
+            self.fields.push((field.name().to_string(), value.to_string()));
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == "message" {
+            self.message = format!("{:?}", value);
+        } else {
+            self.fields.push((field.name().to_string(), format!("{:?}", value)));
+        }
+    }
+}
+
+/// Levels offered in the filter, most severe first
+const LEVELS: [Level; 5] = [Level::ERROR, Level::WARN, Level::INFO, Level::DEBUG, Level::TRACE];
+
+fn level_color(level: Level) -> egui::Color32 {
+    match level {
+        Level::ERROR => egui::Color32::from_rgb(255, 100, 100),
+        Level::WARN => egui::Color32::from_rgb(220, 160, 60),
+        Level::INFO => egui::Color32::from_rgb(120, 170, 255),
+        Level::DEBUG => egui::Color32::GRAY,
+        _ => egui::Color32::DARK_GRAY,
+    }
+}
+
+/// State of the Log panel
+pub struct LogPanel {
+    /// Whether the panel is showing
+    pub visible: bool,
+    buffer: LogBuffer,
+    /// Least severe level shown
+    max_level: Level,
+    search: String,
+    /// Keep the newest record in view
+    follow: bool,
+}
+
+impl Default for LogPanel {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LogPanel {
+    /// A panel over the buffer [`LogLayer::new`] writes to
+    pub fn new() -> Self {
+        Self::with_buffer(LogBuffer::global().clone())
+    }
+
+    pub fn with_buffer(buffer: LogBuffer) -> Self {
+        Self { visible: false, buffer, max_level: Level::INFO, search: String::new(), follow: true }
+    }
+
+    /// Show the panel at the bottom of the window; call before the central panel
+    pub fn show(&mut self, ctx: &egui::Context) {
+        if !self.visible {
+            return;
+        }
+        let records = self.buffer.filtered(self.max_level, &self.search);
+
+        egui::TopBottomPanel::bottom("log_panel")
+            .resizable(true)
+            .default_height(200.0)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.strong(tr("log.title"));
+                    egui::ComboBox::from_id_salt("log_level")
+                        .selected_text(self.max_level.as_str())
+                        .show_ui(ui, |ui| {
+                            for level in LEVELS {
+                                ui.selectable_value(&mut self.max_level, level, level.as_str());
+                            }
+                        });
+                    ui.label("🔍");
+                    ui.add(egui::TextEdit::singleline(&mut self.search).hint_text(tr("log.search")).desired_width(180.0));
+                    ui.checkbox(&mut self.follow, tr("log.follow"));
+                    ui.weak(trf("log.count", &[&records.len(), &self.buffer.len()]));
+
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        if ui.small_button("×").clicked() {
+                            self.visible = false;
+                        }
+                        if ui.small_button(tr("log.clear")).clicked() {
+                            self.buffer.clear();
+                        }
+                        if ui.small_button(tr("log.copy")).on_hover_text(tr("log.copy_hint")).clicked() {
+                            ui.ctx().copy_text(copy_text(&records));
+                        }
+                    });
+                });
+                ui.separator();
+
+                let row_height = ui.text_style_height(&egui::TextStyle::Monospace);
+                egui::ScrollArea::both()
+                    .auto_shrink([false, false])
+                    .stick_to_bottom(self.follow)
+                    .show_rows(ui, row_height, records.len(), |ui, rows| {
+                        for record in &records[rows] {
+                            ui.horizontal(|ui| {
+                                ui.monospace(egui::RichText::new(format!("{:>5}", record.level)).color(level_color(record.level)));
+                                ui.monospace(egui::RichText::new(&record.target).weak());
+                                let mut text = record.message.clone();
+                                for (name, value) in &record.fields {
+                                    let _ = write!(text, " {}={}", name, value);
+                                }
+                                ui.monospace(text);
+                            });
+                        }
+                    });
+            });
+        // New records arrive from other threads without any input
+        ctx.request_repaint_after(std::time::Duration::from_millis(500));
+    }
+}
+
+/// Records as text, one per line
+pub fn copy_text(records: &[LogRecord]) -> String {
+    records.iter().map(LogRecord::to_line).collect::<Vec<_>>().join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    #[test]
+    fn test_layer_captures_events() {
+        let buffer = LogBuffer::new();
+        let subscriber = tracing_subscriber::registry().with(LogLayer::with_buffer(buffer.clone()));
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::info!(project = "shop", "Loaded project");
+            tracing::debug!("Details");
+            tracing::error!(code = 3, "Save failed");
+        });
+
+        let records = buffer.filtered(Level::INFO, "");
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].message, "Loaded project");
+        assert_eq!(records[0].fields, [("project".to_string(), "shop".to_string())]);
+        assert_eq!(records[1].level, Level::ERROR);
+        assert_eq!(buffer.filtered(Level::TRACE, "").len(), 3);
+
+        // Search covers fields too
+        assert_eq!(buffer.filtered(Level::TRACE, "SHOP").len(), 1);
+        let text = copy_text(&buffer.filtered(Level::ERROR, ""));
+        assert!(text.ends_with("ERROR imortal_ui::log_panel::tests: Save failed code=3"));
+    }
+
+    #[test]
+    fn test_buffer_drops_oldest() {
+        let buffer = LogBuffer::new();
+        for i in 0..CAPACITY + 5 {
+            buffer.push(LogRecord {
+                time: SystemTime::now(),
+                level: Level::INFO,
+                target: "test".to_string(),
+                message: format!("event {}", i),
+                fields: Vec::new(),
+            });
+        }
+        assert_eq!(buffer.len(), CAPACITY);
+        assert_eq!(buffer.filtered(Level::INFO, "")[0].message, "event 5");
+    }
+}
@@ -0,0 +1,216 @@
+//! Macro manager for Immortal Engine UI
+//!
+//! Macros recorded with Tools > Record Macro are saved as JSON files in the
+//! user's config folder, so they can be replayed on any project. The manager
+//! window names and saves a just-recorded macro, lists the saved ones, and
+//! returns replays as [`MacroAction`]s for the app to apply through undo.
+
+use std::path::{Path, PathBuf};
+
+use eframe::egui;
+use imortal_core::{EngineError, EngineResult};
+use imortal_ir::Macro;
+
+use crate::i18n::{tr, trf};
+
+/// Something to do with a macro chosen in the manager
+#[derive(Debug, Clone, PartialEq)]
+pub enum MacroAction {
+    /// Replay the macro on the live project
+    Replay(Macro),
+}
+
+/// A macro saved on disk
+#[derive(Debug, Clone, PartialEq)]
+pub struct SavedMacro {
+    pub path: PathBuf,
+    pub recorded: Macro,
+}
+
+/// Load every `*.json` macro in `dir`, sorted by name, skipping files that
+/// don't parse
+pub fn load_macros(dir: &Path) -> Vec<SavedMacro> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut macros: Vec<SavedMacro> = entries
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .filter_map(|path| {
+            let json = std::fs::read_to_string(&path).ok()?;
+            match Macro::from_json(&json) {
+                Ok(recorded) => Some(SavedMacro { path, recorded }),
+                Err(e) => {
+                    tracing::warn!("Skipping macro {}: {}", path.display(), e);
+                    None
+                }
+            }
+        })
+        .collect();
+    macros.sort_by(|a, b| a.recorded.name.cmp(&b.recorded.name));
+    macros
+}
+
+/// Save a macro to `dir` under a file name built from its name, replacing
+/// a macro saved under the same name
+pub fn save_macro(dir: &Path, recorded: &Macro) -> EngineResult<PathBuf> {
+    let stem: String = recorded
+        .name
+        .chars()
+        .map(|c| if c.is_alphanumeric() || c == '-' || c == '_' { c.to_ascii_lowercase() } else { '_' })
+        .collect();
+    if stem.trim_matches('_').is_empty() {
+        return Err(EngineError::custom("The macro needs a name"));
+    }
+    std::fs::create_dir_all(dir)?;
+    let path = dir.join(format!("{}.json", stem));
+    std::fs::write(&path, recorded.to_json()?)?;
+    Ok(path)
+}
+
+/// The Tools > Macros window
+#[derive(Default)]
+pub struct MacroManager {
+    /// Whether the window is open
+    pub visible: bool,
+    /// Folder macros are saved in
+    dir: Option<PathBuf>,
+    /// Saved macros, reloaded when the window opens
+    macros: Vec<SavedMacro>,
+    /// Just-recorded macro waiting for a name
+    pending: Option<Macro>,
+    /// Last save, load or delete problem
+    error: Option<String>,
+}
+
+impl MacroManager {
+    /// Create a hidden manager saving macros in `dir`
+    pub fn new(dir: Option<PathBuf>) -> Self {
+        Self { dir, ..Self::default() }
+    }
+
+    /// Open the window, reloading the saved macros
+    pub fn open(&mut self) {
+        self.visible = true;
+        self.error = None;
+        self.macros = self.dir.as_deref().map(load_macros).unwrap_or_default();
+    }
+
+    /// Open the window to name and save a just-recorded macro
+    pub fn save_recording(&mut self, recorded: Macro) {
+        self.open();
+        self.pending = Some(recorded);
+    }
+
+    /// Render the window and return the actions taken this frame
+    pub fn show(&mut self, ctx: &egui::Context) -> Vec<MacroAction> {
+        let mut actions = Vec::new();
+        if !self.visible {
+            return actions;
+        }
+
+        let mut visible = self.visible;
+        let mut save = false;
+        let mut discard = false;
+        let mut delete = None;
+        egui::Window::new(tr("macros.title"))
+            .open(&mut visible)
+            .resizable(true)
+            .default_size([420.0, 320.0])
+            .show(ctx, |ui| {
+                if let Some(pending) = &mut self.pending {
+                    ui.strong(trf("macros.recorded", &[&pending.steps.len()]));
+                    ui.horizontal(|ui| {
+                        ui.label(tr("macros.name"));
+                        ui.text_edit_singleline(&mut pending.name);
+                    });
+                    let description = pending.description.get_or_insert_with(String::new);
+                    ui.add(egui::TextEdit::multiline(description).desired_rows(2).hint_text(tr("macros.description")));
+                    ui.horizontal(|ui| {
+                        save = ui.add_enabled(!pending.name.trim().is_empty(), egui::Button::new(tr("macros.save"))).clicked();
+                        discard = ui.button(tr("macros.discard")).clicked();
+                    });
+                    ui.separator();
+                }
+
+                if let Some(error) = &self.error {
+                    ui.colored_label(ui.visuals().error_fg_color, error);
+                }
+                if self.macros.is_empty() {
+                    ui.weak(tr("macros.empty"));
+                }
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    for (idx, saved) in self.macros.iter().enumerate() {
+                        ui.horizontal(|ui| {
+                            let steps: Vec<String> = saved.recorded.steps.iter().map(|s| s.describe()).collect();
+                            ui.label(&saved.recorded.name).on_hover_text(steps.join("\n"));
+                            if ui.small_button(tr("macros.replay")).clicked() {
+                                actions.push(MacroAction::Replay(saved.recorded.clone()));
+                            }
+                            if ui.small_button("🗑").on_hover_text(tr("macros.delete")).clicked() {
+                                delete = Some(idx);
+                            }
+                        });
+                        if let Some(description) = saved.recorded.description.as_deref().filter(|d| !d.is_empty()) {
+                            ui.weak(description);
+                        }
+                    }
+                });
+            });
+        self.visible = visible;
+
+        if save {
+            if let Some(mut pending) = self.pending.take() {
+                pending.name = pending.name.trim().to_string();
+                pending.description = pending.description.filter(|d| !d.trim().is_empty());
+                let saved = match &self.dir {
+                    Some(dir) => save_macro(dir, &pending),
+                    None => Err(EngineError::custom("No config folder to save macros in")),
+                };
+                match saved {
+                    Ok(_) => self.open(),
+                    Err(e) => {
+                        self.error = Some(e.to_string());
+                        self.pending = Some(pending);
+                    }
+                }
+            }
+        } else if discard {
+            self.pending = None;
+        }
+        if let Some(idx) = delete {
+            let saved = self.macros.remove(idx);
+            if let Err(e) = std::fs::remove_file(&saved.path) {
+                self.error = Some(e.to_string());
+            }
+        }
+
+        actions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use imortal_ir::MacroStep;
+
+    #[test]
+    fn test_save_and_load_macros() {
+        let dir = std::env::temp_dir().join(format!("imortal-macros-{}", uuid::Uuid::new_v4()));
+        let mut audit = Macro::new("Audit Fields");
+        audit.record(MacroStep::IncludeMixin { name: "Auditable".to_string() });
+        let path = save_macro(&dir, &audit).unwrap();
+        assert_eq!(path.file_name().unwrap(), "audit_fields.json");
+        save_macro(&dir, &Macro::new("Another")).unwrap();
+        std::fs::write(dir.join("broken.json"), "{").unwrap();
+
+        let loaded = load_macros(&dir);
+        let names: Vec<_> = loaded.iter().map(|m| m.recorded.name.as_str()).collect();
+        assert_eq!(names, vec!["Another", "Audit Fields"]);
+        assert_eq!(loaded[1].recorded, audit);
+
+        assert!(save_macro(&dir, &Macro::new(" ! ")).is_err());
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert!(load_macros(&dir).is_empty());
+    }
+}
@@ -0,0 +1,121 @@
+//! Keyboard navigation of the canvas
+//!
+//! Picks which node receives keyboard focus: Tab cycles through nodes in
+//! reading order (top to bottom, then left to right), and the arrow keys
+//! jump to the nearest node in that direction.
+
+use imortal_core::NodeId;
+use imortal_ir::{Node, ProjectGraph};
+
+/// Direction of an arrow key
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Left,
+    Right,
+    Up,
+    Down,
+}
+
+impl Direction {
+    /// Unit vector in canvas coordinates (y grows downwards)
+    fn vector(self) -> (f32, f32) {
+        match self {
+            Direction::Left => (-1.0, 0.0),
+            Direction::Right => (1.0, 0.0),
+            Direction::Up => (0.0, -1.0),
+            Direction::Down => (0.0, 1.0),
+        }
+    }
+}
+
+/// Nodes in reading order
+pub fn reading_order(graph: &ProjectGraph) -> Vec<&Node> {
+    let mut nodes: Vec<&Node> = graph.nodes().collect();
+    nodes.sort_by(|a, b| {
+        a.position.y
+            .total_cmp(&b.position.y)
+            .then(a.position.x.total_cmp(&b.position.x))
+            .then(a.id.cmp(&b.id))
+    });
+    nodes
+}
+
+/// Next node after `current` in reading order, wrapping around
+///
+/// With no current node, starts at the first (or, backwards, the last) node.
+pub fn cycle_focus(graph: &ProjectGraph, current: Option<NodeId>, backwards: bool) -> Option<NodeId> {
+    let order = reading_order(graph);
+    if order.is_empty() {
+        return None;
+    }
+    let len = order.len();
+    let index = match current.and_then(|id| order.iter().position(|n| n.id == id)) {
+        Some(i) if backwards => (i + len - 1) % len,
+        Some(i) => (i + 1) % len,
+        None if backwards => len - 1,
+        None => 0,
+    };
+    Some(order[index].id)
+}
+
+/// Nearest node from `current` in a direction
+///
+/// Only nodes whose center lies within 45° of the direction are considered;
+/// distance off the axis counts double so nodes in line are preferred.
+pub fn nearest_in_direction(graph: &ProjectGraph, current: NodeId, direction: Direction) -> Option<NodeId> {
+    let center = graph.get_node(current)?.center();
+    let (dx, dy) = direction.vector();
+
+    graph
+        .nodes()
+        .filter(|n| n.id != current)
+        .filter_map(|n| {
+            let other = n.center();
+            let (vx, vy) = (other.x - center.x, other.y - center.y);
+            let along = vx * dx + vy * dy;
+            let across = (vx * dy - vy * dx).abs();
+            (along > 0.0 && across <= along).then_some((along + 2.0 * across, n.id))
+        })
+        .min_by(|a, b| a.0.total_cmp(&b.0))
+        .map(|(_, id)| id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node_at(graph: &mut ProjectGraph, name: &str, x: f32, y: f32) -> NodeId {
+        let mut node = Node::new_entity(name);
+        node.position.x = x;
+        node.position.y = y;
+        graph.add_node(node)
+    }
+
+    #[test]
+    fn test_cycle_focus_reading_order() {
+        let mut graph = ProjectGraph::with_name("test");
+        let b = node_at(&mut graph, "B", 400.0, 0.0);
+        let a = node_at(&mut graph, "A", 0.0, 0.0);
+        let c = node_at(&mut graph, "C", 0.0, 300.0);
+
+        assert_eq!(cycle_focus(&graph, None, false), Some(a));
+        assert_eq!(cycle_focus(&graph, Some(a), false), Some(b));
+        assert_eq!(cycle_focus(&graph, Some(c), false), Some(a));
+        assert_eq!(cycle_focus(&graph, Some(a), true), Some(c));
+        assert_eq!(cycle_focus(&ProjectGraph::with_name("empty"), None, false), None);
+    }
+
+    #[test]
+    fn test_nearest_in_direction() {
+        let mut graph = ProjectGraph::with_name("test");
+        let origin = node_at(&mut graph, "Origin", 0.0, 0.0);
+        let right = node_at(&mut graph, "Right", 400.0, 20.0);
+        let far_right = node_at(&mut graph, "FarRight", 800.0, 0.0);
+        let below = node_at(&mut graph, "Below", 50.0, 500.0);
+
+        assert_eq!(nearest_in_direction(&graph, origin, Direction::Right), Some(right));
+        assert_eq!(nearest_in_direction(&graph, right, Direction::Right), Some(far_right));
+        assert_eq!(nearest_in_direction(&graph, origin, Direction::Down), Some(below));
+        assert_eq!(nearest_in_direction(&graph, origin, Direction::Left), None);
+    }
+}
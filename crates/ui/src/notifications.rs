@@ -0,0 +1,424 @@
+//! Notification center for Immortal Engine UI
+//!
+//! Messages show as toasts in the bottom-right corner and are kept in a
+//! history drawer opened from the status bar. A background task from
+//! [`crate::tasks`] gets a toast with a progress bar and a Cancel button
+//! from [`Notifications::start_task`] until the app
+//! [`finish`](Notifications::finish_task)es it with the outcome. A
+//! notification may carry a [`NotificationAction`] the user can click, which
+//! is handed back to the app.
+
+use std::collections::VecDeque;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use eframe::egui;
+
+use crate::i18n::{tr, trf};
+use crate::tasks::TaskId;
+
+/// Most notifications kept in the history
+const HISTORY_LIMIT: usize = 200;
+
+/// Identifies a notification, and the task behind it if any
+pub type NotificationId = u64;
+
+/// How important a notification is
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Info,
+    Success,
+    Warning,
+    Error,
+}
+
+impl Severity {
+    pub fn icon(&self) -> &'static str {
+        match self {
+            Severity::Info => "ℹ",
+            Severity::Success => "✅",
+            Severity::Warning => "⚠",
+            Severity::Error => "❌",
+        }
+    }
+
+    pub fn color(&self) -> egui::Color32 {
+        match self {
+            Severity::Info => egui::Color32::from_rgb(120, 170, 255),
+            Severity::Success => egui::Color32::from_rgb(100, 220, 100),
+            Severity::Warning => egui::Color32::from_rgb(220, 160, 60),
+            Severity::Error => egui::Color32::from_rgb(255, 100, 100),
+        }
+    }
+
+    /// How long the toast stays up; problems stay longer so they get read
+    fn toast_duration(&self) -> Duration {
+        match self {
+            Severity::Info | Severity::Success => Duration::from_secs(4),
+            Severity::Warning | Severity::Error => Duration::from_secs(8),
+        }
+    }
+}
+
+/// Something the user can do from a notification
+#[derive(Debug, Clone, PartialEq)]
+pub enum NotificationAction {
+    /// Open a folder in the system file manager
+    OpenFolder(PathBuf),
+    /// Show the validation report
+    ShowValidation,
+    /// Stop a background task
+    CancelTask(TaskId),
+}
+
+impl NotificationAction {
+    pub fn label(&self) -> &'static str {
+        match self {
+            NotificationAction::OpenFolder(_) => tr("notifications.open_folder"),
+            NotificationAction::ShowValidation => tr("notifications.show_validation"),
+            NotificationAction::CancelTask(_) => tr("notifications.cancel"),
+        }
+    }
+}
+
+/// A message for the user
+#[derive(Debug, Clone)]
+pub struct Notification {
+    pub id: NotificationId,
+    pub severity: Severity,
+    pub message: String,
+    pub action: Option<NotificationAction>,
+    /// Background task this notification follows
+    pub task: Option<TaskId>,
+    /// Fraction done, while the task is running
+    pub progress: Option<f32>,
+    /// When the notification was posted or last updated
+    pub time: Instant,
+    /// Closed before its toast timed out
+    dismissed: bool,
+}
+
+impl Notification {
+    pub fn new(severity: Severity, message: impl Into<String>) -> Self {
+        Self {
+            id: 0,
+            severity,
+            message: message.into(),
+            action: None,
+            task: None,
+            progress: None,
+            time: Instant::now(),
+            dismissed: false,
+        }
+    }
+
+    pub fn info(message: impl Into<String>) -> Self {
+        Self::new(Severity::Info, message)
+    }
+
+    pub fn success(message: impl Into<String>) -> Self {
+        Self::new(Severity::Success, message)
+    }
+
+    pub fn warning(message: impl Into<String>) -> Self {
+        Self::new(Severity::Warning, message)
+    }
+
+    pub fn error(message: impl Into<String>) -> Self {
+        Self::new(Severity::Error, message)
+    }
+
+    pub fn with_action(mut self, action: NotificationAction) -> Self {
+        self.action = Some(action);
+        self
+    }
+
+    /// Whether a task is still working on this notification
+    pub fn is_running(&self) -> bool {
+        self.progress.is_some()
+    }
+
+    /// Whether the toast is still up
+    fn is_toast(&self) -> bool {
+        !self.dismissed && (self.is_running() || self.time.elapsed() < self.severity.toast_duration())
+    }
+}
+
+/// All notifications, newest last
+pub struct Notifications {
+    history: VecDeque<Notification>,
+    next_id: NotificationId,
+    /// Whether the history drawer is open
+    pub drawer_open: bool,
+    /// Newest notification seen in the drawer
+    seen: NotificationId,
+}
+
+impl Default for Notifications {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Notifications {
+    pub fn new() -> Self {
+        Self { history: VecDeque::new(), next_id: 1, drawer_open: false, seen: 0 }
+    }
+
+    /// Post a notification
+    pub fn push(&mut self, mut notification: Notification) -> NotificationId {
+        notification.id = self.next_id;
+        self.next_id += 1;
+        self.history.push_back(notification);
+        // Never drop a running task's notification; it still has to finish
+        while self.history.len() > HISTORY_LIMIT {
+            match self.history.iter().position(|n| !n.is_running()) {
+                Some(index) => self.history.remove(index),
+                None => break,
+            };
+        }
+        self.next_id - 1
+    }
+
+    /// Follow a background task with a progress toast
+    pub fn start_task(&mut self, task: TaskId, message: impl Into<String>) -> NotificationId {
+        let mut notification = Notification::info(message).with_action(NotificationAction::CancelTask(task));
+        notification.task = Some(task);
+        notification.progress = Some(0.0);
+        self.push(notification)
+    }
+
+    /// Update a task's toast with how far along it is
+    pub fn task_progress(&mut self, task: TaskId, fraction: f32, message: impl Into<String>) {
+        let message = message.into();
+        if let Some(notification) = self.running_task(task) {
+            notification.progress = Some(fraction);
+            if !message.is_empty() {
+                notification.message = message;
+            }
+        }
+    }
+
+    /// Replace a task's progress toast with its outcome
+    pub fn finish_task(&mut self, task: TaskId, outcome: Notification) {
+        match self.running_task(task) {
+            Some(notification) => *notification = Notification { id: notification.id, task: Some(task), ..outcome },
+            None => {
+                self.push(outcome);
+            }
+        }
+    }
+
+    fn running_task(&mut self, task: TaskId) -> Option<&mut Notification> {
+        self.history.iter_mut().find(|n| n.task == Some(task) && n.is_running())
+    }
+
+    /// Get a notification by ID
+    pub fn get(&self, id: NotificationId) -> Option<&Notification> {
+        self.history.iter().find(|n| n.id == id)
+    }
+
+    /// All notifications, oldest first
+    pub fn history(&self) -> impl DoubleEndedIterator<Item = &Notification> {
+        self.history.iter()
+    }
+
+    /// Notifications posted since the drawer was last open
+    pub fn unread(&self) -> usize {
+        self.history.iter().filter(|n| n.id > self.seen).count()
+    }
+
+    /// Whether any background task is still running
+    pub fn is_busy(&self) -> bool {
+        self.history.iter().any(Notification::is_running)
+    }
+
+    /// Close every toast; they stay in the history
+    pub fn dismiss_all(&mut self) {
+        for notification in &mut self.history {
+            notification.dismissed = true;
+        }
+    }
+
+    /// Forget finished notifications
+    pub fn clear(&mut self) {
+        self.history.retain(Notification::is_running);
+    }
+
+    /// Bell button for the status bar, toggling the drawer
+    pub fn status_button(&mut self, ui: &mut egui::Ui) {
+        let unread = self.unread();
+        let label = if unread > 0 { format!("🔔 {}", unread) } else { "🔔".to_string() };
+        if ui.selectable_label(self.drawer_open, label).on_hover_text(tr("notifications.title")).clicked() {
+            self.drawer_open = !self.drawer_open;
+        }
+        if self.is_busy() {
+            ui.spinner();
+        }
+    }
+
+    /// Show the history drawer; call before the central panel
+    pub fn show_drawer(&mut self, ctx: &egui::Context) -> Option<NotificationAction> {
+        if !self.drawer_open {
+            return None;
+        }
+        self.seen = self.next_id - 1;
+
+        let mut action = None;
+        let mut clear = false;
+        egui::SidePanel::right("notification_drawer")
+            .resizable(true)
+            .default_width(300.0)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.heading(tr("notifications.title"));
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        if ui.small_button("×").clicked() {
+                            self.drawer_open = false;
+                        }
+                        clear = ui.small_button(tr("notifications.clear")).clicked();
+                    });
+                });
+                ui.separator();
+
+                if self.history.is_empty() {
+                    ui.weak(tr("notifications.empty"));
+                }
+                egui::ScrollArea::vertical().auto_shrink([false, false]).show(ui, |ui| {
+                    for notification in self.history.iter().rev() {
+                        ui.group(|ui| {
+                            ui.set_width(ui.available_width());
+                            if let Some(clicked) = notification_body(ui, notification) {
+                                action = Some(clicked);
+                            }
+                            ui.weak(elapsed(notification.time));
+                        });
+                    }
+                });
+            });
+
+        if clear {
+            self.clear();
+        }
+        action
+    }
+
+    /// Show the toasts; returns the action the user clicked, if any
+    pub fn show_toasts(&mut self, ctx: &egui::Context) -> Option<NotificationAction> {
+        let mut action = None;
+        let mut dismiss = None;
+        let toasts: Vec<&Notification> = self.history.iter().filter(|n| n.is_toast()).collect();
+        if !toasts.is_empty() {
+            egui::Area::new(egui::Id::new("notification_toasts"))
+                .order(egui::Order::Foreground)
+                .anchor(egui::Align2::RIGHT_BOTTOM, [-12.0, -36.0])
+                .show(ctx, |ui| {
+                    ui.set_max_width(320.0);
+                    for notification in toasts.iter().rev().take(5).rev() {
+                        egui::Frame::popup(ui.style()).show(ui, |ui| {
+                            ui.set_width(300.0);
+                            ui.horizontal_top(|ui| {
+                                ui.vertical(|ui| {
+                                    ui.set_max_width(270.0);
+                                    if let Some(clicked) = notification_body(ui, notification) {
+                                        action = Some(clicked);
+                                    }
+                                });
+                                if !notification.is_running() && ui.small_button("×").clicked() {
+                                    dismiss = Some(notification.id);
+                                }
+                            });
+                        });
+                        ui.add_space(4.0);
+                    }
+                });
+            // Toasts time out, and progress moves, without any input
+            ctx.request_repaint_after(Duration::from_millis(if self.is_busy() { 100 } else { 500 }));
+        }
+
+        if let Some(id) = dismiss {
+            if let Some(notification) = self.history.iter_mut().find(|n| n.id == id) {
+                notification.dismissed = true;
+            }
+        }
+        action
+    }
+}
+
+/// Icon, message, progress bar, and action button of a notification
+fn notification_body(ui: &mut egui::Ui, notification: &Notification) -> Option<NotificationAction> {
+    ui.horizontal_wrapped(|ui| {
+        ui.colored_label(notification.severity.color(), notification.severity.icon());
+        ui.label(&notification.message);
+    });
+    if let Some(fraction) = notification.progress {
+        ui.add(egui::ProgressBar::new(fraction).show_percentage().desired_width(ui.available_width().min(260.0)));
+    }
+    let action = notification.action.as_ref()?;
+    ui.small_button(action.label()).clicked().then(|| action.clone())
+}
+
+/// How long ago an instant was, e.g. "3 min ago"
+fn elapsed(time: Instant) -> String {
+    let minutes = time.elapsed().as_secs() / 60;
+    match minutes {
+        0 => tr("history.just_now").to_string(),
+        1..60 => trf("history.minutes_ago", &[&minutes]),
+        _ => trf("history.hours_ago", &[&(minutes / 60)]),
+    }
+}
+
+/// Open a file or folder with the system's default application
+pub fn open_path(path: &std::path::Path) -> std::io::Result<()> {
+    let program = if cfg!(target_os = "windows") {
+        "explorer"
+    } else if cfg!(target_os = "macos") {
+        "open"
+    } else {
+        "xdg-open"
+    };
+    std::process::Command::new(program).arg(path).spawn().map(|_| ())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_task_toast_follows_progress_to_outcome() {
+        let mut notifications = Notifications::new();
+        let id = notifications.start_task(7, "Working");
+        assert!(notifications.is_busy());
+        assert_eq!(notifications.get(id).unwrap().action, Some(NotificationAction::CancelTask(7)));
+
+        notifications.task_progress(7, 0.5, "Halfway");
+        let running = notifications.get(id).unwrap();
+        assert_eq!(running.progress, Some(0.5));
+        assert_eq!(running.message, "Halfway");
+
+        notifications.finish_task(7, Notification::success("Done").with_action(NotificationAction::ShowValidation));
+        assert!(!notifications.is_busy());
+        let done = notifications.get(id).unwrap();
+        assert_eq!(done.severity, Severity::Success);
+        assert_eq!(done.action, Some(NotificationAction::ShowValidation));
+        assert_eq!(notifications.history().count(), 1);
+
+        // Late progress from a finished task is ignored
+        notifications.task_progress(7, 0.9, "Late");
+        assert_eq!(notifications.get(id).unwrap().message, "Done");
+    }
+
+    #[test]
+    fn test_history_is_capped_and_tracks_unread() {
+        let mut notifications = Notifications::new();
+        for i in 0..HISTORY_LIMIT + 10 {
+            notifications.push(Notification::info(format!("message {}", i)));
+        }
+        assert_eq!(notifications.history().count(), HISTORY_LIMIT);
+        assert_eq!(notifications.history().next().unwrap().message, "message 10");
+        assert_eq!(notifications.unread(), HISTORY_LIMIT);
+
+        notifications.clear();
+        assert_eq!(notifications.unread(), 0);
+    }
+}
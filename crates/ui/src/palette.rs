@@ -92,22 +92,7 @@ impl PaletteWidget {
                                 response.dragged_component = Some(id.clone());
                             }
 
-                            // Tooltip with description
-                            button_response.on_hover_ui(|ui| {
-                                ui.vertical(|ui| {
-                                    ui.strong(&component.name);
-                                    ui.label(&component.description);
-
-                                    if !component.tags.is_empty() {
-                                        ui.add_space(4.0);
-                                        ui.horizontal(|ui| {
-                                            for tag in &component.tags {
-                                                ui.label(egui::RichText::new(format!("#{}", tag)).small().color(egui::Color32::GRAY));
-                                            }
-                                        });
-                                    }
-                                });
-                            });
+                            button_response.on_hover_ui(|ui| crate::docs::component_tooltip(ui, component));
                         }
                     });
 
@@ -0,0 +1,133 @@
+//! Presentation mode for Immortal Engine UI
+//!
+//! A read-only walkthrough of the canvas: panels are hidden, editing is
+//! disabled, and the arrow keys step through the project's slides
+//! (bookmarked viewpoints) with a smooth pan/zoom transition between them.
+
+use imortal_ir::Slide;
+use imortal_ir::graph::Viewport;
+
+/// Duration of the transition between two slides, in seconds
+pub const TRANSITION_SECONDS: f32 = 0.6;
+
+/// An in-progress pan/zoom transition
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Transition {
+    from: Viewport,
+    to: Viewport,
+    elapsed: f32,
+}
+
+/// State of a running presentation
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Presentation {
+    /// Index of the current slide
+    slide: usize,
+    transition: Option<Transition>,
+}
+
+impl Presentation {
+    /// Start presenting at the first slide
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Index of the current slide
+    pub fn slide(&self) -> usize {
+        self.slide
+    }
+
+    /// Whether a transition is still running
+    pub fn is_animating(&self) -> bool {
+        self.transition.is_some()
+    }
+
+    /// Animate from `current` to the slide at `index`
+    pub fn go_to(&mut self, slides: &[Slide], index: usize, current: Viewport) {
+        let Some(slide) = slides.get(index) else {
+            return;
+        };
+        self.slide = index;
+        self.transition = Some(Transition {
+            from: current,
+            to: slide.viewport,
+            elapsed: 0.0,
+        });
+    }
+
+    /// Advance to the next slide, if any
+    pub fn next(&mut self, slides: &[Slide], current: Viewport) {
+        if self.slide + 1 < slides.len() {
+            self.go_to(slides, self.slide + 1, current);
+        }
+    }
+
+    /// Go back to the previous slide, if any
+    pub fn previous(&mut self, slides: &[Slide], current: Viewport) {
+        if self.slide > 0 {
+            self.go_to(slides, self.slide - 1, current);
+        }
+    }
+
+    /// Advance the transition by `dt` seconds, returning the viewport to show
+    pub fn tick(&mut self, dt: f32) -> Option<Viewport> {
+        let transition = self.transition.as_mut()?;
+        transition.elapsed += dt;
+        let t = (transition.elapsed / TRANSITION_SECONDS).min(1.0);
+        let viewport = transition.from.lerp(&transition.to, ease_in_out(t));
+        if t >= 1.0 {
+            self.transition = None;
+        }
+        Some(viewport)
+    }
+}
+
+/// Smoothstep easing: slow start, slow finish
+fn ease_in_out(t: f32) -> f32 {
+    t * t * (3.0 - 2.0 * t)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn slides() -> Vec<Slide> {
+        [0.0, 1000.0]
+            .iter()
+            .enumerate()
+            .map(|(i, pan_x)| Slide {
+                name: format!("Slide {}", i + 1),
+                viewport: Viewport { pan_x: *pan_x, ..Viewport::default() },
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_navigation_bounds() {
+        let slides = slides();
+        let mut presentation = Presentation::new();
+
+        presentation.previous(&slides, Viewport::default());
+        assert!(!presentation.is_animating());
+
+        presentation.next(&slides, Viewport::default());
+        presentation.next(&slides, Viewport::default());
+        assert_eq!(presentation.slide(), 1);
+    }
+
+    #[test]
+    fn test_transition_eases_to_slide() {
+        let slides = slides();
+        let mut presentation = Presentation::new();
+        presentation.go_to(&slides, 1, slides[0].viewport);
+
+        let halfway = presentation.tick(TRANSITION_SECONDS / 2.0).unwrap();
+        assert!((halfway.pan_x - 500.0).abs() < 0.01);
+        let early = ease_in_out(0.1) * 1000.0;
+        assert!(early < 100.0);
+
+        let end = presentation.tick(TRANSITION_SECONDS).unwrap();
+        assert_eq!(end, slides[1].viewport);
+        assert!(presentation.tick(0.1).is_none());
+    }
+}
@@ -7,6 +7,8 @@ use eframe::egui;
 use imortal_ir::{Node, Edge, Field, Port, ProjectGraph};
 use imortal_core::ConfigValue;
 
+use crate::i18n::{tr, trf};
+
 /// Properties panel widget
 pub struct PropertiesPanel {
     /// Search filter for properties
@@ -37,7 +39,7 @@ impl PropertiesPanel {
 
     /// Render the properties panel
     pub fn show(&mut self, ui: &mut egui::Ui, graph: &mut ProjectGraph) {
-        ui.heading("Properties");
+        ui.heading(tr("properties.title"));
         ui.separator();
 
         // Search box
@@ -45,7 +47,7 @@ impl PropertiesPanel {
             ui.label("🔍");
             ui.text_edit_singleline(&mut self.search_filter);
         });
-        ui.checkbox(&mut self.show_advanced, "Show Advanced");
+        ui.checkbox(&mut self.show_advanced, tr("properties.show_advanced"));
         ui.separator();
 
         // Get selection info
@@ -53,7 +55,7 @@ impl PropertiesPanel {
         let selected_edges: Vec<_> = graph.selected_edges.iter().copied().collect();
 
         if selected_nodes.is_empty() && selected_edges.is_empty() {
-            ui.label("Select a component to view its properties");
+            ui.label(tr("properties.empty"));
             return;
         }
 
@@ -68,11 +70,7 @@ impl PropertiesPanel {
                 self.render_edge_properties(ui, &edge, graph);
             }
         } else {
-            ui.label(format!(
-                "{} nodes and {} edges selected",
-                selected_nodes.len(),
-                selected_edges.len()
-            ));
+            ui.label(trf("properties.selection", &[&selected_nodes.len(), &selected_edges.len()]));
             self.render_multi_selection_properties(ui, &selected_nodes, &selected_edges, graph);
         }
     }
@@ -96,11 +94,11 @@ impl PropertiesPanel {
     /// Render properties for a single node
     fn render_node_properties(&mut self, ui: &mut egui::Ui, node: &Node, graph: &mut ProjectGraph) {
         // Basic info section
-        if self.render_section_header(ui, "Basic Info") {
+        if self.render_section_header(ui, tr("properties.basic_info")) {
             ui.indent("basic_info", |ui| {
                 // Node name
                 ui.horizontal(|ui| {
-                    ui.label("Name:");
+                    ui.label(tr("properties.name"));
                     let mut name = node.name.clone();
                     if ui.text_edit_singleline(&mut name).changed() {
                         if let Some(n) = graph.get_node_mut(node.id) {
@@ -111,20 +109,20 @@ impl PropertiesPanel {
 
                 // Component type (read-only)
                 ui.horizontal(|ui| {
-                    ui.label("Type:");
+                    ui.label(tr("properties.type"));
                     ui.label(&node.component_type);
                 });
 
                 // Category
                 ui.horizontal(|ui| {
-                    ui.label("Category:");
+                    ui.label(tr("properties.category"));
                     ui.label(format!("{} {}", node.category.icon(), node.category.display_name()));
                 });
 
                 // Description
                 if let Some(desc) = &node.description {
                     ui.horizontal(|ui| {
-                        ui.label("Description:");
+                        ui.label(tr("properties.description"));
                         ui.label(desc);
                     });
                 }
@@ -134,13 +132,9 @@ impl PropertiesPanel {
 
         // Fields section
         if !node.fields.is_empty() {
-            let filter = self.search_filter.clone();
-            if self.render_section_header(ui, "Fields") {
+            if self.render_section_header(ui, tr("properties.fields")) {
                 ui.indent("fields", |ui| {
-                    for field in &node.fields {
-                        if !filter.is_empty() && !field.name.to_lowercase().contains(&filter.to_lowercase()) {
-                            continue;
-                        }
+                    for field in node.fields.iter().filter(|field| self.filter_matches(&field.name)) {
                         Self::render_field_editor_static(ui, field);
                     }
                 });
@@ -150,16 +144,16 @@ impl PropertiesPanel {
 
         // Ports section
         if !node.ports.inputs.is_empty() || !node.ports.outputs.is_empty() {
-            if self.render_section_header(ui, "Ports") {
+            if self.render_section_header(ui, tr("properties.ports")) {
                 ui.indent("ports", |ui| {
                     if !node.ports.inputs.is_empty() {
-                        ui.label("Inputs:");
+                        ui.label(tr("properties.inputs"));
                         for port in &node.ports.inputs {
                             Self::render_port_info_static(ui, port, true);
                         }
                     }
                     if !node.ports.outputs.is_empty() {
-                        ui.label("Outputs:");
+                        ui.label(tr("properties.outputs"));
                         for port in &node.ports.outputs {
                             Self::render_port_info_static(ui, port, false);
                         }
@@ -171,13 +165,9 @@ impl PropertiesPanel {
 
         // Configuration section
         if !node.config.is_empty() {
-            let filter = self.search_filter.clone();
-            if self.render_section_header(ui, "Configuration") {
+            if self.render_section_header(ui, tr("properties.configuration")) {
                 ui.indent("config", |ui| {
-                    for (key, value) in &node.config {
-                        if !filter.is_empty() && !key.to_lowercase().contains(&filter.to_lowercase()) {
-                            continue;
-                        }
+                    for (key, value) in node.config.iter().filter(|(key, _)| self.filter_matches(key)) {
                         Self::render_config_editor_static(ui, key, value);
                     }
                 });
@@ -187,7 +177,7 @@ impl PropertiesPanel {
 
         // Position section (advanced)
         if self.show_advanced {
-            if self.render_section_header(ui, "Position & Size") {
+            if self.render_section_header(ui, tr("properties.position")) {
                 ui.indent("position", |ui| {
                     ui.horizontal(|ui| {
                         ui.label("X:");
@@ -207,14 +197,14 @@ impl PropertiesPanel {
                     });
 
                     ui.horizontal(|ui| {
-                        ui.label("Width:");
+                        ui.label(tr("properties.width"));
                         let mut w = node.size.width;
-                        if ui.add(egui::DragValue::new(&mut w).speed(1.0).range(50.0..=1000.0)).changed() {
+                        if ui.add(egui::DragValue::new(&mut w).speed(1.0).range(Node::MIN_WIDTH..=Node::MAX_WIDTH)).changed() {
                             if let Some(n) = graph.get_node_mut(node.id) {
-                                n.size.width = w;
+                                n.set_width(w);
                             }
                         }
-                        ui.label("Height:");
+                        ui.label(tr("properties.height"));
                         let mut h = node.size.height;
                         if ui.add(egui::DragValue::new(&mut h).speed(1.0).range(30.0..=1000.0)).changed() {
                             if let Some(n) = graph.get_node_mut(node.id) {
@@ -230,11 +220,11 @@ impl PropertiesPanel {
 
     /// Render properties for an edge
     fn render_edge_properties(&mut self, ui: &mut egui::Ui, edge: &Edge, graph: &mut ProjectGraph) {
-        if self.render_section_header(ui, "Connection") {
+        if self.render_section_header(ui, tr("properties.connection")) {
             ui.indent("connection", |ui| {
                 // From node
                 ui.horizontal(|ui| {
-                    ui.label("From:");
+                    ui.label(tr("properties.from"));
                     if let Some(from_node) = graph.get_node(edge.from_node) {
                         ui.label(format!("{}.{}", from_node.name, edge.from_port));
                     }
@@ -242,7 +232,7 @@ impl PropertiesPanel {
 
                 // To node
                 ui.horizontal(|ui| {
-                    ui.label("To:");
+                    ui.label(tr("properties.to"));
                     if let Some(to_node) = graph.get_node(edge.to_node) {
                         ui.label(format!("{}.{}", to_node.name, edge.to_port));
                     }
@@ -250,14 +240,14 @@ impl PropertiesPanel {
 
                 // Connection type
                 ui.horizontal(|ui| {
-                    ui.label("Type:");
+                    ui.label(tr("properties.type"));
                     ui.label(format!("{:?}", edge.connection_type));
                 });
 
                 // Label
                 if let Some(label) = &edge.label {
                     ui.horizontal(|ui| {
-                        ui.label("Label:");
+                        ui.label(tr("properties.label"));
                         ui.label(label);
                     });
                 }
@@ -267,22 +257,22 @@ impl PropertiesPanel {
 
         // Style section
         if self.show_advanced {
-            if self.render_section_header(ui, "Style") {
+            if self.render_section_header(ui, tr("properties.style")) {
                 ui.indent("style", |ui| {
                     ui.horizontal(|ui| {
-                        ui.label("Color:");
+                        ui.label(tr("properties.color"));
                         let (r, g, b) = edge.style.color.rgb();
                         let color = egui::Color32::from_rgb(r, g, b);
                         ui.colored_label(color, "●");
                     });
 
                     ui.horizontal(|ui| {
-                        ui.label("Line Style:");
-                        ui.label(format!("{:?}", edge.style.line_style));
+                        ui.label(tr("properties.line_style"));
+                        ui.label(edge.style.line_style.display_name());
                     });
 
                     ui.horizontal(|ui| {
-                        ui.label("Thickness:");
+                        ui.label(tr("properties.thickness"));
                         ui.label(format!("{:.1}", edge.style.thickness));
                     });
                 });
@@ -299,24 +289,24 @@ impl PropertiesPanel {
         edges: &[imortal_core::EdgeId],
         _graph: &mut ProjectGraph,
     ) {
-        ui.label("Multiple items selected");
+        ui.label(tr("properties.multiple_items"));
         ui.separator();
 
         if !nodes.is_empty() {
-            ui.label(format!("Nodes: {}", nodes.len()));
+            ui.label(trf("properties.nodes", &[&nodes.len()]));
         }
         if !edges.is_empty() {
-            ui.label(format!("Edges: {}", edges.len()));
+            ui.label(trf("properties.edges", &[&edges.len()]));
         }
 
         ui.separator();
-        ui.label("Bulk operations:");
+        ui.label(tr("properties.bulk"));
 
         ui.horizontal(|ui| {
-            if ui.button("Delete All").clicked() {
+            if ui.button(tr("properties.delete_all")).clicked() {
                 // Would trigger delete_selected on graph
             }
-            if ui.button("Group").clicked() {
+            if ui.button(tr("properties.group")).clicked() {
                 // Would trigger group_selected_nodes on graph
             }
         });
@@ -361,7 +351,7 @@ impl PropertiesPanel {
             ui.label(egui::RichText::new(format!("{:?}", port.data_type)).weak().small());
 
             if port.required {
-                ui.label(egui::RichText::new("required").weak().small());
+                ui.label(egui::RichText::new(tr("properties.required")).weak().small());
             }
         });
 
@@ -392,10 +382,10 @@ impl PropertiesPanel {
                     ui.label(egui::RichText::new("null").weak());
                 }
                 ConfigValue::Array(arr) => {
-                    ui.label(format!("[{} items]", arr.len()));
+                    ui.label(trf("properties.items", &[&arr.len()]));
                 }
                 ConfigValue::Object(obj) => {
-                    ui.label(format!("{{{} keys}}", obj.len()));
+                    ui.label(trf("properties.keys", &[&obj.len()]));
                 }
             }
         });
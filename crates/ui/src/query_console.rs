@@ -0,0 +1,181 @@
+//! Query console for Immortal Engine UI
+//!
+//! Runs [`Query`] pipelines such as `nodes(type:"data.entity") | connected_to("Database")`
+//! against the project and lists the matches. Selecting, deleting, or setting
+//! a config value on the matches is returned as a [`QueryAction`] for the app
+//! to apply, so it goes through undo.
+
+use eframe::egui;
+use imortal_core::{ConfigValue, NodeId};
+use imortal_ir::{ProjectGraph, Query};
+
+use crate::i18n::{tr, trf};
+
+/// Most recent queries kept for the history menu
+const HISTORY_LEN: usize = 10;
+
+/// A batch operation on the matches of a query
+#[derive(Debug, Clone, PartialEq)]
+pub enum QueryAction {
+    /// Select the nodes on the canvas
+    Select(Vec<NodeId>),
+    /// Delete the nodes
+    Delete(Vec<NodeId>),
+    /// Set a config value on every node
+    SetConfig { nodes: Vec<NodeId>, key: String, value: ConfigValue },
+}
+
+/// The Tools > Query Console window
+#[derive(Default)]
+pub struct QueryConsole {
+    /// Whether the window is open
+    pub visible: bool,
+    /// Query being typed
+    input: String,
+    /// Last query run, or why it didn't parse
+    query: Option<Result<Query, String>>,
+    /// Recent queries, newest first
+    history: Vec<String>,
+    /// Config key and value for "Set config"
+    set_key: String,
+    set_value: String,
+}
+
+impl QueryConsole {
+    /// Create a new, hidden console
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parse the input and remember it; matches are re-run every frame so
+    /// they follow edits to the graph
+    pub fn run(&mut self) {
+        let source = self.input.trim().to_string();
+        if source.is_empty() {
+            self.query = None;
+            return;
+        }
+        self.query = Some(Query::parse(&source).map_err(|e| e.to_string()));
+        self.history.retain(|q| *q != source);
+        self.history.insert(0, source);
+        self.history.truncate(HISTORY_LEN);
+    }
+
+    /// Render the console and return the actions taken this frame
+    pub fn show(&mut self, ctx: &egui::Context, graph: &ProjectGraph) -> Vec<QueryAction> {
+        let mut actions = Vec::new();
+        if !self.visible {
+            return actions;
+        }
+
+        let mut visible = self.visible;
+        egui::Window::new(tr("query.title"))
+            .open(&mut visible)
+            .resizable(true)
+            .default_size([520.0, 360.0])
+            .show(ctx, |ui| {
+                let mut run = false;
+                ui.horizontal(|ui| {
+                    let response = ui.add(
+                        egui::TextEdit::singleline(&mut self.input)
+                            .code_editor()
+                            .desired_width(ui.available_width() - 110.0)
+                            .hint_text(r#"nodes(type:"data.entity") | connected_to("Database")"#),
+                    );
+                    run |= response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                    run |= ui.button(tr("query.run")).clicked();
+                    ui.menu_button("🕘", |ui| {
+                        if self.history.is_empty() {
+                            ui.weak(tr("query.no_history"));
+                        }
+                        for query in &self.history {
+                            if ui.button(query).clicked() {
+                                self.input = query.clone();
+                                run = true;
+                                ui.close_menu();
+                            }
+                        }
+                    });
+                });
+                if run {
+                    self.run();
+                }
+
+                let matches = match &self.query {
+                    None => {
+                        ui.weak(tr("query.help"));
+                        return;
+                    }
+                    Some(Err(error)) => {
+                        ui.colored_label(ui.visuals().error_fg_color, error);
+                        return;
+                    }
+                    Some(Ok(query)) => query.run(graph),
+                };
+
+                ui.separator();
+                ui.horizontal(|ui| {
+                    ui.label(trf("query.matches", &[&matches.len()]));
+                    let any = !matches.is_empty();
+                    if ui.add_enabled(any, egui::Button::new(tr("query.select"))).clicked() {
+                        actions.push(QueryAction::Select(matches.clone()));
+                    }
+                    if ui.add_enabled(any, egui::Button::new(tr("query.delete"))).clicked() {
+                        actions.push(QueryAction::Delete(matches.clone()));
+                    }
+                });
+                ui.horizontal(|ui| {
+                    ui.label(tr("query.set_config"));
+                    ui.add(egui::TextEdit::singleline(&mut self.set_key).desired_width(100.0).hint_text("key"));
+                    ui.label("=");
+                    ui.add(egui::TextEdit::singleline(&mut self.set_value).desired_width(100.0).hint_text("value"));
+                    let key = self.set_key.trim();
+                    if ui.add_enabled(!matches.is_empty() && !key.is_empty(), egui::Button::new(tr("query.apply"))).clicked() {
+                        actions.push(QueryAction::SetConfig {
+                            nodes: matches.clone(),
+                            key: key.to_string(),
+                            value: ConfigValue::parse_literal(self.set_value.trim()),
+                        });
+                    }
+                });
+                ui.separator();
+
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    for node in matches.iter().filter_map(|id| graph.get_node(*id)) {
+                        let text = format!("{} {}  ({})", node.category.icon(), node.name, node.component_type);
+                        if ui.selectable_label(graph.selected_nodes.contains(&node.id), text).clicked() {
+                            actions.push(QueryAction::Select(vec![node.id]));
+                        }
+                    }
+                });
+            });
+        self.visible = visible;
+
+        actions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_history() {
+        let mut console = QueryConsole::new();
+        for query in ["name(\"a\")", "name(\"b\")", "name(\"a\")"] {
+            console.input = query.to_string();
+            console.run();
+        }
+        assert_eq!(console.history, vec!["name(\"a\")", "name(\"b\")"]);
+        assert!(matches!(console.query, Some(Ok(_))));
+
+        console.input = "nodes(".to_string();
+        console.run();
+        assert!(matches!(console.query, Some(Err(_))));
+
+        console.input = "  ".to_string();
+        console.run();
+        assert!(console.query.is_none());
+        assert_eq!(console.history.len(), 3);
+    }
+}
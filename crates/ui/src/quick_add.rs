@@ -0,0 +1,206 @@
+//! Quick-add component search
+//!
+//! The popup opened with Space/Tab, by double-clicking empty canvas, or by
+//! dropping a connection on empty canvas, when it only offers components
+//! that can connect to the dragged port.
+//! Components are matched with a fuzzy subsequence search, so "usrent"
+//! finds "User Entity", and recently added components rank first.
+
+use std::collections::VecDeque;
+
+use eframe::egui;
+use imortal_components::{ComponentDefinition, ComponentRegistry, PortDefinition};
+pub use imortal_components::fuzzy_score;
+use imortal_core::NodeId;
+use imortal_ir::{Node, Port, Position};
+
+/// How many recently added components are remembered
+const RECENT_LIMIT: usize = 8;
+
+/// State of the quick-add popup
+pub struct QuickAdd {
+    /// Where the component will be placed, in canvas units
+    pub position: Position,
+    /// Where the popup is shown
+    pub screen_pos: egui::Pos2,
+    /// Search text
+    pub search: String,
+    /// Highlighted row in the results
+    pub selected: usize,
+    /// Node and side (true for output) of a dropped connection to complete
+    pub connect_from: Option<(NodeId, bool)>,
+}
+
+impl QuickAdd {
+    /// Open an empty search at a canvas position
+    pub fn new(position: Position, screen_pos: egui::Pos2) -> Self {
+        Self {
+            position,
+            screen_pos,
+            search: String::new(),
+            selected: 0,
+            connect_from: None,
+        }
+    }
+
+    /// Open a search for components to connect to a dropped connection
+    pub fn connecting(position: Position, screen_pos: egui::Pos2, node_id: NodeId, is_output: bool) -> Self {
+        Self {
+            connect_from: Some((node_id, is_output)),
+            ..Self::new(position, screen_pos)
+        }
+    }
+}
+
+/// A component that can take a dropped connection
+pub struct Connectable<'a> {
+    pub component: &'a ComponentDefinition,
+    /// Port of the dragged node
+    pub port: &'a Port,
+    /// Port of the component it connects to
+    pub component_port: &'a PortDefinition,
+}
+
+/// Components with a port compatible with one of `node`'s outputs (or
+/// inputs), each paired with the first matching ports
+pub fn connectable<'a>(registry: &'a ComponentRegistry, node: &'a Node, is_output: bool) -> Vec<Connectable<'a>> {
+    let ports = if is_output { &node.ports.outputs } else { &node.ports.inputs };
+    let mut found: Vec<Connectable> = Vec::new();
+    for port in ports {
+        for (component, component_port) in registry.compatible_with(port) {
+            if !found.iter().any(|c| c.component.id == component.id) {
+                found.push(Connectable { component, port, component_port });
+            }
+        }
+    }
+    found
+}
+
+/// A component offered by the popup
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Candidate {
+    /// Registry id
+    pub id: String,
+    /// Component icon
+    pub icon: String,
+    /// Display name, already translated
+    pub name: String,
+    /// Description, already translated
+    pub description: String,
+}
+
+/// Components added most recently first
+#[derive(Debug, Clone, Default)]
+pub struct RecentComponents {
+    ids: VecDeque<String>,
+}
+
+impl RecentComponents {
+    /// Record that a component was added
+    pub fn record(&mut self, id: &str) {
+        self.ids.retain(|recent| recent != id);
+        self.ids.push_front(id.to_string());
+        self.ids.truncate(RECENT_LIMIT);
+    }
+
+    /// Position in the recent list, 0 being the latest
+    pub fn rank(&self, id: &str) -> Option<usize> {
+        self.ids.iter().position(|recent| recent == id)
+    }
+}
+
+/// Filter and order candidates for a query
+///
+/// Name matches rank above description matches, and within the same score
+/// recently added components come first. An empty query lists recent
+/// components, then the rest alphabetically.
+pub fn rank(candidates: Vec<Candidate>, query: &str, recent: &RecentComponents) -> Vec<Candidate> {
+    let query = query.trim();
+    let mut scored: Vec<(u32, Candidate)> = candidates
+        .into_iter()
+        .filter_map(|candidate| {
+            let score = if query.is_empty() {
+                0
+            } else {
+                let description_match = candidate.description.to_lowercase().contains(&query.to_lowercase());
+                fuzzy_score(query, &candidate.name)
+                    .map(|score| score + 10)
+                    .or(description_match.then_some(1))?
+            };
+            Some((score, candidate))
+        })
+        .collect();
+
+    scored.sort_by(|(a_score, a), (b_score, b)| {
+        b_score
+            .cmp(a_score)
+            .then_with(|| {
+                let recency = |c: &Candidate| recent.rank(&c.id).unwrap_or(usize::MAX);
+                recency(a).cmp(&recency(b))
+            })
+            .then_with(|| a.name.cmp(&b.name))
+    });
+    scored.into_iter().map(|(_, candidate)| candidate).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidate(id: &str, name: &str, description: &str) -> Candidate {
+        Candidate {
+            id: id.to_string(),
+            icon: String::new(),
+            name: name.to_string(),
+            description: description.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_fuzzy_score() {
+        assert!(fuzzy_score("usrent", "User Entity").is_some());
+        assert!(fuzzy_score("xyz", "User Entity").is_none());
+        assert_eq!(fuzzy_score("", "Anything"), Some(0));
+
+        // Word starts and runs beat scattered letters
+        assert!(fuzzy_score("db", "Database") < fuzzy_score("db", "Data Backup"));
+        assert!(fuzzy_score("ent", "Entity") > fuzzy_score("ent", "Payment"));
+    }
+
+    #[test]
+    fn test_connectable() {
+        let registry = ComponentRegistry::with_builtins();
+        let mut node = Node::new("logic.test", "Source");
+        node.ports.add_output(PortDefinition::trigger_out("done", "Done").to_port());
+
+        let found = connectable(&registry, &node, true);
+        assert!(!found.is_empty());
+        for c in &found {
+            assert_eq!(c.port.id, "done");
+            assert!(c.port.can_connect_to(&c.component_port.to_port()));
+        }
+        // Nothing matches the node's (missing) inputs
+        assert!(connectable(&registry, &node, false).is_empty());
+    }
+
+    #[test]
+    fn test_rank_with_recent() {
+        let candidates = vec![
+            candidate("data.entity", "Entity", "A data model"),
+            candidate("api.rest", "REST Endpoint", "HTTP endpoint"),
+            candidate("storage.database", "Database", "Stores entity data"),
+        ];
+        let mut recent = RecentComponents::default();
+
+        let names = |ranked: Vec<Candidate>| ranked.into_iter().map(|c| c.name).collect::<Vec<_>>();
+        assert_eq!(names(rank(candidates.clone(), "", &recent)), ["Database", "Entity", "REST Endpoint"]);
+        // Name matches first, then description matches
+        assert_eq!(names(rank(candidates.clone(), "entity", &recent)), ["Entity", "Database"]);
+
+        recent.record("api.rest");
+        recent.record("storage.database");
+        recent.record("api.rest");
+        assert_eq!(recent.rank("api.rest"), Some(0));
+        assert_eq!(names(rank(candidates, "", &recent)), ["REST Endpoint", "Database", "Entity"]);
+    }
+}
@@ -0,0 +1,328 @@
+//! Schema overview panel for Immortal Engine UI
+//!
+//! A data dictionary outside the canvas: every entity field in one sortable
+//! table. Edits made in the table are returned as [`SchemaAction`]s for the
+//! app to apply (so they go through undo), and the table can be exported
+//! as CSV.
+
+use eframe::egui;
+use egui_extras::{Column, TableBuilder};
+use imortal_core::{DataType, NodeId};
+use imortal_ir::ProjectGraph;
+use uuid::Uuid;
+
+use crate::app::ImmortalApp;
+use crate::i18n::{tr, trf};
+
+/// Column the schema table is sorted by
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SchemaColumn {
+    Entity,
+    Field,
+    Type,
+    Required,
+}
+
+impl SchemaColumn {
+    /// Header label
+    pub fn label(&self) -> &'static str {
+        match self {
+            SchemaColumn::Entity => tr("schema.entity"),
+            SchemaColumn::Field => tr("schema.field"),
+            SchemaColumn::Type => tr("schema.type"),
+            SchemaColumn::Required => tr("schema.required"),
+        }
+    }
+}
+
+/// One field of one entity
+#[derive(Debug, Clone, PartialEq)]
+pub struct SchemaRow {
+    pub node_id: NodeId,
+    pub field_id: Uuid,
+    pub entity: String,
+    pub field: String,
+    pub data_type: DataType,
+    pub required: bool,
+    pub primary_key: bool,
+    pub unique: bool,
+    pub description: String,
+}
+
+impl SchemaRow {
+    /// Type as shown in the table, including referenced entity names
+    pub fn type_label(&self) -> String {
+        type_label(&self.data_type)
+    }
+}
+
+/// An edit or selection made in the schema panel
+#[derive(Debug, Clone, PartialEq)]
+pub enum SchemaAction {
+    /// Select the entity on the canvas
+    Select(NodeId),
+    /// Rename a field
+    RenameField { node_id: NodeId, field_id: Uuid, name: String },
+    /// Change a field's type
+    SetType { node_id: NodeId, field_id: Uuid, data_type: DataType },
+    /// Toggle whether a field is required
+    SetRequired { node_id: NodeId, field_id: Uuid, required: bool },
+    /// Export the table as CSV
+    ExportCsv,
+}
+
+/// Collect one row per entity field, in entity then field order
+pub fn schema_rows(graph: &ProjectGraph) -> Vec<SchemaRow> {
+    let mut entities = graph.find_nodes_by_type("data.entity");
+    entities.sort_by(|a, b| a.name.cmp(&b.name));
+
+    entities
+        .into_iter()
+        .flat_map(|node| {
+            node.fields.iter().map(move |field| SchemaRow {
+                node_id: node.id,
+                field_id: field.id,
+                entity: node.name.clone(),
+                field: field.name.clone(),
+                data_type: field.data_type.clone(),
+                required: field.required,
+                primary_key: field.is_primary_key(),
+                unique: field.is_unique(),
+                description: field.description.clone().unwrap_or_default(),
+            })
+        })
+        .collect()
+}
+
+/// Sort rows by a column; ties keep entity/field order
+pub fn sort_rows(rows: &mut [SchemaRow], column: SchemaColumn, ascending: bool) {
+    rows.sort_by(|a, b| {
+        let ordering = match column {
+            SchemaColumn::Entity => a.entity.cmp(&b.entity),
+            SchemaColumn::Field => a.field.cmp(&b.field),
+            SchemaColumn::Type => a.type_label().cmp(&b.type_label()),
+            SchemaColumn::Required => a.required.cmp(&b.required),
+        };
+        if ascending { ordering } else { ordering.reverse() }
+    });
+}
+
+/// Render rows as CSV with a header line
+pub fn to_csv(rows: &[SchemaRow]) -> String {
+    let mut csv = String::from("entity,field,type,required,primary_key,unique,description\n");
+    for row in rows {
+        let cells = [
+            csv_escape(&row.entity),
+            csv_escape(&row.field),
+            csv_escape(&row.type_label()),
+            row.required.to_string(),
+            row.primary_key.to_string(),
+            row.unique.to_string(),
+            csv_escape(&row.description),
+        ];
+        csv.push_str(&cells.join(","));
+        csv.push('\n');
+    }
+    csv
+}
+
+fn csv_escape(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn type_label(data_type: &DataType) -> String {
+    match data_type {
+        DataType::Reference(name) | DataType::Entity(name) => {
+            format!("{} ({})", ImmortalApp::data_type_display(data_type), name)
+        }
+        DataType::Optional(inner) => format!("Optional<{}>", type_label(inner)),
+        DataType::Array(inner) => format!("Array<{}>", type_label(inner)),
+        other => ImmortalApp::data_type_display(other).to_string(),
+    }
+}
+
+/// The schema overview window
+pub struct SchemaPanel {
+    /// Whether the panel is visible
+    pub visible: bool,
+    /// Column the table is sorted by
+    pub sort_column: SchemaColumn,
+    /// Sort direction
+    pub ascending: bool,
+}
+
+impl Default for SchemaPanel {
+    fn default() -> Self {
+        Self {
+            visible: false,
+            sort_column: SchemaColumn::Entity,
+            ascending: true,
+        }
+    }
+}
+
+impl SchemaPanel {
+    /// Create a new, hidden panel
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Render the panel and return the actions taken this frame
+    pub fn show(&mut self, ctx: &egui::Context, graph: &ProjectGraph) -> Vec<SchemaAction> {
+        let mut actions = Vec::new();
+        if !self.visible {
+            return actions;
+        }
+
+        let mut rows = schema_rows(graph);
+        sort_rows(&mut rows, self.sort_column, self.ascending);
+        let mut visible = self.visible;
+
+        egui::Window::new(tr("schema.title"))
+            .open(&mut visible)
+            .resizable(true)
+            .default_size([560.0, 360.0])
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label(trf("schema.count", &[&rows.len()]));
+                    if ui.button(tr("schema.export_csv")).clicked() {
+                        actions.push(SchemaAction::ExportCsv);
+                    }
+                });
+                ui.separator();
+
+                TableBuilder::new(ui)
+                    .striped(true)
+                    .resizable(true)
+                    .sense(egui::Sense::click())
+                    .column(Column::auto().at_least(90.0))
+                    .column(Column::auto().at_least(110.0))
+                    .column(Column::auto().at_least(120.0))
+                    .column(Column::auto())
+                    .column(Column::remainder())
+                    .header(20.0, |mut header| {
+                        for column in [SchemaColumn::Entity, SchemaColumn::Field, SchemaColumn::Type, SchemaColumn::Required] {
+                            header.col(|ui| {
+                                let arrow = match (self.sort_column == column, self.ascending) {
+                                    (true, true) => " ⏶",
+                                    (true, false) => " ⏷",
+                                    _ => "",
+                                };
+                                if ui.button(format!("{}{}", column.label(), arrow)).clicked() {
+                                    if self.sort_column == column {
+                                        self.ascending = !self.ascending;
+                                    } else {
+                                        self.sort_column = column;
+                                        self.ascending = true;
+                                    }
+                                }
+                            });
+                        }
+                        header.col(|ui| {
+                            ui.strong(tr("schema.description"));
+                        });
+                    })
+                    .body(|mut body| {
+                        for row in &rows {
+                            body.row(22.0, |mut table_row| {
+                                table_row.set_selected(graph.selected_nodes.contains(&row.node_id));
+                                self.render_row(&mut table_row, row, &mut actions);
+                                if table_row.response().clicked() {
+                                    actions.push(SchemaAction::Select(row.node_id));
+                                }
+                            });
+                        }
+                    });
+            });
+
+        self.visible = visible;
+        actions
+    }
+
+    fn render_row(&self, table_row: &mut egui_extras::TableRow<'_, '_>, row: &SchemaRow, actions: &mut Vec<SchemaAction>) {
+        let (node_id, field_id) = (row.node_id, row.field_id);
+
+        table_row.col(|ui| {
+            if ui.selectable_label(false, &row.entity).clicked() {
+                actions.push(SchemaAction::Select(node_id));
+            }
+        });
+        table_row.col(|ui| {
+            let mut name = row.field.clone();
+            let key = if row.primary_key { "🔑 " } else { "" };
+            ui.label(key);
+            if ui.add_enabled(!row.primary_key, egui::TextEdit::singleline(&mut name)).changed() {
+                actions.push(SchemaAction::RenameField { node_id, field_id, name });
+            }
+        });
+        table_row.col(|ui| {
+            if matches!(row.data_type, DataType::Reference(_) | DataType::Entity(_) | DataType::Optional(_) | DataType::Array(_)) {
+                ui.label(row.type_label());
+                return;
+            }
+            egui::ComboBox::from_id_salt(("schema_type", field_id))
+                .selected_text(row.type_label())
+                .show_ui(ui, |ui| {
+                    for (idx, label) in ImmortalApp::data_type_options().iter().enumerate() {
+                        let data_type = ImmortalApp::index_to_data_type(idx);
+                        if ui.selectable_label(data_type == row.data_type, *label).clicked() && data_type != row.data_type {
+                            actions.push(SchemaAction::SetType { node_id, field_id, data_type });
+                        }
+                    }
+                });
+        });
+        table_row.col(|ui| {
+            let mut required = row.required;
+            if ui.add_enabled(!row.primary_key, egui::Checkbox::without_text(&mut required)).changed() {
+                actions.push(SchemaAction::SetRequired { node_id, field_id, required });
+            }
+        });
+        table_row.col(|ui| {
+            ui.label(&row.description);
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use imortal_ir::{Field, Node};
+
+    fn sample_graph() -> ProjectGraph {
+        let mut graph = ProjectGraph::with_name("test");
+        let mut user = Node::new_entity("User");
+        user.add_field(Field::string("email").required().with_description("Login, unique"));
+        graph.add_node(user);
+        let mut post = Node::new_entity("Post");
+        post.add_field(Field::new("author", DataType::Reference("User".to_string())));
+        graph.add_node(post);
+        graph
+    }
+
+    #[test]
+    fn test_schema_rows_and_sorting() {
+        let mut rows = schema_rows(&sample_graph());
+        assert_eq!(rows.len(), 4);
+        assert_eq!((rows[0].entity.as_str(), rows[0].field.as_str()), ("Post", "id"));
+
+        sort_rows(&mut rows, SchemaColumn::Required, false);
+        assert!(rows[0].required);
+        assert_eq!(rows[3].field, "author");
+        sort_rows(&mut rows, SchemaColumn::Field, true);
+        assert_eq!(rows[0].field, "author");
+        assert_eq!(rows[0].type_label(), "Reference (User)");
+    }
+
+    #[test]
+    fn test_to_csv_escapes() {
+        let csv = to_csv(&schema_rows(&sample_graph()));
+        let lines: Vec<_> = csv.lines().collect();
+        assert_eq!(lines[0], "entity,field,type,required,primary_key,unique,description");
+        assert!(lines.contains(&"User,email,String,true,false,false,\"Login, unique\""));
+        assert!(lines.contains(&"Post,author,Reference (User),false,false,false,"));
+    }
+}
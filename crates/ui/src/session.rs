@@ -0,0 +1,152 @@
+//! Session restore
+//!
+//! On exit the editor saves a [`Session`]: the open project, its viewport
+//! and selection, the expanded palette categories, and which panels were
+//! open. On the next launch, when [`Session::restore`] is on, the editor
+//! reopens that project as it was instead of showing the welcome screen.
+
+use std::path::{Path, PathBuf};
+
+use imortal_core::ComponentCategory;
+use imortal_ir::graph::Viewport;
+use imortal_ir::{EdgeId, NodeId};
+use serde::{Deserialize, Serialize};
+
+/// The editor's state when it last closed
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Session {
+    /// Whether to restore the session on launch (the setting)
+    #[serde(default = "default_restore")]
+    pub restore: bool,
+    /// Project file that was open, if any
+    #[serde(default)]
+    pub project: Option<PathBuf>,
+    /// Canvas viewport of that project
+    #[serde(default)]
+    pub viewport: Option<Viewport>,
+    #[serde(default)]
+    pub selected_nodes: Vec<NodeId>,
+    #[serde(default)]
+    pub selected_edges: Vec<EdgeId>,
+    /// Palette categories that were expanded
+    #[serde(default)]
+    pub expanded_categories: Vec<ComponentCategory>,
+    #[serde(default)]
+    pub panels: OpenPanels,
+}
+
+fn default_restore() -> bool {
+    true
+}
+
+impl Default for Session {
+    fn default() -> Self {
+        Self {
+            restore: default_restore(),
+            project: None,
+            viewport: None,
+            selected_nodes: Vec::new(),
+            selected_edges: Vec::new(),
+            expanded_categories: Vec::new(),
+            panels: OpenPanels::default(),
+        }
+    }
+}
+
+/// Panels that can be shown or hidden
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OpenPanels {
+    pub log: bool,
+    pub history: bool,
+    pub suggestions: bool,
+    pub query_console: bool,
+    pub notifications: bool,
+    pub minimap: bool,
+}
+
+impl Default for OpenPanels {
+    fn default() -> Self {
+        Self { log: false, history: false, suggestions: false, query_console: false, notifications: false, minimap: true }
+    }
+}
+
+impl Session {
+    /// Where the session is saved
+    pub fn path() -> Option<PathBuf> {
+        dirs::config_dir().map(|p| p.join("immortal-engine").join("session.json"))
+    }
+
+    /// The last saved session, or a fresh one when there is none
+    pub fn load() -> Self {
+        Self::path().map(|path| Self::load_from(&path)).unwrap_or_default()
+    }
+
+    /// The session saved at `path`, or a fresh one when it can't be read
+    pub fn load_from(path: &Path) -> Self {
+        std::fs::read_to_string(path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    /// Save the session, logging failures; there is nobody left to tell
+    /// on exit
+    pub fn save(&self) {
+        if let Some(path) = Self::path() {
+            if let Err(e) = self.save_to(&path) {
+                tracing::warn!("Failed to save the session to {}: {}", path.display(), e);
+            }
+        }
+    }
+
+    pub fn save_to(&self, path: &Path) -> std::io::Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(self)?)
+    }
+
+    /// The project to reopen on launch, if restoring is on and it still exists
+    pub fn project_to_restore(&self) -> Option<&Path> {
+        self.project.as_deref().filter(|path| self.restore && path.is_file())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_session_round_trip() {
+        let dir = std::env::temp_dir().join(format!("imortal-session-{}", uuid::Uuid::new_v4()));
+        let path = dir.join("session.json");
+        // A missing or damaged file is a fresh session
+        assert_eq!(Session::load_from(&path), Session::default());
+
+        let project = dir.join("shop.imortal");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(&project, "{}").unwrap();
+        let session = Session {
+            project: Some(project.clone()),
+            viewport: Some(Viewport { pan_x: 120.0, pan_y: -40.0, zoom: 1.5 }),
+            selected_nodes: vec![uuid::Uuid::new_v4()],
+            expanded_categories: vec![ComponentCategory::Data, ComponentCategory::Api],
+            panels: OpenPanels { log: true, ..OpenPanels::default() },
+            ..Session::default()
+        };
+        session.save_to(&path).unwrap();
+        let loaded = Session::load_from(&path);
+        assert_eq!(loaded, session);
+        assert_eq!(loaded.project_to_restore(), Some(project.as_path()));
+
+        let off = Session { restore: false, ..loaded };
+        assert_eq!(off.project_to_restore(), None);
+        std::fs::remove_file(&project).unwrap();
+        assert_eq!(Session { restore: true, ..off }.project_to_restore(), None);
+
+        std::fs::write(&path, "not json").unwrap();
+        assert_eq!(Session::load_from(&path), Session::default());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -3,11 +3,12 @@
 //! This module provides state management for the Immortal Engine visual editor.
 //! It tracks the current project, selection, interaction state, and undo/redo history.
 
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::time::SystemTime;
 use uuid::Uuid;
 
 use imortal_ir::{ProjectGraph, NodeId, EdgeId};
-use imortal_components::ComponentRegistry;
+use imortal_components::{ComponentRegistry, SearchFilter};
 
 /// The main editor state
 #[derive(Debug)]
@@ -50,6 +51,20 @@ pub struct EditorState {
 
     /// Expanded categories in the palette
     pub palette_expanded: std::collections::HashSet<imortal_core::ComponentCategory>,
+
+    /// Category and tag chips selected in the palette
+    pub palette_filter: SearchFilter,
+
+    /// Module entered through the breadcrumb bar; the canvas only shows its
+    /// nodes and new components are added to it
+    pub namespace: Vec<String>,
+
+    /// Tags selected in the tag bar; nodes carrying none of them are dimmed
+    /// or hidden
+    pub tag_filter: Vec<String>,
+
+    /// Hide nodes outside the tag filter instead of dimming them
+    pub hide_untagged: bool,
 }
 
 impl EditorState {
@@ -74,6 +89,10 @@ impl EditorState {
             panels: PanelVisibility::default(),
             palette_search: String::new(),
             palette_expanded,
+            palette_filter: SearchFilter::default(),
+            namespace: Vec::new(),
+            tag_filter: Vec::new(),
+            hide_untagged: false,
         }
     }
 
@@ -98,6 +117,10 @@ impl EditorState {
             panels: PanelVisibility::default(),
             palette_search: String::new(),
             palette_expanded,
+            palette_filter: SearchFilter::default(),
+            namespace: Vec::new(),
+            tag_filter: Vec::new(),
+            hide_untagged: false,
         }
     }
 
@@ -460,22 +483,53 @@ pub enum Tool {
 }
 
 /// Undo/Redo history
+///
+/// States form a tree: an edit made after undoing starts a new branch
+/// instead of discarding the states that were undone, so the timeline can
+/// still jump to them. Redo follows the branch most recently created or
+/// left by undo.
 #[derive(Debug)]
 pub struct History {
-    /// Undo stack
-    undo_stack: Vec<HistoryEntry>,
-    /// Redo stack
-    redo_stack: Vec<HistoryEntry>,
-    /// Maximum history size
+    /// Every state kept, keyed by creation order
+    states: BTreeMap<usize, HistoryState>,
+    /// Key of the current state
+    current: usize,
+    /// Key of the next state created
+    next_key: usize,
+    /// Maximum number of states kept besides the current one
     max_size: usize,
 }
 
 #[derive(Debug, Clone)]
-struct HistoryEntry {
-    /// Action name for display
+struct HistoryState {
+    /// State this one was reached from, `None` for the oldest kept
+    parent: Option<usize>,
+    /// Child redo moves to
+    redo_child: Option<usize>,
+    /// Action that led here from the parent, for display
     action_name: String,
-    /// Snapshot of the project state
-    snapshot: ProjectGraph,
+    /// When that action was performed
+    timestamp: SystemTime,
+    /// Snapshot of the project state, `None` for the current one, which
+    /// lives in the editor
+    snapshot: Option<ProjectGraph>,
+}
+
+/// One state in the history timeline
+#[derive(Debug, Clone, Copy)]
+pub struct TimelineEntry<'a> {
+    /// Action that led to this state, or `None` for the oldest state kept
+    pub action_name: Option<&'a str>,
+    /// When that action was performed
+    pub timestamp: Option<SystemTime>,
+    /// The state, or `None` for the current one, which lives in the editor
+    pub snapshot: Option<&'a ProjectGraph>,
+    /// How far the state's branch is indented: 0 for the line redo follows,
+    /// more for branches left behind by editing after an undo
+    pub depth: usize,
+    /// Whether the state is reached by redoing or jumping from the current
+    /// one, rather than by undoing
+    pub ahead: bool,
 }
 
 impl History {
@@ -486,84 +540,222 @@ impl History {
 
     /// Create a new history with custom max size
     pub fn with_max_size(max_size: usize) -> Self {
-        Self {
-            undo_stack: Vec::new(),
-            redo_stack: Vec::new(),
-            max_size,
-        }
+        let mut history = Self { states: BTreeMap::new(), current: 0, next_key: 0, max_size };
+        history.clear();
+        history
     }
 
-    /// Push a new state onto the history
+    /// Record an action; `snapshot` is the state before it
+    ///
+    /// States undone before the action stay in the timeline as a branch.
     pub fn push(&mut self, action_name: &str, snapshot: ProjectGraph) {
-        // Clear redo stack when new action is performed
-        self.redo_stack.clear();
-
-        // Add to undo stack
-        self.undo_stack.push(HistoryEntry {
-            action_name: action_name.to_string(),
-            snapshot,
-        });
-
-        // Trim if exceeds max size
-        while self.undo_stack.len() > self.max_size {
-            self.undo_stack.remove(0);
+        let key = self.next_key;
+        self.next_key += 1;
+        let previous = self.current;
+        if let Some(state) = self.states.get_mut(&previous) {
+            state.snapshot = Some(snapshot);
+            state.redo_child = Some(key);
         }
+        self.states.insert(
+            key,
+            HistoryState {
+                parent: Some(previous),
+                redo_child: None,
+                action_name: action_name.to_string(),
+                timestamp: SystemTime::now(),
+                snapshot: None,
+            },
+        );
+        self.current = key;
+        self.trim();
     }
 
     /// Undo the last action, returns the previous state
     pub fn undo(&mut self, current_state: ProjectGraph) -> Option<ProjectGraph> {
-        if let Some(entry) = self.undo_stack.pop() {
-            // Push current state to redo stack
-            self.redo_stack.push(HistoryEntry {
-                action_name: entry.action_name.clone(),
-                snapshot: current_state,
-            });
-
-            Some(entry.snapshot)
-        } else {
-            None
-        }
+        let parent = self.states.get(&self.current)?.parent?;
+        self.move_to(parent, current_state)
     }
 
     /// Redo the last undone action
     pub fn redo(&mut self, current_state: ProjectGraph) -> Option<ProjectGraph> {
-        if let Some(entry) = self.redo_stack.pop() {
-            // Push current state to undo stack
-            self.undo_stack.push(HistoryEntry {
-                action_name: entry.action_name.clone(),
-                snapshot: current_state,
-            });
-
-            Some(entry.snapshot)
-        } else {
-            None
-        }
+        let child = self.states.get(&self.current)?.redo_child?;
+        self.move_to(child, current_state)
     }
 
     /// Check if undo is available
     pub fn can_undo(&self) -> bool {
-        !self.undo_stack.is_empty()
+        self.states.get(&self.current).is_some_and(|state| state.parent.is_some())
     }
 
     /// Check if redo is available
     pub fn can_redo(&self) -> bool {
-        !self.redo_stack.is_empty()
+        self.states.get(&self.current).is_some_and(|state| state.redo_child.is_some())
     }
 
     /// Get the name of the action that would be undone
     pub fn undo_action_name(&self) -> Option<&str> {
-        self.undo_stack.last().map(|e| e.action_name.as_str())
+        let state = self.states.get(&self.current)?;
+        state.parent.map(|_| state.action_name.as_str())
     }
 
     /// Get the name of the action that would be redone
     pub fn redo_action_name(&self) -> Option<&str> {
-        self.redo_stack.last().map(|e| e.action_name.as_str())
+        let child = self.states.get(&self.current)?.redo_child?;
+        self.states.get(&child).map(|state| state.action_name.as_str())
+    }
+
+    /// Every state kept, oldest first, with each branch left behind listed
+    /// right after the state it branched from
+    ///
+    /// The current state is at [`current_index`](Self::current_index).
+    pub fn timeline(&self) -> Vec<TimelineEntry<'_>> {
+        let behind = self.ancestors(self.current);
+        self.timeline_keys()
+            .into_iter()
+            .map(|(key, depth)| {
+                let state = &self.states[&key];
+                let has_parent = state.parent.is_some();
+                TimelineEntry {
+                    action_name: has_parent.then_some(state.action_name.as_str()),
+                    timestamp: has_parent.then_some(state.timestamp),
+                    snapshot: state.snapshot.as_ref(),
+                    depth,
+                    ahead: !behind.contains(&key),
+                }
+            })
+            .collect()
+    }
+
+    /// Position of the current state in the [`timeline`](Self::timeline)
+    pub fn current_index(&self) -> usize {
+        self.timeline_keys().iter().position(|(key, _)| *key == self.current).unwrap_or(0)
+    }
+
+    /// Move to a state in the [`timeline`](Self::timeline), returning it
+    ///
+    /// Nothing is discarded, so jumping back restores the same timeline, and
+    /// redo then leads towards the state jumped from.
+    pub fn jump_to(&mut self, index: usize, current_state: ProjectGraph) -> Option<ProjectGraph> {
+        let (key, _) = *self.timeline_keys().get(index)?;
+        if key == self.current {
+            return None;
+        }
+        // Redo from the target retraces the way to where we were
+        let mut child = self.current;
+        for ancestor in self.ancestors(self.current).into_iter().skip(1) {
+            if let Some(state) = self.states.get_mut(&ancestor) {
+                state.redo_child = Some(child);
+            }
+            child = ancestor;
+        }
+        self.move_to(key, current_state)
     }
 
     /// Clear all history
     pub fn clear(&mut self) {
-        self.undo_stack.clear();
-        self.redo_stack.clear();
+        self.states.clear();
+        self.current = self.next_key;
+        self.next_key += 1;
+        self.states.insert(
+            self.current,
+            HistoryState {
+                parent: None,
+                redo_child: None,
+                action_name: String::new(),
+                timestamp: SystemTime::now(),
+                snapshot: None,
+            },
+        );
+    }
+
+    /// Make `key` the current state, keeping `current_state` as the one left
+    fn move_to(&mut self, key: usize, current_state: ProjectGraph) -> Option<ProjectGraph> {
+        let snapshot = self.states.get_mut(&key)?.snapshot.take()?;
+        if let Some(parent) = self.states[&key].parent {
+            // Redo returns down the branch just taken
+            if let Some(state) = self.states.get_mut(&parent) {
+                state.redo_child = Some(key);
+            }
+        }
+        if let Some(state) = self.states.get_mut(&self.current) {
+            state.snapshot = Some(current_state);
+        }
+        self.current = key;
+        Some(snapshot)
+    }
+
+    /// `key` and the states before it, nearest first
+    fn ancestors(&self, key: usize) -> Vec<usize> {
+        std::iter::successors(Some(key), |key| self.states.get(key)?.parent).collect()
+    }
+
+    /// States in timeline order, with their depth
+    fn timeline_keys(&self) -> Vec<(usize, usize)> {
+        let mut children: HashMap<usize, Vec<usize>> = HashMap::new();
+        let mut roots = Vec::new();
+        for (&key, state) in &self.states {
+            match state.parent {
+                Some(parent) => children.entry(parent).or_default().push(key),
+                None => roots.push(key),
+            }
+        }
+
+        let mut keys = Vec::with_capacity(self.states.len());
+        // Depth-first; the redo child goes on the stack first so it comes
+        // out last, after the branches left behind, at the same depth
+        let mut stack: Vec<(usize, usize)> = roots.into_iter().rev().map(|key| (key, 0)).collect();
+        while let Some((key, depth)) = stack.pop() {
+            keys.push((key, depth));
+            let redo_child = self.states[&key].redo_child;
+            let kids = children.get(&key).map(Vec::as_slice).unwrap_or_default();
+            if let Some(redo) = redo_child.filter(|child| kids.contains(child)) {
+                stack.push((redo, depth));
+            }
+            for &child in kids.iter().rev().filter(|child| Some(**child) != redo_child) {
+                stack.push((child, depth + 1));
+            }
+        }
+        keys
+    }
+
+    /// Drop states beyond the maximum: branches left behind first, oldest
+    /// first, then the oldest states undo reaches, then the far end of redo
+    fn trim(&mut self) {
+        while self.states.len() > self.max_size + 1 {
+            let behind = self.ancestors(self.current);
+            let parents: HashSet<usize> = self.states.values().filter_map(|state| state.parent).collect();
+            let is_ahead = |key: usize| self.ancestors(key).contains(&self.current);
+            let leaves = || self.states.keys().copied().filter(|key| !parents.contains(key) && *key != self.current);
+
+            let abandoned = leaves().find(|key| !is_ahead(*key));
+            let oldest = behind.last().copied().filter(|root| *root != self.current);
+            let Some(key) = abandoned.or(oldest).or_else(|| leaves().next()) else {
+                break;
+            };
+            self.remove(key);
+        }
+    }
+
+    /// Remove a leaf or the oldest state
+    fn remove(&mut self, key: usize) {
+        let Some(removed) = self.states.remove(&key) else {
+            return;
+        };
+        // The children of the oldest state become the oldest
+        for state in self.states.values_mut().filter(|state| state.parent == Some(key)) {
+            state.parent = None;
+        }
+        if let Some(parent) = removed.parent.and_then(|parent| self.states.get_mut(&parent)) {
+            if parent.redo_child == Some(key) {
+                parent.redo_child = None;
+            }
+        }
+        if let Some(parent) = removed.parent {
+            let newest_child = self.states.iter().rev().find(|(_, state)| state.parent == Some(parent)).map(|(key, _)| *key);
+            if let Some(state) = self.states.get_mut(&parent).filter(|state| state.redo_child.is_none()) {
+                state.redo_child = newest_child;
+            }
+        }
     }
 }
 
@@ -668,6 +860,107 @@ mod tests {
         assert!(history.can_redo());
     }
 
+    #[test]
+    fn test_history_timeline_jump() {
+        let mut history = History::new();
+        let mut project = ProjectGraph::with_name("v0");
+        for (action, name) in [("Add User", "v1"), ("Add Post", "v2"), ("Connect", "v3")] {
+            history.push(action, project.clone());
+            project = ProjectGraph::with_name(name);
+        }
+
+        let timeline = history.timeline();
+        let actions: Vec<_> = timeline.iter().map(|entry| entry.action_name).collect();
+        assert_eq!(actions, [None, Some("Add User"), Some("Add Post"), Some("Connect")]);
+        assert_eq!(history.current_index(), 3);
+        assert!(timeline[3].snapshot.is_none());
+        assert_eq!(timeline[1].snapshot.unwrap().meta.name, "v1");
+        assert!(timeline[0].timestamp.is_none() && timeline[1].timestamp.is_some());
+
+        // Jumping back keeps the later states as redo steps
+        project = history.jump_to(1, project).unwrap();
+        assert_eq!(project.meta.name, "v1");
+        assert_eq!(history.current_index(), 1);
+        let actions: Vec<_> = history.timeline().iter().map(|entry| entry.action_name).collect();
+        assert_eq!(actions, [None, Some("Add User"), Some("Add Post"), Some("Connect")]);
+        assert_eq!(history.timeline()[3].snapshot.unwrap().meta.name, "v3");
+
+        project = history.jump_to(3, project).unwrap();
+        assert_eq!(project.meta.name, "v3");
+        assert!(!history.can_redo());
+        assert!(history.jump_to(3, project.clone()).is_none());
+        assert!(history.jump_to(4, project).is_none());
+    }
+
+    #[test]
+    fn test_history_edit_after_jump_keeps_branch() {
+        let mut history = History::new();
+        let mut project = ProjectGraph::with_name("v0");
+        for (action, name) in [("Add User", "v1"), ("Add Post", "v2"), ("Connect", "v3")] {
+            history.push(action, project.clone());
+            project = ProjectGraph::with_name(name);
+        }
+
+        // Jump back to v1 and edit from there
+        project = history.jump_to(1, project).unwrap();
+        history.push("Add Comment", project.clone());
+        project = ProjectGraph::with_name("v1b");
+        assert!(!history.can_redo());
+        assert_eq!(history.undo_action_name(), Some("Add Comment"));
+
+        // The abandoned states come right after the state they branched
+        // from, indented, and the new edit is where redo from v1 now leads
+        let timeline = history.timeline();
+        let rows: Vec<_> = timeline.iter().map(|entry| (entry.action_name, entry.depth, entry.ahead)).collect();
+        assert_eq!(
+            rows,
+            [
+                (None, 0, false),
+                (Some("Add User"), 0, false),
+                (Some("Add Post"), 1, true),
+                (Some("Connect"), 1, true),
+                (Some("Add Comment"), 0, false),
+            ]
+        );
+        assert_eq!(history.current_index(), 4);
+
+        // The old future is still reachable, and the new branch stays too
+        project = history.jump_to(3, project).unwrap();
+        assert_eq!(project.meta.name, "v3");
+        project = history.undo(project).unwrap();
+        assert_eq!(project.meta.name, "v2");
+        project = history.undo(project).unwrap();
+        assert_eq!(project.meta.name, "v1");
+        // Redo now follows the branch just walked back along
+        assert_eq!(history.redo_action_name(), Some("Add Post"));
+        let index = history.timeline().iter().position(|entry| entry.action_name == Some("Add Comment")).unwrap();
+        project = history.jump_to(index, project).unwrap();
+        assert_eq!(project.meta.name, "v1b");
+    }
+
+    #[test]
+    fn test_history_trims_abandoned_branches_first() {
+        let mut history = History::with_max_size(3);
+        let mut project = ProjectGraph::with_name("v0");
+        for (action, name) in [("A", "v1"), ("B", "v2")] {
+            history.push(action, project.clone());
+            project = ProjectGraph::with_name(name);
+        }
+        project = history.undo(project).unwrap();
+        for (action, name) in [("C", "v3"), ("D", "v4")] {
+            history.push(action, project.clone());
+            project = ProjectGraph::with_name(name);
+        }
+
+        let actions: Vec<_> = history.timeline().iter().map(|entry| entry.action_name).collect();
+        assert_eq!(actions, [None, Some("A"), Some("C"), Some("D")]);
+        project = history.undo(project).unwrap();
+        project = history.undo(project).unwrap();
+        project = history.undo(project).unwrap();
+        assert_eq!(project.meta.name, "v0");
+        assert!(!history.can_undo());
+    }
+
     #[test]
     fn test_snap_to_grid() {
         let view = ViewState {
@@ -0,0 +1,148 @@
+//! Suggested connections panel
+//!
+//! [`SuggestionsPanel`] lists the edges [`suggest_connections`] proposes for
+//! the project, grouped by the heuristic behind them. Each can be accepted
+//! with one click or dismissed for the rest of the session; clicking a node
+//! name selects it on the canvas.
+
+use std::collections::HashSet;
+
+use eframe::egui;
+use imortal_ir::{suggest_connections, NodeId, ProjectGraph, Suggestion, SuggestionKind};
+
+use crate::i18n::{tr, trf};
+
+/// What the user did in the panel
+#[derive(Debug, Clone, PartialEq)]
+pub enum SuggestionAction {
+    /// Add the suggested edges
+    Accept(Vec<Suggestion>),
+    /// Select a node on the canvas
+    Focus(NodeId),
+}
+
+/// State of the Suggested Connections panel
+#[derive(Debug, Default)]
+pub struct SuggestionsPanel {
+    /// Whether the panel is showing
+    pub visible: bool,
+    /// Suggestions the user dismissed, by kind and endpoints
+    dismissed: HashSet<(SuggestionKind, NodeId, NodeId)>,
+}
+
+impl SuggestionsPanel {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Suggestions for `project` the user hasn't dismissed
+    pub fn suggestions(&self, project: &ProjectGraph) -> Vec<Suggestion> {
+        suggest_connections(project)
+            .into_iter()
+            .filter(|s| !self.dismissed.contains(&(s.kind, s.from, s.to)))
+            .collect()
+    }
+
+    /// Show the panel at the right of the window; call before the central
+    /// panel
+    pub fn show(&mut self, ctx: &egui::Context, project: &ProjectGraph) -> Option<SuggestionAction> {
+        if !self.visible {
+            return None;
+        }
+        // Recomputed every frame so the list follows edits on the canvas
+        let suggestions = self.suggestions(project);
+        let mut action = None;
+        let mut dismiss = None;
+
+        egui::SidePanel::right("suggestions_panel")
+            .resizable(true)
+            .default_width(260.0)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.strong(tr("suggestions.title"));
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        if ui.small_button("✕").clicked() {
+                            self.visible = false;
+                        }
+                    });
+                });
+                ui.small(tr("suggestions.hint"));
+                ui.separator();
+
+                if suggestions.is_empty() {
+                    ui.weak(tr("suggestions.empty"));
+                    return;
+                }
+                egui::ScrollArea::vertical().auto_shrink([false, false]).show(ui, |ui| {
+                    for kind in SuggestionKind::all() {
+                        let of_kind: Vec<_> = suggestions.iter().filter(|s| s.kind == *kind).collect();
+                        if of_kind.is_empty() {
+                            continue;
+                        }
+                        egui::CollapsingHeader::new(format!("{} ({})", kind_name(*kind), of_kind.len()))
+                            .default_open(true)
+                            .show(ui, |ui| {
+                                for suggestion in of_kind {
+                                    ui.label(&suggestion.message);
+                                    ui.horizontal_wrapped(|ui| {
+                                        for id in [suggestion.from, suggestion.to] {
+                                            let name = project.get_node(id).map_or("?", |n| n.name.as_str());
+                                            if ui.small_button(name).on_hover_text(tr("suggestions.select")).clicked() {
+                                                action = Some(SuggestionAction::Focus(id));
+                                            }
+                                        }
+                                        if ui.small_button(tr("suggestions.accept")).clicked() {
+                                            action = Some(SuggestionAction::Accept(vec![suggestion.clone()]));
+                                        }
+                                        if ui.small_button(tr("suggestions.dismiss")).clicked() {
+                                            dismiss = Some((suggestion.kind, suggestion.from, suggestion.to));
+                                        }
+                                    });
+                                    ui.add_space(4.0);
+                                }
+                            });
+                    }
+                });
+
+                ui.separator();
+                if ui.button(trf("suggestions.accept_all", &[&suggestions.len()])).clicked() {
+                    action = Some(SuggestionAction::Accept(suggestions.clone()));
+                }
+            });
+
+        if let Some(key) = dismiss {
+            self.dismissed.insert(key);
+        }
+        action
+    }
+}
+
+/// Name of a heuristic in the panel
+pub fn kind_name(kind: SuggestionKind) -> &'static str {
+    match kind {
+        SuggestionKind::EntityStorage => tr("suggestions.entity_storage"),
+        SuggestionKind::EndpointEntity => tr("suggestions.endpoint_entity"),
+        SuggestionKind::ForeignKeyField => tr("suggestions.foreign_key"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use imortal_ir::Node;
+
+    #[test]
+    fn test_dismissed_suggestions() {
+        let mut project = ProjectGraph::with_name("test");
+        project.add_node(Node::new_database("Main"));
+        project.add_node(Node::new_entity("User"));
+        project.add_node(Node::new_entity("Post"));
+
+        let mut panel = SuggestionsPanel::new();
+        let suggestions = panel.suggestions(&project);
+        assert_eq!(suggestions.len(), 2);
+        let first = &suggestions[0];
+        panel.dismissed.insert((first.kind, first.from, first.to));
+        assert_eq!(panel.suggestions(&project), vec![suggestions[1].clone()]);
+    }
+}
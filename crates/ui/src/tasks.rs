@@ -0,0 +1,269 @@
+//! Background task executor for Immortal Engine UI
+//!
+//! Slow work (code generation, imports, connection tests, file IO) runs on
+//! a small pool of worker threads so the editor stays responsive. Each task
+//! returns a value of the app's message type `M`; progress, results, and
+//! cancellations come back as [`TaskEvent`]s that the app drains once per
+//! frame with [`Executor::poll`]. Cancelling is cooperative: a task checks
+//! [`TaskContext::is_cancelled`] between steps, and the result of a
+//! cancelled task is dropped either way.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+
+use crate::crash;
+
+/// Identifies a task
+pub type TaskId = u64;
+
+/// Most worker threads in the pool
+const MAX_WORKERS: usize = 4;
+
+/// Something that happened to a task
+#[derive(Debug)]
+pub enum TaskEvent<M> {
+    /// Fraction done, from 0 to 1, and what the task is doing
+    Progress { id: TaskId, fraction: f32, message: String },
+    /// The task finished with a result
+    Finished { id: TaskId, result: M },
+    /// The task was cancelled; any result it produced was dropped
+    Cancelled { id: TaskId },
+    /// The task panicked
+    Failed { id: TaskId },
+}
+
+impl<M> TaskEvent<M> {
+    pub fn id(&self) -> TaskId {
+        match self {
+            TaskEvent::Progress { id, .. }
+            | TaskEvent::Finished { id, .. }
+            | TaskEvent::Cancelled { id }
+            | TaskEvent::Failed { id } => *id,
+        }
+    }
+}
+
+/// Handed to a running task to report progress and check for cancellation
+pub struct TaskContext<M> {
+    id: TaskId,
+    cancelled: Arc<AtomicBool>,
+    events: Sender<TaskEvent<M>>,
+}
+
+impl<M> TaskContext<M> {
+    pub fn id(&self) -> TaskId {
+        self.id
+    }
+
+    /// Report the fraction done, from 0 to 1, and what is happening
+    pub fn progress(&self, fraction: f32, message: impl Into<String>) {
+        let _ = self.events.send(TaskEvent::Progress {
+            id: self.id,
+            fraction: fraction.clamp(0.0, 1.0),
+            message: message.into(),
+        });
+    }
+
+    /// Whether the user asked to stop; the task should return soon
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
+
+type Job = Box<dyn FnOnce() + Send>;
+
+/// A task that hasn't finished
+struct Running {
+    label: String,
+    cancelled: Arc<AtomicBool>,
+}
+
+/// Runs tasks on worker threads and collects their events
+pub struct Executor<M> {
+    jobs: Option<Sender<Job>>,
+    workers: Vec<JoinHandle<()>>,
+    events: Sender<TaskEvent<M>>,
+    inbox: Receiver<TaskEvent<M>>,
+    running: HashMap<TaskId, Running>,
+    next_id: TaskId,
+}
+
+impl<M: Send + 'static> Default for Executor<M> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<M: Send + 'static> Executor<M> {
+    /// An executor with one worker per core, up to [`MAX_WORKERS`]
+    pub fn new() -> Self {
+        let cores = std::thread::available_parallelism().map_or(1, |n| n.get());
+        Self::with_workers(cores.clamp(1, MAX_WORKERS))
+    }
+
+    /// An executor with `count` worker threads
+    pub fn with_workers(count: usize) -> Self {
+        let (jobs, queue) = mpsc::channel::<Job>();
+        let queue = Arc::new(Mutex::new(queue));
+        let workers = (0..count.max(1))
+            .map(|index| {
+                let queue = Arc::clone(&queue);
+                std::thread::Builder::new()
+                    .name(format!("imortal-task-{}", index))
+                    .spawn(move || loop {
+                        // The lock is held only while waiting, not while working
+                        let job = queue.lock().map_err(|_| ()).and_then(|queue| queue.recv().map_err(|_| ()));
+                        match job {
+                            Ok(job) => job(),
+                            Err(()) => break,
+                        }
+                    })
+                    .expect("failed to start task worker")
+            })
+            .collect();
+
+        let (events, inbox) = mpsc::channel();
+        Self { jobs: Some(jobs), workers, events, inbox, running: HashMap::new(), next_id: 1 }
+    }
+
+    /// Queue `work` to run on a worker thread
+    pub fn spawn<F>(&mut self, label: impl Into<String>, work: F) -> TaskId
+    where
+        F: FnOnce(&TaskContext<M>) -> M + Send + 'static,
+    {
+        let id = self.next_id;
+        self.next_id += 1;
+        let cancelled = Arc::new(AtomicBool::new(false));
+        self.running.insert(id, Running { label: label.into(), cancelled: Arc::clone(&cancelled) });
+
+        let context = TaskContext { id, cancelled, events: self.events.clone() };
+        let job: Job = Box::new(move || {
+            let event = if context.is_cancelled() {
+                TaskEvent::Cancelled { id }
+            } else {
+                match crash::catch_task(|| work(&context)) {
+                    Ok(_) if context.is_cancelled() => TaskEvent::Cancelled { id },
+                    Ok(result) => TaskEvent::Finished { id, result },
+                    Err(_) => TaskEvent::Failed { id },
+                }
+            };
+            let _ = context.events.send(event);
+        });
+        if let Some(jobs) = &self.jobs {
+            let _ = jobs.send(job);
+        }
+        id
+    }
+
+    /// Ask a task to stop
+    pub fn cancel(&self, id: TaskId) {
+        if let Some(task) = self.running.get(&id) {
+            task.cancelled.store(true, Ordering::Relaxed);
+        }
+    }
+
+    /// Events since the last poll, oldest first
+    pub fn poll(&mut self) -> Vec<TaskEvent<M>> {
+        let events: Vec<_> = self.inbox.try_iter().collect();
+        for event in &events {
+            if !matches!(event, TaskEvent::Progress { .. }) {
+                self.running.remove(&event.id());
+            }
+        }
+        events
+    }
+
+    /// Whether a task is queued or running
+    pub fn is_running(&self, id: TaskId) -> bool {
+        self.running.contains_key(&id)
+    }
+
+    /// Tasks not finished yet, with their labels
+    pub fn running(&self) -> impl Iterator<Item = (TaskId, &str)> {
+        self.running.iter().map(|(id, task)| (*id, task.label.as_str()))
+    }
+
+    /// Whether any task is queued or running
+    pub fn is_busy(&self) -> bool {
+        !self.running.is_empty()
+    }
+}
+
+impl<M> Drop for Executor<M> {
+    fn drop(&mut self) {
+        for task in self.running.values() {
+            task.cancelled.store(true, Ordering::Relaxed);
+        }
+        // Closing the queue lets idle workers exit; busy ones finish their task
+        self.jobs = None;
+        self.workers.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, Instant};
+
+    /// Poll until every task finishes, collecting their events
+    fn drain<M: Send + 'static>(executor: &mut Executor<M>) -> Vec<TaskEvent<M>> {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        let mut events = Vec::new();
+        while executor.is_busy() {
+            assert!(Instant::now() < deadline, "tasks did not finish");
+            events.extend(executor.poll());
+            std::thread::sleep(Duration::from_millis(2));
+        }
+        events
+    }
+
+    #[test]
+    fn test_task_reports_progress_and_result() {
+        let mut executor = Executor::with_workers(2);
+        let id = executor.spawn("Counting", |cx| {
+            cx.progress(0.5, "Halfway");
+            42
+        });
+        assert!(executor.is_running(id));
+        assert_eq!(executor.running().next(), Some((id, "Counting")));
+
+        let events = drain(&mut executor);
+        assert!(matches!(&events[0], TaskEvent::Progress { fraction, message, .. } if *fraction == 0.5 && message == "Halfway"));
+        assert!(matches!(events[1], TaskEvent::Finished { result: 42, .. }));
+        assert!(!executor.is_busy());
+    }
+
+    #[test]
+    fn test_cancelled_task_drops_its_result() {
+        let mut executor = Executor::with_workers(1);
+        let (started, wait) = mpsc::channel();
+        let id = executor.spawn("Looping", move |cx| {
+            let _ = started.send(());
+            while !cx.is_cancelled() {
+                std::thread::sleep(Duration::from_millis(1));
+            }
+            "stopped"
+        });
+        // Queued behind the first on the only worker, so cancelled before it starts
+        let queued = executor.spawn("Queued", |_| "never");
+
+        wait.recv().unwrap();
+        executor.cancel(id);
+        executor.cancel(queued);
+        let events = drain(&mut executor);
+        assert!(matches!(events[..], [TaskEvent::Cancelled { id: first }, TaskEvent::Cancelled { id: second }] if first == id && second == queued));
+    }
+
+    #[test]
+    fn test_panicking_task_fails_and_worker_survives() {
+        let mut executor = Executor::with_workers(1);
+        let doomed = executor.spawn("Doomed", |_| -> u32 { panic!("boom") });
+        assert!(matches!(drain(&mut executor)[..], [TaskEvent::Failed { id }] if id == doomed));
+
+        let next = executor.spawn("Next", |_| 7);
+        assert!(matches!(drain(&mut executor)[..], [TaskEvent::Finished { id, result: 7 }] if id == next));
+    }
+}
@@ -2,7 +2,12 @@
 //!
 //! This module provides theming and styling utilities for the visual editor.
 
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
 use eframe::egui;
+use imortal_core::ComponentCategory;
+use serde::{Deserialize, Serialize};
 
 /// Theme preset options
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
@@ -408,14 +413,154 @@ pub fn category_color(category: imortal_core::ComponentCategory) -> egui::Color3
         ComponentCategory::Logic => egui::Color32::from_rgb(200, 80, 200),
         ComponentCategory::Ui => egui::Color32::from_rgb(80, 200, 200),
         ComponentCategory::Embedded => egui::Color32::from_rgb(200, 200, 80),
+        ComponentCategory::Config => egui::Color32::from_rgb(120, 120, 200),
+        ComponentCategory::Service => egui::Color32::from_rgb(80, 180, 140),
         ComponentCategory::Custom => egui::Color32::from_rgb(150, 150, 150),
     }
 }
 
+/// How nodes of one category differ from the defaults; `None` keeps the default
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CategoryOverride {
+    /// Header color as RGB
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub header_color: Option<[u8; 3]>,
+    /// Width of new nodes, in canvas units
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_width: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub corner_radius: Option<f32>,
+    /// Icon for nodes without their own, as an emoji or icon name
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub icon: Option<String>,
+}
+
+impl CategoryOverride {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Per-category styling, saved with the theme in `theme.json` so a team
+/// can match its own color conventions
+///
+/// The canvas reads it directly; exports and the history thumbnails get it
+/// through [`apply_to`](Self::apply_to), so nodes look the same everywhere.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CategoryOverrides {
+    #[serde(default)]
+    pub categories: HashMap<ComponentCategory, CategoryOverride>,
+}
+
+impl CategoryOverrides {
+    /// Where the overrides are saved
+    pub fn path() -> Option<PathBuf> {
+        dirs::config_dir().map(|p| p.join("immortal-engine").join("theme.json"))
+    }
+
+    /// The saved overrides, or none when there are none saved
+    pub fn load() -> Self {
+        Self::path().map(|path| Self::load_from(&path)).unwrap_or_default()
+    }
+
+    pub fn load_from(path: &Path) -> Self {
+        std::fs::read_to_string(path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) -> std::io::Result<()> {
+        match Self::path() {
+            Some(path) => self.save_to(&path),
+            None => Ok(()),
+        }
+    }
+
+    pub fn save_to(&self, path: &Path) -> std::io::Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(self)?)
+    }
+
+    /// The override for a category, if any
+    pub fn get(&self, category: ComponentCategory) -> Option<&CategoryOverride> {
+        self.categories.get(&category)
+    }
+
+    /// Header color of nodes in `category`
+    pub fn header_color(&self, category: ComponentCategory) -> egui::Color32 {
+        self.get(category)
+            .and_then(|o| o.header_color)
+            .map_or_else(|| category_color(category), |[r, g, b]| egui::Color32::from_rgb(r, g, b))
+    }
+
+    /// Corner radius of nodes in `category`, or `default`
+    pub fn corner_radius(&self, category: ComponentCategory, default: f32) -> f32 {
+        self.get(category).and_then(|o| o.corner_radius).unwrap_or(default)
+    }
+
+    /// Icon of a node, falling back to its category's
+    pub fn node_icon<'a>(&'a self, node: &'a imortal_ir::Node) -> Option<&'a str> {
+        node.icon.as_deref().or_else(|| self.get(node.category).and_then(|o| o.icon.as_deref()))
+    }
+
+    /// Width of new nodes in `category`, if overridden
+    pub fn default_width(&self, category: ComponentCategory) -> Option<f32> {
+        self.get(category).and_then(|o| o.default_width)
+    }
+
+    /// Copy the overrides into a render style, for exports
+    pub fn apply_to(&self, style: &mut imortal_render::Style) {
+        style.categories = self
+            .categories
+            .iter()
+            .map(|(category, o)| {
+                let style = imortal_render::CategoryStyle {
+                    header_color: o.header_color.map(|[r, g, b]| imortal_render::Color::rgb(r, g, b)),
+                    corner_radius: o.corner_radius,
+                    icon: o.icon.clone(),
+                };
+                (*category, style)
+            })
+            .collect();
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_category_overrides() {
+        let mut overrides = CategoryOverrides::default();
+        assert_eq!(overrides.header_color(ComponentCategory::Data), category_color(ComponentCategory::Data));
+        assert_eq!(overrides.corner_radius(ComponentCategory::Data, 5.0), 5.0);
+
+        overrides.categories.insert(
+            ComponentCategory::Data,
+            CategoryOverride { header_color: Some([10, 20, 30]), default_width: Some(320.0), ..Default::default() },
+        );
+        assert_eq!(overrides.header_color(ComponentCategory::Data), egui::Color32::from_rgb(10, 20, 30));
+        assert_eq!(overrides.default_width(ComponentCategory::Data), Some(320.0));
+        assert_eq!(overrides.default_width(ComponentCategory::Api), None);
+
+        let mut style = imortal_render::Style::default();
+        overrides.apply_to(&mut style);
+        assert_eq!(style.header_color(ComponentCategory::Data), imortal_render::Color::rgb(10, 20, 30));
+        assert_eq!(style.corner_radius(ComponentCategory::Data), style.node_corner_radius);
+
+        let path = std::env::temp_dir().join(format!("imortal-theme-{}.json", uuid::Uuid::new_v4()));
+        overrides.save_to(&path).unwrap();
+        let saved = std::fs::read_to_string(&path).unwrap();
+        assert!(saved.contains("\"data\"") && saved.contains("\"default_width\": 320.0"));
+        assert!(!saved.contains("icon"));
+        assert_eq!(CategoryOverrides::load_from(&path), overrides);
+        std::fs::remove_file(&path).unwrap();
+    }
+
     #[test]
     fn test_theme_presets() {
         let dark = Theme::dark();
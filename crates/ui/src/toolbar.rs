@@ -281,8 +281,10 @@ mod tests {
 
     #[test]
     fn test_toolbar_response_with_action() {
-        let mut response = ToolbarResponse::default();
-        response.save_project = true;
+        let response = ToolbarResponse {
+            save_project: true,
+            ..Default::default()
+        };
         assert!(response.has_action());
     }
 
@@ -0,0 +1,364 @@
+//! First-run tutorial for Immortal Engine UI
+//!
+//! A guided overlay that highlights one part of the editor at a time: the
+//! palette, dropping an entity, drawing a connection, validating, and
+//! generating code. The steps form a small state machine advanced by
+//! [`TutorialEvent`]s from the app. Progress is saved in the config
+//! directory, so the tutorial only starts on the first run; Help > Tutorial
+//! starts it again.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use eframe::egui;
+use imortal_ir::ProjectGraph;
+use serde::{Deserialize, Serialize};
+
+use crate::i18n::{tr, trf};
+
+/// A step of the tutorial, in order
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TutorialStep {
+    Palette,
+    AddEntity,
+    Connect,
+    Validate,
+    Generate,
+    Finished,
+}
+
+impl TutorialStep {
+    /// Steps the user works through, without the closing message
+    pub const ALL: [TutorialStep; 5] = [
+        TutorialStep::Palette,
+        TutorialStep::AddEntity,
+        TutorialStep::Connect,
+        TutorialStep::Validate,
+        TutorialStep::Generate,
+    ];
+
+    /// The step after this one
+    pub fn next(self) -> Self {
+        match self {
+            TutorialStep::Palette => TutorialStep::AddEntity,
+            TutorialStep::AddEntity => TutorialStep::Connect,
+            TutorialStep::Connect => TutorialStep::Validate,
+            TutorialStep::Validate => TutorialStep::Generate,
+            TutorialStep::Generate | TutorialStep::Finished => TutorialStep::Finished,
+        }
+    }
+
+    /// What the user has to do to finish this step
+    pub fn completed_by(self) -> TutorialEvent {
+        match self {
+            TutorialStep::Palette | TutorialStep::Finished => TutorialEvent::Next,
+            TutorialStep::AddEntity => TutorialEvent::EntityAdded,
+            TutorialStep::Connect => TutorialEvent::Connected,
+            TutorialStep::Validate => TutorialEvent::Validated,
+            TutorialStep::Generate => TutorialEvent::Generated,
+        }
+    }
+
+    /// Part of the editor highlighted during this step
+    pub fn target(self) -> Option<TutorialTarget> {
+        match self {
+            TutorialStep::Palette => Some(TutorialTarget::Palette),
+            TutorialStep::AddEntity | TutorialStep::Connect => Some(TutorialTarget::Canvas),
+            TutorialStep::Validate | TutorialStep::Generate => Some(TutorialTarget::GenerateMenu),
+            TutorialStep::Finished => None,
+        }
+    }
+
+    /// Translation keys of the step's title and instructions
+    fn text(self) -> (&'static str, &'static str) {
+        match self {
+            TutorialStep::Palette => ("tutorial.palette.title", "tutorial.palette.body"),
+            TutorialStep::AddEntity => ("tutorial.add_entity.title", "tutorial.add_entity.body"),
+            TutorialStep::Connect => ("tutorial.connect.title", "tutorial.connect.body"),
+            TutorialStep::Validate => ("tutorial.validate.title", "tutorial.validate.body"),
+            TutorialStep::Generate => ("tutorial.generate.title", "tutorial.generate.body"),
+            TutorialStep::Finished => ("tutorial.finished.title", "tutorial.finished.body"),
+        }
+    }
+}
+
+/// Something the user did that may finish a step
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TutorialEvent {
+    /// Clicked "Next" on a step that only explains
+    Next,
+    EntityAdded,
+    Connected,
+    Validated,
+    Generated,
+}
+
+/// Part of the editor a step points at
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TutorialTarget {
+    Palette,
+    Canvas,
+    GenerateMenu,
+}
+
+/// Tutorial progress saved between runs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TutorialProgress {
+    /// Step the user is on
+    pub step: TutorialStep,
+    /// Whether the user skipped the tutorial
+    pub skipped: bool,
+}
+
+impl Default for TutorialProgress {
+    fn default() -> Self {
+        Self { step: TutorialStep::Palette, skipped: false }
+    }
+}
+
+impl TutorialProgress {
+    /// Whether the tutorial still has something to show
+    pub fn is_pending(&self) -> bool {
+        !self.skipped && self.step != TutorialStep::Finished
+    }
+}
+
+/// The tutorial overlay
+pub struct Tutorial {
+    progress: TutorialProgress,
+    /// Where progress is saved
+    path: Option<PathBuf>,
+    /// Whether the overlay is showing
+    active: bool,
+    /// Entity and edge counts last seen, to notice additions
+    seen: Option<(usize, usize)>,
+    /// Screen areas of the highlighted targets, from this frame
+    targets: HashMap<TutorialTarget, egui::Rect>,
+}
+
+impl Tutorial {
+    /// Load saved progress; a first run starts the tutorial
+    pub fn load() -> Self {
+        Self::at(dirs::config_dir().map(|p| p.join("immortal-engine").join("tutorial.json")))
+    }
+
+    /// Load progress saved at `path`
+    pub fn at(path: Option<PathBuf>) -> Self {
+        let progress = path
+            .as_ref()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .and_then(|content| serde_json::from_str::<TutorialProgress>(&content).ok())
+            .unwrap_or_default();
+        Self {
+            active: progress.is_pending(),
+            progress,
+            path,
+            seen: None,
+            targets: HashMap::new(),
+        }
+    }
+
+    /// Whether the overlay is showing
+    pub fn is_active(&self) -> bool {
+        self.active
+    }
+
+    /// Current step
+    pub fn step(&self) -> TutorialStep {
+        self.progress.step
+    }
+
+    /// Start again from the first step
+    pub fn restart(&mut self) {
+        self.progress = TutorialProgress::default();
+        self.active = true;
+        self.seen = None;
+        self.save();
+    }
+
+    /// Stop showing the tutorial, for good
+    pub fn skip(&mut self) {
+        self.progress.skipped = true;
+        self.active = false;
+        self.save();
+    }
+
+    /// Advance if `event` finishes the current step
+    ///
+    /// Returns whether the tutorial moved on.
+    pub fn handle(&mut self, event: TutorialEvent) -> bool {
+        if !self.active || self.progress.step.completed_by() != event {
+            return false;
+        }
+        if self.progress.step == TutorialStep::Finished {
+            self.active = false;
+        } else {
+            self.progress.step = self.progress.step.next();
+        }
+        self.save();
+        true
+    }
+
+    /// Notice entities and connections added to the project since the last
+    /// frame
+    pub fn observe(&mut self, project: &ProjectGraph) {
+        if !self.active {
+            return;
+        }
+        let counts = (project.find_nodes_by_type("data.entity").len(), project.edge_count());
+        if let Some((entities, edges)) = self.seen {
+            if counts.0 > entities {
+                self.handle(TutorialEvent::EntityAdded);
+            }
+            if counts.1 > edges {
+                self.handle(TutorialEvent::Connected);
+            }
+        }
+        self.seen = Some(counts);
+    }
+
+    /// Forget the counts seen, e.g. after switching to another project
+    pub fn reset_counts(&mut self) {
+        self.seen = None;
+    }
+
+    /// Record where a target is on screen this frame
+    pub fn set_target(&mut self, target: TutorialTarget, rect: egui::Rect) {
+        if self.active {
+            self.targets.insert(target, rect);
+        }
+    }
+
+    /// Highlight the current step's target and show its instructions
+    pub fn show(&mut self, ctx: &egui::Context) {
+        if !self.active {
+            return;
+        }
+        let step = self.progress.step;
+        let target = step.target().and_then(|target| self.targets.get(&target).copied());
+        let accent = ctx.style().visuals.selection.stroke.color;
+
+        if let Some(rect) = target {
+            let painter = ctx.layer_painter(egui::LayerId::new(egui::Order::Foreground, egui::Id::new("tutorial_highlight")));
+            // Pulse so the highlight catches the eye
+            let pulse = (ctx.input(|i| i.time) * 3.0).sin() as f32 * 0.5 + 0.5;
+            painter.rect_stroke(rect.shrink(2.0), 6.0, egui::Stroke::new(2.0 + pulse * 2.0, accent));
+            ctx.request_repaint();
+        }
+
+        let screen = ctx.screen_rect();
+        let (pos, pivot) = match target {
+            Some(rect) if rect.right() + 340.0 < screen.right() => (rect.right_top() + egui::vec2(12.0, 12.0), egui::Align2::LEFT_TOP),
+            Some(rect) => (rect.center_top() + egui::vec2(0.0, 40.0), egui::Align2::CENTER_TOP),
+            None => (screen.center(), egui::Align2::CENTER_CENTER),
+        };
+
+        let mut next = false;
+        let mut skip = false;
+        egui::Area::new(egui::Id::new("tutorial_card"))
+            .order(egui::Order::Foreground)
+            .fixed_pos(pos)
+            .pivot(pivot)
+            .show(ctx, |ui| {
+                egui::Frame::popup(ui.style()).show(ui, |ui| {
+                    ui.set_max_width(320.0);
+                    if step != TutorialStep::Finished {
+                        let number = TutorialStep::ALL.iter().position(|s| *s == step).unwrap_or(0) + 1;
+                        ui.weak(trf("tutorial.progress", &[&number, &TutorialStep::ALL.len()]));
+                    }
+                    let (title, body) = step.text();
+                    ui.heading(tr(title));
+                    ui.label(tr(body));
+                    ui.add_space(8.0);
+                    ui.horizontal(|ui| {
+                        match step {
+                            TutorialStep::Finished => next = ui.button(tr("tutorial.done")).clicked(),
+                            TutorialStep::Palette => next = ui.button(tr("tutorial.next")).clicked(),
+                            _ => {
+                                ui.weak(tr("tutorial.waiting"));
+                            }
+                        }
+                        if step != TutorialStep::Finished {
+                            skip = ui.small_button(tr("tutorial.skip")).clicked();
+                        }
+                    });
+                });
+            });
+
+        if next {
+            self.handle(TutorialEvent::Next);
+        } else if skip {
+            self.skip();
+        }
+        self.targets.clear();
+    }
+
+    fn save(&self) {
+        let Some(path) = &self.path else {
+            return;
+        };
+        if let Some(parent) = path.parent() {
+            let _ = std::fs::create_dir_all(parent);
+        }
+        if let Ok(content) = serde_json::to_string_pretty(&self.progress) {
+            let _ = std::fs::write(path, content);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use imortal_ir::{Node, ProjectMeta, RelationType};
+
+    #[test]
+    fn test_steps_advance_in_order() {
+        let mut tutorial = Tutorial::at(None);
+        assert!(tutorial.is_active());
+
+        // Events for later steps don't skip ahead
+        assert!(!tutorial.handle(TutorialEvent::Validated));
+        assert!(tutorial.handle(TutorialEvent::Next));
+        assert_eq!(tutorial.step(), TutorialStep::AddEntity);
+
+        let mut project = ProjectGraph::new(ProjectMeta::new("shop"));
+        tutorial.observe(&project);
+        let user = project.add_node(Node::new_entity("User"));
+        tutorial.observe(&project);
+        assert_eq!(tutorial.step(), TutorialStep::Connect);
+        let post = project.add_node(Node::new_entity("Post"));
+        project.add_relationship(user, post, RelationType::OneToMany).unwrap();
+        tutorial.observe(&project);
+        assert_eq!(tutorial.step(), TutorialStep::Validate);
+
+        tutorial.handle(TutorialEvent::Validated);
+        tutorial.handle(TutorialEvent::Generated);
+        assert_eq!(tutorial.step(), TutorialStep::Finished);
+        assert!(tutorial.is_active());
+        tutorial.handle(TutorialEvent::Next);
+        assert!(!tutorial.is_active());
+    }
+
+    #[test]
+    fn test_progress_is_saved() {
+        let dir = std::env::temp_dir().join(format!("imortal-tutorial-{}", uuid::Uuid::new_v4()));
+        let path = dir.join("tutorial.json");
+
+        let mut tutorial = Tutorial::at(Some(path.clone()));
+        tutorial.handle(TutorialEvent::Next);
+        // Picks up where it left off
+        let mut tutorial = Tutorial::at(Some(path.clone()));
+        assert_eq!(tutorial.step(), TutorialStep::AddEntity);
+
+        // Skipped for good, until restarted
+        tutorial.skip();
+        assert!(!Tutorial::at(Some(path.clone())).is_active());
+        tutorial.restart();
+        let tutorial = Tutorial::at(Some(path));
+        assert!(tutorial.is_active());
+        assert_eq!(tutorial.step(), TutorialStep::Palette);
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
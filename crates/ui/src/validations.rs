@@ -0,0 +1,194 @@
+//! Field validation badges and editor helpers
+//!
+//! Entity fields with validations or a unique constraint get small badges on
+//! the canvas. Clicking one opens the validation editor for that field, and
+//! the properties panel lists every constraint on the entity. A field's unit
+//! counts too, as it implies a range.
+
+use eframe::egui;
+use imortal_core::Validation;
+use imortal_ir::{Field, Unit};
+use imortal_ir::field::FieldConstraint;
+
+use crate::i18n::tr;
+
+/// Kind of badge drawn next to a field on the canvas
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValidationBadge {
+    /// Regex pattern
+    Pattern,
+    /// Min/max value or length
+    Range,
+    /// Email, URL or UUID format
+    Format,
+    /// Unique constraint
+    Unique,
+    /// Named custom validator
+    Custom,
+}
+
+impl ValidationBadge {
+    /// Badges for a field, in drawing order (left to right)
+    pub fn for_field(field: &Field) -> Vec<Self> {
+        let implied = field.unit_validations();
+        let has = |matches: fn(&Validation) -> bool| field.validations.iter().chain(&implied).any(matches);
+        let mut badges = Vec::new();
+        if has(|v| matches!(v, Validation::Pattern(_))) {
+            badges.push(Self::Pattern);
+        }
+        if has(|v| matches!(v, Validation::Min(_) | Validation::Max(_) | Validation::MinLength(_) | Validation::MaxLength(_))) {
+            badges.push(Self::Range);
+        }
+        if has(|v| matches!(v, Validation::Email | Validation::Url | Validation::Uuid)) {
+            badges.push(Self::Format);
+        }
+        if has(|v| matches!(v, Validation::Custom { .. })) {
+            badges.push(Self::Custom);
+        }
+        if field.constraints.contains(&FieldConstraint::Unique) {
+            badges.push(Self::Unique);
+        }
+        badges
+    }
+
+    /// Short text drawn inside the badge
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Pattern => ".*",
+            Self::Range => "↔",
+            Self::Format => "@",
+            Self::Unique => "U",
+            Self::Custom => "ƒ",
+        }
+    }
+
+    /// Badge fill color
+    pub fn color(&self) -> egui::Color32 {
+        match self {
+            Self::Pattern => egui::Color32::from_rgb(150, 110, 200),
+            Self::Range => egui::Color32::from_rgb(80, 150, 200),
+            Self::Format => egui::Color32::from_rgb(80, 170, 120),
+            Self::Unique => egui::Color32::from_rgb(210, 150, 60),
+            Self::Custom => egui::Color32::from_rgb(140, 140, 150),
+        }
+    }
+}
+
+/// Templates offered by the editor's "add validation" menu
+pub fn templates() -> Vec<Validation> {
+    vec![
+        Validation::Required,
+        Validation::MinLength(1),
+        Validation::MaxLength(255),
+        Validation::Min(0.0),
+        Validation::Max(100.0),
+        Validation::Pattern("^.+$".to_string()),
+        Validation::Email,
+        Validation::Url,
+        Validation::Uuid,
+        Validation::Custom { name: "custom".to_string(), message: "Invalid value".to_string() },
+    ]
+}
+
+/// Short name of a validation's kind, e.g. "min_length"
+pub fn kind_name(validation: &Validation) -> &'static str {
+    match validation {
+        Validation::Required => "required",
+        Validation::MinLength(_) => "min_length",
+        Validation::MaxLength(_) => "max_length",
+        Validation::Min(_) => "min",
+        Validation::Max(_) => "max",
+        Validation::Pattern(_) => "pattern",
+        Validation::Email => "email",
+        Validation::Url => "url",
+        Validation::Uuid => "uuid",
+        Validation::Custom { .. } => "custom",
+    }
+}
+
+/// Name of a unit's kind in the editor
+pub fn unit_name(unit: &Unit) -> &'static str {
+    match unit {
+        Unit::Currency(_) => tr("units.currency"),
+        Unit::Bytes => tr("units.bytes"),
+        Unit::Seconds => tr("units.seconds"),
+        Unit::Percent => tr("units.percent"),
+    }
+}
+
+/// One-line description of a database constraint, for the summary tab
+///
+/// Primary keys and auto-increment are shown elsewhere, so they're skipped.
+pub fn describe_constraint(constraint: &FieldConstraint) -> Option<String> {
+    match constraint {
+        FieldConstraint::Unique => Some("Must be unique".to_string()),
+        FieldConstraint::Indexed => Some("Indexed".to_string()),
+        FieldConstraint::ForeignKey { entity, field, .. } => Some(format!("References {}.{}", entity, field)),
+        FieldConstraint::Check(expr) => Some(format!("Check: {}", expr)),
+        FieldConstraint::DefaultExpression(expr) => Some(format!("Defaults to {}", expr)),
+        FieldConstraint::PrimaryKey | FieldConstraint::AutoIncrement => None,
+    }
+}
+
+/// Every constraint on a field as summary lines
+pub fn field_summary(field: &Field) -> Vec<String> {
+    let mut lines = Vec::new();
+    if field.required && !field.validations.contains(&Validation::Required) {
+        lines.push(Validation::Required.error_message());
+    }
+    lines.extend(field.validations.iter().chain(&field.unit_validations()).map(Validation::error_message));
+    if let Some(unit) = &field.unit {
+        lines.push(format!("Measured in {}", unit));
+    }
+    lines.extend(field.constraints.iter().filter_map(describe_constraint));
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use imortal_core::DataType;
+
+    #[test]
+    fn test_badges_for_field() {
+        let plain = Field::new("notes", DataType::String);
+        assert!(ValidationBadge::for_field(&plain).is_empty());
+
+        let email = Field::new("email", DataType::String)
+            .with_validation(Validation::Email)
+            .with_validation(Validation::MaxLength(255))
+            .with_validation(Validation::Pattern("@".to_string()))
+            .unique();
+        assert_eq!(
+            ValidationBadge::for_field(&email),
+            vec![ValidationBadge::Pattern, ValidationBadge::Range, ValidationBadge::Format, ValidationBadge::Unique],
+        );
+
+        // Required alone isn't drawn as a badge
+        let required = Field::new("name", DataType::String).with_validation(Validation::Required);
+        assert!(ValidationBadge::for_field(&required).is_empty());
+    }
+
+    #[test]
+    fn test_field_summary() {
+        let mut field = Field::new("age", DataType::Int32)
+            .with_validation(Validation::Min(18.0))
+            .unique();
+        field.required = true;
+        assert_eq!(field_summary(&field), vec![
+            "This field is required".to_string(),
+            "Minimum value is 18".to_string(),
+            "Must be unique".to_string(),
+        ]);
+        assert!(templates().iter().all(|t| !kind_name(t).is_empty()));
+
+        // A unit's range shows like the field's own validations
+        let share = Field::new("share", DataType::Float64).with_unit(Unit::Percent);
+        assert_eq!(ValidationBadge::for_field(&share), vec![ValidationBadge::Range]);
+        assert_eq!(field_summary(&share), vec![
+            "Minimum value is 0".to_string(),
+            "Maximum value is 100".to_string(),
+            "Measured in percent".to_string(),
+        ]);
+    }
+}
@@ -0,0 +1,198 @@
+//! Canvas views for Immortal Engine UI
+//!
+//! A project can be looked at through several named views, each with its own
+//! pan and zoom. One view is active in the main canvas; a second one can be
+//! shown beside it in a split canvas, so two distant areas of a large graph
+//! are visible at once.
+//!
+//! The active view's viewport lives on the project graph while it is being
+//! edited, so every method that changes the active view takes that live
+//! viewport and stores it back into its slot.
+
+use imortal_ir::graph::Viewport;
+
+/// A named viewport onto a project
+#[derive(Debug, Clone, PartialEq)]
+pub struct CanvasView {
+    /// Label shown in the view tabs
+    pub name: String,
+    /// Pan and zoom of this view; stale for the active view
+    pub viewport: Viewport,
+}
+
+/// The views of one project
+#[derive(Debug, Clone, PartialEq)]
+pub struct ViewSet {
+    views: Vec<CanvasView>,
+    active: usize,
+    split: Option<usize>,
+}
+
+impl Default for ViewSet {
+    fn default() -> Self {
+        Self {
+            views: vec![CanvasView {
+                name: "View 1".to_string(),
+                viewport: Viewport::default(),
+            }],
+            active: 0,
+            split: None,
+        }
+    }
+}
+
+impl ViewSet {
+    /// Create a set with a single view
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// All views, in tab order
+    pub fn views(&self) -> &[CanvasView] {
+        &self.views
+    }
+
+    /// Number of views
+    pub fn len(&self) -> usize {
+        self.views.len()
+    }
+
+    /// Always false; a set has at least one view
+    pub fn is_empty(&self) -> bool {
+        self.views.is_empty()
+    }
+
+    /// Index of the view shown in the main canvas
+    pub fn active(&self) -> usize {
+        self.active
+    }
+
+    /// Index of the view shown in the split canvas, if split
+    pub fn split(&self) -> Option<usize> {
+        self.split
+    }
+
+    /// Add a view starting at the live viewport and make it active
+    pub fn add(&mut self, live: &mut Viewport) -> usize {
+        let index = self.push_copy(*live);
+        self.activate(index, live);
+        index
+    }
+
+    /// Make another view active, swapping viewports through `live`
+    ///
+    /// Activating the split view swaps the two panes.
+    pub fn activate(&mut self, index: usize, live: &mut Viewport) {
+        if index == self.active || index >= self.views.len() {
+            return;
+        }
+        self.views[self.active].viewport = *live;
+        if self.split == Some(index) {
+            self.split = Some(self.active);
+        }
+        self.active = index;
+        *live = self.views[index].viewport;
+    }
+
+    /// Close a view; the last remaining view cannot be closed
+    pub fn close(&mut self, index: usize, live: &mut Viewport) {
+        if self.views.len() < 2 || index >= self.views.len() {
+            return;
+        }
+        if index == self.active {
+            let next = self.split.unwrap_or(if index == 0 { 1 } else { index - 1 });
+            self.activate(next, live);
+        }
+        self.views.remove(index);
+        self.split = match self.split {
+            Some(split) if split == index || split == self.active => None,
+            Some(split) if split > index => Some(split - 1),
+            other => other,
+        };
+        if self.active > index {
+            self.active -= 1;
+        }
+        if self.split == Some(self.active) {
+            self.split = None;
+        }
+    }
+
+    /// Show or hide the split canvas
+    ///
+    /// Splitting shows the next view beside the active one, creating a copy
+    /// of the live viewport if there is only one view.
+    pub fn toggle_split(&mut self, live: &Viewport) {
+        if self.split.take().is_some() {
+            return;
+        }
+        let other = if self.views.len() > 1 {
+            (self.active + 1) % self.views.len()
+        } else {
+            self.push_copy(*live)
+        };
+        self.split = Some(other);
+    }
+
+    /// Viewport of the split view, to swap in while rendering its pane
+    pub fn split_viewport_mut(&mut self) -> Option<&mut Viewport> {
+        let split = self.split?;
+        self.views.get_mut(split).map(|v| &mut v.viewport)
+    }
+
+    fn push_copy(&mut self, viewport: Viewport) -> usize {
+        let number = (1..).find(|n| !self.views.iter().any(|v| v.name == format!("View {}", n))).unwrap_or(1);
+        self.views.push(CanvasView {
+            name: format!("View {}", number),
+            viewport,
+        });
+        self.views.len() - 1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn viewport(pan_x: f32) -> Viewport {
+        Viewport { pan_x, ..Viewport::default() }
+    }
+
+    #[test]
+    fn test_views_keep_independent_viewports() {
+        let mut views = ViewSet::new();
+        let mut live = viewport(10.0);
+
+        let second = views.add(&mut live);
+        assert_eq!((views.len(), views.active()), (2, second));
+        assert_eq!(live.pan_x, 10.0);
+
+        live.pan_x = 500.0;
+        views.activate(0, &mut live);
+        assert_eq!(live.pan_x, 10.0);
+        views.activate(second, &mut live);
+        assert_eq!(live.pan_x, 500.0);
+        assert_eq!(views.views()[1].name, "View 2");
+    }
+
+    #[test]
+    fn test_split_and_close() {
+        let mut views = ViewSet::new();
+        let mut live = viewport(0.0);
+
+        views.toggle_split(&live);
+        assert_eq!((views.len(), views.split()), (2, Some(1)));
+        views.split_viewport_mut().unwrap().pan_x = -300.0;
+
+        // Activating the split view swaps the panes
+        views.activate(1, &mut live);
+        assert_eq!((views.active(), views.split()), (1, Some(0)));
+        assert_eq!(live.pan_x, -300.0);
+
+        views.close(0, &mut live);
+        assert_eq!((views.len(), views.active(), views.split()), (1, 0, None));
+        assert_eq!(live.pan_x, -300.0);
+
+        views.close(0, &mut live);
+        assert_eq!(views.len(), 1);
+    }
+}
@@ -271,7 +271,7 @@ impl WelcomeScreen {
 
             if ui.add(open_btn).clicked() {
                 if let Some(path) = rfd::FileDialog::new()
-                    .add_filter("Immortal Project", &["imortal"])
+                    .add_filter("Immortal Project", &[imortal_ir::PROJECT_EXTENSION, imortal_ir::DB_EXTENSION])
                     .pick_file()
                 {
                     action = WelcomeAction::OpenProject(path);
@@ -452,7 +452,7 @@ impl WelcomeScreen {
                             self.error_message = Some("Location cannot be empty".to_string());
                         } else {
                             // Sanitize project name for filesystem
-                            let sanitized_name = sanitize_filename(&self.new_project.name);
+                            let _sanitized_name = sanitize_filename(&self.new_project.name);
                             let location = PathBuf::from(&self.new_project.location);
 
                             action = WelcomeAction::CreateProject(NewProjectInfo {
@@ -488,8 +488,6 @@ fn sanitize_filename(name: &str) -> String {
         .map(|c| {
             if c.is_alphanumeric() || c == '-' || c == '_' {
                 c
-            } else if c == ' ' {
-                '_'
             } else {
                 '_'
             }
@@ -7,15 +7,29 @@
 use std::env;
 use std::path::PathBuf;
 
+use tracing::level_filters::LevelFilter;
+use tracing_subscriber::filter::Targets;
+use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, Layer};
+
 fn main() {
-    // Initialize tracing for logging
-    tracing_subscriber::fmt()
-        .with_env_filter(
-            tracing_subscriber::EnvFilter::try_from_default_env()
-                .unwrap_or_else(|_| "imortal=info,eframe=warn".into()),
+    // Log to the terminal, and to the editor's Log panel; the panel keeps
+    // debug events from our own crates so they can be attached to reports
+    tracing_subscriber::registry()
+        .with(
+            tracing_subscriber::fmt::layer().with_filter(
+                tracing_subscriber::EnvFilter::try_from_default_env()
+                    .unwrap_or_else(|_| "imortal=info,eframe=warn".into()),
+            ),
+        )
+        .with(
+            imortal_ui::log_panel::LogLayer::new()
+                .with_filter(Targets::new().with_target("imortal", LevelFilter::DEBUG).with_default(LevelFilter::WARN)),
         )
         .init();
 
+    // Rescue unsaved work and write a crash report if anything panics
+    imortal_ui::crash::install_panic_hook();
+
     tracing::info!("Starting Immortal Engine...");
 
     // Check for command line arguments